@@ -0,0 +1,184 @@
+//! Terminal color depth and the palette fallback for terminals without
+//! truecolor.
+//!
+//! Themes are RGB. The depth is detected once — `COLORTERM`, then `TERM`,
+//! then terminfo (`tput colors`) — and can be forced with `--colors=` or
+//! `CP_COLORS` (`truecolor`, `256`, `16`). Below truecolor, every RGB cell of
+//! a drawn frame is mapped to the nearest xterm-256 or ANSI-16 color by
+//! [`downgrade_buffer`].
+
+use std::sync::OnceLock;
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB.
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// The 16 ANSI colors.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Parse an override value (`truecolor`/`24bit`, `256`, `16`).
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Some(Self::TrueColor),
+            "256" => Some(Self::Ansi256),
+            "16" => Some(Self::Ansi16),
+            _ => None,
+        }
+    }
+}
+
+/// Depth forced on the command line (`--colors=`).
+static REQUESTED: OnceLock<ColorDepth> = OnceLock::new();
+/// Depth in effect, resolved on first use.
+static DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+/// Force the depth from a `--colors=` value. Returns `false` for an unknown
+/// value (detection then applies).
+#[must_use]
+pub fn request(value: &str) -> bool {
+    ColorDepth::parse(value).is_some_and(|depth| REQUESTED.set(depth).is_ok())
+}
+
+/// The color depth in effect.
+#[must_use]
+pub fn current() -> ColorDepth {
+    *DEPTH.get_or_init(|| {
+        REQUESTED.get().copied().unwrap_or_else(|| detect(|key| std::env::var(key).ok(), terminfo_colors))
+    })
+}
+
+/// Colors reported by terminfo for the current `TERM`.
+fn terminfo_colors() -> Option<u32> {
+    let output = std::process::Command::new("tput").arg("colors").output().ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// The depth for an environment read through `var`, asking `terminfo` only
+/// when the variables are inconclusive.
+fn detect(var: impl Fn(&str) -> Option<String>, terminfo: impl FnOnce() -> Option<u32>) -> ColorDepth {
+    if let Some(depth) = var("CP_COLORS").as_deref().and_then(ColorDepth::parse) {
+        return depth;
+    }
+    if var("COLORTERM").is_some_and(|v| matches!(v.as_str(), "truecolor" | "24bit")) {
+        return ColorDepth::TrueColor;
+    }
+    let term = var("TERM").unwrap_or_default();
+    if term.contains("direct") || term.contains("truecolor") {
+        return ColorDepth::TrueColor;
+    }
+    if term.contains("256color") {
+        return ColorDepth::Ansi256;
+    }
+    match terminfo() {
+        Some(colors) if colors >= 256 => ColorDepth::Ansi256,
+        _ => ColorDepth::Ansi16,
+    }
+}
+
+/// Channel levels of the xterm 6×6×6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Typical RGB of the 16 ANSI colors, with their ratatui names.
+const ANSI16: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+/// Squared distance between two RGB colors.
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter().zip(b).map(|(x, y)| u32::from(x.abs_diff(y)).pow(2)).sum()
+}
+
+/// Index (0–5) of the cube level closest to `channel`.
+fn cube_step(channel: u8) -> u8 {
+    let steps = (0u8..).zip(CUBE_LEVELS);
+    steps.min_by_key(|&(_, level)| level.abs_diff(channel)).map_or(0, |(i, _)| i)
+}
+
+/// Nearest xterm-256 index: the closer of the color-cube and grayscale-ramp candidates.
+fn nearest_256(rgb: [u8; 3]) -> u8 {
+    let steps = rgb.map(cube_step);
+    let cube = steps.map(|step| CUBE_LEVELS.get(usize::from(step)).copied().unwrap_or(0));
+    let cube_index = steps.iter().fold(0u8, |acc, step| acc.saturating_mul(6).saturating_add(*step)).saturating_add(16);
+    let mean = rgb.iter().map(|c| u32::from(*c)).sum::<u32>().checked_div(3).unwrap_or(0);
+    let gray_step = u8::try_from(mean.saturating_sub(3).checked_div(10).unwrap_or(0).min(23)).unwrap_or(23);
+    let gray_value = 8u8.saturating_add(gray_step.saturating_mul(10));
+    if distance(rgb, [gray_value; 3]) < distance(rgb, cube) { 232u8.saturating_add(gray_step) } else { cube_index }
+}
+
+/// Nearest of the 16 ANSI colors.
+fn nearest_16(rgb: [u8; 3]) -> Color {
+    ANSI16.iter().min_by_key(|&&(_, ansi)| distance(rgb, ansi)).map_or(Color::Reset, |&(color, _)| color)
+}
+
+/// `color` as the terminal can show it at `depth`.
+fn downgrade(color: Color, depth: ColorDepth) -> Color {
+    let Color::Rgb(red, green, blue) = color else { return color };
+    match depth {
+        ColorDepth::TrueColor => color,
+        ColorDepth::Ansi256 => Color::Indexed(nearest_256([red, green, blue])),
+        ColorDepth::Ansi16 => nearest_16([red, green, blue]),
+    }
+}
+
+/// Map every RGB cell of a drawn frame to the detected depth (no-op with
+/// truecolor).
+pub fn downgrade_buffer(buffer: &mut Buffer) {
+    let depth = current();
+    if depth == ColorDepth::TrueColor {
+        return;
+    }
+    for cell in &mut buffer.content {
+        cell.fg = downgrade(cell.fg, depth);
+        cell.bg = downgrade(cell.bg, depth);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let owned: Vec<(String, String)> = pairs.iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect();
+        move |key| owned.iter().find(|pair| pair.0 == key).map(|pair| pair.1.clone())
+    }
+
+    #[test]
+    fn detects_depth_from_environment_and_terminfo() {
+        assert_eq!(detect(env(&[("COLORTERM", "truecolor")]), || None), ColorDepth::TrueColor);
+        assert_eq!(detect(env(&[("TERM", "xterm-256color")]), || None), ColorDepth::Ansi256);
+        assert_eq!(detect(env(&[("TERM", "xterm")]), || Some(256)), ColorDepth::Ansi256);
+        assert_eq!(detect(env(&[("TERM", "linux")]), || Some(8)), ColorDepth::Ansi16);
+        assert_eq!(detect(env(&[("COLORTERM", "truecolor"), ("CP_COLORS", "16")]), || None), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn maps_rgb_to_the_nearest_palette_entry() {
+        assert_eq!(downgrade(Color::Rgb(255, 0, 0), ColorDepth::Ansi256), Color::Indexed(196));
+        assert_eq!(downgrade(Color::Rgb(30, 30, 30), ColorDepth::Ansi256), Color::Indexed(234));
+        assert_eq!(downgrade(Color::Rgb(250, 250, 250), ColorDepth::Ansi16), Color::White);
+        assert_eq!(downgrade(Color::Reset, ColorDepth::Ansi16), Color::Reset);
+    }
+}
@@ -68,6 +68,9 @@ pub fn active_theme() -> &'static Theme {
 /// (atomic pointer).
 pub mod theme;
 
+/// Terminal color depth detection and the 256/16-color fallback for RGB themes.
+pub mod color_depth;
+
 // =============================================================================
 // UI CHARACTERS
 // =============================================================================
@@ -27,3 +27,8 @@ pub fn panel_footer() -> &'static str {
 pub fn panel_footer_ack() -> &'static str {
     &PROMPTS.panel.footer_ack
 }
+/// System-prompt paragraph on panel provenance and trust.
+#[must_use]
+pub fn panel_provenance_policy() -> &'static str {
+    &PROMPTS.panel.provenance_policy
+}
@@ -61,7 +61,8 @@ fn config_dir() -> Option<PathBuf> {
 }
 
 /// `~/.config/context-pilot/config.json`
-fn config_path() -> Option<PathBuf> {
+#[must_use]
+pub fn config_path() -> Option<PathBuf> {
     config_dir().map(|d| d.join("config.json"))
 }
 
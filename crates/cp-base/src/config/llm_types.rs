@@ -138,3 +138,90 @@ pub enum LlmProvider {
     #[serde(alias = "claudecodev2")]
     ClaudeCodeV2,
 }
+
+impl LlmProvider {
+    /// Host serving this provider's API (probed by the connectivity monitor).
+    #[must_use]
+    pub const fn api_host(self) -> &'static str {
+        match self {
+            Self::Anthropic | Self::ClaudeCode | Self::ClaudeCodeApiKey | Self::ClaudeCodeV2 => "api.anthropic.com",
+            Self::Grok => "api.x.ai",
+            Self::Groq => "api.groq.com",
+            Self::DeepSeek => "api.deepseek.com",
+            Self::MiniMax => "api.minimax.io",
+        }
+    }
+}
+
+/// Network timeouts for one provider's streaming requests, all in seconds.
+///
+/// Overridable through the global config settings: `"timeouts"` applies to
+/// every provider, `"timeouts.<provider>"` (e.g. `"timeouts.groq"`) to one.
+/// Both hold a JSON object with any subset of the fields below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(default)]
+pub struct ProviderTimeouts {
+    /// Max time to establish the TCP/TLS connection.
+    pub connect: u64,
+    /// Max silence on an open connection before the read fails (and is retried).
+    pub idle: u64,
+    /// Interval of TCP keepalive probes on idle sockets.
+    pub keepalive: u64,
+    /// Silence after which the status bar flags the stream as stalled.
+    pub stall: u64,
+}
+
+impl Default for ProviderTimeouts {
+    fn default() -> Self {
+        Self { connect: 15, idle: 180, keepalive: 30, stall: 30 }
+    }
+}
+
+impl ProviderTimeouts {
+    /// Timeouts for `provider`: defaults, then the global overrides, then the
+    /// provider-specific ones.
+    #[must_use]
+    pub fn for_provider(provider: LlmProvider) -> Self {
+        Self::from_settings(provider, crate::config::global::get_setting)
+    }
+
+    /// Merge the `"timeouts"` and `"timeouts.<provider>"` values read through
+    /// `setting` over the defaults; unparsable values are ignored.
+    fn from_settings(provider: LlmProvider, setting: impl Fn(&str) -> Option<String>) -> Self {
+        let name = serde_json::to_value(provider).ok().and_then(|v| v.as_str().map(str::to_owned)).unwrap_or_default();
+        let mut merged = serde_json::Map::new();
+        for key in ["timeouts".to_owned(), format!("timeouts.{name}")] {
+            let parsed = setting(&key).and_then(|s| serde_json::from_str(&s).ok());
+            if let Some(serde_json::Value::Object(fields)) = parsed {
+                merged.extend(fields);
+            }
+        }
+        serde_json::from_value(serde_json::Value::Object(merged)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A settings lookup serving only `pairs`.
+    fn settings(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let owned: Vec<(String, String)> = pairs.iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect();
+        move |key| owned.iter().find(|pair| pair.0 == key).map(|pair| pair.1.clone())
+    }
+
+    #[test]
+    fn provider_overrides_win_over_global_ones_and_defaults() {
+        let lookup = settings(&[("timeouts", r#"{"idle": 60, "stall": 20}"#), ("timeouts.groq", r#"{"stall": 5}"#)]);
+        let groq = ProviderTimeouts::from_settings(LlmProvider::Groq, &lookup);
+        assert_eq!(groq, ProviderTimeouts { connect: 15, idle: 60, keepalive: 30, stall: 5 });
+        let others = ProviderTimeouts::from_settings(LlmProvider::Grok, &lookup);
+        assert_eq!(others, ProviderTimeouts { connect: 15, idle: 60, keepalive: 30, stall: 20 });
+    }
+
+    #[test]
+    fn unparsable_overrides_fall_back_to_defaults() {
+        let lookup = settings(&[("timeouts", "not json"), ("timeouts.deepseek", r#"{"idle": "slow"}"#)]);
+        assert_eq!(ProviderTimeouts::from_settings(LlmProvider::DeepSeek, lookup), ProviderTimeouts::default());
+    }
+}
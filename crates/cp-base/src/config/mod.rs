@@ -62,6 +62,9 @@ pub struct PanelPrompts {
     pub footer: String,
     /// Assistant acknowledgment injected after the footer.
     pub footer_ack: String,
+    /// Appended to the system prompt: how to weigh panels by provenance.
+    #[serde(default)]
+    pub provenance_policy: String,
 }
 
 // ============================================================================
@@ -98,6 +101,21 @@ pub struct SpineInjections {
     /// The "continue" synthetic message content.
     #[serde(rename = "continue")]
     pub continue_msg: String,
+    /// Notice sent when the agent reaches its tool-turn cap (`{count}`).
+    pub check_in: String,
+    /// Notice sent when the agent repeats an identical tool call (`{tool}`, `{count}`).
+    pub tool_loop: String,
+    /// Grace notice sent before the panel GC closes idle panels (`{panels}`, `{minutes}`).
+    pub panel_gc: String,
+    /// Context health warning once usage passes the warn threshold
+    /// (`{used}`, `{budget}`, `{pct}`, `{panels}`).
+    pub context_warning: String,
+    /// Context health alert naming panels over their budget share
+    /// (`{panels}`, `{pct}`, `{budget}`).
+    pub heavy_panel: String,
+    /// Warning appended to a tool result when panels the agent saw have
+    /// changed behind the freeze (`{panels}`).
+    pub stale_read: String,
 }
 
 /// Warning banners rendered inside editor panels to prevent the LLM
@@ -161,6 +179,27 @@ pub struct ProviderInjections {
     pub seed_reinjection_ack: String,
     /// System suffix for GPT-OSS compatible providers.
     pub gpt_oss_suffix: String,
+    /// Wrapper for the user's standing instructions (`{instructions}` placeholder).
+    pub standing_instructions: String,
+    /// Instruction for a bare JSON answer matching a schema (`{schema}` placeholder).
+    pub structured_output: String,
+    /// Follow-up asking to fix an invalid structured answer (`{error}` placeholder).
+    pub structured_repair: String,
+    /// System prompt of the cleaner's eviction-plan query.
+    pub cleaner_plan_system: String,
+    /// Prompt asking the cleaner for an eviction plan (`{panels}`, `{messages}`,
+    /// `{last_user}` and `{constraints}` placeholders).
+    pub cleaner_plan: String,
+    /// System prompt of the work-report query.
+    pub work_report_system: String,
+    /// Prompt asking for a work report (`{actions}`, `{todos}` and `{focus}`
+    /// placeholders).
+    pub work_report: String,
+    /// System prompt of the commit-message reformat query.
+    pub commit_message_system: String,
+    /// Prompt asking to bring a commit message in line with the policy
+    /// (`{rules}`, `{problems}` and `{message}` placeholders).
+    pub commit_message: String,
 }
 
 // ============================================================================
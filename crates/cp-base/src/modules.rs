@@ -110,6 +110,13 @@ pub trait Module: Send + Sync {
         None
     }
 
+    /// Tool input as recorded in the conversation, with secrets masked. The
+    /// call still executes with the original input. Returns `None` to record
+    /// the input unchanged (always, for tools this module doesn't own).
+    fn transcript_input(&self, _tool: &ToolUse) -> Option<serde_json::Value> {
+        None
+    }
+
     /// Create a panel for the given context type. Returns None if not owned by this module.
     fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>>;
 
@@ -201,6 +208,10 @@ pub trait Module: Send + Sync {
     /// Modules can update their state to reflect the stop.
     fn on_stream_stop(&self, _state: &mut State) {}
 
+    /// Called when a stream completes normally, after its `StreamDone` has
+    /// been applied (message saved, spine cues raised).
+    fn on_stream_done(&self, _state: &mut State) {}
+
     /// Called for each text chunk the LLM streams.
     ///
     /// Fires before the chunk reaches the typewriter.  The bridge module
@@ -226,6 +237,12 @@ pub trait Module: Send + Sync {
     /// indicators).
     fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
 
+    /// Called after a tool call the model made has run, with its result.
+    ///
+    /// Fires for active modules only, whichever module owned the tool — the
+    /// Neovim module mirrors `Open` into the editor from here.
+    fn on_tool_executed(&self, _tool: &ToolUse, _result: &ToolResult, _state: &mut State) {}
+
     // === File watcher delegation ===
 
     /// Return filesystem paths this module wants the file watcher to monitor.
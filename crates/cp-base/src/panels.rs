@@ -26,6 +26,7 @@ use crate::cast::Safe as _;
 use crate::config::constants::{SCROLL_ARROW_AMOUNT, SCROLL_PAGE_AMOUNT};
 use crate::state::actions::Action;
 use crate::state::context::{Entry, Kind};
+use crate::state::data::context_item::ContextItem;
 use crate::state::runtime::State;
 
 // =============================================================================
@@ -361,31 +362,6 @@ you need NOW; prefer searching/opening a specific range over walking pages.]\n{p
     )
 }
 
-/// A single context item to be sent to the LLM
-#[derive(Debug, Clone)]
-pub struct ContextItem {
-    /// Context element ID (e.g., "P7", "P8") for LLM reference
-    pub id: String,
-    /// Header/title for this context (e.g., "File: src/main.rs" or "Todo List")
-    pub header: String,
-    /// The actual content
-    pub content: String,
-    /// Last refresh timestamp in milliseconds since UNIX epoch (for sorting panels)
-    pub last_refresh_ms: u64,
-}
-
-impl ContextItem {
-    /// Create a context item from its components.
-    pub fn new<I, H, C>(id: I, header: H, content: C, last_refresh_ms: u64) -> Self
-    where
-        I: Into<String>,
-        H: Into<String>,
-        C: Into<String>,
-    {
-        Self { id: id.into(), header: header.into(), content: content.into(), last_refresh_ms }
-    }
-}
-
 /// Trait for all panel types
 pub trait Panel {
     /// Generate the panel's title for display
@@ -454,6 +430,18 @@ pub trait Panel {
         0
     }
 
+    /// Send only what changed since the LLM's last view of this panel.
+    ///
+    /// For panels that refresh constantly and mostly append (consoles, logs),
+    /// re-sending the full content every turn is wasted tokens. When `true`,
+    /// lines the LLM already saw are replaced by a one-line marker, keeping
+    /// a short anchor of unchanged lines before the new ones.
+    ///
+    /// Default `false` means "always send the full content".
+    fn diff_on_refresh(&self) -> bool {
+        false
+    }
+
     /// Generate context items to send to the LLM
     /// Returns empty vec if this panel doesn't contribute to LLM context
     fn context(&self, _state: &State) -> Vec<ContextItem> {
@@ -9,6 +9,8 @@ pub enum Action {
     InsertText(String),
     /// Paste from clipboard (triggers paste-sentinel expansion).
     PasteText(String),
+    /// Move the offered large paste into a Pasted Content panel (Ctrl+T).
+    PasteToPanel,
     /// Delete character before cursor.
     InputBackspace,
     /// Delete character after cursor.
@@ -43,6 +45,24 @@ pub enum Action {
     CursorHomeSelect,
     /// Extend selection to end of line (Shift+End).
     CursorEndSelect,
+    /// Move cursor one visual line up in the draft, else scroll up (Up arrow).
+    CursorUp,
+    /// Move cursor one visual line down in the draft, else scroll down (Down arrow).
+    CursorDown,
+    /// Extend selection one visual line up (Shift+Up).
+    CursorUpSelect,
+    /// Extend selection one visual line down (Shift+Down).
+    CursorDownSelect,
+    /// Cut from cursor to end of line into the kill buffer (Alt+K).
+    KillLineEnd,
+    /// Paste the kill buffer at the cursor (Ctrl+Y).
+    Yank,
+    /// Open spelling suggestions for the misspelled word at the cursor (Alt+S).
+    SpellSuggest,
+    /// Undo the last edit unit of the input draft (Ctrl+Z).
+    UndoInput,
+    /// Redo the last undone edit unit of the input draft (Ctrl+Shift+Z).
+    RedoInput,
     /// Select all text in input (Ctrl+A).
     SelectAll,
     /// Navigate to previous (older) prompt in history (Ctrl+U).
@@ -51,6 +71,10 @@ pub enum Action {
     HistoryNext,
     /// Copy current panel content to clipboard (Ctrl+C).
     CopyPanelContent,
+    /// Edit the standing instructions in the input field, or save them (Ctrl+S).
+    ToggleStandingInstructions,
+    /// Discard edits to the standing instructions and restore the draft (Esc).
+    CancelStandingInstructions,
 
     // === Conversation lifecycle ===
     /// Discard all messages and start fresh.
@@ -97,6 +121,8 @@ pub enum Action {
         /// Key sequence to send.
         keys: String,
     },
+    /// Start recording voice input, or stop and transcribe it (Ctrl+R).
+    ToggleVoiceInput,
     /// Toggle the F12 performance overlay.
     TogglePerfMonitor,
     /// Toggle the config/settings overlay (F1).
@@ -142,6 +168,14 @@ pub enum Action {
 
     /// Toggle reverie (background optimizer) on/off.
     ConfigToggleReverie,
+    /// Toggle opening `@`-mentioned files as panels on send.
+    ConfigToggleMentionOpen,
+    /// Toggle underlining misspelled words in the draft.
+    ConfigToggleSpellCheck,
+    /// Cycle the idle panel GC policy (off → presets → off).
+    ConfigCyclePanelGc,
+    /// Cycle the panel cache memory budget (presets → off).
+    ConfigCycleCacheBudget,
 
     // === UI ===
     /// Jump to first dynamic panel on the next page (Shift+Right).
@@ -170,10 +204,45 @@ pub enum Action {
     ThreadToggleArchivedView,
     /// Open the Ctrl+P command palette.
     OpenCommandPalette,
+    /// Open the Ctrl+K cleaning scope picker (palette in scope mode).
+    OpenCleanScopePicker,
+    /// Open the Ctrl+B markers palette: tag messages and jump to tagged ones.
+    OpenMarkers,
     /// Reset the session cost counters to zero.
     ResetSessionCosts,
     /// Jump to a specific context panel by ID string (e.g., `"P3"`).
     SelectContextById(String),
+    /// Alt+1..9: jump to the panel in this quick slot, or bind/unbind the
+    /// selected panel.
+    QuickSlot(u8),
+    /// `1`..`9` in the Recent panel: reopen (or jump to) the file on that line.
+    ReopenRecent(u8),
+    /// `1`..`9` in the Bookmarks panel: show the bookmarked line.
+    GotoBookmark(String),
+    /// `n`/`p`/`a`/`r` in the Review panel: move between hunks or decide on
+    /// the comments of the current one.
+    Review(ReviewMove),
+    /// `n`/`p`/`a`/`r` (`A`/`R` for all) in the Approvals panel: move between
+    /// refused edits or decide on them.
+    Approvals(ApprovalMove),
+    /// `x` in the sidebar: close the selected dynamic panel.
+    CloseSelectedPanel,
+    /// Shift+Up / Shift+Down: move the selected dynamic panel up (`true`) or
+    /// down the sidebar and the request.
+    MoveSelectedPanel(bool),
+    /// Close every deprecated panel (deleted file, finished console).
+    CloseDeprecatedPanels,
+    /// Ctrl+X: close the panels the context health check suggests.
+    CloseSuggestedPanels,
+    /// Ctrl+Z while a GC batch is undoable: reopen the panels the idle panel
+    /// GC closed last.
+    UndoPanelGc,
+    /// Start, step through, or skip the guided tour.
+    Tour(TourMove),
+    /// `?` / F1: show or hide the keybinding reference.
+    ToggleKeyHelp,
+    /// `i` on a selected panel: show or hide its token breakdown in the sidebar.
+    ToggleTokenDetail,
     /// No-op — used as a default / placeholder.
     None,
 }
@@ -192,3 +261,46 @@ pub enum ActionResult {
     /// Persist state and show a status-bar message.
     SaveMessage(String),
 }
+
+/// A move through the guided tour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourMove {
+    /// Show the first step (palette "Tour", or the first run).
+    Start,
+    /// Next step; past the last one the tour is finished.
+    Next,
+    /// Previous step.
+    Back,
+    /// Close the tour for good.
+    Skip,
+}
+
+/// A step through a loaded review, from the Review panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewMove {
+    /// Next hunk.
+    Next,
+    /// Previous hunk.
+    Prev,
+    /// Accept the current hunk's comments, then go to the next hunk.
+    Accept,
+    /// Reject the current hunk's comments, then go to the next hunk.
+    Reject,
+}
+
+/// A step through the refused edits, from the Approvals panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalMove {
+    /// Next edit.
+    Next,
+    /// Previous edit.
+    Prev,
+    /// Approve the selected edit's path.
+    Approve,
+    /// Reject the selected edit.
+    Reject,
+    /// Approve every listed edit.
+    ApproveAll,
+    /// Reject every listed edit.
+    RejectAll,
+}
@@ -1,4 +1,4 @@
-//! File path autocomplete triggered by `@` in the input field.
+//! File path and panel autocomplete triggered by `@` in the input field.
 //!
 //! Works like shell tab-completion:
 //! - Shows entries (files + folders) in the current directory
@@ -6,10 +6,15 @@
 //! - Tab on a folder → completes to `folder/` and shows its contents
 //! - Tab on a file → inserts the full path and closes
 //!
+//! While the query has no `/`, open panel IDs and a fuzzy search over every
+//! workspace file are listed too, so `@mainrs` finds `src/main.rs`.
+//!
 //! Stored in `State.module_data` via the TypeMap pattern (get_ext/set_ext).
 
 /// Maximum number of matches to display in the autocomplete popup.
 const MAX_VISIBLE: usize = 10;
+/// Maximum number of fuzzy workspace-file matches listed for a query.
+const MAX_FUZZY: usize = 50;
 
 /// A single entry in the autocomplete list.
 #[derive(Debug, Clone)]
@@ -18,6 +23,8 @@ pub struct Completion {
     pub name: String,
     /// Whether this entry is a directory.
     pub is_dir: bool,
+    /// Panel title when this entry is an open panel (`name` is then its ID).
+    pub panel: Option<String>,
 }
 
 impl Completion {
@@ -27,8 +34,40 @@ impl Completion {
     where
         S: Into<String>,
     {
-        Self { name: name.into(), is_dir }
+        Self { name: name.into(), is_dir, panel: None }
+    }
+
+    /// Build a completion entry for an open panel.
+    #[must_use]
+    pub fn panel(id: &str, title: &str) -> Self {
+        Self { name: id.to_owned(), is_dir: false, panel: Some(title.to_owned()) }
+    }
+}
+
+/// Bonus for a match at byte `idx` of `hay`: word boundaries and the file name
+/// (past `basename_start`) rank higher.
+fn boundary_bonus(hay: &str, idx: usize, basename_start: usize) -> u32 {
+    let at_boundary =
+        hay.get(..idx).and_then(|h| h.chars().next_back()).is_none_or(|c| matches!(c, '/' | '_' | '-' | '.'));
+    u32::from(at_boundary).saturating_mul(3).saturating_add(if idx >= basename_start { 2 } else { 0 })
+}
+
+/// Fuzzy subsequence score of `query` in `candidate` (case-insensitive, higher
+/// is better), or `None` when the query characters do not appear in order.
+#[must_use]
+pub fn fuzzy_score(candidate: &str, query: &str) -> Option<u32> {
+    let hay = candidate.to_lowercase();
+    let basename_start = hay.rfind('/').map_or(0, |i| i.saturating_add(1));
+    let mut score = 0u32;
+    let mut pos = 0usize;
+    for qc in query.to_lowercase().chars() {
+        let idx = pos.saturating_add(hay.get(pos..)?.find(qc)?);
+        let contiguous = pos > 0 && idx == pos;
+        score = score.saturating_add(1).saturating_add(boundary_bonus(&hay, idx, basename_start));
+        score = score.saturating_add(if contiguous { 4 } else { 0 });
+        pos = idx.saturating_add(qc.len_utf8());
     }
+    Some(score)
 }
 
 /// State for the @-triggered file path autocomplete popup.
@@ -53,6 +92,12 @@ pub struct Suggestions {
     /// Number of visual lines the input area occupies (set by conversation panel render).
     /// Used to position the popup just above the input field.
     pub input_visual_lines: u16,
+    /// Workspace file paths captured at activation (fuzzy search corpus).
+    pub workspace_files: Vec<String>,
+    /// Open panels as `(id, title)`, captured at activation.
+    pub panels: Vec<(String, String)>,
+    /// File paths inserted from the popup since the last send.
+    pub mentioned: Vec<String>,
 }
 
 impl Default for Suggestions {
@@ -75,6 +120,9 @@ impl Suggestions {
             selected: 0,
             scroll_offset: 0,
             input_visual_lines: 2,
+            workspace_files: Vec::new(),
+            panels: Vec::new(),
+            mentioned: Vec::new(),
         }
     }
 
@@ -100,6 +148,39 @@ impl Suggestions {
         self.matches.clear();
         self.selected = 0;
         self.scroll_offset = 0;
+        self.workspace_files.clear();
+        self.panels.clear();
+    }
+
+    /// Full match list for the current query: with a `/`, just `dir_entries`;
+    /// otherwise matching panels, then `dir_entries`, then fuzzy file matches.
+    #[must_use]
+    pub fn build_matches(&self, dir_entries: Vec<Completion>) -> Vec<Completion> {
+        if self.query.contains('/') {
+            return dir_entries;
+        }
+        let query_lower = self.query.to_lowercase();
+        let mut out: Vec<Completion> = self
+            .panels
+            .iter()
+            .filter(|panel| {
+                panel.0.to_lowercase().starts_with(&query_lower) || fuzzy_score(&panel.1, &self.query).is_some()
+            })
+            .map(|panel| Completion::panel(&panel.0, &panel.1))
+            .collect();
+        out.extend(dir_entries);
+        if self.query.is_empty() {
+            return out;
+        }
+        let mut scored: Vec<(u32, &String)> = self
+            .workspace_files
+            .iter()
+            .filter(|f| !out.iter().any(|e| e.name == **f))
+            .filter_map(|f| fuzzy_score(f, &self.query).map(|score| (score, f)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.len().cmp(&b.1.len())));
+        out.extend(scored.into_iter().take(MAX_FUZZY).map(|(_, f)| Completion::new(f.clone(), false)));
+        out
     }
 
     /// Append a character to the query. Caller must call `set_matches()` afterward.
@@ -204,3 +285,31 @@ impl Suggestions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_and_boundary_matches_rank_first() {
+        assert_eq!(fuzzy_score("src/main.rs", "xyz"), None);
+        assert_eq!(fuzzy_score("src/main.rs", "sm"), fuzzy_score("src/main.rs", "SM"));
+        let in_name = fuzzy_score("src/main.rs", "main");
+        let scattered = fuzzy_score("src/mod/app/in.rs", "main");
+        assert!(in_name > scattered, "{in_name:?} vs {scattered:?}");
+    }
+
+    #[test]
+    fn panels_then_entries_then_ranked_files() {
+        let mut suggestions = Suggestions::new();
+        suggestions.query = "mainrs".to_owned();
+        suggestions.panels = vec![("P3".to_owned(), "main.rs".to_owned()), ("P4".to_owned(), "Todo".to_owned())];
+        suggestions.workspace_files =
+            ["crates/domain/ui/runner.rs", "src/main.rs", "docs/readme.md"].map(str::to_owned).to_vec();
+        let names: Vec<String> = suggestions.build_matches(Vec::new()).into_iter().map(|c| c.name).collect();
+        assert_eq!(names, ["P3", "src/main.rs", "crates/domain/ui/runner.rs"]);
+        suggestions.query = "src/ma".to_owned();
+        let listed = suggestions.build_matches(vec![Completion::new("main.rs", false)]);
+        assert_eq!(listed.len(), 1);
+    }
+}
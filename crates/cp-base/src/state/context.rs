@@ -8,7 +8,7 @@ use crate::cast::Safe;
 use crate::config::accessors::active_theme;
 use crate::config::constants::CHARS_PER_TOKEN;
 use crate::config::normalize_icon;
-use crate::panels::ContextItem;
+use crate::state::data::context_item::ContextItem;
 
 // =============================================================================
 // Kind Registry — modules register metadata at startup
@@ -118,6 +118,8 @@ impl Kind {
     pub const GIT: &str = "git";
     /// Git command result panel.
     pub const GIT_RESULT: &str = "git_result";
+    /// Repository activity feed panel.
+    pub const GIT_ACTIVITY: &str = "git_activity";
     /// GitHub CLI result panel.
     pub const GITHUB_RESULT: &str = "github_result";
     /// Scratchpad cells panel.
@@ -146,8 +148,30 @@ impl Kind {
     pub const ENTITIES: &str = "entities";
     /// Entity SQL query result panel (dynamic, for large results).
     pub const ENTITY_RESULT: &str = "entity_result";
+    /// Database schema panel (tables and columns of the connected database).
+    pub const DB_SCHEMA: &str = "db_schema";
+    /// Python kernel panel (variables defined by `py_exec` cells).
+    pub const PYTHON_KERNEL: &str = "python_kernel";
+    /// Neovim diagnostics panel (LSP diagnostics of the connected editor).
+    pub const NVIM_DIAGNOSTICS: &str = "nvim_diagnostics";
     /// Threads panel (parallel discussion topics).
     pub const THREADS: &str = "threads";
+    /// Cleaner panel (context optimizer runs and undo).
+    pub const CLEANER: &str = "cleaner";
+    /// Actions panel (changelog of files touched, commands run, commits made).
+    pub const LEDGER: &str = "ledger";
+    /// Pasted content moved out of the input into its own panel.
+    pub const PASTED: &str = "pasted";
+    /// Recent panel (files opened or edited this session, newest first).
+    pub const RECENT: &str = "recent";
+    /// Bookmarks panel (labelled `path:line` anchors).
+    pub const BOOKMARKS: &str = "bookmarks";
+    /// Review panel (a diff walked hunk by hunk, with the agent's comments).
+    pub const REVIEW: &str = "review";
+    /// Code Debt panel (TODO/FIXME/HACK comments found in the codebase).
+    pub const CODE_DEBT: &str = "code_debt";
+    /// Approvals panel (refused edits waiting for the user's decision).
+    pub const APPROVALS: &str = "approvals";
 
     /// Returns true if this is a fixed/system context type (looked up from registry).
     #[must_use]
@@ -192,6 +216,23 @@ pub struct EmittedState {
     pub context: Option<ContextItem>,
     /// SHA-256 of the content that was emitted (for change detection).
     pub hash: Option<String>,
+    /// Per-line hashes of the full content the LLM last saw, for panels that
+    /// send only their delta on refresh.
+    pub sent_lines: Option<Vec<u64>>,
+    /// Line hashes diffed against this tick; promoted to `sent_lines` once
+    /// the freeze pass confirms the panel was emitted fresh.
+    pub pending_lines: Option<Vec<u64>>,
+    /// Version of the emitted content, bumped on every fresh emission of
+    /// changed content — the version the LLM last saw.
+    pub version: u32,
+    /// The panel's content changed since `version` but the freeze kept the
+    /// older snapshot in the prompt.
+    pub stale: bool,
+    /// The LLM was already warned about the current staleness.
+    pub stale_noted: bool,
+    /// `panel_refresh` asked for the latest content on the next emission,
+    /// bypassing the freeze.
+    pub refresh_requested: bool,
 }
 
 impl EmittedState {
@@ -199,7 +240,16 @@ impl EmittedState {
     /// its content hash.
     #[must_use]
     pub const fn new(context: Option<ContextItem>, hash: Option<String>) -> Self {
-        Self { context, hash }
+        Self {
+            context,
+            hash,
+            sent_lines: None,
+            pending_lines: None,
+            version: 0,
+            stale: false,
+            stale_noted: false,
+            refresh_requested: false,
+        }
     }
 }
 
@@ -220,6 +270,15 @@ pub struct ScrollState {
 // Entry
 // =============================================================================
 
+/// Why a panel's content is left unloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unloaded {
+    /// Dropped by the cache memory budget.
+    Evicted,
+    /// Seeding deferred at startup until the panel is viewed or sent.
+    Deferred,
+}
+
 /// A single context panel in the LLM prompt — the core unit of the context window.
 /// Fixed panels (P1–P7) are always present; dynamic panels (P8+) are created by tools.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -254,6 +313,9 @@ pub struct Entry {
     /// A cache request is already in-flight for this element (prevents duplicate spawning)
     #[serde(skip)]
     pub cache_in_flight: bool,
+    /// Content left unloaded on purpose; loaded when selected or sent
+    #[serde(skip)]
+    pub cache_unloaded: Option<Unloaded>,
     /// Last time this element was refreshed (content actually changed — for display "refreshed X ago")
     #[serde(skip)]
     pub last_refresh_ms: u64,
@@ -309,6 +371,13 @@ pub struct Entry {
 
 // === Entry metadata helpers ===
 impl Entry {
+    /// Content left unloaded on purpose: not fetched in the background until
+    /// selected, and loaded before a request.
+    #[must_use]
+    pub const fn awaits_load(&self) -> bool {
+        self.cache_unloaded.is_some()
+    }
+
     /// Set the dynamic-panel UID (builder).
     #[must_use]
     pub fn with_uid(mut self, uid: String) -> Self {
@@ -441,6 +510,7 @@ pub fn make_default_entry(id: &str, context_type: Kind, name: &str, cache_deprec
         history_messages: None,
         cache_deprecated,
         cache_in_flight: false,
+        cache_unloaded: None,
         last_refresh_ms: crate::panels::now_ms(),
         content_hash: None,
         source_hash: None,
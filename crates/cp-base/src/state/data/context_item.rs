@@ -0,0 +1,126 @@
+//! The serialized form of a panel: what the LLM sees, with where it came from.
+//!
+//! Every item carries a [`Provenance`] — the source it was read from and how
+//! far it can be trusted — printed under its banner, so the model can tell
+//! the user's own notes and the repository apart from fetched web pages.
+
+/// How far a panel's content can be trusted as instructions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Trust {
+    /// Written by the user or the agent itself (notes, todos, conversation).
+    #[default]
+    User,
+    /// Read from the workspace: files, git, command output.
+    Repo,
+    /// Fetched from the internet; data only, never instructions.
+    ExternalWeb,
+}
+
+impl Trust {
+    /// Label used in the provenance line.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Repo => "repo",
+            Self::ExternalWeb => "external-web",
+        }
+    }
+}
+
+/// Where a panel's content came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// Path, command, URL or query the content was produced from.
+    pub source: Option<String>,
+    /// Trust level of the content.
+    pub trust: Trust,
+}
+
+impl Provenance {
+    /// Content read from the workspace through `source`.
+    pub fn repo<S>(source: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { source: Some(source.into()), trust: Trust::Repo }
+    }
+
+    /// Content fetched from the internet through `source`.
+    pub fn external_web<S>(source: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { source: Some(source.into()), trust: Trust::ExternalWeb }
+    }
+
+    /// The `[provenance: …]` line printed under a panel banner.
+    #[must_use]
+    pub fn line(&self) -> String {
+        let trust = self.trust.label();
+        self.source.as_ref().map_or_else(
+            || format!("[provenance: trust={trust}]"),
+            |source| format!("[provenance: source={source}; trust={trust}]"),
+        )
+    }
+}
+
+/// A single context item to be sent to the LLM
+#[derive(Debug, Clone)]
+pub struct ContextItem {
+    /// Context element ID (e.g., "P7", "P8") for LLM reference
+    pub id: String,
+    /// Header/title for this context (e.g., "File: src/main.rs" or "Todo List")
+    pub header: String,
+    /// The actual content
+    pub content: String,
+    /// Last refresh timestamp in milliseconds since UNIX epoch (for sorting panels)
+    pub last_refresh_ms: u64,
+    /// Source and trust level of the content (user-authored unless set).
+    pub provenance: Provenance,
+}
+
+impl ContextItem {
+    /// Create a context item from its components.
+    pub fn new<I, H, C>(id: I, header: H, content: C, last_refresh_ms: u64) -> Self
+    where
+        I: Into<String>,
+        H: Into<String>,
+        C: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            header: header.into(),
+            content: content.into(),
+            last_refresh_ms,
+            provenance: Provenance::default(),
+        }
+    }
+
+    /// Set where the content came from (builder).
+    #[must_use]
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = provenance;
+        self
+    }
+
+    /// The `======= [P7] File: src/main.rs =======` line and the provenance
+    /// line that precede the content in the prompt.
+    #[must_use]
+    pub fn banner(&self) -> String {
+        format!("======= [{}] {} =======\n{}", self.id, self.header, self.provenance.line())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banner_carries_provenance() {
+        let note = ContextItem::new("P3", "Todo List", "- [ ] ship", 0);
+        assert_eq!(note.banner(), "======= [P3] Todo List =======\n[provenance: trust=user]");
+        let page = note.with_provenance(Provenance::external_web("https://example.com"));
+        assert_eq!(page.provenance.line(), "[provenance: source=https://example.com; trust=external-web]");
+    }
+}
@@ -1,5 +1,9 @@
+use std::borrow::Cow;
+
 use serde::{Deserialize, Serialize};
 
+use super::review::MsgMarker;
+
 /// Discriminator for the three message shapes in a conversation.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -20,12 +24,52 @@ pub enum MsgStatus {
     /// Included in full in the LLM prompt.
     #[default]
     Full,
+    /// Sent with only its first and last paragraphs; the middle is elided.
+    HeadTail,
+    /// Sent as a one-line stub naming the message ID.
+    Reference,
     /// Removed from context (freed budget).
     Deleted,
     /// Archived into a conversation history panel.
     Detached,
 }
 
+impl MsgStatus {
+    /// Whether the message still reaches the LLM, but not verbatim.
+    #[must_use]
+    pub const fn is_compressed(self) -> bool {
+        matches!(self, Self::HeadTail | Self::Reference)
+    }
+
+    /// Apply this compression level to `text` belonging to message `id`.
+    /// `Full` (and the statuses that are never sent) return `text` unchanged.
+    #[must_use]
+    pub fn compress<'text>(self, id: &str, text: &'text str) -> Cow<'text, str> {
+        match self {
+            Self::HeadTail => head_tail(id, text),
+            Self::Reference if !text.trim().is_empty() => Cow::Owned(format!(
+                "[{id} compressed to a reference \u{2014} {} chars omitted; message_expand restores it]",
+                text.chars().count()
+            )),
+            Self::Full | Self::Reference | Self::Deleted | Self::Detached => Cow::Borrowed(text),
+        }
+    }
+}
+
+/// Keep the first and last paragraphs of `text`, replacing the middle with
+/// a marker. Texts of two paragraphs or fewer are returned unchanged.
+fn head_tail<'text>(id: &str, text: &'text str) -> Cow<'text, str> {
+    let paragraphs: Vec<&str> = text.split("\n\n").filter(|p| !p.trim().is_empty()).collect();
+    let (Some(first), Some(last)) = (paragraphs.first(), paragraphs.last()) else {
+        return Cow::Borrowed(text);
+    };
+    if paragraphs.len() <= 2 {
+        return Cow::Borrowed(text);
+    }
+    let elided = paragraphs.len().saturating_sub(2);
+    Cow::Owned(format!("{first}\n\n[\u{2026} {elided} paragraph(s) of {id} elided \u{2026}]\n\n{last}"))
+}
+
 /// Record of a single tool invocation inside a [`Message`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolUseRecord {
@@ -101,6 +145,19 @@ impl ToolResultRecord {
     }
 }
 
+/// Timing of the request that produced an assistant message, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Latency {
+    /// Request sent → first streamed event (time to first token).
+    pub ttft: u64,
+    /// Request sent → stream finished (model time).
+    pub stream: u64,
+    /// Stream finished → next request sent, spent running the tools it
+    /// called (0 when it called none).
+    #[serde(default)]
+    pub tools: u64,
+}
+
 /// A single message in the conversation — user text, assistant text,
 /// tool call, or tool result. The atomic unit of the message history.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -123,6 +180,9 @@ pub struct Message {
     /// Message status for context management.
     #[serde(default)]
     pub status: MsgStatus,
+    /// Sent verbatim despite `status` until the next user turn (`message_expand`).
+    #[serde(skip)]
+    pub expanded: bool,
     /// Tool uses in this message (for assistant messages).
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tool_uses: Vec<ToolUseRecord>,
@@ -135,9 +195,36 @@ pub struct Message {
     /// Timestamp when this message was created (ms since UNIX epoch).
     #[serde(default)]
     pub timestamp_ms: u64,
+    /// Review markers tagged by the user (markers palette, Ctrl+B).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<MsgMarker>,
+    /// Private note by the user (`/note`): shown dimmed under the message,
+    /// never sent to the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Request timing of an assistant message (shown in dev mode).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<Latency>,
 }
 
 impl Message {
+    /// The message's text content as the LLM sees it at its compression level.
+    #[must_use]
+    pub fn sent_content(&self) -> Cow<'_, str> {
+        self.sent_level().compress(&self.id, &self.content)
+    }
+
+    /// A tool result's content as the LLM sees it at this message's compression level.
+    #[must_use]
+    pub fn sent_result<'text>(&self, result: &'text ToolResultRecord) -> Cow<'text, str> {
+        self.sent_level().compress(&self.id, &result.content)
+    }
+
+    /// Compression level actually applied: `Full` while temporarily expanded.
+    const fn sent_level(&self) -> MsgStatus {
+        if self.expanded { MsgStatus::Full } else { self.status }
+    }
+
     /// Create a new user text message with the given ID, UID, and content.
     #[must_use]
     pub fn new_user(id: String, uid: String, content: String, token_count: usize) -> Self {
@@ -149,6 +236,10 @@ impl Message {
             content,
             content_token_count: token_count,
             status: MsgStatus::Full,
+            expanded: false,
+            markers: Vec::new(),
+            note: None,
+            latency: None,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
             input_tokens: 0,
@@ -167,6 +258,10 @@ impl Message {
             content: String::new(),
             content_token_count: 0,
             status: MsgStatus::Full,
+            expanded: false,
+            markers: Vec::new(),
+            note: None,
+            latency: None,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
             input_tokens: 0,
@@ -185,6 +280,10 @@ impl Message {
             content: String::new(),
             content_token_count: 0,
             status: MsgStatus::Full,
+            expanded: false,
+            markers: Vec::new(),
+            note: None,
+            latency: None,
             tool_uses,
             tool_results: Vec::new(),
             input_tokens: 0,
@@ -203,6 +302,10 @@ impl Message {
             content: String::new(),
             content_token_count: 0,
             status: MsgStatus::Full,
+            expanded: false,
+            markers: Vec::new(),
+            note: None,
+            latency: None,
             tool_uses: Vec::new(),
             tool_results,
             input_tokens: 0,
@@ -222,6 +325,10 @@ impl Message {
             content,
             content_token_count: 0,
             status: MsgStatus::Full,
+            expanded: false,
+            markers: Vec::new(),
+            note: None,
+            latency: None,
             tool_uses: Vec::new(),
             tool_results: Vec::new(),
             input_tokens: 0,
@@ -262,6 +369,10 @@ pub mod test_helpers {
                     content: String::new(),
                     content_token_count: 0,
                     status: MsgStatus::Full,
+                    expanded: false,
+                    markers: Vec::new(),
+                    note: None,
+                    latency: None,
                     tool_uses: Vec::new(),
                     tool_results: Vec::new(),
                     input_tokens: 0,
@@ -337,7 +448,8 @@ pub mod test_helpers {
 ///
 /// Skips Deleted/Detached messages. Uses the same format the LLM sees:
 /// tool calls as `tool_call name(json)`, tool results as raw content,
-/// and text messages as `[role]: content`.
+/// and text messages as `[role]: content`. Compressed messages keep their
+/// compressed form.
 #[must_use]
 pub fn format_messages_to_chunk(messages: &[Message]) -> String {
     use std::fmt::Write as _;
@@ -363,16 +475,37 @@ pub fn format_messages_to_chunk(messages: &[Message]) -> String {
                     // When detaching into history, prefer the tldr so verbose
                     // Think bodies shrink to their essence. Falls through to
                     // the full content when no tldr was provided.
-                    let body = tr.tldr.as_deref().unwrap_or(&tr.content);
+                    let body = tr.tldr.as_deref().map_or_else(|| msg.sent_result(tr), Cow::Borrowed);
                     let _r = writeln!(output, "{body}");
                 }
             }
             MsgKind::TextMessage => {
                 if !msg.content.is_empty() {
-                    let _r = writeln!(output, "[{}]: {}", msg.role, msg.content);
+                    let _r = writeln!(output, "[{}]: {}", msg.role, msg.sent_content());
                 }
             }
         }
     }
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_tail_keeps_first_and_last_paragraphs() {
+        let text = "Intro.\n\nMiddle one.\n\nMiddle two.\n\nConclusion.";
+        let sent = MsgStatus::HeadTail.compress("A4", text);
+        assert_eq!(sent, "Intro.\n\n[\u{2026} 2 paragraph(s) of A4 elided \u{2026}]\n\nConclusion.");
+        assert_eq!(MsgStatus::HeadTail.compress("A4", "Short.\n\nReply."), "Short.\n\nReply.");
+    }
+
+    #[test]
+    fn reference_is_a_one_line_stub() {
+        let sent = MsgStatus::Reference.compress("R7", "line one\nline two");
+        assert_eq!(sent, "[R7 compressed to a reference \u{2014} 17 chars omitted; message_expand restores it]");
+        assert_eq!(MsgStatus::Reference.compress("T2", ""), "");
+        assert_eq!(MsgStatus::Full.compress("U1", "kept"), "kept");
+    }
+}
@@ -2,10 +2,16 @@
 
 /// Persistence structs: `config::Shared`, `WorkerState`, `PanelData`.
 pub mod config;
+/// Serialized panels (`ContextItem`) and their provenance.
+pub mod context_item;
 /// Message struct and conversation formatting.
 pub mod message;
 /// Model selection, pricing, and cleaning-threshold helpers for [`super::runtime::State`].
 pub mod model_helpers;
+/// Review markers on messages and the jump to a marked message.
+pub mod review;
+/// Standing user instructions injected at the end of every request.
+pub mod sticky;
 
 // ─── Per-tick cache-break telemetry ─────────────────────────────────────────
 
@@ -6,11 +6,15 @@
 
 use super::super::runtime::State;
 use crate::config::llm_types::{LlmProvider, ModelInfo as _};
+use crate::config::models::{AnthropicModel, ClaudeCodeV2Model, DeepSeekModel, GrokModel, GroqModel, MiniMaxModel};
 
 /// Model-selection, pricing, and context-budget helpers for [`State`].
 pub trait ModelPricing {
     /// API model string for the active provider/model selection.
     fn current_model(&self) -> String;
+    /// API model string of the active provider's cheapest model, for
+    /// background chores (summaries, reports).
+    fn cheap_model(&self) -> String;
     /// Max output tokens for the active provider/model.
     fn current_max_output_tokens(&self) -> u32;
     /// Context window size (tokens) for the active model.
@@ -43,6 +47,20 @@ impl ModelPricing for State {
         }
     }
 
+    fn cheap_model(&self) -> String {
+        let name = match self.llm_provider {
+            LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
+                AnthropicModel::ClaudeHaiku45.api_name()
+            }
+            LlmProvider::Grok => GrokModel::Grok41Fast.api_name(),
+            LlmProvider::Groq => GroqModel::GptOss20b.api_name(),
+            LlmProvider::DeepSeek => DeepSeekModel::V4Flash.api_name(),
+            LlmProvider::MiniMax => MiniMaxModel::M27.api_name(),
+            LlmProvider::ClaudeCodeV2 => ClaudeCodeV2Model::ClaudeSonnet46.api_name(),
+        };
+        name.to_owned()
+    }
+
     fn current_max_output_tokens(&self) -> u32 {
         match self.llm_provider {
             LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => {
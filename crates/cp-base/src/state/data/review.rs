@@ -0,0 +1,97 @@
+//! Review markers and private notes on the conversation.
+//!
+//! Markers (⭐ important, ❓ revisit, 🐛 bug) and notes tag messages; notes
+//! also tag panels. Also the pending scroll to a marked message.
+
+use serde::{Deserialize, Serialize};
+
+/// A review marker tagged onto a [`super::message::Message`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MsgMarker {
+    /// A decision or result worth keeping.
+    Important,
+    /// Something to come back to.
+    Revisit,
+    /// Something went wrong here.
+    Bug,
+}
+
+impl MsgMarker {
+    /// All markers, in display order (Alt+1, Alt+2, Alt+3 in the markers palette).
+    pub const ALL: [Self; 3] = [Self::Important, Self::Revisit, Self::Bug];
+
+    /// Glyph shown beside the message.
+    #[must_use]
+    pub const fn icon(self) -> &'static str {
+        match self {
+            Self::Important => "\u{2b50}",
+            Self::Revisit => "\u{2753}",
+            Self::Bug => "\u{1f41b}",
+        }
+    }
+
+    /// Lowercase name, also a palette search keyword.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Important => "important",
+            Self::Revisit => "revisit",
+            Self::Bug => "bug",
+        }
+    }
+
+    /// The marker bound to digit key `c` (`'1'` = important).
+    #[must_use]
+    pub fn for_digit(c: char) -> Option<Self> {
+        let index = c.to_digit(10)?.checked_sub(1)?;
+        Self::ALL.get(usize::try_from(index).ok()?).copied()
+    }
+
+    /// Add `self` to `markers` or remove it if present, keeping display order.
+    pub fn toggle_in(self, markers: &mut Vec<Self>) {
+        if markers.contains(&self) {
+            markers.retain(|&m| m != self);
+        } else {
+            markers.push(self);
+            markers.sort_by_key(|m| Self::ALL.iter().position(|a| a == m));
+        }
+    }
+
+    /// Glyphs of `markers`, concatenated.
+    #[must_use]
+    pub fn icons(markers: &[Self]) -> String {
+        markers.iter().map(|m| m.icon()).collect()
+    }
+}
+
+/// Panel metadata key of the user's private note (`/note P3 ...`). Like a
+/// message's `note`, it is only rendered, never sent to the model.
+pub const PANEL_NOTE_META: &str = "user_note";
+
+/// Pending scroll of the conversation to a message (stored in `State`'s
+/// `TypeMap`; set by the markers palette, consumed by the renderer).
+#[derive(Debug, Clone, Default)]
+pub struct MessageJump {
+    /// Display ID of the message to bring into view.
+    pub message_id: String,
+    /// Its first line in the conversation, once the content builder found it.
+    pub line: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggling_keeps_display_order() {
+        let mut markers = Vec::new();
+        MsgMarker::Bug.toggle_in(&mut markers);
+        MsgMarker::Important.toggle_in(&mut markers);
+        assert_eq!(markers, [MsgMarker::Important, MsgMarker::Bug]);
+        MsgMarker::Important.toggle_in(&mut markers);
+        assert_eq!(markers, [MsgMarker::Bug]);
+        assert_eq!(MsgMarker::for_digit('2'), Some(MsgMarker::Revisit));
+        assert_eq!(MsgMarker::for_digit('4'), None);
+    }
+}
@@ -0,0 +1,35 @@
+//! Standing instructions — user rules sent with every request.
+//!
+//! Edited in the input box (the draft is stashed meanwhile) and persisted as
+//! worker data. They are appended to the last user turn of each request rather
+//! than stored as a message, so cleaning, compression and detachment never
+//! touch them.
+
+use serde::{Deserialize, Serialize};
+
+/// The user's standing instructions plus the in-progress edit state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StandingInstructions {
+    /// Instruction text (empty = none).
+    #[serde(default)]
+    pub text: String,
+    /// Input draft stashed while the instructions occupy the input box.
+    #[serde(skip)]
+    pub stashed_draft: Option<String>,
+}
+
+impl StandingInstructions {
+    /// Whether the input box is currently editing the instructions.
+    #[must_use]
+    pub const fn is_editing(&self) -> bool {
+        self.stashed_draft.is_some()
+    }
+
+    /// Text injected into the request, or `None` when there are no instructions.
+    #[must_use]
+    pub fn prompt_text(&self) -> Option<String> {
+        let text = self.text.trim();
+        (!text.is_empty())
+            .then(|| crate::config::INJECTIONS.providers.standing_instructions.replace("{instructions}", text))
+    }
+}
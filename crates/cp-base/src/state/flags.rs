@@ -63,6 +63,10 @@ pub struct StreamState {
     pub phase: StreamPhase,
     /// Whether the user has manually scrolled (disables auto-scroll to bottom).
     pub user_scrolled: bool,
+    /// When the last stream event arrived (ms since epoch), for stall detection.
+    pub last_delta_ms: u64,
+    /// Seconds without deltas before the stream is shown as stalled.
+    pub stall_secs: u64,
 }
 
 /// UI and lifecycle status flags — separated from [`StreamState`] to stay under
@@ -84,6 +88,8 @@ pub struct ConfigOverlay {
     pub config_view: bool,
     /// Whether the reverie system is enabled (auto-trigger on threshold breach).
     pub reverie_enabled: bool,
+    /// Open files picked from the `@` popup as panels when the message is sent.
+    pub mention_auto_open: bool,
 }
 
 /// Lifecycle flags for async operations and reload state.
@@ -95,6 +101,8 @@ pub struct Lifecycle {
     pub reload_pending: bool,
     /// Waiting for file panels to load before continuing stream.
     pub waiting_for_panels: bool,
+    /// When the connectivity monitor found the provider unreachable; `None` while online.
+    pub offline_since_ms: Option<u64>,
 }
 
 /// Module-specific overlay flags — kept separate from core UI flags
@@ -106,6 +114,15 @@ pub struct ModuleOverlays {
     /// Timestamp (ms since epoch) of the last "Copied!" flash.
     /// Overlay shows a brief "✓ Copied!" when `now < copied_flash_ms + 1500`.
     pub copied_flash_ms: u64,
+    /// Timestamp (ms since epoch) of the last spine cue flash; 0 when idle.
+    /// The status bar is highlighted while set (cleared by the spine after the flash).
+    pub cue_flash_ms: u64,
+    /// Guided tour step on screen; `None` when the tour is closed.
+    pub tour_step: Option<usize>,
+    /// Keybinding reference overlay (`?` / F1) is visible.
+    pub key_help: bool,
+    /// The sidebar shows the selected panel's token breakdown (`i` to toggle).
+    pub token_detail: bool,
 }
 
 /// Composite of all boolean status flags, organized by domain.
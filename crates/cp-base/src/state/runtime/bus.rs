@@ -0,0 +1,184 @@
+//! Typed event bus: one bounded, prioritized queue per event type.
+//!
+//! Background threads publish with [`publish`] — or [`publish_wait`], which
+//! blocks while the topic is full — and the main loop takes everything queued
+//! with [`drain`]. A topic is created the first time its event type is named,
+//! so a new subsystem needs no `Sender` threaded through `App`: publishing an
+//! event and draining it by type is the whole contract.
+//!
+//! Each topic holds at most [`CAPACITY`] normal and low events. When it is
+//! full, a normal event evicts the oldest low one (or is refused if there is
+//! none), a low event evicts the oldest low one, and a high event is always
+//! accepted. [`drain`] yields high before normal before low, FIFO within a
+//! priority.
+
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Condvar, LazyLock, Mutex, PoisonError};
+
+/// Normal plus low events a topic holds before applying backpressure.
+pub const CAPACITY: usize = 4096;
+
+/// Delivery priority of a published event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Control events; never refused and drained first.
+    High,
+    /// Ordinary events; refused when the topic is full of them.
+    Normal,
+    /// Lossy events; the oldest is shed to make room.
+    Low,
+}
+
+/// An event the bus refused because its topic was full.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Full<E>(pub E);
+
+/// The three queues of a topic.
+struct Queues<E> {
+    /// High-priority events, unbounded.
+    high: VecDeque<E>,
+    /// Normal-priority events.
+    normal: VecDeque<E>,
+    /// Low-priority events.
+    low: VecDeque<E>,
+}
+
+impl<E> Queues<E> {
+    /// Whether the bounded queues are at [`CAPACITY`].
+    fn is_full(&self) -> bool {
+        self.normal.len().saturating_add(self.low.len()) >= CAPACITY
+    }
+
+    /// Enqueue `event`, handing it back when the topic has no room.
+    fn push(&mut self, event: E, priority: Priority) -> Result<(), E> {
+        if priority != Priority::High && self.is_full() && self.low.pop_front().is_none() {
+            return Err(event);
+        }
+        match priority {
+            Priority::High => self.high.push_back(event),
+            Priority::Normal => self.normal.push_back(event),
+            Priority::Low => self.low.push_back(event),
+        }
+        Ok(())
+    }
+}
+
+/// The queues of one event type, plus the condition producers wait on.
+struct Topic<E> {
+    /// Pending events.
+    queues: Mutex<Queues<E>>,
+    /// Signalled whenever the topic is drained.
+    space: Condvar,
+}
+
+/// A type-erased `&'static Topic<E>`.
+type AnyTopic = &'static (dyn Any + Send + Sync);
+
+/// Every topic created so far, keyed by event type. Topics live as long as
+/// the process.
+static TOPICS: LazyLock<Mutex<HashMap<TypeId, AnyTopic>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The topic carrying `E`, created on first use.
+fn topic<E>() -> &'static Topic<E>
+where
+    E: Send + 'static,
+{
+    let mut topics = TOPICS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(found) = topics.get(&TypeId::of::<E>()).copied().and_then(|t| t.downcast_ref::<Topic<E>>()) {
+        return found;
+    }
+    let created: &'static Topic<E> = Box::leak(Box::new(Topic {
+        queues: Mutex::new(Queues { high: VecDeque::new(), normal: VecDeque::new(), low: VecDeque::new() }),
+        space: Condvar::new(),
+    }));
+    let _previous = topics.insert(TypeId::of::<E>(), created);
+    created
+}
+
+/// Publish `event` without blocking.
+///
+/// # Errors
+///
+/// Returns the event in [`Full`] when its topic has no room (see the module
+/// docs for which priorities can be refused).
+pub fn publish<E>(event: E, priority: Priority) -> Result<(), Full<E>>
+where
+    E: Send + 'static,
+{
+    topic::<E>().queues.lock().unwrap_or_else(PoisonError::into_inner).push(event, priority).map_err(Full)
+}
+
+/// Publish `event`, blocking while its topic is full. For producers off the
+/// main loop — called from the thread that drains the topic it never returns.
+pub fn publish_wait<E>(event: E, priority: Priority)
+where
+    E: Send + 'static,
+{
+    let topic = topic::<E>();
+    let locked = topic.queues.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut queues = if priority == Priority::High {
+        locked
+    } else {
+        topic.space.wait_while(locked, |q| q.is_full() && q.low.is_empty()).unwrap_or_else(PoisonError::into_inner)
+    };
+    // Room was made above, so the push cannot be refused.
+    drop(queues.push(event, priority));
+}
+
+/// Take every queued `E`: high, then normal, then low. Wakes producers
+/// blocked in [`publish_wait`].
+pub fn drain<E>() -> Vec<E>
+where
+    E: Send + 'static,
+{
+    let topic = topic::<E>();
+    let mut queues = topic.queues.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut events: Vec<E> = queues.high.drain(..).collect();
+    events.extend(queues.normal.drain(..));
+    events.extend(queues.low.drain(..));
+    drop(queues);
+    topic.space.notify_all();
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drains_by_priority_then_fifo() {
+        #[derive(Debug, PartialEq, Eq)]
+        struct Ordered(u8);
+        for (n, priority) in [(1, Priority::Low), (2, Priority::Normal), (3, Priority::High), (4, Priority::Normal)] {
+            assert_eq!(publish(Ordered(n), priority), Ok(()));
+        }
+        assert_eq!(drain::<Ordered>(), [Ordered(3), Ordered(2), Ordered(4), Ordered(1)]);
+        assert!(drain::<Ordered>().is_empty());
+    }
+
+    /// Queues one short of full: normal events, then a single low one.
+    fn nearly_full() -> Queues<usize> {
+        let mut queues = Queues { high: VecDeque::new(), normal: (1..CAPACITY).collect(), low: VecDeque::new() };
+        assert_eq!(queues.push(0, Priority::Low), Ok(()));
+        queues
+    }
+
+    #[test]
+    fn full_topic_sheds_low_before_refusing_normal() {
+        let mut queues = nearly_full();
+        assert_eq!(queues.push(CAPACITY, Priority::Normal), Ok(()), "evicts the low event");
+        assert!(queues.low.is_empty());
+        assert_eq!(queues.push(1, Priority::Normal), Err(1));
+        assert_eq!(queues.push(2, Priority::Low), Err(2));
+    }
+
+    #[test]
+    fn full_topic_still_admits_high() {
+        let mut queues = nearly_full();
+        assert_eq!(queues.push(1, Priority::Low), Ok(()), "replaces the oldest low event");
+        assert_eq!(queues.low, [1]);
+        assert_eq!(queues.push(2, Priority::High), Ok(()));
+        assert_eq!(queues.high, [2]);
+    }
+}
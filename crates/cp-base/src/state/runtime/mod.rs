@@ -7,10 +7,12 @@ use super::data::config::ViewMode;
 use super::data::message::Message;
 use super::flags::{HighlightIrFn, StatusBools, StreamPhase, StreamingTool};
 use crate::config::llm_types::LlmProvider;
-use crate::panels::ContextItem;
+use crate::state::data::context_item::ContextItem;
 use crate::tools::ToolDefinition;
 use crate::ui::render_cache::{FullCache, InputCache, MessageCache};
 
+/// Typed event bus with priorities and backpressure, one topic per event type.
+pub mod bus;
 /// Ephemeral reverie sub-agent state (context optimizer, cartographer).
 pub mod reverie;
 
@@ -405,9 +407,12 @@ impl State {
     }
 
     /// Prepare state for a new stream: transition to [`StreamPhase::Receiving`],
-    /// clear stop reason, reset tick counters.
+    /// clear stop reason, reset tick counters and arm stall detection.
     pub fn begin_streaming(&mut self) {
         self.flags.stream.phase.transition(StreamPhase::Receiving);
+        self.flags.stream.last_delta_ms = crate::panels::now_ms();
+        self.flags.stream.stall_secs =
+            crate::config::llm_types::ProviderTimeouts::for_provider(self.llm_provider).stall;
         self.last_stop_reason = None;
         self.streaming_estimated_tokens = 0;
         self.tick_cache_hit_tokens = 0;
@@ -0,0 +1,235 @@
+//! Error clusters: one diagnostic repeated across many sites, shown once.
+//!
+//! A compiler run often reports the same error at dozens of call sites. The
+//! batch's tool results are scanned for diagnostics (`error[E0597]: ...` with
+//! a `--> file:line:col` line, or `file:line:col: error: ...`); a diagnostic
+//! seen at least [`MIN_SITES`] times keeps its first full report and every
+//! later one is replaced by a summary line listing the other sites. Messages
+//! are compared with their backticked and quoted names blanked, so "`a` does
+//! not live long enough" and "`b` does not live long enough" cluster too.
+
+use std::collections::{HashMap, HashSet};
+
+use super::ToolResult;
+
+/// Reports of one diagnostic needed before the repeats are folded.
+const MIN_SITES: usize = 3;
+
+/// Sites listed per summary line before "+N more".
+const MAX_LISTED: usize = 8;
+
+/// One diagnostic report in an output.
+#[derive(Debug)]
+struct Report {
+    /// First line of the report, index into the output's lines.
+    start: usize,
+    /// One past its last line.
+    end: usize,
+    /// What identical reports share: severity, code, blanked message.
+    key: String,
+    /// Where it points (`src/a.rs:10:5`).
+    site: String,
+}
+
+/// `file:line:col: error: msg` on one line: the site and the diagnostic.
+fn inline_header(line: &str) -> Option<(&str, &str)> {
+    ["error", "warning"].iter().find_map(|severity| {
+        let (site, _) = line.split_once(&format!(": {severity}"))?;
+        let has_position =
+            site.rsplit(':').next().is_some_and(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+        has_position.then(|| (site, line.get(site.len().saturating_add(2)..).unwrap_or_default()))
+    })
+}
+
+/// Whether `line` opens a rustc-style report (`error[E0597]: ...`, `warning: ...`).
+fn block_header(line: &str) -> bool {
+    ["error", "warning"]
+        .iter()
+        .any(|severity| line.strip_prefix(severity).is_some_and(|rest| rest.starts_with(':') || rest.starts_with('[')))
+}
+
+/// `diagnostic` with quoted names blanked, so reports about different names
+/// of the same mistake share a key.
+fn blank_names(diagnostic: &str) -> String {
+    let mut key = String::with_capacity(diagnostic.len());
+    let mut open: Option<char> = None;
+    for c in diagnostic.chars() {
+        match open {
+            Some(quote) if c == quote => {
+                key.push(c);
+                open = None;
+            }
+            Some(_) => {}
+            None => {
+                key.push(c);
+                if matches!(c, '`' | '\'' | '"') {
+                    key.push('_');
+                    open = Some(c);
+                }
+            }
+        }
+    }
+    key
+}
+
+/// The diagnostic reports of `lines`, in order. Reports without a site (the
+/// closing "could not compile" line) are left out.
+fn reports(lines: &[&str]) -> Vec<Report> {
+    let mut found = Vec::new();
+    let mut i = 0usize;
+    while let Some(&line) = lines.get(i) {
+        let next = i.saturating_add(1);
+        let (site, diagnostic, end) = if let Some((site, diagnostic)) = inline_header(line) {
+            let rest = lines.get(next..).unwrap_or_default();
+            let body = rest.iter().take_while(|l| l.starts_with(char::is_whitespace) && !l.trim().is_empty()).count();
+            (Some(site.trim().to_owned()), diagnostic, next.saturating_add(body))
+        } else if block_header(line) {
+            let rest = lines.get(next..).unwrap_or_default();
+            let body = rest.iter().take_while(|l| !l.trim().is_empty() && !block_header(l)).count();
+            let site = rest.iter().take(body).find_map(|l| l.trim_start().strip_prefix("--> ")).map(str::to_owned);
+            (site, line, next.saturating_add(body))
+        } else {
+            i = next;
+            continue;
+        };
+        if let Some(pointed) = site {
+            found.push(Report { start: i, end, key: blank_names(diagnostic.trim()), site: pointed });
+        }
+        i = end.max(next);
+    }
+    found
+}
+
+/// The summary line replacing the folded reports of `key`.
+fn summary(key: &str, sites: &[String]) -> String {
+    let listed = sites.iter().take(MAX_LISTED).map(String::as_str).collect::<Vec<_>>().join(", ");
+    let unlisted = sites.len().saturating_sub(MAX_LISTED);
+    let more = if unlisted > 0 { format!(" (+{unlisted} more)") } else { String::new() };
+    let count = sites.len();
+    let plural = if count == 1 { "" } else { "s" };
+    format!("\u{27f2} {key} \u{2014} same as above, at {count} more site{plural}: {listed}{more}")
+}
+
+/// What folding one output drops, and the summaries standing in for it.
+#[derive(Debug, Default)]
+struct Folding {
+    /// Lines removed, by index.
+    dropped: Vec<bool>,
+    /// Folded keys, in order of appearance, with the sites they were seen at.
+    sites: Vec<(String, Vec<String>)>,
+    /// Where the summaries go: the first removed report.
+    anchor: Option<usize>,
+}
+
+impl Folding {
+    /// Drop `report` (and the blank line after it) from `lines`.
+    fn drop_report(&mut self, lines: &[&str], report: &Report) {
+        let blank_after = lines.get(report.end).is_some_and(|l| l.trim().is_empty());
+        let stop = if blank_after { report.end.saturating_add(1) } else { report.end };
+        self.dropped.resize(lines.len(), false);
+        self.dropped.iter_mut().take(stop).skip(report.start).for_each(|d| *d = true);
+        let _anchor = self.anchor.get_or_insert(report.start);
+        match self.sites.iter_mut().find(|entry| entry.0 == report.key) {
+            Some(entry) => entry.1.push(report.site.clone()),
+            None => self.sites.push((report.key.clone(), vec![report.site.clone()])),
+        }
+    }
+
+    /// `lines` without the dropped reports, the summaries in their place;
+    /// `None` when nothing was dropped.
+    fn render(&self, lines: &[&str]) -> Option<String> {
+        let at = self.anchor?;
+        let mut out: Vec<String> = Vec::with_capacity(lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            if i == at {
+                out.extend(self.sites.iter().map(|entry| summary(&entry.0, &entry.1)));
+                out.push(String::new());
+            }
+            if !self.dropped.get(i).copied().unwrap_or(false) {
+                out.push((*line).to_owned());
+            }
+        }
+        Some(out.join("\n"))
+    }
+}
+
+/// `content` with every report of a folded key but the batch's first one
+/// replaced by a summary; `None` when nothing in it folds.
+fn fold(content: &str, counts: &HashMap<String, usize>, shown: &mut HashSet<String>) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut folding = Folding::default();
+    for report in reports(&lines) {
+        if counts.get(&report.key).copied().unwrap_or(0) >= MIN_SITES && !shown.insert(report.key.clone()) {
+            folding.drop_report(&lines, &report);
+        }
+    }
+    folding.render(&lines)
+}
+
+/// Fold repeated diagnostics across `results` (one batch, in order).
+pub fn collapse(results: &mut [ToolResult]) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for result in results.iter() {
+        let lines: Vec<&str> = result.content.lines().collect();
+        for report in reports(&lines) {
+            let count = counts.entry(report.key).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+    }
+    if counts.values().all(|&n| n < MIN_SITES) {
+        return;
+    }
+    let mut shown = HashSet::new();
+    for result in results.iter_mut() {
+        if let Some(folded) = fold(&result.content, &counts, &mut shown) {
+            result.content = folded;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A rustc report of the borrow error on `name` at `line`.
+    fn borrow_error(name: &str, line: u32) -> String {
+        format!(
+            "error[E0597]: `{name}` does not live long enough\n  --> src/lib.rs:{line}:5\n   |\n{line} |     \
+             let r = &{name};\n   |             ^^ borrowed value does not live long enough\n\n"
+        )
+    }
+
+    #[test]
+    fn repeated_errors_keep_one_report_and_list_the_sites() {
+        let output: String = [("a", 10), ("b", 20), ("c", 30), ("d", 40)]
+            .iter()
+            .map(|&(name, line)| borrow_error(name, line))
+            .chain(["error: could not compile `demo` due to 4 previous errors\n".to_owned()])
+            .collect();
+        let mut results = [ToolResult::new("t1".to_owned(), output, true)];
+        collapse(&mut results);
+        let text = results.first().map(|r| r.content.as_str()).unwrap_or_default();
+        assert!(text.contains("`a` does not live long enough") && !text.contains("`b`"));
+        assert!(text.contains("at 3 more sites: src/lib.rs:20:5, src/lib.rs:30:5, src/lib.rs:40:5"));
+        assert!(text.ends_with("could not compile `demo` due to 4 previous errors"));
+    }
+
+    #[test]
+    fn repeats_fold_across_the_batch_and_rare_errors_stay() {
+        let first = format!("{}{}", borrow_error("a", 1), "src/x.c:3:1: warning: unused variable 'n'\n");
+        let second = format!("{}{}", borrow_error("b", 2), borrow_error("c", 3));
+        let mut results =
+            [ToolResult::new("t1".to_owned(), first, true), ToolResult::new("t2".to_owned(), second, true)];
+        collapse(&mut results);
+        let texts: Vec<&str> = results.iter().map(|r| r.content.as_str()).collect();
+        assert!(texts.first().is_some_and(|t| t.contains("`a` does not") && t.contains("unused variable 'n'")));
+        assert!(texts.get(1).is_some_and(|t| !t.contains("live long enough\n") && t.contains("at 2 more sites")));
+    }
+
+    #[test]
+    fn inline_diagnostics_need_a_position() {
+        assert_eq!(inline_header("src/x.c:3:1: error: oops"), Some(("src/x.c:3:1", "error: oops")));
+        assert_eq!(inline_header("note: error: nothing"), None);
+        assert_eq!(blank_names("`a` and 'b' differ"), "`_` and '_' differ");
+    }
+}
@@ -0,0 +1,62 @@
+//! Failure excerpt: the lines of a failed run worth showing the agent.
+//!
+//! Keeps compiler errors, failing test names, panics and assertion details
+//! (with a few lines of context each) instead of the whole log. Output with
+//! no recognisable failure line falls back to its tail.
+
+/// Substrings marking a line as part of a failure report.
+const MARKERS: &[&str] = &["error", "Error", "FAILED", "FAIL", "panicked", "Traceback", "assert"];
+
+/// Lines kept after each marker line (locations, `left:` / `right:` values).
+const CONTEXT_LINES: usize = 3;
+
+/// Most lines an excerpt keeps.
+const MAX_LINES: usize = 40;
+
+/// Tail kept when no line matches a marker.
+const TAIL_LINES: usize = 20;
+
+/// The failure-relevant lines of `output`, capped at [`MAX_LINES`].
+#[must_use]
+pub fn excerpt(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+    let mut keep = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if MARKERS.iter().any(|m| line.contains(m)) {
+            keep.iter_mut().skip(i).take(CONTEXT_LINES.saturating_add(1)).for_each(|k| *k = true);
+        }
+    }
+    let picked: Vec<&str> = lines.iter().zip(&keep).filter(|pair| *pair.1).map(|pair| *pair.0).collect();
+    if picked.is_empty() {
+        return lines.iter().skip(lines.len().saturating_sub(TAIL_LINES)).copied().collect::<Vec<_>>().join("\n");
+    }
+    let text = picked.iter().take(MAX_LINES).copied().collect::<Vec<_>>().join("\n");
+    match picked.len().checked_sub(MAX_LINES).filter(|&more| more > 0) {
+        Some(more) => format!("{text}\n\u{2026} {more} more lines"),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_failing_tests_and_their_assertions() {
+        let output = "running 2 tests\ntest ok_one ... ok\ntest parser::nested ... FAILED\n\nfailures:\n\n\
+                      ---- parser::nested stdout ----\n\nthread 'parser::nested' panicked at src/parser.rs:40:9:\n\
+                      assertion `left == right` failed\n  left: 1\n right: 2\n\n\ntest result: FAILED. 1 passed";
+        let text = excerpt(output);
+        assert!(text.contains("test parser::nested ... FAILED"));
+        assert!(text.contains(" right: 2"));
+        assert!(!text.contains("ok_one"));
+    }
+
+    #[test]
+    fn falls_back_to_the_tail() {
+        let output = (1..=30u32).map(|n| format!("line {n}")).collect::<Vec<_>>().join("\n");
+        let text = excerpt(&output);
+        assert!(text.starts_with("line 11\n"));
+        assert!(text.ends_with("line 30"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Workspace jail: tool paths must stay inside the project root.
+//!
+//! An opt-in boundary: every path a tool touches must resolve inside the root
+//! or an allowlisted directory, so a prompt-injected request for
+//! `~/.ssh/id_rsa` or `../../etc/passwd` is refused before anything runs.
+//!
+//! Paths are resolved before the check — `..` applied and symlinks followed —
+//! so neither an absolute path, a traversal nor a link leads out. The user
+//! sets it with `/jail on|off` and `/jail allow <dirs>` and the files module
+//! persists it; pre-flight checks the path parameters of every tool, and the
+//! watchers skip paths the jail refuses.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Tool parameters holding file system paths (a string or an array of them):
+/// files and directories, bookmark `path:line` locations, working
+/// directories, and files read or written by query tools.
+const PATH_PARAMS: [&str; 7] = ["path", "paths", "file_path", "location", "cwd", "output_path", "request_path"];
+
+/// Jail settings as stored in the files module data.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Settings {
+    /// Refuse paths resolving outside the workspace root and the allowlist.
+    #[serde(default)]
+    enabled: bool,
+    /// Directories reachable outside the root: absolute, or `~/`-relative.
+    #[serde(default)]
+    allow: Vec<String>,
+}
+
+/// The workspace jail: its settings and the directories they open, resolved
+/// once when the jail is built rather than on every check.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Settings", into = "Settings")]
+pub struct Jail {
+    /// Refuse paths resolving outside the workspace root and the allowlist.
+    enabled: bool,
+    /// Directories reachable outside the root: absolute, or `~/`-relative.
+    allow: Vec<String>,
+    /// The workspace root and the allowlisted directories, resolved.
+    roots: Vec<PathBuf>,
+}
+
+impl From<Settings> for Jail {
+    fn from(settings: Settings) -> Self {
+        Self::new(settings.enabled, settings.allow)
+    }
+}
+
+impl From<Jail> for Settings {
+    fn from(jail: Jail) -> Self {
+        Self { enabled: jail.enabled, allow: jail.allow }
+    }
+}
+
+impl Jail {
+    /// A jail, `enabled` or not, opening the `allow` directories besides the
+    /// workspace root.
+    #[must_use]
+    pub fn new(enabled: bool, allow: Vec<String>) -> Self {
+        let roots = resolve_roots(&allow);
+        Self { enabled, allow, roots }
+    }
+
+    /// Whether the jail refuses anything.
+    #[must_use]
+    pub const fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Directories reachable outside the root, as configured.
+    #[must_use]
+    pub fn allow(&self) -> &[String] {
+        &self.allow
+    }
+
+    /// Replace the allowlist, resolving its directories again.
+    pub fn set_allow(&mut self, allow: Vec<String>) {
+        self.roots = resolve_roots(&allow);
+        self.allow = allow;
+    }
+
+    /// `path` resolved, or why the jail refuses it.
+    ///
+    /// # Errors
+    ///
+    /// The path cannot be resolved, or resolves outside the jail.
+    pub fn check(&self, path: &Path) -> Result<PathBuf, String> {
+        let target = resolve(path)?;
+        if !self.enabled || self.roots.iter().any(|root| target.starts_with(root)) {
+            return Ok(target);
+        }
+        Err(format!(
+            "'{}' resolves to {}, outside the workspace; the workspace jail refuses it. \
+             Do not retry through another path — ask the user to add the directory to the \
+             jail allowlist (`/jail allow <dir>`) if it is really needed.",
+            path.display(),
+            target.display()
+        ))
+    }
+
+    /// Whether the jail lets `path` through.
+    #[must_use]
+    pub fn allows(&self, path: &Path) -> bool {
+        self.check(path).is_ok()
+    }
+
+    /// Refusals for the path parameters of a tool call, one per path.
+    #[must_use]
+    pub fn violations(&self, input: &Value) -> Vec<String> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        PATH_PARAMS
+            .iter()
+            .filter_map(|name| input.get(name))
+            .flat_map(|value| {
+                value.as_str().into_iter().chain(value.as_array().into_iter().flatten().filter_map(Value::as_str))
+            })
+            .filter_map(|path| self.check(Path::new(path)).err())
+            .collect()
+    }
+}
+
+/// The workspace root and the `allow` directories, resolved; entries that
+/// cannot be resolved open nothing.
+fn resolve_roots(allow: &[String]) -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let allowed = allow.iter().filter_map(|entry| {
+        let expanded = match entry.strip_prefix("~/") {
+            Some(rest) => home.as_ref()?.join(rest),
+            None => PathBuf::from(entry),
+        };
+        resolve(&expanded).ok()
+    });
+    let root = std::env::current_dir().and_then(|dir| dir.canonicalize()).ok();
+    root.into_iter().chain(allowed).collect()
+}
+
+/// Resolve `path`, which need not exist: its deepest existing ancestor is
+/// canonicalized (symlinks followed), then the missing rest applied lexically.
+///
+/// # Errors
+///
+/// The working directory is unknown, or the ancestor is a broken symlink or
+/// unreadable — a dangling link could otherwise be written through.
+pub fn resolve(path: &Path) -> Result<PathBuf, String> {
+    let absolute = std::path::absolute(path).map_err(|e| format!("cannot resolve '{}': {e}", path.display()))?;
+    let existing = absolute.ancestors().find(|a| a.symlink_metadata().is_ok()).unwrap_or_else(|| Path::new("/"));
+    let base = existing.canonicalize().map_err(|e| {
+        format!("cannot resolve '{}': '{}' is a broken symlink or unreadable ({e})", path.display(), existing.display())
+    })?;
+    let rest = absolute.strip_prefix(existing).unwrap_or(&absolute);
+    let mut target = base;
+    for component in rest.components() {
+        match component {
+            Component::ParentDir => {
+                let _popped = target.pop();
+            }
+            Component::Normal(name) => target.push(name),
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+        }
+    }
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn refuses_paths_outside_the_workspace() {
+        let root = std::env::current_dir().and_then(|dir| dir.canonicalize()).unwrap_or_default();
+        let jail = Jail::new(true, vec![String::from("/tmp")]);
+
+        assert_eq!(resolve(Path::new("src/../missing/x.rs")).ok(), Some(root.join("missing/x.rs")));
+        assert!(jail.allows(Path::new("Cargo.toml")));
+        assert!(jail.allows(Path::new("/tmp/scratch.txt")));
+        assert!(!jail.allows(&root.join("../../..").join("etc/passwd")));
+
+        let refused = jail.violations(&json!({"path": "/etc/passwd", "paths": ["src", "../../../../etc/shadow"]}));
+        assert_eq!(refused.len(), 2);
+        assert!(Jail::default().violations(&json!({"path": "/etc/passwd"})).is_empty());
+    }
+
+    #[test]
+    fn checks_locations_and_working_directories() {
+        let jail = Jail::new(true, vec![]);
+        let refused = jail.violations(&json!({"location": "/etc/passwd:1", "cwd": "/etc", "file_path": "src/x.rs"}));
+        assert_eq!(refused.len(), 2);
+    }
+
+    #[test]
+    fn allowlist_roots_are_resolved_when_set() {
+        let mut jail: Jail = serde_json::from_value(json!({"enabled": true, "allow": []})).unwrap_or_default();
+        assert!(jail.enabled());
+        assert!(!jail.allows(Path::new("/tmp/scratch.txt")));
+        jail.set_allow(vec![String::from("/tmp")]);
+        assert!(jail.allows(Path::new("/tmp/scratch.txt")));
+        assert_eq!(serde_json::to_value(&jail).ok(), Some(json!({"enabled": true, "allow": ["/tmp"]})));
+    }
+}
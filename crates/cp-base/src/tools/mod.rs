@@ -13,6 +13,18 @@ pub mod async_exec;
 /// LLM API schema builder — injects global `intent`/`verb` into every tool.
 pub mod api_schema;
 
+/// Structured output — JSON Schema answers for tool-free requests.
+pub mod output_schema;
+
+/// Failure excerpts — the lines of a failed check worth showing the agent.
+pub mod failures;
+
+/// Error clusters — repeated diagnostics across a batch folded into one report.
+pub mod clusters;
+
+/// Workspace jail — refuses tool paths resolving outside the project root.
+pub mod jail;
+
 /// Recursive JSON-Schema type of a tool parameter — the [`ParamType`] enum's
 /// inherent `impl` block lives in the private `param_type` sibling module.
 mod param_type;
@@ -429,4 +441,4 @@ impl ToolDefBuilder<'_> {
 
 /// Parameter names reserved by the global tool metadata middleware.
 /// No tool definition may declare a parameter with these names.
-const RESERVED_PARAM_NAMES: &[&str] = &["intent", "verb"];
+pub const RESERVED_PARAM_NAMES: &[&str] = &["intent", "verb"];
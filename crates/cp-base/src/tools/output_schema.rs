@@ -0,0 +1,214 @@
+//! Structured output: the JSON Schema a tool-free answer must match.
+//!
+//! Providers with native support receive the schema as their response format;
+//! everywhere else it is spelled out in the prompt. Either way the answer is
+//! parsed and validated here against the subset of JSON Schema that strict
+//! structured-output modes accept (`type`, `enum`, `properties`, `required`,
+//! `additionalProperties`, `items`).
+
+use serde_json::{Map, Value, json};
+
+use crate::config::INJECTIONS;
+use crate::config::llm_types::LlmProvider;
+
+/// Named JSON Schema for a structured answer.
+#[derive(Debug, Clone)]
+pub struct OutputSchema {
+    /// Schema name (letters, digits, `_` and `-`), required by OpenAI-style APIs.
+    pub name: String,
+    /// The JSON Schema itself.
+    pub schema: Value,
+}
+
+impl OutputSchema {
+    /// Schema `name` describing answers shaped like `schema`.
+    #[must_use]
+    pub fn new(name: &str, schema: Value) -> Self {
+        Self { name: name.to_owned(), schema }
+    }
+
+    /// OpenAI-style `response_format` (strict JSON Schema).
+    #[must_use]
+    pub fn openai_response_format(&self) -> Value {
+        json!({"type": "json_schema", "json_schema": {"name": self.name, "schema": self.schema, "strict": true}})
+    }
+
+    /// Anthropic-style `output_format`.
+    #[must_use]
+    pub fn anthropic_output_format(&self) -> Value {
+        json!({"type": "json_schema", "schema": self.schema})
+    }
+
+    /// Prompt text asking for a bare JSON answer matching the schema.
+    #[must_use]
+    pub fn instruction(&self) -> String {
+        let pretty = serde_json::to_string_pretty(&self.schema).unwrap_or_default();
+        INJECTIONS.providers.structured_output.replace("{schema}", &pretty)
+    }
+
+    /// Follow-up prompt asking the model to fix an answer rejected for `reason`.
+    #[must_use]
+    pub fn repair_prompt(reason: &str) -> String {
+        INJECTIONS.providers.structured_repair.replace("{error}", reason)
+    }
+
+    /// Parse `text` as JSON (tolerating a Markdown code fence around it) and
+    /// validate it against the schema.
+    ///
+    /// # Errors
+    ///
+    /// Describes the first problem found, worded to be fed back to the model.
+    pub fn parse(&self, text: &str) -> Result<Value, String> {
+        let value: Value = serde_json::from_str(strip_fence(text)).map_err(|e| format!("not valid JSON ({e})"))?;
+        validate(&self.schema, &value, "$")?;
+        Ok(value)
+    }
+}
+
+/// A tool-free request whose answer must match `schema`.
+#[derive(Debug, Clone)]
+pub struct StructuredQuery {
+    /// Provider to ask.
+    pub provider: LlmProvider,
+    /// Model identifier.
+    pub model: String,
+    /// System prompt.
+    pub system: String,
+    /// The question; the schema instruction is appended to it.
+    pub prompt: String,
+    /// Shape of the expected answer.
+    pub schema: OutputSchema,
+    /// Worker label used for request dumps.
+    pub worker_id: String,
+}
+
+/// Runs a [`StructuredQuery`] (blocking) and returns the validated answer.
+/// The binary owns the LLM clients, so module crates receive it as a callback.
+pub type StructuredRunner = fn(&StructuredQuery) -> Result<Value, String>;
+
+/// `text` without a surrounding ```` ```json ```` fence.
+fn strip_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else { return trimmed };
+    let body = rest.split_once('\n').map_or("", |pair| pair.1).trim_end();
+    body.strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// JSON type name of `value`, for error messages.
+const fn kind_of(value: &Value) -> &'static str {
+    match *value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Whether `value` is of the JSON Schema type `expected` (unknown types pass).
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Check the schema's `type` (a name or a list of names) against `value`.
+fn check_type(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let declared = schema.get("type");
+    let allowed: Vec<&str> = declared.and_then(Value::as_str).map_or_else(
+        || declared.and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect(),
+        |single| vec![single],
+    );
+    if allowed.is_empty() || allowed.iter().any(|t| type_matches(t, value)) {
+        return Ok(());
+    }
+    Err(format!("{path}: expected {}, got {}", allowed.join(" or "), kind_of(value)))
+}
+
+/// Check required fields, known fields and (when closed) unexpected fields of an object.
+fn check_object(schema: &Value, object: &Map<String, Value>, path: &str) -> Result<(), String> {
+    let required = schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str);
+    if let Some(missing) = required.into_iter().find(|key| !object.contains_key(*key)) {
+        return Err(format!("{path}: missing required field `{missing}`"));
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+    for (key, field) in object {
+        if let Some(sub) = properties.and_then(|p| p.get(key)) {
+            validate(sub, field, &format!("{path}.{key}"))?;
+        } else if closed {
+            return Err(format!("{path}: unexpected field `{key}`"));
+        } else {
+            // Open object: extra fields are allowed.
+        }
+    }
+    Ok(())
+}
+
+/// Validate `value` against `schema`, reporting the first violation with its JSON path.
+fn validate(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    check_type(schema, value, path)?;
+    if let Some(options) = schema.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        return Err(format!("{path}: must be one of {}", Value::Array(options.clone())));
+    }
+    if let Some(object) = value.as_object() {
+        check_object(schema, object, path)?;
+    }
+    if let Some(items) = schema.get("items")
+        && let Some(array) = value.as_array()
+    {
+        for (i, item) in array.iter().enumerate() {
+            validate(items, item, &format!("{path}[{i}]"))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan_schema() -> OutputSchema {
+        OutputSchema::new(
+            "plan",
+            json!({
+                "type": "object",
+                "properties": {
+                    "close": {"type": "array", "items": {"type": "string"}},
+                    "mode": {"type": "string", "enum": ["fast", "thorough"]}
+                },
+                "required": ["close"],
+                "additionalProperties": false
+            }),
+        )
+    }
+
+    #[test]
+    fn accepts_fenced_valid_answer() {
+        let parsed = plan_schema().parse("```json\n{\"close\": [\"P3\"], \"mode\": \"fast\"}\n```");
+        assert_eq!(parsed, Ok(json!({"close": ["P3"], "mode": "fast"})));
+    }
+
+    #[test]
+    fn reports_first_violation_with_path() {
+        let schema = plan_schema();
+        assert_eq!(schema.parse("{}"), Err("$: missing required field `close`".to_owned()));
+        assert_eq!(schema.parse("{\"close\": [3]}"), Err("$.close[0]: expected string, got number".to_owned()));
+        assert_eq!(schema.parse("{\"close\": [], \"x\": 1}"), Err("$: unexpected field `x`".to_owned()));
+        assert_eq!(
+            schema.parse("{\"close\": [], \"mode\": \"slow\"}"),
+            Err("$.mode: must be one of [\"fast\",\"thorough\"]".to_owned())
+        );
+        assert!(schema.parse("close: P3").is_err_and(|e| e.starts_with("not valid JSON")));
+    }
+}
@@ -20,6 +20,9 @@ pub struct Verdict {
     /// executed immediately. Used by destructive operations that need
     /// queue protection (e.g. `Close_conversation_history`).
     pub activate_queue: bool,
+    /// Path of a refused call the user can still approve: the pipeline
+    /// lists the call in the Approvals panel.
+    pub awaiting_approval: Option<String>,
 }
 
 impl Verdict {
@@ -72,6 +75,9 @@ impl Verdict {
         self.errors.extend(other.errors);
         self.warnings.extend(other.warnings);
         self.activate_queue = self.activate_queue || other.activate_queue;
+        if self.awaiting_approval.is_none() {
+            self.awaiting_approval = other.awaiting_approval;
+        }
     }
 
     /// Format errors and warnings into a human-readable string.
@@ -7,6 +7,10 @@ use unicode_width::UnicodeWidthStr;
 
 /// Render cache types for conversation panel performance.
 pub mod render_cache;
+/// Wordlist spell checking and the suggestion popup state for the draft.
+pub mod spell;
+/// Grapheme-aware cursor movement, truncation, and hard wrapping.
+pub mod text;
 
 /// Column alignment for table cells.
 #[derive(Debug, Clone, Copy, Default)]
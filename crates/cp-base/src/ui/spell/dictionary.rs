@@ -0,0 +1,135 @@
+//! Wordlist loading, lookup with inflection stripping, and suggestions.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Wordlists tried in order when `CP_SPELL_DICT` is unset.
+const SYSTEM_DICTIONARIES: [&str; 4] = [
+    "/usr/share/hunspell/en_US.dic",
+    "/usr/share/myspell/en_US.dic",
+    "/usr/share/myspell/dicts/en_US.dic",
+    "/usr/share/dict/words",
+];
+
+/// Suffixes stripped (and what replaces them) when a word is not listed as is.
+const INFLECTIONS: [(&str, &str); 16] = [
+    ("'s", ""),
+    ("ies", "y"),
+    ("ied", "y"),
+    ("es", ""),
+    ("s", ""),
+    ("ed", ""),
+    ("ed", "e"),
+    ("ing", ""),
+    ("ing", "e"),
+    ("er", ""),
+    ("er", "e"),
+    ("est", ""),
+    ("est", "e"),
+    ("ly", ""),
+    ("ness", ""),
+    ("ment", ""),
+];
+
+/// Largest edit distance of a suggestion.
+const MAX_DISTANCE: usize = 2;
+
+/// A set of known words, lowercased.
+pub(super) struct Dictionary {
+    /// Known words.
+    words: HashSet<String>,
+}
+
+/// The loaded dictionary, `None` when no wordlist could be read.
+static LOADED: OnceLock<Option<Dictionary>> = OnceLock::new();
+
+/// The dictionary, loading it on first use.
+pub(super) fn get() -> Option<&'static Dictionary> {
+    LOADED.get_or_init(load).as_ref()
+}
+
+/// Read the first available wordlist.
+fn load() -> Option<Dictionary> {
+    let custom = std::env::var("CP_SPELL_DICT").ok();
+    let mut paths = custom.iter().map(String::as_str).chain(SYSTEM_DICTIONARIES);
+    paths.find_map(|path| std::fs::read_to_string(path).ok()).map(|text| Dictionary::parse(&text))
+}
+
+impl Dictionary {
+    /// Parse a plain wordlist or a hunspell `.dic` (leading count line and
+    /// `/FLAGS` suffixes are dropped).
+    pub(super) fn parse(text: &str) -> Self {
+        let words = text
+            .lines()
+            .map(|line| line.split('/').next().unwrap_or("").trim())
+            .filter(|word| !word.is_empty() && !word.bytes().all(|b| b.is_ascii_digit()))
+            .map(str::to_lowercase)
+            .collect();
+        Self { words }
+    }
+
+    /// Whether `word` is listed, directly or once a common suffix is stripped
+    /// (a doubled final consonant is undone too: `running` → `run`).
+    pub(super) fn knows(&self, word: &str) -> bool {
+        let lower = word.to_lowercase();
+        self.words.contains(&lower)
+            || INFLECTIONS.iter().any(|&(suffix, replacement)| {
+                lower.strip_suffix(suffix).is_some_and(|stem| stem.len() >= 2 && self.knows_stem(stem, replacement))
+            })
+    }
+
+    /// Whether `stem` + `replacement`, or `stem` minus a doubled last letter, is listed.
+    fn knows_stem(&self, stem: &str, replacement: &str) -> bool {
+        if self.words.contains(&format!("{stem}{replacement}")) {
+            return true;
+        }
+        let mut last_two = stem.chars().rev();
+        let doubled = replacement.is_empty() && last_two.next().is_some_and(|last| last_two.next() == Some(last));
+        doubled && stem.get(..stem.len().saturating_sub(1)).is_some_and(|undoubled| self.words.contains(undoubled))
+    }
+
+    /// Up to `limit` listed words within two edits of `word`, closest first
+    /// (ties: same first letter, then alphabetical).
+    pub(super) fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let lower: Vec<char> = word.to_lowercase().chars().collect();
+        let first = lower.first().copied();
+        let mut ranked: Vec<(usize, bool, &String)> = self
+            .words
+            .iter()
+            .filter(|candidate| candidate.chars().count().abs_diff(lower.len()) <= MAX_DISTANCE)
+            .filter_map(|candidate| {
+                let chars: Vec<char> = candidate.chars().collect();
+                let distance = edit_distance(&lower, &chars);
+                (distance <= MAX_DISTANCE).then(|| (distance, chars.first().copied() != first, candidate))
+            })
+            .collect();
+        ranked.sort();
+        ranked.into_iter().take(limit).map(|(_, _, candidate)| candidate.clone()).collect()
+    }
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions
+/// and transpositions of adjacent characters each cost one.
+fn edit_distance(left: &[char], right: &[char]) -> usize {
+    let mut before_prev: Vec<usize> = Vec::new();
+    let mut prev: Vec<usize> = (0..=right.len()).collect();
+    for (i, &lc) in left.iter().enumerate() {
+        let mut row = vec![i.saturating_add(1)];
+        for (j, &rc) in right.iter().enumerate() {
+            let substitution = prev.get(j).map_or(usize::MAX, |&d| d.saturating_add(usize::from(lc != rc)));
+            let deletion = prev.get(j.saturating_add(1)).map_or(usize::MAX, |&d| d.saturating_add(1));
+            let insertion = row.get(j).map_or(usize::MAX, |&d| d.saturating_add(1));
+            let mut best = substitution.min(deletion).min(insertion);
+            let swapped = i > 0
+                && j > 0
+                && left.get(i.saturating_sub(1)) == Some(&rc)
+                && right.get(j.saturating_sub(1)) == Some(&lc);
+            if swapped && let Some(&d) = before_prev.get(j.saturating_sub(1)) {
+                best = best.min(d.saturating_add(1));
+            }
+            row.push(best);
+        }
+        before_prev = std::mem::replace(&mut prev, row);
+    }
+    prev.last().copied().unwrap_or(0)
+}
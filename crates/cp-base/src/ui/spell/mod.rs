@@ -0,0 +1,196 @@
+//! Lightweight spell checking for the input draft.
+//!
+//! Words are looked up in a plain wordlist: `CP_SPELL_DICT` when set, else the
+//! first hunspell/myspell `en_US.dic` or `/usr/share/dict/words` found. Affix
+//! rules are not applied; common English inflections (`-s`, `-ed`, `-ing`, …)
+//! are stripped instead. Only plain alphabetic words in lower or title case
+//! are checked, so code identifiers, paths and acronyms are never flagged.
+//! Without a wordlist nothing is flagged.
+
+/// Wordlist loading, lookup and suggestions.
+mod dictionary;
+
+use std::ops::Range;
+
+use crate::state::runtime::State;
+
+/// Suggestions listed in the popup.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Punctuation trimmed from both ends of a whitespace-separated chunk before
+/// it is checked.
+const EDGE_PUNCTUATION: &[char] = &['"', '\'', '(', ')', '[', ']', '{', '}', ',', '.', ';', ':', '!', '?', '*'];
+
+/// Spell-check config (stored in `State`'s `TypeMap`; absent = off).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Settings {
+    /// Underline misspelled words in the draft.
+    pub enabled: bool,
+}
+
+impl Settings {
+    /// Whether spell checking is switched on.
+    #[must_use]
+    pub fn enabled(state: &State) -> bool {
+        state.get_ext::<Self>().is_some_and(|settings| settings.enabled)
+    }
+}
+
+/// Suggestion popup for one misspelled word of the draft (stored in `State`'s
+/// `TypeMap`).
+#[derive(Debug, Clone, Default)]
+pub struct Popup {
+    /// Whether the popup is currently visible.
+    pub active: bool,
+    /// Byte range of the word in the draft.
+    pub range: Range<usize>,
+    /// The misspelled word.
+    pub word: String,
+    /// Replacements, closest first.
+    pub suggestions: Vec<String>,
+    /// Index of the highlighted suggestion.
+    pub selected: usize,
+}
+
+impl Popup {
+    /// Popup for the misspelled word at `range` of `input`, or `None` when
+    /// that word is spelled correctly (or there is no wordlist).
+    #[must_use]
+    pub fn for_word(input: &str, range: Range<usize>) -> Option<Self> {
+        let dictionary = dictionary::get()?;
+        let word = input.get(range.clone())?.to_owned();
+        if dictionary.knows(&word) {
+            return None;
+        }
+        let suggestions =
+            dictionary.suggest(&word, MAX_SUGGESTIONS).into_iter().map(|s| match_case(&word, &s)).collect();
+        Some(Self { active: true, range, word, suggestions, selected: 0 })
+    }
+
+    /// Whether a popup is open in `state`.
+    #[must_use]
+    pub fn is_open(state: &State) -> bool {
+        state.get_ext::<Self>().is_some_and(|popup| popup.active)
+    }
+
+    /// The highlighted suggestion, if any.
+    #[must_use]
+    pub fn selection(&self) -> Option<&str> {
+        self.suggestions.get(self.selected).map(String::as_str)
+    }
+
+    /// Highlight the previous suggestion (wrapping).
+    pub const fn select_prev(&mut self) {
+        self.selected =
+            if self.selected == 0 { self.suggestions.len().saturating_sub(1) } else { self.selected.saturating_sub(1) };
+    }
+
+    /// Highlight the next suggestion (wrapping).
+    pub const fn select_next(&mut self) {
+        let next = self.selected.saturating_add(1);
+        self.selected = if next >= self.suggestions.len() { 0 } else { next };
+    }
+
+    /// Hide the popup.
+    pub const fn close(&mut self) {
+        self.active = false;
+    }
+}
+
+/// `suggestion` capitalised like `word` (title case carries over).
+fn match_case(word: &str, suggestion: &str) -> String {
+    let mut chars = suggestion.chars();
+    match (word.chars().next().is_some_and(char::is_uppercase), chars.next()) {
+        (true, Some(first)) => first.to_uppercase().chain(chars).collect(),
+        (true | false, _) => suggestion.to_owned(),
+    }
+}
+
+/// Whether a wordlist is available.
+#[must_use]
+pub fn available() -> bool {
+    dictionary::get().is_some()
+}
+
+/// Byte ranges of the misspelled words in `text`.
+#[must_use]
+pub fn misspelled(text: &str) -> Vec<Range<usize>> {
+    dictionary::get().map_or_else(Vec::new, |dictionary| {
+        checked_words(text)
+            .into_iter()
+            .filter(|range| text.get(range.clone()).is_some_and(|w| !dictionary.knows(w)))
+            .collect()
+    })
+}
+
+/// Byte range of the checkable word touching byte offset `cursor`.
+#[must_use]
+pub fn word_at(text: &str, cursor: usize) -> Option<Range<usize>> {
+    checked_words(text).into_iter().find(|range| range.start <= cursor && cursor <= range.end)
+}
+
+/// Byte ranges of the words worth checking: alphabetic, lower or title case,
+/// at least two letters, with hyphenated compounds split into their parts.
+fn checked_words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    for (start, chunk) in chunks(text) {
+        let trimmed = chunk.trim_start_matches(EDGE_PUNCTUATION);
+        let core_start = start.saturating_add(chunk.len().saturating_sub(trimmed.len()));
+        let core = trimmed.trim_end_matches(EDGE_PUNCTUATION);
+        if !core.chars().all(|c| c.is_ascii_alphabetic() || c == '\'' || c == '-') {
+            continue;
+        }
+        let mut offset = core_start;
+        for part in core.split('-') {
+            if is_plain_word(part) {
+                words.push(offset..offset.saturating_add(part.len()));
+            }
+            offset = offset.saturating_add(part.len()).saturating_add(1);
+        }
+    }
+    words
+}
+
+/// Whitespace-separated chunks of `text` with their byte offsets.
+fn chunks(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(char::is_whitespace).scan(0usize, |offset, chunk| {
+        let start = *offset;
+        *offset = start.saturating_add(chunk.len()).saturating_add(1);
+        Some((start, chunk))
+    })
+}
+
+/// Lower-case or title-case letters (inner apostrophes allowed), two or more.
+fn is_plain_word(part: &str) -> bool {
+    let mut letters = part.chars().filter(char::is_ascii_alphabetic);
+    let first_ok = letters.next().is_some();
+    first_ok && part.len() >= 2 && !part.starts_with('\'') && letters.all(|c| c.is_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dictionary::Dictionary;
+    use super::*;
+
+    const WORDS: &str = "6\nrun/SG\nmake\nspell\nword\nhello\ncity";
+
+    #[test]
+    fn lookup_strips_flags_and_inflections() {
+        let dictionary = Dictionary::parse(WORDS);
+        for word in ["run", "running", "making", "Spelled", "words", "cities", "hello"] {
+            assert!(dictionary.knows(word), "{word}");
+        }
+        assert!(!dictionary.knows("helo"));
+        assert_eq!(dictionary.suggest("wrod", 3), ["word"]);
+        assert_eq!(dictionary.suggest("helo", 3), ["hello"]);
+    }
+
+    #[test]
+    fn only_plain_words_are_checked() {
+        let text = "Teh `code` src/main.rs HTTP camelCase well-knwon, (fine).";
+        let words: Vec<&str> = checked_words(text).into_iter().filter_map(|r| text.get(r)).collect();
+        assert_eq!(words, ["Teh", "well", "knwon", "fine"]);
+        assert_eq!(word_at(text, 2), Some(0..3));
+        assert_eq!(match_case("Teh", "the"), "The");
+    }
+}
@@ -0,0 +1,182 @@
+//! Grapheme-aware text helpers.
+//!
+//! Byte offsets into user text must land between grapheme clusters: slicing
+//! elsewhere either panics (mid-character) or splits what the terminal draws
+//! as one glyph (combining marks, emoji sequences). Every cursor move, cut
+//! and wrap in the UI goes through these helpers; widths are display columns.
+
+use std::ops::Range;
+
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation as _};
+use unicode_width::UnicodeWidthStr as _;
+
+/// The grapheme boundary at or before byte offset `at` (clamped to the end).
+#[must_use]
+pub fn floor_boundary(s: &str, at: usize) -> usize {
+    let char_at = s.floor_char_boundary(at);
+    let mut cursor = GraphemeCursor::new(char_at, s.len(), true);
+    match cursor.is_boundary(s, 0) {
+        Ok(true) => char_at,
+        Ok(false) | Err(_) => cursor.prev_boundary(s, 0).ok().flatten().unwrap_or(0),
+    }
+}
+
+/// Start of the grapheme before byte offset `at` (0 at the start).
+#[must_use]
+pub fn prev_boundary(s: &str, at: usize) -> usize {
+    let floor = floor_boundary(s, at);
+    if floor < at.min(s.len()) {
+        return floor;
+    }
+    GraphemeCursor::new(floor, s.len(), true).prev_boundary(s, 0).ok().flatten().unwrap_or(0)
+}
+
+/// End of the grapheme after byte offset `at` (`s.len()` at the end).
+#[must_use]
+pub fn next_boundary(s: &str, at: usize) -> usize {
+    let floor = floor_boundary(s, at);
+    GraphemeCursor::new(floor, s.len(), true).next_boundary(s, 0).ok().flatten().unwrap_or(s.len())
+}
+
+/// Insert `text` at byte offset `at`, snapped back to a grapheme boundary.
+/// Returns the offset just past the inserted text (the new cursor).
+pub fn insert_at(s: &mut String, at: usize, text: &str) -> usize {
+    let pos = floor_boundary(s, at);
+    s.insert_str(pos, text);
+    pos.saturating_add(text.len())
+}
+
+/// The longest prefix of whole graphemes fitting in `max_width` columns.
+#[must_use]
+pub fn fit_width(s: &str, max_width: usize) -> &str {
+    let mut width = 0usize;
+    let mut end = 0usize;
+    for (i, grapheme) in s.grapheme_indices(true) {
+        width = width.saturating_add(grapheme.width());
+        if width > max_width {
+            break;
+        }
+        end = i.saturating_add(grapheme.len());
+    }
+    s.get(..end).unwrap_or("")
+}
+
+/// `s` cut to `max_width` columns, ending in `marker` (e.g. `"…"` or
+/// `"..."`) when shortened. The result never exceeds `max_width` columns.
+#[must_use]
+pub fn ellipsize(s: &str, max_width: usize, marker: &str) -> String {
+    if s.width() <= max_width {
+        return s.to_owned();
+    }
+    format!("{}{marker}", fit_width(s, max_width.saturating_sub(marker.width())))
+}
+
+/// Hard-wrap `s` at `max_width` columns between graphemes, keeping all
+/// whitespace (code lines, over-long words).
+#[must_use]
+pub fn wrap_graphemes(s: &str, max_width: usize) -> Vec<String> {
+    if max_width == 0 || s.width() <= max_width {
+        return vec![s.to_owned()];
+    }
+    let mut lines = Vec::new();
+    let mut current_line = String::new();
+    let mut current_width = 0usize;
+    for grapheme in s.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if current_width.saturating_add(grapheme_width) > max_width && !current_line.is_empty() {
+            lines.push(std::mem::take(&mut current_line));
+            current_width = 0;
+        }
+        current_line.push_str(grapheme);
+        current_width = current_width.saturating_add(grapheme_width);
+    }
+    lines.push(current_line);
+    lines
+}
+
+/// Byte ranges of the visual lines of `s` word-wrapped at `max_width` columns.
+///
+/// Newlines are excluded, an empty line is an empty range, and a wrapped line
+/// ends where the next begins.
+#[must_use]
+pub fn visual_lines(s: &str, max_width: usize) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = 0usize;
+    for logical in s.split('\n') {
+        let mut wrap = LineWrap { offset, max_width, start: 0, width: 0, space_end: 0 };
+        for (i, grapheme) in logical.grapheme_indices(true) {
+            wrap.push(logical, (i, grapheme), &mut lines);
+        }
+        lines.push(offset.saturating_add(wrap.start)..offset.saturating_add(logical.len()));
+        offset = offset.saturating_add(logical.len()).saturating_add(1);
+    }
+    lines
+}
+
+/// Greedy word-wrap state for one logical line in [`visual_lines`].
+struct LineWrap {
+    /// Byte offset of the logical line within the whole text.
+    offset: usize,
+    /// Wrap width in columns (0 = no wrapping).
+    max_width: usize,
+    /// Start of the visual line being filled (relative to the logical line).
+    start: usize,
+    /// Display width of the visual line so far.
+    width: usize,
+    /// End of the last whitespace run on this visual line (0 = none yet).
+    space_end: usize,
+}
+
+impl LineWrap {
+    /// Add the grapheme at byte `i`, closing the current visual line first
+    /// (at its last whitespace, else right here) when it would overflow.
+    fn push(&mut self, line: &str, (i, grapheme): (usize, &str), out: &mut Vec<Range<usize>>) {
+        let grapheme_width = grapheme.width();
+        if self.max_width > 0 && i > self.start && self.width.saturating_add(grapheme_width) > self.max_width {
+            let at = if self.space_end > self.start { self.space_end } else { i };
+            out.push(self.offset.saturating_add(self.start)..self.offset.saturating_add(at));
+            self.start = at;
+            self.width = line.get(at..i).map_or(0, str::width);
+        }
+        self.width = self.width.saturating_add(grapheme_width);
+        if grapheme.chars().all(char::is_whitespace) {
+            self.space_end = i.saturating_add(grapheme.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "e" + combining acute, then a family emoji (ZWJ sequence), then "x".
+    const MIXED: &str = "e\u{301}\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}x";
+
+    #[test]
+    fn boundaries_step_over_whole_graphemes() {
+        let emoji_start = "e\u{301}".len();
+        let x_start = MIXED.len().saturating_sub(1);
+        assert_eq!(next_boundary(MIXED, 0), emoji_start);
+        assert_eq!(next_boundary(MIXED, emoji_start), x_start);
+        assert_eq!(prev_boundary(MIXED, x_start), emoji_start);
+        assert_eq!(floor_boundary(MIXED, 1), 0);
+        assert_eq!(floor_boundary(MIXED, emoji_start.saturating_add(2)), emoji_start);
+        let mut input = MIXED.to_owned();
+        assert_eq!(insert_at(&mut input, emoji_start.saturating_add(5), "!"), emoji_start.saturating_add(1));
+    }
+
+    #[test]
+    fn truncation_respects_display_width() {
+        assert_eq!(ellipsize("hello world", 8, "..."), "hello...");
+        assert_eq!(ellipsize("\u{65e5}\u{672c}\u{8a9e}", 4, "\u{2026}"), "\u{65e5}\u{2026}");
+        assert_eq!(fit_width(MIXED, 1), "e\u{301}");
+        assert_eq!(wrap_graphemes("abcdefg", 3), ["abc", "def", "g"]);
+    }
+
+    #[test]
+    fn visual_lines_wrap_at_words_and_newlines() {
+        assert_eq!(visual_lines("one two three\n\nfour", 8), [0..8, 8..13, 14..14, 15..19]);
+        assert_eq!(visual_lines("abcdefghij", 4), [0..4, 4..8, 8..10]);
+        assert_eq!(visual_lines("", 10), vec![0..0]);
+    }
+}
@@ -0,0 +1,230 @@
+//! Local HTTP control API — drive a running session from editors and scripts.
+//!
+//! Started with `--control` (or `--control=PORT`, `--control=HOST:PORT`), it
+//! listens on `127.0.0.1` unless another host is named explicitly. Every
+//! request must carry `Authorization: Bearer <token>`; the token comes from
+//! `CP_CONTROL_TOKEN` or is minted at startup, and the URL and token are
+//! written to `.context-pilot/control.json` (mode `0600`) for clients to find.
+//!
+//! | Method | Path | Body / query | Effect |
+//! |---|---|---|---|
+//! | `GET`  | `/transcript` | `?limit=N` (default 50) | last messages, oldest first |
+//! | `GET`  | `/panels` | | context panels, in sidebar order |
+//! | `POST` | `/message` | `{"content": "..."}` | send a message, as if typed |
+//! | `POST` | `/approve` | `{"path": "..."}` | approve the edit the agent waits on |
+//!
+//! The server threads only parse and authenticate: each accepted [`Request`]
+//! reaches the main loop as a [`Call`], which answers it on its own tick so
+//! session state is never touched off the loop.
+//!
+//! `--serve` starts the read-only sibling, a web page of the session
+//! ([`viewer`]).
+
+/// Routes and their parameters.
+pub mod request;
+/// The HTTP listener.
+mod server;
+/// `--serve`: the read-only web viewer.
+pub mod viewer;
+
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::{Read as _, Write as _};
+use std::os::unix::fs::OpenOptionsExt as _;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread;
+
+use tiny_http::Server;
+
+use cp_base::config::constants::STORE_DIR;
+
+use self::request::{Reply, Request};
+
+/// The command-line flag.
+pub const FLAG: &str = "--control";
+
+/// Environment variable that fixes the bearer token instead of minting one.
+const TOKEN_ENV: &str = "CP_CONTROL_TOKEN";
+
+/// Discovery file under the store, holding the URL and token.
+const DISCOVERY_FILE: &str = "control.json";
+
+/// Bytes of randomness behind a minted token (256 bits).
+const TOKEN_BYTES: usize = 32;
+
+/// A request waiting for the main loop's answer.
+#[derive(Debug)]
+pub struct Call {
+    /// What the client asked for.
+    request: Request,
+    /// Where the answer goes.
+    reply: SyncSender<Reply>,
+}
+
+impl Call {
+    /// What the client asked for.
+    #[must_use]
+    pub const fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// Answer the call. A client that gave up waiting is ignored.
+    pub fn respond(self, reply: Reply) {
+        drop(self.reply.send(reply));
+    }
+}
+
+/// A running control server.
+#[derive(Debug)]
+pub struct Control {
+    /// Calls accepted by the server threads.
+    calls: Receiver<Call>,
+    /// Base URL clients reach it at.
+    url: String,
+    /// The discovery file, removed on drop.
+    discovery: PathBuf,
+}
+
+impl Control {
+    /// The next call waiting to be answered, without blocking.
+    #[must_use]
+    pub fn poll(&self) -> Option<Call> {
+        self.calls.try_recv().ok()
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:41234`.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for Control {
+    fn drop(&mut self) {
+        drop(std::fs::remove_file(&self.discovery));
+    }
+}
+
+/// The address to bind for the value `spec` of `flag`: loopback on an
+/// ephemeral port by default, loopback on `PORT`, or `HOST:PORT` as given.
+fn bind_addr(flag: &str, spec: Option<&str>) -> Result<String, String> {
+    match spec {
+        None | Some("") => Ok("127.0.0.1:0".to_owned()),
+        Some(port) if port.parse::<u16>().is_ok() => Ok(format!("127.0.0.1:{port}")),
+        Some(addr) if addr.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) => Ok(addr.to_owned()),
+        Some(other) => Err(format!("{flag}: expected PORT or HOST:PORT, got `{other}`")),
+    }
+}
+
+/// The token from the environment variable `env`, or 256 fresh bits from
+/// `/dev/urandom` as hex.
+fn token(flag: &str, env: &str) -> Result<String, String> {
+    if let Some(token) = std::env::var(env).ok().filter(|t| !t.is_empty()) {
+        return Ok(token);
+    }
+    let mut buf = [0u8; TOKEN_BYTES];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut buf))
+        .map_err(|e| format!("{flag}: cannot mint a token: {e}"))?;
+    Ok(buf.iter().fold(String::with_capacity(TOKEN_BYTES.saturating_mul(2)), |mut hex, b| {
+        let _r = write!(hex, "{b:02x}");
+        hex
+    }))
+}
+
+/// Write `{"url", "token"}` to `path`, readable by the owner only.
+fn write_discovery(flag: &str, path: &Path, url: &str, token: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{flag}: {}: {e}", dir.display()))?;
+    }
+    let body = serde_json::json!({ "url": url, "token": token }).to_string();
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .and_then(|mut f| f.write_all(body.as_bytes()))
+        .map_err(|e| format!("{flag}: {}: {e}", path.display()))
+}
+
+/// Bind `addr`, publish the URL and `token` at `discovery`, and serve.
+fn start_at(addr: &str, token: String, discovery: PathBuf) -> Result<Control, String> {
+    let server = Server::http(addr).map_err(|e| format!("{FLAG}: cannot listen on {addr}: {e}"))?;
+    let local = server.server_addr().to_ip().ok_or_else(|| format!("{FLAG}: {addr} is not a TCP address"))?;
+    let url = format!("http://{local}");
+    write_discovery(FLAG, &discovery, &url, &token)?;
+    let (calls_tx, calls) = mpsc::channel();
+    let _thread = thread::Builder::new()
+        .name("control".to_owned())
+        .spawn(move || server::serve(&server, &token, &calls_tx))
+        .map_err(|e| format!("{FLAG}: cannot start the server thread: {e}"))?;
+    log::info!("control API listening on {url}");
+    Ok(Control { calls, url, discovery })
+}
+
+/// Start the control server for the flag value `spec` (`None` for a bare
+/// `--control`).
+///
+/// # Errors
+///
+/// A bad flag value, an address that cannot be bound, or a discovery file
+/// that cannot be written; the message is meant for stderr.
+pub fn start(spec: Option<&str>) -> Result<Control, String> {
+    start_at(&bind_addr(FLAG, spec)?, token(FLAG, TOKEN_ENV)?, Path::new(STORE_DIR).join(DISCOVERY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::TcpStream;
+
+    #[test]
+    fn binds_loopback_unless_a_host_is_named() {
+        assert_eq!(bind_addr(FLAG, None).ok().as_deref(), Some("127.0.0.1:0"));
+        assert_eq!(bind_addr(FLAG, Some("8080")).ok().as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(bind_addr(FLAG, Some("0.0.0.0:8080")).ok().as_deref(), Some("0.0.0.0:8080"));
+        assert_eq!(bind_addr(FLAG, Some("everywhere")).ok(), None);
+    }
+
+    /// Send a raw HTTP/1.0 request to `url` and return the response text.
+    fn send(url: &str, head: &str, body: &str) -> String {
+        let Some(addr) = url.strip_prefix("http://") else { return String::new() };
+        let Ok(mut stream) = TcpStream::connect(addr) else { return String::new() };
+        let request = format!("{head}\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+        let mut response = String::new();
+        let _sent = stream.write_all(request.as_bytes()).and_then(|()| stream.read_to_string(&mut response));
+        response
+    }
+
+    #[test]
+    fn authorized_calls_reach_the_loop_and_get_its_answer() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let discovery = dir.path().join(DISCOVERY_FILE);
+        let Ok(control) = start_at("127.0.0.1:0", "secret".to_owned(), discovery.clone()) else { return };
+        let published = std::fs::read_to_string(&discovery).unwrap_or_default();
+        assert!(published.contains(control.url()) && published.contains("secret"));
+
+        assert!(send(control.url(), "GET /panels HTTP/1.0\r\nAuthorization: Bearer wrong", "").contains(" 401 "));
+        assert!(control.poll().is_none());
+
+        let url = control.url().to_owned();
+        let client = thread::spawn(move || {
+            send(&url, "POST /message HTTP/1.0\r\nAuthorization: Bearer secret", r#"{"content":"hi"}"#)
+        });
+        let call = loop {
+            if let Some(call) = control.poll() {
+                break call;
+            }
+            thread::sleep(std::time::Duration::from_millis(5));
+        };
+        assert_eq!(call.request(), &Request::SendMessage { content: "hi".to_owned() });
+        call.respond(Reply::ok(serde_json::json!({ "sent": true })));
+        assert!(client.join().unwrap_or_default().ends_with(r#"{"sent":true}"#));
+
+        drop(control);
+        assert!(!discovery.exists());
+    }
+}
@@ -0,0 +1,117 @@
+use serde_json::{Value, json};
+
+/// Messages returned by `GET /transcript` when no `limit` is given.
+const DEFAULT_TRANSCRIPT_LIMIT: usize = 50;
+
+/// Most messages one `GET /transcript` returns.
+const MAX_TRANSCRIPT_LIMIT: usize = 500;
+
+/// What a client asked the session to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// `POST /message`: send `content` as if typed and submitted.
+    SendMessage {
+        /// Message text.
+        content: String,
+    },
+    /// `GET /transcript`: the last `limit` messages.
+    Transcript {
+        /// How many messages, counted from the end.
+        limit: usize,
+    },
+    /// `GET /panels`: the context panels, in sidebar order.
+    Panels,
+    /// `POST /approve`: approve the edit to `path` the agent is waiting on.
+    Approve {
+        /// File the approval names.
+        path: String,
+    },
+}
+
+/// A JSON response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response body.
+    pub body: Value,
+}
+
+impl Reply {
+    /// `200 OK` with `body`.
+    #[must_use]
+    pub const fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// An error status with `{"error": message}`.
+    #[must_use]
+    pub fn error(status: u16, message: &str) -> Self {
+        Self { status, body: json!({ "error": message }) }
+    }
+}
+
+/// The value of `key` in a `a=1&b=2` query string.
+fn query_param<'query>(query: &'query str, key: &str) -> Option<&'query str> {
+    query.split('&').filter_map(|pair| pair.split_once('=')).find(|&(k, _)| k == key).map(|(_, v)| v)
+}
+
+/// The non-empty string field `key` of a JSON `body`.
+fn body_field(body: &[u8], key: &str) -> Result<String, Reply> {
+    let value: Value =
+        serde_json::from_slice(body).map_err(|e| Reply::error(400, &format!("body is not JSON: {e}")))?;
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_owned)
+        .ok_or_else(|| Reply::error(400, &format!("missing string field `{key}`")))
+}
+
+/// Parse `GET /transcript?limit=N`.
+fn transcript(query: &str) -> Result<Request, Reply> {
+    let limit = match query_param(query, "limit") {
+        Some(raw) => raw.parse::<usize>().map_err(|_e| Reply::error(400, "`limit` must be a number"))?,
+        None => DEFAULT_TRANSCRIPT_LIMIT,
+    };
+    Ok(Request::Transcript { limit: limit.min(MAX_TRANSCRIPT_LIMIT) })
+}
+
+/// Turn a method, path, query string and body into a [`Request`], or the
+/// error reply for a bad one.
+pub(crate) fn route(method: &str, path: &str, query: &str, body: &[u8]) -> Result<Request, Reply> {
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/transcript") => transcript(query),
+        ("GET", "/panels") => Ok(Request::Panels),
+        ("POST", "/message") => Ok(Request::SendMessage { content: body_field(body, "content")? }),
+        ("POST", "/approve") => Ok(Request::Approve { path: body_field(body, "path")? }),
+        (_, "/transcript" | "/panels" | "/message" | "/approve") => Err(Reply::error(405, "method not allowed")),
+        _ => Err(Reply::error(404, "no such route")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_parse_their_parameters() {
+        assert_eq!(route("GET", "/panels/", "", b""), Ok(Request::Panels));
+        assert_eq!(route("GET", "/transcript", "", b""), Ok(Request::Transcript { limit: 50 }));
+        assert_eq!(route("GET", "/transcript", "x=1&limit=9999", b""), Ok(Request::Transcript { limit: 500 }));
+        let sent = route("POST", "/message", "", br#"{"content": "run the tests"}"#);
+        assert_eq!(sent, Ok(Request::SendMessage { content: "run the tests".to_owned() }));
+        let approved = route("POST", "/approve", "", br#"{"path": "src/lib.rs"}"#);
+        assert_eq!(approved, Ok(Request::Approve { path: "src/lib.rs".to_owned() }));
+    }
+
+    #[test]
+    fn bad_requests_get_error_statuses() {
+        let status = |r: Result<Request, Reply>| r.err().map(|reply| reply.status);
+        assert_eq!(status(route("GET", "/transcript", "limit=many", b"")), Some(400));
+        assert_eq!(status(route("POST", "/message", "", b"not json")), Some(400));
+        assert_eq!(status(route("POST", "/message", "", br#"{"content": "  "}"#)), Some(400));
+        assert_eq!(status(route("DELETE", "/panels", "", b"")), Some(405));
+        assert_eq!(status(route("GET", "/secrets", "", b"")), Some(404));
+    }
+}
@@ -0,0 +1,88 @@
+use std::io::Read as _;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use tiny_http::{Header, Response, Server};
+
+use crate::Call;
+use crate::request::{Reply, route};
+
+/// Largest request body read; a message is plain text, so this is generous.
+const MAX_BODY: u64 = 8 * 1024 * 1024;
+
+/// How long a request waits for the main loop before giving up. The loop
+/// answers within a tick unless it is blocked on a long synchronous step.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serve `server` until the process exits, one thread per request. Requests
+/// must carry `Authorization: Bearer <token>`; accepted ones are handed to the
+/// main loop through `calls`.
+pub(crate) fn serve(server: &Server, token: &str, calls: &Sender<Call>) {
+    for mut request in server.incoming_requests() {
+        let (token_owned, calls_owned) = (token.to_owned(), calls.clone());
+        let _handle = thread::spawn(move || {
+            let reply = answer(&mut request, &token_owned, &calls_owned);
+            respond(request, &reply);
+        });
+    }
+}
+
+/// Whether `a` equals `b`, in time independent of where they first differ.
+pub(crate) fn same(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Whether `request` presents `token` as its bearer.
+fn authorized(request: &tiny_http::Request, token: &str) -> bool {
+    request.headers().iter().any(|h| {
+        h.field.equiv("Authorization") && h.value.as_str().strip_prefix("Bearer ").is_some_and(|t| same(t, token))
+    })
+}
+
+/// Authenticate, parse and forward one request; the reply to send back.
+fn answer(request: &mut tiny_http::Request, token: &str, calls: &Sender<Call>) -> Reply {
+    if !authorized(request, token) {
+        return Reply::error(401, "missing or wrong bearer token");
+    }
+    let method = request.method().as_str().to_owned();
+    let url = request.url().to_owned();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let mut body = Vec::new();
+    if request.as_reader().take(MAX_BODY).read_to_end(&mut body).is_err() {
+        return Reply::error(400, "could not read the request body");
+    }
+    let parsed = match route(&method, path, query, &body) {
+        Ok(parsed) => parsed,
+        Err(reply) => return reply,
+    };
+    let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+    if calls.send(Call { request: parsed, reply: reply_tx }).is_err() {
+        return Reply::error(503, "the session is shutting down");
+    }
+    reply_rx.recv_timeout(REPLY_TIMEOUT).unwrap_or_else(|_e| Reply::error(503, "the session did not answer in time"))
+}
+
+/// Send `reply` as JSON.
+fn respond(request: tiny_http::Request, reply: &Reply) {
+    let mut response = Response::from_string(reply.body.to_string()).with_status_code(reply.status);
+    if let Ok(header) = Header::from_bytes("Content-Type", "application/json") {
+        response = response.with_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        log::debug!("control: could not send reply: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_compare_whole() {
+        assert!(same("abc123", "abc123"));
+        assert!(!same("abc123", "abc124"));
+        assert!(!same("abc", "abc123"));
+        assert!(!same("", "abc"));
+    }
+}
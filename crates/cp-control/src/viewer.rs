@@ -0,0 +1,211 @@
+//! Read-only web viewer — follow a running session from a browser.
+//!
+//! Started with `--serve` (or `--serve=PORT`, `--serve=HOST:PORT`; loopback
+//! unless a host is named, so `--serve=0.0.0.0:8080` for a phone on the same
+//! network). The main loop renders the page every couple of seconds and hands
+//! it over with [`Viewer::publish`]; the server thread only ever sends the
+//! latest copy, so a slow or idle browser never touches session state. The
+//! page refreshes itself in place.
+//!
+//! Browsers cannot send a bearer header from a typed URL, so the token rides
+//! in the query string instead: the full URL, token included, is written to
+//! `.context-pilot/serve.json` (mode `0600`).
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+use tiny_http::{Header, Response, Server};
+
+use cp_base::config::constants::STORE_DIR;
+
+use crate::server::same;
+
+/// The command-line flag.
+pub const FLAG: &str = "--serve";
+
+/// Environment variable that fixes the token instead of minting one.
+const TOKEN_ENV: &str = "CP_SERVE_TOKEN";
+
+/// Discovery file under the store, holding the URL.
+const DISCOVERY_FILE: &str = "serve.json";
+
+/// How often the main loop renders the page (ms).
+const PUBLISH_INTERVAL_MS: u64 = 2_000;
+
+/// How often the page fetches itself again (ms).
+const REFRESH_MS: u64 = 3_000;
+
+/// Shown until the main loop publishes the first page.
+const PLACEHOLDER: &str = "<p>Starting\u{2026}</p>";
+
+/// A running viewer.
+#[derive(Debug)]
+pub struct Viewer {
+    /// The latest page, shared with the server thread.
+    page: Arc<Mutex<String>>,
+    /// URL to open, token included.
+    url: String,
+    /// The discovery file, removed on drop.
+    discovery: PathBuf,
+    /// When the page was last published (ms).
+    published_ms: u64,
+}
+
+impl Viewer {
+    /// Whether the page is due for a new render at `now_ms`; if so, the
+    /// render is counted as done.
+    pub const fn due(&mut self, now_ms: u64) -> bool {
+        if now_ms.saturating_sub(self.published_ms) < PUBLISH_INTERVAL_MS {
+            return false;
+        }
+        self.published_ms = now_ms;
+        true
+    }
+
+    /// Serve `body` (HTML, inside `<main>`) under `title` from now on.
+    pub fn publish(&self, title: &str, body: &str) {
+        *self.page.lock().unwrap_or_else(PoisonError::into_inner) = page(title, body);
+    }
+
+    /// URL to open, e.g. `http://127.0.0.1:41234/?token=...`.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for Viewer {
+    fn drop(&mut self) {
+        drop(std::fs::remove_file(&self.discovery));
+    }
+}
+
+/// `text` with the HTML special characters escaped.
+#[must_use]
+pub fn escape(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut out, c| {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+        out
+    })
+}
+
+/// The whole document around `body`. The script swaps `<main>` for a fresh
+/// copy every [`REFRESH_MS`], keeping open `<details>` open.
+fn page(title: &str, body: &str) -> String {
+    let mut html = String::new();
+    let _r = write!(
+        html,
+        r#"<!doctype html><html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title><style>
+body{{font-family:system-ui,sans-serif;margin:0 auto;max-width:60rem;padding:0 .8rem;background:#111;color:#ddd}}
+pre{{white-space:pre-wrap;word-break:break-word;background:#1b1b1b;padding:.5rem;border-radius:4px}}
+.user{{color:#7fb8ff}}.assistant{{color:#9fdf9f}}.tool{{color:#aaa;font-size:.9em}}.error{{color:#ff8080}}
+summary{{cursor:pointer}}h2{{border-bottom:1px solid #333}}
+</style></head><body><main>{body}</main><script>
+setInterval(async()=>{{const r=await fetch(location.href);if(!r.ok)return;
+const next=new DOMParser().parseFromString(await r.text(),"text/html").querySelector("main");
+const open=[...document.querySelectorAll("details[open]")].map(d=>d.id);
+document.querySelector("main").innerHTML=next.innerHTML;
+open.forEach(id=>{{const d=document.getElementById(id);if(d)d.open=true}});}},{REFRESH_MS});
+</script></body></html>"#,
+        title = escape(title),
+    );
+    html
+}
+
+/// The `token` value of a `a=1&b=2` query string.
+fn query_token(url: &str) -> Option<&str> {
+    let query = url.split_once('?')?.1;
+    query.split('&').filter_map(|pair| pair.split_once('=')).find(|&(k, _)| k == "token").map(|(_, v)| v)
+}
+
+/// Send `body` as `content_type` with `status`.
+fn respond(request: tiny_http::Request, status: u16, content_type: &str, body: String) {
+    let mut response = Response::from_string(body).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+        response = response.with_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        log::debug!("viewer: could not send the page: {e}");
+    }
+}
+
+/// Serve the latest page until the process exits.
+fn serve(server: &Server, token: &str, page: &Mutex<String>) {
+    for request in server.incoming_requests() {
+        let url = request.url().to_owned();
+        let path = url.split_once('?').map_or(url.as_str(), |(path, _)| path);
+        if path != "/" && path != "/index.html" {
+            respond(request, 404, "text/plain", "not found".to_owned());
+        } else if !query_token(&url).is_some_and(|t| same(t, token)) {
+            respond(request, 401, "text/plain", "missing or wrong token".to_owned());
+        } else {
+            let html = page.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            respond(request, 200, "text/html; charset=utf-8", html);
+        }
+    }
+}
+
+/// Bind `addr`, publish the URL at `discovery`, and serve.
+fn start_at(addr: &str, token: String, discovery: PathBuf) -> Result<Viewer, String> {
+    let server = Server::http(addr).map_err(|e| format!("{FLAG}: cannot listen on {addr}: {e}"))?;
+    let local = server.server_addr().to_ip().ok_or_else(|| format!("{FLAG}: {addr} is not a TCP address"))?;
+    let url = format!("http://{local}/?token={token}");
+    crate::write_discovery(FLAG, &discovery, &url, &token)?;
+    let latest = Arc::new(Mutex::new(page("Context Pilot", PLACEHOLDER)));
+    let shared = Arc::clone(&latest);
+    let _thread = thread::Builder::new()
+        .name("viewer".to_owned())
+        .spawn(move || serve(&server, &token, &shared))
+        .map_err(|e| format!("{FLAG}: cannot start the server thread: {e}"))?;
+    log::info!("web viewer listening on http://{local}");
+    Ok(Viewer { page: latest, url, discovery, published_ms: 0 })
+}
+
+/// Start the viewer for the flag value `spec` (`None` for a bare `--serve`).
+///
+/// # Errors
+///
+/// A bad flag value, an address that cannot be bound, or a discovery file
+/// that cannot be written; the message is meant for stderr.
+pub fn start(spec: Option<&str>) -> Result<Viewer, String> {
+    start_at(&crate::bind_addr(FLAG, spec)?, crate::token(FLAG, TOKEN_ENV)?, Path::new(STORE_DIR).join(DISCOVERY_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{Read as _, Write as _};
+    use std::net::TcpStream;
+
+    /// `GET path` from `host` over HTTP/1.0; the response text.
+    fn get(host: &str, path: &str) -> String {
+        let Ok(mut stream) = TcpStream::connect(host) else { return String::new() };
+        let mut response = String::new();
+        let _sent = stream
+            .write_all(format!("GET {path} HTTP/1.0\r\n\r\n").as_bytes())
+            .and_then(|()| stream.read_to_string(&mut response));
+        response
+    }
+
+    #[test]
+    fn the_published_page_needs_the_token() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let Ok(viewer) = start_at("127.0.0.1:0", "secret".to_owned(), dir.path().join(DISCOVERY_FILE)) else {
+            return;
+        };
+        let host = viewer.url().trim_start_matches("http://").split('/').next().unwrap_or_default().to_owned();
+        viewer.publish("run", &escape("<b>U1</b>"));
+        assert!(get(&host, "/?token=wrong").contains(" 401 "));
+        assert!(get(&host, "/?token=secret").contains("&lt;b&gt;U1&lt;/b&gt;"));
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::OnceLock;
+
+/// A terminal graphics protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// The kitty graphics protocol (kitty, `WezTerm`, `Ghostty`).
+    Kitty,
+    /// DEC sixel (foot, mlterm, xterm with sixel), encoded by `img2sixel`.
+    Sixel,
+}
+
+/// Detected once per process.
+static PROTOCOL: OnceLock<Option<Protocol>> = OnceLock::new();
+
+/// The protocol to draw with, `None` for text only. Guessed from the
+/// environment; `CP_GRAPHICS=kitty|sixel|off` overrides the guess.
+#[must_use]
+pub fn protocol() -> Option<Protocol> {
+    *PROTOCOL.get_or_init(|| from_env(|key| std::env::var(key).ok()))
+}
+
+/// The protocol for an environment read through `var`.
+fn from_env(var: impl Fn(&str) -> Option<String>) -> Option<Protocol> {
+    match var("CP_GRAPHICS").as_deref() {
+        Some("kitty") => Some(Protocol::Kitty),
+        Some("sixel") => Some(Protocol::Sixel),
+        Some("off") => None,
+        // Multiplexers swallow graphics escapes unless specially configured.
+        _ if var("TMUX").is_some() || var("STY").is_some() => None,
+        _ => guess(
+            &var("TERM").unwrap_or_default(),
+            &var("TERM_PROGRAM").unwrap_or_default(),
+            var("KITTY_WINDOW_ID").is_some(),
+        ),
+    }
+}
+
+/// The protocol a terminal identified by `term` / `program` is known to speak.
+fn guess(term: &str, program: &str, kitty_window: bool) -> Option<Protocol> {
+    let kitty =
+        kitty_window || term.contains("kitty") || term.contains("ghostty") || matches!(program, "WezTerm" | "ghostty");
+    if kitty {
+        return Some(Protocol::Kitty);
+    }
+    let sixel = ["foot", "mlterm", "sixel"].iter().any(|name| term.contains(name));
+    sixel.then_some(Protocol::Sixel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let owned: Vec<(String, String)> = pairs.iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect();
+        move |key| owned.iter().find(|pair| pair.0 == key).map(|pair| pair.1.clone())
+    }
+
+    #[test]
+    fn detects_from_terminal_and_override() {
+        assert_eq!(from_env(env(&[("TERM", "xterm-kitty")])), Some(Protocol::Kitty));
+        assert_eq!(from_env(env(&[("TERM", "foot")])), Some(Protocol::Sixel));
+        assert_eq!(from_env(env(&[("TERM", "xterm-256color")])), None);
+        assert_eq!(from_env(env(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux")])), None);
+        assert_eq!(from_env(env(&[("TERM", "xterm-kitty"), ("CP_GRAPHICS", "off")])), None);
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::process::Command;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use cp_base::cast::Safe as _;
+
+use crate::Placement;
+use crate::detect::Protocol;
+
+/// Kitty: delete every image placement and free its data.
+pub(crate) const KITTY_DELETE_ALL: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+/// Kitty: base64 bytes per escape-sequence chunk (protocol maximum).
+const KITTY_CHUNK: usize = 4096;
+/// Assumed cell size in pixels when asking `img2sixel` for a size.
+const CELL_PX: (u16, u16) = (10, 20);
+
+/// Encoded payloads by image, size and protocol.
+type Payloads = Mutex<HashMap<String, Arc<str>>>;
+
+/// Payloads encoded so far.
+static PAYLOADS: LazyLock<Payloads> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The escape sequence drawing `placement` at the cursor, `None` while it is
+/// still being encoded (or when it cannot be).
+pub(crate) fn payload(protocol: Protocol, placement: &Placement) -> Option<Arc<str>> {
+    let image = &placement.image;
+    let key = format!("{protocol:?}:{}:{}:{}x{}", image.path.display(), image.modified, placement.cols, placement.rows);
+    if let Some(cached) = PAYLOADS.lock().unwrap_or_else(PoisonError::into_inner).get(&key) {
+        return Some(Arc::clone(cached));
+    }
+    match protocol {
+        Protocol::Kitty => {
+            let bytes = std::fs::read(&image.path).ok()?;
+            let encoded: Arc<str> = kitty(&bytes, placement.cols, placement.rows).into();
+            store(key, Arc::clone(&encoded));
+            Some(encoded)
+        }
+        Protocol::Sixel => {
+            let (path, cols, rows) = (image.path.clone(), placement.cols, placement.rows);
+            crate::spawn_once(key.clone(), move || {
+                if let Some(encoded) = sixel(&path, cols, rows) {
+                    store(key, encoded.into());
+                }
+            });
+            None
+        }
+    }
+}
+
+/// Remember an encoded payload.
+fn store(key: String, encoded: Arc<str>) {
+    let _previous = PAYLOADS.lock().unwrap_or_else(PoisonError::into_inner).insert(key, encoded);
+}
+
+/// Kitty transmit-and-display sequence for PNG `bytes`, scaled to
+/// `cols` × `rows` cells, leaving the cursor where it was.
+fn kitty(bytes: &[u8], cols: u16, rows: u16) -> String {
+    let encoded = STANDARD.encode(bytes);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK).collect();
+    let last = chunks.len().saturating_sub(1);
+    let mut out = String::with_capacity(encoded.len().saturating_add(chunks.len().saturating_mul(32)));
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i < last);
+        let data = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            let _r = write!(out, "\x1b_Ga=T,f=100,c={cols},r={rows},C=1,q=2,m={more};{data}\x1b\\");
+        } else {
+            let _r = write!(out, "\x1b_Gm={more};{data}\x1b\\");
+        }
+    }
+    out
+}
+
+/// Sixel data for the image at `path`, produced by `img2sixel` (libsixel).
+/// `None` when the tool is missing or fails — the reference stays text.
+fn sixel(path: &std::path::Path, cols: u16, rows: u16) -> Option<String> {
+    let width = cols.to_u32().saturating_mul(CELL_PX.0.to_u32());
+    let height = rows.to_u32().saturating_mul(CELL_PX.1.to_u32());
+    let output = Command::new("img2sixel")
+        .args(["-w", &width.to_string(), "-h", &height.to_string()])
+        .arg(path)
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kitty_payload_is_chunked() {
+        let bytes = vec![0u8; 6000];
+        let encoded = kitty(&bytes, 20, 10);
+        assert!(encoded.starts_with("\x1b_Ga=T,f=100,c=20,r=10,C=1,q=2,m=1;"));
+        assert_eq!(encoded.matches("\x1b_G").count(), 2);
+        assert!(encoded.contains("\x1b_Gm=0;"));
+    }
+}
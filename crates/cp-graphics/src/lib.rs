@@ -0,0 +1,161 @@
+//! Terminal graphics for Context Pilot panels.
+//!
+//! On terminals that speak the kitty graphics protocol or sixel, images
+//! referenced in a panel — markdown images, bare paths to image files (plots
+//! written by scratchpad runs), mermaid diagrams — are drawn inside the panel
+//! area. Everywhere else the reference simply stays text.
+//!
+//! The panel renderer [`source::scan`]s its lines, reserves rows under every
+//! reference whose image is ready, and [`queue`]s a [`Placement`] for each one
+//! fully on screen. Once ratatui has flushed the frame, [`flush`] writes the
+//! escape sequences — only when the placements changed since the last frame.
+//! Slow work (mermaid rendering, sixel encoding) runs on background threads;
+//! [`take_ready`] tells the main loop to redraw when a result lands.
+
+/// Protocol detection from the environment.
+pub mod detect;
+/// Escape-sequence payloads for both protocols.
+mod encode;
+/// Image references in panel text and the images they resolve to.
+pub mod source;
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use cp_base::cast::Safe as _;
+use detect::Protocol;
+use source::Image;
+
+/// Terminal cells per image row at most (before the panel height caps it).
+pub const MAX_IMAGE_ROWS: u16 = 16;
+
+/// One image drawn at a terminal cell position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Leftmost column.
+    pub x: u16,
+    /// Top row.
+    pub y: u16,
+    /// Width in cells.
+    pub cols: u16,
+    /// Height in cells.
+    pub rows: u16,
+    /// The image to draw.
+    pub image: Image,
+}
+
+/// Placements queued for the frame being drawn and those currently shown.
+#[derive(Default)]
+struct Screen {
+    /// Queued by the renderer during this frame.
+    queued: Vec<Placement>,
+    /// Drawn by the previous [`flush`].
+    shown: Vec<Placement>,
+}
+
+/// Placement bookkeeping shared by the renderer and [`flush`].
+static SCREEN: LazyLock<Mutex<Screen>> = LazyLock::new(|| Mutex::new(Screen::default()));
+/// Keys of the background jobs started so far.
+static JOBS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+/// Set when a background job finished and the panel should be redrawn.
+static READY: AtomicBool = AtomicBool::new(false);
+
+/// Whether images can be drawn at all.
+#[must_use]
+pub fn enabled() -> bool {
+    detect::protocol().is_some()
+}
+
+/// Cells an image of `image`'s aspect takes within `max_cols` × `max_rows`,
+/// assuming cells twice as tall as wide.
+#[must_use]
+pub fn fit(image: &Image, max_cols: u16, max_rows: u16) -> (u16, u16) {
+    let (w, h) = (u64::from(image.width.max(1)), u64::from(image.height.max(1)));
+    let full_rows = u64::from(max_rows.clamp(1, MAX_IMAGE_ROWS));
+    let full_cols = full_rows.saturating_mul(2).saturating_mul(w).checked_div(h).unwrap_or(1);
+    let (cols, rows) = if full_cols > u64::from(max_cols) {
+        let capped = u64::from(max_cols);
+        (capped, capped.saturating_mul(h).checked_div(w.saturating_mul(2)).unwrap_or(1))
+    } else {
+        (full_cols, full_rows)
+    };
+    (cols.clamp(1, u64::from(u16::MAX)).to_u16(), rows.clamp(1, u64::from(u16::MAX)).to_u16())
+}
+
+/// Queue `placement` for the frame being drawn.
+pub fn queue(placement: Placement) {
+    SCREEN.lock().unwrap_or_else(PoisonError::into_inner).queued.push(placement);
+}
+
+/// Draw the queued placements after ratatui flushed the frame.
+///
+/// Does nothing when they match what is already on screen; with kitty, images
+/// that left the screen are deleted first. A placement whose payload is still
+/// being encoded is skipped and retried on the next frame.
+///
+/// # Errors
+///
+/// Returns any error from writing to `out`.
+pub fn flush<W>(out: &mut W) -> io::Result<()>
+where
+    W: Write,
+{
+    let (queued, shown) = {
+        let mut screen = SCREEN.lock().unwrap_or_else(PoisonError::into_inner);
+        (std::mem::take(&mut screen.queued), screen.shown.clone())
+    };
+    let Some(protocol) = detect::protocol() else { return Ok(()) };
+    if queued == shown {
+        return Ok(());
+    }
+    if protocol == Protocol::Kitty {
+        out.write_all(encode::KITTY_DELETE_ALL.as_bytes())?;
+    }
+    let mut drawn = Vec::with_capacity(queued.len());
+    for placement in queued {
+        let Some(payload) = encode::payload(protocol, &placement) else { continue };
+        write!(out, "\x1b7\x1b[{};{}H", placement.y.saturating_add(1), placement.x.saturating_add(1))?;
+        out.write_all(payload.as_bytes())?;
+        out.write_all(b"\x1b8")?;
+        drawn.push(placement);
+    }
+    SCREEN.lock().unwrap_or_else(PoisonError::into_inner).shown = drawn;
+    out.flush()
+}
+
+/// Whether a background job finished since the last call (the panel should
+/// be redrawn).
+#[must_use]
+pub fn take_ready() -> bool {
+    READY.swap(false, Ordering::Relaxed)
+}
+
+/// Run `job` on a background thread once per `key`, then flag a redraw.
+fn spawn_once(key: String, job: impl FnOnce() + Send + 'static) {
+    if !JOBS.lock().unwrap_or_else(PoisonError::into_inner).insert(key) {
+        return;
+    }
+    let _handle = std::thread::spawn(move || {
+        job();
+        READY.store(true, Ordering::Relaxed);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32) -> Image {
+        Image { path: std::path::PathBuf::from("plot.png"), modified: 0, width, height }
+    }
+
+    #[test]
+    fn fit_keeps_the_aspect_ratio() {
+        // 800×400 at 16 rows → 64 cols (cells twice as tall as wide).
+        assert_eq!(fit(&image(800, 400), 120, 20), (64, 16));
+        // Too wide for 40 columns → rows shrink to match.
+        assert_eq!(fit(&image(800, 400), 40, 20), (40, 10));
+    }
+}
@@ -0,0 +1,175 @@
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use cp_base::config::constants::STORE_DIR;
+
+/// PNG file signature.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/// Opening fence of a mermaid block.
+const MERMAID_FENCE: &str = "```mermaid";
+
+/// A PNG image on disk, ready to draw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Path of the PNG file.
+    pub path: PathBuf,
+    /// Modification time (ms since the epoch), so edits are re-encoded.
+    pub modified: u64,
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+}
+
+/// What a panel line refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// An image file (markdown image or bare path).
+    File(PathBuf),
+    /// The body of a mermaid block.
+    Mermaid(String),
+}
+
+/// An image reference ending on panel line `line`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anchor {
+    /// Index of the reference's last line (the closing fence for mermaid).
+    pub line: usize,
+    /// What it refers to.
+    pub source: Source,
+}
+
+/// Find the image references in a panel's plain-text `lines`: markdown
+/// images `![alt](path.png)`, bare `.png` paths, and mermaid blocks.
+#[must_use]
+pub fn scan(lines: &[String]) -> Vec<Anchor> {
+    let mut anchors = Vec::new();
+    let mut mermaid: Option<String> = None;
+    for (line, text) in lines.iter().enumerate() {
+        match mermaid.as_mut() {
+            Some(body) if text.contains("```") => {
+                anchors.push(Anchor { line, source: Source::Mermaid(std::mem::take(body)) });
+                mermaid = None;
+            }
+            Some(body) => {
+                body.push_str(text);
+                body.push('\n');
+            }
+            None if text.contains(MERMAID_FENCE) => mermaid = Some(String::new()),
+            None => anchors.extend(image_path(text).map(|path| Anchor { line, source: Source::File(path) })),
+        }
+    }
+    anchors
+}
+
+/// The PNG path a line refers to: a markdown image target, else the first
+/// whitespace-separated word ending in `.png`.
+fn image_path(text: &str) -> Option<PathBuf> {
+    let markdown = text.find("![").and_then(|start| {
+        let rest = text.get(start..)?;
+        let open = rest.find("](")?.saturating_add(2);
+        let target = rest.get(open..)?;
+        let close = target.find(')')?;
+        target.get(..close)
+    });
+    let word = || {
+        text.split_whitespace()
+            .map(|w| {
+                w.trim_start_matches(['"', '\'', '`', '(']).trim_end_matches(['"', '\'', '`', ')', '.', ',', ';', ':'])
+            })
+            .find(|w| w.to_ascii_lowercase().ends_with(".png"))
+    };
+    markdown.or_else(word).filter(|p| p.to_ascii_lowercase().ends_with(".png")).map(PathBuf::from)
+}
+
+/// The drawable image behind `source`, `None` until it exists. Mermaid
+/// blocks are rendered to PNG by `mmdc` (mermaid-cli) in the background;
+/// without it they stay text.
+#[must_use]
+pub fn resolve(source: &Source) -> Option<Image> {
+    cp_base::deref_match!(source, {
+        Source::File(ref path) => load(path),
+        Source::Mermaid(ref body) => render(body),
+    })
+}
+
+/// The PNG of a mermaid block, rendering it in the background on first use.
+fn render(body: &str) -> Option<Image> {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let dir = Path::new(STORE_DIR).join("graphics");
+    let png = dir.join(format!("{:016x}.png", hasher.finish()));
+    if png.exists() {
+        return load(&png);
+    }
+    let owned = body.to_owned();
+    crate::spawn_once(png.display().to_string(), move || render_mermaid(&dir, &owned, &png));
+    None
+}
+
+/// Read a PNG's size and modification time.
+fn load(path: &Path) -> Option<Image> {
+    let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let modified = mtime
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+    let mut header = [0u8; 24];
+    std::fs::File::open(path).and_then(|mut f| f.read_exact(&mut header)).ok()?;
+    let (width, height) = png_size(&header)?;
+    Some(Image { path: path.to_path_buf(), modified, width, height })
+}
+
+/// Width and height from the first 24 bytes of a PNG (signature + IHDR).
+fn png_size(header: &[u8; 24]) -> Option<(u32, u32)> {
+    if header.get(..8)? != PNG_SIGNATURE {
+        return None;
+    }
+    let big_endian = |bytes: &[u8]| bytes.iter().fold(0u32, |acc, b| acc.wrapping_shl(8) | u32::from(*b));
+    Some((big_endian(header.get(16..20)?), big_endian(header.get(20..24)?)))
+}
+
+/// Render a mermaid `body` to `png` with `mmdc`.
+fn render_mermaid(dir: &Path, body: &str, png: &Path) {
+    let source = png.with_extension("mmd");
+    if std::fs::create_dir_all(dir).is_err() || std::fs::write(&source, body).is_err() {
+        return;
+    }
+    let _status = Command::new("mmdc").arg("-i").arg(&source).arg("-o").arg(png).args(["-b", "transparent"]).output();
+    let _removed = std::fs::remove_file(&source);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_finds_images_and_mermaid_blocks() {
+        let lines: Vec<String> = [
+            "Result: ![loss curve](out/loss.png)",
+            "saved plot to 'plots/acc.png'.",
+            "```mermaid",
+            "graph TD; A-->B",
+            "```",
+            "nothing here",
+        ]
+        .iter()
+        .map(|l| (*l).to_owned())
+        .collect();
+        let anchors = scan(&lines);
+        assert_eq!(anchors.len(), 3);
+        assert_eq!(anchors.first().map(|a| &a.source), Some(&Source::File(PathBuf::from("out/loss.png"))));
+        assert_eq!(anchors.get(1).map(|a| &a.source), Some(&Source::File(PathBuf::from("plots/acc.png"))));
+        assert_eq!(anchors.get(2), Some(&Anchor { line: 4, source: Source::Mermaid("graph TD; A-->B\n".to_owned()) }));
+    }
+
+    #[test]
+    fn png_size_reads_the_ihdr() {
+        let bytes = [PNG_SIGNATURE.as_slice(), &[0u8; 8], &[0, 0, 2, 128], &[0, 0, 1, 224]].concat();
+        let header: Option<[u8; 24]> = bytes.try_into().ok();
+        assert_eq!(header.and_then(|h| png_size(&h)), Some((640, 480)));
+        assert_eq!(png_size(&[0u8; 24]), None);
+    }
+}
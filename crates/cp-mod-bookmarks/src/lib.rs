@@ -0,0 +1,243 @@
+//! Bookmarks module — labelled anchors on lines of files.
+//!
+//! Three tools: `bookmark_add` (`path:line` plus a label), `bookmark_goto`
+//! (open the file panel scrolled to the line) and `bookmark_remove`. The
+//! user adds them with `/bookmark path:line label` and follows one with
+//! `/bookmark B2`.
+//! Each bookmark keeps the text of its line, so when edits shift the file it
+//! is found again on the way there. Bookmarks persist with the session and
+//! are listed in a fixed panel, where `1`..`9` goes to one.
+
+/// Panel rendering for bookmarks.
+mod panel;
+/// Tool implementations for adding, following and removing bookmarks.
+mod tools;
+/// Bookmark state types: `Bookmark`, `BookmarksState`.
+pub mod types;
+
+use types::BookmarksState;
+
+use serde_json::json;
+
+use cp_base::modules::ToolVisualizer;
+use cp_base::panels::Panel;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::pre_flight::Verdict;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::panel::BookmarksPanel;
+use cp_base::cast::Safe as _;
+use cp_base::modules::Module;
+
+/// Lazily-parsed tool descriptions loaded from the bookmarks YAML definition.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/bookmarks.yaml")));
+
+/// Index of the file panel showing bookmark `id`, once it is open.
+#[must_use]
+pub fn panel_of(state: &State, id: &str) -> Option<usize> {
+    let bookmark = state.get_ext::<BookmarksState>()?.bookmarks.iter().find(|b| b.id == id)?;
+    let canonical = std::fs::canonicalize(&bookmark.path).ok()?.to_string_lossy().into_owned();
+    state
+        .context
+        .iter()
+        .position(|c| c.context_type.as_str() == Kind::FILE && c.get_meta_str("file_path") == Some(canonical.as_str()))
+}
+
+/// Bookmarks module: labelled `path:line` anchors for long refactors.
+#[derive(Debug, Clone, Copy)]
+pub struct BookmarksModule;
+
+impl Default for BookmarksModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BookmarksModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for BookmarksModule {
+    fn id(&self) -> &'static str {
+        "bookmarks"
+    }
+    fn name(&self) -> &'static str {
+        "Bookmarks"
+    }
+    fn description(&self) -> &'static str {
+        "Labelled path:line anchors, reopened on demand"
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(BookmarksState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(BookmarksState::new());
+    }
+
+    fn save_module_data(&self, state: &State) -> serde_json::Value {
+        let bs = BookmarksState::get(state);
+        json!({
+            "bookmarks": bs.bookmarks,
+            "next_id": bs.next_id,
+        })
+    }
+    fn load_module_data(&self, data: &serde_json::Value, state: &mut State) {
+        let bs = BookmarksState::get_mut(state);
+        if let Some(arr) = data.get("bookmarks")
+            && let Ok(v) = serde_json::from_value(arr.clone())
+        {
+            bs.bookmarks = v;
+        }
+        if let Some(v) = data.get("next_id").and_then(serde_json::Value::as_u64) {
+            bs.next_id = v.to_usize();
+        }
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::BOOKMARKS)]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![(Kind::new(Kind::BOOKMARKS), "Bookmarks", false)]
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::BOOKMARKS => Some(Box::new(BookmarksPanel)),
+            _ => None,
+        }
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("bookmark_add", t)
+                .short_desc("Bookmark a line")
+                .category("Bookmarks")
+                .param("location", ParamType::String, true)
+                .param("label", ParamType::String, true)
+                .build(),
+            ToolDefinition::from_yaml("bookmark_goto", t)
+                .short_desc("Open a bookmarked line")
+                .category("Bookmarks")
+                .reverie_allowed(true)
+                .param("id", ParamType::String, true)
+                .build(),
+            ToolDefinition::from_yaml("bookmark_remove", t)
+                .short_desc("Delete bookmarks")
+                .category("Bookmarks")
+                .param_array("ids", ParamType::String, true)
+                .build(),
+        ]
+    }
+
+    fn pre_flight(&self, tool: &ToolUse, state: &State) -> Option<Verdict> {
+        (tool.name == "bookmark_goto").then(|| {
+            let mut pf = Verdict::new();
+            if let Some(id) = tool.input.get("id").and_then(|v| v.as_str())
+                && !BookmarksState::get(state).bookmarks.iter().any(|b| b.id == id)
+            {
+                pf.errors.push(format!("Bookmark '{id}' not found"));
+            }
+            pf
+        })
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        match tool.name.as_str() {
+            "bookmark_add" => Some(tools::execute_add(tool, state)),
+            "bookmark_goto" => Some(tools::execute_goto(tool, state)),
+            "bookmark_remove" => Some(tools::execute_remove(tool, state)),
+            _ => None,
+        }
+    }
+
+    fn tool_visualizers(&self) -> Vec<(&'static str, ToolVisualizer)> {
+        vec![]
+    }
+
+    fn context_type_metadata(&self) -> Vec<cp_base::state::context::TypeMeta> {
+        vec![cp_base::state::context::TypeMeta {
+            context_type: Kind::BOOKMARKS,
+            icon_id: "bookmarks",
+            is_fixed: true,
+            needs_cache: false,
+            fixed_order: Some(14),
+            display_name: "bookmarks",
+            short_name: "marks",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Bookmarks", "Labelled anchors on lines of files, to come back to")]
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &["files"]
+    }
+    fn is_core(&self) -> bool {
+        false
+    }
+    fn is_global(&self) -> bool {
+        false
+    }
+    fn save_worker_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+    fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+    fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
+        None
+    }
+    fn context_detail(&self, _ctx: &cp_base::state::context::Entry) -> Option<String> {
+        None
+    }
+    fn overview_context_section(&self, _state: &State) -> Option<String> {
+        None
+    }
+    fn overview_render_sections(&self, _state: &State) -> Vec<(u8, Vec<cp_render::Block>)> {
+        vec![]
+    }
+    fn on_close_context(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _state: &mut State,
+    ) -> Option<Result<String, String>> {
+        None
+    }
+    fn on_user_message(&self, _state: &mut State) {}
+    fn on_stream_stop(&self, _state: &mut State) {}
+
+    fn on_stream_chunk(&self, _text: &str, _state: &mut State) {}
+
+    fn on_tool_progress(&self, _tool_name: &str, _input_so_far: &str, _state: &mut State) {}
+
+    fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
+    fn watch_paths(&self, _state: &State) -> Vec<cp_base::panels::WatchSpec> {
+        vec![]
+    }
+    fn should_invalidate_on_fs_change(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _changed_path: &str,
+        _is_dir_event: bool,
+    ) -> bool {
+        false
+    }
+    fn watcher_immediate_refresh(&self) -> bool {
+        true
+    }
+}
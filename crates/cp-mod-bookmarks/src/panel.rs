@@ -0,0 +1,123 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use cp_base::panels::{Panel, scroll_key_action};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::types::BookmarksState;
+use std::fmt::Write as _;
+
+/// Panel that lists the bookmarks and provides them as LLM context.
+pub(crate) struct BookmarksPanel;
+
+impl BookmarksPanel {
+    /// Format the bookmarks for LLM context
+    fn format_for_context(state: &State) -> String {
+        let bs = BookmarksState::get(state);
+        if bs.bookmarks.is_empty() {
+            return "No bookmarks".to_owned();
+        }
+        let mut output = String::new();
+        for b in &bs.bookmarks {
+            let _r = writeln!(output, "[{}] {} \u{2014} {}\n    > {}", b.id, b.location(), b.label, b.snippet);
+        }
+        output.trim_end().to_owned()
+    }
+}
+
+impl Panel for BookmarksPanel {
+    fn handle_key(&self, key: &KeyEvent, state: &State) -> Option<Action> {
+        if let KeyCode::Char(digit @ '1'..='9') = key.code
+            && key.modifiers.is_empty()
+        {
+            let nth = digit.to_digit(10).and_then(|d| usize::try_from(d).ok()).unwrap_or(0).saturating_sub(1);
+            return BookmarksState::get(state).bookmarks.get(nth).map(|b| Action::GotoBookmark(b.id.clone()));
+        }
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        let bs = BookmarksState::get(state);
+        if bs.bookmarks.is_empty() {
+            return vec![
+                Block::Line(vec![S::muted("  No bookmarks".into()).italic()]),
+                Block::Line(vec![S::muted("  Type /bookmark path:line label to add one".into())]),
+            ];
+        }
+
+        let mut blocks = Vec::new();
+        for (n, b) in bs.bookmarks.iter().enumerate() {
+            let key = if n < 9 { format!(" {} ", n.saturating_add(1)) } else { "   ".to_owned() };
+            blocks.push(Block::Line(vec![
+                S::styled(key, Semantic::KeyHint),
+                S::accent(b.id.clone()).bold(),
+                S::new(" ".into()),
+                S::new(b.label.clone()).bold(),
+                S::muted(format!("  {}", b.location())),
+            ]));
+            blocks.push(Block::Line(vec![S::muted(format!("      {}", b.snippet))]));
+        }
+        blocks.push(Block::Empty);
+        blocks.push(Block::Line(vec![S::muted("  Go to: ".into()), S::styled("1..9".into(), Semantic::KeyHint)]));
+        blocks
+    }
+    fn title(&self, state: &State) -> String {
+        match BookmarksState::get(state).bookmarks.len() {
+            0 => "Bookmarks".to_owned(),
+            n => format!("Bookmarks ({n})"),
+        }
+    }
+
+    fn refresh(&self, state: &mut State) {
+        let content = Self::format_for_context(state);
+        let token_count = estimate_tokens(&content);
+
+        if let Some(ctx) = state.context.iter_mut().find(|c| c.context_type.as_str() == Kind::BOOKMARKS) {
+            ctx.token_count = token_count;
+            let _changed = cp_base::panels::update_if_changed(ctx, &content);
+        }
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let Some(ctx) = state.context.iter().find(|c| c.context_type.as_str() == Kind::BOOKMARKS) else {
+            return Vec::new();
+        };
+        vec![ContextItem::new(&ctx.id, "Bookmarks", Self::format_for_context(state), ctx.last_refresh_ms)]
+    }
+
+    fn needs_cache(&self) -> bool {
+        false
+    }
+    fn refresh_cache(&self, _request: cp_base::panels::CacheRequest) -> Option<cp_base::panels::CacheUpdate> {
+        None
+    }
+    fn build_cache_request(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _state: &State,
+    ) -> Option<cp_base::panels::CacheRequest> {
+        None
+    }
+    fn apply_cache_update(
+        &self,
+        _update: cp_base::panels::CacheUpdate,
+        _ctx: &mut cp_base::state::context::Entry,
+        _state: &mut State,
+    ) -> bool {
+        false
+    }
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        None
+    }
+    fn suicide(&self, _ctx: &cp_base::state::context::Entry, _state: &State) -> bool {
+        false
+    }
+}
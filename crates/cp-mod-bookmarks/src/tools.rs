@@ -0,0 +1,113 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::jail::Jail;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::types::{Bookmark, BookmarksState, parse_location, relocate};
+
+/// The text of `path`, for checking and following bookmarked lines. The
+/// workspace jail applies: a location outside it is never read.
+fn read(state: &State, path: &str) -> Result<String, String> {
+    let target = match state.get_ext::<Jail>() {
+        Some(jail) => jail.check(Path::new(path))?,
+        None => PathBuf::from(path),
+    };
+    std::fs::read_to_string(target).map_err(|e| format!("cannot read '{path}': {e}"))
+}
+
+/// Add a bookmark on `location` (`path:line`) with a `label`.
+pub(crate) fn execute_add(tool: &ToolUse, state: &mut State) -> ToolResult {
+    match add(tool, state) {
+        Ok(message) => ToolResult::new(tool.id.clone(), message, false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    }
+}
+
+/// Validate the location against the file and store the bookmark.
+fn add(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let location = tool.input.get("location").and_then(|v| v.as_str()).ok_or("Missing 'location' parameter")?;
+    let label = tool.input.get("label").and_then(|v| v.as_str()).unwrap_or("").trim().to_owned();
+    let (path, line) = parse_location(location)?;
+    let content = read(state, &path)?;
+    let snippet = content
+        .lines()
+        .nth(line.saturating_sub(1))
+        .ok_or_else(|| format!("'{path}' has {} lines, no line {line}", content.lines().count()))?;
+    let bs = BookmarksState::get_mut(state);
+    let id = format!("B{}", bs.next_id);
+    bs.next_id = bs.next_id.saturating_add(1);
+    let bookmark = Bookmark { id: id.clone(), path, line, label, snippet: snippet.trim().to_owned() };
+    let message = format!("Bookmarked {} as {id} '{}'", bookmark.location(), bookmark.label);
+    bs.bookmarks.push(bookmark);
+    state.touch_panel(Kind::BOOKMARKS);
+    Ok(message)
+}
+
+/// Show a bookmark's line in its file panel, opening the file if needed.
+pub(crate) fn execute_goto(tool: &ToolUse, state: &mut State) -> ToolResult {
+    match goto(tool, state) {
+        Ok(message) => ToolResult::new(tool.id.clone(), message, false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    }
+}
+
+/// Follow the line if edits moved it, then show it.
+fn goto(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let id = tool.input.get("id").and_then(|v| v.as_str()).ok_or("Missing 'id' parameter")?;
+    let bookmark = BookmarksState::get(state)
+        .bookmarks
+        .iter()
+        .find(|b| b.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Bookmark not found: {id}"))?;
+    let line = relocate(&read(state, &bookmark.path)?, bookmark.line, &bookmark.snippet);
+    let panel = cp_mod_files::show_line(state, &bookmark.path, line)?;
+    let mut message = format!("{id} '{}' {}:{line} shown in {panel}", bookmark.label, bookmark.path);
+    if line != bookmark.line {
+        let _r = write!(message, " (moved from line {})", bookmark.line);
+        if let Some(b) = BookmarksState::get_mut(state).bookmarks.iter_mut().find(|b| b.id == id) {
+            b.line = line;
+        }
+        state.touch_panel(Kind::BOOKMARKS);
+    }
+    Ok(message)
+}
+
+/// Remove bookmarks by ID, or all of them for an empty list.
+pub(crate) fn execute_remove(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(values) = tool.input.get("ids").and_then(|v| v.as_array()) else {
+        return ToolResult::new(tool.id.clone(), "Missing 'ids' array parameter".to_owned(), true);
+    };
+    let ids: Vec<&str> = values.iter().filter_map(serde_json::Value::as_str).collect();
+    let bs = BookmarksState::get_mut(state);
+    let before = bs.bookmarks.len();
+    bs.bookmarks.retain(|b| !ids.is_empty() && !ids.contains(&b.id.as_str()));
+    let removed = before.saturating_sub(bs.bookmarks.len());
+    if removed > 0 {
+        state.touch_panel(Kind::BOOKMARKS);
+    }
+    let mut output = format!("Removed {removed} bookmark(s)");
+    if removed < ids.len() {
+        let _r = write!(output, ", {} not found", ids.len().saturating_sub(removed));
+    }
+    ToolResult::new(tool.id.clone(), output, removed == 0 && !ids.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn locations_outside_the_jail_are_not_read() {
+        let mut state = State::default();
+        state.set_ext(Jail::new(true, vec![]));
+        let tool = ToolUse::new("t".to_owned(), "bookmark_add".to_owned(), json!({"location": "/etc/passwd:1"}));
+        let result = execute_add(&tool, &mut state);
+        assert!(result.is_error);
+        assert!(result.content.contains("workspace jail"), "{}", result.content);
+    }
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+use cp_base::state::runtime::State;
+
+/// A labelled anchor on one line of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Bookmark ID (B1, B2, ...).
+    pub id: String,
+    /// File path, as given when added.
+    pub path: String,
+    /// Line number, 1-based; follows the line when edits move it.
+    pub line: usize,
+    /// What the place is.
+    pub label: String,
+    /// The line's text (trimmed) when last seen, used to find it again.
+    pub snippet: String,
+}
+
+impl Bookmark {
+    /// `path:line`.
+    #[must_use]
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+}
+
+/// Module-owned state for the Bookmarks module.
+#[derive(Debug)]
+pub struct BookmarksState {
+    /// All bookmarks, ordered by creation.
+    pub bookmarks: Vec<Bookmark>,
+    /// Counter for generating unique IDs (B1, B2, ...).
+    pub next_id: usize,
+}
+
+impl Default for BookmarksState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BookmarksState {
+    /// Create an empty bookmarks state with ID counter at 1.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { bookmarks: vec![], next_id: 1 }
+    }
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+}
+
+/// Split `path:line` into its parts.
+///
+/// # Errors
+///
+/// No `:line` suffix, or a line that is not a positive number.
+pub fn parse_location(location: &str) -> Result<(String, usize), String> {
+    let (path, line) = location.rsplit_once(':').ok_or_else(|| format!("'{location}' is not path:line"))?;
+    match line.trim().parse::<usize>() {
+        Ok(n) if n > 0 && !path.trim().is_empty() => Ok((path.trim().to_owned(), n)),
+        Ok(_) | Err(_) => Err(format!("'{location}' is not path:line with a line from 1")),
+    }
+}
+
+/// Where the bookmarked line is now in `content`: `line` itself when its text
+/// is still `snippet`, else the nearest line with that text, else `line`.
+#[must_use]
+pub fn relocate(content: &str, line: usize, snippet: &str) -> usize {
+    let lines: Vec<&str> = content.lines().collect();
+    let same = |n: usize| lines.get(n.wrapping_sub(1)).is_some_and(|l| l.trim() == snippet);
+    if snippet.is_empty() || same(line) {
+        return line;
+    }
+    (1..=lines.len()).filter(|&n| same(n)).min_by_key(|&n| n.abs_diff(line)).unwrap_or(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locations_split_on_the_last_colon() {
+        assert_eq!(parse_location("src/main.rs:42"), Ok(("src/main.rs".to_owned(), 42)));
+        assert_eq!(parse_location("C:/x.rs:7"), Ok(("C:/x.rs".to_owned(), 7)));
+        assert!(parse_location("src/main.rs").is_err_and(|e| e.contains("path:line")));
+        assert!(parse_location("src/main.rs:0").is_err_and(|e| e.contains("from 1")));
+    }
+
+    #[test]
+    fn bookmarks_follow_their_line() {
+        let content = "a\nfn target() {\nb\nc\nfn target() {\n";
+        assert_eq!(relocate(content, 2, "fn target() {"), 2);
+        assert_eq!(relocate("x\n".repeat(3).as_str(), 2, "fn target() {"), 2);
+        assert_eq!(relocate(content, 4, "fn target() {"), 5);
+        assert_eq!(relocate(content, 3, "fn target() {"), 2);
+    }
+}
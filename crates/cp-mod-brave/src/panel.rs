@@ -1,9 +1,10 @@
 use crossterm::event::KeyEvent;
 
 use cp_base::panels::scroll_key_action;
-use cp_base::panels::{CacheRequest, CacheUpdate, ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, paginate_content, update_if_changed};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
 use cp_base::state::runtime::State;
 
 /// Context type identifier for Brave result panels.
@@ -129,7 +130,10 @@ impl Panel for Results {
             .filter_map(|c| {
                 let content = c.cached_content.as_ref()?;
                 let output = paginate_content(content, c.current_page, c.total_pages, &c.page_descriptions);
-                Some(ContextItem::new(&c.id, &c.name, output, c.last_refresh_ms))
+                Some(
+                    ContextItem::new(&c.id, &c.name, output, c.last_refresh_ms)
+                        .with_provenance(Provenance::external_web(&c.name)),
+                )
             })
             .collect()
     }
@@ -4,8 +4,9 @@ use std::path::PathBuf;
 use cp_base::config::INJECTIONS;
 
 use cp_base::config::constants;
-use cp_base::panels::{ContextItem, Panel};
+use cp_base::panels::Panel;
 use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
 use crate::types::CallbackState;
@@ -1,6 +1,7 @@
 use cp_base::panels::{CacheRequest, CacheUpdate, hash_content};
-use cp_base::panels::{ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::panels::{Panel, paginate_content, update_if_changed};
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
 use cp_base::state::runtime::State;
 
 use crate::types::ConsoleState;
@@ -200,6 +201,10 @@ impl Panel for ConsolePanel {
         0
     }
 
+    fn diff_on_refresh(&self) -> bool {
+        true
+    }
+
     fn context(&self, state: &State) -> Vec<ContextItem> {
         state
             .context
@@ -214,7 +219,11 @@ impl Panel for ConsolePanel {
 
                 // Content is already truncated to MAX_CONTEXT_CHARS in refresh_cache
                 let output = paginate_content(content, c.current_page, c.total_pages, &c.page_descriptions);
-                Some(ContextItem::new(&c.id, header, output, c.last_refresh_ms))
+                let source = c.get_meta_str("console_command").unwrap_or(desc);
+                Some(
+                    ContextItem::new(&c.id, header, output, c.last_refresh_ms)
+                        .with_provenance(Provenance::repo(source)),
+                )
             })
             .collect()
     }
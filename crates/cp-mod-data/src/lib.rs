@@ -0,0 +1,166 @@
+//! Data module — structured views of JSON, YAML and tabular files.
+//!
+//! `data_view` opens a `.json`/`.yaml` file, or the JSON of a tool result
+//! panel, as a tree folded below a chosen depth; every line carries the
+//! node's path as a breadcrumb. `json_query` evaluates jq-like paths on the
+//! same sources, so a field can be read without loading the whole document.
+//! `table_head` and `table_stats` preview CSV/TSV and Parquet files.
+
+/// Panel rendering a document as a tree.
+mod panel;
+/// The jq-like query language.
+mod query;
+/// Document sources: files and tool result panels.
+mod source;
+/// CSV/Parquet table previews.
+mod table;
+/// Tool dispatch.
+mod tools;
+/// Tree rendering with folding.
+mod tree;
+
+use cp_base::modules::Module;
+use cp_base::panels::{Panel, WatchSpec};
+use cp_base::state::context::{Entry, Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+/// Lazily parsed tool texts from the data YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/data.yaml")));
+
+/// The file a data or table panel follows, if it was opened on one.
+fn watched_file(ctx: &Entry) -> Option<&str> {
+    match ctx.context_type.as_str() {
+        panel::DATA_PANEL_TYPE => ctx.get_meta_str(panel::META_PATH),
+        table::panel::TABLE_PANEL_TYPE => ctx.get_meta_str(table::panel::META_PATH),
+        _ => None,
+    }
+}
+
+/// Data module: tree panels and path queries over JSON/YAML.
+#[derive(Debug, Clone, Copy)]
+pub struct DataModule;
+
+impl Default for DataModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for DataModule {
+    fn id(&self) -> &'static str {
+        "data"
+    }
+
+    fn name(&self) -> &'static str {
+        "Data"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tree views of JSON/YAML, jq-like queries and table previews"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![
+            TypeMeta {
+                context_type: panel::DATA_PANEL_TYPE,
+                icon_id: "tree",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "data",
+                short_name: "data",
+                needs_async_wait: false,
+            },
+            TypeMeta {
+                context_type: table::panel::TABLE_PANEL_TYPE,
+                icon_id: "entities",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "table",
+                short_name: "table",
+                needs_async_wait: false,
+            },
+        ]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(panel::DATA_PANEL_TYPE), Kind::new(table::panel::TABLE_PANEL_TYPE)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("data_view", t)
+                .short_desc("Open JSON/YAML as a tree")
+                .category("Data")
+                .param("path", ParamType::String, false)
+                .param("panel", ParamType::String, false)
+                .param_array("expand", ParamType::String, false)
+                .param("depth", ParamType::Integer, false)
+                .build(),
+            ToolDefinition::from_yaml("json_query", t)
+                .short_desc("Query JSON/YAML by path")
+                .category("Data")
+                .param("query", ParamType::String, true)
+                .param("path", ParamType::String, false)
+                .param("panel", ParamType::String, false)
+                .build(),
+            ToolDefinition::from_yaml("table_head", t)
+                .short_desc("Preview a CSV/Parquet file")
+                .category("Data")
+                .param("path", ParamType::String, true)
+                .param("rows", ParamType::Integer, false)
+                .build(),
+            ToolDefinition::from_yaml("table_stats", t)
+                .short_desc("Column statistics of a table")
+                .category("Data")
+                .param("path", ParamType::String, true)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        tools::dispatch(tool, state)
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            panel::DATA_PANEL_TYPE => Some(Box::new(panel::DataPanel)),
+            table::panel::TABLE_PANEL_TYPE => Some(Box::new(table::panel::TablePanel)),
+            _ => None,
+        }
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Data", "Browse and query structured documents")]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn watch_paths(&self, state: &State) -> Vec<WatchSpec> {
+        state.context.iter().filter_map(watched_file).map(|p| WatchSpec::File(p.to_owned())).collect()
+    }
+
+    fn should_invalidate_on_fs_change(&self, ctx: &Entry, changed_path: &str, is_dir_event: bool) -> bool {
+        !is_dir_event && watched_file(ctx) == Some(changed_path)
+    }
+}
@@ -0,0 +1,155 @@
+use crossterm::event::KeyEvent;
+
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
+use cp_base::state::runtime::State;
+
+use crate::source::Source;
+use crate::tree::{self, Folding};
+
+/// Context type of structured data panels.
+pub(crate) const DATA_PANEL_TYPE: &str = "data_view";
+
+/// Metadata key: canonical path of a file source.
+pub(crate) const META_PATH: &str = "data_path";
+
+/// Metadata key: id of the panel a copied document came from.
+pub(crate) const META_FROM: &str = "data_from";
+
+/// Metadata key: the copied JSON text.
+pub(crate) const META_CONTENT: &str = "data_content";
+
+/// Metadata key: paths opened explicitly.
+pub(crate) const META_EXPAND: &str = "data_expand";
+
+/// Metadata key: depth shown open.
+pub(crate) const META_DEPTH: &str = "data_depth";
+
+/// Levels open when no depth is given.
+pub(crate) const DEFAULT_DEPTH: usize = 2;
+
+/// Data sent to the cache thread for a tree refresh.
+struct DataRequest {
+    /// Panel to fill.
+    context_id: String,
+    /// Document shown.
+    source: Source,
+    /// Paths opened explicitly.
+    expand: Vec<String>,
+    /// Depth shown open.
+    depth: usize,
+}
+
+/// Panel showing a document as a tree.
+pub(crate) struct DataPanel;
+
+/// A file is workspace content; data lifted from another panel may have
+/// been fetched from the web, so it is not trusted.
+fn provenance(ctx: &Entry) -> Provenance {
+    ctx.get_meta_str(META_PATH).map_or_else(
+        || Provenance::external_web(format!("panel {}", ctx.get_meta_str(META_FROM).unwrap_or("?"))),
+        Provenance::repo,
+    )
+}
+
+impl Panel for DataPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &Entry, _state: &State) -> Option<CacheRequest> {
+        Some(CacheRequest::new(
+            Kind::new(DATA_PANEL_TYPE),
+            Box::new(DataRequest {
+                context_id: ctx.id.clone(),
+                source: Source::of_panel(ctx)?,
+                expand: ctx.get_meta::<Vec<String>>(META_EXPAND).unwrap_or_default(),
+                depth: ctx.get_meta_usize(META_DEPTH).unwrap_or(DEFAULT_DEPTH),
+            }),
+        ))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<DataRequest>().ok()?;
+        let label = req.source.label();
+        let content = match req.source.load() {
+            Ok(doc) => {
+                let title = format!("{label} (every path below works in json_query)");
+                tree::render(&doc, &title, &Folding { depth: req.depth, expand: &req.expand })
+            }
+            Err(e) => format!("{label}: {e}\n"),
+        };
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id: req.context_id, content, token_count })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, _state: &mut State) -> bool {
+        // Only a Content update carries new bytes; Unchanged/ModuleSpecific are no-ops.
+        if let CacheUpdate::Content { content, token_count, .. } = update {
+            ctx.token_count = token_count;
+            ctx.full_token_count = token_count;
+            ctx.cache_deprecated = false;
+            let changed = update_if_changed(ctx, &content);
+            ctx.cached_content = Some(content);
+            changed
+        } else {
+            false
+        }
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Span as S};
+
+        let cached = state
+            .context
+            .get(state.selected_context)
+            .filter(|c| c.context_type.as_str() == DATA_PANEL_TYPE)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content
+            .lines()
+            .map(|line| match line.split_once(": ") {
+                Some((path, rest)) if path.trim_start().starts_with('.') => {
+                    Block::Line(vec![S::accent(format!(" {path}")), S::muted(": ".into()), S::new(rest.to_owned())])
+                }
+                _ => Block::text(format!(" {line}")),
+            })
+            .collect()
+    }
+
+    fn title(&self, state: &State) -> String {
+        state.context.get(state.selected_context).map_or_else(|| "Data".to_owned(), |ctx| ctx.name.clone())
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == DATA_PANEL_TYPE)
+            .filter_map(|c| {
+                let content = c.cached_content.as_ref()?;
+                Some(
+                    ContextItem::new(&c.id, &c.name, content.clone(), c.last_refresh_ms).with_provenance(provenance(c)),
+                )
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
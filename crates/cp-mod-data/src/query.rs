@@ -0,0 +1,212 @@
+//! A jq-like path language, just enough to pull fields out of a document.
+//!
+//! A query is a pipeline of stages separated by `|`. A stage is a path
+//! (`.`, `.name`, `."odd key"`, `.items[0]`, `.items[-1]`, `.items[]`) or one
+//! of the builtins `keys`, `length` and `type`. Each stage maps every value
+//! of the stream; `[]` fans one value out into many, like jq.
+
+use serde_json::Value;
+
+/// One step of a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// Object field.
+    Key(String),
+    /// Array element; negative counts from the end.
+    Index(i64),
+    /// Every element of an array, or every value of an object.
+    Iterate,
+}
+
+/// One stage of a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Stage {
+    /// Walk a path.
+    Path(Vec<Segment>),
+    /// Object keys (sorted) or array indices.
+    Keys,
+    /// Element count, string length in chars, 0 for null.
+    Length,
+    /// JSON type name.
+    Type,
+}
+
+/// Evaluate `query` against `doc`; the resulting stream of values.
+pub(crate) fn evaluate(doc: &Value, query: &str) -> Result<Vec<Value>, String> {
+    let mut stream = vec![doc.clone()];
+    for raw in query.split('|') {
+        let stage = parse_stage(raw.trim())?;
+        stream = stream.iter().map(|v| apply(&stage, v)).collect::<Result<Vec<_>, _>>()?.concat();
+    }
+    Ok(stream)
+}
+
+/// Parse one pipeline stage.
+fn parse_stage(text: &str) -> Result<Stage, String> {
+    match text {
+        "keys" => Ok(Stage::Keys),
+        "length" => Ok(Stage::Length),
+        "type" => Ok(Stage::Type),
+        "" => Err("empty stage in query".to_owned()),
+        _ => parse_path(text).map(Stage::Path),
+    }
+}
+
+/// Parse a path such as `.items[0]."odd key"[]`.
+fn parse_path(text: &str) -> Result<Vec<Segment>, String> {
+    if !text.starts_with(['.', '[']) {
+        return Err(format!("'{text}': a path starts with '.'"));
+    }
+    let mut segments = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' if chars.peek() == Some(&'"') => {
+                let _quote = chars.next();
+                segments.push(Segment::Key(quoted(&mut chars)?));
+            }
+            '.' => {
+                let mut name = String::new();
+                while let Some(n) = chars.next_if(|n| n.is_alphanumeric() || *n == '_' || *n == '-') {
+                    name.push(n);
+                }
+                if !name.is_empty() {
+                    segments.push(Segment::Key(name));
+                }
+            }
+            '[' => segments.push(bracket(&mut chars)?),
+            other => return Err(format!("'{text}': unexpected '{other}'")),
+        }
+    }
+    Ok(segments)
+}
+
+/// The rest of a `"..."` string, the opening quote already consumed.
+fn quoted(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Result<String, String> {
+    let mut out = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Ok(out),
+            '\\' => out.extend(chars.next()),
+            _ => out.push(c),
+        }
+    }
+    Err("unterminated string in query".to_owned())
+}
+
+/// The inside of `[...]`, the opening bracket already consumed.
+fn bracket(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Result<Segment, String> {
+    if chars.next_if_eq(&'"').is_some() {
+        let key = quoted(chars)?;
+        return chars.next_if_eq(&']').map(|_close| Segment::Key(key)).ok_or_else(|| "expected ']'".to_owned());
+    }
+    let inner: String = chars.by_ref().take_while(|c| *c != ']').collect();
+    let trimmed = inner.trim();
+    if trimmed.is_empty() {
+        return Ok(Segment::Iterate);
+    }
+    trimmed.parse::<i64>().map(Segment::Index).map_err(|_nan| format!("'[{trimmed}]': index must be an integer"))
+}
+
+/// Apply one stage to one value.
+fn apply(stage: &Stage, value: &Value) -> Result<Vec<Value>, String> {
+    cp_base::deref_match!(stage, {
+        Stage::Path(ref segments) => segments.iter().try_fold(vec![value.clone()], |stream, segment| {
+            Ok(stream.iter().map(|v| step(segment, v)).collect::<Result<Vec<_>, String>>()?.concat())
+        }),
+        Stage::Keys => keys(value).map(|k| vec![k]),
+        Stage::Length => length(value).map(|n| vec![Value::from(n)]),
+        Stage::Type => Ok(vec![Value::from(type_name(value))]),
+    })
+}
+
+/// Walk one segment from one value; fields and indices of null are null.
+fn step(segment: &Segment, value: &Value) -> Result<Vec<Value>, String> {
+    if value.is_null() && *segment != Segment::Iterate {
+        return Ok(vec![Value::Null]);
+    }
+    let kind = type_name(value);
+    cp_base::deref_match!(segment, {
+        Segment::Key(ref key) => value
+            .as_object()
+            .map(|map| vec![map.get(key).cloned().unwrap_or(Value::Null)])
+            .ok_or_else(|| format!("cannot index {kind} with \"{key}\"")),
+        Segment::Index(index) => value
+            .as_array()
+            .map(|items| vec![nth(items, index)])
+            .ok_or_else(|| format!("cannot index {kind} with [{index}]")),
+        Segment::Iterate => value
+            .as_array()
+            .cloned()
+            .or_else(|| value.as_object().map(|map| map.values().cloned().collect()))
+            .ok_or_else(|| format!("cannot iterate over {kind}")),
+    })
+}
+
+/// Element `index` of `items`, negative from the end; null when out of range.
+fn nth(items: &[Value], index: i64) -> Value {
+    let len = i64::try_from(items.len()).unwrap_or(i64::MAX);
+    let at = if index < 0 { len.saturating_add(index) } else { index };
+    usize::try_from(at).ok().and_then(|i| items.get(i)).cloned().unwrap_or(Value::Null)
+}
+
+/// `keys`: sorted object keys, or array indices.
+fn keys(value: &Value) -> Result<Value, String> {
+    cp_base::deref_match!(value, {
+        Value::Object(ref map) => {
+            let mut names: Vec<&String> = map.keys().collect();
+            names.sort();
+            Ok(Value::from(names.into_iter().cloned().collect::<Vec<_>>()))
+        }
+        Value::Array(ref items) => Ok(Value::from((0..items.len()).collect::<Vec<_>>())),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {
+            Err(format!("{} has no keys", type_name(value)))
+        }
+    })
+}
+
+/// `length`: element count, characters of a string, 0 for null.
+fn length(value: &Value) -> Result<usize, String> {
+    cp_base::deref_match!(value, {
+        Value::Null => Ok(0),
+        Value::Array(ref items) => Ok(items.len()),
+        Value::Object(ref map) => Ok(map.len()),
+        Value::String(ref s) => Ok(s.chars().count()),
+        Value::Bool(_) | Value::Number(_) => Err(format!("{} has no length", type_name(value))),
+    })
+}
+
+/// jq's name for the type of `value`.
+pub(crate) const fn type_name(value: &Value) -> &'static str {
+    match *value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn paths_and_iteration() {
+        let doc = json!({"items": [{"name": "a", "tags": [1i32, 2i32]}, {"name": "b", "tags": []}]});
+        assert_eq!(evaluate(&doc, ".items[].name"), Ok(vec![json!("a"), json!("b")]));
+        assert_eq!(evaluate(&doc, ".items[-1].name"), Ok(vec![json!("b")]));
+        assert_eq!(evaluate(&doc, ".items[0].tags | length"), Ok(vec![json!(2i32)]));
+    }
+
+    #[test]
+    fn quoted_keys_builtins_and_missing_paths() {
+        let doc = json!({"items": [{"name": "a"}], "odd key": true});
+        assert_eq!(evaluate(&doc, ".\"odd key\""), Ok(vec![json!(true)]));
+        assert_eq!(evaluate(&doc, "keys"), Ok(vec![json!(["items", "odd key"])]));
+        assert_eq!(evaluate(&doc, ".missing.deeper"), Ok(vec![Value::Null]));
+        assert_eq!(evaluate(&doc, ".items.name").ok(), None);
+    }
+}
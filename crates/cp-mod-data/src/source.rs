@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io::Read as _;
+use std::path::Path;
+
+use serde_json::Value;
+
+use cp_base::state::context::Entry;
+use cp_base::state::runtime::State;
+use cp_base::tools::ToolUse;
+
+use crate::panel::{DATA_PANEL_TYPE, META_CONTENT, META_FROM, META_PATH};
+
+/// Largest document read from disk.
+const MAX_DOCUMENT_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Where a document comes from.
+#[derive(Debug, Clone)]
+pub(crate) enum Source {
+    /// A `.json`/`.yaml` file, by canonical path (re-read on change).
+    File(String),
+    /// JSON text copied out of another panel.
+    Panel {
+        /// Id of the panel it came from.
+        from: String,
+        /// The JSON text.
+        text: String,
+    },
+}
+
+impl Source {
+    /// Short name for titles and messages.
+    pub(crate) fn label(&self) -> String {
+        cp_base::deref_match!(self, {
+            Self::File(ref path) => {
+                Path::new(path).file_name().map_or_else(|| path.clone(), |n| n.to_string_lossy().into_owned())
+            }
+            Self::Panel { ref from, .. } => format!("{from} result"),
+        })
+    }
+
+    /// Parse the document.
+    pub(crate) fn load(&self) -> Result<Value, String> {
+        cp_base::deref_match!(self, {
+            Self::File(ref path) => parse_file(path),
+            Self::Panel { ref from, ref text } => {
+                parse_json_text(text).ok_or_else(|| format!("panel {from} holds no JSON"))
+            }
+        })
+    }
+
+    /// The source a data panel was opened on.
+    pub(crate) fn of_panel(ctx: &Entry) -> Option<Self> {
+        if let Some(path) = ctx.get_meta_str(META_PATH) {
+            return Some(Self::File(path.to_owned()));
+        }
+        let from = ctx.get_meta_str(META_FROM)?.to_owned();
+        Some(Self::Panel { from, text: ctx.get_meta_str(META_CONTENT)?.to_owned() })
+    }
+
+    /// The JSON text of a copied document.
+    pub(crate) const fn copied_text(&self) -> Option<&str> {
+        cp_base::deref_match!(self, {
+            Self::File(_) => None,
+            Self::Panel { ref text, .. } => Some(text.as_str()),
+        })
+    }
+
+    /// Whether a data panel shows this source.
+    pub(crate) fn matches(&self, ctx: &Entry) -> bool {
+        cp_base::deref_match!(self, {
+            Self::File(ref path) => ctx.get_meta_str(META_PATH) == Some(path.as_str()),
+            Self::Panel { ref from, .. } => ctx.get_meta_str(META_FROM) == Some(from.as_str()),
+        })
+    }
+}
+
+/// Source named by a tool's `path` or `panel` parameter. A data panel
+/// stands for the document it shows.
+pub(crate) fn from_tool(tool: &ToolUse, state: &State) -> Result<Source, String> {
+    if let Some(path) = tool.input.get("path").and_then(Value::as_str) {
+        let resolved = Path::new(path).canonicalize().ok().filter(|p| p.is_file());
+        return resolved
+            .map(|p| Source::File(p.to_string_lossy().into_owned()))
+            .ok_or_else(|| format!("'{path}' is not a file"));
+    }
+    let id = tool.input.get("panel").and_then(Value::as_str).ok_or("Missing 'path' or 'panel' parameter")?;
+    let ctx = state.context.iter().find(|c| c.id == id).ok_or_else(|| format!("No panel '{id}'"))?;
+    if ctx.context_type.as_str() == DATA_PANEL_TYPE {
+        return Source::of_panel(ctx).ok_or_else(|| format!("Panel '{id}' lost its source"));
+    }
+    let text = ctx.cached_content.as_deref().ok_or_else(|| format!("Panel '{id}' has no content yet"))?;
+    let json = json_slice(text).ok_or_else(|| format!("Panel '{id}' holds no JSON"))?;
+    Ok(Source::Panel { from: id.to_owned(), text: json.to_owned() })
+}
+
+/// Read and parse a file; YAML by extension, JSON otherwise.
+fn parse_file(path: &str) -> Result<Value, String> {
+    let file = File::open(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    let mut bytes = Vec::new();
+    let _read =
+        file.take(MAX_DOCUMENT_BYTES).read_to_end(&mut bytes).map_err(|e| format!("cannot read {path}: {e}"))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let is_yaml = Path::new(path).extension().is_some_and(|ext| ext == "yaml" || ext == "yml");
+    if is_yaml {
+        serde_yaml::from_str::<Value>(&text).map_err(|e| format!("invalid YAML in {path}: {e}"))
+    } else {
+        serde_json::from_str::<Value>(&text).map_err(|e| format!("invalid JSON in {path}: {e}"))
+    }
+}
+
+/// Parse JSON text.
+fn parse_json_text(text: &str) -> Option<Value> {
+    serde_json::from_str(text).ok()
+}
+
+/// The JSON in a tool result: the whole text, its first fenced
+/// ```` ```json ```` block (HTTP panels), or everything from the first line
+/// opening an object or array (k8s panels with `-o json`).
+fn json_slice(text: &str) -> Option<&str> {
+    if parse_json_text(text).is_some() {
+        return Some(text);
+    }
+    fenced_json(text).or_else(|| {
+        let start = text.find("\n{").or_else(|| text.find("\n["))?.saturating_add(1);
+        let rest = text.get(start..)?.trim_end();
+        parse_json_text(rest).is_some().then_some(rest)
+    })
+}
+
+/// The first ```` ```json ```` block of `text`, when it parses.
+fn fenced_json(text: &str) -> Option<&str> {
+    let (_before, after) = text.split_once("```json\n")?;
+    let (block, _rest) = after.split_once("\n```")?;
+    parse_json_text(block).is_some().then_some(block)
+}
@@ -0,0 +1,231 @@
+//! CSV/TSV loading: one streaming pass collects the head rows, the row
+//! count, inferred column kinds and, on request, per-column statistics.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead as _, BufReader};
+use std::path::Path;
+
+use cp_base::cast::Safe as _;
+use cp_base::cast::float_math;
+
+use super::{Column, ColumnStats, Table};
+
+/// Rows scanned before the count stops (reported as a lower bound).
+const MAX_SCAN_ROWS: u64 = 5_000_000;
+
+/// Rows sampled to infer column kinds.
+const INFER_ROWS: u64 = 1_000;
+
+/// Distinct values tracked per column before counting gives up.
+const DISTINCT_CAP: usize = 10_000;
+
+/// Delimiters the sniffer picks from.
+const DELIMITERS: [u8; 4] = *b",;\t|";
+
+/// Kind of a column, widened as values are seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Inferred {
+    /// Only empty cells so far.
+    Empty,
+    /// `true`/`false`.
+    Boolean,
+    /// Whole numbers.
+    Integer,
+    /// Numbers.
+    Float,
+    /// Anything else.
+    Text,
+}
+
+impl Inferred {
+    /// Kind of a single non-empty cell.
+    fn of(cell: &str) -> Self {
+        if cell.parse::<i64>().is_ok() {
+            Self::Integer
+        } else if cell.parse::<f64>().is_ok() {
+            Self::Float
+        } else if cell.eq_ignore_ascii_case("true") || cell.eq_ignore_ascii_case("false") {
+            Self::Boolean
+        } else {
+            Self::Text
+        }
+    }
+
+    /// The narrowest kind covering both.
+    fn merge(self, cell: &str) -> Self {
+        if cell.is_empty() {
+            return self;
+        }
+        match (self, Self::of(cell)) {
+            (Self::Empty, seen) => seen,
+            (kept, seen) if kept == seen => kept,
+            (Self::Integer | Self::Float, Self::Integer | Self::Float) => Self::Float,
+            _ => Self::Text,
+        }
+    }
+
+    /// Name shown in the schema.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Empty => "empty",
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Text => "text",
+        }
+    }
+}
+
+/// Running statistics of one column.
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    /// Empty cells.
+    nulls: u64,
+    /// Values seen, until the cap.
+    distinct: HashSet<String>,
+    /// The cap was hit.
+    overflow: bool,
+    /// Numeric cells.
+    numbers: u64,
+    /// Sum of numeric cells.
+    sum: f64,
+    /// Smallest and largest numeric cell.
+    range: Option<(f64, f64)>,
+    /// Smallest and largest cell as text.
+    text_range: Option<(String, String)>,
+}
+
+impl Accumulator {
+    /// Account for one cell.
+    fn add(&mut self, cell: &str) {
+        if cell.is_empty() {
+            self.nulls = self.nulls.saturating_add(1);
+            return;
+        }
+        if !self.overflow && !self.distinct.contains(cell) {
+            self.overflow = self.distinct.len() >= DISTINCT_CAP;
+            let _new = self.distinct.insert(cell.to_owned());
+        }
+        if let Ok(number) = cell.parse::<f64>() {
+            self.numbers = self.numbers.saturating_add(1);
+            self.sum = float_math::add(self.sum, number);
+            self.range = Some(self.range.map_or((number, number), |(lo, hi)| (lo.min(number), hi.max(number))));
+        }
+        self.text_range = Some(match self.text_range.take() {
+            None => (cell.to_owned(), cell.to_owned()),
+            Some((lo, hi)) => (lo.min(cell.to_owned()), hi.max(cell.to_owned())),
+        });
+    }
+
+    /// Final figures; numeric min/max/mean when every value was a number.
+    fn finish(self, rows: u64) -> ColumnStats {
+        let all_numeric = self.numbers > 0 && self.numbers == rows.saturating_sub(self.nulls);
+        let numeric_range = self.range.filter(|_range| all_numeric);
+        let (min, max, mean) = if let Some((lo, hi)) = numeric_range {
+            let mean = float_math::div(self.sum, self.numbers.to_f64());
+            (Some(lo.to_string()), Some(hi.to_string()), Some(mean))
+        } else {
+            let (lo, hi) = self.text_range.unzip();
+            (lo, hi, None)
+        };
+        let distinct = (!self.overflow).then(|| u64::try_from(self.distinct.len()).unwrap_or(u64::MAX));
+        ColumnStats { nulls: self.nulls, distinct, min, max, mean }
+    }
+}
+
+/// The delimiter of `path`: tab for `.tsv`, else the most frequent
+/// candidate on the header line.
+fn sniff_delimiter(path: &str) -> Result<u8, String> {
+    if Path::new(path).extension().is_some_and(|ext| ext == "tsv") {
+        return Ok(b'\t');
+    }
+    let file = File::open(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    let mut header = String::new();
+    let _read = BufReader::new(file).read_line(&mut header).map_err(|e| format!("cannot read {path}: {e}"))?;
+    let count = |d: u8| header.bytes().filter(|b| *b == d).count();
+    Ok(DELIMITERS.into_iter().max_by_key(|d| count(*d)).unwrap_or(b','))
+}
+
+/// One pass over the rows.
+struct Scan {
+    /// What was read so far.
+    table: Table,
+    /// Column kinds so far.
+    kinds: Vec<Inferred>,
+    /// Statistics, when asked for.
+    stats: Option<Vec<Accumulator>>,
+    /// Rows kept for the preview.
+    head_rows: usize,
+}
+
+impl Scan {
+    /// Account for one row.
+    fn visit(&mut self, row: &csv::StringRecord) {
+        if self.table.rows < INFER_ROWS {
+            for (kind, cell) in self.kinds.iter_mut().zip(row.iter()) {
+                *kind = kind.merge(cell);
+            }
+        }
+        if let Some(accumulators) = self.stats.as_mut() {
+            accumulators.iter_mut().zip(row.iter()).for_each(|(acc, cell)| acc.add(cell));
+        }
+        if self.table.head.len() < self.head_rows {
+            self.table.head.push(row.iter().map(str::to_owned).collect());
+        }
+        self.table.rows = self.table.rows.saturating_add(1);
+    }
+}
+
+/// Load a delimited file.
+pub(super) fn load(path: &str, head_rows: usize, with_stats: bool) -> Result<Table, String> {
+    let delimiter = sniff_delimiter(path)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| format!("cannot read {path}: {e}"))?;
+    let names: Vec<String> =
+        reader.headers().map_err(|e| format!("bad header in {path}: {e}"))?.iter().map(str::to_owned).collect();
+    let mut scan = Scan {
+        table: Table::default(),
+        kinds: vec![Inferred::Empty; names.len()],
+        stats: with_stats.then(|| vec![Accumulator::default(); names.len()]),
+        head_rows,
+    };
+    for record in reader.records() {
+        if scan.table.rows >= MAX_SCAN_ROWS {
+            scan.table.truncated = true;
+            break;
+        }
+        let row = record.map_err(|e| format!("{path}: {e}"))?;
+        scan.visit(&row);
+    }
+    let rows = scan.table.rows;
+    scan.table.columns =
+        names.into_iter().zip(scan.kinds).map(|(name, kind)| Column { name, kind: kind.name().to_owned() }).collect();
+    scan.table.stats = scan.stats.map(|accumulators| accumulators.into_iter().map(|acc| acc.finish(rows)).collect());
+    Ok(scan.table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_kinds_and_counts() {
+        let path = std::env::temp_dir().join(format!("cp_table_{}.csv", std::process::id()));
+        let written = std::fs::write(&path, "id;price;name\n1;2.5;a\n2;;b\n3;4;a\n");
+        assert_eq!(written.ok(), Some(()));
+        let table = load(&path.to_string_lossy(), 2, true);
+        let _removed = std::fs::remove_file(&path);
+        let kinds: Vec<String> = table.iter().flat_map(|t| t.columns.iter().map(|c| c.kind.clone())).collect();
+        assert_eq!(kinds, ["integer", "float", "text"]);
+        assert_eq!(table.as_ref().map(|t| (t.rows, t.head.len())), Ok((3, 2)));
+        let price = table.ok().and_then(|t| t.stats).and_then(|s| s.into_iter().nth(1));
+        assert_eq!(
+            price.map(|p| (p.nulls, p.distinct, p.max, p.mean)),
+            Some((1, Some(2), Some("4".to_owned()), Some(3.25f64)))
+        );
+    }
+}
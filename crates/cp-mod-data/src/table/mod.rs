@@ -0,0 +1,118 @@
+//! Table previews of CSV/TSV and Parquet files: schema, row count and the
+//! first rows as an aligned table, plus per-column statistics on request.
+//!
+//! Files are read by the cache worker, never on the tool call; the panel
+//! is cut to a token budget however many rows were asked for.
+
+/// CSV/TSV loading.
+mod csv;
+/// Table panel and rendering.
+pub(crate) mod panel;
+/// Parquet loading through pyarrow.
+mod parquet;
+
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use cp_base::state::context::{Kind, make_default_entry};
+use cp_base::state::runtime::State;
+use cp_base::tools::ToolUse;
+
+use self::panel::{DEFAULT_HEAD_ROWS, META_HEAD_ROWS, META_PATH, META_STATS, TABLE_PANEL_TYPE};
+
+/// Highest row count `table_head` accepts.
+const MAX_HEAD_ROWS: usize = 500;
+
+/// A loaded table.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Table {
+    /// Columns, in file order.
+    pub columns: Vec<Column>,
+    /// Rows counted.
+    pub rows: u64,
+    /// The count stopped early; `rows` is a lower bound.
+    pub truncated: bool,
+    /// First rows, as text.
+    pub head: Vec<Vec<String>>,
+    /// Per-column statistics, when asked for.
+    #[serde(default)]
+    pub stats: Option<Vec<ColumnStats>>,
+}
+
+/// One column of the schema.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Column {
+    /// Header name.
+    pub name: String,
+    /// Inferred (CSV) or declared (Parquet) type.
+    pub kind: String,
+}
+
+/// Statistics of one column.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ColumnStats {
+    /// Empty or null cells.
+    pub nulls: u64,
+    /// Distinct values; `None` when too many to count.
+    pub distinct: Option<u64>,
+    /// Smallest value.
+    pub min: Option<String>,
+    /// Largest value.
+    pub max: Option<String>,
+    /// Mean of a numeric column.
+    pub mean: Option<f64>,
+}
+
+/// Whether `path` is a Parquet file (else it is read as delimited text).
+fn is_parquet(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|ext| ext == "parquet" || ext == "pq")
+}
+
+/// Load `path` with `head_rows` preview rows.
+pub(crate) fn load(path: &str, head_rows: usize, with_stats: bool) -> Result<Table, String> {
+    if is_parquet(path) { parquet::load(path, head_rows, with_stats) } else { csv::load(path, head_rows, with_stats) }
+}
+
+/// Index of the table panel on `canonical`, created when missing (`true`
+/// then).
+fn table_panel_index(state: &mut State, canonical: &str) -> (usize, bool) {
+    let existing = state
+        .context
+        .iter()
+        .position(|c| c.context_type.as_str() == TABLE_PANEL_TYPE && c.get_meta_str(META_PATH) == Some(canonical));
+    if let Some(idx) = existing {
+        return (idx, false);
+    }
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+    let file_name =
+        Path::new(canonical).file_name().map_or_else(|| canonical.to_owned(), |n| n.to_string_lossy().into_owned());
+    let mut entry = make_default_entry(&panel_id, Kind::new(TABLE_PANEL_TYPE), &format!("table {file_name}"), true);
+    entry.uid = Some(uid);
+    entry.set_meta(META_PATH, &canonical);
+    state.context.push(entry);
+    (state.context.len().saturating_sub(1), true)
+}
+
+/// Execute `table_head` (`with_stats` false) or `table_stats`: point a
+/// table panel at a file; the cache worker loads it.
+pub(crate) fn execute(tool: &ToolUse, state: &mut State, with_stats: bool) -> Result<String, String> {
+    let path = tool.input.get("path").and_then(Value::as_str).ok_or("Missing 'path' parameter")?;
+    let resolved = Path::new(path).canonicalize().ok().filter(|p| p.is_file());
+    let canonical = resolved.ok_or_else(|| format!("'{path}' is not a file"))?.to_string_lossy().into_owned();
+    let rows = tool.input.get("rows").and_then(Value::as_u64).map(|n| usize::try_from(n).unwrap_or(MAX_HEAD_ROWS));
+    let (idx, created) = table_panel_index(state, &canonical);
+    let ctx = state.context.get_mut(idx).ok_or("table panel vanished")?;
+    let head_rows = rows.or_else(|| ctx.get_meta_usize(META_HEAD_ROWS)).unwrap_or(DEFAULT_HEAD_ROWS).min(MAX_HEAD_ROWS);
+    ctx.set_meta(META_HEAD_ROWS, &head_rows);
+    if with_stats {
+        ctx.set_meta(META_STATS, &true);
+    }
+    ctx.cache_deprecated = true;
+    let verb = if created { "created" } else { "updated" };
+    let what = if with_stats { "schema, row count and column statistics" } else { "schema, row count and first rows" };
+    Ok(format!("Table panel {} {verb} for {canonical}: {what} load in the background ({head_rows} rows).", ctx.id))
+}
@@ -0,0 +1,222 @@
+use std::fmt::Write as _;
+
+use crossterm::event::KeyEvent;
+
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
+use cp_base::state::runtime::State;
+
+use super::Table;
+
+/// Context type of table panels.
+pub(crate) const TABLE_PANEL_TYPE: &str = "table_view";
+
+/// Metadata key: canonical path of the table file.
+pub(crate) const META_PATH: &str = "table_path";
+
+/// Metadata key: preview rows.
+pub(crate) const META_HEAD_ROWS: &str = "table_head_rows";
+
+/// Metadata key: whether column statistics are shown.
+pub(crate) const META_STATS: &str = "table_stats";
+
+/// Preview rows when none are asked for.
+pub(crate) const DEFAULT_HEAD_ROWS: usize = 20;
+
+/// Tokens a table panel may take; preview rows past it are dropped.
+const TOKEN_BUDGET: usize = 6_000;
+
+/// Characters of a cell shown before it is ellipsized.
+const CELL_CHARS: usize = 32;
+
+/// Data sent to the cache thread for a table load.
+struct TableRequest {
+    /// Panel to fill.
+    context_id: String,
+    /// Table file.
+    path: String,
+    /// Preview rows.
+    head_rows: usize,
+    /// Compute column statistics.
+    with_stats: bool,
+}
+
+/// Rows padded into aligned columns, a dash rule under the first.
+fn aligned(rows: &[Vec<String>]) -> Vec<String> {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|c| cp_base::ui::text::ellipsize(c, CELL_CHARS, "...")).collect())
+        .collect();
+    let mut widths: Vec<usize> = Vec::new();
+    for row in &cells {
+        for (i, cell) in row.iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(i) {
+                Some(w) => *w = (*w).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+    let line = |row: &[String]| {
+        let padded: Vec<String> = row.iter().zip(&widths).map(|(c, w)| format!("{c:<w$}")).collect();
+        padded.join("  ").trim_end().to_owned()
+    };
+    let mut out: Vec<String> = cells.iter().map(|row| line(row)).collect();
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    out.insert(1.min(out.len()), rule.join("  "));
+    out
+}
+
+/// Schema rows, with statistics columns when loaded.
+fn schema_rows(table: &Table) -> Vec<Vec<String>> {
+    let with_stats = table.stats.is_some();
+    let mut header = vec!["column".to_owned(), "type".to_owned()];
+    if with_stats {
+        header.extend(["nulls", "distinct", "min", "max", "mean"].map(str::to_owned));
+    }
+    let stats = table.stats.iter().flatten().map(Some).chain(std::iter::repeat(None));
+    let body = table.columns.iter().zip(stats).map(|(column, stat)| {
+        let mut row = vec![column.name.clone(), column.kind.clone()];
+        if let Some(s) = stat {
+            row.extend([
+                s.nulls.to_string(),
+                s.distinct.map_or_else(|| "10000+".to_owned(), |d| d.to_string()),
+                s.min.clone().unwrap_or_default(),
+                s.max.clone().unwrap_or_default(),
+                s.mean.map(|m| format!("{m:.4}")).unwrap_or_default(),
+            ]);
+        }
+        row
+    });
+    std::iter::once(header).chain(body).collect()
+}
+
+/// The panel text: summary, schema, then preview rows within the budget.
+pub(crate) fn render(table: &Table, path: &str) -> String {
+    let bound = if table.truncated { "at least " } else { "" };
+    let mut out = format!("{path}: {bound}{} rows, {} columns\n\nSchema:\n", table.rows, table.columns.len());
+    for line in aligned(&schema_rows(table)) {
+        let _r = writeln!(out, "{line}");
+    }
+    let header: Vec<String> = table.columns.iter().map(|c| c.name.clone()).collect();
+    let preview: Vec<Vec<String>> = std::iter::once(header).chain(table.head.iter().cloned()).collect();
+    let _r = writeln!(out, "\nFirst {} rows:", table.head.len());
+    let mut tokens = estimate_tokens(&out);
+    let lines = aligned(&preview);
+    let total = lines.len();
+    for (shown, line) in lines.into_iter().enumerate() {
+        tokens = tokens.saturating_add(estimate_tokens(&line));
+        if tokens > TOKEN_BUDGET {
+            let _r2 = writeln!(out, "[{} rows left out: token budget]", total.saturating_sub(shown));
+            break;
+        }
+        let _r3 = writeln!(out, "{line}");
+    }
+    out
+}
+
+/// Panel previewing a table file.
+pub(crate) struct TablePanel;
+
+impl Panel for TablePanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &Entry, _state: &State) -> Option<CacheRequest> {
+        Some(CacheRequest::new(
+            Kind::new(TABLE_PANEL_TYPE),
+            Box::new(TableRequest {
+                context_id: ctx.id.clone(),
+                path: ctx.get_meta_str(META_PATH)?.to_owned(),
+                head_rows: ctx.get_meta_usize(META_HEAD_ROWS).unwrap_or(DEFAULT_HEAD_ROWS),
+                with_stats: ctx.get_meta::<bool>(META_STATS).unwrap_or(false),
+            }),
+        ))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<TableRequest>().ok()?;
+        let content = match super::load(&req.path, req.head_rows, req.with_stats) {
+            Ok(table) => render(&table, &req.path),
+            Err(e) => format!("{e}\n"),
+        };
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id: req.context_id, content, token_count })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, _state: &mut State) -> bool {
+        // Only a Content update carries new bytes; Unchanged/ModuleSpecific are no-ops.
+        if let CacheUpdate::Content { content, token_count, .. } = update {
+            ctx.token_count = token_count;
+            ctx.full_token_count = token_count;
+            ctx.cache_deprecated = false;
+            let changed = update_if_changed(ctx, &content);
+            ctx.cached_content = Some(content);
+            changed
+        } else {
+            false
+        }
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Span as S};
+
+        let cached = state
+            .context
+            .get(state.selected_context)
+            .filter(|c| c.context_type.as_str() == TABLE_PANEL_TYPE)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content.lines().map(|line| Block::text(format!(" {line}"))).collect()
+    }
+
+    fn title(&self, state: &State) -> String {
+        state.context.get(state.selected_context).map_or_else(|| "Table".to_owned(), |ctx| ctx.name.clone())
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == TABLE_PANEL_TYPE)
+            .filter_map(|c| {
+                let content = c.cached_content.as_ref()?;
+                let source = c.get_meta_str(META_PATH).unwrap_or(&c.name);
+                Some(
+                    ContextItem::new(&c.id, &c.name, content.clone(), c.last_refresh_ms)
+                        .with_provenance(Provenance::repo(source)),
+                )
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_columns_under_a_rule() {
+        let rows = vec![vec!["id".to_owned(), "name".to_owned()], vec!["1000".to_owned(), "a".to_owned()]];
+        assert_eq!(aligned(&rows), ["id    name", "----  ----", "1000  a"]);
+    }
+}
@@ -0,0 +1,50 @@
+"""Describe a Parquet file for the Context Pilot table panel.
+
+Usage: parquet.py PATH HEAD_ROWS [stats]. Prints one JSON object: the
+columns with their Arrow types, the row count from the footer, the first
+HEAD_ROWS rows as strings and, with 'stats', per-column statistics.
+"""
+import json
+import sys
+
+try:
+    import pyarrow.compute as pc
+    import pyarrow.parquet as pq
+except ImportError:
+    sys.exit("pyarrow is not installed (pip install pyarrow)")
+
+
+def cell(value):
+    return "" if value is None else str(value)
+
+
+def column_stats(column):
+    """Nulls, distinct count, min/max and mean where the type allows."""
+    entry = {"nulls": column.null_count, "distinct": None, "min": None, "max": None, "mean": None}
+    try:
+        entry["distinct"] = len(pc.unique(column))
+        bounds = pc.min_max(column)
+        low, high = bounds["min"].as_py(), bounds["max"].as_py()
+        entry["min"] = None if low is None else str(low)
+        entry["max"] = None if high is None else str(high)
+        entry["mean"] = pc.mean(column).as_py()
+    except Exception:
+        pass
+    return entry
+
+
+path, head_rows, with_stats = sys.argv[1], int(sys.argv[2]), sys.argv[3:] == ["stats"]
+source = pq.ParquetFile(path)
+table = {
+    "columns": [{"name": field.name, "kind": str(field.type)} for field in source.schema_arrow],
+    "rows": source.metadata.num_rows,
+    "truncated": False,
+    "head": [],
+}
+batch = next(source.iter_batches(batch_size=head_rows), None) if head_rows else None
+if batch is not None:
+    columns = [batch.column(i).to_pylist() for i in range(batch.num_columns)]
+    table["head"] = [[cell(value) for value in row] for row in zip(*columns)][:head_rows]
+if with_stats:
+    table["stats"] = [column_stats(column) for column in source.read().columns]
+print(json.dumps(table))
@@ -0,0 +1,35 @@
+//! Parquet loading through pyarrow: a small helper script reads the footer
+//! for the schema and row count, the first batch for the preview and, on
+//! request, the whole file for statistics.
+
+use std::path::Path;
+use std::process::Command;
+
+use cp_base::modules::run_with_timeout;
+
+use super::Table;
+
+/// The helper script.
+const HELPER: &str = include_str!("parquet.py");
+
+/// Project virtualenv interpreter, preferred over `python3` when present.
+const VENV_PYTHON: &str = ".venv/bin/python";
+
+/// Seconds the helper may take (statistics read the whole file).
+const HELPER_TIMEOUT_SECS: u64 = 120;
+
+/// Load a Parquet file.
+pub(super) fn load(path: &str, head_rows: usize, with_stats: bool) -> Result<Table, String> {
+    let python = if Path::new(VENV_PYTHON).exists() { VENV_PYTHON } else { "python3" };
+    let mut cmd = Command::new(python);
+    let _r = cmd.args(["-c", HELPER, path, &head_rows.to_string()]);
+    if with_stats {
+        let _r2 = cmd.arg("stats");
+    }
+    let output = run_with_timeout(cmd, HELPER_TIMEOUT_SECS).map_err(|e| format!("cannot run {python}: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("cannot read {path}: {}", stderr.trim().lines().last().unwrap_or("helper failed")));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("unexpected helper output: {e}"))
+}
@@ -0,0 +1,103 @@
+use serde_json::Value;
+
+use cp_base::state::context::{Kind, make_default_entry};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::panel::{DATA_PANEL_TYPE, DEFAULT_DEPTH, META_CONTENT, META_DEPTH, META_EXPAND, META_FROM, META_PATH};
+use crate::query;
+use crate::source::{self, Source};
+use crate::table;
+
+/// Deepest open depth accepted.
+const MAX_DEPTH: usize = 32;
+
+/// Bytes of query output returned before it is cut.
+const MAX_RESULT_BYTES: usize = 16_000;
+
+/// Dispatch data tool calls.
+pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+    let outcome = match tool.name.as_str() {
+        "data_view" => execute_view(tool, state),
+        "json_query" => execute_query(tool, state),
+        "table_head" => table::execute(tool, state, false),
+        "table_stats" => table::execute(tool, state, true),
+        _ => return None,
+    };
+    Some(match outcome {
+        Ok(message) => ToolResult::new(tool.id.clone(), message, false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    })
+}
+
+/// Index of the data panel showing `source`, created when missing (`true`
+/// then).
+fn data_panel_index(state: &mut State, source: &Source) -> (usize, bool) {
+    let existing = state.context.iter().position(|c| c.context_type.as_str() == DATA_PANEL_TYPE && source.matches(c));
+    if let Some(idx) = existing {
+        return (idx, false);
+    }
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+    let mut entry =
+        make_default_entry(&panel_id, Kind::new(DATA_PANEL_TYPE), &format!("data {}", source.label()), true);
+    entry.uid = Some(uid);
+    cp_base::deref_match!(source, {
+        Source::File(ref path) => entry.set_meta(META_PATH, path),
+        Source::Panel { ref from, .. } => entry.set_meta(META_FROM, from),
+    });
+    state.context.push(entry);
+    (state.context.len().saturating_sub(1), true)
+}
+
+/// Execute `data_view`: open a tree panel on a document, or refold an open one.
+fn execute_view(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let source = source::from_tool(tool, state)?;
+    let _parses = source.load()?;
+    let expand: Vec<String> = tool
+        .input
+        .get("expand")
+        .and_then(Value::as_array)
+        .map(|paths| paths.iter().filter_map(Value::as_str).map(str::trim).filter(|p| !p.is_empty()))
+        .map_or_else(Vec::new, |paths| paths.map(str::to_owned).collect());
+    let depth = tool
+        .input
+        .get("depth")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_DEPTH, |n| usize::try_from(n).unwrap_or(MAX_DEPTH))
+        .clamp(1, MAX_DEPTH);
+    let (idx, created) = data_panel_index(state, &source);
+    let ctx = state.context.get_mut(idx).ok_or("data panel vanished")?;
+    if let Some(text) = source.copied_text() {
+        ctx.set_meta(META_CONTENT, &text);
+    }
+    ctx.set_meta(META_EXPAND, &expand);
+    ctx.set_meta(META_DEPTH, &depth);
+    ctx.cache_deprecated = true;
+    let verb = if created { "created" } else { "updated" };
+    Ok(format!(
+        "Data panel {} {verb}: {}, {depth} levels open, {} paths expanded.",
+        ctx.id,
+        source.label(),
+        expand.len()
+    ))
+}
+
+/// Execute `json_query`: evaluate a path expression, return the values.
+fn execute_query(tool: &ToolUse, state: &State) -> Result<String, String> {
+    let expr = tool.input.get("query").and_then(Value::as_str).ok_or("Missing 'query' parameter")?;
+    let doc = source::from_tool(tool, state)?.load()?;
+    let values = query::evaluate(&doc, expr).map_err(|e| format!("query '{expr}': {e}"))?;
+    if values.is_empty() {
+        return Ok("(no results)".to_owned());
+    }
+    let rendered: Vec<String> =
+        values.iter().map(|v| serde_json::to_string_pretty(v).unwrap_or_else(|_unprintable| v.to_string())).collect();
+    let out = rendered.join("\n");
+    if out.len() <= MAX_RESULT_BYTES {
+        return Ok(out);
+    }
+    let kept = out.get(..out.floor_char_boundary(MAX_RESULT_BYTES)).unwrap_or_default();
+    Ok(format!("{kept}\n[{} results, output cut at {MAX_RESULT_BYTES} bytes; narrow the query]", values.len()))
+}
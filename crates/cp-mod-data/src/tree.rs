@@ -0,0 +1,128 @@
+//! Collapsible tree rendering of a structured document.
+//!
+//! Every line carries the full path of its node (the breadcrumb), in the
+//! syntax `json_query` accepts. Containers deeper than the open depth are
+//! folded into a `{N keys}` / `[N items]` summary unless their path, or a
+//! path below them, is in the expand list.
+
+use std::fmt::Write as _;
+
+use serde_json::Value;
+
+/// Children listed per container before the rest is summarised.
+const CHILDREN_SHOWN: usize = 50;
+
+/// Lines rendered before the tree is cut.
+const MAX_TREE_LINES: usize = 600;
+
+/// Characters of a scalar shown on its line.
+const SCALAR_CHARS: usize = 120;
+
+/// Which nodes of a tree are open.
+#[derive(Debug)]
+pub(crate) struct Folding<'paths> {
+    /// Containers shallower than this are open.
+    pub depth: usize,
+    /// Paths opened explicitly (their ancestors open too).
+    pub expand: &'paths [String],
+}
+
+impl Folding<'_> {
+    /// Whether the container at `path`, `level` deep, is shown open.
+    fn is_open(&self, path: &str, level: usize) -> bool {
+        level < self.depth
+            || self.expand.iter().any(|e| {
+                e == path || e.strip_prefix(path).is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+            })
+    }
+}
+
+/// Path of the field `key` below `parent`.
+pub(crate) fn key_path(parent: &str, key: &str) -> String {
+    let plain = !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if plain { format!("{parent}.{key}") } else { format!("{parent}.{}", Value::from(key)) }
+}
+
+/// One-line summary of a node.
+fn summary(value: &Value) -> String {
+    cp_base::deref_match!(value, {
+        Value::Object(ref map) => format!("{{{} keys}}", map.len()),
+        Value::Array(ref items) => format!("[{} items]", items.len()),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {
+            cp_base::ui::text::ellipsize(&value.to_string(), SCALAR_CHARS, "...")
+        }
+    })
+}
+
+/// Tree walk state.
+struct Walk<'fold> {
+    /// Fold settings.
+    folding: &'fold Folding<'fold>,
+    /// Rendered text.
+    out: String,
+    /// Lines written so far.
+    lines: usize,
+}
+
+impl Walk<'_> {
+    /// Write one line, unless the budget is spent.
+    fn line(&mut self, level: usize, text: &str) -> bool {
+        if self.lines >= MAX_TREE_LINES {
+            return false;
+        }
+        self.lines = self.lines.saturating_add(1);
+        let _r = writeln!(self.out, "{}{text}", "  ".repeat(level));
+        true
+    }
+
+    /// Render `value` at `path` and, when open, its children.
+    fn node(&mut self, value: &Value, path: &str, level: usize) {
+        let shown = if path.is_empty() { "." } else { path };
+        if !self.line(level, &format!("{shown}: {}", summary(value))) {
+            return;
+        }
+        if !self.folding.is_open(path, level) {
+            return;
+        }
+        let children: Vec<(String, &Value)> = cp_base::deref_match!(value, {
+            Value::Object(ref map) => map.iter().map(|(k, v)| (key_path(path, k), v)).collect(),
+            Value::Array(ref items) => items.iter().enumerate().map(|(i, v)| (format!("{path}[{i}]"), v)).collect(),
+            Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => Vec::new(),
+        });
+        let child_level = level.saturating_add(1);
+        let total = children.len();
+        for (child_path, child) in children.into_iter().take(CHILDREN_SHOWN) {
+            self.node(child, &child_path, child_level);
+        }
+        let hidden = total.saturating_sub(CHILDREN_SHOWN);
+        if hidden > 0 {
+            let _shown = self.line(child_level, &format!("... {hidden} more (use json_query on {shown})"));
+        }
+    }
+}
+
+/// Render `doc` as an indented tree under a `title` line.
+pub(crate) fn render(doc: &Value, title: &str, folding: &Folding<'_>) -> String {
+    let mut walk = Walk { folding, out: format!("{title}\n"), lines: 0 };
+    walk.node(doc, "", 0);
+    if walk.lines >= MAX_TREE_LINES {
+        let _r = writeln!(walk.out, "[tree cut at {MAX_TREE_LINES} lines; expand fewer paths or use json_query]");
+    }
+    walk.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn folds_below_depth_unless_expanded() {
+        let doc = json!({"a": {"b": {"c": 1i32}}, "odd key": [true]});
+        let expand = vec![".a.b".to_owned()];
+        let rendered = render(&doc, "doc", &Folding { depth: 1, expand: &expand });
+        let expected =
+            "doc\n.: {2 keys}\n  .a: {1 keys}\n    .a.b: {1 keys}\n      .a.b.c: 1\n  .\"odd key\": [1 items]\n";
+        assert_eq!(rendered, expected);
+    }
+}
@@ -0,0 +1,165 @@
+//! Database access: one short-lived connection per call.
+//!
+//! Every backend opens the connection read-only unless writes are allowed
+//! (`SQLITE_OPEN_READ_ONLY`, a read-only session on Postgres and `MySQL`), so
+//! the statement check in `guard` is not the only line of defense. Values
+//! come back as text, rows stop at the caller's limit.
+
+/// `MySQL` / `MariaDB` through the `mysql` crate.
+mod mysql_db;
+/// Postgres through the `postgres` crate (simple query protocol).
+mod postgres_db;
+/// `SQLite` through `rusqlite`.
+mod sqlite_db;
+
+/// Seconds to wait for a server connection.
+pub(crate) const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Column rows read for the schema panel at most.
+const SCHEMA_COLUMNS: usize = 5_000;
+
+/// File extensions taken as `SQLite` databases.
+const SQLITE_EXTENSIONS: &[&str] = &[".db", ".db3", ".sqlite", ".sqlite3"];
+
+/// Database engine, from the connection string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Backend {
+    /// `sqlite://path`, `sqlite:path` or a database file path.
+    Sqlite,
+    /// `postgres://` or `postgresql://`.
+    Postgres,
+    /// `mysql://`.
+    Mysql,
+}
+
+impl Backend {
+    /// The engine `url` points at.
+    pub(crate) fn of(url: &str) -> Result<Self, String> {
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            Ok(Self::Postgres)
+        } else if url.starts_with("mysql://") {
+            Ok(Self::Mysql)
+        } else if url.starts_with("sqlite:") || SQLITE_EXTENSIONS.iter().any(|ext| url.ends_with(ext)) {
+            Ok(Self::Sqlite)
+        } else {
+            Err("unrecognized connection string (expected postgres://, mysql://, sqlite://path or a .db/.sqlite file)"
+                .to_owned())
+        }
+    }
+
+    /// Display name.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Sqlite => "SQLite",
+            Self::Postgres => "Postgres",
+            Self::Mysql => "MySQL",
+        }
+    }
+
+    /// Query listing `table, column, type, nullable (YES/NO), key (PRI)` for
+    /// every user table and view.
+    const fn schema_sql(self) -> &'static str {
+        match self {
+            Self::Sqlite => {
+                "SELECT m.name, p.name, p.type, CASE WHEN p.\"notnull\" = 1 THEN 'NO' ELSE 'YES' END, \
+                 CASE WHEN p.pk > 0 THEN 'PRI' ELSE '' END \
+                 FROM sqlite_master m JOIN pragma_table_info(m.name) p \
+                 WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%' ORDER BY m.name, p.cid"
+            }
+            Self::Postgres => {
+                "SELECT CASE WHEN c.table_schema = 'public' THEN c.table_name \
+                 ELSE c.table_schema || '.' || c.table_name END, c.column_name, c.data_type, c.is_nullable, \
+                 CASE WHEN EXISTS (SELECT 1 FROM information_schema.table_constraints tc \
+                 JOIN information_schema.key_column_usage k USING (constraint_schema, constraint_name) \
+                 WHERE tc.constraint_type = 'PRIMARY KEY' AND k.table_schema = c.table_schema \
+                 AND k.table_name = c.table_name AND k.column_name = c.column_name) THEN 'PRI' ELSE '' END \
+                 FROM information_schema.columns c \
+                 WHERE c.table_schema NOT IN ('pg_catalog', 'information_schema') \
+                 ORDER BY 1, c.ordinal_position"
+            }
+            Self::Mysql => {
+                "SELECT table_name, column_name, column_type, is_nullable, column_key \
+                 FROM information_schema.columns WHERE table_schema = DATABASE() \
+                 ORDER BY table_name, ordinal_position"
+            }
+        }
+    }
+}
+
+/// What a statement returned.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ResultSet {
+    /// Column names; empty for statements that return no rows.
+    pub columns: Vec<String>,
+    /// Values as text (`NULL` for nulls), up to the limit.
+    pub rows: Vec<Vec<String>>,
+    /// More rows were available past the limit.
+    pub more: bool,
+    /// Rows changed, for statements that return none.
+    pub affected: Option<u64>,
+}
+
+impl ResultSet {
+    /// Result of a statement that returned no rows.
+    pub(crate) fn affected(count: u64) -> Self {
+        Self { affected: Some(count), ..Self::default() }
+    }
+}
+
+/// The connection string `url` stands for: `$VAR` is read from the
+/// environment, so the secret is never saved with the project.
+pub(crate) fn resolve(url: &str) -> Result<String, String> {
+    let Some(var) = url.strip_prefix('$') else { return Ok(url.to_owned()) };
+    std::env::var(var).map_err(|_unset| format!("environment variable {var} is not set"))
+}
+
+/// Run one statement against the database at `url`, keeping `limit` rows.
+pub(crate) fn run(url: &str, sql: &str, limit: usize, read_only: bool) -> Result<ResultSet, String> {
+    let resolved = resolve(url)?;
+    match Backend::of(&resolved)? {
+        Backend::Sqlite => {
+            let path =
+                resolved.strip_prefix("sqlite://").or_else(|| resolved.strip_prefix("sqlite:")).unwrap_or(&resolved);
+            sqlite_db::run(path, sql, limit, read_only)
+        }
+        Backend::Postgres => postgres_db::run(&resolved, sql, limit, read_only),
+        Backend::Mysql => mysql_db::run(&resolved, sql, limit, read_only),
+    }
+}
+
+/// `table, column, type, nullable, key` rows of every user table.
+pub(crate) fn schema(url: &str) -> Result<ResultSet, String> {
+    let backend = Backend::of(&resolve(url)?)?;
+    run(url, backend.schema_sql(), SCHEMA_COLUMNS, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqlite_reads_are_limited_and_writes_refused() {
+        let path = std::env::temp_dir().join(format!("cp_mod_db_{}.db", std::process::id()));
+        let url = path.to_string_lossy().into_owned();
+        let created = rusqlite::Connection::open(&path).and_then(|conn| {
+            conn.execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT NOT NULL); \
+                                INSERT INTO users (email) VALUES ('a@x'), ('b@x'), ('c@x');",
+            )
+        });
+        assert_eq!(created, Ok(()));
+
+        let first = run(&url, "SELECT email FROM users ORDER BY id", 2, true);
+        let expected = ResultSet {
+            columns: vec!["email".to_owned()],
+            rows: vec![vec!["a@x".to_owned()], vec!["b@x".to_owned()]],
+            more: true,
+            affected: None,
+        };
+        assert_eq!(first, Ok(expected));
+        assert_eq!(schema(&url).map(|s| s.rows.len()), Ok(2));
+        assert_eq!(run(&url, "DELETE FROM users", 10, true).ok(), None);
+        assert_eq!(run(&url, "DELETE FROM users", 10, false), Ok(ResultSet::affected(3)));
+        let _removed = std::fs::remove_file(&path);
+    }
+}
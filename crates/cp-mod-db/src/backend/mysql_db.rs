@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use mysql::prelude::Queryable as _;
+use mysql::{Conn, Opts, OptsBuilder, Value};
+
+use super::{CONNECT_TIMEOUT_SECS, ResultSet};
+
+/// Render one value as text.
+fn cell(value: Value) -> String {
+    match value {
+        Value::NULL => "NULL".to_owned(),
+        Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Value::Int(n) => n.to_string(),
+        Value::UInt(n) => n.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Double(f) => f.to_string(),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}.{micros:06}")
+        }
+        Value::Time(negative, days, hours, minutes, seconds, micros) => {
+            let sign = if negative { "-" } else { "" };
+            format!("{sign}{days}d {hours:02}:{minutes:02}:{seconds:02}.{micros:06}")
+        }
+    }
+}
+
+/// Connect to `url`, read-only unless writes are allowed.
+fn connect(url: &str, read_only: bool) -> Result<Conn, String> {
+    let opts = Opts::from_url(url).map_err(|e| e.to_string())?;
+    let builder = OptsBuilder::from_opts(opts).tcp_connect_timeout(Some(Duration::from_secs(CONNECT_TIMEOUT_SECS)));
+    let mut conn = Conn::new(builder).map_err(|e| e.to_string())?;
+    if read_only {
+        conn.query_drop("SET SESSION TRANSACTION READ ONLY").map_err(|e| e.to_string())?;
+    }
+    Ok(conn)
+}
+
+/// Run `sql` on the server at `url`, streaming rows until `limit`.
+pub(super) fn run(url: &str, sql: &str, limit: usize, read_only: bool) -> Result<ResultSet, String> {
+    let mut conn = connect(url, read_only)?;
+    let mut result = conn.query_iter(sql).map_err(|e| e.to_string())?;
+    let columns: Vec<String> = result.columns().as_ref().iter().map(|c| c.name_str().into_owned()).collect();
+    if columns.is_empty() {
+        return Ok(ResultSet::affected(result.affected_rows()));
+    }
+    let mut out = ResultSet { columns, ..ResultSet::default() };
+    for row in result.by_ref() {
+        let values = row.map_err(|e| e.to_string())?.unwrap();
+        if out.rows.len() >= limit {
+            out.more = true;
+            break;
+        }
+        out.rows.push(values.into_iter().map(cell).collect());
+    }
+    Ok(out)
+}
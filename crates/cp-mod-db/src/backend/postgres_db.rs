@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use postgres::{Client, Config, NoTls, SimpleQueryMessage};
+
+use super::{CONNECT_TIMEOUT_SECS, ResultSet};
+use crate::guard::is_query;
+
+/// Connect to `url` (without TLS), read-only unless writes are allowed.
+fn connect(url: &str, read_only: bool) -> Result<Client, String> {
+    let mut config: Config = url.parse().map_err(|e: postgres::Error| e.to_string())?;
+    let mut client =
+        config.connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS)).connect(NoTls).map_err(|e| e.to_string())?;
+    if read_only {
+        client.batch_execute("SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY").map_err(|e| e.to_string())?;
+    }
+    Ok(client)
+}
+
+/// Fold the simple-query messages into rows, keeping `limit` of them.
+fn collect(messages: Vec<SimpleQueryMessage>, limit: usize) -> ResultSet {
+    let mut out = ResultSet::default();
+    for message in messages {
+        if let SimpleQueryMessage::RowDescription(columns) = message {
+            out.columns = columns.iter().map(|c| c.name().to_owned()).collect();
+        } else if let SimpleQueryMessage::Row(row) = message {
+            if out.rows.len() >= limit {
+                out.more = true;
+            } else {
+                out.rows.push((0..row.len()).map(|idx| row.get(idx).unwrap_or("NULL").to_owned()).collect());
+            }
+        } else if let SimpleQueryMessage::CommandComplete(count) = message {
+            out.affected = out.columns.is_empty().then_some(count);
+        } else {
+            // Future message kinds carry nothing shown here.
+        }
+    }
+    out
+}
+
+/// Run `sql` on the server at `url`. Queries go through a cursor so only
+/// `limit + 1` rows ever leave the server.
+pub(super) fn run(url: &str, sql: &str, limit: usize, read_only: bool) -> Result<ResultSet, String> {
+    let mut client = connect(url, read_only)?;
+    if !is_query(sql) {
+        return client.simple_query(sql).map(|messages| collect(messages, limit)).map_err(|e| e.to_string());
+    }
+    client.batch_execute(&format!("BEGIN; DECLARE cp_rows NO SCROLL CURSOR FOR {sql}")).map_err(|e| e.to_string())?;
+    let fetched = client.simple_query(&format!("FETCH {} FROM cp_rows", limit.saturating_add(1)));
+    let _rollback = client.batch_execute("ROLLBACK");
+    fetched.map(|messages| collect(messages, limit)).map_err(|e| e.to_string())
+}
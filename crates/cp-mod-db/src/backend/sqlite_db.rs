@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags, Row};
+
+use super::ResultSet;
+
+/// How long a locked database is waited for.
+const BUSY_TIMEOUT_SECS: u64 = 5;
+
+/// Render one cell as text.
+fn cell(row: &Row<'_>, idx: usize) -> String {
+    match row.get_ref(idx) {
+        Ok(ValueRef::Null) | Err(_) => "NULL".to_owned(),
+        Ok(ValueRef::Integer(n)) => n.to_string(),
+        Ok(ValueRef::Real(f)) => f.to_string(),
+        Ok(ValueRef::Text(bytes)) => String::from_utf8_lossy(bytes).into_owned(),
+        Ok(ValueRef::Blob(b)) => format!("[BLOB {} bytes]", b.len()),
+    }
+}
+
+/// Run `sql` on the database file at `path` (never created when missing).
+pub(super) fn run(path: &str, sql: &str, limit: usize, read_only: bool) -> Result<ResultSet, String> {
+    let mode = if read_only { OpenFlags::SQLITE_OPEN_READ_ONLY } else { OpenFlags::SQLITE_OPEN_READ_WRITE };
+    let conn = Connection::open_with_flags(path, mode | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| format!("cannot open {path}: {e}"))?;
+    conn.busy_timeout(Duration::from_secs(BUSY_TIMEOUT_SECS)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    if stmt.column_count() == 0 {
+        let changed = stmt.execute([]).map_err(|e| e.to_string())?;
+        return Ok(ResultSet::affected(u64::try_from(changed).unwrap_or(u64::MAX)));
+    }
+    let columns: Vec<String> = stmt.column_names().iter().map(|&name| name.to_owned()).collect();
+    let width = columns.len();
+    let mut out = ResultSet { columns, ..ResultSet::default() };
+    let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        if out.rows.len() >= limit {
+            out.more = true;
+            break;
+        }
+        out.rows.push((0..width).map(|idx| cell(row, idx)).collect());
+    }
+    Ok(out)
+}
@@ -0,0 +1,128 @@
+//! Statement checks run before anything reaches the database.
+//!
+//! SQL is reduced to tokens — lowercased words and punctuation, with string
+//! literals and quoted identifiers blanked and comments dropped — so a keyword
+//! inside a value neither blocks a query nor hides one. Read-only mode is
+//! also enforced by the connection itself (see `backend`); this check gives
+//! the agent a clear refusal instead of a driver error.
+
+/// Words a read-only statement may start with.
+const READ_FIRST: &[&str] = &["select", "with", "values", "table", "explain", "show", "describe", "desc", "pragma"];
+
+/// Words that make any statement a write (`into` covers `SELECT … INTO`).
+const WRITE_WORDS: &[&str] = &[
+    "insert", "update", "delete", "merge", "upsert", "create", "alter", "drop", "truncate", "grant", "revoke",
+    "attach", "detach", "copy", "call", "into", "lock", "vacuum",
+];
+
+/// Words that start a row-returning query (limited with a cursor on Postgres).
+const QUERY_FIRST: &[&str] = &["select", "with", "values", "table"];
+
+/// A token: a lowercased word, one punctuation character, or an empty string
+/// for a literal.
+struct Token {
+    /// The token text.
+    text: String,
+    /// Byte offset just past the token.
+    end: usize,
+}
+
+/// Byte length of the literal or comment `sql` starts with, and whether it
+/// is a comment; `None` when it starts with neither. An unterminated one runs
+/// to the end.
+fn literal_len(sql: &str) -> Option<(usize, bool)> {
+    if let Some(body) = sql.strip_prefix("--") {
+        return Some((body.find('\n').map_or(sql.len(), |i| i.saturating_add(2)), true));
+    }
+    if let Some(body) = sql.strip_prefix("/*") {
+        return Some((body.find("*/").map_or(sql.len(), |i| i.saturating_add(4)), true));
+    }
+    let quote = sql.chars().next().filter(|c| matches!(c, '\'' | '"' | '`'))?;
+    let body = sql.get(1..)?;
+    // A doubled quote reads as two adjacent literals, which is just as good.
+    Some((body.find(quote).map_or(sql.len(), |i| i.saturating_add(2)), false))
+}
+
+/// Whether `c` can be part of a word.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte length of the token `rest` starts with, and its text (`None` for
+/// whitespace and comments).
+fn next_token(rest: &str) -> (usize, Option<String>) {
+    if let Some((len, comment)) = literal_len(rest) {
+        return (len, (!comment).then(String::new));
+    }
+    let c = rest.chars().next().unwrap_or(' ');
+    if is_word_char(c) {
+        let end = rest.find(|ch: char| !is_word_char(ch)).unwrap_or(rest.len());
+        return (end, Some(rest.get(..end).unwrap_or_default().to_lowercase()));
+    }
+    (c.len_utf8(), (!c.is_whitespace()).then(|| c.to_string()))
+}
+
+/// The tokens of `sql`, in order.
+fn tokens(sql: &str) -> Vec<Token> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while let Some(rest) = sql.get(pos..).filter(|r| !r.is_empty()) {
+        let (len, text) = next_token(rest);
+        pos = pos.saturating_add(len);
+        out.extend(text.map(|t| Token { text: t, end: pos }));
+    }
+    out
+}
+
+/// `sql` as one statement, without trailing `;` and comments; several
+/// statements are refused so a read-only query cannot smuggle a second one.
+pub(crate) fn single_statement(sql: &str) -> Result<&str, String> {
+    let found = tokens(sql);
+    let body: Vec<&Token> = found.iter().skip_while(|t| t.text == ";").collect();
+    let statement: Vec<&&Token> = body.iter().take_while(|t| t.text != ";").collect();
+    let Some(last) = statement.last() else { return Err("the query is empty".to_owned()) };
+    if body.iter().skip(statement.len()).any(|t| t.text != ";") {
+        return Err("run one statement at a time".to_owned());
+    }
+    Ok(sql.get(..last.end).unwrap_or(sql).trim())
+}
+
+/// Refuse `sql` unless it only reads.
+pub(crate) fn check_read_only(sql: &str) -> Result<(), String> {
+    let found = tokens(sql);
+    let first = found.first().map_or("", |t| t.text.as_str());
+    if !READ_FIRST.contains(&first) {
+        return Err(format!("`{}` statements are not read-only", first.to_uppercase()));
+    }
+    found
+        .iter()
+        .find(|t| WRITE_WORDS.contains(&t.text.as_str()))
+        .map_or(Ok(()), |word| Err(format!("`{}` changes the database", word.text.to_uppercase())))
+}
+
+/// Whether `sql` is a row-returning query.
+pub(crate) fn is_query(sql: &str) -> bool {
+    tokens(sql).first().is_some_and(|t| QUERY_FIRST.contains(&t.text.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals_and_comments_do_not_count() {
+        assert_eq!(check_read_only("SELECT 'drop table x' AS \"delete\" -- update\nFROM t"), Ok(()));
+        assert_eq!(
+            check_read_only("WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d"),
+            Err("`DELETE` changes the database".to_owned())
+        );
+        assert_eq!(check_read_only("update t set a = 1"), Err("`UPDATE` statements are not read-only".to_owned()));
+    }
+
+    #[test]
+    fn only_one_statement_runs() {
+        assert_eq!(single_statement("SELECT count(*) FROM t; -- done\n"), Ok("SELECT count(*) FROM t"));
+        assert_eq!(single_statement("SELECT ';'; ;"), Ok("SELECT ';'"));
+        assert_eq!(single_statement("SELECT 1; DROP TABLE t"), Err("run one statement at a time".to_owned()));
+    }
+}
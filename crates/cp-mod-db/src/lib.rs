@@ -0,0 +1,178 @@
+//! Db module — inspect a project database from the conversation.
+//!
+//! Three tools: `db_connect` (Postgres, `MySQL` or `SQLite` connection string),
+//! `db_schema` (tables and columns as a panel) and `db_query` (one statement,
+//! rows as a markdown table). Statements that change data or schema are
+//! refused, and the connection itself is opened read-only, unless the user
+//! enabled writes with `/db-writes on`; no tool can.
+
+/// Drivers: one connection per call, read-only unless writes are allowed.
+mod backend;
+/// Statement checks: single statement, read-only words.
+mod guard;
+/// Schema panel.
+mod panel;
+/// Tool dispatch: connect, schema, query.
+mod tools;
+/// Db state types: `DbConfig`, `DbState`.
+pub mod types;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::types::{DbConfig, DbState};
+
+/// Lazily parsed tool texts from the db YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/db.yaml")));
+
+/// Allow or stop writes on the connected database. User-side only: no tool
+/// calls this.
+///
+/// # Errors
+///
+/// Writes are enabled while no database is connected.
+pub fn set_writes(state: &mut State, enabled: bool) -> Result<String, String> {
+    tools::set_writes(state, enabled)
+}
+
+/// Db module: schema panel and guarded queries.
+#[derive(Debug, Clone, Copy)]
+pub struct DbModule;
+
+impl Default for DbModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DbModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for DbModule {
+    fn id(&self) -> &'static str {
+        "db"
+    }
+
+    fn name(&self) -> &'static str {
+        "Database"
+    }
+
+    fn description(&self) -> &'static str {
+        "Inspect the project database: schema panel and read-only queries"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: Kind::DB_SCHEMA,
+            icon_id: "entities",
+            is_fixed: false,
+            needs_cache: true,
+            fixed_order: None,
+            display_name: "db-schema",
+            short_name: "schema",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::DB_SCHEMA)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("db_connect", t)
+                .short_desc("Connect to the project database")
+                .category("Database")
+                .param("url", ParamType::String, true)
+                .build(),
+            ToolDefinition::from_yaml("db_schema", t)
+                .short_desc("Show tables and columns as a panel")
+                .category("Database")
+                .build(),
+            ToolDefinition::from_yaml("db_query", t)
+                .short_desc("Run a read-only SQL query")
+                .category("Database")
+                .param("sql", ParamType::String, true)
+                .param("limit", ParamType::Integer, false)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        tools::dispatch(tool, state)
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        (context_type.as_str() == Kind::DB_SCHEMA).then(|| {
+            let panel: Box<dyn Panel> = Box::new(panel::SchemaPanel);
+            panel
+        })
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Database", "Inspect and query the project database")]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(DbState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(DbState::new());
+    }
+
+    fn save_module_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    fn load_module_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+
+    fn save_worker_data(&self, state: &State) -> serde_json::Value {
+        let db = DbState::get(state);
+        serde_json::json!({ "config": db.config, "allow_writes": db.allow_writes })
+    }
+
+    fn load_worker_data(&self, data: &serde_json::Value, state: &mut State) {
+        if let Some(v) = data.get("config")
+            && let Ok(config) = serde_json::from_value::<Option<DbConfig>>(v.clone())
+        {
+            DbState::get_mut(state).config = config;
+        }
+        DbState::get_mut(state).allow_writes =
+            data.get("allow_writes").and_then(serde_json::Value::as_bool).unwrap_or(false);
+    }
+
+    fn context_display_name(&self, context_type: &str) -> Option<&'static str> {
+        (context_type == Kind::DB_SCHEMA).then_some("Schema")
+    }
+
+    fn overview_context_section(&self, state: &State) -> Option<String> {
+        let db = state.get_ext::<DbState>()?;
+        let config = db.config.as_ref()?;
+        let engine = backend::resolve(&config.url)
+            .and_then(|url| backend::Backend::of(&url))
+            .map_or("unknown engine", backend::Backend::name);
+        let mode = if db.allow_writes { "writes allowed" } else { "read-only" };
+        Some(format!("Database: {engine} ({mode})\n"))
+    }
+}
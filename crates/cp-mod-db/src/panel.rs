@@ -0,0 +1,135 @@
+//! Schema panel: every table of the connected database with its columns.
+//!
+//! Created by `db_schema` and filled on a cache worker; calling `db_schema`
+//! again (say, after a migration) marks it stale to refresh it.
+
+use std::fmt::Write as _;
+
+use crossterm::event::KeyEvent;
+
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::backend::{self, ResultSet};
+use crate::types::{DbState, SchemaRequest};
+
+/// Shown while no database is configured.
+const NOT_CONNECTED: &str = "No database connected (db_connect).";
+
+/// `table` headers followed by `column  type [NOT NULL] [PK]` lines.
+pub(crate) fn render_schema(schema: &ResultSet) -> String {
+    let mut out = String::new();
+    let mut current: Option<&str> = None;
+    for row in &schema.rows {
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        let &[table, column, kind, nullable, key] = cells.as_slice() else { continue };
+        if current != Some(table) {
+            let _r = writeln!(out, "{}{table}", if current.is_some() { "\n" } else { "" });
+            current = Some(table);
+        }
+        let not_null = if nullable == "NO" { " NOT NULL" } else { "" };
+        let primary = if key == "PRI" { " PK" } else { "" };
+        let _r = writeln!(out, "  {column}  {kind}{not_null}{primary}");
+    }
+    if out.is_empty() {
+        out.push_str("No tables.\n");
+    }
+    out
+}
+
+/// Panel listing the database schema.
+pub(crate) struct SchemaPanel;
+
+impl Panel for SchemaPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &Entry, state: &State) -> Option<CacheRequest> {
+        let config = DbState::get(state).config.as_ref()?;
+        Some(CacheRequest::new(
+            Kind::new(Kind::DB_SCHEMA),
+            Box::new(SchemaRequest { context_id: ctx.id.clone(), url: config.url.clone() }),
+        ))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<SchemaRequest>().ok()?;
+        let content = backend::schema(&req.url)
+            .map_or_else(|e| format!("Schema unavailable: {e}\n"), |schema| render_schema(&schema));
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id: req.context_id, content, token_count })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, _state: &mut State) -> bool {
+        // Only a Content update carries new bytes; Unchanged/ModuleSpecific are no-ops.
+        if let CacheUpdate::Content { content, token_count, .. } = update {
+            ctx.token_count = token_count;
+            ctx.full_token_count = token_count;
+            ctx.cache_deprecated = false;
+            let _changed = update_if_changed(ctx, &content);
+            ctx.cached_content = Some(content);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        if DbState::get(state).config.is_none() {
+            return vec![Block::styled_text(format!(" {NOT_CONNECTED}"), Semantic::Muted)];
+        }
+        let cached = state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == Kind::DB_SCHEMA)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content
+            .lines()
+            .map(|line| {
+                let semantic = if line.starts_with(' ') { Semantic::Default } else { Semantic::Accent };
+                Block::Line(vec![S::styled(format!(" {line}"), semantic)])
+            })
+            .collect()
+    }
+
+    fn title(&self, _state: &State) -> String {
+        "Schema".to_owned()
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let connected = DbState::get(state).config.is_some();
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::DB_SCHEMA)
+            .map(|c| {
+                let content =
+                    if connected { c.cached_content.as_deref().unwrap_or("[loading...]") } else { NOT_CONNECTED };
+                ContextItem::new(&c.id, "Database schema", content.to_owned(), c.last_refresh_ms)
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
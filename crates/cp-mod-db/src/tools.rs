@@ -0,0 +1,183 @@
+use std::fmt::Write as _;
+
+use cp_base::config::constants;
+use cp_base::modules::truncate_output;
+use cp_base::state::context::{Kind, make_default_entry};
+use cp_base::state::runtime::State;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::backend::{self, Backend, CONNECT_TIMEOUT_SECS, ResultSet};
+use crate::guard::{check_read_only, single_statement};
+use crate::types::{DEFAULT_ROWS, DbConfig, DbState, MAX_ROWS};
+
+/// Budget of one statement, connection included (seconds).
+const QUERY_TIMEOUT_SECS: u64 = 60;
+
+/// Characters shown per cell before it is cut.
+const MAX_CELL_CHARS: usize = 200;
+
+/// Dispatch a db tool call.
+pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+    match tool.name.as_str() {
+        "db_connect" => Some(execute_connect(tool, state)),
+        "db_schema" => Some(execute_schema(tool, state)),
+        "db_query" => Some(execute_query(tool, state)),
+        _ => None,
+    }
+}
+
+/// The error of a db tool called before `db_connect`.
+fn not_connected(tool: &ToolUse) -> ToolResult {
+    ToolResult::new(tool.id.clone(), "Error: no database connected. Use db_connect first.".to_owned(), true)
+}
+
+/// `db_connect`: set (or clear) the connection and try it.
+fn execute_connect(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let url = tool.input.get("url").and_then(|v| v.as_str()).map(str::trim).unwrap_or_default().to_owned();
+    if url.is_empty() {
+        DbState::get_mut(state).config = None;
+        return ToolResult::new(tool.id.clone(), "Database disconnected.".to_owned(), false);
+    }
+    let backend = match backend::resolve(&url).and_then(|resolved| Backend::of(&resolved)) {
+        Ok(backend) => backend,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    };
+    let db = DbState::get_mut(state);
+    if db.config.as_ref().is_none_or(|config| config.url != url) {
+        db.allow_writes = false;
+    }
+    db.config = Some(DbConfig { url: url.clone() });
+    let mode = if db.allow_writes { "writes allowed" } else { "read-only" };
+    cp_base::panels::mark_panels_dirty(state, Kind::DB_SCHEMA);
+    spawn_async_tool(state, tool, CONNECT_TIMEOUT_SECS.saturating_add(5), move || {
+        match backend::run(&url, "SELECT 1", 1, true) {
+            Ok(_) => ToolOutput::ok(format!("Connected to the {} database ({mode}).", backend.name())),
+            Err(e) => ToolOutput::error(format!(
+                "Connection to the {} database failed: {e}\nThe settings are saved; call db_connect again to fix them.",
+                backend.name()
+            )),
+        }
+    })
+}
+
+/// Let `db_query` change data and schema on the connected database, or stop
+/// it; a line saying what changed.
+pub(crate) fn set_writes(state: &mut State, enabled: bool) -> Result<String, String> {
+    let db = DbState::get_mut(state);
+    if enabled && db.config.is_none() {
+        return Err("no database connected; writes apply to the connected one".to_owned());
+    }
+    db.allow_writes = enabled;
+    Ok(if enabled {
+        "Database writes allowed until the agent connects to another database.".to_owned()
+    } else {
+        "Database read-only.".to_owned()
+    })
+}
+
+/// `db_schema`: open the schema panel, or refresh it.
+fn execute_schema(tool: &ToolUse, state: &mut State) -> ToolResult {
+    if DbState::get(state).config.is_none() {
+        return not_connected(tool);
+    }
+    if let Some(ctx) = state.context.iter().find(|c| c.context_type.as_str() == Kind::DB_SCHEMA) {
+        let id = ctx.id.clone();
+        cp_base::panels::mark_panels_dirty(state, Kind::DB_SCHEMA);
+        return ToolResult::new(tool.id.clone(), format!("Schema panel {id} refreshing."), false);
+    }
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+    let mut ctx = make_default_entry(&panel_id, Kind::new(Kind::DB_SCHEMA), "Schema", true);
+    ctx.uid = Some(uid);
+    state.context.push(ctx);
+    ToolResult::new(tool.id.clone(), format!("Schema panel {panel_id} created: every table with its columns."), false)
+}
+
+/// One cell for a markdown table: single line, pipes escaped, cut when long.
+fn table_cell(value: &str) -> String {
+    let flat = value.replace('|', "\\|").replace(['\n', '\r'], " ");
+    if flat.chars().count() <= MAX_CELL_CHARS {
+        return flat;
+    }
+    let cut: String = flat.chars().take(MAX_CELL_CHARS).collect();
+    format!("{cut}\u{2026}")
+}
+
+/// What the agent sees for a statement's result.
+pub(crate) fn render_rows(result: &ResultSet, limit: usize) -> String {
+    if let Some(count) = result.affected {
+        return format!("{count} row(s) affected.");
+    }
+    if result.columns.is_empty() {
+        return "Statement ran; it returned no rows.".to_owned();
+    }
+    let line =
+        |cells: &[String]| format!("| {} |\n", cells.iter().map(|c| table_cell(c)).collect::<Vec<_>>().join(" | "));
+    let mut out = line(&result.columns);
+    let _r = writeln!(out, "|{}", "---|".repeat(result.columns.len()));
+    for row in &result.rows {
+        out.push_str(&line(row));
+    }
+    let count = result.rows.len();
+    let footer = if result.more {
+        format!("\n(first {limit} rows; more match \u{2014} narrow the query or raise limit, max {MAX_ROWS})")
+    } else {
+        format!("\n({count} row(s))")
+    };
+    out.push_str(&footer);
+    out
+}
+
+/// The statement to run: exactly one, and read-only unless writes are allowed.
+fn checked_statement(raw: &str, allow_writes: bool) -> Result<String, String> {
+    let stmt = single_statement(raw)?;
+    if !allow_writes && let Err(e) = check_read_only(stmt) {
+        return Err(format!(
+            "{e}. The database is read-only: only the user can enable writes (by typing /db-writes on)."
+        ));
+    }
+    Ok(stmt.to_owned())
+}
+
+/// `db_query`: run one statement, read-only unless writes are allowed.
+fn execute_query(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(config) = DbState::get(state).config.clone() else { return not_connected(tool) };
+    let allow_writes = DbState::get(state).allow_writes;
+    let raw = tool.input.get("sql").and_then(|v| v.as_str()).unwrap_or_default();
+    let sql = match checked_statement(raw, allow_writes) {
+        Ok(sql) => sql,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Refused: {e}"), true),
+    };
+    let limit = tool
+        .input
+        .get("limit")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(DEFAULT_ROWS, |n| usize::try_from(n).unwrap_or(MAX_ROWS))
+        .clamp(1, MAX_ROWS);
+    spawn_async_tool(state, tool, QUERY_TIMEOUT_SECS, move || {
+        match backend::run(&config.url, &sql, limit, !allow_writes) {
+            Ok(rows) => {
+                ToolOutput::ok(truncate_output(&render_rows(&rows, limit), constants::MAX_RESULT_CONTENT_BYTES))
+            }
+            Err(e) => ToolOutput::error(format!("Query failed: {e}")),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_need_a_connection_and_the_user() {
+        let mut state = State::default();
+        state.set_ext(DbState::new());
+        assert!(set_writes(&mut state, true).is_err_and(|e| e.contains("no database")));
+        DbState::get_mut(&mut state).config = Some(DbConfig { url: "sqlite://app.db".to_owned() });
+        assert!(set_writes(&mut state, true).is_ok_and(|m| m.contains("allowed")));
+        assert!(DbState::get(&state).allow_writes);
+        assert!(checked_statement("DELETE FROM users", false).is_err_and(|e| e.contains("/db-writes")));
+    }
+}
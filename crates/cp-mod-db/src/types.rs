@@ -0,0 +1,63 @@
+use cp_base::state::runtime::State;
+use serde::{Deserialize, Serialize};
+
+/// Rows `db_query` returns when no limit is given.
+pub const DEFAULT_ROWS: usize = 50;
+
+/// Highest row limit `db_query` accepts.
+pub const MAX_ROWS: usize = 500;
+
+/// The project database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbConfig {
+    /// Connection string (`postgres://…`, `mysql://…`, `sqlite://path` or a
+    /// `.db` path), or `$VAR` to read it from the environment on each use.
+    pub url: String,
+}
+
+/// Module-owned state for the Db module.
+/// Stored in `State.module_data` via `TypeMap`.
+#[derive(Debug, Default)]
+pub struct DbState {
+    /// The configured database; `None` when disconnected.
+    pub config: Option<DbConfig>,
+    /// Statements that change data or schema may run. Set by the user only
+    /// (`/db-writes`), and dropped when the agent connects elsewhere.
+    pub allow_writes: bool,
+}
+
+impl DbState {
+    /// Create a disconnected state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+}
+
+/// Cache request of the schema panel (runs on a cache worker).
+#[derive(Debug)]
+pub(crate) struct SchemaRequest {
+    /// Panel to fill.
+    pub context_id: String,
+    /// Connection string, as configured.
+    pub url: String,
+}
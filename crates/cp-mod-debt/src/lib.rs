@@ -0,0 +1,127 @@
+//! Code Debt module — the TODO/FIXME/HACK comments of the codebase.
+//!
+//! A cache worker walks the files git does not ignore, collects comments
+//! that open with one of the three markers and dates them with `git blame`.
+//! They are listed in a fixed panel with their place and age; `debt_to_todo`
+//! lets the AI turn the ones worth doing into todos.
+
+/// Panel rendering for the debt comments.
+mod panel;
+/// File walk, comment detection and `git blame`.
+mod scan;
+/// `debt_to_todo` execution.
+mod tools;
+/// Debt state types: `DebtItem`, `DebtState`.
+mod types;
+
+use types::DebtState;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::panel::DebtPanel;
+
+/// Lazily parsed tool texts from the debt YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/debt.yaml")));
+
+/// Code Debt module: the debt panel and `debt_to_todo`.
+#[derive(Debug, Clone, Copy)]
+pub struct DebtModule;
+
+impl Default for DebtModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebtModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for DebtModule {
+    fn id(&self) -> &'static str {
+        "debt"
+    }
+
+    fn name(&self) -> &'static str {
+        "Code Debt"
+    }
+
+    fn description(&self) -> &'static str {
+        "TODO/FIXME/HACK comments of the codebase, with their age"
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(DebtState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(DebtState::new());
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::CODE_DEBT)]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![(Kind::new(Kind::CODE_DEBT), "Code Debt", false)]
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::CODE_DEBT => Some(Box::new(DebtPanel)),
+            _ => None,
+        }
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("debt_to_todo", t)
+                .short_desc("Turn debt comments into todos")
+                .category("Todo")
+                .param_array("ids", ParamType::String, true)
+                .param("parent_id", ParamType::String, false)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        (tool.name == "debt_to_todo").then(|| tools::execute_to_todo(tool, state))
+    }
+
+    fn context_type_metadata(&self) -> Vec<cp_base::state::context::TypeMeta> {
+        vec![cp_base::state::context::TypeMeta {
+            context_type: Kind::CODE_DEBT,
+            icon_id: "code_debt",
+            is_fixed: true,
+            needs_cache: true,
+            fixed_order: Some(16),
+            display_name: "code debt",
+            short_name: "debt",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &["todo"]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,117 @@
+//! Code Debt panel: the TODO/FIXME/HACK comments of the codebase, rescanned
+//! on a cache worker every [`REFRESH_MS`].
+
+use crossterm::event::KeyEvent;
+
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::scan;
+use crate::types::{DebtState, ScanRequest, ScanResult};
+
+/// How often the codebase is rescanned (ms).
+const REFRESH_MS: u64 = 120_000;
+
+/// Current time in Unix seconds, for ages.
+pub(crate) fn now_secs() -> u64 {
+    cp_base::panels::now_ms().checked_div(1000).unwrap_or(0)
+}
+
+/// Panel listing the debt comments.
+pub(crate) struct DebtPanel;
+
+impl Panel for DebtPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        Some(REFRESH_MS)
+    }
+
+    fn build_cache_request(&self, _ctx: &Entry, _state: &State) -> Option<CacheRequest> {
+        let root = std::env::current_dir().ok()?;
+        Some(CacheRequest::new(Kind::new(Kind::CODE_DEBT), Box::new(ScanRequest { root })))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        // Always answer, even on a foreign payload, so `cache_in_flight` clears.
+        let result =
+            request.data.downcast::<ScanRequest>().map_or_else(|_| ScanResult::default(), |req| scan::scan(&req.root));
+        Some(CacheUpdate::ModuleSpecific { context_type: Kind::new(Kind::CODE_DEBT), data: Box::new(result) })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, state: &mut State) -> bool {
+        ctx.cache_deprecated = false;
+        let CacheUpdate::ModuleSpecific { data, .. } = update else { return false };
+        let Ok(result) = data.downcast::<ScanResult>() else { return false };
+        let debt = DebtState::get_mut(state);
+        debt.absorb(*result);
+        let content = debt.render(now_secs());
+        let token_count = estimate_tokens(&content);
+        ctx.token_count = token_count;
+        ctx.full_token_count = token_count;
+        let changed = update_if_changed(ctx, &content);
+        ctx.cached_content = Some(content);
+        changed
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        let cached = state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == Kind::CODE_DEBT)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Scanning...".into()).italic()])];
+        };
+        content
+            .lines()
+            .map(|line| {
+                let tag = line.split(' ').nth(1).unwrap_or("");
+                let semantic = match tag {
+                    "FIXME" => Semantic::Warning,
+                    "HACK" => Semantic::Error,
+                    _ if line.contains(" -> X") => Semantic::Muted,
+                    _ => Semantic::Default,
+                };
+                Block::Line(vec![S::styled(format!(" {line}"), semantic)])
+            })
+            .collect()
+    }
+
+    fn title(&self, _state: &State) -> String {
+        "Code Debt".to_owned()
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::CODE_DEBT)
+            .map(|c| {
+                let content = c.cached_content.as_deref().unwrap_or("[scanning...]");
+                ContextItem::new(&c.id, "Code debt", content.to_owned(), c.last_refresh_ms)
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
@@ -0,0 +1,229 @@
+//! The background scan: debt comments in the files git does not ignore,
+//! dated with `git blame`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use cp_base::modules::run_with_timeout;
+
+use crate::types::{Blame, DebtItem, ScanResult, Tag};
+
+/// Comments collected at most.
+const MAX_ITEMS: usize = 500;
+
+/// Files larger than this are skipped (generated or vendored, most likely).
+const MAX_FILE_BYTES: u64 = 1_000_000;
+
+/// Files blamed at most per scan; the rest show no age.
+const MAX_BLAME_FILES: usize = 100;
+
+/// Max seconds for one `git blame`.
+const BLAME_TIMEOUT_SECS: u64 = 20;
+
+/// Markers that open a comment in common languages.
+const COMMENT_MARKERS: &[&str] = &["//", "#", "/*", "--", "<!--", ";", "%"];
+
+/// Whether `c` can be part of a word.
+const fn is_word(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Byte offset of `label` standing as a whole word in `line`.
+fn word_at(line: &str, label: &str) -> Option<usize> {
+    line.match_indices(label).map(|m| m.0).find(|&pos| {
+        let before = line.get(..pos).and_then(|b| b.chars().next_back());
+        let after = line.get(pos.saturating_add(label.len())..).and_then(|a| a.chars().next());
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Whether `before` (the line up to a tag) ends with the opening of a
+/// comment outside a string literal, so the tag is the comment's first word.
+fn opens_comment(before: &str) -> bool {
+    let head = before.trim_end();
+    let code = head.trim_end_matches(['/', '#', '*', '-', '!', '<', ';', '%']);
+    let marker = head.get(code.len()..).unwrap_or("");
+    let commented =
+        COMMENT_MARKERS.iter().any(|m| marker.contains(m)) || (marker.starts_with('*') && code.trim().is_empty());
+    commented && code.matches('"').count().is_multiple_of(2)
+}
+
+/// The tag and text of the debt comment on `line`, if it has one.
+pub(crate) fn parse_line(line: &str) -> Option<(Tag, String)> {
+    let (pos, tag) =
+        Tag::ALL.iter().filter_map(|&tag| word_at(line, tag.label()).map(|pos| (pos, tag))).min_by_key(|e| e.0)?;
+    if !opens_comment(line.get(..pos)?) {
+        return None;
+    }
+    let rest = line.get(pos.saturating_add(tag.label().len())..)?;
+    let text = rest.trim_start_matches([':', ' ', '-']).trim_end().trim_end_matches("*/").trim_end_matches("-->");
+    Some((tag, text.trim().to_owned()))
+}
+
+/// Candidate files under `root`, in path order, honouring `.gitignore`.
+fn files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_some_and(|t| !t.is_dir()))
+        .filter(|e| e.metadata().is_ok_and(|m| m.len() <= MAX_FILE_BYTES))
+        .map(ignore::DirEntry::into_path)
+        .collect();
+    files.sort();
+    files
+}
+
+/// Collect the debt comments of one file; `false` once the limit is hit.
+fn scan_file(root: &Path, path: &Path, items: &mut Vec<DebtItem>) -> bool {
+    let Ok(text) = std::fs::read_to_string(path) else { return true };
+    let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy().into_owned();
+    for (index, line) in text.lines().enumerate() {
+        let Some((tag, comment)) = parse_line(line) else { continue };
+        if items.len() >= MAX_ITEMS {
+            return false;
+        }
+        items.push(DebtItem {
+            id: String::new(),
+            path: relative.clone(),
+            line: index.saturating_add(1),
+            tag,
+            text: comment,
+            blame: None,
+            todo: None,
+        });
+    }
+    true
+}
+
+/// Reads `git blame --line-porcelain` output into blame per final line.
+#[derive(Default)]
+struct PorcelainReader {
+    /// Final line number of the entry being read.
+    line: Option<usize>,
+    /// Its author.
+    author: String,
+    /// Its author time, unless uncommitted.
+    time: Option<u64>,
+    /// Whether the entry is an uncommitted change.
+    uncommitted: bool,
+    /// Entries read.
+    blames: BTreeMap<usize, Blame>,
+}
+
+impl PorcelainReader {
+    /// Read one output line.
+    fn row(&mut self, row: &str) {
+        if row.starts_with('\t') {
+            if let Some(line) = self.line.take() {
+                let time = if self.uncommitted { None } else { self.time };
+                let _prev = self.blames.insert(line, Blame { author: std::mem::take(&mut self.author), time });
+            }
+        } else if let Some(author) = row.strip_prefix("author ") {
+            author.clone_into(&mut self.author);
+        } else if let Some(time) = row.strip_prefix("author-time ") {
+            self.time = time.trim().parse().ok();
+        } else {
+            self.header(row);
+        }
+    }
+
+    /// Start an entry at a `<sha> <orig-line> <final-line> [<count>]` header.
+    fn header(&mut self, row: &str) {
+        let mut fields = row.split(' ');
+        let Some(sha) = fields.next().filter(|s| s.len() >= 40 && s.bytes().all(|b| b.is_ascii_hexdigit())) else {
+            return;
+        };
+        self.uncommitted = sha.bytes().all(|b| b == b'0');
+        self.line = fields.nth(1).and_then(|n| n.parse().ok());
+    }
+}
+
+/// Blame per final line, from `git blame --line-porcelain` output.
+pub(crate) fn parse_porcelain(output: &str) -> BTreeMap<usize, Blame> {
+    let mut reader = PorcelainReader::default();
+    for row in output.lines() {
+        reader.row(row);
+    }
+    reader.blames
+}
+
+/// Blame `lines` of the file at `path` (relative to `root`).
+fn blame_file(root: &Path, path: &str, lines: &[usize]) -> BTreeMap<usize, Blame> {
+    let mut cmd = Command::new("git");
+    let _c = cmd.current_dir(root).env("GIT_TERMINAL_PROMPT", "0").args(["blame", "--line-porcelain"]);
+    for line in lines {
+        let _l = cmd.arg("-L").arg(format!("{line},{line}"));
+    }
+    let _p = cmd.arg("--").arg(path);
+    run_with_timeout(cmd, BLAME_TIMEOUT_SECS)
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Date the items with `git blame`, file by file.
+fn blame(root: &Path, items: &mut [DebtItem]) {
+    let mut by_file: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for item in items.iter() {
+        by_file.entry(item.path.clone()).or_default().push(item.line);
+    }
+    for (path, lines) in by_file.iter().take(MAX_BLAME_FILES) {
+        let blames = blame_file(root, path, lines);
+        for item in items.iter_mut().filter(|i| &i.path == path) {
+            item.blame = blames.get(&item.line).cloned();
+        }
+    }
+}
+
+/// Scan the project at `root` (runs on a cache worker).
+pub(crate) fn scan(root: &Path) -> ScanResult {
+    let mut result = ScanResult::default();
+    for path in files(root) {
+        if !scan_file(root, &path, &mut result.items) {
+            result.truncated = true;
+            break;
+        }
+    }
+    blame(root, &mut result.items);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `TAG|text` of the debt comment on `line`.
+    fn parsed(line: &str) -> Option<String> {
+        parse_line(line).map(|(tag, text)| format!("{}|{text}", tag.label()))
+    }
+
+    #[test]
+    fn debt_comments_keep_their_text() {
+        assert_eq!(parsed("    // TODO: retry on timeout"), Some("TODO|retry on timeout".to_owned()));
+        assert_eq!(parsed("x = 1  # FIXME(ann) off by one"), Some("FIXME|(ann) off by one".to_owned()));
+        assert_eq!(parsed(" * HACK - until the API is fixed */"), Some("HACK|until the API is fixed".to_owned()));
+    }
+
+    #[test]
+    fn debt_comments_need_a_comment_marker() {
+        assert_eq!(parsed("let s = \"// TODO not a comment\";"), None);
+        assert_eq!(parsed("fn todo_list() {} // TODOS"), None);
+        assert_eq!(parsed("TODO: no marker"), None);
+        assert_eq!(parsed("//! Lists TODO comments"), None);
+    }
+
+    #[test]
+    fn porcelain_gives_author_and_time_per_line() {
+        let sha = "a".repeat(40);
+        let zero = "0".repeat(40);
+        let output = format!(
+            "{sha} 10 12 1\nauthor Ann\nauthor-time 1700000000\nsummary x\n\tfirst\n\
+             {zero} 30 40 1\nauthor Not Committed Yet\nauthor-time 1800000000\n\tsecond\n"
+        );
+        let blames = parse_porcelain(&output);
+        assert_eq!(blames.get(&12), Some(&Blame { author: "Ann".to_owned(), time: Some(1_700_000_000) }));
+        assert_eq!(blames.get(&40).map(|b| b.time), Some(None));
+    }
+}
@@ -0,0 +1,91 @@
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+use cp_mod_todo::types::{TodoItem, TodoState, TodoStatus};
+
+use crate::panel::now_secs;
+use crate::types::{DebtItem, DebtState};
+
+/// Longest todo name taken from a comment, in characters.
+const NAME_CHARS: usize = 80;
+
+/// The todo made from `item`: the comment as its name, the place and age
+/// as its description.
+fn todo_of(item: &DebtItem, id: String, parent_id: Option<String>) -> TodoItem {
+    let text = if item.text.is_empty() { item.tag.label() } else { item.text.as_str() };
+    let mut name: String = text.chars().take(NAME_CHARS).collect();
+    if text.chars().count() > NAME_CHARS {
+        name.push('\u{2026}');
+    }
+    let age = item.blame.as_ref().map(|b| format!(", {} old, by {}", b.age(now_secs()), b.author)).unwrap_or_default();
+    let description = format!("{} at {}{age}: {}", item.tag.label(), item.location(), item.text);
+    TodoItem { id, parent_id, name, description, status: TodoStatus::Pending }
+}
+
+/// Execute `debt_to_todo`: turn debt comments into todos.
+pub(crate) fn execute_to_todo(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let ids: Vec<String> = tool
+        .input
+        .get("ids")
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_owned)).collect())
+        .unwrap_or_default();
+    if ids.is_empty() {
+        return ToolResult::new(tool.id.clone(), "Missing 'ids': debt IDs from the Code Debt panel".to_owned(), true);
+    }
+    let parent_id = tool.input.get("parent_id").and_then(|v| v.as_str()).map(str::to_owned);
+    if let Some(parent) = parent_id.as_deref()
+        && !TodoState::get(state).todos.iter().any(|t| t.id == parent)
+    {
+        return ToolResult::new(tool.id.clone(), format!("Parent todo '{parent}' not found"), true);
+    }
+    let mut lines = Vec::new();
+    for id in &ids {
+        lines.push(convert(state, id, parent_id.clone()));
+    }
+    state.touch_panel(Kind::TODO);
+    state.touch_panel(Kind::CODE_DEBT);
+    ToolResult::new(tool.id.clone(), lines.join("\n"), false)
+}
+
+/// Turn debt comment `id` into a todo; a line saying what happened.
+fn convert(state: &mut State, id: &str, parent_id: Option<String>) -> String {
+    let Some(item) = DebtState::get(state).items.iter().find(|i| i.id == id).cloned() else {
+        return format!("{id}: not in the Code Debt panel");
+    };
+    if let Some(todo) = item.todo.as_deref() {
+        return format!("{id}: already {todo}");
+    }
+    let todos = TodoState::get_mut(state);
+    let todo_id = format!("X{}", todos.next_todo_id);
+    todos.next_todo_id = todos.next_todo_id.saturating_add(1);
+    let todo = todo_of(&item, todo_id.clone(), parent_id);
+    let line = format!("{id} -> {todo_id}: {}", todo.name);
+    todos.todos.push(todo);
+    if let Some(entry) = DebtState::get_mut(state).items.iter_mut().find(|i| i.id == id) {
+        entry.todo = Some(todo_id);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Blame, Tag};
+
+    #[test]
+    fn todos_carry_place_and_age() {
+        let item = DebtItem {
+            id: "D4".to_owned(),
+            path: "src/net.rs".to_owned(),
+            line: 42,
+            tag: Tag::Fixme,
+            text: "retry drops the last chunk".to_owned(),
+            blame: Some(Blame { author: "ann".to_owned(), time: None }),
+            todo: None,
+        };
+        let todo = todo_of(&item, "X7".to_owned(), None);
+        assert_eq!(todo.name, "retry drops the last chunk");
+        assert_eq!(todo.description, "FIXME at src/net.rs:42, new old, by ann: retry drops the last chunk");
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use cp_base::state::runtime::State;
+
+/// Kind of debt comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tag {
+    /// Work left to do.
+    Todo,
+    /// Known bug.
+    Fixme,
+    /// Deliberate shortcut.
+    Hack,
+}
+
+impl Tag {
+    /// Every tag, in the order they are looked for.
+    pub(crate) const ALL: [Self; 3] = [Self::Todo, Self::Fixme, Self::Hack];
+
+    /// The marker as written in comments.
+    pub(crate) const fn label(self) -> &'static str {
+        match self {
+            Self::Todo => "TODO",
+            Self::Fixme => "FIXME",
+            Self::Hack => "HACK",
+        }
+    }
+}
+
+/// Who last touched a line, and when.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Blame {
+    /// Author name.
+    pub author: String,
+    /// Commit time (Unix seconds); `None` for uncommitted lines.
+    pub time: Option<u64>,
+}
+
+impl Blame {
+    /// Age relative to `now` (Unix seconds): `today`, `12d`, `5mo`, `2y`.
+    pub(crate) fn age(&self, now: u64) -> String {
+        let Some(time) = self.time else { return "new".to_owned() };
+        let days = now.saturating_sub(time).checked_div(86_400).unwrap_or(0);
+        match days {
+            0 => "today".to_owned(),
+            1..60 => format!("{days}d"),
+            60..730 => format!("{}mo", days.checked_div(30).unwrap_or(0)),
+            _ => format!("{}y", days.checked_div(365).unwrap_or(0)),
+        }
+    }
+}
+
+/// One debt comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DebtItem {
+    /// Display ID (D1, D2, ...), kept across scans while the comment stays.
+    pub id: String,
+    /// Path relative to the project.
+    pub path: String,
+    /// 1-based line.
+    pub line: usize,
+    /// TODO, FIXME or HACK.
+    pub tag: Tag,
+    /// The comment after the marker.
+    pub text: String,
+    /// Last change of the line, when git knows it.
+    pub blame: Option<Blame>,
+    /// The todo it was turned into.
+    pub todo: Option<String>,
+}
+
+impl DebtItem {
+    /// `path:line`.
+    pub(crate) fn location(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+
+    /// Whether `other` is the same comment, possibly moved.
+    fn same_as(&self, other: &Self) -> bool {
+        self.path == other.path && self.tag == other.tag && self.text == other.text
+    }
+}
+
+/// Module-owned state: the last scan.
+#[derive(Debug)]
+pub(crate) struct DebtState {
+    /// Comments found, in path and line order.
+    pub items: Vec<DebtItem>,
+    /// Counter for new IDs.
+    pub next_id: usize,
+    /// Whether the scan stopped at its limit.
+    pub truncated: bool,
+    /// Whether a scan has completed.
+    pub scanned: bool,
+}
+
+impl DebtState {
+    /// Create an empty state, before the first scan.
+    pub(crate) const fn new() -> Self {
+        Self { items: Vec::new(), next_id: 1, truncated: false, scanned: false }
+    }
+
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub(crate) fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub(crate) fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+
+    /// Replace the items with a new scan. A comment seen before keeps its ID
+    /// and the todo made from it; new ones get fresh IDs.
+    pub(crate) fn absorb(&mut self, result: ScanResult) {
+        let mut previous = std::mem::take(&mut self.items);
+        for mut item in result.items {
+            if let Some(index) = previous.iter().position(|old| old.same_as(&item)) {
+                let old = previous.swap_remove(index);
+                item.id = old.id;
+                item.todo = old.todo;
+            } else {
+                item.id = format!("D{}", self.next_id);
+                self.next_id = self.next_id.saturating_add(1);
+            }
+            self.items.push(item);
+        }
+        self.truncated = result.truncated;
+        self.scanned = true;
+    }
+
+    /// The panel text.
+    pub(crate) fn render(&self, now: u64) -> String {
+        if !self.scanned {
+            return "Scanning for TODO/FIXME/HACK comments...\n".to_owned();
+        }
+        let count = |tag: Tag| self.items.iter().filter(|i| i.tag == tag).count();
+        let mut out = format!(
+            "{} debt comment(s): {} TODO, {} FIXME, {} HACK{}\n",
+            self.items.len(),
+            count(Tag::Todo),
+            count(Tag::Fixme),
+            count(Tag::Hack),
+            if self.truncated { " (scan limit reached)" } else { "" }
+        );
+        for item in &self.items {
+            let blame = item.blame.as_ref().map(|b| format!("  {} {}", b.age(now), b.author)).unwrap_or_default();
+            let todo = item.todo.as_ref().map(|t| format!("  -> {t}")).unwrap_or_default();
+            let _r = writeln!(out, "{} {} {}{blame}  {}{todo}", item.id, item.tag.label(), item.location(), item.text);
+        }
+        out
+    }
+}
+
+/// Cache request of the Code Debt panel.
+#[derive(Debug)]
+pub(crate) struct ScanRequest {
+    /// Project root.
+    pub root: PathBuf,
+}
+
+/// What one scan found (IDs not yet assigned).
+#[derive(Debug, Default)]
+pub(crate) struct ScanResult {
+    /// Comments, in path and line order.
+    pub items: Vec<DebtItem>,
+    /// Whether the scan stopped at its limit.
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rescans_keep_ids_and_todos() {
+        let item = |line: usize, text: &str| DebtItem {
+            id: String::new(),
+            path: "src/a.rs".to_owned(),
+            line,
+            tag: Tag::Todo,
+            text: text.to_owned(),
+            blame: Some(Blame { author: "ann".to_owned(), time: Some(0) }),
+            todo: None,
+        };
+        let mut debt = DebtState::new();
+        debt.absorb(ScanResult { items: vec![item(3, "retry"), item(9, "cache")], truncated: false });
+        if let Some(first) = debt.items.first_mut() {
+            first.todo = Some("X2".to_owned());
+        }
+        debt.absorb(ScanResult { items: vec![item(1, "new"), item(5, "retry")], truncated: false });
+        let ids: Vec<(&str, Option<&str>)> = debt.items.iter().map(|i| (i.id.as_str(), i.todo.as_deref())).collect();
+        assert_eq!(ids, vec![("D3", None), ("D1", Some("X2"))]);
+        let text = debt.render(34_560_000);
+        assert!(text.starts_with("2 debt comment(s): 2 TODO, 0 FIXME, 0 HACK\n"));
+        assert!(text.contains("D1 TODO src/a.rs:5  13mo ann  retry  -> X2\n"));
+    }
+}
@@ -0,0 +1,157 @@
+//! Known advisories: a local database kept in the project, and the
+//! comparison of dependency versions against it.
+//!
+//! The database is `.context-pilot/advisories.yaml`, a list of entries:
+//!
+//! ```yaml
+//! - id: RUSTSEC-2024-0001
+//!   ecosystem: crates.io   # or npm, PyPI; omitted = any
+//!   package: foo
+//!   fixed: "1.2.4"         # versions below are affected; omitted = all
+//!   title: Out-of-bounds read in Foo::parse
+//! ```
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use cp_base::config::constants::STORE_DIR;
+
+use crate::manifest::{Dependency, Ecosystem};
+
+/// File name of the local advisory database, under the store dir.
+const LOCAL_DB: &str = "advisories.yaml";
+
+/// One entry of the local advisory database.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Advisory {
+    /// Identifier (`RUSTSEC-...`, `GHSA-...`, `CVE-...`).
+    pub id: String,
+    /// Registry; any when omitted.
+    #[serde(default)]
+    pub ecosystem: Option<String>,
+    /// Affected package.
+    pub package: String,
+    /// First fixed version; every version is affected when omitted.
+    #[serde(default)]
+    pub fixed: Option<String>,
+    /// One-line description.
+    #[serde(default)]
+    pub title: String,
+}
+
+/// A dependency version hit by an advisory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Finding {
+    /// Affected package.
+    pub package: String,
+    /// Version in use.
+    pub version: String,
+    /// Advisory identifier.
+    pub id: String,
+    /// One-line description.
+    pub title: String,
+    /// Versions with the fix, when there are any.
+    pub fixed: Option<String>,
+    /// Where the advisory was found (`local db`, `cargo audit`).
+    pub source: &'static str,
+}
+
+/// The local advisory database of the project at `root`: `None` when there
+/// is none, an error when it does not parse.
+pub(crate) fn load_local(root: &Path) -> Option<Result<Vec<Advisory>, String>> {
+    let path = root.join(STORE_DIR).join(LOCAL_DB);
+    let text = std::fs::read_to_string(&path).ok()?;
+    Some(serde_yaml::from_str(&text).map_err(|e| format!("{}: {e}", path.display())))
+}
+
+/// The concrete version in a requirement (`^1.2.3` gives `1.2.3`, `>=2.31,<3`
+/// gives `2.31`), if it names one.
+pub(crate) fn version_in(requirement: &str) -> Option<&str> {
+    let start = requirement.find(|c: char| c.is_ascii_digit())?;
+    let rest = requirement.get(start..)?;
+    let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))).unwrap_or(rest.len());
+    rest.get(..end)
+}
+
+/// Compare dotted versions numerically, missing parts counting as 0; a
+/// pre-release (`1.0.0-rc1`) sorts before its release.
+pub(crate) fn compare(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> (Vec<u64>, bool) {
+        let (release, pre) = v.split_once('-').map_or((v, false), |(r, _)| (r, true));
+        let numbers = release
+            .split('+')
+            .next()
+            .unwrap_or(release)
+            .split('.')
+            .map(|p| p.chars().take_while(char::is_ascii_digit).collect::<String>().parse().unwrap_or(0));
+        (numbers.collect(), pre)
+    };
+    let ((left, left_pre), (right, right_pre)) = (split(a), split(b));
+    let len = left.len().max(right.len());
+    let pad = |v: &[u64]| (0..len).map(|i| v.get(i).copied().unwrap_or(0)).collect::<Vec<_>>();
+    pad(&left).cmp(&pad(&right)).then(right_pre.cmp(&left_pre))
+}
+
+/// Whether `advisory` applies to `dep` at `version`.
+fn affects(advisory: &Advisory, dep: &Dependency, version: &str) -> bool {
+    advisory.package.eq_ignore_ascii_case(&dep.name)
+        && advisory.ecosystem.as_deref().is_none_or(|e| Ecosystem::parse(e) == Some(dep.ecosystem))
+        && advisory.fixed.as_deref().is_none_or(|fixed| compare(version, fixed) == Ordering::Less)
+}
+
+/// Local advisories hitting `deps`, each paired with its version in use
+/// (skipped when unknown).
+pub(crate) fn match_local<'dep>(
+    advisories: &[Advisory],
+    deps: impl IntoIterator<Item = (&'dep Dependency, Option<&'dep str>)>,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (dep, in_use) in deps {
+        let Some(version) = in_use else { continue };
+        findings.extend(advisories.iter().filter(|a| affects(a, dep, version)).map(|a| Finding {
+            package: dep.name.clone(),
+            version: version.to_owned(),
+            id: a.id.clone(),
+            title: a.title.clone(),
+            fixed: a.fixed.as_ref().map(|f| format!(">={f}")),
+            source: "local db",
+        }));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert_eq!(compare("1.10.0", "1.9"), Ordering::Greater);
+        assert_eq!(compare("2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare("1.0.0-rc1", "1.0.0"), Ordering::Less);
+        assert_eq!(version_in("^1.2.3"), Some("1.2.3"));
+        assert_eq!(version_in(">=2.31,<3"), Some("2.31"));
+        assert_eq!(version_in("workspace"), None);
+    }
+
+    #[test]
+    fn advisories_match_versions_below_the_fix() {
+        let advisories: Vec<Advisory> = serde_yaml::from_str(
+            "- {id: A-1, ecosystem: crates.io, package: foo, fixed: \"1.2.4\", title: bad}\n\
+             - {id: A-2, ecosystem: npm, package: foo, title: other registry}\n",
+        )
+        .unwrap_or_default();
+        let dep = |name: &str| Dependency {
+            name: name.to_owned(),
+            requirement: String::new(),
+            ecosystem: Ecosystem::Cargo,
+            dev: false,
+        };
+        let (old, new, other) = (dep("foo"), dep("foo"), dep("bar"));
+        let found = match_local(&advisories, [(&old, Some("1.2.3")), (&new, Some("1.3.0")), (&other, Some("0.1.0"))]);
+        let ids: Vec<(&str, &str)> = found.iter().map(|f| (f.id.as_str(), f.version.as_str())).collect();
+        assert_eq!(ids, vec![("A-1", "1.2.3")]);
+    }
+}
@@ -0,0 +1,195 @@
+//! External checkers, each optional: `cargo audit` for RustSec advisories,
+//! `cargo outdated` and `npm outdated` for newer releases, `cargo metadata`
+//! for crate licenses. A checker that is not installed, or fails, becomes a
+//! note in the report.
+
+use std::path::Path;
+use std::process::Command;
+
+use cp_base::modules::run_with_timeout;
+
+use crate::advisory::Finding;
+use crate::manifest::Ecosystem;
+
+/// Max seconds per checker (`cargo audit` may fetch its database first).
+const CHECK_TIMEOUT_SECS: u64 = 120;
+
+/// A dependency with a newer release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Outdated {
+    /// Registry.
+    pub ecosystem: Ecosystem,
+    /// Package name.
+    pub package: String,
+    /// Version in use.
+    pub current: String,
+    /// Latest release.
+    pub latest: String,
+}
+
+/// Run `program args` in `dir`; its stdout even on a non-zero exit, since
+/// the checkers exit 1 when they find something.
+pub(crate) fn run(dir: &Path, program: &str, args: &[&str], install_hint: &str) -> Result<String, String> {
+    let mut cmd = Command::new(program);
+    let _c = cmd.args(args).current_dir(dir).env("NO_COLOR", "1");
+    let label = format!("{program} {}", args.first().copied().unwrap_or(""));
+    let output = run_with_timeout(cmd, CHECK_TIMEOUT_SECS).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            format!("{label}: not installed ({install_hint})")
+        } else {
+            format!("{label}: {e}")
+        }
+    })?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if stdout.trim().is_empty() && !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("failed").trim();
+        return Err(format!("{label}: {reason}"));
+    }
+    Ok(stdout)
+}
+
+/// Advisories of the `RustSec` database for the `Cargo.lock` in `dir`, transitive crates included.
+pub(crate) fn cargo_audit(dir: &Path) -> Result<Vec<Finding>, String> {
+    let stdout = run(dir, "cargo", &["audit", "--json"], "cargo install cargo-audit")?;
+    parse_cargo_audit(&stdout)
+}
+
+/// Findings of `cargo audit --json` output.
+fn parse_cargo_audit(stdout: &str) -> Result<Vec<Finding>, String> {
+    let json: serde_json::Value =
+        serde_json::from_str(stdout).map_err(|e| format!("cargo audit: unreadable output ({e})"))?;
+    let list = json.pointer("/vulnerabilities/list").and_then(serde_json::Value::as_array);
+    let text = |v: &serde_json::Value, path: &str| {
+        v.pointer(path).and_then(serde_json::Value::as_str).unwrap_or("").to_owned()
+    };
+    Ok(list
+        .into_iter()
+        .flatten()
+        .map(|v| {
+            let patched: Vec<&str> = v
+                .pointer("/versions/patched")
+                .and_then(serde_json::Value::as_array)
+                .map(|p| p.iter().filter_map(serde_json::Value::as_str).collect())
+                .unwrap_or_default();
+            Finding {
+                package: text(v, "/package/name"),
+                version: text(v, "/package/version"),
+                id: text(v, "/advisory/id"),
+                title: text(v, "/advisory/title"),
+                fixed: (!patched.is_empty()).then(|| patched.join(" or ")),
+                source: "cargo audit",
+            }
+        })
+        .collect())
+}
+
+/// Direct crates of the workspace in `dir` with newer releases.
+pub(crate) fn cargo_outdated(dir: &Path) -> Result<Vec<Outdated>, String> {
+    let stdout =
+        run(dir, "cargo", &["outdated", "--root-deps-only", "--format", "json"], "cargo install cargo-outdated")?;
+    Ok(parse_cargo_outdated(&stdout))
+}
+
+/// Entries of `cargo outdated --format json` output (one JSON object per
+/// workspace member), deduplicated.
+fn parse_cargo_outdated(stdout: &str) -> Vec<Outdated> {
+    let mut out: Vec<Outdated> = Vec::new();
+    for json in stdout.lines().filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok()) {
+        let Some(deps) = json.get("dependencies").and_then(serde_json::Value::as_array) else { continue };
+        for dep in deps {
+            let field = |key: &str| dep.get(key).and_then(serde_json::Value::as_str).unwrap_or("").to_owned();
+            let entry = Outdated {
+                ecosystem: Ecosystem::Cargo,
+                package: field("name"),
+                current: field("project"),
+                latest: field("latest"),
+            };
+            if entry.latest != entry.current
+                && entry.latest != "---"
+                && !entry.latest.is_empty()
+                && !out.contains(&entry)
+            {
+                out.push(entry);
+            }
+        }
+    }
+    out
+}
+
+/// Packages of the `package.json` in `dir` with newer releases.
+pub(crate) fn npm_outdated(dir: &Path) -> Result<Vec<Outdated>, String> {
+    let stdout = run(dir, "npm", &["outdated", "--json"], "install Node.js")?;
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).map_err(|e| format!("npm outdated: unreadable output ({e})"))?;
+    let Some(packages) = json.as_object() else { return Ok(Vec::new()) };
+    Ok(packages
+        .iter()
+        .map(|(name, info)| {
+            let field = |key: &str| info.get(key).and_then(serde_json::Value::as_str).unwrap_or("?").to_owned();
+            Outdated {
+                ecosystem: Ecosystem::Npm,
+                package: name.clone(),
+                current: field("current"),
+                latest: field("latest"),
+            }
+        })
+        .collect())
+}
+
+/// A resolved package and the license it declares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Licensed {
+    /// Package name.
+    pub package: String,
+    /// Resolved version.
+    pub version: String,
+    /// SPDX expression, when declared.
+    pub license: Option<String>,
+}
+
+/// Licenses of every crate the workspace in `dir` resolves, its own members
+/// (which have no registry or git source) left out.
+pub(crate) fn cargo_licenses(dir: &Path) -> Result<Vec<Licensed>, String> {
+    let stdout = run(dir, "cargo", &["metadata", "--format-version", "1"], "install Rust")?;
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).map_err(|e| format!("cargo metadata: unreadable output ({e})"))?;
+    let packages = json.get("packages").and_then(serde_json::Value::as_array);
+    Ok(packages
+        .into_iter()
+        .flatten()
+        .filter(|p| p.get("source").is_some_and(|s| !s.is_null()))
+        .map(|p| {
+            let field = |key: &str| p.get(key).and_then(serde_json::Value::as_str).map(str::to_owned);
+            Licensed {
+                package: field("name").unwrap_or_default(),
+                version: field("version").unwrap_or_default(),
+                license: field("license"),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checker_outputs_are_read() {
+        let audit = r#"{"vulnerabilities": {"found": true, "count": 1, "list": [{
+            "advisory": {"id": "RUSTSEC-2023-0071", "package": "rsa", "title": "Marvin Attack"},
+            "versions": {"patched": [], "unaffected": []},
+            "package": {"name": "rsa", "version": "0.9.6"}}]}}"#;
+        let found = parse_cargo_audit(audit).unwrap_or_default();
+        let ids: Vec<String> = found.iter().map(|f| format!("{} {} {:?}", f.id, f.version, f.fixed)).collect();
+        assert_eq!(ids, vec!["RUSTSEC-2023-0071 0.9.6 None".to_owned()]);
+
+        let outdated = "{\"crate_name\": \"a\", \"dependencies\": [\
+            {\"name\": \"log\", \"project\": \"0.4.20\", \"compat\": \"0.4.22\", \"latest\": \"0.4.22\"},\
+            {\"name\": \"serde\", \"project\": \"1.0.0\", \"compat\": \"---\", \"latest\": \"---\"}]}\n\
+            {\"crate_name\": \"b\", \"dependencies\": [\
+            {\"name\": \"log\", \"project\": \"0.4.20\", \"compat\": \"0.4.22\", \"latest\": \"0.4.22\"}]}\n";
+        let names: Vec<String> = parse_cargo_outdated(outdated).into_iter().map(|o| o.package).collect();
+        assert_eq!(names, vec!["log".to_owned()]);
+    }
+}
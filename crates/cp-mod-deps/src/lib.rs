@@ -0,0 +1,132 @@
+//! Deps module — audit a project's dependencies and license compliance.
+//!
+//! `deps_audit` reads every `Cargo.toml`, `package.json` and `pyproject.toml`
+//! under a directory, resolves versions from the lockfiles, and checks them
+//! against the project's local advisory database and `cargo audit`, and for
+//! newer releases with `cargo outdated` / `npm outdated`. The report lands in
+//! a panel: vulnerable first, then outdated, then every dependency.
+//!
+//! `license_check` enforces `.context-pilot/license_policy.yaml`: a header
+//! on new and modified source files, and allowed dependency licenses.
+
+/// Local advisory database and version comparison.
+mod advisory;
+/// `cargo audit`, `cargo outdated`, `npm outdated` and `cargo metadata`.
+mod external;
+/// `license_check`: header and dependency license compliance.
+mod license;
+/// Manifest and lockfile parsing.
+mod manifest;
+/// Audit report panel.
+mod panel;
+/// The license policy file.
+mod policy;
+/// `deps_audit` execution and its report.
+mod tools;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+/// Lazily parsed tool texts from the deps YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/deps.yaml")));
+
+/// Deps module: dependency listing, advisories and outdated releases.
+#[derive(Debug, Clone, Copy)]
+pub struct DepsModule;
+
+impl Default for DepsModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DepsModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for DepsModule {
+    fn id(&self) -> &'static str {
+        "deps"
+    }
+
+    fn name(&self) -> &'static str {
+        "Dependencies"
+    }
+
+    fn description(&self) -> &'static str {
+        "Audit dependencies for advisories, newer releases and license compliance"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: panel::DEPS_PANEL_TYPE,
+            icon_id: "library",
+            is_fixed: false,
+            needs_cache: false,
+            fixed_order: None,
+            display_name: "deps",
+            short_name: "deps",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(panel::DEPS_PANEL_TYPE)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("deps_audit", t)
+                .short_desc("Audit dependencies for advisories")
+                .category("Dependencies")
+                .param("path", ParamType::String, false)
+                .build(),
+            ToolDefinition::from_yaml("license_check", t)
+                .short_desc("Check license headers and dependency licenses")
+                .category("Dependencies")
+                .param("base", ParamType::String, false)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        match tool.name.as_str() {
+            "deps_audit" => Some(tools::execute_audit(tool, state)),
+            "license_check" => Some(license::execute_license_check(tool, state)),
+            _ => None,
+        }
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        (context_type.as_str() == panel::DEPS_PANEL_TYPE).then(|| {
+            let panel: Box<dyn Panel> = Box::new(panel::ResultPanel);
+            panel
+        })
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![(
+            "Dependencies",
+            "Check the project's dependencies and licensing before upgrading, committing or shipping",
+        )]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,235 @@
+//! `license_check` — compliance of the working tree with the project's
+//! license policy: the required header on every new or modified source file,
+//! and the licenses of the crates and Node packages the project pulls in.
+//! The result is a YAML report, returned as an error when anything fails.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use cp_base::state::runtime::State;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::external::{self, Licensed};
+use crate::manifest;
+use crate::policy::{self, HeaderCheck, HeaderRule, LicenseRule};
+
+/// Max seconds for the check (`cargo metadata` may resolve the lockfile).
+const LICENSE_TIMEOUT_SECS: u64 = 180;
+
+/// A changed file without the full header.
+#[derive(Debug, Serialize)]
+struct MissingHeader {
+    /// Path relative to the project.
+    path: String,
+    /// Header lines not found near its top.
+    missing: Vec<String>,
+}
+
+/// Outcome of the header check.
+#[derive(Debug, Default, Serialize)]
+struct HeaderReport {
+    /// Changed files the rule applies to.
+    checked: usize,
+    /// Those lacking the header.
+    missing: Vec<MissingHeader>,
+}
+
+/// A dependency whose license breaks the policy.
+#[derive(Debug, Serialize)]
+struct LicenseViolation {
+    /// Package name.
+    package: String,
+    /// Resolved version.
+    version: String,
+    /// Declared license expression.
+    license: String,
+    /// What is wrong with it.
+    reason: String,
+}
+
+/// Outcome of the dependency license check.
+#[derive(Debug, Default, Serialize)]
+struct DependencyReport {
+    /// Packages checked.
+    checked: usize,
+    /// Those breaking the policy.
+    violations: Vec<LicenseViolation>,
+}
+
+/// The structured report.
+#[derive(Debug, Default, Serialize)]
+struct Report {
+    /// `pass` or `fail`.
+    status: &'static str,
+    /// Header check, when the policy has a header rule.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    headers: Option<HeaderReport>,
+    /// License check, when the policy has dependency rules.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependencies: Option<DependencyReport>,
+    /// Checks that could not run fully.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+}
+
+impl Report {
+    /// Whether anything breaks the policy.
+    fn failed(&self) -> bool {
+        self.headers.as_ref().is_some_and(|h| !h.missing.is_empty())
+            || self.dependencies.as_ref().is_some_and(|d| !d.violations.is_empty())
+    }
+}
+
+/// Paths of new and modified files in `git status --porcelain` output
+/// (deleted ones left out; renames give the new path).
+fn parse_status(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter(|l| l.len() > 3 && !l.get(..2).is_some_and(|xy| xy.contains('D')))
+        .filter_map(|l| l.get(3..))
+        .map(|path| path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"').to_owned())
+        .collect()
+}
+
+/// New and modified files: uncommitted ones, plus those changed since `base`.
+fn changed_files(root: &Path, base: Option<&str>) -> Result<Vec<String>, String> {
+    let status = external::run(root, "git", &["status", "--porcelain", "--untracked-files=all"], "install git")?;
+    let mut files = parse_status(&status);
+    if let Some(rev) = base {
+        let diff = external::run(root, "git", &["diff", "--name-only", "--diff-filter=d", rev], "install git")?;
+        files.extend(diff.lines().map(str::to_owned));
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Check `files` for the header `rule` requires.
+fn check_headers(root: &Path, rule: &HeaderRule, files: &[String]) -> Result<HeaderReport, String> {
+    let check = HeaderCheck::new(rule)?;
+    let mut report = HeaderReport::default();
+    for path in files.iter().filter(|p| check.applies(p)) {
+        let Ok(content) = std::fs::read_to_string(root.join(path)) else { continue };
+        report.checked = report.checked.saturating_add(1);
+        let missing = check.missing(&content);
+        if !missing.is_empty() {
+            report
+                .missing
+                .push(MissingHeader { path: path.clone(), missing: missing.into_iter().map(str::to_owned).collect() });
+        }
+    }
+    Ok(report)
+}
+
+/// License of an installed Node package, from its own `package.json`.
+fn npm_license(root: &Path, name: &str) -> Option<Licensed> {
+    let text = std::fs::read_to_string(root.join("node_modules").join(name).join("package.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&text).ok()?;
+    let license = json
+        .get("license")
+        .or_else(|| json.pointer("/licenses/0/type"))
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_owned);
+    let version = json.get("version").and_then(serde_json::Value::as_str).unwrap_or("?").to_owned();
+    Some(Licensed { package: name.to_owned(), version, license })
+}
+
+/// Licensed packages of the project at `root`, with notes on what was not checked.
+fn dependency_licenses(root: &Path, notes: &mut Vec<String>) -> Vec<Licensed> {
+    let mut packages = Vec::new();
+    if root.join("Cargo.toml").is_file() {
+        match external::cargo_licenses(root) {
+            Ok(found) => packages.extend(found),
+            Err(e) => notes.push(e),
+        }
+    }
+    if let Ok(text) = std::fs::read_to_string(root.join("package.json")) {
+        let declared = manifest::parse("package.json", &text).unwrap_or_default();
+        let (installed, absent): (Vec<_>, Vec<_>) =
+            declared.iter().map(|d| (d, npm_license(root, &d.name))).partition(|entry| entry.1.is_some());
+        packages.extend(installed.into_iter().filter_map(|entry| entry.1));
+        if !absent.is_empty() {
+            notes
+                .push(format!("{} npm package(s) not installed, licenses not checked (run npm install)", absent.len()));
+        }
+    }
+    if root.join("pyproject.toml").is_file() {
+        notes.push("PyPI package licenses are not checked".to_owned());
+    }
+    packages
+}
+
+/// Check every dependency license against `rule`.
+fn check_dependencies(root: &Path, rule: &LicenseRule, notes: &mut Vec<String>) -> DependencyReport {
+    let packages = dependency_licenses(root, notes);
+    let violations = packages
+        .iter()
+        .filter_map(|p| {
+            let reason = rule.violation(p.license.as_deref())?;
+            Some(LicenseViolation {
+                package: p.package.clone(),
+                version: p.version.clone(),
+                license: p.license.clone().unwrap_or_default(),
+                reason,
+            })
+        })
+        .collect();
+    DependencyReport { checked: packages.len(), violations }
+}
+
+/// Run every check the policy defines.
+fn run_check(root: &Path, base: Option<&str>) -> ToolOutput {
+    let policy = match policy::load(root) {
+        Ok(p) => p,
+        Err(e) => return ToolOutput::error(e),
+    };
+    let mut report = Report::default();
+    if let Some(rule) = policy.header.as_ref() {
+        let headers = changed_files(root, base).and_then(|files| check_headers(root, rule, &files));
+        match headers {
+            Ok(h) => report.headers = Some(h),
+            Err(e) => return ToolOutput::error(format!("header check failed: {e}")),
+        }
+    }
+    if let Some(rule) = policy.dependencies.as_ref() {
+        report.dependencies = Some(check_dependencies(root, rule, &mut report.notes));
+    }
+    if report.headers.is_none() && report.dependencies.is_none() {
+        return ToolOutput::error(format!("{} defines neither `header` nor `dependencies`", policy::POLICY_FILE));
+    }
+    let failed = report.failed();
+    report.status = if failed { "fail" } else { "pass" };
+    let yaml = serde_yaml::to_string(&report).unwrap_or_default();
+    if failed { ToolOutput::error(yaml) } else { ToolOutput::ok(yaml) }
+}
+
+/// Execute `license_check` off the main loop.
+pub(crate) fn execute_license_check(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let base =
+        tool.input.get("base").and_then(|v| v.as_str()).map(str::trim).filter(|b| !b.is_empty()).map(str::to_owned);
+    if base.as_deref().is_some_and(|b| b.starts_with('-')) {
+        return ToolResult::new(tool.id.clone(), "Error: base must be a revision, not an option".to_owned(), true);
+    }
+    spawn_async_tool(state, tool, LICENSE_TIMEOUT_SECS, move || run_check(Path::new("."), base.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_lists_new_and_modified_files() {
+        let status = " M src/lib.rs\n?? src/new.rs\nD  gone.rs\nR  old.rs -> src/moved.rs\nA  \"with space.rs\"\n";
+        assert_eq!(parse_status(status), vec!["src/lib.rs", "src/new.rs", "src/moved.rs", "with space.rs"]);
+        let report = Report {
+            headers: Some(HeaderReport {
+                checked: 1,
+                missing: vec![MissingHeader { path: "src/new.rs".to_owned(), missing: vec!["Copyright".to_owned()] }],
+            }),
+            ..Report::default()
+        };
+        assert!(report.failed());
+    }
+}
@@ -0,0 +1,405 @@
+//! Direct dependencies read from `Cargo.toml`, `package.json` and
+//! `pyproject.toml`, and the versions their lockfiles resolve.
+//!
+//! The TOML manifests are read line by line rather than fully parsed: the
+//! dependency tables have a regular shape, and a key the reader does not
+//! understand only costs that one entry.
+
+use std::collections::BTreeMap;
+
+/// Manifest file names the audit looks for.
+pub(crate) const MANIFESTS: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml"];
+
+/// Package registry a dependency comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Ecosystem {
+    /// Rust crates.
+    Cargo,
+    /// Node packages.
+    Npm,
+    /// Python packages.
+    PyPI,
+}
+
+impl Ecosystem {
+    /// Registry name, as advisory databases spell it.
+    pub(crate) const fn label(self) -> &'static str {
+        match self {
+            Self::Cargo => "crates.io",
+            Self::Npm => "npm",
+            Self::PyPI => "PyPI",
+        }
+    }
+
+    /// Match a registry name from an advisory entry.
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "crates.io" | "cargo" | "rust" => Some(Self::Cargo),
+            "npm" | "node" => Some(Self::Npm),
+            "pypi" | "pip" | "python" => Some(Self::PyPI),
+            _ => None,
+        }
+    }
+}
+
+/// A direct dependency declared in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Dependency {
+    /// Package name.
+    pub name: String,
+    /// Version requirement as written (`^1.2`, `>=2.31`), or where it comes
+    /// from when it has none (`path`, `git`, `workspace`).
+    pub requirement: String,
+    /// Registry.
+    pub ecosystem: Ecosystem,
+    /// Only used by tests, builds or optional extras.
+    pub dev: bool,
+}
+
+impl Dependency {
+    /// A dependency of `ecosystem`.
+    fn new(name: &str, requirement: &str, ecosystem: Ecosystem, dev: bool) -> Self {
+        Self { name: name.to_owned(), requirement: requirement.to_owned(), ecosystem, dev }
+    }
+}
+
+/// Dependencies declared in the manifest `file_name` (one of [`MANIFESTS`]).
+pub(crate) fn parse(file_name: &str, text: &str) -> Result<Vec<Dependency>, String> {
+    match file_name {
+        "Cargo.toml" => Ok(parse_cargo(text)),
+        "package.json" => parse_package_json(text),
+        "pyproject.toml" => Ok(parse_pyproject(text)),
+        other => Err(format!("{other} is not a known manifest")),
+    }
+}
+
+/// `line` without a trailing `# comment` (a `#` inside quotes is kept).
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return line.get(..i).unwrap_or(line),
+            (Some(_) | None, _) => {}
+        }
+    }
+    line
+}
+
+/// The string value of `key = "..."` inside an inline table.
+fn inline_value<'line>(table: &'line str, key: &str) -> Option<&'line str> {
+    table.split([',', '{', '}']).find_map(|field| {
+        let (k, v) = field.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().trim_matches(['"', '\'']))
+    })
+}
+
+/// Keys that say where an unversioned Cargo dependency comes from.
+const UNVERSIONED: [&str; 3] = ["workspace", "path", "git"];
+
+/// Requirement of a Cargo dependency value: `"1.0"`, `{ version = "1.0" }`,
+/// or where an unversioned one comes from.
+fn cargo_requirement(raw: &str) -> String {
+    let value = raw.trim();
+    if value.starts_with('"') || value.starts_with('\'') {
+        return value.trim_matches(['"', '\'']).to_owned();
+    }
+    if let Some(version) = inline_value(value, "version") {
+        return version.to_owned();
+    }
+    UNVERSIONED.iter().find(|key| inline_value(value, key).is_some()).map_or_else(String::new, |k| (*k).to_owned())
+}
+
+/// Whether a Cargo section header (without brackets) lists dependencies,
+/// and whether they are dev or build ones.
+fn cargo_section(header: &str) -> Option<bool> {
+    let table = header.strip_suffix("dependencies")?;
+    Some(table.ends_with("dev-") || table.ends_with("build-"))
+}
+
+/// Where the `Cargo.toml` reader is.
+#[derive(Clone, Copy)]
+enum CargoSection {
+    /// Somewhere without dependencies.
+    Other,
+    /// A dependency table; `true` for dev and build ones.
+    Deps(bool),
+    /// A `[dependencies.foo]` table, describing the last dependency.
+    Table,
+}
+
+/// The section a header opens; a `[dependencies.foo]` table adds `foo`.
+fn cargo_header(header: &str, deps: &mut Vec<Dependency>) -> CargoSection {
+    let table = header
+        .rsplit_once("dependencies.")
+        .and_then(|(before, name)| Some((cargo_section(&format!("{before}dependencies"))?, name)));
+    if let Some((dev, name)) = table {
+        deps.push(Dependency::new(name.trim_matches('"'), "", Ecosystem::Cargo, dev));
+        return CargoSection::Table;
+    }
+    cargo_section(header).map_or(CargoSection::Other, CargoSection::Deps)
+}
+
+/// One `name = ...` line of a dependency table.
+fn cargo_entry(key: &str, value: &str, dev: bool) -> Dependency {
+    let (name, requirement) = key
+        .strip_suffix(".workspace")
+        .map_or_else(|| (key, cargo_requirement(value)), |name| (name, "workspace".to_owned()));
+    Dependency::new(name.trim_matches('"'), &requirement, Ecosystem::Cargo, dev)
+}
+
+/// One `key = ...` line of a `[dependencies.foo]` table.
+fn cargo_table_field(dep: &mut Dependency, key: &str, value: &str) {
+    match key {
+        "version" => value.trim().trim_matches('"').clone_into(&mut dep.requirement),
+        k if dep.requirement.is_empty() && UNVERSIONED.contains(&k) => k.clone_into(&mut dep.requirement),
+        _ => {}
+    }
+}
+
+/// Direct dependencies of a `Cargo.toml`, workspace table included.
+fn parse_cargo(text: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut section = CargoSection::Other;
+    for raw in text.lines() {
+        let line = strip_comment(raw).trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = cargo_header(header, &mut deps);
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        match section {
+            CargoSection::Deps(dev) => deps.push(cargo_entry(key.trim(), value, dev)),
+            CargoSection::Table => {
+                if let Some(dep) = deps.last_mut() {
+                    cargo_table_field(dep, key.trim(), value);
+                }
+            }
+            CargoSection::Other => {}
+        }
+    }
+    deps
+}
+
+/// Direct dependencies of a `package.json`.
+fn parse_package_json(text: &str) -> Result<Vec<Dependency>, String> {
+    let json: serde_json::Value = serde_json::from_str(text).map_err(|e| format!("invalid JSON: {e}"))?;
+    let mut deps = Vec::new();
+    for (key, dev) in [("dependencies", false), ("devDependencies", true), ("optionalDependencies", true)] {
+        let Some(table) = json.get(key).and_then(serde_json::Value::as_object) else { continue };
+        for (name, requirement) in table {
+            deps.push(Dependency::new(name, requirement.as_str().unwrap_or(""), Ecosystem::Npm, dev));
+        }
+    }
+    Ok(deps)
+}
+
+/// Name and requirement of a PEP 508 string such as `requests[socks]>=2.31; python_version>"3.8"`.
+fn pep508(raw: &str) -> Option<(&str, &str)> {
+    let spec = raw.split(';').next().unwrap_or(raw).trim();
+    let end = spec.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))).unwrap_or(spec.len());
+    let (name, rest) = spec.split_at(end);
+    let requirement = rest.find(']').map_or(rest, |i| rest.get(i.saturating_add(1)..).unwrap_or("")).trim();
+    (!name.is_empty()).then_some((name, requirement))
+}
+
+/// The quoted strings on one line of a TOML array, and whether the line
+/// holds the array's closing `]`.
+fn array_line(line: &str) -> (Vec<&str>, bool) {
+    let mut items = Vec::new();
+    let mut open: Option<(char, usize)> = None;
+    let mut closed = false;
+    for (i, c) in line.char_indices() {
+        match open {
+            Some((quote, start)) if c == quote => {
+                items.push(line.get(start..i).unwrap_or(""));
+                open = None;
+            }
+            Some(_) => {}
+            None if c == '"' || c == '\'' => open = Some((c, i.saturating_add(1))),
+            None => closed |= c == ']',
+        }
+    }
+    (items, closed)
+}
+
+/// PEP 508 entries of one array line, and whether the array ends there.
+fn pep508_line(line: &str, dev: bool) -> (Vec<Dependency>, bool) {
+    let (items, closed) = array_line(line);
+    let deps = items.into_iter().filter_map(pep508).map(|(n, r)| Dependency::new(n, r, Ecosystem::PyPI, dev)).collect();
+    (deps, closed)
+}
+
+/// Where the `pyproject.toml` reader is.
+#[derive(Clone, Copy, Default)]
+enum PySection {
+    /// Somewhere without dependencies.
+    #[default]
+    Other,
+    /// `[project]`: the `dependencies = [...]` array.
+    Project,
+    /// `[project.optional-dependencies]`: arrays of extras.
+    Optional,
+    /// A Poetry dependency table; `true` for the dev groups.
+    Poetry(bool),
+}
+
+impl PySection {
+    /// The section a header (without brackets) opens.
+    fn of(header: &str) -> Self {
+        match header {
+            "project" => Self::Project,
+            "project.optional-dependencies" => Self::Optional,
+            "tool.poetry.dependencies" => Self::Poetry(false),
+            h if h.starts_with("tool.poetry.") && h.ends_with("dependencies") => Self::Poetry(true),
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Line-by-line reader of a `pyproject.toml`.
+#[derive(Default)]
+struct PyReader {
+    /// Current section.
+    section: PySection,
+    /// Inside a PEP 508 array spanning lines: whether its entries are optional.
+    array: Option<bool>,
+    /// Dependencies read so far.
+    deps: Vec<Dependency>,
+}
+
+impl PyReader {
+    /// Take one line (comment already stripped).
+    fn line(&mut self, line: &str) {
+        if let Some(dev) = self.array {
+            self.array_items(line, dev);
+        } else if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            self.section = PySection::of(header);
+        } else if let Some((key, value)) = line.split_once('=') {
+            self.entry(key.trim(), value.trim());
+        } else {
+            // Blank or continuation lines outside dependency arrays.
+        }
+    }
+
+    /// PEP 508 entries of an array line; the array stays open until its `]`.
+    fn array_items(&mut self, line: &str, dev: bool) {
+        let (found, closed) = pep508_line(line, dev);
+        self.deps.extend(found);
+        self.array = (!closed).then_some(dev);
+    }
+
+    /// A `key = value` line of the current section.
+    fn entry(&mut self, key: &str, value: &str) {
+        let dev = match self.section {
+            PySection::Project if key == "dependencies" => false,
+            PySection::Optional => true,
+            PySection::Poetry(dev) if key != "python" => {
+                self.deps.push(Dependency::new(key, &cargo_requirement(value), Ecosystem::PyPI, dev));
+                return;
+            }
+            PySection::Project | PySection::Poetry(_) | PySection::Other => return,
+        };
+        if let Some(items) = value.strip_prefix('[') {
+            self.array_items(items, dev);
+        }
+    }
+}
+
+/// Direct dependencies of a `pyproject.toml` (PEP 621 or Poetry).
+fn parse_pyproject(text: &str) -> Vec<Dependency> {
+    let mut reader = PyReader::default();
+    for raw in text.lines() {
+        reader.line(strip_comment(raw).trim());
+    }
+    reader.deps
+}
+
+/// Versions resolved by a `Cargo.lock` (name to version; the first one
+/// listed when several are locked).
+pub(crate) fn cargo_lock(text: &str) -> BTreeMap<String, String> {
+    let mut versions = BTreeMap::new();
+    let mut name: Option<&str> = None;
+    for line in text.lines().map(str::trim) {
+        if line == "[[package]]" {
+            name = None;
+        } else if let Some(n) = line.strip_prefix("name = ") {
+            name = Some(n.trim_matches('"'));
+        } else if let Some(v) = line.strip_prefix("version = ")
+            && let Some(n) = name.take()
+        {
+            let _existing = versions.entry(n.to_owned()).or_insert_with(|| v.trim_matches('"').to_owned());
+        } else {
+            // Other keys (source, checksum, dependencies) are not needed.
+        }
+    }
+    versions
+}
+
+/// Versions resolved by a `package-lock.json` (top-level `node_modules` only).
+pub(crate) fn package_lock(text: &str) -> BTreeMap<String, String> {
+    let json: serde_json::Value = serde_json::from_str(text).unwrap_or_default();
+    let Some(packages) = json.get("packages").and_then(serde_json::Value::as_object) else {
+        return BTreeMap::new();
+    };
+    packages
+        .iter()
+        .filter_map(|(path, entry)| {
+            let name = path.strip_prefix("node_modules/").filter(|n| !n.contains("/node_modules/"))?;
+            Some((name.to_owned(), entry.get("version")?.as_str()?.to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(name, requirement, dev)` of each dependency.
+    fn summary(deps: &[Dependency]) -> Vec<(&str, &str, bool)> {
+        deps.iter().map(|d| (d.name.as_str(), d.requirement.as_str(), d.dev)).collect()
+    }
+
+    #[test]
+    fn cargo_tables_and_inline_forms() {
+        let text = "[package]\nname = \"x\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] } # ser\n\
+                    log = \"0.4\"\ncp-base.workspace = true\nlocal = { path = \"../local\" }\n\n\
+                    [target.'cfg(unix)'.dependencies]\nlibc = \"0.2\"\n\n[dev-dependencies]\ntempfile = \"3\"\n\n\
+                    [dependencies.tokio]\nfeatures = [\"full\"]\nversion = \"1.38\"\n";
+        assert_eq!(
+            summary(&parse_cargo(text)),
+            vec![
+                ("serde", "1.0", false),
+                ("log", "0.4", false),
+                ("cp-base", "workspace", false),
+                ("local", "path", false),
+                ("libc", "0.2", false),
+                ("tempfile", "3", true),
+                ("tokio", "1.38", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn pyproject_and_package_json() {
+        let text = "[project]\nname = \"app\"\ndependencies = [\n  \"requests[socks]>=2.31\",\n  \"numpy==1.26; python_version>'3.9'\",\n]\n\n\
+                    [project.optional-dependencies]\ntest = [\"pytest>=8\"]\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\nrich = \"^13.7\"\n";
+        assert_eq!(
+            summary(&parse_pyproject(text)),
+            vec![
+                ("requests", ">=2.31", false),
+                ("numpy", "==1.26", false),
+                ("pytest", ">=8", true),
+                ("rich", "^13.7", false)
+            ]
+        );
+        let json = r#"{"dependencies": {"react": "^18.2.0"}, "devDependencies": {"vitest": "1.6.0"}}"#;
+        assert_eq!(
+            parse_package_json(json).map(|d| summary(&d).into_iter().map(|(n, _, dev)| (n.to_owned(), dev)).collect()),
+            Ok(vec![("react".to_owned(), false), ("vitest".to_owned(), true)])
+        );
+        let lock = "[[package]]\nname = \"log\"\nversion = \"0.4.22\"\nsource = \"registry\"\n";
+        assert_eq!(cargo_lock(lock).get("log").map(String::as_str), Some("0.4.22"));
+    }
+}
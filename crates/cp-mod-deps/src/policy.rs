@@ -0,0 +1,216 @@
+//! The license policy `license_check` enforces, from
+//! `.context-pilot/license_policy.yaml`:
+//!
+//! ```yaml
+//! header:
+//!   text: |                       # every line must appear near the top
+//!     Copyright (c) Acme Corp.
+//!     SPDX-License-Identifier: Apache-2.0
+//!   lines: 20                     # how near (default 20)
+//!   include: ["**/*.rs", "**/*.py"]  # default: common source files
+//!   exclude: ["vendor/**"]
+//! dependencies:
+//!   allow: [MIT, Apache-2.0, BSD-3-Clause, ISC, Unicode-3.0]
+//!   deny: [GPL-3.0-only, AGPL-3.0-only]
+//!   allow_unknown: false          # packages declaring no license
+//! ```
+
+use std::path::Path;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use cp_base::config::constants::STORE_DIR;
+
+/// File name of the policy, under the store dir.
+pub(crate) const POLICY_FILE: &str = "license_policy.yaml";
+
+/// Source files checked for the header when the policy names none.
+const DEFAULT_INCLUDE: &[&str] = &[
+    "**/*.rs",
+    "**/*.py",
+    "**/*.js",
+    "**/*.jsx",
+    "**/*.ts",
+    "**/*.tsx",
+    "**/*.go",
+    "**/*.java",
+    "**/*.c",
+    "**/*.h",
+    "**/*.cpp",
+    "**/*.hpp",
+];
+
+/// The whole policy; either part may be left out.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Policy {
+    /// Required file header.
+    #[serde(default)]
+    pub header: Option<HeaderRule>,
+    /// Dependency license rules.
+    #[serde(default)]
+    pub dependencies: Option<LicenseRule>,
+}
+
+/// The header new and modified source files must carry.
+#[derive(Debug, Deserialize)]
+pub(crate) struct HeaderRule {
+    /// Required text; each non-blank line must appear, whatever the comment syntax.
+    pub text: String,
+    /// Lines from the top the header must sit within.
+    #[serde(default = "default_header_lines")]
+    pub lines: usize,
+    /// Globs of files checked.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Globs of files skipped.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+/// Default for [`HeaderRule::lines`].
+const fn default_header_lines() -> usize {
+    20
+}
+
+/// Which dependency licenses are acceptable.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct LicenseRule {
+    /// SPDX ids accepted; any id not denied when empty.
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// SPDX ids refused.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Accept packages that declare no license.
+    #[serde(default)]
+    pub allow_unknown: bool,
+}
+
+/// The policy of the project at `root`.
+pub(crate) fn load(root: &Path) -> Result<Policy, String> {
+    let path = root.join(STORE_DIR).join(POLICY_FILE);
+    let text = std::fs::read_to_string(&path).map_err(|_e| {
+        format!("no license policy at {}; see the license_check tool description for its format", path.display())
+    })?;
+    serde_yaml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// A glob set from `patterns`, naming the first invalid one.
+fn glob_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let _b = builder.add(Glob::new(pattern).map_err(|e| format!("invalid glob '{pattern}': {e}"))?);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Header rule compiled for matching.
+pub(crate) struct HeaderCheck<'rule> {
+    /// The rule.
+    rule: &'rule HeaderRule,
+    /// Files checked.
+    include: GlobSet,
+    /// Files skipped.
+    exclude: GlobSet,
+}
+
+impl<'rule> HeaderCheck<'rule> {
+    /// Compile `rule`'s globs.
+    pub(crate) fn new(rule: &'rule HeaderRule) -> Result<Self, String> {
+        let include = if rule.include.is_empty() {
+            glob_set(&DEFAULT_INCLUDE.iter().map(|g| (*g).to_owned()).collect::<Vec<_>>())?
+        } else {
+            glob_set(&rule.include)?
+        };
+        Ok(Self { rule, include, exclude: glob_set(&rule.exclude)? })
+    }
+
+    /// Whether the file at `path` (relative to the project) is checked.
+    pub(crate) fn applies(&self, path: &str) -> bool {
+        self.include.is_match(path) && !self.exclude.is_match(path)
+    }
+
+    /// Lines of the required header missing from the top of `content`.
+    pub(crate) fn missing<'text>(&'text self, content: &str) -> Vec<&'text str> {
+        let top: Vec<&str> = content.lines().take(self.rule.lines).collect();
+        self.rule
+            .text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .filter(|l| !top.iter().any(|t| t.contains(l)))
+            .collect()
+    }
+}
+
+impl LicenseRule {
+    /// Whether a single SPDX id is acceptable.
+    fn accepts_id(&self, id: &str) -> bool {
+        let same = |other: &String| other.eq_ignore_ascii_case(id);
+        !self.deny.iter().any(same) && (self.allow.is_empty() || self.allow.iter().any(same))
+    }
+
+    /// Why a declared license expression breaks the policy, if it does.
+    /// `A OR B` (or the legacy `A/B`) needs one acceptable side, `A AND B`
+    /// both; parentheses are flattened, which is exact for the usual
+    /// `(MIT OR Apache-2.0) AND Unicode-3.0` shapes.
+    pub(crate) fn violation(&self, expression: Option<&str>) -> Option<String> {
+        let Some(expr) = expression.map(str::trim).filter(|e| !e.is_empty()) else {
+            return (!self.allow_unknown).then(|| "no license declared".to_owned());
+        };
+        let flat = expr.replace(['(', ')'], " ").replace('/', " OR ");
+        let clauses: Vec<Vec<&str>> = flat
+            .split(" AND ")
+            .map(|clause| {
+                clause.split(" OR ").map(|id| id.trim().trim_end_matches('+')).filter(|id| !id.is_empty()).collect()
+            })
+            .collect();
+        let failing: Vec<String> = clauses
+            .iter()
+            .filter(|options| !options.iter().any(|id| self.accepts_id(id)))
+            .map(|options| options.join(" OR "))
+            .collect();
+        (!failing.is_empty()).then(|| format!("not allowed: {}", failing.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn license_expressions_follow_the_policy() {
+        let rule = LicenseRule {
+            allow: vec!["MIT".to_owned(), "Apache-2.0".to_owned(), "Unicode-3.0".to_owned()],
+            deny: vec!["GPL-3.0-only".to_owned()],
+            allow_unknown: false,
+        };
+        assert_eq!(rule.violation(Some("MIT OR Apache-2.0")), None);
+        assert_eq!(rule.violation(Some("MIT/Apache-2.0")), None);
+        assert_eq!(rule.violation(Some("(MIT OR Apache-2.0) AND Unicode-3.0")), None);
+        assert_eq!(rule.violation(Some("GPL-3.0-only")), Some("not allowed: GPL-3.0-only".to_owned()));
+        assert_eq!(rule.violation(Some("MIT AND BSL-1.0")), Some("not allowed: BSL-1.0".to_owned()));
+        assert_eq!(rule.violation(None), Some("no license declared".to_owned()));
+    }
+
+    #[test]
+    fn headers_are_found_near_the_top() {
+        let policy: Policy = serde_yaml::from_str(
+            "header:\n  text: |\n    Copyright Acme\n    SPDX-License-Identifier: MIT\n  lines: 3\n  exclude: [\"vendor/**\"]\n",
+        )
+        .unwrap_or_default();
+        let check = policy.header.as_ref().and_then(|rule| HeaderCheck::new(rule).ok());
+        assert!(
+            check
+                .as_ref()
+                .is_some_and(|c| c.applies("src/main.rs") && !c.applies("vendor/x.rs") && !c.applies("README.md"))
+        );
+        let missing = |content: &str| check.as_ref().map(|c| c.missing(content).join(", "));
+        assert_eq!(missing("// Copyright Acme\n// SPDX-License-Identifier: MIT\nfn main() {}"), Some(String::new()));
+        assert_eq!(
+            missing("# Copyright Acme\n\n\n# SPDX-License-Identifier: MIT\n"),
+            Some("SPDX-License-Identifier: MIT".to_owned())
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use cp_base::state::runtime::State;
+use cp_base::state::watchers::DYN_PANEL_ID_PLACEHOLDER;
+use cp_base::state::watchers::carriers::DynPanel;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::advisory::{self, Finding};
+use crate::external::{self, Outdated};
+use crate::manifest::{self, Dependency, Ecosystem, MANIFESTS};
+use crate::panel::{DEPS_PANEL_TYPE, META_CONTENT};
+
+/// Max seconds for the whole audit (checkers run one after another).
+const AUDIT_TIMEOUT_SECS: u64 = 400;
+
+/// Directory levels searched for manifests below the audited one.
+const MAX_DEPTH: usize = 4;
+
+/// A manifest and what it declares.
+struct Manifest {
+    /// Path relative to the audited directory.
+    path: String,
+    /// Declared dependencies.
+    deps: Vec<Dependency>,
+}
+
+/// Everything the audit found.
+#[derive(Default)]
+struct Audit {
+    /// Manifests, in path order.
+    manifests: Vec<Manifest>,
+    /// Versions resolved by lockfiles.
+    locked: HashMap<(Ecosystem, String), String>,
+    /// Dependencies hit by advisories.
+    vulnerable: Vec<Finding>,
+    /// Dependencies with newer releases.
+    outdated: Vec<Outdated>,
+    /// Checks that could not run, and other caveats.
+    notes: Vec<String>,
+    /// Directories holding a `Cargo.lock`.
+    cargo_roots: Vec<PathBuf>,
+    /// Directories with installed Node packages.
+    npm_roots: Vec<PathBuf>,
+}
+
+impl Audit {
+    /// The version of `dep` in use: locked, else the one its requirement names.
+    fn version_of<'dep>(&'dep self, dep: &'dep Dependency) -> Option<&'dep str> {
+        self.locked
+            .get(&(dep.ecosystem, dep.name.clone()))
+            .map(String::as_str)
+            .or_else(|| advisory::version_in(&dep.requirement))
+    }
+
+    /// Whether a package is declared directly in some manifest.
+    fn is_direct(&self, package: &str) -> bool {
+        self.manifests.iter().flat_map(|m| &m.deps).any(|d| d.name == package)
+    }
+
+    /// Read one manifest or lockfile found by the walk.
+    fn read_file(&mut self, root: &Path, path: &Path) {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let Ok(text) = std::fs::read_to_string(path) else { return };
+        let dir = path.parent().unwrap_or(root).to_path_buf();
+        let (ecosystem, versions) = match name {
+            "Cargo.lock" => {
+                self.cargo_roots.push(dir);
+                (Ecosystem::Cargo, manifest::cargo_lock(&text))
+            }
+            "package-lock.json" => (Ecosystem::Npm, manifest::package_lock(&text)),
+            _ => {
+                let relative = path.strip_prefix(root).unwrap_or(path).display().to_string();
+                match manifest::parse(name, &text) {
+                    Ok(deps) => self.manifests.push(Manifest { path: relative, deps }),
+                    Err(e) => self.notes.push(format!("{relative}: {e}")),
+                }
+                if name == "package.json" && dir.join("node_modules").is_dir() {
+                    self.npm_roots.push(dir);
+                }
+                return;
+            }
+        };
+        for (package, version) in versions {
+            let _existing = self.locked.entry((ecosystem, package)).or_insert(version);
+        }
+    }
+}
+
+/// Find and read the manifests and lockfiles under `root`.
+fn discover(root: &Path) -> Audit {
+    let mut audit = Audit::default();
+    let mut paths: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .max_depth(Some(MAX_DEPTH))
+        .filter_entry(|e| e.file_name() != "node_modules" && e.file_name() != "target")
+        .build()
+        .filter_map(Result::ok)
+        .map(ignore::DirEntry::into_path)
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| MANIFESTS.contains(&n) || n == "Cargo.lock" || n == "package-lock.json")
+        })
+        .collect();
+    paths.sort();
+    for path in &paths {
+        audit.read_file(root, path);
+    }
+    audit
+}
+
+/// Run the advisory checks: the local database, then `cargo audit`.
+fn check_advisories(root: &Path, audit: &mut Audit) {
+    match advisory::load_local(root) {
+        Some(Ok(advisories)) => {
+            let deps = audit.manifests.iter().flat_map(|m| &m.deps).map(|d| (d, audit.version_of(d)));
+            let found = advisory::match_local(&advisories, deps);
+            audit.vulnerable.extend(found);
+        }
+        Some(Err(e)) => audit.notes.push(e),
+        None => audit.notes.push("no local advisory database (.context-pilot/advisories.yaml)".to_owned()),
+    }
+    for dir in audit.cargo_roots.clone() {
+        match external::cargo_audit(&dir) {
+            Ok(found) => {
+                for finding in found {
+                    if !audit.vulnerable.contains(&finding) {
+                        audit.vulnerable.push(finding);
+                    }
+                }
+            }
+            Err(e) => audit.notes.push(e),
+        }
+    }
+}
+
+/// Run the outdated checks for Cargo and npm.
+fn check_outdated(audit: &mut Audit) {
+    let runs = audit
+        .cargo_roots
+        .iter()
+        .map(|d| external::cargo_outdated(d))
+        .chain(audit.npm_roots.iter().map(|d| external::npm_outdated(d)))
+        .collect::<Vec<_>>();
+    for result in runs {
+        match result {
+            Ok(found) => audit.outdated.extend(found),
+            Err(e) => audit.notes.push(e),
+        }
+    }
+    if audit.manifests.iter().flat_map(|m| &m.deps).any(|d| d.ecosystem == Ecosystem::PyPI) {
+        audit
+            .notes
+            .push("PyPI: not checked for newer releases; run `pip list --outdated` in the environment".to_owned());
+    }
+}
+
+/// The headline: counts of dependencies, vulnerable packages and outdated ones.
+fn summary(audit: &Audit) -> String {
+    let total: usize = audit.manifests.iter().map(|m| m.deps.len()).sum();
+    let mut vulnerable: Vec<&str> = audit.vulnerable.iter().map(|f| f.package.as_str()).collect();
+    vulnerable.sort_unstable();
+    vulnerable.dedup();
+    format!(
+        "{total} direct dependencies in {} manifest(s): {} vulnerable, {} outdated",
+        audit.manifests.len(),
+        vulnerable.len(),
+        audit.outdated.len()
+    )
+}
+
+/// The `## Vulnerable` and `## Outdated` sections, when there is anything to list.
+fn findings_section(out: &mut String, audit: &Audit) {
+    if !audit.vulnerable.is_empty() {
+        out.push_str("\n## Vulnerable\n");
+    }
+    for f in &audit.vulnerable {
+        let fix = f.fixed.as_ref().map_or_else(|| "no fixed release".to_owned(), |v| format!("fixed in {v}"));
+        let scope = if audit.is_direct(&f.package) { "" } else { ", transitive" };
+        let _r = writeln!(out, "- {} {}: {} {}; {fix} [{}{scope}]", f.package, f.version, f.id, f.title, f.source);
+    }
+    if !audit.outdated.is_empty() {
+        out.push_str("\n## Outdated\n");
+    }
+    for o in &audit.outdated {
+        let _r = writeln!(out, "- {} {} -> {} ({})", o.package, o.current, o.latest, o.ecosystem.label());
+    }
+}
+
+/// The report shown in the panel.
+fn report(root: &str, audit: &Audit) -> String {
+    let mut out = format!("Dependency audit of {root}\n{}\n", summary(audit));
+    findings_section(&mut out, audit);
+    out.push_str("\n## Dependencies\n");
+    for m in &audit.manifests {
+        let _r = writeln!(out, "{}", m.path);
+        for d in &m.deps {
+            let locked =
+                audit.locked.get(&(d.ecosystem, d.name.clone())).map(|v| format!(" = {v}")).unwrap_or_default();
+            let dev = if d.dev { " (dev)" } else { "" };
+            let _w = writeln!(out, "  {} {}{locked}{dev}", d.name, d.requirement);
+        }
+    }
+    if !audit.notes.is_empty() {
+        out.push_str("\n## Notes\n");
+    }
+    for note in &audit.notes {
+        let _r = writeln!(out, "- {note}");
+    }
+    out
+}
+
+/// Audit `root` and put the report in a panel.
+fn run_audit(root: &Path, label: &str) -> ToolOutput {
+    let mut audit = discover(root);
+    if audit.manifests.is_empty() {
+        return ToolOutput::error(format!("No {} found under {label}", MANIFESTS.join(", ")));
+    }
+    check_advisories(root, &mut audit);
+    check_outdated(&mut audit);
+    let content = report(label, &audit);
+    let panel = DynPanel::new(DEPS_PANEL_TYPE.to_owned(), format!("deps: {label}"))
+        .metadata(vec![(META_CONTENT.to_owned(), content.clone())])
+        .content(content);
+    ToolOutput::ok(format!("Created panel {DYN_PANEL_ID_PLACEHOLDER}: {}", summary(&audit))).with_panel(panel)
+}
+
+/// Execute `deps_audit` off the main loop.
+pub(crate) fn execute_audit(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let label = tool.input.get("path").and_then(|v| v.as_str()).unwrap_or(".").trim().to_owned();
+    let root = PathBuf::from(if label.is_empty() { "." } else { &label });
+    if !root.is_dir() {
+        return ToolResult::new(tool.id.clone(), format!("Error: '{label}' is not a directory"), true);
+    }
+    spawn_async_tool(state, tool, AUDIT_TIMEOUT_SECS, move || run_audit(&root, &label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_flags_transitive_advisories() {
+        let dep = Dependency {
+            name: "log".to_owned(),
+            requirement: "0.4".to_owned(),
+            ecosystem: Ecosystem::Cargo,
+            dev: false,
+        };
+        let finding = |package: &str| Finding {
+            package: package.to_owned(),
+            version: "0.9.6".to_owned(),
+            id: "RUSTSEC-1".to_owned(),
+            title: "bad".to_owned(),
+            fixed: None,
+            source: "cargo audit",
+        };
+        let mut audit = Audit {
+            manifests: vec![Manifest { path: "Cargo.toml".to_owned(), deps: vec![dep] }],
+            vulnerable: vec![finding("rsa"), finding("log")],
+            ..Audit::default()
+        };
+        let _r = audit.locked.insert((Ecosystem::Cargo, "log".to_owned()), "0.4.22".to_owned());
+        let text = report(".", &audit);
+        assert!(text.contains("1 direct dependencies in 1 manifest(s): 2 vulnerable, 0 outdated"));
+        assert!(text.contains("- rsa 0.9.6: RUSTSEC-1 bad; no fixed release [cargo audit, transitive]"));
+        assert!(text.contains("- log 0.9.6: RUSTSEC-1 bad; no fixed release [cargo audit]"));
+        assert!(text.contains("  log 0.4 = 0.4.22\n"));
+    }
+}
@@ -302,11 +302,7 @@ fn visualize_entity_output(content: &str, width: usize) -> Vec<cp_render::Block>
             }
 
             // Truncate long lines
-            let display = if line.len() > width {
-                format!("{}…", line.get(..line.floor_char_boundary(width.saturating_sub(1))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "\u{2026}");
 
             // Error lines
             if display.starts_with("SQL error:") || display.starts_with("FAILED") {
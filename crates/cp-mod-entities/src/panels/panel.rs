@@ -1,7 +1,8 @@
 //! Fixed Entities panel — live schema, sample data, and empty-state guide.
 
-use cp_base::panels::{ContextItem, Panel};
+use cp_base::panels::Panel;
 use cp_base::state::context::Kind;
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 use cp_render::{Block, Semantic, Span};
 
@@ -4,10 +4,9 @@
 //! Live panels store the SQL query in metadata and re-execute it every 2 seconds
 //! via the background cache thread.
 
-use cp_base::panels::{
-    CacheRequest, CacheUpdate, ContextItem, Panel, hash_content, paginate_content, update_if_changed,
-};
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, hash_content, paginate_content, update_if_changed};
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens, make_default_entry};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 use cp_render::{Block, Semantic, Span};
 
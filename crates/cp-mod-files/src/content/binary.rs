@@ -0,0 +1,205 @@
+//! Binary file detection: content sniffing, file type from magic bytes, and
+//! the bounded hex dump a file panel shows instead of text.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Read as _;
+use std::path::Path;
+
+/// Bytes sniffed to tell text from binary content.
+const SNIFF_BYTES: u64 = 8 * 1024;
+
+/// Bytes shown by a hex dump panel.
+pub(crate) const HEX_DUMP_BYTES: u64 = 4 * 1024;
+
+/// Bytes per hex dump line.
+const ROW_BYTES: usize = 16;
+
+/// Column after which a hex dump line has a wider gap.
+const HALF_ROW: usize = 7;
+
+/// What to use instead of `Open` for an image or PDF.
+const OCR_ADVICE: &str = "use the ocr tool to extract its text";
+
+/// What to use instead of `Open` for an archive.
+const ARCHIVE_ADVICE: &str = "list its entries from a console (unzip -l, tar -tvf)";
+
+/// What to use instead of `Open` for compiled code.
+const EXECUTABLE_ADVICE: &str = "inspect it from a console (file, objdump -h, strings)";
+
+/// What to use instead of `Open` for audio and video.
+const MEDIA_ADVICE: &str = "inspect it from a console (ffprobe, file)";
+
+/// What to use instead of `Open` for anything unrecognised.
+const GENERIC_ADVICE: &str = "inspect it from a console (file, strings) or with py_exec";
+
+/// A file signature: magic bytes at an offset.
+struct Signature {
+    /// Offset of the magic bytes.
+    offset: usize,
+    /// The magic bytes.
+    magic: &'static [u8],
+    /// File type shown to the user.
+    name: &'static str,
+    /// Tools recommended instead of `Open`.
+    advice: &'static str,
+}
+
+/// Known signatures, most specific first.
+const SIGNATURES: &[Signature] = &[
+    Signature { offset: 0, magic: b"\x89PNG\r\n\x1a\n", name: "PNG image", advice: OCR_ADVICE },
+    Signature { offset: 0, magic: b"\xff\xd8\xff", name: "JPEG image", advice: OCR_ADVICE },
+    Signature { offset: 0, magic: b"GIF8", name: "GIF image", advice: OCR_ADVICE },
+    Signature { offset: 8, magic: b"WEBP", name: "WebP image", advice: OCR_ADVICE },
+    Signature { offset: 0, magic: b"II*\0", name: "TIFF image", advice: OCR_ADVICE },
+    Signature { offset: 0, magic: b"MM\0*", name: "TIFF image", advice: OCR_ADVICE },
+    Signature { offset: 0, magic: b"BM", name: "BMP image", advice: OCR_ADVICE },
+    Signature { offset: 0, magic: b"\0\0\x01\0", name: "ICO icon", advice: GENERIC_ADVICE },
+    Signature { offset: 0, magic: b"%PDF-", name: "PDF document", advice: OCR_ADVICE },
+    Signature {
+        offset: 0,
+        magic: b"SQLite format 3\0",
+        name: "SQLite database",
+        advice: "connect with db_connect (sqlite://<path>), then use db_schema and db_query",
+    },
+    Signature {
+        offset: 0,
+        magic: b"PAR1",
+        name: "Parquet table",
+        advice: "preview it with table_head, or profile it with table_stats",
+    },
+    Signature { offset: 0, magic: b"PK\x03\x04", name: "ZIP archive (or docx/xlsx/jar)", advice: ARCHIVE_ADVICE },
+    Signature { offset: 0, magic: b"\x1f\x8b", name: "gzip archive", advice: ARCHIVE_ADVICE },
+    Signature { offset: 0, magic: b"BZh", name: "bzip2 archive", advice: ARCHIVE_ADVICE },
+    Signature { offset: 0, magic: b"\xfd7zXZ\0", name: "xz archive", advice: ARCHIVE_ADVICE },
+    Signature { offset: 0, magic: b"\x28\xb5\x2f\xfd", name: "zstd archive", advice: ARCHIVE_ADVICE },
+    Signature { offset: 0, magic: b"7z\xbc\xaf\x27\x1c", name: "7-Zip archive", advice: ARCHIVE_ADVICE },
+    Signature { offset: 257, magic: b"ustar", name: "tar archive", advice: ARCHIVE_ADVICE },
+    Signature { offset: 0, magic: b"\x7fELF", name: "ELF executable", advice: EXECUTABLE_ADVICE },
+    Signature { offset: 0, magic: b"\xcf\xfa\xed\xfe", name: "Mach-O executable", advice: EXECUTABLE_ADVICE },
+    Signature { offset: 0, magic: b"\xce\xfa\xed\xfe", name: "Mach-O executable", advice: EXECUTABLE_ADVICE },
+    Signature {
+        offset: 0,
+        magic: b"\xca\xfe\xba\xbe",
+        name: "Mach-O universal binary or Java class",
+        advice: EXECUTABLE_ADVICE,
+    },
+    Signature { offset: 0, magic: b"MZ", name: "Windows executable", advice: EXECUTABLE_ADVICE },
+    Signature { offset: 0, magic: b"\0asm", name: "WebAssembly module", advice: EXECUTABLE_ADVICE },
+    Signature { offset: 0, magic: b"ID3", name: "MP3 audio", advice: MEDIA_ADVICE },
+    Signature { offset: 0, magic: b"OggS", name: "Ogg media", advice: MEDIA_ADVICE },
+    Signature { offset: 0, magic: b"fLaC", name: "FLAC audio", advice: MEDIA_ADVICE },
+    Signature { offset: 4, magic: b"ftyp", name: "MP4/QuickTime media", advice: MEDIA_ADVICE },
+    Signature { offset: 0, magic: b"RIFF", name: "RIFF media (WAV/AVI)", advice: MEDIA_ADVICE },
+];
+
+/// A file found to hold binary content.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Binary {
+    /// Size on disk, in bytes.
+    pub size: u64,
+    /// File type, from its magic bytes.
+    pub kind: &'static str,
+    /// Tools recommended instead of `Open`.
+    pub advice: &'static str,
+}
+
+impl Binary {
+    /// Error returned when `Open` is asked for this file as text.
+    pub(crate) fn open_error(&self, path: &str) -> String {
+        format!(
+            "Error: '{path}' is a binary file ({}, {} bytes) and cannot be opened as text. To work with it, {}. \
+             Open it with hex: true for a hex dump of its first {HEX_DUMP_BYTES} bytes.",
+            self.kind, self.size, self.advice
+        )
+    }
+
+    /// Panel text for a file that turned binary after it was opened.
+    pub(crate) fn notice(&self) -> String {
+        format!(
+            "[Binary file: {}, {} bytes. Close this panel and open it again with hex: true for a hex dump.]",
+            self.kind, self.size
+        )
+    }
+}
+
+/// The first `limit` bytes of `path`.
+fn read_head(path: &Path, limit: u64) -> Option<Vec<u8>> {
+    let file = File::open(path).ok()?;
+    let mut bytes = Vec::new();
+    let _read = file.take(limit).read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+/// Whether `head`, the start of a file, is binary: no text encoding fits it.
+fn is_binary(head: &[u8]) -> bool {
+    super::encoding::sniff(head).is_none()
+}
+
+/// The signature `head` starts with.
+fn signature(head: &[u8]) -> Option<&'static Signature> {
+    SIGNATURES.iter().find(|s| head.get(s.offset..).is_some_and(|rest| rest.starts_with(s.magic)))
+}
+
+/// `path` as a binary file, or `None` when it reads as text (or not at all).
+pub(crate) fn detect(path: &Path) -> Option<Binary> {
+    let head = read_head(path, SNIFF_BYTES)?;
+    if !is_binary(&head) {
+        return None;
+    }
+    let size = std::fs::metadata(path).map_or(0, |m| m.len());
+    let (kind, advice) = signature(&head).map_or(("unknown binary data", GENERIC_ADVICE), |s| (s.name, s.advice));
+    Some(Binary { size, kind, advice })
+}
+
+/// Classic hex dump of `bytes`: offset, sixteen hex bytes, printable ASCII.
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(ROW_BYTES).enumerate() {
+        let mut hex = String::new();
+        for (col, byte) in chunk.iter().enumerate() {
+            let gap = if col == HALF_ROW { "  " } else { " " };
+            let _r = write!(hex, "{byte:02x}{gap}");
+        }
+        let ascii: String =
+            chunk.iter().map(|b| if b.is_ascii_graphic() || *b == b' ' { char::from(*b) } else { '.' }).collect();
+        let _r = writeln!(out, "{:08x}  {hex:<50}|{ascii}|", row.saturating_mul(ROW_BYTES));
+    }
+    out
+}
+
+/// Panel text of a hex dump of `path`: type and size, then its first
+/// [`HEX_DUMP_BYTES`] bytes.
+pub(crate) fn hex_view(path: &Path) -> Option<String> {
+    let head = read_head(path, HEX_DUMP_BYTES)?;
+    let size = std::fs::metadata(path).map_or(0, |m| m.len());
+    let kind =
+        signature(&head).map_or_else(|| if is_binary(&head) { "unknown binary data" } else { "text" }, |s| s.name);
+    let shown = u64::try_from(head.len()).unwrap_or(HEX_DUMP_BYTES);
+    let mut out = format!("[{kind}, {size} bytes; hex dump of the first {shown} bytes]\n");
+    out.push_str(&hex_dump(&head));
+    let rest = size.saturating_sub(shown);
+    if rest > 0 {
+        let _r = writeln!(out, "[... {rest} more bytes not shown]");
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_png_and_dumps_hex() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR-";
+        assert!(is_binary(png));
+        assert!(!is_binary("caf\u{e9}".as_bytes().get(..4).unwrap_or_default()));
+        assert!(!is_binary(b"caf\xe9 latin-1"));
+        assert_eq!(signature(png).map(|s| s.name), Some("PNG image"));
+        let expected = concat!(
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n",
+            "00000010  2d                                                |-|\n",
+        );
+        assert_eq!(hex_dump(png), expected);
+    }
+}
@@ -0,0 +1,143 @@
+//! Text encodings: files are decoded to UTF-8 on read, so Latin-1 and UTF-16
+//! files open as text, and re-encoded on write, so edits keep a file's
+//! encoding, byte order mark and CRLF line endings.
+//!
+//! Detection is a byte order mark, then the NUL pattern of UTF-16, then
+//! UTF-8 validity; any other text is read as windows-1252, which maps every
+//! byte and so writes back unchanged whatever the file really was.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+/// Control bytes allowed in text besides tab, newlines, form feed and ESC
+/// (ANSI colours in logs) mark a file as binary.
+const TEXT_CONTROLS: &[u8] = b"\t\n\r\x0c\x1b";
+
+/// How a text file is stored on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Format {
+    /// Character encoding.
+    pub encoding: &'static Encoding,
+    /// The file starts with a byte order mark.
+    pub bom: bool,
+    /// Lines end in CRLF (they are LF in the decoded text).
+    pub crlf: bool,
+}
+
+impl Format {
+    /// UTF-8 with LF line endings: the format of new files.
+    pub(crate) const UTF8: Self = Self { encoding: UTF_8, bom: false, crlf: false };
+}
+
+/// UTF-16 without a byte order mark: ASCII-range text has a NUL in every
+/// other byte.
+fn utf16_without_bom(sample: &[u8]) -> Option<&'static Encoding> {
+    let pairs: Vec<&[u8]> = sample.chunks_exact(2).collect();
+    if pairs.len() < 2 {
+        return None;
+    }
+    let zeros = |at: usize| pairs.iter().filter(|pair| pair.get(at) == Some(&0)).count();
+    let (even, odd) = (zeros(0), zeros(1));
+    let mostly = |count: usize| count.saturating_mul(2) >= pairs.len();
+    if even == 0 && mostly(odd) {
+        Some(UTF_16LE)
+    } else if odd == 0 && mostly(even) {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Encoding of `sample`, the start of a file or all of it, and whether it
+/// has a byte order mark; `None` when it is binary.
+pub(crate) fn sniff(sample: &[u8]) -> Option<(&'static Encoding, bool)> {
+    if let Some((encoding, _bom_len)) = Encoding::for_bom(sample) {
+        return Some((encoding, true));
+    }
+    if let Some(encoding) = utf16_without_bom(sample) {
+        return Some((encoding, false));
+    }
+    let control = |b: &u8| *b < 0x20 && !TEXT_CONTROLS.contains(b);
+    if sample.iter().any(control) {
+        return None;
+    }
+    // A character cut at the end of a sample is still UTF-8.
+    let utf8 = std::str::from_utf8(sample).map_or_else(|e| e.error_len().is_none(), |_text| true);
+    Some((if utf8 { UTF_8 } else { WINDOWS_1252 }, false))
+}
+
+/// Decode a whole file; `None` when it is binary.
+pub(crate) fn decode(bytes: &[u8]) -> Option<(String, Format)> {
+    let (encoding, bom) = sniff(bytes)?;
+    let (text, _malformed) = encoding.decode_with_bom_removal(bytes);
+    let newlines = text.matches('\n').count();
+    let crlf = newlines > 0 && text.matches("\r\n").count().saturating_mul(2) > newlines;
+    let decoded = if crlf { text.replace("\r\n", "\n") } else { text.into_owned() };
+    Some((decoded, Format { encoding, bom, crlf }))
+}
+
+/// Encode `text` in `format`. Fails when a character has no byte form in a
+/// legacy encoding, rather than writing a substitute.
+pub(crate) fn encode(text: &str, format: Format) -> Result<Vec<u8>, String> {
+    let lines: Cow<'_, str> =
+        if format.crlf { Cow::Owned(text.replace("\r\n", "\n").replace('\n', "\r\n")) } else { Cow::Borrowed(text) };
+    let utf16 = |to_bytes: fn(u16) -> [u8; 2]| {
+        let bom = format.bom.then_some(0xfeff);
+        bom.into_iter().chain(lines.encode_utf16()).flat_map(to_bytes).collect()
+    };
+    if format.encoding == UTF_16LE {
+        return Ok(utf16(u16::to_le_bytes));
+    }
+    if format.encoding == UTF_16BE {
+        return Ok(utf16(u16::to_be_bytes));
+    }
+    let (bytes, _used, unmappable) = format.encoding.encode(&lines);
+    if unmappable {
+        return Err(format!("the text has characters {} cannot store", format.encoding.name()));
+    }
+    let bom: &[u8] = if format.bom && format.encoding == UTF_8 { b"\xef\xbb\xbf" } else { b"" };
+    Ok([bom, &bytes].concat())
+}
+
+/// Read a text file, whatever its encoding.
+pub(crate) fn read_text(path: &Path) -> io::Result<(String, Format)> {
+    let bytes = fs::read(path)?;
+    decode(&bytes).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "binary content"))
+}
+
+/// Write `text` to `path` in `format`; text the encoding cannot store is
+/// an `InvalidData` error.
+pub(crate) fn write_text(path: &Path, text: &str, format: Format) -> io::Result<()> {
+    let bytes = encode(text, format).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(path, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_legacy_and_utf16() {
+        let latin1 = b"caf\xe9\r\nna\xefve\r\n";
+        let decoded = decode(latin1);
+        assert_eq!(
+            decoded.as_ref().map(|read| (read.0.as_str(), read.1.encoding.name(), read.1.crlf)),
+            Some(("caf\u{e9}\nna\u{ef}ve\n", "windows-1252", true))
+        );
+        let edited = decoded.and_then(|(text, format)| encode(&text.replace("na", "Na"), format).ok());
+        assert_eq!(edited.as_deref(), Some(b"caf\xe9\r\nNa\xefve\r\n".as_slice()));
+
+        let utf16 = b"\xff\xfea\0b\0\n\0";
+        let wide = decode(utf16);
+        assert_eq!(wide.as_ref().map(|read| (read.0.as_str(), read.1.bom)), Some(("ab\n", true)));
+        let written = wide.and_then(|(text, format)| encode(&text, format).ok());
+        assert_eq!(written.as_deref(), Some(utf16.as_slice()));
+
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n"), None);
+        assert_eq!(encode("\u{2603}", Format { encoding: WINDOWS_1252, bom: false, crlf: false }).ok(), None);
+    }
+}
@@ -0,0 +1,9 @@
+//! What a file holds, and how it is read and written: text encodings,
+//! binary detection with hex dumps, and windows over large files.
+
+/// Binary file detection and hex dumps.
+pub(crate) mod binary;
+/// Text encoding detection and re-encoding.
+pub(crate) mod encoding;
+/// Windowed reads of large files.
+pub(crate) mod window;
@@ -0,0 +1,164 @@
+//! Windowed views of large files: the panel holds a bounded range of lines,
+//! read by streaming the file, plus a header giving its place in the file.
+//! `file_scroll` moves the window. Lines are split on `\n` bytes, so
+//! ASCII-compatible encodings window fine and UTF-16 files do not.
+
+use std::fs::File;
+use std::io::{self, BufRead as _, BufReader};
+use std::path::Path;
+
+/// Files larger than this open as a window instead of whole.
+pub(crate) const WINDOW_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
+/// Lines in a window unless asked otherwise.
+pub(crate) const DEFAULT_WINDOW_LINES: usize = 400;
+
+/// Most lines a window holds.
+pub(crate) const MAX_WINDOW_LINES: usize = 2_000;
+
+/// Bytes of one line kept in a window (minified files are one huge line).
+const MAX_LINE_BYTES: usize = 2_000;
+
+/// Meta key: first line of the window, 0-based.
+pub(crate) const META_START: &str = "window_start";
+
+/// Meta key: lines in the window.
+pub(crate) const META_LINES: &str = "window_lines";
+
+/// A window: first line (0-based) and line count.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Span {
+    /// First line shown, 0-based.
+    pub start: usize,
+    /// Lines shown.
+    pub lines: usize,
+}
+
+impl Span {
+    /// The window of a file panel, when it shows one.
+    pub(crate) fn of_panel(ctx: &cp_base::state::context::Entry) -> Option<Self> {
+        let start = ctx.get_meta_usize(META_START)?;
+        Some(Self { start, lines: ctx.get_meta_usize(META_LINES).unwrap_or(DEFAULT_WINDOW_LINES) })
+    }
+
+    /// Store the window on a file panel and schedule a reload.
+    pub(crate) fn store(self, ctx: &mut cp_base::state::context::Entry) {
+        ctx.set_meta(META_START, &self.start);
+        ctx.set_meta(META_LINES, &self.lines);
+        ctx.cache_deprecated = true;
+    }
+}
+
+/// One streaming pass: keeps the lines of the window, counts them all.
+struct Scan {
+    /// The window.
+    span: Span,
+    /// Index of the line being read.
+    line: usize,
+    /// Bytes of the current line seen so far.
+    line_bytes: usize,
+    /// Kept text.
+    out: Vec<u8>,
+    /// The last byte read ended a line.
+    at_line_start: bool,
+}
+
+impl Scan {
+    /// Whether the current line is in the window.
+    const fn in_window(&self) -> bool {
+        self.line >= self.span.start && self.line.saturating_sub(self.span.start) < self.span.lines
+    }
+
+    /// Account for a piece of a line, ending it when `piece` ends in a newline.
+    fn feed(&mut self, piece: &[u8]) {
+        let ends_line = piece.last() == Some(&b'\n');
+        if self.in_window() {
+            let body = piece.strip_suffix(b"\r\n").or_else(|| piece.strip_suffix(b"\n")).unwrap_or(piece);
+            let room = MAX_LINE_BYTES.saturating_sub(self.line_bytes);
+            self.out.extend_from_slice(body.get(..room.min(body.len())).unwrap_or_default());
+            self.line_bytes = self.line_bytes.saturating_add(body.len());
+            if ends_line {
+                self.end_kept_line();
+            }
+        }
+        if ends_line {
+            self.line = self.line.saturating_add(1);
+            self.line_bytes = 0;
+        }
+        self.at_line_start = ends_line;
+    }
+
+    /// Close a kept line, marking it when it was cut.
+    fn end_kept_line(&mut self) {
+        if self.line_bytes > MAX_LINE_BYTES {
+            self.out.extend_from_slice(format!(" [line cut: {} bytes]", self.line_bytes).as_bytes());
+        }
+        self.out.push(b'\n');
+    }
+}
+
+/// Panel text for `span` of `path`: a header line, then the window.
+pub(crate) fn read(path: &Path, span: Span) -> io::Result<String> {
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let encoding = super::encoding::sniff(reader.fill_buf()?).map_or(encoding_rs::UTF_8, |(found, _bom)| found);
+    if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "UTF-16 files cannot be windowed"));
+    }
+    let mut pass = Scan { span, line: 0, line_bytes: 0, out: Vec::new(), at_line_start: true };
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        let len = chunk.len();
+        for piece in chunk.split_inclusive(|b| *b == b'\n') {
+            pass.feed(piece);
+        }
+        reader.consume(len);
+    }
+    if !pass.at_line_start {
+        if pass.in_window() {
+            pass.end_kept_line();
+        }
+        pass.line = pass.line.saturating_add(1);
+    }
+    let (text, _malformed) = encoding.decode_with_bom_removal(&pass.out);
+    Ok(format!("{}\n{text}", header(span, pass.line, size)))
+}
+
+/// The header line of a window over a file of `total` lines.
+fn header(span: Span, total: usize, size: u64) -> String {
+    if span.start >= total && total > 0 {
+        return format!("[window past the end: the file has {total} lines ({size} bytes); move back with file_scroll]");
+    }
+    let last = span.start.saturating_add(span.lines).min(total);
+    format!("[lines {}-{last} of {total} ({size} bytes); move with file_scroll]", span.start.saturating_add(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_window_and_counts_lines() {
+        let path = std::env::temp_dir().join(format!("cp_window_{}.txt", std::process::id()));
+        let long = "x".repeat(MAX_LINE_BYTES.saturating_add(5));
+        let written = std::fs::write(&path, format!("a\nb\n{long}\nd\ne"));
+        assert_eq!(written.ok(), Some(()));
+        let window = read(&path, Span { start: 1, lines: 2 });
+        let past = read(&path, Span { start: 9, lines: 2 });
+        let _removed = std::fs::remove_file(&path);
+        let kept = "x".repeat(MAX_LINE_BYTES);
+        let cut = MAX_LINE_BYTES.saturating_add(5);
+        let size = cut.saturating_add(8);
+        assert_eq!(
+            window.ok(),
+            Some(format!(
+                "[lines 2-3 of 5 ({size} bytes); move with file_scroll]\nb\n{kept} [line cut: {cut} bytes]\n"
+            ))
+        );
+        assert!(past.is_ok_and(|text| text.starts_with("[window past the end: the file has 5 lines")));
+    }
+}
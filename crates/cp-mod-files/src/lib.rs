@@ -1,22 +1,37 @@
 //! Files module — read, edit, and write project files.
 //!
-//! Three tools: `Open` (read file into context panel with syntax highlighting),
-//! `Edit` (`old_string/new_string` diff replacement), `Write` (create or fully
-//! overwrite). File panels auto-refresh on filesystem changes via the watcher.
-
+//! Five tools: `Open` (read file into context panel with syntax highlighting,
+//! or a bounded hex dump for binary files), `file_scroll` (move the window of
+//! a large file opened a few hundred lines at a time), `Edit`
+//! (`old_string/new_string` diff replacement), `Write` (create or fully
+//! overwrite), `log_tail` (follow the end of a file, filtered). File and tail
+//! panels auto-refresh on filesystem changes via the watcher.
+
+/// File content: encodings, binary detection, windows of large files.
+mod content;
 /// File panel rendering and caching.
 mod panel;
+/// Local-import resolution and symbol outlines for the related-files section.
+mod related;
+/// Log tail panels and the `log_tail` tool.
+mod tail;
 /// Tool implementations for Open, Edit, and Write.
 mod tools;
+/// Tool-result visualizers for Edit and Write.
+mod visualize;
 
 use cp_base::modules::ToolVisualizer;
 use cp_base::panels::Panel;
 use cp_base::state::context::Kind;
 use cp_base::state::runtime::State;
+use cp_base::tools::jail::Jail;
 use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
 use cp_base::tools::{ToolResult, ToolUse};
 
 use self::panel::FilePanel;
+use self::tail::{LOG_TAIL_PANEL_TYPE, TailPanel};
+use self::tools::format::FormatterConfig;
+use self::visualize::visualize_diff;
 use cp_base::modules::Module;
 use cp_base::tools::pre_flight::Verdict;
 use cp_mod_queue::types::QueueState;
@@ -55,10 +70,7 @@ fn build_virtual_content(disk_content: &str, canonical_path: &str, state: &State
                 let new = call.input.get("new_string").and_then(|v| v.as_str()).unwrap_or("");
 
                 if let Some(actual) = tools::edit_file::find_normalized_match(base, old) {
-                    let actual_owned = actual.to_owned();
-                    let mut buf = base.to_owned();
-                    buf = buf.replacen(&actual_owned, new, 1);
-                    virtual_content = Some(buf);
+                    virtual_content = Some(base.replacen(actual, new, 1));
                 }
                 // If the queued edit doesn't match, skip it — it may fail at flush time
             }
@@ -112,7 +124,7 @@ fn preflight_edit_oldstring(tool: &ToolUse, state: &State, target: &EditTarget<'
     let Some(old_string) = tool.input.get("old_string").and_then(|v| v.as_str()) else {
         return;
     };
-    let Ok(content) = std::fs::read_to_string(target.path) else {
+    let Ok((content, _format)) = content::encoding::read_text(target.path) else {
         return;
     };
     let current_ok = tools::edit_file::find_normalized_match(&content, old_string).is_some();
@@ -184,6 +196,79 @@ fn preflight_write(tool: &ToolUse) -> Verdict {
     pf
 }
 
+/// The file a panel follows: the path of a file or log tail panel.
+fn watched_file(ctx: &cp_base::state::context::Entry) -> Option<&str> {
+    match ctx.context_type.as_str() {
+        Kind::FILE => ctx.get_meta_str("file_path"),
+        LOG_TAIL_PANEL_TYPE => ctx.get_meta_str(tail::META_PATH),
+        _ => None,
+    }
+}
+
+/// Turn the post-edit formatter on or off; the message to show the user.
+/// User-side only: no tool reaches it.
+pub fn set_formatter(state: &mut State, enabled: bool) -> String {
+    tools::format::set_enabled(state, enabled)
+}
+
+/// Turn the workspace jail on or off, keeping its allowlist; the message to
+/// show the user. User-side only: no tool reaches it.
+pub fn set_jail(state: &mut State, enabled: bool) -> String {
+    let allow = state.get_ext::<Jail>().map(|jail| jail.allow().to_vec()).unwrap_or_default();
+    state.set_ext(Jail::new(enabled, allow));
+    if enabled {
+        "Workspace jail on: tool paths must stay inside the project or the allowlist.".to_owned()
+    } else {
+        "Workspace jail off: tools may reach any path.".to_owned()
+    }
+}
+
+/// Replace the directories the jail opens besides the project root (absolute
+/// or `~/`-relative); the message to show the user. User-side only.
+pub fn set_jail_allow(state: &mut State, allow: Vec<String>) -> String {
+    let message = if allow.is_empty() {
+        "Jail allowlist cleared: only the project is reachable.".to_owned()
+    } else {
+        format!("Jail allowlist: {}", allow.join(", "))
+    };
+    match state.get_ext_mut::<Jail>() {
+        Some(jail) => jail.set_allow(allow),
+        None => state.set_ext(Jail::new(false, allow)),
+    }
+    message
+}
+
+/// Whether the user turned the post-edit formatter on.
+#[must_use]
+pub fn formatter_enabled(state: &State) -> bool {
+    state.get_ext::<FormatterConfig>().is_some_and(|config| config.enabled)
+}
+
+/// Set the formatters of the active directory profile (extension → argv).
+///
+/// They run after `Edit`/`Write`, ahead of the configured ones, while the
+/// formatter is on or the user `trusted` the profile. Empty clears them.
+pub fn set_profile_formatters(
+    state: &mut State,
+    commands: std::collections::BTreeMap<String, Vec<String>>,
+    trusted: bool,
+) {
+    if let Some(config) = state.get_ext_mut::<FormatterConfig>() {
+        config.profile = commands;
+        config.profile_trusted = trusted;
+    }
+}
+
+/// Show line `line` (1-based) of `path` in its file panel, opening the file
+/// if needed and moving a window onto the line. Returns the panel ID.
+///
+/// # Errors
+///
+/// The file cannot be opened (missing, outside the workspace, binary).
+pub fn show_line(state: &mut State, path: &str, line: usize) -> Result<String, String> {
+    tools::file::show_line(path, line, state)
+}
+
 /// Files module: Open, Edit, Write tools for file manipulation.
 #[derive(Debug, Clone, Copy)]
 pub struct FilesModule;
@@ -221,12 +306,13 @@ impl Module for FilesModule {
     }
 
     fn dynamic_panel_types(&self) -> Vec<Kind> {
-        vec![Kind::new(Kind::FILE)]
+        vec![Kind::new(Kind::FILE), Kind::new(LOG_TAIL_PANEL_TYPE)]
     }
 
     fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
         match context_type.as_str() {
             Kind::FILE => Some(Box::new(FilePanel)),
+            LOG_TAIL_PANEL_TYPE => Some(Box::new(TailPanel)),
             _ => None,
         }
     }
@@ -239,6 +325,18 @@ impl Module for FilesModule {
                 .category("File")
                 .reverie_allowed(true)
                 .param_array("path", ParamType::String, true)
+                .param("related", ParamType::Boolean, false)
+                .param("hex", ParamType::Boolean, false)
+                .param("window", ParamType::Integer, false)
+                .build(),
+            ToolDefinition::from_yaml("file_scroll", t)
+                .short_desc("Move the window of a large file")
+                .category("File")
+                .reverie_allowed(true)
+                .param("panel", ParamType::String, true)
+                .param("line", ParamType::Integer, false)
+                .param("delta", ParamType::Integer, false)
+                .param("lines", ParamType::Integer, false)
                 .build(),
             ToolDefinition::from_yaml("Edit", t)
                 .short_desc("Modify file content")
@@ -254,6 +352,14 @@ impl Module for FilesModule {
                 .param("contents", ParamType::String, true)
                 .param_array("skip_callbacks", ParamType::String, false)
                 .build(),
+            ToolDefinition::from_yaml("log_tail", t)
+                .short_desc("Follow the end of a log file")
+                .category("File")
+                .param("path", ParamType::String, true)
+                .param("include", ParamType::String, false)
+                .param("exclude", ParamType::String, false)
+                .param("max_lines", ParamType::Integer, false)
+                .build(),
         ]
     }
 
@@ -269,8 +375,10 @@ impl Module for FilesModule {
     fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
         match tool.name.as_str() {
             "Open" => Some(tools::file::execute_open(tool, state)),
+            "file_scroll" => Some(tools::scroll::execute(tool, state)),
             "Edit" => Some(tools::edit_file::execute_edit(tool, state)),
             "Write" => Some(tools::write::execute(tool, state)),
+            "log_tail" => Some(tail::execute_log_tail(tool, state)),
 
             _ => None,
         }
@@ -281,16 +389,28 @@ impl Module for FilesModule {
     }
 
     fn context_type_metadata(&self) -> Vec<cp_base::state::context::TypeMeta> {
-        vec![cp_base::state::context::TypeMeta {
-            context_type: "file",
-            icon_id: "file",
-            is_fixed: false,
-            needs_cache: true,
-            fixed_order: None,
-            display_name: "file",
-            short_name: "file",
-            needs_async_wait: true,
-        }]
+        vec![
+            cp_base::state::context::TypeMeta {
+                context_type: "file",
+                icon_id: "file",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "file",
+                short_name: "file",
+                needs_async_wait: true,
+            },
+            cp_base::state::context::TypeMeta {
+                context_type: LOG_TAIL_PANEL_TYPE,
+                icon_id: "file",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "log-tail",
+                short_name: "tail",
+                needs_async_wait: false,
+            },
+        ]
     }
 
     fn context_detail(&self, ctx: &cp_base::state::context::Entry) -> Option<String> {
@@ -302,12 +422,7 @@ impl Module for FilesModule {
     }
 
     fn watch_paths(&self, state: &State) -> Vec<cp_base::panels::WatchSpec> {
-        state
-            .context
-            .iter()
-            .filter(|c| c.context_type.as_str() == Kind::FILE)
-            .filter_map(|c| c.get_meta_str("file_path").map(|p| cp_base::panels::WatchSpec::File(p.to_owned())))
-            .collect()
+        state.context.iter().filter_map(watched_file).map(|p| cp_base::panels::WatchSpec::File(p.to_owned())).collect()
     }
 
     fn should_invalidate_on_fs_change(
@@ -319,18 +434,32 @@ impl Module for FilesModule {
         if is_dir_event {
             return false;
         }
-        ctx.context_type.as_str() == Kind::FILE && ctx.get_meta_str("file_path") == Some(changed_path)
+        watched_file(ctx) == Some(changed_path)
     }
 
     fn dependencies(&self) -> &[&'static str] {
         &[]
     }
-    fn init_state(&self, _state: &mut State) {}
-    fn reset_state(&self, _state: &mut State) {}
-    fn save_module_data(&self, _state: &State) -> serde_json::Value {
-        serde_json::Value::Null
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(FormatterConfig::default());
+        state.set_ext(Jail::default());
+    }
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(FormatterConfig::default());
+        state.set_ext(Jail::default());
+    }
+    fn save_module_data(&self, state: &State) -> serde_json::Value {
+        serde_json::json!({
+            "formatter": state.get_ext::<FormatterConfig>(),
+            "jail": state.get_ext::<Jail>(),
+        })
+    }
+    fn load_module_data(&self, data: &serde_json::Value, state: &mut State) {
+        let formatter = data.get("formatter").and_then(|v| serde_json::from_value::<FormatterConfig>(v.clone()).ok());
+        state.set_ext(formatter.unwrap_or_default());
+        let jail = data.get("jail").and_then(|v| serde_json::from_value::<Jail>(v.clone()).ok());
+        state.set_ext(jail.unwrap_or_default());
     }
-    fn load_module_data(&self, _data: &serde_json::Value, _state: &mut State) {}
     fn save_worker_data(&self, _state: &State) -> serde_json::Value {
         serde_json::Value::Null
     }
@@ -369,123 +498,3 @@ impl Module for FilesModule {
         true
     }
 }
-
-/// Style one line inside a diff fenced block: red deletes, green adds,
-/// muted context.
-fn style_diff_block_line(line: &str, width: usize) -> cp_render::Block {
-    use cp_render::{Block, Semantic, Span};
-    let semantic = if line.starts_with("- ") {
-        Semantic::DiffRemove
-    } else if line.starts_with("+ ") {
-        Semantic::DiffAdd
-    } else {
-        Semantic::Muted
-    };
-    Block::Line(vec![Span::styled(truncate_line(line, width), semantic)])
-}
-
-/// Visualizer for Edit and Write tool results.
-///
-/// Parses diff blocks and renders deleted lines in red, added lines in green.
-/// Callback summary blocks get compact styled rendering (only status word colored).
-/// Non-diff content is rendered in secondary text color.
-#[must_use]
-pub fn visualize_diff(content: &str, width: usize) -> Vec<cp_render::Block> {
-    use cp_render::{Block, Span};
-
-    let mut blocks = Vec::new();
-    let mut in_diff_block = false;
-
-    for line in content.lines() {
-        // Detect diff block markers
-        if line.trim() == "```diff" {
-            in_diff_block = true;
-            continue;
-        }
-        if line.trim() == "```" && in_diff_block {
-            in_diff_block = false;
-            continue;
-        }
-
-        if line.is_empty() {
-            blocks.push(Block::empty());
-        } else if in_diff_block {
-            blocks.push(style_diff_block_line(line, width));
-        } else if let Some(styled) = style_callback_line_ir(line, width) {
-            blocks.push(styled);
-        } else {
-            // Non-diff content: plain muted text
-            blocks.push(Block::Line(vec![Span::muted(truncate_line(line, width))]));
-        }
-    }
-
-    blocks
-}
-
-/// Truncate a line to fit within the given width.
-fn truncate_line(line: &str, width: usize) -> String {
-    if line.len() > width {
-        format!("{}…", line.get(..line.floor_char_boundary(width.saturating_sub(1))).unwrap_or(""))
-    } else {
-        line.to_owned()
-    }
-}
-
-/// Style callback-related lines in tool results using IR blocks.
-/// Format: "Callbacks:" header, "· name passed/FAILED/TIMED OUT ...", "    error line"
-fn style_callback_line_ir(line: &str, width: usize) -> Option<cp_render::Block> {
-    use cp_render::{Block, Semantic, Span};
-
-    let trimmed = line.trim();
-
-    // "Callbacks:" header
-    if trimmed == "Callbacks:" {
-        return Some(Block::Line(vec![Span::muted(truncate_line(trimmed, width))]));
-    }
-
-    // "· name passed ..." or "· name FAILED ..." etc.
-    if let Some(rest) = trimmed.strip_prefix("\u{b7} ") {
-        let mut spans = vec![Span::muted("\u{b7} ".to_owned())];
-
-        let status_patterns: &[(&str, Semantic)] = &[
-            (" passed", Semantic::Success),
-            (" FAILED", Semantic::Error),
-            (" TIMED OUT", Semantic::Error),
-            (" dispatched", Semantic::Info),
-            (" skipped", Semantic::Muted),
-        ];
-
-        let mut matched = false;
-        for &(pattern, semantic) in status_patterns {
-            if let Some(pos) = rest.find(pattern) {
-                let name = rest.get(..pos).unwrap_or("");
-                spans.push(Span::muted(name.to_owned()));
-                spans.push(Span::styled(pattern.to_owned(), semantic));
-                let after_start = pos.saturating_add(pattern.len());
-                if let Some(after) = rest.get(after_start..)
-                    && !after.is_empty()
-                {
-                    spans.push(Span::muted(after.to_owned()));
-                }
-                matched = true;
-                break;
-            }
-        }
-        if !matched {
-            spans.push(Span::muted(rest.to_owned()));
-        }
-        return Some(Block::line(spans));
-    }
-
-    // Indented error lines (4 spaces)
-    if line.starts_with("    ") && !line.trim().is_empty() {
-        return Some(Block::Line(vec![Span::error(truncate_line(line, width))]));
-    }
-
-    // [skip_callbacks warnings: ...]
-    if trimmed.starts_with("[skip_callbacks warnings:") {
-        return Some(Block::Line(vec![Span::warning(truncate_line(trimmed, width))]));
-    }
-
-    None
-}
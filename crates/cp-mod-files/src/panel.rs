@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crossterm::event::KeyEvent;
 
@@ -8,11 +8,14 @@ use cp_base::cast::Safe as _;
 use cp_base::config::constants;
 use cp_base::panels::scroll_key_action;
 use cp_base::panels::{CacheRequest, CacheUpdate, hash_content};
-use cp_base::panels::{ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::panels::{Panel, paginate_content, update_if_changed};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
 use cp_base::state::runtime::State;
 
+use crate::content::window::Span;
+
 /// Data sent to the background cache thread for a file panel refresh.
 pub(crate) struct FileCacheRequest {
     /// Identifier of the context element to update.
@@ -21,6 +24,113 @@ pub(crate) struct FileCacheRequest {
     pub file_path: String,
     /// Hash of the currently cached source (used to skip unchanged files).
     pub current_source_hash: Option<String>,
+    /// How the file is shown.
+    pub view: View,
+}
+
+/// How a file panel shows its file.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum View {
+    /// The whole file, as text.
+    Text,
+    /// A bounded hex dump.
+    Hex,
+    /// A window of lines, under a header line.
+    Window(Span),
+}
+
+impl View {
+    /// The view of a file panel.
+    pub(crate) fn of_panel(ctx: &Entry) -> Self {
+        if ctx.get_meta::<bool>("hex") == Some(true) {
+            Self::Hex
+        } else {
+            Span::of_panel(ctx).map_or(Self::Text, Self::Window)
+        }
+    }
+}
+
+/// Panel text for `path` in `view`, or a notice when it is too large or
+/// has turned binary.
+fn load_content(path: &Path, view: View) -> Option<String> {
+    match view {
+        View::Hex => return crate::content::binary::hex_view(path),
+        View::Window(span) => {
+            let window = crate::content::window::read(path, span);
+            return Some(window.unwrap_or_else(|e| format!("[Cannot show a window of this file: {e}]")));
+        }
+        View::Text => {}
+    }
+    // Hard byte limit: refuse to load oversized files
+    if let Ok(meta) = fs::metadata(path)
+        && meta.len().to_usize() > constants::PANEL_MAX_LOAD_BYTES
+    {
+        return Some(format!(
+            "[File too large to load: {} bytes (limit: {} bytes). Close this panel and open it again to page through it with file_scroll.]",
+            meta.len(),
+            constants::PANEL_MAX_LOAD_BYTES
+        ));
+    }
+    match crate::content::encoding::read_text(path) {
+        Ok((content, _format)) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+            crate::content::binary::detect(path).map(|b| b.notice())
+        }
+        Err(_unreadable) => None,
+    }
+}
+
+/// Recompute the "related files" section of a panel opened with `related`,
+/// counting it towards the panel's tokens.
+fn refresh_related(ctx: &mut Entry) {
+    if ctx.get_meta::<bool>("related") != Some(true) {
+        return;
+    }
+    let section = ctx
+        .get_meta_str("file_path")
+        .zip(ctx.cached_content.as_deref())
+        .and_then(|(path, content)| crate::related::related_section(Path::new(path), content));
+    if let Some(text) = section.as_deref() {
+        ctx.token_count = ctx.token_count.saturating_add(estimate_tokens(text));
+    }
+    ctx.set_meta("related_files", &section);
+}
+
+/// Numbered lines of `content`, syntax highlighted when `file_path` is set;
+/// numbering starts after `first_line`.
+fn line_blocks(state: &State, file_path: &str, content: &str, first_line: usize) -> Vec<cp_render::Block> {
+    // Get IR syntax highlighting (RGB spans)
+    let highlighted = if file_path.is_empty() {
+        std::sync::Arc::new(Vec::new())
+    } else {
+        state.highlight_ir_fn.map_or_else(|| std::sync::Arc::new(Vec::new()), |f| f(file_path, content))
+    };
+    let number = |i: usize| cp_render::Span::muted(format!(" {:4} ", first_line.saturating_add(i).saturating_add(1)));
+
+    if highlighted.is_empty() {
+        // Plain text fallback — no syntax highlighting available
+        content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                cp_render::Block::Line(vec![
+                    number(i),
+                    cp_render::Span::new(" ".to_owned()),
+                    cp_render::Span::new(line.to_owned()),
+                ])
+            })
+            .collect()
+    } else {
+        highlighted
+            .iter()
+            .enumerate()
+            .map(|(i, spans)| {
+                let mut line_spans = vec![number(i), cp_render::Span::new(" ".to_owned())];
+                line_spans.extend(spans.iter().cloned());
+                cp_render::Block::Line(line_spans)
+            })
+            .collect()
+    }
 }
 
 /// Panel implementation for displaying file contents with syntax highlighting.
@@ -56,10 +166,13 @@ impl Panel for FilePanel {
     fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
         let selected = state.context.get(state.selected_context);
 
+        let view = selected.map_or(View::Text, View::of_panel);
+        let loaded = selected.is_some_and(|ctx| ctx.cached_content.is_some());
         let (content, file_path) = selected.map_or_else(
             || (String::new(), String::new()),
             |ctx| {
-                let path = ctx.get_meta_str("file_path").unwrap_or("");
+                // Hex dumps are not highlighted as source.
+                let path = if matches!(view, View::Hex) { "" } else { ctx.get_meta_str("file_path").unwrap_or("") };
                 let content = ctx.cached_content.clone().unwrap_or_else(|| {
                     if ctx.cache_deprecated { "Loading...".to_owned() } else { "No content".to_owned() }
                 });
@@ -67,35 +180,17 @@ impl Panel for FilePanel {
             },
         );
 
-        // Get IR syntax highlighting (RGB spans)
-        let highlighted = if file_path.is_empty() {
-            std::sync::Arc::new(Vec::new())
-        } else {
-            state.highlight_ir_fn.map_or_else(|| std::sync::Arc::new(Vec::new()), |f| f(&file_path, &content))
-        };
-
+        // A window starts with its header line; its lines keep their file numbers.
         let mut blocks = Vec::new();
-
-        if highlighted.is_empty() {
-            // Plain text fallback — no syntax highlighting available
-            for (i, line) in content.lines().enumerate() {
-                let line_num = i.saturating_add(1);
-                blocks.push(cp_render::Block::Line(vec![
-                    cp_render::Span::muted(format!(" {line_num:4} ")),
-                    cp_render::Span::new(" ".to_owned()),
-                    cp_render::Span::new(line.to_owned()),
-                ]));
-            }
-        } else {
-            for (i, spans) in highlighted.iter().enumerate() {
-                let line_num = i.saturating_add(1);
-                let mut line_spans =
-                    vec![cp_render::Span::muted(format!(" {line_num:4} ")), cp_render::Span::new(" ".to_owned())];
-                line_spans.extend(spans.iter().cloned());
-                blocks.push(cp_render::Block::Line(line_spans));
+        let (body, first_line) = match view {
+            View::Window(span) if loaded => {
+                let (head, rest) = content.split_once('\n').unwrap_or((content.as_str(), ""));
+                blocks.push(cp_render::Block::Line(vec![cp_render::Span::muted(head.to_owned())]));
+                (rest.to_owned(), span.start)
             }
-        }
-
+            View::Text | View::Hex | View::Window(_) => (content, 0),
+        };
+        blocks.extend(line_blocks(state, &file_path, &body, first_line));
         blocks
     }
     fn title(&self, state: &State) -> String {
@@ -110,6 +205,7 @@ impl Panel for FilePanel {
                 context_id: ctx.id.clone(),
                 file_path: path.to_owned(),
                 current_source_hash: ctx.source_hash.clone(),
+                view: View::of_panel(ctx),
             }),
         ))
     }
@@ -136,6 +232,7 @@ impl Panel for FilePanel {
             ctx.token_count = token_count;
         }
         ctx.cache_deprecated = false;
+        refresh_related(ctx);
         let content_ref = ctx.cached_content.clone().unwrap_or_default();
         let _changed = update_if_changed(ctx, &content_ref);
         true
@@ -146,24 +243,12 @@ impl Panel for FilePanel {
     }
     fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
         let req = request.data.downcast::<FileCacheRequest>().ok()?;
-        let FileCacheRequest { context_id, file_path, current_source_hash } = *req;
+        let FileCacheRequest { context_id, file_path, current_source_hash, view } = *req;
         let path = PathBuf::from(&file_path);
         if !path.exists() {
             return None;
         }
-        // Hard byte limit: refuse to load oversized files
-        if let Ok(meta) = fs::metadata(&path)
-            && meta.len().to_usize() > constants::PANEL_MAX_LOAD_BYTES
-        {
-            let msg = format!(
-                "[File too large to load: {} bytes (limit: {} bytes). Close this panel and use grep or other tools to inspect portions of the file.]",
-                meta.len(),
-                constants::PANEL_MAX_LOAD_BYTES
-            );
-            let token_count = estimate_tokens(&msg);
-            return Some(CacheUpdate::Content { context_id, content: msg, token_count });
-        }
-        let content = fs::read_to_string(&path).ok()?;
+        let content = load_content(&path, view)?;
         let new_hash = hash_content(&content);
         if current_source_hash.as_ref() == Some(&new_hash) {
             return Some(CacheUpdate::Unchanged { context_id });
@@ -185,8 +270,14 @@ impl Panel for FilePanel {
                 let path = c.get_meta_str("file_path")?;
                 // Use cached content only - no blocking file reads
                 let content = c.cached_content.as_ref()?;
-                let output = paginate_content(content, c.current_page, c.total_pages, &c.page_descriptions);
-                Some(ContextItem::new(&c.id, format!("File: {path}"), output, c.last_refresh_ms))
+                let mut output = paginate_content(content, c.current_page, c.total_pages, &c.page_descriptions);
+                if let Some(related) = c.get_meta_str("related_files") {
+                    output = format!("{output}\n\n{related}");
+                }
+                Some(
+                    ContextItem::new(&c.id, format!("File: {path}"), output, c.last_refresh_ms)
+                        .with_provenance(Provenance::repo(path)),
+                )
             })
             .collect()
     }
@@ -0,0 +1,270 @@
+//! Related files — local imports of an open file, with a symbol outline.
+//!
+//! Opt-in per panel (`Open` with `related: true`). When the file content
+//! changes, its local imports are resolved (Rust `mod`/`use crate|super|self`,
+//! Python relative and package imports, JS/TS relative specifiers) and listed
+//! after the file content with the top-level symbols each one defines, so the
+//! model can open exactly the dependency it needs instead of grepping for it.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Most related files listed per panel.
+const MAX_RELATED: usize = 12;
+/// Most symbols listed per related file.
+const MAX_SYMBOLS: usize = 15;
+/// Related files larger than this are listed without an outline.
+const MAX_OUTLINE_BYTES: u64 = 512 * 1024;
+
+/// Rust `mod foo;` declarations.
+static RUST_MOD: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_]\w*)\s*;").ok());
+/// Rust `use crate::…` / `use super::…` / `use self::…` paths.
+static RUST_USE: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+((?:crate|super|self)(?:::\w+)+)").ok());
+/// Python `from .x import y` / `import x.y`.
+static PY_IMPORT: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:from\s+(\.*[\w.]*)\s+import|import\s+([\w.]+))").ok());
+/// JS/TS relative `from './x'`, `import './x'` and `require('./x')`.
+static JS_IMPORT: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r#"(?:from|import|require\()\s*['"](\.{1,2}/[^'"]+)['"]"#).ok());
+/// Rust top-level items.
+static RUST_SYMBOL: LazyLock<Option<Regex>> = LazyLock::new(|| {
+    Regex::new(
+        r"(?m)^(?:pub(?:\([^)]*\))?\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?(fn|struct|enum|trait|type|const|static|mod)\s+([A-Za-z_]\w*)",
+    )
+    .ok()
+});
+/// Python top-level definitions.
+static PY_SYMBOL: LazyLock<Option<Regex>> = LazyLock::new(|| Regex::new(r"(?m)^(?:async\s+)?(def|class)\s+(\w+)").ok());
+/// JS/TS top-level declarations.
+static JS_SYMBOL: LazyLock<Option<Regex>> = LazyLock::new(|| {
+    Regex::new(
+        r"(?m)^(?:export\s+)?(?:default\s+)?(?:async\s+)?(function|class|interface|type|const|enum)\s+([A-Za-z_$][\w$]*)",
+    )
+    .ok()
+});
+
+/// Source languages with import resolution.
+#[derive(Clone, Copy)]
+enum Lang {
+    /// `.rs`
+    Rust,
+    /// `.py`
+    Python,
+    /// `.js`, `.jsx`, `.ts`, `.tsx`, `.mjs`
+    Script,
+}
+
+impl Lang {
+    /// Language of `path`, from its extension.
+    fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" => Some(Self::Python),
+            "js" | "jsx" | "ts" | "tsx" | "mjs" => Some(Self::Script),
+            _ => None,
+        }
+    }
+
+    /// Regex matching this language's top-level symbols (kind, name).
+    fn symbol_regex(self) -> Option<&'static Regex> {
+        match self {
+            Self::Rust => RUST_SYMBOL.as_ref(),
+            Self::Python => PY_SYMBOL.as_ref(),
+            Self::Script => JS_SYMBOL.as_ref(),
+        }
+    }
+}
+
+/// First existing file among `candidates`.
+fn first_file(candidates: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+/// Directory holding the submodules of the Rust file at `path`.
+fn rust_module_dir(path: &Path) -> Option<PathBuf> {
+    let dir = path.parent()?;
+    let stem = path.file_stem()?.to_str()?;
+    Some(if matches!(stem, "mod" | "lib" | "main") { dir.to_path_buf() } else { dir.join(stem) })
+}
+
+/// Module file for `dir/name` (`name.rs` or `name/mod.rs`).
+fn rust_module_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    first_file([dir.join(format!("{name}.rs")), dir.join(name).join("mod.rs")])
+}
+
+/// Resolve a `crate::…` / `super::…` / `self::…` path to the deepest module file.
+fn resolve_rust_use(path: &Path, use_path: &str) -> Option<PathBuf> {
+    let mut segments = use_path.split("::");
+    let mut dir = match segments.next()? {
+        "crate" => path
+            .ancestors()
+            .find(|a| a.ends_with("src") && a.parent().is_some_and(|p| p.join("Cargo.toml").is_file()))?
+            .to_path_buf(),
+        "super" => rust_module_dir(path)?.parent()?.to_path_buf(),
+        _ => rust_module_dir(path)?,
+    };
+    let mut found = None;
+    for segment in segments {
+        let Some(file) = rust_module_file(&dir, segment) else { break };
+        dir = dir.join(segment);
+        found = Some(file);
+    }
+    found
+}
+
+/// Local imports of a Rust file.
+fn rust_imports(path: &Path, content: &str) -> Vec<PathBuf> {
+    let mods = RUST_MOD.iter().flat_map(|re| re.captures_iter(content)).filter_map(|c| {
+        let dir = rust_module_dir(path)?;
+        rust_module_file(&dir, c.get(1)?.as_str())
+    });
+    let uses = RUST_USE
+        .iter()
+        .flat_map(|re| re.captures_iter(content))
+        .filter_map(|c| resolve_rust_use(path, c.get(1)?.as_str()));
+    mods.chain(uses).collect()
+}
+
+/// Local imports of a Python file (relative imports, then package-relative absolute ones).
+fn python_imports(path: &Path, content: &str) -> Vec<PathBuf> {
+    let Some(dir) = path.parent() else { return Vec::new() };
+    PY_IMPORT
+        .iter()
+        .flat_map(|re| re.captures_iter(content))
+        .filter_map(|c| {
+            let spec = c.get(1).or_else(|| c.get(2))?.as_str();
+            let dots = spec.chars().take_while(|ch| *ch == '.').count();
+            let base = dir.ancestors().nth(dots.saturating_sub(1))?;
+            let rel = spec.trim_start_matches('.').replace('.', "/");
+            if rel.is_empty() {
+                return None;
+            }
+            first_file([base.join(format!("{rel}.py")), base.join(&rel).join("__init__.py")])
+        })
+        .collect()
+}
+
+/// Local imports of a JS/TS file.
+fn script_imports(path: &Path, content: &str) -> Vec<PathBuf> {
+    let Some(dir) = path.parent() else { return Vec::new() };
+    JS_IMPORT
+        .iter()
+        .flat_map(|re| re.captures_iter(content))
+        .filter_map(|c| {
+            let target = dir.join(c.get(1)?.as_str());
+            let exts = ["", ".ts", ".tsx", ".js", ".jsx", ".mjs", "/index.ts", "/index.js"];
+            first_file(exts.iter().map(|ext| PathBuf::from(format!("{}{ext}", target.display()))))
+        })
+        .collect()
+}
+
+/// Top-level symbols of `path` as `kind name`, or `None` when unreadable/too large.
+fn outline(path: &Path) -> Option<Vec<String>> {
+    let re = Lang::of(path)?.symbol_regex()?;
+    if std::fs::metadata(path).ok()?.len() > MAX_OUTLINE_BYTES {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(
+        re.captures_iter(&content)
+            .filter_map(|c| Some(format!("{} {}", c.get(1)?.as_str(), c.get(2)?.as_str())))
+            .take(MAX_SYMBOLS)
+            .collect(),
+    )
+}
+
+/// Build the "related files" section for the file at `path` with `content`,
+/// or `None` when it has no resolvable local imports.
+pub(crate) fn related_section(path: &Path, content: &str) -> Option<String> {
+    let files = match Lang::of(path)? {
+        Lang::Rust => rust_imports(path, content),
+        Lang::Python => python_imports(path, content),
+        Lang::Script => script_imports(path, content),
+    };
+    let mut unique: Vec<PathBuf> = Vec::new();
+    for file in files.into_iter().filter_map(|f| f.canonicalize().ok()).filter(|f| f != path) {
+        if !unique.contains(&file) {
+            unique.push(file);
+        }
+    }
+    if unique.is_empty() {
+        return None;
+    }
+    let cwd = std::env::current_dir().ok();
+    let lines: Vec<String> = unique
+        .iter()
+        .take(MAX_RELATED)
+        .map(|file| {
+            let shown = cwd.as_deref().and_then(|c| file.strip_prefix(c).ok()).unwrap_or(file);
+            let symbols = outline(file).map(|s| s.join(", ")).filter(|s| !s.is_empty());
+            symbols.map_or_else(|| format!("- {}", shown.display()), |s| format!("- {}: {s}", shown.display()))
+        })
+        .collect();
+    let more = unique.len().saturating_sub(MAX_RELATED);
+    let tail = if more > 0 { format!("\n- … {more} more") } else { String::new() };
+    Some(format!("Related files (local imports):\n{}{tail}", lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `files` (relative path, content) under a fresh temp dir; its
+    /// canonical path.
+    fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("cp-related-{name}-{}", std::process::id()));
+        for &(rel, content) in files {
+            let path = root.join(rel);
+            let _dir = path.parent().map(std::fs::create_dir_all);
+            let _w = std::fs::write(&path, content);
+        }
+        root.canonicalize().unwrap_or(root)
+    }
+
+    #[test]
+    fn rust_mods_and_crate_paths_resolve_to_module_files() {
+        let root = project(
+            "rs",
+            &[
+                ("Cargo.toml", "[package]\n"),
+                ("src/lib.rs", "mod parser;\nuse crate::util::helpers::Helper;\n"),
+                ("src/parser.rs", "pub fn parse() {}\npub(crate) struct Ast;\n"),
+                ("src/util/mod.rs", "pub mod helpers;\n"),
+                ("src/util/helpers.rs", "pub struct Helper;\n"),
+            ],
+        );
+        let lib = root.join("src/lib.rs");
+        let section = related_section(&lib, "mod parser;\nuse crate::util::helpers::Helper;\n");
+        let _removed = std::fs::remove_dir_all(&root);
+        let text = section.unwrap_or_default();
+        assert!(text.contains("src/parser.rs: fn parse, struct Ast"), "{text}");
+        assert!(text.contains("src/util/helpers.rs: struct Helper"), "{text}");
+    }
+
+    #[test]
+    fn python_and_script_relative_imports_resolve() {
+        let root = project(
+            "py",
+            &[
+                ("pkg/models.py", "class User:\n    pass\n"),
+                ("web/api.ts", "export function fetchUser() {}\n"),
+                ("web/widgets/index.ts", "export const Button = 1;\n"),
+            ],
+        );
+        let python = related_section(&root.join("pkg/main.py"), "from .models import User\nimport os\n");
+        let script =
+            related_section(&root.join("web/app.ts"), "import { fetchUser } from './api';\nimport './widgets';\n");
+        let missing = related_section(&root.join("web/app.ts"), "import x from './gone';\nimport y from 'react';\n");
+        let _removed = std::fs::remove_dir_all(&root);
+        assert!(python.is_some_and(|t| t.contains("pkg/models.py: class User")));
+        assert!(
+            script
+                .is_some_and(|t| t.contains("web/api.ts: function fetchUser") && t.contains("index.ts: const Button"))
+        );
+        assert_eq!(missing, None);
+    }
+}
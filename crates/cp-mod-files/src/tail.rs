@@ -0,0 +1,279 @@
+//! Log tail panels: the end of a growing file, like `tail -f`, with regex
+//! include/exclude filters and a line budget.
+//!
+//! The file watcher marks a panel stale when its file changes and the cache
+//! worker re-reads the end of the file; a slow timer covers log rotation,
+//! which swaps the inode the watcher follows.
+
+use std::fs::File;
+use std::io::{Read as _, Seek as _, SeekFrom};
+use std::path::Path;
+
+use crossterm::event::KeyEvent;
+use regex::Regex;
+
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens, make_default_entry};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+/// Context type of log tail panels.
+pub(crate) const LOG_TAIL_PANEL_TYPE: &str = "log_tail";
+
+/// Metadata key: canonical path of the followed file.
+pub(crate) const META_PATH: &str = "tail_path";
+
+/// Metadata key: include regex.
+const META_INCLUDE: &str = "tail_include";
+
+/// Metadata key: exclude regex.
+const META_EXCLUDE: &str = "tail_exclude";
+
+/// Metadata key: line budget.
+const META_MAX_LINES: &str = "tail_max_lines";
+
+/// Lines shown when no budget is given.
+const DEFAULT_MAX_LINES: usize = 200;
+
+/// Highest line budget accepted.
+const MAX_LINES_LIMIT: usize = 2_000;
+
+/// Bytes read from the end of the file per refresh.
+const TAIL_WINDOW_BYTES: u64 = 1024 * 1024;
+
+/// Fallback refresh interval, for rotated files the watcher lost.
+const ROTATION_CHECK_MS: u64 = 5_000;
+
+/// What a tail panel shows.
+#[derive(Debug)]
+pub(crate) struct TailFilter {
+    /// Keep only lines matching this.
+    pub include: Option<Regex>,
+    /// Drop lines matching this.
+    pub exclude: Option<Regex>,
+    /// Last lines kept.
+    pub max_lines: usize,
+}
+
+impl TailFilter {
+    /// Compile the filter stored on a panel (or passed to the tool).
+    fn compile(include: Option<&str>, exclude: Option<&str>, max_lines: usize) -> Result<Self, String> {
+        let build = |pattern: Option<&str>| {
+            pattern.filter(|p| !p.is_empty()).map(Regex::new).transpose().map_err(|e| format!("invalid regex: {e}"))
+        };
+        Ok(Self { include: build(include)?, exclude: build(exclude)?, max_lines })
+    }
+
+    /// The last `max_lines` lines of `text` passing the filters, with a header.
+    pub(crate) fn render(&self, path: &str, text: &str) -> String {
+        let kept: Vec<&str> = text
+            .lines()
+            .filter(|line| self.include.as_ref().is_none_or(|re| re.is_match(line)))
+            .filter(|line| self.exclude.as_ref().is_none_or(|re| !re.is_match(line)))
+            .collect();
+        let shown = kept.get(kept.len().saturating_sub(self.max_lines)..).unwrap_or_default();
+        let mut out = format!("tail {path}: last {} of {} matching lines\n", shown.len(), kept.len());
+        for line in shown {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The last [`TAIL_WINDOW_BYTES`] of `path`, starting at a line boundary.
+fn read_end(path: &str) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(TAIL_WINDOW_BYTES);
+    let _pos = file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    let _read = file.take(TAIL_WINDOW_BYTES).read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+    if start == 0 {
+        return Ok(text);
+    }
+    Ok(text.split_once('\n').map_or_else(String::new, |(_partial, rest)| rest.to_owned()))
+}
+
+/// Index of the tail panel following `canonical`, created when missing
+/// (`true` then).
+fn tail_panel_index(state: &mut State, canonical: &str, path: &str) -> (usize, bool) {
+    let existing = state
+        .context
+        .iter()
+        .position(|c| c.context_type.as_str() == LOG_TAIL_PANEL_TYPE && c.get_meta_str(META_PATH) == Some(canonical));
+    if let Some(idx) = existing {
+        return (idx, false);
+    }
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+    let file_name = Path::new(path).file_name().map_or_else(|| path.to_owned(), |n| n.to_string_lossy().into_owned());
+    let mut entry = make_default_entry(&panel_id, Kind::new(LOG_TAIL_PANEL_TYPE), &format!("tail {file_name}"), true);
+    entry.uid = Some(uid);
+    entry.set_meta(META_PATH, &canonical);
+    state.context.push(entry);
+    (state.context.len().saturating_sub(1), true)
+}
+
+/// Execute `log_tail`: open a tail panel on a file, or retune an open one.
+pub(crate) fn execute_log_tail(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(path) = tool.input.get("path").and_then(serde_json::Value::as_str) else {
+        return ToolResult::new(tool.id.clone(), "Missing 'path' parameter".to_owned(), true);
+    };
+    let Some(resolved) = Path::new(path).canonicalize().ok().filter(|p| p.is_file()) else {
+        return ToolResult::new(tool.id.clone(), format!("Error: '{path}' is not a file"), true);
+    };
+    let canonical = resolved.to_string_lossy().into_owned();
+    let include = tool.input.get("include").and_then(serde_json::Value::as_str);
+    let exclude = tool.input.get("exclude").and_then(serde_json::Value::as_str);
+    let max_lines = tool
+        .input
+        .get("max_lines")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(DEFAULT_MAX_LINES, |n| usize::try_from(n).unwrap_or(MAX_LINES_LIMIT))
+        .clamp(1, MAX_LINES_LIMIT);
+    if let Err(e) = TailFilter::compile(include, exclude, max_lines) {
+        return ToolResult::new(tool.id.clone(), format!("Error: {e}"), true);
+    }
+    let (idx, created) = tail_panel_index(state, &canonical, path);
+    let Some(ctx) = state.context.get_mut(idx) else {
+        return ToolResult::new(tool.id.clone(), "Error: tail panel vanished".to_owned(), true);
+    };
+    ctx.set_meta(META_INCLUDE, &include);
+    ctx.set_meta(META_EXCLUDE, &exclude);
+    ctx.set_meta(META_MAX_LINES, &max_lines);
+    ctx.cache_deprecated = true;
+    let verb = if created { "created" } else { "updated" };
+    let message = format!("Tail panel {} {verb}: following {canonical} (last {max_lines} lines).", ctx.id);
+    ToolResult::new(tool.id.clone(), message, false)
+}
+
+/// Data sent to the cache thread for a tail refresh.
+struct TailRequest {
+    /// Panel to fill.
+    context_id: String,
+    /// Followed file.
+    path: String,
+    /// Include regex, as typed.
+    include: Option<String>,
+    /// Exclude regex, as typed.
+    exclude: Option<String>,
+    /// Line budget.
+    max_lines: usize,
+}
+
+/// Panel following the end of a file.
+pub(crate) struct TailPanel;
+
+impl Panel for TailPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &Entry, _state: &State) -> Option<CacheRequest> {
+        Some(CacheRequest::new(
+            Kind::new(LOG_TAIL_PANEL_TYPE),
+            Box::new(TailRequest {
+                context_id: ctx.id.clone(),
+                path: ctx.get_meta_str(META_PATH)?.to_owned(),
+                include: ctx.get_meta::<Option<String>>(META_INCLUDE).flatten(),
+                exclude: ctx.get_meta::<Option<String>>(META_EXCLUDE).flatten(),
+                max_lines: ctx.get_meta_usize(META_MAX_LINES).unwrap_or(DEFAULT_MAX_LINES),
+            }),
+        ))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<TailRequest>().ok()?;
+        let filter = TailFilter::compile(req.include.as_deref(), req.exclude.as_deref(), req.max_lines);
+        let content = match (filter, read_end(&req.path)) {
+            (Ok(compiled), Ok(text)) => compiled.render(&req.path, &text),
+            (Err(e), _) => format!("tail {}: {e}\n", req.path),
+            (_, Err(e)) => format!("tail {}: cannot read: {e}\n", req.path),
+        };
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id: req.context_id, content, token_count })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, _state: &mut State) -> bool {
+        // Only a Content update carries new bytes; Unchanged/ModuleSpecific are no-ops.
+        if let CacheUpdate::Content { content, token_count, .. } = update {
+            ctx.token_count = token_count;
+            ctx.full_token_count = token_count;
+            ctx.cache_deprecated = false;
+            let changed = update_if_changed(ctx, &content);
+            ctx.cached_content = Some(content);
+            changed
+        } else {
+            false
+        }
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        Some(ROTATION_CHECK_MS)
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Span as S};
+
+        let cached = state
+            .context
+            .get(state.selected_context)
+            .filter(|c| c.context_type.as_str() == LOG_TAIL_PANEL_TYPE)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content.lines().map(|line| Block::text(format!(" {line}"))).collect()
+    }
+
+    fn title(&self, state: &State) -> String {
+        state.context.get(state.selected_context).map_or_else(|| "Tail".to_owned(), |ctx| ctx.name.clone())
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == LOG_TAIL_PANEL_TYPE)
+            .filter_map(|c| {
+                let content = c.cached_content.as_ref()?;
+                let item = ContextItem::new(&c.id, &c.name, content.clone(), c.last_refresh_ms);
+                Some(item.with_provenance(Provenance::repo(c.get_meta_str(META_PATH).unwrap_or(&c.name))))
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_then_keeps_the_last_lines() {
+        let text = "INFO start\nDEBUG poll\nERROR disk full\nINFO retry\nERROR disk full again\n";
+        let filter = TailFilter::compile(Some("ERROR|INFO"), Some("retry"), 2);
+        let rendered = filter.map(|f| f.render("app.log", text));
+        let expected = "tail app.log: last 2 of 3 matching lines\nERROR disk full\nERROR disk full again\n";
+        assert_eq!(rendered.as_deref(), Ok(expected));
+        assert_eq!(TailFilter::compile(Some("("), None, 10).map(|f| f.max_lines).ok(), None);
+    }
+}
@@ -1,4 +1,3 @@
-use std::fs;
 use std::path::Path;
 
 use cp_base::state::context::{Kind, estimate_tokens};
@@ -6,6 +5,8 @@ use cp_base::state::runtime::State;
 use cp_base::tools::{ToolResult, ToolUse};
 
 use super::diff::generate_unified_diff;
+use super::paths::{self, Resolved};
+use crate::content::encoding::{self, Format};
 use std::fmt::Write as _;
 
 /// Normalize a string for matching: trim trailing whitespace per line, normalize line endings.
@@ -92,11 +93,7 @@ fn find_closest_match(haystack: &str, needle: &str) -> Option<(usize, String)> {
         let total_score = score.max(contains_score);
 
         if total_score > 0 && best_match.as_ref().is_none_or(|b| total_score > b.1) {
-            let preview = if norm_line.len() > 60 {
-                format!("{}...", norm_line.get(..norm_line.floor_char_boundary(60)).unwrap_or(""))
-            } else {
-                norm_line.to_owned()
-            };
+            let preview = cp_base::ui::text::ellipsize(norm_line, 60, "...");
             best_match = Some((idx.saturating_add(1), total_score, preview));
         }
     }
@@ -113,11 +110,7 @@ fn no_match_result(tool: &ToolUse, content: &str, old_string: &str) -> ToolResul
         String::new()
     };
 
-    let needle_preview = if old_string.len() > 50 {
-        format!("{}...", old_string.get(..old_string.floor_char_boundary(50)).unwrap_or(""))
-    } else {
-        old_string.to_owned()
-    };
+    let needle_preview = cp_base::ui::text::ellipsize(old_string, 50, "...");
 
     ToolResult::new(tool.id.clone(), format!("No match found for \"{needle_preview}\"{hint}"), true)
 }
@@ -136,12 +129,14 @@ struct EditReport<'report> {
     new_string: &'report str,
     /// The file panel's ID, when one is open (for the refresh note).
     panel_ref: Option<&'report str>,
+    /// Post-edit formatter note, when a formatter ran.
+    format_note: Option<&'report str>,
 }
 
 /// Build the user-facing display (with diff) and the LLM-facing content
 /// (summary + panel-refresh note) for a successful edit.
 fn build_edit_messages(report: &EditReport<'_>) -> (String, String) {
-    let EditReport { path_str, lines_changed, is_open, old_string, new_string, panel_ref } = *report;
+    let EditReport { path_str, lines_changed, is_open, old_string, new_string, panel_ref, format_note } = *report;
     let mut display_msg = String::new();
 
     // Warn if file was not open in context (edit still succeeded via unique match)
@@ -157,6 +152,9 @@ fn build_edit_messages(report: &EditReport<'_>) -> (String, String) {
     display_msg.push_str("```diff\n");
     display_msg.push_str(&generate_unified_diff(old_string, new_string));
     display_msg.push_str("```");
+    if let Some(note) = format_note {
+        let _r2 = write!(display_msg, "\n{note}");
+    }
 
     // LLM-facing content: short summary + panel reference. The file panel is
     // already updated (instant refresh), so tell the LLM explicitly.
@@ -174,10 +172,21 @@ fn build_edit_messages(report: &EditReport<'_>) -> (String, String) {
             "Panel {pid} has been UPDATED and now shows the current file content — do NOT expect to see stale content there."
         ).unwrap_or(());
     }
+    if let Some(note) = format_note {
+        writeln!(llm_msg, "{note}").unwrap_or(());
+    }
 
     (display_msg, llm_msg)
 }
 
+/// The file an edit targets, its decoded text and how it is stored.
+fn load(path_str: &str, state: &State) -> Result<(Resolved, String, Format), String> {
+    let resolved = paths::writable(Path::new(path_str), state)?;
+    let (content, format) =
+        encoding::read_text(&resolved.target).map_err(|e| paths::explain(&e, "read", &resolved.target))?;
+    Ok((resolved, content, format))
+}
+
 /// Execute the Edit tool: replace `old_string` with `new_string` in a file.
 pub(crate) fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
     let _fg = cp_base::flame!("file_edit");
@@ -196,22 +205,19 @@ pub(crate) fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
         return ToolResult::new(tool.id.clone(), "Missing required parameter: new_string".to_owned(), true);
     };
 
-    // Check if file is open in context (canonicalize for consistent comparison)
-    let path = Path::new(path_str);
-    let canonical = path.canonicalize().map_or_else(|_| path_str.to_owned(), |p| p.to_string_lossy().to_string());
+    // Resolve symlinks and decode the file from whatever encoding it is stored in
+    let (resolved, mut content, stored_as) = match load(path_str, state) {
+        Ok(loaded) => loaded,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    };
+    let path = resolved.target.as_path();
+    let canonical = resolved.canonical();
+    // Check if file is open in context (canonical for consistent comparison)
     let is_open = state
         .context
         .iter()
         .any(|c| c.context_type.as_str() == Kind::FILE && c.get_meta_str("file_path") == Some(&canonical));
 
-    // Read file
-    let mut content = match fs::read_to_string(path) {
-        Ok(c) => c,
-        Err(e) => {
-            return ToolResult::new(tool.id.clone(), format!("Failed to read file: {e}"), true);
-        }
-    };
-
     // Try normalized matching (handles trailing whitespace differences).
     // Clone the match to break the borrow on `content`, allowing mutation below.
     let actual_match_opt = find_normalized_match(&content, old_string).map(str::to_owned);
@@ -220,9 +226,13 @@ pub(crate) fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
     };
     content = content.replacen(actual_match.as_str(), new_string, 1);
 
-    // Write file
-    if let Err(e) = fs::write(path, &content) {
-        return ToolResult::new(tool.id.clone(), format!("Failed to write file: {e}"), true);
+    // Write file back in its original encoding, line endings and permissions
+    let formatted = match super::write::save(state, path, &content, stored_as) {
+        Ok(formatted) => formatted,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    };
+    if let Some(new_content) = formatted.as_ref().and_then(|f| f.content.clone()) {
+        content = new_content;
     }
 
     // Update the context element's token count
@@ -250,6 +260,7 @@ pub(crate) fn execute_edit(tool: &ToolUse, state: &mut State) -> ToolResult {
         old_string,
         new_string,
         panel_ref: panel_ref.as_deref(),
+        format_note: formatted.as_ref().map(|f| f.note.as_str()),
     });
 
     let mut result = ToolResult::new(tool.id.clone(), llm_msg, false);
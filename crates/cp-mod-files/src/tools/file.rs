@@ -4,6 +4,9 @@ use cp_base::state::context::Kind;
 use cp_base::state::runtime::State;
 use cp_base::tools::{ToolResult, ToolUse};
 
+use super::paths;
+use crate::content::window::{DEFAULT_WINDOW_LINES, MAX_WINDOW_LINES, Span, WINDOW_THRESHOLD_BYTES};
+
 /// Execute the Open tool: add one or more files to the context.
 pub(crate) fn execute_open(tool: &ToolUse, state: &mut State) -> ToolResult {
     let _fg = cp_base::flame!("file_open");
@@ -20,10 +23,19 @@ pub(crate) fn execute_open(tool: &ToolUse, state: &mut State) -> ToolResult {
         return ToolResult::new(tool.id.clone(), "Empty path list".to_owned(), true);
     }
 
+    let options = OpenOptions {
+        related: tool.input.get("related").and_then(serde_json::Value::as_bool) == Some(true),
+        hex: tool.input.get("hex").and_then(serde_json::Value::as_bool) == Some(true),
+        window: tool
+            .input
+            .get("window")
+            .and_then(serde_json::Value::as_u64)
+            .map(|n| usize::try_from(n).unwrap_or(MAX_WINDOW_LINES).clamp(1, MAX_WINDOW_LINES)),
+    };
     let mut results = Vec::new();
 
     for path in &paths {
-        results.push(open_single_file(path, state));
+        results.push(open_single_file(path, options, state));
     }
 
     let content = results.join("\n");
@@ -31,8 +43,70 @@ pub(crate) fn execute_open(tool: &ToolUse, state: &mut State) -> ToolResult {
     ToolResult::new(tool.id.clone(), content, has_error)
 }
 
+/// How `Open` shows a file.
+#[derive(Debug, Clone, Copy)]
+struct OpenOptions {
+    /// List the file's local imports after its content.
+    related: bool,
+    /// Show a bounded hex dump instead of text (required for binary files).
+    hex: bool,
+    /// Lines per window when paging through the file; large files are
+    /// windowed even without it.
+    window: Option<usize>,
+}
+
+impl OpenOptions {
+    /// The window to open `path` with, if any: when asked for, or when the
+    /// file is too large to hold whole.
+    fn window_for(self, path: &Path) -> Option<Span> {
+        let large = path.metadata().is_ok_and(|m| m.len() > WINDOW_THRESHOLD_BYTES);
+        let lines = self.window.or_else(|| large.then_some(DEFAULT_WINDOW_LINES))?;
+        (!self.hex).then_some(Span { start: 0, lines })
+    }
+}
+
+/// Auto-expand the tree's parent folders of `canonical` so the opened file is visible.
+fn expand_tree_to(canonical: &str, state: &mut State) {
+    if state.active_modules.contains("tree")
+        && let Ok(cwd) = std::env::current_dir().and_then(|d| d.canonicalize())
+        && let Ok(rel) = Path::new(canonical).strip_prefix(&cwd)
+    {
+        let ts = cp_mod_tree::types::TreeState::get_mut(state);
+        let mut accumulator = String::new();
+        for component in rel.parent().into_iter().flat_map(Path::components) {
+            if !accumulator.is_empty() {
+                accumulator.push('/');
+            }
+            accumulator.push_str(&component.as_os_str().to_string_lossy());
+            if !ts.open_folders.contains(&accumulator) {
+                ts.open_folders.push(accumulator.clone());
+            }
+        }
+        cp_base::panels::mark_panels_dirty(state, Kind::TREE);
+    }
+}
+
+/// Status message when `canonical` is already open; asking for `related` or
+/// `hex` on such a panel switches that view on and schedules a refresh.
+fn reopen_message(path: &str, canonical: &str, options: OpenOptions, state: &mut State) -> Option<String> {
+    let open = state.context.iter_mut().find(|c| c.get_meta_str("file_path") == Some(canonical))?;
+    if options.hex && open.get_meta::<bool>("hex") != Some(true) {
+        open.set_meta("hex", &true);
+        open.cache_deprecated = true;
+        return Some(format!("File '{path}' is already open in context; it now shows a hex dump"));
+    }
+    if options.related && open.get_meta::<bool>("related") != Some(true) {
+        open.set_meta("related", &true);
+        open.cache_deprecated = true;
+        return Some(format!("File '{path}' is already open in context; related files will be listed"));
+    }
+    Some(format!("File '{path}' is already open in context"))
+}
+
 /// Open a single file and add it as a context element, returning a status message.
-fn open_single_file(path: &str, state: &mut State) -> String {
+/// With `related`, the panel also lists the file's local imports; binary
+/// files are refused unless `hex` asks for a hex dump.
+fn open_single_file(path: &str, options: OpenOptions, state: &mut State) -> String {
     // Check if file exists (quick metadata check, not a full read)
     let path_obj = Path::new(path);
     if !path_obj.exists() {
@@ -43,12 +117,22 @@ fn open_single_file(path: &str, state: &mut State) -> String {
         return format!("Error: '{path}' is not a file");
     }
 
-    // Canonicalize to absolute path so lookups match regardless of relative/absolute input
-    let canonical = path_obj.canonicalize().map_or_else(|_| path.to_owned(), |p| p.to_string_lossy().to_string());
+    if !options.hex
+        && let Some(binary) = crate::content::binary::detect(path_obj)
+    {
+        return binary.open_error(path);
+    }
+
+    // Resolve symlinks so lookups match regardless of relative/absolute/linked input
+    let resolved = match paths::resolve(path_obj, state) {
+        Ok(resolved) => resolved,
+        Err(e) => return format!("Error: {e}"),
+    };
+    let canonical = resolved.canonical();
 
     // Check if file is already open (using canonical path)
-    if state.context.iter().any(|c| c.get_meta_str("file_path") == Some(&canonical)) {
-        return format!("File '{path}' is already open in context");
+    if let Some(msg) = reopen_message(path, &canonical, options, state) {
+        return msg;
     }
 
     let file_name = path_obj.file_name().map_or_else(|| path.to_owned(), |n| n.to_string_lossy().to_string());
@@ -63,26 +147,69 @@ fn open_single_file(path: &str, state: &mut State) -> String {
     let mut elem = cp_base::state::context::make_default_entry(&context_id, Kind::new(Kind::FILE), &file_name, true);
     elem.uid = Some(uid);
     elem.set_meta("file_path", &canonical);
+    let window = options.window_for(path_obj);
+    if options.hex {
+        elem.set_meta("hex", &true);
+    } else if let Some(span) = window {
+        span.store(&mut elem);
+    } else if options.related {
+        elem.set_meta("related", &true);
+    } else {
+        // Plain text view.
+    }
     state.context.push(elem);
 
-    // Auto-expand parent folders in the tree so the opened file is visible
-    if state.active_modules.contains("tree")
-        && let Ok(cwd) = std::env::current_dir().and_then(|d| d.canonicalize())
-        && let Ok(rel) = Path::new(&canonical).strip_prefix(&cwd)
-    {
-        let ts = cp_mod_tree::types::TreeState::get_mut(state);
-        let mut accumulator = String::new();
-        for component in rel.parent().into_iter().flat_map(Path::components) {
-            if !accumulator.is_empty() {
-                accumulator.push('/');
-            }
-            accumulator.push_str(&component.as_os_str().to_string_lossy());
-            if !ts.open_folders.contains(&accumulator) {
-                ts.open_folders.push(accumulator.clone());
-            }
+    expand_tree_to(&canonical, state);
+
+    let link = resolved.link_note();
+    window.map_or_else(
+        || format!("Opened '{path}'{link} as {context_id}"),
+        |span| format!("Opened '{path}'{link} as {context_id}, {} lines at a time (move with file_scroll)", span.lines),
+    )
+}
+
+/// Lines kept above the target when `show_line` moves a window.
+const LINES_ABOVE: usize = 10;
+
+/// Row of the 0-based line `target` in a file panel's view, first moving a
+/// window that does not hold it. A window shows its header line first.
+fn view_row(ctx: &mut cp_base::state::context::Entry, target: usize) -> usize {
+    let Some(span) = Span::of_panel(ctx) else { return target };
+    let start = if (span.start..span.start.saturating_add(span.lines)).contains(&target) {
+        span.start
+    } else {
+        let start = target.saturating_sub(LINES_ABOVE.min(span.lines));
+        Span { start, lines: span.lines }.store(ctx);
+        start
+    };
+    target.saturating_sub(start).saturating_add(1)
+}
+
+/// Show line `line` (1-based) of `path`, opening the file first unless it is
+/// open: a windowed panel moves its window there, and the panel's view is
+/// scrolled to it. Returns the panel ID.
+pub(crate) fn show_line(path: &str, line: usize, state: &mut State) -> Result<String, String> {
+    let canonical = paths::resolve(Path::new(path), state)?.canonical();
+    let is_open = |current: &State| current.context.iter().any(|c| c.get_meta_str("file_path") == Some(&canonical));
+    if !is_open(state) {
+        let message = open_single_file(path, OpenOptions { related: false, hex: false, window: None }, state);
+        if message.starts_with("Error:") || !is_open(state) {
+            return Err(message);
         }
-        cp_base::panels::mark_panels_dirty(state, Kind::TREE);
     }
-
-    format!("Opened '{path}' as {context_id}")
+    let selected = state.context.get(state.selected_context).map(|c| c.id.clone());
+    let ctx = state
+        .context
+        .iter_mut()
+        .find(|c| c.get_meta_str("file_path") == Some(&canonical))
+        .ok_or_else(|| format!("File '{path}' is not open"))?;
+    let row = view_row(ctx, line.saturating_sub(1));
+    ctx.scroll_state.offset = cp_base::cast::Safe::to_f32(row);
+    ctx.scroll_state.user_scrolled = true;
+    let id = ctx.id.clone();
+    if selected.as_ref() == Some(&id) {
+        state.scroll_offset = cp_base::cast::Safe::to_f32(row);
+        state.flags.stream.user_scrolled = true;
+    }
+    Ok(id)
 }
@@ -0,0 +1,231 @@
+//! Post-edit formatter: run the configured formatter on a file after `Edit`/`Write`.
+//!
+//! Off until the user types `/formatter on`. The per-extension commands
+//! (rustfmt, prettier, black) and the exclusion globs keep their defaults; the
+//! active directory profile may add its own. A formatter gets
+//! [`FORMAT_TIMEOUT_SECS`] to finish, since the tool waits for it. When it
+//! rewrites the file, its diff is folded into the tool result so the model
+//! knows the file on disk no longer matches what it wrote.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use cp_base::modules::run_with_timeout;
+use cp_base::state::runtime::State;
+use serde::{Deserialize, Serialize};
+
+use super::diff::generate_unified_diff;
+
+/// Max time a formatter may take before the file is left as written.
+pub(crate) const FORMAT_TIMEOUT_SECS: u64 = 30;
+
+/// Persisted formatter configuration (module data of the files module).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FormatterConfig {
+    /// Run formatters after successful edits and writes.
+    #[serde(default)]
+    pub enabled: bool,
+    /// File extension (without dot) → formatter argv; the file path is appended.
+    #[serde(default = "default_commands")]
+    pub commands: BTreeMap<String, Vec<String>>,
+    /// Glob patterns of files never formatted.
+    #[serde(default = "default_exclude")]
+    pub exclude: Vec<String>,
+    /// Formatters of the active directory profile, ahead of `commands`. Not
+    /// persisted.
+    #[serde(skip)]
+    pub profile: BTreeMap<String, Vec<String>>,
+    /// The user trusts the active profile: its formatters run even while
+    /// `enabled` is off. Not persisted.
+    #[serde(skip)]
+    pub profile_trusted: bool,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commands: default_commands(),
+            exclude: default_exclude(),
+            profile: BTreeMap::new(),
+            profile_trusted: false,
+        }
+    }
+}
+
+/// Built-in formatters: rustfmt, prettier and black.
+fn default_commands() -> BTreeMap<String, Vec<String>> {
+    let rustfmt = ["rustfmt", "--edition", "2024"].as_slice();
+    let prettier = ["prettier", "--write", "--log-level", "warn"].as_slice();
+    let black = ["black", "--quiet"].as_slice();
+    [
+        ("rs", rustfmt),
+        ("js", prettier),
+        ("jsx", prettier),
+        ("ts", prettier),
+        ("tsx", prettier),
+        ("css", prettier),
+        ("py", black),
+    ]
+    .into_iter()
+    .map(|(ext, argv)| (ext.to_owned(), argv.iter().map(|a| (*a).to_owned()).collect()))
+    .collect()
+}
+
+/// Built-in exclusions: build output and vendored dependencies.
+fn default_exclude() -> Vec<String> {
+    ["**/target/**", "**/node_modules/**", "**/vendor/**"].iter().map(|p| (*p).to_owned()).collect()
+}
+
+/// What the formatter did, to be appended to the tool result.
+pub(crate) struct FormatReport {
+    /// Human- and LLM-facing note (formatter name plus diff, or the failure).
+    pub note: String,
+    /// New file content when the formatter changed it.
+    pub content: Option<String>,
+}
+
+impl FormatterConfig {
+    /// Formatter argv for `path`, unless disabled, excluded or unconfigured.
+    fn command_for(&self, path: &Path) -> Option<&[String]> {
+        let path_str = path.to_string_lossy();
+        let excluded = self
+            .exclude
+            .iter()
+            .filter_map(|p| globset::Glob::new(p).ok())
+            .any(|g| g.compile_matcher().is_match(path_str.as_ref()));
+        if excluded {
+            return None;
+        }
+        let ext = path.extension()?.to_str()?;
+        let profiled = self.profile.get(ext).filter(|argv| !argv.is_empty() && (self.enabled || self.profile_trusted));
+        if profiled.is_some() {
+            return profiled.map(Vec::as_slice);
+        }
+        self.commands.get(ext).map(Vec::as_slice).filter(|argv| self.enabled && !argv.is_empty())
+    }
+}
+
+/// Strip the lines `before` and `after` share at both ends, so the diff only
+/// covers the region the formatter touched.
+fn changed_region(before: &str, after: &str) -> (String, String) {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let head = old.iter().zip(&new).take_while(|pair| pair.0 == pair.1).count();
+    let old_rest = old.get(head..).unwrap_or_default();
+    let new_rest = new.get(head..).unwrap_or_default();
+    let tail = old_rest.iter().rev().zip(new_rest.iter().rev()).take_while(|pair| pair.0 == pair.1).count();
+    let old_mid = old_rest.get(..old_rest.len().saturating_sub(tail)).unwrap_or_default();
+    let new_mid = new_rest.get(..new_rest.len().saturating_sub(tail)).unwrap_or_default();
+    (old_mid.join("\n"), new_mid.join("\n"))
+}
+
+/// Run `program` with `args` and `path`; the reason it failed, if it did.
+fn format_file(program: &str, args: &[String], path: &Path, timeout_secs: u64) -> Option<String> {
+    let mut cmd = Command::new(program);
+    let _cmd = cmd.args(args).arg(path);
+    match run_with_timeout(cmd, timeout_secs) {
+        Ok(out) if out.status.success() => None,
+        Ok(out) => Some(String::from_utf8_lossy(&out.stderr).lines().next().unwrap_or("non-zero exit").to_owned()),
+        Err(e) if e.kind() == std::io::ErrorKind::TimedOut => Some(format!("timed out after {timeout_secs}s")),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Run the configured formatter on `path`, whose content is `written`.
+/// Returns `None` when no formatter applies or the file came out unchanged.
+pub(crate) fn run(state: &State, path: &Path, written: &str) -> Option<FormatReport> {
+    let default_config = FormatterConfig::default();
+    let config = state.get_ext::<FormatterConfig>().unwrap_or(&default_config);
+    report(config.command_for(path)?, path, written, FORMAT_TIMEOUT_SECS)
+}
+
+/// Format `path` with `command` and describe the outcome.
+fn report(command: &[String], path: &Path, written: &str, timeout_secs: u64) -> Option<FormatReport> {
+    let (program, args) = command.split_first()?;
+    if let Some(reason) = format_file(program, args, path, timeout_secs) {
+        return Some(FormatReport {
+            note: format!("Formatter {program} failed ({reason}); file left as written."),
+            content: None,
+        });
+    }
+    let formatted = std::fs::read_to_string(path).ok().filter(|c| c != written)?;
+    let (old, new) = changed_region(written, &formatted);
+    let note = format!("Formatted with {program}:\n```diff\n{}```", generate_unified_diff(&old, &new));
+    Some(FormatReport { note, content: Some(formatted) })
+}
+
+/// Turn the post-edit formatter on or off. User-side only.
+pub(crate) fn set_enabled(state: &mut State, enabled: bool) -> String {
+    if let Some(config) = state.get_ext_mut::<FormatterConfig>() {
+        config.enabled = enabled;
+    } else {
+        state.set_ext(FormatterConfig { enabled, ..FormatterConfig::default() });
+    }
+    if enabled {
+        "Formatter on: files are formatted after every Edit and Write.".to_owned()
+    } else {
+        "Formatter off: files stay as written.".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Owned argv from `list`.
+    fn argv(list: &[&str]) -> Vec<String> {
+        list.iter().map(|&a| a.to_owned()).collect()
+    }
+
+    /// The formatter program `config` picks for `path`.
+    fn program<'config>(config: &'config FormatterConfig, path: &str) -> Option<&'config str> {
+        config.command_for(Path::new(path)).and_then(<[String]>::first).map(String::as_str)
+    }
+
+    #[test]
+    fn commands_follow_extension_and_exclusions() {
+        let mut config = FormatterConfig::default();
+        assert_eq!(program(&config, "src/lib.rs"), None);
+        config.enabled = true;
+        let picked: Vec<Option<&str>> = [
+            "src/lib.rs",
+            "web/app.tsx",
+            "target/debug/build.rs",
+            "ui/node_modules/x/index.js",
+            "README.md",
+            "Makefile",
+        ]
+        .iter()
+        .map(|path| program(&config, path))
+        .collect();
+        assert_eq!(picked, [Some("rustfmt"), Some("prettier"), None, None, None, None]);
+    }
+
+    #[test]
+    fn profile_formatters_need_the_formatter_on_or_a_trusted_profile() {
+        let profile = BTreeMap::from([("ts".to_owned(), argv(&["./evil.sh"]))]);
+        let mut config = FormatterConfig { profile, ..FormatterConfig::default() };
+        assert_eq!(program(&config, "web/app.ts"), None);
+        config.profile_trusted = true;
+        assert_eq!(program(&config, "web/app.ts"), Some("./evil.sh"));
+        config.profile_trusted = false;
+        config.enabled = true;
+        assert_eq!(program(&config, "web/app.ts"), Some("./evil.sh"));
+    }
+
+    #[test]
+    fn failures_and_timeouts_leave_the_file_as_written() {
+        let path = std::env::temp_dir().join(format!("cp-format-{}.txt", std::process::id()));
+        let _written = std::fs::write(&path, "x\n");
+        let failed = report(&argv(&["false"]), &path, "x\n", FORMAT_TIMEOUT_SECS).map(|r| (r.note, r.content));
+        let slow = report(&argv(&["sh", "-c", "sleep 3", "sh"]), &path, "x\n", 1).map(|r| (r.note, r.content));
+        let reformat = argv(&["sh", "-c", "printf 'y\\n' > \"$1\"", "sh"]);
+        let formatted = report(&reformat, &path, "x\n", FORMAT_TIMEOUT_SECS).and_then(|r| r.content);
+        let _removed = std::fs::remove_file(&path);
+        assert!(failed.is_some_and(|(note, content)| note.contains("Formatter false failed") && content.is_none()));
+        assert!(slow.is_some_and(|(note, content)| note.contains("timed out after 1s") && content.is_none()));
+        assert_eq!(formatted.as_deref(), Some("y\n"));
+    }
+}
@@ -4,5 +4,11 @@ pub(crate) mod diff;
 pub(crate) mod edit_file;
 /// Open tool: read a file into the context panel.
 pub(crate) mod file;
+/// Post-edit formatter run after Edit and Write.
+pub(crate) mod format;
+/// Symlink resolution, the workspace jail and permission handling.
+pub(crate) mod paths;
+/// `file_scroll`: move the window of a large file panel.
+pub(crate) mod scroll;
 /// Write tool: create or fully overwrite a file.
 pub(crate) mod write;
@@ -0,0 +1,165 @@
+//! Path handling for the file tools: symlinks are resolved explicitly and
+//! named in results, the workspace jail is applied again at execution,
+//! permission bits survive rewrites, and I/O failures come back as messages
+//! saying what is wrong and what to do.
+//!
+//! Pre-flight already checked the path parameters, but a link may have been
+//! swapped since, so [`resolve`] asks the jail again with the path about to be
+//! opened. What the jail opens is the user's call (`/jail on`, `/jail allow`).
+
+use std::fs::{self, Metadata, Permissions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cp_base::config::constants::STORE_DIR;
+use cp_base::state::runtime::State;
+use cp_base::tools::jail::{self, Jail};
+
+/// A tool path resolved to the file it names.
+#[derive(Debug)]
+pub(crate) struct Resolved {
+    /// Real location, symlinks followed.
+    pub target: PathBuf,
+    /// The path given was a symlink.
+    pub via_link: bool,
+}
+
+impl Resolved {
+    /// The target as a string, the form panels are keyed by.
+    pub(crate) fn canonical(&self) -> String {
+        self.target.to_string_lossy().into_owned()
+    }
+
+    /// ` (symlink to …)` when the path was a symlink, else empty.
+    pub(crate) fn link_note(&self) -> String {
+        if self.via_link { format!(" (symlink to {})", self.target.display()) } else { String::new() }
+    }
+}
+
+/// Resolve `path`, which need not exist yet, through the workspace jail.
+pub(crate) fn resolve(path: &Path, state: &State) -> Result<Resolved, String> {
+    let via_link = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    let target = match state.get_ext::<Jail>() {
+        Some(jail) => jail.check(path)?,
+        None => jail::resolve(path)?,
+    };
+    Ok(Resolved { target, via_link })
+}
+
+/// Resolve `path` for `Edit`/`Write`. Anything under `.context-pilot/`
+/// (plugin grants, profiles, saved state) is the user's to change, so the
+/// agent may read it but never rewrite it.
+pub(crate) fn writable(path: &Path, state: &State) -> Result<Resolved, String> {
+    let resolved = resolve(path, state)?;
+    let store = Path::new(STORE_DIR).file_name().unwrap_or_default();
+    if path.components().chain(resolved.target.components()).any(|c| c.as_os_str() == store) {
+        return Err(format!(
+            "'{}' is inside {STORE_DIR}, which only the user may change. Ask the user to make this edit.",
+            path.display()
+        ));
+    }
+    Ok(resolved)
+}
+
+/// A failed file operation, explained: what is wrong and what to do.
+pub(crate) fn explain(err: &io::Error, action: &str, path: &Path) -> String {
+    let shown = path.display();
+    let kind = err.kind();
+    if kind == io::ErrorKind::PermissionDenied {
+        permission_hint(action, path)
+    } else if kind == io::ErrorKind::ReadOnlyFilesystem {
+        format!("cannot {action} '{shown}': it is on a read-only file system")
+    } else if kind == io::ErrorKind::NotFound {
+        format!("cannot {action} '{shown}': it does not exist")
+    } else {
+        format!("cannot {action} '{shown}': {err}")
+    }
+}
+
+/// Why permission was denied on `path`, and what to do about it.
+fn permission_hint(action: &str, path: &Path) -> String {
+    let shown = path.display();
+    if let Ok(meta) = fs::metadata(path)
+        && meta.permissions().readonly()
+    {
+        return format!(
+            "cannot {action} '{shown}': the file is read-only{}. Ask the user before making it writable (chmod u+w).",
+            mode(&meta)
+        );
+    }
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    if let Ok(meta) = fs::metadata(parent)
+        && meta.permissions().readonly()
+    {
+        return format!(
+            "cannot {action} '{shown}': the directory '{}' is read-only{}. Ask the user before changing it.",
+            parent.display(),
+            mode(&meta)
+        );
+    }
+    format!(
+        "cannot {action} '{shown}': permission denied (owned by another user or protected). \
+         Ask the user to fix its ownership or permissions rather than working around it."
+    )
+}
+
+/// ` (mode 644)`: the permission bits, on Unix.
+#[cfg(unix)]
+fn mode(meta: &Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt as _;
+    format!(" (mode {:o})", meta.permissions().mode() & 0o777)
+}
+
+/// Permission bits are not shown off Unix.
+#[cfg(not(unix))]
+fn mode(_meta: &Metadata) -> String {
+    String::new()
+}
+
+/// Permissions of `path` before a rewrite.
+pub(crate) fn permissions(path: &Path) -> Option<Permissions> {
+    fs::metadata(path).ok().map(|m| m.permissions())
+}
+
+/// Put back permissions lost in a rewrite (a formatter replacing the file
+/// drops the executable bit, for one).
+pub(crate) fn restore_permissions(path: &Path, before: Option<Permissions>) {
+    if let Some(perms) = before
+        && fs::metadata(path).is_ok_and(|m| m.permissions() != perms)
+    {
+        let _restored = fs::set_permissions(path, perms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_links() {
+        let dir = std::env::temp_dir().join(format!("cp_paths_{}", std::process::id()));
+        let created = fs::create_dir_all(dir.join("real"));
+        assert_eq!(created.ok(), Some(()));
+        let base = dir.canonicalize().unwrap_or_default();
+
+        #[cfg(unix)]
+        {
+            let linked = std::os::unix::fs::symlink(dir.join("real"), dir.join("link"));
+            assert_eq!(linked.ok(), Some(()));
+            let state = State::default();
+            let resolved = resolve(&dir.join("link"), &state);
+            assert_eq!(resolved.map(|r| (r.target, r.via_link)).ok(), Some((base.join("real"), true)));
+        }
+        let _removed = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn store_files_are_read_only_to_the_agent() {
+        let state = State::default();
+        for path in [".context-pilot/plugin_grants.yaml", "sub/.context-pilot/config.yaml", "src/../.context-pilot/x"] {
+            assert!(writable(Path::new(path), &state).is_err_and(|e| e.contains("only the user may change")));
+        }
+        assert!(writable(Path::new("src/context-pilot.rs"), &state).is_ok_and(|r| !r.via_link));
+        assert!(resolve(Path::new(".context-pilot/plugin_grants.yaml"), &state).is_ok_and(|r| !r.via_link));
+    }
+}
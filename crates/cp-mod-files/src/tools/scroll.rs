@@ -0,0 +1,41 @@
+use serde_json::Value;
+
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::content::window::{MAX_WINDOW_LINES, Span};
+
+/// Execute `file_scroll`: move or resize the window of a windowed file panel.
+pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    match scroll(tool, state) {
+        Ok(message) => ToolResult::new(tool.id.clone(), message, false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    }
+}
+
+/// Apply the new window; the cache worker reads it.
+fn scroll(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let id = tool.input.get("panel").and_then(Value::as_str).ok_or("Missing 'panel' parameter")?;
+    let ctx = state
+        .context
+        .iter_mut()
+        .find(|c| c.id == id && c.context_type.as_str() == Kind::FILE)
+        .ok_or_else(|| format!("No file panel '{id}'"))?;
+    let current = Span::of_panel(ctx)
+        .ok_or_else(|| format!("Panel '{id}' shows the whole file; open it with window to page through it"))?;
+    let jump = tool.input.get("line").and_then(Value::as_u64);
+    let moved = || {
+        let delta = tool.input.get("delta").and_then(Value::as_i64).unwrap_or(0);
+        current.start.saturating_add_signed(isize::try_from(delta).unwrap_or(0))
+    };
+    let start = jump.map_or_else(moved, |line| usize::try_from(line).unwrap_or(usize::MAX).saturating_sub(1));
+    let lines = tool
+        .input
+        .get("lines")
+        .and_then(Value::as_u64)
+        .map_or(current.lines, |n| usize::try_from(n).unwrap_or(MAX_WINDOW_LINES))
+        .clamp(1, MAX_WINDOW_LINES);
+    Span { start, lines }.store(ctx);
+    Ok(format!("Panel {id} now shows lines {}-{}", start.saturating_add(1), start.saturating_add(lines)))
+}
@@ -6,6 +6,34 @@ use cp_base::state::runtime::State;
 use cp_base::tools::{ToolResult, ToolUse};
 use std::fmt::Write as _;
 
+use super::format::FormatReport;
+use super::paths;
+use crate::content::encoding::{self, Format};
+
+/// Append a diff-style preview of written content (truncated for large files).
+fn push_preview(result_msg: &mut String, contents: &str) {
+    let line_count = contents.lines().count();
+    result_msg.push_str("```diff\n");
+    for (i, line) in contents.lines().enumerate() {
+        if i >= 20 {
+            let _r = writeln!(result_msg, "+ ... ({} more lines)", line_count.saturating_sub(20));
+            break;
+        }
+        let _r = writeln!(result_msg, "+ {line}");
+    }
+    result_msg.push_str("```");
+}
+
+/// Write `text` to `path` in `format`, then run the formatter; the file
+/// keeps its permission bits through both.
+pub(crate) fn save(state: &State, path: &Path, text: &str, format: Format) -> Result<Option<FormatReport>, String> {
+    let permissions = paths::permissions(path);
+    encoding::write_text(path, text, format).map_err(|e| paths::explain(&e, "write", path))?;
+    let formatted = super::format::run(state, path, text);
+    paths::restore_permissions(path, permissions);
+    Ok(formatted)
+}
+
 /// Execute the Write tool: create or overwrite a file and update context.
 pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
     let _fg = cp_base::flame!("file_write");
@@ -18,7 +46,12 @@ pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
         return ToolResult::new(tool.id.clone(), "Missing required parameter: contents".to_owned(), true);
     };
 
-    let path = Path::new(path_str);
+    let resolved = match paths::writable(Path::new(path_str), state) {
+        Ok(resolved) => resolved,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    };
+    let path = resolved.target.as_path();
+    let canonical = resolved.canonical();
     let is_new = !path.exists();
 
     // Create parent directories if needed
@@ -27,26 +60,24 @@ pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
         && !parent.exists()
         && let Err(e) = fs::create_dir_all(parent)
     {
-        return ToolResult::new(
-            tool.id.clone(),
-            format!("Failed to create directory '{}': {}", parent.display(), e),
-            true,
-        );
-    }
-
-    // Write the file
-    if let Err(e) = fs::write(path, contents) {
-        return ToolResult::new(tool.id.clone(), format!("Failed to write file '{path_str}': {e}"), true);
+        return ToolResult::new(tool.id.clone(), format!("Error: {}", paths::explain(&e, "create", parent)), true);
     }
 
-    let token_count = estimate_tokens(contents);
+    // Write the file, keeping the encoding and line endings of a file it replaces
+    let stored_as = encoding::read_text(path).map_or(Format::UTF8, |(_text, format)| format);
+    let formatted = match save(state, path, contents, stored_as) {
+        Ok(formatted) => formatted,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    };
+    let final_contents = formatted.as_ref().and_then(|f| f.content.as_deref()).unwrap_or(contents);
+    let token_count = estimate_tokens(final_contents);
     let line_count = contents.lines().count();
 
     // Check if file is already open in context
     let already_open = state
         .context
         .iter_mut()
-        .find(|c| c.context_type.as_str() == Kind::FILE && c.get_meta_str("file_path") == Some(path_str));
+        .find(|c| c.context_type.as_str() == Kind::FILE && c.get_meta_str("file_path") == Some(canonical.as_str()));
 
     if let Some(ctx) = already_open {
         // Update existing context element
@@ -64,8 +95,8 @@ pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
             cp_base::state::context::make_default_entry(&context_id, Kind::new(Kind::FILE), &file_name, true);
         elem.uid = Some(uid);
         elem.token_count = token_count;
-        elem.cached_content = Some(contents.to_owned());
-        elem.set_meta("file_path", &path_str.to_owned());
+        elem.cached_content = Some(final_contents.to_owned());
+        elem.set_meta("file_path", &canonical);
         state.context.push(elem);
 
         // Invalidate tree cache
@@ -73,18 +104,13 @@ pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
     }
 
     let action = if is_new { "Created" } else { "Wrote" };
-    let mut result_msg = format!("{action} '{path_str}' ({line_count} lines, {token_count} tokens)\n");
+    let link = resolved.link_note();
+    let mut result_msg = format!("{action} '{path_str}'{link} ({line_count} lines, {token_count} tokens)\n");
 
-    // Add diff-style preview of written content (truncated for large files)
-    result_msg.push_str("```diff\n");
-    for (i, line) in contents.lines().enumerate() {
-        if i >= 20 {
-            let _r = writeln!(result_msg, "+ ... ({} more lines)", line_count.saturating_sub(20));
-            break;
-        }
-        let _r = writeln!(result_msg, "+ {line}");
+    push_preview(&mut result_msg, contents);
+    if let Some(report) = formatted {
+        let _r = write!(result_msg, "\n{}", report.note);
     }
-    result_msg.push_str("```");
 
     ToolResult::new(tool.id.clone(), result_msg, false)
 }
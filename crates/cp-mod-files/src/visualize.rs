@@ -0,0 +1,117 @@
+//! Tool-result visualizers: colored diffs and callback summaries.
+
+/// Style one line inside a diff fenced block: red deletes, green adds,
+/// muted context.
+fn style_diff_block_line(line: &str, width: usize) -> cp_render::Block {
+    use cp_render::{Block, Semantic, Span};
+    let semantic = if line.starts_with("- ") {
+        Semantic::DiffRemove
+    } else if line.starts_with("+ ") {
+        Semantic::DiffAdd
+    } else {
+        Semantic::Muted
+    };
+    Block::Line(vec![Span::styled(truncate_line(line, width), semantic)])
+}
+
+/// Visualizer for Edit and Write tool results.
+///
+/// Parses diff blocks and renders deleted lines in red, added lines in green.
+/// Callback summary blocks get compact styled rendering (only status word colored).
+/// Non-diff content is rendered in secondary text color.
+#[must_use]
+pub(crate) fn visualize_diff(content: &str, width: usize) -> Vec<cp_render::Block> {
+    use cp_render::{Block, Span};
+
+    let mut blocks = Vec::new();
+    let mut in_diff_block = false;
+
+    for line in content.lines() {
+        // Detect diff block markers
+        if line.trim() == "```diff" {
+            in_diff_block = true;
+            continue;
+        }
+        if line.trim() == "```" && in_diff_block {
+            in_diff_block = false;
+            continue;
+        }
+
+        if line.is_empty() {
+            blocks.push(Block::empty());
+        } else if in_diff_block {
+            blocks.push(style_diff_block_line(line, width));
+        } else if let Some(styled) = style_callback_line_ir(line, width) {
+            blocks.push(styled);
+        } else {
+            // Non-diff content: plain muted text
+            blocks.push(Block::Line(vec![Span::muted(truncate_line(line, width))]));
+        }
+    }
+
+    blocks
+}
+
+/// Truncate a line to fit within the given width.
+fn truncate_line(line: &str, width: usize) -> String {
+    cp_base::ui::text::ellipsize(line, width, "\u{2026}")
+}
+
+/// Style callback-related lines in tool results using IR blocks.
+/// Format: "Callbacks:" header, "· name passed/FAILED/TIMED OUT ...", "    error line"
+fn style_callback_line_ir(line: &str, width: usize) -> Option<cp_render::Block> {
+    use cp_render::{Block, Semantic, Span};
+
+    let trimmed = line.trim();
+
+    // "Callbacks:" header
+    if trimmed == "Callbacks:" {
+        return Some(Block::Line(vec![Span::muted(truncate_line(trimmed, width))]));
+    }
+
+    // "· name passed ..." or "· name FAILED ..." etc.
+    if let Some(rest) = trimmed.strip_prefix("\u{b7} ") {
+        let mut spans = vec![Span::muted("\u{b7} ".to_owned())];
+
+        let status_patterns: &[(&str, Semantic)] = &[
+            (" passed", Semantic::Success),
+            (" FAILED", Semantic::Error),
+            (" TIMED OUT", Semantic::Error),
+            (" dispatched", Semantic::Info),
+            (" skipped", Semantic::Muted),
+        ];
+
+        let mut matched = false;
+        for &(pattern, semantic) in status_patterns {
+            if let Some(pos) = rest.find(pattern) {
+                let name = rest.get(..pos).unwrap_or("");
+                spans.push(Span::muted(name.to_owned()));
+                spans.push(Span::styled(pattern.to_owned(), semantic));
+                let after_start = pos.saturating_add(pattern.len());
+                if let Some(after) = rest.get(after_start..)
+                    && !after.is_empty()
+                {
+                    spans.push(Span::muted(after.to_owned()));
+                }
+                matched = true;
+                break;
+            }
+        }
+        if !matched {
+            spans.push(Span::muted(rest.to_owned()));
+        }
+        return Some(Block::line(spans));
+    }
+
+    // Indented error lines (4 spaces)
+    if line.starts_with("    ") && !line.trim().is_empty() {
+        return Some(Block::Line(vec![Span::error(truncate_line(line, width))]));
+    }
+
+    // [skip_callbacks warnings: ...]
+    if trimmed.starts_with("[skip_callbacks warnings:") {
+        return Some(Block::Line(vec![Span::warning(truncate_line(trimmed, width))]));
+    }
+
+    None
+}
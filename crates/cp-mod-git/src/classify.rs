@@ -118,6 +118,26 @@ pub(crate) fn validate_git_command(command: &str) -> Result<Vec<String>, String>
     Ok(args)
 }
 
+/// Global options (before the subcommand) whose value is the next arg.
+const GLOBAL_VALUE_OPTIONS: &[&str] =
+    &["-c", "-C", "--git-dir", "--work-tree", "--namespace", "--config-env", "--super-prefix"];
+
+/// Position of the subcommand in `args` (without `git`), global options such
+/// as `-c k=v`, `-C dir`, `--no-pager` or `--git-dir=...` skipped.
+pub(crate) fn subcommand_index(args: &[String]) -> Option<usize> {
+    let mut skip_value = false;
+    for (i, arg) in args.iter().enumerate() {
+        if skip_value {
+            skip_value = false;
+        } else if arg.starts_with('-') {
+            skip_value = GLOBAL_VALUE_OPTIONS.contains(&arg.as_str());
+        } else {
+            return Some(i);
+        }
+    }
+    None
+}
+
 /// Git subcommands that only ever read repository state.
 fn is_read_only_subcmd(subcmd: &str) -> bool {
     matches!(
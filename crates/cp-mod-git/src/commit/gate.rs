@@ -0,0 +1,185 @@
+//! Pre-commit gate: configured checks that must pass before `git commit`.
+//!
+//! The user sets the checks with `/commit-gate` (e.g. `cargo fmt --check`,
+//! `cargo clippy`, `cargo test`); no tool changes them, so the agent cannot
+//! switch off the gate meant to check its own commits. While any are set, a
+//! `git_execute` commit runs them in order as console jobs from the tool's
+//! worker thread; the first failure aborts the commit and its excerpt becomes
+//! the tool result, so the agent sees what to fix. Git's `--no-verify` only
+//! skips git hooks, never this gate.
+
+use std::time::{Duration, Instant};
+
+use cp_base::state::runtime::State;
+use cp_base::tools::failures;
+use cp_mod_console::manager::SessionHandle;
+
+use crate::classify::subcommand_index;
+use crate::types::GitState;
+
+/// Max execution time of one check (test suites can be slow).
+pub(crate) const CHECK_TIMEOUT_SECS: u64 = 600;
+
+/// Console session key of the running check (one at a time, log reused).
+const SESSION: &str = "commit-gate";
+
+/// How often the worker looks at the running check.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Whether the parsed git args (without `git`) make a commit, global options
+/// before the subcommand included.
+pub(crate) fn is_commit(args: &[String]) -> bool {
+    subcommand_index(args).and_then(|i| args.get(i)).is_some_and(|a| a == "commit")
+}
+
+/// Async budget for the checks, on top of the commit itself.
+pub(crate) fn budget_secs(checks: &[String]) -> u64 {
+    u64::try_from(checks.len()).unwrap_or(u64::MAX).saturating_mul(CHECK_TIMEOUT_SECS)
+}
+
+/// How one check ended.
+#[derive(Debug)]
+pub(crate) enum CheckRun {
+    /// Exit code 0.
+    Passed,
+    /// Non-zero exit, with the job's output.
+    Failed {
+        /// Exit code (-1 when killed).
+        code: i32,
+        /// Full output of the job.
+        output: String,
+    },
+    /// Still running after [`CHECK_TIMEOUT_SECS`]; the job was killed.
+    TimedOut {
+        /// Output up to the kill.
+        output: String,
+    },
+    /// The job could not be started.
+    NotStarted(String),
+}
+
+/// Run the checks in order through `run`. The first failure is returned as
+/// the message that replaces the commit's output.
+pub(crate) fn run_checks(checks: &[String], mut run: impl FnMut(&str) -> CheckRun) -> Result<(), String> {
+    for check in checks {
+        let (why, output) = match run(check) {
+            CheckRun::Passed => continue,
+            CheckRun::Failed { code, output } => (format!("failed (exit {code})"), output),
+            CheckRun::TimedOut { output } => (format!("timed out after {CHECK_TIMEOUT_SECS}s"), output),
+            CheckRun::NotStarted(e) => {
+                return Err(format!("Commit aborted: pre-commit check `{check}` could not run: {e}"));
+            }
+        };
+        return Err(format!(
+            "Commit aborted: pre-commit check `{check}` {why}.\n{}\nFix the failures, then commit again.",
+            failures::excerpt(&output),
+        ));
+    }
+    Ok(())
+}
+
+/// Run one check as a console job from the project root and wait for it
+/// (blocking: worker thread only).
+pub(crate) fn run_in_console(check: &str) -> CheckRun {
+    let cwd = std::env::current_dir().ok().map(|d| d.to_string_lossy().to_string());
+    let handle = match SessionHandle::spawn(SESSION.to_owned(), check.to_owned(), cwd) {
+        Ok(handle) => handle,
+        Err(e) => return CheckRun::NotStarted(e),
+    };
+    let deadline = Instant::now().checked_add(Duration::from_secs(CHECK_TIMEOUT_SECS));
+    while !handle.get_status().is_terminal() {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            handle.kill();
+            return CheckRun::TimedOut { output: job_output(&handle) };
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    match handle.exit_code() {
+        Some(0) => CheckRun::Passed,
+        code => CheckRun::Failed { code: code.unwrap_or(-1), output: job_output(&handle) },
+    }
+}
+
+/// Everything the job printed. The log is complete at exit; the ring buffer
+/// may still be catching up.
+fn job_output(handle: &SessionHandle) -> String {
+    std::fs::read_to_string(&handle.log_path).unwrap_or_else(|_| handle.buffer.read_all().0)
+}
+
+/// Parse `/commit-gate` arguments: checks separated by `;`, or `off`.
+pub(crate) fn parse_checks(args: &str) -> Vec<String> {
+    if args.trim() == "off" {
+        return Vec::new();
+    }
+    args.split(';').map(str::trim).filter(|c| !c.is_empty()).map(str::to_owned).collect()
+}
+
+/// Set (or clear, with an empty list) the checks run before every commit.
+/// User-side only.
+pub(crate) fn set_checks(state: &mut State, checks: Vec<String>) -> String {
+    let message = if checks.is_empty() {
+        "Pre-commit gate off: commits run without checks.".to_owned()
+    } else {
+        let listed: Vec<String> = checks.iter().map(|c| format!("`{c}`")).collect();
+        format!("Pre-commit gate on: every commit first runs {}.", listed.join(", "))
+    };
+    GitState::get_mut(state).commit_checks = checks;
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Owned args from `list`.
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|&a| a.to_owned()).collect()
+    }
+
+    #[test]
+    fn global_options_before_commit_are_skipped() {
+        assert!(is_commit(&args(&["commit", "-m", "x"])));
+        assert!(is_commit(&args(&["-c", "core.hooksPath=/dev/null", "commit", "-m", "x"])));
+        assert!(is_commit(&args(&["--no-pager", "-C", "sub", "commit"])));
+        assert!(is_commit(&args(&["--git-dir=.git", "commit", "--no-verify", "-m", "x"])));
+        assert!(!is_commit(&args(&["-c", "commit", "log"])));
+        assert!(!is_commit(&args(&["log", "--grep", "commit"])));
+    }
+
+    #[test]
+    fn checks_stop_at_the_first_failure() {
+        let checks = args(&["cargo fmt --check", "cargo test", "cargo clippy"]);
+        let mut ran = Vec::new();
+        let result = run_checks(&checks, |check| {
+            ran.push(check.to_owned());
+            if check == "cargo test" {
+                CheckRun::Failed { code: 101i32, output: "test parse ... FAILED".to_owned() }
+            } else {
+                CheckRun::Passed
+            }
+        });
+        assert_eq!(ran, args(&["cargo fmt --check", "cargo test"]));
+        assert!(result.is_err_and(|e| e.contains("`cargo test` failed (exit 101)") && e.contains("FAILED")));
+    }
+
+    #[test]
+    fn passing_or_absent_checks_let_the_commit_through() {
+        assert_eq!(run_checks(&args(&["true", "true"]), |_| CheckRun::Passed), Ok(()));
+        assert_eq!(run_checks(&[], |_| CheckRun::NotStarted("unused".to_owned())), Ok(()));
+    }
+
+    #[test]
+    fn a_check_over_its_budget_aborts_the_commit() {
+        let timed_out = run_checks(&args(&["cargo test"]), |_| CheckRun::TimedOut { output: "running 3 tests".into() });
+        assert!(timed_out.is_err_and(|e| e.contains(&format!("timed out after {CHECK_TIMEOUT_SECS}s"))));
+        let unstarted = run_checks(&args(&["cargo test"]), |_| CheckRun::NotStarted("no console server".into()));
+        assert!(unstarted.is_err_and(|e| e.contains("could not run: no console server")));
+        assert_eq!(budget_secs(&args(&["a", "b"])), CHECK_TIMEOUT_SECS.saturating_mul(2));
+    }
+
+    #[test]
+    fn gate_checks_are_separated_by_semicolons() {
+        assert_eq!(parse_checks("cargo fmt --check; cargo test ;"), args(&["cargo fmt --check", "cargo test"]));
+        assert!(parse_checks("off").is_empty());
+    }
+}
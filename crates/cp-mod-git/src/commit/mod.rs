@@ -0,0 +1,6 @@
+//! Commit guards applied to `git commit` before it runs.
+
+/// Pre-commit gate: checks run before every commit.
+pub(crate) mod gate;
+/// Commit-message policy: validation and cheap-model reformatting.
+pub(crate) mod policy;
@@ -0,0 +1,361 @@
+//! Commit-message policy: rules every `git commit -m` message must follow.
+//!
+//! The user sets the rules with `/commit-policy` (a subject regex —
+//! `conventional` for Conventional Commits —, a max subject length, a
+//! required ticket ID); no tool changes them. A message that breaks one is
+//! handed to the cheap model for a rewrite on the tool's worker thread; the
+//! rewrite is committed only if it passes, otherwise the commit is rejected
+//! with the problems found.
+
+use regex::Regex;
+use serde_json::json;
+
+use cp_base::config::INJECTIONS;
+use cp_base::state::data::model_helpers::ModelPricing as _;
+use cp_base::state::runtime::State;
+use cp_base::tools::output_schema::{OutputSchema, StructuredQuery, StructuredRunner};
+
+use crate::classify::subcommand_index;
+use crate::types::{CommitPolicy, GitState};
+
+/// Conventional Commits subject: `type(scope)!: description`.
+pub(crate) const CONVENTIONAL: &str =
+    r"^(feat|fix|docs|style|refactor|perf|test|build|ci|chore|revert)(\([\w./-]+\))?!?: \S";
+
+/// Async budget of the reformat query, on top of the commit itself.
+pub(crate) const REFORMAT_BUDGET_SECS: u64 = 60;
+
+/// Short flags that may precede `m` in a cluster such as `-am`.
+const CLUSTER_FLAGS: &str = "anpqsv";
+
+/// A message flag found in the commit args.
+struct MessageFlag {
+    /// Other short flags of the cluster (`-a` of `-am`), kept as they are.
+    kept: Option<String>,
+    /// Message glued to the flag; `None` when it is the next arg.
+    inline: Option<String>,
+}
+
+/// Recognize `-m`, `-m<msg>`, `-am`, `--message` and `--message=<msg>`.
+fn message_flag(arg: &str) -> Option<MessageFlag> {
+    if let Some(text) = arg.strip_prefix("--message=") {
+        return Some(MessageFlag { kept: None, inline: Some(text.to_owned()) });
+    }
+    if arg == "--message" {
+        return Some(MessageFlag { kept: None, inline: None });
+    }
+    let cluster = arg.strip_prefix('-').filter(|c| !c.starts_with('-'))?;
+    let (before, after) = cluster.split_once('m')?;
+    if !before.chars().all(|c| CLUSTER_FLAGS.contains(c)) {
+        return None;
+    }
+    let kept = (!before.is_empty()).then(|| format!("-{before}"));
+    let inline = (!after.is_empty()).then(|| after.to_owned());
+    Some(MessageFlag { kept, inline })
+}
+
+/// The message of a `git commit`, split from its other args.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct CommitMessage {
+    /// Global options and `commit` itself, kept in front.
+    head: Vec<String>,
+    /// The args after `commit`, without the message flags.
+    rest: Vec<String>,
+    /// The message; several `-m` make separate paragraphs, as in git.
+    text: String,
+}
+
+impl CommitMessage {
+    /// Split the message out of commit `args`; `None` when it is not given on
+    /// the command line (`-F`, `--no-edit`, an editor).
+    pub(crate) fn split(args: &[String]) -> Option<Self> {
+        let at = subcommand_index(args)?;
+        let (head, tail) = args.split_at(at.saturating_add(1));
+        let mut rest = Vec::new();
+        let mut paragraphs: Vec<String> = Vec::new();
+        let mut iter = tail.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--" {
+                rest.push(arg.clone());
+                rest.extend(iter.by_ref().cloned());
+            } else if let Some(flag) = message_flag(arg) {
+                rest.extend(flag.kept);
+                paragraphs.extend(flag.inline.or_else(|| iter.next().cloned()));
+            } else {
+                rest.push(arg.clone());
+            }
+        }
+        (!paragraphs.is_empty()).then(|| Self { head: head.to_vec(), rest, text: paragraphs.join("\n\n") })
+    }
+
+    /// The commit args with `text` as the only message.
+    pub(crate) fn args_with(&self, text: &str) -> Vec<String> {
+        let mut args = self.head.clone();
+        args.push("-m".to_owned());
+        args.push(text.to_owned());
+        args.extend(self.rest.iter().cloned());
+        args
+    }
+}
+
+/// Whether `pattern` compiles and matches `text`.
+fn matches(pattern: &str, text: &str) -> bool {
+    Regex::new(pattern).is_ok_and(|re| re.is_match(text))
+}
+
+/// The rules of `policy` that `message` breaks, worded for the agent.
+pub(crate) fn violations(policy: &CommitPolicy, message: &str) -> Vec<String> {
+    let subject = message.lines().next().unwrap_or_default();
+    let mut problems = Vec::new();
+    if let Some(pattern) = policy.subject_pattern.as_deref()
+        && !matches(pattern, subject)
+    {
+        problems.push(format!("the subject `{subject}` does not match `{pattern}`"));
+    }
+    let length = subject.chars().count();
+    if let Some(max) = policy.max_subject_length
+        && length > max
+    {
+        problems.push(format!("the subject is {length} characters long (max {max})"));
+    }
+    if let Some(pattern) = policy.ticket_pattern.as_deref()
+        && !matches(pattern, message)
+    {
+        problems.push(format!("no ticket ID matching `{pattern}`"));
+    }
+    problems
+}
+
+/// One line per rule of `policy`.
+fn rule_lines(policy: &CommitPolicy) -> Vec<String> {
+    let mut rules = Vec::new();
+    if let Some(pattern) = policy.subject_pattern.as_deref() {
+        rules.push(format!("- the subject line matches the regex `{pattern}`"));
+    }
+    if let Some(max) = policy.max_subject_length {
+        rules.push(format!("- the subject line has at most {max} characters"));
+    }
+    if let Some(pattern) = policy.ticket_pattern.as_deref() {
+        rules.push(format!("- the message contains a ticket ID matching the regex `{pattern}`"));
+    }
+    rules
+}
+
+/// Compact one-line form of `policy`, for the overview.
+pub(crate) fn summary(policy: &CommitPolicy) -> String {
+    let mut parts = Vec::new();
+    if let Some(pattern) = policy.subject_pattern.as_deref() {
+        parts.push(format!("subject `{pattern}`"));
+    }
+    if let Some(max) = policy.max_subject_length {
+        parts.push(format!("subject \u{2264} {max} chars"));
+    }
+    if let Some(pattern) = policy.ticket_pattern.as_deref() {
+        parts.push(format!("ticket `{pattern}`"));
+    }
+    parts.join(" | ")
+}
+
+/// Shape of the reformat answer.
+fn message_schema() -> OutputSchema {
+    OutputSchema::new(
+        "commit_message",
+        json!({
+            "type": "object",
+            "properties": {"message": {"type": "string"}},
+            "required": ["message"],
+            "additionalProperties": false
+        }),
+    )
+}
+
+/// A commit whose message breaks the policy, with everything needed to ask
+/// the cheap model for a compliant rewrite off the main thread.
+#[derive(Debug)]
+pub(crate) struct Rewrite {
+    /// The commit and its message.
+    message: CommitMessage,
+    /// The policy the rewrite must pass.
+    policy: CommitPolicy,
+    /// What is wrong with the original message.
+    problems: Vec<String>,
+    /// The reformat query.
+    query: StructuredQuery,
+    /// Runs the query (the binary owns the LLM clients).
+    run: StructuredRunner,
+}
+
+impl Rewrite {
+    /// The rewrite a commit needs; `None` when there is no policy, the message
+    /// is not on the command line, or it already follows the policy.
+    pub(crate) fn check(state: &State, args: &[String], run: StructuredRunner) -> Option<Self> {
+        let policy = GitState::get(state).commit_policy.clone();
+        if policy.is_empty() {
+            return None;
+        }
+        let split = CommitMessage::split(args)?;
+        let found = violations(&policy, &split.text);
+        if found.is_empty() {
+            return None;
+        }
+        let prompt = INJECTIONS
+            .providers
+            .commit_message
+            .replace("{rules}", &rule_lines(&policy).join("\n"))
+            .replace("{problems}", &found.join("\n"))
+            .replace("{message}", &split.text);
+        let query = StructuredQuery {
+            provider: state.llm_provider,
+            model: state.cheap_model(),
+            system: INJECTIONS.providers.commit_message_system.clone(),
+            prompt,
+            schema: message_schema(),
+            worker_id: "commit-message".to_owned(),
+        };
+        Some(Self { message: split, policy, problems: found, query, run })
+    }
+
+    /// Ask for the rewrite (blocking) and return the commit args carrying it
+    /// plus the new message, or the rejection shown to the agent.
+    pub(crate) fn apply(self) -> Result<(Vec<String>, String), String> {
+        let answer = (self.run)(&self.query)
+            .map(|value| value.get("message").and_then(|m| m.as_str()).unwrap_or_default().trim().to_owned());
+        let remaining = match answer.as_deref() {
+            Ok(text) => violations(&self.policy, text),
+            Err(e) => vec![format!("the reformat attempt failed: {e}")],
+        };
+        match answer {
+            Ok(text) if remaining.is_empty() => Ok((self.message.args_with(&text), text)),
+            Ok(_) | Err(_) => Err(format!(
+                "Commit aborted: the message breaks the commit policy:\n- {}\nAutomatic reformat did not fix it:\n- {}\nRewrite the message, then commit again.",
+                self.problems.join("\n- "),
+                remaining.join("\n- "),
+            )),
+        }
+    }
+}
+
+/// A regex rule from `/commit-policy`; `conventional` stands for
+/// [`CONVENTIONAL`]. Errors name the bad pattern.
+fn regex_rule(name: &str, raw: &str) -> Result<String, String> {
+    let pattern = if raw == "conventional" { CONVENTIONAL } else { raw };
+    let _re = Regex::new(pattern).map_err(|e| format!("invalid {name} regex: {e}"))?;
+    Ok(pattern.to_owned())
+}
+
+/// Parse `/commit-policy` arguments: `off`, or any of `subject=<regex>`,
+/// `max=<n>` and `ticket=<regex>`.
+pub(crate) fn parse_rules(args: &str) -> Result<CommitPolicy, String> {
+    let mut policy = CommitPolicy::new();
+    if args.trim() == "off" {
+        return Ok(policy);
+    }
+    for word in args.split_whitespace() {
+        let Some((key, value)) = word.split_once('=') else {
+            return Err(format!("'{word}': use subject=<regex>, max=<n>, ticket=<regex> or off"));
+        };
+        match key {
+            "subject" => policy.subject_pattern = Some(regex_rule(key, value)?),
+            "ticket" => policy.ticket_pattern = Some(regex_rule(key, value)?),
+            "max" => {
+                policy.max_subject_length =
+                    Some(value.parse().ok().filter(|&n| n > 0).ok_or_else(|| format!("'{value}' is not a length"))?);
+            }
+            _ => return Err(format!("'{key}': use subject, max or ticket")),
+        }
+    }
+    if policy.is_empty() {
+        return Err("usage: /commit-policy [subject=<regex>|conventional] [max=<n>] [ticket=<regex>] | off".to_owned());
+    }
+    Ok(policy)
+}
+
+/// Set the commit-message policy; an empty one turns it off. User-side only.
+pub(crate) fn set_policy(state: &mut State, policy: CommitPolicy) -> String {
+    let message = if policy.is_empty() {
+        "Commit policy off: commit messages are not checked.".to_owned()
+    } else {
+        format!("Commit policy on: every `git commit -m` message must follow\n{}", rule_lines(&policy).join("\n"))
+    };
+    GitState::get_mut(state).commit_policy = policy;
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|&a| a.to_owned()).collect()
+    }
+
+    /// A reformat runner: the model answers for "tidy parser" and is
+    /// unreachable for anything else.
+    fn reformat(query: &StructuredQuery) -> Result<serde_json::Value, String> {
+        if query.prompt.contains("tidy parser") {
+            Ok(json!({"message": "fix: tidy the parser"}))
+        } else {
+            Err("offline".to_owned())
+        }
+    }
+
+    /// A state whose policy requires Conventional Commits subjects.
+    fn policed() -> State {
+        let mut state = State::default();
+        state.set_ext(GitState::new());
+        GitState::get_mut(&mut state).commit_policy =
+            CommitPolicy { subject_pattern: Some(CONVENTIONAL.to_owned()), ..CommitPolicy::new() };
+        state
+    }
+
+    #[test]
+    fn split_collects_message_flags() {
+        let split = CommitMessage::split(&args(&["commit", "-am", "fix: a", "-m", "body", "--", "-m"]));
+        let expected = CommitMessage {
+            head: args(&["commit"]),
+            rest: args(&["-a", "--", "-m"]),
+            text: "fix: a\n\nbody".to_owned(),
+        };
+        assert_eq!(split, Some(expected));
+        assert_eq!(CommitMessage::split(&args(&["commit", "--no-edit", "--amend"])), None);
+        let inline = CommitMessage::split(&args(&["commit", "--message=wip"])).map(|m| m.args_with("chore: wip"));
+        assert_eq!(inline, Some(args(&["commit", "-m", "chore: wip"])));
+    }
+
+    #[test]
+    fn violations_name_each_broken_rule() {
+        let policy = CommitPolicy {
+            subject_pattern: Some(CONVENTIONAL.to_owned()),
+            max_subject_length: Some(20),
+            ticket_pattern: Some(r"[A-Z]+-\d+".to_owned()),
+        };
+        assert!(violations(&policy, "feat(git): add X\n\nRefs CP-12").is_empty());
+        assert_eq!(violations(&policy, "Added a rather long subject line").len(), 3);
+    }
+
+    #[test]
+    fn commits_behind_global_options_are_still_rewritten_or_refused() {
+        let state = policed();
+        let commit = args(&["-c", "user.name=x", "commit", "-m", "tidy parser"]);
+        let rewritten = Rewrite::check(&state, &commit, reformat).map(Rewrite::apply);
+        let expected = args(&["-c", "user.name=x", "commit", "-m", "fix: tidy the parser"]);
+        assert_eq!(rewritten, Some(Ok((expected, "fix: tidy the parser".to_owned()))));
+        let refused = Rewrite::check(&state, &args(&["--no-pager", "commit", "-m", "tidy"]), reformat);
+        assert!(refused.map(Rewrite::apply).is_some_and(|r| r.is_err_and(|e| e.starts_with("Commit aborted"))));
+    }
+
+    #[test]
+    fn policy_rules_parse_from_the_command_words() {
+        let policy = parse_rules(r"subject=conventional max=72 ticket=[A-Z]+-\d+");
+        let expected = CommitPolicy {
+            subject_pattern: Some(CONVENTIONAL.to_owned()),
+            max_subject_length: Some(72),
+            ticket_pattern: Some(r"[A-Z]+-\d+".to_owned()),
+        };
+        assert_eq!(policy, Ok(expected));
+        assert_eq!(parse_rules("off"), Ok(CommitPolicy::new()));
+        assert!(parse_rules("max=0").is_err_and(|e| e.contains("not a length")));
+        assert!(parse_rules("subject=(").is_err_and(|e| e.contains("invalid subject regex")));
+        assert!(parse_rules("").is_err_and(|e| e.starts_with("usage")));
+    }
+}
@@ -1,16 +1,27 @@
 //! Git module — version control integration via the `git` CLI.
 //!
-//! One tool: `git_execute`. Read-only commands (log, diff, status, etc.) create
-//! auto-refreshing dynamic panels. Mutating commands (commit, push, merge, etc.)
-//! execute directly and return output. Shell operators are blocked for safety.
+//! Tools: `git_execute` and the branch workflow (`git_branch_create`,
+//! `git_switch`, `git_stash`). The pre-commit gate, the commit-message policy
+//! and the edit guard (warnings before editing files owned by others) are set
+//! by the user only, through [`set_commit_checks`], [`set_commit_policy`],
+//! [`set_edit_guard`] and [`approve_edit`]; the edits the guard refuses wait
+//! for the user's decision in the Approvals panel.
+//! Read-only commands (log, diff, status, etc.) create auto-refreshing dynamic
+//! panels. Mutating commands (commit, push, merge, etc.) execute directly and
+//! return output; commit messages must follow the commit policy and commits
+//! first pass the configured pre-commit checks. Shell operators are blocked.
 
 /// Cache invalidation rules for git result panels.
 pub(crate) mod cache_invalidation;
 /// Git command classification (read-only vs mutating).
 mod classify;
-/// Panel implementation for displaying git command results.
-mod result_panel;
-/// Tool execution logic for `git_execute`.
+/// Commit guards: pre-commit checks and the message policy.
+mod commit;
+/// Panels: git command results and the activity feed.
+mod panels;
+/// Rendering: `git_execute` result coloring and the overview changes table.
+mod render;
+/// Tool execution logic for `git_execute` and the branch workflow tools.
 mod tools;
 /// Git state types: `GitState`, `GitFileChange`, `GitChangeType`.
 pub mod types;
@@ -136,16 +147,56 @@ pub fn refresh_git_status(state: &mut State) {
     GitState::get_mut(state).file_changes = file_changes;
 }
 
-/// Timeout for git commands (seconds)
-pub const GIT_CMD_TIMEOUT_SECS: u64 = 30;
+/// Edit-guard verdict for an `Edit` or `Write` call; run by the binary's
+/// pre-flight, since the files module owns those tools.
+#[must_use]
+pub fn edit_verdict(tool: &ToolUse, state: &State) -> Option<Verdict> {
+    tools::ownership::edit_verdict(tool, state)
+}
+
+/// Set the pre-commit checks from `/commit-gate` arguments (`;`-separated,
+/// or `off`). User-side only: no tool calls this.
+pub fn set_commit_checks(state: &mut State, args: &str) -> String {
+    commit::gate::set_checks(state, commit::gate::parse_checks(args))
+}
 
-/// Hard byte cap on the git section of the Overview panel (context + content).
+/// Set the commit-message policy from `/commit-policy` arguments. User-side
+/// only: no tool calls this.
+///
+/// # Errors
 ///
-/// A broken `.gitignore` (commonly during a rebase) can make `git` report
-/// thousands of changed files; without a cap the per-file table grows to 1M+
-/// tokens and destroys the agent's context. Beyond this budget the section is
-/// truncated with a `(Rest hidden, N kB left)` marker.
-const GIT_OVERVIEW_CAP_BYTES: usize = 8 * 1024;
+/// Returns a message naming the bad rule.
+pub fn set_commit_policy(state: &mut State, args: &str) -> Result<String, String> {
+    commit::policy::parse_rules(args).map(|policy| commit::policy::set_policy(state, policy))
+}
+
+/// Turn the edit guard on (`Some`) or off. User-side only: no tool calls this.
+pub fn set_edit_guard(state: &mut State, guard: Option<types::EditGuard>) -> String {
+    tools::ownership::set_guard(state, guard)
+}
+
+/// Approve editing `path` despite the edit guard. User-side only.
+pub fn approve_edit(state: &mut State, path: &str) -> String {
+    tools::ownership::approve(state, path)
+}
+
+/// List an `Edit` or `Write` the edit guard refused in the Approvals panel;
+/// run by the binary's pipeline on every pre-flight refusal.
+pub fn request_approval(state: &mut State, tool: &ToolUse, verdict: &Verdict) {
+    tools::ownership::request_approval(state, tool, verdict);
+}
+
+/// Apply an Approvals panel key; the user's decision to tell the agent, if
+/// one was made.
+pub fn approval_step(state: &mut State, step: cp_base::state::actions::ApprovalMove) -> Option<String> {
+    tools::ownership::step(state, step)
+}
+
+/// Days of history the edit guard searches when the user gives none.
+pub const EDIT_GUARD_DEFAULT_DAYS: u64 = tools::ownership::DEFAULT_RECENT_DAYS;
+
+/// Timeout for git commands (seconds)
+pub const GIT_CMD_TIMEOUT_SECS: u64 = 30;
 
 use serde_json::json;
 
@@ -153,10 +204,14 @@ use cp_base::modules::ToolVisualizer;
 use cp_base::panels::Panel;
 use cp_base::state::context::Kind;
 use cp_base::state::runtime::State;
+use cp_base::tools::output_schema::StructuredRunner;
+use cp_base::tools::pre_flight::Verdict;
 use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
 use cp_base::tools::{ToolResult, ToolUse};
 
-use self::result_panel::GitResultPanel;
+use self::panels::activity::ActivityPanel;
+use self::panels::approvals::ApprovalsPanel;
+use self::panels::result::GitResultPanel;
 use cp_base::modules::Module;
 
 /// Parsed tool description YAML for the git module.
@@ -165,20 +220,16 @@ static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
 
 /// Git module: version control tools, status tracking, and result panels.
 #[derive(Debug, Clone, Copy)]
-pub struct GitModule;
-
-impl Default for GitModule {
-    fn default() -> Self {
-        Self::new()
-    }
+pub struct GitModule {
+    /// Runs the commit-message reformat query (the binary owns the LLM clients).
+    run: StructuredRunner,
 }
 
 impl GitModule {
-    /// Construct the module marker (funnels cross-crate construction of this
-    /// `non_exhaustive` unit struct through an associated fn).
+    /// Construct the module with the runner used to reformat commit messages.
     #[must_use]
-    pub const fn new() -> Self {
-        Self
+    pub const fn new(run: StructuredRunner) -> Self {
+        Self { run }
     }
 }
 
@@ -205,6 +256,9 @@ impl Module for GitModule {
         let gs = GitState::get(state);
         json!({
             "git_diff_base": gs.diff_base,
+            "commit_checks": gs.commit_checks,
+            "commit_policy": gs.commit_policy,
+            "edit_guard": gs.edit_guard,
         })
     }
 
@@ -212,10 +266,19 @@ impl Module for GitModule {
         if let Some(v) = data.get("git_diff_base").and_then(|v| v.as_str()) {
             GitState::get_mut(state).diff_base = Some(v.to_owned());
         }
+        if let Some(Ok(checks)) = data.get("commit_checks").map(|v| serde_json::from_value(v.clone())) {
+            GitState::get_mut(state).commit_checks = checks;
+        }
+        if let Some(Ok(policy)) = data.get("commit_policy").map(|v| serde_json::from_value(v.clone())) {
+            GitState::get_mut(state).commit_policy = policy;
+        }
+        if let Some(Ok(guard)) = data.get("edit_guard").map(|v| serde_json::from_value(v.clone())) {
+            GitState::get_mut(state).edit_guard = guard;
+        }
     }
 
     fn fixed_panel_types(&self) -> Vec<Kind> {
-        vec![]
+        vec![Kind::new(Kind::GIT_ACTIVITY), Kind::new(Kind::APPROVALS)]
     }
 
     fn dynamic_panel_types(&self) -> Vec<Kind> {
@@ -223,12 +286,14 @@ impl Module for GitModule {
     }
 
     fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
-        vec![]
+        vec![(Kind::new(Kind::GIT_ACTIVITY), "Activity", false), (Kind::new(Kind::APPROVALS), "Approvals", false)]
     }
 
     fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
         match context_type.as_str() {
             Kind::GIT_RESULT => Some(Box::new(GitResultPanel)),
+            Kind::GIT_ACTIVITY => Some(Box::new(ActivityPanel)),
+            Kind::APPROVALS => Some(Box::new(ApprovalsPanel)),
             _ => None,
         }
     }
@@ -241,31 +306,76 @@ impl Module for GitModule {
                 .category("Git")
                 .param("command", ParamType::String, true)
                 .build(),
+            ToolDefinition::from_yaml("git_branch_create", t)
+                .short_desc("Create a branch and switch to it")
+                .category("Git")
+                .param("name", ParamType::String, true)
+                .param("from", ParamType::String, false)
+                .param("switch", ParamType::Boolean, false)
+                .build(),
+            ToolDefinition::from_yaml("git_switch", t)
+                .short_desc("Switch to a branch")
+                .category("Git")
+                .param("branch", ParamType::String, true)
+                .build(),
+            ToolDefinition::from_yaml("git_stash", t)
+                .short_desc("Stash or restore uncommitted changes")
+                .category("Git")
+                .param_enum("action", tools::branch::STASH_ACTIONS, false)
+                .param("message", ParamType::String, false)
+                .param("include_untracked", ParamType::Boolean, false)
+                .param("index", ParamType::Integer, false)
+                .build(),
         ]
     }
 
     fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
         match tool.name.as_str() {
-            "git_execute" => Some(tools::execute_git_command(tool, state)),
+            "git_execute" => Some(tools::execute_git_command(tool, state, self.run)),
+            "git_branch_create" => Some(tools::branch::execute_branch_create(tool, state)),
+            "git_switch" => Some(tools::branch::execute_switch(tool, state)),
+            "git_stash" => Some(tools::branch::execute_stash(tool, state)),
             _ => None,
         }
     }
 
     fn tool_visualizers(&self) -> Vec<(&'static str, ToolVisualizer)> {
-        vec![("git_execute", visualize_git_output)]
+        vec![("git_execute", render::visualize_git_output)]
     }
 
     fn context_type_metadata(&self) -> Vec<cp_base::state::context::TypeMeta> {
-        vec![cp_base::state::context::TypeMeta {
-            context_type: "git_result",
-            icon_id: "git",
-            is_fixed: false,
-            needs_cache: true,
-            fixed_order: None,
-            display_name: "git-result",
-            short_name: "git-cmd",
-            needs_async_wait: false,
-        }]
+        vec![
+            cp_base::state::context::TypeMeta {
+                context_type: "git_result",
+                icon_id: "git",
+                is_fixed: false,
+                needs_cache: true,
+                fixed_order: None,
+                display_name: "git-result",
+                short_name: "git-cmd",
+                needs_async_wait: false,
+            },
+            cp_base::state::context::TypeMeta {
+                context_type: Kind::GIT_ACTIVITY,
+                icon_id: "git",
+                is_fixed: true,
+                needs_cache: true,
+                fixed_order: Some(12),
+                display_name: "activity",
+                short_name: "activity",
+                needs_async_wait: false,
+            },
+            cp_base::state::context::TypeMeta {
+                context_type: Kind::APPROVALS,
+                icon_id: "approvals",
+                is_fixed: true,
+                needs_cache: false,
+                fixed_order: Some(17),
+                display_name: "approvals",
+                short_name: "approvals",
+                needs_async_wait: false,
+            },
+        ]
     }
 
     fn context_detail(&self, ctx: &cp_base::state::context::Entry) -> Option<String> {
@@ -282,12 +392,13 @@ impl Module for GitModule {
         if let Some(branch) = gs.branch.as_ref() {
             let _r = write!(output, "\nGit Branch: {branch}\n");
         }
+        render::push_guards(&mut output, gs);
         if gs.file_changes.is_empty() {
             output.push_str("Git Status: Working tree clean\n");
         } else {
-            push_changes_table(&mut output, &gs.file_changes);
+            render::push_changes_table(&mut output, &gs.file_changes);
         }
-        cap_overview_output(&mut output);
+        render::cap_overview_output(&mut output);
         Some(output)
     }
 
@@ -335,7 +446,7 @@ impl Module for GitModule {
         serde_json::Value::Null
     }
     fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
-    fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<cp_base::tools::pre_flight::Verdict> {
+    fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<Verdict> {
         None
     }
     fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
@@ -360,117 +471,3 @@ impl Module for GitModule {
 
     fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
 }
-
-/// Render the changed-files table (per-file +/- and a totals row) into `output`.
-fn push_changes_table(output: &mut String, changes: &[GitFileChange]) {
-    output.push_str("\nGit Changes:\n\n");
-    output.push_str("| File | + | - | Net |\n");
-    output.push_str("|------|---|---|-----|\n");
-    let mut total_add: i32 = 0;
-    let mut total_del: i32 = 0;
-    for file in changes {
-        total_add = total_add.saturating_add(file.additions);
-        total_del = total_del.saturating_add(file.deletions);
-        let net = file.additions.saturating_sub(file.deletions);
-        let net_str = if net >= 0i32 { format!("+{net}") } else { format!("{net}") };
-        let _r = writeln!(output, "| {} | +{} | -{} | {} |", file.path, file.additions, file.deletions, net_str);
-    }
-    let total_net = total_add.saturating_sub(total_del);
-    let total_net_str = if total_net >= 0i32 { format!("+{total_net}") } else { format!("{total_net}") };
-    let _r = writeln!(output, "| **Total** | **+{total_add}** | **-{total_del}** | **{total_net_str}** |");
-}
-
-/// Hard-cap the overview section at [`GIT_OVERVIEW_CAP_BYTES`].
-///
-/// A broken `.gitignore` (e.g. mid-rebase) can make git report thousands of
-/// changed files, ballooning this section to 1M+ tokens and wrecking the
-/// agent's context. Cut on the last newline within budget so the table never
-/// ends mid-row (that offset is a guaranteed UTF-8 boundary), then note the
-/// elided size.
-fn cap_overview_output(output: &mut String) {
-    if output.len() <= GIT_OVERVIEW_CAP_BYTES {
-        return;
-    }
-    let kb_left = output.len().saturating_sub(GIT_OVERVIEW_CAP_BYTES).div_ceil(1024);
-    let cut = output
-        .char_indices()
-        .take_while(|entry| entry.0 <= GIT_OVERVIEW_CAP_BYTES)
-        .filter(|entry| entry.1 == '\n')
-        .map(|entry| entry.0)
-        .last()
-        .unwrap_or(0);
-    output.truncate(cut);
-    let _r = write!(output, "\n\n_(Rest hidden, {kb_left} kB left)_\n");
-}
-
-/// Status / message lines: panel notices, errors, staged-file markers, comments.
-fn git_status_semantic(line: &str) -> Option<cp_render::Semantic> {
-    use cp_render::Semantic;
-    if line.starts_with("Panel created:") || line.starts_with("Panel updated:") {
-        Some(Semantic::Success)
-    } else if line.starts_with("Error:") || line.starts_with("fatal:") || line.starts_with("error:") {
-        Some(Semantic::Error)
-    } else if line.starts_with("modified:") || line.starts_with("new file:") || line.starts_with("deleted:") {
-        Some(Semantic::Warning)
-    } else if line.starts_with('#') {
-        Some(Semantic::Muted)
-    } else {
-        None
-    }
-}
-
-/// Diff hunk body lines: `+`/`-` additions and removals.
-fn git_diff_semantic(line: &str) -> Option<cp_render::Semantic> {
-    use cp_render::Semantic;
-    if line.starts_with("+ ") || line.starts_with("+++ ") {
-        Some(Semantic::DiffAdd)
-    } else if line.starts_with("- ") || line.starts_with("--- ") {
-        Some(Semantic::DiffRemove)
-    } else {
-        None
-    }
-}
-
-/// Metadata lines: hunk headers, commit/author/date, ref pointers.
-fn git_meta_semantic(line: &str) -> Option<cp_render::Semantic> {
-    use cp_render::Semantic;
-    let is_meta = line.starts_with("@@")
-        || line.starts_with("commit ")
-        || line.starts_with("Author:")
-        || line.starts_with("Date:")
-        || line.starts_with("* ")
-        || line.contains("HEAD ->")
-        || line.contains("origin/");
-    is_meta.then_some(Semantic::Info)
-}
-
-/// Pick the semantic color for one line of `git_execute` output.
-fn git_line_semantic(line: &str) -> cp_render::Semantic {
-    git_status_semantic(line)
-        .or_else(|| git_diff_semantic(line))
-        .or_else(|| git_meta_semantic(line))
-        .unwrap_or(cp_render::Semantic::Default)
-}
-
-/// Visualizer for `git_execute` tool results.
-/// Color-codes git command output with branch names, status indicators,
-/// diff hunks with +/- in green/red, file names highlighted.
-fn visualize_git_output(content: &str, width: usize) -> Vec<cp_render::Block> {
-    use cp_render::{Block, Span};
-
-    content
-        .lines()
-        .map(|line| {
-            if line.is_empty() {
-                return Block::empty();
-            }
-            let semantic = git_line_semantic(line);
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
-            Block::Line(vec![Span::styled(display, semantic)])
-        })
-        .collect()
-}
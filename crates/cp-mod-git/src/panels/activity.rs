@@ -0,0 +1,226 @@
+//! Activity panel: what changed in the repository lately, and who changed it.
+//!
+//! Lists the last commits with their authors, the files those commits touched
+//! most recently, and the number of open pull requests (via `gh`, when it is
+//! installed and authenticated). Refreshed on a cache worker every
+//! [`ACTIVITY_REFRESH_MS`], so the LLM notices teammates' pushes and merges.
+
+use std::fmt::Write as _;
+use std::process::Command;
+
+use crossterm::event::KeyEvent;
+
+use cp_base::modules::run_with_timeout;
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::GIT_CMD_TIMEOUT_SECS;
+use crate::types::ActivityRequest;
+
+/// Refresh interval of the activity feed (ms).
+pub(crate) const ACTIVITY_REFRESH_MS: u64 = 60_000;
+
+/// Commits listed.
+const COMMITS: usize = 15;
+
+/// Commits scanned for recently modified files.
+const FILE_SCAN_COMMITS: usize = 50;
+
+/// Recently modified files listed.
+const FILES: usize = 15;
+
+/// Open PRs counted at most (`gh pr list --limit`).
+const PR_LIMIT: usize = 200;
+
+/// Stdout of `cmd`, or `None` when it cannot run or fails.
+fn stdout_of(mut cmd: Command) -> Option<String> {
+    let _e = cmd.env("GIT_TERMINAL_PROMPT", "0");
+    let output = run_with_timeout(cmd, GIT_CMD_TIMEOUT_SECS).ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Stdout of `git args`.
+fn git(args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new("git");
+    let _c = cmd.args(args);
+    stdout_of(cmd)
+}
+
+/// `short-hash  relative-date  author  subject` lines of the last commits.
+fn push_commits(out: &mut String) {
+    let count = format!("-{COMMITS}");
+    let Some(log) = git(&["log", &count, "--no-merges", "--pretty=format:%h\u{1f}%ar\u{1f}%an\u{1f}%s"]) else {
+        out.push_str("Recent commits: unavailable (not a git repository?)\n");
+        return;
+    };
+    out.push_str("Recent commits:\n");
+    for line in log.lines() {
+        let fields: Vec<&str> = line.split('\u{1f}').collect();
+        if let &[hash, when, author, subject] = fields.as_slice() {
+            let _r = writeln!(out, "  {hash}  {when}  {author}  {subject}");
+        }
+    }
+}
+
+/// Files touched by the latest commits, most recent first, without repeats.
+fn recent_files(log: &str) -> Vec<&str> {
+    let mut files: Vec<&str> = Vec::new();
+    for path in log.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+        if files.len() >= FILES {
+            break;
+        }
+    }
+    files
+}
+
+/// The recently modified files section.
+fn push_files(out: &mut String) {
+    let count = format!("-{FILE_SCAN_COMMITS}");
+    let Some(log) = git(&["log", &count, "--name-only", "--pretty=format:"]) else { return };
+    let files = recent_files(&log);
+    if files.is_empty() {
+        return;
+    }
+    out.push_str("\nRecently modified files:\n");
+    for path in files {
+        let _r = writeln!(out, "  {path}");
+    }
+}
+
+/// The open pull request count, via `gh`.
+fn push_pull_requests(out: &mut String, github_token: Option<&String>) {
+    let mut cmd = Command::new("gh");
+    let limit = PR_LIMIT.to_string();
+    let _c = cmd.args(["pr", "list", "--state", "open", "--limit", &limit, "--json", "number", "--jq", "length"]);
+    if let Some(token) = github_token {
+        let _t = cmd.env("GH_TOKEN", token).env("GITHUB_TOKEN", token);
+    }
+    let count = stdout_of(cmd).map(|s| s.trim().to_owned()).filter(|s| !s.is_empty());
+    let line = count.map_or_else(
+        || "Open pull requests: unavailable (gh missing or not authenticated)".to_owned(),
+        |n| format!("Open pull requests: {n}"),
+    );
+    let _r = writeln!(out, "\n{line}");
+}
+
+/// The whole feed (runs on a cache worker).
+fn build_feed(github_token: Option<&String>) -> String {
+    let mut out = String::new();
+    push_commits(&mut out);
+    push_files(&mut out);
+    push_pull_requests(&mut out, github_token);
+    out
+}
+
+/// Panel summarizing recent repository activity.
+pub(crate) struct ActivityPanel;
+
+impl Panel for ActivityPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        Some(ACTIVITY_REFRESH_MS)
+    }
+
+    fn build_cache_request(&self, ctx: &Entry, _state: &State) -> Option<CacheRequest> {
+        let github_token = cp_vault::vault().get("github").map(|s| s.expose().to_owned());
+        Some(CacheRequest::new(
+            Kind::new(Kind::GIT_ACTIVITY),
+            Box::new(ActivityRequest { context_id: ctx.id.clone(), github_token }),
+        ))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<ActivityRequest>().ok()?;
+        let content = build_feed(req.github_token.as_ref());
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id: req.context_id, content, token_count })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, _state: &mut State) -> bool {
+        // Only a Content update carries new bytes; Unchanged/ModuleSpecific are no-ops.
+        if let CacheUpdate::Content { content, token_count, .. } = update {
+            ctx.token_count = token_count;
+            ctx.full_token_count = token_count;
+            ctx.cache_deprecated = false;
+            let _changed = update_if_changed(ctx, &content);
+            ctx.cached_content = Some(content);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        let cached = state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == Kind::GIT_ACTIVITY)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content
+            .lines()
+            .map(|line| {
+                let semantic = if line.ends_with(':') || line.starts_with("Open pull requests") {
+                    Semantic::Accent
+                } else {
+                    Semantic::Default
+                };
+                Block::Line(vec![S::styled(format!(" {line}"), semantic)])
+            })
+            .collect()
+    }
+
+    fn title(&self, _state: &State) -> String {
+        "Activity".to_owned()
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::GIT_ACTIVITY)
+            .map(|c| {
+                let content = c.cached_content.as_deref().unwrap_or("[loading...]");
+                ContextItem::new(&c.id, "Repository activity", content.to_owned(), c.last_refresh_ms)
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_files_keep_first_occurrence_order() {
+        let log = "src/a.rs\nsrc/b.rs\n\nsrc/a.rs\nREADME.md\n";
+        assert_eq!(recent_files(log), vec!["src/a.rs", "src/b.rs", "README.md"]);
+    }
+}
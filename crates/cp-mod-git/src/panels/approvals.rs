@@ -0,0 +1,163 @@
+//! Approvals panel: edits the edit guard refused, waiting for the user.
+//!
+//! Each refused `Edit` or `Write` is listed once with why it was flagged; the
+//! selected one shows its diff. The user moves with `n`/`p`, approves (`a`)
+//! or rejects (`r`) the selected edit, or all of them with `A`/`R`, instead
+//! of answering one prompt per edit.
+
+use std::fmt::Write as _;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use cp_base::panels::{Panel, scroll_key_action};
+use cp_base::state::actions::{Action, ApprovalMove};
+use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::types::{GitState, PendingApproval};
+
+/// Panel listing the refused edits, with the selected one's diff.
+pub(crate) struct ApprovalsPanel;
+
+impl ApprovalsPanel {
+    /// Format the waiting edits for LLM context.
+    fn format_for_context(state: &State) -> String {
+        let pending = &GitState::get(state).pending_approvals;
+        if pending.is_empty() {
+            return "No edits awaiting the user's approval".to_owned();
+        }
+        let mut output = format!("{} edit(s) awaiting the user's approval:\n", pending.len());
+        for p in pending {
+            let _r = writeln!(output, "- {} {}: {}", p.tool, p.path, p.reason);
+        }
+        output
+    }
+
+    /// One refused edit; its diff too when `selected`.
+    fn item_lines(p: &PendingApproval, selected: bool) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+        let marker = if selected { " \u{25b8} " } else { "   " };
+        let mut blocks = vec![
+            Block::Line(vec![
+                S::styled(marker.to_owned(), Semantic::KeyHint),
+                S::accent(p.tool.clone()).bold(),
+                S::new(format!(" {}", p.path)).bold(),
+            ]),
+            Block::Line(vec![S::muted(format!("     {}", p.reason))]),
+        ];
+        if selected {
+            blocks.extend(p.preview.iter().map(|line| {
+                let semantic = if line.starts_with('+') { Semantic::DiffAdd } else { Semantic::DiffRemove };
+                Block::Line(vec![S::styled(format!("     {line}"), semantic)])
+            }));
+        }
+        blocks
+    }
+}
+
+impl Panel for ApprovalsPanel {
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        if let KeyCode::Char(c) = key.code
+            && key.modifiers.difference(KeyModifiers::SHIFT).is_empty()
+        {
+            let step = match c {
+                'n' => ApprovalMove::Next,
+                'p' => ApprovalMove::Prev,
+                'a' => ApprovalMove::Approve,
+                'r' => ApprovalMove::Reject,
+                'A' => ApprovalMove::ApproveAll,
+                'R' => ApprovalMove::RejectAll,
+                _ => return None,
+            };
+            return Some(Action::Approvals(step));
+        }
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        let gs = GitState::get(state);
+        if gs.pending_approvals.is_empty() {
+            return vec![
+                Block::Line(vec![S::muted("  No edits awaiting approval".into()).italic()]),
+                Block::Line(vec![S::muted("  Edits refused by /edit-guard on approval wait here".into())]),
+            ];
+        }
+
+        let mut blocks = vec![
+            Block::Line(vec![S::muted(format!(" {} edit(s) awaiting your approval", gs.pending_approvals.len()))]),
+            Block::Empty,
+        ];
+        for (n, p) in gs.pending_approvals.iter().enumerate() {
+            blocks.extend(Self::item_lines(p, n == gs.approval_cursor));
+        }
+        blocks.push(Block::Empty);
+        blocks.push(Block::Line(vec![
+            S::styled(" n p ".into(), Semantic::KeyHint),
+            S::muted("edit  ".into()),
+            S::styled("a r ".into(), Semantic::KeyHint),
+            S::muted("approve / reject  ".into()),
+            S::styled("A R ".into(), Semantic::KeyHint),
+            S::muted("all".into()),
+        ]));
+        blocks
+    }
+
+    fn title(&self, state: &State) -> String {
+        match GitState::get(state).pending_approvals.len() {
+            0 => "Approvals".to_owned(),
+            n => format!("Approvals ({n})"),
+        }
+    }
+
+    fn refresh(&self, state: &mut State) {
+        let content = Self::format_for_context(state);
+        let token_count = estimate_tokens(&content);
+
+        if let Some(ctx) = state.context.iter_mut().find(|c| c.context_type.as_str() == Kind::APPROVALS) {
+            ctx.token_count = token_count;
+            let _changed = cp_base::panels::update_if_changed(ctx, &content);
+        }
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let Some(ctx) = state.context.iter().find(|c| c.context_type.as_str() == Kind::APPROVALS) else {
+            return Vec::new();
+        };
+        vec![ContextItem::new(&ctx.id, "Approvals", Self::format_for_context(state), ctx.last_refresh_ms)]
+    }
+
+    fn needs_cache(&self) -> bool {
+        false
+    }
+    fn refresh_cache(&self, _request: cp_base::panels::CacheRequest) -> Option<cp_base::panels::CacheUpdate> {
+        None
+    }
+    fn build_cache_request(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _state: &State,
+    ) -> Option<cp_base::panels::CacheRequest> {
+        None
+    }
+    fn apply_cache_update(
+        &self,
+        _update: cp_base::panels::CacheUpdate,
+        _ctx: &mut cp_base::state::context::Entry,
+        _state: &mut State,
+    ) -> bool {
+        false
+    }
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        None
+    }
+    fn suicide(&self, _ctx: &cp_base::state::context::Entry, _state: &State) -> bool {
+        false
+    }
+}
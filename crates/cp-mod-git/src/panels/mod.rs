@@ -0,0 +1,9 @@
+//! Git panels: command results, the repository activity feed and the edits
+//! awaiting approval.
+
+/// Recent commits, recently modified files and open PRs.
+pub(crate) mod activity;
+/// Edits the edit guard refused, for the user to approve or reject.
+pub(crate) mod approvals;
+/// Output of a read-only git command, auto-refreshed.
+pub(crate) mod result;
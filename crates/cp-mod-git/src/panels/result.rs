@@ -3,12 +3,13 @@ use crossterm::event::KeyEvent;
 use cp_base::config::constants;
 use cp_base::modules::{run_with_timeout, truncate_output};
 use cp_base::panels::{CacheRequest, CacheUpdate};
-use cp_base::panels::{ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::panels::{Panel, paginate_content, update_if_changed};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
 use cp_base::state::runtime::State;
 
-use super::GIT_CMD_TIMEOUT_SECS;
+use crate::GIT_CMD_TIMEOUT_SECS;
 use crate::types::GitResultRequest;
 use cp_base::panels::scroll_key_action;
 
@@ -65,7 +66,7 @@ impl Panel for GitResultPanel {
         let GitResultRequest { context_id, command } = *req;
 
         // Parse and execute the command with timeout
-        let args = super::classify::validate_git_command(&command).ok()?;
+        let args = crate::classify::validate_git_command(&command).ok()?;
 
         let mut cmd = std::process::Command::new("git");
         let _c = cmd.args(&args).env("GIT_TERMINAL_PROMPT", "0");
@@ -137,11 +138,7 @@ impl Panel for GitResultPanel {
             && ctx.context_type.as_str() == Kind::GIT_RESULT
             && let Some(cmd) = ctx.get_meta_str("result_command")
         {
-            let short = if cmd.len() > 40 {
-                format!("{}...", cmd.get(..cmd.floor_char_boundary(37)).unwrap_or(""))
-            } else {
-                cmd.to_owned()
-            };
+            let short = cp_base::ui::text::ellipsize(cmd, 40, "...");
             return short;
         }
         "Git Result".to_owned()
@@ -151,6 +148,10 @@ impl Panel for GitResultPanel {
         0
     }
 
+    fn diff_on_refresh(&self) -> bool {
+        true
+    }
+
     fn context(&self, state: &State) -> Vec<ContextItem> {
         let mut items = Vec::new();
         for ctx in &state.context {
@@ -160,7 +161,10 @@ impl Panel for GitResultPanel {
             let content = ctx.cached_content.as_deref().unwrap_or("[loading...]");
             let header = ctx.get_meta_str("result_command").unwrap_or("Git Result");
             let output = paginate_content(content, ctx.current_page, ctx.total_pages, &ctx.page_descriptions);
-            items.push(ContextItem::new(&ctx.id, header, output, ctx.last_refresh_ms));
+            items.push(
+                ContextItem::new(&ctx.id, header, output, ctx.last_refresh_ms)
+                    .with_provenance(Provenance::repo(header)),
+            );
         }
         items
     }
@@ -0,0 +1,139 @@
+//! Rendering: per-line coloring of `git_execute` results, the guard lines and
+//! the changed-files table of the overview section.
+
+use std::fmt::Write as _;
+
+use crate::types::{GitFileChange, GitState};
+
+/// Hard byte cap on the git section of the Overview panel (context + content).
+///
+/// A broken `.gitignore` (commonly during a rebase) can make `git` report
+/// thousands of changed files; without a cap the per-file table grows to 1M+
+/// tokens and destroys the agent's context. Beyond this budget the section is
+/// truncated with a `(Rest hidden, N kB left)` marker.
+const GIT_OVERVIEW_CAP_BYTES: usize = 8 * 1024;
+
+/// Overview lines of the active guards: pre-commit checks, commit policy and
+/// edit guard.
+pub(crate) fn push_guards(output: &mut String, gs: &GitState) {
+    if !gs.commit_checks.is_empty() {
+        let _r = writeln!(output, "Pre-commit checks: {}", gs.commit_checks.join(" | "));
+    }
+    if !gs.commit_policy.is_empty() {
+        let _r = writeln!(output, "Commit policy: {}", crate::commit::policy::summary(&gs.commit_policy));
+    }
+    if let Some(guard) = gs.edit_guard.as_ref() {
+        let mode = if guard.require_approval { "approval required" } else { "warnings" };
+        let _r = writeln!(output, "Edit guard: CODEOWNERS + last {} days of history ({mode})", guard.recent_days);
+    }
+}
+
+/// Status / message lines: panel notices, errors, staged-file markers, comments.
+fn git_status_semantic(line: &str) -> Option<cp_render::Semantic> {
+    use cp_render::Semantic;
+    if line.starts_with("Panel created:") || line.starts_with("Panel updated:") {
+        Some(Semantic::Success)
+    } else if line.starts_with("Error:") || line.starts_with("fatal:") || line.starts_with("error:") {
+        Some(Semantic::Error)
+    } else if line.starts_with("modified:") || line.starts_with("new file:") || line.starts_with("deleted:") {
+        Some(Semantic::Warning)
+    } else if line.starts_with('#') {
+        Some(Semantic::Muted)
+    } else {
+        None
+    }
+}
+
+/// Diff hunk body lines: `+`/`-` additions and removals.
+fn git_diff_semantic(line: &str) -> Option<cp_render::Semantic> {
+    use cp_render::Semantic;
+    if line.starts_with("+ ") || line.starts_with("+++ ") {
+        Some(Semantic::DiffAdd)
+    } else if line.starts_with("- ") || line.starts_with("--- ") {
+        Some(Semantic::DiffRemove)
+    } else {
+        None
+    }
+}
+
+/// Metadata lines: hunk headers, commit/author/date, ref pointers.
+fn git_meta_semantic(line: &str) -> Option<cp_render::Semantic> {
+    use cp_render::Semantic;
+    let is_meta = line.starts_with("@@")
+        || line.starts_with("commit ")
+        || line.starts_with("Author:")
+        || line.starts_with("Date:")
+        || line.starts_with("* ")
+        || line.contains("HEAD ->")
+        || line.contains("origin/");
+    is_meta.then_some(Semantic::Info)
+}
+
+/// Pick the semantic color for one line of `git_execute` output.
+fn git_line_semantic(line: &str) -> cp_render::Semantic {
+    git_status_semantic(line)
+        .or_else(|| git_diff_semantic(line))
+        .or_else(|| git_meta_semantic(line))
+        .unwrap_or(cp_render::Semantic::Default)
+}
+
+/// Visualizer for `git_execute` tool results.
+/// Color-codes git command output with branch names, status indicators,
+/// diff hunks with +/- in green/red, file names highlighted.
+pub(crate) fn visualize_git_output(content: &str, width: usize) -> Vec<cp_render::Block> {
+    use cp_render::{Block, Span};
+
+    content
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                return Block::empty();
+            }
+            let semantic = git_line_semantic(line);
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
+            Block::Line(vec![Span::styled(display, semantic)])
+        })
+        .collect()
+}
+
+/// Render the changed-files table (per-file +/- and a totals row) into `output`.
+pub(crate) fn push_changes_table(output: &mut String, changes: &[GitFileChange]) {
+    output.push_str("\nGit Changes:\n\n");
+    output.push_str("| File | + | - | Net |\n");
+    output.push_str("|------|---|---|-----|\n");
+    let mut total_add: i32 = 0;
+    let mut total_del: i32 = 0;
+    for file in changes {
+        total_add = total_add.saturating_add(file.additions);
+        total_del = total_del.saturating_add(file.deletions);
+        let net = file.additions.saturating_sub(file.deletions);
+        let net_str = if net >= 0i32 { format!("+{net}") } else { format!("{net}") };
+        let _r = writeln!(output, "| {} | +{} | -{} | {} |", file.path, file.additions, file.deletions, net_str);
+    }
+    let total_net = total_add.saturating_sub(total_del);
+    let total_net_str = if total_net >= 0i32 { format!("+{total_net}") } else { format!("{total_net}") };
+    let _r = writeln!(output, "| **Total** | **+{total_add}** | **-{total_del}** | **{total_net_str}** |");
+}
+
+/// Hard-cap the overview section at [`GIT_OVERVIEW_CAP_BYTES`].
+///
+/// A broken `.gitignore` (e.g. mid-rebase) can make git report thousands of
+/// changed files, ballooning this section to 1M+ tokens and wrecking the
+/// agent's context. Cut on the last newline within budget so the table never
+/// ends mid-row (that offset is a guaranteed UTF-8 boundary), then note the
+/// elided size.
+pub(crate) fn cap_overview_output(output: &mut String) {
+    if output.len() <= GIT_OVERVIEW_CAP_BYTES {
+        return;
+    }
+    let kb_left = output.len().saturating_sub(GIT_OVERVIEW_CAP_BYTES).div_ceil(1024);
+    let cut = output
+        .char_indices()
+        .take_while(|entry| entry.0 <= GIT_OVERVIEW_CAP_BYTES)
+        .filter(|entry| entry.1 == '\n')
+        .map(|entry| entry.0)
+        .last()
+        .unwrap_or(0);
+    output.truncate(cut);
+    let _r = write!(output, "\n\n_(Rest hidden, {kb_left} kB left)_\n");
+}
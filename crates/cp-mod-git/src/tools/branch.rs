@@ -0,0 +1,159 @@
+//! Branch workflow tools: `git_branch_create`, `git_switch` and `git_stash`.
+//!
+//! Structured wrappers so the agent can follow "create a feature branch first"
+//! instructions without composing raw commands. Each builds its git args from
+//! validated parameters and goes through the same rules as `git_execute`
+//! (shell-operator check, classification, panel invalidation). They are quick
+//! local operations, so they run inline and refresh the git status right away:
+//! the status bar and the overview show the new branch as the tool returns.
+
+use std::process::Command;
+
+use serde_json::Value;
+
+use cp_base::config::constants;
+use cp_base::modules::{run_with_timeout, truncate_output};
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use super::invalidate_for;
+use crate::GIT_CMD_TIMEOUT_SECS;
+use crate::classify::check_shell_operators;
+
+/// Sub-commands `git_stash` accepts (the first is the default).
+pub(crate) const STASH_ACTIONS: &[&str] = &["push", "pop", "apply", "drop", "list", "show"];
+
+/// A non-empty string parameter, trimmed.
+fn str_param<'tool>(tool: &'tool ToolUse, name: &str) -> Option<&'tool str> {
+    tool.input.get(name).and_then(Value::as_str).map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// An optional branch or ref parameter, rejected when it could be read as a
+/// flag or smuggle shell syntax.
+fn ref_param(tool: &ToolUse, name: &str) -> Result<Option<String>, String> {
+    let Some(value) = str_param(tool, name) else { return Ok(None) };
+    if value.starts_with('-') || value.chars().any(|c| c.is_whitespace() || matches!(c, '\'' | '"')) {
+        return Err(format!("'{value}' is not a valid {name}"));
+    }
+    check_shell_operators(value)?;
+    Ok(Some(value.to_owned()))
+}
+
+/// A required branch or ref parameter.
+fn required_ref(tool: &ToolUse, name: &str) -> Result<String, String> {
+    ref_param(tool, name)?.ok_or_else(|| format!("'{name}' parameter is required"))
+}
+
+/// Args of `git_branch_create`: `switch -c` by default, plain `branch` when
+/// `switch` is false.
+fn branch_create_args(tool: &ToolUse) -> Result<Vec<String>, String> {
+    let name = required_ref(tool, "name")?;
+    let from = ref_param(tool, "from")?;
+    let switch = tool.input.get("switch").and_then(Value::as_bool).unwrap_or(true);
+    let mut args: Vec<String> =
+        if switch { vec!["switch".to_owned(), "-c".to_owned()] } else { vec!["branch".to_owned()] };
+    args.push(name);
+    args.extend(from);
+    Ok(args)
+}
+
+/// Args of `git_stash`.
+fn stash_args(tool: &ToolUse) -> Result<Vec<String>, String> {
+    let action = str_param(tool, "action").unwrap_or("push");
+    if !STASH_ACTIONS.contains(&action) {
+        return Err(format!("unknown stash action '{action}' (expected one of: {})", STASH_ACTIONS.join(", ")));
+    }
+    let mut args = vec!["stash".to_owned(), action.to_owned()];
+    if action == "push" {
+        if tool.input.get("include_untracked").and_then(Value::as_bool).unwrap_or(false) {
+            args.push("--include-untracked".to_owned());
+        }
+        if let Some(message) = str_param(tool, "message") {
+            args.push("-m".to_owned());
+            args.push(message.to_owned());
+        }
+    } else {
+        args.extend(tool.input.get("index").and_then(Value::as_u64).map(|index| format!("stash@{{{index}}}")));
+    }
+    Ok(args)
+}
+
+/// `git_branch_create`: create a branch (and switch to it by default).
+pub(crate) fn execute_branch_create(tool: &ToolUse, state: &mut State) -> ToolResult {
+    run_args(tool, state, branch_create_args(tool))
+}
+
+/// `git_switch`: switch to an existing branch.
+pub(crate) fn execute_switch(tool: &ToolUse, state: &mut State) -> ToolResult {
+    run_args(tool, state, required_ref(tool, "branch").map(|branch| vec!["switch".to_owned(), branch]))
+}
+
+/// `git_stash`: push, pop, apply, drop, list or show stashes.
+pub(crate) fn execute_stash(tool: &ToolUse, state: &mut State) -> ToolResult {
+    run_args(tool, state, stash_args(tool))
+}
+
+/// Run the built args inline, then refresh the git status and the panels
+/// showing it.
+fn run_args(tool: &ToolUse, state: &mut State, built: Result<Vec<String>, String>) -> ToolResult {
+    let args = match built {
+        Ok(args) => args,
+        Err(e) => return ToolResult::new(tool.id.clone(), format!("Validation error: {e}"), true),
+    };
+    let command = format!("git {}", args.join(" "));
+    invalidate_for(state, &command, &args);
+
+    let mut cmd = Command::new("git");
+    let _c = cmd.args(&args).env("GIT_TERMINAL_PROMPT", "0");
+    let result = run_with_timeout(cmd, GIT_CMD_TIMEOUT_SECS);
+
+    crate::refresh_git_status(state);
+    cp_base::panels::mark_panels_dirty(state, Kind::OVERVIEW);
+
+    match result {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let combined = format!("{}\n{}", stdout.trim(), stderr.trim());
+            let text = combined.trim();
+            let is_error = !output.status.success();
+            let content = match (text.is_empty(), is_error) {
+                (true, true) => format!("`{command}` failed with no output"),
+                (true, false) => format!("`{command}` completed successfully"),
+                (false, _) => truncate_output(text, constants::MAX_RESULT_CONTENT_BYTES),
+            };
+            ToolResult::new(tool.id.clone(), content, is_error)
+        }
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error running `{command}`: {e}"), true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(input: Value) -> ToolUse {
+        ToolUse::new("t".to_owned(), "git_stash".to_owned(), input)
+    }
+
+    #[test]
+    fn branch_names_cannot_pass_as_flags_or_shell() {
+        let made = branch_create_args(&tool(serde_json::json!({"name": "feat/x", "from": "main"})));
+        assert_eq!(made, Ok(vec!["switch".into(), "-c".into(), "feat/x".into(), "main".into()]));
+        let flag = branch_create_args(&tool(serde_json::json!({"name": "--force"})));
+        assert_eq!(flag, Err("'--force' is not a valid name".to_owned()));
+        assert_eq!(required_ref(&tool(serde_json::json!({"branch": "a|b"})), "branch").ok(), None);
+    }
+
+    #[test]
+    fn stash_args_follow_the_action() {
+        let push = stash_args(&tool(serde_json::json!({"message": "wip", "include_untracked": true})));
+        assert_eq!(
+            push,
+            Ok(vec!["stash".into(), "push".into(), "--include-untracked".into(), "-m".into(), "wip".into()])
+        );
+        let pop = stash_args(&tool(serde_json::json!({"action": "pop", "index": 2i32})));
+        assert_eq!(pop, Ok(vec!["stash".into(), "pop".into(), "stash@{2}".into()]));
+    }
+}
@@ -1,3 +1,8 @@
+/// Branch workflow tools: `git_branch_create`, `git_switch`, `git_stash`.
+pub(crate) mod branch;
+/// Edit guard: CODEOWNERS and recent-history warnings before edits.
+pub(crate) mod ownership;
+
 use std::process::Command;
 use std::time::Instant;
 
@@ -9,9 +14,13 @@ use cp_base::state::runtime::State;
 use cp_base::state::watchers::DYN_PANEL_ID_PLACEHOLDER;
 use cp_base::state::watchers::carriers::DynPanel;
 use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::output_schema::StructuredRunner;
 use cp_base::tools::{ToolResult, ToolUse};
 
 use super::classify::{CommandClass, classify_git, validate_git_command};
+use super::commit::gate::{budget_secs, is_commit, run_checks, run_in_console};
+use super::commit::policy::{REFORMAT_BUDGET_SECS, Rewrite};
+use super::types::GitState;
 
 /// Max lines for inline output (matches console's `easy_bash` threshold).
 const INLINE_MAX_LINES: usize = 150;
@@ -22,12 +31,33 @@ const INLINE_MAX_BYTES: usize = 8_000;
 /// Max execution time (ms) for inline treatment — slow commands always get panels.
 const INLINE_MAX_DURATION_MS: u128 = 10_000;
 
+/// Classify `args` and, for a mutating command, pre-invalidate the cached
+/// git result panels it may change (needs `&mut State`, so before spawning).
+pub(crate) fn invalidate_for(state: &mut State, command: &str, args: &[String]) {
+    if classify_git(args) != CommandClass::Mutating {
+        return;
+    }
+    let invalidations = super::cache_invalidation::find_invalidations(command);
+    if invalidations.is_empty() {
+        cp_base::panels::mark_panels_dirty(state, Kind::GIT_RESULT);
+        return;
+    }
+    for ctx in &mut state.context {
+        if ctx.context_type.as_str() == Kind::GIT_RESULT
+            && let Some(cached_cmd) = ctx.get_meta_str("result_command")
+            && invalidations.iter().any(|re| re.is_match(cached_cmd))
+        {
+            ctx.cache_deprecated = true;
+        }
+    }
+}
+
 /// Execute a raw git command.
 ///
 /// Short, fast results are returned **inline** (preserving tempo).
 /// Long or slow results create a static `git_result` panel.
 /// Mutating commands pre-invalidate cached panels before execution.
-pub(crate) fn execute_git_command(tool: &ToolUse, state: &mut State) -> ToolResult {
+pub(crate) fn execute_git_command(tool: &ToolUse, state: &mut State, run: StructuredRunner) -> ToolResult {
     let _fg = cp_base::flame!("git_exec");
     let Some(command) = tool.input.get("command").and_then(|v| v.as_str()) else {
         return ToolResult::new(tool.id.clone(), "Error: 'command' parameter is required".to_owned(), true);
@@ -41,35 +71,46 @@ pub(crate) fn execute_git_command(tool: &ToolUse, state: &mut State) -> ToolResu
         }
     };
 
-    // Classify
-    let class = classify_git(&args);
-
-    // Pre-invalidate cached panels for mutating commands (needs &mut State).
-    if class == CommandClass::Mutating {
-        let invalidations = super::cache_invalidation::find_invalidations(command);
-        if invalidations.is_empty() {
-            cp_base::panels::mark_panels_dirty(state, Kind::GIT_RESULT);
-        } else {
-            for ctx in &mut state.context {
-                if ctx.context_type.as_str() == Kind::GIT_RESULT
-                    && let Some(cached_cmd) = ctx.get_meta_str("result_command")
-                    && invalidations.iter().any(|re| re.is_match(cached_cmd))
-                {
-                    ctx.cache_deprecated = true;
-                }
-            }
-        }
-    }
+    invalidate_for(state, command, &args);
 
     // All commands: run async, decide inline vs panel on completion.
+    // Commits first get their message checked against the policy (rewritten
+    // by the cheap model when it breaks a rule), then go through the
+    // pre-commit gate (no checks = no gate).
     let command_owned = command.to_owned();
     let github_token = cp_vault::vault().get("github").map(|s| s.expose().to_owned());
-
-    spawn_async_tool(state, tool, GIT_CMD_TIMEOUT_SECS.saturating_add(5), move || {
-        run_git_command(&args, github_token.as_ref(), &command_owned)
+    let (checks, rewrite) = commit_guards(state, &args, run);
+    let rewrite_budget = rewrite.as_ref().map_or(0, |_| REFORMAT_BUDGET_SECS);
+    let timeout =
+        GIT_CMD_TIMEOUT_SECS.saturating_add(5).saturating_add(budget_secs(&checks)).saturating_add(rewrite_budget);
+
+    spawn_async_tool(state, tool, timeout, move || {
+        let (final_args, rewritten) = match rewrite.map(Rewrite::apply).transpose() {
+            Ok(Some((new_args, text))) => (new_args, Some(text)),
+            Ok(None) => (args, None),
+            Err(rejection) => return ToolOutput::error(rejection),
+        };
+        if let Err(failure) = run_checks(&checks, run_in_console) {
+            return ToolOutput::error(failure);
+        }
+        let mut output = run_git_command(&final_args, github_token.as_ref(), &command_owned);
+        if let Some(text) = rewritten {
+            output.content =
+                format!("Commit message rewritten to follow the commit policy:\n{text}\n\n{}", output.content);
+        }
+        output
     })
 }
 
+/// The pre-commit checks and the message rewrite a commit needs (none for
+/// other commands).
+fn commit_guards(state: &State, args: &[String], run: StructuredRunner) -> (Vec<String>, Option<Rewrite>) {
+    if !is_commit(args) {
+        return (Vec::new(), None);
+    }
+    (GitState::get(state).commit_checks.clone(), Rewrite::check(state, args, run))
+}
+
 /// Run one git invocation off the main loop: wire up `GIT_ASKPASS` from a
 /// token-bearing temp script (when a token is present), execute with a timeout,
 /// clean the script up, and fold the result into a [`ToolOutput`].
@@ -146,11 +187,7 @@ fn format_git_output(output: &std::process::Output, command: &str, elapsed_ms: u
 
     // Long or slow → static panel.
     let display_content = truncate_output(&combined, constants::MAX_RESULT_CONTENT_BYTES);
-    let display_name = if command.len() > 40 {
-        format!("{}...", command.get(..command.floor_char_boundary(37)).unwrap_or(""))
-    } else {
-        command.to_owned()
-    };
+    let display_name = cp_base::ui::text::ellipsize(command, 40, "...");
     ToolOutput::new(
         format!("Panel created: {DYN_PANEL_ID_PLACEHOLDER}"),
         is_error,
@@ -162,3 +199,39 @@ fn format_git_output(output: &std::process::Output, command: &str, elapsed_ms: u
         false,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CommitPolicy;
+    use cp_base::tools::output_schema::StructuredQuery;
+
+    /// A reformat runner that is never expected to be asked.
+    fn never(_query: &StructuredQuery) -> Result<serde_json::Value, String> {
+        Err("not asked".to_owned())
+    }
+
+    /// Owned args from `list`.
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|&a| a.to_owned()).collect()
+    }
+
+    #[test]
+    fn guards_apply_to_commits_behind_global_options_and_no_verify() {
+        let mut state = State::default();
+        state.set_ext(GitState::new());
+        GitState::get_mut(&mut state).commit_checks = args(&["cargo test"]);
+        GitState::get_mut(&mut state).commit_policy =
+            CommitPolicy { max_subject_length: Some(10), ..CommitPolicy::new() };
+        for commit in [
+            args(&["-c", "core.hooksPath=/dev/null", "commit", "-m", "a subject far too long"]),
+            args(&["--no-pager", "commit", "--no-verify", "-m", "a subject far too long"]),
+        ] {
+            let (checks, rewrite) = commit_guards(&state, &commit, never);
+            assert_eq!(checks, args(&["cargo test"]));
+            assert!(rewrite.is_some());
+        }
+        let (checks, rewrite) = commit_guards(&state, &args(&["-C", "sub", "log"]), never);
+        assert!(checks.is_empty() && rewrite.is_none());
+    }
+}
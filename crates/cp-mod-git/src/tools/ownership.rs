@@ -0,0 +1,388 @@
+//! Edit guard: warn before editing files owned or recently changed by others.
+//!
+//! The user turns the check on with `/edit-guard`; no tool can, so the agent
+//! cannot switch off its own guard. Before every `Edit` and `Write` the
+//! target is matched against CODEOWNERS (last matching rule wins) and its
+//! recent history is searched for other authors. Findings are attached to the
+//! tool result as warnings; with `require_approval` they refuse the edit until
+//! the user approves that exact path. Refused edits wait in the Approvals
+//! panel, where the user approves or rejects them one by one or all at once;
+//! `/approve <path>` and the control API approve a path directly.
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use globset::{Glob, GlobMatcher};
+
+use cp_base::modules::run_with_timeout;
+use cp_base::state::actions::ApprovalMove;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::ToolUse;
+use cp_base::tools::jail;
+use cp_base::tools::pre_flight::Verdict;
+
+use crate::GIT_CMD_TIMEOUT_SECS;
+use crate::types::{EditGuard, GitState, PendingApproval};
+
+/// Default days of history searched for other authors.
+pub(crate) const DEFAULT_RECENT_DAYS: u64 = 14;
+
+/// Where CODEOWNERS may live, in GitHub's lookup order.
+const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Other authors named in a warning at most.
+const MAX_AUTHORS: usize = 3;
+
+/// Lines of a refused edit kept for its preview at most.
+const PREVIEW_LINES: usize = 40;
+
+/// One CODEOWNERS rule.
+struct OwnerRule {
+    /// Matches the paths the rule covers.
+    matchers: Vec<GlobMatcher>,
+    /// Owners of those paths (empty = explicitly unowned).
+    owners: Vec<String>,
+}
+
+/// Globs of a CODEOWNERS pattern: the path itself and everything below it.
+/// Unanchored patterns (no inner `/`) match at any depth.
+fn pattern_globs(pattern: &str) -> Vec<String> {
+    let trimmed = pattern.trim_start_matches('/').trim_end_matches('/');
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let base = if anchored { trimmed.to_owned() } else { format!("**/{trimmed}") };
+    vec![format!("{base}/**"), base]
+}
+
+/// Parse CODEOWNERS text; unparsable patterns are skipped.
+fn parse_codeowners(text: &str) -> Vec<OwnerRule> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+            let matchers = pattern_globs(pattern)
+                .iter()
+                .filter_map(|g| Glob::new(g).ok().map(|glob| glob.compile_matcher()))
+                .collect();
+            Some(OwnerRule {
+                matchers,
+                owners: fields.take_while(|f| !f.starts_with('#')).map(str::to_owned).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Owners of `path` (relative to the repository root), by the last matching rule.
+fn owners_of(rules: &[OwnerRule], path: &str) -> Vec<String> {
+    rules.iter().rev().find(|r| r.matchers.iter().any(|m| m.is_match(path))).map_or_else(Vec::new, |r| r.owners.clone())
+}
+
+/// Stdout of `git args`, trimmed; empty on failure.
+fn git(args: &[&str]) -> String {
+    let mut cmd = Command::new("git");
+    let _c = cmd.args(args).env("GIT_TERMINAL_PROMPT", "0");
+    run_with_timeout(cmd, GIT_CMD_TIMEOUT_SECS)
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())
+        .unwrap_or_default()
+}
+
+/// `path` relative to the project root (the working directory), resolved
+/// the way the workspace jail resolves it (`..` applied, symlinks followed),
+/// so `src/../CODEOWNERS` or a link is matched as the file it names.
+fn relative_path(path: &str) -> String {
+    let Ok(target) = jail::resolve(Path::new(path)) else {
+        return path.trim_start_matches("./").to_owned();
+    };
+    let root = jail::resolve(Path::new(".")).unwrap_or_default();
+    target.strip_prefix(&root).unwrap_or(&target).to_string_lossy().into_owned()
+}
+
+/// The user's git email, read once per session: pre-flight runs on every
+/// edit, and the identity does not change under it.
+fn user_email() -> &'static str {
+    static EMAIL: OnceLock<String> = OnceLock::new();
+    EMAIL.get_or_init(|| git(&["config", "user.email"]))
+}
+
+/// Warning when the file's owners do not include the user.
+fn ownership_warning(guard: &EditGuard, path: &str, email: &str) -> Option<String> {
+    let text = CODEOWNERS_PATHS.iter().find_map(|p| std::fs::read_to_string(p).ok())?;
+    let owners = owners_of(&parse_codeowners(&text), path);
+    let mine =
+        |owner: &String| owner.eq_ignore_ascii_case(email) || guard.mine.iter().any(|m| m.eq_ignore_ascii_case(owner));
+    (!owners.is_empty() && !owners.iter().any(mine))
+        .then(|| format!("`{path}` is owned by {} (CODEOWNERS)", owners.join(", ")))
+}
+
+/// Warning when others committed to the file recently.
+fn history_warning(guard: &EditGuard, path: &str, email: &str) -> Option<String> {
+    let since = format!("--since={}.days", guard.recent_days);
+    let log = git(&["log", &since, "--format=%ae\u{1f}%an", "--", path]);
+    let mut authors: Vec<&str> = Vec::new();
+    for (author_email, name) in log.lines().filter_map(|l| l.split_once('\u{1f}')) {
+        if !author_email.eq_ignore_ascii_case(email) && !authors.contains(&name) {
+            authors.push(name);
+        }
+    }
+    if authors.is_empty() {
+        return None;
+    }
+    let shown: Vec<&str> = authors.iter().take(MAX_AUTHORS).copied().collect();
+    let more = authors.len().saturating_sub(MAX_AUTHORS);
+    let others = if more > 0 { format!(" and {more} more") } else { String::new() };
+    Some(format!("`{path}` was changed in the last {} days by {}{others}", guard.recent_days, shown.join(", ")))
+}
+
+/// Whether the user approved editing `path` (relative to the project root).
+fn approved(state: &State, path: &str) -> bool {
+    GitState::get(state).approved_edits.iter().any(|p| p == path)
+}
+
+/// Ownership verdict for an `Edit` or `Write` call; `None` for other tools,
+/// with the guard off, or when nothing stands out.
+#[must_use]
+pub(crate) fn edit_verdict(tool: &ToolUse, state: &State) -> Option<Verdict> {
+    if !matches!(tool.name.as_str(), "Edit" | "Write") {
+        return None;
+    }
+    let gs = state.get_ext::<GitState>().filter(|gs| gs.is_repo)?;
+    let guard = gs.edit_guard.as_ref()?;
+    let path = relative_path(tool.input.get("file_path").and_then(|v| v.as_str())?);
+    let email = user_email();
+    let findings: Vec<String> =
+        [ownership_warning(guard, &path, email), history_warning(guard, &path, email)].into_iter().flatten().collect();
+    if findings.is_empty() {
+        return None;
+    }
+    let verdict = findings.into_iter().fold(Verdict::new(), Verdict::warning);
+    if !guard.require_approval || approved(state, &path) {
+        return Some(verdict);
+    }
+    let mut refusal = verdict.error(format!(
+        "Edit refused until the user approves changing `{path}`; only they can, in the Approvals panel or with \
+         /approve {path}. Ask them, then retry."
+    ));
+    refusal.awaiting_approval = Some(path);
+    Some(refusal)
+}
+
+/// Diff preview of an `Edit` (`old_string` to `new_string`) or a `Write`
+/// (`content`).
+fn preview_of(tool: &ToolUse) -> Vec<String> {
+    let text = |key: &str| tool.input.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    let (removed, added) =
+        if tool.name == "Write" { ("", text("content")) } else { (text("old_string"), text("new_string")) };
+    removed.lines().map(|l| format!("-{l}")).chain(added.lines().map(|l| format!("+{l}"))).take(PREVIEW_LINES).collect()
+}
+
+/// List a call refused by the guard in the Approvals panel; nothing for
+/// other refusals. A call refused again is listed once.
+pub(crate) fn request_approval(state: &mut State, tool: &ToolUse, verdict: &Verdict) {
+    let Some(path) = verdict.awaiting_approval.clone() else { return };
+    let pending = PendingApproval {
+        tool: tool.name.clone(),
+        path,
+        reason: verdict.warnings.join("; "),
+        preview: preview_of(tool),
+    };
+    let gs = GitState::get_mut(state);
+    if !gs.pending_approvals.contains(&pending) {
+        gs.pending_approvals.push(pending);
+    }
+    state.touch_panel(Kind::APPROVALS);
+}
+
+/// `path` in backticks, comma-separated.
+fn path_list(items: &[PendingApproval]) -> String {
+    let mut paths: Vec<String> = Vec::new();
+    for path in items.iter().map(|p| format!("`{}`", p.path)) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths.join(", ")
+}
+
+/// Approve (`true`) or reject the selected edit, or all of them; the
+/// decision to tell the agent.
+fn decide(gs: &mut GitState, all: bool, approve: bool) -> String {
+    let cursor = gs.approval_cursor;
+    let mut decided = Vec::new();
+    let mut kept = Vec::new();
+    for (n, item) in std::mem::take(&mut gs.pending_approvals).into_iter().enumerate() {
+        if all || n == cursor { decided.push(item) } else { kept.push(item) }
+    }
+    gs.pending_approvals = kept;
+    let paths = path_list(&decided);
+    if !approve {
+        return format!("The user rejected the edits to {paths}: do not retry them.");
+    }
+    for item in decided {
+        gs.pending_approvals.retain(|p| p.path != item.path);
+        if !gs.approved_edits.contains(&item.path) {
+            gs.approved_edits.push(item.path);
+        }
+    }
+    format!("The user approved editing {paths}: retry the refused edits.")
+}
+
+/// Apply an Approvals panel key; the decision to tell the agent, if one
+/// was made.
+pub(crate) fn step(state: &mut State, step: ApprovalMove) -> Option<String> {
+    let gs = GitState::get_mut(state);
+    let last = gs.pending_approvals.len().checked_sub(1)?;
+    let cursor = gs.approval_cursor.min(last);
+    gs.approval_cursor = cursor;
+    let decision = match step {
+        ApprovalMove::Next => {
+            gs.approval_cursor = cursor.saturating_add(1).min(last);
+            None
+        }
+        ApprovalMove::Prev => {
+            gs.approval_cursor = cursor.saturating_sub(1);
+            None
+        }
+        ApprovalMove::Approve => Some(decide(gs, false, true)),
+        ApprovalMove::Reject => Some(decide(gs, false, false)),
+        ApprovalMove::ApproveAll => Some(decide(gs, true, true)),
+        ApprovalMove::RejectAll => Some(decide(gs, true, false)),
+    };
+    gs.approval_cursor = gs.approval_cursor.min(gs.pending_approvals.len().saturating_sub(1));
+    state.touch_panel(Kind::APPROVALS);
+    decision
+}
+
+/// Turn the edit guard on (`Some`) or off; a line saying what it does now.
+/// Approvals are kept only while the guard stays on.
+pub(crate) fn set_guard(state: &mut State, guard: Option<EditGuard>) -> String {
+    let gs = GitState::get_mut(state);
+    let Some(settings) = guard else {
+        gs.edit_guard = None;
+        gs.approved_edits.clear();
+        gs.pending_approvals.clear();
+        gs.approval_cursor = 0;
+        state.touch_panel(Kind::APPROVALS);
+        return "Edit guard off.".to_owned();
+    };
+    let then = if settings.require_approval { "refused until you /approve them" } else { "flagged with a warning" };
+    let message = format!(
+        "Edit guard on: edits to files owned by others in CODEOWNERS or changed by others in the last {} days are {then}.",
+        settings.recent_days
+    );
+    gs.edit_guard = Some(settings);
+    message
+}
+
+/// Record that the user approves editing `path`; a line saying so.
+pub(crate) fn approve(state: &mut State, path: &str) -> String {
+    let relative = relative_path(path);
+    let gs = GitState::get_mut(state);
+    if !gs.approved_edits.contains(&relative) {
+        gs.approved_edits.push(relative.clone());
+    }
+    gs.pending_approvals.retain(|p| p.path != relative);
+    state.touch_panel(Kind::APPROVALS);
+    format!("Edits to `{relative}` approved for this session.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_matching_codeowners_rule_wins() {
+        let rules = parse_codeowners("# owners\n* @org/core\n/docs/ @org/docs\n*.lock\nsrc/api/ @alice @bob # api\n");
+        assert_eq!(owners_of(&rules, "src/main.rs"), vec!["@org/core"]);
+        assert_eq!(owners_of(&rules, "docs/guide/intro.md"), vec!["@org/docs"]);
+        assert_eq!(owners_of(&rules, "crates/x/Cargo.lock"), Vec::<String>::new());
+        assert_eq!(owners_of(&rules, "src/api/routes.rs"), vec!["@alice", "@bob"]);
+    }
+
+    #[test]
+    fn approvals_name_the_exact_path() {
+        let mut state = State::default();
+        state.set_ext(GitState::new());
+        let _m = approve(&mut state, "./src/app/mod.rs");
+        assert!(approved(&state, "src/app/mod.rs"));
+        assert!(!approved(&state, "src/ui/mod.rs"));
+        assert!(!approved(&state, "mod.rs"));
+        let _off = set_guard(&mut state, None);
+        assert!(!approved(&state, "src/app/mod.rs"));
+    }
+
+    /// A state with refused edits of `paths` waiting in the Approvals panel.
+    fn with_pending(paths: &[&str]) -> State {
+        let mut state = State::default();
+        state.set_ext(GitState::new());
+        GitState::get_mut(&mut state).pending_approvals = paths
+            .iter()
+            .map(|p| PendingApproval {
+                tool: "Edit".to_owned(),
+                path: (*p).to_owned(),
+                reason: String::new(),
+                preview: vec![],
+            })
+            .collect();
+        state
+    }
+
+    #[test]
+    fn approving_the_selected_edit_approves_its_path_only() {
+        let mut state = with_pending(&["a.rs", "b.rs", "a.rs"]);
+        assert_eq!(step(&mut state, ApprovalMove::Prev), None);
+        let decision = step(&mut state, ApprovalMove::Approve);
+        assert_eq!(decision.as_deref(), Some("The user approved editing `a.rs`: retry the refused edits."));
+        assert!(approved(&state, "a.rs"));
+        let gs = GitState::get(&state);
+        assert_eq!(gs.pending_approvals.iter().map(|p| p.path.as_str()).collect::<Vec<_>>(), vec!["b.rs"]);
+        assert_eq!(gs.approval_cursor, 0);
+    }
+
+    #[test]
+    fn rejecting_all_clears_the_panel_without_approving() {
+        let mut state = with_pending(&["a.rs", "b.rs"]);
+        assert_eq!(step(&mut state, ApprovalMove::Next), None);
+        assert_eq!(GitState::get(&state).approval_cursor, 1);
+        let decision = step(&mut state, ApprovalMove::RejectAll);
+        assert_eq!(decision.as_deref(), Some("The user rejected the edits to `a.rs`, `b.rs`: do not retry them."));
+        assert!(GitState::get(&state).pending_approvals.is_empty());
+        assert!(!approved(&state, "a.rs"));
+        assert_eq!(step(&mut state, ApprovalMove::ApproveAll), None);
+    }
+
+    #[test]
+    fn refused_calls_are_listed_once_with_their_diff() {
+        let mut state = with_pending(&[]);
+        let tool = ToolUse {
+            id: "t1".to_owned(),
+            name: "Edit".to_owned(),
+            input: serde_json::json!({"file_path": "a.rs", "old_string": "x", "new_string": "y\nz"}),
+        };
+        let mut verdict = Verdict::new().warning("`a.rs` is owned by @ann (CODEOWNERS)").error("refused");
+        verdict.awaiting_approval = Some("a.rs".to_owned());
+        request_approval(&mut state, &tool, &verdict);
+        request_approval(&mut state, &tool, &verdict);
+        request_approval(&mut state, &tool, &Verdict::new().error("bad input"));
+        let pending = &GitState::get(&state).pending_approvals;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending.first().map(|p| p.preview.clone()), Some(vec!["-x".into(), "+y".into(), "+z".into()]));
+        let _a = approve(&mut state, "a.rs");
+        assert!(GitState::get(&state).pending_approvals.is_empty());
+    }
+
+    #[test]
+    fn paths_are_matched_as_the_file_they_name() {
+        let rules = parse_codeowners("/Cargo.toml @release\nsrc/ @core\n");
+        let through_parent = relative_path("src/../Cargo.toml");
+        assert_eq!(through_parent, "Cargo.toml");
+        assert_eq!(owners_of(&rules, &through_parent), vec!["@release".to_owned()]);
+        let absolute = std::env::current_dir().unwrap_or_default().join("missing/../src/new.rs");
+        assert_eq!(relative_path(&absolute.to_string_lossy()), "src/new.rs");
+        assert_eq!(relative_path("./src/lib.rs"), "src/lib.rs");
+    }
+}
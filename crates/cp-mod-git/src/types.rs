@@ -1,4 +1,5 @@
 use cp_base::state::runtime::State;
+use serde::{Deserialize, Serialize};
 
 // === Git change types ===
 
@@ -48,6 +49,59 @@ pub struct GitFileChange {
     pub change_type: GitChangeType,
 }
 
+// === Commit-message policy ===
+
+/// Commit-message rules; each is off when `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitPolicy {
+    /// Regex the subject line must match (e.g. Conventional Commits).
+    pub subject_pattern: Option<String>,
+    /// Max characters in the subject line.
+    pub max_subject_length: Option<usize>,
+    /// Regex of the ticket ID the message must contain (e.g. `[A-Z]+-\d+`).
+    pub ticket_pattern: Option<String>,
+}
+
+impl CommitPolicy {
+    /// A policy with no rules.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { subject_pattern: None, max_subject_length: None, ticket_pattern: None }
+    }
+
+    /// Whether no rule is set.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.subject_pattern.is_none() && self.max_subject_length.is_none() && self.ticket_pattern.is_none()
+    }
+}
+
+// === Edit guard ===
+
+/// Settings of the ownership check run before `Edit` and `Write`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditGuard {
+    /// CODEOWNERS owners that count as the user (handles, teams, emails).
+    pub mine: Vec<String>,
+    /// Days of history searched for other authors of the file.
+    pub recent_days: u64,
+    /// Refuse flagged edits until the user approves the file.
+    pub require_approval: bool,
+}
+
+/// An edit the guard refused, waiting in the Approvals panel for the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingApproval {
+    /// Tool that was refused (`Edit` or `Write`).
+    pub tool: String,
+    /// Target path, relative to the project root.
+    pub path: String,
+    /// Why the guard flagged it (ownership, recent authors).
+    pub reason: String,
+    /// Diff preview: `-` lines replaced, `+` lines written.
+    pub preview: Vec<String>,
+}
+
 // === Module-owned state ===
 
 /// Live git repository state, refreshed on every cache tick.
@@ -63,6 +117,19 @@ pub struct GitState {
     pub file_changes: Vec<GitFileChange>,
     /// Ref used as diff base (e.g., "main", "HEAD~3"). None = default branch.
     pub diff_base: Option<String>,
+    /// Shell commands that must pass before `git commit` runs; empty = no gate.
+    pub commit_checks: Vec<String>,
+    /// Rules every `git commit -m` message must follow.
+    pub commit_policy: CommitPolicy,
+    /// Warnings on edits to files owned or recently changed by others; `None` = off.
+    pub edit_guard: Option<EditGuard>,
+    /// Paths (relative to the project root) the user approved editing this
+    /// session, for the edit guard's `require_approval`.
+    pub approved_edits: Vec<String>,
+    /// Refused edits waiting for the user's decision, oldest first.
+    pub pending_approvals: Vec<PendingApproval>,
+    /// Selected entry of the Approvals panel.
+    pub approval_cursor: usize,
 }
 
 impl Default for GitState {
@@ -75,7 +142,19 @@ impl GitState {
     /// Create a fresh state with no git info.
     #[must_use]
     pub const fn new() -> Self {
-        Self { branch: None, branches: vec![], is_repo: false, file_changes: vec![], diff_base: None }
+        Self {
+            branch: None,
+            branches: vec![],
+            is_repo: false,
+            file_changes: vec![],
+            diff_base: None,
+            commit_checks: vec![],
+            commit_policy: CommitPolicy::new(),
+            edit_guard: None,
+            approved_edits: vec![],
+            pending_approvals: vec![],
+            approval_cursor: 0,
+        }
     }
     /// Get shared ref from State's `TypeMap`.
     ///
@@ -104,3 +183,12 @@ pub struct GitResultRequest {
     /// Git command to re-run for content refresh.
     pub command: String,
 }
+
+/// Payload for an activity panel refresh request.
+#[derive(Debug)]
+pub struct ActivityRequest {
+    /// Context element ID of the activity panel.
+    pub context_id: String,
+    /// Token for `gh` (open PR count), when configured.
+    pub github_token: Option<String>,
+}
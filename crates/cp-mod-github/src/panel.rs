@@ -5,9 +5,10 @@ use super::GH_CMD_TIMEOUT_SECS;
 use cp_base::config::constants;
 use cp_base::modules::{run_with_timeout, truncate_output};
 use cp_base::panels::{CacheRequest, CacheUpdate};
-use cp_base::panels::{ContextItem, Panel, paginate_content, update_if_changed};
+use cp_base::panels::{Panel, paginate_content, update_if_changed};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
 use cp_base::state::runtime::State;
 
 use crate::types::{GithubResultRequest, GithubState};
@@ -165,11 +166,7 @@ impl Panel for GithubResultPanel {
             && ctx.context_type.as_str() == Kind::GITHUB_RESULT
             && let Some(cmd) = ctx.get_meta_str("result_command")
         {
-            let short = if cmd.len() > 40 {
-                format!("{}...", cmd.get(..cmd.floor_char_boundary(37)).unwrap_or(""))
-            } else {
-                cmd.to_owned()
-            };
+            let short = cp_base::ui::text::ellipsize(cmd, 40, "...");
             return short;
         }
         "GitHub Result".to_owned()
@@ -188,7 +185,9 @@ impl Panel for GithubResultPanel {
             let content = ctx.cached_content.as_deref().unwrap_or("[loading...]");
             let header = ctx.get_meta_str("result_command").unwrap_or("GitHub Result");
             let output = paginate_content(content, ctx.current_page, ctx.total_pages, &ctx.page_descriptions);
-            items.push(ContextItem::new(&ctx.id, header, output, ctx.last_refresh_ms));
+            // Issues, PRs and comments are written by anyone with access: untrusted.
+            let item = ContextItem::new(&ctx.id, header, output, ctx.last_refresh_ms);
+            items.push(item.with_provenance(Provenance::external_web(header)));
         }
         items
     }
@@ -144,11 +144,7 @@ fn format_gh_output(output: &std::process::Output, command: &str, token: &str, e
 
     // Long or slow → static panel.
     let display_content = truncate_output(&combined, constants::MAX_RESULT_CONTENT_BYTES);
-    let display_name = if command.len() > 40 {
-        format!("{}...", command.get(..command.floor_char_boundary(37)).unwrap_or(""))
-    } else {
-        command.to_owned()
-    };
+    let display_name = cp_base::ui::text::ellipsize(command, 40, "...");
     let dyn_panel = DynPanel::new(Kind::GITHUB_RESULT.to_owned(), display_name)
         .metadata(vec![("result_command".to_owned(), command.to_owned())])
         .content(display_content);
@@ -0,0 +1,121 @@
+//! Http module — call HTTP APIs without shelling out to curl.
+//!
+//! One tool: `http_request` (method, URL, headers, body, timeout). The
+//! response lands in a dynamic panel with the status, headers and a
+//! pretty-printed JSON body. Credential headers are masked in the panel and
+//! in the conversation's record of the call.
+
+/// Response panel.
+mod panel;
+/// Credential masking for headers and the recorded tool input.
+mod redact;
+/// Request validation, sending and response formatting.
+mod request;
+/// Tool dispatch: `http_request`.
+mod tools;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+/// Lazily parsed tool texts from the http YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/http.yaml")));
+
+/// Http module: HTTP requests with responses as panels.
+#[derive(Debug, Clone, Copy)]
+pub struct HttpModule;
+
+impl Default for HttpModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for HttpModule {
+    fn id(&self) -> &'static str {
+        "http"
+    }
+
+    fn name(&self) -> &'static str {
+        "HTTP"
+    }
+
+    fn description(&self) -> &'static str {
+        "HTTP requests with the response shown as a panel"
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &["core"]
+    }
+
+    fn is_global(&self) -> bool {
+        true
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: panel::HTTP_PANEL_TYPE,
+            icon_id: "scrape",
+            is_fixed: false,
+            needs_cache: false,
+            fixed_order: None,
+            display_name: "http",
+            short_name: "http",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(panel::HTTP_PANEL_TYPE)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![
+            ToolDefinition::from_yaml("http_request", &TOOL_TEXTS)
+                .short_desc("Send an HTTP request")
+                .category("HTTP")
+                .param_enum("method", request::METHODS, false)
+                .param("url", ParamType::String, true)
+                .param_array("headers", ParamType::String, false)
+                .param("body", ParamType::String, false)
+                .param("timeout", ParamType::Integer, false)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        tools::dispatch(tool, state)
+    }
+
+    fn transcript_input(&self, tool: &ToolUse) -> Option<serde_json::Value> {
+        (tool.name == "http_request").then(|| redact::transcript_input(&tool.input)).flatten()
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        (context_type.as_str() == panel::HTTP_PANEL_TYPE).then(|| {
+            let panel: Box<dyn Panel> = Box::new(panel::ResponsePanel);
+            panel
+        })
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("HTTP", "Call HTTP APIs and inspect responses")]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+}
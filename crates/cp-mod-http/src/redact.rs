@@ -0,0 +1,63 @@
+use serde_json::Value;
+
+/// Headers whose values are credentials (compared case-insensitively).
+const SENSITIVE_HEADERS: &[&str] =
+    &["authorization", "proxy-authorization", "cookie", "set-cookie", "x-api-key", "api-key", "x-auth-token"];
+
+/// Stands in for a masked value.
+const MASK: &str = "[REDACTED]";
+
+/// The header `name` carries a credential.
+fn is_sensitive(name: &str) -> bool {
+    SENSITIVE_HEADERS.iter().any(|s| name.trim().eq_ignore_ascii_case(s))
+}
+
+/// `value` of the header `name`, masked when it is a credential. The scheme of
+/// an `Authorization` value (`Bearer`, `Basic`) stays visible.
+pub(crate) fn mask_value(name: &str, value: &str) -> String {
+    if !is_sensitive(name) {
+        return value.trim().to_owned();
+    }
+    if name.trim().to_ascii_lowercase().ends_with("authorization")
+        && let Some((scheme, _secret)) = value.trim().split_once(' ')
+    {
+        return format!("{scheme} {MASK}");
+    }
+    MASK.to_owned()
+}
+
+/// A `Name: value` header line with its value masked.
+pub(crate) fn mask_line(line: &str) -> String {
+    line.split_once(':').map_or_else(|| line.to_owned(), |(name, value)| format!("{name}: {}", mask_value(name, value)))
+}
+
+/// `http_request` input as kept in the conversation: header values masked.
+/// `None` when the call sends no headers.
+pub(crate) fn transcript_input(input: &Value) -> Option<Value> {
+    let headers = input.get("headers")?.as_array()?;
+    let masked: Vec<Value> =
+        headers.iter().map(|h| h.as_str().map_or_else(|| h.clone(), |line| Value::String(mask_line(line)))).collect();
+    let mut out = input.clone();
+    drop(out.as_object_mut()?.insert("headers".to_owned(), Value::Array(masked)));
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_are_masked_in_the_transcript() {
+        let input = serde_json::json!({
+            "url": "https://api.example.com/v1/items",
+            "headers": ["Authorization: Bearer sk-live-123", "x-api-key: abc", "Accept: application/json"],
+        });
+        let expected = serde_json::json!({
+            "url": "https://api.example.com/v1/items",
+            "headers": ["Authorization: Bearer [REDACTED]", "x-api-key: [REDACTED]", "Accept: application/json"],
+        });
+        assert_eq!(transcript_input(&input), Some(expected));
+        assert_eq!(mask_value("Cookie", "session=42"), MASK);
+        assert_eq!(transcript_input(&serde_json::json!({ "url": "https://example.com" })), None);
+    }
+}
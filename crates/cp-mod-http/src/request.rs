@@ -0,0 +1,148 @@
+use std::fmt::Write as _;
+use std::io::Read as _;
+use std::time::{Duration, Instant};
+
+use reqwest::Method;
+use reqwest::blocking::Client;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
+
+use crate::redact::mask_value;
+
+/// Methods `http_request` accepts.
+pub(crate) const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// Seconds a request may take when no timeout is given.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Highest timeout accepted, in seconds.
+pub(crate) const MAX_TIMEOUT_SECS: u64 = 300;
+
+/// Response body KB read at most; the rest is dropped.
+const MAX_BODY_KB: u64 = 256;
+
+/// [`MAX_BODY_KB`] in bytes.
+const MAX_BODY_BYTES: u64 = MAX_BODY_KB * 1024;
+
+/// One request, validated from the tool input.
+#[derive(Debug)]
+pub(crate) struct Call {
+    /// HTTP method.
+    pub method: Method,
+    /// Absolute `http(s)://` URL.
+    pub url: String,
+    /// Request headers.
+    pub headers: HeaderMap,
+    /// Request body, sent as-is.
+    pub body: Option<String>,
+    /// Whole-request timeout.
+    pub timeout: Duration,
+}
+
+/// What came back, ready to show.
+#[derive(Debug)]
+pub(crate) struct Exchange {
+    /// One-line outcome: status, time and size.
+    pub summary: String,
+    /// Panel body: request, status, headers and the (pretty-printed) body.
+    pub content: String,
+}
+
+/// Parse `Name: value` header lines.
+fn parse_headers(input: &Value) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    let lines = input.get("headers").and_then(Value::as_array).map_or(&[][..], Vec::as_slice);
+    for line in lines.iter().filter_map(Value::as_str) {
+        let (name, value) = line.split_once(':').ok_or_else(|| format!("header '{line}' is not 'Name: value'"))?;
+        let header_name =
+            HeaderName::from_bytes(name.trim().as_bytes()).map_err(|e| format!("header '{name}': {e}"))?;
+        let header_value = HeaderValue::from_str(value.trim()).map_err(|e| format!("header '{name}': {e}"))?;
+        let _appended = headers.append(header_name, header_value);
+    }
+    Ok(headers)
+}
+
+impl Call {
+    /// Validate the tool input.
+    pub(crate) fn from_input(input: &Value) -> Result<Self, String> {
+        let url = input.get("url").and_then(Value::as_str).ok_or("Missing required parameter 'url'")?.trim();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("'{url}' is not an http:// or https:// URL"));
+        }
+        let name = input.get("method").and_then(Value::as_str).unwrap_or("GET").to_ascii_uppercase();
+        if !METHODS.contains(&name.as_str()) {
+            return Err(format!("unsupported method '{name}' (expected one of {})", METHODS.join(", ")));
+        }
+        let method = Method::from_bytes(name.as_bytes()).map_err(|e| e.to_string())?;
+        let mut headers = parse_headers(input)?;
+        let body = input.get("body").and_then(Value::as_str).map(str::to_owned);
+        if body.as_deref().is_some_and(|b| serde_json::from_str::<Value>(b).is_ok())
+            && !headers.contains_key(CONTENT_TYPE)
+        {
+            drop(headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json")));
+        }
+        let secs =
+            input.get("timeout").and_then(Value::as_u64).unwrap_or(DEFAULT_TIMEOUT_SECS).clamp(1, MAX_TIMEOUT_SECS);
+        Ok(Self { method, url: url.to_owned(), headers, body, timeout: Duration::from_secs(secs) })
+    }
+
+    /// Send the request and describe the response.
+    pub(crate) fn send(self) -> Result<Exchange, String> {
+        let client = Client::builder().timeout(self.timeout).build().map_err(|e| e.to_string())?;
+        let mut content = String::new();
+        push_request(&mut content, &self);
+        let mut request = client.request(self.method, &self.url).headers(self.headers);
+        if let Some(body) = self.body {
+            request = request.body(body);
+        }
+        let started = Instant::now();
+        let response = request.send().map_err(|e| format!("request failed: {e}"))?;
+        let status = response.status();
+        let _r = writeln!(content, "## Response\n\nHTTP {status} ({} ms)\n", started.elapsed().as_millis());
+        push_headers(&mut content, response.headers());
+        let mut bytes = Vec::new();
+        let _read = response.take(MAX_BODY_BYTES.saturating_add(1)).read_to_end(&mut bytes);
+        let summary = format!("HTTP {status} ({} ms, {} bytes)", started.elapsed().as_millis(), bytes.len());
+        push_body(&mut content, &bytes);
+        Ok(Exchange { summary, content })
+    }
+}
+
+/// Append the request line, its headers (credentials masked) and body size.
+fn push_request(content: &mut String, call: &Call) {
+    let _r = writeln!(content, "## Request\n\n{} {}\n", call.method, call.url);
+    push_headers(content, &call.headers);
+    if let Some(body) = call.body.as_ref() {
+        let _r2 = writeln!(content, "Body: {} bytes\n", body.len());
+    }
+}
+
+/// Append `name: value` lines with credentials masked.
+fn push_headers(content: &mut String, headers: &HeaderMap) {
+    for (name, value) in headers {
+        let text = String::from_utf8_lossy(value.as_bytes());
+        let _r = writeln!(content, "{name}: {}", mask_value(name.as_str(), &text));
+    }
+    content.push('\n');
+}
+
+/// Append the response body: JSON pretty-printed, text as-is, binary noted.
+fn push_body(content: &mut String, bytes: &[u8]) {
+    content.push_str("## Body\n\n");
+    let truncated = u64::try_from(bytes.len()).unwrap_or(u64::MAX) > MAX_BODY_BYTES;
+    let kept = bytes.get(..usize::try_from(MAX_BODY_BYTES).unwrap_or(usize::MAX)).unwrap_or(bytes);
+    if kept.is_empty() {
+        content.push_str("(empty)\n");
+        return;
+    }
+    let Ok(text) = std::str::from_utf8(kept) else {
+        let _r = writeln!(content, "[binary body, {} bytes]", kept.len());
+        return;
+    };
+    let pretty = serde_json::from_str::<Value>(text).ok().and_then(|json| serde_json::to_string_pretty(&json).ok());
+    let fence = if pretty.is_some() { "json" } else { "" };
+    let _r = writeln!(content, "```{fence}\n{}\n```", pretty.as_deref().unwrap_or(text).trim_end());
+    if truncated {
+        let _r2 = writeln!(content, "\n(truncated at {MAX_BODY_KB} KB)");
+    }
+}
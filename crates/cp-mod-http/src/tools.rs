@@ -0,0 +1,48 @@
+use cp_base::state::runtime::State;
+use cp_base::state::watchers::DYN_PANEL_ID_PLACEHOLDER;
+use cp_base::state::watchers::carriers::DynPanel;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::panel::{HTTP_PANEL_TYPE, META_CONTENT};
+use crate::request::{Call, MAX_TIMEOUT_SECS};
+
+/// Seconds the async task outlives the longest request timeout.
+const ASYNC_GRACE_SECS: u64 = 5;
+
+/// Dispatch http tool calls.
+pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+    (tool.name == "http_request").then(|| execute_request(tool, state))
+}
+
+/// Build an error `ToolResult` for input validation failures.
+fn err_result(tool: &ToolUse, content: String) -> ToolResult {
+    ToolResult {
+        tool_use_id: tool.id.clone(),
+        content,
+        display: None,
+        tldr: None,
+        is_error: true,
+        preserves_tempo: false,
+        tool_name: tool.name.clone(),
+    }
+}
+
+/// Execute `http_request`: send on a worker thread, response into a panel.
+fn execute_request(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let call = match Call::from_input(&tool.input) {
+        Ok(call) => call,
+        Err(e) => return err_result(tool, e),
+    };
+    let title = format!("http: {} {}", call.method, call.url);
+    let timeout = call.timeout.as_secs().min(MAX_TIMEOUT_SECS).saturating_add(ASYNC_GRACE_SECS);
+    spawn_async_tool(state, tool, timeout, move || match call.send() {
+        Ok(exchange) => {
+            let panel = DynPanel::new(HTTP_PANEL_TYPE.to_owned(), title)
+                .metadata(vec![(META_CONTENT.to_owned(), exchange.content.clone())])
+                .content(exchange.content);
+            ToolOutput::ok(format!("Created panel {DYN_PANEL_ID_PLACEHOLDER}: {}", exchange.summary)).with_panel(panel)
+        }
+        Err(e) => ToolOutput::error(e),
+    })
+}
@@ -0,0 +1,183 @@
+//! Command classification for kubectl commands.
+
+/// Accumulator state for the quote-aware shell-args lexer.
+struct ShellLexer {
+    /// Inside a single-quoted span (double quotes then lose their meaning).
+    in_single: bool,
+    /// Inside a double-quoted span (single quotes then lose their meaning).
+    in_double: bool,
+    /// Completed argument tokens.
+    args: Vec<String>,
+    /// Token currently being built.
+    current: String,
+}
+
+impl ShellLexer {
+    /// Fresh lexer with empty accumulators.
+    const fn new() -> Self {
+        Self { in_single: false, in_double: false, args: Vec::new(), current: String::new() }
+    }
+
+    /// Feed one character, updating quote state and flushing tokens on unquoted
+    /// whitespace. Kept flat so [`parse_shell_args`] stays a plain iteration.
+    fn feed(&mut self, c: char) {
+        match c {
+            '\'' if !self.in_double => self.in_single = !self.in_single,
+            '"' if !self.in_single => self.in_double = !self.in_double,
+            ws if ws.is_whitespace() && !self.in_single && !self.in_double => {
+                if !self.current.is_empty() {
+                    self.args.push(std::mem::take(&mut self.current));
+                }
+            }
+            _ => self.current.push(c),
+        }
+    }
+
+    /// Finalize: error on an unterminated quote, else flush the trailing token.
+    fn finish(mut self) -> Result<Vec<String>, String> {
+        if self.in_single {
+            return Err("Unterminated single quote".to_owned());
+        }
+        if self.in_double {
+            return Err("Unterminated double quote".to_owned());
+        }
+        if !self.current.is_empty() {
+            self.args.push(self.current);
+        }
+        Ok(self.args)
+    }
+}
+
+/// Parse a command string into arguments, respecting single and double quotes.
+fn parse_shell_args(command: &str) -> Result<Vec<String>, String> {
+    let mut lexer = ShellLexer::new();
+    for c in command.chars() {
+        lexer.feed(c);
+    }
+    lexer.finish()
+}
+
+/// Check for shell metacharacters outside of quoted strings.
+fn check_shell_operators(command: &str) -> Result<(), String> {
+    let mut in_single = false;
+    let mut in_double = false;
+    let chars: Vec<char> = command.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            _ if in_single || in_double => {}
+            '|' | ';' | '`' | '>' | '<' => {
+                return Err(format!("Shell operator '{c}' is not allowed"));
+            }
+            '$' if chars.get(i.saturating_add(1)) == Some(&'(') => {
+                return Err("Shell operator '$(' is not allowed".to_owned());
+            }
+            '&' if chars.get(i.saturating_add(1)) == Some(&'&') => {
+                return Err("Shell operator '&&' is not allowed".to_owned());
+            }
+            '\n' | '\r' => {
+                return Err("Newlines are not allowed outside of quoted strings".to_owned());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Validate a raw command string intended for `kubectl`.
+/// Returns parsed args (without `kubectl`) on success, or an error message.
+pub(crate) fn validate_kubectl_command(command: &str) -> Result<Vec<String>, String> {
+    let trimmed = command.trim();
+    if !trimmed.starts_with("kubectl ") && trimmed != "kubectl" {
+        return Err("Command must start with 'kubectl '".to_owned());
+    }
+
+    check_shell_operators(trimmed)?;
+
+    let args: Vec<String> = parse_shell_args(trimmed)?.into_iter().skip(1).collect();
+    if verb(&args).is_none() {
+        return Err("No kubectl subcommand specified".to_owned());
+    }
+    Ok(args)
+}
+
+/// Global flags whose value may follow as a separate argument.
+const VALUE_FLAGS: &[&str] =
+    &["--cluster", "--context", "--kubeconfig", "--namespace", "--request-timeout", "--server", "--user", "-n", "-s"];
+
+/// Verbs that only read cluster state.
+const READ_ONLY_VERBS: &[&str] = &[
+    "api-resources",
+    "api-versions",
+    "cluster-info",
+    "describe",
+    "diff",
+    "events",
+    "explain",
+    "get",
+    "logs",
+    "top",
+    "version",
+];
+
+/// `(verb, action)` pairs that only read, for verbs that also write.
+const READ_ONLY_ACTIONS: &[(&str, &str)] = &[
+    ("auth", "can-i"),
+    ("auth", "whoami"),
+    ("config", "current-context"),
+    ("config", "get-clusters"),
+    ("config", "get-contexts"),
+    ("config", "get-users"),
+    ("config", "view"),
+    ("rollout", "history"),
+    ("rollout", "status"),
+];
+
+/// Positional arguments, global flags (and their values) left out.
+fn positionals(args: &[String]) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut skip_value = false;
+    for arg in args.iter().map(String::as_str) {
+        if skip_value {
+            skip_value = false;
+        } else if arg.starts_with('-') {
+            skip_value = VALUE_FLAGS.contains(&arg);
+        } else {
+            out.push(arg);
+        }
+    }
+    out
+}
+
+/// The kubectl verb (`get`, `apply`, ...), global flags skipped.
+pub(crate) fn verb(args: &[String]) -> Option<&str> {
+    positionals(args).first().copied()
+}
+
+/// Whether `args` only read cluster state. Unknown verbs count as mutating.
+pub(crate) fn is_read_only(args: &[String]) -> bool {
+    let words = positionals(args);
+    let (Some(&verb_word), action) = (words.first(), words.get(1).copied().unwrap_or("")) else {
+        return false;
+    };
+    READ_ONLY_VERBS.contains(&verb_word) || READ_ONLY_ACTIONS.contains(&(verb_word, action))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutating_verbs_are_told_apart() {
+        let read = validate_kubectl_command("kubectl -n prod get pods -l 'app=api'");
+        assert_eq!(read.as_deref().map(verb), Ok(Some("get")));
+        assert_eq!(read.as_deref().map(is_read_only), Ok(true));
+        let rollout = validate_kubectl_command("kubectl --context staging rollout restart deploy/api");
+        assert_eq!(rollout.as_deref().map(is_read_only), Ok(false));
+        let status = validate_kubectl_command("kubectl rollout status deploy/api");
+        assert_eq!(status.as_deref().map(is_read_only), Ok(true));
+        assert!(validate_kubectl_command("kubectl delete pod x; rm -rf /").ok().is_none());
+    }
+}
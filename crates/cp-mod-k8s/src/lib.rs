@@ -0,0 +1,172 @@
+//! K8s module — inspect Kubernetes through kubectl.
+//!
+//! Read-only tools against the current kubecontext: `k8s_get`,
+//! `k8s_describe` and `k8s_logs`, each result in a panel cut to a size
+//! budget. `k8s_execute` runs a raw kubectl command: read-only verbs always,
+//! mutating ones (`apply`, `delete`, `rollout restart`, ...) only when the
+//! user whitelisted them with `/k8s-allow`; the AI has no tool to do so.
+
+/// Command classification for kubectl commands.
+mod classify;
+/// Result panel.
+mod panel;
+/// Tool dispatch and kubectl execution.
+mod tools;
+/// K8s state types: `K8sState`.
+mod types;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::types::K8sState;
+
+/// Lazily parsed tool texts from the k8s YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/k8s.yaml")));
+
+/// Set the mutating kubectl verbs `k8s_execute` may run, replacing the
+/// previous list (empty = read-only). User-side only: no tool calls this.
+pub fn allow_verbs(state: &mut State, verbs: &[&str]) -> String {
+    tools::allow(state, verbs)
+}
+
+/// K8s module: cluster inspection with guarded kubectl.
+#[derive(Debug, Clone, Copy)]
+pub struct K8sModule;
+
+impl Default for K8sModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl K8sModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for K8sModule {
+    fn id(&self) -> &'static str {
+        "k8s"
+    }
+
+    fn name(&self) -> &'static str {
+        "Kubernetes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Inspect Kubernetes resources and logs through kubectl"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: panel::K8S_PANEL_TYPE,
+            icon_id: "radar",
+            is_fixed: false,
+            needs_cache: false,
+            fixed_order: None,
+            display_name: "k8s",
+            short_name: "k8s",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(panel::K8S_PANEL_TYPE)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("k8s_get", t)
+                .short_desc("List Kubernetes resources")
+                .category("Kubernetes")
+                .param("resource", ParamType::String, true)
+                .param("name", ParamType::String, false)
+                .param("namespace", ParamType::String, false)
+                .param("all_namespaces", ParamType::Boolean, false)
+                .param("selector", ParamType::String, false)
+                .param_enum("output", tools::GET_OUTPUTS, false)
+                .build(),
+            ToolDefinition::from_yaml("k8s_describe", t)
+                .short_desc("Describe Kubernetes resources")
+                .category("Kubernetes")
+                .param("resource", ParamType::String, true)
+                .param("name", ParamType::String, false)
+                .param("namespace", ParamType::String, false)
+                .param("selector", ParamType::String, false)
+                .build(),
+            ToolDefinition::from_yaml("k8s_logs", t)
+                .short_desc("Read pod logs")
+                .category("Kubernetes")
+                .param("pod", ParamType::String, true)
+                .param("namespace", ParamType::String, false)
+                .param("container", ParamType::String, false)
+                .param("tail", ParamType::Integer, false)
+                .param("since", ParamType::String, false)
+                .param("previous", ParamType::Boolean, false)
+                .build(),
+            ToolDefinition::from_yaml("k8s_execute", t)
+                .short_desc("Run a kubectl command")
+                .category("Kubernetes")
+                .param("command", ParamType::String, true)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        tools::dispatch(tool, state)
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        (context_type.as_str() == panel::K8S_PANEL_TYPE).then(|| {
+            let panel: Box<dyn Panel> = Box::new(panel::ResultPanel);
+            panel
+        })
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Kubernetes", "Inspect the cluster of the current kubecontext")]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(K8sState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(K8sState::new());
+    }
+
+    fn save_worker_data(&self, state: &State) -> serde_json::Value {
+        serde_json::json!({ "allowed_verbs": K8sState::get(state).allowed_verbs })
+    }
+
+    fn load_worker_data(&self, data: &serde_json::Value, state: &mut State) {
+        if let Some(v) = data.get("allowed_verbs")
+            && let Ok(verbs) = serde_json::from_value::<Vec<String>>(v.clone())
+        {
+            K8sState::get_mut(state).allowed_verbs = verbs;
+        }
+    }
+
+    fn overview_context_section(&self, state: &State) -> Option<String> {
+        let verbs = &state.get_ext::<K8sState>()?.allowed_verbs;
+        (!verbs.is_empty()).then(|| format!("Kubernetes: k8s_execute may run kubectl {}\n", verbs.join(", ")))
+    }
+}
@@ -0,0 +1,211 @@
+use std::process::Command;
+
+use cp_base::modules::run_with_timeout;
+use cp_base::state::runtime::State;
+use cp_base::state::watchers::DYN_PANEL_ID_PLACEHOLDER;
+use cp_base::state::watchers::carriers::DynPanel;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::classify::{is_read_only, validate_kubectl_command, verb};
+use crate::panel::{K8S_PANEL_TYPE, META_CONTENT};
+use crate::types::K8sState;
+
+/// Seconds a kubectl call may take.
+const KUBECTL_TIMEOUT_SECS: u64 = 30;
+
+/// Output bytes kept for `get`, `describe` and `k8s_execute` (head kept).
+const LISTING_BUDGET_BYTES: usize = 32_000;
+
+/// Output bytes kept for logs (tail kept: the latest lines matter).
+const LOGS_BUDGET_BYTES: usize = 48_000;
+
+/// Log lines fetched when no tail is given.
+const DEFAULT_TAIL_LINES: u64 = 200;
+
+/// Highest log tail accepted.
+const MAX_TAIL_LINES: u64 = 5_000;
+
+/// Output formats `k8s_get` accepts.
+pub(crate) const GET_OUTPUTS: &[&str] = &["wide", "yaml", "json", "name"];
+
+/// Which end of oversized output survives.
+#[derive(Debug, Clone, Copy)]
+enum Budget {
+    /// Keep the first bytes.
+    Head(usize),
+    /// Keep the last bytes.
+    Tail(usize),
+}
+
+/// Dispatch k8s tool calls.
+pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+    let outcome = match tool.name.as_str() {
+        "k8s_get" => get_args(tool).map(|args| (args, Budget::Head(LISTING_BUDGET_BYTES))),
+        "k8s_describe" => describe_args(tool).map(|args| (args, Budget::Head(LISTING_BUDGET_BYTES))),
+        "k8s_logs" => logs_args(tool).map(|args| (args, Budget::Tail(LOGS_BUDGET_BYTES))),
+        "k8s_execute" => execute_args(tool, state).map(|args| (args, Budget::Head(LISTING_BUDGET_BYTES))),
+        _ => return None,
+    };
+    Some(match outcome {
+        Ok((args, budget)) => spawn_kubectl(tool, state, args, budget),
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    })
+}
+
+/// String parameter `key`, refused when it could pass as a flag.
+fn word(tool: &ToolUse, key: &str) -> Result<Option<String>, String> {
+    let Some(value) = tool.input.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    if value.starts_with('-') || value.chars().any(char::is_whitespace) {
+        return Err(format!("'{key}' must be a single name, got '{value}'"));
+    }
+    Ok(Some(value.to_owned()))
+}
+
+/// Required string parameter `key`.
+fn required_word(tool: &ToolUse, key: &str) -> Result<String, String> {
+    word(tool, key)?.ok_or_else(|| format!("Missing required parameter '{key}'"))
+}
+
+/// Append `-n namespace` (or `-A`) and `-l selector` when given.
+fn push_scope(tool: &ToolUse, args: &mut Vec<String>) -> Result<(), String> {
+    if tool.input.get("all_namespaces").and_then(serde_json::Value::as_bool) == Some(true) {
+        args.push("--all-namespaces".to_owned());
+    } else if let Some(namespace) = word(tool, "namespace")? {
+        args.extend(["-n".to_owned(), namespace]);
+    } else {
+        // Current namespace of the kubecontext.
+    }
+    if let Some(selector) = word(tool, "selector")? {
+        args.extend(["-l".to_owned(), selector]);
+    }
+    Ok(())
+}
+
+/// `kubectl get` arguments.
+fn get_args(tool: &ToolUse) -> Result<Vec<String>, String> {
+    let mut args = vec!["get".to_owned(), required_word(tool, "resource")?];
+    args.extend(word(tool, "name")?);
+    push_scope(tool, &mut args)?;
+    let output = word(tool, "output")?.unwrap_or_else(|| "wide".to_owned());
+    if !GET_OUTPUTS.contains(&output.as_str()) {
+        return Err(format!("'output' must be one of {}", GET_OUTPUTS.join(", ")));
+    }
+    args.extend(["-o".to_owned(), output]);
+    Ok(args)
+}
+
+/// `kubectl describe` arguments.
+fn describe_args(tool: &ToolUse) -> Result<Vec<String>, String> {
+    let mut args = vec!["describe".to_owned(), required_word(tool, "resource")?];
+    args.extend(word(tool, "name")?);
+    push_scope(tool, &mut args)?;
+    Ok(args)
+}
+
+/// `kubectl logs` arguments.
+fn logs_args(tool: &ToolUse) -> Result<Vec<String>, String> {
+    let mut args = vec!["logs".to_owned(), required_word(tool, "pod")?];
+    if let Some(namespace) = word(tool, "namespace")? {
+        args.extend(["-n".to_owned(), namespace]);
+    }
+    if let Some(container) = word(tool, "container")? {
+        args.extend(["-c".to_owned(), container]);
+    }
+    if let Some(since) = word(tool, "since")? {
+        args.push(format!("--since={since}"));
+    }
+    if tool.input.get("previous").and_then(serde_json::Value::as_bool) == Some(true) {
+        args.push("--previous".to_owned());
+    }
+    let tail = tool
+        .input
+        .get("tail")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(DEFAULT_TAIL_LINES)
+        .clamp(1, MAX_TAIL_LINES);
+    args.push(format!("--tail={tail}"));
+    Ok(args)
+}
+
+/// `k8s_execute` arguments: read-only verbs always, others when whitelisted.
+fn execute_args(tool: &ToolUse, state: &State) -> Result<Vec<String>, String> {
+    let command = tool.input.get("command").and_then(|v| v.as_str()).ok_or("Missing required parameter 'command'")?;
+    let args = validate_kubectl_command(command).map_err(|e| format!("Validation error: {e}"))?;
+    let name = verb(&args).unwrap_or_default();
+    if is_read_only(&args) || K8sState::get(state).allowed_verbs.iter().any(|v| v == name) {
+        return Ok(args);
+    }
+    Err(format!(
+        "'kubectl {name}' changes the cluster and is not whitelisted. Only the user can allow it, by \
+         typing /k8s-allow. Read-only inspection: k8s_get, k8s_describe, k8s_logs."
+    ))
+}
+
+/// Replace the whitelist of mutating verbs; a line saying what may now run.
+pub(crate) fn allow(state: &mut State, requested: &[&str]) -> String {
+    let verbs: Vec<String> = requested.iter().map(|v| v.trim().to_lowercase()).filter(|v| !v.is_empty()).collect();
+    let message = if verbs.is_empty() {
+        "Whitelist cleared: k8s_execute runs read-only commands only.".to_owned()
+    } else {
+        format!("k8s_execute may now run: kubectl {}.", verbs.join(", kubectl "))
+    };
+    K8sState::get_mut(state).allowed_verbs = verbs;
+    message
+}
+
+/// `text` within `budget`, with a note on what was cut.
+fn within_budget(text: &str, budget: Budget) -> String {
+    match budget {
+        Budget::Head(max) if text.len() > max => {
+            let kept = text.get(..text.floor_char_boundary(max)).unwrap_or_default();
+            format!("{kept}\n[output cut at {max} bytes; narrow the query]")
+        }
+        Budget::Tail(max) if text.len() > max => {
+            let kept = text.get(text.ceil_char_boundary(text.len().saturating_sub(max))..).unwrap_or_default();
+            let from_line = kept.split_once('\n').map_or(kept, |(_partial, rest)| rest);
+            format!("[earlier output cut; last {max} bytes shown]\n{from_line}")
+        }
+        Budget::Head(_) | Budget::Tail(_) => text.to_owned(),
+    }
+}
+
+/// `kubectl config current-context`, or a placeholder.
+fn current_context() -> String {
+    let mut cmd = Command::new("kubectl");
+    let _r = cmd.args(["config", "current-context"]);
+    run_with_timeout(cmd, KUBECTL_TIMEOUT_SECS)
+        .ok()
+        .filter(|out| out.status.success())
+        .map_or_else(|| "(none)".to_owned(), |out| String::from_utf8_lossy(&out.stdout).trim().to_owned())
+}
+
+/// Run kubectl on a worker thread; the output goes into a panel.
+fn spawn_kubectl(tool: &ToolUse, state: &mut State, args: Vec<String>, budget: Budget) -> ToolResult {
+    let command = format!("kubectl {}", args.join(" "));
+    spawn_async_tool(state, tool, KUBECTL_TIMEOUT_SECS.saturating_add(5), move || {
+        let mut cmd = Command::new("kubectl");
+        let _r = cmd.args(&args).env("KUBECTL_INTERACTIVE", "false");
+        let output = match run_with_timeout(cmd, KUBECTL_TIMEOUT_SECS) {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return ToolOutput::error("kubectl not found on PATH");
+            }
+            Err(e) => return ToolOutput::error(format!("Error running kubectl: {e}")),
+        };
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+        if !output.status.success() {
+            return ToolOutput::error(format!("{command} failed:\n{}", within_budget(&stderr, budget)));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines = stdout.lines().count();
+        let body = within_budget(stdout.trim_end(), budget);
+        let content = format!("Context: {}\n$ {command}\n\n{body}\n{stderr}", current_context());
+        let panel = DynPanel::new(K8S_PANEL_TYPE.to_owned(), cp_base::ui::text::ellipsize(&command, 40, "..."))
+            .metadata(vec![(META_CONTENT.to_owned(), content.clone())])
+            .content(content);
+        ToolOutput::ok(format!("Created panel {DYN_PANEL_ID_PLACEHOLDER}: {command} ({lines} lines)")).with_panel(panel)
+    })
+}
@@ -0,0 +1,36 @@
+use cp_base::state::runtime::State;
+
+/// Module-owned state for the K8s module.
+/// Stored in `State.module_data` via `TypeMap`.
+#[derive(Debug, Default)]
+pub(crate) struct K8sState {
+    /// Mutating kubectl verbs `k8s_execute` may run (e.g. `rollout`).
+    pub allowed_verbs: Vec<String>,
+}
+
+impl K8sState {
+    /// Create a state with nothing whitelisted.
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub(crate) fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub(crate) fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+}
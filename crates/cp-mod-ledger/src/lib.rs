@@ -0,0 +1,230 @@
+//! Ledger module — an automatic changelog of the agent's actions.
+//!
+//! Every file the main agent edits or writes, every command it runs, and
+//! every commit it makes is appended to `.context-pilot/actions.log` with a
+//! timestamp and the ID of the tool-call message, and listed in the Actions
+//! panel. When the session ends (the user quits) the session's entries are
+//! exported as a markdown work report under `.context-pilot/reports/`; the
+//! report can also be exported on demand from the command palette, or with
+//! `/report [focus]`, which has a cheap model summarize what was done, what
+//! remains and the risks on top of it.
+
+/// Actions panel rendering.
+mod panel;
+/// Tool-call classification and the append-only log file.
+mod record;
+/// Markdown work report.
+mod report;
+/// `/report`: the cheap model's summary of the session.
+mod summary;
+/// Ledger state types: `LedgerEntry`, `LedgerState`.
+pub mod types;
+
+use types::LedgerState;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::output_schema::StructuredRunner;
+use cp_base::tools::pre_flight::Verdict;
+use cp_base::tools::{ToolDefinition, ToolResult, ToolUse};
+
+use self::panel::LedgerPanel;
+
+/// Module ID (also the activation key in `active_modules`).
+const MODULE_ID: &str = "ledger";
+
+/// Record an executed tool call in the ledger if it touched a file, ran a
+/// command, or made a commit. No-op while the module is inactive.
+pub fn record(state: &mut State, tool: &ToolUse, result: &ToolResult) {
+    if state.active_modules.contains(MODULE_ID) {
+        record::record(state, tool, result);
+    }
+}
+
+/// Export the session's work report; returns its path.
+pub fn export_report(state: &mut State) -> Option<String> {
+    let path = report::export(state.get_ext::<LedgerState>()?)?;
+    LedgerState::get_mut(state).last_report = Some(path.clone());
+    Some(path)
+}
+
+/// Ask the cheap model for a summarized work report (the `/report` command);
+/// `focus` is the optional text typed after it. Returns `false` when the
+/// Actions panel is not open.
+pub fn request_summary(state: &mut State, focus: &str) -> bool {
+    state.active_modules.contains(MODULE_ID) && summary::request(state, focus)
+}
+
+/// End the session: export its work report (when anything was done) and
+/// start a fresh, empty one.
+pub fn end_session(state: &mut State) {
+    if state.get_ext::<LedgerState>().is_none_or(|ledger| ledger.entries.is_empty()) {
+        return;
+    }
+    let last_report = export_report(state);
+    state.set_ext(LedgerState { last_report, ..LedgerState::new() });
+}
+
+/// Ledger module: the Actions panel and the work report.
+#[derive(Debug, Clone, Copy)]
+pub struct LedgerModule {
+    /// Runs the `/report` summary query (the binary owns the LLM clients).
+    run: StructuredRunner,
+}
+
+impl LedgerModule {
+    /// Construct the module with the runner used for `/report` summaries.
+    #[must_use]
+    pub const fn new(run: StructuredRunner) -> Self {
+        Self { run }
+    }
+}
+
+impl Module for LedgerModule {
+    fn id(&self) -> &'static str {
+        MODULE_ID
+    }
+    fn name(&self) -> &'static str {
+        "Actions"
+    }
+    fn description(&self) -> &'static str {
+        "Changelog of files touched, commands run, and commits made"
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(LedgerState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(LedgerState::new());
+    }
+
+    fn save_module_data(&self, state: &State) -> serde_json::Value {
+        let ledger = LedgerState::get(state);
+        serde_json::json!({
+            "entries": ledger.entries,
+            "session_start_ms": ledger.session_start_ms,
+            "last_report": ledger.last_report,
+        })
+    }
+    fn load_module_data(&self, data: &serde_json::Value, state: &mut State) {
+        let ledger = LedgerState::get_mut(state);
+        if let Some(arr) = data.get("entries")
+            && let Ok(v) = serde_json::from_value(arr.clone())
+        {
+            ledger.entries = v;
+        }
+        if let Some(v) = data.get("session_start_ms").and_then(serde_json::Value::as_u64) {
+            ledger.session_start_ms = v;
+        }
+        if let Some(v) = data.get("last_report").and_then(serde_json::Value::as_str) {
+            ledger.last_report = Some(v.to_owned());
+        }
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::LEDGER)]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![(Kind::new(Kind::LEDGER), "Actions", false)]
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::LEDGER => Some(Box::new(LedgerPanel { run: self.run })),
+            _ => None,
+        }
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![]
+    }
+    fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<Verdict> {
+        None
+    }
+
+    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        None
+    }
+
+    fn context_type_metadata(&self) -> Vec<cp_base::state::context::TypeMeta> {
+        vec![cp_base::state::context::TypeMeta {
+            context_type: Kind::LEDGER,
+            icon_id: "ledger",
+            is_fixed: true,
+            needs_cache: false,
+            fixed_order: Some(11),
+            display_name: "actions",
+            short_name: "actions",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &[]
+    }
+    fn is_core(&self) -> bool {
+        false
+    }
+    fn is_global(&self) -> bool {
+        false
+    }
+    fn save_worker_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+    fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+    fn tool_visualizers(&self) -> Vec<(&'static str, cp_base::modules::ToolVisualizer)> {
+        vec![]
+    }
+    fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
+        None
+    }
+    fn context_detail(&self, _ctx: &cp_base::state::context::Entry) -> Option<String> {
+        None
+    }
+    fn overview_context_section(&self, _state: &State) -> Option<String> {
+        None
+    }
+    fn overview_render_sections(&self, _state: &State) -> Vec<(u8, Vec<cp_render::Block>)> {
+        vec![]
+    }
+    fn on_close_context(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _state: &mut State,
+    ) -> Option<Result<String, String>> {
+        None
+    }
+    fn on_user_message(&self, _state: &mut State) {}
+    fn on_stream_stop(&self, _state: &mut State) {}
+
+    fn on_stream_chunk(&self, _text: &str, _state: &mut State) {}
+
+    fn on_tool_progress(&self, _tool_name: &str, _input_so_far: &str, _state: &mut State) {}
+
+    fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
+    fn watch_paths(&self, _state: &State) -> Vec<cp_base::panels::WatchSpec> {
+        vec![]
+    }
+    fn should_invalidate_on_fs_change(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _changed_path: &str,
+        _is_dir_event: bool,
+    ) -> bool {
+        false
+    }
+    fn watcher_immediate_refresh(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,154 @@
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel};
+use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+use cp_base::tools::output_schema::{StructuredQuery, StructuredRunner};
+
+use crate::report::{clock, local_time};
+use crate::summary::{self, WorkSummary};
+use crate::types::{EntryKind, LedgerEntry, LedgerState};
+use std::fmt::Write as _;
+
+/// Entries shown in the panel, newest first.
+const PANEL_ENTRIES: usize = 200;
+
+/// Entries included in the LLM context, newest first.
+const CONTEXT_ENTRIES: usize = 30;
+
+/// Panel listing the files touched, commands run, and commits made this session.
+pub(crate) struct LedgerPanel {
+    /// Runs the `/report` summary query on a cache worker.
+    pub run: StructuredRunner,
+}
+
+impl Panel for LedgerPanel {
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        let ledger = LedgerState::get(state);
+        let mut blocks = vec![
+            Block::Line(vec![S::muted(format!("  Session since {}", local_time(ledger.session_start_ms)))]),
+            Block::Line(vec![S::muted(format!("  {}", ledger.summary()))]),
+        ];
+        if let Some(path) = ledger.last_report.as_ref() {
+            blocks.push(Block::Line(vec![S::muted("  Last report: ".into()), S::accent(path.clone())]));
+        }
+        if let Some(status) = ledger.summary.as_ref() {
+            let line = status.error().map_or_else(
+                || S::warning("  Writing the work report\u{2026}".into()),
+                |error| S::error(format!("  Report failed: {error}")),
+            );
+            blocks.push(Block::Line(vec![line]));
+        }
+        blocks.push(Block::empty());
+
+        if ledger.entries.is_empty() {
+            blocks.push(Block::Line(vec![S::muted("  No actions yet.".into())]));
+            return blocks;
+        }
+
+        for entry in ledger.entries.iter().rev().take(PANEL_ENTRIES) {
+            let semantic = match entry.kind {
+                EntryKind::File => Semantic::Info,
+                EntryKind::Command => Semantic::Code,
+                EntryKind::Commit => Semantic::Success,
+            };
+            let subject = cp_base::ui::text::ellipsize(&entry.subject.replace('\n', " "), 100, "...");
+            blocks.push(Block::Line(vec![
+                S::muted(format!("  {} {:>5} ", clock(entry.timestamp_ms), entry.message_id)),
+                S::styled(format!("{} ", entry.kind.glyph()), semantic).bold(),
+                S::styled(subject, semantic),
+            ]));
+        }
+        blocks
+    }
+    fn title(&self, state: &State) -> String {
+        let ledger = LedgerState::get(state);
+        if ledger.entries.is_empty() { "Actions".to_owned() } else { format!("Actions ({})", ledger.entries.len()) }
+    }
+
+    fn refresh(&self, state: &mut State) {
+        let content = Self::format_context_text(state);
+        let token_count = estimate_tokens(&content);
+        for ctx in &mut state.context {
+            if ctx.context_type.as_str() == Kind::LEDGER {
+                ctx.token_count = token_count;
+                let _changed = cp_base::panels::update_if_changed(ctx, &content);
+                break;
+            }
+        }
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let content = Self::format_context_text(state);
+        let (id, last_refresh_ms) = state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == Kind::LEDGER)
+            .map_or(("", 0), |c| (c.id.as_str(), c.last_refresh_ms));
+        vec![ContextItem::new(id, "Actions", content, last_refresh_ms)]
+    }
+
+    fn handle_key(&self, _key: &crossterm::event::KeyEvent, _state: &State) -> Option<cp_base::state::actions::Action> {
+        None
+    }
+    fn needs_cache(&self) -> bool {
+        false
+    }
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        // Always answer, even on a foreign payload, so `cache_in_flight` clears.
+        let result = request
+            .data
+            .downcast::<StructuredQuery>()
+            .map_or_else(|_| Err("unexpected cache request".to_owned()), |query| summary::fetch(self.run, &query));
+        Some(CacheUpdate::ModuleSpecific { context_type: Kind::new(Kind::LEDGER), data: Box::new(result) })
+    }
+    fn build_cache_request(&self, _ctx: &cp_base::state::context::Entry, state: &State) -> Option<CacheRequest> {
+        let query = summary::pending_query(state)?;
+        Some(CacheRequest::new(Kind::new(Kind::LEDGER), Box::new(query)))
+    }
+    fn apply_cache_update(
+        &self,
+        update: CacheUpdate,
+        ctx: &mut cp_base::state::context::Entry,
+        state: &mut State,
+    ) -> bool {
+        ctx.cache_deprecated = false;
+        let CacheUpdate::ModuleSpecific { data, .. } = update else { return false };
+        let Ok(result) = data.downcast::<Result<WorkSummary, String>>() else { return false };
+        summary::receive(state, *result);
+        true
+    }
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        None
+    }
+    fn suicide(&self, _ctx: &cp_base::state::context::Entry, _state: &State) -> bool {
+        false
+    }
+}
+
+/// One plain-text line per entry: time, message ID, kind, subject.
+fn context_line(text: &mut String, entry: &LedgerEntry) {
+    let subject = cp_base::ui::text::ellipsize(&entry.subject.replace('\n', " "), 160, "...");
+    let _r = writeln!(text, "{} {} {} {subject}", clock(entry.timestamp_ms), entry.message_id, entry.kind.label());
+}
+
+impl LedgerPanel {
+    /// Shared text builder for both `refresh()` and `context()`
+    fn format_context_text(state: &State) -> String {
+        let ledger = LedgerState::get(state);
+        let mut text = format!("Actions this session: {}.\n", ledger.summary());
+        let skipped = ledger.entries.len().saturating_sub(CONTEXT_ENTRIES);
+        if skipped > 0 {
+            let _r = writeln!(text, "({skipped} older action(s) omitted)");
+        }
+        for entry in ledger.entries.iter().skip(skipped) {
+            context_line(&mut text, entry);
+        }
+        text
+    }
+}
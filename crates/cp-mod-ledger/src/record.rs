@@ -0,0 +1,113 @@
+//! Classification of executed tool calls and the append-only `actions.log`.
+
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use cp_base::config::constants;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::types::{EntryKind, LedgerEntry, LedgerState};
+
+/// Append-only ledger file inside the store directory.
+const LOG_FILE: &str = "actions.log";
+
+/// Path of the append-only ledger.
+fn log_path() -> PathBuf {
+    PathBuf::from(constants::STORE_DIR).join(LOG_FILE)
+}
+
+/// Record `tool` if it touched a file, ran a command, or made a commit.
+/// Failed calls and every other tool are ignored.
+pub(crate) fn record(state: &mut State, tool: &ToolUse, result: &ToolResult) {
+    if result.is_error || result.content.starts_with("Queued as #") {
+        return;
+    }
+    let Some((kind, subject)) = classify(tool) else { return };
+    // The tool-call message is saved before the tool runs, so it is the newest one
+    let message_id = state.messages.last().map(|m| m.id.clone()).unwrap_or_default();
+    let entry =
+        LedgerEntry { timestamp_ms: cp_base::panels::now_ms(), kind, tool: tool.name.clone(), subject, message_id };
+    append_line(&entry);
+    if let Some(ledger) = state.get_ext_mut::<LedgerState>() {
+        ledger.entries.push(entry);
+    }
+}
+
+/// What `tool` did, with its file path, command line, or commit message.
+fn classify(tool: &ToolUse) -> Option<(EntryKind, String)> {
+    let param = |name: &str| tool.input.get(name).and_then(serde_json::Value::as_str).map(str::to_owned);
+    match tool.name.as_str() {
+        "Edit" | "Write" => param("file_path").map(|path| (EntryKind::File, path)),
+        "console_create" | "console_easy_bash" | "git_execute" | "gh_execute" => {
+            param("command").map(|command| command_entry(&command))
+        }
+        _ => None,
+    }
+}
+
+/// A command line, or its commit message when it runs `git commit`.
+fn command_entry(command: &str) -> (EntryKind, String) {
+    if runs_commit(command) {
+        (EntryKind::Commit, commit_message(command).unwrap_or(command).to_owned())
+    } else {
+        (EntryKind::Command, command.to_owned())
+    }
+}
+
+/// Whether some `git` invocation in `command` has `commit` as its subcommand.
+fn runs_commit(command: &str) -> bool {
+    let mut tokens = command.split_whitespace();
+    while tokens.any(|token| token == "git") {
+        if tokens.clone().find(|token| !token.starts_with('-')) == Some("commit") {
+            return true;
+        }
+    }
+    false
+}
+
+/// The `-m` message of a commit command, unquoted.
+fn commit_message(command: &str) -> Option<&str> {
+    let (_, after_flag) = command.split_once(" -m ").or_else(|| command.split_once(" -am "))?;
+    let rest = after_flag.trim_start();
+    let message = match rest.chars().next() {
+        Some(quote @ ('"' | '\'')) => rest.get(1..).and_then(|inner| inner.split(quote).next()),
+        Some(_) | None => rest.split_whitespace().next(),
+    };
+    message.filter(|m| !m.is_empty())
+}
+
+/// Append `entry` to `actions.log` as one tab-separated line:
+/// time, message ID, kind, tool, subject.
+fn append_line(entry: &LedgerEntry) {
+    let when = i64::try_from(entry.timestamp_ms).ok().and_then(cp_mod_utilities::time::epoch_ms_to_rfc3339);
+    let subject = entry.subject.replace(['\n', '\t'], " ");
+    let line = format!(
+        "{}\t{}\t{}\t{}\t{subject}\n",
+        when.unwrap_or_default(),
+        entry.message_id,
+        entry.kind.label(),
+        entry.tool
+    );
+    let path = log_path();
+    if let Some(dir) = path.parent() {
+        let _r = std::fs::create_dir_all(dir);
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _r = file.write_all(line.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commits_are_told_apart_from_commands() {
+        let commit = |message: &str| (EntryKind::Commit, message.to_owned());
+        assert_eq!(command_entry("git commit -m \"Fix parser\" --no-verify"), commit("Fix parser"));
+        assert_eq!(command_entry("cargo fmt && git commit -am 'Tidy'"), commit("Tidy"));
+        assert_eq!(command_entry("git commit --amend --no-edit"), commit("git commit --amend --no-edit"));
+        assert_eq!(command_entry("git log --oneline"), (EntryKind::Command, "git log --oneline".to_owned()));
+    }
+}
@@ -0,0 +1,92 @@
+//! Markdown work report of a session's ledger.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use cp_base::config::constants;
+
+use crate::summary::WorkSummary;
+use crate::types::{EntryKind, LedgerEntry, LedgerState};
+
+/// Reports subdirectory inside the store directory.
+const REPORTS_DIR: &str = "reports";
+
+/// Local `YYYY-MM-DD HH:MM:SS` of an epoch-ms timestamp.
+pub(crate) fn local_time(ms: u64) -> String {
+    i64::try_from(ms).ok().and_then(cp_mod_utilities::time::epoch_ms_to_local_ymd_hms).unwrap_or_default()
+}
+
+/// Local `HH:MM:SS` of an epoch-ms timestamp.
+pub(crate) fn clock(ms: u64) -> String {
+    let full = local_time(ms);
+    full.split_once(' ').map_or_else(String::new, |(_, time)| time.to_owned())
+}
+
+/// Write the report to `.context-pilot/reports/` and return its path.
+pub(crate) fn export(ledger: &LedgerState) -> Option<String> {
+    write(&render(ledger, None, cp_base::panels::now_ms()))
+}
+
+/// Write the report with the model's `summary` on top and return its path.
+pub(crate) fn export_summary(ledger: &LedgerState, summary: &WorkSummary) -> Option<String> {
+    write(&render(ledger, Some(summary), cp_base::panels::now_ms()))
+}
+
+/// Save `contents` as a new timestamped file in the reports directory.
+fn write(contents: &str) -> Option<String> {
+    let dir = PathBuf::from(constants::STORE_DIR).join(REPORTS_DIR);
+    std::fs::create_dir_all(&dir).ok()?;
+    let stamp = cp_mod_utilities::time::now_local_ymd_hms_file();
+    let path = dir.join(format!("work-report-{stamp}.md"));
+    std::fs::write(&path, contents).ok()?;
+    Some(path.to_string_lossy().into_owned())
+}
+
+/// The report: the summary (when there is one), then commits, files touched
+/// (grouped by path) and commands.
+fn render(ledger: &LedgerState, summary: Option<&WorkSummary>, end_ms: u64) -> String {
+    let mut out = String::from("# Work report\n\n");
+    let _r = writeln!(out, "Session: {} \u{2192} {}", local_time(ledger.session_start_ms), local_time(end_ms));
+    let _r2 = writeln!(out, "{}.", ledger.summary());
+    if let Some(work) = summary {
+        work.push_markdown(&mut out);
+    }
+    push_timeline(&mut out, "Commits", ledger, EntryKind::Commit);
+    push_files(&mut out, ledger);
+    push_timeline(&mut out, "Commands run", ledger, EntryKind::Command);
+    out
+}
+
+/// A section listing the entries of `kind` in order.
+fn push_timeline(out: &mut String, title: &str, ledger: &LedgerState, kind: EntryKind) {
+    let entries: Vec<&LedgerEntry> = ledger.entries.iter().filter(|e| e.kind == kind).collect();
+    if entries.is_empty() {
+        return;
+    }
+    let _r = write!(out, "\n## {title}\n\n");
+    for entry in entries {
+        let one_line = entry.subject.replace('\n', " ");
+        let subject = if kind == EntryKind::Command { format!("`{one_line}`") } else { one_line };
+        let _r2 = writeln!(out, "- `{}` {subject} ({})", clock(entry.timestamp_ms), entry.message_id);
+    }
+}
+
+/// The files section: one line per path, first touched first, with the
+/// messages that changed it.
+fn push_files(out: &mut String, ledger: &LedgerState) {
+    let mut files: Vec<(&str, Vec<&str>)> = Vec::new();
+    for entry in ledger.entries.iter().filter(|e| e.kind == EntryKind::File) {
+        if let Some(&mut (_, ref mut ids)) = files.iter_mut().find(|file| file.0 == entry.subject) {
+            ids.push(&entry.message_id);
+        } else {
+            files.push((&entry.subject, vec![&entry.message_id]));
+        }
+    }
+    if files.is_empty() {
+        return;
+    }
+    out.push_str("\n## Files touched\n\n");
+    for (path, ids) in files {
+        let _r = writeln!(out, "- `{path}` \u{2014} {} change(s) ({})", ids.len(), ids.join(", "));
+    }
+}
@@ -0,0 +1,218 @@
+//! `/report` — a cheap model's summary of the session for a handoff or a
+//! standup.
+//!
+//! The ledger entries and the todo list are sent as a structured query to the
+//! active provider's cheapest model. The query runs on the cache pool (the
+//! Actions panel's cache request); the answer is written on top of the usual
+//! work report under `.context-pilot/reports/`.
+
+use std::fmt::Write as _;
+
+use serde::Deserialize;
+use serde_json::json;
+
+use cp_base::config::INJECTIONS;
+use cp_base::state::context::Kind;
+use cp_base::state::data::model_helpers::ModelPricing as _;
+use cp_base::state::runtime::State;
+use cp_base::tools::output_schema::{OutputSchema, StructuredQuery, StructuredRunner};
+use cp_mod_todo::types::{TodoState, TodoStatus};
+
+use crate::report::clock;
+use crate::types::{LedgerState, SummaryStatus};
+
+/// Characters of a ledger subject quoted in the prompt.
+const SUBJECT_CHARS: usize = 160;
+
+/// The model's summary of the session.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct WorkSummary {
+    /// One-sentence headline.
+    pub headline: String,
+    /// What was accomplished.
+    pub done: Vec<String>,
+    /// What is still open.
+    pub remaining: Vec<String>,
+    /// What deserves a second look.
+    pub risks: Vec<String>,
+}
+
+impl WorkSummary {
+    /// Append the headline and the Done / Remaining / Risks sections.
+    pub(crate) fn push_markdown(&self, out: &mut String) {
+        let _r = write!(out, "\n{}\n", self.headline);
+        for (title, items) in [("Done", &self.done), ("Remaining", &self.remaining), ("Risks", &self.risks)] {
+            if items.is_empty() {
+                continue;
+            }
+            let _r2 = write!(out, "\n## {title}\n\n");
+            for item in items {
+                let _r3 = writeln!(out, "- {item}");
+            }
+        }
+    }
+}
+
+/// Shape of the summary answer.
+fn summary_schema() -> OutputSchema {
+    let list = json!({"type": "array", "items": {"type": "string"}});
+    OutputSchema::new(
+        "work_report",
+        json!({
+            "type": "object",
+            "properties": {"headline": {"type": "string"}, "done": list, "remaining": list, "risks": list},
+            "required": ["headline", "done", "remaining", "risks"],
+            "additionalProperties": false
+        }),
+    )
+}
+
+/// One `time | message | kind | subject` line per ledger entry.
+fn action_lines(ledger: &LedgerState) -> String {
+    ledger
+        .entries
+        .iter()
+        .map(|e| {
+            let subject: String = e.subject.replace('\n', " ").chars().take(SUBJECT_CHARS).collect();
+            format!("{} | {} | {} | {subject}", clock(e.timestamp_ms), e.message_id, e.kind.label())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One `ID | status | name` line per todo.
+fn todo_lines(state: &State) -> String {
+    let Some(todos) = state.get_ext::<TodoState>() else { return String::new() };
+    todos
+        .todos
+        .iter()
+        .map(|t| {
+            let status = match t.status {
+                TodoStatus::Pending => "pending",
+                TodoStatus::InProgress => "in progress",
+                TodoStatus::Done => "done",
+            };
+            format!("{} | {status} | {}", t.id, t.name)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Ask for a summary focused on `focus` and dirty the Actions panel so the
+/// timer loop hands its cache request to a worker. Returns `false` when the
+/// panel is not open.
+pub(crate) fn request(state: &mut State, focus: &str) -> bool {
+    let Some(ctx) = state.context.iter_mut().find(|c| c.context_type.as_str() == Kind::LEDGER) else {
+        return false;
+    };
+    ctx.cache_deprecated = true;
+    LedgerState::get_mut(state).summary = Some(SummaryStatus::Pending { focus: focus.to_owned() });
+    state.flags.ui.dirty = true;
+    true
+}
+
+/// The structured query for a pending summary, sent to the cheap model.
+pub(crate) fn pending_query(state: &State) -> Option<StructuredQuery> {
+    let ledger = LedgerState::get(state);
+    let wanted = ledger.summary.as_ref().and_then(SummaryStatus::pending_focus)?;
+    let focus_text = if wanted.is_empty() { String::new() } else { format!("\nFocus the report on: {wanted}\n") };
+    let prompt = INJECTIONS
+        .providers
+        .work_report
+        .replace("{actions}", &action_lines(ledger))
+        .replace("{todos}", &todo_lines(state))
+        .replace("{focus}", &focus_text);
+    Some(StructuredQuery {
+        provider: state.llm_provider,
+        model: state.cheap_model(),
+        system: INJECTIONS.providers.work_report_system.clone(),
+        prompt,
+        schema: summary_schema(),
+        worker_id: "work-report".to_owned(),
+    })
+}
+
+/// Run `query` through `run` (blocking, on a worker thread) and decode the summary.
+pub(crate) fn fetch(run: StructuredRunner, query: &StructuredQuery) -> Result<WorkSummary, String> {
+    let value = run(query)?;
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// File a summary answer: write the report, or keep the error for the panel.
+pub(crate) fn receive(state: &mut State, result: Result<WorkSummary, String>) {
+    let ledger = LedgerState::get_mut(state);
+    let written = result.and_then(|summary| {
+        crate::report::export_summary(ledger, &summary).ok_or_else(|| "could not write the report file".to_owned())
+    });
+    match written {
+        Ok(path) => {
+            ledger.last_report = Some(path);
+            ledger.summary = None;
+        }
+        Err(error) => ledger.summary = Some(SummaryStatus::Failed(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EntryKind, LedgerEntry};
+    use cp_base::state::context::make_default_entry;
+    use cp_mod_todo::types::TodoItem;
+
+    /// A canned answer for reports focused on the parser; offline otherwise.
+    fn answer(query: &StructuredQuery) -> Result<serde_json::Value, String> {
+        if !query.prompt.contains("Focus the report on: the parser") {
+            return Err("offline".to_owned());
+        }
+        Ok(
+            json!({"headline": "Parser fixed.", "done": ["Fixed src/parse.rs"], "remaining": [], "risks": ["No error tests"]}),
+        )
+    }
+
+    /// A session that edited `src/parse.rs` with one todo in progress.
+    fn session() -> State {
+        let mut state = State::default();
+        let mut ledger = LedgerState::new();
+        let subject = "src/parse.rs".to_owned();
+        let edit = LedgerEntry {
+            timestamp_ms: 0,
+            kind: EntryKind::File,
+            tool: "Edit".into(),
+            subject,
+            message_id: "T3".into(),
+        };
+        ledger.entries.push(edit);
+        state.set_ext(ledger);
+        let mut todos = TodoState::new();
+        let status = TodoStatus::InProgress;
+        todos.todos.push(TodoItem {
+            id: "X1".into(),
+            parent_id: None,
+            name: "Fix parser".into(),
+            description: String::new(),
+            status,
+        });
+        state.set_ext(todos);
+        state
+    }
+
+    #[test]
+    fn the_report_sends_actions_todos_and_focus() {
+        let mut state = session();
+        assert!(!request(&mut state, "the parser"), "no Actions panel to run the query");
+        state.context.push(make_default_entry("P9", Kind::new(Kind::LEDGER), "Actions", false));
+        assert!(request(&mut state, "the parser"));
+        let query = pending_query(&state);
+        let prompt = query.as_ref().map(|q| q.prompt.clone()).unwrap_or_default();
+        assert!(
+            prompt.contains("| T3 | file | src/parse.rs") && prompt.contains("X1 | in progress | Fix parser"),
+            "{prompt}"
+        );
+        let mut markdown = String::new();
+        if let Some(Ok(summary)) = query.map(|q| fetch(answer, &q)) {
+            summary.push_markdown(&mut markdown);
+        }
+        assert_eq!(markdown, "\nParser fixed.\n\n## Done\n\n- Fixed src/parse.rs\n\n## Risks\n\n- No error tests\n");
+    }
+}
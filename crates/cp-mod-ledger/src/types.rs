@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+/// What a ledger entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// A file created or modified (`Edit`, `Write`).
+    File,
+    /// A shell or git command run.
+    Command,
+    /// A git commit.
+    Commit,
+}
+
+impl EntryKind {
+    /// Lowercase label used in `actions.log` and the panel.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Command => "command",
+            Self::Commit => "commit",
+        }
+    }
+
+    /// Glyph shown before the entry in the panel.
+    #[must_use]
+    pub const fn glyph(self) -> &'static str {
+        match self {
+            Self::File => "\u{270e}",
+            Self::Command => "$",
+            Self::Commit => "\u{25cf}",
+        }
+    }
+}
+
+/// One action taken by the agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// When the tool ran (ms since epoch).
+    pub timestamp_ms: u64,
+    /// What was done.
+    pub kind: EntryKind,
+    /// Tool that did it (e.g. "`Edit`", "`git_execute`").
+    pub tool: String,
+    /// File path, command line, or commit message.
+    pub subject: String,
+    /// ID of the tool-call message that requested it (e.g. "T12").
+    pub message_id: String,
+}
+
+/// Where the latest `/report` request stands.
+#[derive(Debug, Clone)]
+pub enum SummaryStatus {
+    /// Waiting for the model; `focus` is the text typed after `/report`.
+    Pending {
+        /// What the report should focus on (may be empty).
+        focus: String,
+    },
+    /// The request failed or the answer never validated.
+    Failed(String),
+}
+
+impl SummaryStatus {
+    /// The focus text, while the request is pending.
+    #[must_use]
+    pub const fn pending_focus(&self) -> Option<&str> {
+        cp_base::deref_match!(self, {
+            Self::Pending { ref focus } => Some(focus.as_str()),
+            Self::Failed(_) => None,
+        })
+    }
+
+    /// The error, once the request failed.
+    #[must_use]
+    pub const fn error(&self) -> Option<&str> {
+        cp_base::deref_match!(self, {
+            Self::Failed(ref error) => Some(error.as_str()),
+            Self::Pending { .. } => None,
+        })
+    }
+}
+
+/// Module state: the actions of the current session.
+#[derive(Debug, Clone, Default)]
+pub struct LedgerState {
+    /// Entries recorded since the session started, oldest first.
+    pub entries: Vec<LedgerEntry>,
+    /// When the session started (ms since epoch).
+    pub session_start_ms: u64,
+    /// Path of the last exported work report.
+    pub last_report: Option<String>,
+    /// The in-flight or failed `/report` request, if any.
+    pub summary: Option<SummaryStatus>,
+}
+
+impl LedgerState {
+    /// Empty ledger for a session starting now.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { entries: Vec::new(), session_start_ms: cp_base::panels::now_ms(), last_report: None, summary: None }
+    }
+
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// Delegates to [`State::ext()`] which centralizes the panic for unregistered module state.
+    #[must_use]
+    pub fn get(state: &cp_base::state::runtime::State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// Delegates to [`State::ext_mut()`] which centralizes the panic for unregistered module state.
+    pub fn get_mut(state: &mut cp_base::state::runtime::State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+
+    /// Number of entries of `kind`.
+    #[must_use]
+    pub fn count(&self, kind: EntryKind) -> usize {
+        self.entries.iter().filter(|e| e.kind == kind).count()
+    }
+
+    /// `N commit(s), N file change(s), N command(s)`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "{} commit(s), {} file change(s), {} command(s)",
+            self.count(EntryKind::Commit),
+            self.count(EntryKind::File),
+            self.count(EntryKind::Command)
+        )
+    }
+}
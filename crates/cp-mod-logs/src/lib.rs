@@ -447,11 +447,7 @@ fn visualize_logs_output(content: &str, width: usize) -> Vec<cp_render::Block> {
             } else {
                 Semantic::Default
             };
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
             Block::Line(vec![Span::styled(display, semantic)])
         })
         .collect()
@@ -1,4 +1,5 @@
-use cp_base::panels::{ContextItem, Panel};
+use cp_base::panels::Panel;
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::context::{Kind, estimate_tokens};
 use cp_base::state::runtime::State;
 
@@ -187,6 +188,10 @@ impl Panel for LogsPanel {
         3
     }
 
+    fn diff_on_refresh(&self) -> bool {
+        true
+    }
+
     fn context(&self, state: &State) -> Vec<ContextItem> {
         let content = Self::format_logs_tree(state);
         let (id, last_refresh_ms) = state
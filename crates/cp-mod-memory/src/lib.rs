@@ -328,9 +328,5 @@ fn visualize_memory_output(content: &str, width: usize) -> Vec<cp_render::Block>
 
 /// Truncate a line for memory visualizer output.
 fn truncate_mem_line(line: &str, width: usize) -> String {
-    if line.len() > width {
-        format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-    } else {
-        line.to_owned()
-    }
+    cp_base::ui::text::ellipsize(line, width, "...")
 }
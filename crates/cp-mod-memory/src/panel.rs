@@ -1,9 +1,10 @@
 use crossterm::event::KeyEvent;
 
-use cp_base::panels::{CacheRequest, CacheUpdate, ContextItem, Panel};
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel};
 use cp_base::state::actions::Action;
 use cp_base::state::context::Entry;
 use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
 use crate::types::{MemoryImportance, MemoryState};
@@ -55,11 +55,7 @@ fn create_one_memory(memory_value: &serde_json::Value, state: &mut State) -> Res
         storage::upsert_yaml_entry(item);
     }
 
-    let preview = if content.len() > 40 {
-        format!("{}...", content.get(..content.floor_char_boundary(37)).unwrap_or(""))
-    } else {
-        content
-    };
+    let preview = cp_base::ui::text::ellipsize(&content, 40, "...");
     Ok(format!("{} [{}]: {}", id, importance.as_str(), preview))
 }
 
@@ -0,0 +1,131 @@
+//! Neovim module — bridge the agent and a running editor.
+//!
+//! `Nvim_connect` attaches to a Neovim listening on a server address (or the
+//! one whose terminal hosts us, via `$NVIM`). While connected, files the agent
+//! `Open`s are opened in the editor too, a Diagnostics panel mirrors its LSP
+//! diagnostics, and `:'<,'>ContextPilotSend` in the editor drops the selected
+//! lines into the prompt. All calls go through `nvim --server`.
+
+/// Diagnostics panel.
+mod panel;
+/// `nvim --server` calls and their output.
+mod remote;
+/// Tool dispatch: `Nvim_connect`, and mirroring `Open`.
+mod tools;
+/// Neovim state types: `NvimState`, poll request and result.
+mod types;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::types::NvimState;
+
+/// Lazily parsed tool texts from the nvim YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/nvim.yaml")));
+
+/// Neovim module: editor connection, diagnostics and selections.
+#[derive(Debug, Clone, Copy)]
+pub struct NvimModule;
+
+impl Default for NvimModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NvimModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for NvimModule {
+    fn id(&self) -> &'static str {
+        "nvim"
+    }
+
+    fn name(&self) -> &'static str {
+        "Neovim"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mirror opened files into Neovim and read its diagnostics"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: Kind::NVIM_DIAGNOSTICS,
+            icon_id: "file",
+            is_fixed: false,
+            needs_cache: true,
+            fixed_order: None,
+            display_name: "nvim-diagnostics",
+            short_name: "diagnostics",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::NVIM_DIAGNOSTICS)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("Nvim_connect", t)
+                .short_desc("Connect to a running Neovim")
+                .category("Neovim")
+                .param("server", ParamType::String, false)
+                .param("mirror_open", ParamType::Boolean, false)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        tools::dispatch(tool, state)
+    }
+
+    fn on_tool_executed(&self, tool: &ToolUse, result: &ToolResult, state: &mut State) {
+        tools::mirror_open(tool, result, state);
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        (context_type.as_str() == Kind::NVIM_DIAGNOSTICS).then(|| {
+            let panel: Box<dyn Panel> = Box::new(panel::DiagnosticsPanel);
+            panel
+        })
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Neovim", "Work alongside the user's Neovim")]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(NvimState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(NvimState::new());
+    }
+
+    fn overview_context_section(&self, state: &State) -> Option<String> {
+        let server = state.get_ext::<NvimState>()?.server.as_ref()?;
+        Some(format!("Neovim: connected at {server}\n"))
+    }
+}
@@ -0,0 +1,155 @@
+//! Diagnostics panel: the editor's LSP diagnostics, polled on a timer.
+//!
+//! Each poll also collects the selections sent with `:ContextPilotSend` and
+//! appends them to the input box, so the user only has to add the question.
+
+use crossterm::event::KeyEvent;
+
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::remote;
+use crate::types::{NvimState, PollRequest, PollResult};
+
+/// How often the editor is polled.
+const REFRESH_MS: u64 = 3_000;
+
+/// Ask the editor for its diagnostics and queued selections (runs on a cache worker).
+fn poll(request: &PollRequest) -> PollResult {
+    let diagnostics = remote::diagnostics(&request.server).map(|d| remote::render(d, &request.root));
+    let selections = remote::take_selections(&request.server)
+        .unwrap_or_default()
+        .iter()
+        .map(|s| remote::quote(s, &request.root))
+        .collect();
+    PollResult { diagnostics, selections }
+}
+
+/// Append `quoted` to the draft, on its own paragraph, cursor at the end.
+pub(crate) fn append_to_input(state: &mut State, quoted: &str) {
+    if !state.input.is_empty() && !state.input.ends_with("\n\n") {
+        state.input.push_str(if state.input.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+    state.input.push_str(quoted);
+    state.input_cursor = state.input.len();
+    state.flags.ui.dirty = true;
+}
+
+/// Panel listing the connected editor's diagnostics.
+pub(crate) struct DiagnosticsPanel;
+
+impl Panel for DiagnosticsPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, _ctx: &Entry, state: &State) -> Option<CacheRequest> {
+        let server = NvimState::get(state).server.clone()?;
+        let root = std::env::current_dir().ok()?;
+        Some(CacheRequest::new(Kind::new(Kind::NVIM_DIAGNOSTICS), Box::new(PollRequest { server, root })))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        // Always answer, even on a foreign payload, so `cache_in_flight` clears.
+        let result = request.data.downcast::<PollRequest>().map_or_else(
+            |_| PollResult { diagnostics: Err("unexpected cache request".to_owned()), selections: vec![] },
+            |req| poll(&req),
+        );
+        Some(CacheUpdate::ModuleSpecific { context_type: Kind::new(Kind::NVIM_DIAGNOSTICS), data: Box::new(result) })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, state: &mut State) -> bool {
+        ctx.cache_deprecated = false;
+        let CacheUpdate::ModuleSpecific { data, .. } = update else { return false };
+        let Ok(result) = data.downcast::<PollResult>() else { return false };
+        for quoted in &result.selections {
+            append_to_input(state, quoted);
+        }
+        let content = result.diagnostics.unwrap_or_else(|e| format!("Neovim unreachable: {e}\n"));
+        let token_count = estimate_tokens(&content);
+        ctx.token_count = token_count;
+        ctx.full_token_count = token_count;
+        let changed = update_if_changed(ctx, &content);
+        ctx.cached_content = Some(content);
+        changed
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        Some(REFRESH_MS)
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        let cached = state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == Kind::NVIM_DIAGNOSTICS)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content
+            .lines()
+            .map(|line| {
+                let semantic = if line.starts_with("E ") {
+                    Semantic::Error
+                } else if line.starts_with("W ") {
+                    Semantic::Warning
+                } else {
+                    Semantic::Default
+                };
+                Block::Line(vec![S::styled(format!(" {line}"), semantic)])
+            })
+            .collect()
+    }
+
+    fn title(&self, state: &State) -> String {
+        NvimState::get(state).server.as_ref().map_or_else(|| "Diagnostics".to_owned(), |s| format!("Diagnostics ({s})"))
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::NVIM_DIAGNOSTICS)
+            .map(|c| {
+                let content = c.cached_content.as_deref().unwrap_or("[loading...]");
+                ContextItem::new(&c.id, "Neovim diagnostics", content.to_owned(), c.last_refresh_ms)
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, state: &State) -> bool {
+        NvimState::get(state).server.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selections_land_on_their_own_paragraph() {
+        let mut state = State::default();
+        append_to_input(&mut state, "a\n");
+        assert_eq!(state.input, "a\n");
+        state.input.push_str("why?");
+        append_to_input(&mut state, "b\n");
+        assert_eq!(state.input, "a\nwhy?\n\nb\n");
+        assert_eq!(state.input_cursor, state.input.len());
+    }
+}
@@ -0,0 +1,215 @@
+//! The editor side, reached through `nvim --server ADDR --remote*`: no RPC
+//! client of our own, just the Neovim binary acting as one.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+
+use cp_base::modules::run_with_timeout;
+
+/// Seconds one `nvim --server` call may take.
+const NVIM_TIMEOUT_SECS: u64 = 3;
+
+/// Diagnostics listed in the panel at most, most severe first.
+const MAX_DIAGNOSTICS: usize = 200;
+
+/// Evaluated in Neovim: every diagnostic of every buffer, as JSON.
+const DIAGNOSTICS_EXPR: &str = "luaeval('vim.json.encode(vim.tbl_map(function(d) return { \
+     file = vim.api.nvim_buf_get_name(d.bufnr), line = d.lnum + 1, col = d.col + 1, \
+     severity = d.severity, message = d.message, source = d.source } end, vim.diagnostic.get()))')";
+
+/// Evaluated in Neovim: defines `:ContextPilotSend`, which queues the lines
+/// of its range in `g:context_pilot_outbox` for [`OUTBOX_EXPR`] to collect.
+const INSTALL_EXPR: &str = "luaeval('vim.api.nvim_create_user_command(\"ContextPilotSend\", function(o) \
+     local q = vim.g.context_pilot_outbox or {} \
+     table.insert(q, { file = vim.api.nvim_buf_get_name(0), first = o.line1, last = o.line2, \
+     filetype = vim.bo.filetype, \
+     text = table.concat(vim.api.nvim_buf_get_lines(0, o.line1 - 1, o.line2, false), \"\\n\") }) \
+     vim.g.context_pilot_outbox = q end, { range = true, force = true })')";
+
+/// Evaluated in Neovim: the queued selections as JSON, emptying the queue.
+const OUTBOX_EXPR: &str = "luaeval('(function() local q = vim.g.context_pilot_outbox or {} \
+     vim.g.context_pilot_outbox = {} return vim.json.encode(q) end)()')";
+
+/// One LSP (or linter) diagnostic reported by the editor.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct Diagnostic {
+    /// Absolute path of the buffer.
+    file: String,
+    /// 1-based line.
+    line: u64,
+    /// 1-based column.
+    col: u64,
+    /// 1 error, 2 warning, 3 info, 4 hint.
+    severity: u8,
+    /// The message, possibly on several lines.
+    message: String,
+    /// Reporting server or linter, when known.
+    #[serde(default)]
+    source: Option<String>,
+}
+
+/// Lines sent from the editor with `:ContextPilotSend`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct Selection {
+    /// Absolute path of the buffer (empty for an unnamed one).
+    file: String,
+    /// First line, 1-based.
+    first: u64,
+    /// Last line, 1-based, inclusive.
+    last: u64,
+    /// Buffer filetype, used as the code fence language.
+    #[serde(default)]
+    filetype: String,
+    /// The lines themselves.
+    text: String,
+}
+
+/// Run `nvim --server server args` and return its stdout.
+fn nvim(server: &str, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("nvim");
+    let _c = cmd.arg("--server").arg(server).args(args);
+    let output = run_with_timeout(cmd, NVIM_TIMEOUT_SECS).map_err(|e| format!("cannot run nvim: {e}"))?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+    Err(if stderr.is_empty() { format!("no Neovim answered at {server}") } else { stderr })
+}
+
+/// The server's own name for itself — a cheap reachability check.
+pub(crate) fn ping(server: &str) -> Result<String, String> {
+    nvim(server, &["--remote-expr", "v:servername"]).map(|name| name.trim().to_owned())
+}
+
+/// Open `path` in the editor.
+pub(crate) fn open(server: &str, path: &Path) -> Result<(), String> {
+    nvim(server, &["--remote", &path.to_string_lossy()]).map(drop)
+}
+
+/// Define `:ContextPilotSend` in the editor (idempotent).
+pub(crate) fn install(server: &str) -> Result<(), String> {
+    nvim(server, &["--remote-expr", INSTALL_EXPR]).map(drop)
+}
+
+/// Every diagnostic the editor currently knows of.
+pub(crate) fn diagnostics(server: &str) -> Result<Vec<Diagnostic>, String> {
+    parse(&nvim(server, &["--remote-expr", DIAGNOSTICS_EXPR])?)
+}
+
+/// Selections sent since the last call, oldest first.
+pub(crate) fn take_selections(server: &str) -> Result<Vec<Selection>, String> {
+    parse(&nvim(server, &["--remote-expr", OUTBOX_EXPR])?)
+}
+
+/// Decode a JSON list produced in the editor, skipping malformed items. An
+/// empty Lua table encodes as `{}`.
+fn parse<T>(json: &str) -> Result<Vec<T>, String>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let value: serde_json::Value =
+        serde_json::from_str(json.trim()).map_err(|e| format!("unexpected output from Neovim: {e}"))?;
+    let serde_json::Value::Array(items) = value else { return Ok(vec![]) };
+    Ok(items.into_iter().filter_map(|v| serde_json::from_value(v).ok()).collect())
+}
+
+/// A selection as prompt text: where it comes from, then the lines fenced.
+pub(crate) fn quote(selection: &Selection, root: &Path) -> String {
+    let file = if selection.file.is_empty() {
+        "[No Name]".to_owned()
+    } else {
+        Path::new(&selection.file)
+            .strip_prefix(root)
+            .map_or_else(|_| selection.file.clone(), |p| p.display().to_string())
+    };
+    let lines = if selection.first == selection.last {
+        format!("line {}", selection.first)
+    } else {
+        format!("lines {}-{}", selection.first, selection.last)
+    };
+    format!("`{file}` {lines}:\n```{}\n{}\n```\n", selection.filetype, selection.text.trim_end_matches('\n'))
+}
+
+/// One-letter severity tag.
+const fn tag(severity: u8) -> char {
+    match severity {
+        1 => 'E',
+        2 => 'W',
+        3 => 'I',
+        _ => 'H',
+    }
+}
+
+/// Panel text: a count line, then `E path:line:col message [source]` lines,
+/// errors first, paths relative to `root` when inside it.
+pub(crate) fn render(mut diagnostics: Vec<Diagnostic>, root: &Path) -> String {
+    if diagnostics.is_empty() {
+        return "No diagnostics.\n".to_owned();
+    }
+    diagnostics.sort_by(|a, b| (a.severity, &a.file, a.line, a.col).cmp(&(b.severity, &b.file, b.line, b.col)));
+    let count = |severity: u8| diagnostics.iter().filter(|d| d.severity == severity).count();
+    let mut out = format!(
+        "{} errors, {} warnings, {} other\n",
+        count(1),
+        count(2),
+        diagnostics.len().saturating_sub(count(1)).saturating_sub(count(2))
+    );
+    for d in diagnostics.iter().take(MAX_DIAGNOSTICS) {
+        let file = Path::new(&d.file).strip_prefix(root).map_or_else(|_| d.file.clone(), |p| p.display().to_string());
+        let message = d.message.split_whitespace().collect::<Vec<_>>().join(" ");
+        let source = d.source.as_ref().map(|s| format!(" [{s}]")).unwrap_or_default();
+        let _r = writeln!(out, "{} {file}:{}:{} {message}{source}", tag(d.severity), d.line, d.col);
+    }
+    if diagnostics.len() > MAX_DIAGNOSTICS {
+        let _r = writeln!(out, "... {} more", diagnostics.len().saturating_sub(MAX_DIAGNOSTICS));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_diagnostics_and_the_empty_table() {
+        let json = r#"[{"file":"/p/src/a.rs","line":3,"col":1,"severity":2,"message":"unused"},
+                       {"file":"/p/src/b.rs","line":9,"col":5,"severity":1,"message":"x","source":"rustc"}]"#;
+        assert_eq!(parse::<Diagnostic>(json).map(|d| d.len()), Ok(2));
+        assert_eq!(parse::<Diagnostic>("{}\n"), Ok(vec![]));
+        assert!(parse::<Diagnostic>("E5108: error").is_err_and(|e| e.contains("unexpected")));
+    }
+
+    #[test]
+    fn quotes_selections_as_fenced_code() {
+        let json = r#"[{"file":"/p/src/a.rs","first":3,"last":4,"filetype":"rust","text":"let a = 1;\nlet b = a;"}]"#;
+        let selections = parse::<Selection>(json).unwrap_or_default();
+        assert_eq!(
+            selections.iter().map(|s| quote(s, Path::new("/p"))).collect::<Vec<_>>(),
+            ["`src/a.rs` lines 3-4:\n```rust\nlet a = 1;\nlet b = a;\n```\n"]
+        );
+    }
+
+    #[test]
+    fn renders_errors_first_with_relative_paths() {
+        let diagnostic = |file: &str, severity: u8, message: &str| Diagnostic {
+            file: file.to_owned(),
+            line: 4,
+            col: 2,
+            severity,
+            message: message.to_owned(),
+            source: Some("rustc".to_owned()),
+        };
+        let text = render(
+            vec![diagnostic("/p/src/a.rs", 2, "unused\n  variable"), diagnostic("/elsewhere/b.rs", 1, "mismatched")],
+            Path::new("/p"),
+        );
+        assert_eq!(
+            text,
+            "1 errors, 1 warnings, 0 other\nE /elsewhere/b.rs:4:2 mismatched [rustc]\nW src/a.rs:4:2 unused variable [rustc]\n"
+        );
+        assert_eq!(render(vec![], Path::new("/p")), "No diagnostics.\n");
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use cp_base::state::context::{Kind, make_default_entry};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::remote;
+use crate::types::NvimState;
+
+/// Environment variable Neovim sets in its terminal buffers.
+const NVIM_ENV: &str = "NVIM";
+
+/// Dispatch nvim tool calls.
+pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+    match tool.name.as_str() {
+        "Nvim_connect" => Some(execute_connect(tool, state)),
+        _ => None,
+    }
+}
+
+/// Create the diagnostics panel unless one is open.
+fn ensure_panel(state: &mut State) {
+    if state.context.iter().any(|c| c.context_type.as_str() == Kind::NVIM_DIAGNOSTICS) {
+        return;
+    }
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+    let mut ctx = make_default_entry(&panel_id, Kind::new(Kind::NVIM_DIAGNOSTICS), "Diagnostics", true);
+    ctx.uid = Some(uid);
+    state.context.push(ctx);
+}
+
+/// Execute `Nvim_connect`: reach the editor, or disconnect on an empty server.
+fn execute_connect(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let server = match tool.input.get("server").and_then(|v| v.as_str()) {
+        Some(s) => s.trim().to_owned(),
+        None => match std::env::var(NVIM_ENV) {
+            Ok(s) if !s.is_empty() => s,
+            _ => {
+                let message =
+                    "No server given and $NVIM is unset: pass the address Neovim listens on (:echo v:servername).";
+                return ToolResult::new(tool.id.clone(), message.to_owned(), true);
+            }
+        },
+    };
+    if server.is_empty() {
+        let was = NvimState::get_mut(state).server.take();
+        state.context.retain(|c| c.context_type.as_str() != Kind::NVIM_DIAGNOSTICS);
+        let message = was.map_or_else(|| "Not connected.".to_owned(), |s| format!("Disconnected from Neovim at {s}."));
+        return ToolResult::new(tool.id.clone(), message, false);
+    }
+    if let Err(e) = remote::ping(&server).and_then(|_name| remote::install(&server)) {
+        return ToolResult::new(tool.id.clone(), format!("Cannot reach Neovim at {server}: {e}"), true);
+    }
+    let mirror_open = tool.input.get("mirror_open").and_then(serde_json::Value::as_bool).unwrap_or(true);
+    let ns = NvimState::get_mut(state);
+    ns.server = Some(server.clone());
+    ns.mirror_open = mirror_open;
+    ensure_panel(state);
+    let mirror = if mirror_open { " Files you Open are opened in the editor too." } else { "" };
+    ToolResult::new(
+        tool.id.clone(),
+        format!(
+            "Connected to Neovim at {server}; its diagnostics are in the Diagnostics panel.{mirror} \
+             The user can send lines to the prompt with :'<,'>ContextPilotSend."
+        ),
+        false,
+    )
+}
+
+/// After a successful `Open`, open the same files in the editor (off the main loop).
+pub(crate) fn mirror_open(tool: &ToolUse, result: &ToolResult, state: &State) {
+    let ns = NvimState::get(state);
+    if tool.name != "Open" || result.is_error || !ns.mirror_open {
+        return;
+    }
+    let Some(server) = ns.server.clone() else { return };
+    let input = tool.input.get("path");
+    let paths: Vec<PathBuf> = if let Some(s) = input.and_then(serde_json::Value::as_str) {
+        vec![PathBuf::from(s)]
+    } else if let Some(items) = input.and_then(serde_json::Value::as_array) {
+        items.iter().filter_map(serde_json::Value::as_str).map(PathBuf::from).collect()
+    } else {
+        return;
+    };
+    let cwd = std::env::current_dir().unwrap_or_default();
+    drop(std::thread::spawn(move || {
+        for path in paths {
+            if let Err(e) = remote::open(&server, &cwd.join(&path)) {
+                log::warn!("nvim: cannot open {} in the editor: {e}", path.display());
+            }
+        }
+    }));
+}
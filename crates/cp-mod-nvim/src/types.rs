@@ -0,0 +1,56 @@
+use cp_base::state::runtime::State;
+
+/// Module-owned state for the Neovim module.
+/// Stored in `State.module_data` via `TypeMap`.
+#[derive(Debug, Default)]
+pub(crate) struct NvimState {
+    /// Address of the connected Neovim; `None` when disconnected.
+    pub server: Option<String>,
+    /// Whether files the agent opens are also opened in the editor.
+    pub mirror_open: bool,
+}
+
+impl NvimState {
+    /// Create a disconnected state.
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub(crate) fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub(crate) fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+}
+
+/// Cache request of the diagnostics panel.
+#[derive(Debug)]
+pub(crate) struct PollRequest {
+    /// Editor to ask.
+    pub server: String,
+    /// Project root, to shorten paths.
+    pub root: std::path::PathBuf,
+}
+
+/// What one poll of the editor brought back.
+#[derive(Debug)]
+pub(crate) struct PollResult {
+    /// Panel text, or why the editor could not be read.
+    pub diagnostics: Result<String, String>,
+    /// Selections sent with `:ContextPilotSend`, already quoted.
+    pub selections: Vec<String>,
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// User-side grants file under the store, outside every plugin directory so
+/// a plugin cannot grant itself anything.
+pub(crate) const GRANTS_FILE: &str = "plugin_grants.yaml";
+
+/// Capabilities the user granted, by plugin id.
+pub(crate) type Grants = BTreeMap<String, Capabilities>;
+
+/// Read the grants file at `path`; a missing or empty file grants nothing.
+pub(crate) fn load_grants(path: &Path) -> Result<Grants, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Grants::new()),
+        Err(e) => return Err(format!("{GRANTS_FILE}: cannot read: {e}")),
+    };
+    if text.trim().is_empty() {
+        return Ok(Grants::new());
+    }
+    serde_yaml::from_str(&text).map_err(|e| format!("{GRANTS_FILE}: invalid: {e}"))
+}
+
+/// What a WASM plugin may reach outside its sandbox. Everything not listed
+/// is denied; process plugins run unsandboxed and take no capabilities.
+/// A manifest lists what it requests; only the user's grants take effect.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Capabilities {
+    /// Directories, relative to the project root, whose files it may read.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Hosts it may send HTTPS GET requests to.
+    #[serde(default)]
+    pub network: Vec<String>,
+}
+
+impl Capabilities {
+    /// Whether nothing is granted.
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.network.is_empty()
+    }
+
+    /// The file `path` names, resolved against `root`, if it lies inside a
+    /// granted directory once symlinks and `..` are resolved. Granted
+    /// directories outside `root` (`/`, `../..`) grant nothing.
+    pub(crate) fn readable(&self, root: &Path, path: &str) -> Option<PathBuf> {
+        let project = root.canonicalize().ok()?;
+        let file = project.join(path).canonicalize().ok()?;
+        self.files
+            .iter()
+            .filter_map(|dir| project.join(dir).canonicalize().ok())
+            .filter(|dir| dir.starts_with(&project))
+            .any(|dir| file.starts_with(dir))
+            .then_some(file)
+    }
+
+    /// Granted directories that resolve outside `root`, and so grant nothing.
+    pub(crate) fn escaping(&self, root: &Path) -> Vec<&str> {
+        let Ok(project) = root.canonicalize() else { return vec![] };
+        self.files
+            .iter()
+            .filter(|dir| project.join(dir).canonicalize().is_ok_and(|resolved| !resolved.starts_with(&project)))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// `url`, if it is HTTPS to a granted host.
+    pub(crate) fn reachable(&self, url: &str) -> Option<reqwest::Url> {
+        let parsed = reqwest::Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        (parsed.scheme() == "https" && self.network.iter().any(|granted| granted.eq_ignore_ascii_case(host)))
+            .then_some(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Grants for the `docs` directory and `api.example.com`.
+    fn granted() -> Capabilities {
+        Capabilities { files: vec!["docs".to_owned()], network: vec!["api.example.com".to_owned()] }
+    }
+
+    #[test]
+    fn files_outside_granted_directories_are_denied() {
+        let Ok(root) = tempfile::tempdir() else { return };
+        let created = std::fs::create_dir_all(root.path().join("docs"))
+            .and_then(|()| std::fs::write(root.path().join("docs/guide.md"), "guide"))
+            .and_then(|()| std::fs::write(root.path().join("secret.env"), "key"));
+        assert_eq!(created.ok(), Some(()));
+        let caps = granted();
+        assert!(caps.readable(root.path(), "docs/guide.md").is_some());
+        assert!(caps.readable(root.path(), "docs/../secret.env").is_none());
+        assert!(caps.readable(root.path(), "secret.env").is_none());
+        assert!(Capabilities::default().readable(root.path(), "docs/guide.md").is_none());
+    }
+
+    #[test]
+    fn directories_outside_the_root_grant_nothing() {
+        let Ok(outer) = tempfile::tempdir() else { return };
+        let root = outer.path().join("project");
+        let created =
+            std::fs::create_dir_all(&root).and_then(|()| std::fs::write(outer.path().join("secret.env"), "key"));
+        assert_eq!(created.ok(), Some(()));
+        let caps = Capabilities { files: vec!["/".to_owned(), "..".to_owned()], network: vec![] };
+        assert!(caps.readable(&root, "../secret.env").is_none());
+        assert!(caps.readable(&root, &outer.path().join("secret.env").to_string_lossy()).is_none());
+        assert_eq!(caps.escaping(&root), ["/", ".."]);
+    }
+
+    #[test]
+    fn grants_are_keyed_by_plugin_id() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let path = dir.path().join(GRANTS_FILE);
+        assert_eq!(load_grants(&path).map(|g| g.len()), Ok(0));
+        let written = std::fs::write(&path, "jira:\n  files: [docs]\n  network: [api.example.com]\n");
+        assert_eq!(written.ok(), Some(()));
+        let grants = load_grants(&path).unwrap_or_default();
+        assert_eq!(grants.get("jira").map(|c| c.files.clone()), Some(vec!["docs".to_owned()]));
+        assert_eq!(std::fs::write(&path, "jira: [docs]\n").ok(), Some(()));
+        assert!(load_grants(&path).is_err_and(|e| e.contains(GRANTS_FILE)));
+    }
+
+    #[test]
+    fn only_https_to_granted_hosts_is_reachable() {
+        let caps = granted();
+        assert!(caps.reachable("https://API.example.com/v1/items").is_some());
+        assert!(caps.reachable("http://api.example.com/v1/items").is_none());
+        assert!(caps.reachable("https://api.example.com.evil.net/").is_none());
+        assert!(caps.reachable("not a url").is_none());
+    }
+}
@@ -0,0 +1,251 @@
+//! Plugins module — third-party tools without forking.
+//!
+//! Every `.context-pilot/plugins/<dir>/plugin.yaml` declares a plugin: the
+//! command to run and the schemas of the tools it offers. Each plugin becomes
+//! a module of its own (`plugin_<id>`, toggled like any other), and each call
+//! to one of its tools runs the command once, speaking the JSON-RPC protocol
+//! described in [`rpc`]. Replies travel the normal `ToolResult` path and may
+//! open a result panel.
+//!
+//! Untrusted plugins ship a `wasm` module instead of a `command`: it runs in
+//! a wasmtime sandbox (see [`wasm`]) with no access to files or network
+//! beyond what the user grants it in `.context-pilot/plugin_grants.yaml`,
+//! keyed by plugin id (`jira: {files: [docs], network: [api.example.com]}`);
+//! `Edit` and `Write` refuse that file, so only the user changes it.
+//! The manifest's `capabilities` only say what the plugin asks for; granted
+//! directories must stay inside the project.
+//!
+//! Plugins are discovered once per process; restart to pick up changes.
+
+/// Capabilities granted to WASM plugins.
+mod capability;
+/// `plugin.yaml` parsing, validation and discovery.
+mod manifest;
+/// Result panel.
+mod panel;
+/// The JSON-RPC exchange with a plugin process.
+mod rpc;
+/// Sandboxed WASM plugins.
+mod wasm;
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use cp_base::config::constants::STORE_DIR;
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::state::watchers::DYN_PANEL_ID_PLACEHOLDER;
+use cp_base::state::watchers::carriers::DynPanel;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolDefinition, ToolResult, ToolUse};
+
+use self::manifest::{Plugin, Registry};
+use self::panel::{META_CONTENT, PLUGIN_PANEL_TYPE};
+
+/// Directory under the store holding one subdirectory per plugin.
+const PLUGINS_DIR: &str = "plugins";
+
+/// Seconds granted on top of a plugin's own timeout before the async tool
+/// gives up on it.
+const TIMEOUT_GRACE_SECS: u64 = 5;
+
+/// Plugins discovered at first use.
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// One module per discovered plugin. `reserved_tools` yields the ids of the
+/// built-in tools; it is called once, on first use, and plugin tools that
+/// clash with it are skipped.
+pub fn plugin_modules<F>(reserved_tools: F) -> Vec<Box<dyn Module>>
+where
+    F: FnOnce() -> HashSet<String>,
+{
+    let registry = REGISTRY.get_or_init(|| {
+        let store = Path::new(STORE_DIR);
+        let (grants, error) = match capability::load_grants(&store.join(capability::GRANTS_FILE)) {
+            Ok(grants) => (grants, None),
+            Err(e) => (capability::Grants::new(), Some(e)),
+        };
+        let mut found = manifest::discover(&store.join(PLUGINS_DIR), &grants, &reserved_tools());
+        found.errors.extend(error);
+        found
+    });
+    registry
+        .plugins
+        .iter()
+        .map(|plugin| {
+            let module: Box<dyn Module> = Box::new(PluginModule { plugin });
+            module
+        })
+        .collect()
+}
+
+/// Plugins host: owns the result panel type and reports what loaded.
+#[derive(Debug, Clone, Copy)]
+pub struct PluginsModule;
+
+impl Default for PluginsModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluginsModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for PluginsModule {
+    fn id(&self) -> &'static str {
+        "plugins"
+    }
+
+    fn name(&self) -> &'static str {
+        "Plugins"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tools provided by external plugins in .context-pilot/plugins"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: PLUGIN_PANEL_TYPE,
+            icon_id: "plugin",
+            is_fixed: false,
+            needs_cache: false,
+            fixed_order: None,
+            display_name: "plugin",
+            short_name: "plugin",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(PLUGIN_PANEL_TYPE)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![]
+    }
+
+    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        None
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        (context_type.as_str() == PLUGIN_PANEL_TYPE).then(|| {
+            let panel: Box<dyn Panel> = Box::new(panel::ResultPanel);
+            panel
+        })
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn overview_context_section(&self, _state: &State) -> Option<String> {
+        let registry = REGISTRY.get()?;
+        if registry.plugins.is_empty() && registry.errors.is_empty() {
+            return None;
+        }
+        let mut section = String::from("Plugins:\n");
+        for plugin in &registry.plugins {
+            let tools: Vec<&str> = plugin.manifest.tools.iter().map(|t| t.id.as_str()).collect();
+            let _r = writeln!(section, "  {} ({}): {}", plugin.manifest.name, plugin.module_id, tools.join(", "));
+            let escaping = plugin.granted.escaping(Path::new("."));
+            if !escaping.is_empty() {
+                let _e = writeln!(section, "    grants outside the project ignored: {}", escaping.join(", "));
+            }
+            if plugin.manifest.wasm.is_some() && plugin.granted.is_empty() && !plugin.manifest.capabilities.is_empty() {
+                let _g = writeln!(section, "    asks for capabilities not granted in {}", capability::GRANTS_FILE);
+            }
+        }
+        for error in &registry.errors {
+            let _r = writeln!(section, "  skipped {error}");
+        }
+        Some(section)
+    }
+}
+
+/// A discovered plugin, exposed as a module.
+#[derive(Debug, Clone, Copy)]
+struct PluginModule {
+    /// The plugin, alive for the whole process.
+    plugin: &'static Plugin,
+}
+
+impl Module for PluginModule {
+    fn id(&self) -> &'static str {
+        &self.plugin.module_id
+    }
+
+    fn name(&self) -> &'static str {
+        &self.plugin.manifest.name
+    }
+
+    fn description(&self) -> &'static str {
+        &self.plugin.manifest.description
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &["plugins"]
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        self.plugin.tool_definitions()
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        self.plugin.manifest.tools.iter().any(|t| t.id == tool.name).then(|| execute(self.plugin, tool, state))
+    }
+
+    fn create_panel(&self, _context_type: &Kind) -> Option<Box<dyn Panel>> {
+        None
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![(&self.plugin.manifest.name, &self.plugin.manifest.description)]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+}
+
+/// Run one plugin tool call in the background.
+fn execute(plugin: &'static Plugin, tool: &ToolUse, state: &mut State) -> ToolResult {
+    let name = tool.name.clone();
+    let input = tool.input.clone();
+    spawn_async_tool(state, tool, plugin.manifest.timeout_secs.saturating_add(TIMEOUT_GRACE_SECS), move || {
+        let reply = match rpc::call(plugin, &name, &input) {
+            Ok(reply) => reply,
+            Err(e) => return ToolOutput::error(e),
+        };
+        let mut output = if reply.is_error { ToolOutput::error(reply.content) } else { ToolOutput::ok(reply.content) };
+        if let Some((title, content)) = reply.panel {
+            output.content = format!("Created panel {DYN_PANEL_ID_PLACEHOLDER}: {title}\n{}", output.content);
+            output = output.with_panel(
+                DynPanel::new(PLUGIN_PANEL_TYPE.to_owned(), title)
+                    .metadata(vec![(META_CONTENT.to_owned(), content.clone())])
+                    .content(content),
+            );
+        }
+        output
+    })
+}
@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use cp_base::tools::{ParamType, RESERVED_PARAM_NAMES, ToolDefinition, ToolParam};
+
+use crate::capability::{Capabilities, Grants};
+
+/// Manifest file name inside each plugin directory.
+pub(crate) const MANIFEST_FILE: &str = "plugin.yaml";
+
+/// Seconds a plugin call may take unless its manifest says otherwise.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Longest timeout a manifest may ask for.
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+/// `plugin.yaml`: what a plugin is, how to run it, and the tools it offers.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Manifest {
+    /// Identifier: lowercase letters, digits and `_`.
+    pub id: String,
+    /// Display name, also the tool category.
+    pub name: String,
+    /// One line on what the plugin does.
+    pub description: String,
+    /// Program and arguments, run from the plugin directory.
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// WebAssembly module, relative to the plugin directory, run sandboxed
+    /// instead of `command`.
+    #[serde(default)]
+    pub wasm: Option<String>,
+    /// What the WASM module asks to reach; nothing is granted until the
+    /// user lists it in the grants file.
+    #[serde(default)]
+    pub capabilities: Capabilities,
+    /// Seconds one call may take.
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    /// Tools the plugin implements.
+    pub tools: Vec<ToolSpec>,
+}
+
+/// One tool declared by a manifest.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ToolSpec {
+    /// Tool identifier the model calls.
+    pub id: String,
+    /// Sidebar description.
+    pub short_desc: String,
+    /// Model-facing description.
+    pub description: String,
+    /// Parameter schema.
+    #[serde(default)]
+    pub params: Vec<ParamSpec>,
+}
+
+/// One tool parameter declared by a manifest.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ParamSpec {
+    /// JSON key.
+    pub name: String,
+    /// `string`, `integer`, `number`, `boolean`, or `{array: <type>}`.
+    #[serde(rename = "type")]
+    pub param_type: ParamType,
+    /// Model-facing description.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Whether the model must pass it.
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values.
+    #[serde(default, rename = "enum")]
+    pub values: Option<Vec<String>>,
+}
+
+/// `timeout_secs` when the manifest leaves it out.
+const fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+/// A plugin whose manifest loaded and validated.
+#[derive(Debug)]
+pub(crate) struct Plugin {
+    /// Module id: `plugin_<id>`.
+    pub module_id: String,
+    /// Directory holding the manifest; the command runs here.
+    pub dir: PathBuf,
+    /// The parsed manifest.
+    pub manifest: Manifest,
+    /// What the user granted the WASM module.
+    pub granted: Capabilities,
+}
+
+impl Plugin {
+    /// The tool definitions sent to the model.
+    pub(crate) fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        self.manifest
+            .tools
+            .iter()
+            .map(|tool| ToolDefinition {
+                id: tool.id.clone(),
+                name: String::new(),
+                short_desc: tool.short_desc.clone(),
+                description: tool.description.trim().to_owned(),
+                params: tool.params.iter().map(ParamSpec::to_param).collect(),
+                enabled: true,
+                reverie_allowed: false,
+                category: self.manifest.name.clone(),
+            })
+            .collect()
+    }
+}
+
+impl ParamSpec {
+    /// The schema parameter.
+    fn to_param(&self) -> ToolParam {
+        let mut param = ToolParam::new(&self.name, self.param_type.clone());
+        param.description.clone_from(&self.description);
+        param.required = self.required;
+        param.enum_values.clone_from(&self.values);
+        param
+    }
+}
+
+/// Plugins found under a directory, plus one line per plugin or tool that
+/// was skipped.
+#[derive(Debug, Default)]
+pub(crate) struct Registry {
+    /// Loaded plugins, by directory name.
+    pub plugins: Vec<Plugin>,
+    /// Why plugins or tools were skipped.
+    pub errors: Vec<String>,
+}
+
+/// Whether `id` is lowercase letters, digits and `_`, starting with a letter.
+fn is_identifier(id: &str) -> bool {
+    id.starts_with(|c: char| c.is_ascii_lowercase())
+        && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Check how the plugin runs: a command or a WASM module, never both, and
+/// capabilities only for the sandboxed kind.
+fn validate_runtime(manifest: &Manifest) -> Result<(), String> {
+    match manifest.wasm.as_deref() {
+        Some(_) if !manifest.command.is_empty() => Err("set either command or wasm, not both".to_owned()),
+        Some(module) if module.trim().is_empty() => Err("wasm is empty".to_owned()),
+        None if manifest.command.first().is_none_or(|program| program.trim().is_empty()) => {
+            Err("command is empty".to_owned())
+        }
+        None if !manifest.capabilities.is_empty() => Err("capabilities only apply to wasm plugins".to_owned()),
+        Some(_) | None => Ok(()),
+    }
+}
+
+/// Check a manifest on its own: identifiers, runtime, timeout, parameters.
+fn validate(manifest: &Manifest) -> Result<(), String> {
+    if !is_identifier(&manifest.id) {
+        return Err(format!("id '{}' must be lowercase letters, digits and '_'", manifest.id));
+    }
+    validate_runtime(manifest)?;
+    if manifest.timeout_secs == 0 || manifest.timeout_secs > MAX_TIMEOUT_SECS {
+        return Err(format!("timeout_secs must be between 1 and {MAX_TIMEOUT_SECS}"));
+    }
+    for tool in &manifest.tools {
+        if !is_identifier(&tool.id) {
+            return Err(format!("tool id '{}' must be lowercase letters, digits and '_'", tool.id));
+        }
+        if let Some(param) = tool.params.iter().find(|p| RESERVED_PARAM_NAMES.contains(&p.name.as_str())) {
+            return Err(format!("tool '{}': parameter '{}' is reserved", tool.id, param.name));
+        }
+    }
+    Ok(())
+}
+
+/// Read and validate `dir/plugin.yaml`.
+fn load_one(dir: &Path) -> Result<Manifest, String> {
+    let text = std::fs::read_to_string(dir.join(MANIFEST_FILE)).map_err(|e| format!("cannot read manifest: {e}"))?;
+    // Through a JSON value, so parameter types read as `{array: string}` rather
+    // than needing YAML tags.
+    let value: serde_json::Value = serde_yaml::from_str(&text).map_err(|e| format!("invalid manifest: {e}"))?;
+    let manifest: Manifest = serde_json::from_value(value).map_err(|e| format!("invalid manifest: {e}"))?;
+    validate(&manifest)?;
+    Ok(manifest)
+}
+
+/// Load every `<root>/<dir>/plugin.yaml`, in directory-name order, each with
+/// the `grants` the user gave its id. Tools whose id is in `taken` (built-in
+/// tools, or an earlier plugin's) are skipped, as are plugins whose id repeats.
+pub(crate) fn discover(root: &Path, grants: &Grants, taken: &HashSet<String>) -> Registry {
+    let mut registry = Registry::default();
+    let Ok(entries) = std::fs::read_dir(root) else { return registry };
+    let mut dirs: Vec<PathBuf> =
+        entries.filter_map(Result::ok).map(|e| e.path()).filter(|p| p.join(MANIFEST_FILE).is_file()).collect();
+    dirs.sort();
+    let mut tool_ids = taken.clone();
+    for dir in dirs {
+        let label = dir.file_name().map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        let mut manifest = match load_one(&dir) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                registry.errors.push(format!("{label}: {e}"));
+                continue;
+            }
+        };
+        if registry.plugins.iter().any(|p| p.manifest.id == manifest.id) {
+            registry.errors.push(format!("{label}: plugin id '{}' already loaded", manifest.id));
+            continue;
+        }
+        manifest.tools.retain(|tool| {
+            let fresh = tool_ids.insert(tool.id.clone());
+            if !fresh {
+                registry.errors.push(format!("{label}: tool '{}' already exists, skipped", tool.id));
+            }
+            fresh
+        });
+        let granted = grants.get(&manifest.id).cloned().unwrap_or_default();
+        registry.plugins.push(Plugin { module_id: format!("plugin_{}", manifest.id), dir, manifest, granted });
+    }
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `manifest` as `root/<dir>/plugin.yaml`.
+    fn write(root: &Path, dir: &str, manifest: &str) {
+        let plugin = root.join(dir);
+        let written =
+            std::fs::create_dir_all(&plugin).and_then(|()| std::fs::write(plugin.join(MANIFEST_FILE), manifest));
+        assert_eq!(written.ok(), Some(()));
+    }
+
+    const JIRA: &str = "id: jira\nname: Jira\ndescription: Issues\ncommand: [./jira]\ntools:\n  - id: jira_issue\n    \
+                        short_desc: Fetch an issue\n    description: Fetch one issue.\n    params:\n      - name: key\n        \
+                        type: string\n        required: true\n      - name: fields\n        type: {array: string}\n";
+
+    #[test]
+    fn manifests_become_tool_definitions() {
+        let Ok(root) = tempfile::tempdir() else { return };
+        write(root.path(), "jira", JIRA);
+        let registry = discover(root.path(), &Grants::new(), &HashSet::new());
+        assert!(registry.errors.is_empty(), "{:?}", registry.errors);
+        let tools: Vec<ToolDefinition> = registry.plugins.iter().flat_map(Plugin::tool_definitions).collect();
+        assert_eq!(tools.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), ["jira_issue"]);
+        assert_eq!(tools.first().map(|t| (t.category.as_str(), t.params.len())), Some(("Jira", 2)));
+        assert_eq!(registry.plugins.first().map(|p| p.manifest.timeout_secs), Some(DEFAULT_TIMEOUT_SECS));
+    }
+
+    #[test]
+    fn invalid_and_clashing_plugins_are_reported() {
+        let Ok(root) = tempfile::tempdir() else { return };
+        write(root.path(), "a_jira", JIRA);
+        write(root.path(), "b_again", JIRA);
+        write(root.path(), "c_bad", &JIRA.replace("id: jira\n", "id: Bad-Id\n"));
+        write(root.path(), "d_clash", &JIRA.replace("id: jira\n", "id: other\n"));
+        let taken = HashSet::from(["git_execute".to_owned()]);
+        let registry = discover(root.path(), &Grants::new(), &taken);
+        assert_eq!(
+            registry.plugins.iter().map(|p| p.module_id.as_str()).collect::<Vec<_>>(),
+            ["plugin_jira", "plugin_other"]
+        );
+        assert_eq!(registry.plugins.get(1).map(|p| p.manifest.tools.len()), Some(0));
+        assert_eq!(registry.errors.len(), 3, "{:?}", registry.errors);
+    }
+
+    #[test]
+    fn runtime_is_a_command_or_a_sandboxed_module() {
+        let Ok(root) = tempfile::tempdir() else { return };
+        let wasm = JIRA.replace("command: [./jira]\n", "wasm: jira.wasm\ncapabilities:\n  files: [docs]\n");
+        write(root.path(), "a_wasm", &wasm);
+        write(root.path(), "b_both", &JIRA.replace("id: jira\n", "id: both\nwasm: both.wasm\n"));
+        let granted = JIRA.replace("id: jira\n", "id: granted\ncapabilities:\n  network: [example.com]\n");
+        write(root.path(), "c_granted", &granted);
+        let registry = discover(root.path(), &Grants::new(), &HashSet::new());
+        assert_eq!(
+            registry.plugins.iter().map(|p| p.manifest.wasm.as_deref()).collect::<Vec<_>>(),
+            [Some("jira.wasm")]
+        );
+        assert_eq!(registry.errors.len(), 2, "{:?}", registry.errors);
+    }
+
+    #[test]
+    fn capabilities_come_from_the_user_grants_only() {
+        let Ok(root) = tempfile::tempdir() else { return };
+        let wasm = JIRA.replace("command: [./jira]\n", "wasm: jira.wasm\ncapabilities:\n  files: [\"/\"]\n");
+        write(root.path(), "jira", &wasm);
+        let ungranted = discover(root.path(), &Grants::new(), &HashSet::new());
+        assert!(ungranted.plugins.first().is_some_and(|p| p.granted.is_empty()));
+        let docs = Capabilities { files: vec!["docs".to_owned()], network: vec![] };
+        let grants = Grants::from([("jira".to_owned(), docs)]);
+        let granted = discover(root.path(), &grants, &HashSet::new());
+        assert_eq!(granted.plugins.first().map(|p| p.granted.files.clone()), Some(vec!["docs".to_owned()]));
+    }
+}
@@ -0,0 +1,214 @@
+//! One JSON-RPC 2.0 exchange with a plugin process.
+//!
+//! The host writes a single request line to the plugin's stdin and closes it:
+//!
+//! ```json
+//! {"jsonrpc":"2.0","id":1,"method":"execute","params":{"tool":"jira_issue","input":{"key":"CP-1"}}}
+//! ```
+//!
+//! and reads one response from stdout, either a result or an error:
+//!
+//! ```json
+//! {"jsonrpc":"2.0","id":1,"result":{"content":"...","is_error":false,"panel":{"title":"CP-1","content":"..."}}}
+//! {"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"unknown key"}}
+//! ```
+//!
+//! `is_error` and `panel` are optional. Stderr is ignored unless the process
+//! fails without a response. WASM plugins exchange the same two documents
+//! through guest memory instead (see [`crate::wasm`]).
+
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::manifest::Plugin;
+
+/// Output bytes kept from a plugin response or failure.
+const OUTPUT_BUDGET_BYTES: usize = 64_000;
+
+/// What a plugin call produced.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Reply {
+    /// Tool result text.
+    pub content: String,
+    /// Whether the tool failed.
+    pub is_error: bool,
+    /// Panel to open: title and content.
+    pub panel: Option<(String, String)>,
+}
+
+/// The `result` object of a response.
+#[derive(Debug, Deserialize)]
+struct RpcResult {
+    /// Tool result text.
+    content: String,
+    /// Whether the tool failed.
+    #[serde(default)]
+    is_error: bool,
+    /// Panel to open.
+    #[serde(default)]
+    panel: Option<RpcPanel>,
+}
+
+/// A panel requested by a plugin.
+#[derive(Debug, Deserialize)]
+struct RpcPanel {
+    /// Panel title.
+    title: String,
+    /// Panel body.
+    content: String,
+}
+
+/// The `error` object of a response.
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    /// JSON-RPC error code.
+    code: i64,
+    /// Human-readable reason.
+    message: String,
+}
+
+/// A JSON-RPC 2.0 response.
+#[derive(Debug, Deserialize)]
+struct Response {
+    /// Success payload.
+    #[serde(default)]
+    result: Option<RpcResult>,
+    /// Failure payload.
+    #[serde(default)]
+    error: Option<RpcError>,
+}
+
+/// The request line sent for one tool call.
+pub(crate) fn request(tool: &str, input: &serde_json::Value) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "execute",
+        "params": { "tool": tool, "input": input },
+    })
+    .to_string()
+}
+
+/// Interpret the plugin's stdout.
+pub(crate) fn parse_response(stdout: &str) -> Result<Reply, String> {
+    let response: Response =
+        serde_json::from_str(stdout.trim()).map_err(|e| format!("plugin replied with invalid JSON-RPC: {e}"))?;
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(format!("plugin error {}: {}", error.code, error.message)),
+        (Some(result), None) => Ok(Reply {
+            content: cp_base::modules::truncate_output(&result.content, OUTPUT_BUDGET_BYTES),
+            is_error: result.is_error,
+            panel: result.panel.map(|p| (p.title, cp_base::modules::truncate_output(&p.content, OUTPUT_BUDGET_BYTES))),
+        }),
+        (None, None) => Err("plugin response has neither result nor error".to_owned()),
+    }
+}
+
+/// Run `plugin` once for `tool`, giving up after the manifest timeout.
+pub(crate) fn call(plugin: &Plugin, tool: &str, input: &serde_json::Value) -> Result<Reply, String> {
+    let line = request(tool, input);
+    let stdout = match plugin.manifest.wasm.as_deref() {
+        Some(file) => crate::wasm::run(plugin, file, &line, Path::new("."))?,
+        None => run_process(plugin, &line)?,
+    };
+    parse_response(&stdout)
+}
+
+/// Start the plugin command, send `line` on its stdin and return its stdout.
+fn run_process(plugin: &Plugin, line: &str) -> Result<String, String> {
+    let (program, args) = plugin.manifest.command.split_first().ok_or("plugin command is empty")?;
+    let mut cmd = Command::new(program);
+    let _cmd =
+        cmd.args(args).current_dir(&plugin.dir).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = cmd.spawn().map_err(|e| format!("cannot start plugin '{}': {e}", plugin.manifest.id))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that exits without reading still gets its output read below.
+        let _written = writeln!(stdin, "{line}");
+    }
+    let output = wait(child, plugin.manifest.timeout_secs)?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if stdout.trim().is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "plugin '{}' exited ({}) without a response:\n{}",
+            plugin.manifest.id,
+            output.status,
+            cp_base::modules::truncate_output(stderr.trim(), OUTPUT_BUDGET_BYTES)
+        ));
+    }
+    Ok(stdout)
+}
+
+/// Collect the child's output, or fail after `timeout_secs`.
+fn wait(child: std::process::Child, timeout_secs: u64) -> Result<Output, String> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    drop(std::thread::spawn(move || {
+        let _r = tx.send(child.wait_with_output());
+    }));
+    match rx.recv_timeout(Duration::from_secs(timeout_secs)) {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(e)) => Err(format!("plugin failed: {e}")),
+        Err(_) => Err(format!("plugin timed out after {timeout_secs}s")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_wraps_tool_and_input() {
+        let line = request("jira_issue", &serde_json::json!({"key": "CP-1"}));
+        let parsed: serde_json::Value = serde_json::from_str(&line).unwrap_or_default();
+        assert_eq!(parsed.pointer("/params/tool").and_then(serde_json::Value::as_str), Some("jira_issue"));
+        assert_eq!(parsed.pointer("/params/input/key").and_then(serde_json::Value::as_str), Some("CP-1"));
+        assert_eq!(parsed.get("method").and_then(serde_json::Value::as_str), Some("execute"));
+    }
+
+    #[test]
+    fn results_and_panels_parse() {
+        let reply = parse_response(
+            r#"{"jsonrpc":"2.0","id":1,"result":{"content":"ok","panel":{"title":"T","content":"body"}}}"#,
+        );
+        assert_eq!(
+            reply,
+            Ok(Reply { content: "ok".to_owned(), is_error: false, panel: Some(("T".to_owned(), "body".to_owned())) })
+        );
+        let failed = parse_response(r#"{"result":{"content":"no such issue","is_error":true}}"#);
+        assert_eq!(failed.map(|r| r.is_error), Ok(true));
+    }
+
+    #[test]
+    fn errors_and_garbage_are_refused() {
+        let error = parse_response(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"bad key"}}"#);
+        assert_eq!(error, Err("plugin error -32602: bad key".to_owned()));
+        assert!(parse_response("not json").is_err_and(|e| e.contains("invalid JSON-RPC")));
+        assert!(parse_response("{}").is_err_and(|e| e.contains("neither")));
+    }
+
+    /// A plugin whose command is `sh -c <script>`, run from the temp dir.
+    fn shell_plugin(dir: &Path, script: &str) -> Option<Plugin> {
+        let yaml = serde_json::json!({
+            "id": "echo", "name": "Echo", "description": "Test", "command": ["sh", "-c", script], "tools": [],
+        });
+        let manifest = serde_yaml::from_str(&yaml.to_string()).ok()?;
+        let granted = crate::capability::Capabilities::default();
+        Some(Plugin { module_id: "plugin_echo".to_owned(), dir: dir.to_path_buf(), manifest, granted })
+    }
+
+    #[test]
+    fn call_round_trips_through_the_process() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let script =
+            r#"read line; case "$line" in *'"tool":"echo_tool"'*) echo '{"result":{"content":"pong"}}';; esac"#;
+        let Some(plugin) = shell_plugin(dir.path(), script) else { return };
+        let reply = call(&plugin, "echo_tool", &serde_json::json!({}));
+        assert_eq!(reply.map(|r| r.content), Ok("pong".to_owned()));
+        let Some(silent) = shell_plugin(dir.path(), "echo boom >&2; exit 3") else { return };
+        assert!(call(&silent, "echo_tool", &serde_json::json!({})).is_err_and(|e| e.contains("boom")));
+    }
+}
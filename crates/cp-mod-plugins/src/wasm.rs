@@ -0,0 +1,288 @@
+//! WebAssembly plugins: untrusted tools run inside wasmtime.
+//!
+//! A WASM plugin exports `memory`, `cp_alloc(len) -> ptr` and
+//! `cp_execute(ptr, len) -> packed`, where `packed` holds a pointer in its
+//! high 32 bits and a length in its low 32 bits. `cp_execute` receives the
+//! JSON-RPC request line a process plugin would read on stdin and returns the
+//! response text. The module sees nothing of the host beyond two imports from
+//! `cp`, each gated by the capabilities the user granted the plugin:
+//!
+//! - `read_file(ptr, len) -> packed`: a file under one of `capabilities.files`
+//! - `http_get(ptr, len) -> packed`: an HTTPS URL on one of `capabilities.network`
+//!
+//! Both return [`DENIED`] when the capability is missing and [`FAILED`] when
+//! the read or request fails. Every call gets a fresh instance with bounded
+//! memory and is interrupted once the manifest timeout elapses.
+
+use std::collections::HashMap;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use cp_base::cast::Safe as _;
+use wasmtime::{
+    AsContext, AsContextMut, Caller, Config, Engine, Extern, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, Trap, TypedFunc,
+};
+
+use crate::capability::Capabilities;
+use crate::manifest::Plugin;
+
+/// Linear memory one instance may grow to.
+const MAX_MEMORY_BYTES: usize = 64 << 20;
+
+/// Bytes a file read, HTTP body or response may carry.
+const MAX_IO_BYTES: usize = 1 << 20;
+
+/// Epoch granularity: timeouts are enforced to within one tick.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Ticks per second of timeout.
+const TICKS_PER_SEC: u64 = 10;
+
+/// Returned by a host import when the capability is not granted.
+pub(crate) const DENIED: i64 = -1;
+
+/// Returned by a host import when the granted read or request failed.
+pub(crate) const FAILED: i64 = -2;
+
+/// Per-call store data.
+struct Host {
+    /// Directory `capabilities.files` are relative to.
+    root: PathBuf,
+    /// What the module may reach.
+    capabilities: Capabilities,
+    /// Memory and instance limits.
+    limits: StoreLimits,
+}
+
+/// The shared engine, with a background thread advancing its epoch every
+/// [`TICK`] so running calls can be interrupted.
+static ENGINE: LazyLock<Result<Engine, String>> = LazyLock::new(|| {
+    let mut config = Config::new();
+    let _config = config.epoch_interruption(true);
+    let engine = Engine::new(&config).map_err(|e| format!("cannot start the WASM runtime: {e}"))?;
+    let ticker = engine.clone();
+    let _ticker = thread::Builder::new().name("wasm-epoch".to_owned()).spawn(move || tick(&ticker));
+    Ok(engine)
+});
+
+/// An instance's memory and `cp_alloc`.
+type Guest = (Memory, TypedFunc<i32, i32>);
+
+/// A store with its instance's memory, `cp_alloc` and `cp_execute`.
+type Instance = (Store<Host>, Memory, TypedFunc<i32, i32>, TypedFunc<(i32, i32), i64>);
+
+/// Compiled modules, by plugin module id.
+static COMPILED: LazyLock<Mutex<HashMap<String, Module>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Advance `engine`'s epoch forever.
+fn tick(engine: &Engine) -> ! {
+    loop {
+        thread::sleep(TICK);
+        engine.increment_epoch();
+    }
+}
+
+/// A pointer and a length in one `i64`.
+fn pack(ptr: u32, len: u32) -> i64 {
+    i64::from(ptr).wrapping_shl(32) | i64::from(len)
+}
+
+/// The pointer and length in `packed`.
+fn unpack(packed: i64) -> (u32, u32) {
+    let bits = packed.cast_unsigned();
+    (bits.wrapping_shr(32).to_u32(), (bits & u64::from(u32::MAX)).to_u32())
+}
+
+/// The guest bytes at `packed`.
+fn copy_out<T>(ctx: T, memory: Memory, packed: i64) -> Option<Vec<u8>>
+where
+    T: AsContext,
+{
+    let (ptr, len) = unpack(packed);
+    let start = ptr.to_usize();
+    let end = start.checked_add(len.to_usize())?;
+    memory.data(&ctx).get(start..end).map(<[u8]>::to_vec)
+}
+
+/// Copy `bytes` into guest memory allocated with `alloc`, returning the
+/// packed location.
+fn copy_in<T>(mut ctx: T, memory: Memory, alloc: &TypedFunc<i32, i32>, bytes: &[u8]) -> Option<i64>
+where
+    T: AsContextMut,
+{
+    let len = u32::try_from(bytes.len()).ok()?;
+    let ptr = alloc.call(&mut ctx, len.cast_signed()).ok()?.cast_unsigned();
+    memory.write(&mut ctx, ptr.to_usize(), bytes).ok()?;
+    Some(pack(ptr, len))
+}
+
+/// The guest's memory and allocator, as seen from a host import.
+fn guest(caller: &mut Caller<'_, Host>) -> Option<Guest> {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    let alloc = caller.get_export("cp_alloc").and_then(Extern::into_func)?.typed(&*caller).ok()?;
+    Some((memory, alloc))
+}
+
+/// Run a host import: read its string argument, apply `handler`, and hand
+/// the bytes back to the guest.
+fn serve<F>(mut caller: Caller<'_, Host>, ptr: i32, len: i32, handler: F) -> i64
+where
+    F: FnOnce(&Host, &str) -> Result<Vec<u8>, i64>,
+{
+    let Some((memory, alloc)) = guest(&mut caller) else { return FAILED };
+    let Some(raw) = copy_out(&caller, memory, pack(ptr.cast_unsigned(), len.cast_unsigned())) else { return FAILED };
+    let arg = String::from_utf8_lossy(&raw);
+    match handler(caller.data(), &arg) {
+        Ok(bytes) => copy_in(&mut caller, memory, &alloc, &bytes).unwrap_or(FAILED),
+        Err(code) => code,
+    }
+}
+
+/// `cp.read_file`: a file inside a granted directory.
+fn read_file(host: &Host, path: &str) -> Result<Vec<u8>, i64> {
+    let file = host.capabilities.readable(&host.root, path).ok_or(DENIED)?;
+    let mut bytes = Vec::new();
+    let _read = std::fs::File::open(file)
+        .and_then(|f| f.take(MAX_IO_BYTES.to_u64()).read_to_end(&mut bytes))
+        .map_err(|_e| FAILED)?;
+    Ok(bytes)
+}
+
+/// `cp.http_get`: an HTTPS GET to a granted host, redirects not followed.
+fn http_get(host: &Host, url: &str) -> Result<Vec<u8>, i64> {
+    let target = host.capabilities.reachable(url).ok_or(DENIED)?;
+    let client = reqwest::blocking::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|_e| FAILED)?;
+    let response = client.get(target).send().map_err(|_e| FAILED)?;
+    let mut bytes = Vec::new();
+    let _read = response.take(MAX_IO_BYTES.to_u64()).read_to_end(&mut bytes).map_err(|_e| FAILED)?;
+    Ok(bytes)
+}
+
+/// The compiled module for `plugin`, compiled on first use.
+fn module(engine: &Engine, plugin: &Plugin, file: &str) -> Result<Module, String> {
+    let cached = COMPILED.lock().unwrap_or_else(PoisonError::into_inner).get(&plugin.module_id).cloned();
+    if let Some(found) = cached {
+        return Ok(found);
+    }
+    let loaded = Module::from_file(engine, plugin.dir.join(file)).map_err(|e| format!("cannot load {file}: {e:#}"))?;
+    let _previous =
+        COMPILED.lock().unwrap_or_else(PoisonError::into_inner).insert(plugin.module_id.clone(), loaded.clone());
+    Ok(loaded)
+}
+
+/// A fresh, limited store and the instance's entry points.
+fn instantiate(engine: &Engine, plugin: &Plugin, module: &Module, root: &Path) -> Result<Instance, String> {
+    let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).instances(1).build();
+    let capabilities = plugin.granted.clone();
+    let mut store = Store::new(engine, Host { root: root.to_path_buf(), capabilities, limits });
+    store.limiter(|host| &mut host.limits);
+    store.set_epoch_deadline(plugin.manifest.timeout_secs.saturating_mul(TICKS_PER_SEC));
+    let mut linker = Linker::new(engine);
+    let _linker = linker
+        .func_wrap("cp", "read_file", |caller: Caller<'_, Host>, ptr: i32, len: i32| serve(caller, ptr, len, read_file))
+        .and_then(|l| {
+            l.func_wrap("cp", "http_get", |caller: Caller<'_, Host>, ptr: i32, len: i32| {
+                serve(caller, ptr, len, http_get)
+            })
+        })
+        .map_err(|e| format!("cannot link host imports: {e}"))?;
+    let instance = linker.instantiate(&mut store, module).map_err(|e| format!("cannot instantiate: {e:#}"))?;
+    let memory = instance.get_memory(&mut store, "memory").ok_or("module does not export memory")?;
+    let alloc = instance.get_typed_func(&mut store, "cp_alloc").map_err(|e| format!("cp_alloc: {e}"))?;
+    let execute = instance.get_typed_func(&mut store, "cp_execute").map_err(|e| format!("cp_execute: {e}"))?;
+    Ok((store, memory, alloc, execute))
+}
+
+/// Run `request` through `plugin`'s module and return the response text.
+/// Granted files are looked up under `root`, the project directory.
+pub(crate) fn run(plugin: &Plugin, file: &str, request: &str, root: &Path) -> Result<String, String> {
+    let engine = ENGINE.as_ref().map_err(Clone::clone)?;
+    let module = module(engine, plugin, file)?;
+    let (mut store, memory, alloc, execute) = instantiate(engine, plugin, &module, root)?;
+    let input = copy_in(&mut store, memory, &alloc, request.as_bytes()).ok_or("cannot pass the request in")?;
+    let (ptr, len) = unpack(input);
+    let packed = execute.call(&mut store, (ptr.cast_signed(), len.cast_signed())).map_err(|e| {
+        if e.downcast_ref::<Trap>() == Some(&Trap::Interrupt) {
+            format!("plugin timed out after {}s", plugin.manifest.timeout_secs)
+        } else {
+            format!("plugin trapped: {e:#}")
+        }
+    })?;
+    if unpack(packed).1.to_usize() > MAX_IO_BYTES {
+        return Err(format!("plugin response exceeds {MAX_IO_BYTES} bytes"));
+    }
+    let bytes = copy_out(&store, memory, packed).ok_or("plugin response is out of bounds")?;
+    String::from_utf8(bytes).map_err(|_e| "plugin response is not UTF-8".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module with a bump allocator, `data` at offset 0 and `cp_execute`
+    /// running `body`.
+    fn source(data: &str, body: &str) -> String {
+        format!(
+            r#"(module
+                (import "cp" "read_file" (func $read_file (param i32 i32) (result i64)))
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 1024))
+                (data (i32.const 0) "{data}")
+                (func (export "cp_alloc") (param $len i32) (result i32)
+                    (global.get $next)
+                    (global.set $next (i32.add (global.get $next) (local.get $len))))
+                (func (export "cp_execute") (param i32 i32) (result i64) {body}))"#
+        )
+    }
+
+    /// Compile `wat` into `dir/plugin.wasm` and describe it as plugin `id`.
+    fn plugin(dir: &Path, id: &str, wat: &str, files: &[&str]) -> Option<Plugin> {
+        std::fs::write(dir.join("plugin.wasm"), wat::parse_str(wat).ok()?).ok()?;
+        let manifest = serde_json::from_value(serde_json::json!({
+            "id": id, "name": id, "description": "Test", "wasm": "plugin.wasm", "timeout_secs": 1u64,
+            "tools": [],
+        }))
+        .ok()?;
+        let granted = Capabilities { files: files.iter().map(|f| (*f).to_owned()).collect(), network: vec![] };
+        Some(Plugin { module_id: format!("plugin_{id}"), dir: dir.to_path_buf(), manifest, granted })
+    }
+
+    #[test]
+    fn response_is_read_from_guest_memory() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let wat = source(r#"{\"result\":{\"content\":\"pong\"}}"#, "(i64.const 29)");
+        let Some(echo) = plugin(dir.path(), "wasm_echo", &wat, &[]) else { return };
+        let reply = run(&echo, "plugin.wasm", "{}", dir.path());
+        assert_eq!(reply, Ok(r#"{"result":{"content":"pong"}}"#.to_owned()));
+    }
+
+    #[test]
+    fn files_need_a_capability() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let written = std::fs::create_dir_all(dir.path().join("docs"))
+            .and_then(|()| std::fs::write(dir.path().join("docs/reply.json"), "granted"));
+        assert_eq!(written.ok(), Some(()));
+        let wat = source("docs/reply.json", "(call $read_file (i32.const 0) (i32.const 15))");
+        let Some(granted) = plugin(dir.path(), "wasm_granted", &wat, &["docs"]) else { return };
+        assert_eq!(run(&granted, "plugin.wasm", "{}", dir.path()), Ok("granted".to_owned()));
+        let Some(denied) = plugin(dir.path(), "wasm_denied", &wat, &[]) else { return };
+        assert_eq!(run(&denied, "plugin.wasm", "{}", dir.path()).ok(), None);
+    }
+
+    #[test]
+    fn runaway_modules_are_interrupted() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let wat = source("", "(loop $spin (br $spin)) (i64.const 0)");
+        let Some(spin) = plugin(dir.path(), "wasm_spin", &wat, &[]) else { return };
+        let reply = run(&spin, "plugin.wasm", "{}", dir.path());
+        assert!(reply.is_err_and(|e| e.contains("timed out")));
+    }
+}
@@ -406,11 +406,7 @@ fn visualize_prompt_output(content: &str, width: usize) -> Vec<cp_render::Block>
                 return Block::empty();
             }
             let semantic = prompt_line_semantic(line);
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
             Block::Line(vec![Span::styled(display, semantic)])
         })
         .collect()
@@ -2,9 +2,10 @@ use crossterm::event::KeyEvent;
 
 use crate::types::{PromptState, PromptType};
 
-use cp_base::panels::{CacheRequest, CacheUpdate, ContextItem, Panel, scroll_key_action};
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 use std::fmt::Write as _;
 
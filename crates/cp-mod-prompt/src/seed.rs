@@ -1,4 +1,4 @@
-use cp_base::config::accessors::library;
+use cp_base::config::accessors::{library, prompts};
 use cp_base::state::runtime::State;
 
 use crate::types::{PromptState, PromptType};
@@ -19,17 +19,20 @@ pub fn ensure_default_agent(state: &mut State) {
     }
 }
 
-/// Get the active agent's content (system prompt).
+/// Get the active agent's content (system prompt), followed by the panel
+/// provenance policy so every agent treats fetched content as untrusted.
 /// Dynamically loads from disk every call — no caching.
 #[must_use]
 pub fn get_active_agent_content(state: &State) -> String {
     let ps = PromptState::get(state);
     let agents = crate::storage::load_prompts_for(PromptType::Agent);
-    if let Some(active_id) = ps.active_agent_id.as_ref()
-        && let Some(agent) = agents.iter().find(|a| &a.id == active_id)
-    {
-        return agent.content.clone();
-    }
-    // Fallback to default
-    library::agents().first().map(|a| a.content.clone()).unwrap_or_default()
+    let content = ps
+        .active_agent_id
+        .as_ref()
+        .and_then(|active_id| agents.iter().find(|a| &a.id == active_id))
+        .map(|agent| agent.content.clone())
+        // Fallback to default
+        .or_else(|| library::agents().first().map(|a| a.content.clone()))
+        .unwrap_or_default();
+    format!("{}\n\n{}", content.trim_end(), prompts::panel_provenance_policy().trim_end())
 }
@@ -2,9 +2,10 @@ use crossterm::event::KeyEvent;
 
 use crate::types::PromptType;
 
-use cp_base::panels::{CacheRequest, CacheUpdate, ContextItem, Panel, scroll_key_action};
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
 /// Panel displaying a single loaded skill's content.
@@ -0,0 +1,105 @@
+"""Context Pilot Python kernel.
+
+Reads one JSON cell per line on stdin ({"code": ...}) and answers with one
+JSON line: captured stdout/stderr, the traceback if the cell raised, the
+plots it drew (saved as PNG under argv[1]) and the user variables. Globals
+persist across cells. The protocol keeps its own copy of stdout; fd 1 is
+pointed at stderr so subprocesses cannot corrupt it.
+"""
+import ast
+import contextlib
+import io
+import json
+import os
+import sys
+import traceback
+
+REQUESTS = sys.stdin
+REPLIES = os.fdopen(os.dup(1), "w")
+os.dup2(2, 1)
+sys.stdin = io.StringIO()
+os.environ.setdefault("MPLBACKEND", "Agg")
+
+PLOT_DIR = sys.argv[1]
+NAMESPACE = {"__name__": "__main__"}
+SUMMARY_CHARS = 60
+plot_count = 0
+
+
+def run(code):
+    """Execute a cell; echo the value of a trailing expression like a notebook."""
+    tree = ast.parse(code, "<cell>", "exec")
+    last = None
+    if tree.body and isinstance(tree.body[-1], ast.Expr):
+        last = ast.Expression(tree.body.pop().value)
+    exec(compile(tree, "<cell>", "exec"), NAMESPACE)
+    if last is not None:
+        value = eval(compile(last, "<cell>", "eval"), NAMESPACE)
+        if value is not None:
+            NAMESPACE["_"] = value
+            print(repr(value))
+
+
+def save_plots():
+    """Save and close every open matplotlib figure."""
+    global plot_count
+    plt = sys.modules.get("matplotlib.pyplot")
+    if plt is None:
+        return []
+    paths = []
+    for number in plt.get_fignums():
+        plot_count += 1
+        os.makedirs(PLOT_DIR, exist_ok=True)
+        path = os.path.join(PLOT_DIR, "plot_%d.png" % plot_count)
+        plt.figure(number).savefig(path, bbox_inches="tight")
+        paths.append(path)
+    plt.close("all")
+    return paths
+
+
+def describe(value):
+    """Short summary: shape for arrays and frames, length for containers, else repr."""
+    shape = getattr(value, "shape", None)
+    if isinstance(shape, tuple):
+        return "shape %s" % (shape,)
+    if isinstance(value, (list, tuple, dict, set, str, bytes)):
+        return "len %d" % len(value)
+    text = repr(value).replace("\n", " ")
+    return text if len(text) <= SUMMARY_CHARS else text[: SUMMARY_CHARS - 1] + "\u2026"
+
+
+def variables():
+    """User globals, modules and private names left out."""
+    found = []
+    for name, value in list(NAMESPACE.items()):
+        if name.startswith("_") or isinstance(value, type(sys)):
+            continue
+        try:
+            summary = describe(value)
+        except Exception:
+            summary = "?"
+        found.append({"name": name, "kind": type(value).__name__, "summary": summary})
+    return found
+
+
+def execute(code):
+    """Run one cell and build its reply."""
+    out, err = io.StringIO(), io.StringIO()
+    error = None
+    with contextlib.redirect_stdout(out), contextlib.redirect_stderr(err):
+        try:
+            run(code)
+        except BaseException as exc:
+            error = "".join(traceback.format_exception(type(exc), exc, exc.__traceback__.tb_next))
+        try:
+            plots = save_plots()
+        except Exception as exc:
+            plots = []
+            print("could not save plots: %s" % exc, file=sys.stderr)
+    return {"stdout": out.getvalue(), "stderr": err.getvalue(), "error": error, "plots": plots, "vars": variables()}
+
+
+for line in REQUESTS:
+    reply = execute(json.loads(line)["code"])
+    REPLIES.write(json.dumps(reply) + "\n")
+    REPLIES.flush()
@@ -0,0 +1,125 @@
+//! The Python subprocess: started on the first cell, kept alive between
+//! cells so globals persist, killed on timeout, reset or drop.
+
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Protocol loop run by the interpreter.
+const DRIVER: &str = include_str!("driver.py");
+
+/// Project virtualenv interpreter, preferred over `python3` when present.
+const VENV_PYTHON: &str = ".venv/bin/python";
+
+/// One user variable, as listed in the kernel panel.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct VarInfo {
+    /// Global name.
+    pub name: String,
+    /// Type name (`int`, `DataFrame`, ...).
+    pub kind: String,
+    /// Shape, length or a short repr.
+    pub summary: String,
+}
+
+/// What a cell produced.
+#[derive(Debug, Deserialize)]
+pub(crate) struct CellReply {
+    /// Captured stdout, trailing expression value included.
+    pub stdout: String,
+    /// Captured stderr.
+    pub stderr: String,
+    /// Traceback when the cell raised.
+    pub error: Option<String>,
+    /// PNG files of the figures the cell drew.
+    pub plots: Vec<String>,
+    /// User globals after the cell.
+    pub vars: Vec<VarInfo>,
+}
+
+/// A running interpreter.
+#[derive(Debug)]
+pub(crate) struct Kernel {
+    /// The interpreter process.
+    child: Child,
+    /// Cells go here, one JSON line each.
+    stdin: ChildStdin,
+    /// Reply lines, read on a helper thread so waits can time out.
+    replies: Receiver<String>,
+    /// Interpreter command, for display.
+    python: String,
+}
+
+impl Kernel {
+    /// Start an interpreter in the project root; plots are saved to `plot_dir`.
+    pub(crate) fn start(plot_dir: &Path) -> Result<Self, String> {
+        let python = if Path::new(VENV_PYTHON).exists() { VENV_PYTHON } else { "python3" };
+        let mut child = Command::new(python)
+            .args(["-u", "-c", DRIVER])
+            .arg(plot_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("cannot start {python}: {e}"))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _killed = child.kill();
+            return Err("interpreter pipes unavailable".to_owned());
+        };
+        let (tx, replies) = mpsc::channel();
+        let _reader = std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self { child, stdin, replies, python: python.to_owned() })
+    }
+
+    /// Interpreter command (`python3` or the project virtualenv's).
+    pub(crate) fn python(&self) -> &str {
+        &self.python
+    }
+
+    /// Run one cell, waiting at most `timeout`. `Err` means the interpreter
+    /// is gone or stuck; the caller drops it.
+    pub(crate) fn run(&mut self, code: &str, timeout: Duration) -> Result<CellReply, String> {
+        let request = serde_json::json!({ "code": code }).to_string();
+        writeln!(self.stdin, "{request}").and_then(|()| self.stdin.flush()).map_err(|e| format!("kernel died: {e}"))?;
+        match self.replies.recv_timeout(timeout) {
+            Ok(line) => serde_json::from_str(&line).map_err(|e| format!("bad kernel reply: {e}")),
+            Err(RecvTimeoutError::Timeout) => Err(format!("cell still running after {}s", timeout.as_secs())),
+            Err(RecvTimeoutError::Disconnected) => Err("kernel died".to_owned()),
+        }
+    }
+}
+
+impl Drop for Kernel {
+    fn drop(&mut self) {
+        let _killed = self.child.kill();
+        let _reaped = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globals_persist_across_cells() {
+        let plots = std::env::temp_dir().join("cp_mod_python_plots");
+        let Ok(mut kernel) = Kernel::start(&plots) else { return };
+        let timeout = Duration::from_secs(30);
+        let first = kernel.run("x = 2\nprint('set')", timeout);
+        assert_eq!(first.map(|r| r.stdout), Ok("set\n".to_owned()));
+        let second = kernel.run("x * 21", timeout).map(|r| (r.stdout, r.vars.into_iter().map(|v| v.name).collect()));
+        assert_eq!(second, Ok(("42\n".to_owned(), vec!["x".to_owned()])));
+        let failed = kernel.run("1 / 0", timeout);
+        assert!(failed.is_ok_and(|r| r.error.is_some_and(|e| e.contains("ZeroDivisionError"))));
+    }
+}
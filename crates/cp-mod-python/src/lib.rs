@@ -0,0 +1,122 @@
+//! Python module — notebook-style code cells for data analysis.
+//!
+//! Two tools: `py_exec` (run a cell on a persistent interpreter; globals,
+//! imports and loaded data survive between calls; stdout, tracebacks and
+//! matplotlib figures come back) and `py_reset` (stop the interpreter). The
+//! Kernel panel lists the variables currently defined.
+
+/// The interpreter subprocess and its line protocol.
+mod kernel;
+/// Kernel panel.
+mod panel;
+/// Tool dispatch: `py_exec`, `py_reset`.
+mod tools;
+/// Python state types: `PythonState`, `KernelView`.
+mod types;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::types::PythonState;
+
+/// Lazily parsed tool texts from the python YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/python.yaml")));
+
+/// Python module: persistent interpreter and kernel panel.
+#[derive(Debug, Clone, Copy)]
+pub struct PythonModule;
+
+impl Default for PythonModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PythonModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for PythonModule {
+    fn id(&self) -> &'static str {
+        "python"
+    }
+
+    fn name(&self) -> &'static str {
+        "Python"
+    }
+
+    fn description(&self) -> &'static str {
+        "Notebook-style Python cells on a persistent interpreter"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: Kind::PYTHON_KERNEL,
+            icon_id: "spine",
+            is_fixed: false,
+            needs_cache: true,
+            fixed_order: None,
+            display_name: "python-kernel",
+            short_name: "kernel",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::PYTHON_KERNEL)]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("py_exec", t)
+                .short_desc("Run a Python cell")
+                .category("Python")
+                .param("code", ParamType::String, true)
+                .param("timeout", ParamType::Integer, false)
+                .build(),
+            ToolDefinition::from_yaml("py_reset", t).short_desc("Restart the Python kernel").category("Python").build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        tools::dispatch(tool, state)
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        (context_type.as_str() == Kind::PYTHON_KERNEL).then(|| {
+            let panel: Box<dyn Panel> = Box::new(panel::KernelPanel);
+            panel
+        })
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Python", "Run Python code on a persistent interpreter")]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(PythonState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(PythonState::new());
+    }
+}
@@ -0,0 +1,122 @@
+//! Kernel panel: whether the interpreter runs and the variables it holds.
+//!
+//! Created by the first `py_exec`; re-reads the shared view on a timer, so
+//! cells finishing on a worker show up without a nudge.
+
+use std::fmt::Write as _;
+
+use crossterm::event::KeyEvent;
+
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action, update_if_changed};
+use cp_base::state::actions::Action;
+use cp_base::state::context::{Entry, Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::types::{KernelRequest, KernelView, PythonState};
+
+/// How often the panel re-reads the kernel view.
+const REFRESH_MS: u64 = 1_000;
+
+/// Status line followed by `name: type (summary)` lines.
+pub(crate) fn render_view(view: &KernelView) -> String {
+    let Some(python) = view.python.as_deref() else {
+        return "Kernel not running (py_exec starts it).\n".to_owned();
+    };
+    let mut out = format!("Kernel: {python}, {} cells run\n", view.cells);
+    if view.vars.is_empty() {
+        out.push_str("\nNo variables defined.\n");
+        return out;
+    }
+    out.push('\n');
+    for var in &view.vars {
+        let _r = writeln!(out, "{}: {} ({})", var.name, var.kind, var.summary);
+    }
+    out
+}
+
+/// Panel listing the kernel's variables.
+pub(crate) struct KernelPanel;
+
+impl Panel for KernelPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &Entry, state: &State) -> Option<CacheRequest> {
+        let view = PythonState::get(state).view.lock().ok()?.clone();
+        Some(CacheRequest::new(
+            Kind::new(Kind::PYTHON_KERNEL),
+            Box::new(KernelRequest { context_id: ctx.id.clone(), view }),
+        ))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<KernelRequest>().ok()?;
+        let content = render_view(&req.view);
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id: req.context_id, content, token_count })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, _state: &mut State) -> bool {
+        // Only a Content update carries new bytes; Unchanged/ModuleSpecific are no-ops.
+        if let CacheUpdate::Content { content, token_count, .. } = update {
+            ctx.token_count = token_count;
+            ctx.full_token_count = token_count;
+            ctx.cache_deprecated = false;
+            let changed = update_if_changed(ctx, &content);
+            ctx.cached_content = Some(content);
+            changed
+        } else {
+            false
+        }
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        Some(REFRESH_MS)
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Span as S};
+
+        let cached = state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == Kind::PYTHON_KERNEL)
+            .and_then(|c| c.cached_content.as_ref());
+        let Some(content) = cached else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content.lines().map(|line| Block::text(format!(" {line}"))).collect()
+    }
+
+    fn title(&self, _state: &State) -> String {
+        "Python Kernel".to_owned()
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::PYTHON_KERNEL)
+            .map(|c| {
+                let content = c.cached_content.as_deref().unwrap_or("[loading...]");
+                ContextItem::new(&c.id, "Python kernel", content.to_owned(), c.last_refresh_ms)
+            })
+            .collect()
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
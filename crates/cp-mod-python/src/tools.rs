@@ -0,0 +1,139 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cp_base::config::constants::STORE_DIR;
+use cp_base::state::context::{Kind, make_default_entry};
+use cp_base::state::runtime::State;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::kernel::{CellReply, Kernel};
+use crate::types::{KernelView, PythonState};
+
+/// Seconds a cell may run when no timeout is given.
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Highest cell timeout accepted, in seconds.
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+/// Seconds the async task outlives the cell timeout.
+const ASYNC_GRACE_SECS: u64 = 5;
+
+/// Characters of cell output returned at most (per stream).
+const MAX_OUTPUT_CHARS: usize = 20_000;
+
+/// Dispatch python tool calls.
+pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+    match tool.name.as_str() {
+        "py_exec" => Some(execute_cell(tool, state)),
+        "py_reset" => Some(execute_reset(tool, state)),
+        _ => None,
+    }
+}
+
+/// Create the kernel panel unless one is open.
+fn ensure_panel(state: &mut State) {
+    if state.context.iter().any(|c| c.context_type.as_str() == Kind::PYTHON_KERNEL) {
+        return;
+    }
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+    let mut ctx = make_default_entry(&panel_id, Kind::new(Kind::PYTHON_KERNEL), "Python Kernel", true);
+    ctx.uid = Some(uid);
+    state.context.push(ctx);
+}
+
+/// `text` cut to [`MAX_OUTPUT_CHARS`], with a note when cut.
+fn capped(text: &str) -> String {
+    if text.chars().count() <= MAX_OUTPUT_CHARS {
+        return text.trim_end().to_owned();
+    }
+    let head: String = text.chars().take(MAX_OUTPUT_CHARS).collect();
+    format!("{head}\n[output truncated at {MAX_OUTPUT_CHARS} characters]")
+}
+
+/// What the agent sees for a cell: streams, traceback and plot files.
+fn render_reply(reply: &CellReply, cell: u32) -> String {
+    let mut out = format!("In [{cell}]");
+    if !reply.stdout.is_empty() {
+        let _r = write!(out, "\n{}", capped(&reply.stdout));
+    }
+    if !reply.stderr.is_empty() {
+        let _r = write!(out, "\n[stderr]\n{}", capped(&reply.stderr));
+    }
+    if let Some(error) = reply.error.as_deref() {
+        let _r = write!(out, "\n{}", capped(error));
+    }
+    for plot in &reply.plots {
+        let _r = write!(out, "\nPlot saved: {plot}");
+    }
+    if reply.stdout.is_empty() && reply.stderr.is_empty() && reply.error.is_none() && reply.plots.is_empty() {
+        out.push_str("\n(no output)");
+    }
+    out
+}
+
+/// Run `code` on the kernel (started if needed), publishing the variables.
+fn run_cell(kernel: &Mutex<Option<Kernel>>, view: &Mutex<KernelView>, code: &str, timeout: Duration) -> ToolOutput {
+    let Ok(mut slot) = kernel.lock() else { return ToolOutput::error("kernel lock poisoned") };
+    if slot.is_none() {
+        match Kernel::start(&PathBuf::from(STORE_DIR).join("python")) {
+            Ok(started) => *slot = Some(started),
+            Err(e) => return ToolOutput::error(e),
+        }
+    }
+    let result = slot.as_mut().map(|k| (k.run(code, timeout), k.python().to_owned()));
+    let Some((Ok(reply), python)) = result else {
+        // Stuck or dead: drop it, the next cell starts a fresh one.
+        *slot = None;
+        if let Ok(mut shown) = view.lock() {
+            *shown = KernelView::default();
+        }
+        let reason = result.and_then(|(outcome, _python)| outcome.err()).unwrap_or_default();
+        return ToolOutput::error(format!("{reason}; kernel restarted, all variables are lost"));
+    };
+    let cell = view.lock().map_or(1, |mut shown| {
+        shown.cells = shown.cells.saturating_add(1);
+        shown.python = Some(python);
+        shown.vars.clone_from(&reply.vars);
+        shown.cells
+    });
+    ToolOutput::new(render_reply(&reply, cell), reply.error.is_some(), None, false)
+}
+
+/// Execute `py_exec`: run a cell on a worker thread.
+fn execute_cell(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(code) = tool.input.get("code").and_then(|v| v.as_str()).map(str::to_owned) else {
+        return ToolResult::new(tool.id.clone(), "Missing required parameter 'code'".to_owned(), true);
+    };
+    let secs = tool
+        .input
+        .get("timeout")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(1, MAX_TIMEOUT_SECS);
+    ensure_panel(state);
+    let ps = PythonState::get(state);
+    let (kernel, view) = (Arc::clone(&ps.kernel), Arc::clone(&ps.view));
+    spawn_async_tool(state, tool, secs.saturating_add(ASYNC_GRACE_SECS), move || {
+        run_cell(&kernel, &view, &code, Duration::from_secs(secs))
+    })
+}
+
+/// Execute `py_reset`: stop the kernel, dropping every variable.
+fn execute_reset(tool: &ToolUse, state: &State) -> ToolResult {
+    let ps = PythonState::get(state);
+    let Ok(mut slot) = ps.kernel.try_lock() else {
+        return ToolResult::new(tool.id.clone(), "A cell is still running; try again when it ends.".to_owned(), true);
+    };
+    let was_running = slot.take().is_some();
+    drop(slot);
+    if let Ok(mut shown) = ps.view.lock() {
+        *shown = KernelView::default();
+    }
+    let message = if was_running { "Kernel stopped; variables cleared." } else { "No kernel was running." };
+    ToolResult::new(tool.id.clone(), message.to_owned(), false)
+}
@@ -0,0 +1,53 @@
+use std::sync::{Arc, Mutex};
+
+use cp_base::state::runtime::State;
+
+use crate::kernel::{Kernel, VarInfo};
+
+/// What the kernel panel shows; updated by the worker running a cell.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct KernelView {
+    /// Interpreter command; `None` while no kernel runs.
+    pub python: Option<String>,
+    /// Cells run since the kernel started.
+    pub cells: u32,
+    /// User globals after the last cell.
+    pub vars: Vec<VarInfo>,
+}
+
+/// Module-owned state for the Python module.
+/// Stored in `State.module_data` via `TypeMap`.
+#[derive(Debug, Default)]
+pub(crate) struct PythonState {
+    /// The interpreter, locked by the worker for the whole cell.
+    pub kernel: Arc<Mutex<Option<Kernel>>>,
+    /// Panel data, locked only briefly so the UI never waits on a cell.
+    pub view: Arc<Mutex<KernelView>>,
+}
+
+impl PythonState {
+    /// Create a state with no kernel running.
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub(crate) fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+}
+
+/// Cache request of the kernel panel: a snapshot of the view.
+#[derive(Debug)]
+pub(crate) struct KernelRequest {
+    /// Panel to fill.
+    pub context_id: String,
+    /// View at request time.
+    pub view: KernelView,
+}
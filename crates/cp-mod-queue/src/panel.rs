@@ -1,5 +1,6 @@
-use cp_base::panels::{ContextItem, Panel};
+use cp_base::panels::Panel;
 use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
 use crate::types::QueueState;
@@ -39,15 +40,7 @@ impl Panel for QueuePanel {
 
         for call in &qs.queued_calls {
             let params = serde_json::to_string(&call.input).unwrap_or_default();
-            let short = if params.len() > 80 {
-                let mut end = 77;
-                while !params.is_char_boundary(end) {
-                    end = end.saturating_sub(1);
-                }
-                format!("{}...", params.get(..end).unwrap_or(""))
-            } else {
-                params
-            };
+            let short = cp_base::ui::text::ellipsize(&params, 80, "...");
             blocks.push(Block::Line(vec![
                 S::muted(format!("  {}. ", call.index)),
                 S::accent(call.tool_name.clone()).bold(),
@@ -138,15 +131,7 @@ impl QueuePanel {
             let _r1 = write!(text, "Queue {} — {} action(s) queued:\n\n", status, qs.queued_calls.len());
             for call in &qs.queued_calls {
                 let params = serde_json::to_string(&call.input).unwrap_or_default();
-                let short = if params.len() > 120 {
-                    let mut end = 117;
-                    while !params.is_char_boundary(end) {
-                        end = end.saturating_sub(1);
-                    }
-                    format!("{}...", params.get(..end).unwrap_or(""))
-                } else {
-                    params
-                };
+                let short = cp_base::ui::text::ellipsize(&params, 120, "...");
                 let _r2 = writeln!(text, "{}. {}({})", call.index, call.tool_name, short);
             }
         }
@@ -0,0 +1,172 @@
+//! Loading a diff to review: where it comes from, and its split into hunks.
+
+use std::process::Command;
+
+use cp_base::modules::run_with_timeout;
+
+use crate::types::Hunk;
+
+/// Max seconds for `git diff` / `gh pr diff`.
+const DIFF_TIMEOUT_SECS: u64 = 60;
+
+/// What to diff.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Source<'input> {
+    /// Uncommitted changes, staged or not (`git diff HEAD`).
+    WorkingTree,
+    /// A revision range such as `main..HEAD` or `v1.2...feature`.
+    Range(&'input str),
+    /// A pull request, by number (`gh pr diff`).
+    PullRequest(u64),
+}
+
+impl Source<'_> {
+    /// Parse the `source` argument: `working`, `A..B` / `A...B`, or `pr:42` / `#42`.
+    pub(crate) fn parse(raw: &str) -> Result<Source<'_>, String> {
+        let arg = raw.trim();
+        if arg.is_empty() || arg == "working" {
+            return Ok(Source::WorkingTree);
+        }
+        if let Some(number) = arg.strip_prefix("pr:").or_else(|| arg.strip_prefix('#')) {
+            return number.trim().parse().map(Source::PullRequest).map_err(|_e| format!("'{arg}' is not a PR number"));
+        }
+        if arg.contains("..") && !arg.starts_with('-') && !arg.contains(char::is_whitespace) {
+            return Ok(Source::Range(arg));
+        }
+        Err(format!("'{arg}' is not 'working', a range like 'main..HEAD', or 'pr:42'"))
+    }
+
+    /// How the review names it.
+    pub(crate) fn label(&self) -> String {
+        match *self {
+            Source::WorkingTree => "working tree".to_owned(),
+            Source::Range(range) => range.to_owned(),
+            Source::PullRequest(n) => format!("PR #{n}"),
+        }
+    }
+}
+
+/// Run the diff for `source`; `token` authenticates `gh` for pull requests.
+pub(crate) fn fetch(source: &Source<'_>, token: Option<&str>) -> Result<String, String> {
+    let mut cmd = match *source {
+        Source::WorkingTree => git(&["diff", "--no-color", "HEAD"]),
+        Source::Range(range) => git(&["diff", "--no-color", range]),
+        Source::PullRequest(n) => {
+            let gh_token = token.ok_or("GITHUB_TOKEN not set; it is needed to fetch a PR diff")?;
+            let mut cmd = Command::new("gh");
+            let _c = cmd
+                .args(["pr", "diff", &n.to_string()])
+                .env("GITHUB_TOKEN", gh_token)
+                .env("GH_TOKEN", gh_token)
+                .env("GH_PROMPT_DISABLED", "1")
+                .env("NO_COLOR", "1");
+            cmd
+        }
+    };
+    let _c = cmd.env("GIT_TERMINAL_PROMPT", "0");
+    let output = run_with_timeout(cmd, DIFF_TIMEOUT_SECS).map_err(|e| format!("cannot run the diff: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A `git` command with `args`.
+fn git(args: &[&str]) -> Command {
+    let mut cmd = Command::new("git");
+    let _c = cmd.args(args);
+    cmd
+}
+
+/// Start line of one side of a hunk header (`-10,3` or `+20`).
+fn side_start(range: &str) -> usize {
+    range.get(1..).and_then(|r| r.split(',').next()).and_then(|n| n.parse().ok()).unwrap_or(1)
+}
+
+/// File names of the diff section being read.
+#[derive(Default)]
+struct FileHeader {
+    /// Path on the old side.
+    old: String,
+    /// Path hunks belong to, once `+++` is seen.
+    new: Option<String>,
+    /// Between `diff --git` and the first `@@`, where `---`/`+++` name files
+    /// (inside a hunk they are a removed `--` or added `++` line).
+    open: bool,
+}
+
+impl FileHeader {
+    /// Take `line` if it belongs to a file header.
+    fn read(&mut self, line: &str) -> bool {
+        if line.starts_with("diff --git ") {
+            *self = Self { open: true, ..Self::default() };
+            return true;
+        }
+        if !self.open {
+            return false;
+        }
+        if let Some(path) = line.strip_prefix("--- ") {
+            path.trim_start_matches("a/").clone_into(&mut self.old);
+            return true;
+        }
+        let Some(path) = line.strip_prefix("+++ ") else { return false };
+        self.new = Some(if path == "/dev/null" { self.old.clone() } else { path.trim_start_matches("b/").to_owned() });
+        true
+    }
+}
+
+/// An empty hunk of `file` from its `@@ -a,b +c,d @@` line.
+fn hunk_start(header: &str, file: String) -> Hunk {
+    let mut ranges = header.split_whitespace().skip(1);
+    let (old, new) = (ranges.next().unwrap_or(""), ranges.next().unwrap_or(""));
+    Hunk { file, header: header.to_owned(), old_start: side_start(old), new_start: side_start(new), lines: Vec::new() }
+}
+
+/// Split a unified diff into hunks, each tagged with its file.
+pub(crate) fn parse(diff: &str) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut header = FileHeader::default();
+    for line in diff.lines() {
+        if header.read(line) {
+            continue;
+        }
+        if line.starts_with("@@") {
+            header.open = false;
+            hunks.push(hunk_start(line, header.new.clone().unwrap_or_default()));
+            continue;
+        }
+        if header.new.is_some()
+            && let Some(hunk) = hunks.last_mut()
+        {
+            hunk.lines.push(line.to_owned());
+        }
+    }
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_split_into_hunks_per_file() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\nindex 1..2 100644\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+                    @@ -1,3 +1,3 @@ fn main\n a\n-b\n+c\n--- sql\n@@ -10 +10,2 @@\n x\n+y\n\
+                    diff --git a/old.rs b/old.rs\ndeleted file mode 100644\n--- a/old.rs\n+++ /dev/null\n\
+                    @@ -1 +0,0 @@\n-gone\n";
+        let hunks = parse(diff);
+        let summary: Vec<_> =
+            hunks.iter().map(|h| (h.file.as_str(), h.old_start, h.new_start, h.lines.len())).collect();
+        assert_eq!(summary, vec![("src/a.rs", 1, 1, 4), ("src/a.rs", 10, 10, 2), ("old.rs", 1, 0, 1)]);
+    }
+
+    #[test]
+    fn sources_name_what_to_diff() {
+        assert_eq!(Source::parse(""), Ok(Source::WorkingTree));
+        assert_eq!(Source::parse("main...HEAD"), Ok(Source::Range("main...HEAD")));
+        assert_eq!(Source::parse("pr:42"), Ok(Source::PullRequest(42)));
+        assert_eq!(Source::parse("#7").map(|s| s.label()), Ok("PR #7".to_owned()));
+        assert!(Source::parse("--output=x..y").is_err_and(|e| e.contains("not")));
+        assert!(Source::parse("main").is_err_and(|e| e.contains("range")));
+    }
+}
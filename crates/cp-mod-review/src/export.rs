@@ -0,0 +1,110 @@
+//! Export of the accepted comments: a markdown report, or the JSON body of a
+//! GitHub pull request review (`POST /repos/{owner}/{repo}/pulls/{n}/reviews`).
+
+use std::fmt::Write as _;
+
+use serde_json::{Value, json};
+
+use crate::types::{Decision, ReviewComment, ReviewState};
+
+/// An accepted comment and where it attaches.
+struct Placed<'review> {
+    /// The comment.
+    comment: &'review ReviewComment,
+    /// File of its hunk.
+    path: &'review str,
+    /// Line on `side`.
+    line: usize,
+    /// `RIGHT` (new side) or `LEFT` (old side).
+    side: &'static str,
+}
+
+/// Accepted comments in diff order.
+fn accepted(rs: &ReviewState) -> Vec<Placed<'_>> {
+    let mut out: Vec<_> = rs
+        .comments
+        .iter()
+        .filter(|c| c.decision == Decision::Accepted)
+        .filter_map(|comment| {
+            let hunk = rs.hunks.get(comment.hunk)?;
+            let (line, side) = comment.line.map_or_else(|| hunk.anchor(), |l| (l, "RIGHT"));
+            Some(Placed { comment, path: hunk.file.as_str(), line, side })
+        })
+        .collect();
+    out.sort_by_key(|p| (p.comment.hunk, p.line));
+    out
+}
+
+/// The accepted comments as a markdown report, grouped by file.
+pub(crate) fn markdown(rs: &ReviewState) -> String {
+    let comments = accepted(rs);
+    let mut out = format!("# Review: {}\n\n{} comment(s)\n", rs.source, comments.len());
+    let mut file = "";
+    for p in comments {
+        if p.path != file {
+            file = p.path;
+            let _r = write!(out, "\n## `{file}`\n");
+        }
+        let old = if p.side == "LEFT" { " (removed)" } else { "" };
+        let (severity, body) = (p.comment.severity.label(), p.comment.body.trim());
+        let _r = write!(out, "\n**{severity}** \u{b7} line {}{old}\n\n{body}\n", p.line);
+    }
+    out
+}
+
+/// The accepted comments as a pull request review payload, for
+/// `gh api repos/{owner}/{repo}/pulls/{n}/reviews --input <file>`.
+pub(crate) fn gh_payload(rs: &ReviewState) -> Value {
+    let comments: Vec<Value> = accepted(rs)
+        .into_iter()
+        .map(|p| {
+            json!({
+                "path": p.path,
+                "line": p.line,
+                "side": p.side,
+                "body": format!("**{}**: {}", p.comment.severity.label(), p.comment.body.trim()),
+            })
+        })
+        .collect();
+    json!({
+        "event": "COMMENT",
+        "body": format!("Review of {}: {} comment(s)", rs.source, comments.len()),
+        "comments": comments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Hunk, Severity};
+
+    #[test]
+    fn only_accepted_comments_are_exported() {
+        let hunk = Hunk {
+            file: "src/a.rs".to_owned(),
+            header: "@@ -1,2 +1,2 @@".to_owned(),
+            old_start: 1,
+            new_start: 1,
+            lines: vec![" a".to_owned(), "-b".to_owned(), "+c".to_owned()],
+        };
+        let comment = |id: &str, decision| ReviewComment {
+            id: id.to_owned(),
+            hunk: 0,
+            line: None,
+            severity: Severity::Issue,
+            body: format!("fix {id}"),
+            decision,
+        };
+        let rs = ReviewState {
+            source: "PR #3".to_owned(),
+            hunks: vec![hunk],
+            comments: vec![comment("R1", Decision::Accepted), comment("R2", Decision::Rejected)],
+            ..ReviewState::default()
+        };
+        let payload = gh_payload(&rs);
+        let expected = json!([{"path": "src/a.rs", "line": 2usize, "side": "RIGHT", "body": "**issue**: fix R1"}]);
+        assert_eq!(payload.get("comments"), Some(&expected));
+        let report = markdown(&rs);
+        assert!(report.contains("## `src/a.rs`") && report.contains("fix R1") && !report.contains("fix R2"));
+    }
+}
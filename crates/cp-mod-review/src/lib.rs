@@ -0,0 +1,272 @@
+//! Review module — a code review over a diff, one hunk at a time.
+//!
+//! `review_load` takes the working tree, a revision range or a pull request
+//! and splits its diff into hunks; `review_comment` files a structured comment
+//! (severity, body, optional line) on the hunk under review and moves on, and
+//! `review_goto` jumps around. In the Review panel the user steps with `n`/`p`
+//! and accepts (`a`) or rejects (`r`) each hunk's comments; `review_export`
+//! writes the accepted ones as markdown or as a `gh` pull request review.
+
+/// Diff sources and the unified-diff parser.
+mod diff;
+/// Markdown and GitHub review renderings of the accepted comments.
+mod export;
+/// Panel rendering for the review.
+mod panel;
+/// Tool implementations for loading, commenting, moving and exporting.
+mod tools;
+/// Review state types: `Hunk`, `ReviewComment`, `ReviewState`.
+pub mod types;
+
+use types::{Decision, ReviewState};
+
+use serde_json::json;
+
+use cp_base::modules::ToolVisualizer;
+use cp_base::panels::Panel;
+use cp_base::state::actions::ReviewMove;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::panel::ReviewPanel;
+use cp_base::cast::Safe as _;
+use cp_base::modules::Module;
+
+/// Lazily-parsed tool descriptions loaded from the review YAML definition.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/review.yaml")));
+
+/// Apply a Review panel key; `false` when there is nothing to do.
+pub fn step(state: &mut State, step: ReviewMove) -> bool {
+    let rs = ReviewState::get_mut(state);
+    if rs.hunks.is_empty() {
+        return false;
+    }
+    let decision = match step {
+        ReviewMove::Next | ReviewMove::Prev => None,
+        ReviewMove::Accept => Some(Decision::Accepted),
+        ReviewMove::Reject => Some(Decision::Rejected),
+    };
+    let cursor = rs.cursor;
+    if let Some(d) = decision {
+        rs.comments.iter_mut().filter(|c| c.hunk == cursor).for_each(|c| c.decision = d);
+    }
+    rs.cursor = if step == ReviewMove::Prev {
+        cursor.saturating_sub(1)
+    } else {
+        cursor.saturating_add(1).min(rs.hunks.len().saturating_sub(1))
+    };
+    state.touch_panel(Kind::REVIEW);
+    true
+}
+
+/// Review module: diff loading, per-hunk comments and their export.
+#[derive(Debug, Clone, Copy)]
+pub struct ReviewModule;
+
+impl Default for ReviewModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReviewModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for ReviewModule {
+    fn id(&self) -> &'static str {
+        "review"
+    }
+    fn name(&self) -> &'static str {
+        "Review"
+    }
+    fn description(&self) -> &'static str {
+        "Hunk-by-hunk code review of a diff, exported as markdown or a gh review"
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(ReviewState::default());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(ReviewState::default());
+    }
+
+    fn save_module_data(&self, state: &State) -> serde_json::Value {
+        let rs = ReviewState::get(state);
+        json!({
+            "source": rs.source,
+            "pr": rs.pr,
+            "hunks": rs.hunks,
+            "cursor": rs.cursor,
+            "comments": rs.comments,
+            "next_id": rs.next_id,
+        })
+    }
+    fn load_module_data(&self, data: &serde_json::Value, state: &mut State) {
+        let rs = ReviewState::get_mut(state);
+        if let Some(v) = data.get("source").and_then(serde_json::Value::as_str) {
+            v.clone_into(&mut rs.source);
+        }
+        rs.pr = data.get("pr").and_then(serde_json::Value::as_u64);
+        if let Some(arr) = data.get("hunks")
+            && let Ok(v) = serde_json::from_value(arr.clone())
+        {
+            rs.hunks = v;
+        }
+        if let Some(arr) = data.get("comments")
+            && let Ok(v) = serde_json::from_value(arr.clone())
+        {
+            rs.comments = v;
+        }
+        if let Some(v) = data.get("cursor").and_then(serde_json::Value::as_u64) {
+            rs.cursor = v.to_usize().min(rs.hunks.len().saturating_sub(1));
+        }
+        if let Some(v) = data.get("next_id").and_then(serde_json::Value::as_u64) {
+            rs.next_id = v.to_usize();
+        }
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::REVIEW)]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![(Kind::new(Kind::REVIEW), "Review", false)]
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::REVIEW => Some(Box::new(ReviewPanel)),
+            _ => None,
+        }
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("review_load", t)
+                .short_desc("Load a diff to review")
+                .category("Review")
+                .param("source", ParamType::String, false)
+                .build(),
+            ToolDefinition::from_yaml("review_comment", t)
+                .short_desc("Comment on a hunk")
+                .category("Review")
+                .param_enum("severity", &["issue", "suggestion", "question", "nit", "praise"], true)
+                .param("body", ParamType::String, true)
+                .param("line", ParamType::Integer, false)
+                .param("hunk", ParamType::Integer, false)
+                .build(),
+            ToolDefinition::from_yaml("review_goto", t)
+                .short_desc("Show a hunk")
+                .category("Review")
+                .reverie_allowed(true)
+                .param("hunk", ParamType::Integer, true)
+                .build(),
+            ToolDefinition::from_yaml("review_export", t)
+                .short_desc("Export accepted comments")
+                .category("Review")
+                .param_enum("format", &["markdown", "gh"], false)
+                .param("path", ParamType::String, false)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        match tool.name.as_str() {
+            "review_load" => Some(tools::execute_load(tool, state)),
+            "review_comment" => Some(tools::execute_comment(tool, state)),
+            "review_goto" => Some(tools::execute_goto(tool, state)),
+            "review_export" => Some(tools::execute_export(tool, state)),
+            _ => None,
+        }
+    }
+
+    fn tool_visualizers(&self) -> Vec<(&'static str, ToolVisualizer)> {
+        vec![]
+    }
+
+    fn context_type_metadata(&self) -> Vec<cp_base::state::context::TypeMeta> {
+        vec![cp_base::state::context::TypeMeta {
+            context_type: Kind::REVIEW,
+            icon_id: "review",
+            is_fixed: true,
+            needs_cache: false,
+            fixed_order: Some(15),
+            display_name: "review",
+            short_name: "review",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Review", "Walk a diff hunk by hunk, comment on each, export the accepted comments")]
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &["github"]
+    }
+    fn is_core(&self) -> bool {
+        false
+    }
+    fn is_global(&self) -> bool {
+        false
+    }
+    fn save_worker_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+    fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+    fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
+        None
+    }
+    fn context_detail(&self, _ctx: &cp_base::state::context::Entry) -> Option<String> {
+        None
+    }
+    fn overview_context_section(&self, _state: &State) -> Option<String> {
+        None
+    }
+    fn overview_render_sections(&self, _state: &State) -> Vec<(u8, Vec<cp_render::Block>)> {
+        vec![]
+    }
+    fn on_close_context(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _state: &mut State,
+    ) -> Option<Result<String, String>> {
+        None
+    }
+    fn on_user_message(&self, _state: &mut State) {}
+    fn on_stream_stop(&self, _state: &mut State) {}
+
+    fn on_stream_chunk(&self, _text: &str, _state: &mut State) {}
+
+    fn on_tool_progress(&self, _tool_name: &str, _input_so_far: &str, _state: &mut State) {}
+
+    fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
+    fn watch_paths(&self, _state: &State) -> Vec<cp_base::panels::WatchSpec> {
+        vec![]
+    }
+    fn should_invalidate_on_fs_change(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _changed_path: &str,
+        _is_dir_event: bool,
+    ) -> bool {
+        false
+    }
+    fn watcher_immediate_refresh(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,199 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use cp_base::panels::{Panel, scroll_key_action};
+use cp_base::state::actions::{Action, ReviewMove};
+use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
+use cp_base::state::runtime::State;
+
+use crate::types::{Decision, ReviewComment, ReviewState};
+use std::fmt::Write as _;
+
+/// Panel that walks the loaded diff hunk by hunk, with the agent's comments.
+pub(crate) struct ReviewPanel;
+
+/// Marker of a comment's decision.
+const fn mark(decision: Decision) -> &'static str {
+    match decision {
+        Decision::Pending => "?",
+        Decision::Accepted => "\u{2713}",
+        Decision::Rejected => "\u{2717}",
+    }
+}
+
+impl ReviewPanel {
+    /// Format the review for LLM context: progress, the current hunk, every comment.
+    fn format_for_context(state: &State) -> String {
+        let rs = ReviewState::get(state);
+        let Some(hunk) = rs.current() else {
+            return "No review loaded".to_owned();
+        };
+        let mut output = format!(
+            "Review of {}: hunk {}/{}, {} comment(s)\n\nCurrent hunk:\n{}\n",
+            rs.source,
+            rs.cursor.saturating_add(1),
+            rs.hunks.len(),
+            rs.comments.len(),
+            hunk.text()
+        );
+        for c in &rs.comments {
+            let _r = write!(
+                output,
+                "\n[{}] {} hunk {} {}: {}",
+                c.id,
+                mark(c.decision),
+                c.hunk.saturating_add(1),
+                c.severity.label(),
+                c.body.lines().next().unwrap_or("")
+            );
+        }
+        output
+    }
+
+    /// One comment under the hunk.
+    fn comment_lines(c: &ReviewComment) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+        let semantic = match c.decision {
+            Decision::Pending => Semantic::Warning,
+            Decision::Accepted => Semantic::Success,
+            Decision::Rejected => Semantic::Muted,
+        };
+        let line = c.line.map(|l| format!(" line {l}")).unwrap_or_default();
+        let mut blocks = vec![Block::Line(vec![
+            S::styled(format!(" {} ", mark(c.decision)), semantic),
+            S::accent(c.id.clone()).bold(),
+            S::new(format!(" {}{line}", c.severity.label())).bold(),
+        ])];
+        blocks.extend(c.body.lines().map(|l| Block::Line(vec![S::new(format!("     {l}"))])));
+        blocks
+    }
+}
+
+impl Panel for ReviewPanel {
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        if let KeyCode::Char(c) = key.code
+            && key.modifiers.is_empty()
+        {
+            let step = match c {
+                'n' => ReviewMove::Next,
+                'p' => ReviewMove::Prev,
+                'a' => ReviewMove::Accept,
+                'r' => ReviewMove::Reject,
+                _ => return None,
+            };
+            return Some(Action::Review(step));
+        }
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<cp_render::Block> {
+        use cp_render::{Block, Semantic, Span as S};
+
+        let rs = ReviewState::get(state);
+        let Some(hunk) = rs.current() else {
+            return vec![
+                Block::Line(vec![S::muted("  No review loaded".into()).italic()]),
+                Block::Line(vec![S::muted("  Ask the agent to review the working tree, a range or a PR".into())]),
+            ];
+        };
+
+        let decided = rs.comments.iter().filter(|c| c.decision != Decision::Pending).count();
+        let mut blocks = vec![
+            Block::Line(vec![
+                S::new(format!(" {} ", rs.source)).bold(),
+                S::muted(format!(
+                    " hunk {}/{} \u{b7} {decided}/{} comment(s) decided",
+                    rs.cursor.saturating_add(1),
+                    rs.hunks.len(),
+                    rs.comments.len()
+                )),
+            ]),
+            Block::Empty,
+            Block::Line(vec![S::accent(format!(" {}", hunk.file)).bold()]),
+            Block::Line(vec![S::styled(format!(" {}", hunk.header), Semantic::Info)]),
+        ];
+        for line in &hunk.lines {
+            let semantic = match line.chars().next() {
+                Some('+') => Semantic::DiffAdd,
+                Some('-') => Semantic::DiffRemove,
+                Some(_) | None => Semantic::Default,
+            };
+            blocks.push(Block::Line(vec![S::styled(format!(" {line}"), semantic)]));
+        }
+        blocks.push(Block::Empty);
+        let comments: Vec<&ReviewComment> = rs.comments_on(rs.cursor).collect();
+        if comments.is_empty() {
+            blocks.push(Block::Line(vec![S::muted("  No comments on this hunk".into()).italic()]));
+        }
+        for c in comments {
+            blocks.extend(Self::comment_lines(c));
+        }
+        blocks.push(Block::Empty);
+        blocks.push(Block::Line(vec![
+            S::styled(" n p ".into(), Semantic::KeyHint),
+            S::muted("hunk  ".into()),
+            S::styled("a r ".into(), Semantic::KeyHint),
+            S::muted("accept / reject the comments".into()),
+        ]));
+        blocks
+    }
+
+    fn title(&self, state: &State) -> String {
+        let rs = ReviewState::get(state);
+        let pending = rs.comments.iter().filter(|c| c.decision == Decision::Pending).count();
+        match (rs.hunks.len(), pending) {
+            (0, _) => "Review".to_owned(),
+            (_, 0) => format!("Review ({})", rs.source),
+            (_, n) => format!("Review ({}, {n} to decide)", rs.source),
+        }
+    }
+
+    fn refresh(&self, state: &mut State) {
+        let content = Self::format_for_context(state);
+        let token_count = estimate_tokens(&content);
+
+        if let Some(ctx) = state.context.iter_mut().find(|c| c.context_type.as_str() == Kind::REVIEW) {
+            ctx.token_count = token_count;
+            let _changed = cp_base::panels::update_if_changed(ctx, &content);
+        }
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let Some(ctx) = state.context.iter().find(|c| c.context_type.as_str() == Kind::REVIEW) else {
+            return Vec::new();
+        };
+        vec![ContextItem::new(&ctx.id, "Review", Self::format_for_context(state), ctx.last_refresh_ms)]
+    }
+
+    fn needs_cache(&self) -> bool {
+        false
+    }
+    fn refresh_cache(&self, _request: cp_base::panels::CacheRequest) -> Option<cp_base::panels::CacheUpdate> {
+        None
+    }
+    fn build_cache_request(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _state: &State,
+    ) -> Option<cp_base::panels::CacheRequest> {
+        None
+    }
+    fn apply_cache_update(
+        &self,
+        _update: cp_base::panels::CacheUpdate,
+        _ctx: &mut cp_base::state::context::Entry,
+        _state: &mut State,
+    ) -> bool {
+        false
+    }
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        None
+    }
+    fn suicide(&self, _ctx: &cp_base::state::context::Entry, _state: &State) -> bool {
+        false
+    }
+}
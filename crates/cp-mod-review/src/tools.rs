@@ -0,0 +1,158 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use cp_base::config::constants::{MAX_RESULT_CONTENT_BYTES, STORE_DIR};
+use cp_base::modules::truncate_output;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+use cp_mod_github::types::GithubState;
+
+use crate::diff::{self, Source};
+use crate::export;
+use crate::types::{Decision, ReviewComment, ReviewState, Severity};
+
+/// Wrap a tool body's outcome into a result.
+fn respond(tool: &ToolUse, outcome: Result<String, String>) -> ToolResult {
+    match outcome {
+        Ok(message) => ToolResult::new(tool.id.clone(), truncate_output(&message, MAX_RESULT_CONTENT_BYTES), false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    }
+}
+
+/// The hunk under review with its number and any comments on it.
+fn show_current(rs: &ReviewState) -> String {
+    let Some(hunk) = rs.current() else {
+        return "No diff loaded".to_owned();
+    };
+    let mut out = format!("Hunk {}/{}: {}", rs.cursor.saturating_add(1), rs.hunks.len(), hunk.text());
+    for c in rs.comments_on(rs.cursor) {
+        let _r = write!(out, "\n[{}] {}: {}", c.id, c.severity.label(), c.body);
+    }
+    out
+}
+
+/// 1-based `hunk` parameter as an index, defaulting to the hunk under review.
+fn hunk_param(tool: &ToolUse, rs: &ReviewState) -> Result<usize, String> {
+    let Some(n) = tool.input.get("hunk").and_then(serde_json::Value::as_u64) else {
+        return Ok(rs.cursor);
+    };
+    let index = usize::try_from(n).unwrap_or(usize::MAX).saturating_sub(1);
+    if index < rs.hunks.len() { Ok(index) } else { Err(format!("no hunk {n}, the diff has {}", rs.hunks.len())) }
+}
+
+/// Load a diff (working tree, range, or PR) and show its first hunk.
+pub(crate) fn execute_load(tool: &ToolUse, state: &mut State) -> ToolResult {
+    respond(tool, load(tool, state))
+}
+
+/// Fetch and split the diff, replacing any review in progress.
+fn load(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let arg = tool.input.get("source").and_then(|v| v.as_str()).unwrap_or("");
+    let source = Source::parse(arg)?;
+    let token = state.get_ext::<GithubState>().and_then(|gs| gs.github_token.clone());
+    let hunks = diff::parse(&diff::fetch(&source, token.as_deref())?);
+    if hunks.is_empty() {
+        return Err(format!("the {} diff is empty, nothing to review", source.label()));
+    }
+    let mut files: Vec<&str> = hunks.iter().map(|h| h.file.as_str()).collect();
+    files.dedup();
+    let summary = format!("Loaded {} hunk(s) in {} file(s) from {}.", hunks.len(), files.len(), source.label());
+    let pr = if let Source::PullRequest(n) = source { Some(n) } else { None };
+    *ReviewState::get_mut(state) = ReviewState { source: source.label(), pr, hunks, ..ReviewState::default() };
+    state.touch_panel(Kind::REVIEW);
+    Ok(format!("{summary}\n\n{}", show_current(ReviewState::get(state))))
+}
+
+/// Comment on a hunk; commenting on the hunk under review moves to the next.
+pub(crate) fn execute_comment(tool: &ToolUse, state: &mut State) -> ToolResult {
+    respond(tool, comment(tool, state))
+}
+
+/// Validate and store the comment, then show what comes next.
+fn comment(tool: &ToolUse, state: &mut State) -> Result<String, String> {
+    let rs = ReviewState::get_mut(state);
+    let index = hunk_param(tool, rs)?;
+    let severity_arg = tool.input.get("severity").and_then(|v| v.as_str()).unwrap_or("");
+    let labels: Vec<&str> = Severity::ALL.iter().map(|s| s.label()).collect();
+    let severity = Severity::parse(severity_arg)
+        .ok_or_else(|| format!("severity '{severity_arg}' is not one of {}", labels.join(", ")))?;
+    let body = tool.input.get("body").and_then(|v| v.as_str()).unwrap_or("").trim().to_owned();
+    if body.is_empty() {
+        return Err("the comment body is empty".to_owned());
+    }
+    let line = tool.input.get("line").and_then(serde_json::Value::as_u64).and_then(|l| usize::try_from(l).ok());
+    if let Some(l) = line
+        && !rs.hunks.get(index).is_some_and(|h| h.new_lines().contains(&l))
+    {
+        return Err(format!("line {l} is not on the new side of hunk {}", index.saturating_add(1)));
+    }
+    rs.next_id = rs.next_id.saturating_add(1);
+    let id = format!("R{}", rs.next_id);
+    rs.comments.push(ReviewComment { id: id.clone(), hunk: index, line, severity, body, decision: Decision::Pending });
+    let last = rs.cursor.saturating_add(1) >= rs.hunks.len();
+    let next = if index != rs.cursor {
+        format!("Still on hunk {}/{}.", rs.cursor.saturating_add(1), rs.hunks.len())
+    } else if !last {
+        rs.cursor = rs.cursor.saturating_add(1);
+        format!("Next: {}", show_current(rs))
+    } else {
+        "That was the last hunk. The user accepts or rejects comments in the Review panel; then review_export."
+            .to_owned()
+    };
+    state.touch_panel(Kind::REVIEW);
+    Ok(format!("{id} added on hunk {}.\n\n{next}", index.saturating_add(1)))
+}
+
+/// Move the review to a hunk and show it.
+pub(crate) fn execute_goto(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let rs = ReviewState::get_mut(state);
+    let outcome = hunk_param(tool, rs).map(|index| {
+        rs.cursor = index;
+        show_current(rs)
+    });
+    state.touch_panel(Kind::REVIEW);
+    respond(tool, outcome)
+}
+
+/// Write the accepted comments as markdown or a GitHub review payload.
+pub(crate) fn execute_export(tool: &ToolUse, state: &State) -> ToolResult {
+    respond(tool, export_to_file(tool, ReviewState::get(state)))
+}
+
+/// Render the export and write it where asked (or under the store dir).
+fn export_to_file(tool: &ToolUse, rs: &ReviewState) -> Result<String, String> {
+    let accepted = rs.comments.iter().filter(|c| c.decision == Decision::Accepted).count();
+    if accepted == 0 {
+        return Err("no accepted comments yet; the user accepts them in the Review panel".to_owned());
+    }
+    let gh = match tool.input.get("format").and_then(|v| v.as_str()).unwrap_or("markdown") {
+        "markdown" | "md" => false,
+        "gh" | "github" => true,
+        other => return Err(format!("format '{other}' is not 'markdown' or 'gh'")),
+    };
+    let (default_name, content) = if gh {
+        ("review.json", serde_json::to_string_pretty(&export::gh_payload(rs)).unwrap_or_default())
+    } else {
+        ("review.md", export::markdown(rs))
+    };
+    let path = tool
+        .input
+        .get("path")
+        .and_then(|v| v.as_str())
+        .map_or_else(|| Path::new(STORE_DIR).join("reviews").join(default_name), |p| Path::new(p).to_path_buf());
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+    }
+    std::fs::write(&path, content).map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+    let mut message = format!("Exported {accepted} accepted comment(s) to {}", path.display());
+    if gh {
+        let pr = rs.pr.map_or_else(|| "<number>".to_owned(), |n| n.to_string());
+        let _r = write!(
+            message,
+            "\nPost it with: gh api repos/{{owner}}/{{repo}}/pulls/{pr}/reviews --method POST --input {}",
+            path.display()
+        );
+    }
+    Ok(message)
+}
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use cp_base::state::runtime::State;
+
+/// One `@@` hunk of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hunk {
+    /// File path on the new side (the old one for deletions).
+    pub file: String,
+    /// The `@@ -a,b +c,d @@ ...` line.
+    pub header: String,
+    /// First line on the old side.
+    pub old_start: usize,
+    /// First line on the new side.
+    pub new_start: usize,
+    /// Body lines, each with its ` `/`+`/`-` prefix.
+    pub lines: Vec<String>,
+}
+
+impl Hunk {
+    /// New-side line numbers of the context and added lines.
+    #[must_use]
+    pub fn new_lines(&self) -> Vec<usize> {
+        let mut line = self.new_start;
+        let mut out = Vec::new();
+        for text in &self.lines {
+            if text.starts_with(' ') || text.starts_with('+') {
+                out.push(line);
+                line = line.saturating_add(1);
+            }
+        }
+        out
+    }
+
+    /// Where a review comment on the whole hunk attaches, as GitHub wants it:
+    /// the last added line (`RIGHT`), else the last removed one (`LEFT`).
+    #[must_use]
+    pub fn anchor(&self) -> (usize, &'static str) {
+        let (mut old, mut new) = (self.old_start, self.new_start);
+        let (mut added, mut removed) = (None, None);
+        for text in &self.lines {
+            match text.chars().next() {
+                Some('+') => {
+                    added = Some(new);
+                    new = new.saturating_add(1);
+                }
+                Some('-') => {
+                    removed = Some(old);
+                    old = old.saturating_add(1);
+                }
+                Some(' ') => {
+                    old = old.saturating_add(1);
+                    new = new.saturating_add(1);
+                }
+                Some(_) | None => {}
+            }
+        }
+        added.map(|n| (n, "RIGHT")).or_else(|| removed.map(|n| (n, "LEFT"))).unwrap_or((self.new_start, "RIGHT"))
+    }
+
+    /// Header and body, as shown to the agent.
+    #[must_use]
+    pub fn text(&self) -> String {
+        let mut out = format!("{}\n{}", self.file, self.header);
+        for line in &self.lines {
+            out.push('\n');
+            out.push_str(line);
+        }
+        out
+    }
+}
+
+/// How much a comment matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Must change before merging.
+    Issue,
+    /// Would be better another way.
+    Suggestion,
+    /// The reviewer needs an answer.
+    Question,
+    /// Cosmetic.
+    Nit,
+    /// Worth keeping as is.
+    Praise,
+}
+
+impl Severity {
+    /// Every severity, most serious first.
+    pub const ALL: [Self; 5] = [Self::Issue, Self::Suggestion, Self::Question, Self::Nit, Self::Praise];
+
+    /// Lowercase name, as the tools take it.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Issue => "issue",
+            Self::Suggestion => "suggestion",
+            Self::Question => "question",
+            Self::Nit => "nit",
+            Self::Praise => "praise",
+        }
+    }
+
+    /// Parse a tool argument.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.label().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// The user's call on a comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    /// Not looked at yet.
+    #[default]
+    Pending,
+    /// Goes into the export.
+    Accepted,
+    /// Dropped.
+    Rejected,
+}
+
+/// The agent's comment on one hunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewComment {
+    /// Comment ID (R1, R2, ...).
+    pub id: String,
+    /// Index of the hunk in [`ReviewState::hunks`].
+    pub hunk: usize,
+    /// New-side line it is about; the hunk's anchor when `None`.
+    pub line: Option<usize>,
+    /// How much it matters.
+    pub severity: Severity,
+    /// The comment text (markdown).
+    pub body: String,
+    /// Accepted, rejected, or not decided yet.
+    #[serde(default)]
+    pub decision: Decision,
+}
+
+/// Module-owned state for the Review module: the loaded diff and its comments.
+#[derive(Debug, Default)]
+pub struct ReviewState {
+    /// What was loaded, e.g. `working tree`, `main..HEAD`, `PR #42`.
+    pub source: String,
+    /// Pull request number when the diff came from one.
+    pub pr: Option<u64>,
+    /// Hunks in diff order.
+    pub hunks: Vec<Hunk>,
+    /// Index of the hunk under review.
+    pub cursor: usize,
+    /// Comments, ordered by creation.
+    pub comments: Vec<ReviewComment>,
+    /// Counter for generating unique IDs (R1, R2, ...).
+    pub next_id: usize,
+}
+
+impl ReviewState {
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+
+    /// The hunk under review.
+    #[must_use]
+    pub fn current(&self) -> Option<&Hunk> {
+        self.hunks.get(self.cursor)
+    }
+
+    /// Comments on hunk `index`.
+    pub fn comments_on(&self, index: usize) -> impl Iterator<Item = &ReviewComment> {
+        self.comments.iter().filter(move |c| c.hunk == index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(lines: &[&str]) -> Hunk {
+        Hunk {
+            file: "a.rs".to_owned(),
+            header: "@@ -10,3 +20,3 @@".to_owned(),
+            old_start: 10,
+            new_start: 20,
+            lines: lines.iter().map(|l| (*l).to_owned()).collect(),
+        }
+    }
+
+    #[test]
+    fn comments_anchor_to_the_last_change() {
+        assert_eq!(hunk(&[" a", "-b", "+c", "+d", " e"]).anchor(), (22, "RIGHT"));
+        assert_eq!(hunk(&[" a", "-b", "-c", " d"]).anchor(), (12, "LEFT"));
+        assert_eq!(hunk(&[" a", "+b", " c"]).new_lines(), vec![20, 21, 22]);
+        assert_eq!(Severity::parse(" Nit"), Some(Severity::Nit));
+    }
+}
@@ -287,11 +287,7 @@ fn visualize_scratchpad_output(content: &str, width: usize) -> Vec<cp_render::Bl
                 return Block::empty();
             }
             let semantic = scratchpad_line_semantic(line);
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
             Block::Line(vec![Span::styled(display, semantic)])
         })
         .collect()
@@ -1,8 +1,9 @@
 use crossterm::event::KeyEvent;
 
-use cp_base::panels::{ContextItem, Panel, scroll_key_action};
+use cp_base::panels::{Panel, scroll_key_action};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
 use crate::types::ScratchpadState;
@@ -30,11 +30,7 @@ pub(crate) fn execute_create(tool: &ToolUse, state: &mut State) -> ToolResult {
     // Update Scratchpad panel timestamp
     state.touch_panel(Kind::SCRATCHPAD);
 
-    let preview = if contents.len() > 50 {
-        format!("{}...", contents.get(..contents.floor_char_boundary(47)).unwrap_or(""))
-    } else {
-        contents
-    };
+    let preview = cp_base::ui::text::ellipsize(&contents, 50, "...");
 
     ToolResult::new(tool.id.clone(), format!("Created cell {id} '{title}': {preview}"), false)
 }
@@ -0,0 +1,130 @@
+//! What a script sees and what it may ask for.
+//!
+//! Scripts read a snapshot of the session taken when the hook fires:
+//!
+//! - `panels()`: `[#{id, name, type}]`, in sidebar order
+//! - `messages()`: the last messages, oldest first, as
+//!   `#{role, content, tools: [#{id, name, input}], results: [#{tool_use_id, content, is_error}]}`
+//! - `active_modules()`: module ids
+//!
+//! and queue effects, applied in order once the hook returns:
+//!
+//! - `tool(name)` / `tool(name, #{..params})`: run a tool, as the model would
+//! - `notify(text)`: raise a spine notification the agent sees
+//!
+//! `print` and `debug` go to the log.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map};
+
+use cp_base::state::runtime::State;
+
+/// Messages in the snapshot, counted from the end.
+const SNAPSHOT_MESSAGES: usize = 50;
+
+/// Tool calls one hook may queue.
+const MAX_TOOL_CALLS: usize = 16;
+
+/// Something a script asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Effect {
+    /// Run a tool.
+    Tool {
+        /// Tool id.
+        name: String,
+        /// Tool parameters.
+        input: serde_json::Value,
+    },
+    /// Raise a notification.
+    Notify(String),
+}
+
+/// The session as scripts see it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Snapshot {
+    /// `panels()`.
+    panels: Array,
+    /// `messages()`.
+    messages: Array,
+    /// `active_modules()`.
+    modules: Array,
+}
+
+impl Snapshot {
+    /// Capture `state`.
+    pub(crate) fn of(state: &State) -> Self {
+        let panels = state
+            .context
+            .iter()
+            .map(|c| serde_json::json!({ "id": c.id, "name": c.name, "type": c.context_type.as_str() }))
+            .collect();
+        let messages = state
+            .messages
+            .iter()
+            .skip(state.messages.len().saturating_sub(SNAPSHOT_MESSAGES))
+            .map(|m| {
+                serde_json::json!({
+                    "role": m.role,
+                    "content": m.content,
+                    "tools": m.tool_uses.iter()
+                        .map(|t| serde_json::json!({ "id": t.id, "name": t.name, "input": t.input }))
+                        .collect::<Vec<_>>(),
+                    "results": m.tool_results.iter()
+                        .map(|r| serde_json::json!({
+                            "tool_use_id": r.tool_use_id, "content": r.content, "is_error": r.is_error,
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        let mut modules: Vec<&String> = state.active_modules.iter().collect();
+        modules.sort();
+        Self::from_json(panels, messages, &modules)
+    }
+
+    /// Build from JSON values, dropping anything Rhai cannot represent.
+    pub(crate) fn from_json(
+        panels: Vec<serde_json::Value>,
+        messages: Vec<serde_json::Value>,
+        modules: &[&String],
+    ) -> Self {
+        let convert = |values: Vec<serde_json::Value>| -> Array {
+            values.iter().filter_map(|v| rhai::serde::to_dynamic(v).ok()).collect()
+        };
+        Self {
+            panels: convert(panels),
+            messages: convert(messages),
+            modules: modules.iter().map(|m| Dynamic::from((*m).clone())).collect(),
+        }
+    }
+}
+
+/// Effects queued by the running script.
+pub(crate) type Queue = Rc<RefCell<Vec<Effect>>>;
+
+/// Queue a tool call, refusing past [`MAX_TOOL_CALLS`].
+fn queue_tool(queue: &Queue, name: &str, params: Map) -> Result<(), Box<EvalAltResult>> {
+    let input = rhai::serde::from_dynamic::<serde_json::Value>(&Dynamic::from_map(params))?;
+    let mut effects = queue.borrow_mut();
+    if effects.iter().filter(|e| matches!(e, Effect::Tool { .. })).count() >= MAX_TOOL_CALLS {
+        return Err(format!("at most {MAX_TOOL_CALLS} tool calls per hook").into());
+    }
+    effects.push(Effect::Tool { name: name.to_owned(), input });
+    Ok(())
+}
+
+/// Register the script API on `engine`, reading `snapshot` and writing `queue`.
+pub(crate) fn register(engine: &mut Engine, snapshot: &Rc<Snapshot>, queue: &Queue) {
+    let (panels, messages, modules) = (Rc::clone(snapshot), Rc::clone(snapshot), Rc::clone(snapshot));
+    let _engine = engine
+        .register_fn("panels", move || panels.panels.clone())
+        .register_fn("messages", move || messages.messages.clone())
+        .register_fn("active_modules", move || modules.modules.clone());
+    let (plain, with_params, notify) = (Rc::clone(queue), Rc::clone(queue), Rc::clone(queue));
+    let _registered = engine
+        .register_fn("tool", move |name: &str| queue_tool(&plain, name, Map::new()))
+        .register_fn("tool", move |name: &str, params: Map| queue_tool(&with_params, name, params))
+        .register_fn("notify", move |text: &str| notify.borrow_mut().push(Effect::Notify(text.to_owned())));
+}
@@ -0,0 +1,131 @@
+//! Scripts module — user automation without recompiling.
+//!
+//! Every `.context-pilot/scripts/*.rhai` file may define hook functions:
+//!
+//! - `on_user_message()`: the user submitted a message
+//! - `on_stream_end()`: a stream completed normally
+//! - `on_stream_stop()`: the user stopped a stream
+//! - `on_tool_call(name)`: the model called the tool `name`
+//!
+//! Hooks read the session and queue tool calls and notifications through the
+//! API in [`api`]; the queued effects are applied once the hook returns.
+//! Scripts run only while this module is active.
+
+/// What scripts see and may ask for.
+mod api;
+/// Script discovery and hook dispatch.
+mod runner;
+
+use std::path::Path;
+
+use rhai::Dynamic;
+
+use cp_base::config::constants::STORE_DIR;
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolDefinition, ToolResult, ToolUse};
+use cp_mod_spine::types::{NotificationType, SpineState};
+
+use self::api::{Effect, Snapshot};
+
+/// Directory under the store holding the scripts.
+const SCRIPTS_DIR: &str = "scripts";
+
+/// Runs a tool call on behalf of a script, as if the model had made it.
+pub type ToolRunner = fn(&ToolUse, &mut State) -> ToolResult;
+
+/// Scripts module: fires lifecycle hooks in user scripts.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptsModule {
+    /// Dispatches the tool calls scripts queue.
+    run: ToolRunner,
+}
+
+impl ScriptsModule {
+    /// Create the module; `run` executes the tool calls scripts ask for.
+    #[must_use]
+    pub const fn new(run: ToolRunner) -> Self {
+        Self { run }
+    }
+
+    /// Fire `hook` if the module is active, then apply what the scripts queued.
+    fn fire(self, state: &mut State, hook: &str, args: &[Dynamic]) {
+        if !state.active_modules.contains(self.id()) {
+            return;
+        }
+        let dir = Path::new(STORE_DIR).join(SCRIPTS_DIR);
+        for (n, (script, effect)) in runner::fire(&dir, hook, args, || Snapshot::of(state)).into_iter().enumerate() {
+            match effect {
+                Effect::Tool { name, input } => {
+                    let tool = ToolUse::new(format!("script-{script}-{n}"), name, input);
+                    let result = (self.run)(&tool, state);
+                    if result.is_error {
+                        log::warn!(target: "scripts", "{script}: {} failed: {}", tool.name, result.content);
+                    } else {
+                        log::info!(target: "scripts", "{script}: ran {}", tool.name);
+                    }
+                }
+                Effect::Notify(text) => {
+                    drop(SpineState::create_notification(
+                        state,
+                        NotificationType::Custom,
+                        format!("script {script}"),
+                        text,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl Module for ScriptsModule {
+    fn id(&self) -> &'static str {
+        "scripts"
+    }
+
+    fn name(&self) -> &'static str {
+        "Scripts"
+    }
+
+    fn description(&self) -> &'static str {
+        "Automation hooks from Rhai scripts in .context-pilot/scripts"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![]
+    }
+
+    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        None
+    }
+
+    fn create_panel(&self, _context_type: &Kind) -> Option<Box<dyn Panel>> {
+        None
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn on_user_message(&self, state: &mut State) {
+        self.fire(state, "on_user_message", &[]);
+    }
+
+    fn on_stream_stop(&self, state: &mut State) {
+        self.fire(state, "on_stream_stop", &[]);
+    }
+
+    fn on_stream_done(&self, state: &mut State) {
+        self.fire(state, "on_stream_end", &[]);
+    }
+
+    fn on_tool_complete(&self, tool_name: &str, state: &mut State) {
+        self.fire(state, "on_tool_call", &[Dynamic::from(tool_name.to_owned())]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Script discovery and hook dispatch.
+//!
+//! Every `*.rhai` file in the scripts directory is compiled each time a hook
+//! fires, so edits apply without a restart. A script handles a hook by
+//! defining a function of the same name; top-level statements never run.
+//! Scripts run in file-name order, each under operation and size limits; one
+//! that fails is logged and its queued effects dropped.
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use rhai::module_resolvers::FileModuleResolver;
+use rhai::{CallFnOptions, Dynamic, Engine, Scope};
+
+use crate::api::{self, Effect, Queue, Snapshot};
+
+/// Script file extension.
+const EXTENSION: &str = "rhai";
+
+/// Operations one hook call may run before it is aborted.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Deepest function-call nesting.
+const MAX_CALL_LEVELS: usize = 32;
+
+/// Deepest expression nesting, at top level and inside functions.
+const MAX_EXPR_DEPTHS: (usize, usize) = (64, 64);
+
+/// Largest string, array or map a script may build.
+const MAX_SIZE: usize = 1 << 20;
+
+/// The `*.rhai` files in `dir`, by name.
+pub(crate) fn scripts(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return vec![] };
+    let mut found: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == EXTENSION))
+        .collect();
+    found.sort();
+    found
+}
+
+/// An engine with limits and the script API; `import` resolves inside `dir`.
+fn engine(dir: &Path, snapshot: &Rc<Snapshot>, queue: &Queue) -> Engine {
+    let mut engine = Engine::new();
+    let _engine = engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTHS.0, MAX_EXPR_DEPTHS.1)
+        .set_max_string_size(MAX_SIZE)
+        .set_max_array_size(MAX_SIZE)
+        .set_max_map_size(MAX_SIZE)
+        .set_module_resolver(FileModuleResolver::new_with_path(dir))
+        .disable_symbol("eval")
+        .on_print(|text| log::info!(target: "scripts", "{text}"))
+        .on_debug(|text, source, pos| log::debug!(target: "scripts", "{} {pos}: {text}", source.unwrap_or("")));
+    api::register(&mut engine, snapshot, queue);
+    engine
+}
+
+/// Call `hook` in the script at `path`, if it defines it.
+fn run_one(engine: &Engine, path: &Path, hook: &str, args: &[Dynamic]) -> Result<(), String> {
+    let ast = engine.compile_file(path.to_path_buf()).map_err(|e| e.to_string())?;
+    if !ast.iter_functions().any(|f| f.name == hook && f.params.len() == args.len()) {
+        return Ok(());
+    }
+    let options = CallFnOptions::new().eval_ast(false);
+    engine
+        .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &ast, hook, args.to_vec())
+        .map(|_value| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Fire `hook` in every script under `dir`. Returns each effect with the file
+/// name of the script that queued it. `snapshot` is only taken when there is a
+/// script to run.
+pub(crate) fn fire<F>(dir: &Path, hook: &str, args: &[Dynamic], snapshot: F) -> Vec<(String, Effect)>
+where
+    F: FnOnce() -> Snapshot,
+{
+    let paths = scripts(dir);
+    if paths.is_empty() {
+        return vec![];
+    }
+    let queue = Queue::default();
+    let engine = engine(dir, &Rc::new(snapshot()), &queue);
+    let mut fired = Vec::new();
+    for path in paths {
+        let name = path.file_name().map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        let outcome = run_one(&engine, &path, hook, args);
+        let queued = std::mem::take(&mut *queue.borrow_mut());
+        match outcome {
+            Ok(()) => fired.extend(queued.into_iter().map(|effect| (name.clone(), effect))),
+            Err(e) => log::warn!(target: "scripts", "{name}: {hook} failed: {e}"),
+        }
+    }
+    fired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A directory holding `scripts` as `(file name, source)`.
+    fn dir_with(scripts: &[(&str, &str)]) -> Option<tempfile::TempDir> {
+        let dir = tempfile::tempdir().ok()?;
+        for &(name, source) in scripts {
+            std::fs::write(dir.path().join(name), source).ok()?;
+        }
+        Some(dir)
+    }
+
+    /// A snapshot with one failed `cargo test` result.
+    fn failed_tests() -> Snapshot {
+        let message = serde_json::json!({
+            "role": "user", "content": "",
+            "tools": [], "results": [{ "tool_use_id": "T1", "content": "FAILED src/lib.rs", "is_error": true }],
+        });
+        Snapshot::from_json(vec![], vec![message], &[])
+    }
+
+    const OPEN_FAILURES: &str = r#"
+        fn on_stream_end() {
+            for m in messages() {
+                for r in m.results {
+                    if r.is_error { tool("Open", #{ path: r.content.split(" ")[1] }); }
+                }
+            }
+        }
+        tool("never_runs");
+    "#;
+
+    #[test]
+    fn hooks_queue_effects_from_the_snapshot() {
+        let Some(dir) = dir_with(&[("a_open.rhai", OPEN_FAILURES), ("b_other.rhai", "fn on_user_message() {}")]) else {
+            return;
+        };
+        let fired = fire(dir.path(), "on_stream_end", &[], failed_tests);
+        let open = Effect::Tool { name: "Open".to_owned(), input: serde_json::json!({ "path": "src/lib.rs" }) };
+        assert_eq!(fired, [("a_open.rhai".to_owned(), open)]);
+        assert!(fire(dir.path(), "on_stream_stop", &[], failed_tests).is_empty());
+    }
+
+    #[test]
+    fn failing_and_runaway_scripts_queue_nothing() {
+        let scripts = [
+            ("broken.rhai", "fn on_stream_end() { notify(\"before\"); undefined_call(); }"),
+            ("spin.rhai", "fn on_stream_end() { notify(\"before\"); loop {} }"),
+            ("syntax.rhai", "fn on_stream_end( {"),
+            ("tools.rhai", "fn on_tool_call(name) { for i in 0..20 { tool(name); } }"),
+        ];
+        let Some(dir) = dir_with(&scripts) else { return };
+        assert!(fire(dir.path(), "on_stream_end", &[], Snapshot::default).is_empty());
+        assert!(fire(dir.path(), "on_tool_call", &[Dynamic::from("x")], Snapshot::default).is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+//! `impact_of` — the blast radius of a symbol before a refactor.
+//!
+//! Every indexable project file is scanned for the symbol as a whole word;
+//! in languages with a tree-sitter grammar each hit is placed in the syntax
+//! tree, so definitions, call sites, test code and plain references are told
+//! apart and mentions in comments or strings are dropped. The result is a
+//! YAML report in a search result panel.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tree_sitter::{Node, Parser};
+
+use cp_base::state::runtime::State;
+use cp_base::state::watchers::DYN_PANEL_ID_PLACEHOLDER;
+use cp_base::state::watchers::carriers::DynPanel;
+use cp_base::tools::async_exec::{ToolOutput, spawn_async_tool};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::splitter::tree_sitter::{extract_name, language_for_ext};
+
+/// Max seconds for the scan.
+const IMPACT_TIMEOUT_SECS: u64 = 60;
+
+/// References listed before the report is cut.
+const MAX_REFERENCES: usize = 300;
+
+/// What a reference to the symbol is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// Where the symbol is declared.
+    Definition,
+    /// The symbol is called (function, method or macro position).
+    Call,
+    /// Any use inside test code.
+    Test,
+    /// Any other use: imports, types, arguments, paths.
+    Reference,
+}
+
+/// One hit of the symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reference {
+    /// Project-relative file.
+    path: String,
+    /// 1-based line.
+    line: usize,
+    /// What kind of use it is.
+    role: Role,
+    /// Innermost named function, method or type around it.
+    within: String,
+    /// The line's code, trimmed.
+    code: String,
+}
+
+/// What `impact_of` was asked about.
+#[derive(Debug, PartialEq, Eq)]
+struct Target<'input> {
+    /// The argument as given.
+    raw: &'input str,
+    /// File expected to hold the definition.
+    path: Option<&'input str>,
+    /// Name searched for.
+    symbol: &'input str,
+}
+
+/// Split `path:symbol` (or a bare symbol); `a::b::c` and `a.b` keep the last part.
+fn parse_target(raw: &str) -> Result<Target<'_>, String> {
+    let (path, qualified) = match raw.trim().split_once(':') {
+        Some((p, s)) if !p.is_empty() && !s.starts_with(':') => (Some(p), s),
+        Some(_) | None => (None, raw.trim()),
+    };
+    let symbol = qualified.rsplit("::").next().unwrap_or(qualified).rsplit('.').next().unwrap_or(qualified).trim();
+    if symbol.is_empty() || !symbol.chars().all(is_ident_char) {
+        return Err(format!("'{raw}' is not path:symbol or a symbol name"));
+    }
+    Ok(Target { raw, path, symbol })
+}
+
+/// Identifier character in the languages scanned.
+const fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Byte offsets of `symbol` as a whole word in `content`.
+fn word_hits(content: &str, symbol: &str) -> Vec<usize> {
+    content
+        .match_indices(symbol)
+        .map(|m| m.0)
+        .filter(|&at| {
+            let before = content.get(..at).and_then(|s| s.chars().next_back());
+            let after = content.get(at.saturating_add(symbol.len())..).and_then(|s| s.chars().next());
+            !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+        })
+        .collect()
+}
+
+/// Whether the path alone says test code.
+fn is_test_path(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    path.split('/').any(|part| part == "tests" || part == "test" || part == "__tests__")
+        || name.starts_with("test_")
+        || ["_test.", ".test.", ".spec.", "_tests."].iter().any(|marker| name.contains(marker))
+}
+
+/// Whether `node` is a comment, or text inside a string literal.
+fn is_prose(node: Node<'_>) -> bool {
+    let quoted = |n: Node<'_>| n.kind().contains("comment") || n.kind().contains("string");
+    quoted(node) || node.parent().is_some_and(quoted)
+}
+
+/// Whether `node` is the name a declaration introduces.
+fn is_definition(node: Node<'_>) -> bool {
+    let Some(parent) = node.parent() else { return false };
+    let kind = parent.kind();
+    let named = parent.child_by_field_name("name").is_some_and(|n| n.id() == node.id())
+        || (kind == "function_declarator"
+            && parent.child_by_field_name("declarator").is_some_and(|n| n.id() == node.id()));
+    named && !kind.contains("call") && !kind.contains("invocation")
+}
+
+/// Whether `node` sits in the callee position of a nearby call.
+fn is_call(node: Node<'_>) -> bool {
+    let mut current = node.parent();
+    for _ in 0..3u8 {
+        let Some(ancestor) = current else { return false };
+        let kind = ancestor.kind();
+        if kind.contains("call") || kind.contains("invocation") {
+            let callee = ["function", "name", "macro", "method"].iter().find_map(|f| ancestor.child_by_field_name(f));
+            return callee.is_some_and(|c| c.start_byte() <= node.start_byte() && node.end_byte() <= c.end_byte());
+        }
+        current = ancestor.parent();
+    }
+    false
+}
+
+/// Whether a function or module node is test code by name or attribute.
+fn is_test_scope(node: Node<'_>, name: &str, source: &[u8]) -> bool {
+    let by_name = name.starts_with("test") || name.starts_with("Test") || name == "tests";
+    let attribute = node
+        .prev_named_sibling()
+        .filter(|s| s.kind() == "attribute_item")
+        .and_then(|s| source.get(s.byte_range()))
+        .is_some_and(|text| String::from_utf8_lossy(text).contains("test"));
+    by_name || attribute
+}
+
+/// Innermost named scope around `node`, and whether any scope is test code.
+fn scope_of(node: Node<'_>, source: &[u8]) -> (String, bool) {
+    let (mut within, mut test) = (String::new(), false);
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        let kind = ancestor.kind();
+        let scoped = ["function", "method", "impl", "class", "mod_item", "trait"].iter().any(|k| kind.contains(k));
+        if scoped && !kind.contains("call") && !kind.contains("invocation") {
+            let name = extract_name(&ancestor, source);
+            test = test || is_test_scope(ancestor, &name, source);
+            if within.is_empty() {
+                within = name;
+            }
+        }
+        current = ancestor.parent();
+    }
+    (within, test)
+}
+
+/// Role and scope of the hit at `at`; `None` for comments and strings.
+fn classify(root: Node<'_>, at: usize, len: usize, source: &[u8]) -> Option<(Role, String, bool)> {
+    let node = root.descendant_for_byte_range(at, at.saturating_add(len))?;
+    if is_prose(node) {
+        return None;
+    }
+    let (within, test) = scope_of(node, source);
+    let role = if is_definition(node) {
+        Role::Definition
+    } else if is_call(node) {
+        Role::Call
+    } else {
+        Role::Reference
+    };
+    Some((role, within, test))
+}
+
+/// The references to `symbol` in one file, and how many prose mentions were dropped.
+fn scan_file(path: &str, content: &str, symbol: &str) -> (Vec<Reference>, usize) {
+    let hits = word_hits(content, symbol);
+    if hits.is_empty() {
+        return (Vec::new(), 0);
+    }
+    let ext = Path::new(path).extension().and_then(std::ffi::OsStr::to_str).unwrap_or("");
+    let mut parser = Parser::new();
+    let tree = language_for_ext(ext)
+        .filter(|entry| parser.set_language(&entry.0).is_ok())
+        .and_then(|_| parser.parse(content, None));
+    let test_path = is_test_path(path);
+    let mut dropped = 0usize;
+    let refs = hits
+        .into_iter()
+        .filter_map(|at| {
+            let found = tree.as_ref().map_or_else(
+                || Some((Role::Reference, String::new(), false)),
+                |t| classify(t.root_node(), at, symbol.len(), content.as_bytes()),
+            );
+            let Some((kind, within, test)) = found else {
+                dropped = dropped.saturating_add(1);
+                return None;
+            };
+            let line = content.get(..at).map_or(0, |s| s.matches('\n').count()).saturating_add(1);
+            let code = content.lines().nth(line.saturating_sub(1)).unwrap_or("").trim().to_owned();
+            let role = if (test || test_path) && kind != Role::Definition { Role::Test } else { kind };
+            Some(Reference { path: path.to_owned(), line, role, within, code })
+        })
+        .collect();
+    (refs, dropped)
+}
+
+/// `path:line (in scope): code`, as listed in the report.
+fn entry(r: &Reference) -> String {
+    let within = if r.within.is_empty() { String::new() } else { format!(" (in {})", r.within) };
+    format!("{}:{}{within}: {}", r.path, r.line, cp_base::ui::text::ellipsize(&r.code, 120, "..."))
+}
+
+/// The YAML report for `target`.
+fn report(target: &Target<'_>, refs: &[Reference], dropped: usize) -> String {
+    let of = |role: Role| -> Vec<String> { refs.iter().filter(|r| r.role == role).map(entry).collect() };
+    let mut files: Vec<&str> = refs.iter().map(|r| r.path.as_str()).collect();
+    files.dedup();
+    let (defs, calls, tests, others) = (of(Role::Definition), of(Role::Call), of(Role::Test), of(Role::Reference));
+    let mut root = serde_json::Map::new();
+    drop(root.insert("target".into(), serde_json::json!(target.raw)));
+    let summary = format!(
+        "{} references in {} files: {} definitions, {} call sites, {} in tests, {} other",
+        refs.len(),
+        files.len(),
+        defs.len(),
+        calls.len(),
+        tests.len(),
+        others.len()
+    );
+    drop(root.insert("summary".into(), serde_json::json!(summary)));
+    if let Some(p) = target.path
+        && !refs.iter().any(|r| r.role == Role::Definition && r.path.ends_with(p.trim_start_matches("./")))
+    {
+        let note = format!("no definition of {} found in {p}", target.symbol);
+        drop(root.insert("note".into(), serde_json::json!(note)));
+    }
+    for (key, list) in [("definitions", defs), ("call_sites", calls), ("tests", tests), ("other_references", others)] {
+        if !list.is_empty() {
+            drop(root.insert(key.into(), serde_json::json!(list)));
+        }
+    }
+    if dropped > 0 {
+        drop(root.insert("skipped".into(), serde_json::json!(format!("{dropped} mentions in comments or strings"))));
+    }
+    serde_yaml::to_string(&serde_json::Value::Object(root)).unwrap_or_else(|_| "# Failed to serialize impact\n".into())
+}
+
+/// Scan the project for the symbol and build the panel-bearing result.
+fn analyze(raw: &str) -> ToolOutput {
+    let target = match parse_target(raw) {
+        Ok(t) => t,
+        Err(e) => return ToolOutput::error(e),
+    };
+    let symbol = target.symbol;
+    let root = std::env::current_dir().unwrap_or_default();
+    let mut files: Vec<String> =
+        crate::index::reconcile::disk_map(&root, &crate::types::IgnoreRules::load(&root, false)).into_keys().collect();
+    files.sort();
+    let (mut refs, mut dropped) = (Vec::new(), 0usize);
+    for file in &files {
+        let Ok(content) = std::fs::read_to_string(root.join(file)) else { continue };
+        let (found, skipped) = scan_file(file, &content, symbol);
+        refs.extend(found);
+        dropped = dropped.saturating_add(skipped);
+    }
+    let total = refs.len();
+    refs.truncate(MAX_REFERENCES);
+    let mut content = report(&target, &refs, dropped);
+    if total > MAX_REFERENCES {
+        let _r = writeln!(content, "truncated: {} more references not listed", total.saturating_sub(MAX_REFERENCES));
+    }
+    let summary = content.lines().find_map(|l| l.strip_prefix("summary: ")).unwrap_or("").to_owned();
+    let dyn_panel = DynPanel::new(crate::panel::SEARCH_PANEL_TYPE.to_owned(), format!("impact: {symbol}"))
+        .metadata(vec![("result_content".to_owned(), content.clone())])
+        .content(content);
+    ToolOutput::ok(format!(
+        "Created panel {DYN_PANEL_ID_PLACEHOLDER}: impact of {symbol}, {summary}{}",
+        crate::tools::PANEL_WARNING
+    ))
+    .with_panel(dyn_panel)
+}
+
+/// Execute the `impact_of` tool: scan off the main loop into a result panel.
+pub(crate) fn exec_impact(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let raw = tool.input.get("target").and_then(|v| v.as_str()).unwrap_or("").to_owned();
+    if let Err(e) = parse_target(&raw) {
+        return ToolResult::new(tool.id.clone(), format!("Error: {e}"), true);
+    }
+    spawn_async_tool(state, tool, IMPACT_TIMEOUT_SECS, move || analyze(&raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_name_a_file_and_a_symbol() {
+        let parts = |raw| parse_target(raw).map(|t| (t.path, t.symbol));
+        assert_eq!(parts("src/app.rs:handle_key"), Ok((Some("src/app.rs"), "handle_key")));
+        assert_eq!(parts("src/app.rs:State::touch_panel"), Ok((Some("src/app.rs"), "touch_panel")));
+        assert_eq!(parts("State::touch_panel"), Ok((None, "touch_panel")));
+        assert_eq!(parts("run"), Ok((None, "run")));
+        assert!(parse_target("src/app.rs:").is_err_and(|e| e.contains("path:symbol")));
+    }
+
+    #[test]
+    fn references_are_told_apart() {
+        let code = "fn load(x: u8) -> u8 { x }\n\
+                    // load is documented here\n\
+                    fn run() { let _v = load(1); let _f = load; }\n\
+                    #[cfg(test)]\nmod tests {\n    #[test]\n    fn loads() { assert_eq!(super::load(2), 2); }\n}\n";
+        let (refs, dropped) = scan_file("src/lib.rs", code, "load");
+        let roles: Vec<(usize, Role, &str)> = refs.iter().map(|r| (r.line, r.role, r.within.as_str())).collect();
+        assert_eq!(
+            roles,
+            vec![
+                (1, Role::Definition, "load"),
+                (3, Role::Call, "run"),
+                (3, Role::Reference, "run"),
+                (7, Role::Test, "loads"),
+            ]
+        );
+        assert_eq!(dropped, 1);
+        assert!(is_test_path("pkg/handler_test.go") && is_test_path("tests/cli.rs") && !is_test_path("src/testing.rs"));
+    }
+}
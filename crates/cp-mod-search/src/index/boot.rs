@@ -55,7 +55,8 @@ pub(crate) fn compute_boot_plan(
     }
 
     let client = MeiliClient::new(persist.port, &persist.master_key).ok()?;
-    index::reconcile::compute_plan(&client, &files_uid, std::path::Path::new(project_path)).ok()
+    index::reconcile::compute_plan(&client, &files_uid, std::path::Path::new(project_path), persist.include_ignored)
+        .ok()
 }
 
 /// Queue the offline reconcile delta onto the indexer channel, then mark the
@@ -95,6 +96,7 @@ pub(crate) fn spawn_indexer_pipeline(
         project_root: std::path::PathBuf::from(project_path),
         metrics: std::sync::Arc::clone(metrics),
         skip_initial_scan: true,
+        include_ignored: persist.include_ignored,
     }) {
         Ok((tx, w)) => (Some(tx), Some(types::WatcherHandle::new(w))),
         Err(e) => {
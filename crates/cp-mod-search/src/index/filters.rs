@@ -1,6 +1,9 @@
-//! File indexability gates: extension allowlist, directory/suffix exclusions,
-//! size cap, and the shared `is_indexable` predicate used by both the live
-//! indexer and the boot/hourly reconcile disk-walk.
+//! File indexability gates: git ignore rules, extension allowlist,
+//! directory/suffix exclusions, size cap, and the shared `is_indexable`
+//! predicate used by both the live indexer and the boot/hourly reconcile
+//! disk-walk.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
 // -- Configuration constants -------------------------------------------------
 
@@ -69,6 +72,34 @@ pub(crate) fn is_excluded_file(filename: &str) -> bool {
     EXCLUDED_SUFFIXES.iter().any(|suffix| filename.ends_with(suffix))
 }
 
+/// Ignore files read from the project root, relative to it.
+const IGNORE_FILES: &[&str] = &[".gitignore", ".git/info/exclude"];
+
+/// The project's git ignore rules (root `.gitignore` and
+/// `.git/info/exclude`); matches nothing when the user opted into indexing
+/// ignored files (`include_ignored`).
+pub(crate) struct IgnoreRules(Option<Gitignore>);
+
+impl IgnoreRules {
+    /// Read the rules under `project_root`; missing files add no rule.
+    pub(crate) fn load(project_root: &std::path::Path, include_ignored: bool) -> Self {
+        if include_ignored {
+            return Self(None);
+        }
+        let mut builder = GitignoreBuilder::new(project_root);
+        for file in IGNORE_FILES {
+            let _missing = builder.add(project_root.join(file));
+        }
+        Self(builder.build().ok())
+    }
+
+    /// Whether git ignores `rel_path` (relative to the project root) or one
+    /// of its parent directories.
+    pub(crate) fn is_ignored(&self, rel_path: &std::path::Path, is_dir: bool) -> bool {
+        self.0.as_ref().is_some_and(|rules| rules.matched_path_or_any_parents(rel_path, is_dir).is_ignore())
+    }
+}
+
 /// Shared indexability gate — the single source of truth for "does this file
 /// belong in the search index?".
 ///
@@ -77,8 +108,8 @@ pub(crate) fn is_excluded_file(filename: &str) -> bool {
 /// than the indexer, it would forever re-queue files the indexer silently
 /// rejects (they'd stay "on disk, not in index" → infinite re-queue churn).
 ///
-/// Applies the cheap, read-free gates (symlink, excluded dir, extension
-/// allowlist, excluded suffix, size cap). The UTF-8 readability check is an
+/// Applies the cheap, read-free gates (symlink, excluded dir, git ignore
+/// rules, extension allowlist, excluded suffix, size cap). The UTF-8 readability check is an
 /// inherent post-gate shared by both paths (reconcile routes through
 /// `index_one_file`, which reads the file), so it is deliberately not here.
 ///
@@ -88,6 +119,7 @@ pub(crate) fn is_indexable(
     abs_path: &std::path::Path,
     project_root: &std::path::Path,
     meta: &std::fs::Metadata,
+    rules: &IgnoreRules,
 ) -> bool {
     if abs_path.is_symlink() {
         return false;
@@ -104,6 +136,11 @@ pub(crate) fn is_indexable(
         }
     }
 
+    // Ignored by git (unless the user opted in).
+    if rules.is_ignored(rel_path, false) {
+        return false;
+    }
+
     // Extension allowlist (text files only).
     let ext = rel_path.extension().and_then(std::ffi::OsStr::to_str).unwrap_or("");
     if !is_allowed_extension(ext) {
@@ -142,7 +179,7 @@ mod tests {
         }
         std::fs::write(&abs, bytes).map_err(|e| format!("write test file: {e}"))?;
         let meta = std::fs::metadata(&abs).map_err(|e| format!("stat test file: {e}"))?;
-        Ok(is_indexable(&abs, root, &meta))
+        Ok(is_indexable(&abs, root, &meta, &IgnoreRules::load(root, false)))
     }
 
     #[test]
@@ -189,6 +226,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn gitignored_file_rejected_unless_included() -> Result<(), String> {
+        let root = tmp_root("ign")?;
+        std::fs::write(root.join(".gitignore"), "generated/\n*.local.rs\n").map_err(|e| format!("write: {e}"))?;
+        let ignored = indexable(&root, "generated/api.rs", b"x")?;
+        let local = indexable(&root, "src/db.local.rs", b"x")?;
+        let kept = indexable(&root, "src/db.rs", b"x")?;
+        let meta = std::fs::metadata(root.join("generated/api.rs")).map_err(|e| format!("stat: {e}"))?;
+        let included = is_indexable(&root.join("generated/api.rs"), &root, &meta, &IgnoreRules::load(&root, true));
+        drop(std::fs::remove_dir_all(&root));
+        if ignored || local || !kept || !included {
+            return Err(format!("ignored={ignored} local={local} kept={kept} included={included}"));
+        }
+        Ok(())
+    }
+
     #[test]
     fn oversized_file_rejected() -> Result<(), String> {
         let root = tmp_root("big")?;
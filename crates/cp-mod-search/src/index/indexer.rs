@@ -39,6 +39,8 @@ pub(crate) struct IndexerParams {
     /// the previous session and the `RecommendedWatcher` picks up incremental
     /// changes.  Set to `false` on first boot (fresh indexes).
     pub skip_initial_scan: bool,
+    /// Index files git ignores too (the user's `include_ignored`).
+    pub include_ignored: bool,
 }
 
 /// Internal context for the running indexer thread.
@@ -61,6 +63,8 @@ struct IndexerCtx {
     /// notification, keeping Meilisearch at 200 %+ CPU via embedding
     /// regeneration even when the project is idle.
     last_indexed_mtime: HashMap<String, u64>,
+    /// Git ignore rules every file passes through.
+    rules: types::IgnoreRules,
 }
 
 /// Start the background indexer and file watcher.
@@ -113,10 +117,11 @@ pub(crate) fn start(params: IndexerParams) -> Result<(mpsc::Sender<IndexerCmd>,
     } else {
         let scan_tx = tx.clone();
         let scan_root = params.project_root.clone();
+        let scan_rules = types::IgnoreRules::load(&scan_root, params.include_ignored);
         let _scan_handle = std::thread::Builder::new()
             .name("search-scan".into())
             .spawn(move || {
-                scan_directory(&scan_tx, &scan_root);
+                scan_directory(&scan_tx, &scan_root, &scan_root, &scan_rules);
                 let _r = scan_tx.send(IndexerCmd::ScanComplete);
             })
             .map_err(|e| format!("Cannot spawn scan thread: {e}"))?;
@@ -188,6 +193,7 @@ fn indexer_loop(rx: &mpsc::Receiver<IndexerCmd>, params: &IndexerParams) {
         splitter: SplitterChain::new(),
         metrics: std::sync::Arc::clone(&params.metrics),
         last_indexed_mtime: HashMap::new(),
+        rules: types::IgnoreRules::load(&params.project_root, params.include_ignored),
     };
 
     while let Ok(first) = rx.recv() {
@@ -321,7 +327,7 @@ fn index_one_file(ctx: &mut IndexerCtx, abs_path: &Path) {
 
     // Shared indexability gate — SAME predicate the reconcile disk-walk uses,
     // so the two paths never disagree (see types::is_indexable).
-    if !types::is_indexable(abs_path, &ctx.project_root, &meta) {
+    if !types::is_indexable(abs_path, &ctx.project_root, &meta, &ctx.rules) {
         return;
     }
 
@@ -392,10 +398,11 @@ fn delete_one_file(ctx: &mut IndexerCtx, abs_path: &Path) {
 
 /// Recursively scan a directory and queue eligible files for indexing.
 ///
-/// Skips symlinks, excluded directories, and sends `IndexFile` for
-/// every regular file encountered.  Filtering (extension, size) is
-/// done by the indexer thread when it processes each command.
-fn scan_directory(tx: &mpsc::Sender<IndexerCmd>, dir: &Path) {
+/// Skips symlinks, excluded and git-ignored directories, and sends
+/// `IndexFile` for every regular file encountered.  Filtering (extension,
+/// size, ignored files) is done by the indexer thread when it processes
+/// each command.
+fn scan_directory(tx: &mpsc::Sender<IndexerCmd>, root: &Path, dir: &Path, rules: &types::IgnoreRules) {
     let Ok(entries) = std::fs::read_dir(dir) else {
         return;
     };
@@ -411,8 +418,9 @@ fn scan_directory(tx: &mpsc::Sender<IndexerCmd>, dir: &Path) {
         if path.is_dir() {
             let name = entry.file_name();
             let name_str = name.to_str().unwrap_or("");
-            if !types::is_excluded_dir(name_str) {
-                scan_directory(tx, &path);
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            if !types::is_excluded_dir(name_str) && !rules.is_ignored(rel, true) {
+                scan_directory(tx, root, &path, rules);
             }
         } else if path.is_file() {
             let _r = tx.send(IndexerCmd::IndexFile(path));
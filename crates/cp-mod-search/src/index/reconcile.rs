@@ -109,14 +109,14 @@ fn index_map(client: &MeiliClient, files_uid: &str) -> Result<HashMap<String, Fi
 
 /// Stat-walk the tree, collecting a fingerprint for every indexable file (same
 /// gate as the live indexer, via [`types::is_indexable`]).
-fn disk_map(project_root: &Path) -> HashMap<String, FilePrint> {
+pub(crate) fn disk_map(project_root: &Path, rules: &types::IgnoreRules) -> HashMap<String, FilePrint> {
     let mut map: HashMap<String, FilePrint> = HashMap::new();
-    walk(project_root, project_root, &mut map);
+    walk(project_root, project_root, rules, &mut map);
     map
 }
 
 /// Recursive helper for [`disk_map`], mirroring the indexer's directory filter.
-fn walk(root: &Path, dir: &Path, map: &mut HashMap<String, FilePrint>) {
+fn walk(root: &Path, dir: &Path, rules: &types::IgnoreRules, map: &mut HashMap<String, FilePrint>) {
     let Ok(entries) = std::fs::read_dir(dir) else {
         return;
     };
@@ -127,14 +127,15 @@ fn walk(root: &Path, dir: &Path, map: &mut HashMap<String, FilePrint>) {
         }
         if path.is_dir() {
             let name = entry.file_name();
-            if !types::is_excluded_dir(name.to_str().unwrap_or("")) {
-                walk(root, &path, map);
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            if !types::is_excluded_dir(name.to_str().unwrap_or("")) && !rules.is_ignored(rel, true) {
+                walk(root, &path, rules, map);
             }
         } else if path.is_file() {
             let Ok(meta) = std::fs::metadata(&path) else {
                 continue;
             };
-            if !types::is_indexable(&path, root, &meta) {
+            if !types::is_indexable(&path, root, &meta, rules) {
                 continue;
             }
             let rel = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
@@ -145,7 +146,8 @@ fn walk(root: &Path, dir: &Path, map: &mut HashMap<String, FilePrint>) {
     }
 }
 
-/// Compute the offline delta between the files index and the current disk.
+/// Compute the offline delta between the files index and the current disk;
+/// files git ignores count as absent unless `include_ignored`.
 ///
 /// # Errors
 ///
@@ -154,9 +156,10 @@ pub(crate) fn compute_plan(
     client: &MeiliClient,
     files_uid: &str,
     project_root: &Path,
+    include_ignored: bool,
 ) -> Result<ReconcilePlan, String> {
     let index = index_map(client, files_uid)?;
-    let disk = disk_map(project_root);
+    let disk = disk_map(project_root, &types::IgnoreRules::load(project_root, include_ignored));
     Ok(diff(&index, &disk))
 }
 
@@ -38,6 +38,8 @@ pub(crate) struct TickParams {
     pub project_root: PathBuf,
     /// Channel into the running indexer, used to queue the reconcile delta.
     pub indexer_tx: mpsc::Sender<IndexerCmd>,
+    /// Index files git ignores too (the user's `include_ignored`).
+    pub include_ignored: bool,
 }
 
 /// Handle to the running tick thread. Dropping it stops the thread (and joins
@@ -105,7 +107,7 @@ fn tick(params: &TickParams) {
     let logs_uid = format!("cp_{}_logs", params.project_hash);
 
     // 1. Reconcile against current disk, queueing the delta through the indexer.
-    match reconcile::compute_plan(&client, &files_uid, &params.project_root) {
+    match reconcile::compute_plan(&client, &files_uid, &params.project_root, params.include_ignored) {
         Ok(plan) => {
             if !plan.is_empty() {
                 log::info!(
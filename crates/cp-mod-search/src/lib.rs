@@ -4,9 +4,13 @@
 //! Meilisearch server. Files are chunked using tree-sitter AST parsing
 //! (with a fixed-size fallback) and indexed in the background.
 //!
-//! One tool: `search` — queries both file and log indexes.
-//! Results appear as dynamic search result panels.
+//! Two tools: `search` — queries both file and log indexes — and
+//! `impact_of`, which lists every reference to a symbol before a refactor.
+//! Results appear as dynamic search result panels. Files git ignores are not
+//! indexed unless the user opts in ([`set_include_ignored`]).
 
+/// `impact_of`: definitions, call sites and tests touching a symbol.
+mod impact;
 /// File-indexing pipeline: filters, background indexer, reconciliation.
 pub mod index;
 /// Meilisearch HTTP client, server lifecycle, and binary download.
@@ -15,6 +19,8 @@ pub mod meili;
 pub mod panel;
 /// Context Radar — automatic log recall from Think task signals.
 pub mod radar;
+/// Ranking of file hits by session signals (open panels, recent edits).
+mod rank;
 /// File content splitter chain (fixed-size fallback, future tree-sitter).
 pub mod splitter;
 /// Search tool dispatch and execution.
@@ -194,6 +200,11 @@ impl Module for SearchModule {
                 .param("semantic_ratio", ParamType::Number, false)
                 .param("hide_contents", ParamType::Boolean, false)
                 .build(),
+            ToolDefinition::from_yaml("impact_of", t)
+                .short_desc("List every reference to a symbol before a refactor")
+                .category("Search")
+                .param("target", ParamType::String, true)
+                .build(),
         ]
     }
 
@@ -297,6 +308,7 @@ impl Module for SearchModule {
                 project_hash: persist.project_hash.clone(),
                 project_root: std::path::PathBuf::from(&project_path),
                 indexer_tx: tx,
+                include_ignored: persist.include_ignored,
             })),
             None => None,
         };
@@ -427,3 +439,20 @@ pub fn refresh_radar(state: &State) {
 pub fn push_task_signal(state: &mut State, content: &str) {
     radar::push_signal(state, content);
 }
+
+/// Index files git ignores too (`true`), or skip them. User-side only: no
+/// tool calls this. The walker reads the setting when it starts, so it
+/// applies from the next reload.
+///
+/// # Errors
+///
+/// Returns an error when the search module is not running.
+pub fn set_include_ignored(state: &mut State, enabled: bool) -> Result<String, String> {
+    let ss = state.get_ext_mut::<SearchState>().ok_or_else(|| "search is not running".to_owned())?;
+    ss.persist.include_ignored = enabled;
+    Ok(if enabled {
+        "Search will index files git ignores from the next reload.".to_owned()
+    } else {
+        "Search will skip files git ignores from the next reload.".to_owned()
+    })
+}
@@ -7,15 +7,12 @@
 //! The `format_results` function builds the YAML string consumed by
 //! both the panel and the tool result when `hide_contents` is true.
 
-use std::collections::BTreeMap;
-
 use crossterm::event::KeyEvent;
 
-use cp_base::panels::{
-    CacheRequest, CacheUpdate, ContextItem, Panel, paginate_content, scroll_key_action, update_if_changed,
-};
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, paginate_content, scroll_key_action, update_if_changed};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
 use crate::types::SearchResult;
@@ -175,11 +172,7 @@ pub(crate) fn visualize_search_output(content: &str, width: usize) -> Vec<cp_ren
             }
 
             // Truncate long lines
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
 
             Block::Line(vec![Span::styled(display, search_line_semantic(line))])
         })
@@ -204,6 +197,8 @@ pub(crate) struct EntityHit {
 pub(crate) struct SearchOutput<'results> {
     /// File chunk results from the files index.
     pub files: &'results [SearchResult],
+    /// File hits ranked below the limit and left out.
+    pub more_files: usize,
     /// Log entry results from the logs index.
     pub logs: &'results [SearchResult],
     /// Entity row results from the entities index.
@@ -212,7 +207,8 @@ pub(crate) struct SearchOutput<'results> {
 
 /// Format search results as YAML for panel display.
 ///
-/// File results are grouped by path. All metadata is included.
+/// File results are grouped by path, files in the order of their best hit.
+/// All metadata is included.
 /// Uses `serde_yaml` for consistent formatting matching the brave module style.
 pub(crate) fn format_results(query: &str, output: &SearchOutput<'_>, hide_contents: bool) -> String {
     let total = output.files.len().saturating_add(output.logs.len()).saturating_add(output.entities.len());
@@ -224,26 +220,14 @@ pub(crate) fn format_results(query: &str, output: &SearchOutput<'_>, hide_conten
     // -- File results, grouped by path ---------------------------------------
 
     if !output.files.is_empty() {
-        let mut by_path: BTreeMap<String, Vec<&SearchResult>> = BTreeMap::new();
-        for r in output.files {
-            let path = r.file_path.as_deref().unwrap_or("unknown").to_owned();
-            by_path.entry(path).or_default().push(r);
-        }
-
-        let mut files_arr: Vec<serde_json::Value> = Vec::new();
-        for (path, chunks) in &by_path {
-            let ext = chunks.first().and_then(|c| c.extension.as_deref()).unwrap_or("");
-            let mut file_obj = serde_json::Map::new();
-            drop(file_obj.insert("path".into(), serde_json::Value::String(path.clone())));
-            drop(file_obj.insert("extension".into(), serde_json::Value::String(ext.to_owned())));
-
-            let chunks_arr: Vec<serde_json::Value> =
-                chunks.iter().map(|chunk| build_chunk_value(chunk, hide_contents)).collect();
-
-            drop(file_obj.insert("chunks".into(), serde_json::Value::Array(chunks_arr)));
-            files_arr.push(serde_json::Value::Object(file_obj));
-        }
-        drop(root.insert("files".into(), serde_json::Value::Array(files_arr)));
+        drop(root.insert("files".into(), build_files_value(output.files, hide_contents)));
+    }
+    if output.more_files > 0 {
+        let note = format!(
+            "{} lower-ranked file chunks not shown; narrow with path_prefix or extension, or raise limit",
+            output.more_files
+        );
+        drop(root.insert("more_files".into(), serde_json::Value::String(note)));
     }
 
     // -- Log results ---------------------------------------------------------
@@ -266,6 +250,36 @@ pub(crate) fn format_results(query: &str, output: &SearchOutput<'_>, hide_conten
     serde_yaml::to_string(&serde_json::Value::Object(root)).unwrap_or_else(|_| "# Failed to serialize results\n".into())
 }
 
+/// Build the file results: chunks grouped by path, files in the order of
+/// their first (best-ranked) chunk.
+fn build_files_value(files: &[SearchResult], hide_contents: bool) -> serde_json::Value {
+    let mut by_path: Vec<(&str, Vec<&SearchResult>)> = Vec::new();
+    for r in files {
+        let path = r.file_path.as_deref().unwrap_or("unknown");
+        if let Some(group) = by_path.iter_mut().find(|group| group.0 == path) {
+            group.1.push(r);
+        } else {
+            by_path.push((path, vec![r]));
+        }
+    }
+
+    let files_arr: Vec<serde_json::Value> = by_path
+        .iter()
+        .map(|group| {
+            let (path, chunks) = (group.0, &group.1);
+            let ext = chunks.first().and_then(|c| c.extension.as_deref()).unwrap_or("");
+            let mut file_obj = serde_json::Map::new();
+            drop(file_obj.insert("path".into(), serde_json::Value::String(path.to_owned())));
+            drop(file_obj.insert("extension".into(), serde_json::Value::String(ext.to_owned())));
+            let chunks_arr: Vec<serde_json::Value> =
+                chunks.iter().map(|chunk| build_chunk_value(chunk, hide_contents)).collect();
+            drop(file_obj.insert("chunks".into(), serde_json::Value::Array(chunks_arr)));
+            serde_json::Value::Object(file_obj)
+        })
+        .collect();
+    serde_json::Value::Array(files_arr)
+}
+
 /// Build a JSON value for a single file chunk.
 fn build_chunk_value(chunk: &SearchResult, hide_contents: bool) -> serde_json::Value {
     let mut obj = serde_json::Map::new();
@@ -13,9 +13,10 @@ use std::collections::HashMap;
 
 use crossterm::event::KeyEvent;
 
-use cp_base::panels::{CacheRequest, CacheUpdate, ContextItem, Panel, scroll_key_action};
+use cp_base::panels::{CacheRequest, CacheUpdate, Panel, scroll_key_action};
 use cp_base::state::actions::Action;
 use cp_base::state::context::Entry;
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
 use crate::meili::api::{MeiliClient, SearchParams};
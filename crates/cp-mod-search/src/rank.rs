@@ -0,0 +1,151 @@
+//! Ranking of file hits beyond the index's own relevance score.
+//!
+//! The index only scores how well a chunk matches the query. Before file hits
+//! are cut to the requested limit they are ranked again with what the session
+//! knows: files open as panels (and their folders), files edited in the last
+//! hours, and definitions (functions, structs, classes) over other chunks.
+//! The index is asked for more hits than the limit so the cut has something
+//! to choose from, and how many were cut is reported back.
+
+use std::path::Path;
+
+use cp_base::cast::float_math;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+
+use crate::types::SearchResult;
+
+/// Extra score of a hit in a file open as a panel.
+const OPEN_FILE_BOOST: f64 = 0.15;
+/// Extra score of a hit next to an open file (same folder).
+const OPEN_FOLDER_BOOST: f64 = 0.05;
+/// Extra score of a hit in a file edited within [`RECENT_MS`].
+const RECENT_BOOST: f64 = 0.10;
+/// Extra score of a hit in a file edited within [`TODAY_MS`].
+const TODAY_BOOST: f64 = 0.05;
+/// Extra score of a definition chunk.
+const DEFINITION_BOOST: f64 = 0.05;
+/// "Recently edited": the last hour.
+const RECENT_MS: u64 = 3_600_000;
+/// "Edited today": the last 24 hours.
+const TODAY_MS: u64 = 86_400_000;
+/// Chunk types that define a symbol rather than use it.
+const DEFINITIONS: [&str; 9] = ["function", "method", "struct", "enum", "trait", "class", "interface", "type", "impl"];
+/// How many hits the index is asked for, per requested result.
+pub(crate) const FETCH_FACTOR: u32 = 2;
+
+/// What the session knows that the index does not.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RankHints {
+    /// Files open as panels, relative to the project root.
+    pub open_files: Vec<String>,
+    /// When the search ran.
+    pub now_ms: u64,
+}
+
+impl RankHints {
+    /// Collect the hints from the open panels.
+    pub(crate) fn from_state(state: &State) -> Self {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let open_files = state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::FILE)
+            .filter_map(|c| c.get_meta_str("file_path"))
+            .map(|path| {
+                Path::new(path).strip_prefix(&cwd).map_or_else(|_| path.to_owned(), |rel| rel.display().to_string())
+            })
+            .collect();
+        Self { open_files, now_ms: cp_base::panels::now_ms() }
+    }
+
+    /// Score added to `hit` on top of the index's.
+    fn boost(&self, hit: &SearchResult) -> f64 {
+        let definition = hit.chunk_type.as_deref().is_some_and(|kind| DEFINITIONS.contains(&kind));
+        let place = hit.file_path.as_deref().map_or(0.0f64, |path| self.place_boost(path));
+        let age = hit.last_modified_ms.map_or(0.0f64, |modified| self.age_boost(modified));
+        float_math::sum3(place, age, if definition { DEFINITION_BOOST } else { 0.0f64 })
+    }
+
+    /// Boost of a hit in `path`: open as a panel, or next to one.
+    fn place_boost(&self, path: &str) -> f64 {
+        let folder = Path::new(path).parent();
+        if self.open_files.iter().any(|open| open == path) {
+            OPEN_FILE_BOOST
+        } else if self.open_files.iter().any(|open| Path::new(open).parent() == folder) {
+            OPEN_FOLDER_BOOST
+        } else {
+            0.0f64
+        }
+    }
+
+    /// Boost of a hit in a file last modified at `modified`.
+    const fn age_boost(&self, modified: u64) -> f64 {
+        let age = self.now_ms.saturating_sub(modified);
+        if age < RECENT_MS {
+            RECENT_BOOST
+        } else if age < TODAY_MS {
+            TODAY_BOOST
+        } else {
+            0.0f64
+        }
+    }
+}
+
+/// Order `found` by index score plus boost, keep the best `limit`; returns how
+/// many were cut.
+pub(crate) fn rank(found: &mut Vec<SearchResult>, hints: &RankHints, limit: u32) -> usize {
+    let score = |hit: &SearchResult| float_math::add(hit.ranking_score.unwrap_or(0.0f64), hints.boost(hit));
+    found.sort_by(|a, b| score(b).partial_cmp(&score(a)).unwrap_or(std::cmp::Ordering::Equal));
+    let keep = usize::try_from(limit).unwrap_or(usize::MAX);
+    let cut = found.len().saturating_sub(keep);
+    found.truncate(keep);
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file hit with an index score.
+    fn hit(path: &str, kind: &str, score: f64) -> SearchResult {
+        SearchResult {
+            content: String::new(),
+            file_path: Some(path.to_owned()),
+            chunk_type: Some(kind.to_owned()),
+            chunk_name: None,
+            line_start: Some(1),
+            line_end: Some(2),
+            extension: Some("rs".to_owned()),
+            last_modified_ms: Some(0),
+            log_id: None,
+            datetime: None,
+            importance: None,
+            ranking_score: Some(score),
+        }
+    }
+
+    #[test]
+    fn open_files_and_definitions_outrank_close_scores() {
+        let hints = RankHints { open_files: vec!["src/app.rs".to_owned()], now_ms: TODAY_MS.saturating_mul(2) };
+        let mut found = vec![
+            hit("src/other.rs", "other", 0.80),
+            hit("lib/util.rs", "function", 0.78),
+            hit("src/app.rs", "other", 0.72),
+        ];
+        let cut = rank(&mut found, &hints, 2);
+        let order: Vec<&str> = found.iter().filter_map(|h| h.file_path.as_deref()).collect();
+        assert_eq!(order, ["src/app.rs", "src/other.rs"]);
+        assert_eq!(cut, 1);
+    }
+
+    #[test]
+    fn recent_edits_rank_first() {
+        let hints = RankHints { open_files: Vec::new(), now_ms: 1_000 };
+        let mut old = hit("a.rs", "other", 0.9);
+        old.last_modified_ms = None;
+        let mut found = vec![old, hit("b.rs", "other", 0.85)];
+        assert_eq!(rank(&mut found, &hints, 5), 0);
+        assert_eq!(found.first().and_then(|h| h.file_path.as_deref()), Some("b.rs"));
+    }
+}
@@ -91,7 +91,7 @@ const CPP_KINDS: &[&str] = &[
 
 /// Map a file extension to a tree-sitter [`Language`] and its
 /// set of top-level node kinds that constitute "semantic items".
-fn language_for_ext(ext: &str) -> Option<(Language, &'static [&'static str])> {
+pub(crate) fn language_for_ext(ext: &str) -> Option<(Language, &'static [&'static str])> {
     match ext {
         "rs" => Some((tree_sitter_rust::LANGUAGE.into(), RUST_KINDS)),
         "py" => Some((tree_sitter_python::LANGUAGE.into(), PYTHON_KINDS)),
@@ -108,7 +108,7 @@ fn language_for_ext(ext: &str) -> Option<(Language, &'static [&'static str])> {
 
 /// Extract the name of a semantic node by looking for common child
 /// node kinds that carry the identifier.
-fn extract_name(node: &tree_sitter::Node<'_>, source: &[u8]) -> String {
+pub(crate) fn extract_name(node: &tree_sitter::Node<'_>, source: &[u8]) -> String {
     // Try direct "name" field first (covers most grammars)
     if let Some(name_node) = node.child_by_field_name("name") {
         let range = name_node.byte_range();
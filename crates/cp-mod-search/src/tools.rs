@@ -11,12 +11,14 @@ use cp_base::tools::{ToolResult, ToolUse};
 
 use crate::meili::api::MeiliClient;
 use crate::panel::format_results;
+use crate::rank::{FETCH_FACTOR, RankHints, rank};
 use crate::types::{SearchResult, SearchState};
 
 /// Dispatch search tool calls.
 pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
     match tool.name.as_str() {
         "search" => Some(exec_search(tool, state)),
+        "impact_of" => Some(crate::impact::exec_impact(tool, state)),
         _ => None,
     }
 }
@@ -101,7 +103,7 @@ fn iso_to_ms(s: &str) -> Option<u64> {
 ///
 /// Prevents the LLM from closing result panels before acting on their content.
 /// Closing a panel causes instant, irreversible context loss.
-const PANEL_WARNING: &str = "\n\nIMPORTANT: Results live in this panel. Act on the information FIRST (write \
+pub(crate) const PANEL_WARNING: &str = "\n\nIMPORTANT: Results live in this panel. Act on the information FIRST (write \
     files, answer questions, store in scratchpad, etc.), THEN close the panel. Closing it IMMEDIATELY and \
     IRREVERSIBLY erases all content from your context \u{2014} you cannot recall it from memory afterward. \
     Never close-then-act; always act-then-close.";
@@ -178,6 +180,7 @@ fn parse_file_hit(hit: &serde_json::Value) -> SearchResult {
         line_start: hit.get("line_start").and_then(serde_json::Value::as_u64).and_then(|n| u32::try_from(n).ok()),
         line_end: hit.get("line_end").and_then(serde_json::Value::as_u64).and_then(|n| u32::try_from(n).ok()),
         extension: hit.get("extension").and_then(serde_json::Value::as_str).map(String::from),
+        last_modified_ms: hit.get("last_modified_ms").and_then(serde_json::Value::as_u64),
         log_id: None,
         datetime: None,
         importance: None,
@@ -197,6 +200,7 @@ fn parse_log_hit(hit: &serde_json::Value) -> SearchResult {
         line_start: None,
         line_end: None,
         extension: None,
+        last_modified_ms: None,
         log_id: hit.get("id").and_then(serde_json::Value::as_str).map(String::from),
         datetime: hit.get("datetime").and_then(serde_json::Value::as_str).map(String::from),
         importance: hit.get("importance").and_then(serde_json::Value::as_str).map(String::from),
@@ -329,6 +333,8 @@ struct SearchArgs {
     log_sort: Option<&'static str>,
     /// Per-index result cap.
     limit: u32,
+    /// Session signals to rank file hits with; `None` for date sorts.
+    hints: Option<RankHints>,
     /// Whether to omit contents (compact metadata output).
     hide_contents: bool,
 }
@@ -385,6 +391,7 @@ fn parse_search_args(tool: &ToolUse, state: &State) -> Result<SearchArgs, Box<To
         file_sort: file_sort_string(sort),
         log_sort: log_sort_string(sort),
         limit,
+        hints: (sort == "relevance").then(|| RankHints::from_state(state)),
         hide_contents,
     })
 }
@@ -392,7 +399,9 @@ fn parse_search_args(tool: &ToolUse, state: &State) -> Result<SearchArgs, Box<To
 /// Run the (up to) three index queries off the main loop and fold them into a
 /// panel-bearing (or compact) [`ToolOutput`].
 fn run_search_query(client: &MeiliClient, args: &SearchArgs) -> ToolOutput {
-    let file_results: Vec<SearchResult> = if args.search_files {
+    // Ranked searches fetch extra file hits for the ranking to choose from.
+    let fetch = if args.hints.is_some() { args.limit.saturating_mul(FETCH_FACTOR) } else { args.limit };
+    let mut file_results: Vec<SearchResult> = if args.search_files {
         search_index_dual(
             client,
             &DualSearchSpec {
@@ -401,13 +410,14 @@ fn run_search_query(client: &MeiliClient, args: &SearchArgs) -> ToolOutput {
                 semantic_query: &args.semantic_query,
                 filter: args.file_filter.as_deref(),
                 sort: args.file_sort,
-                limit: args.limit,
+                limit: fetch,
             },
             parse_file_hit,
         )
     } else {
         Vec::new()
     };
+    let more_files = args.hints.as_ref().map_or(0, |hints| rank(&mut file_results, hints, args.limit));
 
     let log_results: Vec<SearchResult> = if args.search_logs {
         search_index_dual(
@@ -436,7 +446,7 @@ fn run_search_query(client: &MeiliClient, args: &SearchArgs) -> ToolOutput {
     let log_count = log_results.len();
     let entity_count = entity_results.len();
     let search_output =
-        crate::panel::SearchOutput { files: &file_results, logs: &log_results, entities: &entity_results };
+        crate::panel::SearchOutput { files: &file_results, more_files, logs: &log_results, entities: &entity_results };
     let panel_content = format_results(&args.query, &search_output, args.hide_contents);
 
     if args.hide_contents {
@@ -448,9 +458,11 @@ fn run_search_query(client: &MeiliClient, args: &SearchArgs) -> ToolOutput {
         .metadata(vec![("result_content".to_owned(), panel_content.clone())])
         .content(panel_content);
 
+    let more =
+        if more_files > 0 { format!(" ({more_files} lower-ranked file chunks not shown)") } else { String::new() };
     ToolOutput::ok(format!(
         "Created panel {DYN_PANEL_ID_PLACEHOLDER}: \
-         {file_count} file chunks, {log_count} logs, {entity_count} entities for \"{query}\"{PANEL_WARNING}",
+         {file_count} file chunks{more}, {log_count} logs, {entity_count} entities for \"{query}\"{PANEL_WARNING}",
     ))
     .with_panel(dyn_panel)
 }
@@ -72,6 +72,10 @@ pub(crate) struct SearchPersistData {
     /// Used by Context Radar to query the logs index for automatic recall.
     #[serde(default)]
     pub task_signals: Vec<TaskSignal>,
+    /// Index files git ignores too. User-only (`/search-ignored`); applied
+    /// from the next reload.
+    #[serde(default)]
+    pub include_ignored: bool,
 }
 
 /// Full runtime search state stored in the `State` `TypeMap`.
@@ -365,6 +369,8 @@ pub(crate) struct SearchResult {
     pub line_end: Option<u32>,
     /// File extension — file results only.
     pub extension: Option<String>,
+    /// When the file was last modified (ms since epoch) — file results only.
+    pub last_modified_ms: Option<u64>,
     /// Log entry ID — log results only.
     pub log_id: Option<String>,
     /// ISO 8601 datetime string — log results only.
@@ -380,7 +386,7 @@ pub(crate) struct SearchResult {
 // The indexability gates, extension allowlist, size cap and exclusion lists
 // live in the sibling `filters` module; re-exported here so existing
 // `types::is_indexable` / `types::MAX_FILE_SIZE` call-sites keep resolving.
-pub(crate) use crate::index::filters::{FALLBACK_CHUNK_SIZE, is_excluded_dir, is_indexable};
+pub(crate) use crate::index::filters::{FALLBACK_CHUNK_SIZE, IgnoreRules, is_excluded_dir, is_indexable};
 
 /// Meilisearch settings for the **files** index.
 ///
@@ -0,0 +1,90 @@
+//! Snippets module — a library of approved boilerplate with placeholders.
+//!
+//! Snippets ship in `yamls/snippets/`; a project adds or overrides them in
+//! `.context-pilot/snippets/`. The user inserts them from the command palette
+//! or `/snippet`, into the draft or a file; `snippet_get` gives the AI the
+//! same library, filled in, so it reuses the approved text.
+
+/// The snippet store, placeholder expansion and file insertion.
+pub mod store;
+/// `snippet_get` execution.
+mod tools;
+
+use cp_base::modules::Module;
+use cp_base::panels::Panel;
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolParam, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+/// Lazily parsed tool texts from the snippets YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/snippets.yaml")));
+
+/// Snippets module: the `snippet_get` tool.
+#[derive(Debug, Clone, Copy)]
+pub struct SnippetsModule;
+
+impl Default for SnippetsModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnippetsModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for SnippetsModule {
+    fn id(&self) -> &'static str {
+        "snippets"
+    }
+
+    fn name(&self) -> &'static str {
+        "Snippets"
+    }
+
+    fn description(&self) -> &'static str {
+        "Approved boilerplate with placeholders, for the user and the AI"
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("snippet_get", t)
+                .short_desc("Get approved boilerplate")
+                .category("File")
+                .param("id", ParamType::String, false)
+                .param_array(
+                    "values",
+                    ParamType::Object(vec![
+                        ToolParam::new("name", ParamType::String).desc("Placeholder name").required(),
+                        ToolParam::new("value", ParamType::String).desc("Text to put there").required(),
+                    ]),
+                    false,
+                )
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        (tool.name == "snippet_get").then(|| tools::execute_get(tool))
+    }
+
+    fn create_panel(&self, _context_type: &Kind) -> Option<Box<dyn Panel>> {
+        None
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,236 @@
+//! The snippet store: built-ins from `yamls/snippets/`, extended and
+//! overridden by id with the `.yaml` files of `.context-pilot/snippets/`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use cp_base::config::constants::STORE_DIR;
+
+/// Directory of a project's snippets, under the store dir.
+pub const SNIPPETS_DIR: &str = "snippets";
+
+/// Built-in snippet files, by name.
+const BUILT_IN: &[(&str, &str)] = &[
+    ("rust.yaml", include_str!("../../../yamls/snippets/rust.yaml")),
+    ("python.yaml", include_str!("../../../yamls/snippets/python.yaml")),
+];
+
+/// One piece of approved boilerplate.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Snippet {
+    /// Unique id; a project snippet with a built-in's id replaces it.
+    pub id: String,
+    /// Picker label.
+    pub name: String,
+    /// What it is for.
+    #[serde(default)]
+    pub description: String,
+    /// Language of the body.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Placeholder name to what goes there.
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    /// The text, with `{{name}}` placeholders.
+    pub body: String,
+}
+
+/// Layout of a snippet file.
+#[derive(Debug, Deserialize)]
+struct SnippetFile {
+    /// The snippets, in picker order.
+    snippets: Vec<Snippet>,
+}
+
+/// Every snippet, and the project files that could not be read.
+#[derive(Debug, Default)]
+pub struct Library {
+    /// Built-ins first, then the project's, in file name order.
+    pub snippets: Vec<Snippet>,
+    /// One line per ignored file.
+    pub errors: Vec<String>,
+}
+
+impl Library {
+    /// The snippet with this id.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Snippet> {
+        self.snippets.iter().find(|s| s.id == id)
+    }
+
+    /// Add `snippet`, replacing the one with the same id.
+    fn insert(&mut self, snippet: Snippet) {
+        match self.snippets.iter_mut().find(|s| s.id == snippet.id) {
+            Some(slot) => *slot = snippet,
+            None => self.snippets.push(snippet),
+        }
+    }
+}
+
+/// The snippets of the project at `root`.
+#[must_use]
+pub fn load(root: &Path) -> Library {
+    let mut library = Library::default();
+    for &(name, text) in BUILT_IN {
+        for snippet in cp_base::config::parse_yaml::<SnippetFile>(name, text).snippets {
+            library.insert(snippet);
+        }
+    }
+    let dir = root.join(STORE_DIR).join(SNIPPETS_DIR);
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .map(|entries| entries.filter_map(Result::ok).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|p| p.extension().is_some_and(|ext| ext == "yaml" || ext == "yml"));
+    files.sort();
+    for path in files {
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_yaml::from_str::<SnippetFile>(&text).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(file) => file.snippets.into_iter().for_each(|s| library.insert(s)),
+            Err(e) => library.errors.push(format!("{}: {e}", path.display())),
+        }
+    }
+    library
+}
+
+/// Whether `name` can be a placeholder name.
+fn is_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+/// A `{{name}}` placeholder in a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placeholder<'text> {
+    /// Byte offset of `{{`.
+    pub start: usize,
+    /// Byte offset just past `}}`.
+    pub end: usize,
+    /// The name between the braces.
+    pub name: &'text str,
+}
+
+/// Every `{{name}}` placeholder of `text`, in order. Braces around anything
+/// but a name (`format!("{{}}")`) are left alone.
+#[must_use]
+pub fn placeholders(text: &str) -> Vec<Placeholder<'_>> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(open) = text.get(from..).and_then(|rest| rest.find("{{")).map(|i| i.saturating_add(from)) {
+        let inner = open.saturating_add(2);
+        let Some(close) = text.get(inner..).and_then(|rest| rest.find("}}")).map(|i| i.saturating_add(inner)) else {
+            break;
+        };
+        let name = text.get(inner..close).unwrap_or("");
+        if is_name(name) {
+            found.push(Placeholder { start: open, end: close.saturating_add(2), name });
+            from = close.saturating_add(2);
+        } else {
+            from = inner;
+        }
+    }
+    found
+}
+
+/// `body` with the placeholders named in `values` filled in; the others stay.
+#[must_use]
+pub fn expand(body: &str, values: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut copied = 0;
+    for p in placeholders(body) {
+        if let Some(value) = values.get(p.name) {
+            out.push_str(body.get(copied..p.start).unwrap_or(""));
+            out.push_str(value);
+            copied = p.end;
+        }
+    }
+    out.push_str(body.get(copied..).unwrap_or(""));
+    out
+}
+
+/// Names of the placeholders left in `text`, each once.
+#[must_use]
+pub fn unfilled(text: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for p in placeholders(text) {
+        if !names.contains(&p.name) {
+            names.push(p.name);
+        }
+    }
+    names
+}
+
+/// The `(old_string, new_string)` of an `Edit` inserting `text` before
+/// 1-based `line` of `content`.
+///
+/// One past the last line appends. The old string grows around the spot
+/// until its first occurrence is there.
+///
+/// # Errors
+///
+/// An empty file, or a line outside it.
+pub fn insertion(content: &str, line: usize, text: &str) -> Result<(String, String), String> {
+    let starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|m| m.0.saturating_add(1)))
+        .filter(|&s| s < content.len())
+        .collect();
+    if starts.is_empty() {
+        return Err("the file is empty; write the snippet with Write instead".to_owned());
+    }
+    let index = line.saturating_sub(1);
+    if line == 0 || index > starts.len() {
+        return Err(format!("the file has {} lines, no line {line}", starts.len()));
+    }
+    let at = starts.get(index).copied().unwrap_or(content.len());
+    let mut block = text.to_owned();
+    if !block.ends_with('\n') {
+        block.push('\n');
+    }
+    if at == content.len() && !content.ends_with('\n') {
+        block.insert(0, '\n');
+    }
+    let offset = |i: usize| starts.get(i).copied().unwrap_or(content.len());
+    for radius in 1..=starts.len() {
+        let (from, to) = (offset(index.saturating_sub(radius)), offset(index.saturating_add(radius)));
+        let window = content.get(from..to).unwrap_or("");
+        if !window.is_empty() && content.find(window) == Some(from) {
+            let new = format!("{}{block}{}", content.get(from..at).unwrap_or(""), content.get(at..to).unwrap_or(""));
+            return Ok((window.to_owned(), new));
+        }
+    }
+    Err("no unique anchor for the insertion".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_expand_by_name() {
+        let body = "fn {{name}}() { format!(\"{{}}\"); {{name}}; {{body}} }";
+        assert_eq!(placeholders(body).iter().map(|p| p.name).collect::<Vec<_>>(), vec!["name", "name", "body"]);
+        let values = BTreeMap::from([("name".to_owned(), "check".to_owned())]);
+        let expanded = expand(body, &values);
+        assert_eq!(expanded, "fn check() { format!(\"{{}}\"); check; {{body}} }");
+        assert_eq!(unfilled(&expanded), vec!["body"]);
+        let library = load(Path::new("/nonexistent"));
+        assert!(library.get("rust-test-module").is_some_and(|s| unfilled(&s.body) == vec!["test"]));
+        assert!(library.snippets.iter().all(|s| unfilled(&s.body).iter().all(|n| s.params.contains_key(*n))));
+    }
+
+    #[test]
+    fn insertions_anchor_on_a_unique_window() {
+        let content = "a\n}\nb\n}\n";
+        let apply = |line: usize| {
+            insertion(content, line, "x").map(|(old, new)| content.replacen(&old, &new, 1)).unwrap_or_default()
+        };
+        assert_eq!(apply(1), "x\na\n}\nb\n}\n");
+        assert_eq!(apply(4), "a\n}\nb\nx\n}\n");
+        assert_eq!(apply(5), "a\n}\nb\n}\nx\n");
+        assert!(insertion(content, 6, "x").is_err_and(|e| e.contains("no line 6")));
+        assert!(insertion("", 1, "x").is_err_and(|e| e.contains("empty")));
+        assert_eq!(insertion("a", 2, "x").map(|(_, new)| new), Ok("a\nx\n".to_owned()));
+    }
+}
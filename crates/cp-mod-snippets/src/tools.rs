@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::store::{self, Library, Snippet};
+
+/// One line per snippet: id, name, language, description and placeholders.
+fn listing(library: &Library) -> String {
+    let mut out = format!("{} snippet(s):\n", library.snippets.len());
+    for s in &library.snippets {
+        let language = s.language.as_deref().map(|l| format!(" ({l})")).unwrap_or_default();
+        let params = if s.params.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", s.params.keys().map(String::as_str).collect::<Vec<_>>().join(", "))
+        };
+        let _r = writeln!(out, "- {}: {}{language}{params}; {}", s.id, s.name, s.description);
+    }
+    for e in &library.errors {
+        let _r = writeln!(out, "ignored {e}");
+    }
+    out
+}
+
+/// The `values` input as a name-to-value map.
+fn values(tool: &ToolUse) -> BTreeMap<String, String> {
+    tool.input
+        .get("values")
+        .and_then(|v| v.as_array())
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|e| {
+                    let name = e.get("name")?.as_str()?;
+                    let value = e.get("value")?.as_str()?;
+                    Some((name.to_owned(), value.to_owned()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `snippet`'s body with `values` filled in, or what is still missing.
+fn fill(snippet: &Snippet, values: &BTreeMap<String, String>) -> Result<String, String> {
+    let text = store::expand(&snippet.body, values);
+    let missing = store::unfilled(&text);
+    if missing.is_empty() {
+        return Ok(text);
+    }
+    let described: Vec<String> = missing
+        .iter()
+        .map(|name| snippet.params.get(*name).map_or_else(|| (*name).to_owned(), |d| format!("{name} ({d})")))
+        .collect();
+    Err(format!("'{}' needs values for: {}", snippet.id, described.join(", ")))
+}
+
+/// Execute `snippet_get`: list the snippets, or return one filled in.
+pub(crate) fn execute_get(tool: &ToolUse) -> ToolResult {
+    let library = store::load(Path::new("."));
+    let Some(id) = tool.input.get("id").and_then(|v| v.as_str()).map(str::trim).filter(|id| !id.is_empty()) else {
+        return ToolResult::new(tool.id.clone(), listing(&library), false);
+    };
+    let Some(snippet) = library.get(id) else {
+        return ToolResult::new(tool.id.clone(), format!("Error: no snippet '{id}'\n{}", listing(&library)), true);
+    };
+    match fill(snippet, &values(tool)) {
+        Ok(text) => ToolResult::new(tool.id.clone(), text, false),
+        Err(e) => ToolResult::new(tool.id.clone(), format!("Error: {e}"), true),
+    }
+}
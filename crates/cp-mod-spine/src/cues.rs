@@ -0,0 +1,272 @@
+//! Completion cues — bell, status-bar flash, or shell command on spine events.
+//!
+//! Events are raised where they happen (stream finished, guard rail blocked,
+//! stream failed, guarded edit awaiting approval) and queued; the main loop
+//! fires them once per tick via [`fire_pending`]. Each event has its own
+//! enable flags, all off by default. Only the user sets them, with `/cues`
+//! (see [`set_from_command`]): a cue may run a shell command, which no tool
+//! may choose.
+
+use std::io::Write as _;
+
+use cp_base::panels::now_ms;
+use cp_base::state::runtime::State;
+use serde::{Deserialize, Serialize};
+
+use crate::types::SpineState;
+
+/// How long the status bar stays highlighted after a flash cue.
+pub const FLASH_MS: u64 = 1_500;
+
+/// Spine events that can trigger a cue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CueEvent {
+    /// The assistant finished its turn.
+    StreamDone,
+    /// A guard rail blocked auto-continuation — the user has to step in.
+    Blocked,
+    /// The stream failed after all retries.
+    Error,
+    /// A guarded edit waits for the user's approval.
+    ApprovalNeeded,
+}
+
+/// Every event, in display order.
+const EVENTS: [CueEvent; 4] = [CueEvent::StreamDone, CueEvent::Blocked, CueEvent::Error, CueEvent::ApprovalNeeded];
+
+impl CueEvent {
+    /// Wire name (`/cues` argument, `CP_CUE_EVENT` for the command).
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::StreamDone => "stream_done",
+            Self::Blocked => "blocked",
+            Self::Error => "error",
+            Self::ApprovalNeeded => "approval_needed",
+        }
+    }
+}
+
+/// Which cues fire for one event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CueSet {
+    /// Ring the terminal bell.
+    #[serde(default)]
+    pub bell: bool,
+    /// Briefly highlight the status bar.
+    #[serde(default)]
+    pub flash: bool,
+    /// Run the configured shell command.
+    #[serde(default)]
+    pub command: bool,
+}
+
+impl CueSet {
+    /// Whether any cue is enabled.
+    #[must_use]
+    pub const fn any(self) -> bool {
+        self.bell || self.flash || self.command
+    }
+}
+
+/// Per-event cue configuration (persisted with the spine module data).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CueConfig {
+    /// Cues for [`CueEvent::StreamDone`].
+    #[serde(default)]
+    pub stream_done: CueSet,
+    /// Cues for [`CueEvent::Blocked`].
+    #[serde(default)]
+    pub blocked: CueSet,
+    /// Cues for [`CueEvent::Error`].
+    #[serde(default)]
+    pub error: CueSet,
+    /// Cues for [`CueEvent::ApprovalNeeded`].
+    #[serde(default)]
+    pub approval_needed: CueSet,
+    /// Shell command run by `command` cues (`sh -c`, with `CP_CUE_EVENT` set).
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Events raised since the last tick, fired by [`fire_pending`].
+    #[serde(skip)]
+    pub pending: Vec<CueEvent>,
+}
+
+impl CueConfig {
+    /// The cue set for `event`.
+    #[must_use]
+    pub const fn for_event(&self, event: CueEvent) -> CueSet {
+        match event {
+            CueEvent::StreamDone => self.stream_done,
+            CueEvent::Blocked => self.blocked,
+            CueEvent::Error => self.error,
+            CueEvent::ApprovalNeeded => self.approval_needed,
+        }
+    }
+
+    /// One-line summary of enabled cues, e.g. `stream_done: bell+flash`, or `off`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = EVENTS
+            .into_iter()
+            .filter_map(|event| {
+                let set = self.for_event(event);
+                let kinds: Vec<&str> = [(set.bell, "bell"), (set.flash, "flash"), (set.command, "command")]
+                    .into_iter()
+                    .filter_map(|(on, name)| on.then_some(name))
+                    .collect();
+                (!kinds.is_empty()).then(|| format!("{}: {}", event.name(), kinds.join("+")))
+            })
+            .collect();
+        if parts.is_empty() { "off".to_owned() } else { parts.join(", ") }
+    }
+
+    /// Mutable cue set for `event`.
+    pub const fn for_event_mut(&mut self, event: CueEvent) -> &mut CueSet {
+        match event {
+            CueEvent::StreamDone => &mut self.stream_done,
+            CueEvent::Blocked => &mut self.blocked,
+            CueEvent::Error => &mut self.error,
+            CueEvent::ApprovalNeeded => &mut self.approval_needed,
+        }
+    }
+}
+
+/// Queue `event` if any cue is enabled for it.
+pub fn raise(state: &mut State, event: CueEvent) {
+    let cues = &mut SpineState::get_mut(state).cues;
+    if cues.for_event(event).any() {
+        cues.pending.push(event);
+    }
+}
+
+/// `sh -c command` with the event name in `CP_CUE_EVENT`.
+fn shell(command: &str, event: CueEvent) -> std::process::Command {
+    let mut cmd = std::process::Command::new("sh");
+    let _cmd = cmd.args(["-c", command]).env("CP_CUE_EVENT", event.name());
+    cmd
+}
+
+/// Run `command` in the background with the event name in its environment.
+fn spawn_command(command: &str, event: CueEvent) {
+    let spawned = shell(command, event)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+    if let Ok(mut child) = spawned {
+        // Reap in the background so the command never becomes a zombie.
+        drop(std::thread::spawn(move || child.wait()));
+    }
+}
+
+/// Fire every queued cue, and end an expired status-bar flash.
+pub fn fire_pending(state: &mut State) {
+    let flash_ms = state.flags.overlays.cue_flash_ms;
+    if flash_ms > 0 && now_ms().saturating_sub(flash_ms) >= FLASH_MS {
+        state.flags.overlays.cue_flash_ms = 0;
+        state.flags.ui.dirty = true;
+    }
+    let cues = &mut SpineState::get_mut(state).cues;
+    if cues.pending.is_empty() {
+        return;
+    }
+    let events = std::mem::take(&mut cues.pending);
+    let command = cues.command.clone();
+    let sets: Vec<(CueEvent, CueSet)> = events.into_iter().map(|e| (e, cues.for_event(e))).collect();
+    for (event, set) in sets {
+        if set.bell {
+            let mut out = std::io::stdout();
+            let _r = out.write_all(b"\x07").and_then(|()| out.flush());
+        }
+        if set.flash {
+            state.flags.overlays.cue_flash_ms = now_ms();
+            state.flags.ui.dirty = true;
+        }
+        if let Some(cmd) = command.as_deref().filter(|_| set.command) {
+            spawn_command(cmd, event);
+        }
+    }
+}
+
+/// Cue set from `bell+flash+command` (any subset, `+` or `,` separated) or
+/// `off`.
+fn parse_set(kinds: &str) -> Result<CueSet, String> {
+    let mut set = CueSet::default();
+    for kind in kinds.split(['+', ',']).filter(|k| !k.is_empty() && *k != "off") {
+        match kind {
+            "bell" => set.bell = true,
+            "flash" => set.flash = true,
+            "command" => set.command = true,
+            _ => return Err(format!("'{kind}': use bell, flash, command or off")),
+        }
+    }
+    Ok(set)
+}
+
+/// Apply `/cues` arguments (user-side only): `<event> bell+flash+command|off`
+/// or `command <shell command>|off`. The message to show the user.
+///
+/// # Errors
+///
+/// Unknown event or cue kind, or missing arguments.
+pub fn set_from_command(state: &mut State, args: &str) -> Result<String, String> {
+    let usage = || {
+        let events: Vec<&str> = EVENTS.iter().map(|e| e.name()).collect();
+        format!("usage: /cues <{}> bell+flash+command|off, or /cues command <shell>|off", events.join("|"))
+    };
+    let (head, rest) = args.split_once(char::is_whitespace).map_or((args, ""), |(h, r)| (h, r.trim()));
+    let cues = &mut SpineState::get_mut(state).cues;
+    if head == "command" && !rest.is_empty() {
+        cues.command = (rest != "off").then(|| rest.to_owned());
+        return Ok(format!("Cue command: {}", cues.command.as_deref().unwrap_or("none")));
+    }
+    let event = EVENTS.into_iter().find(|e| e.name() == head).filter(|_| !rest.is_empty()).ok_or_else(usage)?;
+    *cues.for_event_mut(event) = parse_set(rest)?;
+    Ok(format!("Cues: {}", cues.summary()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A state with the spine config.
+    fn spine() -> State {
+        let mut state = State::default();
+        state.set_ext(SpineState::new());
+        state
+    }
+
+    #[test]
+    fn only_events_with_cues_are_queued_and_fired() {
+        let mut state = spine();
+        let set = set_from_command(&mut state, "approval_needed flash");
+        assert_eq!(set, Ok("Cues: approval_needed: flash".to_owned()));
+        raise(&mut state, CueEvent::StreamDone);
+        raise(&mut state, CueEvent::ApprovalNeeded);
+        assert_eq!(SpineState::get(&state).cues.pending, [CueEvent::ApprovalNeeded]);
+        fire_pending(&mut state);
+        assert!(SpineState::get(&state).cues.pending.is_empty());
+        assert!(state.flags.overlays.cue_flash_ms > 0);
+    }
+
+    #[test]
+    fn the_command_gets_the_event_name() {
+        let output = shell("printf %s \"$CP_CUE_EVENT\"", CueEvent::ApprovalNeeded).output();
+        assert_eq!(output.ok().map(|o| o.stdout), Some(b"approval_needed".to_vec()));
+    }
+
+    #[test]
+    fn cues_are_set_by_command_words() {
+        let mut state = spine();
+        assert_eq!(
+            set_from_command(&mut state, "command notify-send done"),
+            Ok("Cue command: notify-send done".into())
+        );
+        assert_eq!(set_from_command(&mut state, "error bell+command").ok(), Some("Cues: error: bell+command".into()));
+        assert_eq!(set_from_command(&mut state, "error off").ok(), Some("Cues: off".into()));
+        assert!(set_from_command(&mut state, "error siren").is_err_and(|e| e.contains("'siren'")));
+        assert!(set_from_command(&mut state, "finished bell").is_err_and(|e| e.starts_with("usage")));
+    }
+}
@@ -5,6 +5,9 @@
 //! - UserMessage / ReloadResume → synthetic message or relaunch
 //! - Custom (from watchers, coucou, context threshold) → synthetic message
 //!
+//! One trigger bypasses notifications: an answer cut off by `max_tokens` is
+//! resumed in place (up to `max_token_continuations` times in a row).
+//!
 //! No more AutoContinuation trait — all triggers go through the watcher → notification pipeline.
 
 use cp_base::config::INJECTIONS;
@@ -88,6 +91,7 @@ fn check_guard_rails(state: &mut State) -> Option<String> {
                 source_tag,
                 format!("Auto-continuation blocked by {}: {}", guard.name(), reason),
             ));
+            crate::cues::raise(state, crate::cues::CueEvent::Blocked);
         }
         // Persistent watchers recreate notifications next poll; re-evaluated then.
         SpineState::mark_all_unprocessed_as_blocked(state);
@@ -114,11 +118,22 @@ pub fn check_spine(state: &mut State) -> SpineDecision {
         return SpineDecision::Idle;
     }
 
+    // Provider unreachable: notifications (user messages included) queue until
+    // the connectivity monitor sees it again, then dispatch as usual.
+    if state.flags.lifecycle.offline_since_ms.is_some() {
+        return SpineDecision::Idle;
+    }
+
     // Backoff after consecutive failed continuations.
     if in_backoff(state) {
         return SpineDecision::Idle;
     }
 
+    // Answer cut off by max_tokens — splice a continuation into it
+    if let Some(decision) = check_truncated_answer(state) {
+        return decision;
+    }
+
     // Nothing to do if no unprocessed notifications
     if !SpineState::has_unprocessed_notifications(state) {
         return SpineDecision::Idle;
@@ -150,6 +165,35 @@ pub fn check_spine(state: &mut State) -> SpineDecision {
     SpineDecision::Continue(action)
 }
 
+/// Decide whether the last answer, cut off by `max_tokens`, should be resumed.
+///
+/// Only a text answer can be spliced — a truncated tool call has no usable
+/// input. Any other stop reason resets the chain counter.
+fn check_truncated_answer(state: &mut State) -> Option<SpineDecision> {
+    let truncated = state.last_stop_reason.as_deref() == Some("max_tokens");
+    let cfg = SpineState::get(state).config;
+    if !truncated {
+        if state.last_stop_reason.is_some() && cfg.token_continuation_count > 0 {
+            SpineState::get_mut(state).config.token_continuation_count = 0;
+        }
+        return None;
+    }
+    let resumable = state
+        .messages
+        .last()
+        .is_some_and(|m| m.role == "assistant" && m.tool_uses.is_empty() && !m.content.trim().is_empty());
+    if !resumable || cfg.token_continuation_count >= cfg.max_token_continuations {
+        return None;
+    }
+    if let Some(reason) = check_guard_rails(state) {
+        return Some(SpineDecision::Blocked(reason));
+    }
+    let count = &mut SpineState::get_mut(state).config.token_continuation_count;
+    *count = count.saturating_add(1);
+    state.touch_panel(Kind::SPINE);
+    Some(SpineDecision::Continue(ContinuationAction::Splice))
+}
+
 /// Build a `ContinuationAction` directly from unprocessed notifications.
 ///
 /// Logic:
@@ -236,5 +280,238 @@ pub fn apply_continuation(state: &mut State, action: ContinuationAction) -> bool
             state.begin_streaming();
             true
         }
+        ContinuationAction::Splice => {
+            // A prefill turn must not end in whitespace; the model re-emits it.
+            let existing = state.messages.last_mut().map_or(0, |msg| {
+                msg.content.truncate(msg.content.trim_end().len());
+                cp_base::state::context::estimate_tokens(&msg.content)
+            });
+            state.begin_streaming();
+            // Already counted: only the continuation's tokens are new.
+            state.streaming_estimated_tokens = existing;
+            true
+        }
+    }
+}
+
+/// Length of the trailing run of identical tool calls (same tool, same input).
+///
+/// The run is counted since the user last spoke. Tool results and injected `/* … */` messages do
+/// not break the run; a different call or a real user message does.
+#[must_use]
+pub fn identical_tool_streak(state: &State) -> usize {
+    let mut calls = state
+        .messages
+        .iter()
+        .rev()
+        .take_while(|m| {
+            m.role != "user"
+                || m.msg_type == cp_base::state::data::message::MsgKind::ToolResult
+                || m.content.trim_start().starts_with("/*")
+        })
+        .flat_map(|m| m.tool_uses.iter().rev());
+    let Some(last) = calls.next() else { return 0 };
+    calls.take_while(|t| t.name == last.name && t.input == last.input).count().saturating_add(1)
+}
+
+/// The identical-call streak once it reaches `max_identical_tool_calls`
+/// (`None` below the limit or when the loop guard is disabled).
+#[must_use]
+pub fn tool_loop_streak(state: &State) -> Option<usize> {
+    let max = SpineState::get(state).config.max_identical_tool_calls;
+    let streak = identical_tool_streak(state);
+    (max > 0 && streak >= max).then_some(streak)
+}
+
+/// Notify the agent that it is looping on `tool` and must change strategy.
+///
+/// The notice reaches it through the normal auto-continuation path; if it
+/// repeats the call anyway, the `RepeatedToolCall` guard rail stops and
+/// waits for the user.
+pub fn notify_tool_loop(state: &mut State, tool_name: &str, repeats: usize) {
+    let content =
+        INJECTIONS.spine.tool_loop.trim().replace("{tool}", tool_name).replace("{count}", &repeats.to_string());
+    drop(SpineState::create_notification(state, NotificationType::Custom, "loop_guard".to_owned(), content));
+}
+
+/// True once `max_tool_turns` tool turns ran without human input: further
+/// tool calls are refused until the user speaks.
+#[must_use]
+pub fn check_in_due(state: &State) -> bool {
+    let cfg = &SpineState::get(state).config;
+    cfg.max_tool_turns.is_some_and(|max| cfg.tool_turn_count >= max)
+}
+
+/// Count one executed tool batch toward `max_tool_turns`.
+///
+/// The turn that reaches the cap posts the check-in notice, telling the agent
+/// to stop and summarize its progress. Returns `true` when the agent already
+/// had that notice and called tools anyway, so the caller ends the turn.
+pub fn record_tool_turn(state: &mut State) -> bool {
+    let cfg = &mut SpineState::get_mut(state).config;
+    cfg.tool_turn_count = cfg.tool_turn_count.saturating_add(1);
+    let (turns, max) = (cfg.tool_turn_count, cfg.max_tool_turns);
+    let Some(limit) = max else { return false };
+    if turns == limit {
+        let content = INJECTIONS.spine.check_in.trim().replace("{count}", &turns.to_string());
+        drop(SpineState::create_notification(state, NotificationType::Custom, "check_in".to_owned(), content));
+    }
+    turns > limit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::data::message::{Message, ToolUseRecord};
+    use serde_json::json;
+
+    /// A state with the spine config and `messages`.
+    fn conversation(messages: Vec<Message>) -> State {
+        let mut state = State::default();
+        state.set_ext(SpineState::new());
+        state.messages = messages;
+        state
+    }
+
+    /// A user message saying `text`.
+    fn user(text: &str) -> Message {
+        Message::new_user("U1".to_owned(), "UID_1_U".to_owned(), text.to_owned(), 1)
+    }
+
+    /// A tool-call message for `name` with `{"pattern": pattern}`.
+    fn call(name: &str, pattern: &str) -> Message {
+        let record = ToolUseRecord::new("toolu".to_owned(), name.to_owned(), json!({"pattern": pattern}));
+        Message::new_tool_call("T1".to_owned(), None, vec![record])
+    }
+
+    /// An empty tool-result message.
+    fn result() -> Message {
+        Message::new_tool_result("R1".to_owned(), None, vec![])
+    }
+
+    #[test]
+    fn identical_calls_count_across_results_and_injected_notes() {
+        let state = conversation(vec![
+            user("fix it"),
+            call("grep", "x"),
+            result(),
+            user("/* auto-continue */"),
+            call("grep", "x"),
+            result(),
+            call("grep", "x"),
+        ]);
+        assert_eq!(identical_tool_streak(&state), 3);
+    }
+
+    #[test]
+    fn different_input_or_a_user_message_breaks_the_streak() {
+        let changed = conversation(vec![call("grep", "x"), result(), call("grep", "x"), result(), call("grep", "y")]);
+        assert_eq!(identical_tool_streak(&changed), 1);
+        let renamed = conversation(vec![call("grep", "x"), result(), call("glob", "x")]);
+        assert_eq!(identical_tool_streak(&renamed), 1);
+        let spoke = conversation(vec![call("grep", "x"), result(), user("try again"), call("grep", "x")]);
+        assert_eq!(identical_tool_streak(&spoke), 1);
+        assert_eq!(identical_tool_streak(&conversation(vec![user("hi")])), 0);
+    }
+
+    #[test]
+    fn tool_loop_streak_starts_at_the_threshold() {
+        let mut state = conversation(vec![call("grep", "x"), result(), call("grep", "x")]);
+        assert_eq!(tool_loop_streak(&state), None);
+        state.messages.extend([result(), call("grep", "x")]);
+        assert_eq!(tool_loop_streak(&state), Some(3));
+        SpineState::get_mut(&mut state).config.max_identical_tool_calls = 0;
+        assert_eq!(tool_loop_streak(&state), None);
+    }
+
+    /// An assistant message saying `text`.
+    fn answer(text: &str) -> Message {
+        let mut msg = Message::new_assistant("A1".to_owned(), "UID_2_A".to_owned());
+        msg.content = text.to_owned();
+        msg
+    }
+
+    /// Whether `decision` splices a continuation into the last answer.
+    const fn splices(decision: Option<&SpineDecision>) -> bool {
+        matches!(decision, Some(SpineDecision::Continue(ContinuationAction::Splice)))
+    }
+
+    #[test]
+    fn truncated_answers_are_resumed_up_to_the_cap() {
+        let mut state = conversation(vec![user("explain"), answer("The first half")]);
+        state.last_stop_reason = Some("max_tokens".to_owned());
+        for _ in 0..3usize {
+            assert!(splices(check_truncated_answer(&mut state).as_ref()));
+        }
+        assert!(check_truncated_answer(&mut state).is_none());
+        assert_eq!(SpineState::get(&state).config.token_continuation_count, 3);
+    }
+
+    #[test]
+    fn truncated_tool_calls_are_not_resumed_and_other_stops_reset_the_chain() {
+        let mut state = conversation(vec![user("explain"), call("grep", "x")]);
+        state.last_stop_reason = Some("max_tokens".to_owned());
+        SpineState::get_mut(&mut state).config.token_continuation_count = 2;
+        assert!(check_truncated_answer(&mut state).is_none());
+        assert_eq!(SpineState::get(&state).config.token_continuation_count, 2);
+        state.last_stop_reason = Some("end_turn".to_owned());
+        assert!(check_truncated_answer(&mut state).is_none());
+        assert_eq!(SpineState::get(&state).config.token_continuation_count, 0);
+    }
+
+    #[test]
+    fn splicing_trims_the_prefill_and_counts_only_new_tokens() {
+        let mut state = conversation(vec![answer("The first half \n")]);
+        assert!(apply_continuation(&mut state, ContinuationAction::Splice));
+        assert_eq!(state.messages.last().map(|m| m.content.as_str()), Some("The first half"));
+        assert_eq!(state.streaming_estimated_tokens, cp_base::state::context::estimate_tokens("The first half"));
+    }
+
+    #[test]
+    fn notifications_are_held_while_offline_and_dispatched_once_back() {
+        let mut state = conversation(vec![user("hi")]);
+        drop(SpineState::create_notification(
+            &mut state,
+            NotificationType::UserMessage,
+            "user".to_owned(),
+            "hi".to_owned(),
+        ));
+        state.flags.lifecycle.offline_since_ms = Some(1);
+        assert!(matches!(check_spine(&mut state), SpineDecision::Idle));
+        assert!(SpineState::has_unprocessed_notifications(&state));
+        state.flags.lifecycle.offline_since_ms = None;
+        assert!(matches!(check_spine(&mut state), SpineDecision::Continue(_)));
+    }
+
+    #[test]
+    fn check_in_is_due_only_at_the_tool_turn_cap() {
+        let mut state = conversation(vec![user("go")]);
+        assert!(!check_in_due(&state));
+        SpineState::get_mut(&mut state).config.max_tool_turns = Some(2);
+        assert!(!record_tool_turn(&mut state));
+        assert!(!check_in_due(&state));
+        assert!(!record_tool_turn(&mut state));
+        assert!(check_in_due(&state));
+    }
+
+    #[test]
+    fn the_cap_posts_one_notice_and_ends_the_turn_after_it() {
+        let mut state = conversation(vec![user("go")]);
+        SpineState::get_mut(&mut state).config.max_tool_turns = Some(1);
+        assert!(!record_tool_turn(&mut state));
+        let notices = |s: &State| SpineState::get(s).notifications.iter().filter(|n| n.source == "check_in").count();
+        assert_eq!(notices(&state), 1);
+        assert!(record_tool_turn(&mut state));
+        assert_eq!(notices(&state), 1);
+    }
+
+    #[test]
+    fn uncapped_tool_turns_are_counted_without_a_notice() {
+        let mut state = conversation(vec![user("go")]);
+        for _ in 0..5usize {
+            assert!(!record_tool_turn(&mut state));
+        }
+        assert_eq!(SpineState::get(&state).config.tool_turn_count, 5);
+        assert!(SpineState::get(&state).notifications.is_empty());
     }
 }
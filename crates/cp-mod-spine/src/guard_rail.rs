@@ -9,8 +9,9 @@ use crate::types::SpineState;
 /// If any guard rail returns `should_block() == true`, no auto-continuation
 /// will happen — the system will stop and wait for human input.
 ///
-/// All guard rails are parameterized via `SpineConfig` and are nullable
-/// (disabled by default).
+/// All guard rails are parameterized via `SpineConfig`. The numeric limits
+/// are nullable (disabled by default); the repeated-call guard is on by
+/// default and disabled with 0.
 pub(crate) trait GuardRailStopLogic: Send + Sync {
     /// Human-readable name for logging/debugging
     fn name(&self) -> &'static str;
@@ -28,8 +29,14 @@ pub(crate) trait GuardRailStopLogic: Send + Sync {
 ///
 /// All guard rails are checked — if ANY blocks, continuation is prevented.
 pub(crate) fn all_guard_rails() -> &'static [&'static dyn GuardRailStopLogic] {
-    static GUARD_RAILS: &[&dyn GuardRailStopLogic] =
-        &[&MaxOutputTokensGuard, &MaxDurationGuard, &MaxMessagesGuard, &MaxAutoRetriesGuard];
+    static GUARD_RAILS: &[&dyn GuardRailStopLogic] = &[
+        &MaxOutputTokensGuard,
+        &MaxDurationGuard,
+        &MaxMessagesGuard,
+        &MaxAutoRetriesGuard,
+        &MaxToolTurnsGuard,
+        &RepeatedToolCallGuard,
+    ];
     GUARD_RAILS
 }
 
@@ -150,3 +157,58 @@ impl GuardRailStopLogic for MaxAutoRetriesGuard {
         )
     }
 }
+
+// ============================================================================
+// Implementation: MaxToolTurnsGuard
+// ============================================================================
+
+/// Block once the agent has used up its tool turns without human input.
+/// The agent was told to post a progress summary; only a user message
+/// (which resets the counter) lets work continue.
+pub(crate) struct MaxToolTurnsGuard;
+
+impl GuardRailStopLogic for MaxToolTurnsGuard {
+    fn name(&self) -> &'static str {
+        "MaxToolTurns"
+    }
+
+    fn should_block(&self, state: &State) -> bool {
+        crate::engine::check_in_due(state)
+    }
+
+    fn block_reason(&self, state: &State) -> String {
+        format!(
+            "Tool-turn limit reached: {} / {} turns \u{2014} waiting for the user",
+            SpineState::get(state).config.tool_turn_count,
+            SpineState::get(state).config.max_tool_turns.unwrap_or(0)
+        )
+    }
+}
+
+// ============================================================================
+// Implementation: RepeatedToolCallGuard
+// ============================================================================
+
+/// Block if the agent kept repeating the same tool call after the loop
+/// notice: the streak went past `max_identical_tool_calls`, so the notice
+/// did not help and the user has to step in.
+pub(crate) struct RepeatedToolCallGuard;
+
+impl GuardRailStopLogic for RepeatedToolCallGuard {
+    fn name(&self) -> &'static str {
+        "RepeatedToolCall"
+    }
+
+    fn should_block(&self, state: &State) -> bool {
+        let max = SpineState::get(state).config.max_identical_tool_calls;
+        max > 0 && crate::engine::identical_tool_streak(state) > max
+    }
+
+    fn block_reason(&self, state: &State) -> String {
+        format!(
+            "Same tool call repeated {} times with identical input (limit {})",
+            crate::engine::identical_tool_streak(state),
+            SpineState::get(state).config.max_identical_tool_calls
+        )
+    }
+}
@@ -2,9 +2,12 @@
 //!
 //! Three tools: `notification_mark_processed`, `spine_configure`, and `coucou`
 //! (timer/datetime scheduling). Drives the autonomous continuation loop and
-//! manages guard rails (max tokens, duration, messages, retries).
+//! manages guard rails (max tokens, duration, messages, retries, tool turns,
+//! repeated tool calls).
 
 pub(crate) mod coucou;
+/// Completion cues: bell, status-bar flash, or shell command on spine events.
+pub mod cues;
 /// Auto-continuation engine: `should_auto_continue()`, message injection, guard rail checks.
 pub mod engine;
 /// Guard rail implementations: safety limits for auto-continuation.
@@ -160,6 +163,7 @@ impl Module for SpineModule {
             "notifications": to_save,
             "next_notification_id": ss.next_notification_id,
             "spine_config": ss.config,
+            "cues": ss.cues,
             "pending_coucous": pending_coucous,
         })
     }
@@ -177,6 +181,11 @@ impl Module for SpineModule {
         {
             SpineState::get_mut(state).config = v;
         }
+        if let Some(cues) = data.get("cues")
+            && let Ok(v) = serde_json::from_value(cues.clone())
+        {
+            SpineState::get_mut(state).cues = v;
+        }
         // Prune stale processed notifications on load too
         prune_notifications(&mut SpineState::get_mut(state).notifications);
 
@@ -224,6 +233,11 @@ impl Module for SpineModule {
                 .param("max_duration_secs", ParamType::Integer, false)
                 .param("max_messages", ParamType::Integer, false)
                 .param("max_auto_retries", ParamType::Integer, false)
+                .param("max_tool_turns", ParamType::Integer, false)
+                .param("max_token_continuations", ParamType::Integer, false)
+                .param("max_identical_tool_calls", ParamType::Integer, false)
+                .param("context_warn_pct", ParamType::Integer, false)
+                .param("panel_alert_pct", ParamType::Integer, false)
                 .param("reset_counters", ParamType::Boolean, false)
                 .build(),
             ToolDefinition::from_yaml("coucou", t)
@@ -298,6 +312,8 @@ impl Module for SpineModule {
         // Human input resets auto-continuation counters — human is back in the loop
         let ss = SpineState::get_mut(state);
         ss.config.auto_continuation_count = 0;
+        ss.config.token_continuation_count = 0;
+        ss.config.tool_turn_count = 0;
         ss.config.autonomous_start_ms = None;
         ss.config.user_stopped = false;
         // Reset error backoff — human can immediately trigger a new stream
@@ -343,11 +359,7 @@ fn visualize_spine_output(content: &str, width: usize) -> Vec<cp_render::Block>
             } else {
                 Semantic::Default
             };
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
             Block::Line(vec![Span::styled(display, semantic)])
         })
         .collect()
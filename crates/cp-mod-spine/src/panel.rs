@@ -1,8 +1,9 @@
 use crossterm::event::KeyEvent;
 
-use cp_base::panels::{ContextItem, Panel, now_ms, scroll_key_action};
+use cp_base::panels::{Panel, now_ms, scroll_key_action};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 use cp_base::state::watchers::WatcherRegistry;
 
@@ -36,9 +37,17 @@ fn push_config_summary(output: &mut String, state: &State) {
     let cfg = &SpineState::get(state).config;
     let _r1 = writeln!(output, "continue_until_todos_done: {}", cfg.continue_until_todos_done);
     let _r2 = writeln!(output, "auto_continuation_count: {}", cfg.auto_continuation_count);
+    let _r4 = writeln!(output, "max_token_continuations: {}", cfg.max_token_continuations);
+    let _r6 = writeln!(output, "max_identical_tool_calls: {}", cfg.max_identical_tool_calls);
+    let _r8 = writeln!(output, "context_warn_pct: {}", cfg.context_warn_pct);
+    let _r9 = writeln!(output, "panel_alert_pct: {}", cfg.panel_alert_pct);
+    let _r5 = writeln!(output, "cues: {}", SpineState::get(state).cues.summary());
     if let Some(v) = cfg.max_auto_retries {
         let _r3 = writeln!(output, "max_auto_retries: {v}");
     }
+    if let Some(v) = cfg.max_tool_turns {
+        let _r7 = writeln!(output, "tool_turns: {} / {v}", cfg.tool_turn_count);
+    }
 }
 
 /// Append the active-watchers list (mode, recurrence, age), if any.
@@ -244,6 +253,14 @@ fn push_config_blocks(blocks: &mut Vec<cp_render::Block>, state: &State) {
             vec![S::new(format!("{}", cfg.continue_until_todos_done))],
         ),
         (vec![S::muted("  auto_continuations".into())], vec![S::new(format!("{}", cfg.auto_continuation_count))]),
+        (vec![S::muted("  max_token_continuations".into())], vec![S::new(format!("{}", cfg.max_token_continuations))]),
+        (
+            vec![S::muted("  max_identical_tool_calls".into())],
+            vec![S::new(format!("{}", cfg.max_identical_tool_calls))],
+        ),
+        (vec![S::muted("  context_warn_pct".into())], vec![S::new(format!("{}%", cfg.context_warn_pct))]),
+        (vec![S::muted("  panel_alert_pct".into())], vec![S::new(format!("{}%", cfg.panel_alert_pct))]),
+        (vec![S::muted("  cues".into())], vec![S::new(SpineState::get(state).cues.summary())]),
     ]));
 }
 
@@ -283,6 +300,7 @@ const fn notification_type_semantic(nt: NotificationType) -> cp_render::Semantic
     match nt {
         NotificationType::UserMessage => cp_render::Semantic::Accent,
         NotificationType::ReloadResume | NotificationType::Custom => cp_render::Semantic::Code,
+        NotificationType::ContextHealth => cp_render::Semantic::Warning,
     }
 }
 
@@ -302,14 +320,7 @@ fn notification_row(n: &crate::types::Notification, semantic: cp_render::Semanti
     ]
 }
 
-/// Truncate a string to `max_len` characters, appending "…" if truncated.
+/// Flatten newlines and truncate to `max_len` display columns, appending "…" if truncated.
 fn truncate_str(s: &str, max_len: usize) -> String {
-    let trimmed = s.replace('\n', " ");
-    if trimmed.chars().count() <= max_len {
-        trimmed
-    } else {
-        let mut result: String = trimmed.chars().take(max_len.saturating_sub(1)).collect();
-        result.push('\u{2026}');
-        result
-    }
+    cp_base::ui::text::ellipsize(&s.replace('\n', " "), max_len, "\u{2026}")
 }
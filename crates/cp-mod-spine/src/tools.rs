@@ -86,26 +86,34 @@ fn zero_limit_error(tool: &ToolUse, key: &str) -> ToolResult {
     )
 }
 
-/// Apply all four guard-rail limit fields. Returns `Some(error)` on a zero value,
-/// else `None` after recording any changes into `changes`.
-fn apply_limits(tool: &ToolUse, state: &mut State, changes: &mut Vec<String>) -> Option<ToolResult> {
+/// Apply one count-valued guard-rail limit (`key`) to `slot`. Returns
+/// `Some(error)` on a zero value.
+fn apply_count_limit(
+    tool: &ToolUse,
+    key: &str,
+    slot: &mut Option<usize>,
+    changes: &mut Vec<String>,
+) -> Option<ToolResult> {
     use cp_base::cast::Safe as _;
-    let input = &tool.input;
-
-    match read_limit(input, "max_output_tokens") {
+    match read_limit(&tool.input, key) {
         LimitAction::Disable => {
-            SpineState::get_mut(state).config.max_output_tokens = None;
-            changes.push("max_output_tokens = disabled".to_owned());
+            *slot = None;
+            changes.push(format!("{key} = disabled"));
         }
         LimitAction::Set(n) => {
-            SpineState::get_mut(state).config.max_output_tokens = Some(n.to_usize());
-            changes.push(format!("max_output_tokens = {n}"));
+            *slot = Some(n.to_usize());
+            changes.push(format!("{key} = {n}"));
         }
-        LimitAction::Zero => return Some(zero_limit_error(tool, "max_output_tokens")),
+        LimitAction::Zero => return Some(zero_limit_error(tool, key)),
         LimitAction::Absent => {}
     }
+    None
+}
 
-    match read_limit(input, "max_duration_secs") {
+/// Apply all five guard-rail limit fields. Returns `Some(error)` on a zero value,
+/// else `None` after recording any changes into `changes`.
+fn apply_limits(tool: &ToolUse, state: &mut State, changes: &mut Vec<String>) -> Option<ToolResult> {
+    match read_limit(&tool.input, "max_duration_secs") {
         LimitAction::Disable => {
             SpineState::get_mut(state).config.max_duration_secs = None;
             changes.push("max_duration_secs = disabled".to_owned());
@@ -118,33 +126,38 @@ fn apply_limits(tool: &ToolUse, state: &mut State, changes: &mut Vec<String>) ->
         LimitAction::Absent => {}
     }
 
-    match read_limit(input, "max_messages") {
-        LimitAction::Disable => {
-            SpineState::get_mut(state).config.max_messages = None;
-            changes.push("max_messages = disabled".to_owned());
-        }
-        LimitAction::Set(n) => {
-            SpineState::get_mut(state).config.max_messages = Some(n.to_usize());
-            changes.push(format!("max_messages = {n}"));
-        }
-        LimitAction::Zero => return Some(zero_limit_error(tool, "max_messages")),
-        LimitAction::Absent => {}
-    }
+    let cfg = &mut SpineState::get_mut(state).config;
+    apply_count_limit(tool, "max_output_tokens", &mut cfg.max_output_tokens, changes)
+        .or_else(|| apply_count_limit(tool, "max_messages", &mut cfg.max_messages, changes))
+        .or_else(|| apply_count_limit(tool, "max_auto_retries", &mut cfg.max_auto_retries, changes))
+        .or_else(|| apply_count_limit(tool, "max_tool_turns", &mut cfg.max_tool_turns, changes))
+}
 
-    match read_limit(input, "max_auto_retries") {
-        LimitAction::Disable => {
-            SpineState::get_mut(state).config.max_auto_retries = None;
-            changes.push("max_auto_retries = disabled".to_owned());
-        }
-        LimitAction::Set(n) => {
-            SpineState::get_mut(state).config.max_auto_retries = Some(n.to_usize());
-            changes.push(format!("max_auto_retries = {n}"));
-        }
-        LimitAction::Zero => return Some(zero_limit_error(tool, "max_auto_retries")),
-        LimitAction::Absent => {}
+/// Apply the auto-continuation toggles (`continue_until_todos_done`,
+/// `max_token_continuations`, `max_identical_tool_calls`) and the context
+/// health thresholds, recording any changes into `changes`.
+fn apply_toggles(tool: &ToolUse, state: &mut State, changes: &mut Vec<String>) {
+    use cp_base::cast::Safe as _;
+    if let Some(v) = tool.input.get("continue_until_todos_done").and_then(serde_json::Value::as_bool) {
+        SpineState::get_mut(state).config.continue_until_todos_done = v;
+        changes.push(format!("continue_until_todos_done = {v}"));
+    }
+    if let Some(n) = tool.input.get("max_token_continuations").and_then(serde_json::Value::as_u64) {
+        SpineState::get_mut(state).config.max_token_continuations = n.to_usize();
+        changes.push(format!("max_token_continuations = {n}"));
+    }
+    if let Some(n) = tool.input.get("max_identical_tool_calls").and_then(serde_json::Value::as_u64) {
+        SpineState::get_mut(state).config.max_identical_tool_calls = n.to_usize();
+        changes.push(format!("max_identical_tool_calls = {n}"));
+    }
+    if let Some(n) = tool.input.get("context_warn_pct").and_then(serde_json::Value::as_u64) {
+        SpineState::get_mut(state).config.context_warn_pct = n.min(100).to_usize();
+        changes.push(format!("context_warn_pct = {}", n.min(100)));
+    }
+    if let Some(n) = tool.input.get("panel_alert_pct").and_then(serde_json::Value::as_u64) {
+        SpineState::get_mut(state).config.panel_alert_pct = n.min(100).to_usize();
+        changes.push(format!("panel_alert_pct = {}", n.min(100)));
     }
-
-    None
 }
 
 /// Execute the `spine_configure` tool — update spine auto-continuation and guard rail settings
@@ -152,10 +165,7 @@ pub(crate) fn execute_configure(tool: &ToolUse, state: &mut State) -> ToolResult
     let mut changes: Vec<String> = Vec::new();
 
     // === Auto-continuation toggles ===
-    if let Some(v) = tool.input.get("continue_until_todos_done").and_then(serde_json::Value::as_bool) {
-        SpineState::get_mut(state).config.continue_until_todos_done = v;
-        changes.push(format!("continue_until_todos_done = {v}"));
-    }
+    apply_toggles(tool, state, &mut changes);
 
     // === Guard rail limits (null disables, zero rejected) ===
     if let Some(err) = apply_limits(tool, state, &mut changes) {
@@ -165,6 +175,7 @@ pub(crate) fn execute_configure(tool: &ToolUse, state: &mut State) -> ToolResult
     // === Reset runtime counters ===
     if tool.input.get("reset_counters").and_then(serde_json::Value::as_bool) == Some(true) {
         SpineState::get_mut(state).config.auto_continuation_count = 0;
+        SpineState::get_mut(state).config.tool_turn_count = 0;
         SpineState::get_mut(state).config.autonomous_start_ms = None;
         changes.push("reset runtime counters".to_owned());
     }
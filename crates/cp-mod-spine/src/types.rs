@@ -12,6 +12,8 @@ pub enum NotificationType {
     ReloadResume,
     /// Custom notification from a module or external source
     Custom,
+    /// Context window filling up, or one panel taking too much of it
+    ContextHealth,
 }
 
 impl NotificationType {
@@ -22,6 +24,7 @@ impl NotificationType {
             Self::UserMessage => "User Message",
             Self::ReloadResume => "Reload Resume",
             Self::Custom => "Custom",
+            Self::ContextHealth => "Context Health",
         }
     }
 }
@@ -91,10 +94,34 @@ pub enum ContinuationAction {
     SyntheticMessage(String),
     /// Just relaunch streaming with existing context (no new message)
     Relaunch,
+    /// Resume the assistant message cut off by `max_tokens`: stream again
+    /// with it as the final (prefill) turn so the continuation lands in place.
+    Splice,
+}
+
+/// Default cap on chained `max_tokens` continuations.
+const fn default_max_token_continuations() -> usize {
+    3
+}
+
+/// Default number of identical consecutive tool calls treated as a loop.
+const fn default_max_identical_tool_calls() -> usize {
+    3
+}
+
+/// Default share of the context budget (percent) that triggers a warning.
+const fn default_context_warn_pct() -> usize {
+    70
+}
+
+/// Default share of the context budget (percent) one panel may hold before
+/// it is flagged.
+const fn default_panel_alert_pct() -> usize {
+    25
 }
 
 /// Configuration for spine module (per-worker, persisted)
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct SpineConfig {
     /// Whether to continue until all todos are done
     #[serde(default)]
@@ -113,6 +140,27 @@ pub struct SpineConfig {
     /// Max consecutive auto-continuations without human input
     #[serde(default)]
     pub max_auto_retries: Option<usize>,
+    /// Max consecutive tool turns without human input before the agent must
+    /// stop, post a progress summary, and wait for the user
+    #[serde(default)]
+    pub max_tool_turns: Option<usize>,
+    /// Max chained continuations of an answer cut off by `max_tokens`
+    /// (0 disables; the truncated answer is then left as-is).
+    #[serde(default = "default_max_token_continuations")]
+    pub max_token_continuations: usize,
+    /// Identical consecutive tool calls (same tool, same input) treated as a
+    /// loop: the call is refused and the agent told to change strategy
+    /// (0 disables).
+    #[serde(default = "default_max_identical_tool_calls")]
+    pub max_identical_tool_calls: usize,
+    /// Context usage (percent of the effective budget) that raises a context
+    /// health warning (0 disables).
+    #[serde(default = "default_context_warn_pct")]
+    pub context_warn_pct: usize,
+    /// Share of the budget (percent) a single panel may hold before a context
+    /// health alert names it (0 disables).
+    #[serde(default = "default_panel_alert_pct")]
+    pub panel_alert_pct: usize,
 
     /// User explicitly stopped streaming (Esc). Pauses auto-continuation
     /// without disabling it. Cleared when user sends a new message.
@@ -126,6 +174,9 @@ pub struct SpineConfig {
     /// Timestamp when autonomous operation started (for duration guard)
     #[serde(default)]
     pub autonomous_start_ms: Option<u64>,
+    /// Count of tool turns (tool batches executed) since the user last spoke
+    #[serde(default)]
+    pub tool_turn_count: usize,
 
     /// Count of consecutive auto-continuations that ended in a stream error
     /// (all retries exhausted). Used for exponential backoff. Reset on successful
@@ -135,6 +186,34 @@ pub struct SpineConfig {
     /// Timestamp (ms) of when the last continuation error occurred. Used for backoff delay.
     #[serde(default)]
     pub last_continuation_error_ms: Option<u64>,
+    /// Continuations chained onto the current truncated answer. Reset when a
+    /// stream ends for any other reason or the user sends a message.
+    #[serde(default)]
+    pub token_continuation_count: usize,
+}
+
+impl Default for SpineConfig {
+    fn default() -> Self {
+        Self {
+            continue_until_todos_done: false,
+            max_output_tokens: None,
+            max_duration_secs: None,
+            max_messages: None,
+            max_auto_retries: None,
+            max_tool_turns: None,
+            max_token_continuations: default_max_token_continuations(),
+            max_identical_tool_calls: default_max_identical_tool_calls(),
+            context_warn_pct: default_context_warn_pct(),
+            panel_alert_pct: default_panel_alert_pct(),
+            user_stopped: false,
+            auto_continuation_count: 0,
+            autonomous_start_ms: None,
+            tool_turn_count: 0,
+            consecutive_continuation_errors: 0,
+            last_continuation_error_ms: None,
+            token_continuation_count: 0,
+        }
+    }
 }
 
 /// Module-owned state for the Spine module
@@ -146,6 +225,8 @@ pub struct SpineState {
     pub next_notification_id: usize,
     /// Per-worker spine configuration (guard rails, auto-continuation settings).
     pub config: SpineConfig,
+    /// Completion cue configuration and the queue of raised events.
+    pub cues: crate::cues::CueConfig,
 }
 
 impl Default for SpineState {
@@ -158,7 +239,12 @@ impl SpineState {
     /// Create an empty spine state with default configuration.
     #[must_use]
     pub fn new() -> Self {
-        Self { notifications: vec![], next_notification_id: 1, config: SpineConfig::default() }
+        Self {
+            notifications: vec![],
+            next_notification_id: 1,
+            config: SpineConfig::default(),
+            cues: crate::cues::CueConfig::default(),
+        }
     }
 
     /// Get shared ref from State's `TypeMap`.
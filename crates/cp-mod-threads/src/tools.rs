@@ -20,11 +20,7 @@ fn clamp_bytes(s: &str, max: usize) -> String {
 
 /// Truncate `s` to at most `max` chars, appending `…` when shortened.
 fn preview_ellipsis(s: &str, max: usize) -> String {
-    if s.len() > max {
-        format!("{}…", s.get(..s.floor_char_boundary(max.saturating_sub(3))).unwrap_or(""))
-    } else {
-        s.to_owned()
-    }
+    cp_base::ui::text::ellipsize(s, max, "\u{2026}")
 }
 
 /// Push `msg` onto thread `tid`, flipping status when the turn is handed back.
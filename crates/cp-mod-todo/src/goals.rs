@@ -0,0 +1,214 @@
+//! Goals — the acceptance criteria of the current task.
+//!
+//! The user lists them when handing over a task (`/goals a; b; c`); the
+//! agent marks each one met with `goal_check` once it has verified it. While
+//! any criterion is unmet the agent may not stop: each time it ends a turn,
+//! the spine wakes it again with the criteria still open. `/goals accept`
+//! lets the user call the task done anyway; `/goals` alone clears them.
+
+use serde::{Deserialize, Serialize};
+
+use cp_base::state::context::Kind;
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+use cp_mod_spine::types::{NotificationType, SpineState};
+
+/// Notification source of the "criteria still unmet" wake-up.
+const CONTINUATION_SOURCE: &str = "goal_continuation";
+
+/// One acceptance criterion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Criterion {
+    /// Criterion ID (G1, G2, ...).
+    pub id: String,
+    /// What must hold for the task to be done.
+    pub text: String,
+    /// Whether the agent marked it met.
+    #[serde(default)]
+    pub met: bool,
+}
+
+/// The acceptance criteria of the current task.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GoalState {
+    /// Criteria, in the order the user gave them.
+    #[serde(default)]
+    pub criteria: Vec<Criterion>,
+    /// The user accepted the task as done with criteria still unmet.
+    #[serde(default)]
+    pub accepted: bool,
+}
+
+impl GoalState {
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+
+    /// Replace the criteria with `texts` (G1, G2, ... in order).
+    pub fn set(&mut self, texts: &[&str]) {
+        self.criteria = texts
+            .iter()
+            .map(|text| text.trim())
+            .filter(|text| !text.is_empty())
+            .zip(1usize..)
+            .map(|(text, n)| Criterion { id: format!("G{n}"), text: text.to_owned(), met: false })
+            .collect();
+        self.accepted = false;
+    }
+
+    /// `(met, total)` criteria.
+    #[must_use]
+    pub fn progress(&self) -> (usize, usize) {
+        (self.criteria.iter().filter(|c| c.met).count(), self.criteria.len())
+    }
+
+    /// Criteria not met yet.
+    pub fn unmet(&self) -> impl Iterator<Item = &Criterion> {
+        self.criteria.iter().filter(|c| !c.met)
+    }
+
+    /// Whether the agent must keep working: criteria are unmet and the user
+    /// has not accepted the task anyway.
+    #[must_use]
+    pub fn blocks_completion(&self) -> bool {
+        !self.accepted && self.unmet().next().is_some()
+    }
+
+    /// The criteria for the LLM context, `None` when there are none.
+    #[must_use]
+    pub fn render(&self) -> Option<String> {
+        if self.criteria.is_empty() {
+            return None;
+        }
+        let (met, total) = self.progress();
+        let header = if self.accepted {
+            format!("Acceptance criteria ({met}/{total} met, accepted by the user):")
+        } else {
+            format!("Acceptance criteria ({met}/{total} met):")
+        };
+        let lines = self.criteria.iter().map(|c| format!("[{}] {} {}", if c.met { 'x' } else { ' ' }, c.id, c.text));
+        Some(std::iter::once(header).chain(lines).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+/// The notification text when the agent stopped with criteria unmet.
+#[must_use]
+pub fn continuation_message(goals: &GoalState) -> String {
+    let open: Vec<String> = goals.unmet().map(|c| format!("{} {}", c.id, c.text)).collect();
+    format!(
+        "The task is not complete: {} acceptance criteria unmet ({}). Verify and mark each with goal_check, or keep working.",
+        open.len(),
+        open.join("; ")
+    )
+}
+
+/// Panel lines of the criteria, followed by a blank line; none without goals.
+pub(crate) fn blocks(goals: &GoalState) -> Vec<cp_render::Block> {
+    use cp_render::{Block, Semantic, Span as S};
+    if goals.criteria.is_empty() {
+        return Vec::new();
+    }
+    let (met, total) = goals.progress();
+    let header_sem = if goals.blocks_completion() { Semantic::Warning } else { Semantic::Success };
+    let accepted = if goals.accepted { " (accepted)" } else { "" };
+    let mut lines = vec![Block::Line(vec![S::styled(format!(" Goals {met}/{total}{accepted}"), header_sem).bold()])];
+    for criterion in &goals.criteria {
+        let (mark, sem) = if criterion.met { ('x', Semantic::Success) } else { (' ', Semantic::Muted) };
+        lines.push(Block::Line(vec![
+            S::muted(" [".into()),
+            S::styled(format!("{mark}"), sem),
+            S::muted("] ".into()),
+            S::styled(criterion.id.clone(), Semantic::AccentDim),
+            S::new(format!(" {}", criterion.text)),
+        ]));
+    }
+    lines.push(Block::empty());
+    lines
+}
+
+/// Execute `goal_check` — mark criteria met (or unmet again).
+pub(crate) fn execute_check(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let _fg = cp_base::flame!("goal_check");
+    let Some(ids) = tool.input.get("ids").and_then(|v| v.as_array()) else {
+        return ToolResult::new(tool.id.clone(), "Missing 'ids' array".to_owned(), true);
+    };
+    let met = tool.input.get("met").and_then(serde_json::Value::as_bool).unwrap_or(true);
+    let goals = GoalState::get_mut(state);
+    let mut lines = Vec::new();
+    for id in ids.iter().filter_map(|v| v.as_str()) {
+        match goals.criteria.iter_mut().find(|c| c.id == id) {
+            Some(criterion) => {
+                criterion.met = met;
+                lines.push(format!("{id} {}: {}", if met { "met" } else { "unmet" }, criterion.text));
+            }
+            None => lines.push(format!("Error: criterion '{id}' not found")),
+        }
+    }
+    let (done, total) = goals.progress();
+    let is_error = lines.iter().all(|l| l.starts_with("Error:"));
+    lines.push(format!("{done}/{total} criteria met"));
+    state.touch_panel(Kind::TODO);
+    ToolResult::new(tool.id.clone(), lines.join("\n"), is_error)
+}
+
+/// The agent ended its turn with criteria unmet: wake it again with the
+/// open criteria. One deduplicated notification; the spine's guard rails
+/// bound how often this can loop.
+pub fn check_continuation(state: &mut State) {
+    if state.flags.stream.phase.is_streaming() || state.messages.last().is_none_or(|m| m.role != "assistant") {
+        return;
+    }
+    let goals = GoalState::get(state);
+    if !goals.blocks_completion() {
+        return;
+    }
+    let already =
+        SpineState::get(state).notifications.iter().any(|n| !n.is_processed() && n.source == CONTINUATION_SOURCE);
+    if already {
+        return;
+    }
+    let content = continuation_message(goals);
+    let _id = SpineState::create_notification(state, NotificationType::Custom, CONTINUATION_SOURCE.to_owned(), content);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn criteria_are_numbered_skipping_blanks() {
+        let mut goals = GoalState::default();
+        goals.set(&["tests pass", " ", "docs updated"]);
+        assert_eq!(goals.criteria.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(), ["G1", "G2"]);
+    }
+
+    #[test]
+    fn unmet_criteria_block_completion_until_accepted() {
+        let mut goals = GoalState::default();
+        assert!(!goals.blocks_completion());
+        goals.set(&["tests pass", "docs updated"]);
+        assert!(goals.blocks_completion());
+        if let Some(first) = goals.criteria.first_mut() {
+            first.met = true;
+        }
+        assert_eq!(goals.progress(), (1, 2));
+        assert!(continuation_message(&goals).contains("G2 docs updated"));
+        goals.accepted = true;
+        assert!(!goals.blocks_completion());
+    }
+}
@@ -3,7 +3,11 @@
 //! Three tools: `todo_create` (with optional nesting), `todo_update` (status,
 //! name, description, delete), `todo_move` (reorder). Todos are stored per-worker
 //! and drive the spine's `continue_until_todos_done` auto-continuation mode.
+//! The task's acceptance criteria live here too ([`goals`]), checked off with
+//! `goal_check`.
 
+/// Acceptance criteria of the current task: `GoalState`, `goal_check`.
+pub mod goals;
 /// Panel implementation for the todo list view.
 mod panel;
 /// Tool implementations for creating, updating, and moving todos.
@@ -11,6 +15,7 @@ mod tools;
 /// Todo state types: `TodoItem`, `TodoStatus`, `TodoState`.
 pub mod types;
 
+use goals::GoalState;
 use types::{TodoState, TodoStatus};
 
 use serde_json::json;
@@ -63,10 +68,12 @@ impl Module for TodoModule {
 
     fn init_state(&self, state: &mut State) {
         state.set_ext(TodoState::new());
+        state.set_ext(GoalState::default());
     }
 
     fn reset_state(&self, state: &mut State) {
         state.set_ext(TodoState::new());
+        state.set_ext(GoalState::default());
     }
 
     fn save_module_data(&self, state: &State) -> serde_json::Value {
@@ -74,6 +81,7 @@ impl Module for TodoModule {
         json!({
             "todos": ts.todos,
             "next_todo_id": ts.next_todo_id,
+            "goals": GoalState::get(state),
         })
     }
     fn load_module_data(&self, data: &serde_json::Value, state: &mut State) {
@@ -86,6 +94,9 @@ impl Module for TodoModule {
         if let Some(v) = data.get("next_todo_id").and_then(serde_json::Value::as_u64) {
             ts.next_todo_id = v.to_usize();
         }
+        if let Some(goals) = data.get("goals").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.set_ext::<GoalState>(goals);
+        }
     }
 
     fn fixed_panel_types(&self) -> Vec<Kind> {
@@ -148,6 +159,12 @@ impl Module for TodoModule {
                 .param("id", ParamType::String, true)
                 .param("after_id", ParamType::String, false)
                 .build(),
+            ToolDefinition::from_yaml("goal_check", t)
+                .short_desc("Mark acceptance criteria met")
+                .category("Todo")
+                .param_array("ids", ParamType::String, true)
+                .param("met", ParamType::Boolean, false)
+                .build(),
         ]
     }
 
@@ -156,6 +173,7 @@ impl Module for TodoModule {
             "todo_create" => Some(preflight_create(tool, state)),
             "todo_update" => Some(preflight_update(tool, state)),
             "todo_move" => Some(preflight_move(tool, state)),
+            "goal_check" => Some(preflight_goal_check(tool, state)),
             _ => None,
         }
     }
@@ -165,6 +183,7 @@ impl Module for TodoModule {
             "todo_create" => Some(tools::execute_create(tool, state)),
             "todo_update" => Some(tools::execute_update(tool, state)),
             "todo_move" => Some(tools::execute_move(tool, state)),
+            "goal_check" => Some(goals::execute_check(tool, state)),
             _ => None,
         }
     }
@@ -174,6 +193,7 @@ impl Module for TodoModule {
             ("todo_create", visualize_todo_output),
             ("todo_update", visualize_todo_output),
             ("todo_move", visualize_todo_output),
+            ("goal_check", visualize_todo_output),
         ]
     }
 
@@ -322,6 +342,22 @@ fn preflight_move(tool: &ToolUse, state: &State) -> Verdict {
     pf
 }
 
+/// Pre-flight for `goal_check`: error when no goals are set or an `id` is unknown.
+fn preflight_goal_check(tool: &ToolUse, state: &State) -> Verdict {
+    let mut pf = Verdict::new();
+    let goals = GoalState::get(state);
+    if goals.criteria.is_empty() {
+        pf.errors.push("No acceptance criteria are set (the user sets them with /goals)".to_owned());
+        return pf;
+    }
+    for id in tool.input.get("ids").and_then(|v| v.as_array()).into_iter().flatten().filter_map(|v| v.as_str()) {
+        if !goals.criteria.iter().any(|c| c.id == id) {
+            pf.errors.push(format!("Criterion '{id}' not found"));
+        }
+    }
+    pf
+}
+
 /// Primary keyword classes (error / done / in-progress / pending). Returns
 /// `None` when the line matches none, deferring to [`tail_semantic`].
 fn keyword_semantic(line: &str) -> Option<cp_render::Semantic> {
@@ -372,11 +408,7 @@ fn visualize_todo_output(content: &str, width: usize) -> Vec<cp_render::Block> {
                 return Block::empty();
             }
             let semantic = todo_line_semantic(line);
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
             Block::Line(vec![Span::styled(display, semantic)])
         })
         .collect()
@@ -1,10 +1,12 @@
 use crossterm::event::KeyEvent;
 
-use cp_base::panels::{ContextItem, Panel};
+use cp_base::panels::Panel;
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Kind, estimate_tokens};
+use cp_base::state::data::context_item::ContextItem;
 use cp_base::state::runtime::State;
 
+use crate::goals::GoalState;
 use crate::types::{TodoItem, TodoState, TodoStatus};
 use cp_base::panels::scroll_key_action;
 use std::fmt::Write as _;
@@ -16,6 +18,15 @@ type TodoLine = (usize, String, String, TodoStatus, String);
 pub(crate) struct TodoPanel;
 
 impl TodoPanel {
+    /// Format the acceptance criteria, then the todos, for LLM context
+    fn format_for_context(state: &State) -> String {
+        let todos = Self::format_todos_for_context(state);
+        match GoalState::get(state).render() {
+            Some(goals) => format!("{goals}\n\n{todos}"),
+            None => todos,
+        }
+    }
+
     /// Format todos for LLM context
     fn format_todos_for_context(state: &State) -> String {
         fn format_todo(todo: &TodoItem, todos: &[TodoItem], indent: usize) -> String {
@@ -70,14 +81,15 @@ impl Panel for TodoPanel {
         use cp_render::{Block, Semantic, Span as S};
         let ts = TodoState::get(state);
 
+        let mut blocks = crate::goals::blocks(GoalState::get(state));
         if ts.todos.is_empty() {
-            return vec![Block::Line(vec![S::muted("  No todos".into()).italic()])];
+            blocks.push(Block::Line(vec![S::muted("  No todos".into()).italic()]));
+            return blocks;
         }
 
         let mut todo_lines: Vec<TodoLine> = Vec::new();
         collect_todo_lines(&ts.todos, None, 0, &mut todo_lines);
 
-        let mut blocks = Vec::new();
         for (indent, id, name, status, description) in todo_lines {
             let prefix = "  ".repeat(indent);
             let (status_char, status_sem) = match status {
@@ -111,7 +123,7 @@ impl Panel for TodoPanel {
     }
 
     fn refresh(&self, state: &mut State) {
-        let todo_content = Self::format_todos_for_context(state);
+        let todo_content = Self::format_for_context(state);
         let token_count = estimate_tokens(&todo_content);
 
         for ctx in &mut state.context {
@@ -128,7 +140,7 @@ impl Panel for TodoPanel {
     }
 
     fn context(&self, state: &State) -> Vec<ContextItem> {
-        let content = Self::format_todos_for_context(state);
+        let content = Self::format_for_context(state);
         // Find the Todo context element to get its ID and timestamp
         let (id, last_refresh_ms) = state
             .context
@@ -5,6 +5,8 @@
 //! panel auto-refreshes on filesystem changes and provides @-autocomplete
 //! with directory entries.
 
+/// Cached folder listings and file hashes, updated from watcher events.
+mod listing;
 /// Panel implementation for the directory tree view.
 mod panel;
 /// Read-only tree-string rendering (directory walk), split from tools.rs.
@@ -264,7 +266,15 @@ impl Module for TreeModule {
         // noise for a navigation tree — a coding agent never browses them and a
         // slightly-stale child count is harmless — so an event under any of
         // them is dropped before it can invalidate the panel.
-        is_dir_event && ctx.context_type.as_str() == Kind::TREE && !path_under_control_dir(changed_path)
+        //
+        // Only the folder that changed is read again on the rebuild; every
+        // other open folder keeps its cached listing.
+        let invalidate =
+            is_dir_event && ctx.context_type.as_str() == Kind::TREE && !path_under_control_dir(changed_path);
+        if invalidate {
+            listing::forget(changed_path);
+        }
+        invalidate
     }
 
     fn dependencies(&self) -> &[&'static str] {
@@ -372,11 +382,7 @@ fn visualize_tree_output(content: &str, width: usize) -> Vec<cp_render::Block> {
             } else {
                 Semantic::Default
             };
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
             Block::Line(vec![Span::styled(display, semantic)])
         })
         .collect()
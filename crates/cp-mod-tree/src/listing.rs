@@ -0,0 +1,173 @@
+//! In-memory directory listings behind the tree walk.
+//!
+//! The walk used to `read_dir` every open folder, and hash every described
+//! file, on each refresh: in a repository of 100K files that meant a stall of
+//! seconds for each watcher event. Listings are now kept per folder and only
+//! the folders the watcher reports changed are read again ([`forget`]);
+//! described files are hashed again only when their size or mtime moved. The
+//! tree filter is applied while rendering, so changing it needs no re-read.
+//! A listing older than [`MAX_AGE_MS`] is read again anyway, in case the
+//! watcher missed an event.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::SystemTime;
+
+use crate::render::normalize_path;
+use crate::tools::compute_file_hash;
+
+/// Age after which a listing is read again even without a watcher event.
+const MAX_AGE_MS: u64 = 60_000;
+
+/// One entry of a folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Listed {
+    /// File or folder name.
+    pub name: String,
+    /// Whether it is a folder (symlinks followed).
+    pub is_dir: bool,
+}
+
+/// A folder's entries, folders first, then by name.
+#[derive(Debug)]
+struct Listing {
+    /// When the folder was read.
+    read_ms: u64,
+    /// The entries.
+    entries: Arc<[Listed]>,
+}
+
+/// A described file's hash and the metadata it was computed from.
+#[derive(Debug)]
+struct Hashed {
+    /// Modification time when hashed.
+    modified: Option<SystemTime>,
+    /// Size when hashed.
+    len: u64,
+    /// The short content hash.
+    hash: String,
+}
+
+/// Everything cached, shared by the cache workers and the watcher hook.
+#[derive(Debug, Default)]
+struct Cache {
+    /// Bumped by every [`forget`]: a read that started before it is not kept.
+    generation: u64,
+    /// Folder (normalized path) → listing.
+    dirs: HashMap<String, Listing>,
+    /// File (normalized path) → hash.
+    hashes: HashMap<String, Hashed>,
+}
+
+/// The cache.
+static CACHE: LazyLock<Mutex<Cache>> = LazyLock::new(|| Mutex::new(Cache::default()));
+
+/// Run `f` on the locked cache.
+fn with_cache<T>(f: impl FnOnce(&mut Cache) -> T) -> T {
+    f(&mut CACHE.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Read `dir`, folders first, then by name.
+fn read(dir: &Path) -> Vec<Listed> {
+    let Ok(read_dir) = fs::read_dir(dir) else { return Vec::new() };
+    let mut entries: Vec<Listed> = read_dir
+        .filter_map(Result::ok)
+        .map(|e| Listed { name: e.file_name().to_string_lossy().into_owned(), is_dir: e.path().is_dir() })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    entries
+}
+
+/// The entries of `dir`, read from disk only when not cached or too old.
+pub(crate) fn entries(dir: &Path) -> Arc<[Listed]> {
+    let key = normalize_path(dir);
+    let now = cp_base::panels::now_ms();
+    let (cached, generation) = with_cache(|cache| {
+        let fresh = cache
+            .dirs
+            .get(&key)
+            .filter(|listing| now.saturating_sub(listing.read_ms) < MAX_AGE_MS)
+            .map(|listing| Arc::clone(&listing.entries));
+        (fresh, cache.generation)
+    });
+    if let Some(entries) = cached {
+        return entries;
+    }
+    let entries: Arc<[Listed]> = read(dir).into();
+    with_cache(|cache| {
+        if cache.generation == generation {
+            let _previous = cache.dirs.insert(key, Listing { read_ms: now, entries: Arc::clone(&entries) });
+        }
+    });
+    entries
+}
+
+/// The short hash of `path`, computed again only when its size or mtime moved.
+pub(crate) fn file_hash(path: &Path) -> String {
+    let Ok(meta) = fs::metadata(path) else { return String::new() };
+    let modified = meta.modified().ok();
+    let len = meta.len();
+    let key = normalize_path(path);
+    let cached = with_cache(|cache| {
+        cache.hashes.get(&key).filter(|h| h.modified == modified && h.len == len).map(|h| h.hash.clone())
+    });
+    if let Some(hash) = cached {
+        return hash;
+    }
+    let hash = compute_file_hash(path).unwrap_or_default();
+    with_cache(|cache| {
+        let _previous = cache.hashes.insert(key, Hashed { modified, len, hash: hash.clone() });
+    });
+    hash
+}
+
+/// The watcher saw the entries of `dir` change: drop its listing. Open
+/// subfolders are watched on their own, so their listings stay.
+pub(crate) fn forget(dir: &str) {
+    let key = normalize_path(Path::new(dir));
+    with_cache(|cache| {
+        cache.generation = cache.generation.wrapping_add(1);
+        let _dropped = cache.dirs.remove(&key);
+    });
+}
+
+/// Keep only the listings of open folders (a closed folder is not watched,
+/// so its listing would go stale) and the hashes of described files.
+pub(crate) fn retain(open: &HashSet<String>, described: &HashSet<&str>) {
+    with_cache(|cache| {
+        cache.dirs.retain(|path, _| path == "." || open.contains(path));
+        cache.hashes.retain(|path, _| described.contains(path.as_str()));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forgetting_a_folder_drops_only_its_listing() {
+        let listing = || Listing { read_ms: 0, entries: Arc::from([]) };
+        with_cache(|cache| {
+            for path in ["cp_tree_test", "cp_tree_test/a", "cp_tree_test_b"] {
+                let _previous = cache.dirs.insert(path.to_owned(), listing());
+            }
+        });
+        forget("./cp_tree_test/");
+        let kept = with_cache(|cache| {
+            ["cp_tree_test", "cp_tree_test/a", "cp_tree_test_b"].map(|path| cache.dirs.contains_key(path))
+        });
+        assert_eq!(kept, [false, true, true]);
+    }
+
+    #[test]
+    fn folders_sort_before_files() {
+        let dir = std::env::temp_dir().join(format!("cp-tree-listing-{}", std::process::id()));
+        let _r = fs::create_dir_all(dir.join("zeta"));
+        let _w = fs::write(dir.join("alpha.rs"), "fn main() {}");
+        let names: Vec<String> = read(&dir).into_iter().map(|e| e.name).collect();
+        let _rm = fs::remove_dir_all(&dir);
+        assert_eq!(names, ["zeta", "alpha.rs"]);
+    }
+}
@@ -1,9 +1,10 @@
 use crossterm::event::KeyEvent;
 
 use cp_base::panels::{CacheRequest, CacheUpdate};
-use cp_base::panels::{ContextItem, Panel, paginate_content};
+use cp_base::panels::{Panel, paginate_content};
 use cp_base::state::actions::Action;
 use cp_base::state::context::{Entry, Kind, compute_total_pages, estimate_tokens};
+use cp_base::state::data::context_item::{ContextItem, Provenance};
 use cp_base::state::runtime::State;
 use cp_base::ui::{find_children_pattern, find_size_pattern};
 
@@ -147,7 +148,8 @@ impl Panel for TreePanel {
                     && !content.is_empty()
                 {
                     let output = paginate_content(content, ctx.current_page, ctx.total_pages, &ctx.page_descriptions);
-                    return vec![ContextItem::new(&ctx.id, "Directory Tree", output, ctx.last_refresh_ms)];
+                    let item = ContextItem::new(&ctx.id, "Directory Tree", output, ctx.last_refresh_ms);
+                    return vec![item.with_provenance(Provenance::repo("."))];
                 }
                 break;
             }
@@ -3,16 +3,18 @@
 //!
 //! Split from `tools.rs` for the line budget. `tools.rs` owns the mutating
 //! tool dispatch (toggle / describe / filter); this module is the pure
-//! read-only renderer plus the shared `normalize_path` helper.
+//! read-only renderer plus the shared `normalize_path` helper. Folder
+//! listings and file hashes come from the `listing` cache, so a refresh only
+//! touches the disk for what changed.
 
 use std::collections::HashSet;
 use std::fmt::Write as _;
-use std::fs;
 use std::path::{Path, PathBuf};
 
 use ignore::gitignore::GitignoreBuilder;
 
-use crate::tools::{SHOW_CONTEXT_PILOT, compute_file_hash};
+use crate::listing::{self, Listed};
+use crate::tools::SHOW_CONTEXT_PILOT;
 use crate::types::TreeFileDescription;
 
 /// Generate tree string without mutating state (for read-only rendering)
@@ -39,6 +41,9 @@ pub(crate) fn generate_tree_string(
     // Build map of descriptions for quick lookup
     let desc_map: std::collections::HashMap<_, _> = tree_descriptions.iter().map(|d| (d.path.clone(), d)).collect();
 
+    // Closed folders are not watched: forget their listings
+    listing::retain(&open_set, &tree_descriptions.iter().map(|d| d.path.as_str()).collect());
+
     let mut output = String::new();
 
     // Show pwd at the top
@@ -96,7 +101,7 @@ struct Row<'row> {
 }
 
 /// Render a directory entry: the folder line plus (when expanded) its children.
-fn render_dir(entry: &fs::DirEntry, row: &Row<'_>, ctx: &TreeContext<'_>, output: &mut String) {
+fn render_dir(path: &Path, row: &Row<'_>, ctx: &TreeContext<'_>, output: &mut String) {
     let is_open = ctx.open_set.contains(row.entry_path);
     let folder_desc = ctx.desc_map.get(row.entry_path).map(|d| &d.description);
     let triangle = if is_open { "\u{25bc} " } else { "\u{25b6} " };
@@ -107,11 +112,8 @@ fn render_dir(entry: &fs::DirEntry, row: &Row<'_>, ctx: &TreeContext<'_>, output
         } else {
             let _r = writeln!(output, "{}{}{triangle}{}/", row.prefix, row.connector, row.name_str);
         }
-        let child_node = TreeNode {
-            dir: &entry.path(),
-            path_str: row.entry_path,
-            prefix: &format!("{}{}", row.prefix, row.child_prefix),
-        };
+        let child_node =
+            TreeNode { dir: path, path_str: row.entry_path, prefix: &format!("{}{}", row.prefix, row.child_prefix) };
         build_tree_new(&child_node, ctx, output);
     } else if let Some(desc) = folder_desc {
         let _r = writeln!(output, "{}{}{triangle}{}/ - {desc}", row.prefix, row.connector, row.name_str);
@@ -121,9 +123,9 @@ fn render_dir(entry: &fs::DirEntry, row: &Row<'_>, ctx: &TreeContext<'_>, output
 }
 
 /// Render a file entry: description line (with `[!]` stale marker) or plain name.
-fn render_file(entry: &fs::DirEntry, row: &Row<'_>, ctx: &TreeContext<'_>, output: &mut String) {
+fn render_file(path: &Path, row: &Row<'_>, ctx: &TreeContext<'_>, output: &mut String) {
     if let Some(desc) = ctx.desc_map.get(row.entry_path) {
-        let current_hash = compute_file_hash(&entry.path()).unwrap_or_default();
+        let current_hash = listing::file_hash(path);
         let is_stale = !desc.file_hash.is_empty() && desc.file_hash != current_hash;
         let stale_marker = if is_stale { " [!]" } else { "" };
         let _r =
@@ -135,49 +137,35 @@ fn render_file(entry: &fs::DirEntry, row: &Row<'_>, ctx: &TreeContext<'_>, outpu
 
 /// Recursively build the tree output string for a single directory node.
 fn build_tree_new(node: &TreeNode<'_>, ctx: &TreeContext<'_>, output: &mut String) {
-    let Ok(entries) = fs::read_dir(node.dir) else { return };
-
-    let mut items: Vec<_> = entries
-        .filter_map(Result::ok)
+    let entries = listing::entries(node.dir);
+    let items: Vec<&Listed> = entries
+        .iter()
         .filter(|e| {
-            let path = e.path();
-            let is_dir = path.is_dir();
             // .context-pilot/ is internal rigging — hide unless explicitly opted in
-            if is_dir && e.file_name() == ".context-pilot" && !*SHOW_CONTEXT_PILOT {
+            if e.is_dir && e.name == ".context-pilot" && !*SHOW_CONTEXT_PILOT {
                 return false;
             }
-            ctx.gitignore.as_ref().is_none_or(|gi| !gi.matched(&path, is_dir).is_ignore())
+            ctx.gitignore.as_ref().is_none_or(|gi| !gi.matched(node.dir.join(&e.name), e.is_dir).is_ignore())
         })
         .collect();
 
-    // Sort: directories first, then alphabetically
-    items.sort_by(|a, b| {
-        let a_dir = a.path().is_dir();
-        let b_dir = b.path().is_dir();
-        match (a_dir, b_dir) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.file_name().cmp(&b.file_name()),
-        }
-    });
-
     let total = items.len();
     for (i, entry) in items.iter().enumerate() {
         let is_last = i == total.saturating_sub(1);
         let connector = if is_last { "\u{2514}\u{2500}\u{2500} " } else { "\u{251c}\u{2500}\u{2500} " };
         let child_prefix = if is_last { "    " } else { "\u{2502}   " };
 
-        let name = entry.file_name();
-        let name_str = name.to_string_lossy();
+        let name_str = entry.name.as_str();
         let entry_path =
-            if node.path_str == "." { name_str.to_string() } else { format!("{}/{name_str}", node.path_str) };
+            if node.path_str == "." { name_str.to_owned() } else { format!("{}/{name_str}", node.path_str) };
 
-        let row = Row { prefix: node.prefix, connector, child_prefix, name_str: &name_str, entry_path: &entry_path };
+        let row = Row { prefix: node.prefix, connector, child_prefix, name_str, entry_path: &entry_path };
 
-        if entry.path().is_dir() {
-            render_dir(entry, &row, ctx, output);
+        let path = node.dir.join(name_str);
+        if entry.is_dir {
+            render_dir(&path, &row, ctx, output);
         } else {
-            render_file(entry, &row, ctx, output);
+            render_file(&path, &row, ctx, output);
         }
     }
 }
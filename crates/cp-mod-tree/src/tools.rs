@@ -402,3 +402,32 @@ pub fn list_dir_entries(
 
     entries
 }
+
+/// Most workspace files collected for the `@` fuzzy search.
+const MAX_WORKSPACE_FILES: usize = 20_000;
+
+/// Every workspace file as a relative path, honoring `.gitignore` and the tree
+/// filter. Capped at [`MAX_WORKSPACE_FILES`]. Used by the `@` fuzzy search.
+#[must_use]
+pub fn workspace_files(tree_filter: &str) -> Vec<String> {
+    let root = PathBuf::from(".");
+    let mut builder = GitignoreBuilder::new(&root);
+    for line in tree_filter.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let _: Option<&mut GitignoreBuilder> = builder.add_line(None, line).ok();
+    }
+    let gitignore = builder.build().ok();
+    ignore::WalkBuilder::new(&root)
+        .hidden(false)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            let name = entry.file_name();
+            let internal = is_dir && (name == ".git" || (name == ".context-pilot" && !*SHOW_CONTEXT_PILOT));
+            !internal && gitignore.as_ref().is_none_or(|gi| !gi.matched(entry.path(), is_dir).is_ignore())
+        })
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|t| !t.is_dir()))
+        .filter_map(|entry| Some(entry.path().strip_prefix(&root).ok()?.to_string_lossy().into_owned()))
+        .take(MAX_WORKSPACE_FILES)
+        .collect()
+}
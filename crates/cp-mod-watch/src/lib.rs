@@ -0,0 +1,224 @@
+//! Watch module — rerun a command on file changes and fix what it breaks.
+//!
+//! Two tools: `Watch_start` (command, watched paths, auto-fix limit) and
+//! `Watch_stop`. The command runs as a console session after the watched
+//! paths settle; a failure becomes a spine notification carrying the failure
+//! excerpt, which starts an agent turn until `max_auto_fixes` consecutive
+//! failures have been handed over. A pass or a user message resets the count.
+
+/// Debounce, run and outcome reporting.
+pub mod runner;
+/// Tool dispatch: start, stop.
+mod tools;
+/// Watch state types: `WatchConfig`, `WatchState`.
+pub mod types;
+
+use cp_base::modules::Module;
+use cp_base::panels::{Panel, WatchSpec};
+use cp_base::state::context::{Kind, TypeMeta};
+use cp_base::state::runtime::State;
+use cp_base::tools::{ParamType, ToolDefinition, ToolTexts};
+use cp_base::tools::{ToolResult, ToolUse};
+
+use self::types::{WatchConfig, WatchState};
+
+/// Lazily parsed tool texts from the watch YAML definition file.
+static TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/watch.yaml")));
+
+/// Watch module: run a command on file changes, auto-prompt on failure.
+#[derive(Debug, Clone, Copy)]
+pub struct WatchModule;
+
+impl Default for WatchModule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WatchModule {
+    /// Construct the module marker (funnels cross-crate construction of this
+    /// `non_exhaustive` unit struct through an associated fn).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for WatchModule {
+    fn id(&self) -> &'static str {
+        "watch"
+    }
+
+    fn name(&self) -> &'static str {
+        "Watch"
+    }
+
+    fn description(&self) -> &'static str {
+        "Rerun a command on file changes and auto-fix failures"
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &["console"]
+    }
+
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let t = &*TOOL_TEXTS;
+        vec![
+            ToolDefinition::from_yaml("Watch_start", t)
+                .short_desc("Rerun a command on file changes")
+                .category("Watch")
+                .param("command", ParamType::String, true)
+                .param_array("paths", ParamType::String, false)
+                .param("max_auto_fixes", ParamType::Integer, false)
+                .build(),
+            ToolDefinition::from_yaml("Watch_stop", t).short_desc("Stop the file watch").category("Watch").build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        tools::dispatch(tool, state)
+    }
+
+    fn create_panel(&self, _context_type: &Kind) -> Option<Box<dyn Panel>> {
+        None
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![("Watch", "Rerun a command on file changes")]
+    }
+
+    fn is_core(&self) -> bool {
+        false
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(WatchState::new());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(WatchState::new());
+    }
+
+    fn save_module_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    fn load_module_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+
+    fn save_worker_data(&self, state: &State) -> serde_json::Value {
+        serde_json::json!({ "config": WatchState::get(state).config })
+    }
+
+    fn load_worker_data(&self, data: &serde_json::Value, state: &mut State) {
+        if let Some(v) = data.get("config")
+            && let Ok(config) = serde_json::from_value::<Option<WatchConfig>>(v.clone())
+        {
+            WatchState::get_mut(state).config = config;
+        }
+    }
+
+    fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<cp_base::tools::pre_flight::Verdict> {
+        None
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![]
+    }
+
+    fn tool_visualizers(&self) -> Vec<(&'static str, cp_base::modules::ToolVisualizer)> {
+        vec![]
+    }
+
+    fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
+        None
+    }
+
+    fn context_detail(&self, _ctx: &cp_base::state::context::Entry) -> Option<String> {
+        None
+    }
+
+    fn overview_context_section(&self, state: &State) -> Option<String> {
+        let ws = state.get_ext::<WatchState>()?;
+        let config = ws.config.as_ref()?;
+        let status = if ws.failing {
+            format!("failing (automatic fixes {}/{})", ws.auto_fixes, config.max_auto_fixes)
+        } else {
+            "passing".to_owned()
+        };
+        Some(format!("Watch: `{}` on {} \u{2014} {status}\n", config.command, config.paths.join(", ")))
+    }
+
+    fn overview_render_sections(&self, _state: &State) -> Vec<(u8, Vec<cp_render::Block>)> {
+        vec![]
+    }
+
+    fn on_close_context(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _state: &mut State,
+    ) -> Option<Result<String, String>> {
+        None
+    }
+
+    fn on_user_message(&self, state: &mut State) {
+        // The user is back in the loop: failures may start fix turns again.
+        if let Some(ws) = state.get_ext_mut::<WatchState>() {
+            ws.auto_fixes = 0;
+        }
+    }
+
+    fn on_stream_stop(&self, _state: &mut State) {}
+
+    fn on_stream_chunk(&self, _text: &str, _state: &mut State) {}
+
+    fn on_tool_progress(&self, _tool_name: &str, _input_so_far: &str, _state: &mut State) {}
+
+    fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
+
+    fn watch_paths(&self, state: &State) -> Vec<WatchSpec> {
+        let Some(config) = state.get_ext::<WatchState>().and_then(|ws| ws.config.as_ref()) else {
+            return vec![];
+        };
+        config
+            .paths
+            .iter()
+            .map(|p| {
+                if std::path::Path::new(p).is_dir() {
+                    WatchSpec::DirRecursive(p.clone())
+                } else {
+                    WatchSpec::File(p.clone())
+                }
+            })
+            .collect()
+    }
+
+    fn should_invalidate_on_fs_change(
+        &self,
+        _ctx: &cp_base::state::context::Entry,
+        _changed_path: &str,
+        _is_dir_event: bool,
+    ) -> bool {
+        false
+    }
+
+    fn watcher_immediate_refresh(&self) -> bool {
+        true
+    }
+}
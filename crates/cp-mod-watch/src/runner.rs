@@ -0,0 +1,127 @@
+//! Watch runner: debounce changes, run the command, report the outcome.
+//!
+//! The binary feeds file-watcher events to [`note_change`] and calls [`tick`]
+//! on its timer. A run starts once the watched paths have been quiet for
+//! [`DEBOUNCE_MS`] and no stream is in flight (so the agent's own edits are
+//! tested once its turn ends). Changes made while a run is in progress are
+//! ignored, so a command that writes into a watched path cannot loop.
+
+use cp_base::panels::now_ms;
+use cp_base::state::runtime::State;
+use cp_base::tools::failures;
+
+use cp_mod_console::manager::SessionHandle;
+use cp_mod_console::types::ConsoleState;
+
+use crate::types::{DEBOUNCE_MS, WatchConfig, WatchState};
+
+/// Console session key of the watch run (one at a time, log reused).
+pub const SESSION: &str = "watch";
+
+/// A finished run worth a spine notification.
+#[derive(Debug)]
+pub struct Outcome {
+    /// Notification text.
+    pub message: String,
+    /// Whether the notification should start an agent turn.
+    pub auto_fix: bool,
+}
+
+/// Record a file-watcher event for `path` if it is a watched path.
+pub fn note_change(state: &mut State, path: &str) {
+    let Some(ws) = state.get_ext_mut::<WatchState>() else { return };
+    let watched = ws.config.as_ref().is_some_and(|c| c.paths.iter().any(|p| p == path));
+    if !watched || ws.running {
+        return;
+    }
+    if !ws.changed.iter().any(|p| p == path) {
+        ws.changed.push(path.to_owned());
+    }
+    ws.changed_at_ms = now_ms();
+}
+
+/// Advance the watch: collect a finished run, or start one when changes have
+/// settled. Returns the outcome to notify about, if any.
+pub fn tick(state: &mut State) -> Option<Outcome> {
+    let ws = state.get_ext::<WatchState>()?;
+    let config = ws.config.clone()?;
+    if ws.running {
+        return finish(state, &config);
+    }
+    let settled = !ws.changed.is_empty() && now_ms().saturating_sub(ws.changed_at_ms) >= DEBOUNCE_MS;
+    if !settled || state.flags.stream.phase.is_streaming() {
+        return None;
+    }
+    start(state, &config)
+        .err()
+        .map(|e| Outcome { message: format!("watch: cannot run `{}`: {e}", config.command), auto_fix: false })
+}
+
+/// Spawn the command in the console session.
+fn start(state: &mut State, config: &WatchConfig) -> Result<(), String> {
+    stop_run(state);
+    let ws = WatchState::get_mut(state);
+    ws.changed.clear();
+    let cwd = std::env::current_dir().ok().map(|d| d.to_string_lossy().to_string());
+    let handle = SessionHandle::spawn(SESSION.to_owned(), config.command.clone(), cwd)?;
+    drop(ConsoleState::get_mut(state).sessions.insert(SESSION.to_owned(), handle));
+    WatchState::get_mut(state).running = true;
+    Ok(())
+}
+
+/// Kill and forget the run in progress, if any.
+pub fn stop_run(state: &mut State) {
+    if let Some(handle) = ConsoleState::get_mut(state).sessions.remove(SESSION)
+        && !handle.get_status().is_terminal()
+    {
+        handle.kill();
+    }
+    WatchState::get_mut(state).running = false;
+}
+
+/// Collect the run once its process has exited.
+fn finish(state: &mut State, config: &WatchConfig) -> Option<Outcome> {
+    let status = ConsoleState::get(state).sessions.get(SESSION).map(SessionHandle::get_status);
+    if status.is_some_and(|s| !s.is_terminal()) {
+        return None;
+    }
+    let removed = ConsoleState::get_mut(state).sessions.remove(SESSION);
+    WatchState::get_mut(state).running = false;
+    let handle = removed?;
+    match status.and_then(cp_mod_console::types::ProcessStatus::exit_code) {
+        Some(0) => passed(state, config),
+        code => Some(failed(state, config, &handle, code.unwrap_or(-1))),
+    }
+}
+
+/// A passing run after a failure closes the loop; other passes stay quiet.
+fn passed(state: &mut State, config: &WatchConfig) -> Option<Outcome> {
+    let ws = WatchState::get_mut(state);
+    let recovered = ws.failing;
+    ws.failing = false;
+    ws.auto_fixes = 0;
+    recovered.then(|| Outcome { message: format!("watch: `{}` passes again", config.command), auto_fix: false })
+}
+
+/// A failing run: the excerpt, and an agent turn while under the limit.
+fn failed(state: &mut State, config: &WatchConfig, handle: &SessionHandle, code: i32) -> Outcome {
+    let ws = WatchState::get_mut(state);
+    ws.failing = true;
+    let auto_fix = ws.auto_fixes < config.max_auto_fixes;
+    let next = if auto_fix { ws.auto_fixes.saturating_add(1) } else { ws.auto_fixes };
+    ws.auto_fixes = next;
+    let follow_up = if auto_fix {
+        format!("Find the cause and fix it (automatic attempt {next}/{}).", config.max_auto_fixes)
+    } else {
+        format!("Automatic fix limit ({}) reached \u{2014} waiting for the user.", config.max_auto_fixes)
+    };
+    // The log is complete at exit; the ring buffer may still be catching up.
+    let output = std::fs::read_to_string(&handle.log_path).unwrap_or_else(|_| handle.buffer.read_all().0);
+    let message = format!(
+        "watch: `{}` failed (exit {code}). Full output: {}\n{}\n{follow_up}",
+        config.command,
+        handle.log_path,
+        failures::excerpt(&output),
+    );
+    Outcome { message, auto_fix }
+}
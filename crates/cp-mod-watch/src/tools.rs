@@ -0,0 +1,72 @@
+//! Tool execution: `Watch_start` and `Watch_stop`.
+
+use cp_base::state::runtime::State;
+use cp_base::tools::{ToolResult, ToolUse};
+
+use crate::runner;
+use crate::types::{DEFAULT_MAX_AUTO_FIXES, DEFAULT_PATHS, WatchConfig, WatchState};
+
+/// Dispatch watch tool calls.
+pub(crate) fn dispatch(tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+    match tool.name.as_str() {
+        "Watch_start" => Some(execute_start(tool, state)),
+        "Watch_stop" => Some(execute_stop(tool, state)),
+        _ => None,
+    }
+}
+
+/// Parse `Watch_start` parameters into a config.
+fn parse_config(tool: &ToolUse) -> Result<WatchConfig, String> {
+    let command = tool
+        .input
+        .get("command")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or("Missing required parameter 'command'.")?;
+    let paths: Vec<String> = tool.input.get("paths").and_then(|v| v.as_array()).map_or_else(
+        || DEFAULT_PATHS.iter().map(|&p| p.to_owned()).collect(),
+        |arr| arr.iter().filter_map(|p| p.as_str().map(str::to_owned)).collect(),
+    );
+    if let Some(missing) = paths.iter().find(|p| !std::path::Path::new(p).exists()) {
+        return Err(format!("Path not found: '{missing}'"));
+    }
+    if paths.is_empty() || paths.iter().any(|p| p == "." || p.is_empty()) {
+        return Err("Give the source paths to watch (e.g. [\"src\", \"tests\"]), not the project root.".to_owned());
+    }
+    let max_auto_fixes = tool
+        .input
+        .get("max_auto_fixes")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(DEFAULT_MAX_AUTO_FIXES, |n| usize::try_from(n).unwrap_or(usize::MAX));
+    Ok(WatchConfig { command: command.to_owned(), paths, max_auto_fixes })
+}
+
+/// Start (or replace) the watch.
+fn execute_start(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let config = match parse_config(tool) {
+        Ok(config) => config,
+        Err(e) => return ToolResult::new(tool.id.clone(), e, true),
+    };
+    runner::stop_run(state);
+    let summary = format!(
+        "Watching {} \u{2014} `{}` runs after changes; failures start up to {} automatic fix turns in a row.",
+        config.paths.join(", "),
+        config.command,
+        config.max_auto_fixes,
+    );
+    let ws = WatchState::get_mut(state);
+    *ws = WatchState { config: Some(config), ..WatchState::new() };
+    ToolResult::new(tool.id.clone(), summary, false)
+}
+
+/// Stop the watch and any run in progress.
+fn execute_stop(tool: &ToolUse, state: &mut State) -> ToolResult {
+    runner::stop_run(state);
+    let ws = WatchState::get_mut(state);
+    let Some(config) = ws.config.take() else {
+        return ToolResult::new(tool.id.clone(), "No watch is active.".to_owned(), false);
+    };
+    *ws = WatchState::new();
+    ToolResult::new(tool.id.clone(), format!("Stopped watching (`{}`).", config.command), false)
+}
@@ -0,0 +1,69 @@
+use cp_base::state::runtime::State;
+use serde::{Deserialize, Serialize};
+
+/// Default paths watched when `Watch_start` names none.
+pub const DEFAULT_PATHS: &[&str] = &["src"];
+
+/// Default number of consecutive failures that may start an agent turn.
+pub const DEFAULT_MAX_AUTO_FIXES: usize = 3;
+
+/// Quiet period after the last change before the command runs (ms), so a
+/// burst of saves produces one run.
+pub const DEBOUNCE_MS: u64 = 800;
+
+/// What to run and when.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// Shell command run from the project root (e.g., `cargo test`).
+    pub command: String,
+    /// Files or directories (recursive) whose changes trigger a run.
+    pub paths: Vec<String>,
+    /// Consecutive failing runs that start an automatic fix turn before the
+    /// watch waits for the user.
+    pub max_auto_fixes: usize,
+}
+
+/// Module-owned state for the Watch module.
+/// Stored in `State.module_data` via `TypeMap`.
+#[derive(Debug, Default)]
+pub struct WatchState {
+    /// The active watch; `None` when stopped.
+    pub config: Option<WatchConfig>,
+    /// Watched paths that changed since the last run started.
+    pub changed: Vec<String>,
+    /// When the last change arrived (ms since epoch), for debouncing.
+    pub changed_at_ms: u64,
+    /// A run is in progress (console session `runner::SESSION`).
+    pub running: bool,
+    /// The last completed run failed.
+    pub failing: bool,
+    /// Automatic fix turns started since the last pass or user message.
+    pub auto_fixes: usize,
+}
+
+impl WatchState {
+    /// Create a stopped watch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get shared ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    #[must_use]
+    pub fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an internal invariant is violated.
+    pub fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+}
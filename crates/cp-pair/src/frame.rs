@@ -0,0 +1,108 @@
+//! A rendered frame as ANSI bytes a guest terminal can replay as is.
+
+use std::fmt::Write as _;
+
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr as _;
+
+/// SGR codes of the modifiers a terminal can show.
+const MODIFIER_CODES: [(Modifier, &str); 8] = [
+    (Modifier::BOLD, "1"),
+    (Modifier::DIM, "2"),
+    (Modifier::ITALIC, "3"),
+    (Modifier::UNDERLINED, "4"),
+    (Modifier::SLOW_BLINK, "5"),
+    (Modifier::REVERSED, "7"),
+    (Modifier::HIDDEN, "8"),
+    (Modifier::CROSSED_OUT, "9"),
+];
+
+/// SGR parameters selecting `color`, as foreground or background.
+fn color_code(color: Color, background: bool) -> String {
+    let base: u8 = if background { 40 } else { 30 };
+    let named = |offset: u8| base.saturating_add(offset).to_string();
+    let bright = |offset: u8| base.saturating_add(60).saturating_add(offset).to_string();
+    let extended = if background { "48" } else { "38" };
+    match color {
+        Color::Reset => named(9),
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(i) => format!("{extended};5;{i}"),
+        Color::Rgb(red, green, blue) => format!("{extended};2;{red};{green};{blue}"),
+    }
+}
+
+/// The full SGR sequence for a cell style (reset first, so it stands alone).
+fn style_sequence(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut params = vec!["0".to_owned(), color_code(fg, false), color_code(bg, true)];
+    params.extend(MODIFIER_CODES.iter().filter(|&&(m, _)| modifier.contains(m)).map(|&(_, code)| code.to_owned()));
+    format!("\x1b[{}m", params.join(";"))
+}
+
+/// Every row of `buffer`, each positioned absolutely, with style changes
+/// only where the style changes. Cells covered by a wide glyph are skipped.
+#[must_use]
+pub(crate) fn encode(buffer: &Buffer) -> Vec<u8> {
+    let width = usize::from(buffer.area.width);
+    let mut out = String::from("\x1b[?25l");
+    for (row, cells) in buffer.content.chunks(width.max(1)).enumerate() {
+        let _r = write!(out, "\x1b[{};1H", row.saturating_add(1));
+        let mut style = None;
+        let mut covered = 0usize;
+        for cell in cells {
+            if covered > 0 {
+                covered = covered.saturating_sub(1);
+                continue;
+            }
+            if cell.skip {
+                continue;
+            }
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                out.push_str(&style_sequence(cell.fg, cell.bg, cell.modifier));
+                style = Some(current);
+            }
+            let symbol = cell.symbol();
+            out.push_str(symbol);
+            covered = symbol.width().saturating_sub(1);
+        }
+    }
+    out.push_str("\x1b[0m");
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn rows_are_positioned_and_styles_change_only_when_needed() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(0, 0, "ab", Style::default().fg(Color::Red));
+        buffer.set_string(0, 1, "\u{65e5}x", Style::default().bg(Color::Rgb(1, 2, 3)).add_modifier(Modifier::BOLD));
+        let text = String::from_utf8(encode(&buffer)).unwrap_or_default();
+        assert_eq!(
+            text,
+            "\x1b[?25l\x1b[1;1H\x1b[0;31;49mab\x1b[0;39;49m  \
+             \x1b[2;1H\x1b[0;39;48;2;1;2;3;1m\u{65e5}x\x1b[0;39;49m \x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! The guest side: `tui pair [SOCKET]`.
+//!
+//! Frames from the host are written to the terminal as they come; keys go
+//! back as [`GuestMsg`] lines. Ctrl+Q leaves, Ctrl+G asks for (or returns)
+//! the turn. The host ignores keys from a guest without the turn, so nothing
+//! is filtered here.
+
+use std::io::{self, BufWriter, Write as _};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use crossterm::ExecutableCommand as _;
+use crossterm::cursor::Show;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode};
+
+use crate::wire::GuestMsg;
+
+/// How long to wait for a key before checking the connection again.
+const KEY_POLL: Duration = Duration::from_millis(100);
+
+/// Copy the host's frames to the terminal until the connection closes.
+fn mirror(stream: UnixStream) -> Receiver<()> {
+    let (closed_tx, closed) = mpsc::channel();
+    let _thread = thread::spawn(move || {
+        let mut from = stream;
+        drop(io::copy(&mut from, &mut io::stdout()));
+        let _r = closed_tx.send(());
+    });
+    closed
+}
+
+/// Forward keys until Ctrl+Q or until the host goes away. Returns whether the
+/// host closed the connection.
+fn forward_keys(stream: &UnixStream, closed: &Receiver<()>) -> io::Result<bool> {
+    let mut out = BufWriter::new(stream);
+    loop {
+        if closed.try_recv().is_ok() {
+            return Ok(true);
+        }
+        if !event::poll(KEY_POLL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+        if key.code == KeyCode::Char('q') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(false);
+        }
+        let msg = if crate::is_turn_key(&key) { Some(GuestMsg::Turn) } else { GuestMsg::key(key) };
+        let Some(line) = msg.and_then(|m| serde_json::to_string(&m).ok()) else { continue };
+        writeln!(out, "{line}")?;
+        out.flush()?;
+    }
+}
+
+/// Join the session sharing at `socket` and mirror it until either side leaves.
+///
+/// # Errors
+///
+/// The socket cannot be reached or the terminal cannot be set up; the message
+/// is meant for stderr.
+pub fn join(socket: &Path) -> Result<(), String> {
+    let stream = UnixStream::connect(socket).map_err(|e| {
+        format!("{}: cannot join {}: {e} (is the host running with --pair?)", crate::SUBCOMMAND, socket.display())
+    })?;
+    let reader = stream.try_clone().map_err(|e| format!("{}: {e}", crate::SUBCOMMAND))?;
+    enable_raw_mode().map_err(|e| format!("{}: cannot enable raw mode: {e}", crate::SUBCOMMAND))?;
+    let _r_enter = io::stdout().execute(EnterAlternateScreen);
+    let closed = mirror(reader);
+    let result = forward_keys(&stream, &closed);
+    let _r_leave = io::stdout().execute(LeaveAlternateScreen);
+    let _r_show = io::stdout().execute(Show);
+    let _r_raw = disable_raw_mode();
+    match result {
+        Ok(true) => Err(format!("{}: the host ended the session", crate::SUBCOMMAND)),
+        Ok(false) => Ok(()),
+        Err(e) => Err(format!("{}: {e}", crate::SUBCOMMAND)),
+    }
+}
@@ -0,0 +1,338 @@
+//! Pairing mode — a second person watches (or drives) a running session.
+//!
+//! The host starts with `--pair` (watch only) or `--pair=turns`; it listens on
+//! `.context-pilot/pair.sock`, readable by the owner only, so a teammate joins
+//! on the same machine or through an SSH forward
+//! (`ssh -L /tmp/cp.sock:<project>/.context-pilot/pair.sock host`). A guest
+//! runs `tui pair [SOCKET]` and sees every frame the host renders. With
+//! `turns`, a guest presses Ctrl+G to take the turn: their keys then drive the
+//! session and the host's are ignored until either side presses Ctrl+G again.
+//!
+//! Server threads only accept, read and write: guest input reaches the main
+//! loop through [`Host::poll`], so session state is never touched off the loop.
+
+/// Rendered frames as ANSI.
+mod frame;
+/// The guest client.
+pub mod guest;
+/// Guest-to-host messages.
+mod wire;
+
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::os::unix::fs::PermissionsExt as _;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::buffer::Buffer;
+
+use cp_base::config::constants::STORE_DIR;
+
+use self::wire::GuestMsg;
+
+/// The command-line flag.
+pub const FLAG: &str = "--pair";
+
+/// The guest subcommand.
+pub const SUBCOMMAND: &str = "pair";
+
+/// Socket file under the store.
+const SOCKET_FILE: &str = "pair.sock";
+
+/// Frames queued per guest; a slow guest skips frames instead of stalling.
+const FRAME_QUEUE: usize = 4;
+
+/// What guests may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Guests only watch.
+    ReadOnly,
+    /// A guest may take the turn and type.
+    Turns,
+}
+
+/// Pairing state shown to the host (and, through the mirrored frame, to guests).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    /// What guests may do.
+    pub mode: Mode,
+    /// Guests connected.
+    pub guests: usize,
+    /// Whether a guest holds the turn.
+    pub guest_turn: bool,
+}
+
+/// What a guest's threads report to the main loop.
+#[derive(Debug)]
+enum Event {
+    /// A guest connected.
+    Joined(u32),
+    /// A guest sent a line.
+    Message(u32, GuestMsg),
+    /// A guest disconnected.
+    Left(u32),
+}
+
+/// A connected guest: where its frames go.
+#[derive(Debug)]
+struct Guest {
+    /// Connection number.
+    id: u32,
+    /// Feeds the guest's writer thread.
+    frames: SyncSender<Arc<[u8]>>,
+}
+
+/// A running pairing server.
+#[derive(Debug)]
+pub struct Host {
+    /// What guests may do.
+    mode: Mode,
+    /// Connected guests, shared with the accept thread.
+    guests: Arc<Mutex<Vec<Guest>>>,
+    /// Joins, lines and departures, in arrival order.
+    events: Receiver<Event>,
+    /// The guest holding the turn.
+    turn: Option<u32>,
+    /// Set when the host should render again (a guest joined).
+    needs_frame: bool,
+    /// The socket file, removed on drop.
+    socket: PathBuf,
+}
+
+/// The mode for the flag value `spec` (`None` for a bare `--pair`).
+fn mode(spec: Option<&str>) -> Result<Mode, String> {
+    match spec {
+        None | Some("" | "ro" | "read-only") => Ok(Mode::ReadOnly),
+        Some("turns") => Ok(Mode::Turns),
+        Some(other) => Err(format!("{FLAG}: expected `read-only` or `turns`, got `{other}`")),
+    }
+}
+
+/// Whether `key` is the turn toggle (Ctrl+G), for host and guests alike.
+#[must_use]
+pub fn is_turn_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('g') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Read `stream`'s lines as messages until it closes.
+fn read_guest(id: u32, stream: UnixStream, events: &Sender<Event>) {
+    for line in BufReader::new(stream).lines().map_while(Result::ok) {
+        match serde_json::from_str::<GuestMsg>(&line) {
+            Ok(msg) => {
+                if events.send(Event::Message(id, msg)).is_err() {
+                    return;
+                }
+            }
+            Err(e) => log::debug!("pair: guest {id} sent an unreadable line: {e}"),
+        }
+    }
+    drop(events.send(Event::Left(id)));
+}
+
+/// Accept guests until the process exits, two threads per guest.
+fn accept(listener: &UnixListener, guests: &Mutex<Vec<Guest>>, events: &Sender<Event>) {
+    let mut next_id = 0u32;
+    for stream in listener.incoming().map_while(Result::ok) {
+        next_id = next_id.wrapping_add(1);
+        let id = next_id;
+        let Ok(reader) = stream.try_clone() else { continue };
+        let (frames, queue) = mpsc::sync_channel::<Arc<[u8]>>(FRAME_QUEUE);
+        let _writer = thread::spawn(move || {
+            let mut out = stream;
+            for frame in queue {
+                if out.write_all(&frame).and_then(|()| out.flush()).is_err() {
+                    break;
+                }
+            }
+        });
+        let events_owned = events.clone();
+        let _reader = thread::spawn(move || read_guest(id, reader, &events_owned));
+        if let Ok(mut list) = guests.lock() {
+            list.push(Guest { id, frames });
+        }
+        drop(events.send(Event::Joined(id)));
+    }
+}
+
+impl Host {
+    /// Apply waiting guest events; the next key from the guest holding the
+    /// turn, if any. Keys from other guests are dropped.
+    pub fn poll(&mut self) -> Option<KeyEvent> {
+        while let Ok(event) = self.events.try_recv() {
+            if let Some(key) = self.apply(event) {
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// Apply one guest event; the key it carries when it may be typed.
+    fn apply(&mut self, event: Event) -> Option<KeyEvent> {
+        match event {
+            Event::Joined(id) => {
+                log::info!("pair: guest {id} joined");
+                self.needs_frame = true;
+            }
+            Event::Left(id) => self.leave(id),
+            Event::Message(id, GuestMsg::Turn) if self.turn == Some(id) => self.turn = None,
+            Event::Message(id, GuestMsg::Turn) if self.mode == Mode::Turns => self.turn = Some(id),
+            Event::Message(id, msg @ GuestMsg::Key { .. }) if self.turn == Some(id) => return msg.into_key(),
+            Event::Message(..) => {}
+        }
+        None
+    }
+
+    /// Forget guest `id`, handing its turn back to the host.
+    fn leave(&mut self, id: u32) {
+        log::info!("pair: guest {id} left");
+        if let Ok(mut list) = self.guests.lock() {
+            list.retain(|g| g.id != id);
+        }
+        self.turn = self.turn.filter(|&holder| holder != id);
+    }
+
+    /// Take the turn back from whichever guest holds it.
+    pub const fn take_turn(&mut self) {
+        self.turn = None;
+    }
+
+    /// Whether a guest holds the turn.
+    #[must_use]
+    pub const fn guest_has_turn(&self) -> bool {
+        self.turn.is_some()
+    }
+
+    /// Whether a frame should be rendered for a guest that just joined;
+    /// clears the request.
+    pub const fn take_frame_request(&mut self) -> bool {
+        std::mem::replace(&mut self.needs_frame, false)
+    }
+
+    /// What the status banner shows.
+    #[must_use]
+    pub fn status(&self) -> Status {
+        let guests = self.guests.lock().map_or(0, |list| list.len());
+        Status { mode: self.mode, guests, guest_turn: self.turn.is_some() }
+    }
+
+    /// Send the frame just drawn to every guest. Encoded only when someone
+    /// watches.
+    pub fn publish(&self, buffer: &Buffer) {
+        let Ok(mut list) = self.guests.lock() else { return };
+        if list.is_empty() {
+            return;
+        }
+        let frame: Arc<[u8]> = Arc::from(frame::encode(buffer));
+        list.retain(|g| !matches!(g.frames.try_send(Arc::clone(&frame)), Err(TrySendError::Disconnected(_))));
+    }
+}
+
+impl Drop for Host {
+    fn drop(&mut self) {
+        drop(std::fs::remove_file(&self.socket));
+    }
+}
+
+/// Bind `socket` (replacing a stale one), owner-only, and accept guests.
+fn start_at(socket: PathBuf, mode: Mode) -> Result<Host, String> {
+    if let Some(dir) = socket.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{FLAG}: {}: {e}", dir.display()))?;
+    }
+    if UnixStream::connect(&socket).is_ok() {
+        return Err(format!("{FLAG}: another session is already sharing at {}", socket.display()));
+    }
+    drop(std::fs::remove_file(&socket));
+    let listener =
+        UnixListener::bind(&socket).map_err(|e| format!("{FLAG}: cannot listen on {}: {e}", socket.display()))?;
+    std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("{FLAG}: {}: {e}", socket.display()))?;
+    let guests = Arc::new(Mutex::new(Vec::new()));
+    let (events_tx, events) = mpsc::channel();
+    let shared = Arc::clone(&guests);
+    let _thread = thread::Builder::new()
+        .name("pair".to_owned())
+        .spawn(move || accept(&listener, &shared, &events_tx))
+        .map_err(|e| format!("{FLAG}: cannot start the server thread: {e}"))?;
+    log::info!("pairing: guests join with `tui {SUBCOMMAND} {}`", socket.display());
+    Ok(Host { mode, guests, events, turn: None, needs_frame: false, socket })
+}
+
+/// The default socket path, under the store.
+#[must_use]
+pub fn default_socket() -> PathBuf {
+    Path::new(STORE_DIR).join(SOCKET_FILE)
+}
+
+/// Start sharing for the flag value `spec` (`None` for a bare `--pair`).
+///
+/// # Errors
+///
+/// A bad flag value, or a socket that cannot be bound; the message is meant
+/// for stderr.
+pub fn start(spec: Option<&str>) -> Result<Host, String> {
+    start_at(default_socket(), mode(spec)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Read as _;
+    use std::time::Duration;
+
+    /// A host on a fresh socket, with one guest connected and registered.
+    fn paired(dir: &Path) -> Option<(Host, UnixStream)> {
+        let mut host = start_at(dir.join(SOCKET_FILE), Mode::Turns).ok()?;
+        let guest = UnixStream::connect(dir.join(SOCKET_FILE)).ok()?;
+        wait(&mut host, |h| h.poll().is_none() && h.take_frame_request()).then_some((host, guest))
+    }
+
+    /// Poll `host` until `done` holds, for up to a second.
+    fn wait(host: &mut Host, mut done: impl FnMut(&mut Host) -> bool) -> bool {
+        (0..200).any(|_| {
+            thread::sleep(Duration::from_millis(5));
+            done(host)
+        })
+    }
+
+    #[test]
+    fn flag_values_pick_the_mode() {
+        assert_eq!(mode(None), Ok(Mode::ReadOnly));
+        assert_eq!(mode(Some("turns")), Ok(Mode::Turns));
+        assert_eq!(mode(Some("everyone")).ok(), None);
+    }
+
+    #[test]
+    fn guests_receive_frames_and_the_socket_goes_with_the_host() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let Some((host, mut guest)) = paired(dir.path()) else { return };
+        assert_eq!(host.status().guests, 1);
+        assert!(start_at(dir.path().join(SOCKET_FILE), Mode::Turns).err().is_some());
+
+        host.publish(&Buffer::with_lines(["hi"]));
+        let mut received = [0u8; 64];
+        let _t = guest.set_read_timeout(Some(Duration::from_secs(1)));
+        let n = guest.read(&mut received).unwrap_or(0);
+        assert!(String::from_utf8_lossy(received.get(..n).unwrap_or_default()).contains("hi"));
+        drop(host);
+        assert!(!dir.path().join(SOCKET_FILE).exists());
+    }
+
+    #[test]
+    fn only_the_guest_holding_the_turn_types() {
+        let Ok(dir) = tempfile::tempdir() else { return };
+        let Some((mut host, mut guest)) = paired(dir.path()) else { return };
+        let key = r#"{"key":{"code":"c:x","mods":0}}"#;
+        drop(guest.write_all(format!("{key}\n").as_bytes()));
+        assert!(!wait(&mut host, |h| h.poll().is_some()));
+        drop(guest.write_all(format!("\"turn\"\n{key}\n").as_bytes()));
+        assert!(wait(&mut host, |h| h.poll() == Some(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE))));
+        assert!(host.guest_has_turn());
+
+        drop(guest);
+        assert!(wait(&mut host, |h| h.poll().is_none() && !h.guest_has_turn() && h.status().guests == 0));
+    }
+}
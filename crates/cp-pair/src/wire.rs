@@ -0,0 +1,89 @@
+//! What guests send the host: one JSON object per line.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// A line from a guest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GuestMsg {
+    /// Take the turn, or hand it back when holding it.
+    Turn,
+    /// A key pressed while holding the turn.
+    Key {
+        /// Key name: `c:x` for characters, `f:5` for function keys, else the
+        /// key's name (`enter`, `left`, ...).
+        code: String,
+        /// `KeyModifiers` bits.
+        mods: u8,
+    },
+}
+
+/// Keys with a name of their own.
+const NAMED: [(KeyCode, &str); 15] = [
+    (KeyCode::Enter, "enter"),
+    (KeyCode::Backspace, "backspace"),
+    (KeyCode::Tab, "tab"),
+    (KeyCode::BackTab, "backtab"),
+    (KeyCode::Esc, "esc"),
+    (KeyCode::Left, "left"),
+    (KeyCode::Right, "right"),
+    (KeyCode::Up, "up"),
+    (KeyCode::Down, "down"),
+    (KeyCode::Home, "home"),
+    (KeyCode::End, "end"),
+    (KeyCode::PageUp, "pageup"),
+    (KeyCode::PageDown, "pagedown"),
+    (KeyCode::Delete, "delete"),
+    (KeyCode::Insert, "insert"),
+];
+
+impl GuestMsg {
+    /// The message for `key`; `None` for keys the host has no use for.
+    pub(crate) fn key(key: KeyEvent) -> Option<Self> {
+        let code = if let KeyCode::Char(c) = key.code {
+            format!("c:{c}")
+        } else if let KeyCode::F(n) = key.code {
+            format!("f:{n}")
+        } else {
+            NAMED.iter().find(|&&(k, _)| k == key.code).map(|&(_, name)| name.to_owned())?
+        };
+        Some(Self::Key { code, mods: key.modifiers.bits() })
+    }
+
+    /// The key event a [`GuestMsg::Key`] stands for.
+    pub(crate) fn into_key(self) -> Option<KeyEvent> {
+        let Self::Key { code, mods } = self else { return None };
+        let key = if let Some(c) = code.strip_prefix("c:") {
+            let mut chars = c.chars();
+            let (Some(ch), None) = (chars.next(), chars.next()) else { return None };
+            KeyCode::Char(ch)
+        } else if let Some(n) = code.strip_prefix("f:") {
+            KeyCode::F(n.parse().ok()?)
+        } else {
+            NAMED.iter().find(|&&(_, name)| name == code).map(|&(k, _)| k)?
+        };
+        Some(KeyEvent::new(key, KeyModifiers::from_bits_truncate(mods)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_survive_the_round_trip() {
+        for key in [
+            KeyEvent::new(KeyCode::Char('\u{e9}'), KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT),
+            KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE),
+        ] {
+            let line = GuestMsg::key(key).and_then(|m| serde_json::to_string(&m).ok()).unwrap_or_default();
+            let back = serde_json::from_str::<GuestMsg>(&line).ok().and_then(GuestMsg::into_key);
+            assert_eq!(back, Some(key));
+        }
+        assert_eq!(GuestMsg::Turn.into_key(), None);
+        assert_eq!(GuestMsg::key(KeyEvent::new(KeyCode::CapsLock, KeyModifiers::NONE)), None);
+    }
+}
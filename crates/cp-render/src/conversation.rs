@@ -106,10 +106,14 @@ pub enum Overlay {
     Perf(PerfOverlay),
     /// Configuration overlay (Ctrl+H).
     Config(ConfigOverlay),
-    /// Command palette overlay (Ctrl+P / Ctrl+K).
+    /// Command palette overlay (Ctrl+P / Ctrl+K / Ctrl+B).
     CommandPalette(PaletteOverlay),
     /// Meilisearch indexing status overlay (Ctrl+I).
     SearchIndex(Box<SearchIndexOverlay>),
+    /// One step of the first-run guided tour.
+    Tour(TourOverlay),
+    /// Keybinding reference (`?` / F1).
+    KeyHelp(KeyHelpOverlay),
 }
 
 /// A question form overlay (`ask_user_question`).
@@ -182,6 +186,47 @@ pub struct PerfOverlay {
     pub sparkline: Vec<f64>,
     /// Top operations sorted by cumulative time.
     pub operations: Vec<PerfOp>,
+    /// LLM request latency per provider.
+    pub latency: Vec<PerfLatency>,
+    /// Memory held by panel content caches.
+    pub caches: PerfCaches,
+}
+
+/// Panel content caches in the perf overlay.
+#[derive(Debug, Clone, Serialize)]
+pub struct PerfCaches {
+    /// Pre-formatted total held by all panels.
+    pub held: String,
+    /// Pre-formatted budget of the evictable caches (`OFF` when disabled).
+    pub budget: String,
+    /// Panels whose content was evicted.
+    pub evicted: usize,
+    /// Largest caches, largest first.
+    pub panels: Vec<PerfCachePanel>,
+}
+
+/// One panel's cache in the perf overlay.
+#[derive(Debug, Clone, Serialize)]
+pub struct PerfCachePanel {
+    /// Panel ID and name.
+    pub label: String,
+    /// Pre-formatted size.
+    pub size: String,
+}
+
+/// One provider's request latency in the perf overlay.
+#[derive(Debug, Clone, Serialize)]
+pub struct PerfLatency {
+    /// Provider name.
+    pub provider: String,
+    /// Requests measured.
+    pub requests: usize,
+    /// Pre-formatted time to first token, `p50/p90`.
+    pub ttft: String,
+    /// Pre-formatted model (stream) time, `p50/p90`.
+    pub model: String,
+    /// Pre-formatted tool time, `p50/p90`.
+    pub tools: String,
 }
 
 /// Meilisearch process stats for perf overlay.
@@ -302,9 +347,11 @@ pub struct ConfigToggle {
     pub adjust_keys: Option<(String, String)>,
 }
 
-/// Command palette overlay (Ctrl+P / Ctrl+K).
+/// Command palette overlay (Ctrl+P / Ctrl+K / Ctrl+B).
 #[derive(Debug, Clone, Serialize)]
 pub struct PaletteOverlay {
+    /// Placeholder shown while the query is empty.
+    pub placeholder: String,
     /// Current search query.
     pub query: String,
     /// Cursor byte position in query.
@@ -324,9 +371,56 @@ pub struct PaletteEntry {
     pub description: String,
 }
 
-/// File path autocomplete popup.
+/// One step of the guided tour.
+#[derive(Debug, Clone, Serialize)]
+pub struct TourOverlay {
+    /// Step title.
+    pub title: String,
+    /// Body.
+    pub body: Vec<Block>,
+    /// 1-based number of this step.
+    pub step: usize,
+    /// Number of steps.
+    pub total: usize,
+    /// Where the card sits, next to what it explains.
+    pub anchor: TourAnchor,
+}
+
+/// Placement of a tour card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TourAnchor {
+    /// Centered on screen.
+    Center,
+    /// Just right of a sidebar `width` columns wide, top-aligned.
+    Sidebar {
+        /// Sidebar width in columns.
+        width: u16,
+    },
+    /// Centered, just above the status bar.
+    Bottom,
+}
+
+/// The keybinding reference, grouped by where the keys apply.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyHelpOverlay {
+    /// Groups in display order.
+    pub groups: Vec<KeyHelpGroup>,
+}
+
+/// One group of the keybinding reference.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyHelpGroup {
+    /// Group heading (e.g. "Conversation").
+    pub title: String,
+    /// `(keys, description)` pairs.
+    pub entries: Vec<(String, String)>,
+}
+
+/// Popup over the draft: `@` file path autocomplete or spelling suggestions.
 #[derive(Debug, Clone, Serialize)]
 pub struct Autocomplete {
+    /// Title override (spelling popup); `None` titles it with the `@` query.
+    pub title: Option<String>,
     /// Current query / prefix.
     pub query: String,
     /// Matching entries (visible window after scrolling).
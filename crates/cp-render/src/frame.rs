@@ -49,10 +49,53 @@ pub struct Sidebar {
     pub token_bar: Option<TokenBar>,
     /// Token statistics breakdown.
     pub token_stats: Option<TokenStats>,
+    /// Current-task progress card.
+    pub task_card: Option<TaskCard>,
     /// Active PR card.
     pub pr_card: Option<PrCard>,
     /// Keyboard help hints.
     pub help_hints: Vec<HelpHint>,
+    /// Token breakdown of the selected panel, shown under its entry.
+    pub token_detail: Option<TokenDetail>,
+    /// Per-message/panel token shares of the next request (dev mode only).
+    pub heat_map: Option<HeatMap>,
+}
+
+/// Compact bar of what the next request is made of, one cell per message or
+/// panel, coloured by its token share.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatMap {
+    /// Cells in prompt order: system prompt and tools, panels, then messages.
+    pub cells: Vec<HeatCell>,
+    /// Sum of all cell tokens.
+    pub total: u32,
+}
+
+/// One message or panel in the heat map.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatCell {
+    /// Panel or message ID (`sys` for the system prompt and tool definitions).
+    pub label: String,
+    /// Tokens it contributes to the request.
+    pub tokens: u32,
+    /// Share of the request, in per-mille.
+    pub permille: u16,
+    /// Heat colour for its share.
+    pub semantic: Semantic,
+}
+
+/// How a panel's share of the prompt decomposes.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TokenDetail {
+    /// Boilerplate around the content: the panel banner, its timestamp line
+    /// and, when paginated, the page notice.
+    pub header: u32,
+    /// The panel's own content (the current page when paginated).
+    pub content: u32,
+    /// Content left out of the prompt: the other pages of a paginated panel.
+    pub truncated: u32,
+    /// Total context budget, for the percentage.
+    pub budget: u32,
 }
 
 /// A single context element entry in the sidebar.
@@ -129,6 +172,24 @@ pub struct TokenRow {
     pub output_cost: Option<f64>,
 }
 
+/// Progress of the current task (everything since the user last spoke),
+/// shown under the context list.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskCard {
+    /// Name of the active todo, if any.
+    pub todo: Option<String>,
+    /// Tool turns run for this task.
+    pub tool_turns: u32,
+    /// Seconds since the task started (frozen once the stream ends).
+    pub elapsed_secs: u64,
+    /// Tokens spent on this task (input + output, live while streaming).
+    pub tokens: u32,
+    /// Whether the task is still being worked on.
+    pub streaming: bool,
+    /// Acceptance criteria met and total, when the user set goals.
+    pub goals: Option<(u32, u32)>,
+}
+
 /// Pull request summary card shown in sidebar.
 #[derive(Debug, Clone, Serialize)]
 pub struct PrCard {
@@ -182,10 +243,18 @@ pub struct StatusBar {
     pub retry_count: u8,
     /// Max retries allowed.
     pub max_retries: u8,
+    /// Seconds since the last stream delta once past the stall threshold (0 = not stalled).
+    pub stalled_secs: u32,
+    /// Active provider unreachable (connectivity monitor).
+    pub offline: bool,
     /// Number of panels currently loading.
     pub loading_count: u16,
     /// Character count of current input text.
     pub input_char_count: u32,
+    /// Highlight the whole bar (spine completion cue).
+    pub flash: bool,
+    /// Voice input stage ("REC", "Transcribing"), while dictating.
+    pub voice: Option<String>,
 }
 
 /// Primary status badge.
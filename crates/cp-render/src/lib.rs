@@ -80,6 +80,8 @@ pub enum Semantic {
     Border,
     /// Bold emphasis (combined with another semantic via [`Span::bold`]).
     Bold,
+    /// Misspelled word in the input draft (underlined).
+    Misspelled,
 }
 
 /// A styled text fragment — the atomic rendering unit.
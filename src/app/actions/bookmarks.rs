@@ -0,0 +1,111 @@
+//! `/bookmark`: labelled anchors on lines of files.
+//!
+//! `/bookmark src/app.rs:120 retry loop` adds one, `/bookmark B2` shows its
+//! line in the file panel, `/bookmark` alone selects the Bookmarks panel.
+//! Both go through the `bookmark_*` tools, so the agent sees the same state.
+
+use cp_mod_spine::types::{NotificationType, SpineState};
+use serde_json::json;
+
+use super::{ActionResult, switch_to_panel};
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::{Kind, State};
+
+/// What a `/bookmark` input asks for.
+#[derive(Debug, PartialEq, Eq)]
+enum BookmarkCommand<'input> {
+    /// Bookmark a line under a label.
+    Add {
+        /// `path:line`.
+        location: &'input str,
+        /// What the place is (may be empty).
+        label: &'input str,
+    },
+    /// Show the line of this bookmark.
+    Goto(&'input str),
+    /// Select the Bookmarks panel.
+    List,
+}
+
+/// Whether `word` is a bookmark ID (`B` and digits).
+fn is_id(word: &str) -> bool {
+    word.strip_prefix('B').is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Parse a `/bookmark` input; `None` for any other input.
+fn parse(input: &str) -> Option<BookmarkCommand<'_>> {
+    let rest = input.trim().strip_prefix("/bookmark")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let args = rest.trim();
+    Some(match args.split_once(char::is_whitespace) {
+        _ if args.is_empty() => BookmarkCommand::List,
+        None if is_id(args) => BookmarkCommand::Goto(args),
+        None => BookmarkCommand::Add { location: args, label: "" },
+        Some((location, label)) => BookmarkCommand::Add { location, label: label.trim() },
+    })
+}
+
+/// Run a `bookmark_*` tool as the user; a failure becomes a notification.
+fn run_tool(state: &mut State, name: &str, input: serde_json::Value) -> bool {
+    let tool = ToolUse::new("user-bookmark".to_owned(), name.to_owned(), input);
+    let ToolResult { content, is_error, .. } = crate::infra::tools::execute_tool(&tool, state);
+    if is_error {
+        let id = SpineState::create_notification(state, NotificationType::Custom, "bookmarks".to_owned(), content);
+        let _processed = SpineState::mark_notification_processed(state, &id);
+    }
+    !is_error
+}
+
+/// Show bookmark `id` in its file panel and select that panel.
+pub(super) fn goto(state: &mut State, id: &str) -> ActionResult {
+    if run_tool(state, "bookmark_goto", json!({ "id": id }))
+        && let Some(index) = cp_mod_bookmarks::panel_of(state, id)
+    {
+        switch_to_panel(state, index);
+    }
+    state.flags.ui.dirty = true;
+    ActionResult::Save
+}
+
+/// Handle `/bookmark ...`; returns `false` for any other input.
+pub(super) fn bookmark(state: &mut State) -> bool {
+    let input = std::mem::take(&mut state.input);
+    let Some(command) = parse(&input) else {
+        state.input = input;
+        return false;
+    };
+    state.input_cursor = 0;
+    state.input_selection_anchor = None;
+    match command {
+        BookmarkCommand::Add { location, label } => {
+            let _added = run_tool(state, "bookmark_add", json!({ "location": location, "label": label }));
+        }
+        BookmarkCommand::Goto(id) => {
+            let _saved = goto(state, id);
+        }
+        BookmarkCommand::List => {
+            if let Some(index) = state.context.iter().position(|c| c.context_type.as_str() == Kind::BOOKMARKS) {
+                switch_to_panel(state, index);
+            }
+        }
+    }
+    state.flags.ui.dirty = true;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmark_inputs_add_or_follow() {
+        let add = |location, label| Some(BookmarkCommand::Add { location, label });
+        assert_eq!(parse("/bookmark src/app.rs:12  retry loop "), add("src/app.rs:12", "retry loop"));
+        assert_eq!(parse("/bookmark src/app.rs:12"), add("src/app.rs:12", ""));
+        assert_eq!(parse("/bookmark B12"), Some(BookmarkCommand::Goto("B12")));
+        assert_eq!(parse("/bookmark"), Some(BookmarkCommand::List));
+        assert_eq!(parse("/bookmarks"), None);
+    }
+}
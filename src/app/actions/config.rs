@@ -1,5 +1,6 @@
 use cp_base::panels::time_arith;
 use cp_base::state::data::model_helpers::ModelPricing as _;
+use cp_base::ui::spell;
 
 use crate::state::State;
 
@@ -16,14 +17,27 @@ pub(crate) fn api_check(state: &mut State) -> ActionResult {
     ActionResult::StartApiCheck
 }
 
-/// Advance to the next config bar index, wrapping around.
-pub(crate) const fn next_bar(current: usize) -> usize {
-    wrap_next(current, CONFIG_BAR_COUNT)
+/// Handle `ConfigToggleSpellCheck`: flip draft spell checking and re-render
+/// the input so underlines appear or vanish at once.
+pub(crate) fn toggle_spell_check(state: &mut State) -> ActionResult {
+    let enabled = !spell::Settings::enabled(state);
+    state.set_ext(spell::Settings { enabled });
+    state.input_cache = None;
+    state.flags.ui.dirty = true;
+    ActionResult::Save
 }
 
-/// Go to the previous config bar index, wrapping around.
-pub(crate) const fn prev_bar(current: usize) -> usize {
-    if current == 0 { CONFIG_BAR_COUNT.saturating_sub(1) } else { current.saturating_sub(1) }
+/// Select the next (`forward`) or previous config bar, wrapping around.
+pub(crate) const fn select_bar(state: &mut State, forward: bool) {
+    let current = state.config_selected_bar;
+    state.config_selected_bar = if forward {
+        wrap_next(current, CONFIG_BAR_COUNT)
+    } else if current == 0 {
+        CONFIG_BAR_COUNT.saturating_sub(1)
+    } else {
+        current.saturating_sub(1)
+    };
+    state.flags.ui.dirty = true;
 }
 
 /// Compute `(current + 1) % len` without triggering arithmetic lint.
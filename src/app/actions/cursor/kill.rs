@@ -0,0 +1,43 @@
+//! Kill and yank: text removed by a kill (Ctrl+W / Ctrl+Backspace, Alt+K) is
+//! kept in a one-slot kill buffer that Ctrl+Y pastes back at the cursor.
+
+use std::ops::Range;
+
+use cp_base::ui::text;
+
+use crate::state::State;
+
+/// The last killed text (stored in `State`'s `TypeMap`).
+struct KillBuffer(String);
+
+/// Remove `range` from the draft into the kill buffer and leave the cursor
+/// at its start.
+pub(super) fn kill(state: &mut State, range: Range<usize>) {
+    let Some(killed) = state.input.get(range.clone()).map(str::to_owned) else { return };
+    if killed.is_empty() {
+        return;
+    }
+    state.input.replace_range(range.clone(), "");
+    state.input_cursor = range.start;
+    state.set_ext(KillBuffer(killed));
+}
+
+/// Handle `KillLineEnd` — kill from the cursor to the end of the line, or the
+/// line break itself when the cursor already sits at the end.
+pub(in crate::app::actions) fn handle_kill_line_end(state: &mut State) {
+    let start = text::floor_boundary(&state.input, state.input_cursor);
+    let rest = state.input.get(start..).unwrap_or("");
+    let len = match rest.find('\n') {
+        Some(0) => 1,
+        Some(newline) => newline,
+        None => rest.len(),
+    };
+    kill(state, start..start.saturating_add(len));
+}
+
+/// Handle `Yank` — insert the kill buffer at the cursor, replacing any selection.
+pub(in crate::app::actions) fn handle_yank(state: &mut State) {
+    let Some(killed) = state.get_ext::<KillBuffer>().map(|buffer| buffer.0.clone()) else { return };
+    let _r = super::delete_selection(state);
+    state.input_cursor = text::insert_at(&mut state.input, state.input_cursor, &killed);
+}
@@ -1,7 +1,18 @@
 //! Cursor movement, text editing, selection management, and command expansion logic.
 
+/// Kill buffer: Ctrl+W / Alt+K cut into it, Ctrl+Y pastes it back.
+mod kill;
+/// Undo/redo history of the draft.
+mod undo;
+/// Up/Down and Home/End over the draft's wrapped (visual) lines.
+mod vertical;
+
+use cp_base::ui::{spell, text};
+
 use super::helpers::eject_cursor_from_sentinel;
 use crate::state::State;
+pub(super) use kill::{handle_kill_line_end, handle_yank};
+pub(super) use undo::{begin_edit, finish_edit, handle_undo_redo};
 
 // ── Selection helpers ────────────────────────────────────────────────
 
@@ -111,24 +122,20 @@ fn skip_sentinel_right(input: &str, pos: usize) -> usize {
 
 // ── Raw movement helpers (no selection management) ───────────────────
 
-/// Compute cursor position one character to the left, skipping sentinels.
+/// Compute cursor position one grapheme to the left, skipping sentinels.
 fn compute_char_left(input: &str, cursor: usize) -> usize {
     if cursor == 0 {
         return 0;
     }
-    let before = input.get(..cursor).unwrap_or("");
-    let new_pos = before.char_indices().last().map_or(0, |(i, _)| i);
-    skip_sentinel_left(input, new_pos)
+    skip_sentinel_left(input, text::prev_boundary(input, cursor))
 }
 
-/// Compute cursor position one character to the right, skipping sentinels.
+/// Compute cursor position one grapheme to the right, skipping sentinels.
 fn compute_char_right(input: &str, cursor: usize) -> usize {
     if cursor >= input.len() {
         return cursor;
     }
-    let ch_len = input.get(cursor..).unwrap_or("").chars().next().map_or(0, char::len_utf8);
-    let new_pos = cursor.saturating_add(ch_len);
-    skip_sentinel_right(input, new_pos)
+    skip_sentinel_right(input, text::next_boundary(input, cursor))
 }
 
 /// Move cursor to the start of the previous word.
@@ -157,18 +164,14 @@ fn move_word_right(state: &mut State) {
     }
 }
 
-/// Move cursor to the beginning of the current line.
+/// Move cursor to the beginning of the current visual line.
 fn move_home(state: &mut State) {
-    let before_cursor = state.input.get(..state.input_cursor).unwrap_or("");
-    state.input_cursor = before_cursor.rfind('\n').map_or(0, |i| i.saturating_add(1));
-    state.input_cursor = eject_cursor_from_sentinel(&state.input, state.input_cursor);
+    state.input_cursor = eject_cursor_from_sentinel(&state.input, vertical::line_edge(state, false));
 }
 
-/// Move cursor to the end of the current line.
+/// Move cursor to the end of the current visual line.
 fn move_end(state: &mut State) {
-    let after_cursor = state.input.get(state.input_cursor..).unwrap_or("");
-    state.input_cursor = state.input_cursor.saturating_add(after_cursor.find('\n').unwrap_or(after_cursor.len()));
-    state.input_cursor = eject_cursor_from_sentinel(&state.input, state.input_cursor);
+    state.input_cursor = eject_cursor_from_sentinel(&state.input, vertical::line_edge(state, true));
 }
 
 // ── Public handlers: non-selecting movement ──────────────────────────
@@ -233,6 +236,17 @@ pub(super) fn handle_cursor_end(state: &mut State) {
     move_end(state);
 }
 
+/// Handle `CursorUp`/`CursorDown` (and their Shift variants) — move one
+/// visual line. Returns `false` when the draft has no line that way.
+pub(super) fn handle_cursor_vertical(state: &mut State, up: bool, select: bool) -> bool {
+    if select {
+        extend_selection(state);
+    } else {
+        state.input_selection_anchor = None;
+    }
+    vertical::move_vertical(state, up)
+}
+
 // ── Public handlers: selecting movement (Shift+key) ──────────────────
 
 /// Handle `CursorLeftSelect` — extend selection one character left.
@@ -400,10 +414,11 @@ pub(super) fn handle_input_backspace(state: &mut State) {
     }
 }
 
-/// Remove one character before the cursor (normal backspace).
+/// Remove one grapheme before the cursor (normal backspace).
 fn normal_backspace(state: &mut State) {
-    let prev = state.input.get(..state.input_cursor).unwrap_or("").char_indices().last().map_or(0, |(i, _)| i);
-    let _r = state.input.remove(prev);
+    let end = text::floor_boundary(&state.input, state.input_cursor);
+    let prev = text::prev_boundary(&state.input, end);
+    state.input.replace_range(prev..end, "");
     state.input_cursor = prev;
 }
 
@@ -421,12 +436,35 @@ pub(super) fn handle_delete_word_left(state: &mut State) {
         } else {
             trimmed.rfind(|c: char| c.is_whitespace()).map_or(0, |i| i.saturating_add(1))
         };
-        state.input = format!(
-            "{}{}",
-            state.input.get(..word_start).unwrap_or(""),
-            state.input.get(state.input_cursor..).unwrap_or("")
-        );
-        state.input_cursor = word_start;
+        kill::kill(state, word_start..state.input_cursor);
+    }
+}
+
+/// Delete the selection if any, else the character to the right of the cursor.
+pub(super) fn handle_input_delete(state: &mut State) {
+    if !delete_selection(state) && state.input_cursor < state.input.len() {
+        let start = text::floor_boundary(&state.input, state.input_cursor);
+        let end = text::next_boundary(&state.input, start);
+        state.input.replace_range(start..end, "");
+        state.input_cursor = start;
+    }
+}
+
+/// Insert literal text at the cursor, replacing any active selection.
+pub(super) fn handle_insert_text(state: &mut State, inserted: &str) {
+    let _r = delete_selection(state);
+    state.input_cursor = text::insert_at(&mut state.input, state.input_cursor, inserted);
+}
+
+/// Handle `SpellSuggest` — open the suggestion popup for the misspelled word
+/// at the cursor (no-op when spell checking is off or the word is known).
+pub(super) fn handle_spell_suggest(state: &mut State) {
+    if !spell::Settings::enabled(state) {
+        return;
+    }
+    let word = spell::word_at(&state.input, state.input_cursor);
+    if let Some(popup) = word.and_then(|range| spell::Popup::for_word(&state.input, range)) {
+        state.set_ext(popup);
     }
 }
 
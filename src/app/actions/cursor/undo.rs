@@ -0,0 +1,203 @@
+//! Undo/redo for the input draft (Ctrl+Z / Ctrl+Shift+Z).
+//!
+//! Every editing action snapshots the draft before it runs. Consecutive
+//! keystrokes of one kind — a typed word, a Backspace/Delete run — coalesce
+//! into a single undo unit; whitespace, a pause, a cursor jump or another
+//! kind of edit closes it. Sending the draft (or swapping it for the standing
+//! instructions) forgets the history.
+
+use cp_base::panels::now_ms;
+
+use crate::app::actions::Action;
+use crate::infra::constants::{INPUT_UNDO_COALESCE_MS, INPUT_UNDO_LIMIT};
+use crate::state::State;
+
+/// The draft text and cursor at one point of its history.
+struct Snapshot {
+    /// Draft text.
+    input: String,
+    /// Cursor byte offset.
+    cursor: usize,
+}
+
+impl Snapshot {
+    /// The current draft.
+    fn of(state: &State) -> Self {
+        Self { input: state.input.clone(), cursor: state.input_cursor }
+    }
+}
+
+/// How an action groups into undo units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    /// Typed characters: coalesce until whitespace.
+    Typing,
+    /// Backspace/Delete: coalesce.
+    Deleting,
+    /// Cuts, yanks, pastes, history recall: always a unit of their own.
+    Discrete,
+    /// Submit or standing-instructions swap: a change forgets the history.
+    Reset,
+}
+
+/// An action about to run: the draft before it and how it groups.
+pub(in crate::app::actions) struct Pending {
+    /// Draft before the action.
+    before: Snapshot,
+    /// Grouping of the action.
+    kind: EditKind,
+    /// Whether the unit closes after this action (typed whitespace).
+    closes: bool,
+}
+
+/// Edit history of the draft (stored in `State`'s `TypeMap`).
+#[derive(Default)]
+struct EditHistory {
+    /// Drafts to go back to, newest last.
+    undo: Vec<Snapshot>,
+    /// Undone drafts, newest last; cleared by any new edit.
+    redo: Vec<Snapshot>,
+    /// Kind of the unit still open for coalescing.
+    open: Option<EditKind>,
+    /// When the open unit was last extended (ms).
+    last_ms: u64,
+    /// Cursor after the last edit; a move elsewhere closes the open unit.
+    last_cursor: usize,
+}
+
+impl EditHistory {
+    /// Record a finished edit that changed the draft, opening a new unit
+    /// unless it extends the open one.
+    fn record(&mut self, pending: Pending, now: u64, cursor: usize) {
+        let coalesces = pending.kind != EditKind::Discrete
+            && self.open == Some(pending.kind)
+            && now.saturating_sub(self.last_ms) <= INPUT_UNDO_COALESCE_MS;
+        if !coalesces {
+            self.undo.push(pending.before);
+            if self.undo.len() > INPUT_UNDO_LIMIT {
+                let _oldest = self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.open = (pending.kind != EditKind::Discrete && !pending.closes).then_some(pending.kind);
+        self.last_ms = now;
+        self.last_cursor = cursor;
+    }
+}
+
+/// The draft's edit history, created on first use.
+fn history_mut(state: &mut State) -> &mut EditHistory {
+    if state.get_ext::<EditHistory>().is_none() {
+        state.set_ext(EditHistory::default());
+    }
+    state.ext_mut::<EditHistory>()
+}
+
+/// How `action` groups into undo units; `None` for actions that leave the
+/// draft alone.
+fn edit_kind(action: &Action) -> Option<EditKind> {
+    if matches!(action, Action::InputChar(_)) {
+        Some(EditKind::Typing)
+    } else if matches!(action, Action::InputBackspace | Action::InputDelete) {
+        Some(EditKind::Deleting)
+    } else if matches!(action, Action::InputSubmit | Action::ToggleStandingInstructions) {
+        Some(EditKind::Reset)
+    } else {
+        is_discrete(action).then_some(EditKind::Discrete)
+    }
+}
+
+/// Draft edits that always form an undo unit of their own.
+const fn is_discrete(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::DeleteWordLeft
+            | Action::RemoveListItem
+            | Action::KillLineEnd
+            | Action::Yank
+            | Action::InsertText(_)
+            | Action::PasteText(_)
+            | Action::HistoryPrev
+            | Action::HistoryNext
+    )
+}
+
+/// Snapshot the draft before `action` runs, if it may edit it.
+pub(in crate::app::actions) fn begin_edit(state: &State, action: &Action) -> Option<Pending> {
+    let kind = edit_kind(action)?;
+    let closes = matches!(action, &Action::InputChar(c) if c.is_whitespace());
+    Some(Pending { before: Snapshot::of(state), kind, closes })
+}
+
+/// Fold the finished action into the history. Actions that did not change
+/// the draft record nothing, but moving the cursor closes the open unit.
+pub(in crate::app::actions) fn finish_edit(state: &mut State, pending: Option<Pending>) {
+    let cursor = state.input_cursor;
+    let Some(edit) = pending.filter(|edit| edit.before.input != state.input) else {
+        if let Some(history) = state.get_ext_mut::<EditHistory>()
+            && history.last_cursor != cursor
+        {
+            history.open = None;
+        }
+        return;
+    };
+    if edit.kind == EditKind::Reset {
+        state.set_ext(EditHistory::default());
+        return;
+    }
+    history_mut(state).record(edit, now_ms(), cursor);
+}
+
+/// Handle `UndoInput` (`undo = true`) or `RedoInput`: swap the draft with the
+/// newest snapshot on that stack, saving the current draft on the other.
+pub(in crate::app::actions) fn handle_undo_redo(state: &mut State, undo: bool) {
+    let current = Snapshot::of(state);
+    let history = history_mut(state);
+    let (from, to) = if undo { (&mut history.undo, &mut history.redo) } else { (&mut history.redo, &mut history.undo) };
+    let Some(snapshot) = from.pop() else { return };
+    to.push(current);
+    history.open = None;
+    history.last_cursor = snapshot.cursor;
+    state.input_cursor = snapshot.cursor.min(snapshot.input.len());
+    state.input = snapshot.input;
+    state.input_selection_anchor = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run `action` as `apply_action` would, with `change` standing in for its handler.
+    fn edit(state: &mut State, action: &Action, change: impl FnOnce(&mut State)) {
+        let pending = begin_edit(state, action);
+        change(state);
+        finish_edit(state, pending);
+    }
+
+    fn type_str(state: &mut State, typed: &str) {
+        for c in typed.chars() {
+            edit(state, &Action::InputChar(c), |s| {
+                s.input.push(c);
+                s.input_cursor = s.input.len();
+            });
+        }
+    }
+
+    #[test]
+    fn typed_words_undo_one_at_a_time_and_redo() {
+        let mut state = State::default();
+        type_str(&mut state, "hello world");
+        edit(&mut state, &Action::InputBackspace, |s| {
+            let _c = s.input.pop();
+            s.input_cursor = s.input.len();
+        });
+        handle_undo_redo(&mut state, true);
+        assert_eq!(state.input, "hello world");
+        handle_undo_redo(&mut state, true);
+        assert_eq!(state.input, "hello ");
+        handle_undo_redo(&mut state, true);
+        assert_eq!(state.input, "");
+        handle_undo_redo(&mut state, false);
+        assert_eq!((state.input.as_str(), state.input_cursor), ("hello ", 6));
+    }
+}
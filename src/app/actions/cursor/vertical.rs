@@ -0,0 +1,53 @@
+//! Visual-line navigation in the draft: Up/Down move between wrapped lines
+//! at the same column, Home/End stop at the edges of the visual line.
+
+use std::ops::Range;
+
+use cp_base::ui::text;
+use unicode_width::UnicodeWidthStr as _;
+
+use super::super::helpers::eject_cursor_from_sentinel;
+use crate::modules::conversation::render_input_blocks::input_wrap_width;
+use crate::state::State;
+
+/// The draft's visual lines at the current conversation width.
+fn draft_lines(state: &State) -> Vec<Range<usize>> {
+    text::visual_lines(&state.input, input_wrap_width(state.last_viewport_width))
+}
+
+/// Index of the visual line holding `cursor`. A cursor at a wrap point
+/// belongs to the line that starts there.
+fn line_index(lines: &[Range<usize>], cursor: usize) -> usize {
+    lines.iter().rposition(|line| line.start <= cursor).unwrap_or(0)
+}
+
+/// Last cursor position on visual line `idx`: its end, or the grapheme before
+/// it when the next visual line starts there (a soft wrap).
+fn line_last(input: &str, lines: &[Range<usize>], idx: usize) -> usize {
+    let Some(line) = lines.get(idx) else { return input.len() };
+    let wrapped = lines.get(idx.saturating_add(1)).is_some_and(|next| next.start == line.end);
+    if wrapped { text::prev_boundary(input, line.end).max(line.start) } else { line.end }
+}
+
+/// Move the cursor one visual line up or down, keeping its column. Returns
+/// `false` when there is no line in that direction (the caller scrolls).
+pub(in crate::app::actions) fn move_vertical(state: &mut State, up: bool) -> bool {
+    let lines = draft_lines(state);
+    let idx = line_index(&lines, state.input_cursor);
+    let target = if up { idx.checked_sub(1) } else { Some(idx.saturating_add(1)) };
+    let Some((target_idx, target_line)) = target.and_then(|t| lines.get(t).map(|line| (t, line))) else {
+        return false;
+    };
+    let column = lines.get(idx).and_then(|cur| state.input.get(cur.start..state.input_cursor)).map_or(0, str::width);
+    let tail = state.input.get(target_line.start..line_last(&state.input, &lines, target_idx)).unwrap_or("");
+    let offset = text::fit_width(tail, column).len();
+    state.input_cursor = eject_cursor_from_sentinel(&state.input, target_line.start.saturating_add(offset));
+    true
+}
+
+/// Start (`end = false`) or last position (`end = true`) of the cursor's visual line.
+pub(super) fn line_edge(state: &State, end: bool) -> usize {
+    let lines = draft_lines(state);
+    let idx = line_index(&lines, state.input_cursor);
+    if end { line_last(&state.input, &lines, idx) } else { lines.get(idx).map_or(0, |line| line.start) }
+}
@@ -0,0 +1,65 @@
+//! `/goals`: the user's acceptance criteria for the task at hand.
+//!
+//! `/goals tests pass; docs updated` sets them (G1, G2), `/goals accept`
+//! lets the agent stop with criteria still unmet, `/goals` alone clears them.
+
+use cp_mod_todo::goals::GoalState;
+
+use crate::state::{Kind, State};
+
+/// What a `/goals` input asks for.
+#[derive(Debug, PartialEq, Eq)]
+enum GoalsCommand<'input> {
+    /// Replace the criteria with these (`;`-separated).
+    Set(Vec<&'input str>),
+    /// The user accepts the task as done.
+    Accept,
+    /// Drop the criteria.
+    Clear,
+}
+
+/// Parse a `/goals` input; `None` for any other input.
+fn parse(input: &str) -> Option<GoalsCommand<'_>> {
+    let rest = input.trim().strip_prefix("/goals")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(match rest.trim() {
+        "" => GoalsCommand::Clear,
+        "accept" => GoalsCommand::Accept,
+        list => GoalsCommand::Set(list.split(';').map(str::trim).filter(|c| !c.is_empty()).collect()),
+    })
+}
+
+/// Handle `/goals ...`; returns `false` for any other input.
+pub(super) fn set_goals(state: &mut State) -> bool {
+    let input = std::mem::take(&mut state.input);
+    let Some(command) = parse(&input) else {
+        state.input = input;
+        return false;
+    };
+    let goals = GoalState::get_mut(state);
+    match command {
+        GoalsCommand::Set(criteria) => goals.set(&criteria),
+        GoalsCommand::Accept => goals.accepted = true,
+        GoalsCommand::Clear => *goals = GoalState::default(),
+    }
+    state.input_cursor = 0;
+    state.input_selection_anchor = None;
+    state.touch_panel(Kind::TODO);
+    state.flags.ui.dirty = true;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goals_split_on_semicolons() {
+        assert_eq!(parse("/goals tests pass; ; docs"), Some(GoalsCommand::Set(vec!["tests pass", "docs"])));
+        assert_eq!(parse("/goals accept"), Some(GoalsCommand::Accept));
+        assert_eq!(parse("/goals"), Some(GoalsCommand::Clear));
+        assert_eq!(parse("/goalsetting"), None);
+    }
+}
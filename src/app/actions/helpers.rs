@@ -2,6 +2,7 @@ use std::sync::LazyLock;
 
 use regex::Regex;
 
+use crate::modules::overview::panel_layout;
 use crate::state::{Kind, State};
 
 use super::ActionResult;
@@ -10,14 +11,21 @@ use super::config;
 /// Switch to a target panel, saving the outgoing panel's scroll state and restoring
 /// the incoming panel's scroll state. This preserves scroll position across TAB switches.
 pub(crate) fn switch_to_panel(state: &mut State, target_index: usize) {
-    // Save outgoing panel's scroll state
+    save_scroll(state);
+    state.selected_context = target_index;
+    restore_scroll(state);
+}
+
+/// Store the view's scroll state into the selected panel.
+fn save_scroll(state: &mut State) {
     if let Some(outgoing) = state.context.get_mut(state.selected_context) {
         outgoing.scroll_state.offset = state.scroll_offset;
         outgoing.scroll_state.user_scrolled = state.flags.stream.user_scrolled;
     }
-    // Switch to target
-    state.selected_context = target_index;
-    // Restore incoming panel's scroll state
+}
+
+/// Load the selected panel's saved scroll state into the view.
+fn restore_scroll(state: &mut State) {
     if let Some(incoming) = state.context.get(state.selected_context) {
         state.scroll_offset = incoming.scroll_state.offset;
         state.flags.stream.user_scrolled = incoming.scroll_state.user_scrolled;
@@ -130,20 +138,13 @@ pub(super) fn create_new_context(state: &mut State) -> ActionResult {
 /// Maximum dynamic entries per sidebar page (must match `render_sidebar.rs`).
 const DYNAMIC_PAGE_SIZE: usize = 10;
 
-/// Numeric panel-ID sort key (`P12` → 12), `usize::MAX` when unparsable.
-fn panel_id_key(state: &State, idx: usize) -> usize {
-    state
-        .context
-        .get(idx)
-        .and_then(|el| el.id.strip_prefix('P'))
-        .and_then(|n| n.parse::<usize>().ok())
-        .unwrap_or(usize::MAX)
-}
-
-/// Context indices sorted by numeric panel ID (shared ordering).
+/// Context indices in sidebar order (shared ordering): fixed panels, then
+/// manually ordered panels, then the rest by numeric panel ID.
 fn sorted_by_panel_id(state: &State) -> Vec<usize> {
     let mut sorted: Vec<usize> = (0..state.context.len()).collect();
-    sorted.sort_by_key(|&a| panel_id_key(state, a));
+    sorted.sort_by_key(|&a| {
+        state.context.get(a).map_or((usize::MAX, usize::MAX), |c| panel_layout::display_key(state, c))
+    });
     sorted
 }
 
@@ -215,3 +216,47 @@ pub(super) fn page_dynamic(state: &mut State, forward: bool) {
         switch_to_panel(state, selected);
     }
 }
+
+// =============================================================================
+// Sidebar panel management
+// =============================================================================
+
+/// Close panels `ids`, then keep the selection on the same panel if it
+/// survived, else move it to the nearest surviving panel in sidebar order.
+fn close_and_reselect(state: &mut State, ids: &[String]) -> ActionResult {
+    let sorted: Vec<String> =
+        sorted_by_panel_id(state).into_iter().filter_map(|i| state.context.get(i).map(|c| c.id.clone())).collect();
+    let selected_id = state.context.get(state.selected_context).map(|c| c.id.clone());
+    let pos = sorted.iter().position(|id| Some(id) == selected_id.as_ref()).unwrap_or(0);
+    save_scroll(state);
+    if !panel_layout::close_panels(state, ids) {
+        return ActionResult::Nothing;
+    }
+    let after = sorted.iter().skip(pos);
+    let before = sorted.iter().take(pos).rev();
+    state.selected_context =
+        after.chain(before).find_map(|id| state.context.iter().position(|c| c.id == *id)).unwrap_or(0);
+    restore_scroll(state);
+    ActionResult::Save
+}
+
+/// `x` in the sidebar: close the selected dynamic panel (pinned panels refuse).
+pub(super) fn close_selected_panel(state: &mut State) -> ActionResult {
+    let Some(id) =
+        state.context.get(state.selected_context).filter(|c| panel_layout::is_movable(c)).map(|c| c.id.clone())
+    else {
+        return ActionResult::Nothing;
+    };
+    close_and_reselect(state, &[id])
+}
+
+/// Shift+Up/Down: move the selected dynamic panel one step.
+pub(super) fn move_selected_panel(state: &mut State, up: bool) -> ActionResult {
+    if panel_layout::move_selected(state, up) { ActionResult::Save } else { ActionResult::Nothing }
+}
+
+/// Ctrl+P → "Close deprecated panels".
+pub(super) fn close_deprecated_panels(state: &mut State) -> ActionResult {
+    let ids = panel_layout::deprecated_ids(state);
+    close_and_reselect(state, &ids)
+}
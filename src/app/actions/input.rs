@@ -1,6 +1,9 @@
+use crate::infra::tools::ToolUse;
 use crate::state::persistence::message::record_prompt_history;
 use crate::state::persistence::{delete_message, save_message};
-use crate::state::{Kind, Message, State, estimate_tokens};
+use crate::state::{Kind, Message, MsgKind, MsgStatus, State, estimate_tokens};
+use cp_base::state::data::review::PANEL_NOTE_META;
+use cp_base::state::data::sticky::StandingInstructions;
 use cp_mod_prompt::types::PromptItem;
 use cp_mod_spine::types::{NotificationType, SpineState};
 use cp_mod_threads::types::{FocusState, ThreadMessage, ThreadStatus, ThreadsState};
@@ -9,6 +12,20 @@ use super::ActionResult;
 use super::helpers::{find_context_by_id, parse_context_pattern};
 use crate::modules::all_modules;
 
+/// Run a state-changing slash command typed by the user; `false` when the
+/// input is none of them.
+fn slash_command(state: &mut State) -> bool {
+    // `/goals [a; b; ...|accept]`: the task's acceptance criteria;
+    // `/bookmark path:line label` or `/bookmark B2`: add or follow a bookmark;
+    // `/snippet id name=value [> path:line]`: boilerplate into the draft or a file;
+    // `/k8s-allow`, `/db-writes`, `/commit-gate` and the other user-only
+    // settings listed in `permissions`
+    super::goals::set_goals(state)
+        || super::bookmarks::bookmark(state)
+        || super::snippets::snippet(state)
+        || super::permissions::permission(state)
+}
+
 /// Handle `InputSubmit` action — context switching, message creation, stream start.
 pub(crate) fn handle_input_submit(state: &mut State) -> ActionResult {
     if state.input.is_empty() {
@@ -25,6 +42,20 @@ pub(crate) fn handle_input_submit(state: &mut State) -> ActionResult {
         return ActionResult::Nothing;
     }
 
+    // `/report [focus]`: summarized work report, written in the background
+    if request_work_report(state) {
+        return ActionResult::Nothing;
+    }
+
+    if slash_command(state) {
+        return ActionResult::Save;
+    }
+
+    // `/note [ID] [text]`: private annotation, never sent
+    if annotate(state) {
+        return ActionResult::Save;
+    }
+
     // Threads view: route input to the selected thread instead of conversation
     if state.view_mode == cp_base::state::data::config::ViewMode::Threads {
         return handle_thread_input_submit(state);
@@ -34,6 +65,7 @@ pub(crate) fn handle_input_submit(state: &mut State) -> ActionResult {
     let commanded = replace_commands(&state.input, &commands);
     // Expand paste sentinels: replace \x00{idx}\x00 with actual paste buffer content
     let content = expand_paste_sentinels(&commanded, &state.paste_buffers);
+    open_mentioned_files(state, &content);
     state.input.clear();
     state.input_cursor = 0;
     state.input_selection_anchor = None;
@@ -49,11 +81,7 @@ pub(crate) fn handle_input_submit(state: &mut State) -> ActionResult {
 
     // Capture info for notification before moving user_msg
     let user_id_str = user_id.clone();
-    let content_preview = if content.len() > 80 {
-        format!("{}...", content.get(..content.floor_char_boundary(80)).unwrap_or(""))
-    } else {
-        content.clone()
-    };
+    let content_preview = cp_base::ui::text::ellipsize(&content, 80, "...");
 
     // Record to persistent prompt history (append-only, survives clears)
     record_prompt_history(&content);
@@ -130,6 +158,110 @@ pub(crate) fn handle_clear_conversation(state: &mut State) -> ActionResult {
     ActionResult::Save
 }
 
+/// Open the files picked from the `@` popup that `content` still mentions, when
+/// the auto-open toggle is on, so they reach the model as panels with the message.
+fn open_mentioned_files(state: &mut State, content: &str) {
+    let Some(ac) = state.get_ext_mut::<cp_base::state::autocomplete::Suggestions>() else { return };
+    let mut paths = std::mem::take(&mut ac.mentioned);
+    if !state.flags.config.mention_auto_open {
+        return;
+    }
+    paths.retain(|p| content.contains(p.as_str()));
+    paths.sort();
+    paths.dedup();
+    if !paths.is_empty() {
+        let tool = ToolUse::new("mention_open".to_owned(), "Open".to_owned(), serde_json::json!({ "path": paths }));
+        let _r = crate::infra::tools::execute_tool(&tool, state);
+    }
+}
+
+/// The focus text of a `/report [focus]` input, or `None` for any other input.
+fn report_focus(input: &str) -> Option<String> {
+    let rest = input.trim().strip_prefix("/report")?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim().to_owned())
+}
+
+/// Handle `/report [focus]`: ask for a summarized work report and show the
+/// Actions panel. Returns `false` (a normal submit follows) for any other input
+/// or when the Actions panel is not open.
+fn request_work_report(state: &mut State) -> bool {
+    let Some(focus) = report_focus(&state.input) else { return false };
+    if !cp_mod_ledger::request_summary(state, &focus) {
+        return false;
+    }
+    if let Some(index) = state.context.iter().position(|c| c.context_type.as_str() == Kind::LEDGER) {
+        super::switch_to_panel(state, index);
+    }
+    replace_input(state, String::new());
+    true
+}
+
+/// The arguments of a `/note [ID] [text]` input, or `None` for any other input.
+fn note_args(input: &str) -> Option<&str> {
+    let rest = input.trim().strip_prefix("/note")?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+}
+
+/// Handle `/note [ID] [text]`: set the private note of panel or message `ID`,
+/// by default the latest assistant message; no text removes it. Returns
+/// `false` for any other input or when there is nothing to annotate.
+fn annotate(state: &mut State) -> bool {
+    let Some(args) = note_args(&state.input).map(str::to_owned) else { return false };
+    let (first, rest) = args.split_once(char::is_whitespace).unwrap_or((&args, ""));
+    let text = |raw: &str| Some(raw.trim().to_owned()).filter(|t| !t.is_empty());
+    if let Some(ctx) = state.context.iter_mut().find(|c| c.id == first) {
+        match text(rest) {
+            Some(note) => ctx.set_meta(PANEL_NOTE_META, &note),
+            None => drop(ctx.metadata.remove(PANEL_NOTE_META)),
+        }
+    } else {
+        let annotatable = |m: &Message| m.msg_type == MsgKind::TextMessage && m.status != MsgStatus::Deleted;
+        let named = state.messages.iter().position(|m| m.id == first && annotatable(m));
+        let (index, note) = match named {
+            Some(index) => (Some(index), text(rest)),
+            None => (state.messages.iter().rposition(|m| m.role == "assistant" && annotatable(m)), text(&args)),
+        };
+        let Some(msg) = index.and_then(|i| state.messages.get_mut(i)) else { return false };
+        msg.note = note;
+        save_message(msg);
+    }
+    replace_input(state, String::new());
+    true
+}
+
+/// Put `text` in the input field with the cursor at its end.
+fn replace_input(state: &mut State, text: String) {
+    state.input_cursor = text.len();
+    state.input = text;
+    state.input_selection_anchor = None;
+    state.flags.ui.dirty = true;
+}
+
+/// Handle `ToggleStandingInstructions`: load the instructions into the input
+/// field (stashing the draft), or save the edited text and restore the draft.
+pub(crate) fn toggle_standing_instructions(state: &mut State) -> ActionResult {
+    let current = std::mem::take(&mut state.input);
+    let expanded = expand_paste_sentinels(&current, &state.paste_buffers);
+    let instructions = state.ext_mut::<StandingInstructions>();
+    if let Some(draft) = instructions.stashed_draft.take() {
+        expanded.trim().clone_into(&mut instructions.text);
+        replace_input(state, draft);
+        return ActionResult::Save;
+    }
+    // The draft keeps its paste sentinels; the buffers stay until it is sent.
+    instructions.stashed_draft = Some(current);
+    let text = instructions.text.clone();
+    replace_input(state, text);
+    ActionResult::Nothing
+}
+
+/// Handle `CancelStandingInstructions`: drop the edit and restore the draft.
+pub(crate) fn cancel_standing_instructions(state: &mut State) {
+    if let Some(draft) = state.ext_mut::<StandingInstructions>().stashed_draft.take() {
+        replace_input(state, draft);
+    }
+}
+
 /// Create a `UserMessage` notification in the spine system.
 /// This is the primary trigger for starting a stream — the spine engine
 /// will detect the unprocessed notification and launch streaming.
@@ -230,11 +362,7 @@ fn handle_thread_create(state: &mut State) -> ActionResult {
     }
 
     // Cap thread name length
-    let name = if raw_name.len() > MAX_THREAD_NAME_LEN {
-        raw_name.get(..raw_name.floor_char_boundary(MAX_THREAD_NAME_LEN)).unwrap_or(&raw_name).to_owned()
-    } else {
-        raw_name
-    };
+    let name = cp_base::ui::text::fit_width(&raw_name, MAX_THREAD_NAME_LEN).to_owned();
 
     // Generate thread ID and create the thread
     let threads_state = ThreadsState::get_mut(state);
@@ -338,3 +466,35 @@ fn replace_commands(input: &str, commands: &[PromptItem]) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cp_base::state::context::make_default_entry;
+
+    /// The note on the first panel.
+    fn panel_note(state: &State) -> Option<String> {
+        state.context.first().and_then(|c| c.get_meta_str(PANEL_NOTE_META)).map(str::to_owned)
+    }
+
+    #[test]
+    fn only_the_note_command_is_taken() {
+        assert_eq!(note_args("/note P3 wrong file"), Some("P3 wrong file"));
+        assert_eq!(note_args("/notebook"), None);
+    }
+
+    #[test]
+    fn notes_attach_to_panels_and_clear_when_empty() {
+        let mut state = State::default();
+        state.context.push(make_default_entry("P3", Kind::new(Kind::PASTED), "Pasted", false));
+        state.input = "/note P3 stale, reopen it".to_owned();
+        assert!(annotate(&mut state) && state.input.is_empty());
+        assert_eq!(panel_note(&state).as_deref(), Some("stale, reopen it"));
+        state.input = "/note P3".to_owned();
+        assert!(annotate(&mut state));
+        assert_eq!(panel_note(&state), None);
+        state.input = "/note nothing to annotate".to_owned();
+        assert!(!annotate(&mut state));
+    }
+}
@@ -2,13 +2,18 @@
 //!
 //! - `helpers` — Utility functions (`clean_llm_id_prefix`, `parse_context_pattern`, `find_context_by_id`)
 //! - `input` — Input submission and conversation clearing
+//! - `goals` — `/goals` acceptance criteria
+//! - `bookmarks` — `/bookmark` anchors on lines of files
+//! - `snippets` — `/snippet` boilerplate into the draft or a file
+//! - `permissions` — user-only permission commands the AI has no tool for
 //! - `streaming` — Stream append/done/error handling
 //! - `config` — Configuration bar and theme controls
-//! - `cursor` — Cursor movement, text editing, and command expansion
+//! - `cursor` — Cursor movement, text editing, draft undo, and command expansion
 //! - `history` — Prompt history navigation and panel clipboard copy
 //! - `threads` — Thread action handlers (`Thread*` variants)
+//! - `templates` — Conversation templates from the command palette
 //!
-//! [`apply_action`] itself is a single flat `match` over the closed [`Action`]
+//! The dispatch behind [`apply_action`] is a single flat `match` over the closed [`Action`]
 //! enum — the dispatch twin of a flat aggregate initializer. Every arm delegates
 //! to a one-line handler (here or in a sibling module), so the body is a straight
 //! variant→handler table.
@@ -20,22 +25,32 @@
 //! Rust requires each `match` to cover every variant, and a `_` / bare-binding
 //! catch-all trips `wildcard_enum_match_arm` (forbid). A by-value match also
 //! avoids `pattern_type_mismatch` (payload fields bind owned, not by-ref). The
-//! only residual is length — intrinsic to a 70-action enum — so `apply_action`
+//! only residual is length — intrinsic to a 70-action enum — so the dispatch
 //! carries a single `clippy::too_many_lines` allowance, exactly like the flat
 //! `State::default` initializer.
 
+/// `/bookmark`: labelled anchors on lines of files.
+mod bookmarks;
 /// Configuration bar and theme controls.
 pub(crate) mod config;
 /// Cursor movement, text editing, and command expansion.
 mod cursor;
+/// `/goals`: the task's acceptance criteria.
+mod goals;
 /// Utility functions for action handling.
 pub(crate) mod helpers;
 /// Prompt history navigation and panel clipboard copy.
 mod history;
 /// Input submission and conversation clearing.
 pub(crate) mod input;
+/// User-only permission commands.
+mod permissions;
+/// `/snippet`: approved boilerplate into the draft or a file.
+pub(crate) mod snippets;
 /// Stream append/done/error handling.
 pub(crate) mod streaming;
+/// Conversation templates: panels, agent and a seeded first message.
+pub(crate) mod templates;
 /// Thread action handlers (Thread* variants).
 mod threads;
 
@@ -43,11 +58,14 @@ mod threads;
 pub(crate) use helpers::{clean_llm_id_prefix, find_context_by_id, parse_context_pattern, switch_to_panel};
 
 // Re-export Action/ActionResult from cp-base (shared with module crates)
-pub(crate) use cp_base::state::actions::{Action, ActionResult};
+pub(crate) use cp_base::state::actions::{Action, ActionResult, TourMove};
 
 use crate::infra::constants::{SCROLL_ACCEL_INCREMENT, SCROLL_ACCEL_MAX};
 use crate::state::{Kind, State, StreamPhase};
 use cp_base::cast::float_math;
+use cp_base::config::constants::SCROLL_ARROW_AMOUNT;
+use cp_base::state::data::sticky::StandingInstructions;
+use cp_base::ui::text;
 
 // ── Multi-line leaf handlers (kept out of the match so each arm stays 1 line) ─
 
@@ -106,12 +124,26 @@ fn handle_copy_index_overlay(state: &mut State) {
     state.flags.ui.dirty = true;
 }
 
+/// Open the `@` popup at `anchor_pos`, capturing the workspace files and open
+/// panels it searches while active.
+fn open_mention_popup(state: &mut State, anchor_pos: usize) {
+    let filter = cp_mod_tree::types::TreeState::get(state).filter.clone();
+    let entries = cp_mod_tree::tools::list_dir_entries(&filter, "", "");
+    let panels: Vec<(String, String)> = state.context.iter().map(|c| (c.id.clone(), c.name.clone())).collect();
+    if let Some(ac) = state.get_ext_mut::<cp_base::state::autocomplete::Suggestions>() {
+        ac.activate(anchor_pos);
+        ac.workspace_files = cp_mod_tree::tools::workspace_files(&filter);
+        ac.panels = panels;
+        let matches = ac.build_matches(entries);
+        ac.set_matches(matches);
+    }
+}
+
 /// Insert a typed character at the cursor, replacing any active selection,
 /// then trigger `@`-autocomplete or `/command` expansion when warranted.
 fn handle_input_char(state: &mut State, ch: char) {
     let _r = cursor::delete_selection(state);
-    state.input.insert(state.input_cursor, ch);
-    state.input_cursor = state.input_cursor.saturating_add(ch.len_utf8());
+    state.input_cursor = text::insert_at(&mut state.input, state.input_cursor, ch.encode_utf8(&mut [0; 4]));
 
     // '@' at input start or after whitespace opens directory autocomplete.
     if ch == '@' {
@@ -123,12 +155,7 @@ fn handle_input_char(state: &mut State, ch: char) {
                 .get(anchor_pos.saturating_sub(1))
                 .is_some_and(|&b| b == b' ' || b == b'\n' || b == b'\t');
         if should_trigger {
-            let filter = cp_mod_tree::types::TreeState::get(state).filter.clone();
-            let entries = cp_mod_tree::tools::list_dir_entries(&filter, "", "");
-            if let Some(ac) = state.get_ext_mut::<cp_base::state::autocomplete::Suggestions>() {
-                ac.activate(anchor_pos);
-                ac.set_matches(entries);
-            }
+            open_mention_popup(state, anchor_pos);
         }
     }
 
@@ -140,13 +167,6 @@ fn handle_input_char(state: &mut State, ch: char) {
     }
 }
 
-/// Insert literal text at the cursor, replacing any active selection.
-fn handle_insert_text(state: &mut State, text: &str) {
-    let _r = cursor::delete_selection(state);
-    state.input.insert_str(state.input_cursor, text);
-    state.input_cursor = state.input_cursor.saturating_add(text.len());
-}
-
 /// Stash a pasted blob in a paste buffer and insert a `\x00{idx}\x00` sentinel
 /// at the cursor (expanded to the real text at submit time).
 fn handle_paste_text(state: &mut State, text: String) {
@@ -155,15 +175,7 @@ fn handle_paste_text(state: &mut State, text: String) {
     state.paste_buffers.push(text);
     state.paste_buffer_labels.push(None);
     let sentinel = format!("\x00{idx}\x00");
-    state.input.insert_str(state.input_cursor, &sentinel);
-    state.input_cursor = state.input_cursor.saturating_add(sentinel.len());
-}
-
-/// Delete the selection if any, else the character to the right of the cursor.
-fn handle_input_delete(state: &mut State) {
-    if !cursor::delete_selection(state) && state.input_cursor < state.input.len() {
-        let _r = state.input.remove(state.input_cursor);
-    }
+    state.input_cursor = text::insert_at(&mut state.input, state.input_cursor, &sentinel);
 }
 
 /// Scroll the conversation up (`up = true`) or down, applying + growing the
@@ -179,6 +191,14 @@ const fn handle_scroll(state: &mut State, amount: f32, up: bool) {
     state.scroll_accel = float_math::add_f32(state.scroll_accel, SCROLL_ACCEL_INCREMENT).min(SCROLL_ACCEL_MAX);
 }
 
+/// Up/Down: move between the draft's visual lines, scrolling the
+/// conversation once the cursor has no line left in that direction.
+fn handle_cursor_vertical(state: &mut State, up: bool, select: bool) {
+    if !cursor::handle_cursor_vertical(state, up, select) {
+        handle_scroll(state, SCROLL_ARROW_AMOUNT, up);
+    }
+}
+
 /// Send `keys` to the tmux `pane_id` and record them on the matching context.
 fn handle_tmux_send_keys(state: &mut State, pane_id: &str, keys: &str) {
     let _r = std::process::Command::new("tmux").args(["send-keys", "-t", pane_id, keys]).output();
@@ -191,6 +211,9 @@ fn handle_tmux_send_keys(state: &mut State, pane_id: &str, keys: &str) {
 /// Record the trimmed input into prompt history (resetting nav), then delegate
 /// to the input module's submit handler.
 fn handle_input_submit_action(state: &mut State) -> ActionResult {
+    if state.get_ext::<StandingInstructions>().is_some_and(StandingInstructions::is_editing) {
+        return input::toggle_standing_instructions(state);
+    }
     history::ensure_history_nav(state);
     let trimmed = state.input.trim_end().to_owned();
     let nav = state.ext_mut::<history::PromptHistoryNav>();
@@ -209,6 +232,48 @@ fn handle_select_context_by_id(state: &mut State, id: &str) {
     }
 }
 
+/// Alt+1..9: jump to the slot's panel, or persist a slot (un)binding.
+fn handle_quick_slot(state: &mut State, slot: u8) -> ActionResult {
+    use crate::modules::overview::panel_group::{SlotOutcome, quick_slot};
+    match quick_slot(state, slot) {
+        SlotOutcome::Jump(idx) => {
+            switch_to_panel(state, idx);
+            state.flags.ui.dirty = true;
+            ActionResult::Nothing
+        }
+        SlotOutcome::Changed => ActionResult::Save,
+        SlotOutcome::Ignored => ActionResult::Nothing,
+    }
+}
+
+/// Jump to the file on line `slot` of the Recent panel, reopening it if
+/// its panel was closed.
+fn handle_reopen_recent(state: &mut State, slot: u8) -> ActionResult {
+    let Some(idx) = crate::modules::recent::reopen(state, slot) else { return ActionResult::Nothing };
+    switch_to_panel(state, idx);
+    state.flags.ui.dirty = true;
+    ActionResult::Save
+}
+
+/// Step through the loaded review from its panel.
+fn handle_review(state: &mut State, step: cp_base::state::actions::ReviewMove) -> ActionResult {
+    if cp_mod_review::step(state, step) { ActionResult::Save } else { ActionResult::Nothing }
+}
+
+/// Step through the refused edits from the Approvals panel; a decision is
+/// sent to the agent as a notification, so it retries or drops the edits.
+fn handle_approvals(state: &mut State, step: cp_base::state::actions::ApprovalMove) {
+    if let Some(decision) = cp_mod_git::approval_step(state, step) {
+        let _id = cp_mod_spine::types::SpineState::create_notification(
+            state,
+            cp_mod_spine::types::NotificationType::Custom,
+            "approvals".to_owned(),
+            decision,
+        );
+    }
+    state.flags.ui.dirty = true;
+}
+
 /// Toggle the perf monitor overlay and mark the UI dirty.
 fn toggle_perf_monitor(state: &mut State) {
     state.flags.ui.perf_enabled = crate::ui::perf::PERF.toggle();
@@ -234,6 +299,15 @@ const fn cycle_view_mode(state: &mut State) {
 
 // ── Entry point ──────────────────────────────────────────────────────────────
 
+/// Apply an `Action`, recording any change it makes to the input draft in
+/// the draft's undo history.
+pub(crate) fn apply_action(state: &mut State, action: Action) -> ActionResult {
+    let edit = cursor::begin_edit(state, &action);
+    let result = dispatch(state, action);
+    cursor::finish_edit(state, edit);
+    result
+}
+
 /// Dispatch an `Action` to its handler, returning the resulting [`ActionResult`].
 ///
 /// A single flat `match` over every `Action` variant; each arm is a one-line
@@ -243,16 +317,16 @@ const fn cycle_view_mode(state: &mut State) {
     clippy::too_many_lines,
     reason = "exhaustive dispatch over ~70 Action variants; splitting requires either a forbidden wildcard catch-all (wildcard_enum_match_arm) or a duplicated giant or-pattern, both worse than one flat variant→handler table — the dispatch twin of the flat State::default initializer"
 )]
-pub(crate) fn apply_action(state: &mut State, action: Action) -> ActionResult {
+fn dispatch(state: &mut State, action: Action) -> ActionResult {
     // Reset scroll acceleration on any non-scroll action.
-    if !matches!(action, Action::ScrollUp(_) | Action::ScrollDown(_)) {
+    if !matches!(action, Action::ScrollUp(_) | Action::ScrollDown(_) | Action::CursorUp | Action::CursorDown) {
         state.scroll_accel = 1.0;
     }
 
     match action {
         // ── Cursor / text-edit / history (side-effect only → Nothing) ────────
         Action::InputBackspace => cursor::handle_input_backspace(state),
-        Action::InputDelete => handle_input_delete(state),
+        Action::InputDelete => cursor::handle_input_delete(state),
         Action::DeleteWordLeft => cursor::handle_delete_word_left(state),
         Action::RemoveListItem => cursor::handle_remove_list_item(state),
         Action::CursorWordLeft => cursor::handle_cursor_word_left(state),
@@ -267,10 +341,26 @@ pub(crate) fn apply_action(state: &mut State, action: Action) -> ActionResult {
         Action::CursorWordRightSelect => cursor::handle_cursor_word_right_select(state),
         Action::CursorHomeSelect => cursor::handle_cursor_home_select(state),
         Action::CursorEndSelect => cursor::handle_cursor_end_select(state),
+        Action::CursorUp => handle_cursor_vertical(state, true, false),
+        Action::CursorDown => handle_cursor_vertical(state, false, false),
+        Action::CursorUpSelect => handle_cursor_vertical(state, true, true),
+        Action::CursorDownSelect => handle_cursor_vertical(state, false, true),
+        Action::KillLineEnd => cursor::handle_kill_line_end(state),
+        Action::Yank => cursor::handle_yank(state),
+        Action::SpellSuggest => cursor::handle_spell_suggest(state),
+        Action::UndoInput => cursor::handle_undo_redo(state, true),
+        Action::RedoInput => cursor::handle_undo_redo(state, false),
         Action::SelectAll => cursor::handle_select_all(state),
         Action::HistoryPrev => history::handle_history_prev(state),
         Action::HistoryNext => history::handle_history_next(state),
         Action::CopyPanelContent => history::handle_copy_panel_content(state),
+        Action::PasteToPanel => {
+            if crate::modules::pasted::convert_offered_paste(state) {
+                return ActionResult::Save;
+            }
+        }
+        Action::ToggleStandingInstructions => return input::toggle_standing_instructions(state),
+        Action::CancelStandingInstructions => input::cancel_standing_instructions(state),
 
         // ── Text insertion (payload) ─────────────────────────────────────────
         Action::InputChar(ch) => {
@@ -281,7 +371,7 @@ pub(crate) fn apply_action(state: &mut State, action: Action) -> ActionResult {
         }
         Action::InsertText(text) => {
             return {
-                handle_insert_text(state, &text);
+                cursor::handle_insert_text(state, &text);
                 ActionResult::Nothing
             };
         }
@@ -317,9 +407,28 @@ pub(crate) fn apply_action(state: &mut State, action: Action) -> ActionResult {
         Action::PageDynamicNext => helpers::page_dynamic(state, true),
         Action::PageDynamicPrev => helpers::page_dynamic(state, false),
         Action::SelectContextById(id) => handle_select_context_by_id(state, &id),
+        Action::QuickSlot(slot) => return handle_quick_slot(state, slot),
+        Action::ReopenRecent(slot) => return handle_reopen_recent(state, slot),
+        Action::GotoBookmark(id) => return bookmarks::goto(state, &id),
+        Action::Review(step) => return handle_review(state, step),
+        Action::Approvals(step) => handle_approvals(state, step),
+        Action::CloseSelectedPanel => return helpers::close_selected_panel(state),
+        Action::MoveSelectedPanel(up) => return helpers::move_selected_panel(state, up),
+        Action::CloseDeprecatedPanels => return helpers::close_deprecated_panels(state),
+        Action::CloseSuggestedPanels => {
+            if crate::modules::overview::context_health::close_suggested(state) {
+                return ActionResult::Save;
+            }
+        }
+        Action::UndoPanelGc => {
+            if crate::modules::overview::panel_gc::undo(state) > 0 {
+                return ActionResult::Save;
+            }
+        }
 
         // ── Config / toggles / theme ─────────────────────────────────────────
         Action::TogglePerfMonitor => toggle_perf_monitor(state),
+        Action::ToggleVoiceInput => crate::infra::voice::toggle(state),
         Action::ToggleConfigView => {
             state.flags.config.config_view = !state.flags.config.config_view;
             state.flags.ui.dirty = true;
@@ -329,19 +438,30 @@ pub(crate) fn apply_action(state: &mut State, action: Action) -> ActionResult {
             state.flags.ui.dirty = true;
         }
         Action::CopyIndexOverlay => handle_copy_index_overlay(state),
+        Action::Tour(step) => crate::ui::help::tour::apply(state, step),
+        Action::ToggleKeyHelp => crate::ui::help::keymap::toggle(state),
+        Action::ToggleTokenDetail => crate::ui::ir::sidebar::toggle_token_detail(state),
         Action::ConfigToggleReverie => {
             state.flags.config.reverie_enabled = !state.flags.config.reverie_enabled;
             state.flags.ui.dirty = true;
             return ActionResult::Save;
         }
-        Action::ConfigSelectNextBar => {
-            state.config_selected_bar = config::next_bar(state.config_selected_bar);
+        Action::ConfigToggleMentionOpen => {
+            state.flags.config.mention_auto_open = !state.flags.config.mention_auto_open;
             state.flags.ui.dirty = true;
+            return ActionResult::Save;
         }
-        Action::ConfigSelectPrevBar => {
-            state.config_selected_bar = config::prev_bar(state.config_selected_bar);
-            state.flags.ui.dirty = true;
+        Action::ConfigToggleSpellCheck => return config::toggle_spell_check(state),
+        Action::ConfigCyclePanelGc => {
+            crate::modules::overview::panel_gc::cycle_policy(state);
+            return ActionResult::Save;
+        }
+        Action::ConfigCycleCacheBudget => {
+            crate::modules::overview::cache_budget::cycle_budget(state);
+            return ActionResult::Save;
         }
+        Action::ConfigSelectNextBar => config::select_bar(state, true),
+        Action::ConfigSelectPrevBar => config::select_bar(state, false),
         Action::ConfigIncreaseSelectedBar => return config::handle_config_increase_bar(state),
         Action::ConfigDecreaseSelectedBar => return config::handle_config_decrease_bar(state),
         Action::ConfigNextTheme => return config::handle_config_next_theme(state),
@@ -414,7 +534,7 @@ pub(crate) fn apply_action(state: &mut State, action: Action) -> ActionResult {
             return ActionResult::Save;
         }
         // Handled in app.rs directly; a no-op here.
-        Action::OpenCommandPalette | Action::None => {}
+        Action::OpenCommandPalette | Action::OpenCleanScopePicker | Action::OpenMarkers | Action::None => {}
         Action::CycleViewMode => cycle_view_mode(state),
 
         // ── Threads (all no-data variants delegate to the thread dispatcher) ─
@@ -0,0 +1,280 @@
+//! User-only permission commands.
+//!
+//! These settings widen what the AI may do, or decide what it keeps of its
+//! own history, so no tool exposes them: only the user can change them, by
+//! typing the command.
+//!
+//! - `/k8s-allow rollout scale` lets `k8s_execute` run those mutating verbs;
+//!   `/k8s-allow` alone goes back to read-only.
+//! - `/db-writes on|off` lets `db_query` change the connected database.
+//! - `/edit-guard on [approval] [days=N] [mine=@me,@org/team]` or
+//!   `/edit-guard off` sets the git edit guard; `/approve src/lib.rs` lets
+//!   a guarded edit of that exact path through.
+//! - `/search-ignored on|off` lets the search index read files git ignores.
+//! - `/commit-gate cargo fmt --check; cargo test` sets the checks every
+//!   commit must pass first; `/commit-gate off` drops them.
+//! - `/commit-policy subject=conventional max=72 ticket=<regex>` sets the
+//!   rules commit messages must follow; `/commit-policy off` drops them.
+//! - `/formatter on|off` runs the configured formatter after every
+//!   `Edit`/`Write`.
+//! - `/cues approval_needed bell+flash` picks the cues of a spine event;
+//!   `/cues command notify-send done` sets the shell command `command`
+//!   cues run.
+//! - `/jail on|off` confines tool paths to the project; `/jail allow ~/docs`
+//!   opens more directories, `/jail allow` alone closes them again.
+//! - `/tldr-guard TICKET-\d+` adds a pattern TL;DR summaries must keep;
+//!   `/tldr-guard reset` restores the defaults, `/tldr-guard off` drops them.
+//! - `/trust-profile frontend` lets the formatter and test command of
+//!   `frontend/.context-pilot/config.yaml` apply with the formatter off.
+
+use cp_mod_git::types::EditGuard;
+use cp_mod_spine::types::{NotificationType, SpineState};
+
+use crate::state::State;
+
+/// A parsed permission command.
+#[derive(Debug, PartialEq, Eq)]
+enum PermissionCommand<'input> {
+    /// Mutating kubectl verbs to whitelist (empty = none).
+    K8sAllow(Vec<&'input str>),
+    /// Allow (`on`) or stop (`off`) database writes.
+    DbWrites(bool),
+    /// Git edit guard settings; `None` turns it off.
+    EditGuard(Option<EditGuard>),
+    /// Approve a guarded edit of this path.
+    Approve(&'input str),
+    /// Index (`on`) or skip (`off`) files git ignores.
+    SearchIgnored(bool),
+    /// Pre-commit checks, `;`-separated, or `off`.
+    CommitGate(&'input str),
+    /// Commit-message rules, or `off`.
+    CommitPolicy(&'input str),
+    /// Format files after edits (`on`) or leave them as written (`off`).
+    Formatter(bool),
+    /// Trust the profile of this directory.
+    TrustProfile(&'input str),
+    /// Completion cue settings.
+    Cues(&'input str),
+    /// Confine tool paths to the project (`on`) or not (`off`).
+    Jail(bool),
+    /// Directories the jail opens besides the project (empty = none).
+    JailAllow(Vec<&'input str>),
+    /// TL;DR preserve-list change: a pattern, `reset` or `off`.
+    TldrGuard(&'input str),
+}
+
+/// Arguments of `input` when it is `command` (alone or followed by a space).
+fn args_of<'input>(input: &'input str, command: &str) -> Option<&'input str> {
+    let rest = input.trim().strip_prefix(command)?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+}
+
+/// Edit guard settings from the words after `/edit-guard on`.
+fn edit_guard<'input>(words: impl Iterator<Item = &'input str>) -> Result<EditGuard, String> {
+    let mut guard = EditGuard { recent_days: cp_mod_git::EDIT_GUARD_DEFAULT_DAYS, ..EditGuard::default() };
+    for word in words {
+        if word == "approval" {
+            guard.require_approval = true;
+        } else if let Some(days) = word.strip_prefix("days=") {
+            guard.recent_days =
+                days.parse().ok().filter(|&d| d > 0).ok_or_else(|| format!("'{days}' is not a day count"))?;
+        } else if let Some(mine) = word.strip_prefix("mine=") {
+            guard.mine.extend(mine.split(',').filter(|m| !m.is_empty()).map(str::to_owned));
+        } else {
+            return Err(format!("'{word}': use approval, days=N or mine=@a,@b"));
+        }
+    }
+    Ok(guard)
+}
+
+/// Parse `/edit-guard` arguments.
+fn parse_edit_guard(args: &str) -> Result<PermissionCommand<'_>, String> {
+    let mut words = args.split_whitespace();
+    match words.next() {
+        Some("on") => edit_guard(words).map(|guard| PermissionCommand::EditGuard(Some(guard))),
+        Some("off") => Ok(PermissionCommand::EditGuard(None)),
+        _ => Err("usage: /edit-guard on [approval] [days=N] [mine=@a,@b] | off".to_owned()),
+    }
+}
+
+/// `on` or `off` as a switch; `usage` otherwise.
+fn switch(args: &str, usage: &str) -> Result<bool, String> {
+    match args {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err(usage.to_owned()),
+    }
+}
+
+/// Parse `/jail` arguments.
+fn parse_jail(args: &str) -> Result<PermissionCommand<'_>, String> {
+    match args.split_once(char::is_whitespace).map_or((args, ""), |(head, rest)| (head, rest)) {
+        ("allow", dirs) => Ok(PermissionCommand::JailAllow(dirs.split([' ', ',']).filter(|d| !d.is_empty()).collect())),
+        (switched, "") => switch(switched, JAIL_USAGE).map(PermissionCommand::Jail),
+        _ => Err(JAIL_USAGE.to_owned()),
+    }
+}
+
+/// `/jail` usage.
+const JAIL_USAGE: &str = "usage: /jail on|off | /jail allow <dir>...";
+
+/// `make(args)`, unless `args` is empty: then `usage`.
+fn required<'input>(
+    args: &'input str,
+    usage: &str,
+    make: fn(&'input str) -> PermissionCommand<'input>,
+) -> Result<PermissionCommand<'input>, String> {
+    if args.is_empty() { Err(usage.to_owned()) } else { Ok(make(args)) }
+}
+
+/// Parse a permission command; `None` for any other input, an error for a
+/// known command with bad arguments.
+fn parse(input: &str) -> Option<Result<PermissionCommand<'_>, String>> {
+    if let Some(args) = args_of(input, "/k8s-allow") {
+        return Some(Ok(PermissionCommand::K8sAllow(args.split([' ', ',']).filter(|v| !v.is_empty()).collect())));
+    }
+    if let Some(args) = args_of(input, "/edit-guard") {
+        return Some(parse_edit_guard(args));
+    }
+    if let Some(path) = args_of(input, "/approve") {
+        return Some(required(path, "usage: /approve <path>", PermissionCommand::Approve));
+    }
+    if let Some(args) = args_of(input, "/commit-gate") {
+        return Some(required(args, "usage: /commit-gate <check>; <check>... | off", PermissionCommand::CommitGate));
+    }
+    if let Some(args) = args_of(input, "/commit-policy") {
+        return Some(Ok(PermissionCommand::CommitPolicy(args)));
+    }
+    if let Some(args) = args_of(input, "/formatter") {
+        return Some(switch(args, "usage: /formatter on|off").map(PermissionCommand::Formatter));
+    }
+    if let Some(dir) = args_of(input, "/trust-profile") {
+        return Some(required(dir, "usage: /trust-profile <dir>", PermissionCommand::TrustProfile));
+    }
+    if let Some(args) = args_of(input, "/cues") {
+        return Some(Ok(PermissionCommand::Cues(args)));
+    }
+    if let Some(args) = args_of(input, "/jail") {
+        return Some(parse_jail(args));
+    }
+    if let Some(args) = args_of(input, "/tldr-guard") {
+        return Some(Ok(PermissionCommand::TldrGuard(args)));
+    }
+    if let Some(args) = args_of(input, "/search-ignored") {
+        return Some(switch(args, "usage: /search-ignored on|off").map(PermissionCommand::SearchIgnored));
+    }
+    let args = args_of(input, "/db-writes")?;
+    Some(switch(args, "usage: /db-writes on|off").map(PermissionCommand::DbWrites))
+}
+
+/// Apply a permission command; the message to show the user.
+fn apply(state: &mut State, command: PermissionCommand<'_>) -> Result<String, String> {
+    match command {
+        PermissionCommand::K8sAllow(verbs) => Ok(cp_mod_k8s::allow_verbs(state, &verbs)),
+        PermissionCommand::DbWrites(enabled) => cp_mod_db::set_writes(state, enabled),
+        PermissionCommand::EditGuard(guard) => Ok(cp_mod_git::set_edit_guard(state, guard)),
+        PermissionCommand::Approve(path) => Ok(cp_mod_git::approve_edit(state, path)),
+        PermissionCommand::SearchIgnored(enabled) => cp_mod_search::set_include_ignored(state, enabled),
+        PermissionCommand::CommitGate(checks) => Ok(cp_mod_git::set_commit_checks(state, checks)),
+        PermissionCommand::CommitPolicy(rules) => cp_mod_git::set_commit_policy(state, rules),
+        PermissionCommand::Formatter(enabled) => Ok(cp_mod_files::set_formatter(state, enabled)),
+        PermissionCommand::TrustProfile(dir) => crate::state::profile::trust(state, dir),
+        PermissionCommand::Cues(args) => cp_mod_spine::cues::set_from_command(state, args),
+        PermissionCommand::TldrGuard(args) => crate::modules::conversation_history::tldr_guard::configure(state, args),
+        PermissionCommand::Jail(enabled) => Ok(cp_mod_files::set_jail(state, enabled)),
+        PermissionCommand::JailAllow(dirs) => {
+            Ok(cp_mod_files::set_jail_allow(state, dirs.into_iter().map(str::to_owned).collect()))
+        }
+    }
+}
+
+/// Handle a permission command; returns `false` for any other input.
+pub(super) fn permission(state: &mut State) -> bool {
+    let input = std::mem::take(&mut state.input);
+    let Some(command) = parse(&input) else {
+        state.input = input;
+        return false;
+    };
+    let message = command.and_then(|c| apply(state, c)).unwrap_or_else(|e| e);
+    let id = SpineState::create_notification(state, NotificationType::Custom, "permissions".to_owned(), message);
+    let _processed = SpineState::mark_notification_processed(state, &id);
+    state.input_cursor = 0;
+    state.input_selection_anchor = None;
+    state.flags.ui.dirty = true;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn k8s_verbs_split_on_spaces_and_commas() {
+        assert_eq!(parse("/k8s-allow rollout, scale"), Some(Ok(PermissionCommand::K8sAllow(vec!["rollout", "scale"]))));
+        assert_eq!(parse("/k8s-allow"), Some(Ok(PermissionCommand::K8sAllow(vec![]))));
+        assert_eq!(parse("/k8s-allowed x"), None);
+    }
+
+    #[test]
+    fn db_writes_take_on_or_off() {
+        assert_eq!(parse("/db-writes on"), Some(Ok(PermissionCommand::DbWrites(true))));
+        assert_eq!(parse("/db-writes off"), Some(Ok(PermissionCommand::DbWrites(false))));
+        assert!(parse("/db-writes").is_some_and(|p| p.is_err()));
+        assert_eq!(parse("/search-ignored on"), Some(Ok(PermissionCommand::SearchIgnored(true))));
+        assert!(parse("/search-ignored yes").is_some_and(|p| p.is_err()));
+        assert_eq!(parse("/formatter on"), Some(Ok(PermissionCommand::Formatter(true))));
+    }
+
+    #[test]
+    fn edit_guard_takes_approval_days_and_owners() {
+        let guard =
+            EditGuard { mine: vec!["@me".to_owned(), "@org/web".to_owned()], recent_days: 7, require_approval: true };
+        assert_eq!(
+            parse("/edit-guard on approval days=7 mine=@me,@org/web"),
+            Some(Ok(PermissionCommand::EditGuard(Some(guard))))
+        );
+        assert_eq!(parse("/edit-guard off"), Some(Ok(PermissionCommand::EditGuard(None))));
+        assert!(parse("/edit-guard on days=0").is_some_and(|p| p.is_err_and(|e| e.contains("day count"))));
+        assert_eq!(parse("/approve src/lib.rs"), Some(Ok(PermissionCommand::Approve("src/lib.rs"))));
+        assert!(parse("/approve").is_some_and(|p| p.is_err()));
+        assert_eq!(parse("/trust-profile web/"), Some(Ok(PermissionCommand::TrustProfile("web/"))));
+    }
+
+    #[test]
+    fn commit_gate_and_policy_pass_their_arguments_through() {
+        let gate = parse("/commit-gate cargo fmt --check; cargo test");
+        assert_eq!(gate, Some(Ok(PermissionCommand::CommitGate("cargo fmt --check; cargo test"))));
+        assert!(parse("/commit-gate").is_some_and(|p| p.is_err()));
+        assert_eq!(parse("/commit-policy off"), Some(Ok(PermissionCommand::CommitPolicy("off"))));
+    }
+
+    #[test]
+    fn commit_gate_is_set_through_the_command() {
+        let mut state = State::default();
+        state.set_ext(cp_mod_git::types::GitState::new());
+        let gate = parse("/commit-gate cargo test; cargo clippy").and_then(Result::ok);
+        let message = gate.map(|command| apply(&mut state, command));
+        assert!(message.is_some_and(|m| m.is_ok_and(|text| text.starts_with("Pre-commit gate on"))));
+        let checks = &cp_mod_git::types::GitState::get(&state).commit_checks;
+        assert_eq!(checks, &["cargo test".to_owned(), "cargo clippy".to_owned()]);
+    }
+
+    #[test]
+    fn the_jail_is_set_through_the_command() {
+        let mut state = State::default();
+        let outside = std::env::temp_dir().join(format!("cp-jail-{}", std::process::id()));
+        let created = std::fs::create_dir_all(&outside);
+        let allow = format!("/jail allow {}", outside.display());
+        let commands = ["/jail on", allow.as_str()].map(|input| parse(input).and_then(Result::ok));
+        for command in commands.into_iter().flatten() {
+            let _message = apply(&mut state, command);
+        }
+        let jail = state.get_ext::<cp_base::tools::jail::Jail>();
+        let refused = jail.map(|j| j.violations(&serde_json::json!({ "file_path": "/etc/passwd" })).len());
+        let allowed = jail.map(|j| j.violations(&serde_json::json!({ "path": outside.join("notes.md") })).len());
+        let _removed = std::fs::remove_dir_all(&outside);
+        assert_eq!(created.ok(), Some(()));
+        assert_eq!((refused, allowed), (Some(1), Some(0)));
+        assert_eq!(parse("/jail maybe"), Some(Err(JAIL_USAGE.to_owned())));
+    }
+}
@@ -0,0 +1,180 @@
+//! `/snippet`: approved boilerplate into the draft or a file.
+//!
+//! `/snippet rust-test-module test=parses_empty` puts the filled-in snippet
+//! in the draft with the first placeholder left selected;
+//! `/snippet rust-test-module test=parses_empty > src/lib.rs:120` inserts it
+//! before that line through the `Edit` tool. The command palette inserts a
+//! snippet at the cursor.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use cp_base::ui::text;
+use cp_mod_snippets::store;
+use cp_mod_spine::types::{NotificationType, SpineState};
+use serde_json::json;
+
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::State;
+
+/// A parsed `/snippet` input.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SnippetCommand {
+    /// Snippet id.
+    id: String,
+    /// Placeholder values given as `name=value`.
+    values: BTreeMap<String, String>,
+    /// `path:line` to insert before, after `>`.
+    target: Option<String>,
+}
+
+/// Whitespace-separated words; double quotes keep spaces in a word.
+fn words(args: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for ch in args.chars() {
+        if ch == '"' {
+            quoted = !quoted;
+        } else if ch.is_whitespace() && !quoted {
+            if !word.is_empty() {
+                out.push(std::mem::take(&mut word));
+            }
+        } else {
+            word.push(ch);
+        }
+    }
+    if !word.is_empty() {
+        out.push(word);
+    }
+    out
+}
+
+/// Parse a `/snippet` input; `None` for any other input.
+fn parse(input: &str) -> Option<Result<SnippetCommand, String>> {
+    let rest = input.trim().strip_prefix("/snippet")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut words = words(rest).into_iter();
+    let Some(id) = words.next() else {
+        return Some(Err("usage: /snippet <id> [name=value ...] [> path:line]".to_owned()));
+    };
+    let mut command = SnippetCommand { id, ..SnippetCommand::default() };
+    while let Some(word) = words.next() {
+        if let Some(location) = word.strip_prefix('>') {
+            command.target = if location.is_empty() { words.next() } else { Some(location.to_owned()) };
+        } else if let Some((name, value)) = word.split_once('=') {
+            let _previous = command.values.insert(name.to_owned(), value.to_owned());
+        } else {
+            return Some(Err(format!("'{word}' is not name=value")));
+        }
+    }
+    Some(Ok(command))
+}
+
+/// Tell the user something went wrong, without waking the AI.
+fn notify(state: &mut State, message: String) {
+    let id = SpineState::create_notification(state, NotificationType::Custom, "snippets".to_owned(), message);
+    let _processed = SpineState::mark_notification_processed(state, &id);
+}
+
+/// Insert `text` in the draft at the cursor and select its first placeholder.
+fn insert_in_draft(state: &mut State, text: &str) {
+    let offset = state.input_cursor.min(state.input.len());
+    state.input_cursor = text::insert_at(&mut state.input, offset, text);
+    state.input_selection_anchor = None;
+    if let Some(first) = store::placeholders(text).first() {
+        state.input_selection_anchor = Some(offset.saturating_add(first.start));
+        state.input_cursor = offset.saturating_add(first.end);
+    }
+    state.flags.ui.dirty = true;
+}
+
+/// Insert snippet `id` into the draft at the cursor (command palette).
+pub(crate) fn insert(state: &mut State, id: &str) -> bool {
+    let library = store::load(Path::new("."));
+    let Some(snippet) = library.get(id) else { return false };
+    insert_in_draft(state, &snippet.body);
+    true
+}
+
+/// Insert `text` before `location` (`path:line`) with the `Edit` tool.
+fn insert_in_file(state: &mut State, location: &str, text: &str) -> Result<(), String> {
+    let missing = store::unfilled(text);
+    if !missing.is_empty() {
+        return Err(format!("give a value for {} to insert into a file", missing.join(", ")));
+    }
+    let (path, line) = cp_mod_bookmarks::types::parse_location(location)?;
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("cannot read '{path}': {e}"))?;
+    let (old, new) = store::insertion(&content, line, text)?;
+    let input = json!({ "file_path": path, "old_string": old, "new_string": new });
+    let tool = ToolUse::new("user-snippet".to_owned(), "Edit".to_owned(), input);
+    let ToolResult { content: message, is_error, .. } = crate::infra::tools::execute_tool(&tool, state);
+    if is_error { Err(message) } else { Ok(()) }
+}
+
+/// Run a parsed `/snippet`.
+fn run(state: &mut State, command: &SnippetCommand) -> Result<(), String> {
+    let library = store::load(Path::new("."));
+    let snippet = library.get(&command.id).ok_or_else(|| {
+        let ids: Vec<&str> = library.snippets.iter().map(|s| s.id.as_str()).collect();
+        format!("no snippet '{}'; known: {}", command.id, ids.join(", "))
+    })?;
+    let text = store::expand(&snippet.body, &command.values);
+    if let Some(location) = command.target.as_deref() {
+        return insert_in_file(state, location, &text);
+    }
+    insert_in_draft(state, &text);
+    Ok(())
+}
+
+/// Handle `/snippet ...`; returns `false` for any other input.
+pub(super) fn snippet(state: &mut State) -> bool {
+    let Some(parsed) = parse(&state.input) else { return false };
+    let outcome = parsed.and_then(|command| {
+        let draft = std::mem::take(&mut state.input);
+        state.input_cursor = 0;
+        state.input_selection_anchor = None;
+        run(state, &command).inspect_err(|_e| {
+            state.input = draft;
+            state.input_cursor = state.input.len();
+        })
+    });
+    if let Err(e) = outcome {
+        notify(state, e);
+    }
+    state.flags.ui.dirty = true;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippet_inputs_carry_values_and_target() {
+        let parsed = parse("/snippet rust-display type=Span format=\"{}..{}\" fields=\"self.a, self.b\" > src/x.rs:3");
+        let values = BTreeMap::from([
+            ("type".to_owned(), "Span".to_owned()),
+            ("format".to_owned(), "{}..{}".to_owned()),
+            ("fields".to_owned(), "self.a, self.b".to_owned()),
+        ]);
+        let expected = SnippetCommand { id: "rust-display".to_owned(), values, target: Some("src/x.rs:3".to_owned()) };
+        assert_eq!(parsed, Some(Ok(expected)));
+        assert!(parse("/snippet").is_some_and(|p| p.is_err()));
+        assert!(parse("/snippet a oops").is_some_and(|p| p.is_err_and(|e| e.contains("oops"))));
+        assert_eq!(parse("/snippets"), None);
+    }
+
+    #[test]
+    fn drafts_get_the_first_placeholder_selected() {
+        let mut state = State::default().with_draft("see: ".to_owned(), 5);
+        insert_in_draft(&mut state, "fn {{name}}() {}");
+        assert_eq!(state.input, "see: fn {{name}}() {}");
+        assert_eq!(
+            state.input.get(state.input_selection_anchor.unwrap_or_default()..state.input_cursor),
+            Some("{{name}}")
+        );
+    }
+}
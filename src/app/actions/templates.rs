@@ -0,0 +1,170 @@
+//! Conversation templates picked from the command palette.
+//!
+//! A template opens panels, activates an agent and seeds the draft with a
+//! structured first message; its first `{{placeholder}}` is selected, so
+//! typing replaces it. Built-ins come from `yamls/templates.yaml`; a project
+//! adds or overrides them by id in `.context-pilot/templates.yaml`.
+
+use std::path::Path;
+use std::sync::LazyLock;
+
+use cp_mod_spine::types::{NotificationType, SpineState};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::infra::constants::STORE_DIR;
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::{Kind, State};
+
+/// File name of a project's templates, under the store dir.
+const TEMPLATES_FILE: &str = "templates.yaml";
+
+/// One conversation template.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Template {
+    /// Unique id; a project template with a built-in's id replaces it.
+    pub id: String,
+    /// Palette label.
+    pub name: String,
+    /// Palette description.
+    #[serde(default)]
+    pub description: String,
+    /// Agent to activate; the current one stays when absent.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Files to open.
+    #[serde(default)]
+    pub open: Vec<String>,
+    /// Skills to load.
+    #[serde(default)]
+    pub skills: Vec<String>,
+    /// Diff to load in the Review panel (`working`, a range, `pr:N`).
+    #[serde(default)]
+    pub review: Option<String>,
+    /// Fixed panel to select afterwards; the conversation when absent.
+    #[serde(default)]
+    pub show: Option<String>,
+    /// First message, with `{{...}}` placeholders.
+    pub message: String,
+}
+
+/// Layout of a templates file.
+#[derive(Debug, Deserialize)]
+struct TemplateFile {
+    /// The templates, in palette order.
+    templates: Vec<Template>,
+}
+
+/// Templates shipped with the binary.
+static BUILT_IN: LazyLock<Vec<Template>> = LazyLock::new(|| {
+    cp_base::config::parse_yaml::<TemplateFile>("templates.yaml", include_str!("../../../yamls/templates.yaml"))
+        .templates
+});
+
+/// Every template: the built-ins, overridden and extended by the project's.
+/// The error, if any, says why the project file was ignored.
+pub(crate) fn load() -> (Vec<Template>, Option<String>) {
+    let mut templates = BUILT_IN.clone();
+    let path = Path::new(STORE_DIR).join(TEMPLATES_FILE);
+    let Ok(text) = std::fs::read_to_string(&path) else { return (templates, None) };
+    match serde_yaml::from_str::<TemplateFile>(&text) {
+        Ok(file) => {
+            for template in file.templates {
+                match templates.iter_mut().find(|t| t.id == template.id) {
+                    Some(slot) => *slot = template,
+                    None => templates.push(template),
+                }
+            }
+            (templates, None)
+        }
+        Err(e) => (templates, Some(format!("{}: {e}", path.display()))),
+    }
+}
+
+/// Byte range of the first `{{...}}` placeholder in `text`.
+fn first_placeholder(text: &str) -> Option<(usize, usize)> {
+    let start = text.find("{{")?;
+    let close = text.get(start..)?.find("}}")?;
+    Some((start, start.saturating_add(close).saturating_add(2)))
+}
+
+/// Put `message` in the draft (after any text already typed) and select its
+/// first placeholder.
+fn seed(state: &mut State, message: &str) {
+    if state.input.trim().is_empty() {
+        state.input.clear();
+    } else {
+        state.input.push_str("\n\n");
+    }
+    let offset = state.input.len();
+    state.input.push_str(message);
+    if let Some((start, end)) = first_placeholder(message) {
+        state.input_selection_anchor = Some(offset.saturating_add(start));
+        state.input_cursor = offset.saturating_add(end);
+    } else {
+        state.input_selection_anchor = None;
+        state.input_cursor = state.input.len();
+    }
+}
+
+/// Run a tool as the user; a failure becomes a notification.
+fn run_tool(state: &mut State, name: &str, input: serde_json::Value) {
+    let tool = ToolUse::new("user-template".to_owned(), name.to_owned(), input);
+    let ToolResult { content, is_error, .. } = crate::infra::tools::execute_tool(&tool, state);
+    if is_error {
+        let id = SpineState::create_notification(state, NotificationType::Custom, "templates".to_owned(), content);
+        let _processed = SpineState::mark_notification_processed(state, &id);
+    }
+}
+
+/// Apply template `id`: activate its agent, open its panels and seed the
+/// draft. Returns the kind of panel to show, `None` for an unknown id.
+pub(crate) fn apply(state: &mut State, id: &str) -> Option<String> {
+    let template = load().0.into_iter().find(|t| t.id == id)?;
+    if let Some(agent) = template.agent.as_deref() {
+        run_tool(state, "agent_load", json!({ "id": agent }));
+    }
+    if !template.open.is_empty() {
+        run_tool(state, "Open", json!({ "path": template.open }));
+    }
+    for skill in &template.skills {
+        run_tool(state, "skill_load", json!({ "id": skill }));
+    }
+    if let Some(source) = template.review.as_deref() {
+        run_tool(state, "review_load", json!({ "source": source }));
+    }
+    seed(state, &template.message);
+    state.flags.ui.dirty = true;
+    Some(template.show.unwrap_or_else(|| Kind::CONVERSATION.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_templates_have_placeholders() {
+        assert!(BUILT_IN.len() >= 3);
+        assert!(BUILT_IN.iter().all(|t| first_placeholder(&t.message).is_some()));
+        let mut ids: Vec<&str> = BUILT_IN.iter().map(|t| t.id.as_str()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), BUILT_IN.len());
+    }
+
+    #[test]
+    fn seeding_selects_the_first_placeholder() {
+        let mut state = State::default();
+        seed(&mut state, "Fix {{test}} in {{file}}");
+        assert_eq!(state.input_selection_anchor, Some(4));
+        assert_eq!(state.input_cursor, 12);
+
+        state.input = "keep this".to_owned();
+        seed(&mut state, "Then {{what}}");
+        assert_eq!(state.input, "keep this\n\nThen {{what}}");
+        assert_eq!(
+            state.input.get(state.input_selection_anchor.unwrap_or_default()..state.input_cursor),
+            Some("{{what}}")
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Diff-on-refresh — send only the delta of constantly refreshing panels.
+//!
+//! Panels that opt in via [`Panel::diff_on_refresh`](crate::app::panels::Panel)
+//! (consoles, logs, git output) keep per-line hashes of the content the LLM
+//! last saw. Each tick, the lines still present from that version — either a
+//! common prefix or, for scrolling buffers, the old tail now at the top — are
+//! folded into a one-line marker, keeping a short anchor of unchanged lines
+//! so the new output stays readable in place.
+
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+
+use crate::app::panels::ContextItem;
+use crate::state::State;
+
+/// Unchanged lines kept right before the new content, as an anchor.
+const ANCHOR_LINES: usize = 5;
+
+/// Hash of a single line.
+fn line_hash(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Number of leading lines of `new` the LLM already saw in `old`: the longer
+/// of the common prefix and the overlap left after `old` scrolled up.
+fn seen_prefix(old: &[u64], new: &[u64]) -> usize {
+    let common = old.iter().zip(new).take_while(|pair| pair.0 == pair.1).count();
+    let scrolled =
+        (1..old.len()).filter_map(|start| old.get(start..)).find(|tail| new.starts_with(tail)).map_or(0, <[u64]>::len);
+    common.max(scrolled)
+}
+
+/// Replace the already-seen head of `content` with a marker, keeping an anchor.
+/// Returns `None` when too little is unchanged to be worth it.
+fn delta_content(content: &str, seen: usize) -> Option<String> {
+    let omitted = seen.checked_sub(ANCHOR_LINES).filter(|n| *n > 0)?;
+    let rest: Vec<&str> = content.lines().skip(omitted).collect();
+    Some(format!("[\u{2026} {omitted} line(s) unchanged since your last view omitted \u{2026}]\n{}", rest.join("\n")))
+}
+
+/// Rewrite opted-in panel items to their delta against the last-sent version,
+/// staging the fresh line hashes until [`commit_panel_diffs`] confirms emission.
+pub(super) fn apply_panel_diffs(state: &mut State, context_items: &mut [ContextItem]) {
+    for item in context_items.iter_mut().filter(|i| i.id != "chat") {
+        let Some(entry) = state.context.iter_mut().find(|c| c.id == item.id) else { continue };
+        if !crate::app::panels::get_panel(&entry.context_type).diff_on_refresh() {
+            continue;
+        }
+        let lines: Vec<u64> = item.content.lines().map(line_hash).collect();
+        let seen = entry.emitted.sent_lines.as_deref().map_or(0, |old| seen_prefix(old, &lines));
+        if let Some(delta) = delta_content(&item.content, seen) {
+            item.content = delta;
+        }
+        entry.emitted.pending_lines = Some(lines);
+    }
+}
+
+/// Promote staged line hashes for panels emitted fresh this tick; a frozen
+/// panel keeps its previous base, since the LLM still sees the old version.
+pub(super) fn commit_panel_diffs(state: &mut State) {
+    for entry in &mut state.context {
+        if let Some(lines) = entry.emitted.pending_lines.take()
+            && entry.freeze_count == 0
+        {
+            entry.emitted.sent_lines = Some(lines);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Line hashes of `text`.
+    fn hashes(text: &str) -> Vec<u64> {
+        text.lines().map(line_hash).collect()
+    }
+
+    #[test]
+    fn appended_and_scrolled_output_is_seen() {
+        let old = hashes("a\nb\nc\nd");
+        assert_eq!(seen_prefix(&old, &hashes("a\nb\nc\nd\ne")), 4);
+        assert_eq!(seen_prefix(&old, &hashes("c\nd\ne\nf")), 2);
+        assert_eq!(seen_prefix(&old, &hashes("x\ny")), 0);
+    }
+
+    #[test]
+    fn delta_keeps_anchor_before_new_lines() {
+        let content = (1u32..=10).map(|n| format!("line {n}")).collect::<Vec<_>>().join("\n");
+        let delta = delta_content(&content, 9);
+        assert_eq!(
+            delta.as_deref(),
+            Some(
+                "[\u{2026} 4 line(s) unchanged since your last view omitted \u{2026}]\nline 5\nline 6\nline 7\nline 8\nline 9\nline 10"
+            )
+        );
+        assert_eq!(delta_content(&content, ANCHOR_LINES), None);
+    }
+}
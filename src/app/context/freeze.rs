@@ -88,7 +88,7 @@ pub(super) fn free_region_anchor(bp_indices: &[usize], culprit_idx: usize) -> us
 
 /// Whether `item` breaks the cache under the current freeze policy: a panel that
 /// vanished (no matching `Entry`), or one whose content changed AND the policy
-/// would emit it Fresh (not freeze it).
+/// would emit it Fresh (not freeze it) — or `panel_refresh` asked for it.
 fn item_is_culprit(item: &crate::app::panels::ContextItem, state: &State, cond: FreezeConditions) -> bool {
     use crate::state::cache::hash_content;
     let fresh_hash = hash_content(&item.content);
@@ -99,6 +99,9 @@ fn item_is_culprit(item: &crate::app::panels::ContextItem, state: &State, cond:
             if !changed {
                 return false;
             }
+            if entry.emitted.refresh_requested {
+                return true;
+            }
             let panel = crate::app::panels::get_panel(&entry.context_type);
             cond.freeze_panel(false, entry.freeze_count, panel.max_freezes()) == FreezeDecision::Fresh
         }
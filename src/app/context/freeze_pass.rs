@@ -31,6 +31,7 @@ pub(super) fn apply_full_freeze(
     snapshot: &[ContextItem],
     meta: FreezeMeta,
 ) {
+    mark_stale_under_tempo(state, context_items);
     let chat_item = context_items.iter().find(|i| i.id == "chat").cloned();
     context_items.clear();
     context_items.extend_from_slice(snapshot);
@@ -54,6 +55,19 @@ pub(super) fn apply_full_freeze(
     );
 }
 
+/// Flag every panel whose fresh content differs from the snapshot being
+/// replayed: the LLM keeps seeing the older version while tempo holds.
+fn mark_stale_under_tempo(state: &mut State, fresh_items: &[ContextItem]) {
+    for item in fresh_items.iter().filter(|i| i.id != "chat") {
+        let fresh_hash = hash_content(&item.content);
+        if let Some(entry) = state.context.iter_mut().find(|c| c.id == item.id)
+            && entry.emitted.hash.as_deref().is_some_and(|h| h != fresh_hash)
+        {
+            entry.emitted.stale = true;
+        }
+    }
+}
+
 /// The last 3 real tool names (skipping synthetic `Tool_execution` stubs),
 /// joined by comma — for tick-telemetry culprit context.
 fn recent_tool_names(state: &State) -> String {
@@ -106,17 +120,24 @@ fn freeze_one_panel(state: &mut State, item: &mut ContextItem, cond: FreezeCondi
     if !content_changed {
         // Cache preserved naturally — no snapshot mutation.
         entry.emitted.context = Some(item.clone());
+        entry.emitted.stale = false;
+        entry.emitted.refresh_requested = false;
         return PanelEmit { emitted_hash: fresh_hash, culprit: None };
     }
 
     let panel = crate::app::panels::get_panel(&entry.context_type);
-    let decision = cond.freeze_panel(broken, entry.freeze_count, panel.max_freezes());
+    let decision = if entry.emitted.refresh_requested {
+        FreezeDecision::Fresh
+    } else {
+        cond.freeze_panel(broken, entry.freeze_count, panel.max_freezes())
+    };
     if decision == FreezeDecision::Freeze
         && let Some(frozen) = entry.emitted.context.as_ref()
     {
         *item = frozen.clone();
         entry.freeze_count = entry.freeze_count.saturating_add(1);
         entry.total_freezes = entry.total_freezes.saturating_add(1);
+        entry.emitted.stale = true;
         let emitted_hash = entry.emitted.hash.clone().unwrap_or(fresh_hash);
         entry.emitted.context = Some(item.clone());
         return PanelEmit { emitted_hash, culprit: None };
@@ -126,6 +147,10 @@ fn freeze_one_panel(state: &mut State, item: &mut ContextItem, cond: FreezeCondi
     let culprit = (entry.context_type.to_string(), panel.max_freezes(), last_hash.is_none());
     entry.freeze_count = 0;
     entry.emitted.hash = Some(fresh_hash.clone());
+    entry.emitted.version = entry.emitted.version.saturating_add(1);
+    entry.emitted.stale = false;
+    entry.emitted.stale_noted = false;
+    entry.emitted.refresh_requested = false;
     entry.total_cache_misses = entry.total_cache_misses.saturating_add(1);
     entry.emitted.context = Some(item.clone());
     PanelEmit { emitted_hash: fresh_hash, culprit: Some(culprit) }
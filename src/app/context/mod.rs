@@ -4,15 +4,22 @@ use crate::app::panels::{ContextItem, collect_all_context, refresh_all_panels};
 use crate::infra::tools::ToolDefinition;
 use crate::infra::tools::refresh_conversation_context;
 use crate::modules;
+use crate::modules::overview::panel_layout;
 use crate::state::{Message, State};
 
 mod detach;
+/// Diff-on-refresh: send only the delta of constantly refreshing panels.
+mod diff;
 /// Freeze policy: per-panel and ordering freeze decisions (queue, tempo, breath budget).
 mod freeze;
 use freeze::freeze_conditions;
 /// Per-panel freeze pass (normal path): hash, decide freeze/fresh, cost + telemetry.
 mod freeze_pass;
 use freeze_pass::FreezeMeta;
+/// Prompt-injection guard: marks instruction-like text in ingested content.
+mod injection;
+/// Secret-scanning guard: redacts likely credentials from the outgoing context copy.
+mod secrets;
 
 /// Context data prepared for streaming
 pub(super) struct StreamContext {
@@ -69,6 +76,10 @@ pub(super) fn prepare_stream_context(
     // Refresh conversation token counts (not panel-based yet)
     refresh_conversation_context(state);
 
+    // Panels evicted by the cache budget or deferred at startup are loaded
+    // before anything reads them
+    crate::state::cache::load_unloaded(state);
+
     // Refresh all panel token counts
     refresh_all_panels(state);
 
@@ -93,7 +104,8 @@ pub(super) fn prepare_stream_context(
         context_items.sort_by_key(|item| order.iter().position(|id| *id == item.id).unwrap_or(usize::MAX));
         context_items.retain(|item| order.contains(&item.id));
     } else {
-        context_items.sort_by_key(|item| item.last_refresh_ms);
+        // Manually ordered panels (sidebar Shift+Up/Down) lead; the rest follow by freshness
+        context_items.sort_by_key(|item| (panel_layout::request_rank(state, &item.id), item.last_refresh_ms));
         state.previous_panel_order = context_items.iter().map(|item| item.id.clone()).collect();
     }
 
@@ -123,7 +135,9 @@ pub(super) fn prepare_stream_context(
         freeze_pass::apply_full_freeze(state, &mut context_items, &snap, meta);
     } else {
         // ═══ Normal path: per-panel freeze decisions ═════════════════════════
+        diff::apply_panel_diffs(state, &mut context_items);
         freeze_pass::run_panel_freeze_pass(state, &mut context_items, meta);
+        diff::commit_panel_diffs(state);
 
         // Save snapshot for next frozen tick (panels only, no "chat")
         state.frozen_context_snapshot = Some(context_items.iter().filter(|i| i.id != "chat").cloned().collect());
@@ -141,11 +155,17 @@ pub(super) fn prepare_stream_context(
     }
 
     // Prepare messages — branch based on whether this is a reverie or main worker
-    if let Some(rev) = reverie {
+    let mut ctx = if let Some(rev) = reverie {
         build_reverie_stream_context(state, context_items, rev)
     } else {
         build_main_stream_context(state, context_items, include_last_message)
-    }
+    };
+
+    // Mark text in ingested content that addresses the model, then, as the
+    // last step before serialization, scrub likely secrets from what the API will see
+    injection::flag_stream_context(state, &mut ctx);
+    secrets::redact_stream_context(state, &mut ctx);
+    ctx
 }
 
 /// Assemble a reverie sub-agent's `StreamContext`: append the main agent's
@@ -255,6 +275,9 @@ pub(crate) fn build_stream_params(
         seed_content,
         worker_id: crate::infra::constants::DEFAULT_WORKER_ID.to_owned(),
         cache_engine_json: state.cache_engine_json.clone(),
+        standing_instructions: state
+            .get_ext::<cp_base::state::data::sticky::StandingInstructions>()
+            .and_then(cp_base::state::data::sticky::StandingInstructions::prompt_text),
     }
 }
 
@@ -1,14 +1,15 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
 use cp_base::panels::scroll_key_action;
+use cp_base::state::data::sticky::StandingInstructions;
 
-use crate::app::actions::{Action, find_context_by_id, parse_context_pattern};
+use crate::app::actions::{Action, TourMove, find_context_by_id, parse_context_pattern};
 use crate::app::panels::get_panel;
-use crate::llms::LlmProvider;
-use crate::state::State;
+use crate::infra::constants::INLINE_PASTE_MAX_CHARS;
+use crate::state::{Kind, State};
 
-/// Config-overlay model selection dispatch (extracted to keep this file
-/// under the 500-line structure limit).
+/// Config-overlay key dispatch (extracted to keep this file under the
+/// 500-line structure limit).
 mod models;
 
 /// Outcome of a partial key-dispatch helper: quit the app, produce an action,
@@ -22,6 +23,26 @@ enum Dispatch {
     Fallthrough,
 }
 
+/// Normalize a raw terminal event before dispatch. `None` = ignore it.
+///
+/// Key releases are dropped: terminals using the kitty keyboard protocol (and
+/// Windows consoles) report them too, which would type IME and dead-key input
+/// twice. A character typed with Ctrl+Alt held that is not an ASCII letter or
+/// digit is `AltGr` composing it, so its modifiers are cleared and it is typed.
+pub(crate) fn normalize_event(event: Event) -> Option<Event> {
+    let Event::Key(mut key) = event else { return Some(event) };
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+    if let KeyCode::Char(c) = key.code
+        && key.modifiers.contains(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        && !c.is_ascii_alphanumeric()
+    {
+        key.modifiers = KeyModifiers::NONE;
+    }
+    Some(Event::Key(key))
+}
+
 /// Map a terminal event to an application action.
 ///
 /// Returns `None` for Ctrl+Q (quit signal), `Some(Action)` for everything else.
@@ -31,8 +52,12 @@ pub(crate) fn handle_event(event: &Event, state: &State) -> Option<Action> {
     }
     // Bracketed paste: store in buffer, insert placeholder sentinel.
     // Normalize line endings: terminals may send \r\n or \r instead of \n.
+    // Short single-line pastes (IME commits, a copied word) are typed in place.
     if let Event::Paste(text) = event.clone() {
         let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
+        if !normalized.contains('\n') && normalized.chars().count() <= INLINE_PASTE_MAX_CHARS {
+            return Some(Action::InsertText(normalized));
+        }
         return Some(Action::PasteText(normalized));
     }
     Some(Action::None)
@@ -51,13 +76,24 @@ fn handle_key_event(key: &KeyEvent, state: &State) -> Option<Action> {
 
     // Config view handles its own keys when open.
     if state.flags.config.config_view {
-        return Some(handle_config_event(key, state));
+        return Some(models::handle_config_event(key, state));
     }
 
-    if let Some(action) = handle_index_overlay_key(key, state) {
+    // The guided tour, the key reference, then the index overlay take every
+    // other key while open.
+    if let Some(action) = handle_tour_key(key, state)
+        .or_else(|| handle_key_help_key(key, state))
+        .or_else(|| handle_index_overlay_key(key, state))
+    {
         return Some(action);
     }
 
+    // Escape abandons a standing-instructions edit before anything else.
+    if key.code == KeyCode::Esc && state.get_ext::<StandingInstructions>().is_some_and(StandingInstructions::is_editing)
+    {
+        return Some(Action::CancelStandingInstructions);
+    }
+
     // Escape stops streaming.
     if key.code == KeyCode::Esc && state.flags.stream.phase.is_streaming() {
         return Some(Action::StopStreaming);
@@ -75,10 +111,21 @@ fn handle_key_event(key: &KeyEvent, state: &State) -> Option<Action> {
         return Some(Action::TogglePerfMonitor);
     }
 
+    // Alt+1..9: quick slots.
+    if key.modifiers.contains(KeyModifiers::ALT)
+        && let KeyCode::Char(digit @ '1'..='9') = key.code
+    {
+        return Some(Action::QuickSlot(digit.to_digit(10).map_or(0, cp_base::cast::Safe::to_u8)));
+    }
+
     if let Some(action) = handle_context_pattern_submit(key, state) {
         return Some(action);
     }
 
+    if let Some(action) = handle_sidebar_key(key, state) {
+        return Some(action);
+    }
+
     if let Some(action) = handle_panel_key(key, state) {
         return Some(action);
     }
@@ -96,13 +143,20 @@ fn handle_ctrl_shortcuts(key: &KeyEvent, state: &State) -> Dispatch {
     }
     match key.code {
         KeyCode::Char('q') => Dispatch::Quit,
+        KeyCode::Char('z' | 'Z') => Dispatch::Act(undo_action(key, state)),
         KeyCode::Char('l') => Dispatch::Act(Action::ClearConversation),
         KeyCode::Char('n') => Dispatch::Act(Action::NewContext),
         KeyCode::Char('h') => Dispatch::Act(Action::ToggleConfigView),
         KeyCode::Char('i') => Dispatch::Act(Action::ToggleIndexOverlay),
         KeyCode::Char('v') => Dispatch::Act(Action::CycleViewMode),
+        KeyCode::Char('s') => Dispatch::Act(Action::ToggleStandingInstructions),
+        KeyCode::Char('t') => Dispatch::Act(Action::PasteToPanel),
+        KeyCode::Char('x') => Dispatch::Act(Action::CloseSuggestedPanels),
         KeyCode::Char('o') => Dispatch::Act(Action::ResetSessionCosts),
+        KeyCode::Char('r') => Dispatch::Act(Action::ToggleVoiceInput),
         KeyCode::Char('p') => Dispatch::Act(Action::OpenCommandPalette),
+        KeyCode::Char('k') => Dispatch::Act(Action::OpenCleanScopePicker),
+        KeyCode::Char('b') => Dispatch::Act(Action::OpenMarkers),
         KeyCode::Char('u') => Dispatch::Act(Action::HistoryPrev),
         KeyCode::Char('d') => Dispatch::Act(Action::HistoryNext),
         KeyCode::Char('c') => Dispatch::Act(if state.flags.overlays.index_status {
@@ -140,6 +194,18 @@ fn handle_ctrl_shortcuts(key: &KeyEvent, state: &State) -> Dispatch {
     }
 }
 
+/// Ctrl+Z: reopen GC'd panels while a batch is undoable, else undo the last
+/// draft edit. Ctrl+Shift+Z redoes it.
+fn undo_action(key: &KeyEvent, state: &State) -> Action {
+    if key.modifiers.contains(KeyModifiers::SHIFT) || key.code == KeyCode::Char('Z') {
+        Action::RedoInput
+    } else if crate::modules::overview::panel_gc::undoable(state) > 0 {
+        Action::UndoPanelGc
+    } else {
+        Action::UndoInput
+    }
+}
+
 /// Threads-view Ctrl overrides: Ctrl+A archive/restore, Ctrl+U toggle archived
 /// view. `None` = not one of these (fall through to global Ctrl bindings).
 fn handle_threads_ctrl(key: &KeyEvent, state: &State) -> Option<Action> {
@@ -179,6 +245,17 @@ fn handle_threads_ctrl(key: &KeyEvent, state: &State) -> Option<Action> {
     }
 }
 
+/// Key reference: F1, or `?` on an empty draft, opens it; while open, Esc, F1
+/// or `?` close it and all other keys are consumed. `None` otherwise.
+fn handle_key_help_key(key: &KeyEvent, state: &State) -> Option<Action> {
+    let toggle = key.code == KeyCode::F(1) || key.code == KeyCode::Char('?');
+    if state.flags.overlays.key_help {
+        return Some(if toggle || key.code == KeyCode::Esc { Action::ToggleKeyHelp } else { Action::None });
+    }
+    let opens = key.code == KeyCode::F(1) || (key.code == KeyCode::Char('?') && state.input.is_empty());
+    opens.then_some(Action::ToggleKeyHelp)
+}
+
 /// Index-overlay keys: Esc dismisses, all other keys are consumed (return
 /// `Action::None`). `None` when the overlay is closed.
 fn handle_index_overlay_key(key: &KeyEvent, state: &State) -> Option<Action> {
@@ -188,6 +265,41 @@ fn handle_index_overlay_key(key: &KeyEvent, state: &State) -> Option<Action> {
     Some(if key.code == KeyCode::Esc { Action::ToggleIndexOverlay } else { Action::None })
 }
 
+/// Tour keys: →/Enter/Space next, ←/Backspace back, Esc skip; all other keys
+/// are consumed. `None` when the tour is closed.
+const fn handle_tour_key(key: &KeyEvent, state: &State) -> Option<Action> {
+    if state.flags.overlays.tour_step.is_none() {
+        return None;
+    }
+    Some(match key.code {
+        KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => Action::Tour(TourMove::Next),
+        KeyCode::Left | KeyCode::Backspace => Action::Tour(TourMove::Back),
+        KeyCode::Esc => Action::Tour(TourMove::Skip),
+        KeyCode::Up
+        | KeyCode::Down
+        | KeyCode::Home
+        | KeyCode::End
+        | KeyCode::PageUp
+        | KeyCode::PageDown
+        | KeyCode::Tab
+        | KeyCode::BackTab
+        | KeyCode::Delete
+        | KeyCode::Insert
+        | KeyCode::F(_)
+        | KeyCode::Char(_)
+        | KeyCode::Null
+        | KeyCode::CapsLock
+        | KeyCode::ScrollLock
+        | KeyCode::NumLock
+        | KeyCode::PrintScreen
+        | KeyCode::Pause
+        | KeyCode::Menu
+        | KeyCode::KeypadBegin
+        | KeyCode::Media(_)
+        | KeyCode::Modifier(_) => Action::None,
+    })
+}
+
 /// Threads-view navigation (non-Ctrl): archive-confirm y/n, Tab/BackTab select,
 /// Esc exit. `Fallthrough` when the key isn't a threads-nav key.
 fn handle_threads_nav(key: &KeyEvent, state: &State) -> Dispatch {
@@ -247,13 +359,34 @@ fn handle_context_pattern_submit(key: &KeyEvent, state: &State) -> Option<Action
 /// always owns input routing. `None` when no panel consumes the key.
 fn handle_panel_key(key: &KeyEvent, state: &State) -> Option<Action> {
     if state.view_mode == cp_base::state::data::config::ViewMode::Threads {
-        let ctx = state.context.iter().find(|c| c.context_type.as_str() == crate::state::Kind::CONVERSATION)?;
+        let ctx = state.context.iter().find(|c| c.context_type.as_str() == Kind::CONVERSATION)?;
         return get_panel(&ctx.context_type).handle_key(key, state);
     }
     let ctx = state.context.get(state.selected_context)?;
     get_panel(&ctx.context_type).handle_key(key, state)
 }
 
+/// Sidebar panel management: `i` toggles the token breakdown of any selected
+/// panel but the conversation; on a dynamic panel `x` closes it and
+/// Shift+Up/Down moves it.
+fn handle_sidebar_key(key: &KeyEvent, state: &State) -> Option<Action> {
+    if state.view_mode == cp_base::state::data::config::ViewMode::Threads {
+        return None;
+    }
+    let ctx = state.context.get(state.selected_context)?;
+    if key.code == KeyCode::Char('i') && key.modifiers.is_empty() && ctx.context_type.as_str() != Kind::CONVERSATION {
+        return Some(Action::ToggleTokenDetail);
+    }
+    if !crate::modules::overview::panel_layout::is_movable(ctx) {
+        return None;
+    }
+    if key.code == KeyCode::Char('x') && key.modifiers.is_empty() {
+        return Some(Action::CloseSelectedPanel);
+    }
+    let arrow = matches!(key.code, KeyCode::Up | KeyCode::Down);
+    (arrow && key.modifiers == KeyModifiers::SHIFT).then(|| Action::MoveSelectedPanel(key.code == KeyCode::Up))
+}
+
 /// Global fallback: scrolling + context switching. Returns `Action::None` for
 /// unhandled keys.
 fn handle_global_fallback(key: &KeyEvent) -> Action {
@@ -289,67 +422,83 @@ fn handle_global_fallback(key: &KeyEvent) -> Action {
     }
 }
 
-/// Handle key events when config view is open
-const fn handle_config_event(key: &KeyEvent, state: &State) -> Action {
-    match key.code {
-        // Escape closes config
-        KeyCode::Esc => Action::ToggleConfigView,
-        // Number keys select provider
-        KeyCode::Char('1') => Action::ConfigSelectProvider(LlmProvider::Anthropic),
-        KeyCode::Char('2') => Action::ConfigSelectProvider(LlmProvider::ClaudeCode),
-        KeyCode::Char('3') => Action::ConfigSelectProvider(LlmProvider::Grok),
-        KeyCode::Char('4') => Action::ConfigSelectProvider(LlmProvider::Groq),
-        KeyCode::Char('5') => Action::ConfigSelectProvider(LlmProvider::DeepSeek),
-        KeyCode::Char('6') => Action::ConfigSelectProvider(LlmProvider::ClaudeCodeApiKey),
-        KeyCode::Char('7') => Action::ConfigSelectProvider(LlmProvider::MiniMax),
-        KeyCode::Char('8') => Action::ConfigSelectProvider(LlmProvider::ClaudeCodeV2),
-        // Letter keys select model based on current provider
-        KeyCode::Char('a') => dispatch_primary_model(state, 0),
-        KeyCode::Char('b') => dispatch_primary_model(state, 1),
-        KeyCode::Char('c') => dispatch_primary_model(state, 2),
-        KeyCode::Char('d') => dispatch_primary_model(state, 3),
-        // Theme selection - t/T to cycle through themes
-        KeyCode::Char('t') => Action::ConfigNextTheme,
-        KeyCode::Char('T') => Action::ConfigPrevTheme,
-        // Toggle auto-continuation
-        KeyCode::Char('s') => Action::ConfigToggleAutoContinue,
-        // Toggle reverie (context optimizer)
-        KeyCode::Char('r') => Action::ConfigToggleReverie,
-        // Think reminder threshold adjustment
-        KeyCode::Char(']') => Action::ConfigThinkThresholdUp,
-        KeyCode::Char('[') => Action::ConfigThinkThresholdDown,
-        KeyCode::Down => Action::ConfigSelectNextBar,
-        // Left/Right adjust the selected bar
-        KeyCode::Left => Action::ConfigDecreaseSelectedBar,
-        KeyCode::Right => Action::ConfigIncreaseSelectedBar,
-        // Any other key is ignored in config view
-        KeyCode::Backspace
-        | KeyCode::Enter
-        | KeyCode::Up
-        | KeyCode::Home
-        | KeyCode::End
-        | KeyCode::PageUp
-        | KeyCode::PageDown
-        | KeyCode::Tab
-        | KeyCode::BackTab
-        | KeyCode::Delete
-        | KeyCode::Insert
-        | KeyCode::F(_)
-        | KeyCode::Char(_)
-        | KeyCode::Null
-        | KeyCode::CapsLock
-        | KeyCode::ScrollLock
-        | KeyCode::NumLock
-        | KeyCode::PrintScreen
-        | KeyCode::Pause
-        | KeyCode::Menu
-        | KeyCode::KeypadBegin
-        | KeyCode::Media(_)
-        | KeyCode::Modifier(_) => Action::None,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::help::keymap::{BINDINGS, Group};
+
+    /// Documented binding, a key event for it, and the `Action` variant the
+    /// dispatch must produce (`"Quit"` for the quit signal).
+    type Case = (Group, &'static str, KeyModifiers, KeyCode, &'static str);
+
+    const DISPATCH: &[Case] = &[
+        (Group::Global, "Ctrl+P", KeyModifiers::CONTROL, KeyCode::Char('p'), "OpenCommandPalette"),
+        (Group::Global, "Ctrl+K", KeyModifiers::CONTROL, KeyCode::Char('k'), "OpenCleanScopePicker"),
+        (Group::Global, "Ctrl+B", KeyModifiers::CONTROL, KeyCode::Char('b'), "OpenMarkers"),
+        (Group::Global, "Ctrl+H", KeyModifiers::CONTROL, KeyCode::Char('h'), "ToggleConfigView"),
+        (Group::Global, "Ctrl+I", KeyModifiers::CONTROL, KeyCode::Char('i'), "ToggleIndexOverlay"),
+        (Group::Global, "Ctrl+S", KeyModifiers::CONTROL, KeyCode::Char('s'), "ToggleStandingInstructions"),
+        (Group::Global, "Ctrl+V", KeyModifiers::CONTROL, KeyCode::Char('v'), "CycleViewMode"),
+        (Group::Global, "Ctrl+C", KeyModifiers::CONTROL, KeyCode::Char('c'), "CopyPanelContent"),
+        (Group::Global, "Ctrl+T", KeyModifiers::CONTROL, KeyCode::Char('t'), "PasteToPanel"),
+        (Group::Global, "Ctrl+X", KeyModifiers::CONTROL, KeyCode::Char('x'), "CloseSuggestedPanels"),
+        (Group::Global, "Ctrl+U", KeyModifiers::CONTROL, KeyCode::Char('u'), "HistoryPrev"),
+        (Group::Global, "Ctrl+D", KeyModifiers::CONTROL, KeyCode::Char('d'), "HistoryNext"),
+        (Group::Global, "Ctrl+Z", KeyModifiers::CONTROL, KeyCode::Char('z'), "UndoInput"),
+        (Group::Global, "Ctrl+Shift+Z", KeyModifiers::CONTROL, KeyCode::Char('Z'), "RedoInput"),
+        (Group::Global, "Ctrl+N", KeyModifiers::CONTROL, KeyCode::Char('n'), "NewContext"),
+        (Group::Global, "Ctrl+L", KeyModifiers::CONTROL, KeyCode::Char('l'), "ClearConversation"),
+        (Group::Global, "Ctrl+O", KeyModifiers::CONTROL, KeyCode::Char('o'), "ResetSessionCosts"),
+        (Group::Global, "Ctrl+R", KeyModifiers::CONTROL, KeyCode::Char('r'), "ToggleVoiceInput"),
+        (Group::Global, "F12", KeyModifiers::NONE, KeyCode::F(12), "TogglePerfMonitor"),
+        (Group::Global, "F1", KeyModifiers::NONE, KeyCode::F(1), "ToggleKeyHelp"),
+        (Group::Global, "?", KeyModifiers::SHIFT, KeyCode::Char('?'), "ToggleKeyHelp"),
+        (Group::Global, "Ctrl+Q", KeyModifiers::CONTROL, KeyCode::Char('q'), "Quit"),
+        (Group::Panels, "Tab", KeyModifiers::NONE, KeyCode::Tab, "SelectNextContext"),
+        (Group::Panels, "Shift+Tab", KeyModifiers::SHIFT, KeyCode::BackTab, "SelectPrevContext"),
+        (Group::Panels, "\u{2191} \u{2193}", KeyModifiers::NONE, KeyCode::Down, "ScrollDown"),
+        (Group::Panels, "PgUp PgDn", KeyModifiers::NONE, KeyCode::PageUp, "ScrollUp"),
+        (Group::Panels, "Alt+1..9", KeyModifiers::ALT, KeyCode::Char('3'), "QuickSlot"),
+        (Group::Config, "1..8", KeyModifiers::NONE, KeyCode::Char('2'), "ConfigSelectProvider"),
+        (Group::Config, "a..d", KeyModifiers::NONE, KeyCode::Char('a'), "ConfigSelectAnthropicModel"),
+        (Group::Config, "t / T", KeyModifiers::NONE, KeyCode::Char('t'), "ConfigNextTheme"),
+        (Group::Config, "\u{2193}", KeyModifiers::NONE, KeyCode::Down, "ConfigSelectNextBar"),
+        (Group::Config, "\u{2190} \u{2192}", KeyModifiers::NONE, KeyCode::Right, "ConfigIncreaseSelectedBar"),
+        (Group::Config, "s", KeyModifiers::NONE, KeyCode::Char('s'), "ConfigToggleAutoContinue"),
+        (Group::Config, "r", KeyModifiers::NONE, KeyCode::Char('r'), "ConfigToggleReverie"),
+        (Group::Config, "o", KeyModifiers::NONE, KeyCode::Char('o'), "ConfigToggleMentionOpen"),
+        (Group::Config, "k", KeyModifiers::NONE, KeyCode::Char('k'), "ConfigToggleSpellCheck"),
+        (Group::Config, "g", KeyModifiers::NONE, KeyCode::Char('g'), "ConfigCyclePanelGc"),
+        (Group::Config, "m", KeyModifiers::NONE, KeyCode::Char('m'), "ConfigCycleCacheBudget"),
+        (Group::Config, "[ ]", KeyModifiers::NONE, KeyCode::Char(']'), "ConfigThinkThresholdUp"),
+        (Group::Config, "Esc", KeyModifiers::NONE, KeyCode::Esc, "ToggleConfigView"),
+    ];
+
+    #[test]
+    fn documented_keys_dispatch_to_their_actions() {
+        for &(group, keys, modifiers, code, expected) in DISPATCH {
+            assert!(
+                BINDINGS.iter().any(|b| b.group == group && b.keys == keys),
+                "{keys} is not in the keymap registry"
+            );
+            let mut state = State::default();
+            state.flags.config.config_view = group == Group::Config;
+            let got = handle_key_event(&KeyEvent::new(code, modifiers), &state)
+                .map_or_else(|| "Quit".to_owned(), |action| format!("{action:?}"));
+            assert!(got.starts_with(expected), "{keys}: expected {expected}, dispatched {got}");
+        }
     }
-}
 
-/// Dispatch primary model selection based on provider and index (0=a, 1=b, 2=c, 3=d)
-const fn dispatch_primary_model(state: &State, idx: usize) -> Action {
-    models::dispatch_primary_model(state, idx)
+    #[test]
+    fn key_help_closes_and_swallows_keys() {
+        let mut state = State::default();
+        state.flags.overlays.key_help = true;
+        let dispatch = |code| format!("{:?}", handle_key_event(&KeyEvent::new(code, KeyModifiers::NONE), &state));
+        assert_eq!(dispatch(KeyCode::Esc), "Some(ToggleKeyHelp)");
+        assert_eq!(dispatch(KeyCode::Char('x')), "Some(None)");
+        state.input = "why".to_owned();
+        state.flags.overlays.key_help = false;
+        let typed = handle_key_event(&KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT), &state);
+        assert!(!matches!(typed, Some(Action::ToggleKeyHelp)), "`?` in a draft must be typed");
+    }
 }
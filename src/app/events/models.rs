@@ -1,16 +1,86 @@
-//! Config-overlay model selection dispatch.
+//! Config-overlay key dispatch.
 //!
 //! Extracted from [`crate::app::events`] to keep that file under the
-//! 500-line structure limit. Translates a provider + letter-key index
-//! (`a`/`b`/`c`/`d` → 0/1/2/3) into the concrete
-//! `Action::ConfigSelect*Model` variant.
+//! 500-line structure limit. Maps the overlay's keys to actions, and
+//! translates a provider + letter-key index (`a`/`b`/`c`/`d` → 0/1/2/3) into
+//! the concrete `Action::ConfigSelect*Model` variant.
+
+use crossterm::event::{KeyCode, KeyEvent};
 
 use crate::app::actions::Action;
 use crate::llms::{AnthropicModel, ClaudeCodeV2Model, DeepSeekModel, GrokModel, GroqModel, LlmProvider, MiniMaxModel};
 use crate::state::State;
 
+/// Handle key events when config view is open.
+pub(super) const fn handle_config_event(key: &KeyEvent, state: &State) -> Action {
+    match key.code {
+        // Escape closes config
+        KeyCode::Esc => Action::ToggleConfigView,
+        // Number keys select provider
+        KeyCode::Char('1') => Action::ConfigSelectProvider(LlmProvider::Anthropic),
+        KeyCode::Char('2') => Action::ConfigSelectProvider(LlmProvider::ClaudeCode),
+        KeyCode::Char('3') => Action::ConfigSelectProvider(LlmProvider::Grok),
+        KeyCode::Char('4') => Action::ConfigSelectProvider(LlmProvider::Groq),
+        KeyCode::Char('5') => Action::ConfigSelectProvider(LlmProvider::DeepSeek),
+        KeyCode::Char('6') => Action::ConfigSelectProvider(LlmProvider::ClaudeCodeApiKey),
+        KeyCode::Char('7') => Action::ConfigSelectProvider(LlmProvider::MiniMax),
+        KeyCode::Char('8') => Action::ConfigSelectProvider(LlmProvider::ClaudeCodeV2),
+        // Letter keys select model based on current provider
+        KeyCode::Char('a') => dispatch_primary_model(state, 0),
+        KeyCode::Char('b') => dispatch_primary_model(state, 1),
+        KeyCode::Char('c') => dispatch_primary_model(state, 2),
+        KeyCode::Char('d') => dispatch_primary_model(state, 3),
+        // Theme selection - t/T to cycle through themes
+        KeyCode::Char('t') => Action::ConfigNextTheme,
+        KeyCode::Char('T') => Action::ConfigPrevTheme,
+        // Toggle auto-continuation
+        KeyCode::Char('s') => Action::ConfigToggleAutoContinue,
+        // Toggle reverie (context optimizer)
+        KeyCode::Char('r') => Action::ConfigToggleReverie,
+        // Toggle auto-opening @-mentioned files
+        KeyCode::Char('o') => Action::ConfigToggleMentionOpen,
+        // Toggle draft spell checking
+        KeyCode::Char('k') => Action::ConfigToggleSpellCheck,
+        // Cycle the idle panel GC policy
+        KeyCode::Char('g') => Action::ConfigCyclePanelGc,
+        // Cycle the panel cache memory budget
+        KeyCode::Char('m') => Action::ConfigCycleCacheBudget,
+        // Think reminder threshold adjustment
+        KeyCode::Char(']') => Action::ConfigThinkThresholdUp,
+        KeyCode::Char('[') => Action::ConfigThinkThresholdDown,
+        KeyCode::Down => Action::ConfigSelectNextBar,
+        // Left/Right adjust the selected bar
+        KeyCode::Left => Action::ConfigDecreaseSelectedBar,
+        KeyCode::Right => Action::ConfigIncreaseSelectedBar,
+        // Any other key is ignored in config view
+        KeyCode::Backspace
+        | KeyCode::Enter
+        | KeyCode::Up
+        | KeyCode::Home
+        | KeyCode::End
+        | KeyCode::PageUp
+        | KeyCode::PageDown
+        | KeyCode::Tab
+        | KeyCode::BackTab
+        | KeyCode::Delete
+        | KeyCode::Insert
+        | KeyCode::F(_)
+        | KeyCode::Char(_)
+        | KeyCode::Null
+        | KeyCode::CapsLock
+        | KeyCode::ScrollLock
+        | KeyCode::NumLock
+        | KeyCode::PrintScreen
+        | KeyCode::Pause
+        | KeyCode::Menu
+        | KeyCode::KeypadBegin
+        | KeyCode::Media(_)
+        | KeyCode::Modifier(_) => Action::None,
+    }
+}
+
 /// Dispatch primary model selection based on provider and index (0=a, 1=b, 2=c, 3=d).
-pub(super) const fn dispatch_primary_model(state: &State, idx: usize) -> Action {
+const fn dispatch_primary_model(state: &State, idx: usize) -> Action {
     match state.llm_provider {
         LlmProvider::Anthropic | LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeApiKey => anthropic_model(idx),
         LlmProvider::Grok => grok_model(idx),
@@ -15,12 +15,11 @@ pub(crate) mod run;
 
 pub(crate) use context::{ensure_default_agent, ensure_default_contexts};
 
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::mpsc::Receiver;
 
 use crate::infra::tools::{ToolResult, ToolUse};
 use crate::infra::watcher::FileWatcher;
 use crate::state::State;
-use crate::state::cache::CacheUpdate;
 use crate::state::persistence::PersistenceWriter;
 use crate::ui::TypewriterBuffer;
 use crate::ui::help::CommandPalette;
@@ -48,8 +47,6 @@ pub(crate) struct App {
     pub pending_done: Option<PendingDone>,
     /// Pending tool calls accumulated during streaming.
     pub pending_tools: Vec<ToolUse>,
-    /// Sender for cache update requests to the background cache thread.
-    pub cache_tx: Sender<CacheUpdate>,
     /// Optional file-system watcher for auto-refresh on file changes.
     pub file_watcher: Option<FileWatcher>,
     /// Tracks which file paths are being watched
@@ -64,6 +61,8 @@ pub(crate) struct App {
     pub pending_retry_error: Option<String>,
     /// Last render time for throttling
     pub last_render_ms: u64,
+    /// Draw time of the last frame (ms), stretching the streaming render throttle
+    pub render_cost_ms: u64,
     /// Last spinner animation update time
     pub last_spinner_ms: u64,
     /// Last bridge-recovery retry time — throttles the periodic
@@ -74,6 +73,10 @@ pub(crate) struct App {
     pub last_chat_drain_ms: u64,
     /// Channel for API check results
     pub api_check_rx: Option<Receiver<crate::llms::ApiCheckResult>>,
+    /// In-flight connectivity probe (`true` = provider reachable)
+    pub connectivity_rx: Option<Receiver<bool>>,
+    /// Last time a connectivity probe was started
+    pub last_connectivity_probe_ms: u64,
     /// Whether to auto-start streaming on first loop iteration
     pub resume_stream: bool,
     /// Command palette state
@@ -96,6 +99,14 @@ pub(crate) struct App {
     pub accumulated_blocking_results: Vec<cp_base::state::watchers::carriers::WatcherResult>,
     /// Active reverie streams keyed by `agent_id` (one per agent type)
     pub reverie_streams: std::collections::HashMap<String, ReverieStream>,
+    /// Local HTTP control server, when started with `--control`
+    pub control: Option<cp_control::Control>,
+    /// Pairing socket, when started with `--pair`
+    pub pair: Option<cp_pair::Host>,
+    /// Read-only web viewer, when started with `--serve`
+    pub viewer: Option<cp_control::viewer::Viewer>,
+    /// Transcript file, when started with `--mirror`
+    pub mirror: Option<crate::infra::mirror::Mirror>,
 }
 
 // App impl block is in run/input.rs (primary), with additional methods spread
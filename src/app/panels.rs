@@ -9,7 +9,8 @@ use cp_base::panels::{CacheRequest, CacheUpdate};
 use cp_base::state::context::Entry;
 
 // Re-export the Panel trait, ContextItem, and utility functions from cp-base
-pub(crate) use cp_base::panels::{ContextItem, Panel, now_ms, paginate_content, update_if_changed};
+pub(crate) use cp_base::panels::{Panel, now_ms, paginate_content, update_if_changed};
+pub(crate) use cp_base::state::data::context_item::ContextItem;
 
 /// Get the appropriate panel for a context type (delegates to module system).
 /// Returns a no-op fallback for orphaned context types (e.g., removed modules).
@@ -71,6 +71,15 @@ pub(crate) fn assemble_prompt(
     api_messages
 }
 
+/// Append the user's standing instructions to the final message when it is a
+/// user turn. They are never stored in the conversation, so cleaning cannot drop
+/// them, and sitting after every panel keeps the cached prefix untouched.
+pub(crate) fn append_standing_instructions(api_messages: &mut [ApiMessage], text: &str) {
+    if let Some(last) = api_messages.last_mut().filter(|m| m.role == "user") {
+        last.content.push(ContentBlock::Text { text: text.to_owned() });
+    }
+}
+
 /// Per-message inputs for [`convert_conversation_message`]: the full slice, the
 /// current index, the message, and whether the last turn's tool-uses are kept.
 struct ConvertCtx<'conv> {
@@ -134,7 +143,7 @@ fn push_tool_result_message(api_messages: &mut Vec<ApiMessage>, messages: &[Mess
         if has_matching_call {
             content_blocks.push(ContentBlock::ToolResult {
                 tool_use_id: result.tool_use_id.clone(),
-                content: result.content.clone(),
+                content: msg.sent_result(result).into_owned(),
             });
         }
     }
@@ -153,7 +162,7 @@ fn build_text_message_blocks(
 ) -> Vec<ContentBlock> {
     let mut content_blocks: Vec<ContentBlock> = Vec::new();
     if !msg.content.is_empty() {
-        content_blocks.push(ContentBlock::Text { text: msg.content.clone() });
+        content_blocks.push(ContentBlock::Text { text: msg.sent_content().into_owned() });
     }
     let is_last = idx == total.saturating_sub(1);
     if msg.role == "assistant" && include_last_tool_uses && is_last && !msg.tool_uses.is_empty() {
@@ -320,3 +329,35 @@ fn tool_use_block(tool_use: &crate::state::ToolUseRecord) -> ContentBlock {
     };
     ContentBlock::ToolUse { id: tool_use.id.clone(), name: tool_use.name.clone(), input }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::data::sticky::StandingInstructions;
+
+    /// A message from `role` saying `text`.
+    fn turn(role: &str, text: &str) -> ApiMessage {
+        ApiMessage { role: role.to_owned(), content: vec![ContentBlock::Text { text: text.to_owned() }] }
+    }
+
+    /// Text of the last block of `message`, as sent on the wire.
+    fn last_text(message: Option<&ApiMessage>) -> Option<String> {
+        let block = serde_json::to_value(message?.content.last()?).ok()?;
+        block.get("text")?.as_str().map(str::to_owned)
+    }
+
+    #[test]
+    fn standing_instructions_close_the_last_user_turn() {
+        let rules = StandingInstructions { text: "  Always run the tests.\n".to_owned(), stashed_draft: None };
+        let text = rules.prompt_text().unwrap_or_default();
+        assert!(text.contains("Always run the tests.") && text.starts_with("/* Standing instructions"));
+        let mut messages = vec![turn("user", "hi"), turn("assistant", "hello"), turn("user", "fix it")];
+        append_standing_instructions(&mut messages, &text);
+        assert_eq!(last_text(messages.last()), Some(text.clone()));
+        assert_eq!(messages.last().map(|m| m.content.len()), Some(2));
+        let mut answered = vec![turn("user", "hi"), turn("assistant", "hello")];
+        append_standing_instructions(&mut answered, &text);
+        assert_eq!(last_text(answered.last()).as_deref(), Some("hello"));
+        assert_eq!(StandingInstructions::default().prompt_text(), None);
+    }
+}
@@ -5,10 +5,14 @@
 //! - [`repair`] runs as the final assembly phase, enforcing the Anthropic
 //!   tool-call adjacency invariant so an orphaned `tool_use` can never reach the
 //!   API (self-heals reshuffled/truncated histories).
+//! - [`structured`] runs one-shot, tool-free queries whose JSON answer is
+//!   validated against a schema (with one repair round).
 
 /// Wire message assembly: panel injection, conversation, alternation.
 pub(crate) mod builder;
 /// Final-phase tool-pairing repair (adjacency invariant enforcement).
 mod repair;
+/// Schema-validated JSON answers for module queries.
+pub(crate) mod structured;
 
-pub(crate) use builder::assemble_prompt;
+pub(crate) use builder::{append_standing_instructions, assemble_prompt};
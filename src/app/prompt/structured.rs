@@ -0,0 +1,69 @@
+//! Structured queries: one-shot, tool-free requests answered with JSON.
+//!
+//! The schema is sent as the provider's native response format where one
+//! exists and is always spelled out in the prompt as well. The answer is
+//! validated; an invalid one gets a single repair round (the rejected answer
+//! plus the validation error) before the query fails.
+
+use std::sync::mpsc;
+
+use cp_base::tools::output_schema::OutputSchema;
+pub(crate) use cp_base::tools::output_schema::StructuredQuery;
+use serde_json::Value;
+
+use crate::llms::{ApiMessage, ContentBlock, LlmRequest, StreamEvent, get_client};
+
+/// Output budget of a structured answer.
+const MAX_OUTPUT_TOKENS: u32 = 4096;
+
+/// Text-only API message.
+fn text_message(role: &str, text: String) -> ApiMessage {
+    ApiMessage { role: role.to_owned(), content: vec![ContentBlock::Text { text }] }
+}
+
+/// Send `messages` and collect the streamed answer text.
+fn ask(query: &StructuredQuery, messages: Vec<ApiMessage>) -> Result<String, String> {
+    let (tx, rx) = mpsc::channel();
+    let request = LlmRequest {
+        model: query.model.clone(),
+        max_output_tokens: MAX_OUTPUT_TOKENS,
+        messages: Vec::new(),
+        context_items: Vec::new(),
+        tools: Vec::new(),
+        tool_results: None,
+        system_prompt: Some(query.system.clone()),
+        extra_context: None,
+        seed_content: None,
+        worker_id: query.worker_id.clone(),
+        api_messages: messages,
+        cache_engine_json: None,
+        output_schema: Some(query.schema.clone()),
+    };
+    get_client(query.provider).stream(request, tx).map_err(|e| e.to_string())?;
+    let mut answer = String::new();
+    for event in rx.try_iter() {
+        if let StreamEvent::Chunk(text) = event {
+            answer.push_str(&text);
+        } else if let StreamEvent::Error(e) = event {
+            return Err(e);
+        } else {
+            // Usage, stop reason and tool events carry nothing for a structured answer.
+        }
+    }
+    Ok(answer)
+}
+
+/// Run `query` (blocking — call it from a worker thread) and return the
+/// validated JSON answer.
+pub(crate) fn run(query: &StructuredQuery) -> Result<Value, String> {
+    let question = text_message("user", format!("{}\n\n{}", query.prompt, query.schema.instruction()));
+    let first = ask(query, vec![question.clone()])?;
+    let error = match query.schema.parse(&first) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+    let repair =
+        vec![question, text_message("assistant", first), text_message("user", OutputSchema::repair_prompt(&error))];
+    let second = ask(query, repair)?;
+    query.schema.parse(&second).map_err(|e| format!("invalid structured answer after one repair attempt: {e}"))
+}
@@ -69,8 +69,8 @@ pub(crate) fn start_reverie_stream(state: &mut State, agent_id: &str, tx: Sender
 
 /// Build the reverie's seed content — its identity injection after the shared panel prefix.
 ///
-/// Contains the reverie agent's instructions, any user-provided directive, and tool
-/// restrictions. This is what makes the reverie behave differently from the main worker
+/// Contains the reverie agent's instructions, any user-provided directive and scope,
+/// and tool restrictions. This is what makes the reverie behave differently from the main worker
 /// despite sharing the exact same system prompt, panels, and tools.
 fn build_reverie_seed(state: &State, agent_id: &str, tool_restrictions: &str) -> String {
     let mut seed = String::new();
@@ -94,6 +94,13 @@ fn build_reverie_seed(state: &State, agent_id: &str, tool_restrictions: &str) ->
         seed.push('\n');
     }
 
+    // Scope constraints picked by the user (Ctrl+K)
+    if let Some(scope) = crate::modules::cleaner::scope_constraints(state, agent_id) {
+        seed.push_str("\n## Scope\n");
+        seed.push_str(&scope);
+        seed.push('\n');
+    }
+
     // Tool restrictions
     seed.push('\n');
     seed.push_str(tool_restrictions);
@@ -105,8 +105,11 @@ pub(crate) fn execute_optimize_context(tool: &ToolUse, state: &State) -> ToolRes
     let agent_id =
         tool.input.get("agent").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).unwrap_or("cleaner").to_owned();
 
-    // Guard: this specific agent type is already running
-    if state.reveries.contains_key(&agent_id) {
+    // Guard: this specific agent type is already running (planned cleans have no session)
+    let planned_running = state
+        .get_ext::<crate::modules::cleaner::types::CleanerState>()
+        .is_some_and(|cs| cs.running(&agent_id).is_some());
+    if state.reveries.contains_key(&agent_id) || planned_running {
         return ToolResult {
             tool_use_id: tool.id.clone(),
             content: REVERIE.errors.already_running.replace(concat!("{", "agent_id", "}"), &agent_id),
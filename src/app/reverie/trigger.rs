@@ -1,19 +1,24 @@
 //! Reverie trigger system — threshold detection and `optimize_context` tool.
 //!
 //! Two trigger paths:
-//! 1. **Automatic**: context tokens exceed cleaning threshold → fires reverie
-//! 2. **Manual**: main AI calls `optimize_context` tool → fires reverie with directive
+//! 1. **Automatic**: context tokens exceed cleaning threshold → starts a planned clean
+//! 2. **Manual**: main AI calls `optimize_context` tool → fires reverie with directive,
+//!    or the user picks a scope with Ctrl+K → starts a scoped planned clean
+//!
+//! The `cleaner` agent never gets a tool-calling session: its runs ask for a
+//! structured eviction plan that the Cleaner module executes.
 
+use crate::modules::cleaner::plan;
+use crate::modules::cleaner::scope::CleanScope;
 use crate::state::State;
 use cp_base::state::data::model_helpers::ModelPricing as _;
 use cp_base::state::runtime::reverie::{Kind, Session};
 
-/// Check whether the context has breached the cleaning threshold and a reverie
-/// should be auto-triggered.
+/// Check whether the context has breached the cleaning threshold and a
+/// planned clean should be auto-triggered.
 ///
-/// Returns `true` if a reverie was started (caller should begin streaming).
-/// Returns `false` if no action was taken (threshold not breached, reverie
-/// already active, or reverie disabled).
+/// Returns `true` if a run was started. Returns `false` if no action was
+/// taken (threshold not breached, a run already active, or reverie disabled).
 ///
 /// Call this after `prepare_stream_context()` has refreshed token counts.
 pub(crate) fn check_threshold_trigger(state: &mut State) -> bool {
@@ -22,11 +27,6 @@ pub(crate) fn check_threshold_trigger(state: &mut State) -> bool {
         return false;
     }
 
-    // Guard: reverie already running — don't stack 'em
-    if state.reveries.contains_key("cleaner") {
-        return false;
-    }
-
     // Sum all context element token counts
     let total_tokens: usize = state.context.iter().map(|c| c.token_count).sum();
     let threshold = state.cleaning_threshold_tokens();
@@ -35,22 +35,25 @@ pub(crate) fn check_threshold_trigger(state: &mut State) -> bool {
         return false;
     }
 
-    // Threshold breached — fire the reverie
-    // Start the reverie session with the default cleaner agent
-    let mut rev = Session::new(Kind::ContextOptimizer, "cleaner".to_owned(), None);
-    rev.queue_active = true;
-    let _r = state.reveries.insert("cleaner".to_owned(), rev);
-
-    true
+    // Threshold breached — plan a clean (refused while one is already running)
+    plan::start_planned_clean(state, None, CleanScope::Everything)
 }
 
-/// Start a reverie from the `optimize_context` tool (manual trigger).
+/// Start a reverie manually: from the `optimize_context` tool (unscoped) or
+/// the Ctrl+K scope picker.
 ///
 /// Called by the event loop when it detects the `REVERIE_START:` sentinel
-/// in a tool result from `execute_optimize_context()`.
+/// in a tool result from `execute_optimize_context()`, and by the palette.
+///
+/// The `cleaner` agent starts a planned clean instead of a reverie session.
 ///
-/// Returns `true` if the reverie was started, `false` if guards prevented it.
-pub(crate) fn start_manual_reverie(state: &mut State, agent_id: String, context: Option<String>) -> bool {
+/// Returns `true` if the run was started, `false` if guards prevented it.
+pub(crate) fn start_manual_reverie(
+    state: &mut State,
+    agent_id: String,
+    context: Option<String>,
+    scope: CleanScope,
+) -> bool {
     // Guard: this agent type is already running (one per agent)
     if state.reveries.contains_key(&agent_id) {
         return false;
@@ -62,7 +65,12 @@ pub(crate) fn start_manual_reverie(state: &mut State, agent_id: String, context:
         return false;
     }
 
+    if agent_id == plan::PLANNED_AGENT {
+        return plan::start_planned_clean(state, context, scope);
+    }
+
     // Start the reverie session
+    crate::modules::cleaner::begin_run(state, &agent_id, context.clone(), scope);
     let mut rev = Session::new(Kind::ContextOptimizer, agent_id.clone(), context);
     rev.queue_active = true;
     let _r = state.reveries.insert(agent_id, rev);
@@ -1,20 +1,22 @@
-use std::sync::mpsc::Sender;
-
 use crossterm::event;
 
 use crate::app::App;
-use crate::app::actions::Action;
+use crate::app::actions::{Action, TourMove};
 use crate::infra::watcher::FileWatcher;
-use crate::state::cache::CacheUpdate;
-use crate::state::persistence::{build_message_op, build_save_batch};
+use crate::state::persistence::{build_message_op, build_save_batch, save_state};
 use crate::state::{Message, State};
 use crate::ui::TypewriterBuffer;
-use crate::ui::help::CommandPalette;
+use crate::ui::help::{CommandPalette, PaletteMode};
 use cp_base::panels::now_ms;
+use cp_base::state::data::review::{MessageJump, MsgMarker};
+use cp_base::ui::{spell, text};
+
+/// Handler of a prefixed palette command id, given the whole id.
+type PrefixHandler = fn(&mut App, &str) -> Action;
 
 impl App {
-    /// Create a new `App` with the given state, cache channel, and resume flag.
-    pub(crate) fn new(state: State, cache_tx: Sender<CacheUpdate>, resume_stream: bool) -> Self {
+    /// Create a new `App` with the given state and resume flag.
+    pub(crate) fn new(state: State, resume_stream: bool) -> Self {
         let file_watcher = FileWatcher::new().ok();
 
         Self {
@@ -22,7 +24,6 @@ impl App {
             typewriter: TypewriterBuffer::new(),
             pending_done: None,
             pending_tools: Vec::new(),
-            cache_tx,
             file_watcher,
             watched_file_paths: std::collections::HashSet::new(),
             watched_dir_paths: std::collections::HashSet::new(),
@@ -30,10 +31,13 @@ impl App {
             last_ownership_check_ms: now_ms(),
             pending_retry_error: None,
             last_render_ms: 0,
+            render_cost_ms: 0,
             last_spinner_ms: 0,
             last_bridge_recover_ms: 0,
             last_chat_drain_ms: 0,
             api_check_rx: None,
+            connectivity_rx: None,
+            last_connectivity_probe_ms: 0,
             resume_stream,
             command_palette: CommandPalette::new(),
             wait_started_ms: 0,
@@ -44,6 +48,10 @@ impl App {
             pending_console_wait_tool_results: None,
             accumulated_blocking_results: Vec::new(),
             reverie_streams: std::collections::HashMap::new(),
+            control: None,
+            pair: None,
+            viewer: None,
+            mirror: None,
         }
     }
 
@@ -53,15 +61,73 @@ impl App {
         self.writer.send_batch(build_save_batch(&self.state));
     }
 
+    /// User quit: export the session's work report, then flush all pending
+    /// writes and save the final state synchronously.
+    pub(super) fn save_on_quit(&mut self) {
+        cp_mod_ledger::end_session(&mut self.state);
+        self.writer.flush();
+        save_state(&self.state);
+    }
+
     /// Send a message to background writer (non-blocking).
     /// Preferred over `save_message()` in the main event loop.
     pub(super) fn save_message_async(&self, msg: &Message) {
         self.writer.send_message(build_message_op(msg));
     }
 
+    /// Whether a popup over the draft (`@` autocomplete or spelling
+    /// suggestions) is capturing the keyboard.
+    pub(super) fn popup_open(&self) -> bool {
+        spell::Popup::is_open(&self.state)
+            || self.state.get_ext::<cp_base::state::autocomplete::Suggestions>().is_some_and(|ac| ac.active)
+    }
+
+    /// Route an event to the open draft popup. Returns the action to run, if
+    /// any (an accepted spelling fix goes through `InsertText` so it can be undone).
+    pub(super) fn handle_popup_event(&mut self, event: &event::Event) -> Option<Action> {
+        if spell::Popup::is_open(&self.state) {
+            return self.handle_spell_popup_event(event);
+        }
+        self.handle_autocomplete_event(event);
+        None
+    }
+
+    /// Handle keys while the spelling popup is open: Up/Down pick a
+    /// suggestion, Enter/Tab replace the word, any other key closes it.
+    fn handle_spell_popup_event(&mut self, event: &event::Event) -> Option<Action> {
+        use crossterm::event::KeyCode;
+        let &event::Event::Key(key) = event else { return None };
+        let popup = self.state.get_ext_mut::<spell::Popup>()?;
+        if key.code == KeyCode::Up {
+            popup.select_prev();
+        } else if key.code == KeyCode::Down {
+            popup.select_next();
+        } else if matches!(key.code, KeyCode::Enter | KeyCode::Tab) {
+            return self.spell_accept();
+        } else {
+            popup.close();
+        }
+        None
+    }
+
+    /// Close the spelling popup and select the misspelled word, returning the
+    /// `InsertText` that replaces it with the highlighted suggestion.
+    fn spell_accept(&mut self) -> Option<Action> {
+        let popup = self.state.get_ext_mut::<spell::Popup>()?;
+        popup.close();
+        let replacement = popup.selection()?.to_owned();
+        let (range, word) = (popup.range.clone(), popup.word.clone());
+        if self.state.input.get(range.clone()) != Some(word.as_str()) {
+            return None;
+        }
+        self.state.input_selection_anchor = Some(range.start);
+        self.state.input_cursor = range.end;
+        Some(Action::InsertText(replacement))
+    }
+
     /// Handle keyboard events when the @ autocomplete popup is active.
     /// Mutates `Suggestions` and state.input directly.
-    pub(super) fn handle_autocomplete_event(&mut self, event: &event::Event) {
+    fn handle_autocomplete_event(&mut self, event: &event::Event) {
         use crossterm::event::{KeyCode, KeyModifiers};
         let &event::Event::Key(key) = event else { return };
         if self.state.get_ext_mut::<cp_base::state::autocomplete::Suggestions>().is_none() {
@@ -122,16 +188,18 @@ impl App {
         let prefix = ac.current_prefix().to_owned();
         let entries = cp_mod_tree::tools::list_dir_entries(&filter, &dir, &prefix);
         let Some(ac_set) = self.state.get_ext_mut::<cp_base::state::autocomplete::Suggestions>() else { return };
-        ac_set.set_matches(entries);
+        let matches = ac_set.build_matches(entries);
+        ac_set.set_matches(matches);
     }
 
     /// Accept the selected autocomplete entry (Enter/Tab): a directory completes
     /// to `dir/` and refreshes contents (popup stays open); a file inserts its
-    /// full path plus a trailing space and closes the popup.
+    /// full path (remembered for auto-open on send) and a panel its ID, plus a
+    /// trailing space, and closes the popup.
     fn autocomplete_accept(&mut self) {
         let Some(ac) = self.state.get_ext_mut::<cp_base::state::autocomplete::Suggestions>() else { return };
-        let entry_info = ac.selected_match().map(|e| (e.name.clone(), e.is_dir));
-        let Some((name, is_dir)) = entry_info else {
+        let entry_info = ac.selected_match().map(|e| (e.name.clone(), e.is_dir, e.panel.is_some()));
+        let Some((name, is_dir, is_panel)) = entry_info else {
             ac.deactivate();
             return;
         };
@@ -154,7 +222,10 @@ impl App {
             }
             self.autocomplete_refresh_matches();
         } else {
-            // File: insert the full path and close.
+            // File or panel: insert the full path / ID and close.
+            if !is_panel {
+                ac.mentioned.push(full_path.clone());
+            }
             ac.deactivate();
             let cursor = self.state.input_cursor;
             self.state.input = format!(
@@ -210,12 +281,12 @@ impl App {
         let Some(ac) = self.state.get_ext_mut::<cp_base::state::autocomplete::Suggestions>() else { return };
         if c == ' ' || c == '\n' {
             ac.deactivate();
-            self.state.input.insert(self.state.input_cursor, c);
-            self.state.input_cursor = self.state.input_cursor.saturating_add(c.len_utf8());
+            self.state.input_cursor =
+                text::insert_at(&mut self.state.input, self.state.input_cursor, c.encode_utf8(&mut [0; 4]));
         } else {
             ac.push_char(c);
-            self.state.input.insert(self.state.input_cursor, c);
-            self.state.input_cursor = self.state.input_cursor.saturating_add(c.len_utf8());
+            self.state.input_cursor =
+                text::insert_at(&mut self.state.input, self.state.input_cursor, c.encode_utf8(&mut [0; 4]));
             self.autocomplete_refresh_matches();
         }
     }
@@ -224,6 +295,10 @@ impl App {
     pub(super) fn handle_palette_event(&mut self, event: &event::Event) -> Option<Action> {
         use crossterm::event::KeyCode;
 
+        if let event::Event::Paste(pasted) = event.clone() {
+            self.command_palette.insert_str(&pasted, &self.state);
+            return None;
+        }
         let &event::Event::Key(key) = event else {
             return Some(Action::None);
         };
@@ -238,10 +313,34 @@ impl App {
         if key.code == KeyCode::Enter {
             return self.palette_execute_selected();
         }
-        self.palette_edit_nav(key);
+        if !self.palette_toggle_marker(key) {
+            self.palette_edit_nav(key);
+        }
         None
     }
 
+    /// Alt+1/2/3 in the markers palette: toggle ⭐/❓/🐛 on the highlighted
+    /// message, persist it and keep it highlighted as the list reorders.
+    /// `false` when the key is not a marker toggle.
+    fn palette_toggle_marker(&mut self, key: event::KeyEvent) -> bool {
+        use crossterm::event::{KeyCode, KeyModifiers};
+        let KeyCode::Char(c) = key.code else { return false };
+        let in_markers = self.command_palette.mode == PaletteMode::Markers;
+        let Some(marker) = MsgMarker::for_digit(c).filter(|_| in_markers && key.modifiers.contains(KeyModifiers::ALT))
+        else {
+            return false;
+        };
+        let Some(command_id) = self.command_palette.get_selected().map(|cmd| cmd.id.clone()) else { return true };
+        let message_id = command_id.strip_prefix(crate::ui::help::MESSAGE_PREFIX).unwrap_or("");
+        if let Some(msg) = self.state.messages.iter_mut().find(|m| m.id == message_id) {
+            marker.toggle_in(&mut msg.markers);
+            self.writer.send_message(build_message_op(msg));
+        }
+        self.command_palette.update_filtered(&self.state);
+        self.command_palette.select_id(&command_id);
+        true
+    }
+
     /// Query-editing + result-navigation keys for the command palette (every
     /// key except Esc/Enter): arrows/Home/End move the selection or cursor,
     /// Backspace/Delete/Char edit the query, Tab cycles results. Ignores
@@ -292,8 +391,17 @@ impl App {
 
     /// Execute the palette's selected command (Enter): close the palette, then
     /// dispatch by command id — `quit` signals quit (`None`), `reload` sets the
-    /// reload flag, `config` toggles the config view, and any context-panel id
-    /// navigates to that panel. Unknown ids are a no-op (`Action::None`).
+    /// reload flag, `config` toggles the config view, `cleaner_undo` rolls back
+    /// the last cleaning run, `clean_plan` asks for an eviction plan to
+    /// preview, `clean_apply` executes it, `clean:<scope>` starts a scoped
+    /// cleaning run, `msg:<id>` jumps to a message (markers palette),
+    /// `group:<action>:<name>` acts on a panel group,
+    /// `template:<id>` starts a conversation template, `snippet:<id>`
+    /// inserts a snippet into the draft,
+    /// `close_deprecated` closes the deprecated panels, `perf_export` writes
+    /// the recorded profile as folded stacks, and
+    /// any context-panel id navigates to that panel. Unknown ids are a no-op
+    /// (`Action::None`).
     fn palette_execute_selected(&mut self) -> Option<Action> {
         let Some(cmd) = self.command_palette.get_selected() else {
             return Some(Action::None);
@@ -308,14 +416,117 @@ impl App {
                 Some(Action::None)
             }
             "config" => Some(Action::ToggleConfigView),
-            _ => {
-                // Navigate to any context panel (P-prefixed or special IDs like "chat").
-                if self.state.context.iter().any(|c| c.id == id) {
-                    Some(Action::SelectContextById(id))
-                } else {
-                    Some(Action::None)
+            "tour" => Some(Action::Tour(TourMove::Start)),
+            "close_deprecated" => Some(Action::CloseDeprecatedPanels),
+            "cleaner_undo" => {
+                // Success and refusal are both visible in the Cleaner panel; jump there
+                let _r = crate::modules::cleaner::undo_last_run(&mut self.state);
+                self.save_state_async();
+                Some(self.select_fixed_panel(crate::state::Kind::CLEANER))
+            }
+            "clean_plan" => {
+                let _requested = crate::modules::cleaner::plan::request_plan(&mut self.state);
+                Some(self.select_fixed_panel(crate::state::Kind::CLEANER))
+            }
+            "clean_apply" => {
+                if crate::modules::cleaner::apply_ready_plan(&mut self.state) {
+                    self.save_state_async();
                 }
+                Some(self.select_fixed_panel(crate::state::Kind::CLEANER))
+            }
+            "perf_export" => {
+                crate::ui::perf::history::export_and_report(&mut self.state);
+                Some(Action::None)
+            }
+            "ledger_report" => {
+                let _path = cp_mod_ledger::export_report(&mut self.state);
+                self.save_state_async();
+                Some(self.select_fixed_panel(crate::state::Kind::LEDGER))
             }
+            _ => Some(self.run_prefixed_command(id)),
+        }
+    }
+
+    /// Dispatch the palette command ids that carry an argument after a
+    /// prefix; any other id navigates to the context panel it names.
+    fn run_prefixed_command(&mut self, id: String) -> Action {
+        let handlers: [(&str, PrefixHandler); 5] = [
+            (crate::ui::help::CLEAN_SCOPE_PREFIX, Self::start_scoped_clean),
+            (crate::ui::help::MESSAGE_PREFIX, Self::jump_to_message),
+            (crate::ui::help::GROUP_PREFIX, Self::run_group_command),
+            (crate::ui::help::TEMPLATE_PREFIX, Self::start_template),
+            (crate::ui::help::SNIPPET_PREFIX, Self::insert_snippet),
+        ];
+        if let Some(handler) = handlers.iter().find(|h| id.starts_with(h.0)) {
+            return (handler.1)(self, &id);
+        }
+        // Navigate to any context panel (P-prefixed or special IDs like "chat").
+        if self.state.context.iter().any(|c| c.id == id) { Action::SelectContextById(id) } else { Action::None }
+    }
+
+    /// Start a cleaning run restricted to the scope encoded in a Ctrl+K
+    /// picker command id, then show the Cleaner panel.
+    fn start_scoped_clean(&mut self, command_id: &str) -> Action {
+        let spec = command_id.strip_prefix(crate::ui::help::CLEAN_SCOPE_PREFIX).unwrap_or("");
+        let Some(scope) = crate::modules::cleaner::scope::CleanScope::from_spec(&self.state, spec) else {
+            return Action::None;
+        };
+        if !crate::app::reverie::trigger::start_manual_reverie(&mut self.state, "cleaner".to_owned(), None, scope) {
+            return Action::None;
         }
+        self.select_fixed_panel(crate::state::Kind::CLEANER)
+    }
+
+    /// Run a `group:<action>:<name>` palette command on a panel group.
+    fn run_group_command(&mut self, command_id: &str) -> Action {
+        let rest = command_id.strip_prefix(crate::ui::help::GROUP_PREFIX).unwrap_or("");
+        if let Some((action, name)) = rest.split_once(':') {
+            let _r = crate::modules::overview::panel_group::run_action(&mut self.state, action, name);
+            self.save_state_async();
+        }
+        Action::None
+    }
+
+    /// Apply the conversation template named by a palette command id, then
+    /// show the panel it asks for (the conversation by default).
+    fn start_template(&mut self, command_id: &str) -> Action {
+        let id = command_id.strip_prefix(crate::ui::help::TEMPLATE_PREFIX).unwrap_or("");
+        let Some(show) = crate::app::actions::templates::apply(&mut self.state, id) else {
+            return Action::None;
+        };
+        self.save_state_async();
+        self.select_fixed_panel(&show)
+    }
+
+    /// Insert the snippet named by a palette command id into the draft at the
+    /// cursor, then show the conversation where the draft is edited.
+    fn insert_snippet(&mut self, command_id: &str) -> Action {
+        let id = command_id.strip_prefix(crate::ui::help::SNIPPET_PREFIX).unwrap_or("");
+        if !crate::app::actions::snippets::insert(&mut self.state, id) {
+            return Action::None;
+        }
+        self.select_fixed_panel(crate::state::Kind::CONVERSATION)
+    }
+
+    /// Scroll the conversation to the message picked in the Ctrl+B markers
+    /// palette (resolved by the next conversation render) and show it.
+    fn jump_to_message(&mut self, command_id: &str) -> Action {
+        let message_id = command_id.strip_prefix(crate::ui::help::MESSAGE_PREFIX).unwrap_or("").to_owned();
+        self.state.set_ext(MessageJump { message_id, line: None });
+        self.state.full_content_cache = None;
+        self.state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == crate::state::Kind::CONVERSATION)
+            .map_or(Action::None, |c| Action::SelectContextById(c.id.clone()))
+    }
+
+    /// Navigate to the fixed panel of `kind` (no-op when its module is inactive).
+    fn select_fixed_panel(&self, kind: &str) -> Action {
+        self.state
+            .context
+            .iter()
+            .find(|c| c.context_type.as_str() == kind)
+            .map_or(Action::None, |c| Action::SelectContextById(c.id.clone()))
     }
 }
@@ -10,16 +10,14 @@ use crate::app::actions::{Action, ActionResult, apply_action};
 use crate::app::events::handle_event;
 use crate::app::panels::now_ms;
 use crate::infra::api::{StreamEvent, start_streaming};
-use crate::infra::constants::{EVENT_POLL_MS, RENDER_THROTTLE_MS};
 use crate::state::Kind;
-use crate::state::cache::CacheUpdate;
 use crate::state::persistence::{check_ownership, save_state};
 use crate::ui;
 
 use crate::app::App;
 use crate::app::context::{build_stream_params, get_active_agent_content, prepare_stream_context};
 use cp_mod_spine::engine::{SpineDecision, apply_continuation, check_spine};
-use cp_mod_spine::types::{NotificationType, SpineState};
+use cp_mod_spine::types::{ContinuationAction, NotificationType, SpineState};
 
 /// Bundles the I/O channels polled by the main event loop.
 pub(crate) struct EventChannels<'ch> {
@@ -27,8 +25,6 @@ pub(crate) struct EventChannels<'ch> {
     pub tx: &'ch Sender<StreamEvent>,
     /// Receives stream events from the LLM provider thread.
     pub rx: &'ch Receiver<StreamEvent>,
-    /// Receives cache update results from the background hasher.
-    pub cache_rx: &'ch Receiver<CacheUpdate>,
 }
 
 /// Outcome of the input phase, telling the main loop how to proceed this tick.
@@ -62,14 +58,13 @@ impl App {
         super::tools::watchdog::spawn();
 
         self.auto_resume_stream_if_flagged();
-
         loop {
             let current_ms = now_ms();
             let _fg = cp_base::flame!("loop");
 
             // Main-loop heartbeat: a fresh tick every iteration. The watchdog
             // thread declares a wedge if this stops advancing (the loop ticks at
-            // least every ~50 ms even while idle, so staleness is unambiguous).
+            // least every ~250 ms even while idle, so staleness is unambiguous).
             super::tools::watchdog::beat();
             super::tools::watchdog::mark(super::tools::watchdog::Step::Input);
 
@@ -104,14 +99,14 @@ impl App {
             // Update spinner animation if there's active loading/streaming
             self.update_spinner_animation();
 
-            // Render if dirty and enough time has passed (capped at ~28fps)
-            if self.state.flags.ui.dirty && current_ms.saturating_sub(self.last_render_ms) >= RENDER_THROTTLE_MS {
+            // Render if dirty and enough time has passed (paced by super::pacing)
+            if super::pacing::render_due(self, current_ms) {
                 super::tools::watchdog::mark(super::tools::watchdog::Step::Render);
                 self.render_frame(terminal, current_ms)?;
             }
 
             super::tools::watchdog::mark(super::tools::watchdog::Step::Idle);
-            let _r = event::poll(Duration::from_millis(self.compute_poll_ms()))?;
+            let _r = event::poll(Duration::from_millis(super::pacing::poll_ms(self)))?;
         }
 
         Ok(())
@@ -132,31 +127,25 @@ impl App {
         save_state(&self.state);
     }
 
-    /// Draw one frame: render the UI + command palette, clear dirty, stamp render time.
+    /// Draw one frame: render the UI + command palette, clear dirty, stamp render time, draw panel images,
+    /// and mirror the frame to `--pair` guests.
     fn render_frame(
         &mut self,
         terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
         current_ms: u64,
     ) -> io::Result<()> {
-        let _r = terminal.draw(|frame| {
+        let started = std::time::Instant::now();
+        let completed = terminal.draw(|frame| {
             ui::render(frame, &mut self.state);
             self.command_palette.render(frame, &self.state);
         })?;
+        if let Some(host) = self.pair.as_ref() {
+            host.publish(completed.buffer);
+        }
         self.state.flags.ui.dirty = false;
         self.last_render_ms = current_ms;
-        Ok(())
-    }
-
-    /// Adaptive poll interval: short while streaming/active or bridge-driven,
-    /// long when idle — keeps latency low without pinning a core at rest.
-    fn compute_poll_ms(&self) -> u64 {
-        if self.state.flags.stream.phase.is_streaming() || self.state.flags.ui.dirty {
-            EVENT_POLL_MS // 8ms — responsive during streaming/active updates
-        } else if super::threads::bridge_active(&self.state) {
-            2 // bridge-active idle — keep web command→apply latency ≤ a few ms
-        } else {
-            50 // 50ms when idle — still responsive for typing, much less CPU
-        }
+        self.render_cost_ms = cp_base::cast::Safe::to_u64(started.elapsed().as_millis());
+        cp_graphics::flush(terminal.backend_mut())
     }
 
     /// Non-blocking input phase: poll one event and route it (palette,
@@ -168,10 +157,7 @@ impl App {
         ch: &EventChannels<'_>,
         current_ms: u64,
     ) -> io::Result<InputOutcome> {
-        if !event::poll(Duration::ZERO)? {
-            return Ok(InputOutcome::Continue);
-        }
-        let evt = event::read()?;
+        let Some(evt) = super::threads::next_input_event(self)? else { return Ok(InputOutcome::Continue) };
 
         // Command palette takes precedence when open.
         if self.command_palette.is_open {
@@ -183,26 +169,24 @@ impl App {
             return Ok(InputOutcome::Restart);
         }
 
-        // Autocomplete popup handling.
-        if let Some(ac) = self.state.get_ext::<cp_base::state::autocomplete::Suggestions>()
-            && ac.active
-        {
-            self.handle_autocomplete_event(&evt);
+        // Draft popups (autocomplete, spelling suggestions) capture the keyboard.
+        if self.popup_open() {
+            if let Some(action) = self.handle_popup_event(&evt) {
+                self.handle_action(action, ch.tx);
+            }
             self.state.flags.ui.dirty = true;
             self.render_frame(terminal, current_ms)?;
             return Ok(InputOutcome::Restart);
         }
 
         let Some(action) = handle_event(&evt, &self.state) else {
-            // User quit — flush all pending writes and save final state synchronously
-            self.writer.flush();
-            save_state(&self.state);
+            self.save_on_quit();
             return Ok(InputOutcome::Quit);
         };
 
-        // Ctrl+P opens the palette; everything else dispatches normally.
-        if matches!(action, Action::OpenCommandPalette) {
-            self.command_palette.open(&self.state);
+        // Ctrl+P / Ctrl+K open the palette; everything else dispatches normally.
+        if let Some(mode) = ui::help::PaletteMode::for_action(&action) {
+            self.command_palette.open(&self.state, mode);
             self.state.flags.ui.dirty = true;
         } else {
             self.handle_action(action, ch.tx);
@@ -220,6 +204,8 @@ impl App {
     fn run_background_phase(&mut self, ch: &EventChannels<'_>, current_ms: u64) {
         super::tools::watchdog::mark(super::tools::watchdog::Step::Bridge);
         super::threads::poll_bridge_commands(self);
+        super::threads::poll_control_requests(self);
+        super::threads::publish_viewer(self, current_ms);
         super::tools::watchdog::mark(super::tools::watchdog::Step::ThreadsEmit);
         super::threads::emit_vitals(self);
         super::threads::emit_messages(self);
@@ -231,13 +217,18 @@ impl App {
         super::streaming::process_stream_events(self, ch.rx);
         super::streaming::handle_retry(self, ch.tx);
         super::streaming::process_typewriter(self);
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.sync(&self.state.messages);
+        }
+        if let Some(transcript) = crate::infra::voice::poll(&mut self.state) {
+            self.handle_action(Action::InsertText(transcript), ch.tx);
+        }
         super::tools::watchdog::mark(super::tools::watchdog::Step::Cache);
-        super::watchers::process_cache_updates(self, ch.cache_rx);
+        super::watchers::process_cache_updates(self);
         super::tools::watchdog::mark(super::tools::watchdog::Step::Watchers);
         super::watchers::process_watcher_events(self);
-        // Check if we're waiting for panels and they're ready (non-blocking)
+        // Non-blocking: panels ready after a wait? deferred sleep timer expired?
         super::tools::checks::check_waiting_for_panels(self, ch.tx);
-        // Check if deferred sleep timer has expired (non-blocking)
         super::tools::checks::check_deferred_sleep(self, ch.tx);
         // Check watchers (blocking sentinel replacement + async → spine notifications)
         super::tools::cleanup::check_watchers(self, ch.tx);
@@ -248,7 +239,9 @@ impl App {
         super::tools::pipeline::handle_tool_execution(self, ch.tx);
         super::streaming::finalize_stream(self);
         super::tools::watchdog::mark(super::tools::watchdog::Step::Spine);
+        super::streaming::check_connectivity(self, current_ms);
         self.check_spine(ch.tx);
+        cp_mod_spine::cues::fire_pending(&mut self.state);
         super::threads::check_my_turn_threads(self);
         super::streaming::process_api_check_results(self);
 
@@ -357,11 +350,12 @@ impl App {
     }
 
     /// Check the spine for auto-continuation decisions.
-    /// Evaluates guard rails and auto-continuation logic.
-    /// If a continuation fires, starts streaming.
+    /// Evaluates guard rails and auto-continuation logic; starts streaming if one fires.
     fn check_spine(&mut self, tx: &Sender<StreamEvent>) {
         // Check if incomplete todos should trigger auto-continuation
         self.check_todo_continuation();
+        // Unmet acceptance criteria refuse the agent's stop
+        cp_mod_todo::goals::check_continuation(&mut self.state);
 
         // Idle is the implicit no-op tail — a non_exhaustive enum forbids a
         // cross-crate exhaustive match, so the two actionable variants are
@@ -377,8 +371,9 @@ impl App {
                 self.save_state_async();
             }
         } else if let SpineDecision::Continue(action) = decision {
-            // Auto-continuation fired — apply it and start streaming
+            // Auto-continuation fired — apply and stream; a splice keeps the cut-off answer as prefill.
             self.state.guard_rail_blocked = None;
+            let splice = matches!(action, ContinuationAction::Splice);
             let should_stream = apply_continuation(&mut self.state, action);
             if should_stream {
                 // Auto-Read: if unfocused with a MY_TURN thread, inject a
@@ -390,13 +385,14 @@ impl App {
                 // pending_tools (that would wipe the injected Read).
                 // Behaviourally identical to the LLM emitting a Read as its
                 // first action (T322).
-                if !super::threads::maybe_inject_auto_read(self) {
+                if splice || !super::threads::maybe_inject_auto_read(self) {
                     self.typewriter.reset();
                     self.pending_tools.clear();
-                    let ctx = prepare_stream_context(&mut self.state, false, None);
+                    let ctx = prepare_stream_context(&mut self.state, splice, None);
                     let system_prompt = get_active_agent_content(&self.state);
                     let params = build_stream_params(&self.state, ctx, Some(system_prompt));
                     start_streaming(params, tx.clone());
+                    ui::perf::latency::request_started(&mut self.state);
                 }
                 self.save_state_async();
                 self.state.flags.ui.dirty = true;
@@ -478,7 +474,7 @@ impl App {
     /// - a **console is running** — its sidebar glyph spins.
     ///
     /// When none hold, the screen is static and no periodic redraw is needed.
-    fn has_active_animation(state: &crate::state::State) -> bool {
+    pub(super) fn has_active_animation(state: &crate::state::State) -> bool {
         if state.flags.stream.phase.is_streaming() {
             return true; // STREAMING / TOOLING badge spinner
         }
@@ -492,7 +488,7 @@ impl App {
         // A panel still loading its first content (LOADING badge + sidebar
         // spinner) or a running console (animated sidebar glyph).
         state.context.iter().any(|c| {
-            (c.cached_content.is_none() && c.context_type.needs_cache())
+            (c.cached_content.is_none() && c.context_type.needs_cache() && !c.awaits_load())
                 || (c.context_type.as_str() == "console"
                     && c.get_meta_str("console_status").is_some_and(|s| s.starts_with("running")))
         })
@@ -2,9 +2,11 @@
 mod input;
 /// Main event loop (`App::run`) and spine check / auto-continuation.
 pub(crate) mod lifecycle;
+/// Main-loop pacing: adaptive poll interval and render throttle.
+mod pacing;
 /// Reverie (context-optimizer sub-agent) stream lifecycle and tool dispatch.
 mod reverie;
-/// Stream-event processing, retry logic, typewriter buffer, and stream finalization.
+/// Stream-event processing, retry logic, typewriter buffer, stream finalization and connectivity monitor.
 mod streaming;
 /// Thread-related helpers: auto-Read injection, `MY_TURN` detection.
 pub(super) mod threads;
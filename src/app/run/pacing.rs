@@ -0,0 +1,75 @@
+//! Main-loop pacing: how long each tick sleeps and how often the UI redraws.
+//!
+//! Input renders at once (see `handle_input_phase`). Other redraws are spaced
+//! at least [`RENDER_THROTTLE_MS`] apart; while streaming the gap stretches to
+//! a few times the last frame's draw time (up to [`STREAM_RENDER_MAX_MS`]), so
+//! a heavy stream coalesces its chunks into fewer frames instead of spending
+//! the tick drawing. With nothing going on — no stream, no animation, no
+//! pending work — the loop drops to an [`IDLE_POLL_MS`] tick; a key press
+//! still wakes it immediately.
+
+use crate::app::App;
+use crate::infra::constants::{EVENT_POLL_MS, IDLE_POLL_MS, RENDER_THROTTLE_MS, STREAM_RENDER_MAX_MS};
+
+/// Poll interval while background work is pending but nothing streams.
+const BUSY_POLL_MS: u64 = 50;
+/// Poll interval while the web bridge is live (command→apply latency).
+const BRIDGE_POLL_MS: u64 = 2;
+/// While streaming, frames are spaced this many times their draw time.
+const COST_FACTOR: u64 = 4;
+
+/// Minimum gap between two frames, given the last frame's draw time.
+pub(super) fn render_interval_ms(streaming: bool, render_cost_ms: u64) -> u64 {
+    if streaming {
+        render_cost_ms.saturating_mul(COST_FACTOR).clamp(RENDER_THROTTLE_MS, STREAM_RENDER_MAX_MS)
+    } else {
+        RENDER_THROTTLE_MS
+    }
+}
+
+/// Whether the loop should draw a frame now.
+pub(super) fn render_due(app: &App, current_ms: u64) -> bool {
+    let streaming = app.state.flags.stream.phase.is_streaming();
+    app.state.flags.ui.dirty
+        && current_ms.saturating_sub(app.last_render_ms) >= render_interval_ms(streaming, app.render_cost_ms)
+}
+
+/// Work the loop must keep ticking for even though nothing streams: tools,
+/// waits, retries, reverie streams, API checks, guests of a pairing session.
+fn has_pending_work(app: &App) -> bool {
+    !app.pending_tools.is_empty()
+        || app.pending_done.is_some()
+        || app.pending_retry_error.is_some()
+        || app.pending_console_wait_tool_results.is_some()
+        || app.deferred_tool_sleeping
+        || app.state.flags.lifecycle.waiting_for_panels
+        || !app.reverie_streams.is_empty()
+        || app.api_check_rx.is_some()
+        || app.state.get_ext::<cp_pair::Status>().is_some_and(|s| s.guests > 0)
+}
+
+/// How long the next tick may wait for terminal input.
+pub(super) fn poll_ms(app: &App) -> u64 {
+    if app.state.flags.stream.phase.is_streaming() || app.state.flags.ui.dirty {
+        EVENT_POLL_MS
+    } else if super::threads::bridge_active(&app.state) {
+        BRIDGE_POLL_MS
+    } else if has_pending_work(app) || App::has_active_animation(&app.state) {
+        BUSY_POLL_MS
+    } else {
+        IDLE_POLL_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaming_frames_stretch_with_their_cost() {
+        assert_eq!(render_interval_ms(true, 2), RENDER_THROTTLE_MS);
+        assert_eq!(render_interval_ms(true, 20), 80);
+        assert_eq!(render_interval_ms(true, 500), STREAM_RENDER_MAX_MS);
+        assert_eq!(render_interval_ms(false, 500), RENDER_THROTTLE_MS);
+    }
+}
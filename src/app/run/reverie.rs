@@ -6,6 +6,7 @@ use std::sync::mpsc;
 use crate::app::App;
 use crate::app::reverie::{streaming, tools};
 use crate::infra::api::StreamEvent;
+use crate::modules::cleaner::{self, types::RunStatus};
 use crate::state::persistence::save_state;
 use cp_base::config::REVERIE;
 use cp_mod_queue::types::QueueState;
@@ -102,6 +103,7 @@ fn append_reverie_chunk(app: &mut App, agent_id: &str, text: &str) {
 /// A reverie stream errored: notify, discard its queued actions, destroy the
 /// agent's session (non-critical — reveries are best-effort).
 fn destroy_reverie_on_error(app: &mut App, agent_id: &str, err: &str) {
+    cleaner::finish_run(&mut app.state, agent_id, RunStatus::Aborted, &format!("stream error: {err}"));
     let _notif = cp_mod_spine::types::SpineState::create_notification(
         &mut app.state,
         cp_mod_spine::types::NotificationType::Custom,
@@ -163,6 +165,7 @@ fn reverie_over_tool_cap(app: &mut App, agent_id: &str) -> bool {
     if app.state.reveries.get(agent_id).is_none_or(|r| r.tool_call_count <= cap) {
         return false;
     }
+    cleaner::finish_run(&mut app.state, agent_id, RunStatus::Aborted, &format!("tool cap ({cap}) reached"));
     let _notif_cap = cp_mod_spine::types::SpineState::create_notification(
         &mut app.state,
         cp_mod_spine::types::NotificationType::Custom,
@@ -204,6 +207,11 @@ fn dispatch_one_reverie_tool(
         return Some(result);
     }
 
+    // Closing something outside the run's scope is refused before it can be queued
+    if let Err(msg) = cleaner::check_scope(&app.state, agent_id, tool) {
+        return Some(crate::infra::tools::ToolResult::new(tool.id.clone(), msg, true));
+    }
+
     // Tool is allowed — check if reverie queue is active
     let should_queue =
         app.state.reveries.get(agent_id).is_some_and(|r| r.queue_active) && !QueueState::is_queue_tool(&tool.name);
@@ -221,16 +229,12 @@ fn dispatch_one_reverie_tool(
     Some(crate::modules::dispatch_tool(tool, &mut app.state, &active))
 }
 
-/// Handle a `REVERIE_REPORT:` sentinel: emit the summary notification, mark the
-/// stream reported, clear its queued actions, and destroy the session.
+/// Handle a `REVERIE_REPORT:` sentinel: file the summary in the Cleaner panel
+/// (not the main conversation), mark the stream reported, clear its queued
+/// actions, and destroy the session.
 fn destroy_reverie_on_report(app: &mut App, agent_id: &str, content: &str) {
     let summary = content.strip_prefix("REVERIE_REPORT:").unwrap_or("Completed");
-    let _notif_report = cp_mod_spine::types::SpineState::create_notification(
-        &mut app.state,
-        cp_mod_spine::types::NotificationType::Custom,
-        "Reverie".to_owned(),
-        summary.to_owned(),
-    );
+    cleaner::finish_run(&mut app.state, agent_id, RunStatus::Reported, summary);
     if let Some(stream) = app.reverie_streams.get_mut(agent_id) {
         stream.report_called = true;
     }
@@ -249,6 +253,7 @@ fn record_reverie_tool_exchange(
     tool: &cp_base::tools::ToolUse,
     result: &crate::infra::tools::ToolResult,
 ) {
+    cleaner::record_decision(&mut app.state, agent_id, tool, result);
     let Some(rev) = app.state.reveries.get_mut(agent_id) else { return };
     rev.messages.push(crate::state::Message::new_tool_call(
         format!("rev-tc-{}", rev.messages.len()),
@@ -307,6 +312,7 @@ pub(super) fn check_reverie_end_turn(app: &mut App) {
         let retries = app.state.reveries.get(&agent_id).map_or(0, |r| r.report_retries);
         if retries >= 1 {
             // Max retries reached — force destroy
+            cleaner::finish_run(&mut app.state, &agent_id, RunStatus::Aborted, "ended without Report");
             let _notif_end = cp_mod_spine::types::SpineState::create_notification(
                 &mut app.state,
                 cp_mod_spine::types::NotificationType::Custom,
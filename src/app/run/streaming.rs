@@ -1,5 +1,7 @@
 use cp_base::state::data::model_helpers::ModelPricing as _;
-use std::sync::mpsc::{Receiver, Sender};
+use std::net::{TcpStream, ToSocketAddrs as _};
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+use std::time::Duration;
 
 use crate::app::actions::{Action, ActionResult, apply_action};
 use crate::infra::api::{StreamEvent, start_streaming};
@@ -7,19 +9,42 @@ use crate::infra::constants::MAX_API_RETRIES;
 
 use crate::app::App;
 use crate::app::context::{build_stream_params, get_active_agent_content, prepare_stream_context};
-use crate::state::cache::{CacheUpdate, process_cache_request};
+use crate::state::cache::process_cache_request;
 use crate::state::{State, StreamPhase, get_context_type_meta};
+use crate::ui::perf::latency::{self, Mark};
+
+/// Interval between connectivity probes while online (ms).
+const PROBE_INTERVAL_MS: u64 = 15_000;
+/// Interval between connectivity probes while offline (ms).
+const OFFLINE_PROBE_INTERVAL_MS: u64 = 3_000;
+/// TCP connect timeout of a single probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Drain the stream-event channel and apply each event (chunks, tools, done, errors).
+///
+/// Every event (and every tick spent outside [`StreamPhase::Receiving`])
+/// refreshes the stall clock shown in the status bar.
 pub(super) fn process_stream_events(app: &mut App, rx: &Receiver<StreamEvent>) {
     let _guard = crate::profile!("app::stream_events");
     let _fg = cp_base::flame!("stream");
+    let now = crate::app::panels::now_ms();
+    if app.state.flags.stream.phase != StreamPhase::Receiving {
+        app.state.flags.stream.last_delta_ms = now;
+    }
     while let Ok(evt) = rx.try_recv() {
         if !app.state.flags.stream.phase.is_streaming() {
             continue;
         }
         app.state.flags.ui.dirty = true;
+        app.state.flags.stream.last_delta_ms = now;
+        let (done, failed) = (matches!(evt, StreamEvent::Done { .. }), matches!(evt, StreamEvent::Error(_)));
         apply_stream_event(app, evt);
+        let mark = match (failed, done) {
+            (true, _) => Mark::Failed,
+            (false, true) => Mark::Done { calls_tools: !app.pending_tools.is_empty() },
+            (false, false) => Mark::Event,
+        };
+        latency::on_stream_event(&mut app.state, mark);
     }
 }
 
@@ -131,6 +156,7 @@ fn handle_stream_error_event(app: &mut App, e: String) {
         spine.config.consecutive_continuation_errors = spine.config.consecutive_continuation_errors.saturating_add(1);
         spine.config.last_continuation_error_ms = Some(crate::app::panels::now_ms());
         let _action = apply_action(&mut app.state, Action::StreamError(e));
+        cp_mod_spine::cues::raise(&mut app.state, cp_mod_spine::cues::CueEvent::Error);
     }
 }
 
@@ -151,6 +177,8 @@ pub(super) fn handle_retry(app: &mut App, tx: &Sender<StreamEvent>) {
             app.pending_done = None;
             let params = build_stream_params(&app.state, ctx, Some(system_prompt));
             start_streaming(params, tx.clone());
+            latency::request_started(&mut app.state);
+            app.state.flags.stream.last_delta_ms = crate::app::panels::now_ms();
             app.state.flags.ui.dirty = true;
         }
     }
@@ -180,6 +208,55 @@ pub(super) fn process_api_check_results(app: &mut App) {
     }
 }
 
+/// Connectivity monitor: apply the result of the in-flight probe, then start
+/// the next one when due (more often while offline, to dispatch queued messages
+/// soon after the connection returns).
+pub(super) fn check_connectivity(app: &mut App, current_ms: u64) {
+    if let Some(rx) = app.connectivity_rx.as_ref() {
+        let result = rx.try_recv();
+        if matches!(result, Err(TryRecvError::Empty)) {
+            return;
+        }
+        app.connectivity_rx = None;
+        if let Ok(reachable) = result {
+            set_offline(&mut app.state, !reachable, current_ms);
+        }
+    }
+    let offline = app.state.flags.lifecycle.offline_since_ms.is_some();
+    if !probe_due(offline, app.last_connectivity_probe_ms, current_ms) {
+        return;
+    }
+    app.last_connectivity_probe_ms = current_ms;
+    let host = app.state.llm_provider.api_host();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let _r = std::thread::spawn(move || {
+        let _r = tx.send(probe_host(host));
+    });
+    app.connectivity_rx = Some(rx);
+}
+
+/// Whether the next probe is due, `last_ms` being when the previous one started.
+const fn probe_due(offline: bool, last_ms: u64, current_ms: u64) -> bool {
+    let interval = if offline { OFFLINE_PROBE_INTERVAL_MS } else { PROBE_INTERVAL_MS };
+    current_ms.saturating_sub(last_ms) >= interval
+}
+
+/// Whether a TCP connection to `host:443` can be opened (DNS included).
+fn probe_host(host: &str) -> bool {
+    (host, 443u16)
+        .to_socket_addrs()
+        .is_ok_and(|mut addrs| addrs.any(|addr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok()))
+}
+
+/// Record an online/offline transition (no-op when unchanged).
+fn set_offline(state: &mut State, offline: bool, current_ms: u64) {
+    if offline == state.flags.lifecycle.offline_since_ms.is_some() {
+        return;
+    }
+    state.flags.lifecycle.offline_since_ms = offline.then_some(current_ms);
+    state.flags.ui.dirty = true;
+}
+
 /// Continue streaming after tool execution (called when panels are ready).
 pub(super) fn continue_streaming(app: &mut App, tx: &Sender<StreamEvent>) {
     app.state.flags.stream.phase.transition(StreamPhase::Receiving);
@@ -189,6 +266,7 @@ pub(super) fn continue_streaming(app: &mut App, tx: &Sender<StreamEvent>) {
     app.pending_done = None;
     let params = build_stream_params(&app.state, ctx, Some(system_prompt));
     start_streaming(params, tx.clone());
+    latency::request_started(&mut app.state);
 }
 
 /// Finalize a completed stream: apply `StreamDone`, reset counters, and unblock spine.
@@ -268,6 +346,10 @@ fn apply_stream_done(app: &mut App, done: StreamDonePayload) {
     // Unblock any guard-rail-blocked notifications — they get another chance now
     // that a stream has completed successfully.
     cp_mod_spine::types::SpineState::unblock_all(&mut app.state);
+    cp_mod_spine::cues::raise(&mut app.state, cp_mod_spine::cues::CueEvent::StreamDone);
+    for module in crate::modules::all_modules() {
+        module.on_stream_done(&mut app.state);
+    }
 
     record_stream_breakpoints(app, BreakpointRecord { bp_hashes, bp_panel_ids, alive_count, alive_positions_permille });
 }
@@ -330,17 +412,40 @@ pub(super) fn has_dirty_file_panels(state: &State) -> bool {
 
 /// Trigger immediate cache refresh for all dirty async-wait panels.
 /// Returns true if any panels needed refresh.
-pub(super) fn trigger_dirty_panel_refresh(state: &State, cache_tx: &Sender<CacheUpdate>) -> bool {
+pub(super) fn trigger_dirty_panel_refresh(state: &State) -> bool {
     let mut any_triggered = false;
     for ctx in &state.context {
         let needs_wait = get_context_type_meta(ctx.context_type.as_str()).is_some_and(|m| m.needs_async_wait);
         if needs_wait && ctx.cache_deprecated && !ctx.cache_in_flight {
             let panel = crate::app::panels::get_panel(&ctx.context_type);
             if let Some(request) = panel.build_cache_request(ctx, state) {
-                process_cache_request(request, cache_tx.clone());
+                process_cache_request(request);
                 any_triggered = true;
             }
         }
     }
     any_triggered
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probes_run_more_often_while_offline() {
+        assert!(!probe_due(false, 10_000, 10_000 + PROBE_INTERVAL_MS - 1));
+        assert!(probe_due(false, 10_000, 10_000 + PROBE_INTERVAL_MS));
+        assert!(probe_due(true, 10_000, 10_000 + OFFLINE_PROBE_INTERVAL_MS));
+        assert!(!probe_due(true, 10_000, 10_000 + OFFLINE_PROBE_INTERVAL_MS - 1));
+    }
+
+    #[test]
+    fn going_offline_keeps_the_first_timestamp_until_back_online() {
+        let mut state = State::default();
+        set_offline(&mut state, true, 1_000);
+        set_offline(&mut state, true, 4_000);
+        assert_eq!(state.flags.lifecycle.offline_since_ms, Some(1_000));
+        set_offline(&mut state, false, 7_000);
+        assert_eq!(state.flags.lifecycle.offline_since_ms, None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Control API intake — answers the local HTTP server's calls on the main loop.
+//!
+//! The sibling of the bridge intake: `cp_control` authenticates and parses on
+//! its own threads, and [`poll_control_requests`] applies each call here, so
+//! a remote message enters exactly as local input would (the same submit path
+//! `--attach-stdin` uses) without disturbing the user's draft.
+
+use cp_base::state::runtime::State;
+use cp_control::Control;
+use cp_control::request::{Reply, Request};
+use serde_json::{Value, json};
+
+use crate::app::App;
+use crate::app::actions::ActionResult;
+
+/// Upper bound on calls answered per tick.
+const DRAIN_BUDGET: u32 = 16;
+
+/// Answer every call the control server has waiting. A no-op without
+/// `--control`.
+pub(in crate::app::run) fn poll_control_requests(app: &mut App) {
+    let mut budget = DRAIN_BUDGET;
+    while budget > 0 {
+        budget = budget.saturating_sub(1);
+        let Some(call) = app.control.as_ref().and_then(Control::poll) else { break };
+        let reply = answer(app, call.request().clone());
+        call.respond(reply);
+    }
+}
+
+/// Apply one request.
+fn answer(app: &mut App, request: Request) -> Reply {
+    match request {
+        Request::SendMessage { content } => submit(app, &content),
+        Request::Transcript { limit } => Reply::ok(transcript(&app.state, limit)),
+        Request::Panels => Reply::ok(panels(&app.state)),
+        // Recorded for the git edit guard, then told to the agent.
+        Request::Approve { path } => {
+            let _recorded = cp_mod_git::approve_edit(&mut app.state, &path);
+            submit(app, &format!("Approved: go ahead and change `{path}`."))
+        }
+    }
+}
+
+/// Submit `content` as if typed, keeping the draft in the input box.
+fn submit(app: &mut App, content: &str) -> Reply {
+    let state = &mut app.state;
+    let draft = std::mem::replace(&mut state.input, content.to_owned());
+    let cursor = std::mem::replace(&mut state.input_cursor, content.len());
+    let anchor = state.input_selection_anchor.take();
+    let buffers = std::mem::take(&mut state.paste_buffers);
+    let labels = std::mem::take(&mut state.paste_buffer_labels);
+    let result = crate::app::actions::input::handle_input_submit(state);
+    state.input = draft;
+    state.input_cursor = cursor;
+    state.input_selection_anchor = anchor;
+    state.paste_buffers = buffers;
+    state.paste_buffer_labels = labels;
+    state.flags.ui.dirty = true;
+    if matches!(result, ActionResult::Save) {
+        app.save_state_async();
+    }
+    log::info!("control: submitted a {}-byte message", content.len());
+    Reply::ok(json!({ "submitted": true, "streaming": app.state.flags.stream.phase.is_streaming() }))
+}
+
+/// The last `limit` messages, oldest first.
+fn transcript(state: &State, limit: usize) -> Value {
+    let messages: Vec<Value> = state
+        .messages
+        .iter()
+        .skip(state.messages.len().saturating_sub(limit))
+        .map(|m| {
+            json!({
+                "id": m.id,
+                "role": m.role,
+                "status": m.status,
+                "content": m.content,
+                "tools": m.tool_uses.iter()
+                    .map(|t| json!({ "id": t.id, "name": t.name, "input": t.input }))
+                    .collect::<Vec<_>>(),
+                "results": m.tool_results.iter()
+                    .map(|r| json!({ "tool_use_id": r.tool_use_id, "content": r.content, "is_error": r.is_error }))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    json!({ "messages": messages, "streaming": state.flags.stream.phase.is_streaming() })
+}
+
+/// The context panels, in sidebar order.
+fn panels(state: &State) -> Value {
+    let panels: Vec<Value> = state
+        .context
+        .iter()
+        .map(|c| json!({ "id": c.id, "name": c.name, "type": c.context_type.as_str(), "tokens": c.token_count }))
+        .collect();
+    json!({ "panels": panels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cp_base::state::data::message::Message;
+
+    #[test]
+    fn transcript_keeps_the_last_messages_in_order() {
+        let mut state = State::default();
+        for n in 1..=3u32 {
+            state.messages.push(Message::new_user(format!("U{n}"), format!("UID_{n}_U"), format!("m{n}"), 1));
+        }
+        let body = transcript(&state, 2);
+        let messages = body.get("messages").and_then(Value::as_array).cloned().unwrap_or_default();
+        let fields = |key: &str| -> Vec<String> {
+            messages.iter().filter_map(|m| m.get(key).and_then(Value::as_str)).map(str::to_owned).collect()
+        };
+        assert_eq!(fields("id"), ["U2", "U3"]);
+        assert_eq!(fields("content"), ["m2", "m3"]);
+    }
+}
@@ -4,17 +4,25 @@
 //! Contains auto-`Read` injection and `MY_TURN` thread detection here; the
 //! bridge command intake/application + live-vitals emission live in the
 //! sibling [`bridge`] submodule (the two halves split a single file that had
-//! outgrown the 500-line limit).
+//! outgrown the 500-line limit). [`control`] answers the `--control` HTTP
+//! API the same way, [`pair`] merges `--pair` guests' keys into input, and
+//! [`viewer`] renders the `--serve` web page.
 
 mod archived;
 mod bridge;
 mod commands;
+mod control;
 mod messages;
+mod pair;
 mod paused;
+mod viewer;
 pub(super) use archived::emit_thread_archived;
 pub(super) use bridge::{bridge_active, emit_thread_focus, emit_thread_status, emit_vitals, poll_bridge_commands};
+pub(super) use control::poll_control_requests;
 pub(super) use messages::emit_messages;
+pub(super) use pair::next_input_event;
 pub(super) use paused::emit_thread_paused;
+pub(super) use viewer::publish_viewer;
 
 use crate::app::App;
 use crate::app::PendingDone;
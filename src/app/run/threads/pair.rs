@@ -0,0 +1,54 @@
+//! Pairing intake — merges guest keys into the main loop's input.
+//!
+//! `cp_pair` reads guests on its own threads; [`next_input_event`] picks their
+//! keys up here, so a guest holding the turn types through exactly the path
+//! the local keyboard uses. While a guest has the turn the host's keys are
+//! dropped, except Ctrl+G, which takes the turn back.
+
+use std::io;
+use std::time::Duration;
+
+use crossterm::event::{self, Event};
+
+use crate::app::App;
+use crate::app::events::normalize_event;
+
+/// The next input event: a key from the guest holding the turn, else one from
+/// the terminal. Also refreshes the pairing banner and re-renders for a guest
+/// that just joined. Without `--pair`, just the terminal.
+pub(in crate::app::run) fn next_input_event(app: &mut App) -> io::Result<Option<Event>> {
+    if let Some(host) = app.pair.as_mut() {
+        let guest_key = host.poll();
+        if host.take_frame_request() {
+            app.state.flags.ui.dirty = true;
+        }
+        sync_status(app);
+        if let Some(key) = guest_key {
+            return Ok(normalize_event(Event::Key(key)));
+        }
+    }
+    if !event::poll(Duration::ZERO)? {
+        return Ok(None);
+    }
+    let evt = event::read()?;
+    if let (Some(host), &Event::Key(key)) = (app.pair.as_mut(), &evt)
+        && host.guest_has_turn()
+    {
+        if cp_pair::is_turn_key(&key) {
+            host.take_turn();
+            sync_status(app);
+        }
+        return Ok(None);
+    }
+    Ok(normalize_event(evt))
+}
+
+/// Mirror the host's pairing status into state for the banner; redraw when
+/// it changed.
+fn sync_status(app: &mut App) {
+    let Some(status) = app.pair.as_ref().map(cp_pair::Host::status) else { return };
+    if app.state.get_ext::<cp_pair::Status>() != Some(&status) {
+        app.state.set_ext(status);
+        app.state.flags.ui.dirty = true;
+    }
+}
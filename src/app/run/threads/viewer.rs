@@ -0,0 +1,113 @@
+//! Web viewer pages — renders the session for `--serve` on the main loop.
+//!
+//! `cp_control::viewer` serves whatever was published last; every couple of
+//! seconds [`publish_viewer`] renders the status line, the latest messages and
+//! the context panels into it. Long contents are cut: this is for keeping an
+//! eye on a run, not for reading whole files.
+
+use std::fmt::Write as _;
+
+use cp_base::cast::float_math;
+use cp_control::viewer::escape;
+
+use crate::app::App;
+use crate::state::{Message, MsgKind, State};
+
+/// Messages shown, counted from the end.
+const MESSAGES_SHOWN: usize = 60;
+/// Longest tool result shown (bytes).
+const RESULT_BYTES: usize = 2_000;
+/// Longest panel content shown (bytes).
+const PANEL_BYTES: usize = 20_000;
+
+/// Render and publish the page when due. A no-op without `--serve`.
+pub(in crate::app::run) fn publish_viewer(app: &mut App, now_ms: u64) {
+    let Some(viewer) = app.viewer.as_mut() else { return };
+    if viewer.due(now_ms) {
+        viewer.publish(&title(), &body(&app.state));
+    }
+}
+
+/// Page title: the project folder.
+fn title() -> String {
+    let folder = std::env::current_dir().ok().and_then(|d| d.file_name().map(|n| n.to_string_lossy().into_owned()));
+    format!("Context Pilot \u{b7} {}", folder.unwrap_or_default())
+}
+
+/// `text` cut to at most `max` bytes, on a character boundary.
+fn cut(text: &str, max: usize) -> String {
+    let end = (0..=max.min(text.len())).rev().find(|&i| text.is_char_boundary(i)).unwrap_or(0);
+    if end < text.len() { format!("{}\n\u{2026}", text.get(..end).unwrap_or("")) } else { text.to_owned() }
+}
+
+/// One message: text under its author, tool calls and results as small lines.
+fn message(out: &mut String, msg: &Message) {
+    if msg.msg_type == MsgKind::TextMessage && !msg.content.is_empty() {
+        let _r = write!(
+            out,
+            "<h3 class=\"{role}\">{id} {role}</h3><pre>{text}</pre>",
+            role = escape(&msg.role),
+            id = escape(&msg.id),
+            text = escape(&msg.content)
+        );
+    }
+    for tool in &msg.tool_uses {
+        let input = cut(&tool.input.to_string(), RESULT_BYTES);
+        let _r =
+            write!(out, "<div class=\"tool\">\u{2192} {} <code>{}</code></div>", escape(&tool.name), escape(&input));
+    }
+    for result in &msg.tool_results {
+        let class = if result.is_error { "tool error" } else { "tool" };
+        let _r = write!(
+            out,
+            "<details class=\"{class}\" id=\"r-{id}\"><summary>\u{2190} {name} ({lines} lines)</summary><pre>{text}</pre></details>",
+            id = escape(&result.tool_use_id),
+            name = escape(&result.tool_name),
+            lines = result.content.lines().count(),
+            text = escape(&cut(&result.content, RESULT_BYTES))
+        );
+    }
+}
+
+/// Status line, conversation tail, then the panels.
+fn body(state: &State) -> String {
+    let mut out = String::new();
+    let phase = if state.flags.stream.phase.is_streaming() { "working" } else { "idle" };
+    let cost = float_math::sum3(state.cost_hit_usd, state.cost_miss_usd, state.cost_output_usd);
+    let _r = write!(
+        out,
+        "<p>{phase} \u{b7} {} messages \u{b7} ${cost:.2} \u{b7} updated {}</p><h2>Conversation</h2>",
+        state.messages.len(),
+        cp_mod_utilities::time::now_utc_rfc3339_secs()
+    );
+    for msg in state.messages.iter().skip(state.messages.len().saturating_sub(MESSAGES_SHOWN)) {
+        message(&mut out, msg);
+    }
+    out.push_str("<h2>Panels</h2>");
+    for ctx in &state.context {
+        let content = ctx.cached_content.as_deref().unwrap_or("");
+        let _p = write!(
+            out,
+            "<details id=\"p-{id}\"><summary>{id} {name} ({tokens} tokens)</summary><pre>{text}</pre></details>",
+            id = escape(&ctx.id),
+            name = escape(&ctx.name),
+            tokens = ctx.token_count,
+            text = escape(&cut(content, PANEL_BYTES))
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_escaped_and_results_cut() {
+        let mut state = State::default();
+        state.messages.push(Message::new_user("U1".to_owned(), "UID_1_U".to_owned(), "<script>".to_owned(), 1));
+        let html = body(&state);
+        assert!(html.contains("&lt;script&gt;") && !html.contains("<script>"));
+        assert_eq!(cut("h\u{e9}llo", 2), "h\n\u{2026}");
+    }
+}
@@ -1,15 +1,19 @@
-//! Post-tool-execution checks: panel readiness and deferred sleeps.
+//! Post-tool-execution checks: panel readiness, deferred sleeps, the tool-turn
+//! check-in, the repeated-tool-call loop guard, and stale-read warnings.
 //!
 //! Extracted from `tool_pipeline.rs` to keep that module under the 500-line limit.
-//! Both functions are non-blocking polls called from the main event loop.
+//! The readiness and sleep checks are non-blocking polls called from the main
+//! event loop; the check-in and loop guard run inside the tool pipeline.
 
 use std::sync::mpsc::Sender;
 
 use crate::app::panels::now_ms;
 use crate::app::run::streaming::has_dirty_panels;
 use crate::infra::api::StreamEvent;
+use crate::infra::tools::{ToolResult, ToolUse};
 
 use crate::app::App;
+use crate::state::State;
 
 /// Non-blocking check: if we're waiting for file panels to load,
 /// check if they're ready (or timed out) and continue streaming.
@@ -47,3 +51,136 @@ pub(crate) fn check_deferred_sleep(app: &mut App, tx: &Sender<StreamEvent>) {
     // Deferred sleep expired — continue streaming
     crate::app::run::streaming::continue_streaming(app, tx);
 }
+
+/// Start of a tool result refused by the loop guard.
+const LOOP_REFUSAL: &str = "Not executed: loop detected";
+
+/// Refuse `tool` when a check-in with the user is due, or when it completes a
+/// run of identical calls long enough to be a loop (its tool-call message is
+/// already in the conversation).
+pub(crate) fn refuse_guarded_call(state: &State, tool: &ToolUse) -> Option<ToolResult> {
+    if cp_mod_spine::engine::check_in_due(state) {
+        let content = "Not executed: check-in required \u{2014} stop calling tools and post a progress summary.";
+        return Some(ToolResult::new(tool.id.clone(), content.to_owned(), true));
+    }
+    let count = cp_mod_spine::engine::tool_loop_streak(state)?;
+    let content =
+        format!("{LOOP_REFUSAL} \u{2014} `{}` called {count} times in a row with identical input.", tool.name);
+    Some(ToolResult::new(tool.id.clone(), content, true))
+}
+
+/// After a batch with a loop-guard refusal, post the loop notice and report
+/// `true` so the pipeline ends the turn instead of streaming on.
+pub(crate) fn interrupt_tool_loop(state: &mut State, tools: &[ToolUse], results: &[ToolResult]) -> bool {
+    let Some(tool) = tools.iter().zip(results).find(|pair| pair.1.is_error && pair.1.content.starts_with(LOOP_REFUSAL))
+    else {
+        return false;
+    };
+    let count = cp_mod_spine::engine::identical_tool_streak(state);
+    cp_mod_spine::engine::notify_tool_loop(state, &tool.0.name, count);
+    true
+}
+
+/// Warn the agent, at the end of the last tool result, about dynamic panels
+/// whose content changed since the version it saw while the freeze kept the
+/// older snapshot in the prompt. Each staleness is reported once.
+pub(crate) fn append_stale_read_note(state: &mut State, results: &mut [ToolResult]) {
+    let Some(last) = results.last_mut() else { return };
+    let mut lines: Vec<String> = Vec::new();
+    for ctx in state.context.iter_mut().filter(|c| !c.context_type.is_fixed()) {
+        if ctx.emitted.stale && !ctx.emitted.stale_noted {
+            ctx.emitted.stale_noted = true;
+            lines.push(format!("{} ({}, you saw v{})", ctx.id, ctx.name, ctx.emitted.version));
+        }
+    }
+    if lines.is_empty() {
+        return;
+    }
+    let note = cp_base::config::INJECTIONS.spine.stale_read.trim().replace("{panels}", &lines.join(", "));
+    last.content = format!("{}\n\n{note}", last.content);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::context::make_default_entry;
+    use cp_base::state::data::message::{Message, ToolUseRecord};
+    use cp_mod_spine::types::SpineState;
+    use serde_json::json;
+
+    use crate::state::Kind;
+
+    /// A state whose conversation holds `prior` saved calls to `grep` with
+    /// `{"pattern": "x"}`, each followed by its result.
+    fn after_calls(prior: usize) -> State {
+        let mut state = State::default();
+        state.set_ext(SpineState::new());
+        state.messages.push(Message::new_user("U1".to_owned(), "UID_1_U".to_owned(), "go".to_owned(), 1));
+        for n in 0..prior {
+            save_call(&mut state, &grep("x", n));
+            state.messages.push(Message::new_tool_result(format!("R{n}"), None, vec![]));
+        }
+        state
+    }
+
+    /// A `grep` call for `pattern`.
+    fn grep(pattern: &str, n: usize) -> ToolUse {
+        ToolUse { id: format!("toolu_{n}"), name: "grep".to_owned(), input: json!({"pattern": pattern}) }
+    }
+
+    /// Append the call's tool-call message, as `save_tool_call_message` does
+    /// before the guard runs.
+    fn save_call(state: &mut State, tool: &ToolUse) {
+        let record = ToolUseRecord::new(tool.id.clone(), tool.name.clone(), tool.input.clone());
+        state.messages.push(Message::new_tool_call(format!("T{}", tool.id), None, vec![record]));
+    }
+
+    #[test]
+    fn the_call_completing_a_streak_is_refused_once_saved() {
+        let mut state = after_calls(2);
+        let third = grep("x", 2);
+        assert!(refuse_guarded_call(&state, &third).is_none(), "the guard counts only saved calls");
+        save_call(&mut state, &third);
+        let refusal = refuse_guarded_call(&state, &third);
+        assert!(refusal.is_some_and(|r| r.is_error && r.content.starts_with(LOOP_REFUSAL)));
+    }
+
+    #[test]
+    fn a_call_with_different_input_is_not_refused() {
+        let mut state = after_calls(2);
+        let other = grep("y", 2);
+        save_call(&mut state, &other);
+        assert!(refuse_guarded_call(&state, &other).is_none());
+    }
+
+    #[test]
+    fn only_a_loop_refusal_interrupts_the_batch() {
+        let mut state = after_calls(3);
+        let tools = vec![grep("x", 3)];
+        let failed = vec![ToolResult::new("toolu_3".to_owned(), "Error: no match".to_owned(), true)];
+        assert!(!interrupt_tool_loop(&mut state, &tools, &failed));
+        assert!(SpineState::get(&state).notifications.is_empty());
+
+        let refused = refuse_guarded_call(&state, &grep("x", 3)).into_iter().collect::<Vec<_>>();
+        assert!(interrupt_tool_loop(&mut state, &tools, &refused));
+        assert_eq!(SpineState::get(&state).notifications.len(), 1);
+    }
+
+    #[test]
+    fn stale_panels_are_reported_once() {
+        crate::modules::init_registry();
+        let mut state = State::default();
+        let mut entry = make_default_entry("P12", Kind::new(Kind::GREP), "grep TODO", false);
+        entry.emitted.version = 3;
+        entry.emitted.stale = true;
+        state.context.push(entry);
+        let mut results = vec![ToolResult::new("t1".to_owned(), "ok".to_owned(), false)];
+
+        append_stale_read_note(&mut state, &mut results);
+        assert!(results.first().is_some_and(|r| r.content.contains("P12 (grep TODO, you saw v3)")));
+
+        let mut later = vec![ToolResult::new("t2".to_owned(), "ok".to_owned(), false)];
+        append_stale_read_note(&mut state, &mut later);
+        assert!(later.first().is_some_and(|r| r.content == "ok"));
+    }
+}
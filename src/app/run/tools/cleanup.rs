@@ -2,6 +2,7 @@ use std::sync::mpsc::Sender;
 
 use crate::app::panels::now_ms;
 use crate::infra::api::StreamEvent;
+use crate::modules::conversation_history::tldr_guard::guard_tldr;
 use crate::state::{Message, ToolResultRecord};
 
 use cp_base::state::watchers::{ASYNC_ERROR_PREFIX, WatcherRegistry};
@@ -305,6 +306,9 @@ pub(crate) fn check_watchers(app: &mut App, tx: &Sender<StreamEvent>) {
         return;
     }
 
+    // Fold compiler errors repeated across the now-complete results.
+    cp_base::tools::clusters::collapse(&mut tool_results);
+
     // Break tempo, build result + assistant messages, resume streaming.
     resume_pipeline_after_blocking(app, tx, &tool_results, &merged_blocking);
 }
@@ -338,7 +342,7 @@ fn resume_pipeline_after_blocking(
         .map(|r| {
             ToolResultRecord::new(r.tool_use_id.clone(), r.content.clone(), r.is_error)
                 .display(r.display.clone())
-                .tldr(r.tldr.clone())
+                .tldr(guard_tldr(&app.state, &r.content, r.tldr.clone()))
                 .tool_name(r.tool_name.clone())
         })
         .collect();
@@ -370,7 +374,7 @@ fn resume_pipeline_after_blocking(
     app.save_state_async();
     app.state.flags.ui.dirty = true;
 
-    let _r = crate::app::run::streaming::trigger_dirty_panel_refresh(&app.state, &app.cache_tx);
+    let _r = crate::app::run::streaming::trigger_dirty_panel_refresh(&app.state);
     if crate::app::run::streaming::has_dirty_file_panels(&app.state) {
         app.state.flags.lifecycle.waiting_for_panels = true;
         app.wait_started_ms = now_ms();
@@ -4,6 +4,7 @@ use crate::app::actions::clean_llm_id_prefix;
 use crate::app::panels::now_ms;
 use crate::infra::api::StreamEvent;
 use crate::infra::tools::execute_tool;
+use crate::modules::conversation_history::tldr_guard::guard_tldr;
 use crate::modules::pre_flight::pre_flight_tool;
 use crate::state::persistence::build_message_op;
 use crate::state::{Message, StreamPhase, ToolResultRecord, ToolUseRecord};
@@ -74,10 +75,11 @@ fn save_tool_call_message(app: &mut App, tool: &cp_base::tools::ToolUse) {
     app.state.next_tool_id = app.state.next_tool_id.saturating_add(1);
     app.state.global_next_uid = app.state.global_next_uid.saturating_add(1);
 
+    let input = crate::modules::transcript_input(tool, &app.state.active_modules);
     let tool_msg = Message::new_tool_call(
         tool_id,
         Some(tool_global_uid),
-        vec![ToolUseRecord::new(tool.id.clone(), tool.name.clone(), tool.input.clone())],
+        vec![ToolUseRecord::new(tool.id.clone(), tool.name.clone(), input)],
     );
     app.save_message_async(&tool_msg);
     app.state.messages.push(tool_msg);
@@ -85,13 +87,18 @@ fn save_tool_call_message(app: &mut App, tool: &cp_base::tools::ToolUse) {
 
 /// Execute one tool through the pre-flight → queue-intercept → execute pipeline.
 ///
-/// Handles the four dispatch classes (queue control, trap, queue-intercept,
-/// normal execute) and appends any queue-flushed tools onto `flushed_tools`.
+/// Refuses calls held by the check-in or loop guard, then handles the four dispatch
+/// classes (queue control, trap, queue-intercept, normal execute) and
+/// appends any queue-flushed tools onto `flushed_tools`.
 fn execute_one_tool(
     app: &mut App,
     tool: &cp_base::tools::ToolUse,
     flushed_tools: &mut Vec<super::queue_flush::FlushedTool>,
 ) -> crate::infra::tools::ToolResult {
+    crate::modules::overview::panel_gc::note_tool_use(&mut app.state, tool);
+    if let Some(refusal) = super::checks::refuse_guarded_call(&app.state, tool) {
+        return refusal;
+    }
     if tool.name == "Queue_execute" || tool.name == "Queue_pause" {
         return execute_queue_control(app, tool, flushed_tools);
     }
@@ -99,7 +106,11 @@ fn execute_one_tool(
     // Pre-flight: schema check + module semantic check (ALWAYS runs, queue or not)
     let pf = pre_flight_tool(tool, &app.state, &app.state.active_modules.clone());
     if pf.has_errors() {
-        // Hard stop — don't queue, don't execute
+        // Hard stop — don't queue, don't execute; a guarded edit waits for the user
+        cp_mod_git::request_approval(&mut app.state, tool, &pf);
+        if pf.awaiting_approval.is_some() {
+            cp_mod_spine::cues::raise(&mut app.state, cp_mod_spine::cues::CueEvent::ApprovalNeeded);
+        }
         return crate::infra::tools::ToolResult::new(tool.id.clone(), pf.format_errors(), true);
     }
 
@@ -247,7 +258,12 @@ fn maybe_trigger_reverie(app: &mut App, tool_results: &[crate::infra::tools::Too
             let agent_id = lines.next().unwrap_or("cleaner").to_owned();
             let context_line = lines.next().unwrap_or("");
             let context = if context_line.is_empty() { None } else { Some(context_line.to_owned()) };
-            let _r = crate::app::reverie::trigger::start_manual_reverie(&mut app.state, agent_id, context);
+            let _r = crate::app::reverie::trigger::start_manual_reverie(
+                &mut app.state,
+                agent_id,
+                context,
+                crate::modules::cleaner::scope::CleanScope::Everything,
+            );
             break;
         }
     }
@@ -352,6 +368,8 @@ pub(crate) fn handle_tool_execution(app: &mut App, tx: &Sender<StreamEvent>) {
     maybe_trigger_reverie(app, &tool_results);
     super::callbacks::fire_edit_callbacks(app, &tools, &mut tool_results);
     apply_tempo_break(app, &tool_results);
+    super::checks::append_stale_read_note(&mut app.state, &mut tool_results);
+    cp_base::tools::clusters::collapse(&mut tool_results);
 
     // Check if any tool triggered a console blocking wait
     let has_console_wait = tool_results.iter().any(|r| r.content.starts_with(CONSOLE_WAIT_BLOCKING_SENTINEL));
@@ -400,7 +418,7 @@ fn finalize_tool_cycle(app: &mut App, cycle: &ToolCycle<'_>) {
         .map(|(r, t)| {
             ToolResultRecord::new(r.tool_use_id.clone(), r.content.clone(), r.is_error)
                 .display(r.display.clone())
-                .tldr(r.tldr.clone())
+                .tldr(guard_tldr(&app.state, &r.content, r.tldr.clone()))
                 .tool_name(t.name.clone())
         })
         .collect();
@@ -408,8 +426,13 @@ fn finalize_tool_cycle(app: &mut App, cycle: &ToolCycle<'_>) {
     app.save_message_async(&result_msg);
     app.state.messages.push(result_msg);
 
-    // Check if reload was requested — main loop will handle flag + exit
-    if app.state.flags.lifecycle.reload_pending {
+    // Reload requested (main loop handles flag + exit), check-in ignored, or
+    // loop detected (the spine delivers the notice once the stream ends): stop here.
+    let check_in_ignored = cp_mod_spine::engine::record_tool_turn(&mut app.state);
+    if app.state.flags.lifecycle.reload_pending
+        || check_in_ignored
+        || super::checks::interrupt_tool_loop(&mut app.state, tools, tool_results)
+    {
         crate::infra::profiler::log_tool_time(tool_names, pipeline_start.elapsed());
         return;
     }
@@ -436,7 +459,7 @@ fn finalize_tool_cycle(app: &mut App, cycle: &ToolCycle<'_>) {
     }
 
     // Trigger background cache refresh for dirty file panels (non-blocking)
-    let _r = trigger_dirty_panel_refresh(&app.state, &app.cache_tx);
+    let _r = trigger_dirty_panel_refresh(&app.state);
 
     // Check if we need to wait for panels before continuing stream
     if has_dirty_file_panels(&app.state) {
@@ -1,9 +1,14 @@
-use std::sync::mpsc::Receiver;
+use std::path::Path;
+
+use cp_base::panels::WatchSpec;
+use cp_base::state::runtime::bus;
+use cp_base::tools::jail::Jail;
 
 use crate::app::panels::now_ms;
 use crate::infra::watcher::WatchEvent;
-use crate::state::State;
 use crate::state::cache::{CacheRequest, CacheUpdate, process_cache_request};
+use crate::state::{Entry, Kind, State, Unloaded};
+use cp_mod_spine::types::{NotificationType, SpineState};
 
 use crate::app::App;
 
@@ -16,26 +21,46 @@ pub(super) fn setup_file_watchers(app: &mut App) {
     sync_file_watchers(app);
 }
 
+/// Panel kinds whose seeding waits until they are first viewed or sent: a
+/// directory walk, `git` commands and filesystem scans, which can take seconds
+/// in a large repository.
+const DEFERRED_KINDS: [&str; 5] = [Kind::TREE, Kind::GIT_ACTIVITY, Kind::GLOB, Kind::GREP, Kind::GIT_RESULT];
+
+/// Whether seeding `ctx` at startup waits for it to be viewed or sent.
+fn defers_seeding(ctx: &Entry, selected: bool) -> bool {
+    !selected && ctx.cached_content.is_none() && DEFERRED_KINDS.contains(&ctx.context_type.as_str())
+}
+
 /// Schedule initial cache refreshes for fixed context elements only.
-/// Dynamic panels (File, Glob, Grep, Tmux, `GitResult`, `GithubResult`) will be
-/// populated gradually by `check_timer_based_deprecation` via its `needs_initial`
-/// path, staggered by the `cache_in_flight` guard — preventing a massive burst
-/// of concurrent background threads on startup when many panels are persisted.
+/// Dynamic panels (File, Tmux, `GithubResult`) will be populated gradually by
+/// `check_timer_based_deprecation` via its `needs_initial` path, staggered by
+/// the `cache_in_flight` guard — preventing a massive burst of concurrent
+/// background threads on startup when many panels are persisted.
+///
+/// The expensive kinds (`DEFERRED_KINDS`) are not seeded at all: they are
+/// marked `Unloaded::Deferred`, and load once selected, or with every other
+/// unloaded panel — in parallel — before the first request.
 pub(super) fn schedule_initial_cache_refreshes(app: &mut App) {
+    let selected = app.state.selected_context;
+    for (i, ctx) in app.state.context.iter_mut().enumerate() {
+        if defers_seeding(ctx, i == selected) {
+            ctx.cache_unloaded = Some(Unloaded::Deferred);
+        }
+    }
     // Collect requests first (immutable borrow), then mark in-flight (mutable borrow).
     let requests: Vec<(usize, CacheRequest)> = app
         .state
         .context
         .iter()
         .enumerate()
-        .filter(|entry| entry.1.context_type.is_fixed())
+        .filter(|entry| entry.1.context_type.is_fixed() && !entry.1.awaits_load())
         .filter_map(|(i, ctx)| {
             let panel = crate::app::panels::get_panel(&ctx.context_type);
             panel.build_cache_request(ctx, &app.state).map(|req| (i, req))
         })
         .collect();
     for (i, request) in requests {
-        process_cache_request(request, app.cache_tx.clone());
+        process_cache_request(request);
         if let Some(ctx) = app.state.context.get_mut(i) {
             ctx.cache_in_flight = true;
         }
@@ -43,8 +68,8 @@ pub(super) fn schedule_initial_cache_refreshes(app: &mut App) {
 }
 
 /// Process incoming cache updates from background threads
-pub(super) fn process_cache_updates(app: &mut App, cache_rx: &Receiver<CacheUpdate>) {
-    process_cache_updates_static(&mut app.state, cache_rx);
+pub(super) fn process_cache_updates(app: &mut App) {
+    process_cache_updates_static(&mut app.state);
 }
 
 /// Apply an `Unchanged` cache update: clear the in-flight + deprecated flags.
@@ -81,15 +106,16 @@ fn apply_content_update(state: &mut State, update: CacheUpdate) {
     // apply_cache_update calls update_if_changed which sets last_refresh_ms on change
     let _changed = panel.apply_cache_update(update, &mut ctx, state);
     ctx.cache_in_flight = false;
+    ctx.cache_unloaded = None;
     state.context.insert(idx, ctx);
     state.flags.ui.dirty = true;
 }
 
 /// Static version of `process_cache_updates` for use in wait module
-fn process_cache_updates_static(state: &mut State, cache_rx: &Receiver<CacheUpdate>) {
+fn process_cache_updates_static(state: &mut State) {
     let _guard = crate::profile!("app::cache_updates");
     let _fg = cp_base::flame!("cache_updates");
-    while let Ok(update) = cache_rx.try_recv() {
+    for update in bus::drain::<CacheUpdate>() {
         if apply_unchanged_update(state, &update) {
             continue;
         }
@@ -131,6 +157,7 @@ fn collect_invalidations(app: &mut App, events: &[WatchEvent]) -> (Vec<usize>, V
             WatchEvent::DirChanged(ref p) => (p, true),
         });
         refresh_indices.extend(invalidate_matching_panels(app, path, is_dir_event));
+        cp_mod_watch::runner::note_change(&mut app.state, path);
         if !is_dir_event {
             rewatch_paths.push(path.clone());
         }
@@ -151,7 +178,7 @@ fn dispatch_refresh_requests(app: &mut App, mut refresh_indices: Vec<usize>) {
         let panel = crate::app::panels::get_panel(&ctx.context_type);
         let built = panel.build_cache_request(ctx, &app.state);
         if let Some(request) = built {
-            process_cache_request(request, app.cache_tx.clone());
+            process_cache_request(request);
             if let Some(ctx_mut) = app.state.context.get_mut(i) {
                 ctx_mut.cache_in_flight = true;
             }
@@ -173,11 +200,10 @@ fn rewatch_changed_files(app: &mut App, rewatch_paths: Vec<String>) {
 pub(super) fn process_watcher_events(app: &mut App) {
     let _guard = crate::profile!("app::watcher_events");
     let _fg = cp_base::flame!("watcher_events");
-    // Collect events (immutable borrow on file_watcher released after this block)
-    let events = {
-        let Some(watcher) = app.file_watcher.as_ref() else { return };
-        watcher.poll_events()
+    let Some(watcher) = app.file_watcher.as_ref() else {
+        return;
     };
+    let events = watcher.drain();
     if events.is_empty() {
         return;
     }
@@ -197,7 +223,7 @@ enum TimerOutcome {
 
 /// Decide one panel's timer fate: auto-close, refresh (initial / dirty /
 /// interval), or nothing. Pure read of `app` — no mutation.
-fn classify_timer_panel(app: &App, ctx: &crate::state::Entry, current_ms: u64) -> Option<TimerOutcome> {
+fn classify_timer_panel(app: &App, ctx: &Entry, current_ms: u64) -> Option<TimerOutcome> {
     let panel = crate::app::panels::get_panel(&ctx.context_type);
     if panel.suicide(ctx, &app.state) {
         return Some(TimerOutcome::Suicide);
@@ -205,6 +231,12 @@ fn classify_timer_panel(app: &App, ctx: &crate::state::Entry, current_ms: u64) -
     if ctx.cache_in_flight {
         return None;
     }
+    // Evicted by the cache budget, or seeding deferred at startup: loaded once
+    // selected, or before a request.
+    let selected = app.state.context.get(app.state.selected_context).is_some_and(|c| c.id == ctx.id);
+    if ctx.awaits_load() && !selected {
+        return None;
+    }
     // Case 1: Initial load — panel has no content yet.
     // Case 2: Explicitly dirty (watcher event, tool, self-invalidation).
     let needs_initial = ctx.cached_content.is_none() && ctx.context_type.needs_cache();
@@ -293,7 +325,7 @@ pub(super) fn check_timer_based_deprecation(app: &mut App) {
 
     // Mutable pass: send requests, mark in-flight, update poll timestamps
     for (i, request) in requests {
-        process_cache_request(request, app.cache_tx.clone());
+        process_cache_request(request);
         if let Some(ctx) = app.state.context.get_mut(i) {
             ctx.cache_in_flight = true;
             let _r = app.last_poll_ms.insert(ctx.id.clone(), current_ms);
@@ -301,30 +333,64 @@ pub(super) fn check_timer_based_deprecation(app: &mut App) {
     }
 
     remove_suicided_panels(app, &suicide_indices);
+
+    // Idle panel GC (throttled on its own clock)
+    crate::modules::overview::panel_gc::tick(&mut app.state);
+    crate::modules::overview::context_health::tick(&mut app.state);
+    crate::modules::overview::cache_budget::tick(&mut app.state);
+    crate::state::profile::tick(&mut app.state);
+    tick_watch(&mut app.state);
+    // A mermaid render or sixel encode finished in the background: redraw.
+    if cp_graphics::take_ready() {
+        app.state.flags.ui.dirty = true;
+    }
+}
+
+/// Advance the file watch. A finished run becomes a spine notification, which
+/// starts a fix turn unless the auto-fix limit was reached.
+fn tick_watch(state: &mut State) {
+    let Some(outcome) = cp_mod_watch::runner::tick(state) else { return };
+    let nid = SpineState::create_notification(state, NotificationType::Custom, "watch".to_owned(), outcome.message);
+    if !outcome.auto_fix {
+        let _r = SpineState::mark_notification_processed(state, &nid);
+    }
+    state.flags.ui.dirty = true;
 }
 
 /// Gather every path all modules currently want watched, split into files and
 /// dirs. `BTreeSet` (not `HashSet`) for deterministic iteration (dodges the
 /// `iter_over_hash_type` lint).
 fn collect_wanted_paths(app: &App) -> (std::collections::BTreeSet<String>, std::collections::BTreeSet<String>) {
-    use cp_base::panels::WatchSpec;
     let mut wanted_files = std::collections::BTreeSet::new();
     let mut wanted_dirs = std::collections::BTreeSet::new();
-    for module in &crate::modules::all_modules() {
-        for spec in module.watch_paths(&app.state) {
-            // `if let` (not exhaustive match) so WatchSpec stays #[non_exhaustive].
-            if let WatchSpec::File(path) = spec {
-                let _r = wanted_files.insert(path);
-            } else if let WatchSpec::Dir(path) | WatchSpec::DirRecursive(path) = spec {
-                let _r = wanted_dirs.insert(path);
-            } else {
-                // Future non_exhaustive variants: ignored by the watcher sync.
-            }
+    for spec in wanted_specs(&app.state) {
+        // `if let` (not exhaustive match) so WatchSpec stays #[non_exhaustive].
+        if let WatchSpec::File(path) = spec {
+            let _r = wanted_files.insert(path);
+        } else if let WatchSpec::Dir(path) | WatchSpec::DirRecursive(path) = spec {
+            let _r = wanted_dirs.insert(path);
+        } else {
+            // Future non_exhaustive variants: ignored by the watcher sync.
         }
     }
     (wanted_files, wanted_dirs)
 }
 
+/// Every module's watch specs, minus paths the workspace jail refuses — a
+/// jailed path is neither read by tools nor watched.
+fn wanted_specs(state: &State) -> Vec<WatchSpec> {
+    let jail = state.get_ext::<Jail>();
+    let allowed = |spec: &WatchSpec| jail.is_none_or(|j| j.allows(Path::new(spec_path(spec))));
+    crate::modules::all_modules().iter().flat_map(|module| module.watch_paths(state)).filter(allowed).collect()
+}
+
+/// The path a watch spec names.
+fn spec_path(spec: &WatchSpec) -> &str {
+    cp_base::deref_match!(spec, {
+        WatchSpec::File(ref path) | WatchSpec::Dir(ref path) | WatchSpec::DirRecursive(ref path) => path,
+    })
+}
+
 /// Unwatch any file/dir no longer wanted (frees kqueue FDs on macOS, where each
 /// watched path costs one FD against the process limit).
 fn remove_stale_watches(
@@ -358,25 +424,24 @@ fn add_file_watches(app: &mut App, wanted_files: std::collections::BTreeSet<Stri
 
 /// Add dir watches for newly-wanted dirs, honoring each spec's recursive flag
 /// (re-scans module specs since recursion isn't captured in the wanted set).
+/// Recursive specs always reach the watcher, which skips known ones and
+/// upgrades a directory already watched non-recursively.
 fn add_dir_watches(app: &mut App) {
-    use cp_base::panels::WatchSpec;
+    let specs = wanted_specs(&app.state);
     let Some(watcher) = app.file_watcher.as_mut() else { return };
-    for module in &crate::modules::all_modules() {
-        for spec in module.watch_paths(&app.state) {
-            // File specs are handled by add_file_watches; here we only add dir
-            // watches. `if let` (not exhaustive match) so WatchSpec stays #[non_exhaustive].
-            if let WatchSpec::Dir(path) = spec {
-                if !app.watched_dir_paths.contains(&path) && watcher.watch_dir(&path).is_ok() {
-                    let _r = app.watched_dir_paths.insert(path);
-                }
-            } else if let WatchSpec::DirRecursive(path) = spec
-                && !app.watched_dir_paths.contains(&path)
-                && watcher.watch_dir_recursive(&path).is_ok()
-            {
+    for spec in specs {
+        // File specs are handled by add_file_watches; here we only add dir
+        // watches. `if let` (not exhaustive match) so WatchSpec stays #[non_exhaustive].
+        if let WatchSpec::Dir(path) = spec {
+            if !app.watched_dir_paths.contains(&path) && watcher.watch_dir(&path).is_ok() {
                 let _r = app.watched_dir_paths.insert(path);
-            } else {
-                // File specs (handled by add_file_watches) + future non_exhaustive variants.
             }
+        } else if let WatchSpec::DirRecursive(path) = spec
+            && watcher.watch_dir_recursive(&path).is_ok()
+        {
+            let _r = app.watched_dir_paths.insert(path);
+        } else {
+            // File specs (handled by add_file_watches) + future non_exhaustive variants.
         }
     }
 }
@@ -394,3 +459,21 @@ fn sync_file_watchers(app: &mut App) {
     add_file_watches(app, wanted_files);
     add_dir_watches(app);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::context::make_default_entry;
+
+    #[test]
+    fn only_unselected_expensive_panels_defer_seeding() {
+        let tree = make_default_entry("P3", Kind::new(Kind::TREE), "Tree", false);
+        assert!(defers_seeding(&tree, false));
+        assert!(!defers_seeding(&tree, true));
+        let loaded = make_default_entry("P9", Kind::new(Kind::GREP), "grep", false)
+            .with_cached_content(Some("src/main.rs:1: fn main".to_owned()));
+        assert!(!defers_seeding(&loaded, false));
+        let todo = make_default_entry("P1", Kind::new(Kind::TODO), "Todo", false);
+        assert!(!defers_seeding(&todo, false));
+    }
+}
@@ -0,0 +1,173 @@
+//! `attach`: run in a tmux split or popup next to the editor.
+//!
+//! `tui attach` must run inside tmux. Before the terminal is taken over it
+//! lists the other panes of its window (of the window holding `--from PANE`
+//! when started from a popup) and moves to the working directory of the pane
+//! the user came from; once the session is up each of those panes becomes a
+//! Tmux panel. `tui attach --keys [KEY]` prints a tmux.conf snippet binding
+//! `prefix KEY` to toggle the session in a popup and `prefix A` to open it in
+//! a split.
+
+use std::io::{self, Write as _};
+use std::process::{Command, ExitCode};
+
+use crate::state::State;
+
+/// The subcommand.
+pub(super) const SUBCOMMAND: &str = "attach";
+
+/// Print the keybinding snippet instead of starting.
+pub(super) const KEYS_FLAG: &str = "--keys";
+
+/// Pane whose window is mirrored and whose directory is used.
+pub(super) const FROM_FLAG: &str = "--from";
+
+/// Popup toggle key when `--keys` names none.
+const DEFAULT_KEY: &str = "P";
+
+/// Session the popup runs in; toggling detaches from it.
+const POPUP_SESSION: &str = "context-pilot";
+
+/// `tmux list-panes` fields, tab-separated.
+const PANE_FORMAT: &str = "#{pane_id}\t#{pane_active}\t#{pane_last}\t#{pane_current_command}\t#{pane_current_path}";
+
+/// One pane of the window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pane {
+    /// tmux pane ID, e.g. `%3`.
+    id: String,
+    /// The window's active pane.
+    active: bool,
+    /// The pane active before it.
+    last: bool,
+    /// What runs in it (`nvim`, `zsh`, ...).
+    command: String,
+    /// Its working directory.
+    path: String,
+}
+
+/// Panes to register once the session is up.
+pub(crate) struct Attach {
+    /// Sibling panes, in window order.
+    panes: Vec<Pane>,
+}
+
+/// The value following `flag`, if given.
+fn flag_value<'args>(args: &'args [String], flag: &str) -> Option<&'args str> {
+    let pos = args.iter().position(|a| a == flag)?;
+    args.get(pos.saturating_add(1)).map(String::as_str).filter(|a| !a.starts_with("--"))
+}
+
+/// Decode [`PANE_FORMAT`] lines, leaving out `own`.
+fn parse_panes(listing: &str, own: &str) -> Vec<Pane> {
+    listing
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let &[id, active, last, command, path] = fields.as_slice() else { return None };
+            Some(Pane {
+                id: id.to_owned(),
+                active: active == "1",
+                last: last == "1",
+                command: command.to_owned(),
+                path: path.to_owned(),
+            })
+        })
+        .filter(|p| p.id != own)
+        .collect()
+}
+
+/// The pane the working directory follows: `from` when given, else the one
+/// the user left to start us, else the active one, else the first.
+fn cwd_source<'panes>(panes: &'panes [Pane], from: Option<&str>) -> Option<&'panes Pane> {
+    if let Some(id) = from {
+        return panes.iter().find(|p| p.id == id);
+    }
+    panes.iter().find(|p| p.last).or_else(|| panes.iter().find(|p| p.active)).or_else(|| panes.first())
+}
+
+/// For `tui attach`: list the sibling panes and move to the directory of
+/// the pane the user came from. `Ok(None)` for any other launch.
+pub(crate) fn parse(args: &[String]) -> Result<Option<Attach>, String> {
+    if args.get(1).map(String::as_str) != Some(SUBCOMMAND) {
+        return Ok(None);
+    }
+    let own = std::env::var("TMUX_PANE")
+        .ok()
+        .filter(|_| std::env::var_os("TMUX").is_some())
+        .ok_or_else(|| format!("{SUBCOMMAND}: not inside tmux (try `tui {SUBCOMMAND} {KEYS_FLAG}` for a binding)"))?;
+    let from = flag_value(args, FROM_FLAG);
+    let output = Command::new("tmux")
+        .args(["list-panes", "-t", from.unwrap_or(&own), "-F", PANE_FORMAT])
+        .output()
+        .map_err(|e| format!("{SUBCOMMAND}: cannot run tmux: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("{SUBCOMMAND}: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let panes = parse_panes(&String::from_utf8_lossy(&output.stdout), &own);
+    if let Some(source) = cwd_source(&panes, from) {
+        std::env::set_current_dir(&source.path)
+            .map_err(|e| format!("{SUBCOMMAND}: cannot enter {}: {e}", source.path))?;
+    }
+    Ok(Some(Attach { panes }))
+}
+
+/// Register every sibling pane as a Tmux panel.
+pub(crate) fn apply(state: &mut State, attach: Attach) {
+    for pane in attach.panes {
+        let _id = crate::modules::tmux::add_pane_panel(state, &pane.id, &format!("tmux {} {}", pane.id, pane.command));
+    }
+}
+
+/// The tmux.conf lines: `prefix key` toggles a popup session started from the
+/// current pane, `prefix A` opens a split beside it.
+fn snippet(exe: &str, key: &str) -> String {
+    format!(
+        "# Context Pilot: prefix + {key} toggles it in a popup, prefix + A opens it in a split.\n\
+         bind-key {key} {{\n\
+         \x20 if-shell -F '#{{==:#{{session_name}},{POPUP_SESSION}}}' {{\n\
+         \x20   detach-client\n\
+         \x20 }} {{\n\
+         \x20   run-shell -b \"tmux display-popup -E -w 85% -h 85% -d '#{{pane_current_path}}' \
+         'tmux new-session -A -s {POPUP_SESSION} {exe} {SUBCOMMAND} {FROM_FLAG} #{{pane_id}}'\"\n\
+         \x20 }}\n\
+         }}\n\
+         bind-key A split-window -h -l 40% -c '#{{pane_current_path}}' '{exe} {SUBCOMMAND}'\n"
+    )
+}
+
+/// Run `tui attach --keys [KEY]`: print the snippet for `~/.tmux.conf`.
+pub(super) fn print_keys(args: &[String]) -> ExitCode {
+    let key = flag_value(args, KEYS_FLAG).unwrap_or(DEFAULT_KEY);
+    let exe = std::env::current_exe().map_or_else(|_| "tui".to_owned(), |p| p.display().to_string());
+    match io::stdout().write_all(snippet(&exe, key).as_bytes()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(_) => ExitCode::FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LISTING: &str = "%1\t0\t1\tnvim\t/work/app\n%2\t1\t0\ttui\t/work\n%3\t0\t0\tzsh\t/tmp\n";
+
+    #[test]
+    fn siblings_skip_our_pane_and_follow_the_last_one() {
+        let panes = parse_panes(LISTING, "%2");
+        assert_eq!(panes.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), ["%1", "%3"]);
+        assert_eq!(cwd_source(&panes, None).map(|p| p.path.as_str()), Some("/work/app"));
+        assert_eq!(cwd_source(&panes, Some("%3")).map(|p| p.path.as_str()), Some("/tmp"));
+        assert_eq!(parse_panes("garbage\n", "%2"), vec![]);
+    }
+
+    #[test]
+    fn snippet_binds_the_popup_toggle_and_the_split() {
+        let text = snippet("/usr/bin/tui", "C-g");
+        assert!(
+            text.contains("bind-key C-g {\n  if-shell -F '#{==:#{session_name},context-pilot}' {\n    detach-client")
+        );
+        assert!(text.contains("'tmux new-session -A -s context-pilot /usr/bin/tui attach --from #{pane_id}'\"\n"));
+        assert!(text.ends_with("bind-key A split-window -h -l 40% -c '#{pane_current_path}' '/usr/bin/tui attach'\n"));
+    }
+}
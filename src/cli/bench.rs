@@ -0,0 +1,180 @@
+//! `--bench-ui`: frame-time regression guard.
+//!
+//! Renders synthetic sessions — a long conversation, a crowded sidebar, a
+//! huge file — headlessly on a `TestBackend`, reads the frame-time
+//! percentiles back from the perf module, and exits non-zero when a p95 is
+//! over its budget. Budgets are release-build figures; `--budget-scale=N`
+//! multiplies them (debug builds and slow CI runners).
+
+use std::io::{self, Write as _};
+use std::process::ExitCode;
+
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+
+use crate::state::{Kind, Message, State};
+use crate::ui::perf::PERF;
+use cp_base::cast::Safe as _;
+
+/// The flag that selects bench mode.
+pub(crate) const FLAG: &str = "--bench-ui";
+
+/// Frames rendered before measuring (caches filled, layout settled).
+const WARMUP_FRAMES: usize = 4;
+
+/// Frames measured per scenario (the perf module keeps the last 64).
+const MEASURED_FRAMES: usize = 64;
+
+/// Terminal size the scenarios render at.
+const SIZE: (u16, u16) = (200, 60);
+
+/// Percentiles reported, in order.
+const PERCENTILES: [usize; 3] = [50, 95, 99];
+
+/// A synthetic session and the p95 frame time it must stay under.
+struct Scenario {
+    /// Name printed in the report.
+    name: &'static str,
+    /// p95 budget in microseconds.
+    budget_us: u64,
+    /// Builds the session.
+    build: fn() -> State,
+}
+
+/// The scenarios, in report order. Budgets sit well above today's release
+/// figures so only a real regression trips them; the huge file is slow
+/// because generic panels rebuild and re-wrap every line each frame.
+const SCENARIOS: [Scenario; 3] = [
+    Scenario { name: "conversation (1000 messages)", budget_us: 25_000, build: long_conversation },
+    Scenario { name: "sidebar (50 panels)", budget_us: 10_000, build: crowded_sidebar },
+    Scenario { name: "huge file (50k lines)", budget_us: 600_000, build: huge_file },
+];
+
+/// Run every scenario, print the percentiles, fail on a regressed budget.
+pub(crate) fn run(args: &[String]) -> ExitCode {
+    let scale = budget_scale(args);
+    let mut out = io::stdout().lock();
+    let mut failed = 0usize;
+    for scenario in &SCENARIOS {
+        let Some(times) = measure(scenario) else {
+            drop(writeln!(out, "{:<30} could not render", scenario.name));
+            failed = failed.saturating_add(1);
+            continue;
+        };
+        let budget = scenario.budget_us.saturating_mul(scale);
+        let p95 = times.get(1).copied().unwrap_or(0);
+        let verdict = if p95 <= budget { "ok" } else { "OVER BUDGET" };
+        if p95 > budget {
+            failed = failed.saturating_add(1);
+        }
+        let [p50, _, p99] = [0, 1, 2].map(|i| times.get(i).copied().unwrap_or(0));
+        drop(writeln!(
+            out,
+            "{:<30} p50 {:>6}us  p95 {p95:>6}us  p99 {:>6}us  budget {budget:>6}us  {verdict}",
+            scenario.name, p50, p99
+        ));
+    }
+    if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+/// The `--budget-scale=N` multiplier; 1 when absent, invalid or zero.
+fn budget_scale(args: &[String]) -> u64 {
+    args.iter()
+        .find_map(|a| a.strip_prefix("--budget-scale="))
+        .and_then(|n| n.parse::<u64>().ok())
+        .map_or(1, |n| n.max(1))
+}
+
+/// Render one scenario headlessly, scrolling a little each frame, and return
+/// its frame-time percentiles.
+fn measure(scenario: &Scenario) -> Option<Vec<u64>> {
+    let mut state = (scenario.build)();
+    let mut terminal = Terminal::new(TestBackend::new(SIZE.0, SIZE.1)).ok()?;
+    for frame in 0..WARMUP_FRAMES.saturating_add(MEASURED_FRAMES) {
+        if frame == WARMUP_FRAMES {
+            PERF.enable();
+        }
+        state.scroll_offset = frame.saturating_mul(3).to_f32();
+        state.flags.ui.dirty = true;
+        let _drawn = terminal.draw(|f| crate::ui::render(f, &mut state)).ok()?;
+    }
+    Some(PERF.frame_percentiles(&PERCENTILES))
+}
+
+/// A fresh session with the default panels, as after first launch.
+fn base_state() -> State {
+    let mut state = crate::state::persistence::fresh_state();
+    state.highlight_ir_fn = Some(crate::ui::helpers::highlight_file_ir);
+    crate::modules::init_registry();
+    crate::app::ensure_default_contexts(&mut state);
+    crate::app::ensure_default_agent(&mut state);
+    state
+}
+
+/// A file panel showing `content` as `path`.
+fn file_panel(state: &mut State, path: &str, content: String) {
+    let id = state.next_available_context_id();
+    let mut elem = cp_base::state::context::make_default_entry(&id, Kind::new(Kind::FILE), path, false);
+    elem.set_meta("file_path", &path);
+    elem.token_count = crate::state::estimate_tokens(&content);
+    elem.cached_content = Some(content);
+    state.context.push(elem);
+}
+
+/// Rust-looking source, `lines` long.
+fn source(lines: usize) -> String {
+    (0..lines)
+        .map(|i| format!("    let value_{i} = compute(\"item {i}\", {i}).unwrap_or_default(); // step {i}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 1000 alternating messages with markdown, code and long paragraphs.
+fn long_conversation() -> State {
+    let mut state = base_state();
+    for i in 0..1000usize {
+        let (role, content) = if i.is_multiple_of(2) {
+            ("user", format!("Question {i}: why does `parse_{i}` fail on empty input?"))
+        } else {
+            let body = "The parser assumes at least one token before it peeks. ".repeat(8);
+            ("assistant", format!("## Answer {i}\n\n{body}\n\n```rust\n{}\n```\n\n- first\n- second\n", source(6)))
+        };
+        let mut msg = Message::new_text(format!("{}{i}", if role == "user" { "U" } else { "A" }), role, content);
+        msg.content_token_count = crate::state::estimate_tokens(&msg.content);
+        state.messages.push(msg);
+    }
+    state.selected_context = 0;
+    state
+}
+
+/// 50 open file panels, the last one selected.
+fn crowded_sidebar() -> State {
+    let mut state = base_state();
+    for i in 0..50usize {
+        file_panel(&mut state, &format!("src/module_{i}.rs"), source(200));
+    }
+    state.selected_context = state.context.len().saturating_sub(1);
+    state
+}
+
+/// One 50 000-line file panel, selected.
+fn huge_file() -> State {
+    let mut state = base_state();
+    file_panel(&mut state, "src/generated.rs", source(50_000));
+    state.selected_context = state.context.len().saturating_sub(1);
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_scale_defaults_to_one() {
+        let scale = |arg: &str| budget_scale(&[FLAG.to_owned(), arg.to_owned()]);
+        assert_eq!(scale("--budget-scale=8"), 8);
+        assert_eq!(scale("--budget-scale=0"), 1);
+        assert_eq!(scale("--budget-scale=fast"), 1);
+        assert_eq!(scale("--verbose"), 1);
+    }
+}
@@ -0,0 +1,150 @@
+//! Config files that fail to parse (the TUI silently falls back to defaults
+//! on those) and the store directory's permissions.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use super::Check;
+use crate::infra::constants::{CONFIG_FILE, DEFAULT_WORKER_ID, PANELS_DIR, STATES_DIR, STORE_DIR};
+use crate::state::{PanelData, SharedConfig, WorkerState};
+
+/// Parse the JSON file at `path` as `T`. `Ok(false)` when it does not exist.
+fn parse<T>(path: &Path) -> Result<bool, String>
+where
+    T: DeserializeOwned,
+{
+    if !path.exists() {
+        return Ok(false);
+    }
+    let json = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    serde_json::from_str::<T>(&json).map(|_| true).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Config and state checks, then the permissions check.
+pub(super) fn checks() -> Vec<Check> {
+    let store = PathBuf::from(STORE_DIR);
+    vec![
+        project_config(&store),
+        worker_state(&store),
+        panels(&store.join(PANELS_DIR)),
+        global_config(),
+        permissions(&store),
+    ]
+}
+
+/// `.context-pilot/config.json`.
+fn project_config(store: &Path) -> Check {
+    match parse::<SharedConfig>(&store.join(CONFIG_FILE)) {
+        Ok(true) => Check::ok("project config", "parses"),
+        Ok(false) => Check::warn("project config", "not set up yet", "run `tui init` to scan and set up the project"),
+        Err(e) => Check::fail("project config", e, "fix the JSON by hand, or move the file away to start fresh"),
+    }
+}
+
+/// `.context-pilot/states/main_worker.json`.
+fn worker_state(store: &Path) -> Check {
+    let path = store.join(STATES_DIR).join(format!("{DEFAULT_WORKER_ID}.json"));
+    match parse::<WorkerState>(&path) {
+        Ok(true) => Check::ok("worker state", "parses"),
+        Ok(false) => Check::ok("worker state", "none yet (created on first run)"),
+        Err(e) => Check::fail("worker state", e, "fix the JSON by hand, or delete the file to reset the session"),
+    }
+}
+
+/// Every `.context-pilot/panels/*.json`.
+fn panels(dir: &Path) -> Check {
+    let Ok(entries) = fs::read_dir(dir) else { return Check::ok("panels", "none yet") };
+    let paths: Vec<PathBuf> =
+        entries.flatten().map(|e| e.path()).filter(|p| p.extension().is_some_and(|x| x == "json")).collect();
+    let broken: Vec<String> = paths.iter().filter_map(|p| parse::<PanelData>(p).err()).collect();
+    broken.first().map_or_else(
+        || Check::ok("panels", format!("{} panel file(s) parse", paths.len())),
+        |first| {
+            Check::warn(
+                "panels",
+                format!("{} of {} panel file(s) do not parse — {first}", broken.len(), paths.len()),
+                "delete the broken files; the panels are rebuilt on the next run",
+            )
+        },
+    )
+}
+
+/// `~/.config/context-pilot/config.json`.
+fn global_config() -> Check {
+    let Some(path) = cp_base::config::global::config_path() else {
+        return Check::warn("global config", "no config directory (HOME unset?)", "set HOME or XDG_CONFIG_HOME");
+    };
+    match parse::<cp_base::config::global::Config>(&path) {
+        Ok(true) => Check::ok("global config", "parses"),
+        Ok(false) => Check::ok("global config", "none (keys come from the environment)"),
+        Err(e) => {
+            Check::fail("global config", e, "fix the JSON by hand; stored keys and settings are ignored until then")
+        }
+    }
+}
+
+/// Whether the store directory (or the working directory, before the first
+/// run) can be written.
+fn permissions(store: &Path) -> Check {
+    let dir = if store.is_dir() { store } else { Path::new(".") };
+    let probe = dir.join(".doctor-write-test");
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _r = fs::remove_file(&probe);
+            Check::ok("store dir", format!("{} is writable", dir.display()))
+        }
+        Err(e) => Check::fail(
+            "store dir",
+            format!("cannot write to {}: {e}", dir.display()),
+            format!("fix the ownership, e.g. `sudo chown -R $USER {}`", dir.display()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::doctor::Status;
+
+    /// A fresh empty directory under the system temp dir.
+    fn tmp_store(tag: &str) -> Result<PathBuf, String> {
+        let dir = std::env::temp_dir().join(format!("cp-doctor-{tag}-{}", std::process::id()));
+        drop(fs::remove_dir_all(&dir));
+        fs::create_dir_all(dir.join(PANELS_DIR)).map_err(|e| format!("mkdir: {e}"))?;
+        Ok(dir)
+    }
+
+    #[test]
+    fn a_missing_project_config_warns_and_a_broken_one_fails() -> Result<(), String> {
+        let store = tmp_store("config")?;
+        let missing = project_config(&store).status;
+        fs::write(store.join(CONFIG_FILE), "{ not json").map_err(|e| format!("write: {e}"))?;
+        let broken = project_config(&store).status;
+        let writable = permissions(&store).status;
+        drop(fs::remove_dir_all(&store));
+        if (missing, broken, writable) != (Status::Warn, Status::Fail, Status::Ok) {
+            return Err(format!("missing={missing:?} broken={broken:?} writable={writable:?}"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn broken_panel_files_are_counted_and_others_ignored() -> Result<(), String> {
+        let store = tmp_store("panels")?;
+        let dir = store.join(PANELS_DIR);
+        let empty = panels(&dir).detail;
+        fs::write(dir.join("P1.json"), "[").map_err(|e| format!("write: {e}"))?;
+        fs::write(dir.join("notes.txt"), "[").map_err(|e| format!("write: {e}"))?;
+        let check = panels(&dir);
+        drop(fs::remove_dir_all(&store));
+        if empty != "0 panel file(s) parse"
+            || check.status != Status::Warn
+            || !check.detail.starts_with("1 of 1 panel file(s) do not parse")
+        {
+            return Err(format!("empty={empty} status={:?} detail={}", check.status, check.detail));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! `doctor` subcommand: environment diagnostics.
+//!
+//! Runs a list of independent checks — provider keys (and a live probe of the
+//! active one through `check_api`), tmux, the git repository, the project and
+//! global config files, the store directory's permissions and the terminal —
+//! and prints one line per check with an actionable fix for anything wrong.
+//! Exits non-zero when a check fails; warnings alone do not fail the run.
+
+/// Config files and store directory.
+mod files;
+/// Provider keys and the live API probe.
+mod providers;
+/// tmux, git and the terminal.
+mod system;
+
+use std::io::{self, Write as _};
+use std::process::ExitCode;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// Nothing to do.
+    Ok,
+    /// Works, but something is degraded or missing.
+    Warn,
+    /// Context Pilot will not work properly until fixed.
+    Fail,
+}
+
+/// One diagnostic line.
+#[derive(Debug, Clone)]
+struct Check {
+    /// What was checked.
+    name: &'static str,
+    /// How it went.
+    status: Status,
+    /// What was found.
+    detail: String,
+    /// How to fix it, for warnings and failures.
+    fix: Option<String>,
+}
+
+impl Check {
+    /// A passing check.
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self { name, status: Status::Ok, detail: detail.into(), fix: None }
+    }
+
+    /// A degraded check with its fix.
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Warn, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    /// A failed check with its fix.
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Fail, detail: detail.into(), fix: Some(fix.into()) }
+    }
+}
+
+/// Run `doctor`. Flag: `--offline` skips the live provider probe.
+pub(crate) fn run(args: &[String]) -> ExitCode {
+    let offline = args.iter().any(|a| a == "--offline");
+    let mut checks = providers::checks(offline);
+    checks.extend(system::checks());
+    checks.extend(files::checks());
+
+    let mut out = io::stdout().lock();
+    let _r = writeln!(out, "Context Pilot doctor\n");
+    for check in &checks {
+        let mark = match check.status {
+            Status::Ok => '\u{2713}',
+            Status::Warn => '!',
+            Status::Fail => '\u{2717}',
+        };
+        let _r2 = writeln!(out, "{mark} {:<14} {}", check.name, check.detail);
+        if let Some(fix) = check.fix.as_ref() {
+            let _r3 = writeln!(out, "  {:<14} \u{2192} {fix}", "");
+        }
+    }
+    let (failed, warned) = tally(&checks);
+    let _r4 = writeln!(out, "\n{failed} problem(s), {warned} warning(s).");
+    if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+/// Number of failed and of warning checks.
+fn tally(checks: &[Check]) -> (usize, usize) {
+    let count = |status: Status| checks.iter().filter(|c| c.status == status).count();
+    (count(Status::Fail), count(Status::Warn))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_and_warnings_are_tallied_separately() {
+        let checks = [
+            Check::ok("tmux", "tmux 3.4"),
+            Check::warn("size", "70x20", "enlarge the window"),
+            Check::fail("git", "not found", "install git"),
+            Check::warn("colors", "16 colors", "set COLORTERM"),
+        ];
+        assert_eq!(tally(&checks), (1, 2));
+        assert_eq!(checks[1].fix.as_deref(), Some("enlarge the window"));
+        assert_eq!(checks[0].fix, None);
+    }
+}
@@ -0,0 +1,145 @@
+//! Provider keys: which credentials are found, and whether the active
+//! provider actually answers (auth, streaming, tool use).
+
+use cp_base::config::llm_types::LlmProvider;
+use cp_base::state::data::model_helpers::ModelPricing as _;
+use cp_vault::registry::{self, KeyCategory};
+
+use super::Check;
+use crate::state::State;
+use crate::state::persistence::config::load_config;
+
+/// Every provider, for the "other keys found" line.
+const PROVIDERS: [LlmProvider; 8] = [
+    LlmProvider::Anthropic,
+    LlmProvider::ClaudeCode,
+    LlmProvider::ClaudeCodeApiKey,
+    LlmProvider::Grok,
+    LlmProvider::Groq,
+    LlmProvider::DeepSeek,
+    LlmProvider::MiniMax,
+    LlmProvider::ClaudeCodeV2,
+];
+
+/// Vault key the client for `provider` authenticates with.
+const fn vault_key(provider: LlmProvider) -> &'static str {
+    match provider {
+        LlmProvider::Anthropic | LlmProvider::ClaudeCodeApiKey => "anthropic",
+        LlmProvider::ClaudeCode | LlmProvider::ClaudeCodeV2 => "claude_oauth",
+        LlmProvider::Grok => "xai",
+        LlmProvider::Groq => "groq",
+        LlmProvider::DeepSeek => "deepseek",
+        LlmProvider::MiniMax => "minimax",
+    }
+}
+
+/// How to provide the credential named `key`.
+fn key_fix(key: &str) -> String {
+    match registry::resolve_definition(key) {
+        Some(def) if !def.env_var.is_empty() => {
+            format!("export {}=… (or add it to .env, or to ~/.config/context-pilot/config.json)", def.env_var)
+        }
+        Some(_) | None => "run `claude login` to refresh the Claude Code OAuth token".to_owned(),
+    }
+}
+
+/// Display label of the credential named `key`.
+fn key_label(key: &str) -> &str {
+    registry::resolve_definition(key).map_or(key, |def| def.display)
+}
+
+/// A default state carrying the project's provider and model choices. The
+/// full boot is avoided: it starts the console server and writes the store.
+fn configured() -> State {
+    let mut state = State::default();
+    if let Some(core) = load_config().as_ref().and_then(|c| c.modules.get("core")) {
+        crate::modules::overview::load_provider_models(core, &mut state);
+    }
+    state
+}
+
+/// Key checks, plus the live probe of the active provider unless `offline`.
+pub(super) fn checks(offline: bool) -> Vec<Check> {
+    let state = configured();
+    let provider = state.llm_provider;
+    let key = vault_key(provider);
+    let found = |name: &str| cp_vault::vault().get(name).is_some();
+
+    let mut out = Vec::new();
+    if found(key) {
+        out.push(Check::ok("provider key", format!("{} credentials found", key_label(key))));
+    } else {
+        out.push(Check::fail(
+            "provider key",
+            format!("no {} credentials for the active provider", key_label(key)),
+            key_fix(key),
+        ));
+        return out;
+    }
+    out.push(other_keys(provider, &found));
+    if !offline {
+        out.push(probe(provider, &state.current_model()));
+    }
+    out
+}
+
+/// The other provider keys found, so a switch is one `/model` away.
+fn other_keys(active: LlmProvider, found: &impl Fn(&str) -> bool) -> Check {
+    let mut labels: Vec<&str> = PROVIDERS
+        .iter()
+        .map(|&p| vault_key(p))
+        .filter(|&k| k != vault_key(active) && found(k))
+        .filter(|&k| registry::resolve_definition(k).is_some_and(|d| d.category == KeyCategory::LlmProvider))
+        .map(key_label)
+        .collect();
+    labels.sort_unstable();
+    labels.dedup();
+    if labels.is_empty() {
+        Check::ok("other keys", "none (only the active provider is configured)")
+    } else {
+        Check::ok("other keys", labels.join(", "))
+    }
+}
+
+/// Send the provider's own health check for `model`.
+fn probe(provider: LlmProvider, model: &str) -> Check {
+    let result = crate::llms::get_client(provider).check_api(model);
+    if result.all_ok() {
+        return Check::ok("provider API", format!("{model}: auth, streaming and tool use work"));
+    }
+    let failed: Vec<&str> =
+        [(result.auth_ok, "auth"), (result.streaming_ok, "streaming"), (result.tools_ok, "tool use")]
+            .into_iter()
+            .filter(|&(ok, _)| !ok)
+            .map(|(_, name)| name)
+            .collect();
+    let error = result.error.unwrap_or_default();
+    let detail = format!("{model}: {} failed {error}", failed.join(", "));
+    let fix = if result.auth_ok {
+        "check the network, or pick another model with /model".to_owned()
+    } else {
+        format!("the key is rejected — {}", key_fix(vault_key(provider)))
+    };
+    Check::fail("provider API", detail.trim_end().to_owned(), fix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claude_code_variants_share_the_oauth_key() {
+        assert_eq!(vault_key(LlmProvider::ClaudeCode), vault_key(LlmProvider::ClaudeCodeV2));
+        assert_eq!(vault_key(LlmProvider::ClaudeCodeApiKey), vault_key(LlmProvider::Anthropic));
+        assert_eq!(key_label("xai"), "Grok (xAI)");
+    }
+
+    #[test]
+    fn other_keys_skip_the_active_one_and_list_each_key_once() {
+        let found = |key: &str| ["anthropic", "claude_oauth", "groq"].contains(&key);
+        assert_eq!(other_keys(LlmProvider::Groq, &found).detail, "Anthropic, Claude Code (OAuth)");
+        assert_eq!(other_keys(LlmProvider::ClaudeCodeApiKey, &found).detail, "Claude Code (OAuth), Groq");
+        let none = |key: &str| key == "xai";
+        assert_eq!(other_keys(LlmProvider::Grok, &none).detail, "none (only the active provider is configured)");
+    }
+}
@@ -0,0 +1,112 @@
+//! External tools and the terminal: tmux, the git repository, and what the
+//! terminal can draw.
+
+use std::io::IsTerminal as _;
+use std::path::Path;
+use std::process::Command;
+
+use cp_base::config::accessors::color_depth::{self, ColorDepth};
+use cp_graphics::detect::Protocol;
+
+use super::Check;
+
+/// Smallest comfortable terminal size (columns, rows).
+const MIN_SIZE: (u16, u16) = (80, 24);
+
+/// Run `program args…` and return its trimmed stdout, `None` on failure.
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok()?;
+    out.status.success().then(|| String::from_utf8_lossy(&out.stdout).trim().to_owned())
+}
+
+/// tmux, git and terminal checks.
+pub(super) fn checks() -> Vec<Check> {
+    let mut out = vec![tmux()];
+    out.extend(git());
+    out.push(colors());
+    out.push(graphics());
+    out.push(size());
+    out
+}
+
+/// tmux drives the terminal panes the agent sends keys to.
+fn tmux() -> Check {
+    output("tmux", &["-V"]).map_or_else(
+        || Check::warn("tmux", "not found", "install tmux (`apt install tmux` / `brew install tmux`) for pane control"),
+        |version| Check::ok("tmux", version),
+    )
+}
+
+/// Repository present, readable, and not mid-merge or detached.
+fn git() -> Vec<Check> {
+    if output("git", &["--version"]).is_none() {
+        return vec![Check::fail("git", "not found", "install git; the git module and file history need it")];
+    }
+    if output("git", &["rev-parse", "--is-inside-work-tree"]).as_deref() != Some("true") {
+        return vec![Check::warn("git", "not a git repository", "run `git init` to get diffs, history and the ledger")];
+    }
+    let mut out = Vec::new();
+    out.push(output("git", &["status", "--porcelain"]).map_or_else(
+        || Check::fail("git", "`git status` fails", "run `git status` and repair the repository"),
+        |status| Check::ok("git", format!("repository ok, {} changed file(s)", status.lines().count())),
+    ));
+    if let Some(state) = in_progress() {
+        out.push(Check::warn("git state", format!("{state} in progress"), format!("finish or abort the {state}")));
+    }
+    if output("git", &["symbolic-ref", "-q", "HEAD"]).is_none() {
+        out.push(Check::warn("git state", "detached HEAD", "`git switch <branch>` before committing"));
+    }
+    out
+}
+
+/// A merge or rebase left half done.
+fn in_progress() -> Option<&'static str> {
+    let git_dir = output("git", &["rev-parse", "--git-dir"])?;
+    let dir = Path::new(&git_dir);
+    if dir.join("MERGE_HEAD").exists() {
+        Some("merge")
+    } else if dir.join("rebase-merge").exists() || dir.join("rebase-apply").exists() {
+        Some("rebase")
+    } else {
+        None
+    }
+}
+
+/// Color depth in effect.
+fn colors() -> Check {
+    let term = std::env::var("TERM").unwrap_or_default();
+    if !std::io::stdout().is_terminal() {
+        return Check::warn("terminal", "stdout is not a terminal", "run Context Pilot in an interactive terminal");
+    }
+    match color_depth::current() {
+        ColorDepth::TrueColor => Check::ok("colors", format!("truecolor (TERM={term})")),
+        ColorDepth::Ansi256 => Check::ok("colors", format!("256 colors (TERM={term}); themes are approximated")),
+        ColorDepth::Ansi16 => Check::warn(
+            "colors",
+            format!("16 colors (TERM={term})"),
+            "set COLORTERM=truecolor or TERM=xterm-256color if the terminal supports it",
+        ),
+    }
+}
+
+/// Inline image protocol.
+fn graphics() -> Check {
+    match cp_graphics::detect::protocol() {
+        Some(Protocol::Kitty) => Check::ok("graphics", "kitty protocol"),
+        Some(Protocol::Sixel) => Check::ok("graphics", "sixel"),
+        None => Check::ok("graphics", "none (images stay text; CP_GRAPHICS=kitty|sixel forces one)"),
+    }
+}
+
+/// Terminal size.
+fn size() -> Check {
+    match crossterm::terminal::size() {
+        Ok((cols, rows)) if cols >= MIN_SIZE.0 && rows >= MIN_SIZE.1 => Check::ok("size", format!("{cols}x{rows}")),
+        Ok((cols, rows)) => Check::warn(
+            "size",
+            format!("{cols}x{rows}"),
+            format!("enlarge the window to at least {}x{}", MIN_SIZE.0, MIN_SIZE.1),
+        ),
+        Err(e) => Check::warn("size", format!("unknown ({e})"), "run Context Pilot in an interactive terminal"),
+    }
+}
@@ -0,0 +1,97 @@
+//! `experiment` subcommand: A/B test two seed prompts across sessions.
+//!
+//! - `experiment start NAME AGENT_A AGENT_B` — alternate the two agents.
+//! - `experiment stop` — back to the agent picked by hand; outcomes are kept.
+//! - `experiment [report [NAME]]` — per-agent totals of the running (or
+//!   named) experiment.
+//!
+//! The sessions themselves are handled by [`crate::state::experiment`].
+
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+use std::process::ExitCode;
+
+use cp_mod_prompt::types::PromptType;
+
+use crate::state::experiment::{self, Experiment};
+
+/// Run `experiment ...`.
+pub(crate) fn run(args: &[String]) -> ExitCode {
+    let arg = |i: usize| args.get(i).map(String::as_str);
+    let result = match arg(2) {
+        Some("start") => match (arg(3), arg(4), arg(5)) {
+            (Some(name), Some(first), Some(second)) => start(name, [first, second]),
+            _ => Err("Usage: tui experiment start NAME AGENT_A AGENT_B".to_owned()),
+        },
+        Some("stop") => experiment::stop()
+            .map(|()| "Experiment stopped; its outcomes are kept.".to_owned())
+            .map_err(|e| format!("No experiment to stop ({e})")),
+        Some("report") | None => report(arg(3)),
+        Some(other) => Err(format!("Unknown experiment command '{other}' (start, stop, report)")),
+    };
+    match result {
+        Ok(text) => {
+            drop(writeln!(io::stdout(), "{text}"));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            drop(writeln!(io::stderr(), "{e}"));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Start experiment `name` on two existing agents.
+fn start(name: &str, arms: [&str; 2]) -> Result<String, String> {
+    let agents = cp_mod_prompt::storage::load_prompts_for(PromptType::Agent);
+    if let Some(missing) = arms.iter().find(|id| !agents.iter().any(|a| a.id == **id)) {
+        return Err(format!("No agent '{missing}' (see the prompt library)"));
+    }
+    let [first, second] = arms;
+    if first == second {
+        return Err("The two agents must differ".to_owned());
+    }
+    let started = Experiment { name: name.to_owned(), arms: [first.to_owned(), second.to_owned()] };
+    experiment::save(&started).map_err(|e| format!("Cannot save the experiment: {e}"))?;
+    Ok(format!("Experiment '{name}' started: sessions alternate between {first} and {second}."))
+}
+
+/// Per-agent totals of experiment `name`, by default the running one.
+fn report(requested: Option<&str>) -> Result<String, String> {
+    let running = experiment::load();
+    let name = requested.or_else(|| running.as_ref().map(|e| e.name.as_str())).ok_or("No experiment running")?;
+    let recorded = experiment::outcomes(name);
+    let mut agents: Vec<String> = Vec::new();
+    for outcome in running.iter().flat_map(|e| e.arms.iter()).chain(recorded.iter().map(|o| &o.agent)) {
+        if !agents.contains(outcome) {
+            agents.push(outcome.clone());
+        }
+    }
+    let (Some(first), Some(second)) = (agents.first(), agents.get(1)) else {
+        return Err(format!("No sessions recorded for '{name}' yet"));
+    };
+    let compared = Experiment { name: name.to_owned(), arms: [first.clone(), second.clone()] };
+    let mut out = format!(
+        "Experiment '{name}' ({} sessions)\n\n{:<20} {:>8} {:>8} {:>11} {:>9} {:>11}\n",
+        recorded.len(),
+        "agent",
+        "sessions",
+        "tasks",
+        "turns/task",
+        "reverted",
+        "$/session"
+    );
+    for arm in experiment::summarize(&compared, &recorded) {
+        let _r = writeln!(
+            out,
+            "{:<20} {:>8} {:>8} {:>11.1} {:>9} {:>11.3}",
+            arm.agent,
+            arm.sessions,
+            arm.tasks,
+            arm.turns_per_task(),
+            arm.edits_reverted,
+            arm.cost_per_session()
+        );
+    }
+    Ok(out.trim_end().to_owned())
+}
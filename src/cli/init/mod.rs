@@ -0,0 +1,116 @@
+//! `init` subcommand: one-command first-run setup.
+//!
+//! Scans the repository (languages, build systems, test commands, entry
+//! points), prints what it found and what it proposes, and after confirmation
+//! writes `CONTEXT.md`, opens it as a panel, seeds project memories, extends
+//! the tree filter with `.gitignore`d directories, and enables or disables
+//! the optional modules that fit the repository. Everything is applied through
+//! the regular tools and saved like a normal session.
+
+/// The proposal built from a scan.
+mod plan;
+/// Repository scan.
+mod scan;
+
+use std::io::{self, BufRead as _, Write as _};
+use std::path::Path;
+use std::process::ExitCode;
+
+use cp_mod_tree::types::TreeState;
+
+use crate::app::{ensure_default_agent, ensure_default_contexts};
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::State;
+use crate::state::persistence::{load_state, save_state};
+
+use self::plan::InitPlan;
+
+/// File written at the repository root.
+const CONTEXT_FILE: &str = "CONTEXT.md";
+
+/// Run `init`. Flags: `--yes`/`-y` skips the confirmation, `--force` allows
+/// re-running in an already initialized project.
+pub(crate) fn run(args: &[String]) -> ExitCode {
+    let flag = |names: &[&str]| args.iter().any(|a| names.contains(&a.as_str()));
+    let mut out = io::stdout().lock();
+    let config = Path::new(cp_base::config::constants::STORE_DIR).join("config.json");
+    if config.exists() && !flag(&["--force"]) {
+        let _r = writeln!(
+            out,
+            "This project is already set up (.context-pilot/ exists). Re-run with --force to scan again."
+        );
+        return ExitCode::FAILURE;
+    }
+
+    let scan = scan::scan(Path::new("."));
+    let mut state = load_state();
+    crate::modules::init_registry();
+    ensure_default_contexts(&mut state);
+    ensure_default_agent(&mut state);
+    let plan = plan::build(&scan, &state);
+    let _r = writeln!(out, "{}", plan::describe(&scan, &plan));
+
+    if !flag(&["--yes", "-y"]) && !confirm(&mut out) {
+        let _r2 = writeln!(out, "Nothing changed.");
+        return ExitCode::SUCCESS;
+    }
+    for line in apply(&mut state, &plan) {
+        let _r3 = writeln!(out, "  {line}");
+    }
+    save_state(&state);
+    let _r4 = writeln!(out, "Done. Start Context Pilot in this directory to begin.");
+    ExitCode::SUCCESS
+}
+
+/// Ask `Apply this setup? [Y/n]` on stdin; empty means yes.
+fn confirm(out: &mut impl io::Write) -> bool {
+    let _r = write!(out, "Apply this setup? [Y/n] ");
+    let _r2 = out.flush();
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes")
+}
+
+/// Run one tool call as if the agent had made it.
+fn call(state: &mut State, name: &str, input: serde_json::Value) -> ToolResult {
+    let tool = ToolUse::new(format!("init-{name}"), name.to_owned(), input);
+    let active = state.active_modules.clone();
+    crate::modules::dispatch_tool(&tool, state, &active)
+}
+
+/// First line of a tool result, for the progress report.
+fn outcome(result: &ToolResult) -> String {
+    let first = result.content.lines().next().unwrap_or_default();
+    if result.is_error { format!("failed: {first}") } else { first.trim_end_matches(':').to_owned() }
+}
+
+/// Apply `plan`; returns one progress line per step.
+fn apply(state: &mut State, plan: &InitPlan) -> Vec<String> {
+    let mut report = Vec::new();
+    if Path::new(CONTEXT_FILE).exists() {
+        report.push(format!("Kept the existing {CONTEXT_FILE}"));
+    } else if let Err(e) = std::fs::write(CONTEXT_FILE, &plan.context_md) {
+        report.push(format!("Could not write {CONTEXT_FILE}: {e}"));
+    } else {
+        report.push(format!("Wrote {CONTEXT_FILE}"));
+    }
+    report.push(outcome(&call(state, "Open", serde_json::json!({ "path": [CONTEXT_FILE] }))));
+    if !plan.memories.is_empty() {
+        report.push(outcome(&call(state, "memory_create", serde_json::json!({ "memories": plan.memories }))));
+    }
+    if !plan.filter_additions.is_empty() {
+        let filter = format!("{}\n{}\n", TreeState::get(state).filter.trim_end(), plan.filter_additions.join("\n"));
+        report.push(outcome(&call(state, "tree_filter", serde_json::json!({ "filter": filter }))));
+    }
+    if !plan.module_changes.is_empty() {
+        let changes: Vec<serde_json::Value> = plan
+            .module_changes
+            .iter()
+            .map(|&(module, on)| serde_json::json!({"module": module, "action": if on { "activate" } else { "deactivate" }}))
+            .collect();
+        report.push(outcome(&call(state, "module_toggle", serde_json::json!({ "changes": changes }))));
+    }
+    report
+}
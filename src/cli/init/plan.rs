@@ -0,0 +1,172 @@
+//! What `init` proposes from a scan: `CONTEXT.md`, memories, tree filter
+//! additions and module activations.
+
+use std::fmt::Write as _;
+
+use cp_mod_memory::types::MemoryState;
+use cp_mod_tree::types::TreeState;
+
+use crate::state::State;
+
+use super::scan::ProjectScan;
+
+/// Optional modules `init` decides on, dependents before their dependencies
+/// (`github` needs `git`).
+const DECIDED_MODULES: &[&str] = &["brave", "firecrawl", "github", "git"];
+
+/// Everything `init` will write or change.
+#[derive(Debug, Clone, Default)]
+pub(super) struct InitPlan {
+    /// Contents of the generated `CONTEXT.md`.
+    pub context_md: String,
+    /// `memory_create` entries not already remembered.
+    pub memories: Vec<serde_json::Value>,
+    /// Tree filter lines to append.
+    pub filter_additions: Vec<String>,
+    /// Modules to activate (`true`) or deactivate (`false`).
+    pub module_changes: Vec<(&'static str, bool)>,
+}
+
+/// Build the plan for `scan` against the current `state`.
+pub(super) fn build(scan: &ProjectScan, state: &State) -> InitPlan {
+    InitPlan {
+        context_md: context_md(scan),
+        memories: memories(scan, state),
+        filter_additions: filter_additions(scan, state),
+        module_changes: module_changes(scan, state),
+    }
+}
+
+/// `Rust (412 files), TypeScript (80 files)`.
+fn language_list(scan: &ProjectScan) -> String {
+    scan.languages.iter().map(|&(name, files)| format!("{name} ({files} files)")).collect::<Vec<_>>().join(", ")
+}
+
+/// A toolchain with its commands, e.g. ``Cargo: build `cargo build`, test `cargo test` ``.
+fn toolchain_line(name: &str, build: Option<&String>, test: Option<&String>) -> String {
+    let mut line = name.to_owned();
+    let commands: Vec<String> = [("build", build), ("test", test)]
+        .into_iter()
+        .filter_map(|(what, command)| command.map(|c| format!("{what} `{c}`")))
+        .collect();
+    if !commands.is_empty() {
+        let _r = write!(line, ": {}", commands.join(", "));
+    }
+    line
+}
+
+/// The generated `CONTEXT.md`.
+fn context_md(scan: &ProjectScan) -> String {
+    let mut out = String::from("# Project context\n\n");
+    out.push_str("Generated by `init`. Edit freely: it is opened as a panel, so the agent reads it every turn.\n");
+    if !scan.languages.is_empty() {
+        let _r = write!(out, "\n## Languages\n\n{}\n", language_list(scan));
+    }
+    if !scan.toolchains.is_empty() {
+        out.push_str("\n## Build and test\n\n");
+        for tool in &scan.toolchains {
+            let line = toolchain_line(tool.name, tool.build.as_ref(), tool.test.as_ref());
+            let _r = writeln!(out, "- {line} (`{}`)", tool.marker);
+        }
+    }
+    if !scan.entry_points.is_empty() {
+        out.push_str("\n## Entry points\n\n");
+        for path in &scan.entry_points {
+            let _r = writeln!(out, "- `{path}`");
+        }
+    }
+    out.push_str("\n## Notes\n\n<!-- Architecture, conventions and gotchas worth knowing before changing code. -->\n");
+    out
+}
+
+/// One memory per toolchain, plus languages and entry points, minus those
+/// already remembered.
+fn memories(scan: &ProjectScan, state: &State) -> Vec<serde_json::Value> {
+    let toolchains = scan
+        .toolchains
+        .iter()
+        .filter(|t| t.build.is_some() || t.test.is_some())
+        .map(|t| (toolchain_line(t.name, t.build.as_ref(), t.test.as_ref()), "high"));
+    let languages = (!scan.languages.is_empty()).then(|| (format!("Languages: {}", language_list(scan)), "medium"));
+    let entries =
+        (!scan.entry_points.is_empty()).then(|| (format!("Entry points: {}", scan.entry_points.join(", ")), "medium"));
+    let known = state.get_ext::<MemoryState>().map(|ms| ms.memories.as_slice()).unwrap_or_default();
+    toolchains
+        .chain(languages)
+        .chain(entries)
+        .filter(|wanted| !known.iter().any(|m| m.tl_dr == wanted.0))
+        .map(|(content, importance)| {
+            serde_json::json!({"content": content, "importance": importance, "labels": ["project", "init"]})
+        })
+        .collect()
+}
+
+/// `.gitignore` directories the tree filter does not hide yet.
+fn filter_additions(scan: &ProjectScan, state: &State) -> Vec<String> {
+    let filter = state.get_ext::<TreeState>().map(|ts| ts.filter.as_str()).unwrap_or_default();
+    let mut additions: Vec<String> = Vec::new();
+    for dir in &scan.ignored_dirs {
+        if !filter.lines().any(|line| line.trim() == dir) && !additions.contains(dir) {
+            additions.push(dir.clone());
+        }
+    }
+    additions
+}
+
+/// Whether `module` fits the repository: git needs a checkout, GitHub a
+/// GitHub remote, web search and scraping an API key.
+fn module_fits(scan: &ProjectScan, module: &str) -> bool {
+    match module {
+        "git" => scan.git,
+        "github" => scan.git && scan.github,
+        "brave" | "firecrawl" => cp_base::config::global::resolve_api_key(module).is_some(),
+        _ => true,
+    }
+}
+
+/// Activation changes that bring the decided modules in line with the repository.
+fn module_changes(scan: &ProjectScan, state: &State) -> Vec<(&'static str, bool)> {
+    let (mut activations, deactivations): (Vec<_>, Vec<_>) = DECIDED_MODULES
+        .iter()
+        .map(|&module| (module, module_fits(scan, module)))
+        .filter(|&(module, fits)| state.active_modules.contains(module) != fits)
+        .partition(|&(_, activate)| activate);
+    // Deactivations run dependents first, activations dependencies first.
+    activations.reverse();
+    deactivations.into_iter().chain(activations).collect()
+}
+
+/// Human-readable summary of `scan` and `plan`, printed before confirmation.
+pub(super) fn describe(scan: &ProjectScan, plan: &InitPlan) -> String {
+    format!("{}\n{}", describe_scan(scan), describe_plan(plan))
+}
+
+/// What the scan found.
+fn describe_scan(scan: &ProjectScan) -> String {
+    let mut out = String::from("Scanned this repository:\n");
+    let languages = if scan.languages.is_empty() { "none detected".to_owned() } else { language_list(scan) };
+    let _r = writeln!(out, "  Languages:    {languages}");
+    for tool in &scan.toolchains {
+        let _r2 =
+            writeln!(out, "  Build system: {}", toolchain_line(tool.name, tool.build.as_ref(), tool.test.as_ref()));
+    }
+    let entries = if scan.entry_points.is_empty() { "none detected".to_owned() } else { scan.entry_points.join(", ") };
+    let _r3 = writeln!(out, "  Entry points: {entries}");
+    out
+}
+
+/// What applying `plan` will do.
+fn describe_plan(plan: &InitPlan) -> String {
+    let mut out = String::from("Proposed setup:\n  Write CONTEXT.md (if missing) and open it as a panel\n");
+    for memory in &plan.memories {
+        let content = memory.get("content").and_then(serde_json::Value::as_str).unwrap_or_default();
+        let _r = writeln!(out, "  Remember:     {content}");
+    }
+    if !plan.filter_additions.is_empty() {
+        let _r2 = writeln!(out, "  Hide in tree: {}", plan.filter_additions.join(" "));
+    }
+    for &(module, activate) in &plan.module_changes {
+        let _r3 = writeln!(out, "  {} module: {module}", if activate { "Enable " } else { "Disable" });
+    }
+    out
+}
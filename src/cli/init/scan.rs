@@ -0,0 +1,276 @@
+//! Repository scan: languages, build systems, test commands, entry points.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Deepest directory level visited when counting source files.
+const MAX_DEPTH: usize = 8;
+/// Files counted before the walk stops (keeps `init` instant on huge trees).
+const MAX_FILES: usize = 20_000;
+
+/// Directories never descended into (generated or vendored content).
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "vendor", "venv", "__pycache__", "out"];
+
+/// Source extension → language name.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("py", "Python"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cpp", "C++"),
+    ("cc", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("swift", "Swift"),
+    ("scala", "Scala"),
+    ("sh", "Shell"),
+    ("lua", "Lua"),
+    ("zig", "Zig"),
+    ("ex", "Elixir"),
+    ("hs", "Haskell"),
+    ("typ", "Typst"),
+];
+
+/// Files that usually hold a program's entry point.
+const ENTRY_CANDIDATES: &[&str] = &[
+    "src/main.rs",
+    "src/lib.rs",
+    "main.go",
+    "main.py",
+    "app.py",
+    "manage.py",
+    "src/main.ts",
+    "src/index.ts",
+    "src/index.tsx",
+    "src/index.js",
+    "index.js",
+    "src/main.c",
+    "src/main.cpp",
+    "main.c",
+];
+
+/// One build system found at the repository root.
+#[derive(Debug, Clone)]
+pub(super) struct Toolchain {
+    /// Build system name (e.g. "Cargo").
+    pub name: &'static str,
+    /// Marker file that revealed it.
+    pub marker: &'static str,
+    /// Build command, when one is known.
+    pub build: Option<String>,
+    /// Test command, when one is known.
+    pub test: Option<String>,
+}
+
+/// What `init` learned about the repository.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ProjectScan {
+    /// Languages by number of source files, most used first.
+    pub languages: Vec<(&'static str, usize)>,
+    /// Build systems, in detection order.
+    pub toolchains: Vec<Toolchain>,
+    /// Entry-point files that exist.
+    pub entry_points: Vec<String>,
+    /// Directory patterns from `.gitignore` (e.g. `coverage/`).
+    pub ignored_dirs: Vec<String>,
+    /// Whether the repository is a git checkout.
+    pub git: bool,
+    /// Whether a remote points at GitHub.
+    pub github: bool,
+}
+
+/// Scan the repository rooted at `root`.
+pub(super) fn scan(root: &Path) -> ProjectScan {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut budget = MAX_FILES;
+    count_languages(root, 0, &mut counts, &mut budget);
+    let mut languages: Vec<(&'static str, usize)> = counts.into_iter().collect();
+    languages.sort_by_key(|entry| std::cmp::Reverse(entry.1));
+
+    let git_config = fs::read_to_string(root.join(".git").join("config")).unwrap_or_default();
+    ProjectScan {
+        languages,
+        toolchains: toolchains(root),
+        entry_points: entry_points(root),
+        ignored_dirs: ignored_dirs(root),
+        git: root.join(".git").exists(),
+        github: git_config.contains("github.com"),
+    }
+}
+
+/// Count source files per language below `dir`, skipping hidden and generated directories.
+fn count_languages(dir: &Path, depth: usize, counts: &mut BTreeMap<&'static str, usize>, budget: &mut usize) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        if *budget == 0 {
+            return;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if is_dir {
+            if depth < MAX_DEPTH && !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                count_languages(&entry.path(), depth.saturating_add(1), counts, budget);
+            }
+            continue;
+        }
+        *budget = budget.saturating_sub(1);
+        if let Some(language) = language_of(&name) {
+            let count = counts.entry(language).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+    }
+}
+
+/// Language of a file, by extension.
+fn language_of(file_name: &str) -> Option<&'static str> {
+    let (_, ext) = file_name.rsplit_once('.')?;
+    LANGUAGES.iter().find(|entry| entry.0 == ext).map(|entry| entry.1)
+}
+
+/// Looks for one build system at the repository root.
+type Detector = fn(&Path) -> Option<Toolchain>;
+
+/// Build-system detectors, in report order.
+const DETECTORS: &[Detector] = &[cargo, node, python, go, maven, gradle, cmake, make];
+
+/// Build systems whose marker file sits at the root.
+fn toolchains(root: &Path) -> Vec<Toolchain> {
+    DETECTORS.iter().filter_map(|detect| detect(root)).collect()
+}
+
+/// A toolchain with fixed commands (an empty command means none is known).
+fn toolchain(name: &'static str, marker: &'static str, build: &str, test: &str) -> Toolchain {
+    Toolchain {
+        name,
+        marker,
+        build: Some(build.to_owned()).filter(|b| !b.is_empty()),
+        test: Some(test.to_owned()).filter(|t| !t.is_empty()),
+    }
+}
+
+/// Cargo; a workspace tests every member.
+fn cargo(root: &Path) -> Option<Toolchain> {
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).ok()?;
+    let test = if manifest.contains("[workspace]") { "cargo test --workspace" } else { "cargo test" };
+    Some(toolchain("Cargo", "Cargo.toml", "cargo build", test))
+}
+
+/// npm, pnpm or yarn.
+fn node(root: &Path) -> Option<Toolchain> {
+    let package = fs::read_to_string(root.join("package.json")).ok()?;
+    Some(node_toolchain(root, &package))
+}
+
+/// Python (`pyproject.toml` or `setup.py`), tested with pytest.
+fn python(root: &Path) -> Option<Toolchain> {
+    let marker = ["pyproject.toml", "setup.py"].into_iter().find(|m| root.join(m).exists())?;
+    Some(toolchain("Python", marker, "", "pytest"))
+}
+
+/// Go modules.
+fn go(root: &Path) -> Option<Toolchain> {
+    root.join("go.mod").exists().then(|| toolchain("Go", "go.mod", "go build ./...", "go test ./..."))
+}
+
+/// Maven.
+fn maven(root: &Path) -> Option<Toolchain> {
+    root.join("pom.xml").exists().then(|| toolchain("Maven", "pom.xml", "mvn package", "mvn test"))
+}
+
+/// Gradle, through the wrapper when the repository ships one.
+fn gradle(root: &Path) -> Option<Toolchain> {
+    let marker = ["build.gradle", "build.gradle.kts"].into_iter().find(|m| root.join(m).exists())?;
+    let gradle = if root.join("gradlew").exists() { "./gradlew" } else { "gradle" };
+    Some(toolchain("Gradle", marker, &format!("{gradle} build"), &format!("{gradle} test")))
+}
+
+/// `CMake` with an out-of-tree `build/` directory.
+fn cmake(root: &Path) -> Option<Toolchain> {
+    root.join("CMakeLists.txt").exists().then(|| {
+        toolchain("CMake", "CMakeLists.txt", "cmake -B build && cmake --build build", "ctest --test-dir build")
+    })
+}
+
+/// Make; `make test` only when the target exists.
+fn make(root: &Path) -> Option<Toolchain> {
+    let makefile = fs::read_to_string(root.join("Makefile")).ok()?;
+    let test = if has_make_target(&makefile, "test") { "make test" } else { "" };
+    Some(toolchain("Make", "Makefile", "make", test))
+}
+
+/// npm / pnpm / yarn, with the `build` and `test` scripts `package.json` defines.
+fn node_toolchain(root: &Path, package: &str) -> Toolchain {
+    let runner = if root.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if root.join("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    };
+    let json: serde_json::Value = serde_json::from_str(package).unwrap_or_default();
+    let has_script = |name: &str| json.get("scripts").and_then(|s| s.get(name)).is_some();
+    Toolchain {
+        name: runner,
+        marker: "package.json",
+        build: has_script("build").then(|| format!("{runner} run build")),
+        test: has_script("test").then(|| format!("{runner} test")),
+    }
+}
+
+/// Whether `makefile` defines `target`.
+fn has_make_target(makefile: &str, target: &str) -> bool {
+    makefile.lines().any(|line| line.split_once(':').is_some_and(|(name, _)| name.trim() == target))
+}
+
+/// Entry-point candidates that exist, plus the `main` of `package.json`.
+fn entry_points(root: &Path) -> Vec<String> {
+    let mut found: Vec<String> =
+        ENTRY_CANDIDATES.iter().filter(|path| root.join(path).is_file()).map(|path| (*path).to_owned()).collect();
+    let package: serde_json::Value = fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+    if let Some(main) = package.get("main").and_then(serde_json::Value::as_str)
+        && !found.iter().any(|path| path == main)
+    {
+        found.push(main.to_owned());
+    }
+    found
+}
+
+/// Directory patterns (`name/`) listed in the root `.gitignore`.
+fn ignored_dirs(root: &Path) -> Vec<String> {
+    let gitignore = fs::read_to_string(root.join(".gitignore")).unwrap_or_default();
+    gitignore
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.ends_with('/') && !line.starts_with('#') && !line.starts_with('!'))
+        .map(|line| line.trim_start_matches('/').to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_targets_and_languages_are_recognized() {
+        let makefile = "all: build\n\ntest: all\n\tcargo test\n";
+        assert!(has_make_target(makefile, "test"));
+        assert!(!has_make_target(makefile, "lint"));
+        assert_eq!(language_of("panel.rs"), Some("Rust"));
+        assert_eq!(language_of("App.tsx"), Some("TypeScript"));
+        assert_eq!(language_of("README"), None);
+    }
+}
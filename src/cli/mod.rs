@@ -0,0 +1,203 @@
+//! Subcommands that run once and exit, without the TUI.
+//!
+//! - [`init`] scans the repository and sets the project up.
+//! - [`doctor`] diagnoses the environment and prints actionable fixes.
+//! - [`experiment`] alternates two seed prompts across sessions and compares them.
+//! - [`bench`] (`--bench-ui`) times headless renders against frame budgets.
+//! - [`attach`] `--keys` prints the tmux bindings for the attach mode.
+//! - `pair [SOCKET]` joins a session shared with `--pair` (see [`cp_pair`]).
+//!
+//! [`Startup`] gathers what the TUI is launched on: the sibling tmux panes of
+//! `attach` ([`attach`]), files named on the command line and `--prompt`
+//! ([`open`]), piped text from `--attach-stdin` ([`stdin`]), the
+//! `--control` HTTP server, the `--serve` web viewer, the `--pair` socket and
+//! the `--mirror` transcript.
+
+/// `attach`: run next to the editor in a tmux split or popup.
+mod attach;
+/// `--bench-ui`: frame-time budgets on synthetic sessions.
+mod bench;
+/// `doctor`: environment diagnostics.
+mod doctor;
+/// `experiment`: system prompt A/B tests.
+mod experiment;
+/// `init`: first-run setup.
+mod init;
+/// File arguments and `--prompt`: panels and first message at startup.
+mod open;
+/// `--attach-stdin`: piped text as the session's first panel.
+mod stdin;
+
+use std::io::{self, Write as _};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use cp_mod_spine::types::{NotificationType, SpineState};
+
+use crate::app::App;
+use crate::infra::mirror::{FLAG as MIRROR_FLAG, Mirror};
+
+/// Run the subcommand named by `args[1]`, or `None` to start the TUI.
+pub(crate) fn run(args: &[String]) -> Option<ExitCode> {
+    if args.iter().any(|a| a == bench::FLAG) {
+        return Some(bench::run(args));
+    }
+    match args.get(1).map(String::as_str) {
+        Some("init") => Some(init::run(args)),
+        Some("doctor") => Some(doctor::run(args)),
+        Some("experiment") => Some(experiment::run(args)),
+        Some(attach::SUBCOMMAND) if args.iter().any(|a| a == attach::KEYS_FLAG) => Some(attach::print_keys(args)),
+        Some(cp_pair::SUBCOMMAND) => Some(join_pair(args)),
+        Some(_) | None => None,
+    }
+}
+
+/// Run `tui pair [SOCKET]`: mirror a session shared with `--pair`, by
+/// default the one in this directory.
+fn join_pair(args: &[String]) -> ExitCode {
+    let socket = args.get(2).map_or_else(cp_pair::default_socket, PathBuf::from);
+    match cp_pair::guest::join(&socket) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            drop(writeln!(io::stderr(), "{e}"));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// What the session starts on, read from the command line before the
+/// terminal is taken over.
+pub(crate) struct Startup {
+    /// Sibling tmux panes, for `attach`.
+    attach: Option<attach::Attach>,
+    /// Files to open and the first prompt.
+    launch: open::Launch,
+    /// Text piped in with `--attach-stdin`.
+    piped: Option<stdin::Piped>,
+    /// The control server, bound with `--control`.
+    control: Option<cp_control::Control>,
+    /// The web viewer, bound with `--serve`.
+    viewer: Option<cp_control::viewer::Viewer>,
+    /// The pairing socket, bound with `--pair`.
+    pair: Option<cp_pair::Host>,
+    /// The transcript, opened with `--mirror`.
+    mirror: Option<Mirror>,
+}
+
+impl Startup {
+    /// List the tmux panes (moving to the editor's directory), check the file
+    /// arguments, read the pipe and bind the control server and the pairing
+    /// socket. Errors are meant for stderr.
+    pub(crate) fn parse(args: &[String]) -> Result<Self, String> {
+        let attach = attach::parse(args)?;
+        Ok(Self {
+            attach,
+            launch: open::parse(args)?,
+            piped: stdin::read(args)?,
+            control: start_control(args)?,
+            viewer: start_viewer(args)?,
+            pair: start_pair(args)?,
+            mirror: Mirror::start(args)?,
+        })
+    }
+
+    /// Register the tmux panes, open the files, attach the piped text, then
+    /// fill or send the prompt. Hands the control server, the web viewer
+    /// (noting its URL), the pairing socket and the transcript to the app.
+    pub(crate) fn apply(mut self, app: &mut App) {
+        if let Some(mirror) = self.mirror.as_mut() {
+            mirror.skip(app.state.messages.len());
+        }
+        let state = &mut app.state;
+        if let Some(attach) = self.attach {
+            attach::apply(state, attach);
+        }
+        if let Some(piped) = self.piped {
+            stdin::attach(state, piped);
+        }
+        open::apply(state, self.launch);
+        if let Some(host) = self.pair.as_ref() {
+            state.set_ext(host.status());
+        }
+        if let Some(viewer) = self.viewer.as_ref() {
+            let content = format!("Web viewer at {}", viewer.url());
+            let id = SpineState::create_notification(state, NotificationType::Custom, "serve".to_owned(), content);
+            let _processed = SpineState::mark_notification_processed(state, &id);
+        }
+        app.control = self.control;
+        app.viewer = self.viewer;
+        app.pair = self.pair;
+        app.mirror = self.mirror;
+    }
+}
+
+/// Start the control server for `--control`, `--control=PORT` or
+/// `--control=HOST:PORT`; `None` without the flag.
+fn start_control(args: &[String]) -> Result<Option<cp_control::Control>, String> {
+    args.iter()
+        .find_map(|a| if a == cp_control::FLAG { Some(None) } else { a.strip_prefix("--control=").map(Some) })
+        .map(cp_control::start)
+        .transpose()
+}
+
+/// Start the web viewer for `--serve`, `--serve=PORT` or `--serve=HOST:PORT`;
+/// `None` without the flag.
+fn start_viewer(args: &[String]) -> Result<Option<cp_control::viewer::Viewer>, String> {
+    use cp_control::viewer;
+    args.iter()
+        .find_map(|a| if a == viewer::FLAG { Some(None) } else { a.strip_prefix("--serve=").map(Some) })
+        .map(viewer::start)
+        .transpose()
+}
+
+/// Start sharing for `--pair` or `--pair=turns`; `None` without the flag.
+fn start_pair(args: &[String]) -> Result<Option<cp_pair::Host>, String> {
+    args.iter()
+        .find_map(|a| if a == cp_pair::FLAG { Some(None) } else { a.strip_prefix("--pair=").map(Some) })
+        .map(cp_pair::start)
+        .transpose()
+}
+
+/// The arguments kept across a reload: session-wide flags only. Files,
+/// prompts and piped input apply to the first launch.
+pub(crate) fn reload_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    args.filter(|a| {
+        a == "--bridge"
+            || a.starts_with("--colors=")
+            || a == cp_control::FLAG
+            || a.starts_with("--control=")
+            || a == cp_control::viewer::FLAG
+            || a.starts_with("--serve=")
+            || a == cp_pair::FLAG
+            || a.starts_with("--pair=")
+            || a == MIRROR_FLAG
+            || a.starts_with("--mirror=")
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_keeps_only_session_flags() {
+        let args = [
+            "src/a.rs",
+            "--bridge",
+            "--prompt",
+            "go",
+            "--attach-stdin",
+            "explain",
+            "--colors=256",
+            "--control=8080",
+            "--serve=8081",
+            "--pair=turns",
+            "--mirror",
+        ];
+        assert_eq!(
+            reload_args(args.iter().map(|&a| a.to_owned())),
+            vec!["--bridge", "--colors=256", "--control=8080", "--serve=8081", "--pair=turns", "--mirror"]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Open-at-path launch arguments:
+//! `tui src/foo.rs src/bar.rs --prompt "refactor the parser" [--send]`.
+//!
+//! Every positional argument is a file, opened as a File panel once the
+//! session is up; a missing file stops the launch before the terminal is taken
+//! over. `--prompt` fills the draft, and `--send` sends it right away.
+
+use std::path::Path;
+
+use crate::infra::tools::ToolUse;
+use crate::state::State;
+
+/// Files to open and the first prompt.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Launch {
+    /// Paths given on the command line, in order.
+    files: Vec<String>,
+    /// Draft text from `--prompt`.
+    prompt: Option<String>,
+    /// `--send`: submit the prompt instead of leaving it in the draft.
+    send: bool,
+}
+
+/// Collect the files and the prompt from `args` (program name first). Errors
+/// name the missing file or the misused flag.
+pub(crate) fn parse(args: &[String]) -> Result<Launch, String> {
+    let mut launch = Launch::default();
+    let mut rest = args.iter().skip(1).peekable();
+    let _attach = rest.next_if(|a| *a == super::attach::SUBCOMMAND);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--prompt" => launch.prompt = Some(rest.next().cloned().ok_or("--prompt needs a text")?),
+            "--send" => launch.send = true,
+            super::stdin::FLAG => drop(rest.next_if(|a| !a.starts_with("--"))),
+            super::attach::FROM_FLAG => drop(rest.next()),
+            flag if flag.starts_with("--") => {}
+            path => launch.files.push(existing_file(path)?),
+        }
+    }
+    if launch.send && launch.prompt.is_none() {
+        return Err("--send needs --prompt".to_owned());
+    }
+    Ok(launch)
+}
+
+/// `path` when it names a file.
+fn existing_file(path: &str) -> Result<String, String> {
+    if Path::new(path).is_file() { Ok(path.to_owned()) } else { Err(format!("{path}: no such file")) }
+}
+
+/// Open the files, then fill the draft (and send it with `--send`).
+pub(crate) fn apply(state: &mut State, launch: Launch) {
+    if !launch.files.is_empty() {
+        let tool = ToolUse::new("cli_open".to_owned(), "Open".to_owned(), serde_json::json!({ "path": launch.files }));
+        let _r = crate::infra::tools::execute_tool(&tool, state);
+    }
+    let Some(prompt) = launch.prompt else { return };
+    state.input = prompt;
+    state.input_cursor = state.input.len();
+    if launch.send {
+        let _r = crate::app::actions::input::handle_input_submit(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|&a| a.to_owned()).collect()
+    }
+
+    #[test]
+    fn files_and_prompt_are_collected_around_other_flags() {
+        let launch = parse(&args(&["tui", "Cargo.toml", "--bridge", "--prompt", "refactor", "--send"]));
+        assert_eq!(
+            launch,
+            Ok(Launch { files: vec!["Cargo.toml".to_owned()], prompt: Some("refactor".to_owned()), send: true })
+        );
+    }
+
+    #[test]
+    fn stdin_prompt_is_not_a_file_and_missing_files_fail() {
+        assert_eq!(parse(&args(&["tui", "--attach-stdin", "explain"])), Ok(Launch::default()));
+        assert_eq!(parse(&args(&["tui", "attach", "--from", "%3"])), Ok(Launch::default()));
+        assert_eq!(parse(&args(&["tui", "no/such/file.rs"])), Err("no/such/file.rs: no such file".to_owned()));
+        assert_eq!(parse(&args(&["tui", "--send"])), Err("--send needs --prompt".to_owned()));
+    }
+}
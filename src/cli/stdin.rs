@@ -0,0 +1,69 @@
+//! `--attach-stdin [PROMPT]`: start the TUI on text piped from the shell.
+//!
+//! `cargo build 2>&1 | tui --attach-stdin "explain these errors"` reads the
+//! piped text before the terminal is taken over, puts it in a Pasted Content
+//! panel once the session is up, and sends the prompt (with a reference to
+//! the panel) as the first message. Keyboard input then comes from the
+//! controlling terminal, not stdin.
+
+use std::io::{self, IsTerminal as _, Read as _};
+
+use crate::state::State;
+
+/// The flag.
+pub(super) const FLAG: &str = "--attach-stdin";
+
+/// Text read from stdin and the prompt to send with it.
+pub(crate) struct Piped {
+    /// Everything read from stdin.
+    content: String,
+    /// First message, if one was given after the flag.
+    prompt: Option<String>,
+}
+
+/// The prompt following the flag at `pos`, if one was given.
+fn prompt_after(args: &[String], pos: usize) -> Option<String> {
+    args.get(pos.saturating_add(1)).filter(|a| !a.starts_with("--")).cloned()
+}
+
+/// Read stdin when `--attach-stdin` was given. `Ok(None)` without the flag;
+/// an error when nothing is piped or the pipe is empty.
+pub(crate) fn read(args: &[String]) -> Result<Option<Piped>, String> {
+    let Some(pos) = args.iter().position(|a| a == FLAG) else { return Ok(None) };
+    if io::stdin().is_terminal() {
+        return Err(format!("{FLAG}: nothing is piped in (try `some_command | tui {FLAG} \"explain this\"`)"));
+    }
+    let mut bytes = Vec::new();
+    let _n = io::stdin().read_to_end(&mut bytes).map_err(|e| format!("{FLAG}: cannot read stdin: {e}"))?;
+    let content = String::from_utf8_lossy(&bytes).replace("\r\n", "\n");
+    if content.trim().is_empty() {
+        return Err(format!("{FLAG}: stdin was empty"));
+    }
+    Ok(Some(Piped { content, prompt: prompt_after(args, pos) }))
+}
+
+/// Put the piped text in a panel and send the prompt, which starts the first
+/// stream.
+pub(crate) fn attach(state: &mut State, piped: Piped) {
+    let reference = crate::modules::pasted::attach_stdin(state, &piped.content);
+    let Some(prompt) = piped.prompt else { return };
+    state.input = format!("{prompt}\n\n{reference}");
+    state.input_cursor = state.input.len();
+    let _r = crate::app::actions::input::handle_input_submit(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|&a| a.to_owned()).collect()
+    }
+
+    #[test]
+    fn prompt_follows_the_flag_unless_it_is_another_flag() {
+        assert_eq!(prompt_after(&args(&["tui", FLAG, "explain"]), 1), Some("explain".to_owned()));
+        assert_eq!(prompt_after(&args(&["tui", FLAG, "--bridge"]), 1), None);
+        assert_eq!(prompt_after(&args(&["tui", FLAG]), 1), None);
+    }
+}
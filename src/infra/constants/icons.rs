@@ -27,6 +27,10 @@ pub(crate) fn msg_error() -> String {
 pub(crate) fn status_full() -> String {
     normalize_icon(&active_theme().status.full)
 }
+/// Icon for compressed (head/tail or reference) status indicator (normalized to 2 cells).
+pub(crate) fn status_summarized() -> String {
+    normalize_icon(&active_theme().status.summarized)
+}
 /// Icon for deleted status indicator (normalized to 2 cells).
 pub(crate) fn status_deleted() -> String {
     normalize_icon(&active_theme().status.deleted)
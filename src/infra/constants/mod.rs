@@ -34,6 +34,20 @@ pub(crate) const SCROLL_ACCEL_INCREMENT: f32 = 0.3;
 /// Maximum scroll acceleration multiplier
 pub(crate) const SCROLL_ACCEL_MAX: f32 = 2.5;
 
+// =============================================================================
+// TEXT INPUT
+// =============================================================================
+
+/// Single-line pastes up to this many characters are typed in place rather
+/// than stashed behind a paste placeholder (IME commits arrive as pastes).
+pub(crate) const INLINE_PASTE_MAX_CHARS: usize = 64;
+
+/// Undo units kept for the input draft (oldest dropped first)
+pub(crate) const INPUT_UNDO_LIMIT: usize = 200;
+
+/// Keystrokes further apart than this start a new undo unit (ms)
+pub(crate) const INPUT_UNDO_COALESCE_MS: u64 = 1_000;
+
 // =============================================================================
 // TYPEWRITER EFFECT
 // =============================================================================
@@ -70,6 +84,12 @@ pub(crate) const EVENT_POLL_MS: u64 = 8;
 /// Minimum time between renders (ms) - caps at ~28fps
 pub(crate) const RENDER_THROTTLE_MS: u64 = 36;
 
+/// Poll interval when nothing is happening (ms) — input still wakes the loop at once
+pub(crate) const IDLE_POLL_MS: u64 = 250;
+
+/// Longest gap between renders while streaming, however expensive frames get (ms)
+pub(crate) const STREAM_RENDER_MAX_MS: u64 = 120;
+
 /// Interval for CPU/RAM stats refresh in perf overlay (ms)
 pub(crate) const PERF_STATS_REFRESH_MS: u64 = 500;
 
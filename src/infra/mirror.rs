@@ -0,0 +1,176 @@
+//! Transcript mirror: the conversation, appended to a file as it streams.
+//!
+//! Started with `--mirror` (`.context-pilot/transcript.md`) or
+//! `--mirror=PATH`. Messages are written as markdown — a heading per user or
+//! assistant message, then its text as it streams in; tool calls and results
+//! as one quoted line each — so `tail -f` in another terminal, or anything
+//! that follows the file, shows the session live. Messages from before the
+//! launch are not written.
+
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+
+use crate::infra::constants::STORE_DIR;
+use crate::state::{Message, MsgKind};
+
+/// The command-line flag.
+pub(crate) const FLAG: &str = "--mirror";
+/// Transcript file under the store directory, without a path.
+const DEFAULT_FILE: &str = "transcript.md";
+/// Longest tool input or result summary, in characters.
+const SUMMARY_CHARS: usize = 160;
+
+/// How far the transcript got.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Cursor {
+    /// Message being written.
+    index: usize,
+    /// Bytes of its content written (`None`: heading not written yet).
+    text: Option<usize>,
+    /// Tool calls of it written.
+    tool_uses: usize,
+    /// Tool results of it written.
+    tool_results: usize,
+}
+
+/// The open transcript.
+pub(crate) struct Mirror {
+    /// Appended to on every sync.
+    file: File,
+    /// Where the last sync stopped.
+    cursor: Cursor,
+}
+
+impl Mirror {
+    /// Open (appending) the transcript for `--mirror` or `--mirror=PATH`;
+    /// `None` without the flag.
+    pub(crate) fn start(args: &[String]) -> Result<Option<Self>, String> {
+        let Some(path) = args.iter().find_map(|a| {
+            if a == FLAG {
+                Some(Path::new(STORE_DIR).join(DEFAULT_FILE))
+            } else {
+                a.strip_prefix("--mirror=").map(PathBuf::from)
+            }
+        }) else {
+            return Ok(None);
+        };
+        let open = || -> io::Result<File> {
+            if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let started = cp_mod_utilities::time::now_utc_rfc3339_secs();
+            file.write_all(format!("\n# Session {started}\n").as_bytes())?;
+            Ok(file)
+        };
+        let file = open().map_err(|e| format!("Cannot open transcript {}: {e}", path.display()))?;
+        Ok(Some(Self { file, cursor: Cursor::default() }))
+    }
+
+    /// Skip the `count` messages already in the conversation.
+    pub(crate) fn skip(&mut self, count: usize) {
+        self.cursor = Cursor { index: count, ..Cursor::default() };
+    }
+
+    /// Append what changed in `messages` since the last sync. Write errors
+    /// are ignored: the session goes on without its mirror.
+    pub(crate) fn sync(&mut self, messages: &[Message]) {
+        let text = advance(&mut self.cursor, messages);
+        if !text.is_empty() {
+            let _r = self.file.write_all(text.as_bytes());
+        }
+    }
+}
+
+/// `text` on one line, cut to [`SUMMARY_CHARS`].
+fn summarize(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() > SUMMARY_CHARS {
+        format!("{}\u{2026}", flat.chars().take(SUMMARY_CHARS).collect::<String>())
+    } else {
+        flat
+    }
+}
+
+/// The transcript of `msg` past `cursor`, moving the cursor to its end.
+fn write_message(out: &mut String, cursor: &mut Cursor, msg: &Message) {
+    if msg.msg_type == MsgKind::TextMessage {
+        let written = cursor.text.unwrap_or_else(|| {
+            let who = if msg.role == "user" { "User" } else { "Assistant" };
+            let _r = write!(out, "\n## {who} \u{b7} {}\n\n", msg.id);
+            0
+        });
+        // Content rewritten rather than appended to is not mirrored again.
+        if let Some(new) = msg.content.get(written..) {
+            out.push_str(new);
+            cursor.text = Some(msg.content.len());
+        } else {
+            cursor.text = Some(written);
+        }
+    }
+    for tool in msg.tool_uses.iter().skip(cursor.tool_uses) {
+        let _r = writeln!(out, "\n> tool `{}` {}", tool.name, summarize(&tool.input.to_string()));
+        cursor.tool_uses = cursor.tool_uses.saturating_add(1);
+    }
+    for result in msg.tool_results.iter().skip(cursor.tool_results) {
+        let status = if result.is_error { "error" } else { "result" };
+        let lines = result.content.lines().count();
+        let first = summarize(result.content.lines().next().unwrap_or(""));
+        let _r = writeln!(out, "> {status} `{}` ({lines} lines): {first}", result.tool_name);
+        cursor.tool_results = cursor.tool_results.saturating_add(1);
+    }
+}
+
+/// Everything in `messages` past `cursor`; the cursor stays on the last
+/// message, which may still grow.
+fn advance(cursor: &mut Cursor, messages: &[Message]) -> String {
+    if cursor.index > messages.len() {
+        // The conversation was cleared.
+        *cursor = Cursor { index: messages.len(), ..Cursor::default() };
+    }
+    let mut out = String::new();
+    for (index, msg) in messages.iter().enumerate().skip(cursor.index) {
+        if index != cursor.index {
+            if cursor.text.is_some_and(|written| written > 0) {
+                out.push('\n');
+            }
+            *cursor = Cursor { index, ..Cursor::default() };
+        }
+        write_message(&mut out, cursor, msg);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ToolUseRecord;
+
+    /// A text message of `role`.
+    fn text(id: &str, role: &str, content: &str) -> Message {
+        Message { id: id.to_owned(), role: role.to_owned(), content: content.to_owned(), ..Message::default() }
+    }
+
+    #[test]
+    fn streamed_text_is_appended_once() {
+        let mut cursor = Cursor::default();
+        let mut messages = vec![text("U1", "user", "fix it"), text("A1", "assistant", "Look")];
+        assert_eq!(advance(&mut cursor, &messages), "\n## User \u{b7} U1\n\nfix it\n\n## Assistant \u{b7} A1\n\nLook");
+        if let Some(last) = messages.last_mut() {
+            last.content.push_str("ing.");
+        }
+        assert_eq!(advance(&mut cursor, &messages), "ing.");
+        assert_eq!(advance(&mut cursor, &messages), "");
+    }
+
+    #[test]
+    fn tool_calls_are_one_quoted_line() {
+        let mut cursor = Cursor::default();
+        let mut call = Message { msg_type: MsgKind::ToolCall, role: "assistant".to_owned(), ..Message::default() };
+        let input = serde_json::json!({ "path": "src/a.rs" });
+        call.tool_uses.push(ToolUseRecord::new("t1".to_owned(), "Open".to_owned(), input));
+        assert_eq!(advance(&mut cursor, &[call]), "\n> tool `Open` {\"path\":\"src/a.rs\"}\n");
+    }
+}
@@ -6,9 +6,13 @@ pub(crate) mod config;
 pub(crate) mod constants;
 /// Flame graph telemetry — thin re-export from `cp_base::flame`.
 pub(crate) mod flame;
+/// Transcript mirror (`--mirror`): the conversation appended to a file live.
+pub(crate) mod mirror;
 /// Simple profiler for identifying slow operations.
 pub(crate) mod profiler;
 /// Tool definition helpers.
 pub(crate) mod tools;
+/// Voice input: push-to-talk recording transcribed into the draft.
+pub(crate) mod voice;
 /// File-system watcher for detecting changes to open files.
 pub(crate) mod watcher;
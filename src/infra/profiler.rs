@@ -24,12 +24,18 @@ pub(crate) struct ProfileGuard {
     name: &'static str,
     /// Instant when the guard was created.
     start: Instant,
+    /// Charged to the profile history (recording was on when it opened).
+    tracked: bool,
 }
 
 impl ProfileGuard {
     /// Create a new profile guard for the given operation name.
     pub(crate) fn new(name: &'static str) -> Self {
-        Self { name, start: Instant::now() }
+        let tracked = crate::ui::perf::history::is_recording();
+        if tracked {
+            crate::ui::perf::history::enter(name);
+        }
+        Self { name, start: Instant::now(), tracked }
     }
 }
 
@@ -41,6 +47,9 @@ impl Drop for ProfileGuard {
 
         // Always record to in-memory perf system
         crate::ui::perf::PERF.record_op(self.name, us);
+        if self.tracked {
+            crate::ui::perf::history::leave(us);
+        }
 
         // Log to file only for slow operations
         if u128::from(ms) >= THRESHOLD_MS
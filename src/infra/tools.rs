@@ -8,10 +8,16 @@ use crate::state::State;
 pub(crate) use crate::modules::conversation::refresh::refresh_conversation_context;
 
 /// Execute a tool and return the result.
-/// Delegates to the module system for dispatch.
+/// Delegates to the module system for dispatch, then records file edits,
+/// commands and commits in the actions ledger.
 pub(crate) fn execute_tool(tool: &ToolUse, state: &mut State) -> ToolResult {
     let active_modules = state.active_modules.clone();
-    crate::modules::dispatch_tool(tool, state, &active_modules)
+    let result = crate::modules::dispatch_tool(tool, state, &active_modules);
+    cp_mod_ledger::record(state, tool, &result);
+    for module in crate::modules::all_modules().iter().filter(|m| active_modules.contains(m.id())) {
+        module.on_tool_executed(tool, &result, state);
+    }
+    result
 }
 
 /// Execute `reload_tui` tool (public for module access)
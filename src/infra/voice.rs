@@ -0,0 +1,314 @@
+//! Voice input: dictate into the draft with Ctrl+R.
+//!
+//! Ctrl+R starts recording the microphone (ALSA `arecord`, else `SoX` `rec`)
+//! into a temporary WAV; Ctrl+R again stops it and hands the file to the
+//! transcription backend on a background thread. The transcript is typed at
+//! the cursor when it arrives. Terminals do not report key releases reliably,
+//! so push-to-talk is a toggle rather than hold-to-talk.
+//!
+//! The backend comes from the environment, read when recording starts:
+//!
+//! - `CP_VOICE_BACKEND=openai` — the `OpenAI` transcription API with
+//!   `OPENAI_API_KEY`; `CP_VOICE_MODEL` overrides `whisper-1` and
+//!   `OPENAI_BASE_URL` points it at any compatible server.
+//! - `CP_VOICE_BACKEND=whisper.cpp` — a local `whisper-cli` (or
+//!   `CP_WHISPER_BIN`) with the ggml model at `CP_WHISPER_MODEL`.
+//!
+//! Without `CP_VOICE_BACKEND`, a set `CP_WHISPER_MODEL` picks whisper.cpp and
+//! anything else the API.
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use cp_mod_spine::types::{NotificationType, SpineState};
+
+use crate::state::State;
+
+/// Recorders tried in order: program and its arguments before the WAV path.
+/// Both record 16 kHz mono 16-bit, which whisper.cpp requires.
+const RECORDERS: &[(&str, &[&str])] = &[
+    ("arecord", &["-q", "-f", "S16_LE", "-r", "16000", "-c", "1", "-t", "wav"]),
+    ("rec", &["-q", "-r", "16000", "-c", "1", "-b", "16"]),
+];
+
+/// API endpoint base when `OPENAI_BASE_URL` is unset.
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+/// API model when `CP_VOICE_MODEL` is unset.
+const OPENAI_MODEL: &str = "whisper-1";
+/// whisper.cpp program when `CP_WHISPER_BIN` is unset.
+const WHISPER_BIN: &str = "whisper-cli";
+/// Longest wait for a transcription request.
+const REQUEST_TIMEOUT: Duration = Duration::from_mins(2);
+/// A WAV no larger than this holds a header and no audio.
+const EMPTY_WAV_BYTES: u64 = 1_024;
+
+/// Where a recording is transcribed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Backend {
+    /// An `OpenAI`-compatible `/audio/transcriptions` endpoint.
+    OpenAi {
+        /// Endpoint base, without a trailing slash.
+        base_url: String,
+        /// Bearer token.
+        api_key: String,
+        /// Transcription model.
+        model: String,
+    },
+    /// A local whisper.cpp build.
+    WhisperCpp {
+        /// Program to run.
+        bin: String,
+        /// Path to the ggml model.
+        model: String,
+    },
+}
+
+impl Backend {
+    /// The backend configured through `var` (the environment, in practice).
+    fn configured(var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let local = var("CP_WHISPER_MODEL");
+        let chosen = var("CP_VOICE_BACKEND");
+        match chosen.as_deref().unwrap_or_else(|| if local.is_some() { "whisper.cpp" } else { "openai" }) {
+            "whisper.cpp" | "local" => {
+                let model = local.ok_or("Voice input: set CP_WHISPER_MODEL to a whisper.cpp ggml model")?;
+                Ok(Self::WhisperCpp { bin: var("CP_WHISPER_BIN").unwrap_or_else(|| WHISPER_BIN.to_owned()), model })
+            }
+            "openai" => Self::open_ai(&var),
+            other => Err(format!("Voice input: unknown CP_VOICE_BACKEND '{other}' (openai, whisper.cpp)")),
+        }
+    }
+
+    /// The API backend; needs `OPENAI_API_KEY`.
+    fn open_ai(var: &impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let api_key =
+            var("OPENAI_API_KEY").ok_or("Voice input: set OPENAI_API_KEY, or CP_WHISPER_MODEL for whisper.cpp")?;
+        let base_url = var("OPENAI_BASE_URL").unwrap_or_else(|| OPENAI_BASE_URL.to_owned());
+        Ok(Self::OpenAi {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            api_key,
+            model: var("CP_VOICE_MODEL").unwrap_or_else(|| OPENAI_MODEL.to_owned()),
+        })
+    }
+
+    /// The text spoken in `wav`.
+    fn transcribe(&self, wav: &Path) -> Result<String, String> {
+        cp_base::deref_match!(self, {
+            Self::OpenAi { ref base_url, ref api_key, ref model } => transcribe_api(base_url, api_key, model, wav),
+            Self::WhisperCpp { ref bin, ref model } => transcribe_local(bin, model, wav),
+        })
+    }
+}
+
+/// `wav` sent to `{base_url}/audio/transcriptions`.
+fn transcribe_api(base_url: &str, api_key: &str, model: &str, wav: &Path) -> Result<String, String> {
+    let audio = std::fs::read(wav).map_err(|e| format!("Cannot read the recording: {e}"))?;
+    let part = reqwest::blocking::multipart::Part::bytes(audio)
+        .file_name("speech.wav")
+        .mime_str("audio/wav")
+        .map_err(|e| format!("Cannot set MIME type: {e}"))?;
+    let form = reqwest::blocking::multipart::Form::new()
+        .part("file", part)
+        .text("model", model.to_owned())
+        .text("response_format", "text");
+    let response = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .and_then(|http| {
+            http.post(format!("{base_url}/audio/transcriptions")).bearer_auth(api_key).multipart(form).send()
+        })
+        .map_err(|e| format!("Transcription request failed: {e}"))?;
+    let status = response.status();
+    let body = response.text().map_err(|e| format!("Cannot read the transcription: {e}"))?;
+    if status.is_success() {
+        Ok(body.trim().to_owned())
+    } else {
+        Err(format!("Transcription returned HTTP {}: {}", status.as_u16(), body.trim()))
+    }
+}
+
+/// `wav` run through whisper.cpp's `bin` with `model`.
+fn transcribe_local(bin: &str, model: &str, wav: &Path) -> Result<String, String> {
+    let output = Command::new(bin)
+        .args(["-nt", "-np", "-m", model, "-f"])
+        .arg(wav)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Cannot run {bin}: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{bin} failed: {}", stderr.lines().last().unwrap_or("no output")));
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Ok(text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" "))
+}
+
+/// A transcription result, filled in by the worker thread.
+type Slot = Arc<Mutex<Option<Result<String, String>>>>;
+
+/// Where dictation is at; stored as a state extension.
+#[derive(Debug, Default)]
+pub(crate) enum VoiceInput {
+    /// Not recording.
+    #[default]
+    Idle,
+    /// The microphone is being recorded.
+    Recording {
+        /// The recorder process.
+        recorder: Child,
+        /// WAV it writes.
+        wav: PathBuf,
+        /// Backend picked when recording started.
+        backend: Backend,
+    },
+    /// The recording is with the backend.
+    Transcribing(Slot),
+}
+
+impl VoiceInput {
+    /// The result slot of a running transcription.
+    fn pending(&self) -> Option<Slot> {
+        cp_base::deref_match!(self, {
+            Self::Transcribing(ref slot) => Some(Arc::clone(slot)),
+            Self::Idle | Self::Recording { .. } => None,
+        })
+    }
+}
+
+/// Status bar label while dictating.
+pub(crate) fn status(state: &State) -> Option<&'static str> {
+    match *state.get_ext::<VoiceInput>()? {
+        VoiceInput::Idle => None,
+        VoiceInput::Recording { .. } => Some("REC"),
+        VoiceInput::Transcribing(_) => Some("Transcribing"),
+    }
+}
+
+/// Ctrl+R: start recording, or stop and transcribe. Pressed while a
+/// transcription is running, it does nothing.
+pub(crate) fn toggle(state: &mut State) {
+    let current = state.get_ext_mut::<VoiceInput>().map(std::mem::take).unwrap_or_default();
+    let next = match current {
+        VoiceInput::Idle => start().unwrap_or_else(|e| {
+            notify(state, e);
+            VoiceInput::Idle
+        }),
+        VoiceInput::Recording { recorder, wav, backend } => VoiceInput::Transcribing(stop(recorder, wav, backend)),
+        busy @ VoiceInput::Transcribing(_) => busy,
+    };
+    state.set_ext(next);
+    state.flags.ui.dirty = true;
+}
+
+/// The finished transcript, ready to type at the cursor (a space added after
+/// a word). Failures are posted as notifications.
+pub(crate) fn poll(state: &mut State) -> Option<String> {
+    let slot = state.get_ext::<VoiceInput>()?.pending()?;
+    let result = slot.lock().unwrap_or_else(PoisonError::into_inner).take()?;
+    state.set_ext(VoiceInput::Idle);
+    state.flags.ui.dirty = true;
+    match result {
+        Ok(text) if !text.is_empty() => {
+            let before = state.input.get(..state.input_cursor).unwrap_or("");
+            Some(spaced(before, &text))
+        }
+        Ok(_) => {
+            notify(state, "Voice input: nothing was heard".to_owned());
+            None
+        }
+        Err(e) => {
+            notify(state, e);
+            None
+        }
+    }
+}
+
+/// `text` with a leading space when `before` ends in a word.
+fn spaced(before: &str, text: &str) -> String {
+    if before.chars().next_back().is_some_and(|c| !c.is_whitespace()) { format!(" {text}") } else { text.to_owned() }
+}
+
+/// Post `message` without waking the agent.
+fn notify(state: &mut State, message: String) {
+    let id = SpineState::create_notification(state, NotificationType::Custom, "voice".to_owned(), message);
+    let _processed = SpineState::mark_notification_processed(state, &id);
+}
+
+/// Pick the backend and start the first recorder found.
+fn start() -> Result<VoiceInput, String> {
+    let backend = Backend::configured(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))?;
+    let wav = std::env::temp_dir().join(format!("cp-voice-{}.wav", std::process::id()));
+    for &(program, args) in RECORDERS {
+        match Command::new(program)
+            .args(args)
+            .arg(&wav)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(recorder) => return Ok(VoiceInput::Recording { recorder, wav, backend }),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Voice input: cannot start {program}: {e}")),
+        }
+    }
+    Err("Voice input: no recorder found (install alsa-utils for arecord, or SoX for rec)".to_owned())
+}
+
+/// Stop the recorder and transcribe on a worker thread. `SIGINT` lets the
+/// recorder finish the WAV header.
+fn stop(mut recorder: Child, wav: PathBuf, backend: Backend) -> Slot {
+    let slot: Slot = Arc::default();
+    let filled = Arc::clone(&slot);
+    let work = move || {
+        drop(Command::new("kill").args(["-INT", &recorder.id().to_string()]).output());
+        drop(recorder.wait());
+        let has_audio = std::fs::metadata(&wav).is_ok_and(|m| m.len() > EMPTY_WAV_BYTES);
+        let result = if has_audio { backend.transcribe(&wav) } else { Ok(String::new()) };
+        drop(std::fs::remove_file(&wav));
+        *filled.lock().unwrap_or_else(PoisonError::into_inner) = Some(result);
+    };
+    if let Err(e) = thread::Builder::new().name("voice".to_owned()).spawn(work) {
+        *slot.lock().unwrap_or_else(PoisonError::into_inner) = Some(Err(format!("Voice input: no worker thread: {e}")));
+    }
+    slot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A lookup over `pairs`, standing in for the environment.
+    fn env(pairs: &[(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        let owned: Vec<(&str, &str)> = pairs.to_vec();
+        move |name| owned.iter().find(|&&(k, _)| k == name).map(|&(_, v)| v.to_owned())
+    }
+
+    #[test]
+    fn the_backend_follows_the_environment() {
+        let api = Backend::configured(env(&[("OPENAI_API_KEY", "sk"), ("OPENAI_BASE_URL", "http://x/v1/")]));
+        let expected = Backend::OpenAi {
+            base_url: "http://x/v1".to_owned(),
+            api_key: "sk".to_owned(),
+            model: OPENAI_MODEL.to_owned(),
+        };
+        assert_eq!(api, Ok(expected));
+        let local = Backend::configured(env(&[("CP_WHISPER_MODEL", "m.bin"), ("OPENAI_API_KEY", "sk")]));
+        assert_eq!(local, Ok(Backend::WhisperCpp { bin: WHISPER_BIN.to_owned(), model: "m.bin".to_owned() }));
+        assert!(
+            Backend::configured(env(&[("CP_VOICE_BACKEND", "whisper.cpp")]))
+                .is_err_and(|e| e.contains("CP_WHISPER_MODEL"))
+        );
+        assert!(Backend::configured(env(&[])).is_err_and(|e| e.contains("OPENAI_API_KEY")));
+    }
+
+    #[test]
+    fn transcripts_are_spaced_after_a_word() {
+        assert_eq!(spaced("fix the", "parser"), " parser");
+        assert_eq!(spaced("fix ", "parser"), "parser");
+        assert_eq!(spaced("", "parser"), "parser");
+    }
+}
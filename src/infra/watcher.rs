@@ -1,18 +1,24 @@
 //! File watcher for detecting changes to open files and directories.
+//!
+//! Changes are published as [`WatchEvent`]s on the event bus and drained by
+//! the main loop through [`FileWatcher::drain`]. An event still waiting there
+//! is not published again, so a burst of writes to one path costs one bus
+//! slot; events go out at low priority and a refused one is logged.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::mpsc::{self, Receiver};
 use std::sync::{Arc, Mutex};
 
+use cp_base::state::runtime::bus::{self, Priority};
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher as _};
 
 /// Events sent from the file watcher
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) enum WatchEvent {
     /// A watched file changed
     FileChanged(String),
-    /// A watched directory changed (file added/removed)
+    /// A watched directory changed (file added/removed/modified; anywhere
+    /// below it for recursive watches)
     DirChanged(String),
 }
 
@@ -24,19 +30,36 @@ pub(crate) struct FileWatcher {
     watched_files: Arc<Mutex<HashMap<PathBuf, String>>>,
     /// Maps canonical path -> original path
     watched_dirs: Arc<Mutex<HashMap<PathBuf, String>>>,
-    /// Receiver end of the watch-event channel.
-    event_rx: Receiver<WatchEvent>,
+    /// Canonical paths of the directories watched recursively
+    recursive_dirs: HashSet<PathBuf>,
+    /// Events published and not drained yet
+    queued: Arc<Mutex<HashSet<WatchEvent>>>,
+}
+
+/// Publish `event` unless the same event is still queued.
+fn publish(queued: &Mutex<HashSet<WatchEvent>>, event: WatchEvent) {
+    let Ok(mut pending) = queued.lock() else { return };
+    if !pending.insert(event.clone()) {
+        return;
+    }
+    if let Err(bus::Full(refused)) = bus::publish(event, Priority::Low) {
+        log::warn!("file watcher: event bus full, dropped {refused:?}");
+        let _removed = pending.remove(&refused);
+    }
 }
 
 impl FileWatcher {
-    /// Create a new file watcher backed by the OS recommended watcher.
+    /// Create a new file watcher backed by the OS recommended watcher. Events
+    /// are published on the event bus.
     pub(crate) fn new() -> notify::Result<Self> {
-        let (tx, rx) = mpsc::channel();
         let watched_files: Arc<Mutex<HashMap<PathBuf, String>>> = Arc::new(Mutex::new(HashMap::new()));
         let watched_dirs: Arc<Mutex<HashMap<PathBuf, String>>> = Arc::new(Mutex::new(HashMap::new()));
 
+        let queued: Arc<Mutex<HashSet<WatchEvent>>> = Arc::new(Mutex::new(HashSet::new()));
+
         let files_clone = Arc::clone(&watched_files);
         let dirs_clone = Arc::clone(&watched_dirs);
+        let queued_clone = Arc::clone(&queued);
 
         let watcher = RecommendedWatcher::new(
             move |res: Result<Event, notify::Error>| {
@@ -49,16 +72,16 @@ impl FileWatcher {
                         if let Ok(files) = files_clone.lock()
                             && let Some(original_path) = files.get(&canonical)
                         {
-                            let _r = tx.send(WatchEvent::FileChanged(original_path.clone()));
+                            publish(&queued_clone, WatchEvent::FileChanged(original_path.clone()));
                             continue;
                         }
 
-                        // Check if it's in a watched directory
+                        // Check if it's in a watched directory (the nearest one:
+                        // events from deep inside a recursive watch count too)
                         if let Ok(dirs) = dirs_clone.lock()
-                            && let Some(parent) = canonical.parent()
-                            && let Some(original_path) = dirs.get(&parent.to_path_buf())
+                            && let Some(original_path) = canonical.ancestors().skip(1).find_map(|dir| dirs.get(dir))
                         {
-                            let _r = tx.send(WatchEvent::DirChanged(original_path.clone()));
+                            publish(&queued_clone, WatchEvent::DirChanged(original_path.clone()));
                         }
                     }
                 }
@@ -66,7 +89,18 @@ impl FileWatcher {
             Config::default(),
         )?;
 
-        Ok(Self { watcher, watched_files, watched_dirs, event_rx: rx })
+        Ok(Self { watcher, watched_files, watched_dirs, recursive_dirs: HashSet::new(), queued })
+    }
+
+    /// Take the published events; their paths can be published again.
+    pub(crate) fn drain(&self) -> Vec<WatchEvent> {
+        // Drain before clearing: an event published in between stays queued
+        // on the bus and is taken next time.
+        let events = bus::drain::<WatchEvent>();
+        if let Ok(mut queued) = self.queued.lock() {
+            queued.clear();
+        }
+        events
     }
 
     /// Watch a file for changes
@@ -107,7 +141,8 @@ impl FileWatcher {
         Ok(())
     }
 
-    /// Watch a directory recursively (for .git/refs/ subdirs)
+    /// Watch a directory recursively (.git/refs/ subdirs, the file watch).
+    /// A directory already watched non-recursively is upgraded.
     pub(crate) fn watch_dir_recursive(&mut self, path: &str) -> notify::Result<()> {
         let path_buf = PathBuf::from(path);
         if !path_buf.is_dir() {
@@ -116,11 +151,13 @@ impl FileWatcher {
 
         let canonical = path_buf.canonicalize().unwrap_or_else(|_| path_buf.clone());
 
-        if let Ok(mut dirs) = self.watched_dirs.lock()
-            && !dirs.contains_key(&canonical)
-        {
-            let _r = dirs.insert(canonical.clone(), path.to_owned());
+        if self.recursive_dirs.contains(&canonical) {
+            return Ok(());
+        }
+        if let Ok(mut dirs) = self.watched_dirs.lock() {
+            let _r = dirs.entry(canonical.clone()).or_insert_with(|| path.to_owned());
             self.watcher.watch(&canonical, RecursiveMode::Recursive)?;
+            let _new = self.recursive_dirs.insert(canonical);
         }
         Ok(())
     }
@@ -164,19 +201,27 @@ impl FileWatcher {
     pub(crate) fn unwatch_dir(&mut self, path: &str) {
         let path_buf = PathBuf::from(path);
         let canonical = path_buf.canonicalize().unwrap_or(path_buf);
+        let _was_recursive = self.recursive_dirs.remove(&canonical);
         if let Ok(mut dirs) = self.watched_dirs.lock()
             && dirs.remove(&canonical).is_some()
         {
             let _r = self.watcher.unwatch(&canonical);
         }
     }
+}
 
-    /// Poll for watch events (non-blocking)
-    pub(crate) fn poll_events(&self) -> Vec<WatchEvent> {
-        let mut events = Vec::new();
-        while let Ok(event) = self.event_rx.try_recv() {
-            events.push(event);
-        }
-        events
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_queued_event_is_published_once() {
+        let queued = Mutex::new(HashSet::new());
+        let changed = WatchEvent::FileChanged("src/main.rs".to_owned());
+        publish(&queued, changed.clone());
+        publish(&queued, changed.clone());
+        publish(&queued, WatchEvent::DirChanged("src".to_owned()));
+        let events = bus::drain::<WatchEvent>();
+        assert_eq!(events, [changed, WatchEvent::DirChanged("src".to_owned())]);
     }
 }
@@ -64,7 +64,7 @@ fn push_converted_message(api_messages: &mut Vec<ApiMessage>, msg: &Message, ctx
             .iter()
             .map(|result| ContentBlock::ToolResult {
                 tool_use_id: result.tool_use_id.clone(),
-                content: result.content.clone(),
+                content: msg.sent_result(result).into_owned(),
             })
             .collect();
         if !blocks.is_empty() {
@@ -96,7 +96,7 @@ fn push_converted_message(api_messages: &mut Vec<ApiMessage>, msg: &Message, ctx
 fn build_text_message_blocks(msg: &Message, ctx: &MsgConvertCtx<'_>) -> Vec<ContentBlock> {
     let mut content_blocks: Vec<ContentBlock> = Vec::new();
     if !msg.content.is_empty() {
-        content_blocks.push(ContentBlock::Text { text: msg.content.clone() });
+        content_blocks.push(ContentBlock::Text { text: msg.sent_content().into_owned() });
     }
     let is_last = ctx.idx == ctx.all.len().saturating_sub(1);
     if msg.role == "assistant" && ctx.include_last_tool_uses && is_last && !msg.tool_uses.is_empty() {
@@ -7,7 +7,7 @@ use serde_json::Value;
 use std::sync::mpsc::Sender;
 
 use super::error::LlmError;
-use super::{ApiMessage, ContentBlock, LlmClient, LlmRequest, StreamEvent};
+use super::{ApiMessage, ContentBlock, LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use crate::infra::constants::{API_ENDPOINT, API_VERSION, library};
 use crate::infra::tools::build_api;
 use cp_base::config::INJECTIONS;
@@ -15,8 +15,12 @@ use cp_base::config::INJECTIONS;
 pub(in crate::llms) mod messages;
 pub(in crate::llms) mod streaming;
 
+use cp_base::tools::output_schema::OutputSchema;
 use messages::messages_to_api;
 
+/// Beta flag enabling `output_format` (structured outputs).
+const STRUCTURED_OUTPUTS_BETA: &str = "structured-outputs-2025-11-13";
+
 /// Anthropic Claude client
 pub(crate) struct AnthropicClient {
     /// Anthropic API key, resolved from vault (`"anthropic"`).
@@ -50,6 +54,9 @@ struct AnthropicRequest {
     tools: Value,
     /// Whether to stream the response
     stream: bool,
+    /// Structured output schema (beta), when a JSON answer is requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_format: Option<Value>,
 }
 
 /// Assemble the Anthropic `messages` array and resolved system prompt from a
@@ -94,10 +101,9 @@ impl LlmClient for AnthropicClient {
     fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
         let api_key = self.api_key.as_ref().ok_or_else(|| LlmError::Auth("ANTHROPIC_API_KEY not set".into()))?;
 
-        // timeout(None) prevents reqwest from killing long-running SSE streams.
-        // Without this, blocking Client may use system TCP timeouts, causing
-        // silent stream drops mid-response (same fix applied to Claude Code providers).
-        let client = Client::builder().timeout(None).build().map_err(|e| LlmError::Network(e.to_string()))?;
+        // Per-read idle timeout: a stalled SSE stream errors out and is retried
+        // instead of hanging forever (see `ProviderTimeouts`).
+        let client = stream_client(LlmProvider::Anthropic)?;
 
         let (api_messages, system_prompt) = build_messages_and_system(&request);
 
@@ -108,6 +114,7 @@ impl LlmClient for AnthropicClient {
             messages: api_messages,
             tools: build_api(&request.tools),
             stream: true,
+            output_format: request.output_schema.as_ref().map(OutputSchema::anthropic_output_format),
         };
 
         // Dump last request for debugging
@@ -118,13 +125,15 @@ impl LlmClient for AnthropicClient {
             let _r2 = std::fs::write(&path, serde_json::to_string_pretty(&api_request).unwrap_or_default());
         }
 
-        let response = client
+        let mut builder = client
             .post(API_ENDPOINT)
             .header("x-api-key", api_key.expose_secret())
             .header("anthropic-version", API_VERSION)
-            .header("content-type", "application/json")
-            .json(&api_request)
-            .send()?;
+            .header("content-type", "application/json");
+        if api_request.output_format.is_some() {
+            builder = builder.header("anthropic-beta", STRUCTURED_OUTPUTS_BETA);
+        }
+        let response = builder.json(&api_request).send()?;
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -16,7 +16,7 @@ use cp_mod_utilities::secret::Redacted;
 use reqwest::blocking::Client;
 
 use super::error::LlmError;
-use super::{ApiCheckResult, LlmClient, LlmRequest, StreamEvent};
+use super::{ApiCheckResult, LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use crate::infra::constants::{API_VERSION, library};
 use crate::infra::tools::build_api;
 use stream_types::StreamMessage;
@@ -165,7 +165,7 @@ impl ClaudeCodeClient {
             .as_ref()
             .ok_or_else(|| LlmError::Auth("Claude Code OAuth token not found or expired. Run 'claude login'".into()))?;
 
-        let client = Client::builder().timeout(None).build().map_err(|e| LlmError::Network(e.to_string()))?;
+        let client = stream_client(LlmProvider::ClaudeCode)?;
 
         // Handle cleaner mode or custom system prompt
         let system_text =
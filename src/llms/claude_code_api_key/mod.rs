@@ -12,7 +12,7 @@ use cp_mod_utilities::secret::Redacted;
 use reqwest::blocking::Client;
 
 use super::error::LlmError;
-use super::{ApiCheckResult, LlmClient, LlmRequest, StreamEvent};
+use super::{ApiCheckResult, LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use crate::infra::constants::library;
 use crate::infra::tools::build_api;
 
@@ -126,7 +126,7 @@ impl LlmClient for ClaudeCodeApiKeyClient {
         let api_key =
             self.api_key.as_ref().ok_or_else(|| LlmError::Auth("ANTHROPIC_API_KEY not found in environment".into()))?;
 
-        let client = Client::builder().timeout(None).build().map_err(|e| LlmError::Network(e.to_string()))?;
+        let client = stream_client(LlmProvider::ClaudeCodeApiKey)?;
 
         // Handle cleaner mode or custom system prompt
         let system_text =
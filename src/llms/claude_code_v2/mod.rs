@@ -17,7 +17,7 @@ use reqwest::blocking::Client;
 use super::claude_code_api_key::helpers;
 use super::claude_code_api_key::streaming;
 use super::error::LlmError;
-use super::{ApiCheckResult, LlmClient, LlmRequest, StreamEvent};
+use super::{ApiCheckResult, LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use crate::infra::constants::{API_VERSION, library};
 use crate::infra::tools::build_api;
 
@@ -103,7 +103,7 @@ impl ClaudeCodeV2Client {
             .as_ref()
             .ok_or_else(|| LlmError::Auth("Claude Code OAuth token not found or expired. Run 'claude login'".into()))?;
 
-        let client = Client::builder().timeout(None).build().map_err(|e| LlmError::Network(e.to_string()))?;
+        let client = stream_client(LlmProvider::ClaudeCodeV2)?;
 
         // System prompt
         let system_text =
@@ -10,7 +10,7 @@ use serde_json::Value;
 use std::sync::mpsc::Sender;
 
 use super::error::LlmError;
-use super::{ApiMessage, LlmClient, LlmRequest, StreamEvent};
+use super::{ApiMessage, LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use crate::infra::tools::build_api;
 
 /// `MiniMax` Anthropic-compatible API endpoint.
@@ -54,7 +54,7 @@ impl LlmClient for MiniMaxClient {
     fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
         let api_key = self.api_key.as_ref().ok_or_else(|| LlmError::Auth("MINIMAX_API_KEY not set".into()))?;
 
-        let client = Client::builder().timeout(None).build().map_err(|e| LlmError::Network(e.to_string()))?;
+        let client = stream_client(LlmProvider::MiniMax)?;
 
         let (api_messages, system_prompt) = super::anthropic::build_messages_and_system(&request);
 
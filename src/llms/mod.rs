@@ -15,6 +15,7 @@ pub(crate) mod minimax;
 pub(crate) mod oai_providers;
 
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -23,9 +24,10 @@ use crate::app::panels::ContextItem;
 use crate::infra::tools::ToolDefinition;
 use crate::infra::tools::ToolResult;
 use crate::state::Message;
+use cp_base::tools::output_schema::OutputSchema;
 
 // Re-export LLM types from cp-base so that `crate::llms::LlmProvider` etc. work
-pub(crate) use cp_base::config::llm_types::{ApiCheckResult, LlmProvider, ModelInfo, StreamEvent};
+pub(crate) use cp_base::config::llm_types::{ApiCheckResult, LlmProvider, ModelInfo, ProviderTimeouts, StreamEvent};
 pub(crate) use cp_base::config::models::{
     AnthropicModel, ClaudeCodeV2Model, DeepSeekModel, GrokModel, GroqModel, MiniMaxModel,
 };
@@ -64,6 +66,8 @@ pub(crate) struct LlmRequest {
     /// Serialized cache optimization engine state (JSON) for breakpoint placement.
     /// Passed from `State.cache_engine_json` to the streaming thread.
     pub cache_engine_json: Option<String>,
+    /// Schema of a structured (JSON) answer, sent natively where supported.
+    pub output_schema: Option<OutputSchema>,
 }
 
 /// Trait for LLM providers
@@ -98,6 +102,20 @@ pub(crate) fn start_api_check(provider: LlmProvider, model: String, tx: Sender<A
     });
 }
 
+/// Blocking HTTP client for `provider`'s streaming requests, built from its
+/// [`ProviderTimeouts`] (0 disables a limit). The timeout applies per read, so
+/// an SSE stream that goes silent fails and takes the normal retry path.
+pub(crate) fn stream_client(provider: LlmProvider) -> Result<reqwest::blocking::Client, error::LlmError> {
+    let secs = |s: u64| (s > 0).then(|| Duration::from_secs(s));
+    let timeouts = ProviderTimeouts::for_provider(provider);
+    reqwest::blocking::Client::builder()
+        .connect_timeout(secs(timeouts.connect))
+        .timeout(secs(timeouts.idle))
+        .tcp_keepalive(secs(timeouts.keepalive))
+        .build()
+        .map_err(|e| error::LlmError::Network(e.to_string()))
+}
+
 /// Parameters for starting a streaming LLM request
 pub(crate) struct StreamParams {
     /// Which LLM provider to use
@@ -120,6 +138,8 @@ pub(crate) struct StreamParams {
     pub worker_id: String,
     /// Serialized cache optimization engine state for breakpoint placement.
     pub cache_engine_json: Option<String>,
+    /// User's standing instructions, appended to the last user turn.
+    pub standing_instructions: Option<String>,
 }
 
 /// Start streaming with the specified provider and model
@@ -129,12 +149,15 @@ pub(crate) fn start_streaming(params: StreamParams, tx: Sender<StreamEvent>) {
     let _r = std::thread::spawn(move || {
         // Assemble the prompt (panels + seed + conversation → api_messages)
         let include_tool_uses = false; // No pending tool results on first stream
-        let api_messages = crate::app::prompt::assemble_prompt(
+        let mut api_messages = crate::app::prompt::assemble_prompt(
             &params.messages,
             &params.context_items,
             include_tool_uses,
             params.seed_content.as_deref(),
         );
+        if let Some(text) = params.standing_instructions.as_deref() {
+            crate::app::prompt::append_standing_instructions(&mut api_messages, text);
+        }
 
         // Dump prompt tick CSV for debugging cache behavior
         cache::prompt_tick_csv::dump_prompt_tick_csv(&api_messages);
@@ -152,6 +175,7 @@ pub(crate) fn start_streaming(params: StreamParams, tx: Sender<StreamEvent>) {
             worker_id: params.worker_id,
             api_messages,
             cache_engine_json: params.cache_engine_json,
+            output_schema: None,
         };
 
         if let Err(e) = client.stream(request, tx.clone()) {
@@ -265,7 +289,7 @@ pub(crate) fn prepare_panel_messages(context_items: &[ContextItem]) -> Vec<FakeP
         .map(|item| FakePanelMessage {
             panel_id: item.id.clone(),
             timestamp_ms: item.last_refresh_ms,
-            content: format!("======= [{}] {} =======\n{}", item.id, item.header, item.content),
+            content: format!("{}\n{}", item.banner(), item.content),
         })
         .collect()
 }
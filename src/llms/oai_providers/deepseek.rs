@@ -11,7 +11,7 @@ use reqwest::blocking::Client;
 use serde::Serialize;
 
 use super::super::error::LlmError;
-use super::super::{LlmClient, LlmRequest, StreamEvent};
+use super::super::{LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use super::openai_compat::{self, BuildOptions, OaiMessage};
 
 /// `DeepSeek` chat completions API endpoint.
@@ -92,13 +92,17 @@ struct DsRequest {
     max_tokens: u32,
     /// Whether to stream the response via SSE.
     stream: bool,
+    /// JSON mode (`{"type": "json_object"}`) — `DeepSeek` has no schema-constrained output;
+    /// the schema itself travels in the prompt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 impl LlmClient for DeepSeekClient {
     fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
         let api_key = self.api_key.as_ref().ok_or_else(|| LlmError::Auth("DEEPSEEK_API_KEY not set".into()))?;
 
-        let client = Client::new();
+        let client = stream_client(LlmProvider::DeepSeek)?;
         // V4 models use thinking mode by default — reasoning_content is always relevant
         let is_reasoner = true;
 
@@ -151,6 +155,7 @@ impl LlmClient for DeepSeekClient {
             tool_choice,
             max_tokens: request.max_output_tokens,
             stream: true,
+            response_format: request.output_schema.as_ref().map(|_| serde_json::json!({"type": "json_object"})),
         };
 
         super::openai_streaming::dump_request(&request.worker_id, "deepseek", &api_request);
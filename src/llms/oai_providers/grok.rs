@@ -10,8 +10,9 @@ use reqwest::blocking::Client;
 use serde::Serialize;
 
 use super::super::error::LlmError;
-use super::super::{LlmClient, LlmRequest, StreamEvent};
+use super::super::{LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use super::openai_compat::{self, BuildOptions, OaiMessage};
+use cp_base::tools::output_schema::OutputSchema;
 
 /// xAI Grok chat completions API endpoint.
 const GROK_API_ENDPOINT: &str = "https://api.x.ai/v1/chat/completions";
@@ -53,13 +54,16 @@ struct GrokRequest {
     max_tokens: u32,
     /// Whether to stream the response via SSE.
     stream: bool,
+    /// Structured output format, when a JSON answer is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 impl LlmClient for GrokClient {
     fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
         let api_key = self.api_key.as_ref().ok_or_else(|| LlmError::Auth("XAI_API_KEY not set".into()))?;
 
-        let client = Client::new();
+        let client = stream_client(LlmProvider::Grok)?;
 
         // Collect pending tool result IDs
         let pending_tool_ids: Vec<String> = request
@@ -105,6 +109,7 @@ impl LlmClient for GrokClient {
             tool_choice,
             max_tokens: request.max_output_tokens,
             stream: true,
+            response_format: request.output_schema.as_ref().map(OutputSchema::openai_response_format),
         };
 
         super::openai_streaming::dump_request(&request.worker_id, "grok", &api_request);
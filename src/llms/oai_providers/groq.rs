@@ -11,10 +11,11 @@ use serde::Serialize;
 use serde_json::Value;
 
 use super::super::error::LlmError;
-use super::super::{LlmClient, LlmRequest, StreamEvent};
+use super::super::{LlmClient, LlmProvider, LlmRequest, StreamEvent, stream_client};
 use super::openai_compat::{self, BuildOptions, OaiMessage};
 use crate::infra::tools::ToolDefinition;
 use cp_base::config::INJECTIONS;
+use cp_base::tools::output_schema::OutputSchema;
 
 /// Groq chat completions API endpoint.
 const GROQ_API_ENDPOINT: &str = "https://api.groq.com/openai/v1/chat/completions";
@@ -56,13 +57,16 @@ struct GroqRequest {
     max_completion_tokens: u32,
     /// Whether to stream the response via SSE.
     stream: bool,
+    /// Structured output format, when a JSON answer is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
 }
 
 impl LlmClient for GroqClient {
     fn stream(&self, request: LlmRequest, tx: Sender<StreamEvent>) -> Result<(), LlmError> {
         let api_key = self.api_key.as_ref().ok_or_else(|| LlmError::Auth("GROQ_API_KEY not set".into()))?;
 
-        let client = Client::new();
+        let client = stream_client(LlmProvider::Groq)?;
 
         // Collect pending tool result IDs
         let pending_tool_ids: Vec<String> = request
@@ -114,6 +118,7 @@ impl LlmClient for GroqClient {
             tool_choice,
             max_completion_tokens: request.max_output_tokens,
             stream: true,
+            response_format: request.output_schema.as_ref().map(OutputSchema::openai_response_format),
         };
 
         super::openai_streaming::dump_request(&request.worker_id, "groq", &api_request);
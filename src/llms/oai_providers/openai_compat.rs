@@ -310,7 +310,7 @@ fn push_raw_tool_results(out: &mut Vec<OaiMessage>, msg: &Message, included: &Ha
         if included.contains(&result.tool_use_id) {
             out.push(OaiMessage {
                 role: "tool".to_owned(),
-                content: Some(result.content.clone()),
+                content: Some(msg.sent_result(result).into_owned()),
                 tool_calls: None,
                 tool_call_id: Some(result.tool_use_id.clone()),
             });
@@ -376,7 +376,7 @@ fn push_conversation_message(out: &mut Vec<OaiMessage>, msg: &Message, included:
     if !msg.content.is_empty() {
         out.push(OaiMessage {
             role: msg.role.clone(),
-            content: Some(msg.content.clone()),
+            content: Some(msg.sent_content().into_owned()),
             tool_calls: None,
             tool_call_id: None,
         });
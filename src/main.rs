@@ -2,7 +2,8 @@
 //!
 //! Entry point: sets up the terminal, loads state, initializes modules,
 //! and runs the main event loop. Also handles `typst-compile` and
-//! `typst-recompile-watched` subcommands for callback scripts.
+//! `typst-recompile-watched` subcommands for callback scripts, and the `init`
+//! and `doctor` subcommands.
 
 // Force vendored OpenSSL for cross-compilation (activates openssl-sys/vendored).
 // The tui crate doesn't call openssl directly — this is purely for feature unification.
@@ -10,6 +11,8 @@ use openssl as _;
 
 /// Application logic: event loop, actions, context preparation.
 mod app;
+/// Command-line subcommands that run without the TUI (`init`, `doctor`).
+mod cli;
 /// Infrastructure: API clients, tools, constants, file watchers.
 mod infra;
 /// LLM provider abstraction and streaming.
@@ -148,6 +151,7 @@ fn render_boot_screen(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, ste
 
         // Progress gauge
         frame.render_widget(boot_gauge_line(done_count, total, gauge_area.width), gauge_area);
+        cp_base::config::accessors::color_depth::downgrade_buffer(frame.buffer_mut());
     }));
 }
 
@@ -217,7 +221,6 @@ use crossterm::{
 
 use app::{App, ensure_default_agent, ensure_default_contexts};
 use infra::api::StreamEvent;
-use state::cache::CacheUpdate;
 use state::persistence::{
     boot_assemble_state, boot_extract_module_data, boot_init_modules, boot_load_config, boot_load_messages,
     boot_load_panels, load_state,
@@ -259,6 +262,7 @@ fn teardown_and_maybe_reexec(reload_pending: bool) {
     let _r_paste_off = io::stdout().execute(DisableBracketedPaste);
     let _r_leave = io::stdout().execute(LeaveAlternateScreen);
     infra::flame::flush();
+    ui::perf::history::persist();
 
     #[cfg(unix)]
     if reload_pending
@@ -266,7 +270,7 @@ fn teardown_and_maybe_reexec(reload_pending: bool) {
         && let Ok(exe_path) = std::env::current_exe()
     {
         use std::os::unix::process::CommandExt as _;
-        let mut exec_args: Vec<String> = std::env::args().skip(1).collect();
+        let mut exec_args = cli::reload_args(std::env::args().skip(1));
         if !exec_args.iter().any(|a| a == "--resume-stream") {
             exec_args.push("--resume-stream".to_owned());
         }
@@ -368,6 +372,8 @@ fn boot_app_state(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, steps:
     // Phase 6: Prepare workspace
     ensure_default_contexts(&mut state);
     ensure_default_agent(&mut state);
+    state::experiment::begin(&mut state);
+    state.flags.overlays.tour_step = ui::help::tour::first_run_step();
     mark_step_done(steps, STEP_WORKSPACE);
     render_boot_screen(terminal, steps);
 
@@ -378,17 +384,38 @@ fn main() -> ExitCode {
     init_file_logger();
     raise_fd_limit();
     infra::flame::init();
+    ui::perf::history::init();
 
     // Parse CLI args
     let args: Vec<String> = std::env::args().collect();
     let resume_stream = args.iter().any(|a| a == "--resume-stream");
 
+    // init / doctor / experiment / attach --keys / pair: one-shot subcommands, without the TUI.
+    if let Some(code) = cli::run(&args) {
+        return code;
+    }
+
+    // attach, files to open, --prompt, --attach-stdin, --control, --serve, --pair and --mirror: checked
+    // (the pipe read, the servers bound) now, before the terminal is taken over.
+    let startup = match cli::Startup::parse(&args) {
+        Ok(startup) => startup,
+        Err(e) => {
+            drop(writeln!(io::stderr(), "{e}"));
+            return ExitCode::FAILURE;
+        }
+    };
+
     // --bridge: activate the orchestration bridge (equivalent to CP_BRIDGE=1).
     // Uses a safe OnceLock flag so BridgeModule::init_state picks it up during boot.
     if args.iter().any(|a| a == "--bridge") {
         cp_mod_bridge::request_bridge();
     }
 
+    // --colors=truecolor|256|16: force the color depth instead of detecting it.
+    if let Some(depth) = args.iter().find_map(|a| a.strip_prefix("--colors=")) {
+        let _known = cp_base::config::accessors::color_depth::request(depth);
+    }
+
     // Panic hook: restore terminal state and log the panic to disk.
     install_panic_hook();
 
@@ -421,13 +448,16 @@ fn main() -> ExitCode {
 
     // Create channels
     let (tx, rx) = mpsc::channel::<StreamEvent>();
-    let (cache_tx, cache_rx) = mpsc::channel::<CacheUpdate>();
 
     // Create and run app
-    let mut app = App::new(state, cache_tx, resume_stream);
-    let ch = app::run::lifecycle::EventChannels { tx: &tx, rx: &rx, cache_rx: &cache_rx };
+    let mut app = App::new(state, resume_stream);
+    startup.apply(&mut app);
+    let ch = app::run::lifecycle::EventChannels { tx: &tx, rx: &rx };
     let run_result = app.run(&mut terminal, &ch);
 
+    // Record the session's outcome when a prompt experiment is running.
+    state::experiment::finish(&app.state);
+
     // Cleanup + self-restart on reload (see helper).
     teardown_and_maybe_reexec(app.state.flags.lifecycle.reload_pending);
 
@@ -0,0 +1,150 @@
+//! Deterministic execution of an eviction plan.
+//!
+//! Each planned item is checked against the run's scope, then carried out
+//! through the same code as the matching tool (`Close_panel`,
+//! `message_compress`) one item at a time, so every outcome lands in the run
+//! as its own decision — the Cleaner panel shows exactly what was executed.
+//! Nothing outside the plan is touched.
+
+use crate::infra::tools::ToolUse;
+use crate::state::{Kind, MsgStatus, State};
+
+use super::plan::{EvictionPlan, PLANNED_AGENT, PlannedClose, PlannedMessage};
+use super::scope::CleanScope;
+use super::types::{CleanerState, Decision};
+
+/// Outcome of one planned item: first result line and whether it failed.
+type Outcome = (String, bool);
+
+/// Run one synthetic tool call through normal module dispatch.
+fn dispatch(state: &mut State, name: &str, input: serde_json::Value) -> Outcome {
+    let tool = ToolUse::new(format!("plan-{name}"), name.to_owned(), input);
+    let active = state.active_modules.clone();
+    let result = crate::modules::dispatch_tool(&tool, state, &active);
+    (result.content.lines().next().unwrap_or("").to_owned(), result.is_error)
+}
+
+/// Close one planned panel, unless it is missing or outside `scope`.
+fn close_panel(state: &mut State, scope: &CleanScope, planned: &PlannedClose) -> Outcome {
+    let Some(entry) = state.context.iter().find(|c| c.id == planned.id) else {
+        return (format!("{} not found", planned.id), true);
+    };
+    if !scope.allows(entry) {
+        return (format!("refused: outside this run's scope ({})", scope.label()), true);
+    }
+    dispatch(state, "Close_panel", serde_json::json!({ "ids": [planned.id] }))
+}
+
+/// Compress or delete one planned message, unless it is missing, already
+/// gone, or outside `scope`. Returns the outcome and the message's prior
+/// status when it changed.
+fn shrink_message(state: &mut State, scope: &CleanScope, planned: &PlannedMessage) -> (Outcome, Option<MsgStatus>) {
+    let Some(msg) = state.messages.iter().find(|m| m.id == planned.id) else {
+        return ((format!("{} not found", planned.id), true), None);
+    };
+    if !scope.allows_message(msg) {
+        return ((format!("refused: outside this run's scope ({})", scope.label()), true), None);
+    }
+    let prior = msg.status;
+    if !(prior == MsgStatus::Full || prior.is_compressed()) {
+        return ((format!("{} is already {prior:?}", planned.id), true), None);
+    }
+    let outcome = if planned.action == MsgStatus::Deleted {
+        delete_message(state, &planned.id)
+    } else {
+        dispatch(state, "message_compress", serde_json::json!({ "ids": [planned.id], "level": planned.action }))
+    };
+    let changed = state.messages.iter().any(|m| m.id == planned.id && m.status != prior);
+    (outcome, changed.then_some(prior))
+}
+
+/// Drop a live message from the context and persist it.
+fn delete_message(state: &mut State, id: &str) -> Outcome {
+    let Some(msg) = state.messages.iter_mut().find(|m| m.id == id) else {
+        return (format!("{id} not found"), true);
+    };
+    msg.status = MsgStatus::Deleted;
+    crate::state::persistence::save_message(msg);
+    state.touch_panel(Kind::CONVERSATION);
+    (format!("Deleted {id}"), false)
+}
+
+/// Append one executed item to the in-flight planned run.
+fn record(state: &mut State, action: &str, target: (&str, &str), outcome: Outcome) {
+    let (id, reason) = target;
+    let (text, is_error) = outcome;
+    let Some(run) = CleanerState::get_mut(state).running_mut(PLANNED_AGENT) else { return };
+    run.decisions.push(Decision {
+        tool: action.to_owned(),
+        params: format!("{id} \u{2014} {reason}"),
+        outcome: text,
+        is_error,
+    });
+}
+
+/// Execute `plan` into the in-flight planned run: panels first, then messages.
+pub(super) fn execute_plan(state: &mut State, plan: &EvictionPlan) {
+    let Some(scope) = CleanerState::get(state).running(PLANNED_AGENT).map(|r| r.scope.clone()) else { return };
+    for planned in &plan.close {
+        let outcome = close_panel(state, &scope, planned);
+        record(state, "close", (&planned.id, &planned.reason), outcome);
+    }
+    for planned in &plan.messages {
+        let (outcome, prior) = shrink_message(state, &scope, planned);
+        let action = if planned.action == MsgStatus::Deleted { "delete" } else { "compress" };
+        record(state, action, (&planned.id, &planned.reason), outcome);
+        if let Some(status) = prior
+            && let Some(run) = CleanerState::get_mut(state).running_mut(PLANNED_AGENT)
+        {
+            run.message_statuses.push((planned.id.clone(), status));
+        }
+    }
+    state.flags.ui.dirty = true;
+}
+
+/// Apply the previewed plan (Ctrl+K → "Apply cleanup plan") as a cleaning
+/// run of its own. Returns `false` when no plan is ready or a run is active.
+pub(crate) fn apply_ready_plan(state: &mut State) -> bool {
+    let Some(cs) = state.get_ext::<CleanerState>() else { return false };
+    let Some(plan) = cs.plan.as_ref().and_then(super::plan::PlanStatus::ready).cloned() else { return false };
+    if cs.runs.iter().any(|r| r.status == super::types::RunStatus::Running) {
+        return false;
+    }
+    super::begin_run(state, PLANNED_AGENT, None, CleanScope::Everything);
+    execute_plan(state, &plan);
+    super::finish_run(state, PLANNED_AGENT, super::types::RunStatus::Reported, &plan.summary);
+    CleanerState::get_mut(state).plan = None;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Message;
+
+    #[test]
+    fn scope_refusals_are_recorded_and_nothing_changes() {
+        let mut state = State::default();
+        state.set_ext(CleanerState::default());
+        state.messages.push(Message::new_user("U1".to_owned(), "UID_1_U".to_owned(), "hello".to_owned(), 1));
+        super::super::begin_run(&mut state, PLANNED_AGENT, None, CleanScope::PanelsOnly);
+        let plan = EvictionPlan {
+            summary: "drop U1".to_owned(),
+            close: vec![PlannedClose { id: "P9".to_owned(), reason: "gone".to_owned() }],
+            messages: vec![PlannedMessage {
+                id: "U1".to_owned(),
+                action: MsgStatus::Deleted,
+                reason: "stale".to_owned(),
+            }],
+        };
+        execute_plan(&mut state, &plan);
+
+        let run = CleanerState::get(&state).running(PLANNED_AGENT);
+        let outcomes: Vec<(String, bool)> =
+            run.map(|r| r.decisions.iter().map(|d| (d.outcome.clone(), d.is_error)).collect()).unwrap_or_default();
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|o| o.1));
+        assert!(outcomes.last().is_some_and(|o| o.0.starts_with("refused")));
+        assert_eq!(state.messages.first().map(|m| m.status), Some(MsgStatus::Full));
+    }
+}
@@ -0,0 +1,179 @@
+//! Cleaner module — a dedicated panel for the background context optimizer.
+//!
+//! The cleaner answers with a structured eviction plan (panels to close,
+//! messages to compress or delete) that the app executes item by item;
+//! other reverie agents still run as their own tool-calling conversation.
+//! Each run is recorded with the decisions executed and a snapshot of every
+//! panel taken just before it started, so the user can roll the run back
+//! from the command palette. Only a compact summary of the latest run reaches
+//! the LLM. Ctrl+K starts a run restricted to a user-picked scope, or asks
+//! for a plan to preview before applying it.
+
+/// Deterministic plan execution.
+mod execute;
+/// Cleaner panel rendering.
+mod panel;
+/// Eviction plans (structured answers previewed before a run).
+pub(crate) mod plan;
+/// Run lifecycle hooks and undo.
+mod runs;
+/// Cleaning scope constraints (Ctrl+K picker).
+pub(crate) mod scope;
+/// Run history types.
+pub(crate) mod types;
+
+pub(crate) use execute::apply_ready_plan;
+pub(crate) use runs::{begin_run, finish_run, record_decision, undo_last_run};
+pub(crate) use scope::{check_scope, scope_constraints};
+
+use crate::app::panels::Panel;
+use crate::infra::tools::{ToolDefinition, ToolResult, ToolUse};
+use crate::state::{Kind, State, TypeMeta};
+
+use self::panel::CleanerPanel;
+use self::types::CleanerState;
+use super::Module;
+
+/// Module owning the Cleaner panel and run history.
+pub(crate) struct CleanerModule;
+
+impl Module for CleanerModule {
+    fn id(&self) -> &'static str {
+        "cleaner"
+    }
+    fn name(&self) -> &'static str {
+        "Cleaner"
+    }
+    fn description(&self) -> &'static str {
+        "Context optimizer runs, decisions, and undo"
+    }
+    fn is_core(&self) -> bool {
+        false
+    }
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![]
+    }
+
+    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        None
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::CLEANER => Some(Box::new(CleanerPanel)),
+            _ => None,
+        }
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &[]
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(CleanerState::default());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(CleanerState::default());
+    }
+
+    fn save_module_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    fn load_module_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+
+    fn save_worker_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+
+    fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<crate::infra::tools::Verdict> {
+        None
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::CLEANER)]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![(Kind::new(Kind::CLEANER), "Cleaner", false)]
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: Kind::CLEANER,
+            icon_id: "cleaner",
+            is_fixed: true,
+            needs_cache: false,
+            fixed_order: Some(10),
+            display_name: "cleaner",
+            short_name: "cleaner",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn tool_visualizers(&self) -> Vec<(&'static str, super::ToolVisualizer)> {
+        vec![]
+    }
+
+    fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
+        None
+    }
+
+    fn context_detail(&self, _ctx: &crate::state::Entry) -> Option<String> {
+        None
+    }
+
+    fn overview_context_section(&self, _state: &State) -> Option<String> {
+        None
+    }
+
+    fn overview_render_sections(&self, _state: &State) -> Vec<(u8, Vec<cp_render::Block>)> {
+        vec![]
+    }
+
+    fn on_close_context(&self, _ctx: &crate::state::Entry, _state: &mut State) -> Option<Result<String, String>> {
+        None
+    }
+
+    fn on_user_message(&self, _state: &mut State) {}
+
+    fn on_stream_stop(&self, _state: &mut State) {}
+
+    fn on_stream_chunk(&self, _text: &str, _state: &mut State) {}
+
+    fn on_tool_progress(&self, _tool_name: &str, _input_so_far: &str, _state: &mut State) {}
+
+    fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
+
+    fn watch_paths(&self, _state: &State) -> Vec<cp_base::panels::WatchSpec> {
+        vec![]
+    }
+
+    fn should_invalidate_on_fs_change(
+        &self,
+        _ctx: &crate::state::Entry,
+        _changed_path: &str,
+        _is_dir_event: bool,
+    ) -> bool {
+        false
+    }
+
+    fn watcher_immediate_refresh(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt::Write as _;
+
+use crossterm::event::KeyEvent;
+
+use crate::app::actions::Action;
+use crate::app::panels::{ContextItem, Panel};
+use crate::app::prompt::structured::StructuredQuery;
+use crate::state::{Kind, Message, State, estimate_tokens};
+
+use cp_base::panels::{CacheRequest, CacheUpdate, scroll_key_action};
+use cp_render::{Block, Semantic, Span as S};
+
+use super::plan::{self, EvictionPlan, PlanStatus};
+use super::scope::CleanScope;
+use super::types::{CleanRun, CleanerState, RunStatus};
+
+/// Panel showing the cleaner's runs: its own conversation, the decisions it
+/// took, and whether the latest run can be undone.
+pub(super) struct CleanerPanel;
+
+/// `HH:MM:SS` (UTC) for a millisecond timestamp.
+fn format_clock(ms: u64) -> String {
+    let secs = cp_base::panels::time_arith::ms_to_secs(ms);
+    let (hours, minutes, seconds) = cp_base::panels::time_arith::secs_to_hms(secs);
+    format!("{hours:02}:{minutes:02}:{seconds:02}")
+}
+
+/// Semantic colour for a run status.
+const fn status_semantic(status: RunStatus) -> Semantic {
+    match status {
+        RunStatus::Running => Semantic::Accent,
+        RunStatus::Reported => Semantic::Success,
+        RunStatus::Aborted => Semantic::Error,
+        RunStatus::Undone => Semantic::Warning,
+    }
+}
+
+/// Header line + directive for one run.
+fn run_header_blocks(run: &CleanRun) -> Vec<Block> {
+    let mut blocks = vec![Block::Line(vec![
+        S::accent(format!("Run #{} ", run.number)).bold(),
+        S::styled(run.status.label().to_owned(), status_semantic(run.status)).bold(),
+        S::muted(format!("  {}  {}  {} decision(s)", run.agent_id, format_clock(run.started_ms), run.decisions.len())),
+    ])];
+    if run.scope != CleanScope::Everything {
+        blocks.push(Block::Line(vec![S::muted("  Scope: ".into()), S::info(run.scope.label())]));
+    }
+    if let Some(directive) = run.directive.as_ref() {
+        blocks.push(Block::Line(vec![S::muted("  Directive: ".into()), S::new(directive.clone()).italic()]));
+    }
+    blocks
+}
+
+/// The decision list for one run.
+fn decision_blocks(run: &CleanRun) -> Vec<Block> {
+    run.decisions
+        .iter()
+        .map(|d| {
+            let outcome = if d.is_error { S::error(d.outcome.clone()) } else { S::muted(d.outcome.clone()) };
+            Block::Line(vec![
+                S::muted("  \u{2022} ".into()),
+                S::accent(d.tool.clone()).bold(),
+                S::muted(format!(" {} \u{2192} ", d.params)),
+                outcome,
+            ])
+        })
+        .collect()
+}
+
+/// The reverie's own turns: assistant reasoning and the tools it called.
+fn transcript_blocks(messages: &[Message]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    for msg in messages.iter().filter(|m| m.role == "assistant") {
+        for line in msg.content.lines().filter(|l| !l.trim().is_empty()) {
+            blocks.push(Block::Line(vec![S::new(format!("  {line}"))]));
+        }
+        for tu in &msg.tool_uses {
+            blocks.push(Block::Line(vec![S::muted("  \u{2192} ".into()), S::info(tu.name.clone())]));
+        }
+    }
+    blocks
+}
+
+/// The planned actions and how to apply them.
+fn ready_plan_blocks(plan: &EvictionPlan) -> Vec<Block> {
+    let item = |action: String, id: &str, reason: &str| {
+        Block::Line(vec![
+            S::muted(format!("  \u{2022} {action} ")),
+            S::accent(id.to_owned()).bold(),
+            S::muted(format!(" \u{2014} {reason}")),
+        ])
+    };
+    let mut blocks = vec![Block::Line(vec![S::accent("Plan ".into()).bold(), S::new(plan.summary.clone())])];
+    blocks.extend(plan.close.iter().map(|p| item("close".to_owned(), &p.id, &p.reason)));
+    blocks.extend(plan.messages.iter().map(|m| item(format!("{:?}", m.action).to_lowercase(), &m.id, &m.reason)));
+    blocks.push(Block::Line(vec![
+        S::muted("  Apply: Ctrl+K \u{2192} ".into()),
+        S::styled("Apply cleanup plan".into(), Semantic::KeyHint),
+    ]));
+    blocks
+}
+
+/// The latest eviction plan, if one was requested.
+fn plan_blocks(cs: &CleanerState) -> Vec<Block> {
+    let Some(status) = cs.plan.as_ref() else { return Vec::new() };
+    let mut blocks = cp_base::deref_match!(status, {
+        PlanStatus::Pending => vec![Block::Line(vec![S::muted("  Planning cleanup\u{2026}".into()).italic()])],
+        PlanStatus::Failed(ref error) => {
+            vec![Block::Line(vec![S::muted("  Plan failed: ".into()), S::error(error.clone())])]
+        }
+        PlanStatus::Ready(ref ready) => ready_plan_blocks(ready),
+    });
+    blocks.push(Block::Separator);
+    blocks
+}
+
+impl CleanerPanel {
+    /// Compact LLM-facing summary: the latest run's decisions and report only
+    /// (never the cleaner's chatter, which stays in the UI).
+    fn format_context_text(state: &State) -> String {
+        let Some(run) = CleanerState::get(state).runs.last() else {
+            return "No cleaning runs yet.\n".to_owned();
+        };
+        let mut text = format!(
+            "Last cleaning run #{} ({}), {} decision(s):\n",
+            run.number,
+            run.status.label(),
+            run.decisions.len()
+        );
+        for d in &run.decisions {
+            let _r = writeln!(text, "- {} {} -> {}", d.tool, d.params, d.outcome);
+        }
+        if let Some(report) = run.report.as_ref() {
+            let _r = writeln!(text, "Report: {report}");
+        }
+        text
+    }
+}
+
+impl Panel for CleanerPanel {
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<Block> {
+        let cs = CleanerState::get(state);
+        let mut blocks = plan_blocks(cs);
+        if cs.runs.is_empty() {
+            blocks.push(Block::Line(vec![S::muted("  No cleaning runs yet.".into()).italic()]));
+            return blocks;
+        }
+        if let Some(run) = cs.undoable() {
+            blocks.push(Block::Line(vec![
+                S::muted("  Run #".into()),
+                S::accent(run.number.to_string()),
+                S::muted(" can be rolled back: Ctrl+P \u{2192} ".into()),
+                S::styled("Undo last clean".into(), Semantic::KeyHint),
+            ]));
+            blocks.push(Block::empty());
+        }
+        for run in cs.runs.iter().rev() {
+            blocks.extend(run_header_blocks(run));
+            let live = state.reveries.get(&run.agent_id).filter(|_| run.status == RunStatus::Running);
+            blocks.extend(transcript_blocks(live.map_or(&run.transcript, |r| &r.messages)));
+            blocks.extend(decision_blocks(run));
+            if let Some(report) = run.report.as_ref() {
+                blocks.push(Block::Line(vec![S::muted("  Report: ".into()), S::new(report.clone())]));
+            }
+            blocks.push(Block::Separator);
+        }
+        blocks
+    }
+
+    fn title(&self, state: &State) -> String {
+        CleanerState::get(state)
+            .runs
+            .last()
+            .map_or_else(|| "Cleaner".to_owned(), |run| format!("Cleaner (#{} {})", run.number, run.status.label()))
+    }
+
+    fn refresh(&self, state: &mut State) {
+        let content = Self::format_context_text(state);
+        let token_count = estimate_tokens(&content);
+        if let Some(ctx) = state.context.iter_mut().find(|c| c.context_type.as_str() == Kind::CLEANER) {
+            ctx.token_count = token_count;
+            let _changed = cp_base::panels::update_if_changed(ctx, &content);
+        }
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let Some(ctx) = state.context.iter().find(|c| c.context_type.as_str() == Kind::CLEANER) else {
+            return Vec::new();
+        };
+        vec![ContextItem::new(&ctx.id, "Cleaner", Self::format_context_text(state), ctx.last_refresh_ms)]
+    }
+
+    fn needs_cache(&self) -> bool {
+        false
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        // Always answer, even on a foreign payload, so `cache_in_flight` clears.
+        let result = request
+            .data
+            .downcast::<StructuredQuery>()
+            .map_or_else(|_| Err("unexpected cache request".to_owned()), |query| plan::fetch(&query));
+        Some(CacheUpdate::ModuleSpecific { context_type: Kind::new(Kind::CLEANER), data: Box::new(result) })
+    }
+
+    fn build_cache_request(&self, _ctx: &crate::state::Entry, state: &State) -> Option<CacheRequest> {
+        let query = plan::pending_query(state)?;
+        Some(CacheRequest::new(Kind::new(Kind::CLEANER), Box::new(query)))
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut crate::state::Entry, state: &mut State) -> bool {
+        ctx.cache_deprecated = false;
+        let CacheUpdate::ModuleSpecific { data, .. } = update else { return false };
+        let Ok(result) = data.downcast::<Result<EvictionPlan, String>>() else { return false };
+        plan::receive(state, *result);
+        true
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        None
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn suicide(&self, _ctx: &crate::state::Entry, _state: &State) -> bool {
+        false
+    }
+}
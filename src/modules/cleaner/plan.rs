@@ -0,0 +1,241 @@
+//! Eviction plans — the cleaner's structured, tool-free answer.
+//!
+//! Instead of letting the cleaner act through free-form tool calls, the app
+//! asks the active model for a JSON plan matching [`plan_schema`]: panels to
+//! close and messages to compress or delete. The request runs on the cache
+//! pool (the Cleaner panel's cache request), so the UI never blocks on it.
+//!
+//! A plan that arrives while a cleaning run is in progress is executed by
+//! [`super::execute`] straight away; one requested from Ctrl+K → "Plan
+//! cleanup" is only previewed in the Cleaner panel until the user applies it.
+
+use std::fmt::Write as _;
+
+use serde::Deserialize;
+use serde_json::json;
+
+use cp_base::config::INJECTIONS;
+use cp_base::state::data::model_helpers::ModelPricing as _;
+use cp_base::tools::output_schema::OutputSchema;
+
+use crate::app::prompt::structured::{self, StructuredQuery};
+use crate::state::{Kind, MsgStatus, State};
+
+use super::scope::CleanScope;
+use super::types::{CleanerState, RunStatus};
+
+/// Reverie agent ID whose runs are planned instead of tool-driven.
+pub(crate) const PLANNED_AGENT: &str = "cleaner";
+/// Characters of a message quoted in the prompt.
+const EXCERPT_CHARS: usize = 80;
+/// Characters of the last user message quoted in the prompt.
+const LAST_USER_CHARS: usize = 600;
+
+/// One panel the plan proposes to close.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct PlannedClose {
+    /// Panel ID (e.g. `P7`).
+    pub id: String,
+    /// Why the panel can go.
+    pub reason: String,
+}
+
+/// One message the plan proposes to shrink.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct PlannedMessage {
+    /// Message ID (e.g. `U12`).
+    pub id: String,
+    /// Target status: `head_tail`, `reference` or `deleted`.
+    pub action: MsgStatus,
+    /// Why the message can shrink.
+    pub reason: String,
+}
+
+/// The model's eviction plan.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct EvictionPlan {
+    /// One-sentence summary of the plan.
+    pub summary: String,
+    /// Panels to close.
+    pub close: Vec<PlannedClose>,
+    /// Messages to compress or delete.
+    pub messages: Vec<PlannedMessage>,
+}
+
+/// Where the latest plan request stands.
+#[derive(Debug, Clone)]
+pub(crate) enum PlanStatus {
+    /// Waiting for the model.
+    Pending,
+    /// A validated plan, previewed and not applied yet.
+    Ready(EvictionPlan),
+    /// The request failed or the answer never validated.
+    Failed(String),
+}
+
+impl PlanStatus {
+    /// The plan, once it is ready.
+    pub(crate) const fn ready(&self) -> Option<&EvictionPlan> {
+        cp_base::deref_match!(self, {
+            Self::Ready(ref plan) => Some(plan),
+            Self::Pending | Self::Failed(_) => None,
+        })
+    }
+}
+
+/// Shape of the plan answer.
+fn plan_schema() -> OutputSchema {
+    let item = |extra: serde_json::Value| {
+        let mut properties = json!({"id": {"type": "string"}, "reason": {"type": "string"}});
+        let mut required = vec!["id", "reason"];
+        if let (Some(props), Some(more)) = (properties.as_object_mut(), extra.as_object()) {
+            props.extend(more.clone());
+            required.extend(more.keys().map(String::as_str));
+        }
+        json!({"type": "object", "properties": properties, "required": required, "additionalProperties": false})
+    };
+    OutputSchema::new(
+        "eviction_plan",
+        json!({
+            "type": "object",
+            "properties": {
+                "summary": {"type": "string"},
+                "close": {"type": "array", "items": item(json!({}))},
+                "messages": {
+                    "type": "array",
+                    "items": item(json!({"action": {"type": "string", "enum": ["head_tail", "reference", "deleted"]}}))
+                }
+            },
+            "required": ["summary", "close", "messages"],
+            "additionalProperties": false
+        }),
+    )
+}
+
+/// One `ID | type | name | tokens | minutes` line per closable (unpinned) panel.
+fn panel_lines(state: &State) -> String {
+    let now = cp_base::panels::now_ms();
+    state
+        .context
+        .iter()
+        .filter(|c| !c.context_type.is_fixed() && c.context_type.as_str() != Kind::CONVERSATION_HISTORY)
+        .filter(|c| !crate::modules::overview::panel_group::is_pinned(c))
+        .map(|c| {
+            let minutes = now.saturating_sub(c.last_refresh_ms).checked_div(60_000).unwrap_or(0);
+            format!("{} | {} | {} | {} | {minutes}", c.id, c.context_type.as_str(), c.name, c.token_count)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One `ID | role | status | tokens | excerpt` line per live message.
+fn message_lines(state: &State) -> String {
+    state
+        .messages
+        .iter()
+        .filter(|m| m.status == MsgStatus::Full || m.status.is_compressed())
+        .map(|m| {
+            let first = m.content.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+            let excerpt: String = first.chars().take(EXCERPT_CHARS).collect();
+            format!("{} | {} | {:?} | {} | {excerpt}", m.id, m.role, m.status, m.content_token_count)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The latest user message, shortened.
+fn last_user_message(state: &State) -> String {
+    let last = state.messages.iter().rfind(|m| m.role == "user").map_or("", |m| m.content.as_str());
+    last.chars().take(LAST_USER_CHARS).collect()
+}
+
+/// Directive and scope of the in-flight planned run, as prompt sections.
+fn run_constraints(state: &State) -> String {
+    let Some(run) = CleanerState::get(state).running(PLANNED_AGENT) else { return String::new() };
+    let mut text = String::new();
+    if let Some(directive) = run.directive.as_ref() {
+        let _r = write!(text, "\n## Directive\n{directive}\n");
+    }
+    if let Some(scope) = run.scope.constraints_text(state) {
+        let _r = write!(text, "\n## Scope\n{scope}\n");
+    }
+    text
+}
+
+/// Mark a plan pending and dirty the Cleaner panel so the timer loop hands
+/// its cache request to a worker. Returns `false` when the panel is not open.
+fn mark_pending(state: &mut State) -> bool {
+    let Some(ctx) = state.context.iter_mut().find(|c| c.context_type.as_str() == Kind::CLEANER) else {
+        return false;
+    };
+    ctx.cache_deprecated = true;
+    CleanerState::get_mut(state).plan = Some(PlanStatus::Pending);
+    state.flags.ui.dirty = true;
+    true
+}
+
+/// Ask for a plan to preview (Ctrl+K → "Plan cleanup"). Returns `false` when
+/// the Cleaner panel is not open.
+pub(crate) fn request_plan(state: &mut State) -> bool {
+    mark_pending(state)
+}
+
+/// Start a planned cleaning run: the plan is requested now and executed as
+/// soon as it arrives. Returns `false` when a run is already in progress or
+/// the Cleaner panel is not open.
+pub(crate) fn start_planned_clean(state: &mut State, directive: Option<String>, scope: CleanScope) -> bool {
+    let busy = state.get_ext::<CleanerState>().is_none_or(|cs| cs.runs.iter().any(|r| r.status == RunStatus::Running));
+    if busy || !mark_pending(state) {
+        return false;
+    }
+    super::begin_run(state, PLANNED_AGENT, directive, scope);
+    true
+}
+
+/// The structured query for a pending plan, built from the current context.
+pub(super) fn pending_query(state: &State) -> Option<StructuredQuery> {
+    if !matches!(CleanerState::get(state).plan, Some(PlanStatus::Pending)) {
+        return None;
+    }
+    let prompt = INJECTIONS
+        .providers
+        .cleaner_plan
+        .replace("{panels}", &panel_lines(state))
+        .replace("{messages}", &message_lines(state))
+        .replace("{last_user}", &last_user_message(state))
+        .replace("{constraints}", &run_constraints(state));
+    Some(StructuredQuery {
+        provider: state.llm_provider,
+        model: state.current_model(),
+        system: INJECTIONS.providers.cleaner_plan_system.clone(),
+        prompt,
+        schema: plan_schema(),
+        worker_id: "cleaner-plan".to_owned(),
+    })
+}
+
+/// Run `query` (blocking, on a worker thread) and decode the plan.
+pub(super) fn fetch(query: &StructuredQuery) -> Result<EvictionPlan, String> {
+    let value = structured::run(query)?;
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// File a plan answer: execute it into the in-flight planned run, or keep it
+/// as a preview when no run is waiting for it.
+pub(super) fn receive(state: &mut State, result: Result<EvictionPlan, String>) {
+    let waiting = CleanerState::get(state).running(PLANNED_AGENT).is_some();
+    let status = match result {
+        Ok(plan) if waiting => {
+            super::execute::execute_plan(state, &plan);
+            super::finish_run(state, PLANNED_AGENT, RunStatus::Reported, &plan.summary);
+            None
+        }
+        Err(error) if waiting => {
+            super::finish_run(state, PLANNED_AGENT, RunStatus::Aborted, &error);
+            None
+        }
+        Ok(plan) => Some(PlanStatus::Ready(plan)),
+        Err(error) => Some(PlanStatus::Failed(error)),
+    };
+    CleanerState::get_mut(state).plan = status;
+}
@@ -0,0 +1,203 @@
+//! Cleaner run lifecycle hooks (called from the reverie trigger + event loop)
+//! and the undo operation.
+
+use crate::state::{Entry, Kind, Message, MsgStatus, State};
+
+use super::scope::CleanScope;
+use super::types::{CleanRun, CleanerState, Decision, MAX_RUNS, RunStatus};
+
+/// Longest parameter / outcome excerpt kept per decision.
+const EXCERPT_CHARS: usize = 160;
+
+/// Truncate to `EXCERPT_CHARS` characters (char-safe), appending `...` when cut.
+fn excerpt(text: &str) -> String {
+    if text.chars().count() <= EXCERPT_CHARS {
+        return text.to_owned();
+    }
+    let cut: String = text.chars().take(EXCERPT_CHARS.saturating_sub(3)).collect();
+    format!("{cut}...")
+}
+
+/// Mark the cleaner panel as changed so it re-sorts and re-renders.
+fn touch(state: &mut State) {
+    state.touch_panel(Kind::CLEANER);
+    state.flags.ui.dirty = true;
+}
+
+/// Record the start of a reverie run and snapshot every panel for undo.
+pub(crate) fn begin_run(state: &mut State, agent_id: &str, directive: Option<String>, scope: CleanScope) {
+    let snapshot = state.context.clone();
+    let Some(cs) = state.get_ext_mut::<CleanerState>() else { return };
+    cs.next_number = cs.next_number.saturating_add(1);
+    cs.runs.push(CleanRun {
+        number: cs.next_number,
+        agent_id: agent_id.to_owned(),
+        directive,
+        scope,
+        started_ms: crate::app::panels::now_ms(),
+        status: RunStatus::Running,
+        decisions: Vec::new(),
+        report: None,
+        transcript: Vec::new(),
+        snapshot: Some(snapshot),
+        message_statuses: Vec::new(),
+    });
+    if cs.runs.len() > MAX_RUNS {
+        let excess = cs.runs.len().saturating_sub(MAX_RUNS);
+        drop(cs.runs.drain(..excess));
+    }
+    touch(state);
+}
+
+/// Record one tool call made by the running reverie for `agent_id`.
+pub(crate) fn record_decision(
+    state: &mut State,
+    agent_id: &str,
+    tool: &cp_base::tools::ToolUse,
+    result: &crate::infra::tools::ToolResult,
+) {
+    let Some(run) = state.get_ext_mut::<CleanerState>().and_then(|cs| cs.running_mut(agent_id)) else { return };
+    run.decisions.push(Decision {
+        tool: tool.name.clone(),
+        params: excerpt(&serde_json::to_string(&tool.input).unwrap_or_default()),
+        outcome: excerpt(result.content.lines().next().unwrap_or("")),
+        is_error: result.is_error,
+    });
+    touch(state);
+}
+
+/// Close the running run for `agent_id`, keeping the reverie transcript for
+/// the panel. Must be called before the reverie session is removed.
+pub(crate) fn finish_run(state: &mut State, agent_id: &str, status: RunStatus, report: &str) {
+    let transcript: Vec<Message> = state.reveries.get(agent_id).map(|r| r.messages.clone()).unwrap_or_default();
+    let Some(run) = state.get_ext_mut::<CleanerState>().and_then(|cs| cs.running_mut(agent_id)) else { return };
+    run.status = status;
+    run.report = Some(report.to_owned());
+    run.transcript = transcript;
+    touch(state);
+}
+
+/// Whether `current` is the same panel as the snapshot `entry`.
+fn same_panel(current: &Entry, entry: &Entry) -> bool {
+    match (current.uid.as_deref(), entry.uid.as_deref()) {
+        (Some(a_uid), Some(b_uid)) => a_uid == b_uid,
+        _ => current.id == entry.id && current.context_type == entry.context_type,
+    }
+}
+
+/// Re-insert every snapshot panel that no longer exists. A panel whose old ID
+/// was reused in the meantime gets a fresh one. Returns the restored IDs.
+fn restore_panels(state: &mut State, snapshot: Vec<Entry>) -> Vec<String> {
+    let mut restored = Vec::new();
+    for mut entry in snapshot {
+        if state.context.iter().any(|c| same_panel(c, &entry)) {
+            continue;
+        }
+        if state.context.iter().any(|c| c.id == entry.id) {
+            entry.id = state.next_available_context_id();
+        }
+        entry.cache_deprecated = true;
+        entry.cache_in_flight = false;
+        entry.last_refresh_ms = crate::app::panels::now_ms();
+        restored.push(entry.id.clone());
+        state.context.push(entry);
+    }
+    restored
+}
+
+/// Put back the prior status of every message a run compressed or deleted.
+/// Returns how many messages changed.
+fn restore_messages(state: &mut State, statuses: &[(String, MsgStatus)]) -> usize {
+    let mut restored: usize = 0;
+    for saved in statuses {
+        if let Some(msg) = state.messages.iter_mut().find(|m| m.id == saved.0 && m.status != saved.1) {
+            msg.status = saved.1;
+            crate::state::persistence::save_message(msg);
+            restored = restored.saturating_add(1);
+        }
+    }
+    if restored > 0 {
+        state.touch_panel(Kind::CONVERSATION);
+    }
+    restored
+}
+
+/// Roll back the most recent finished run: every panel it closed comes back
+/// and every message it compressed or deleted gets its prior status.
+///
+/// Other side effects (memories, logs, tree descriptions) are not reverted. Refused while any run is still in progress.
+pub(crate) fn undo_last_run(state: &mut State) -> Result<String, String> {
+    let cs = state.get_ext::<CleanerState>().ok_or_else(|| "Cleaner is not initialized.".to_owned())?;
+    if cs.runs.iter().any(|r| r.status == RunStatus::Running) {
+        return Err("A cleaning run is still in progress \u{2014} wait for it to finish.".to_owned());
+    }
+    let number = cs.undoable().map(|r| r.number).ok_or_else(|| "Nothing to undo.".to_owned())?;
+
+    let (snapshot, statuses) = {
+        let cs_mut = CleanerState::get_mut(state);
+        let Some(run) = cs_mut.runs.iter_mut().find(|r| r.number == number) else {
+            return Err("Nothing to undo.".to_owned());
+        };
+        run.status = RunStatus::Undone;
+        (run.snapshot.take().unwrap_or_default(), std::mem::take(&mut run.message_statuses))
+    };
+    let restored = restore_panels(state, snapshot);
+    let messages = restore_messages(state, &statuses);
+    touch(state);
+    Ok(match (restored.is_empty(), messages) {
+        (true, 0) => format!("Undid cleaning run #{number}: nothing needed restoring."),
+        (_, 0) => {
+            format!("Undid cleaning run #{number}: restored {} panel(s) ({}).", restored.len(), restored.join(", "))
+        }
+        _ => format!("Undid cleaning run #{number}: restored {} panel(s) and {messages} message(s).", restored.len()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A state with the cleaner history initialized and one dynamic panel `P12`.
+    fn state_with_panel() -> State {
+        let mut state = State::default();
+        state.set_ext(CleanerState::default());
+        let mut entry = crate::state::make_default_entry("P12", Kind::new(Kind::FILE), "main.rs", false);
+        entry.uid = Some("UID_7_P".to_owned());
+        state.context.push(entry);
+        state
+    }
+
+    #[test]
+    fn undo_restores_closed_panel() {
+        let mut state = state_with_panel();
+        begin_run(&mut state, "cleaner", None, CleanScope::Everything);
+        state.context.retain(|c| c.id != "P12");
+        finish_run(&mut state, "cleaner", RunStatus::Reported, "closed P12");
+
+        let msg = undo_last_run(&mut state);
+        assert!(msg.is_ok_and(|m| m.contains("P12")));
+        assert!(state.context.iter().any(|c| c.uid.as_deref() == Some("UID_7_P")));
+        assert_eq!(CleanerState::get(&state).runs.first().map(|r| r.status), Some(RunStatus::Undone));
+    }
+
+    #[test]
+    fn undo_refused_while_running_and_when_empty() {
+        let mut state = state_with_panel();
+        assert!(undo_last_run(&mut state).err().is_some());
+        begin_run(&mut state, "cleaner", Some("focus".to_owned()), CleanScope::PanelsOnly);
+        assert!(undo_last_run(&mut state).err().is_some());
+    }
+
+    #[test]
+    fn reused_id_gets_fresh_one() {
+        let mut state = state_with_panel();
+        begin_run(&mut state, "cleaner", None, CleanScope::Everything);
+        state.context.retain(|c| c.id != "P12");
+        state.context.push(crate::state::make_default_entry("P12", Kind::new(Kind::GLOB), "glob", false));
+        finish_run(&mut state, "cleaner", RunStatus::Reported, "");
+
+        assert!(undo_last_run(&mut state).ok().is_some());
+        let restored = state.context.iter().find(|c| c.uid.as_deref() == Some("UID_7_P"));
+        assert!(restored.is_some_and(|c| c.id != "P12"));
+    }
+}
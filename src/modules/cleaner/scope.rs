@@ -0,0 +1,242 @@
+//! Cleaning scope: which part of the context a cleaner run may drop.
+//!
+//! Picked by the user from the Ctrl+K picker, injected into the reverie seed
+//! as structured constraints, and enforced on every closing tool the cleaner
+//! calls — an out-of-scope `Close_panel` is refused before it can be queued.
+
+use crate::state::{Entry, Kind, Message, State};
+
+use super::types::{CleanerState, RunStatus};
+
+/// Which part of the context a cleaning run is allowed to drop.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) enum CleanScope {
+    /// One global pass over everything (threshold and `optimize_context` runs).
+    #[default]
+    Everything,
+    /// Only conversation history panels.
+    MessagesOnly,
+    /// Only non-conversation panels.
+    PanelsOnly,
+    /// Only items created before the given message.
+    OlderThan {
+        /// Display ID of the cutoff message (e.g. `U12`).
+        message_id: String,
+        /// Global UID counter value of that message.
+        cutoff: u64,
+    },
+    /// Only the listed panel IDs.
+    Panels(Vec<String>),
+}
+
+/// Numeric part of an internal UID (`UID_42_P` → 42).
+fn uid_number(uid: &str) -> Option<u64> {
+    uid.strip_prefix("UID_")?.split('_').next()?.parse().ok()
+}
+
+/// Creation order of a panel: a history panel is as old as its newest
+/// archived message, anything else as old as its own UID.
+fn panel_age(entry: &Entry) -> Option<u64> {
+    let newest_message = entry.history_messages.as_ref().and_then(|msgs| msgs.last()).and_then(|m| m.uid.as_deref());
+    newest_message.or(entry.uid.as_deref()).and_then(uid_number)
+}
+
+/// Resolve a message reference (`U12`, `a7`, or bare `12` for `U12`) to its
+/// display ID and UID counter, searching live and archived messages.
+fn resolve_message(state: &State, reference: &str) -> Option<(String, u64)> {
+    let wanted =
+        if reference.chars().all(|c| c.is_ascii_digit()) { format!("U{reference}") } else { reference.to_uppercase() };
+    let archived = state.context.iter().filter_map(|c| c.history_messages.as_ref()).flatten();
+    let msg = state.messages.iter().chain(archived).rfind(|m| m.id == wanted)?;
+    Some((wanted, uid_number(msg.uid.as_deref()?)?))
+}
+
+/// Parse a list of existing panel IDs (`P3 P7` or `p3,p7`).
+fn resolve_panels(state: &State, text: &str) -> Option<Vec<String>> {
+    let ids: Vec<String> =
+        text.split(|c: char| c == ',' || c.is_whitespace()).filter(|t| !t.is_empty()).map(str::to_uppercase).collect();
+    let all_exist = !ids.is_empty() && ids.iter().all(|id| state.context.iter().any(|c| c.id == *id));
+    all_exist.then_some(ids)
+}
+
+impl CleanScope {
+    /// Interpret the picker's free-text argument: a message reference gives
+    /// an "older than" scope, a list of panel IDs a "panels" scope.
+    pub(crate) fn from_argument(state: &State, text: &str) -> Option<Self> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+        if let Some(ids) = resolve_panels(state, trimmed) {
+            return Some(Self::Panels(ids));
+        }
+        resolve_message(state, trimmed).map(|(message_id, cutoff)| Self::OlderThan { message_id, cutoff })
+    }
+
+    /// Compact textual form used as the picker command argument.
+    pub(crate) fn to_spec(&self) -> String {
+        cp_base::deref_match!(self, {
+            Self::Everything => "all".to_owned(),
+            Self::MessagesOnly => "messages".to_owned(),
+            Self::PanelsOnly => "panels".to_owned(),
+            Self::OlderThan { ref message_id, .. } => message_id.clone(),
+            Self::Panels(ref ids) => ids.join(" "),
+        })
+    }
+
+    /// Inverse of [`Self::to_spec`], re-resolved against the current state.
+    pub(crate) fn from_spec(state: &State, spec: &str) -> Option<Self> {
+        match spec {
+            "all" => Some(Self::Everything),
+            "messages" => Some(Self::MessagesOnly),
+            "panels" => Some(Self::PanelsOnly),
+            _ => Self::from_argument(state, spec),
+        }
+    }
+
+    /// Short human label (panel header, picker entries).
+    pub(crate) fn label(&self) -> String {
+        cp_base::deref_match!(self, {
+            Self::Everything => "everything".to_owned(),
+            Self::MessagesOnly => "messages only".to_owned(),
+            Self::PanelsOnly => "panels only".to_owned(),
+            Self::OlderThan { ref message_id, .. } => format!("everything older than {message_id}"),
+            Self::Panels(ref ids) => format!("panels {}", ids.join(", ")),
+        })
+    }
+
+    /// Whether a run with this scope may drop `entry`.
+    pub(crate) fn allows(&self, entry: &Entry) -> bool {
+        let is_history = entry.context_type.as_str() == Kind::CONVERSATION_HISTORY;
+        cp_base::deref_match!(self, {
+            Self::Everything => true,
+            Self::MessagesOnly => is_history,
+            Self::PanelsOnly => !is_history,
+            Self::OlderThan { cutoff, .. } => panel_age(entry).is_some_and(|age| age < cutoff),
+            Self::Panels(ref ids) => ids.contains(&entry.id),
+        })
+    }
+
+    /// Whether a run with this scope may compress or delete `msg`.
+    pub(crate) fn allows_message(&self, msg: &Message) -> bool {
+        cp_base::deref_match!(self, {
+            Self::Everything | Self::MessagesOnly => true,
+            Self::PanelsOnly | Self::Panels(_) => false,
+            Self::OlderThan { cutoff, .. } => msg.uid.as_deref().and_then(uid_number).is_some_and(|n| n < cutoff),
+        })
+    }
+
+    /// Constraint text for the reverie seed; `None` for an unscoped run.
+    pub(crate) fn constraints_text(&self, state: &State) -> Option<String> {
+        if *self == Self::Everything {
+            return None;
+        }
+        let panels = state.context.iter().filter(|c| self.allows(c)).map(|c| c.id.as_str());
+        let messages = state.messages.iter().filter(|m| self.allows_message(m)).map(|m| m.id.as_str());
+        let allowed: Vec<&str> = panels.chain(messages).collect();
+        let allowed_list = if allowed.is_empty() { "(none)".to_owned() } else { allowed.join(", ") };
+        Some(format!(
+            "The user limited this run to: {}.\nYou may only close, compress or delete these items: {allowed_list}.\n\
+             Closing anything else will be refused. Leave everything outside the scope untouched.",
+            self.label()
+        ))
+    }
+}
+
+/// IDs of the panels a closing tool targets (`Close_panel` ids,
+/// `Close_conversation_history` panel IDs). Empty for every other tool.
+fn targeted_panels(tool: &cp_base::tools::ToolUse) -> Vec<String> {
+    let as_ids = |items: &Vec<serde_json::Value>, key: Option<&str>| -> Vec<String> {
+        items
+            .iter()
+            .filter_map(|v| key.map_or(Some(v), |k| v.get(k)))
+            .filter_map(serde_json::Value::as_str)
+            .map(str::to_owned)
+            .collect()
+    };
+    match tool.name.as_str() {
+        "Close_panel" => tool.input.get("ids").and_then(serde_json::Value::as_array).map(|a| as_ids(a, None)),
+        "Close_conversation_history" => {
+            tool.input.get("panels").and_then(serde_json::Value::as_array).map(|a| as_ids(a, Some("panel_id")))
+        }
+        _ => None,
+    }
+    .unwrap_or_default()
+}
+
+/// The scope of the in-flight run driven by `agent_id`.
+fn running_scope<'st>(state: &'st State, agent_id: &str) -> Option<&'st CleanScope> {
+    let cs = state.get_ext::<CleanerState>()?;
+    cs.runs.iter().rev().find(|r| r.agent_id == agent_id && r.status == RunStatus::Running).map(|r| &r.scope)
+}
+
+/// Refuse a reverie tool call that would close something outside its run's scope.
+pub(crate) fn check_scope(state: &State, agent_id: &str, tool: &cp_base::tools::ToolUse) -> Result<(), String> {
+    let Some(scope) = running_scope(state, agent_id) else { return Ok(()) };
+    let outside: Vec<String> = targeted_panels(tool)
+        .into_iter()
+        .filter(|id| state.context.iter().find(|c| c.id == *id).is_some_and(|c| !scope.allows(c)))
+        .collect();
+    if outside.is_empty() {
+        return Ok(());
+    }
+    Err(format!("Refused: {} outside this run's scope ({}). Leave them untouched.", outside.join(", "), scope.label()))
+}
+
+/// Seed constraints for the in-flight run driven by `agent_id`, if scoped.
+pub(crate) fn scope_constraints(state: &State, agent_id: &str) -> Option<String> {
+    running_scope(state, agent_id)?.constraints_text(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file panel and a history panel with UIDs 5 and 9, plus message `U3` (UID 7).
+    fn sample_state() -> State {
+        let mut state = State::default();
+        let mut file = crate::state::make_default_entry("P4", Kind::new(Kind::FILE), "main.rs", false);
+        file.uid = Some("UID_5_P".to_owned());
+        let mut hist = crate::state::make_default_entry("P5", Kind::new(Kind::CONVERSATION_HISTORY), "chat", false);
+        hist.uid = Some("UID_9_P".to_owned());
+        state.context.push(file);
+        state.context.push(hist);
+        state.messages.push(Message::new_user("U3".to_owned(), "UID_7_U".to_owned(), "hi".to_owned(), 1));
+        state
+    }
+
+    #[test]
+    fn argument_parsing() {
+        let state = sample_state();
+        assert_eq!(
+            CleanScope::from_argument(&state, "p4, P5"),
+            Some(CleanScope::Panels(vec!["P4".into(), "P5".into()]))
+        );
+        assert_eq!(
+            CleanScope::from_argument(&state, "3"),
+            Some(CleanScope::OlderThan { message_id: "U3".into(), cutoff: 7 })
+        );
+        assert_eq!(CleanScope::from_argument(&state, "P99"), None);
+    }
+
+    #[test]
+    fn scope_membership() {
+        let state = sample_state();
+        let ids = |scope: &CleanScope| -> Vec<String> {
+            state.context.iter().filter(|c| scope.allows(c)).map(|c| c.id.clone()).collect()
+        };
+        assert_eq!(ids(&CleanScope::MessagesOnly), vec!["P5"]);
+        assert_eq!(ids(&CleanScope::PanelsOnly), vec!["P4"]);
+        assert_eq!(ids(&CleanScope::OlderThan { message_id: "U3".into(), cutoff: 7 }), vec!["P4"]);
+    }
+
+    #[test]
+    fn message_membership() {
+        let state = sample_state();
+        let msg_allowed = |scope: &CleanScope| state.messages.iter().any(|m| scope.allows_message(m));
+        assert!(msg_allowed(&CleanScope::MessagesOnly));
+        assert!(!msg_allowed(&CleanScope::PanelsOnly));
+        assert!(!msg_allowed(&CleanScope::OlderThan { message_id: "U3".into(), cutoff: 7 }));
+        assert!(msg_allowed(&CleanScope::OlderThan { message_id: "U4".into(), cutoff: 8 }));
+    }
+}
@@ -0,0 +1,119 @@
+//! Cleaner run history: what each context-optimizer run decided, plus the
+//! pre-clean panel snapshot used by undo.
+//!
+//! RAM-only, like the reverie session itself — snapshots hold full panel
+//! clones and are not worth persisting across reloads.
+
+use crate::state::{Entry, Message, MsgStatus, State};
+
+use super::plan::PlanStatus;
+use super::scope::CleanScope;
+
+/// Maximum number of runs kept in history (oldest dropped first).
+pub(super) const MAX_RUNS: usize = 5;
+
+/// Lifecycle of one cleaner run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RunStatus {
+    /// The reverie is still working.
+    Running,
+    /// The reverie called its Report tool.
+    Reported,
+    /// The run ended abnormally (stream error, tool cap, missing report).
+    Aborted,
+    /// The user rolled the run back.
+    Undone,
+}
+
+impl RunStatus {
+    /// Short label shown in the panel header and LLM context.
+    pub(super) const fn label(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Reported => "reported",
+            Self::Aborted => "aborted",
+            Self::Undone => "undone",
+        }
+    }
+}
+
+/// One tool call the cleaner made, reduced to what a human needs to audit it.
+#[derive(Debug, Clone)]
+pub(crate) struct Decision {
+    /// Tool the cleaner invoked (e.g. `Close_panel`).
+    pub tool: String,
+    /// Compact JSON rendering of the tool input.
+    pub params: String,
+    /// First line of the tool result.
+    pub outcome: String,
+    /// Whether the tool reported an error.
+    pub is_error: bool,
+}
+
+/// A single cleaner (context optimizer) run.
+#[derive(Debug, Clone)]
+pub(crate) struct CleanRun {
+    /// Monotonic run number, starting at 1.
+    pub number: usize,
+    /// Reverie agent that drove the run (usually `cleaner`).
+    pub agent_id: String,
+    /// Directive passed by `optimize_context`, `None` for threshold triggers.
+    pub directive: Option<String>,
+    /// What the run may drop (picked via Ctrl+K; `Everything` otherwise).
+    pub scope: CleanScope,
+    /// When the run started (ms since epoch).
+    pub started_ms: u64,
+    /// Current lifecycle status.
+    pub status: RunStatus,
+    /// Tool calls made during the run, in order.
+    pub decisions: Vec<Decision>,
+    /// Report summary, or the abort reason.
+    pub report: Option<String>,
+    /// The reverie's own conversation, copied out when the run ends.
+    pub transcript: Vec<Message>,
+    /// Every context panel as it was when the run started; dropped once undone.
+    pub snapshot: Option<Vec<Entry>>,
+    /// Prior status of every message the run compressed or deleted (for undo).
+    pub message_statuses: Vec<(String, MsgStatus)>,
+}
+
+/// Cleaner history stored in `State`'s `TypeMap`.
+#[derive(Debug, Default)]
+pub(crate) struct CleanerState {
+    /// Recent runs, oldest first.
+    pub runs: Vec<CleanRun>,
+    /// Number assigned to the next run.
+    pub next_number: usize,
+    /// Latest eviction plan requested from Ctrl+K, if any.
+    pub plan: Option<PlanStatus>,
+}
+
+impl CleanerState {
+    /// Shared reference to the cleaner history.
+    pub(crate) fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Mutable reference to the cleaner history.
+    pub(crate) fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+
+    /// The in-flight run for `agent_id`, if any.
+    pub(crate) fn running(&self, agent_id: &str) -> Option<&CleanRun> {
+        self.runs.iter().rev().find(|r| r.agent_id == agent_id && r.status == RunStatus::Running)
+    }
+
+    /// Mutable access to the in-flight run for `agent_id`, if any.
+    pub(super) fn running_mut(&mut self, agent_id: &str) -> Option<&mut CleanRun> {
+        self.runs.iter_mut().rev().find(|r| r.agent_id == agent_id && r.status == RunStatus::Running)
+    }
+
+    /// The most recent finished run that can still be rolled back.
+    pub(crate) fn undoable(&self) -> Option<&CleanRun> {
+        self.runs
+            .iter()
+            .rev()
+            .find(|r| r.snapshot.is_some() && matches!(r.status, RunStatus::Reported | RunStatus::Aborted))
+    }
+}
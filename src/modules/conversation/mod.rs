@@ -18,6 +18,7 @@ pub(crate) use panel::build_content_cached;
 use crate::app::panels::Panel;
 use crate::infra::tools::{ToolDefinition, ToolResult, ToolUse};
 use crate::state::{Kind, State, TypeMeta};
+use cp_base::state::data::sticky::StandingInstructions;
 
 use self::panel::ConversationPanel;
 use super::Module;
@@ -86,9 +87,13 @@ impl Module for ConversationModule {
         &[]
     }
 
-    fn init_state(&self, _state: &mut State) {}
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(StandingInstructions::default());
+    }
 
-    fn reset_state(&self, _state: &mut State) {}
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(StandingInstructions::default());
+    }
 
     fn save_module_data(&self, _state: &State) -> serde_json::Value {
         serde_json::Value::Null
@@ -96,11 +101,19 @@ impl Module for ConversationModule {
 
     fn load_module_data(&self, _data: &serde_json::Value, _state: &mut State) {}
 
-    fn save_worker_data(&self, _state: &State) -> serde_json::Value {
-        serde_json::Value::Null
+    fn save_worker_data(&self, state: &State) -> serde_json::Value {
+        state
+            .get_ext::<StandingInstructions>()
+            .filter(|s| !s.text.is_empty())
+            .and_then(|s| serde_json::to_value(s).ok())
+            .unwrap_or(serde_json::Value::Null)
     }
 
-    fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+    fn load_worker_data(&self, data: &serde_json::Value, state: &mut State) {
+        if let Ok(instructions) = serde_json::from_value::<StandingInstructions>(data.clone()) {
+            state.set_ext(instructions);
+        }
+    }
 
     fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<crate::infra::tools::Verdict> {
         None
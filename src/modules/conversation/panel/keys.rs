@@ -0,0 +1,158 @@
+//! Key bindings for the conversation panel: typing, cursor movement and
+//! selection, editing chords, and the `Enter` send/newline/list decision.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::actions::Action;
+use crate::state::State;
+use cp_base::panels::scroll_key_action;
+
+use super::super::list::{self, ListAction};
+
+/// Map a key pressed with the conversation panel focused to its action.
+pub(super) fn key_action(key: &KeyEvent, state: &State) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+    // Modifier-combo shortcuts (Ctrl+Backspace/A/W/Y, Alt+K/S, Ctrl/Alt+Arrow word jump)
+    if let Some(action) = handle_modifier_combo(key.code, &Mods { ctrl, shift, alt }) {
+        return Some(action);
+    }
+
+    // Regular typing and editing
+    match key.code {
+        KeyCode::Char(c) => Some(Action::InputChar(c)),
+        KeyCode::Backspace => Some(Action::InputBackspace),
+        KeyCode::Delete => Some(Action::InputDelete),
+        KeyCode::Left if shift => Some(Action::CursorLeftSelect),
+        KeyCode::Left => Some(Action::CursorLeft),
+        KeyCode::Right if shift => Some(Action::CursorRightSelect),
+        KeyCode::Right => Some(Action::CursorRight),
+        KeyCode::Enter => Some(handle_enter_key(state)),
+        KeyCode::Home if shift => Some(Action::CursorHomeSelect),
+        KeyCode::Home => Some(Action::CursorHome),
+        KeyCode::End if shift => Some(Action::CursorEndSelect),
+        KeyCode::End => Some(Action::CursorEnd),
+        KeyCode::Up => Some(vertical_arrow_action(true, shift)),
+        KeyCode::Down => Some(vertical_arrow_action(false, shift)),
+        // Remaining variants: delegate scroll keys, ignore everything else
+        KeyCode::PageUp | KeyCode::PageDown => scroll_key_action(key),
+        KeyCode::Tab
+        | KeyCode::BackTab
+        | KeyCode::Insert
+        | KeyCode::F(_)
+        | KeyCode::Null
+        | KeyCode::Esc
+        | KeyCode::CapsLock
+        | KeyCode::ScrollLock
+        | KeyCode::NumLock
+        | KeyCode::PrintScreen
+        | KeyCode::Pause
+        | KeyCode::Menu
+        | KeyCode::KeypadBegin
+        | KeyCode::Media(_)
+        | KeyCode::Modifier(_) => None,
+    }
+}
+
+/// Word-jump action for a `Ctrl`/`Alt`+arrow, selecting when `shift` is held.
+const fn word_arrow_action(is_left: bool, shift: bool) -> Action {
+    match (is_left, shift) {
+        (true, true) => Action::CursorWordLeftSelect,
+        (true, false) => Action::CursorWordLeft,
+        (false, true) => Action::CursorWordRightSelect,
+        (false, false) => Action::CursorWordRight,
+    }
+}
+
+/// Line-move action for an Up/Down arrow, selecting when `shift` is held.
+const fn vertical_arrow_action(is_up: bool, shift: bool) -> Action {
+    match (is_up, shift) {
+        (true, true) => Action::CursorUpSelect,
+        (true, false) => Action::CursorUp,
+        (false, true) => Action::CursorDownSelect,
+        (false, false) => Action::CursorDown,
+    }
+}
+
+/// Active keyboard modifier flags for a key event.
+struct Mods {
+    /// Control held.
+    ctrl: bool,
+    /// Shift held.
+    shift: bool,
+    /// Alt held.
+    alt: bool,
+}
+
+/// Resolve modifier-combo shortcuts (`Ctrl+Backspace`/`A`/`W`/`Y`, `Alt+K`/`S`,
+/// `Ctrl`/`Alt`+arrow word jump with optional `Shift` selection). `None` falls
+/// through to the plain key match.
+const fn handle_modifier_combo(code: KeyCode, mods: &Mods) -> Option<Action> {
+    let word_mod = mods.ctrl || mods.alt;
+    match code {
+        KeyCode::Backspace if mods.ctrl => Some(Action::DeleteWordLeft),
+        KeyCode::Char(c) => chord_action(c, mods),
+        KeyCode::Left if word_mod => Some(word_arrow_action(true, mods.shift)),
+        KeyCode::Right if word_mod => Some(word_arrow_action(false, mods.shift)),
+        KeyCode::Backspace
+        | KeyCode::Enter
+        | KeyCode::Left
+        | KeyCode::Right
+        | KeyCode::Up
+        | KeyCode::Down
+        | KeyCode::Home
+        | KeyCode::End
+        | KeyCode::PageUp
+        | KeyCode::PageDown
+        | KeyCode::Tab
+        | KeyCode::BackTab
+        | KeyCode::Delete
+        | KeyCode::Insert
+        | KeyCode::F(_)
+        | KeyCode::Null
+        | KeyCode::Esc
+        | KeyCode::CapsLock
+        | KeyCode::ScrollLock
+        | KeyCode::NumLock
+        | KeyCode::PrintScreen
+        | KeyCode::Pause
+        | KeyCode::Menu
+        | KeyCode::KeypadBegin
+        | KeyCode::Media(_)
+        | KeyCode::Modifier(_) => None,
+    }
+}
+
+/// Editing chords on a character key: `Ctrl+A` select all, `Ctrl+W` delete
+/// word, `Ctrl+Y` yank, `Alt+K` kill to end of line, `Alt+S` spelling
+/// suggestions.
+const fn chord_action(c: char, mods: &Mods) -> Option<Action> {
+    match c {
+        'a' if mods.ctrl => Some(Action::SelectAll),
+        'w' if mods.ctrl => Some(Action::DeleteWordLeft),
+        'y' if mods.ctrl => Some(Action::Yank),
+        'k' if mods.alt => Some(Action::KillLineEnd),
+        's' if mods.alt => Some(Action::SpellSuggest),
+        _ => None,
+    }
+}
+
+/// Resolve the `Enter` key: send on an empty trailing line at end-of-input,
+/// else continue/close a markdown list, else insert a newline.
+fn handle_enter_key(state: &State) -> Action {
+    // Send if: cursor at end AND (input empty OR ends with empty line)
+    let at_end = state.input_cursor >= state.input.len();
+    let ends_with_empty_line =
+        state.input.ends_with('\n') || state.input.lines().last().is_none_or(|l| l.trim().is_empty());
+
+    if at_end && ends_with_empty_line {
+        return Action::InputSubmit;
+    }
+    match list::detect_list_action(&state.input) {
+        Some(ListAction::Continue(text)) => Action::InsertText(text),
+        Some(ListAction::RemoveItem) => Action::RemoveListItem,
+        None => Action::InputChar('\n'),
+    }
+}
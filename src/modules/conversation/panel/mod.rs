@@ -1,15 +1,19 @@
+/// Key bindings: typing, cursor movement, editing chords, `Enter`.
+mod keys;
+
 use std::rc::Rc;
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::KeyEvent;
 
 use cp_render::Block;
 
 use crate::app::actions::Action;
 use crate::app::panels::{ContextItem, Panel};
 use crate::state::{FullCache, InputCache, Kind, MessageCache, MsgKind, MsgStatus, State, hash_values};
-use cp_base::panels::scroll_key_action;
+use crate::ui::helpers::truncate_string;
+use cp_base::state::data::review::{MessageJump, MsgMarker};
+use cp_base::state::data::sticky::StandingInstructions;
 
-use super::list::{self, ListAction};
 use super::render_blocks::{self, MessageBlockOpts};
 use super::render_input_blocks::{self, InputBlockCtx};
 use cp_base::cast::Safe as _;
@@ -21,12 +25,12 @@ impl ConversationPanel {
     /// Compute hash for message cache invalidation
     fn compute_message_hash(msg: &crate::state::Message, viewport_width: u16, dev_mode: bool) -> u64 {
         // Include all fields that affect rendering
-        let status_num = if msg.status == MsgStatus::Full {
-            0u8
-        } else if msg.status == MsgStatus::Deleted {
-            2
-        } else {
-            3
+        let status_num = match msg.status {
+            MsgStatus::Full => 0u8,
+            MsgStatus::HeadTail => 1,
+            MsgStatus::Deleted => 2,
+            MsgStatus::Detached => 3,
+            MsgStatus::Reference => 4,
         };
         let tool_uses_len = msg.tool_uses.len();
         let tool_results_len = msg.tool_results.len();
@@ -42,6 +46,9 @@ impl ConversationPanel {
                 tool_results_len,
                 msg.input_tokens
             ),
+            &MsgMarker::icons(&msg.markers),
+            msg.note.as_deref().unwrap_or(""),
+            &msg.latency.map_or_else(String::new, |t| format!("{}/{}/{}", t.ttft, t.stream, t.tools)),
         ])
     }
 
@@ -59,6 +66,7 @@ impl ConversationPanel {
         std::hash::Hash::hash(&viewport_width, &mut hasher);
         std::hash::Hash::hash(&state.flags.ui.dev_mode, &mut hasher);
         std::hash::Hash::hash(&state.flags.stream.phase.is_streaming(), &mut hasher);
+        std::hash::Hash::hash(&state.flags.lifecycle.offline_since_ms.is_some(), &mut hasher);
 
         // Hash conversation history panel count (invalidate when panels added/removed)
         let history_count =
@@ -74,6 +82,7 @@ impl ConversationPanel {
             std::hash::Hash::hash(&msg.tool_uses.len(), &mut hasher);
             std::hash::Hash::hash(&msg.tool_results.len(), &mut hasher);
             std::hash::Hash::hash(&msg.input_tokens, &mut hasher);
+            std::hash::Hash::hash(&msg.markers, &mut hasher);
         }
 
         // Hash streaming tool state (invalidate when tool preview changes)
@@ -82,6 +91,12 @@ impl ConversationPanel {
             std::hash::Hash::hash(&st.input_so_far, &mut hasher);
         }
 
+        // Hash standing instructions (banner)
+        if let Some(instructions) = state.get_ext::<StandingInstructions>() {
+            std::hash::Hash::hash(&instructions.text, &mut hasher);
+            std::hash::Hash::hash(&instructions.is_editing(), &mut hasher);
+        }
+
         // Hash input
         std::hash::Hash::hash(&state.input, &mut hasher);
         std::hash::Hash::hash(&state.input_cursor, &mut hasher);
@@ -119,20 +134,28 @@ impl ConversationPanel {
         }
     }
 
+    /// Whether `msg` is still streaming, or `None` when it is not shown at all
+    /// (deleted, or an empty text message that is not streaming).
+    fn shown_streaming(state: &State, msg: &crate::state::Message, last_msg_id: Option<&String>) -> Option<bool> {
+        if msg.status == MsgStatus::Deleted {
+            return None;
+        }
+        let is_last = last_msg_id == Some(&msg.id);
+        let is_streaming_this = state.flags.stream.phase.is_streaming() && is_last && msg.role == "assistant";
+        let empty = msg.msg_type == MsgKind::TextMessage && msg.content.trim().is_empty();
+        (!empty || is_streaming_this).then_some(is_streaming_this)
+    }
+
     /// Render the live conversation messages (with per-message caching), skipping
     /// deleted + empty-non-streaming messages.
     fn push_message_blocks(state: &mut State, blocks: &mut Vec<Block>, viewport_width: u16) {
         let last_msg_id = state.messages.last().map(|m| m.id.clone());
+        let jump_to = state.get_ext::<MessageJump>().map(|jump| jump.message_id.clone());
+        let mut jump_line = None;
         for msg in &state.messages {
-            if msg.status == MsgStatus::Deleted {
-                continue;
-            }
-            let is_last = last_msg_id.as_ref() == Some(&msg.id);
-            let is_streaming_this = state.flags.stream.phase.is_streaming() && is_last && msg.role == "assistant";
-
-            // Skip empty text messages (unless streaming)
-            if msg.msg_type == MsgKind::TextMessage && msg.content.trim().is_empty() && !is_streaming_this {
-                continue;
+            let Some(is_streaming_this) = Self::shown_streaming(state, msg, last_msg_id.as_ref()) else { continue };
+            if jump_to.as_ref() == Some(&msg.id) {
+                jump_line = Some(blocks.len());
             }
 
             let hash = Self::compute_message_hash(msg, viewport_width, state.flags.ui.dev_mode);
@@ -159,6 +182,9 @@ impl ConversationPanel {
             }
             blocks.extend(rendered);
         }
+        if let Some(jump) = state.get_ext_mut::<MessageJump>() {
+            jump.line = jump_line;
+        }
     }
 
     /// Render the input area fresh and store it in the input cache (miss path).
@@ -177,6 +203,7 @@ impl ConversationPanel {
                 paste_buffers: &state.paste_buffers,
                 paste_buffer_labels: &state.paste_buffer_labels,
                 viewport_width,
+                spell_check: cp_base::ui::spell::Settings::enabled(state),
             },
         );
         state.input_cache = Some(InputCache::new(Rc::from(input_blocks.as_slice()), input_hash, viewport_width));
@@ -186,6 +213,11 @@ impl ConversationPanel {
     /// Render the input area (cached by input hash), updating the autocomplete
     /// popup's visual-line count. Renders fresh + stores on cache miss.
     fn push_input_area(state: &mut State, blocks: &mut Vec<Block>, viewport_width: u16) {
+        Self::push_offline_banner(state, blocks);
+        Self::push_pairing_banner(state, blocks);
+        Self::push_instructions_banner(state, blocks, viewport_width);
+        blocks.extend(crate::modules::pasted::offer_banner(state));
+        blocks.extend(crate::modules::overview::context_health::banner(state));
         let input_hash =
             Self::compute_input_hash(&state.input, state.input_cursor, state.input_selection_anchor, viewport_width);
 
@@ -207,6 +239,54 @@ impl ConversationPanel {
         }
     }
 
+    /// Slim banner above the input while the provider is unreachable.
+    fn push_offline_banner(state: &State, blocks: &mut Vec<Block>) {
+        if state.flags.lifecycle.offline_since_ms.is_some() {
+            let host = state.llm_provider.api_host();
+            let text = format!("\u{26a0} Offline \u{2014} {host} unreachable; messages are queued until it is back");
+            blocks.push(Block::line(vec![cp_render::Span::styled(text, cp_render::Semantic::Error)]));
+        }
+    }
+
+    /// Slim banner above the input while `--pair` guests are connected: who
+    /// is watching and who has the turn. Mirrored to the guests too.
+    fn push_pairing_banner(state: &State, blocks: &mut Vec<Block>) {
+        let Some(status) = state.get_ext::<cp_pair::Status>().filter(|s| s.guests > 0) else { return };
+        let plural = if status.guests == 1 { "" } else { "s" };
+        let who = format!("\u{1f465} Pairing with {} guest{plural} \u{2014} ", status.guests);
+        let spans = match (status.mode, status.guest_turn) {
+            (cp_pair::Mode::ReadOnly, _) => vec![cp_render::Span::muted(format!("{who}watching only"))],
+            (cp_pair::Mode::Turns, false) => vec![
+                cp_render::Span::muted(format!("{who}you have the turn; guests press ")),
+                cp_render::Span::styled("Ctrl+G".into(), cp_render::Semantic::KeyHint),
+                cp_render::Span::muted(" to take it".into()),
+            ],
+            (cp_pair::Mode::Turns, true) => vec![
+                cp_render::Span::styled(format!("{who}a guest is typing; "), cp_render::Semantic::Warning),
+                cp_render::Span::styled("Ctrl+G".into(), cp_render::Semantic::KeyHint),
+                cp_render::Span::styled(" takes the turn back".into(), cp_render::Semantic::Warning),
+            ],
+        };
+        blocks.push(Block::line(spans));
+    }
+
+    /// Slim banner above the input: the standing instructions' first line, or
+    /// the edit hint while the input field holds them. Nothing when unset.
+    fn push_instructions_banner(state: &State, blocks: &mut Vec<Block>, viewport_width: u16) {
+        let Some(instructions) = state.get_ext::<StandingInstructions>() else { return };
+        let (text, semantic) = if instructions.is_editing() {
+            let hint =
+                "\u{270e} Editing standing instructions \u{2014} Ctrl+S or Enter on an empty line saves, Esc cancels";
+            (hint.to_owned(), cp_render::Semantic::Warning)
+        } else {
+            let Some(first) = instructions.text.lines().find(|l| !l.trim().is_empty()) else { return };
+            let more = if instructions.text.trim().lines().nth(1).is_some() { " \u{2026}" } else { "" };
+            (format!("\u{1f4cc} {}{more}  (Ctrl+S to edit)", first.trim()), cp_render::Semantic::Muted)
+        };
+        let width = usize::from(viewport_width).saturating_sub(1);
+        blocks.push(Block::line(vec![cp_render::Span::styled(truncate_string(&text, width), semantic)]));
+    }
+
     /// Assemble the conversation body: history panels, messages (or an empty
     /// placeholder), the streaming-tool preview, and the input area.
     fn assemble_body(state: &mut State, blocks: &mut Vec<Block>, viewport_width: u16) {
@@ -293,47 +373,7 @@ impl Panel for ConversationPanel {
     }
 
     fn handle_key(&self, key: &KeyEvent, state: &State) -> Option<Action> {
-        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
-        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
-        let alt = key.modifiers.contains(KeyModifiers::ALT);
-
-        // Modifier-combo shortcuts (Ctrl+Backspace/A, Ctrl/Alt+Arrow word jump)
-        if let Some(action) = handle_modifier_combo(key.code, &Mods { ctrl, shift, alt }) {
-            return Some(action);
-        }
-
-        // Regular typing and editing
-        match key.code {
-            KeyCode::Char(c) => Some(Action::InputChar(c)),
-            KeyCode::Backspace => Some(Action::InputBackspace),
-            KeyCode::Delete => Some(Action::InputDelete),
-            KeyCode::Left if shift => Some(Action::CursorLeftSelect),
-            KeyCode::Left => Some(Action::CursorLeft),
-            KeyCode::Right if shift => Some(Action::CursorRightSelect),
-            KeyCode::Right => Some(Action::CursorRight),
-            KeyCode::Enter => Some(handle_enter_key(state)),
-            KeyCode::Home if shift => Some(Action::CursorHomeSelect),
-            KeyCode::Home => Some(Action::CursorHome),
-            KeyCode::End if shift => Some(Action::CursorEndSelect),
-            KeyCode::End => Some(Action::CursorEnd),
-            // Remaining variants: delegate scroll keys, ignore everything else
-            KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown => scroll_key_action(key),
-            KeyCode::Tab
-            | KeyCode::BackTab
-            | KeyCode::Insert
-            | KeyCode::F(_)
-            | KeyCode::Null
-            | KeyCode::Esc
-            | KeyCode::CapsLock
-            | KeyCode::ScrollLock
-            | KeyCode::NumLock
-            | KeyCode::PrintScreen
-            | KeyCode::Pause
-            | KeyCode::Menu
-            | KeyCode::KeypadBegin
-            | KeyCode::Media(_)
-            | KeyCode::Modifier(_) => None,
-        }
+        keys::key_action(key, state)
     }
 
     fn refresh(&self, _state: &mut State) {}
@@ -368,84 +408,6 @@ impl Panel for ConversationPanel {
     }
 }
 
-/// Word-jump action for a `Ctrl`/`Alt`+arrow, selecting when `shift` is held.
-const fn word_arrow_action(is_left: bool, shift: bool) -> Action {
-    match (is_left, shift) {
-        (true, true) => Action::CursorWordLeftSelect,
-        (true, false) => Action::CursorWordLeft,
-        (false, true) => Action::CursorWordRightSelect,
-        (false, false) => Action::CursorWordRight,
-    }
-}
-
-/// Active keyboard modifier flags for a key event.
-struct Mods {
-    /// Control held.
-    ctrl: bool,
-    /// Shift held.
-    shift: bool,
-    /// Alt held.
-    alt: bool,
-}
-
-/// Resolve modifier-combo shortcuts (`Ctrl+Backspace`/`Ctrl+A`, `Ctrl`/`Alt`+
-/// arrow word jump with optional `Shift` selection). `None` falls through to the
-/// plain key match.
-const fn handle_modifier_combo(code: KeyCode, mods: &Mods) -> Option<Action> {
-    let word_mod = mods.ctrl || mods.alt;
-    match code {
-        KeyCode::Backspace if mods.ctrl => Some(Action::DeleteWordLeft),
-        KeyCode::Char('a') if mods.ctrl => Some(Action::SelectAll),
-        KeyCode::Left if word_mod => Some(word_arrow_action(true, mods.shift)),
-        KeyCode::Right if word_mod => Some(word_arrow_action(false, mods.shift)),
-        KeyCode::Backspace
-        | KeyCode::Char(_)
-        | KeyCode::Enter
-        | KeyCode::Left
-        | KeyCode::Right
-        | KeyCode::Up
-        | KeyCode::Down
-        | KeyCode::Home
-        | KeyCode::End
-        | KeyCode::PageUp
-        | KeyCode::PageDown
-        | KeyCode::Tab
-        | KeyCode::BackTab
-        | KeyCode::Delete
-        | KeyCode::Insert
-        | KeyCode::F(_)
-        | KeyCode::Null
-        | KeyCode::Esc
-        | KeyCode::CapsLock
-        | KeyCode::ScrollLock
-        | KeyCode::NumLock
-        | KeyCode::PrintScreen
-        | KeyCode::Pause
-        | KeyCode::Menu
-        | KeyCode::KeypadBegin
-        | KeyCode::Media(_)
-        | KeyCode::Modifier(_) => None,
-    }
-}
-
-/// Resolve the `Enter` key: send on an empty trailing line at end-of-input,
-/// else continue/close a markdown list, else insert a newline.
-fn handle_enter_key(state: &State) -> Action {
-    // Send if: cursor at end AND (input empty OR ends with empty line)
-    let at_end = state.input_cursor >= state.input.len();
-    let ends_with_empty_line =
-        state.input.ends_with('\n') || state.input.lines().last().is_none_or(|l| l.trim().is_empty());
-
-    if at_end && ends_with_empty_line {
-        return Action::InputSubmit;
-    }
-    match list::detect_list_action(&state.input) {
-        Some(ListAction::Continue(text)) => Action::InsertText(text),
-        Some(ListAction::RemoveItem) => Action::RemoveListItem,
-        None => Action::InputChar('\n'),
-    }
-}
-
 /// Public entry point for the cached conversation content builder.
 ///
 /// Delegates to [`ConversationPanel::build_content_cached_inner`], which
@@ -2,7 +2,12 @@ use crate::state::{Kind, MsgStatus, State, estimate_tokens};
 
 /// Estimate total tokens for a single message, including content, tool uses, and tool results.
 pub(crate) fn estimate_message_tokens(m: &crate::state::Message) -> usize {
-    let content_tokens = m.content_token_count.max(estimate_tokens(&m.content));
+    // Compressed messages are sent (and billed) in their reduced form
+    let content_tokens = if m.status.is_compressed() {
+        estimate_tokens(&m.sent_content())
+    } else {
+        m.content_token_count.max(estimate_tokens(&m.content))
+    };
 
     // Count tool uses (tool call name + JSON input)
     let tool_use_tokens: usize = m
@@ -15,7 +20,7 @@ pub(crate) fn estimate_message_tokens(m: &crate::state::Message) -> usize {
         .sum();
 
     // Count tool results
-    let tool_result_tokens: usize = m.tool_results.iter().map(|tr| estimate_tokens(&tr.content)).sum();
+    let tool_result_tokens: usize = m.tool_results.iter().map(|tr| estimate_tokens(&m.sent_result(tr))).sum();
 
     content_tokens.saturating_add(tool_use_tokens).saturating_add(tool_result_tokens)
 }
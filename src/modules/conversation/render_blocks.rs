@@ -7,6 +7,7 @@ use super::markdown_ir;
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
+use cp_base::state::data::review::MsgMarker;
 use cp_render::{Block, Semantic, Span};
 
 use crate::infra::constants::icons;
@@ -188,7 +189,7 @@ fn render_text_message_blocks(msg: &Message, opts: &MessageBlockOpts) -> Vec<Blo
         (icons::msg_assistant(), Semantic::AccentDim)
     };
 
-    let status_icon = if msg.status == MsgStatus::Full { icons::status_full() } else { icons::status_deleted() };
+    let status_icon = status_icon(msg);
 
     let content = &msg.content;
     let prefix = format!("{role_icon}{status_icon}");
@@ -207,11 +208,24 @@ fn render_text_message_blocks(msg: &Message, opts: &MessageBlockOpts) -> Vec<Blo
         render_text_body(&mut blocks, content, msg.role == "assistant", &ctx);
     }
 
-    // Dev mode: show token counts
+    // Private note: dimmed under the message, never sent
+    for line in msg.note.iter().flat_map(|note| note.lines()) {
+        blocks.push(Block::line(vec![
+            Span::new(" ".repeat(prefix_width)),
+            Span::styled(format!("\u{270e} {line}"), Semantic::Muted).italic(),
+        ]));
+    }
+
+    // Dev mode: show token counts and request timing
     if opts.dev_mode && msg.role == "assistant" && (msg.input_tokens > 0 || msg.content_token_count > 0) {
+        let timing = msg.latency.map_or_else(String::new, |t| {
+            use crate::ui::perf::latency::format_ms;
+            let tools = if t.tools > 0 { format!(" tools:{}", format_ms(t.tools)) } else { String::new() };
+            format!(" ttft:{} model:{}{tools}", format_ms(t.ttft), format_ms(t.stream))
+        });
         blocks.push(Block::line(vec![
             Span::new(" ".repeat(prefix_width)),
-            Span::styled(format!("[in:{} out:{}]", msg.input_tokens, msg.content_token_count), Semantic::Muted)
+            Span::styled(format!("[in:{} out:{}{timing}]", msg.input_tokens, msg.content_token_count), Semantic::Muted)
                 .italic(),
         ]));
     }
@@ -220,6 +234,18 @@ fn render_text_message_blocks(msg: &Message, opts: &MessageBlockOpts) -> Vec<Blo
     blocks
 }
 
+/// Status glyph for a text message, followed by its review markers.
+fn status_icon(msg: &Message) -> String {
+    let status_glyph = if msg.status == MsgStatus::Full {
+        icons::status_full()
+    } else if msg.status.is_compressed() {
+        icons::status_summarized()
+    } else {
+        icons::status_deleted()
+    };
+    if msg.markers.is_empty() { status_glyph } else { format!("{status_glyph}{} ", MsgMarker::icons(&msg.markers)) }
+}
+
 /// Shared prefix context for text-body line emission.
 struct TextBodyCtx<'ctx> {
     /// Role glyph (user / assistant) shown on the first body line.
@@ -286,6 +312,13 @@ fn render_markdown_line(blocks: &mut Vec<Block>, ctx: &TextBodyCtx<'_>, line: &s
     }
 }
 
+/// Render one verbatim span (code, fences) hard-wrapped at the body width.
+fn push_hard_wrapped(blocks: &mut Vec<Block>, ctx: &TextBodyCtx<'_>, is_first: &mut bool, span: &Span) {
+    for segment in cp_base::ui::text::wrap_graphemes(&span.text, ctx.wrap_width) {
+        push_prefixed(blocks, ctx, is_first, vec![Span { text: segment, ..span.clone() }]);
+    }
+}
+
 /// Render one user line: wrap verbatim, no markdown parsing.
 fn render_user_line(blocks: &mut Vec<Block>, ctx: &TextBodyCtx<'_>, line: &str, is_first: &mut bool) {
     for line_text in &wrap_text(line, ctx.wrap_width) {
@@ -305,12 +338,12 @@ fn render_text_body(blocks: &mut Vec<Block>, content: &str, is_assistant: bool,
 
         if is_assistant && line.trim().starts_with("```") {
             in_code_block = !in_code_block;
-            push_prefixed(blocks, ctx, &mut scan.is_first, vec![Span::styled(line.to_owned(), Semantic::Muted)]);
+            push_hard_wrapped(blocks, ctx, &mut scan.is_first, &Span::styled(line.to_owned(), Semantic::Muted));
             scan.idx = scan.idx.saturating_add(1);
             continue;
         }
         if in_code_block {
-            push_prefixed(blocks, ctx, &mut scan.is_first, vec![Span::styled(line.to_owned(), Semantic::Code)]);
+            push_hard_wrapped(blocks, ctx, &mut scan.is_first, &Span::styled(line.to_owned(), Semantic::Code));
             scan.idx = scan.idx.saturating_add(1);
             continue;
         }
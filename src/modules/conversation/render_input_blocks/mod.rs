@@ -10,6 +10,8 @@ use crate::ui::helpers::wrap_text;
 
 /// Paste-sentinel expansion (split out for the 500-line structure limit).
 mod sentinels;
+/// Misspelled-word underline.
+mod spell;
 use sentinels::expand_paste_sentinels;
 
 /// Sentinel marker used to represent paste placeholders in the input string.
@@ -30,6 +32,8 @@ pub(crate) struct InputBlockCtx<'ctx> {
     pub paste_buffer_labels: &'ctx [Option<String>],
     /// Available viewport width in columns.
     pub viewport_width: u16,
+    /// Underline misspelled words.
+    pub spell_check: bool,
 }
 
 /// Build the content spans for one wrapped input line, honoring an active
@@ -104,6 +108,8 @@ struct LineRenderCtx<'ctx> {
     sel_start: usize,
     /// Selection end byte (post-cursor-insertion coords).
     sel_end: usize,
+    /// Underline misspelled words.
+    spell_check: bool,
 }
 
 /// Process one wrapped line: toggle paste-block state, build content spans,
@@ -122,6 +128,10 @@ fn process_wrapped_line(blocks: &mut Vec<Block>, st: &mut LineEmitState, ctx: &L
         st.in_paste_block = false;
     }
 
+    if ctx.spell_check && !st.in_paste_block {
+        spans = spell::underline_misspellings(spans, ctx.cursor_char);
+    }
+
     if ctx.sel_start < ctx.sel_end {
         spans = apply_selection_to_spans(spans, line_byte_start, ctx.sel_start, ctx.sel_end);
     }
@@ -155,6 +165,14 @@ fn emit_input_body(blocks: &mut Vec<Block>, input_with_cursor: &str, wrap_width:
     }
 }
 
+/// Blank-indent width in front of every input line.
+const INPUT_PREFIX_WIDTH: usize = 8;
+
+/// Column width the draft wraps at in a conversation viewport `viewport_width` wide.
+pub(crate) fn input_wrap_width(viewport_width: u16) -> usize {
+    usize::from(viewport_width).saturating_sub(INPUT_PREFIX_WIDTH.saturating_add(2)).max(20)
+}
+
 /// Render input area to IR blocks.
 pub(crate) fn render_input_blocks(
     raw_input: &str,
@@ -164,8 +182,8 @@ pub(crate) fn render_input_blocks(
 ) -> Vec<Block> {
     let mut blocks: Vec<Block> = Vec::new();
     let role_icon = icons::msg_user();
-    let prefix_width: usize = 8;
-    let wrap_width = usize::from(ctx.viewport_width).saturating_sub(prefix_width.saturating_add(2)).max(20);
+    let prefix_width = INPUT_PREFIX_WIDTH;
+    let wrap_width = input_wrap_width(ctx.viewport_width);
     let cursor_char = "\u{258e}";
     let cursor_char_len = cursor_char.len();
 
@@ -177,7 +195,8 @@ pub(crate) fn render_input_blocks(
     let (display_input, display_cursor, display_anchor) =
         expand_paste_sentinels(raw_input, raw_cursor, raw_anchor, ctx);
     let input = &display_input;
-    let cursor_pos = display_cursor;
+    // Never split a grapheme (e.g. a base letter and its combining accent) with the cursor glyph.
+    let cursor_pos = cp_base::ui::text::floor_boundary(input, display_cursor);
 
     // Insert cursor character at cursor position
     let input_with_cursor = if cursor_pos >= input.len() {
@@ -187,7 +206,7 @@ pub(crate) fn render_input_blocks(
     };
 
     // Compute post-cursor-insertion selection range
-    let (sel_start, sel_end) = compute_post_insertion_selection(display_cursor, display_anchor, cursor_char_len);
+    let (sel_start, sel_end) = compute_post_insertion_selection(cursor_pos, display_anchor, cursor_char_len);
 
     if input.is_empty() {
         blocks.push(Block::line(vec![
@@ -204,6 +223,7 @@ pub(crate) fn render_input_blocks(
             prefix_width,
             sel_start,
             sel_end,
+            spell_check: ctx.spell_check,
         };
         emit_input_body(&mut blocks, &input_with_cursor, wrap_width, &render_ctx);
     }
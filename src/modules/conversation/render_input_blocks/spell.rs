@@ -0,0 +1,59 @@
+//! Misspelling underline for the draft: plain-text spans are split so each
+//! misspelled word renders as [`Semantic::Misspelled`]. The word being typed
+//! (touching the cursor glyph) is left alone until the cursor moves on.
+
+use std::ops::Range;
+
+use cp_base::ui::spell;
+use cp_render::{Semantic, Span};
+
+/// Whether a span sits right after / right before the cursor glyph.
+#[derive(Clone, Copy)]
+struct CursorEdges {
+    /// The cursor glyph ends just before the span.
+    after: bool,
+    /// The cursor glyph starts just after the span.
+    before: bool,
+}
+
+/// Mark the misspelled words in the plain-text spans of one input line.
+pub(super) fn underline_misspellings(spans: Vec<Span>, cursor_char: &str) -> Vec<Span> {
+    let mut result = Vec::with_capacity(spans.len());
+    let mut after_cursor = false;
+    let mut iter = spans.into_iter().peekable();
+    while let Some(span) = iter.next() {
+        let is_cursor = span.text == cursor_char;
+        let edges =
+            CursorEdges { after: after_cursor, before: iter.peek().is_some_and(|next| next.text == cursor_char) };
+        if span.semantic == Semantic::Default {
+            split_misspelled(&mut result, &span, edges);
+        } else {
+            result.push(span);
+        }
+        after_cursor = is_cursor;
+    }
+    result
+}
+
+/// Push `span` split around its misspelled words.
+fn split_misspelled(out: &mut Vec<Span>, span: &Span, edges: CursorEdges) {
+    let len = span.text.len();
+    let mut last = 0usize;
+    for range in spell::misspelled(&span.text) {
+        let being_typed = (edges.after && range.start == 0) || (edges.before && range.end == len);
+        if being_typed {
+            continue;
+        }
+        push_piece(out, span, last..range.start, Semantic::Default);
+        last = range.end;
+        push_piece(out, span, range, Semantic::Misspelled);
+    }
+    push_piece(out, span, last..len, Semantic::Default);
+}
+
+/// Push the non-empty `range` of `span` with `semantic`.
+fn push_piece(out: &mut Vec<Span>, span: &Span, range: Range<usize>, semantic: Semantic) {
+    if let Some(text) = span.text.get(range).filter(|text| !text.is_empty()) {
+        out.push(Span { text: text.to_owned(), semantic, ..span.clone() });
+    }
+}
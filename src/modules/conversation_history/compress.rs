@@ -0,0 +1,82 @@
+//! `message_compress` tool — graduated compression of live messages.
+//!
+//! Between keeping a message verbatim and deleting it there are two levels:
+//! `head_tail` keeps the first and last paragraphs, `reference` shrinks the
+//! message to a one-line stub carrying its ID. The original text stays in the
+//! message file; only what the providers send is reduced.
+
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::{Kind, MsgStatus, State};
+
+/// Set `level` on every listed message. Returns the IDs that changed, or an
+/// error naming the IDs that cannot be compressed (nothing is changed then).
+fn apply(state: &mut State, ids: &[String], level: MsgStatus) -> Result<Vec<String>, String> {
+    let refused: Vec<&str> = ids
+        .iter()
+        .filter(|id| {
+            state
+                .messages
+                .iter()
+                .find(|m| m.id == **id)
+                .is_none_or(|m| !(m.status == MsgStatus::Full || m.status.is_compressed()))
+        })
+        .map(String::as_str)
+        .collect();
+    if !refused.is_empty() {
+        return Err(format!("Cannot compress {}: unknown, deleted or detached message(s)", refused.join(", ")));
+    }
+    let mut changed = Vec::new();
+    for msg in state.messages.iter_mut().filter(|m| ids.contains(&m.id) && m.status != level) {
+        msg.status = level;
+        changed.push(msg.id.clone());
+    }
+    Ok(changed)
+}
+
+/// Execute `message_compress`: validate, apply the level, persist the changed messages.
+pub(super) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let ids: Vec<String> =
+        tool.input.get("ids").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or_default();
+    if ids.is_empty() {
+        return ToolResult::new(tool.id.clone(), "Missing or empty 'ids' parameter".to_owned(), true);
+    }
+    let requested = tool.input.get("level").and_then(|v| serde_json::from_value::<MsgStatus>(v.clone()).ok());
+    let Some(level) = requested.filter(|l| *l == MsgStatus::Full || l.is_compressed()) else {
+        return ToolResult::new(
+            tool.id.clone(),
+            "Invalid 'level': expected 'full', 'head_tail' or 'reference'".to_owned(),
+            true,
+        );
+    };
+    match apply(state, &ids, level) {
+        Ok(changed) => {
+            for msg in state.messages.iter().filter(|m| changed.contains(&m.id)) {
+                crate::state::persistence::save_message(msg);
+            }
+            state.touch_panel(Kind::CONVERSATION);
+            let summary = if changed.is_empty() { "none".to_owned() } else { changed.join(", ") };
+            ToolResult::new(tool.id.clone(), format!("Compressed {summary} to {level:?}"), false)
+        }
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Message;
+
+    #[test]
+    fn levels_apply_and_refuse_deleted() {
+        let mut state = State::default();
+        state.messages.push(Message::new_user("U1".to_owned(), "UID_1_U".to_owned(), "hello".to_owned(), 1));
+        let mut gone = Message::new_user("U2".to_owned(), "UID_2_U".to_owned(), "bye".to_owned(), 1);
+        gone.status = MsgStatus::Deleted;
+        state.messages.push(gone);
+
+        assert_eq!(apply(&mut state, &["U1".to_owned()], MsgStatus::Reference), Ok(vec!["U1".to_owned()]));
+        assert_eq!(apply(&mut state, &["U1".to_owned()], MsgStatus::Reference), Ok(vec![]));
+        assert!(apply(&mut state, &["U1".to_owned(), "U2".to_owned()], MsgStatus::Full).err().is_some());
+        assert_eq!(state.messages.first().map(|m| m.status), Some(MsgStatus::Reference));
+    }
+}
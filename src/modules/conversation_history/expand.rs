@@ -0,0 +1,123 @@
+//! `message_expand` tool — temporarily restore a compressed message.
+//!
+//! The cleaner may shrink a message to head/tail or a one-line reference and
+//! later turn out to have dropped a detail the model needs. Expanding sends
+//! the message verbatim again until the next user turn, without changing its
+//! compression level. Each expansion spends one unit of a per-turn budget so
+//! the model cannot quietly undo the cleaner wholesale.
+
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::{Kind, State};
+
+/// Default number of messages that may be expanded per user turn.
+const DEFAULT_PER_TURN: usize = 3;
+
+/// Re-expansion budget (per-worker state; `per_turn` persisted with the module data).
+#[derive(Debug, Clone)]
+pub(crate) struct ExpandBudget {
+    /// Expansions allowed per user turn.
+    pub per_turn: usize,
+    /// Expansions left in the current turn.
+    pub remaining: usize,
+}
+
+impl Default for ExpandBudget {
+    fn default() -> Self {
+        Self { per_turn: DEFAULT_PER_TURN, remaining: DEFAULT_PER_TURN }
+    }
+}
+
+/// Start a new turn: collapse every expanded message back to its level and refill the budget.
+pub(super) fn end_turn(state: &mut State) {
+    for msg in &mut state.messages {
+        msg.expanded = false;
+    }
+    if let Some(budget) = state.get_ext_mut::<ExpandBudget>() {
+        budget.remaining = budget.per_turn;
+    }
+}
+
+/// Expand the listed messages. Returns the IDs expanded, or an error when an
+/// ID is not a compressed live message or the budget cannot cover the request.
+fn apply(state: &mut State, ids: &[String]) -> Result<Vec<String>, String> {
+    let already = ids
+        .iter()
+        .filter(|id| state.messages.iter().any(|m| m.id == **id && m.status.is_compressed() && m.expanded))
+        .count();
+    let refused: Vec<&str> = ids
+        .iter()
+        .filter(|id| !state.messages.iter().any(|m| m.id == **id && m.status.is_compressed()))
+        .map(String::as_str)
+        .collect();
+    if !refused.is_empty() {
+        return Err(format!("Cannot expand {}: not a compressed message", refused.join(", ")));
+    }
+    let wanted = ids.len().saturating_sub(already);
+    let remaining = state.get_ext::<ExpandBudget>().map_or(0, |b| b.remaining);
+    if wanted > remaining {
+        return Err(format!("Re-expansion budget exceeded: {wanted} requested, {remaining} left this turn"));
+    }
+    let mut expanded = Vec::new();
+    for msg in state.messages.iter_mut().filter(|m| ids.contains(&m.id) && !m.expanded) {
+        msg.expanded = true;
+        expanded.push(msg.id.clone());
+    }
+    if let Some(budget) = state.get_ext_mut::<ExpandBudget>() {
+        budget.remaining = budget.remaining.saturating_sub(expanded.len());
+    }
+    Ok(expanded)
+}
+
+/// Execute `message_expand`: restore compressed messages verbatim until the next user turn.
+pub(super) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let ids: Vec<String> =
+        tool.input.get("ids").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or_default();
+    if ids.is_empty() {
+        return ToolResult::new(tool.id.clone(), "Missing or empty 'ids' parameter".to_owned(), true);
+    }
+    match apply(state, &ids) {
+        Ok(expanded) => {
+            state.touch_panel(Kind::CONVERSATION);
+            let remaining = state.get_ext::<ExpandBudget>().map_or(0, |b| b.remaining);
+            let summary = if expanded.is_empty() { "none (already expanded)".to_owned() } else { expanded.join(", ") };
+            ToolResult::new(
+                tool.id.clone(),
+                format!("Expanded {summary} until the next user turn. {remaining} expansion(s) left this turn."),
+                false,
+            )
+        }
+        Err(e) => ToolResult::new(tool.id.clone(), e, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Message, MsgStatus};
+
+    /// Messages `U1` (reference), `U2` (head/tail), `U3` (full) and a budget of one.
+    fn sample_state() -> State {
+        let mut state = State::default();
+        state.set_ext(ExpandBudget { per_turn: 1, remaining: 1 });
+        for (id, status) in [("U1", MsgStatus::Reference), ("U2", MsgStatus::HeadTail), ("U3", MsgStatus::Full)] {
+            let mut msg = Message::new_user(id.to_owned(), format!("UID_{id}"), "text".to_owned(), 1);
+            msg.status = status;
+            state.messages.push(msg);
+        }
+        state
+    }
+
+    #[test]
+    fn expansion_spends_budget_until_turn_ends() {
+        let mut state = sample_state();
+
+        assert!(apply(&mut state, &["U3".to_owned()]).err().is_some());
+        assert_eq!(apply(&mut state, &["U1".to_owned()]), Ok(vec!["U1".to_owned()]));
+        assert_eq!(state.messages.first().map(|m| m.sent_content().into_owned()), Some("text".to_owned()));
+        assert!(apply(&mut state, &["U2".to_owned()]).err().is_some());
+
+        end_turn(&mut state);
+        assert!(state.messages.iter().all(|m| !m.expanded));
+        assert_eq!(apply(&mut state, &["U2".to_owned()]), Ok(vec!["U2".to_owned()]));
+    }
+}
@@ -1,18 +1,30 @@
+/// `message_compress` tool: head/tail and reference compression levels.
+mod compress;
+/// `message_expand` tool: per-turn re-expansion of compressed messages.
+mod expand;
 /// Panel implementation for frozen conversation history display.
 mod panel;
 /// Recompute open tree folders after history panels are closed.
 pub(crate) mod recompute_toggled_tree_folders;
+/// TL;DR quality guard: keeps preserved fragments out of lossy summaries.
+pub(crate) mod tldr_guard;
 /// History cleanup trap: forces AI to close old panels before queue flush.
 pub(crate) mod trap;
 
 use crate::app::panels::Panel;
-use crate::infra::tools::{ToolDefinition, ToolResult, ToolUse};
+use crate::infra::tools::{ParamType, ToolDefinition, ToolResult, ToolTexts, ToolUse};
 use crate::state::{Kind, State, TypeMeta};
 use cp_base::config::INJECTIONS;
 
+use self::expand::ExpandBudget;
 use self::panel::ConversationHistoryPanel;
+use self::tldr_guard::TldrGuard;
 use super::Module;
 
+/// Lazily parsed tool text definitions for core tools (used by `message_compress`).
+static CORE_TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
+    std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../../yamls/tools/core.yaml")));
+
 /// Module that manages frozen conversation history chunks.
 pub(crate) struct ConversationHistoryModule;
 
@@ -66,26 +78,58 @@ impl Module for ConversationHistoryModule {
     }
 
     fn tool_definitions(&self) -> Vec<ToolDefinition> {
-        vec![]
-    }
-
-    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
-        None
+        vec![
+            ToolDefinition::from_yaml("message_compress", &CORE_TOOL_TEXTS)
+                .short_desc("Compress past messages to head/tail or a reference")
+                .category("Context")
+                .reverie_allowed(true)
+                .param_array("ids", ParamType::String, true)
+                .param_enum("level", &["full", "head_tail", "reference"], true)
+                .build(),
+            ToolDefinition::from_yaml("message_expand", &CORE_TOOL_TEXTS)
+                .short_desc("Restore compressed messages verbatim for one turn")
+                .category("Context")
+                .param_array("ids", ParamType::String, true)
+                .build(),
+        ]
+    }
+
+    fn execute_tool(&self, tool: &ToolUse, state: &mut State) -> Option<ToolResult> {
+        match tool.name.as_str() {
+            "message_compress" => Some(compress::execute(tool, state)),
+            "message_expand" => Some(expand::execute(tool, state)),
+            _ => None,
+        }
     }
 
     fn dependencies(&self) -> &[&'static str] {
         &[]
     }
 
-    fn init_state(&self, _state: &mut State) {}
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(TldrGuard::default());
+        state.set_ext(ExpandBudget::default());
+    }
 
-    fn reset_state(&self, _state: &mut State) {}
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(TldrGuard::default());
+        state.set_ext(ExpandBudget::default());
+    }
 
-    fn save_module_data(&self, _state: &State) -> serde_json::Value {
-        serde_json::Value::Null
+    fn save_module_data(&self, state: &State) -> serde_json::Value {
+        let preserve = state.get_ext::<TldrGuard>().map(|g| g.preserve.clone()).unwrap_or_default();
+        let per_turn = state.get_ext::<ExpandBudget>().map_or(0, |b| b.per_turn);
+        serde_json::json!({ "tldr_preserve": preserve, "expand_budget": per_turn })
     }
 
-    fn load_module_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+    fn load_module_data(&self, data: &serde_json::Value, state: &mut State) {
+        if let Some(preserve) = data.get("tldr_preserve").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.set_ext(TldrGuard { preserve });
+        }
+        if let Some(per_turn) = data.get("expand_budget").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.set_ext(ExpandBudget { per_turn, remaining: per_turn });
+        }
+    }
 
     fn save_worker_data(&self, _state: &State) -> serde_json::Value {
         serde_json::Value::Null
@@ -129,7 +173,9 @@ impl Module for ConversationHistoryModule {
         vec![]
     }
 
-    fn on_user_message(&self, _state: &mut State) {}
+    fn on_user_message(&self, state: &mut State) {
+        expand::end_turn(state);
+    }
 
     fn on_stream_stop(&self, _state: &mut State) {}
 
@@ -57,11 +57,7 @@ fn render_toolresult_hist(msg: &cp_base::state::data::message::Message) -> Vec<c
     ]));
     for tr in &msg.tool_results {
         let preview = tr.content.lines().next().unwrap_or("");
-        let truncated = if preview.len() > 80 {
-            format!("{}…", preview.get(..80).unwrap_or(preview))
-        } else {
-            preview.to_owned()
-        };
+        let truncated = cp_base::ui::text::ellipsize(preview, 80, "\u{2026}");
         blocks.push(cp_render::Block::Line(vec![
             cp_render::Span::muted("  ".to_owned()),
             cp_render::Span::muted(truncated),
@@ -0,0 +1,155 @@
+//! TL;DR quality guard.
+//!
+//! A tool result's `tldr` replaces its full content once the turn is folded
+//! into a history panel, so a careless summary silently loses whatever it
+//! left out. Before a TL;DR is recorded, every fragment of the original that
+//! matches the preserve-list (code blocks, paths, tool parameters, …) must
+//! appear in it verbatim. Short missing fragments are appended; if that
+//! would not fit — or a whole code block went missing — the TL;DR is
+//! dropped and history keeps the full content.
+//!
+//! The user adds patterns with `/tldr-guard <regex>`, e.g. a ticket-ID
+//! format the defaults below miss; `/tldr-guard reset` goes back to the
+//! defaults and `/tldr-guard off` lets every summary through. The list is
+//! saved with the history module's data.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::state::State;
+
+/// Longest run of appended fragments before the summary is skipped instead.
+const MAX_PRESERVED_CHARS: usize = 400;
+
+/// Built-in preserve-list: fenced code, inline code, file paths, JSON-style
+/// tool parameters, and `--flag=value` arguments.
+const DEFAULT_PATTERNS: &[&str] = &[
+    "(?s)```.*?```",
+    r"`[^`\n]+`",
+    r"(?:~|\.{1,2})?/?(?:[\w.-]+/)+[\w.-]*\w",
+    r#""\w+":\s*(?:"[^"\n]*"|[\w.-]+)"#,
+    r"--[A-Za-z][\w-]*=\S+",
+];
+
+/// Persisted guard configuration (module data of the history module).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TldrGuard {
+    /// Regex patterns whose matches must survive summarization verbatim.
+    pub preserve: Vec<String>,
+}
+
+impl Default for TldrGuard {
+    fn default() -> Self {
+        Self { preserve: DEFAULT_PATTERNS.iter().map(|p| (*p).to_owned()).collect() }
+    }
+}
+
+impl TldrGuard {
+    /// Fragments of `content` matched by the preserve-list but absent from
+    /// `tldr`, grouped by pattern. Invalid patterns are ignored.
+    fn missing_fragments(&self, content: &str, tldr: &str) -> Vec<String> {
+        let mut missing: Vec<String> = Vec::new();
+        for re in self.preserve.iter().filter_map(|p| Regex::new(p).ok()) {
+            for found in re.find_iter(content).map(|m| m.as_str()) {
+                let covered = tldr.contains(found) || missing.iter().any(|m| m.contains(found));
+                if !covered {
+                    missing.push(found.to_owned());
+                }
+            }
+        }
+        missing
+    }
+
+    /// Validate a TL;DR against its original: returned unchanged when it
+    /// already carries every preserved fragment, extended with the missing
+    /// ones when they are short, or `None` to skip summarization.
+    pub(crate) fn check(&self, content: &str, tldr: String) -> Option<String> {
+        let missing = self.missing_fragments(content, &tldr);
+        if missing.is_empty() {
+            return Some(tldr);
+        }
+        let appended_chars: usize = missing.iter().map(|m| m.chars().count().saturating_add(1)).sum();
+        if appended_chars > MAX_PRESERVED_CHARS || missing.iter().any(|m| m.contains('\n')) {
+            return None;
+        }
+        Some(format!("{tldr}\nPreserved: {}", missing.join(" ")))
+    }
+}
+
+/// Guard a tool result's TL;DR before it is stored in the conversation.
+pub(crate) fn guard_tldr(state: &State, content: &str, tldr: Option<String>) -> Option<String> {
+    let summary = tldr?;
+    match state.get_ext::<TldrGuard>() {
+        Some(guard) => guard.check(content, summary),
+        None => TldrGuard::default().check(content, summary),
+    }
+}
+
+/// Apply `/tldr-guard` arguments: a regex to add, `reset` or `off`. The
+/// message to show the user.
+pub(crate) fn configure(state: &mut State, args: &str) -> Result<String, String> {
+    let mut guard = state.get_ext::<TldrGuard>().cloned().unwrap_or_default();
+    let message = match args {
+        "" => return Err("usage: /tldr-guard <regex> | reset | off".to_owned()),
+        "reset" => {
+            guard = TldrGuard::default();
+            format!("TL;DR guard reset to its {} built-in patterns.", guard.preserve.len())
+        }
+        "off" => {
+            guard.preserve.clear();
+            "TL;DR guard off: summaries are stored as written.".to_owned()
+        }
+        pattern => {
+            let _valid = Regex::new(pattern).map_err(|e| format!("invalid pattern: {e}"))?;
+            if !guard.preserve.iter().any(|p| p == pattern) {
+                guard.preserve.push(pattern.to_owned());
+            }
+            format!("TL;DR guard preserves `{pattern}` ({} patterns).", guard.preserve.len())
+        }
+    };
+    state.set_ext(guard);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_tldr_is_kept() {
+        let guard = TldrGuard::default();
+        let tldr = guard.check("Edited src/app/mod.rs to fix the loop", "Fixed loop in src/app/mod.rs".to_owned());
+        assert_eq!(tldr.as_deref(), Some("Fixed loop in src/app/mod.rs"));
+    }
+
+    #[test]
+    fn missing_path_and_parameter_are_appended() {
+        let guard = TldrGuard::default();
+        let content = "Ran with \"limit\": 20 over crates/cp-base/src/lib.rs and found nothing";
+        let tldr = guard.check(content, "Nothing found".to_owned());
+        assert_eq!(tldr.as_deref(), Some("Nothing found\nPreserved: crates/cp-base/src/lib.rs \"limit\": 20"));
+    }
+
+    #[test]
+    fn dropped_code_block_skips_summary() {
+        let guard = TldrGuard::default();
+        let content = "Use this:\n```rust\nlet x = 1;\n```\n";
+        assert_eq!(guard.check(content, "Suggested a snippet".to_owned()), None);
+
+        let custom = TldrGuard { preserve: vec!["TICKET-\\d+".to_owned()] };
+        let tldr = custom.check("Closes TICKET-42 and ```code```", "Closed it".to_owned());
+        assert_eq!(tldr.as_deref(), Some("Closed it\nPreserved: TICKET-42"));
+    }
+
+    #[test]
+    fn patterns_are_added_through_the_command() {
+        let mut state = State::default();
+        let added = configure(&mut state, "TICKET-\\d+");
+        assert!(added.is_ok_and(|m| m.contains(&format!("({} patterns)", DEFAULT_PATTERNS.len().saturating_add(1)))));
+        let tldr = guard_tldr(&state, "Closes TICKET-42", Some("Closed it".to_owned()));
+        assert_eq!(tldr.as_deref(), Some("Closed it\nPreserved: TICKET-42"));
+        assert!(configure(&mut state, "(unclosed").is_err_and(|e| e.starts_with("invalid pattern")));
+        assert!(configure(&mut state, "off").is_ok_and(|m| m.contains("guard off")));
+        assert_eq!(guard_tldr(&state, "See src/lib.rs", Some("Done".to_owned())).as_deref(), Some("Done"));
+    }
+}
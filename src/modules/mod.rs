@@ -1,13 +1,21 @@
+/// Cleaner panel: context optimizer run history and undo.
+pub(crate) mod cleaner;
 /// Conversation display, input rendering, and message formatting.
 pub(crate) mod conversation;
 /// Frozen conversation history chunks for context management.
 pub(crate) mod conversation_history;
 /// Overview panel with token usage, statistics, and configuration.
 pub(crate) mod overview;
+/// Large pastes moved out of messages into their own panels.
+pub(crate) mod pasted;
 /// Pre-flight validation for tool calls.
 pub(crate) mod pre_flight;
 /// Interactive user question forms.
 pub(crate) mod questions;
+/// Recently opened and edited files, reopened with one key.
+pub(crate) mod recent;
+/// Sibling tmux panes mirrored as panels.
+pub(crate) mod tmux;
 
 use std::collections::{HashMap, HashSet};
 
@@ -20,26 +28,41 @@ use crate::state::{Kind, State};
 static CORE_TOOL_TEXTS: std::sync::LazyLock<ToolTexts> =
     std::sync::LazyLock::new(|| ToolTexts::parse(include_str!("../../yamls/tools/core.yaml")));
 
+pub(crate) use cp_mod_bookmarks::BookmarksModule;
 pub(crate) use cp_mod_brave::BraveModule;
 pub(crate) use cp_mod_bridge::BridgeModule;
 pub(crate) use cp_mod_callback::CallbackModule;
 pub(crate) use cp_mod_console::ConsoleModule;
+pub(crate) use cp_mod_data::DataModule;
+pub(crate) use cp_mod_db::DbModule;
+pub(crate) use cp_mod_debt::DebtModule;
+pub(crate) use cp_mod_deps::DepsModule;
 pub(crate) use cp_mod_entities::EntitiesModule;
 pub(crate) use cp_mod_files::FilesModule;
 pub(crate) use cp_mod_firecrawl::FirecrawlModule;
 pub(crate) use cp_mod_git::GitModule;
 pub(crate) use cp_mod_github::GithubModule;
+pub(crate) use cp_mod_http::HttpModule;
+pub(crate) use cp_mod_k8s::K8sModule;
+pub(crate) use cp_mod_ledger::LedgerModule;
 pub(crate) use cp_mod_logs::LogsModule;
 pub(crate) use cp_mod_memory::MemoryModule;
+pub(crate) use cp_mod_nvim::NvimModule;
 pub(crate) use cp_mod_ocr::OcrModule;
+pub(crate) use cp_mod_plugins::PluginsModule;
 pub(crate) use cp_mod_prompt::PromptModule;
+pub(crate) use cp_mod_python::PythonModule;
 pub(crate) use cp_mod_queue::QueueModule;
+pub(crate) use cp_mod_review::ReviewModule;
 pub(crate) use cp_mod_scratchpad::ScratchpadModule;
+pub(crate) use cp_mod_scripts::ScriptsModule;
 pub(crate) use cp_mod_search::SearchModule;
+pub(crate) use cp_mod_snippets::SnippetsModule;
 pub(crate) use cp_mod_spine::SpineModule;
 pub(crate) use cp_mod_threads::ThreadsModule;
 pub(crate) use cp_mod_todo::TodoModule;
 pub(crate) use cp_mod_tree::TreeModule;
+pub(crate) use cp_mod_watch::WatchModule;
 
 // Re-export Module trait and helpers from cp-base
 pub(crate) use cp_base::modules::{Module, ToolVisualizer};
@@ -106,20 +129,44 @@ pub(crate) fn make_default_entry(
     cp_base::state::context::make_default_entry(id, context_type, name, cache_deprecated)
 }
 
-/// Returns all registered modules.
+/// Tools handled outside any module; plugin tools may not take these ids.
+const HOST_TOOL_IDS: &[&str] = &["module_toggle", "optimize_context", "reverie_report"];
+
+/// Returns all registered modules: the built-in ones, then one per plugin.
 pub(crate) fn all_modules() -> Vec<Box<dyn Module>> {
+    let mut modules = builtin_modules();
+    modules.extend(cp_mod_plugins::plugin_modules(|| {
+        builtin_modules()
+            .iter()
+            .flat_map(|m| m.tool_definitions())
+            .map(|t| t.id)
+            .chain(HOST_TOOL_IDS.iter().map(|&id| id.to_owned()))
+            .collect()
+    }));
+    modules
+}
+
+/// Returns the modules compiled into the binary.
+fn builtin_modules() -> Vec<Box<dyn Module>> {
     vec![
         Box::new(overview::OverviewModule),
         Box::new(conversation::ConversationModule),
         Box::new(conversation_history::ConversationHistoryModule),
         Box::new(questions::QuestionsModule),
+        Box::new(cleaner::CleanerModule),
+        Box::new(pasted::PastedModule),
+        Box::new(recent::RecentModule),
+        Box::new(tmux::TmuxModule),
         Box::new(PromptModule::new()),
         Box::new(FilesModule::new()),
+        Box::new(BookmarksModule::new()),
         Box::new(TreeModule::new()),
-        Box::new(GitModule::new()),
+        Box::new(GitModule::new(crate::app::prompt::structured::run)),
         Box::new(GithubModule::new()),
+        Box::new(ReviewModule::new()),
         Box::new(ConsoleModule::new()),
         Box::new(CallbackModule::new()),
+        Box::new(WatchModule::new()),
         Box::new(TodoModule::new()),
         Box::new(MemoryModule::new()),
         Box::new(OcrModule::new()),
@@ -130,9 +177,21 @@ pub(crate) fn all_modules() -> Vec<Box<dyn Module>> {
         Box::new(BraveModule::new()),
         Box::new(FirecrawlModule::new()),
         Box::new(QueueModule::new()),
+        Box::new(LedgerModule::new(crate::app::prompt::structured::run)),
         Box::new(SearchModule::new()),
+        Box::new(SnippetsModule::new()),
+        Box::new(DebtModule::new()),
         Box::new(EntitiesModule::new()),
+        Box::new(DbModule::new()),
+        Box::new(HttpModule::new()),
+        Box::new(PythonModule::new()),
+        Box::new(K8sModule::new()),
+        Box::new(DepsModule::new()),
+        Box::new(NvimModule::new()),
+        Box::new(DataModule::new()),
         Box::new(BridgeModule::new()),
+        Box::new(PluginsModule::new()),
+        Box::new(ScriptsModule::new(run_script_tool)),
     ]
 }
 
@@ -159,6 +218,22 @@ pub(crate) fn active_tool_definitions(active_modules: &HashSet<String>) -> Vec<T
     all_modules().into_iter().filter(|m| active_modules.contains(m.id())).flat_map(|m| m.tool_definitions()).collect()
 }
 
+/// The input of `tool` as recorded in the conversation: the owning module
+/// may mask secrets in it (see `Module::transcript_input`).
+pub(crate) fn transcript_input(tool: &ToolUse, active_modules: &HashSet<String>) -> serde_json::Value {
+    all_modules()
+        .iter()
+        .filter(|m| active_modules.contains(m.id()))
+        .find_map(|m| m.transcript_input(tool))
+        .unwrap_or_else(|| tool.input.clone())
+}
+
+/// Run a tool call queued by a user script against the active modules.
+fn run_script_tool(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let active = state.active_modules.clone();
+    dispatch_tool(tool, state, &active)
+}
+
 /// Dispatch a tool call to the appropriate active module.
 pub(crate) fn dispatch_tool(tool: &ToolUse, state: &mut State, active_modules: &HashSet<String>) -> ToolResult {
     let _fg = cp_base::flame!(&format!("tool_{}", tool.name));
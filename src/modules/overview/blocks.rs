@@ -190,11 +190,7 @@ fn panel_row_cells(
         get_context_type_meta(ctx.context_type.as_str()).map_or(ctx.context_type.as_str(), |m| m.display_name);
 
     let details = modules.iter().find_map(|m| m.context_detail(ctx)).unwrap_or_default();
-    let truncated_details = if details.len() > 30 {
-        format!("{}...", details.get(..details.floor_char_boundary(27)).unwrap_or(""))
-    } else {
-        details
-    };
+    let truncated_details = cp_base::ui::text::ellipsize(&details, 30, "...");
 
     let id_with_icon = format!("{}{}", ctx.context_type.icon(), ctx.id);
     let cost_str = format!("${:.2}", ctx.panel_total_cost);
@@ -21,6 +21,12 @@ use crate::infra::tools::{ToolResult, ToolUse};
 use crate::modules::ToolVisualizer;
 use crate::state::{Kind, State, TypeMeta};
 
+pub(crate) use self::tools::cache_budget;
+pub(crate) use self::tools::context_health;
+pub(crate) use self::tools::panel_gc;
+pub(crate) use self::tools::panel_group;
+pub(crate) use self::tools::panel_layout;
+
 use self::panel::OverviewPanel;
 use self::tools_panel::ToolsPanel;
 use super::Module;
@@ -57,7 +63,7 @@ where
 }
 
 /// Restore the LLM provider selection and all per-provider model choices.
-fn load_provider_models(data: &serde_json::Value, state: &mut State) {
+pub(crate) fn load_provider_models(data: &serde_json::Value, state: &mut State) {
     load_enum_field(data, "llm_provider", &mut state.llm_provider);
     load_enum_field(data, "anthropic_model", &mut state.anthropic_model);
     load_enum_field(data, "grok_model", &mut state.grok_model);
@@ -140,6 +146,8 @@ impl Module for OverviewModule {
     fn save_worker_data(&self, state: &State) -> serde_json::Value {
         json!({
             "previous_panel_hash_list": state.previous_panel_hash_list,
+            "panel_groups": state.get_ext::<panel_group::PanelGroups>(),
+            "panel_gc": state.get_ext::<panel_gc::PanelGc>(),
         })
     }
 
@@ -147,6 +155,12 @@ impl Module for OverviewModule {
         if let Some(arr) = data.get("previous_panel_hash_list").and_then(|v| v.as_array()) {
             state.previous_panel_hash_list = arr.iter().filter_map(|v| v.as_str().map(String::from)).collect();
         }
+        if let Some(groups) = data.get("panel_groups").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.set_ext::<panel_group::PanelGroups>(groups);
+        }
+        if let Some(gc) = data.get("panel_gc").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.set_ext::<panel_gc::PanelGc>(gc);
+        }
     }
 
     fn save_module_data(&self, state: &State) -> serde_json::Value {
@@ -161,6 +175,10 @@ impl Module for OverviewModule {
             "minimax_model": state.minimax_model,
             "claude_code_v2_model": state.claude_code_v2_model,
             "reverie_enabled": state.flags.config.reverie_enabled,
+            "mention_auto_open": state.flags.config.mention_auto_open,
+            "spell_check": cp_base::ui::spell::Settings::enabled(state),
+            "panel_gc_policy": panel_gc::policy(state),
+            "cache_budget": cache_budget::budget(state),
             "cleaning_threshold": state.cleaning_threshold,
             "context_budget": state.context_budget,
             "global_next_uid": state.global_next_uid,
@@ -182,6 +200,18 @@ impl Module for OverviewModule {
         if let Some(v) = data.get("reverie_enabled").and_then(serde_json::Value::as_bool) {
             state.flags.config.reverie_enabled = v;
         }
+        if let Some(v) = data.get("mention_auto_open").and_then(serde_json::Value::as_bool) {
+            state.flags.config.mention_auto_open = v;
+        }
+        if let Some(enabled) = data.get("spell_check").and_then(serde_json::Value::as_bool) {
+            state.set_ext(cp_base::ui::spell::Settings { enabled });
+        }
+        if let Some(policy) = data.get("panel_gc_policy").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.set_ext::<panel_gc::GcPolicy>(policy);
+        }
+        if let Some(limit) = data.get("cache_budget").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            state.set_ext::<cache_budget::CacheBudget>(limit);
+        }
         load_budgets_and_costs(data, state);
         load_disabled_tools(data, state);
     }
@@ -244,6 +274,13 @@ impl Module for OverviewModule {
                 .reverie_allowed(true)
                 .param_array("ids", ParamType::String, true)
                 .build(),
+            ToolDefinition::from_yaml("panel_group", t)
+                .short_desc("Open/close/pin panel groups")
+                .category("Context")
+                .param_enum("action", &["create", "open", "close", "pin", "unpin", "delete"], true)
+                .param("name", ParamType::String, true)
+                .param_array("ids", ParamType::String, false)
+                .build(),
             // System tools
             ToolDefinition::from_yaml("system_reload", t).short_desc("Restart the TUI").category("System").build(),
             // Meta tools
@@ -278,6 +315,14 @@ impl Module for OverviewModule {
                 .build(),
         );
 
+        defs.push(
+            ToolDefinition::from_yaml("panel_refresh", t)
+                .short_desc("Show latest panel content")
+                .category("Context")
+                .param_array("ids", ParamType::String, true)
+                .build(),
+        );
+
         // Add module_toggle tool
         defs.push(super::module_toggle_tool_definition());
 
@@ -293,6 +338,8 @@ impl Module for OverviewModule {
             // Context tools
             "Close_panel" => Some(tools::close_context::execute(tool, state)),
             "panel_goto_page" => Some(tools::panel_goto_page::execute(tool, state)),
+            "panel_refresh" => Some(tools::panel_goto_page::execute_refresh(tool, state)),
+            "panel_group" => Some(panel_group::execute(tool, state)),
 
             // System tools (reload stays in core)
             "system_reload" => Some(crate::infra::tools::execute_reload_tui(tool, state)),
@@ -311,6 +358,8 @@ impl Module for OverviewModule {
             ("tool_manage", visualizers::visualize_core_output),
             ("system_reload", visualizers::visualize_core_output),
             ("panel_goto_page", visualizers::visualize_core_output),
+            ("panel_refresh", visualizers::visualize_core_output),
+            ("panel_group", visualizers::visualize_core_output),
         ]
     }
 
@@ -318,9 +367,15 @@ impl Module for OverviewModule {
         &[]
     }
 
-    fn init_state(&self, _state: &mut State) {}
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(panel_group::PanelGroups::default());
+        state.set_ext(panel_gc::PanelGc::default());
+    }
 
-    fn reset_state(&self, _state: &mut State) {}
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(panel_group::PanelGroups::default());
+        state.set_ext(panel_gc::PanelGc::default());
+    }
 
     fn dynamic_panel_types(&self) -> Vec<Kind> {
         vec![]
@@ -346,7 +401,9 @@ impl Module for OverviewModule {
         None
     }
 
-    fn on_user_message(&self, _state: &mut State) {}
+    fn on_user_message(&self, state: &mut State) {
+        panel_gc::on_user_message(state);
+    }
 
     fn on_stream_stop(&self, _state: &mut State) {}
 
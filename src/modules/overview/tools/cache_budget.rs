@@ -0,0 +1,217 @@
+//! Cache memory budget.
+//!
+//! File, glob, grep and git result panels keep their whole content in memory
+//! (`cached_content`). When those caches together outgrow the budget (Ctrl+H →
+//! `m`), the least recently used ones — neither selected nor refreshed for the
+//! longest — are evicted: their content is dropped and the panel keeps its
+//! token count. An evicted panel reloads in the background when selected, and
+//! synchronously before the next request, so the LLM always sees it whole.
+//! Eviction only runs between turns, never while a stream needs the panels.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Entry, Kind, State, Unloaded};
+
+use super::panel_group;
+
+/// Minimum time between two budget checks.
+const CHECK_INTERVAL_MS: u64 = 2_000;
+/// Panel kinds whose content can be fetched again from disk or a command.
+const EVICTABLE: [&str; 4] = [Kind::FILE, Kind::GLOB, Kind::GREP, Kind::GIT_RESULT];
+/// Bytes per megabyte.
+const MB: usize = 1024 * 1024;
+
+/// Budget until the user picks another one, in megabytes.
+const DEFAULT_MB: usize = 256;
+/// Budgets cycled by Ctrl+H → `m`, in megabytes; 0 is off.
+const PRESETS: [usize; 4] = [DEFAULT_MB, 128, 64, 0];
+
+/// Memory the evictable caches may hold together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CacheBudget {
+    /// Budget in megabytes; 0 disables eviction.
+    pub mb: usize,
+}
+
+impl Default for CacheBudget {
+    fn default() -> Self {
+        Self { mb: DEFAULT_MB }
+    }
+}
+
+impl CacheBudget {
+    /// Short label for the config overlay and the perf overlay.
+    pub(crate) fn label(self) -> String {
+        if self.mb == 0 { "OFF".to_owned() } else { format!("{} MB", self.mb) }
+    }
+
+    /// The budget in bytes, `None` when off.
+    const fn bytes(self) -> Option<usize> {
+        if self.mb == 0 { None } else { Some(self.mb.saturating_mul(MB)) }
+    }
+}
+
+/// LRU bookkeeping of this worker (runtime only).
+#[derive(Debug, Clone, Default)]
+struct CacheLru {
+    /// When the last check ran.
+    last_check_ms: u64,
+    /// Panel key → when the user last selected it.
+    selected: BTreeMap<String, u64>,
+}
+
+/// The active budget.
+pub(crate) fn budget(state: &State) -> CacheBudget {
+    state.get_ext::<CacheBudget>().copied().unwrap_or_default()
+}
+
+/// Ctrl+H → `m`: switch to the next preset budget.
+pub(crate) fn cycle_budget(state: &mut State) {
+    let current = budget(state).mb;
+    let next = PRESETS.iter().position(|mb| *mb == current).map_or(0, |i| i.saturating_add(1));
+    state.set_ext(CacheBudget { mb: PRESETS.get(next).copied().unwrap_or(0) });
+    state.flags.ui.dirty = true;
+}
+
+/// Mutable bookkeeping, created on first use.
+fn lru_mut(state: &mut State) -> &mut CacheLru {
+    if state.get_ext::<CacheLru>().is_none() {
+        state.set_ext(CacheLru::default());
+    }
+    state.ext_mut::<CacheLru>()
+}
+
+/// Whether `ctx` holds content the budget may drop.
+fn is_evictable(ctx: &Entry) -> bool {
+    EVICTABLE.contains(&ctx.context_type.as_str()) && !ctx.cache_in_flight && ctx.cached_content.is_some()
+}
+
+/// Bytes held by the content cache of `ctx`.
+pub(crate) fn cache_bytes(ctx: &Entry) -> usize {
+    ctx.cached_content.as_ref().map_or(0, String::capacity)
+}
+
+/// Evictable panels, least recently used first (last selected or refreshed).
+fn eviction_order<'st>(state: &'st State, selected: &BTreeMap<String, u64>) -> Vec<&'st Entry> {
+    let current = state.context.get(state.selected_context).map(|c| c.id.as_str());
+    let mut candidates: Vec<&Entry> =
+        state.context.iter().filter(|c| is_evictable(c) && Some(c.id.as_str()) != current).collect();
+    candidates.sort_by_key(|c| {
+        let seen = selected.get(&panel_group::panel_key(c)).copied().unwrap_or(0);
+        seen.max(c.last_refresh_ms)
+    });
+    candidates
+}
+
+/// IDs to evict so the caches fit in `limit` bytes, oldest first.
+fn over_budget(state: &State, selected: &BTreeMap<String, u64>, limit: usize) -> Vec<String> {
+    let mut held: usize =
+        state.context.iter().filter(|c| EVICTABLE.contains(&c.context_type.as_str())).map(cache_bytes).sum();
+    let mut evict = Vec::new();
+    for ctx in eviction_order(state, selected) {
+        if held <= limit {
+            break;
+        }
+        held = held.saturating_sub(cache_bytes(ctx));
+        evict.push(ctx.id.clone());
+    }
+    evict
+}
+
+/// Drop the content of `ctx`; the next load must not short-circuit on the
+/// source hash, which still matches the file.
+fn evict(ctx: &mut Entry) {
+    ctx.cached_content = None;
+    ctx.source_hash = None;
+    ctx.cache_unloaded = Some(Unloaded::Evicted);
+}
+
+/// Run the check (throttled, between turns only): note the selected panel
+/// and evict the least recently used caches over the budget.
+pub(crate) fn tick(state: &mut State) {
+    let now = cp_base::panels::now_ms();
+    let selected = state.context.get(state.selected_context).map(panel_group::panel_key);
+    let open: Vec<String> = state.context.iter().map(panel_group::panel_key).collect();
+    let streaming = state.flags.stream.phase.is_streaming();
+    let lru = lru_mut(state);
+    if let Some(key) = selected {
+        let _previous = lru.selected.insert(key, now);
+    }
+    if now.saturating_sub(lru.last_check_ms) < CHECK_INTERVAL_MS || streaming {
+        return;
+    }
+    lru.last_check_ms = now;
+    lru.selected.retain(|key, _| open.contains(key));
+    let seen = lru.selected.clone();
+    let Some(limit) = budget(state).bytes() else { return };
+    for id in over_budget(state, &seen, limit) {
+        if let Some(ctx) = state.context.iter_mut().find(|c| c.id == id) {
+            evict(ctx);
+        }
+    }
+}
+
+/// One panel's cache for the perf overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CacheUsage {
+    /// Panel ID.
+    pub id: String,
+    /// Panel name.
+    pub name: String,
+    /// Bytes held.
+    pub bytes: usize,
+}
+
+/// Cache sizes of the open panels, largest first, and how many are evicted.
+pub(crate) fn usage(state: &State) -> (Vec<CacheUsage>, usize) {
+    let mut rows: Vec<CacheUsage> = state
+        .context
+        .iter()
+        .filter(|c| c.cached_content.is_some())
+        .map(|c| CacheUsage { id: c.id.clone(), name: c.name.clone(), bytes: cache_bytes(c) })
+        .collect();
+    rows.sort_by_key(|row| std::cmp::Reverse(row.bytes));
+    (rows, state.context.iter().filter(|c| c.cache_unloaded == Some(Unloaded::Evicted)).count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::context::make_default_entry;
+
+    /// A state holding file panels `(id, bytes, last refresh)`.
+    fn state_with(panels: &[(&str, usize, u64)]) -> State {
+        let mut state = State::default();
+        for &(id, bytes, refreshed) in panels {
+            let mut entry = make_default_entry(id, Kind::new(Kind::FILE), &format!("{id}.rs"), false);
+            entry.cached_content = Some("x".repeat(bytes));
+            entry.last_refresh_ms = refreshed;
+            state.context.push(entry);
+        }
+        state.selected_context = usize::MAX;
+        state
+    }
+
+    #[test]
+    fn least_recently_used_caches_go_first() {
+        let state = state_with(&[("P8", 400, 30), ("P9", 300, 10), ("P10", 200, 20)]);
+        assert_eq!(over_budget(&state, &BTreeMap::new(), 500), ["P9", "P10"]);
+        let viewed = BTreeMap::from([("P9".to_owned(), 99)]);
+        assert_eq!(over_budget(&state, &viewed, 500), ["P10", "P8"]);
+        assert!(over_budget(&state, &BTreeMap::new(), 1_000).is_empty());
+    }
+
+    #[test]
+    fn evicted_panels_report_no_cache() {
+        let mut state = state_with(&[("P8", 100, 0)]);
+        if let Some(ctx) = state.context.first_mut() {
+            evict(ctx);
+        }
+        let (rows, evicted) = usage(&state);
+        assert!(rows.is_empty());
+        assert_eq!(evicted, 1);
+        assert_eq!(CacheBudget { mb: 0 }.bytes(), None);
+    }
+}
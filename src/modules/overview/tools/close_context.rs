@@ -8,7 +8,7 @@ use std::fmt::Write as _;
 struct CloseTally {
     /// Panels successfully closed (with their display descriptions).
     closed: Vec<String>,
-    /// Panels skipped (protected, describe-gated, or module-rejected).
+    /// Panels skipped (protected, pinned, describe-gated, or module-rejected).
     skipped: Vec<String>,
     /// Requested ids that matched no live panel.
     not_found: Vec<String>,
@@ -85,6 +85,10 @@ fn process_close_id(state: &mut State, id: &str, modules: &[Box<dyn Module>], ta
         tally.skipped.push(format!("{id} (protected)"));
         return;
     }
+    if super::panel_group::is_pinned(ctx_elem) {
+        tally.skipped.push(format!("{id} (pinned \u{2014} unpin its group first)"));
+        return;
+    }
     if let Some(skip_msg) = file_panel_needs_describe(state, ctx_elem) {
         tally.skipped.push(skip_msg);
         return;
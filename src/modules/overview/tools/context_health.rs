@@ -0,0 +1,253 @@
+//! Context health alerts.
+//!
+//! Every few seconds the context is measured against the effective budget:
+//! usage past `context_warn_pct` of it, or a single panel holding more than
+//! `panel_alert_pct`, posts a `ContextHealth` spine notification naming the
+//! panels whose closing helps most. The same suggestion stays in a banner
+//! above the input, where Ctrl+X closes them in one go.
+//!
+//! Each alert fires once per crossing: the warning re-arms only after usage
+//! fell [`REARM_PCT`] points under the threshold, a panel alert once that
+//! panel shrank back under its share. Like the panel GC notice, an alert only
+//! reaches the conversation mid-stream — an idle agent is never woken for it.
+
+use cp_base::config::INJECTIONS;
+use cp_mod_spine::types::{NotificationType, SpineConfig, SpineState};
+
+use crate::state::{Entry, State};
+use crate::ui::helpers::format_number;
+
+use super::{panel_group, panel_layout};
+
+/// Minimum time between two checks.
+const CHECK_INTERVAL_MS: u64 = 5_000;
+/// Notification source of the alerts.
+const SOURCE: &str = "context_health";
+/// Points under the warn threshold usage must fall before it warns again.
+const REARM_PCT: usize = 5;
+/// Panels suggested at most.
+const MAX_SUGGESTED: usize = 3;
+
+/// Alert bookkeeping of this worker (runtime only).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ContextHealth {
+    /// When the last check ran.
+    last_check_ms: u64,
+    /// The usage warning fired and has not re-armed yet.
+    warned: bool,
+    /// Keys of the panels already alerted as too large.
+    heavy: Vec<String>,
+    /// IDs of the panels Ctrl+X closes, largest first.
+    suggested: Vec<String>,
+}
+
+/// One check's findings.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Assessment {
+    /// Usage is at or past the warn threshold.
+    over: bool,
+    /// Usage fell far enough under the threshold to warn again.
+    rearmed: bool,
+    /// Keys of the panels over their budget share.
+    heavy: Vec<String>,
+    /// IDs of the panels to suggest closing, largest first.
+    suggested: Vec<String>,
+}
+
+/// `pct` percent of `budget`; `None` when the threshold is disabled.
+fn share(budget: usize, pct: usize) -> Option<usize> {
+    (pct > 0).then(|| budget.saturating_mul(pct).checked_div(100).unwrap_or(0))
+}
+
+/// Whether closing `ctx` is on the table: a dynamic panel that is neither
+/// pinned nor bound to a quick slot.
+fn is_closable(state: &State, ctx: &Entry) -> bool {
+    panel_layout::is_movable(ctx) && !panel_group::is_pinned(ctx) && panel_group::slot_of(state, ctx).is_none()
+}
+
+/// Measure `used` tokens against `budget` with the thresholds of `cfg`.
+fn assess(state: &State, used: usize, budget: usize, cfg: &SpineConfig) -> Assessment {
+    let (warn_pct, panel_pct) = (cfg.context_warn_pct, cfg.panel_alert_pct);
+    let mut closable: Vec<&Entry> = state.context.iter().filter(|c| is_closable(state, c)).collect();
+    closable.sort_by_key(|c| std::cmp::Reverse(c.token_count));
+    let heavy: Vec<&Entry> = share(budget, panel_pct)
+        .map(|limit| closable.iter().copied().filter(|c| c.token_count > limit).collect())
+        .unwrap_or_default();
+    let warn_at = share(budget, warn_pct);
+    let over = warn_at.is_some_and(|at| used >= at);
+    let rearm_at = share(budget, warn_pct.saturating_sub(REARM_PCT));
+    let rearmed = rearm_at.is_some_and(|at| used < at);
+
+    let mut suggested: Vec<String> = heavy.iter().map(|c| c.id.clone()).collect();
+    if let Some(at) = warn_at.filter(|_| over) {
+        // Largest first, until usage would be back under the threshold.
+        let mut freed: usize = heavy.iter().map(|c| c.token_count).sum();
+        for ctx in &closable {
+            if used.saturating_sub(freed) < at || suggested.len() >= MAX_SUGGESTED {
+                break;
+            }
+            if !suggested.contains(&ctx.id) {
+                suggested.push(ctx.id.clone());
+                freed = freed.saturating_add(ctx.token_count);
+            }
+        }
+    }
+    Assessment { over, rearmed, heavy: heavy.iter().map(|c| panel_group::panel_key(c)).collect(), suggested }
+}
+
+/// `P12 src/main.rs (31.2K)` for each of `ids`.
+fn describe(state: &State, ids: &[String]) -> String {
+    let named: Vec<String> = ids
+        .iter()
+        .filter_map(|id| state.context.iter().find(|c| c.id == *id))
+        .map(|c| format!("{} {} ({})", c.id, c.name, format_number(c.token_count)))
+        .collect();
+    named.join(", ")
+}
+
+/// Mutable bookkeeping, created on first use.
+fn health_mut(state: &mut State) -> &mut ContextHealth {
+    if state.get_ext::<ContextHealth>().is_none() {
+        state.set_ext(ContextHealth::default());
+    }
+    state.ext_mut::<ContextHealth>()
+}
+
+/// Run the check (throttled): record the suggestion for the banner and post
+/// an alert for each new crossing.
+pub(crate) fn tick(state: &mut State) {
+    let now = cp_base::panels::now_ms();
+    if now.saturating_sub(health_mut(state).last_check_ms) < CHECK_INTERVAL_MS {
+        return;
+    }
+    health_mut(state).last_check_ms = now;
+    let cfg = SpineState::get(state).config;
+    let (used, _, budget) = super::super::context::context_usage(state);
+    if budget == 0 {
+        return;
+    }
+    let found = assess(state, used, budget, &cfg);
+
+    let health = health_mut(state);
+    let warn = found.over && !health.warned;
+    let new_heavy: Vec<String> = found.heavy.iter().filter(|k| !health.heavy.contains(k)).cloned().collect();
+    health.warned = (health.warned && !found.rearmed) || found.over;
+    health.heavy.clone_from(&found.heavy);
+    if health.suggested != found.suggested {
+        health.suggested.clone_from(&found.suggested);
+        state.flags.ui.dirty = true;
+    }
+
+    if warn {
+        let content = warning_text(state, (used, budget), &found.suggested);
+        post(state, content);
+    }
+    if !new_heavy.is_empty() {
+        let content = heavy_text(state, &new_heavy, budget, cfg.panel_alert_pct);
+        post(state, content);
+    }
+}
+
+/// The usage warning for `tokens` (used, budget), suggesting `ids`.
+fn warning_text(state: &State, tokens: (usize, usize), ids: &[String]) -> String {
+    let percent = tokens.0.saturating_mul(100).checked_div(tokens.1).unwrap_or(0);
+    INJECTIONS
+        .spine
+        .context_warning
+        .trim()
+        .replace("{used}", &format_number(tokens.0))
+        .replace("{budget}", &format_number(tokens.1))
+        .replace("{pct}", &percent.to_string())
+        .replace("{panels}", &describe(state, ids))
+}
+
+/// The alert naming the panels with keys `keys`, over `limit_pct` of `total`.
+fn heavy_text(state: &State, keys: &[String], total: usize, limit_pct: usize) -> String {
+    let ids: Vec<String> =
+        state.context.iter().filter(|c| keys.contains(&panel_group::panel_key(c))).map(|c| c.id.clone()).collect();
+    INJECTIONS
+        .spine
+        .heavy_panel
+        .trim()
+        .replace("{panels}", &describe(state, &ids))
+        .replace("{pct}", &limit_pct.to_string())
+        .replace("{budget}", &format_number(total))
+}
+
+/// Post an alert. It lands in the conversation only mid-stream; either way
+/// it is marked processed at once, so it never triggers a continuation.
+fn post(state: &mut State, content: String) {
+    let id = SpineState::create_notification(state, NotificationType::ContextHealth, SOURCE.to_owned(), content);
+    let _processed = SpineState::mark_notification_processed(state, &id);
+    state.flags.ui.dirty = true;
+}
+
+/// IDs of the panels Ctrl+X would close, still open.
+pub(crate) fn suggested(state: &State) -> Vec<String> {
+    let Some(health) = state.get_ext::<ContextHealth>() else { return Vec::new() };
+    health.suggested.iter().filter(|id| state.context.iter().any(|c| c.id == **id)).cloned().collect()
+}
+
+/// Slim banner above the input while panels are suggested for closing.
+pub(crate) fn banner(state: &State) -> Option<cp_render::Block> {
+    use cp_render::{Semantic, Span as S};
+    let ids = suggested(state);
+    if ids.is_empty() {
+        return None;
+    }
+    Some(cp_render::Block::Line(vec![
+        S::styled(format!("\u{26a0} Context heavy \u{2014} {} ", describe(state, &ids)), Semantic::Warning),
+        S::styled("Ctrl+X".into(), Semantic::KeyHint),
+        S::styled(" closes them".into(), Semantic::Warning),
+    ]))
+}
+
+/// Ctrl+X: close the suggested panels. Returns whether any closed.
+pub(crate) fn close_suggested(state: &mut State) -> bool {
+    let ids = suggested(state);
+    health_mut(state).suggested.clear();
+    panel_layout::close_panels(state, &ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Kind;
+    use cp_base::state::context::make_default_entry;
+
+    /// A state holding file panels `(id, tokens)`.
+    fn state_with(panels: &[(&str, usize)]) -> State {
+        let mut state = State::default();
+        for &(id, tokens) in panels {
+            let mut entry = make_default_entry(id, Kind::new(Kind::FILE), &format!("{id}.rs"), false);
+            entry.token_count = tokens;
+            state.context.push(entry);
+        }
+        state
+    }
+
+    /// Spine config with the given thresholds.
+    fn thresholds(warn: usize, panel: usize) -> SpineConfig {
+        SpineConfig { context_warn_pct: warn, panel_alert_pct: panel, ..SpineConfig::default() }
+    }
+
+    #[test]
+    fn warning_suggests_the_largest_panels_until_under_the_threshold() {
+        let state = state_with(&[("P3", 5_000), ("P4", 20_000), ("P5", 12_000)]);
+        let found = assess(&state, 80_000, 100_000, &thresholds(70, 0));
+        assert!(found.over && found.heavy.is_empty());
+        assert_eq!(found.suggested, ["P4"]);
+        let deeper = assess(&state, 95_000, 100_000, &thresholds(70, 0));
+        assert_eq!(deeper.suggested, ["P4", "P5"]);
+    }
+
+    #[test]
+    fn heavy_panels_are_flagged_below_the_warn_threshold() {
+        let state = state_with(&[("P3", 30_000), ("P4", 2_000)]);
+        let found = assess(&state, 40_000, 100_000, &thresholds(70, 25));
+        assert!(!found.over && found.rearmed);
+        assert_eq!(found.heavy.len(), 1);
+        assert_eq!(found.suggested, ["P3"]);
+        assert_eq!(assess(&state, 40_000, 100_000, &thresholds(0, 0)), Assessment::default());
+    }
+}
@@ -1,8 +1,18 @@
+/// Cache memory budget: LRU eviction of panel content.
+pub(crate) mod cache_budget;
 /// Tool for closing/removing context panels.
 pub(super) mod close_context;
+/// Context health alerts and the Ctrl+X close suggestion.
+pub(crate) mod context_health;
 /// Tool for enabling/disabling other tools.
 pub(super) mod manage_tools;
-/// Tool for navigating paginated panels.
+/// Automatic garbage collection of idle panels.
+pub(crate) mod panel_gc;
+/// Tools for navigating paginated panels and refreshing stale ones.
 pub(super) mod panel_goto_page;
+/// Quick slots (Alt+1..9) and panel groups (`panel_group` tool).
+pub(crate) mod panel_group;
+/// Sidebar close / reorder and the deprecated-panel sweep.
+pub(crate) mod panel_layout;
 /// Pre-flight validation for the core module's tools.
 pub(super) mod preflight;
@@ -0,0 +1,367 @@
+//! Automatic panel garbage collection.
+//!
+//! With a policy set (Ctrl+H → `g`), dynamic file / glob / grep / tmux panels
+//! that neither party touched for `idle_turns` user turns or `idle_minutes`
+//! minutes are collected in two steps: a sweep first tells the agent which
+//! panels are about to go (grace notice), then closes those still untouched
+//! [`GRACE_MS`] later through `Close_panel`. Ctrl+Z reopens the last batch.
+//!
+//! The agent touches a panel by naming it — ID or file path — in a tool call,
+//! the user by selecting it. Pinned and quick-slot panels are never collected.
+//! Sweeps only run while the agent is streaming, so the notice lands mid-turn
+//! and an idle session is never woken up for it.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use cp_base::config::INJECTIONS;
+use cp_mod_spine::types::{NotificationType, SpineState};
+
+use crate::infra::tools::ToolUse;
+use crate::state::{Entry, Kind, State};
+
+use super::{panel_group, panel_layout};
+
+/// Time between the grace notice and the close.
+const GRACE_MS: u64 = 120_000;
+/// Minimum time between two sweeps.
+const SWEEP_INTERVAL_MS: u64 = 5_000;
+/// Notification source of the grace notice.
+const NOTICE_SOURCE: &str = "panel_gc";
+/// Panel kinds the GC may close.
+const COLLECTABLE: [&str; 4] = [Kind::FILE, Kind::GLOB, Kind::GREP, Kind::TMUX];
+
+/// When a panel counts as idle. A zero limit is disabled; both at zero turn
+/// the GC off (the default).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct GcPolicy {
+    /// User turns without a touch.
+    pub idle_turns: usize,
+    /// Minutes without a touch.
+    pub idle_minutes: u64,
+}
+
+/// Policies cycled by Ctrl+H → `g`, starting with "off".
+const PRESETS: [GcPolicy; 4] = [
+    GcPolicy { idle_turns: 0, idle_minutes: 0 },
+    GcPolicy { idle_turns: 20, idle_minutes: 60 },
+    GcPolicy { idle_turns: 10, idle_minutes: 30 },
+    GcPolicy { idle_turns: 5, idle_minutes: 15 },
+];
+
+impl GcPolicy {
+    /// Whether the GC is disabled.
+    pub(crate) const fn is_off(self) -> bool {
+        self.idle_turns == 0 && self.idle_minutes == 0
+    }
+
+    /// Short label for the config overlay (e.g. `10 turns / 30 min`).
+    pub(crate) fn label(self) -> String {
+        match (self.idle_turns, self.idle_minutes) {
+            (0, 0) => "OFF".to_owned(),
+            (turns, 0) => format!("{turns} turns"),
+            (0, minutes) => format!("{minutes} min"),
+            (turns, minutes) => format!("{turns} turns / {minutes} min"),
+        }
+    }
+
+    /// Whether a panel last touched at `last` is idle at `now`.
+    const fn is_idle(self, last: Touch, now: Touch) -> bool {
+        let by_turns = self.idle_turns > 0 && now.turn.saturating_sub(last.turn) >= self.idle_turns;
+        let limit_ms = self.idle_minutes.saturating_mul(60_000);
+        let by_time = self.idle_minutes > 0 && now.ms.saturating_sub(last.ms) >= limit_ms;
+        by_turns || by_time
+    }
+}
+
+/// A point in the session: user turn and wall-clock time.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Touch {
+    /// User turn number.
+    turn: usize,
+    /// Milliseconds since the epoch.
+    ms: u64,
+}
+
+/// The panels named in the pending grace notice.
+#[derive(Debug, Clone)]
+struct Notice {
+    /// Keys of the announced panels.
+    keys: Vec<String>,
+    /// When the notice was posted.
+    at: Touch,
+}
+
+/// GC bookkeeping of this worker.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct PanelGc {
+    /// User turns seen so far.
+    #[serde(default)]
+    turn: usize,
+    /// Panel key → last touch, for the collectable panels.
+    #[serde(default)]
+    touches: BTreeMap<String, Touch>,
+    /// Pending grace notice.
+    #[serde(skip)]
+    notice: Option<Notice>,
+    /// When the last sweep ran.
+    #[serde(skip)]
+    last_sweep_ms: u64,
+    /// Panels closed by the last collection, for Ctrl+Z.
+    #[serde(skip)]
+    collected: Vec<Entry>,
+}
+
+/// Mutable bookkeeping, created on first use.
+fn gc_mut(state: &mut State) -> &mut PanelGc {
+    if state.get_ext::<PanelGc>().is_none() {
+        state.set_ext(PanelGc::default());
+    }
+    state.ext_mut::<PanelGc>()
+}
+
+/// The active policy.
+pub(crate) fn policy(state: &State) -> GcPolicy {
+    state.get_ext::<GcPolicy>().copied().unwrap_or_default()
+}
+
+/// Ctrl+H → `g`: switch to the next preset policy.
+pub(crate) fn cycle_policy(state: &mut State) {
+    let current = policy(state);
+    let next = PRESETS.iter().position(|p| *p == current).map_or(0, |i| i.saturating_add(1));
+    state.set_ext(PRESETS.get(next).copied().unwrap_or_default());
+    gc_mut(state).notice = None;
+    state.flags.ui.dirty = true;
+}
+
+/// Count a user turn.
+pub(crate) fn on_user_message(state: &mut State) {
+    let gc = gc_mut(state);
+    gc.turn = gc.turn.saturating_add(1);
+}
+
+/// The current point in the session.
+fn now(state: &State) -> Touch {
+    Touch { turn: state.get_ext::<PanelGc>().map_or(0, |gc| gc.turn), ms: cp_base::panels::now_ms() }
+}
+
+/// Whether the GC may ever close `ctx`.
+fn is_collectable(state: &State, ctx: &Entry) -> bool {
+    COLLECTABLE.contains(&ctx.context_type.as_str())
+        && !panel_group::is_pinned(ctx)
+        && panel_group::slot_of(state, ctx).is_none()
+}
+
+/// Record the agent touching every panel `tool` names by ID or file path.
+pub(crate) fn note_tool_use(state: &mut State, tool: &ToolUse) {
+    if policy(state).is_off() {
+        return;
+    }
+    let text = tool.input.to_string();
+    let words: HashSet<&str> = text.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+    let keys: Vec<String> = state
+        .context
+        .iter()
+        .filter(|c| COLLECTABLE.contains(&c.context_type.as_str()))
+        .filter(|c| words.contains(c.id.as_str()) || c.get_meta_str("file_path").is_some_and(|p| text.contains(p)))
+        .map(panel_group::panel_key)
+        .collect();
+    let at = now(state);
+    let gc = gc_mut(state);
+    for key in keys {
+        let _previous = gc.touches.insert(key, at);
+    }
+}
+
+/// Start tracking new panels, forget closed ones, and count the selected
+/// panel as touched by the user.
+fn refresh_touches(state: &mut State, at: Touch) {
+    let open: Vec<String> = state
+        .context
+        .iter()
+        .filter(|c| COLLECTABLE.contains(&c.context_type.as_str()))
+        .map(panel_group::panel_key)
+        .collect();
+    let selected = state.context.get(state.selected_context).map(panel_group::panel_key);
+    let gc = gc_mut(state);
+    gc.touches.retain(|key, _| open.contains(key));
+    for key in open {
+        let _touch = gc.touches.entry(key).or_insert(at);
+    }
+    if let Some(key) = selected
+        && let Some(touch) = gc.touches.get_mut(&key)
+    {
+        *touch = at;
+    }
+}
+
+/// Collectable panels whose last touch passes `is_old`, in sidebar order.
+fn untouched_since(state: &State, is_old: impl Fn(Touch) -> bool) -> Vec<&Entry> {
+    let Some(gc) = state.get_ext::<PanelGc>() else { return Vec::new() };
+    let mut panels: Vec<&Entry> = state
+        .context
+        .iter()
+        .filter(|c| is_collectable(state, c))
+        .filter(|c| gc.touches.get(&panel_group::panel_key(c)).is_some_and(|t| is_old(*t)))
+        .collect();
+    panels.sort_by_key(|c| panel_layout::display_key(state, c));
+    panels
+}
+
+/// Run the GC: track touches, then — while the agent streams — post a grace
+/// notice for idle panels, or close the announced ones once the grace is over.
+pub(crate) fn tick(state: &mut State) {
+    let gc_policy = policy(state);
+    let at = now(state);
+    let last_sweep = state.get_ext::<PanelGc>().map_or(0, |gc| gc.last_sweep_ms);
+    if gc_policy.is_off() || at.ms.saturating_sub(last_sweep) < SWEEP_INTERVAL_MS {
+        return;
+    }
+    gc_mut(state).last_sweep_ms = at.ms;
+    refresh_touches(state, at);
+    if !state.flags.stream.phase.is_streaming() {
+        return;
+    }
+    let notice_ms = state.get_ext::<PanelGc>().and_then(|gc| gc.notice.as_ref()).map(|n| n.at.ms);
+    match notice_ms {
+        Some(ms) if at.ms.saturating_sub(ms) >= GRACE_MS => collect(state),
+        Some(_) => {}
+        None => post_notice(state, gc_policy, at),
+    }
+}
+
+/// Announce the idle panels to the agent. The notice only counts when it
+/// reached the conversation mid-stream; otherwise it is withdrawn and the
+/// next sweep tries again.
+fn post_notice(state: &mut State, gc_policy: GcPolicy, at: Touch) {
+    let idle = untouched_since(state, |last| gc_policy.is_idle(last, at));
+    if idle.is_empty() {
+        return;
+    }
+    let keys: Vec<String> = idle.iter().map(|c| panel_group::panel_key(c)).collect();
+    let list: Vec<String> = idle.iter().map(|c| format!("{} ({})", c.id, c.name)).collect();
+    let grace_minutes = GRACE_MS.checked_div(60_000).unwrap_or(0);
+    let content = INJECTIONS
+        .spine
+        .panel_gc
+        .trim()
+        .replace("{panels}", &list.join(", "))
+        .replace("{minutes}", &grace_minutes.to_string());
+    let before = state.messages.len();
+    let id = SpineState::create_notification(state, NotificationType::Custom, NOTICE_SOURCE.to_owned(), content);
+    if state.messages.len() > before {
+        let _processed = SpineState::mark_notification_processed(state, &id);
+        gc_mut(state).notice = Some(Notice { keys, at });
+    } else {
+        let _removed = SpineState::delete_notifications_by_source(state, NOTICE_SOURCE);
+    }
+}
+
+/// Close the announced panels nobody touched since the notice, keeping
+/// them for Ctrl+Z.
+fn collect(state: &mut State) {
+    let Some(notice) = gc_mut(state).notice.take() else { return };
+    let doomed: Vec<Entry> = untouched_since(state, |last| last.ms <= notice.at.ms)
+        .into_iter()
+        .filter(|c| notice.keys.contains(&panel_group::panel_key(c)))
+        .cloned()
+        .collect();
+    let ids: Vec<String> = doomed.iter().map(|c| c.id.clone()).collect();
+    let selected = state.context.get(state.selected_context).map(panel_group::panel_key);
+    if !panel_layout::close_panels(state, &ids) {
+        return;
+    }
+    if let Some(idx) = selected.and_then(|key| state.context.iter().position(|c| panel_group::panel_key(c) == key)) {
+        state.selected_context = idx;
+    }
+    let closed: Vec<Entry> = doomed
+        .into_iter()
+        .filter(|e| !state.context.iter().any(|c| panel_group::panel_key(c) == panel_group::panel_key(e)))
+        .collect();
+    gc_mut(state).collected = closed;
+}
+
+/// Number of panels Ctrl+Z would reopen.
+pub(crate) fn undoable(state: &State) -> usize {
+    state.get_ext::<PanelGc>().map_or(0, |gc| gc.collected.len())
+}
+
+/// Ctrl+Z: reopen the panels of the last collection (a panel whose old ID
+/// was reused meanwhile gets a fresh one). Returns how many came back.
+pub(crate) fn undo(state: &mut State) -> usize {
+    let batch = std::mem::take(&mut gc_mut(state).collected);
+    let at = now(state);
+    let mut restored: usize = 0;
+    for mut entry in batch {
+        let key = panel_group::panel_key(&entry);
+        if state.context.iter().any(|c| panel_group::panel_key(c) == key) {
+            continue;
+        }
+        if state.context.iter().any(|c| c.id == entry.id) {
+            entry.id = state.next_available_context_id();
+        }
+        entry.cache_deprecated = true;
+        entry.cache_in_flight = false;
+        entry.last_refresh_ms = at.ms;
+        state.context.push(entry);
+        let _previous = gc_mut(state).touches.insert(key, at);
+        restored = restored.saturating_add(1);
+    }
+    state.flags.ui.dirty = true;
+    restored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::context::make_default_entry;
+
+    fn state_with_grep(policy: GcPolicy) -> State {
+        let mut state = State::default();
+        state.set_ext(policy);
+        let mut entry = make_default_entry("P12", Kind::new(Kind::GREP), "grep TODO", false);
+        entry.uid = Some("UID_12_P".to_owned());
+        state.context.push(entry);
+        state.context.push(make_default_entry("P1", Kind::new(Kind::TODO), "Todo", false));
+        state.selected_context = 1;
+        state
+    }
+
+    #[test]
+    fn idle_by_turns_unless_named_in_a_tool_call() {
+        let gc_policy = GcPolicy { idle_turns: 2, idle_minutes: 0 };
+        let mut state = state_with_grep(gc_policy);
+        let start = now(&state);
+        refresh_touches(&mut state, start);
+        on_user_message(&mut state);
+        on_user_message(&mut state);
+        let at = now(&state);
+        assert_eq!(untouched_since(&state, |last| gc_policy.is_idle(last, at)).len(), 1);
+
+        let tool = ToolUse::new("t1".to_owned(), "Close_panel".to_owned(), serde_json::json!({"ids": ["P12"]}));
+        note_tool_use(&mut state, &tool);
+        assert!(untouched_since(&state, |last| gc_policy.is_idle(last, at)).is_empty());
+    }
+
+    #[test]
+    fn undo_reopens_the_collected_batch() {
+        let mut state = state_with_grep(GcPolicy { idle_turns: 20, idle_minutes: 60 });
+        let panel = state.context.remove(0);
+        gc_mut(&mut state).collected = vec![panel];
+        assert_eq!(undoable(&state), 1);
+        assert_eq!(undo(&mut state), 1);
+        assert!(state.context.iter().any(|c| c.id == "P12" && c.cache_deprecated));
+        assert_eq!(undoable(&state), 0);
+    }
+
+    #[test]
+    fn presets_cycle_back_to_off() {
+        let mut state = State::default();
+        for _ in 0..PRESETS.len() {
+            cycle_policy(&mut state);
+        }
+        assert!(policy(&state).is_off());
+        assert_eq!(GcPolicy { idle_turns: 10, idle_minutes: 30 }.label(), "10 turns / 30 min");
+    }
+}
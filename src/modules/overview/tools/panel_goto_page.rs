@@ -65,3 +65,23 @@ pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
         false,
     )
 }
+
+/// Execute the `panel_refresh` tool: the next prompt shows the latest content
+/// of each panel, even when the freeze would keep the older snapshot.
+pub(crate) fn execute_refresh(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let Some(ids) = tool.input.get("ids").and_then(serde_json::Value::as_array) else {
+        return ToolResult::new(tool.id.clone(), "Missing 'ids' parameter".to_owned(), true);
+    };
+    let mut lines: Vec<String> = Vec::new();
+    for id in ids.iter().filter_map(serde_json::Value::as_str) {
+        let Some(ctx) = state.context.iter_mut().find(|c| c.id == id) else {
+            lines.push(format!("{id}: not found"));
+            continue;
+        };
+        ctx.emitted.refresh_requested = true;
+        ctx.cache_deprecated = true;
+        lines.push(format!("{id}: refreshing (you saw v{})", ctx.emitted.version));
+    }
+    let is_error = lines.iter().all(|l| l.ends_with("not found"));
+    ToolResult::new(tool.id.clone(), lines.join("\n"), is_error)
+}
@@ -0,0 +1,328 @@
+//! Quick slots and panel groups.
+//!
+//! Quick slots bind up to nine panels to Alt+1..9: pressing a bound slot jumps
+//! to its panel, pressing an empty one binds the selected panel, and pressing
+//! the slot of the panel already shown unbinds it. Groups name a set of panels
+//! that are opened, closed and pinned together (`panel_group` tool, Ctrl+P).
+//! Both live in the overview worker data, so they survive reloads.
+//!
+//! Pinned panels refuse `Close_panel` — and with it every cleaning run.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::{Entry, Kind, State};
+
+/// Metadata flag marking a pinned panel.
+pub(crate) const PINNED_META: &str = "pinned";
+/// Number of quick slots (Alt+1..9).
+pub(crate) const SLOT_COUNT: u8 = 9;
+
+/// One panel of a group. File panels remember their path so a closed member
+/// can be reopened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GroupMember {
+    /// Panel UID (fixed panels: their ID).
+    pub key: String,
+    /// Panel name when the member was added.
+    pub label: String,
+    /// File path of a file panel.
+    #[serde(default)]
+    pub file_path: Option<String>,
+}
+
+/// A named set of panels handled together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PanelGroup {
+    /// Group name (e.g. `frontend`).
+    pub name: String,
+    /// Member panels, in creation order.
+    pub members: Vec<GroupMember>,
+}
+
+/// Quick slots, panel groups and the manual panel order of this worker.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct PanelGroups {
+    /// Slot number (1–9) → panel key.
+    #[serde(default)]
+    pub slots: BTreeMap<u8, String>,
+    /// Named groups.
+    #[serde(default)]
+    pub groups: Vec<PanelGroup>,
+    /// Keys of the dynamic panels the user reordered (Shift+Up/Down), in order.
+    #[serde(default)]
+    pub order: Vec<String>,
+}
+
+/// What a quick-slot key press did.
+pub(crate) enum SlotOutcome {
+    /// The slot's panel is at this index: switch to it.
+    Jump(usize),
+    /// The slot was bound or unbound.
+    Changed,
+    /// Nothing to do (slot out of range, nothing selected).
+    Ignored,
+}
+
+/// Stable identity of a panel: its UID, or its ID for fixed panels.
+pub(super) fn panel_key(ctx: &Entry) -> String {
+    ctx.uid.clone().unwrap_or_else(|| ctx.id.clone())
+}
+
+/// Index of the open panel with `key`.
+fn find_panel(state: &State, key: &str) -> Option<usize> {
+    state.context.iter().position(|c| panel_key(c) == key)
+}
+
+/// Mutable groups, created on first use.
+pub(super) fn groups_mut(state: &mut State) -> &mut PanelGroups {
+    if state.get_ext::<PanelGroups>().is_none() {
+        state.set_ext(PanelGroups::default());
+    }
+    state.ext_mut::<PanelGroups>()
+}
+
+/// The quick slot bound to `ctx`, if any.
+pub(crate) fn slot_of(state: &State, ctx: &Entry) -> Option<u8> {
+    let key = panel_key(ctx);
+    state.get_ext::<PanelGroups>()?.slots.iter().find(|slot| *slot.1 == key).map(|slot| *slot.0)
+}
+
+/// Whether `ctx` is pinned.
+pub(crate) fn is_pinned(ctx: &Entry) -> bool {
+    ctx.metadata.get(PINNED_META).and_then(serde_json::Value::as_bool) == Some(true)
+}
+
+/// Handle Alt+`slot`: jump to its panel, or bind/unbind the selected panel.
+pub(crate) fn quick_slot(state: &mut State, slot: u8) -> SlotOutcome {
+    if slot == 0 || slot > SLOT_COUNT {
+        return SlotOutcome::Ignored;
+    }
+    let bound = state.get_ext::<PanelGroups>().and_then(|g| g.slots.get(&slot)).and_then(|k| find_panel(state, k));
+    if let Some(idx) = bound
+        && idx != state.selected_context
+    {
+        return SlotOutcome::Jump(idx);
+    }
+    let Some(selected) = state.context.get(state.selected_context).map(panel_key) else {
+        return SlotOutcome::Ignored;
+    };
+    let groups = groups_mut(state);
+    if bound.is_some() {
+        let _unbound = groups.slots.remove(&slot);
+    } else {
+        groups.slots.retain(|_, key| *key != selected);
+        let _previous = groups.slots.insert(slot, selected);
+    }
+    state.flags.ui.dirty = true;
+    SlotOutcome::Changed
+}
+
+/// Create (or replace) group `name` from the panels `ids`.
+fn create_group(state: &mut State, name: &str, ids: &[String]) -> Result<String, String> {
+    let mut members = Vec::new();
+    for id in ids {
+        let ctx = state.context.iter().find(|c| c.id == *id).ok_or_else(|| format!("Panel {id} not found"))?;
+        if ctx.context_type.as_str() == Kind::CONVERSATION {
+            return Err("The conversation cannot be grouped".to_owned());
+        }
+        members.push(GroupMember {
+            key: panel_key(ctx),
+            label: ctx.name.clone(),
+            file_path: ctx.get_meta_str("file_path").map(str::to_owned),
+        });
+    }
+    if members.is_empty() {
+        return Err("A group needs at least one panel".to_owned());
+    }
+    let count = members.len();
+    let groups = groups_mut(state);
+    groups.groups.retain(|g| g.name != name);
+    groups.groups.push(PanelGroup { name: name.to_owned(), members });
+    Ok(format!("Group '{name}' saved with {count} panel(s): {}", ids.join(", ")))
+}
+
+/// The group called `name`, or an error listing the existing ones.
+fn find_group(state: &State, name: &str) -> Result<PanelGroup, String> {
+    let groups = state.get_ext::<PanelGroups>().map(|g| g.groups.as_slice()).unwrap_or_default();
+    groups.iter().find(|g| g.name == name).cloned().ok_or_else(|| {
+        let names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+        if names.is_empty() {
+            format!("No group '{name}' (no groups defined)")
+        } else {
+            format!("No group '{name}' (groups: {})", names.join(", "))
+        }
+    })
+}
+
+/// The open panels of `group`.
+pub(crate) fn open_members<'state>(state: &'state State, group: &PanelGroup) -> Vec<&'state Entry> {
+    group.members.iter().filter_map(|m| state.context.iter().find(|c| panel_key(c) == m.key)).collect()
+}
+
+/// IDs of the members of `group` that are open.
+fn open_member_ids(state: &State, group: &PanelGroup) -> Vec<String> {
+    open_members(state, group).into_iter().map(|c| c.id.clone()).collect()
+}
+
+/// Run a synthetic tool call through normal module dispatch.
+pub(super) fn dispatch(state: &mut State, name: &str, input: serde_json::Value) -> ToolResult {
+    let tool = ToolUse::new(format!("group-{name}"), name.to_owned(), input);
+    let active = state.active_modules.clone();
+    crate::modules::dispatch_tool(&tool, state, &active)
+}
+
+/// Reopen the closed file members of group `name`, then re-key every file
+/// member to its (possibly new) panel.
+fn open_group(state: &mut State, name: &str) -> Result<String, String> {
+    let group = find_group(state, name)?;
+    let closed: Vec<&GroupMember> = group.members.iter().filter(|m| find_panel(state, &m.key).is_none()).collect();
+    let paths: Vec<&str> = closed.iter().filter_map(|m| m.file_path.as_deref()).collect();
+    let lost: Vec<&str> = closed.iter().filter(|m| m.file_path.is_none()).map(|m| m.label.as_str()).collect();
+    let mut report = if paths.is_empty() {
+        format!("Group '{name}': all reopenable panels are already open")
+    } else {
+        dispatch(state, "Open", serde_json::json!({ "path": paths })).content
+    };
+    if !lost.is_empty() {
+        let _r = write!(report, "\nCannot reopen (not files): {}", lost.join(", "));
+    }
+    rekey_file_members(state, name);
+    Ok(report)
+}
+
+/// Point file members of group `name` at the open panel showing their file.
+fn rekey_file_members(state: &mut State, name: &str) {
+    let keys: Vec<(String, String)> = state
+        .context
+        .iter()
+        .filter_map(|c| c.get_meta_str("file_path").map(|p| (p.to_owned(), panel_key(c))))
+        .collect();
+    let Some(group) = groups_mut(state).groups.iter_mut().find(|g| g.name == name) else { return };
+    for member in &mut group.members {
+        if let Some(path) = member.file_path.as_deref()
+            && let Some(found) = keys.iter().find(|k| k.0 == path)
+        {
+            member.key.clone_from(&found.1);
+        }
+    }
+}
+
+/// Close the open members of group `name` (pinned members refuse).
+fn close_group(state: &mut State, name: &str) -> Result<String, String> {
+    let group = find_group(state, name)?;
+    let ids = open_member_ids(state, &group);
+    if ids.is_empty() {
+        return Ok(format!("Group '{name}': no member is open"));
+    }
+    let result = dispatch(state, "Close_panel", serde_json::json!({ "ids": ids }));
+    if result.is_error { Err(result.content) } else { Ok(result.content) }
+}
+
+/// Pin or unpin the open members of group `name`.
+fn pin_group(state: &mut State, name: &str, pinned: bool) -> Result<String, String> {
+    let group = find_group(state, name)?;
+    let ids = open_member_ids(state, &group);
+    for ctx in state.context.iter_mut().filter(|c| ids.contains(&c.id)) {
+        if pinned {
+            ctx.set_meta(PINNED_META, &true);
+        } else {
+            let _removed = ctx.metadata.remove(PINNED_META);
+        }
+    }
+    state.flags.ui.dirty = true;
+    let verb = if pinned { "Pinned" } else { "Unpinned" };
+    Ok(format!("{verb} {} panel(s) of group '{name}': {}", ids.len(), ids.join(", ")))
+}
+
+/// Forget group `name` (its panels stay as they are).
+fn delete_group(state: &mut State, name: &str) -> Result<String, String> {
+    let _group = find_group(state, name)?;
+    groups_mut(state).groups.retain(|g| g.name != name);
+    Ok(format!("Group '{name}' deleted"))
+}
+
+/// Run a group `action` (`open`, `close`, `pin`, `unpin`, `delete`) on `name`.
+pub(crate) fn run_action(state: &mut State, action: &str, name: &str) -> Result<String, String> {
+    match action {
+        "open" => open_group(state, name),
+        "close" => close_group(state, name),
+        "pin" => pin_group(state, name, true),
+        "unpin" => pin_group(state, name, false),
+        "delete" => delete_group(state, name),
+        other => Err(format!("Unknown action '{other}'")),
+    }
+}
+
+/// Execute the `panel_group` tool.
+pub(crate) fn execute(tool: &ToolUse, state: &mut State) -> ToolResult {
+    let action = tool.input.get("action").and_then(serde_json::Value::as_str).unwrap_or("");
+    let Some(name) = tool.input.get("name").and_then(serde_json::Value::as_str).filter(|n| !n.trim().is_empty()) else {
+        return ToolResult::new(tool.id.clone(), "Missing 'name' parameter".to_owned(), true);
+    };
+    let outcome = if action == "create" {
+        let ids: Vec<String> = tool
+            .input
+            .get("ids")
+            .and_then(serde_json::Value::as_array)
+            .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_owned)).collect())
+            .unwrap_or_default();
+        create_group(state, name.trim(), &ids)
+    } else {
+        run_action(state, action, name.trim())
+    };
+    match outcome {
+        Ok(text) => ToolResult::new(tool.id.clone(), text, false),
+        Err(text) => ToolResult::new(tool.id.clone(), text, true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::context::make_default_entry;
+
+    fn state_with_panels() -> State {
+        let mut state = State::default();
+        for (id, uid) in [("P8", "UID_8_P"), ("P9", "UID_9_P")] {
+            let mut entry = make_default_entry(id, Kind::new(Kind::PASTED), id, false);
+            entry.uid = Some(uid.to_owned());
+            state.context.push(entry);
+        }
+        state
+    }
+
+    #[test]
+    fn quick_slot_binds_then_jumps() {
+        let mut state = state_with_panels();
+        state.selected_context = 0;
+        assert!(matches!(quick_slot(&mut state, 2), SlotOutcome::Changed));
+        state.selected_context = 1;
+        assert!(matches!(quick_slot(&mut state, 2), SlotOutcome::Jump(0)));
+        assert!(matches!(quick_slot(&mut state, 0), SlotOutcome::Ignored));
+    }
+
+    #[test]
+    fn quick_slot_on_its_own_panel_unbinds() {
+        let mut state = state_with_panels();
+        state.selected_context = 0;
+        assert!(matches!(quick_slot(&mut state, 2), SlotOutcome::Changed));
+        assert!(matches!(quick_slot(&mut state, 2), SlotOutcome::Changed));
+        assert!(state.get_ext::<PanelGroups>().is_some_and(|g| g.slots.is_empty()));
+    }
+
+    #[test]
+    fn pinning_a_group_marks_its_open_members() {
+        let mut state = state_with_panels();
+        let ids = vec!["P8".to_owned(), "P9".to_owned()];
+        assert!(create_group(&mut state, "notes", &ids).is_ok_and(|t| t.contains("2 panel(s)")));
+        assert!(run_action(&mut state, "pin", "notes").is_ok_and(|t| t.starts_with("Pinned 2")));
+        assert!(state.context.iter().all(is_pinned));
+        assert!(run_action(&mut state, "unpin", "notes").is_ok_and(|t| t.starts_with("Unpinned 2")));
+        assert!(!state.context.iter().any(is_pinned));
+        assert!(run_action(&mut state, "pin", "missing").is_err_and(|e| e.contains("groups: notes")));
+    }
+}
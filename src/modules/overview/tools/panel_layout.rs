@@ -0,0 +1,131 @@
+//! Sidebar panel management: `x` closes the selected dynamic panel,
+//! Shift+Up/Down moves it, and Ctrl+P → "Close deprecated panels" sweeps the
+//! panels whose source is gone (deleted files, finished consoles).
+//!
+//! The manual order lives in [`PanelGroups::order`]. Reordered panels come
+//! first among the dynamic panels of the sidebar and of Tab navigation, and
+//! first in the request sent to the model; everything else keeps its usual
+//! order (panel number in the sidebar, freshness in the request) after them.
+//! Closing goes through `Close_panel`, so pinned panels still refuse.
+
+use std::path::Path;
+
+use crate::state::{Entry, Kind, State};
+
+use super::panel_group::{self, PanelGroups};
+
+/// Whether the sidebar may close or move `ctx` (dynamic, not the conversation).
+pub(crate) fn is_movable(ctx: &Entry) -> bool {
+    !ctx.context_type.is_fixed() && ctx.context_type.as_str() != Kind::CONVERSATION
+}
+
+/// Position of `ctx` in the manual order, `usize::MAX` when it was never moved.
+fn manual_rank(state: &State, ctx: &Entry) -> usize {
+    let key = panel_group::panel_key(ctx);
+    state.get_ext::<PanelGroups>().and_then(|g| g.order.iter().position(|k| *k == key)).unwrap_or(usize::MAX)
+}
+
+/// Sidebar sort key of `ctx`: fixed panels, then reordered panels, then the
+/// rest — each by panel number.
+pub(crate) fn display_key(state: &State, ctx: &Entry) -> (usize, usize) {
+    let rank = if ctx.context_type.is_fixed() { 0 } else { manual_rank(state, ctx).saturating_add(1) };
+    let number = ctx.id.strip_prefix('P').and_then(|n| n.parse::<usize>().ok()).unwrap_or(usize::MAX);
+    (rank, number)
+}
+
+/// Request sort rank of panel `id`: its manual position, `usize::MAX` for
+/// panels that were never moved.
+pub(crate) fn request_rank(state: &State, id: &str) -> usize {
+    state.context.iter().find(|c| c.id == id).map_or(usize::MAX, |ctx| manual_rank(state, ctx))
+}
+
+/// Move the selected dynamic panel one step up or down the sidebar. The
+/// whole visible dynamic order is recorded, so later panels append after it.
+/// Returns `false` when nothing moved.
+pub(crate) fn move_selected(state: &mut State, up: bool) -> bool {
+    let Some(selected) = state.context.get(state.selected_context).filter(|c| is_movable(c)) else { return false };
+    let selected_key = panel_group::panel_key(selected);
+    let mut movable: Vec<&Entry> = state.context.iter().filter(|c| is_movable(c)).collect();
+    movable.sort_by_key(|c| display_key(state, c));
+    let mut order: Vec<String> = movable.into_iter().map(panel_group::panel_key).collect();
+    let Some(pos) = order.iter().position(|k| *k == selected_key) else { return false };
+    let target = if up { pos.checked_sub(1) } else { pos.checked_add(1).filter(|t| *t < order.len()) };
+    let Some(neighbour) = target else { return false };
+    order.swap(pos, neighbour);
+    panel_group::groups_mut(state).order = order;
+    state.flags.ui.dirty = true;
+    true
+}
+
+/// Close panels `ids` through `Close_panel`. Returns whether any closed.
+pub(crate) fn close_panels(state: &mut State, ids: &[String]) -> bool {
+    if ids.is_empty() {
+        return false;
+    }
+    let before = state.context.len();
+    let _result = panel_group::dispatch(state, "Close_panel", serde_json::json!({ "ids": ids }));
+    state.flags.ui.dirty = true;
+    state.context.len() < before
+}
+
+/// Whether `ctx` shows something that is gone: a deleted file or a console
+/// whose process has finished.
+fn is_deprecated(ctx: &Entry) -> bool {
+    match ctx.context_type.as_str() {
+        Kind::FILE => ctx.get_meta_str("file_path").is_some_and(|p| !Path::new(p).exists()),
+        Kind::CONSOLE => ctx.get_meta_str("console_status").is_some_and(|s| !s.starts_with("running")),
+        _ => false,
+    }
+}
+
+/// IDs of the unpinned deprecated panels, in sidebar order.
+pub(crate) fn deprecated_ids(state: &State) -> Vec<String> {
+    let mut panels: Vec<&Entry> =
+        state.context.iter().filter(|c| is_movable(c) && is_deprecated(c) && !panel_group::is_pinned(c)).collect();
+    panels.sort_by_key(|c| display_key(state, c));
+    panels.into_iter().map(|c| c.id.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::context::make_default_entry;
+
+    fn state_with_panels() -> State {
+        crate::modules::init_registry();
+        let mut state = State::default();
+        state.context.push(make_default_entry("P1", Kind::new(Kind::TODO), "Todo", false));
+        for (id, uid) in [("P8", "UID_8_P"), ("P9", "UID_9_P"), ("P10", "UID_10_P")] {
+            let mut entry = make_default_entry(id, Kind::new(Kind::PASTED), id, false);
+            entry.uid = Some(uid.to_owned());
+            state.context.push(entry);
+        }
+        state
+    }
+
+    fn sidebar_ids(state: &State) -> Vec<&str> {
+        let mut sorted: Vec<&Entry> = state.context.iter().collect();
+        sorted.sort_by_key(|c| display_key(state, c));
+        sorted.into_iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn moving_reorders_sidebar_and_request() {
+        let mut state = state_with_panels();
+        state.selected_context = 3;
+        assert!(move_selected(&mut state, true));
+        assert!(move_selected(&mut state, true));
+        assert!(!move_selected(&mut state, true));
+        assert_eq!(sidebar_ids(&state), ["P1", "P10", "P8", "P9"]);
+        assert_eq!(request_rank(&state, "P10"), 0);
+        assert_eq!(request_rank(&state, "P1"), usize::MAX);
+    }
+
+    #[test]
+    fn fixed_panels_do_not_move() {
+        let mut state = state_with_panels();
+        state.selected_context = 0;
+        assert!(!move_selected(&mut state, false));
+        assert!(state.get_ext::<PanelGroups>().is_none());
+    }
+}
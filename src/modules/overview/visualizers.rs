@@ -27,11 +27,7 @@ pub(super) fn visualize_core_output(content: &str, width: usize) -> Vec<cp_rende
             } else {
                 Semantic::Default
             };
-            let display = if line.len() > width {
-                format!("{}...", line.get(..line.floor_char_boundary(width.saturating_sub(3))).unwrap_or(""))
-            } else {
-                line.to_owned()
-            };
+            let display = cp_base::ui::text::ellipsize(line, width, "...");
             Block::Line(vec![Span::styled(display, semantic)])
         })
         .collect()
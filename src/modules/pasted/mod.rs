@@ -0,0 +1,289 @@
+//! Pasted content — large pastes moved out of the message into their own panel.
+//!
+//! A paste of at least [`MIN_LINES`] lines still sitting in the input is
+//! offered for conversion (banner above the input, Ctrl+T). Converting creates
+//! a `pasted` panel holding the text under a generated label and swaps the
+//! paste placeholder for a reference to that panel, so the message stays short
+//! and the content can be closed or cleaned like any other panel.
+
+/// Pasted content panel rendering.
+mod panel;
+
+use crate::app::panels::Panel;
+use crate::infra::tools::{ToolDefinition, ToolResult, ToolUse};
+use crate::state::{Kind, State, TypeMeta};
+use cp_base::state::context::{compute_total_pages, estimate_tokens, make_default_entry};
+use cp_render::{Block, Semantic, Span as S};
+
+use self::panel::PastedPanel;
+use super::Module;
+
+/// Pastes with at least this many lines are offered for conversion.
+pub(crate) const MIN_LINES: usize = 40;
+/// Metadata key holding the panel's text (persists across reloads).
+const META_CONTENT: &str = "pasted_content";
+/// Characters of the first line kept in a generated label.
+const LABEL_CHARS: usize = 48;
+
+/// Index of the paste buffer offered for conversion: the most recent unlabeled
+/// paste of at least [`MIN_LINES`] lines whose placeholder is still in the input.
+pub(crate) fn offered_paste(state: &State) -> Option<usize> {
+    if !state.active_modules.contains("pasted") {
+        return None;
+    }
+    state.paste_buffers.iter().enumerate().rev().find_map(|(idx, buf)| {
+        let unlabeled = state.paste_buffer_labels.get(idx).is_none_or(Option::is_none);
+        let large = buf.lines().count() >= MIN_LINES;
+        (unlabeled && large && state.input.contains(&format!("\x00{idx}\x00"))).then_some(idx)
+    })
+}
+
+/// Slim banner above the input offering to move a large paste into a panel.
+pub(crate) fn offer_banner(state: &State) -> Option<Block> {
+    let idx = offered_paste(state)?;
+    let lines = state.paste_buffers.get(idx).map_or(0, |b| b.lines().count());
+    Some(Block::Line(vec![
+        S::muted(format!("\u{1f4cb} Paste #{} is {lines} lines \u{2014} ", idx.saturating_add(1))),
+        S::styled("Ctrl+T".into(), Semantic::KeyHint),
+        S::muted(" moves it into a panel".into()),
+    ]))
+}
+
+/// Panel label for `content` from `source` ("Pasted", "stdin"): its first
+/// non-empty line, shortened, plus its size.
+fn generate_label(source: &str, content: &str) -> String {
+    let first = content.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    let head: String = first.chars().take(LABEL_CHARS).collect();
+    let ellipsis = if first.chars().count() > LABEL_CHARS { "\u{2026}" } else { "" };
+    format!("{source}: {head}{ellipsis} ({} lines)", content.lines().count())
+}
+
+/// Create a `pasted` panel holding `content` under `label`. Returns its ID.
+fn create_panel(state: &mut State, label: &str, content: &str) -> String {
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+
+    let mut elem = make_default_entry(&panel_id, Kind::new(Kind::PASTED), label, false);
+    elem.uid = Some(uid);
+    elem.cached_content = Some(content.to_owned());
+    elem.full_token_count = estimate_tokens(content);
+    elem.total_pages = compute_total_pages(elem.full_token_count);
+    elem.token_count = elem.full_token_count;
+    elem.last_refresh_ms = crate::app::panels::now_ms();
+    elem.set_meta(META_CONTENT, &content);
+    state.context.push(elem);
+    panel_id
+}
+
+/// Move the offered paste into a new panel, replacing its placeholder in the
+/// input with a reference. Returns `false` when nothing was offered.
+pub(crate) fn convert_offered_paste(state: &mut State) -> bool {
+    let Some(idx) = offered_paste(state) else { return false };
+    let content = state.paste_buffers.get_mut(idx).map(std::mem::take).unwrap_or_default();
+    let label = generate_label("Pasted", &content);
+    let panel_id = create_panel(state, &label, &content);
+
+    let sentinel = format!("\x00{idx}\x00");
+    let reference = format!("[pasted content: see panel {panel_id} \u{201c}{label}\u{201d}]");
+    if let Some(pos) = state.input.find(&sentinel) {
+        state.input.replace_range(pos..pos.saturating_add(sentinel.len()), &reference);
+        if state.input_cursor > pos {
+            state.input_cursor = state.input_cursor.saturating_sub(sentinel.len()).saturating_add(reference.len());
+        }
+    }
+    state.input_selection_anchor = None;
+    state.flags.ui.dirty = true;
+    true
+}
+
+/// Put text piped into the TUI (`--attach-stdin`) into a new panel. Returns
+/// the reference to quote in the first message.
+pub(crate) fn attach_stdin(state: &mut State, content: &str) -> String {
+    let label = generate_label("stdin", content);
+    let panel_id = create_panel(state, &label, content);
+    format!("[piped input: see panel {panel_id} \u{201c}{label}\u{201d}]")
+}
+
+/// Module owning the `pasted` panels.
+pub(crate) struct PastedModule;
+
+impl Module for PastedModule {
+    fn id(&self) -> &'static str {
+        "pasted"
+    }
+    fn name(&self) -> &'static str {
+        "Pasted Content"
+    }
+    fn description(&self) -> &'static str {
+        "Move large pastes out of messages into panels"
+    }
+    fn is_core(&self) -> bool {
+        false
+    }
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![]
+    }
+
+    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        None
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::PASTED => Some(Box::new(PastedPanel)),
+            _ => None,
+        }
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &[]
+    }
+
+    fn init_state(&self, _state: &mut State) {}
+
+    fn reset_state(&self, _state: &mut State) {}
+
+    fn save_module_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    fn load_module_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+
+    fn save_worker_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+
+    fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<crate::infra::tools::Verdict> {
+        None
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::PASTED)]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![]
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: Kind::PASTED,
+            icon_id: "pasted",
+            is_fixed: false,
+            needs_cache: false,
+            fixed_order: None,
+            display_name: "pasted",
+            short_name: "paste",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn tool_visualizers(&self) -> Vec<(&'static str, super::ToolVisualizer)> {
+        vec![]
+    }
+
+    fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
+        None
+    }
+
+    fn context_detail(&self, ctx: &crate::state::Entry) -> Option<String> {
+        (ctx.context_type.as_str() == Kind::PASTED).then(|| ctx.name.clone())
+    }
+
+    fn overview_context_section(&self, _state: &State) -> Option<String> {
+        None
+    }
+
+    fn overview_render_sections(&self, _state: &State) -> Vec<(u8, Vec<Block>)> {
+        vec![]
+    }
+
+    fn on_close_context(&self, _ctx: &crate::state::Entry, _state: &mut State) -> Option<Result<String, String>> {
+        None
+    }
+
+    fn on_user_message(&self, _state: &mut State) {}
+
+    fn on_stream_stop(&self, _state: &mut State) {}
+
+    fn on_stream_chunk(&self, _text: &str, _state: &mut State) {}
+
+    fn on_tool_progress(&self, _tool_name: &str, _input_so_far: &str, _state: &mut State) {}
+
+    fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
+
+    fn watch_paths(&self, _state: &State) -> Vec<cp_base::panels::WatchSpec> {
+        vec![]
+    }
+
+    fn should_invalidate_on_fs_change(
+        &self,
+        _ctx: &crate::state::Entry,
+        _changed_path: &str,
+        _is_dir_event: bool,
+    ) -> bool {
+        false
+    }
+
+    fn watcher_immediate_refresh(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `n` numbered lines.
+    fn lines(n: usize) -> String {
+        (1..=n).map(|i| format!("line {i}")).collect::<Vec<_>>().join("\n")
+    }
+
+    /// A state with the module on and `pastes` placed in the input.
+    fn with_pastes(pastes: Vec<String>) -> State {
+        let mut state = State::default();
+        let _on = state.active_modules.insert("pasted".to_owned());
+        state.input = (0..pastes.len()).map(|i| format!("see \x00{i}\x00")).collect::<Vec<_>>().join(" ");
+        state.input_cursor = state.input.len();
+        state.paste_buffer_labels = vec![None; pastes.len()];
+        state.paste_buffers = pastes;
+        state
+    }
+
+    #[test]
+    fn only_large_pastes_still_in_the_input_are_offered() {
+        let below = MIN_LINES.saturating_sub(1);
+        assert_eq!(offered_paste(&with_pastes(vec![lines(below)])), None);
+        assert_eq!(offered_paste(&with_pastes(vec![lines(MIN_LINES), lines(below)])), Some(0));
+        let mut sent = with_pastes(vec![lines(MIN_LINES)]);
+        sent.input.clear();
+        assert_eq!(offered_paste(&sent), None);
+    }
+
+    #[test]
+    fn converting_moves_the_paste_into_a_panel() {
+        let mut state = with_pastes(vec![lines(MIN_LINES)]);
+        assert!(convert_offered_paste(&mut state));
+        let panel = state.context.iter().find(|c| c.context_type.as_str() == Kind::PASTED);
+        let label = format!("Pasted: line 1 ({MIN_LINES} lines)");
+        assert_eq!(panel.map(|p| (p.name.clone(), p.cached_content.clone())), Some((label, Some(lines(MIN_LINES)))));
+        assert!(state.input.starts_with("see [pasted content: see panel ") && !state.input.contains('\x00'));
+        assert_eq!(state.input_cursor, state.input.len());
+        assert!(!convert_offered_paste(&mut state));
+    }
+}
@@ -0,0 +1,78 @@
+use crossterm::event::KeyEvent;
+
+use crate::app::actions::Action;
+use crate::app::panels::{ContextItem, Panel};
+use crate::state::{Entry, Kind, State};
+
+use cp_base::panels::{paginate_content, scroll_key_action};
+use cp_render::{Block, Semantic};
+
+use super::META_CONTENT;
+
+/// Panel showing one pasted blob, stored in the entry's metadata.
+pub(super) struct PastedPanel;
+
+/// The pasted text of `ctx`.
+fn content_of(ctx: &Entry) -> &str {
+    ctx.metadata.get(META_CONTENT).and_then(serde_json::Value::as_str).unwrap_or("")
+}
+
+impl Panel for PastedPanel {
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<Block> {
+        let Some(ctx) = state.context.get(state.selected_context).filter(|c| c.context_type.as_str() == Kind::PASTED)
+        else {
+            return vec![Block::styled_text(" No pasted content panel".into(), Semantic::Muted)];
+        };
+        content_of(ctx).lines().map(|line| Block::text(format!(" {line}"))).collect()
+    }
+
+    fn title(&self, state: &State) -> String {
+        state.context.get(state.selected_context).map_or_else(|| "Pasted Content".to_owned(), |ctx| ctx.name.clone())
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::PASTED)
+            .map(|c| {
+                let output = paginate_content(content_of(c), c.current_page, c.total_pages, &c.page_descriptions);
+                ContextItem::new(&c.id, &c.name, output, c.last_refresh_ms)
+            })
+            .collect()
+    }
+
+    fn needs_cache(&self) -> bool {
+        false
+    }
+
+    fn refresh_cache(&self, _request: cp_base::panels::CacheRequest) -> Option<cp_base::panels::CacheUpdate> {
+        None
+    }
+
+    fn build_cache_request(&self, _ctx: &Entry, _state: &State) -> Option<cp_base::panels::CacheRequest> {
+        None
+    }
+
+    fn apply_cache_update(&self, _update: cp_base::panels::CacheUpdate, _ctx: &mut Entry, _state: &mut State) -> bool {
+        false
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        None
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
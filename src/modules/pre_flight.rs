@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use cp_base::tools::jail::Jail;
+
 use crate::infra::tools::{ToolParam, ToolUse, Verdict};
 use crate::state::State;
 
@@ -35,6 +37,9 @@ pub(crate) fn pre_flight_tool(tool: &ToolUse, state: &State, active_modules: &Ha
     }
     // If tool not found in definitions, skip schema check — dispatch will catch it
 
+    // Phase 1.5: Workspace jail — path parameters resolving outside the project root
+    result.errors.extend(state.get_ext::<Jail>().map(|jail| jail.violations(&tool.input)).unwrap_or_default());
+
     // While the history-cleanup trap is active, the only permitted tool is
     // `Close_conversation_history`. The threads module's focus enforcement
     // would otherwise reject that tool ("focus on a thread"), deadlocking the
@@ -57,6 +62,13 @@ pub(crate) fn pre_flight_tool(tool: &ToolUse, state: &State, active_modules: &Ha
         }
     }
 
+    // Phase 3: Edits to files owned or recently changed by others (git edit guard)
+    if active_modules.contains("git")
+        && let Some(ownership) = cp_mod_git::edit_verdict(tool, state)
+    {
+        result.merge(ownership);
+    }
+
     result
 }
 
@@ -0,0 +1,252 @@
+//! Recent module — the files opened or changed this session, newest first.
+//!
+//! Every successful `Open`, `Edit` and `Write` (the agent's, or the user's
+//! through an @-mention) moves the file to the top of a short list shown in
+//! the Recent panel with its last action and token size. After a clean-up
+//! closed the file panels, either side can reorient from it; with the panel
+//! selected, `1`..`9` reopens (or jumps to) the file on that line.
+
+/// Recent panel rendering.
+mod panel;
+/// The MRU list.
+pub(crate) mod types;
+
+use std::path::Path;
+
+use cp_render::Block;
+
+use crate::app::panels::Panel;
+use crate::infra::tools::{ToolDefinition, ToolResult, ToolUse};
+use crate::state::{Kind, State, TypeMeta};
+
+use self::panel::RecentPanel;
+use self::types::{RecentState, Touch, open_panel};
+use super::Module;
+
+/// Files a finished tool call touched, as given in its input.
+fn touched(tool: &ToolUse) -> Vec<(String, Touch)> {
+    let param = |name: &str| tool.input.get(name);
+    let one = |name: &str, touch: Touch| {
+        param(name).and_then(serde_json::Value::as_str).map(|p| (p.to_owned(), touch)).into_iter().collect()
+    };
+    match tool.name.as_str() {
+        "Open" => param("path").and_then(serde_json::Value::as_array).map_or_else(
+            || one("path", Touch::Opened),
+            |items| items.iter().filter_map(serde_json::Value::as_str).map(|p| (p.to_owned(), Touch::Opened)).collect(),
+        ),
+        "Edit" => one("file_path", Touch::Edited),
+        "Write" => one("file_path", Touch::Written),
+        _ => Vec::new(),
+    }
+}
+
+/// Record the files `tool` touched, keyed the way file panels are.
+fn record(tool: &ToolUse, result: &ToolResult, state: &mut State) {
+    if result.is_error {
+        return;
+    }
+    let files: Vec<(String, Touch)> = touched(tool)
+        .into_iter()
+        .filter(|file| Path::new(&file.0).is_file())
+        .map(|(path, touch)| {
+            let resolved = std::fs::canonicalize(&path).map_or(path, |p| p.to_string_lossy().into_owned());
+            (resolved, touch)
+        })
+        .collect();
+    if files.is_empty() {
+        return;
+    }
+    let now = crate::app::panels::now_ms();
+    let recent = RecentState::get_mut(state);
+    for (path, touch) in files {
+        recent.record(path, touch, now);
+    }
+    state.touch_panel(Kind::RECENT);
+}
+
+/// Panel index of the file on line `slot` (1-based), opening it first when
+/// its panel was closed. `None` past the end of the list.
+pub(crate) fn reopen(state: &mut State, slot: u8) -> Option<usize> {
+    let index = usize::from(slot).checked_sub(1)?;
+    let path = state.get_ext::<RecentState>()?.files.get(index)?.path.clone();
+    if open_panel(state, &path).is_none() {
+        let tool = ToolUse::new("recent-reopen".to_owned(), "Open".to_owned(), serde_json::json!({ "path": path }));
+        let _r = crate::infra::tools::execute_tool(&tool, state);
+    }
+    open_panel(state, &path).map(|(idx, _)| idx)
+}
+
+/// Module owning the Recent panel and its file list.
+pub(crate) struct RecentModule;
+
+impl Module for RecentModule {
+    fn id(&self) -> &'static str {
+        "recent"
+    }
+    fn name(&self) -> &'static str {
+        "Recent"
+    }
+    fn description(&self) -> &'static str {
+        "Recently opened and edited files, with one-key reopen"
+    }
+    fn is_core(&self) -> bool {
+        false
+    }
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![]
+    }
+
+    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        None
+    }
+
+    fn on_tool_executed(&self, tool: &ToolUse, result: &ToolResult, state: &mut State) {
+        record(tool, result, state);
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::RECENT => Some(Box::new(RecentPanel)),
+            _ => None,
+        }
+    }
+
+    fn dependencies(&self) -> &[&'static str] {
+        &[]
+    }
+
+    fn init_state(&self, state: &mut State) {
+        state.set_ext(RecentState::default());
+    }
+
+    fn reset_state(&self, state: &mut State) {
+        state.set_ext(RecentState::default());
+    }
+
+    fn save_module_data(&self, state: &State) -> serde_json::Value {
+        serde_json::json!({ "files": RecentState::get(state).files })
+    }
+
+    fn load_module_data(&self, data: &serde_json::Value, state: &mut State) {
+        if let Some(files) = data.get("files")
+            && let Ok(v) = serde_json::from_value(files.clone())
+        {
+            RecentState::get_mut(state).files = v;
+        }
+    }
+
+    fn save_worker_data(&self, _state: &State) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    fn load_worker_data(&self, _data: &serde_json::Value, _state: &mut State) {}
+
+    fn pre_flight(&self, _tool: &ToolUse, _state: &State) -> Option<crate::infra::tools::Verdict> {
+        None
+    }
+
+    fn fixed_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::RECENT)]
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![]
+    }
+
+    fn fixed_panel_defaults(&self) -> Vec<(Kind, &'static str, bool)> {
+        vec![(Kind::new(Kind::RECENT), "Recent", false)]
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: Kind::RECENT,
+            icon_id: "recent",
+            is_fixed: true,
+            needs_cache: false,
+            fixed_order: Some(13),
+            display_name: "recent",
+            short_name: "recent",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn tool_visualizers(&self) -> Vec<(&'static str, super::ToolVisualizer)> {
+        vec![]
+    }
+
+    fn context_display_name(&self, _context_type: &str) -> Option<&'static str> {
+        None
+    }
+
+    fn context_detail(&self, _ctx: &crate::state::Entry) -> Option<String> {
+        None
+    }
+
+    fn overview_context_section(&self, _state: &State) -> Option<String> {
+        None
+    }
+
+    fn overview_render_sections(&self, _state: &State) -> Vec<(u8, Vec<Block>)> {
+        vec![]
+    }
+
+    fn on_close_context(&self, _ctx: &crate::state::Entry, _state: &mut State) -> Option<Result<String, String>> {
+        None
+    }
+
+    fn on_user_message(&self, _state: &mut State) {}
+
+    fn on_stream_stop(&self, _state: &mut State) {}
+
+    fn on_stream_chunk(&self, _text: &str, _state: &mut State) {}
+
+    fn on_tool_progress(&self, _tool_name: &str, _input_so_far: &str, _state: &mut State) {}
+
+    fn on_tool_complete(&self, _tool_name: &str, _state: &mut State) {}
+
+    fn watch_paths(&self, _state: &State) -> Vec<cp_base::panels::WatchSpec> {
+        vec![]
+    }
+
+    fn should_invalidate_on_fs_change(
+        &self,
+        _ctx: &crate::state::Entry,
+        _changed_path: &str,
+        _is_dir_event: bool,
+    ) -> bool {
+        false
+    }
+
+    fn watcher_immediate_refresh(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_edit_and_write_inputs_name_their_files() {
+        let call = |name: &str, input| ToolUse::new("t".to_owned(), name.to_owned(), input);
+        let opened = touched(&call("Open", serde_json::json!({ "path": ["a.rs", "b.rs"] })));
+        assert_eq!(opened, vec![("a.rs".to_owned(), Touch::Opened), ("b.rs".to_owned(), Touch::Opened)]);
+        assert_eq!(
+            touched(&call("Open", serde_json::json!({ "path": "c.rs" }))),
+            vec![("c.rs".to_owned(), Touch::Opened)]
+        );
+        assert_eq!(
+            touched(&call("Write", serde_json::json!({ "file_path": "d.rs" }))),
+            vec![("d.rs".to_owned(), Touch::Written)]
+        );
+        assert!(touched(&call("Close_panel", serde_json::json!({ "ids": ["P9"] }))).is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Write as _;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::app::actions::Action;
+use crate::app::panels::{ContextItem, Panel};
+use crate::state::{Entry, Kind, State, estimate_tokens};
+
+use cp_base::panels::{CacheRequest, CacheUpdate, scroll_key_action};
+use cp_render::{Block, Semantic, Span as S};
+
+use super::types::{RecentFile, RecentState, display_path, open_panel};
+
+/// Panel listing the recently opened and edited files.
+pub(super) struct RecentPanel;
+
+/// `P7` when the file has an open panel, else `closed`.
+fn where_shown(state: &State, file: &RecentFile) -> String {
+    open_panel(state, &file.path).map_or_else(|| "closed".to_owned(), |(_, ctx)| ctx.id.clone())
+}
+
+/// One numbered line of the list.
+fn file_line(state: &State, line: usize, file: &RecentFile, now: u64) -> Block {
+    let key = if line <= 9 { format!(" {line} ") } else { "   ".to_owned() };
+    Block::Line(vec![
+        S::styled(key, Semantic::KeyHint),
+        S::accent(display_path(&file.path)).bold(),
+        S::muted(format!("  {} \u{b7} ", file.touch.label())),
+        S::info(format!("{} tok", crate::ui::helpers::format_number(file.tokens))),
+        S::muted(format!(
+            " \u{b7} {} \u{b7} {}",
+            where_shown(state, file),
+            crate::ui::helpers::format_time_ago(now.saturating_sub(file.at_ms))
+        )),
+    ])
+}
+
+impl RecentPanel {
+    /// Compact LLM-facing list: path, last action, size, panel.
+    fn format_context_text(state: &State) -> String {
+        let files = &RecentState::get(state).files;
+        if files.is_empty() {
+            return "No files opened or edited yet.\n".to_owned();
+        }
+        let mut text = "Recently opened/edited files, newest first:\n".to_owned();
+        for file in files {
+            let _r = writeln!(
+                text,
+                "- {} ({}, {} tokens, {})",
+                display_path(&file.path),
+                file.touch.label(),
+                file.tokens,
+                where_shown(state, file)
+            );
+        }
+        text
+    }
+}
+
+impl Panel for RecentPanel {
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        if let KeyCode::Char(digit @ '1'..='9') = key.code
+            && key.modifiers.is_empty()
+        {
+            return Some(Action::ReopenRecent(digit.to_digit(10).map_or(0, cp_base::cast::Safe::to_u8)));
+        }
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<Block> {
+        let files = &RecentState::get(state).files;
+        if files.is_empty() {
+            return vec![Block::Line(vec![S::muted("  No files opened or edited yet.".into()).italic()])];
+        }
+        let now = crate::app::panels::now_ms();
+        let mut blocks: Vec<Block> =
+            files.iter().enumerate().map(|(i, file)| file_line(state, i.saturating_add(1), file, now)).collect();
+        blocks.push(Block::empty());
+        blocks.push(Block::Line(vec![S::muted("  Reopen: ".into()), S::styled("1..9".into(), Semantic::KeyHint)]));
+        blocks
+    }
+
+    fn title(&self, state: &State) -> String {
+        match RecentState::get(state).files.len() {
+            0 => "Recent".to_owned(),
+            n => format!("Recent ({n})"),
+        }
+    }
+
+    fn refresh(&self, state: &mut State) {
+        let live: Vec<Option<usize>> = RecentState::get(state)
+            .files
+            .iter()
+            .map(|f| open_panel(state, &f.path).map(|(_, ctx)| ctx.token_count))
+            .collect();
+        for (file, tokens) in RecentState::get_mut(state).files.iter_mut().zip(live) {
+            if let Some(count) = tokens {
+                file.tokens = count;
+            }
+        }
+        let content = Self::format_context_text(state);
+        let token_count = estimate_tokens(&content);
+        if let Some(ctx) = state.context.iter_mut().find(|c| c.context_type.as_str() == Kind::RECENT) {
+            ctx.token_count = token_count;
+            let _changed = cp_base::panels::update_if_changed(ctx, &content);
+        }
+    }
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        let Some(ctx) = state.context.iter().find(|c| c.context_type.as_str() == Kind::RECENT) else {
+            return Vec::new();
+        };
+        vec![ContextItem::new(&ctx.id, "Recent", Self::format_context_text(state), ctx.last_refresh_ms)]
+    }
+
+    fn needs_cache(&self) -> bool {
+        false
+    }
+
+    fn refresh_cache(&self, _request: CacheRequest) -> Option<CacheUpdate> {
+        None
+    }
+
+    fn build_cache_request(&self, _ctx: &Entry, _state: &State) -> Option<CacheRequest> {
+        None
+    }
+
+    fn apply_cache_update(&self, _update: CacheUpdate, _ctx: &mut Entry, _state: &mut State) -> bool {
+        false
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        None
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
@@ -0,0 +1,108 @@
+//! The most-recently-used file list behind the Recent panel.
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Entry, Kind, State};
+
+/// Files kept, newest first (oldest dropped).
+pub(super) const MAX_FILES: usize = 20;
+
+/// Last thing that happened to a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Touch {
+    /// Opened as a panel.
+    Opened,
+    /// Changed with `Edit`.
+    Edited,
+    /// Written whole with `Write`.
+    Written,
+}
+
+impl Touch {
+    /// Lowercase label for the panel and the LLM context.
+    pub(super) const fn label(self) -> &'static str {
+        match self {
+            Self::Opened => "opened",
+            Self::Edited => "edited",
+            Self::Written => "written",
+        }
+    }
+}
+
+/// One file of the list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RecentFile {
+    /// Path as file panels key it (resolved), so open panels can be found.
+    pub path: String,
+    /// Last action on it.
+    pub touch: Touch,
+    /// When that happened (ms).
+    pub at_ms: u64,
+    /// Tokens of its panel when last seen open.
+    pub tokens: usize,
+}
+
+/// Files opened or changed this session, newest first.
+#[derive(Debug, Default)]
+pub(crate) struct RecentState {
+    /// The list, deduplicated by path.
+    pub files: Vec<RecentFile>,
+}
+
+impl RecentState {
+    /// Get shared ref from State's `TypeMap`.
+    pub(crate) fn get(state: &State) -> &Self {
+        state.ext::<Self>()
+    }
+
+    /// Get mutable ref from State's `TypeMap`.
+    pub(crate) fn get_mut(state: &mut State) -> &mut Self {
+        state.ext_mut::<Self>()
+    }
+
+    /// Move `path` to the front with `touch`, keeping its known token count.
+    pub(super) fn record(&mut self, path: String, touch: Touch, at_ms: u64) {
+        let tokens = self.files.iter().find(|f| f.path == path).map_or(0, |f| f.tokens);
+        self.files.retain(|f| f.path != path);
+        self.files.insert(0, RecentFile { path, touch, at_ms, tokens });
+        self.files.truncate(MAX_FILES);
+    }
+}
+
+/// The open file panel showing `path`, with its index.
+pub(crate) fn open_panel<'state>(state: &'state State, path: &str) -> Option<(usize, &'state Entry)> {
+    state
+        .context
+        .iter()
+        .enumerate()
+        .find(|&(_, c)| c.context_type.as_str() == Kind::FILE && c.get_meta_str("file_path") == Some(path))
+}
+
+/// `path` relative to the working directory when it lies below it.
+pub(super) fn display_path(path: &str) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| std::path::Path::new(path).strip_prefix(cwd).ok().map(|p| p.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| path.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn touching_a_file_moves_it_to_the_front_once() {
+        let mut recent = RecentState::default();
+        for n in 0..25u64 {
+            recent.record(format!("f{n}.rs"), Touch::Opened, n);
+        }
+        assert_eq!(recent.files.len(), MAX_FILES);
+        recent.files.iter_mut().for_each(|f| f.tokens = 7);
+        recent.record("f10.rs".to_owned(), Touch::Edited, 99);
+        let first = recent.files.first();
+        assert_eq!(first.map(|f| (f.path.as_str(), f.touch, f.tokens)), Some(("f10.rs", Touch::Edited, 7)));
+        assert_eq!(recent.files.iter().filter(|f| f.path == "f10.rs").count(), 1);
+        assert_eq!(recent.files.len(), MAX_FILES);
+    }
+}
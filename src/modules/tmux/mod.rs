@@ -0,0 +1,102 @@
+//! Tmux panes — sibling terminal panes mirrored as panels.
+//!
+//! `tui attach` registers the other panes of its tmux window (the editor, a
+//! shell, a test watcher) as `tmux` panels. Each panel shows the visible
+//! screen plus some scrollback of its pane, captured on a cache worker, so
+//! the agent sees what the user sees next to it.
+
+/// Tmux pane panel rendering.
+mod panel;
+
+use crate::app::panels::Panel;
+use crate::infra::tools::{ToolDefinition, ToolResult, ToolUse};
+use crate::state::{Kind, State, TypeMeta};
+use cp_base::state::context::make_default_entry;
+
+use self::panel::TmuxPanel;
+use super::Module;
+
+/// Metadata key holding the tmux pane ID (`%3`).
+pub(crate) const META_PANE_ID: &str = "tmux_pane_id";
+
+/// Register the tmux pane `pane` as a panel named `label`, unless one
+/// already shows it. Returns the panel ID.
+pub(crate) fn add_pane_panel(state: &mut State, pane: &str, label: &str) -> String {
+    if let Some(ctx) = state.context.iter().find(|c| c.get_meta_str(META_PANE_ID) == Some(pane)) {
+        return ctx.id.clone();
+    }
+    let panel_id = state.next_available_context_id();
+    let uid = format!("UID_{}_P", state.global_next_uid);
+    state.global_next_uid = state.global_next_uid.saturating_add(1);
+
+    let mut elem = make_default_entry(&panel_id, Kind::new(Kind::TMUX), label, true);
+    elem.uid = Some(uid);
+    elem.set_meta(META_PANE_ID, &pane);
+    state.context.push(elem);
+    panel_id
+}
+
+/// Module owning the `tmux` panels.
+pub(crate) struct TmuxModule;
+
+impl Module for TmuxModule {
+    fn id(&self) -> &'static str {
+        "tmux"
+    }
+    fn name(&self) -> &'static str {
+        "Tmux Panes"
+    }
+    fn description(&self) -> &'static str {
+        "Mirror sibling tmux panes as panels (tui attach)"
+    }
+    fn is_core(&self) -> bool {
+        false
+    }
+    fn is_global(&self) -> bool {
+        false
+    }
+
+    fn tool_category_descriptions(&self) -> Vec<(&'static str, &'static str)> {
+        vec![]
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        vec![]
+    }
+
+    fn execute_tool(&self, _tool: &ToolUse, _state: &mut State) -> Option<ToolResult> {
+        None
+    }
+
+    fn create_panel(&self, context_type: &Kind) -> Option<Box<dyn Panel>> {
+        match context_type.as_str() {
+            Kind::TMUX => Some(Box::new(TmuxPanel)),
+            _ => None,
+        }
+    }
+
+    fn dynamic_panel_types(&self) -> Vec<Kind> {
+        vec![Kind::new(Kind::TMUX)]
+    }
+
+    fn context_type_metadata(&self) -> Vec<TypeMeta> {
+        vec![TypeMeta {
+            context_type: Kind::TMUX,
+            icon_id: "tmux",
+            is_fixed: false,
+            needs_cache: true,
+            fixed_order: None,
+            display_name: "tmux",
+            short_name: "pane",
+            needs_async_wait: false,
+        }]
+    }
+
+    fn context_detail(&self, ctx: &crate::state::Entry) -> Option<String> {
+        (ctx.context_type.as_str() == Kind::TMUX).then(|| ctx.name.clone())
+    }
+
+    fn init_state(&self, _state: &mut State) {}
+
+    fn reset_state(&self, _state: &mut State) {}
+}
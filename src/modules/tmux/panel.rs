@@ -0,0 +1,124 @@
+use std::process::Command;
+
+use crossterm::event::KeyEvent;
+
+use crate::app::actions::Action;
+use crate::app::panels::{ContextItem, Panel};
+use crate::state::{Entry, Kind, State};
+
+use cp_base::modules::run_with_timeout;
+use cp_base::panels::{CacheRequest, CacheUpdate, paginate_content, scroll_key_action, update_if_changed};
+use cp_base::state::context::{compute_total_pages, estimate_tokens};
+use cp_render::{Block, Semantic, Span as S};
+
+use super::META_PANE_ID;
+
+/// How often a pane is captured again.
+const REFRESH_MS: u64 = 2_000;
+
+/// Scrollback lines captured above the visible screen.
+const SCROLLBACK_LINES: u32 = 200;
+
+/// Seconds one `tmux capture-pane` may take.
+const CAPTURE_TIMEOUT_SECS: u64 = 2;
+
+/// Cache request of a tmux panel.
+struct CaptureRequest {
+    /// Panel to fill.
+    context_id: String,
+    /// Pane to capture.
+    pane_id: String,
+}
+
+/// The pane's screen and scrollback, trailing blank lines dropped.
+fn capture(pane_id: &str) -> String {
+    let start = format!("-{SCROLLBACK_LINES}");
+    let mut cmd = Command::new("tmux");
+    let _c = cmd.args(["capture-pane", "-p", "-J", "-t", pane_id, "-S", &start]);
+    match run_with_timeout(cmd, CAPTURE_TIMEOUT_SECS) {
+        Ok(out) if out.status.success() => format!("{}\n", String::from_utf8_lossy(&out.stdout).trim_end()),
+        Ok(_) | Err(_) => format!("[tmux pane {pane_id} is gone]\n"),
+    }
+}
+
+/// Panel mirroring one tmux pane.
+pub(super) struct TmuxPanel;
+
+impl Panel for TmuxPanel {
+    fn needs_cache(&self) -> bool {
+        true
+    }
+
+    fn build_cache_request(&self, ctx: &Entry, _state: &State) -> Option<CacheRequest> {
+        let pane_id = ctx.get_meta_str(META_PANE_ID)?.to_owned();
+        Some(CacheRequest::new(Kind::new(Kind::TMUX), Box::new(CaptureRequest { context_id: ctx.id.clone(), pane_id })))
+    }
+
+    fn refresh_cache(&self, request: CacheRequest) -> Option<CacheUpdate> {
+        let req = request.data.downcast::<CaptureRequest>().ok()?;
+        let content = capture(&req.pane_id);
+        let token_count = estimate_tokens(&content);
+        Some(CacheUpdate::Content { context_id: req.context_id, content, token_count })
+    }
+
+    fn apply_cache_update(&self, update: CacheUpdate, ctx: &mut Entry, _state: &mut State) -> bool {
+        // Only a Content update carries new bytes; Unchanged/ModuleSpecific are no-ops.
+        if let CacheUpdate::Content { content, token_count, .. } = update {
+            ctx.full_token_count = token_count;
+            ctx.total_pages = compute_total_pages(token_count);
+            ctx.token_count = token_count;
+            ctx.cache_deprecated = false;
+            let changed = update_if_changed(ctx, &content);
+            ctx.cached_content = Some(content);
+            changed
+        } else {
+            false
+        }
+    }
+
+    fn cache_refresh_interval_ms(&self) -> Option<u64> {
+        Some(REFRESH_MS)
+    }
+
+    fn handle_key(&self, key: &KeyEvent, _state: &State) -> Option<Action> {
+        scroll_key_action(key)
+    }
+
+    fn blocks(&self, state: &State) -> Vec<Block> {
+        let Some(ctx) = state.context.get(state.selected_context).filter(|c| c.context_type.as_str() == Kind::TMUX)
+        else {
+            return vec![Block::styled_text(" No tmux pane panel".into(), Semantic::Muted)];
+        };
+        let Some(content) = ctx.cached_content.as_ref() else {
+            return vec![Block::Line(vec![S::muted(" Loading...".into()).italic()])];
+        };
+        content.lines().map(|line| Block::text(format!(" {line}"))).collect()
+    }
+
+    fn title(&self, state: &State) -> String {
+        state.context.get(state.selected_context).map_or_else(|| "Tmux Pane".to_owned(), |ctx| ctx.name.clone())
+    }
+
+    fn refresh(&self, _state: &mut State) {}
+
+    fn context(&self, state: &State) -> Vec<ContextItem> {
+        state
+            .context
+            .iter()
+            .filter(|c| c.context_type.as_str() == Kind::TMUX)
+            .filter_map(|c| {
+                let content = c.cached_content.as_ref()?;
+                let output = paginate_content(content, c.current_page, c.total_pages, &c.page_descriptions);
+                Some(ContextItem::new(&c.id, &c.name, output, c.last_refresh_ms))
+            })
+            .collect()
+    }
+
+    fn max_freezes(&self) -> u8 {
+        0
+    }
+
+    fn suicide(&self, _ctx: &Entry, _state: &State) -> bool {
+        false
+    }
+}
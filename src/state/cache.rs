@@ -6,23 +6,29 @@
 use std::sync::mpsc::{self, Sender};
 use std::thread;
 
+use cp_base::state::runtime::bus::{self, Priority};
+
 // Re-export shared cache types from cp-base
 pub(crate) use cp_base::panels::{CacheRequest, CacheUpdate, hash_content};
 
+use crate::app::panels::{get_panel, paginate_content};
+use crate::state::{Entry, State, estimate_tokens};
+
 /// Maximum concurrent cache worker threads
 const CACHE_POOL_SIZE: usize = 6;
 
 /// Bounded thread pool for cache operations.
-/// Workers pull (`CacheRequest`, Sender<CacheUpdate>) pairs from a shared channel.
+/// Workers pull `CacheRequest`s from a shared channel and publish the resulting
+/// `CacheUpdate`s on the event bus, waiting while the main loop is behind.
 pub(crate) struct CachePool {
     /// Sender half of the job channel feeding worker threads.
-    job_tx: Sender<(CacheRequest, Sender<CacheUpdate>)>,
+    job_tx: Sender<CacheRequest>,
 }
 
 impl CachePool {
     /// Create a new pool with `CACHE_POOL_SIZE` worker threads.
     pub(crate) fn new() -> Self {
-        let (job_tx, job_rx_raw) = mpsc::channel::<(CacheRequest, Sender<CacheUpdate>)>();
+        let (job_tx, job_rx_raw) = mpsc::channel::<CacheRequest>();
         let job_rx = std::sync::Arc::new(std::sync::Mutex::new(job_rx_raw));
 
         for i in 0..CACHE_POOL_SIZE {
@@ -36,12 +42,12 @@ impl CachePool {
                             lock.recv()
                         };
                         match job {
-                            Ok((request, tx)) => {
+                            Ok(request) => {
                                 let context_type = request.context_type.clone();
                                 if let Some(panel) = crate::modules::create_panel(&context_type)
                                     && let Some(update) = panel.refresh_cache(request)
                                 {
-                                    let _r = tx.send(update);
+                                    bus::publish_wait(update, Priority::Normal);
                                 }
                             }
                             Err(_) => break, // Channel closed, pool shutting down
@@ -55,15 +61,81 @@ impl CachePool {
     }
 
     /// Submit a cache request to the pool.
-    pub(crate) fn submit(&self, request: CacheRequest, tx: Sender<CacheUpdate>) {
-        let _r = self.job_tx.send((request, tx));
+    pub(crate) fn submit(&self, request: CacheRequest) {
+        let _r = self.job_tx.send(request);
     }
 }
 
 /// Global cache pool instance
 static CACHE_POOL: std::sync::LazyLock<CachePool> = std::sync::LazyLock::new(CachePool::new);
 
-/// Process a cache request in the background via the bounded thread pool.
-pub(crate) fn process_cache_request(request: CacheRequest, tx: Sender<CacheUpdate>) {
-    CACHE_POOL.submit(request, tx);
+/// Process a cache request in the background via the bounded thread pool; the
+/// update arrives on the event bus.
+pub(crate) fn process_cache_request(request: CacheRequest) {
+    CACHE_POOL.submit(request);
+}
+
+/// Load every panel whose content was left unloaded (evicted by the cache
+/// budget, or seeding deferred at startup), before a request is assembled.
+/// The fetches run on up to `CACHE_POOL_SIZE` threads at once; the updates
+/// are applied here, in place, keeping the page each panel was on.
+pub(crate) fn load_unloaded(state: &mut State) {
+    let requests: Vec<(String, CacheRequest)> = state
+        .context
+        .iter()
+        .filter(|c| c.awaits_load())
+        .filter_map(|c| get_panel(&c.context_type).build_cache_request(c, state).map(|req| (c.id.clone(), req)))
+        .collect();
+    if requests.is_empty() {
+        return;
+    }
+    let _guard = crate::profile!("cache::load_unloaded");
+    for (id, update) in fetch_parallel(requests) {
+        let Some(idx) = state.context.iter().position(|c| c.id == id) else { continue };
+        let mut ctx = state.context.remove(idx);
+        apply_loaded(&mut ctx, update, state);
+        state.context.insert(idx, ctx);
+    }
+}
+
+/// Run `requests` on a few scoped threads; returns the updates by panel ID.
+fn fetch_parallel(requests: Vec<(String, CacheRequest)>) -> Vec<(String, CacheUpdate)> {
+    let workers = requests.len().min(CACHE_POOL_SIZE);
+    let queue = std::sync::Mutex::new(requests.into_iter());
+    let next = || queue.lock().unwrap_or_else(std::sync::PoisonError::into_inner).next();
+    let work = || {
+        let mut done = Vec::new();
+        while let Some((id, request)) = next() {
+            let context_type = request.context_type.clone();
+            if let Some(update) =
+                crate::modules::create_panel(&context_type).and_then(|panel| panel.refresh_cache(request))
+            {
+                done.push((id, update));
+            }
+        }
+        done
+    };
+    thread::scope(|scope| {
+        let mut handles = Vec::with_capacity(workers);
+        for _ in 0..workers {
+            handles.push(scope.spawn(work));
+        }
+        handles.into_iter().filter_map(|handle| handle.join().ok()).flatten().collect()
+    })
+}
+
+/// Apply a freshly fetched `update` to `ctx`, keeping its page.
+fn apply_loaded(ctx: &mut Entry, update: CacheUpdate, state: &mut State) {
+    let page = ctx.current_page;
+    let _changed = get_panel(&ctx.context_type).apply_cache_update(update, ctx, state);
+    if ctx.cached_content.is_none() {
+        return;
+    }
+    ctx.cache_unloaded = None;
+    if page > 0 && page < ctx.total_pages {
+        ctx.current_page = page;
+        let content = ctx.cached_content.as_deref().unwrap_or_default();
+        ctx.token_count =
+            estimate_tokens(&paginate_content(content, ctx.current_page, ctx.total_pages, &ctx.page_descriptions));
+    }
 }
@@ -0,0 +1,290 @@
+//! System prompt experiments: two seed prompts, alternated across sessions.
+//!
+//! `tui experiment start NAME AGENT_A AGENT_B` writes the experiment to
+//! `.context-pilot/experiment.json`. Each session then runs on one of the two
+//! agents — the one whose turn it is, counting the sessions already recorded —
+//! and on exit appends its outcome to `.context-pilot/analytics/experiments.jsonl`:
+//! tasks given, model turns, edits reverted and cost. `tui experiment report`
+//! compares the two arms. A session in which no task was given records nothing,
+//! so the next one runs on the same agent.
+//!
+//! An edit counts as reverted when a later `Edit` of the same file swaps its
+//! old and new text back; reverts done outside the agent are not seen.
+
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use cp_base::cast::{Safe as _, float_math};
+use cp_mod_prompt::types::{PromptState, PromptType};
+
+use crate::infra::constants::STORE_DIR;
+use crate::state::{Message, MsgKind, State};
+
+/// The running experiment, under the store directory.
+const EXPERIMENT_FILE: &str = "experiment.json";
+/// Session outcomes, one JSON line per session, under the store directory.
+const RESULTS_FILE: &str = "analytics/experiments.jsonl";
+
+/// An experiment: two agents (seed prompts) to compare.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Experiment {
+    /// Name the outcomes are filed under.
+    pub name: String,
+    /// The two agent IDs, alternated across sessions.
+    pub arms: [String; 2],
+}
+
+/// How one session went.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Outcome {
+    /// Experiment name.
+    pub experiment: String,
+    /// Agent the session ran on.
+    pub agent: String,
+    /// Session start (RFC 3339).
+    pub started: String,
+    /// Session end (RFC 3339).
+    pub ended: String,
+    /// User messages (tasks and follow-ups).
+    pub tasks: u32,
+    /// Model responses.
+    pub turns: u32,
+    /// Edits swapped back by a later edit.
+    pub edits_reverted: u32,
+    /// Spend (USD).
+    pub cost_usd: f64,
+}
+
+/// What the session looked like when it started.
+#[derive(Debug)]
+struct Baseline {
+    /// Experiment name.
+    experiment: String,
+    /// Agent this session runs on.
+    agent: String,
+    /// Start time (RFC 3339).
+    started: String,
+    /// Messages loaded from the previous session.
+    messages: usize,
+    /// Spend carried over from the previous session.
+    cost_usd: f64,
+}
+
+/// This session's arm, set by [`begin`].
+static BASELINE: OnceLock<Baseline> = OnceLock::new();
+
+/// Path of a file under the store directory.
+fn store_path(name: &str) -> PathBuf {
+    PathBuf::from(STORE_DIR).join(name)
+}
+
+/// The running experiment, if any.
+pub(crate) fn load() -> Option<Experiment> {
+    let text = std::fs::read_to_string(store_path(EXPERIMENT_FILE)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Start `experiment` (replacing any running one).
+pub(crate) fn save(experiment: &Experiment) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(experiment).map_err(std::io::Error::other)?;
+    std::fs::create_dir_all(STORE_DIR)?;
+    std::fs::write(store_path(EXPERIMENT_FILE), json)
+}
+
+/// Stop the running experiment; its outcomes are kept.
+pub(crate) fn stop() -> std::io::Result<()> {
+    std::fs::remove_file(store_path(EXPERIMENT_FILE))
+}
+
+/// Every recorded outcome of experiment `name`.
+pub(crate) fn outcomes(name: &str) -> Vec<Outcome> {
+    let text = std::fs::read_to_string(store_path(RESULTS_FILE)).unwrap_or_default();
+    text.lines()
+        .filter_map(|line| serde_json::from_str::<Outcome>(line).ok())
+        .filter(|outcome| outcome.experiment == name)
+        .collect()
+}
+
+/// The agent whose turn it is, after `recorded` sessions.
+fn arm_for(experiment: &Experiment, recorded: usize) -> &str {
+    experiment.arms.get(recorded.checked_rem(2).unwrap_or(0)).map_or("", String::as_str)
+}
+
+/// At startup: switch to this session's agent and note the baseline. Does
+/// nothing without a running experiment or when the agent no longer exists.
+pub(crate) fn begin(state: &mut State) {
+    let Some(experiment) = load() else { return };
+    let agent = arm_for(&experiment, outcomes(&experiment.name).len()).to_owned();
+    if !cp_mod_prompt::storage::load_prompts_for(PromptType::Agent).iter().any(|a| a.id == agent) {
+        return;
+    }
+    PromptState::get_mut(state).active_agent_id = Some(agent.clone());
+    let _set = BASELINE.set(Baseline {
+        experiment: experiment.name,
+        agent,
+        started: cp_mod_utilities::time::now_utc_rfc3339_secs(),
+        messages: state.messages.len(),
+        cost_usd: spent(state),
+    });
+}
+
+/// Spend so far (USD).
+const fn spent(state: &State) -> f64 {
+    float_math::sum3(state.cost_hit_usd, state.cost_miss_usd, state.cost_output_usd)
+}
+
+/// Model responses in `messages`: each run of assistant messages is one.
+fn count_turns(messages: &[Message]) -> u32 {
+    let mut previous_assistant = false;
+    let mut turns = 0u32;
+    for msg in messages {
+        let assistant = msg.role == "assistant";
+        if assistant && !previous_assistant {
+            turns = turns.saturating_add(1);
+        }
+        previous_assistant = assistant;
+    }
+    turns
+}
+
+/// Edits in `messages` that swap an earlier edit of the same file back.
+fn count_reverted_edits(messages: &[Message]) -> u32 {
+    let text = |input: &serde_json::Value, key: &str| input.get(key).and_then(|v| v.as_str()).unwrap_or("").to_owned();
+    let mut applied: HashMap<(String, String, String), u32> = HashMap::new();
+    let mut reverted = 0u32;
+    for tool in messages.iter().flat_map(|m| &m.tool_uses).filter(|t| t.name == "Edit") {
+        let (path, old, new) =
+            (text(&tool.input, "file_path"), text(&tool.input, "old_string"), text(&tool.input, "new_string"));
+        if let Some(count) = applied.get_mut(&(path.clone(), new.clone(), old.clone()))
+            && *count > 0
+        {
+            *count = count.saturating_sub(1);
+            reverted = reverted.saturating_add(1);
+            continue;
+        }
+        let count = applied.entry((path, old, new)).or_insert(0);
+        *count = count.saturating_add(1);
+    }
+    reverted
+}
+
+/// The outcome of the session so far, `None` outside an experiment.
+fn outcome(state: &State) -> Option<Outcome> {
+    let baseline = BASELINE.get()?;
+    let session = state.messages.get(baseline.messages..).unwrap_or_default();
+    let tasks = session.iter().filter(|m| m.role == "user" && m.msg_type == MsgKind::TextMessage).count();
+    Some(Outcome {
+        experiment: baseline.experiment.clone(),
+        agent: baseline.agent.clone(),
+        started: baseline.started.clone(),
+        ended: cp_mod_utilities::time::now_utc_rfc3339_secs(),
+        tasks: tasks.to_u32(),
+        turns: count_turns(session),
+        edits_reverted: count_reverted_edits(session),
+        cost_usd: float_math::sub(spent(state), baseline.cost_usd).max(0.0f64),
+    })
+}
+
+/// On exit: append the session's outcome when a task was given. I/O errors
+/// are ignored.
+pub(crate) fn finish(state: &State) {
+    let Some(outcome) = outcome(state).filter(|o| o.tasks > 0) else { return };
+    let Ok(line) = serde_json::to_string(&outcome) else { return };
+    let path = store_path(RESULTS_FILE);
+    if let Some(dir) = path.parent() {
+        let _mkdir = std::fs::create_dir_all(dir);
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(path) {
+        let _r = file.write_all(format!("{line}\n").as_bytes());
+    }
+}
+
+/// One arm's totals over its sessions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ArmSummary {
+    /// Agent ID.
+    pub agent: String,
+    /// Sessions recorded.
+    pub sessions: u32,
+    /// User messages over all sessions.
+    pub tasks: u32,
+    /// Model responses over all sessions.
+    pub turns: u32,
+    /// Reverted edits over all sessions.
+    pub edits_reverted: u32,
+    /// Spend over all sessions (USD).
+    pub cost_usd: f64,
+}
+
+impl ArmSummary {
+    /// Model responses per user message.
+    pub(crate) fn turns_per_task(&self) -> f64 {
+        if self.tasks == 0 { 0.0f64 } else { float_math::div(self.turns.to_f64(), self.tasks.to_f64()) }
+    }
+
+    /// Mean spend per session (USD).
+    pub(crate) fn cost_per_session(&self) -> f64 {
+        if self.sessions == 0 { 0.0f64 } else { float_math::div(self.cost_usd, self.sessions.to_f64()) }
+    }
+}
+
+/// Totals of each arm of `experiment`, in arm order.
+pub(crate) fn summarize(experiment: &Experiment, recorded: &[Outcome]) -> [ArmSummary; 2] {
+    experiment.arms.clone().map(|agent| {
+        recorded.iter().filter(|o| o.agent == agent).fold(
+            ArmSummary { agent: agent.clone(), ..ArmSummary::default() },
+            |mut sum, o| {
+                sum.sessions = sum.sessions.saturating_add(1);
+                sum.tasks = sum.tasks.saturating_add(o.tasks);
+                sum.turns = sum.turns.saturating_add(o.turns);
+                sum.edits_reverted = sum.edits_reverted.saturating_add(o.edits_reverted);
+                sum.cost_usd = float_math::add(sum.cost_usd, o.cost_usd);
+                sum
+            },
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::data::message::ToolUseRecord;
+
+    /// A message of `role` with optional `Edit` input.
+    fn msg(role: &str, edit: Option<(&str, &str)>) -> Message {
+        let mut m = Message { role: role.to_owned(), ..Message::default() };
+        if let Some((old, new)) = edit {
+            let input = serde_json::json!({ "file_path": "src/a.rs", "old_string": old, "new_string": new });
+            m.tool_uses.push(ToolUseRecord::new("t".to_owned(), "Edit".to_owned(), input));
+        }
+        m
+    }
+
+    #[test]
+    fn turns_count_runs_and_reverts_swap_back() {
+        let session = vec![
+            msg("user", None),
+            msg("assistant", Some(("a", "b"))),
+            msg("assistant", None),
+            msg("user", None),
+            msg("assistant", Some(("b", "a"))),
+            msg("assistant", Some(("b", "a"))),
+        ];
+        assert_eq!(count_turns(&session), 2);
+        assert_eq!(count_reverted_edits(&session), 1);
+    }
+
+    #[test]
+    fn arms_alternate_and_summarize_separately() {
+        let experiment = Experiment { name: "x".to_owned(), arms: ["a".to_owned(), "b".to_owned()] };
+        assert_eq!((arm_for(&experiment, 0), arm_for(&experiment, 1), arm_for(&experiment, 2)), ("a", "b", "a"));
+        let run = |agent: &str, tasks, turns| Outcome { agent: agent.to_owned(), tasks, turns, ..Outcome::default() };
+        let [a, b] = summarize(&experiment, &[run("a", 2, 6), run("b", 1, 2), run("a", 2, 2)]);
+        assert_eq!((a.sessions, b.sessions), (2, 1));
+        assert!(float_math::abs_diff(a.turns_per_task(), 2.0f64) < f64::EPSILON);
+    }
+}
@@ -5,7 +5,7 @@
 
 // ── Re-exports from cp_base sub-modules ──
 pub(crate) use cp_base::state::context::{
-    Entry, Kind, TypeMeta, compute_total_pages, estimate_tokens, fixed_panel_order, get_context_type_meta,
+    Entry, Kind, TypeMeta, Unloaded, compute_total_pages, estimate_tokens, fixed_panel_order, get_context_type_meta,
     init_context_type_registry, make_default_entry,
 };
 pub(crate) use cp_base::state::data::config::{PanelData, Shared as SharedConfig, WorkerState};
@@ -19,4 +19,6 @@ pub(crate) use cp_base::state::data::message::{ToolResultRecord, ToolUseRecord};
 
 // ── Local submodules ──
 pub(crate) mod cache;
+pub(crate) mod experiment;
 pub(crate) mod persistence;
+pub(crate) mod profile;
@@ -203,17 +203,22 @@ pub(crate) fn load_state() -> State {
         boot_init_modules(&mut state, &module_data, |_| {});
         state
     } else {
-        // Fresh start - create default state
-        let mut state = State::default();
-        state.active_modules = crate::modules::default_active_modules();
-        state.tools = crate::modules::active_tool_definitions(&state.active_modules);
-        state.tools.push(crate::app::reverie::tools::optimize_context_tool_definition());
-        for module in crate::modules::all_modules() {
-            module.init_state(&mut state);
-        }
-        set_active_theme(&state.active_theme);
-        state
+        fresh_state()
+    }
+}
+
+/// A default state with every default module initialised, touching no files
+/// (fresh start, and the `--bench-ui` sessions).
+pub(crate) fn fresh_state() -> State {
+    let mut state = State::default();
+    state.active_modules = crate::modules::default_active_modules();
+    state.tools = crate::modules::active_tool_definitions(&state.active_modules);
+    state.tools.push(crate::app::reverie::tools::optimize_context_tool_definition());
+    for module in crate::modules::all_modules() {
+        module.init_state(&mut state);
     }
+    set_active_theme(&state.active_theme);
+    state
 }
 
 /// Convert `PanelData` to `Entry`
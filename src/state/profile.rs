@@ -0,0 +1,365 @@
+//! Per-directory profiles: settings that follow the work into a subdirectory.
+//!
+//! A `.context-pilot/config.yaml` inside a subdirectory describes that part of
+//! the repository, e.g. `frontend/.context-pilot/config.yaml`:
+//!
+//! ```yaml
+//! tree_filter: |          # replaces the tree filter
+//!   node_modules/
+//!   dist/
+//! panels: [package.json]  # opened on activation, relative to frontend/
+//! formatter:              # ahead of the configured formatters
+//!   ts: [prettier, --write]
+//! test_command: npm test
+//! ```
+//!
+//! Every few seconds the most recently opened file panels are matched against
+//! the directories holding such a file; when most of them fall under one, its
+//! profile is applied — and the previous one undone, the tree filter going
+//! back to what it was. A notification tells the agent, test command
+//! included. The active profile is kept in `.context-pilot/profile.json` so
+//! the original filter survives a restart.
+//!
+//! The formatter and test command are commands to run, and the agent could
+//! have written the profile, so they only apply while the user has the
+//! formatter on (`/formatter on`) or has trusted that directory with
+//! `/trust-profile frontend` (kept in `.context-pilot/profile_trust.json`).
+//! `Edit` and `Write` refuse files under `.context-pilot/`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use cp_mod_spine::types::{NotificationType, SpineState};
+use cp_mod_tree::types::TreeState;
+
+use crate::infra::constants::STORE_DIR;
+use crate::infra::tools::{ToolResult, ToolUse};
+use crate::state::{Kind, State};
+
+/// Profile file under a directory's store folder.
+const CONFIG_FILE: &str = "config.yaml";
+/// Active profile, under the root store directory.
+const ACTIVE_FILE: &str = "profile.json";
+/// Directories the user trusts, under the root store directory.
+const TRUST_FILE: &str = "profile_trust.json";
+/// File panels considered, newest first.
+const RECENT_FILES: usize = 5;
+/// Minimum time between two sweeps.
+const SWEEP_INTERVAL_MS: u64 = 5_000;
+/// Notification source.
+const SOURCE: &str = "profile";
+
+/// Where a file falls: under the root settings or a profile directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Place {
+    /// No profile above it.
+    Root,
+    /// Under this profile directory.
+    Dir(String),
+}
+
+/// One directory's `config.yaml`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Profile {
+    /// Tree filter while active.
+    tree_filter: Option<String>,
+    /// Files opened on activation, relative to the directory.
+    panels: Vec<String>,
+    /// Extension → formatter argv (the file path is appended).
+    formatter: BTreeMap<String, Vec<String>>,
+    /// How the tests of this part are run, told to the agent.
+    test_command: Option<String>,
+}
+
+/// The applied profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Active {
+    /// Profile directory, relative to the root.
+    dir: String,
+    /// Tree filter the profile replaced, restored when it is left.
+    base_filter: Option<String>,
+}
+
+/// Sweep bookkeeping, as a state extension.
+#[derive(Debug, Default)]
+struct Tracker {
+    /// Applied profile, if any.
+    active: Option<Active>,
+    /// When the last sweep ran (ms).
+    last_sweep_ms: u64,
+    /// Whether the persisted profile was read.
+    loaded: bool,
+}
+
+/// `<dir>/.context-pilot/config.yaml`.
+fn config_path(dir: &str) -> PathBuf {
+    Path::new(dir).join(STORE_DIR).join(CONFIG_FILE)
+}
+
+/// Profile directories the user trusted.
+fn trusted_dirs() -> Vec<String> {
+    let saved = std::fs::read_to_string(Path::new(STORE_DIR).join(TRUST_FILE)).ok();
+    saved.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default()
+}
+
+/// Whether the user trusted the profile of `dir`.
+fn is_trusted(dir: &str) -> bool {
+    trusted_dirs().iter().any(|d| d == dir)
+}
+
+/// The profile of `dir`; an unreadable one is an error for the notification.
+fn load_profile(dir: &str) -> Result<Profile, String> {
+    let path = config_path(dir);
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+    serde_yaml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Deepest directory above `file` holding a profile (`has_profile`), the
+/// root excluded.
+fn profile_dir(file: &str, has_profile: impl Fn(&str) -> bool) -> Option<String> {
+    let relative =
+        std::env::current_dir().ok().and_then(|cwd| Path::new(file).strip_prefix(cwd).ok().map(Path::to_path_buf));
+    let path = relative.unwrap_or_else(|| PathBuf::from(file));
+    if path.is_absolute() {
+        return None;
+    }
+    path.ancestors()
+        .skip(1)
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .take_while(|dir| !dir.is_empty() && dir != ".")
+        .find(|dir| has_profile(dir))
+}
+
+/// The place shared by more than half of `places`; `None` without such a
+/// majority.
+fn majority(places: &[Place]) -> Option<Place> {
+    places
+        .iter()
+        .find(|&candidate| places.iter().filter(|&p| p == candidate).count().saturating_mul(2) > places.len())
+        .cloned()
+}
+
+/// Paths of the newest file panels (by UID), newest first.
+fn recent_files(state: &State) -> Vec<String> {
+    let order = |uid: Option<&String>| {
+        uid.and_then(|u| u.strip_prefix("UID_")?.split('_').next()?.parse::<u64>().ok()).unwrap_or(0)
+    };
+    let mut files: Vec<(u64, String)> = state
+        .context
+        .iter()
+        .filter(|c| c.context_type.as_str() == Kind::FILE)
+        .filter_map(|c| Some((order(c.uid.as_ref()), c.get_meta_str("file_path")?.to_owned())))
+        .collect();
+    files.sort_by_key(|file| std::cmp::Reverse(file.0));
+    files.into_iter().take(RECENT_FILES).map(|(_, path)| path).collect()
+}
+
+/// Run one tool call as if the agent had made it.
+fn call(state: &mut State, name: &str, input: serde_json::Value) -> ToolResult {
+    let tool = ToolUse::new(format!("profile-{name}"), name.to_owned(), input);
+    crate::infra::tools::execute_tool(&tool, state)
+}
+
+/// Post `message` without waking the agent.
+fn notify(state: &mut State, message: String) {
+    let id = SpineState::create_notification(state, NotificationType::Custom, SOURCE.to_owned(), message);
+    let _processed = SpineState::mark_notification_processed(state, &id);
+}
+
+/// The tracker, created on first use.
+fn tracker_mut(state: &mut State) -> &mut Tracker {
+    if state.get_ext::<Tracker>().is_none() {
+        state.set_ext(Tracker::default());
+    }
+    state.ext_mut::<Tracker>()
+}
+
+/// Persist the applied profile (or its absence).
+fn save_active(active: Option<&Active>) {
+    let path = Path::new(STORE_DIR).join(ACTIVE_FILE);
+    let written = match active {
+        Some(profile) => serde_json::to_string_pretty(profile)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(&path, json)),
+        None if path.exists() => std::fs::remove_file(&path),
+        None => Ok(()),
+    };
+    if let Err(e) = written {
+        log::warn!("profile: cannot save {}: {e}", path.display());
+    }
+}
+
+/// Undo the active profile: restore the tree filter, drop its formatters.
+/// Returns the directory it was for.
+fn leave(state: &mut State) -> Option<String> {
+    let left = tracker_mut(state).active.take()?;
+    if let Some(filter) = left.base_filter {
+        let _r = call(state, "tree_filter", serde_json::json!({ "filter": filter }));
+    }
+    cp_mod_files::set_profile_formatters(state, BTreeMap::new(), false);
+    Some(left.dir)
+}
+
+/// Apply the profile of `dir`; the summary for the notification.
+fn enter(state: &mut State, dir: &str) -> String {
+    let profile = match load_profile(dir) {
+        Ok(profile) => profile,
+        Err(e) => {
+            tracker_mut(state).active = Some(Active { dir: dir.to_owned(), base_filter: None });
+            return format!("Profile {dir}/ not applied: {e}");
+        }
+    };
+    let mut summary = format!("Profile {dir}/ active");
+    let base_filter = profile.tree_filter.as_ref().map(|filter| {
+        let base = TreeState::get(state).filter.clone();
+        let _r = call(state, "tree_filter", serde_json::json!({ "filter": filter }));
+        summary.push_str(" \u{b7} tree filter set");
+        base
+    });
+    tracker_mut(state).active = Some(Active { dir: dir.to_owned(), base_filter });
+    let panels: Vec<String> = profile
+        .panels
+        .iter()
+        .map(|p| Path::new(dir).join(p).to_string_lossy().into_owned())
+        .filter(|p| Path::new(p).is_file())
+        .collect();
+    if !panels.is_empty() {
+        let _r = write!(summary, " \u{b7} opened {}", panels.join(", "));
+        let _o = call(state, "Open", serde_json::json!({ "path": panels }));
+    }
+    let trusted = is_trusted(dir);
+    summary.push_str(&commands_note(&profile, dir, trusted || cp_mod_files::formatter_enabled(state)));
+    cp_mod_files::set_profile_formatters(state, profile.formatter, trusted);
+    summary
+}
+
+/// What the notification says of the profile's formatter and test command:
+/// listed when `usable`, else held until the user trusts `dir`.
+fn commands_note(profile: &Profile, dir: &str, usable: bool) -> String {
+    if profile.formatter.is_empty() && profile.test_command.is_none() {
+        return String::new();
+    }
+    if !usable {
+        return format!(" \u{b7} formatter and test command unused until the user types `/trust-profile {dir}`");
+    }
+    let mut note = String::new();
+    if !profile.formatter.is_empty() {
+        let exts: Vec<&str> = profile.formatter.keys().map(String::as_str).collect();
+        let _r = write!(note, " \u{b7} formatter for .{}", exts.join(", ."));
+    }
+    if let Some(test) = profile.test_command.as_ref() {
+        let _r = write!(note, " \u{b7} run its tests with `{test}` from {dir}/");
+    }
+    note
+}
+
+/// Trust the profile of `dir` (user-side only): its formatter and test
+/// command apply even while the formatter is off. The message to show.
+pub(crate) fn trust(state: &mut State, typed: &str) -> Result<String, String> {
+    let dir = typed.trim_end_matches('/');
+    let profile = load_profile(dir)?;
+    let mut dirs = trusted_dirs();
+    if !dirs.iter().any(|d| d == dir) {
+        dirs.push(dir.to_owned());
+        let path = Path::new(STORE_DIR).join(TRUST_FILE);
+        let json = serde_json::to_string_pretty(&dirs).map_err(|e| e.to_string())?;
+        std::fs::write(&path, json).map_err(|e| format!("cannot save {}: {e}", path.display()))?;
+    }
+    let active = state.get_ext::<Tracker>().and_then(|t| t.active.as_ref()).is_some_and(|a| a.dir == dir);
+    let note = commands_note(&profile, dir, true);
+    if active {
+        cp_mod_files::set_profile_formatters(state, profile.formatter, true);
+    }
+    Ok(format!("Profile {dir}/ trusted{note}"))
+}
+
+/// Read the persisted profile once, reinstating its formatters.
+fn load_active(state: &mut State) {
+    let tracker = tracker_mut(state);
+    if tracker.loaded {
+        return;
+    }
+    tracker.loaded = true;
+    let saved = std::fs::read_to_string(Path::new(STORE_DIR).join(ACTIVE_FILE)).ok();
+    let active: Option<Active> = saved.and_then(|json| serde_json::from_str(&json).ok());
+    if let Some(applied) = active.as_ref()
+        && let Ok(profile) = load_profile(&applied.dir)
+    {
+        cp_mod_files::set_profile_formatters(state, profile.formatter, is_trusted(&applied.dir));
+    }
+    tracker_mut(state).active = active;
+}
+
+/// Switch profiles when the recently opened files moved to another one.
+/// Throttled to one sweep every [`SWEEP_INTERVAL_MS`].
+pub(crate) fn tick(state: &mut State) {
+    let now = crate::app::panels::now_ms();
+    let tracker = tracker_mut(state);
+    if now.saturating_sub(tracker.last_sweep_ms) < SWEEP_INTERVAL_MS {
+        return;
+    }
+    tracker.last_sweep_ms = now;
+    load_active(state);
+    let places: Vec<Place> = recent_files(state)
+        .iter()
+        .map(|f| profile_dir(f, |dir| config_path(dir).is_file()).map_or(Place::Root, Place::Dir))
+        .collect();
+    let current =
+        state.get_ext::<Tracker>().and_then(|t| t.active.as_ref()).map_or(Place::Root, |a| Place::Dir(a.dir.clone()));
+    let Some(detected) = majority(&places).filter(|p| *p != current) else { return };
+    let left = leave(state);
+    let message = match detected {
+        Place::Dir(dir) => enter(state, &dir),
+        Place::Root => format!("Profile {}/ left: root settings restored", left.unwrap_or_default()),
+    };
+    save_active(state.get_ext::<Tracker>().and_then(|t| t.active.as_ref()));
+    notify(state, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_deepest_profile_of_most_recent_files_wins() {
+        let has = |dir: &str| dir == "web" || dir == "web/admin";
+        assert_eq!(profile_dir("web/admin/src/a.ts", has), Some("web/admin".to_owned()));
+        assert_eq!(profile_dir("web/b.ts", has), Some("web".to_owned()));
+        assert_eq!(profile_dir("src/main.rs", has), None);
+        let web = Place::Dir("web".to_owned());
+        assert_eq!(majority(&[web.clone(), Place::Root, web.clone()]), Some(web.clone()));
+        assert_eq!(majority(&[web, Place::Root]), None);
+        assert_eq!(majority(&[Place::Root, Place::Root]), Some(Place::Root));
+    }
+
+    #[test]
+    fn profiles_parse_from_yaml() {
+        let yaml = "panels: [package.json]\nformatter:\n  ts: [prettier, --write]\ntest_command: npm test\n";
+        let parsed: Result<Profile, _> = serde_yaml::from_str(yaml);
+        let expected = Profile {
+            tree_filter: None,
+            panels: vec!["package.json".to_owned()],
+            formatter: BTreeMap::from([("ts".to_owned(), vec!["prettier".to_owned(), "--write".to_owned()])]),
+            test_command: Some("npm test".to_owned()),
+        };
+        assert_eq!(parsed.ok(), Some(expected));
+    }
+
+    #[test]
+    fn untrusted_commands_are_held_back() {
+        let profile = Profile {
+            formatter: BTreeMap::from([("ts".to_owned(), vec!["prettier".to_owned()])]),
+            test_command: Some("npm test".to_owned()),
+            ..Profile::default()
+        };
+        let held = commands_note(&profile, "web", false);
+        assert!(held.contains("/trust-profile web") && !held.contains("npm test"));
+        let usable = commands_note(&profile, "web", true);
+        assert!(usable.contains("formatter for .ts") && usable.contains("`npm test` from web/"));
+        assert_eq!(commands_note(&Profile::default(), "web", false), "");
+    }
+}
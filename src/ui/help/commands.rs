@@ -1,4 +1,11 @@
-use crate::state::{Kind, State};
+use crate::modules::cleaner::plan::PlanStatus;
+use crate::modules::cleaner::scope::CleanScope;
+use crate::modules::cleaner::types::{CleanerState, RunStatus};
+use crate::modules::overview::panel_group::{self, PanelGroups};
+use crate::modules::overview::panel_layout;
+use crate::state::{Kind, Message, MsgKind, MsgStatus, State};
+use cp_base::state::data::review::MsgMarker;
+use cp_mod_ledger::types::LedgerState;
 
 /// A command that can be executed from the palette
 #[derive(Debug, Clone)]
@@ -92,22 +99,30 @@ impl PaletteCommand {
 
 /// Build the list of available commands based on current state
 pub(super) fn get_available_commands(state: &State) -> Vec<PaletteCommand> {
-    let mut commands = Vec::new();
-
     // System commands at the top
-    commands.push(
-        PaletteCommand::new("quit", "Quit", "Exit the application (Ctrl+Q)").with_keywords(&["exit", "close", "q"]),
-    );
+    let mut commands = system_commands();
 
-    commands.push(PaletteCommand::new("reload", "Reload", "Reload the TUI").with_keywords(&["restart", "refresh"]));
+    // Cleaner rollback — only offered when a finished run can still be undone
+    if let Some(cleaner) = state.get_ext::<CleanerState>()
+        && let Some(run) = cleaner.undoable()
+        && !cleaner.runs.iter().any(|r| r.status == RunStatus::Running)
+    {
+        commands.push(
+            PaletteCommand::new(
+                "cleaner_undo",
+                "Undo last clean",
+                format!("Restore the panels cleaning run #{} closed", run.number),
+            )
+            .with_keywords(&["cleaner", "undo", "restore", "rollback", "reverie"]),
+        );
+    }
 
-    commands.push(PaletteCommand::new("config", "Config", "Open configuration panel (Ctrl+H)").with_keywords(&[
-        "settings",
-        "options",
-        "preferences",
-        "provider",
-        "model",
-    ]));
+    commands.extend(ledger_report_command(state));
+    commands.extend(close_deprecated_command(state));
+    commands.extend(perf_export_command());
+    commands.extend(group_commands(state));
+    commands.extend(template_commands());
+    commands.extend(snippet_commands());
 
     // Conversation entry (special: no Px ID, always first in panels)
     if let Some(conv) = state.context.iter().find(|c| c.context_type == Kind::new(Kind::CONVERSATION)) {
@@ -148,3 +163,227 @@ pub(super) fn get_available_commands(state: &State) -> Vec<PaletteCommand> {
 
     commands
 }
+
+/// Quit, reload, config and tour: always offered.
+fn system_commands() -> Vec<PaletteCommand> {
+    vec![
+        PaletteCommand::new("quit", "Quit", "Exit the application (Ctrl+Q)").with_keywords(&["exit", "close", "q"]),
+        PaletteCommand::new("reload", "Reload", "Reload the TUI").with_keywords(&["restart", "refresh"]),
+        PaletteCommand::new("config", "Config", "Open configuration panel (Ctrl+H)").with_keywords(&[
+            "settings",
+            "options",
+            "preferences",
+            "provider",
+            "model",
+        ]),
+        PaletteCommand::new("tour", "Tour", "Replay the guided tour of the interface").with_keywords(&[
+            "help",
+            "tutorial",
+            "intro",
+            "shortcuts",
+            "keys",
+        ]),
+    ]
+}
+
+/// "Export work report" — only offered once the actions ledger has entries.
+fn ledger_report_command(state: &State) -> Option<PaletteCommand> {
+    let ledger = state.get_ext::<LedgerState>()?;
+    (!ledger.entries.is_empty()).then(|| {
+        PaletteCommand::new(
+            "ledger_report",
+            "Export work report",
+            format!("Write this session's actions ({}) as a markdown report", ledger.summary()),
+        )
+        .with_keywords(&["actions", "ledger", "changelog", "report", "export"])
+    })
+}
+
+/// "Close deprecated panels" — only offered when some panel's source is gone.
+fn close_deprecated_command(state: &State) -> Option<PaletteCommand> {
+    let ids = panel_layout::deprecated_ids(state);
+    (!ids.is_empty()).then(|| {
+        PaletteCommand::new(
+            "close_deprecated",
+            "Close deprecated panels",
+            format!("Close {} (deleted files, finished consoles)", ids.join(", ")),
+        )
+        .with_keywords(&["close", "deprecated", "stale", "panels", "cleanup"])
+    })
+}
+
+/// "Export perf profile" — only offered once profile guards were recorded
+/// (perf overlay on, or `CP_PERF_HISTORY=1`).
+fn perf_export_command() -> Option<PaletteCommand> {
+    crate::ui::perf::history::has_samples().then(|| {
+        PaletteCommand::new(
+            "perf_export",
+            "Export perf profile",
+            "Write the recorded timings as folded stacks (speedscope, inferno-flamegraph)",
+        )
+        .with_keywords(&["perf", "profile", "flamegraph", "speedscope", "export"])
+    })
+}
+
+/// Prefix of panel-group command ids; the remainder is `<action>:<group name>`.
+pub(crate) const GROUP_PREFIX: &str = "group:";
+
+/// Open / close / pin-or-unpin entries for every panel group.
+fn group_commands(state: &State) -> Vec<PaletteCommand> {
+    let Some(groups) = state.get_ext::<PanelGroups>() else { return Vec::new() };
+    let mut commands = Vec::new();
+    for group in &groups.groups {
+        let name = &group.name;
+        let open = panel_group::open_members(state, group);
+        let all_pinned = !open.is_empty() && open.iter().all(|c| panel_group::is_pinned(c));
+        let pin = if all_pinned { "unpin" } else { "pin" };
+        let members = format!("{} of {} open", open.len(), group.members.len());
+        for (action, label) in [("open", "Open"), ("close", "Close"), (pin, if all_pinned { "Unpin" } else { "Pin" })] {
+            commands.push(
+                PaletteCommand::new(
+                    format!("{GROUP_PREFIX}{action}:{name}"),
+                    format!("{label} group {name}"),
+                    &members,
+                )
+                .with_keywords(&["group", action, name]),
+            );
+        }
+    }
+    commands
+}
+
+/// Prefix of the conversation template command ids; the remainder is the
+/// template id.
+pub(crate) const TEMPLATE_PREFIX: &str = "template:";
+
+/// One entry per conversation template, plus a disabled one naming the
+/// project templates file when it does not parse.
+fn template_commands() -> Vec<PaletteCommand> {
+    let (templates, error) = crate::app::actions::templates::load();
+    let mut commands: Vec<PaletteCommand> = templates
+        .iter()
+        .map(|t| {
+            PaletteCommand::new(format!("{TEMPLATE_PREFIX}{}", t.id), format!("Template: {}", t.name), &t.description)
+                .with_keywords(&["template", "workflow", "start", &t.id])
+        })
+        .collect();
+    if let Some(e) = error {
+        commands.push(
+            PaletteCommand::new("template_error", "Templates file ignored", e).with_keywords(&["template", "error"]),
+        );
+    }
+    commands
+}
+
+/// Prefix of the snippet command ids; the remainder is the snippet id.
+pub(crate) const SNIPPET_PREFIX: &str = "snippet:";
+
+/// One entry per snippet, inserting it into the draft at the cursor.
+fn snippet_commands() -> Vec<PaletteCommand> {
+    let library = cp_mod_snippets::store::load(std::path::Path::new("."));
+    library
+        .snippets
+        .iter()
+        .map(|s| {
+            let keywords = ["snippet", "insert", "boilerplate", &s.id, s.language.as_deref().unwrap_or("")];
+            PaletteCommand::new(format!("{SNIPPET_PREFIX}{}", s.id), format!("Snippet: {}", s.name), &s.description)
+                .with_keywords(&keywords)
+        })
+        .collect()
+}
+
+/// Prefix of the command ids produced by the Ctrl+K cleaning scope picker;
+/// the remainder is a [`CleanScope`] spec.
+pub(crate) const CLEAN_SCOPE_PREFIX: &str = "clean:";
+
+/// Entries for the Ctrl+K cleaning scope picker. The query is not a filter
+/// but an argument: panel IDs (`P3 P7`) or a message ID (`U12`).
+pub(crate) fn get_clean_scope_commands(state: &State, query: &str) -> Vec<PaletteCommand> {
+    let running =
+        state.get_ext::<CleanerState>().is_some_and(|cs| cs.runs.iter().any(|r| r.status == RunStatus::Running));
+    if !state.flags.config.reverie_enabled || running {
+        let why = if running { "A cleaning run is already in progress" } else { "Reverie is disabled (Ctrl+H)" };
+        return vec![PaletteCommand::new("clean_unavailable", "Cleaning unavailable", why)];
+    }
+    let scope_entry = |scope: &CleanScope, description: &str| {
+        PaletteCommand::new(
+            format!("{CLEAN_SCOPE_PREFIX}{}", scope.to_spec()),
+            format!("Clean {}", scope.label()),
+            description,
+        )
+    };
+    let mut commands = Vec::new();
+    if !query.trim().is_empty() {
+        commands.push(CleanScope::from_argument(state, query).map_or_else(
+            || {
+                PaletteCommand::new(
+                    "clean_unavailable",
+                    format!("No panel or message \"{}\"", query.trim()),
+                    "Type panel IDs (P3 P7) or a message ID (U12)",
+                )
+            },
+            |scope| scope_entry(&scope, "Only what you typed may be dropped"),
+        ));
+    }
+    commands.push(scope_entry(&CleanScope::Everything, "One pass over the whole context"));
+    commands.push(scope_entry(&CleanScope::MessagesOnly, "Only conversation history may be dropped"));
+    commands.push(scope_entry(&CleanScope::PanelsOnly, "Conversation history stays; panels may be dropped"));
+    commands.extend(plan_commands(state));
+    commands
+}
+
+/// Picker entries for eviction plans: apply the previewed plan and ask for a new one.
+fn plan_commands(state: &State) -> Vec<PaletteCommand> {
+    let plan = state.get_ext::<CleanerState>().and_then(|cs| cs.plan.as_ref());
+    let mut commands = Vec::new();
+    if let Some(ready) = plan.and_then(PlanStatus::ready) {
+        commands.push(PaletteCommand::new("clean_apply", "Apply cleanup plan", ready.summary.clone()));
+    }
+    if !plan.is_some_and(|status| matches!(*status, PlanStatus::Pending)) {
+        commands.push(
+            PaletteCommand::new(
+                "clean_plan",
+                "Plan cleanup (preview)",
+                "Ask the model for a plan without executing it",
+            )
+            .with_keywords(&["plan", "evict", "preview"]),
+        );
+    }
+    commands
+}
+
+/// Prefix of the command ids produced by the Ctrl+B markers palette; the
+/// remainder is a message ID.
+pub(crate) const MESSAGE_PREFIX: &str = "msg:";
+
+/// Entries for the Ctrl+B markers palette: the conversation's text messages,
+/// marked or annotated ones first, each group newest first. The query matches
+/// message text, IDs, marker names (`important`, `revisit`, `bug`) and `note`.
+pub(crate) fn get_marker_commands(state: &State, query: &str) -> Vec<PaletteCommand> {
+    let (marked, unmarked): (Vec<&Message>, Vec<&Message>) = state
+        .messages
+        .iter()
+        .rev()
+        .filter(|m| {
+            m.msg_type == MsgKind::TextMessage && m.status != MsgStatus::Deleted && !m.content.trim().is_empty()
+        })
+        .partition(|m| !m.markers.is_empty() || m.note.is_some());
+    marked.into_iter().chain(unmarked).map(marker_command).filter(|cmd| cmd.matches(query)).collect()
+}
+
+/// Markers-palette entry for one message: ID, markers and note glyph, then its
+/// first line.
+fn marker_command(msg: &Message) -> PaletteCommand {
+    let noted = if msg.note.is_some() { "\u{270e}" } else { "" };
+    let icons = format!("{}{noted}", MsgMarker::icons(&msg.markers));
+    let label = if icons.is_empty() { msg.id.clone() } else { format!("{} {icons}", msg.id) };
+    let first_line = msg.content.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    let mut names: Vec<&str> = msg.markers.iter().map(|m| m.name()).collect();
+    names.extend(msg.note.as_ref().map(|_| "note"));
+    PaletteCommand::new(
+        format!("{MESSAGE_PREFIX}{}", msg.id),
+        label,
+        cp_base::ui::text::ellipsize(first_line, 80, "..."),
+    )
+    .with_keywords(&names)
+}
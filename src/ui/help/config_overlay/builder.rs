@@ -9,6 +9,7 @@ use cp_render::conversation::{ConfigBudgetBar, ConfigModel, ConfigOverlay, Confi
 use crate::state::State;
 use cp_base::cast::Safe as _;
 use cp_base::cast::float_math;
+use cp_base::ui::spell;
 
 /// Type alias for the model-entry builder closure (`clippy::type_complexity`).
 type ModelEntryFn = dyn Fn(bool, &str, &dyn crate::llms::ModelInfo) -> ConfigModel;
@@ -164,11 +165,24 @@ fn build_budget_bars(state: &State) -> Vec<ConfigBudgetBar> {
     ]
 }
 
+/// Spell-check toggle value; flags a missing wordlist, since then nothing is underlined.
+fn spell_value_display(enabled: bool) -> String {
+    match (enabled, spell::available()) {
+        (false, _) => "OFF".into(),
+        (true, true) => "ON".into(),
+        (true, false) => "ON (no dictionary)".into(),
+    }
+}
+
 /// Build the toggle entries.
 fn build_toggles(state: &State) -> Vec<ConfigToggle> {
     let spine_cfg = &cp_mod_spine::types::SpineState::get(state).config;
     let auto_on = spine_cfg.continue_until_todos_done;
     let rev_on = state.flags.config.reverie_enabled;
+    let open_on = state.flags.config.mention_auto_open;
+    let spell_on = spell::Settings::enabled(state);
+    let gc_policy = crate::modules::overview::panel_gc::policy(state);
+    let cache_budget = crate::modules::overview::cache_budget::budget(state);
     let think_threshold =
         state.get_ext::<crate::modules::questions::ThinkState>().map_or(-5i32, |ts| ts.reminder_threshold);
 
@@ -187,6 +201,34 @@ fn build_toggles(state: &State) -> Vec<ConfigToggle> {
             key_hint: "r".into(),
             adjust_keys: None,
         },
+        ConfigToggle {
+            label: "Open @files".into(),
+            enabled: open_on,
+            value_display: if open_on { "ON".into() } else { "OFF".into() },
+            key_hint: "o".into(),
+            adjust_keys: None,
+        },
+        ConfigToggle {
+            label: "Spell check".into(),
+            enabled: spell_on,
+            value_display: spell_value_display(spell_on),
+            key_hint: "k".into(),
+            adjust_keys: None,
+        },
+        ConfigToggle {
+            label: "Panel GC".into(),
+            enabled: !gc_policy.is_off(),
+            value_display: gc_policy.label(),
+            key_hint: "g".into(),
+            adjust_keys: None,
+        },
+        ConfigToggle {
+            label: "Cache budget".into(),
+            enabled: cache_budget.mb > 0,
+            value_display: cache_budget.label(),
+            key_hint: "m".into(),
+            adjust_keys: None,
+        },
         ConfigToggle {
             label: "Think nudge".into(),
             enabled: think_threshold < 0,
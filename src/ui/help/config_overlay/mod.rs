@@ -17,7 +17,7 @@ pub(crate) use builder::build_config_overlay;
 pub(crate) fn render_config_overlay(frame: &mut Frame<'_>, config: &ConfigOverlay, area: Rect) {
     // Center the overlay, clamped to available area
     let overlay_width = 56u16.min(area.width);
-    let overlay_height = 34u16.min(area.height);
+    let overlay_height = 35u16.min(area.height);
     let half_width = area.width.saturating_sub(overlay_width).saturating_div(2);
     let x = area.x.saturating_add(half_width);
     let half_height = area.height.saturating_sub(overlay_height).saturating_div(2);
@@ -1,4 +1,4 @@
-//! Autocomplete popup overlay.
+//! Autocomplete popup overlay (also used for spelling suggestions).
 //!
 //! Adapter layer: renders [`Autocomplete`] IR data to ratatui widgets.
 //! No direct `State` access.
@@ -47,7 +47,8 @@ pub(crate) fn render_autocomplete_popup(frame: &mut Frame<'_>, ac: &Autocomplete
 
     // Show matches
     if ac.entries.is_empty() {
-        lines.push(Line::from(vec![Span::styled("  No matches", Style::default().fg(theme::text_muted()))]));
+        let empty = if ac.title.is_some() { "  No suggestions" } else { "  No matches" };
+        lines.push(Line::from(vec![Span::styled(empty, Style::default().fg(theme::text_muted()))]));
     } else {
         for (i, entry) in ac.entries.iter().enumerate() {
             lines.push(autocomplete_entry_line(entry, i == ac.selected_index));
@@ -56,7 +57,10 @@ pub(crate) fn render_autocomplete_popup(frame: &mut Frame<'_>, ac: &Autocomplete
 
     // Count indicator
     let dir_label = if ac.dir_prefix.is_empty() { ".".to_owned() } else { ac.dir_prefix.clone() };
-    let count_text = format!(" @{} — {}/{} in {}/ ", ac.query, ac.total_matches, ac.total_matches, dir_label);
+    let count_text = ac
+        .title
+        .clone()
+        .unwrap_or_else(|| format!(" @{} — {}/{} in {}/ ", ac.query, ac.total_matches, ac.total_matches, dir_label));
 
     let block = Block::default()
         .borders(Borders::ALL)
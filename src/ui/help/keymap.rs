@@ -0,0 +1,225 @@
+//! Keybinding registry: every key the TUI answers to, grouped by where it
+//! applies.
+//!
+//! The `?` / F1 reference overlay is generated from [`BINDINGS`]. The events
+//! tests replay the documented keys through the real key dispatch, so a
+//! binding cannot change without this table changing too.
+
+use cp_render::conversation::{KeyHelpGroup, KeyHelpOverlay};
+use ratatui::prelude::{Frame, Line, Rect, Span, Style};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph};
+
+use crate::state::State;
+use crate::ui::theme;
+
+/// Width of one column of the reference, in cells.
+const COLUMN_WIDTH: u16 = 44;
+
+/// Width of the keys cell within a column.
+const KEYS_WIDTH: usize = 13;
+
+/// Where a binding applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Group {
+    /// Anywhere.
+    Global,
+    /// The sidebar and panel navigation.
+    Panels,
+    /// The conversation draft.
+    Conversation,
+    /// The directory tree panel.
+    Tree,
+    /// The configuration overlay (Ctrl+H).
+    Config,
+    /// The threads view (Ctrl+V).
+    Threads,
+}
+
+impl Group {
+    /// Every group, in display order.
+    const ALL: [Self; 6] = [Self::Global, Self::Panels, Self::Conversation, Self::Tree, Self::Config, Self::Threads];
+
+    /// Heading in the reference overlay.
+    const fn title(self) -> &'static str {
+        match self {
+            Self::Global => "Global",
+            Self::Panels => "Sidebar & panels",
+            Self::Conversation => "Conversation",
+            Self::Tree => "Tree",
+            Self::Config => "Configuration (Ctrl+H)",
+            Self::Threads => "Threads view (Ctrl+V)",
+        }
+    }
+}
+
+/// One documented binding.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Binding {
+    /// Where it applies.
+    pub group: Group,
+    /// Keys as shown to the user.
+    pub keys: &'static str,
+    /// What it does.
+    pub description: &'static str,
+}
+
+/// Shorthand for a table entry.
+const fn bind(group: Group, keys: &'static str, description: &'static str) -> Binding {
+    Binding { group, keys, description }
+}
+
+/// Every binding, grouped and in display order.
+pub(crate) const BINDINGS: &[Binding] = &[
+    bind(Group::Global, "Ctrl+P", "command palette"),
+    bind(Group::Global, "Ctrl+K", "clean the context"),
+    bind(Group::Global, "Ctrl+B", "message markers"),
+    bind(Group::Global, "Ctrl+H", "configuration"),
+    bind(Group::Global, "Ctrl+I", "search index status"),
+    bind(Group::Global, "Ctrl+S", "standing instructions"),
+    bind(Group::Global, "Ctrl+V", "threads view"),
+    bind(Group::Global, "Ctrl+C", "copy the panel"),
+    bind(Group::Global, "Ctrl+T", "move a paste into a panel"),
+    bind(Group::Global, "Ctrl+X", "close the panels context health suggests"),
+    bind(Group::Global, "Ctrl+U", "previous prompt"),
+    bind(Group::Global, "Ctrl+D", "next prompt"),
+    bind(Group::Global, "Ctrl+Z", "undo (or reopen gc'd panels)"),
+    bind(Group::Global, "Ctrl+Shift+Z", "redo"),
+    bind(Group::Global, "Ctrl+N", "new conversation"),
+    bind(Group::Global, "Ctrl+L", "clear the conversation"),
+    bind(Group::Global, "Ctrl+O", "reset session costs"),
+    bind(Group::Global, "Ctrl+R", "voice input: record / transcribe"),
+    bind(Group::Global, "F12", "performance monitor"),
+    bind(Group::Global, "F1", "this reference"),
+    bind(Group::Global, "?", "this reference (empty draft)"),
+    bind(Group::Global, "Esc", "stop the model"),
+    bind(Group::Global, "Ctrl+Q", "quit"),
+    bind(Group::Panels, "Tab", "next panel"),
+    bind(Group::Panels, "Shift+Tab", "previous panel"),
+    bind(Group::Panels, "\u{2191} \u{2193}", "scroll"),
+    bind(Group::Panels, "PgUp PgDn", "scroll a page"),
+    bind(Group::Panels, "Alt+1..9", "quick slot: jump or bind"),
+    bind(Group::Panels, "P3 Enter", "jump to a panel by ID"),
+    bind(Group::Panels, "1..9", "Recent, Bookmarks: open the nth entry"),
+    bind(Group::Panels, "n p  a r", "Review: next / previous hunk, accept / reject its comments"),
+    bind(Group::Panels, "i", "token breakdown of the panel"),
+    bind(Group::Panels, "x", "close the selected panel"),
+    bind(Group::Panels, "Shift+\u{2191} \u{2193}", "move the selected panel"),
+    bind(Group::Conversation, "Enter", "new line; on empty line sends"),
+    bind(Group::Conversation, "@", "mention a file"),
+    bind(Group::Conversation, "Shift+arrows", "select"),
+    bind(Group::Conversation, "Ctrl/Alt+\u{2190} \u{2192}", "jump a word"),
+    bind(Group::Conversation, "Ctrl+A", "select all"),
+    bind(Group::Conversation, "Ctrl+W", "delete the previous word"),
+    bind(Group::Conversation, "Alt+K", "delete to end of line"),
+    bind(Group::Conversation, "Ctrl+Y", "paste what was deleted"),
+    bind(Group::Conversation, "Alt+S", "spelling suggestions"),
+    bind(Group::Tree, "\u{2191} \u{2193} PgUp PgDn", "scroll the tree"),
+    bind(Group::Config, "1..8", "provider"),
+    bind(Group::Config, "a..d", "model"),
+    bind(Group::Config, "t / T", "next / previous theme"),
+    bind(Group::Config, "\u{2193}", "next budget bar"),
+    bind(Group::Config, "\u{2190} \u{2192}", "adjust the bar"),
+    bind(Group::Config, "s", "auto-continue"),
+    bind(Group::Config, "r", "context optimizer"),
+    bind(Group::Config, "o", "open @-mentioned files"),
+    bind(Group::Config, "k", "spell checking"),
+    bind(Group::Config, "g", "idle panel cleanup"),
+    bind(Group::Config, "m", "panel cache memory budget"),
+    bind(Group::Config, "[ ]", "think reminders"),
+    bind(Group::Config, "Esc", "close"),
+    bind(Group::Threads, "Tab/Shift+Tab", "select a thread"),
+    bind(Group::Threads, "Ctrl+A", "archive / restore"),
+    bind(Group::Threads, "Ctrl+U", "show archived threads"),
+    bind(Group::Threads, "y", "confirm archiving"),
+    bind(Group::Threads, "Esc", "back to panels"),
+];
+
+/// The IR of the reference overlay.
+pub(crate) fn build_key_help_overlay() -> KeyHelpOverlay {
+    let groups = Group::ALL
+        .iter()
+        .map(|&group| KeyHelpGroup {
+            title: group.title().to_owned(),
+            entries: BINDINGS
+                .iter()
+                .filter(|b| b.group == group)
+                .map(|b| (b.keys.to_owned(), b.description.to_owned()))
+                .collect(),
+        })
+        .collect();
+    KeyHelpOverlay { groups }
+}
+
+/// Show or hide the reference.
+pub(crate) const fn toggle(state: &mut State) {
+    state.flags.overlays.key_help = !state.flags.overlays.key_help;
+    state.flags.ui.dirty = true;
+}
+
+/// Render the reference over `area`, in as many columns as fit; a group is
+/// never split across columns.
+pub(crate) fn render_key_help_overlay(frame: &mut Frame<'_>, help: &KeyHelpOverlay, area: Rect) {
+    let fit = area.width.saturating_sub(4).saturating_div(COLUMN_WIDTH).clamp(1, 3);
+    let columns = split_columns(&help.groups, usize::from(fit));
+    let count = u16::try_from(columns.len()).unwrap_or(1).max(1);
+    let tallest = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let width = COLUMN_WIDTH.saturating_mul(count).saturating_add(4).min(area.width);
+    let height = u16::try_from(tallest).unwrap_or(u16::MAX).saturating_add(3).min(area.height);
+    let card = Rect::new(
+        area.x.saturating_add(area.width.saturating_sub(width).saturating_div(2)),
+        area.y.saturating_add(area.height.saturating_sub(height).saturating_div(2)),
+        width,
+        height,
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme::accent()))
+        .style(Style::default().bg(theme::bg_surface()))
+        .title(Span::styled(" Keybindings ", Style::default().fg(theme::accent()).bold()))
+        .title_bottom(Span::styled(" Esc / ? close ", Style::default().fg(theme::text_muted())));
+    let inner = block.inner(card);
+    frame.render_widget(Clear, card);
+    frame.render_widget(block, card);
+
+    for (i, lines) in columns.into_iter().enumerate() {
+        let offset = COLUMN_WIDTH.saturating_mul(u16::try_from(i).unwrap_or(0));
+        let column = Rect::new(
+            inner.x.saturating_add(offset).saturating_add(1),
+            inner.y.saturating_add(1),
+            COLUMN_WIDTH.saturating_sub(2).min(inner.width.saturating_sub(offset)),
+            inner.height.saturating_sub(1),
+        );
+        frame.render_widget(Paragraph::new(lines), column);
+    }
+}
+
+/// Lay the groups out in `count` columns of roughly equal height, in order.
+fn split_columns(groups: &[KeyHelpGroup], count: usize) -> Vec<Vec<Line<'static>>> {
+    let total: usize = groups.iter().map(|g| g.entries.len().saturating_add(2)).sum();
+    let target = total.div_ceil(count.max(1));
+    let mut columns: Vec<Vec<Line<'static>>> = vec![Vec::new()];
+    for group in groups {
+        let full =
+            columns.last().is_some_and(|c| !c.is_empty() && c.len().saturating_add(group.entries.len()) > target);
+        if full && columns.len() < count {
+            columns.push(Vec::new());
+        }
+        if let Some(column) = columns.last_mut() {
+            column.extend(group_lines(group));
+        }
+    }
+    columns
+}
+
+/// A group heading, its entries, and a blank separator.
+fn group_lines(group: &KeyHelpGroup) -> Vec<Line<'static>> {
+    let heading = Line::from(Span::styled(group.title.clone(), Style::default().fg(theme::text()).bold()));
+    let entries = group.entries.iter().map(|entry| {
+        Line::from(vec![
+            Span::styled(format!("{:<KEYS_WIDTH$} ", entry.0), Style::default().fg(theme::accent())),
+            Span::styled(entry.1.clone(), Style::default().fg(theme::text_muted())),
+        ])
+    });
+    std::iter::once(heading).chain(entries).chain(std::iter::once(Line::default())).collect()
+}
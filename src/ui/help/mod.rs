@@ -4,7 +4,12 @@ mod commands;
 pub(crate) mod config_overlay;
 /// Question form and autocomplete popup overlay rendering.
 pub(crate) mod input;
-/// Command palette (Ctrl+P) state and rendering.
+/// Keybinding registry and the `?` / F1 reference overlay.
+pub(crate) mod keymap;
+/// Command palette (Ctrl+P / Ctrl+K / Ctrl+B) state and rendering.
 mod palette;
+/// First-run guided tour.
+pub(crate) mod tour;
 
-pub(crate) use palette::CommandPalette;
+pub(crate) use commands::{CLEAN_SCOPE_PREFIX, GROUP_PREFIX, MESSAGE_PREFIX, SNIPPET_PREFIX, TEMPLATE_PREFIX};
+pub(crate) use palette::{CommandPalette, PaletteMode};
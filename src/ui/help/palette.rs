@@ -6,15 +6,47 @@ use ratatui::{
 use crate::state::State;
 use crate::ui::theme;
 
-use super::commands::{PaletteCommand, get_available_commands};
+use super::commands::{PaletteCommand, get_available_commands, get_clean_scope_commands, get_marker_commands};
+use crate::app::actions::Action;
 use cp_base::cast::Safe as _;
+use cp_base::ui::text;
 use cp_render::conversation::{PaletteEntry, PaletteOverlay};
+use unicode_width::UnicodeWidthStr as _;
+
+/// What the palette lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum PaletteMode {
+    /// Fuzzy-filtered commands and panels (Ctrl+P).
+    #[default]
+    Commands,
+    /// Cleaning scope choices; the query is a free-text argument (Ctrl+K).
+    CleanScope,
+    /// Conversation messages, marked ones first; Alt+1/2/3 toggle markers (Ctrl+B).
+    Markers,
+}
+
+impl PaletteMode {
+    /// The mode an action opens the palette in, if it opens it at all.
+    pub(crate) const fn for_action(action: &Action) -> Option<Self> {
+        if matches!(action, Action::OpenCommandPalette) {
+            Some(Self::Commands)
+        } else if matches!(action, Action::OpenCleanScopePicker) {
+            Some(Self::CleanScope)
+        } else if matches!(action, Action::OpenMarkers) {
+            Some(Self::Markers)
+        } else {
+            None
+        }
+    }
+}
 
 /// State for the command palette
 #[derive(Debug, Clone, Default)]
 pub(crate) struct CommandPalette {
     /// Whether the palette is open
     pub is_open: bool,
+    /// What the palette is listing
+    pub mode: PaletteMode,
     /// Current search query
     pub query: String,
     /// Cursor position in query
@@ -31,9 +63,10 @@ impl CommandPalette {
         Self::default()
     }
 
-    /// Open the palette
-    pub(crate) fn open(&mut self, state: &State) {
+    /// Open the palette in the given mode
+    pub(crate) fn open(&mut self, state: &State, mode: PaletteMode) {
         self.is_open = true;
+        self.mode = mode;
         self.query.clear();
         self.cursor = 0;
         self.selected = 0;
@@ -51,6 +84,15 @@ impl CommandPalette {
 
     /// Update the filtered commands based on query
     pub(crate) fn update_filtered(&mut self, state: &State) {
+        if self.mode != PaletteMode::Commands {
+            self.filtered_commands = if self.mode == PaletteMode::Markers {
+                get_marker_commands(state, &self.query)
+            } else {
+                get_clean_scope_commands(state, &self.query)
+            };
+            self.selected = self.selected.min(self.filtered_commands.len().saturating_sub(1));
+            return;
+        }
         let all_commands = get_available_commands(state);
 
         if self.query.is_empty() {
@@ -82,53 +124,47 @@ impl CommandPalette {
 
     /// Insert a character at cursor position
     pub(crate) fn insert_char(&mut self, c: char, state: &State) {
-        self.query.insert(self.cursor, c);
-        self.cursor = self.cursor.saturating_add(c.len_utf8());
+        self.insert_str(c.encode_utf8(&mut [0; 4]), state);
+    }
+
+    /// Insert text at cursor position (pastes and IME commits, flattened to one line)
+    pub(crate) fn insert_str(&mut self, inserted: &str, state: &State) {
+        self.cursor = text::insert_at(&mut self.query, self.cursor, &inserted.replace(['\r', '\n'], " "));
         self.selected = 0; // Reset selection on query change
         self.update_filtered(state);
     }
 
-    /// Delete character before cursor
+    /// Delete the grapheme before the cursor
     pub(crate) fn backspace(&mut self, state: &State) {
         if self.cursor > 0 {
-            // Find the previous character boundary
-            let prev_boundary = self.query.get(..self.cursor).unwrap_or("").char_indices().last().map_or(0, |(i, _)| i);
-            let _r = self.query.remove(prev_boundary);
+            let end = text::floor_boundary(&self.query, self.cursor);
+            let prev_boundary = text::prev_boundary(&self.query, end);
+            self.query.replace_range(prev_boundary..end, "");
             self.cursor = prev_boundary;
             self.selected = 0;
             self.update_filtered(state);
         }
     }
 
-    /// Delete character at cursor
+    /// Delete the grapheme at the cursor
     pub(crate) fn delete(&mut self, state: &State) {
         if self.cursor < self.query.len() {
-            let _r = self.query.remove(self.cursor);
+            let start = text::floor_boundary(&self.query, self.cursor);
+            self.query.replace_range(start..text::next_boundary(&self.query, start), "");
+            self.cursor = start;
             self.selected = 0;
             self.update_filtered(state);
         }
     }
 
-    /// Move cursor left
+    /// Move cursor left by one grapheme
     pub(crate) fn cursor_left(&mut self) {
-        if self.cursor > 0 {
-            let prev_boundary = self.query.get(..self.cursor).unwrap_or("").char_indices().last().map_or(0, |(i, _)| i);
-            self.cursor = prev_boundary;
-        }
+        self.cursor = text::prev_boundary(&self.query, self.cursor);
     }
 
-    /// Move cursor right
+    /// Move cursor right by one grapheme
     pub(crate) fn cursor_right(&mut self) {
-        if self.cursor < self.query.len() {
-            let next_boundary = self
-                .query
-                .get(self.cursor..)
-                .unwrap_or("")
-                .char_indices()
-                .nth(1)
-                .map_or(self.query.len(), |(i, _)| self.cursor.saturating_add(i));
-            self.cursor = next_boundary;
-        }
+        self.cursor = text::next_boundary(&self.query, self.cursor);
     }
 
     /// Move selection up
@@ -153,6 +189,13 @@ impl CommandPalette {
         }
     }
 
+    /// Highlight the command with `id`, if it is listed.
+    pub(crate) fn select_id(&mut self, id: &str) {
+        if let Some(index) = self.filtered_commands.iter().position(|cmd| cmd.id == id) {
+            self.selected = index;
+        }
+    }
+
     /// Get the currently selected command
     pub(crate) fn get_selected(&self) -> Option<&PaletteCommand> {
         self.filtered_commands.get(self.selected)
@@ -165,11 +208,18 @@ impl CommandPalette {
         }
         let ir = self.to_ir();
         render_command_palette_from_ir(frame, &ir, frame.area());
+        cp_base::config::accessors::color_depth::downgrade_buffer(frame.buffer_mut());
     }
 
     /// Build IR snapshot from current palette state.
     fn to_ir(&self) -> PaletteOverlay {
+        let placeholder = if self.mode == PaletteMode::Markers {
+            "Search messages \u{b7} Alt+1 \u{2b50} Alt+2 \u{2753} Alt+3 \u{1f41b} \u{b7} Enter to jump"
+        } else {
+            "Type to search..."
+        };
         PaletteOverlay {
+            placeholder: placeholder.to_owned(),
             query: self.query.clone(),
             cursor: self.cursor,
             entries: self
@@ -231,15 +281,15 @@ fn render_input_line(frame: &mut Frame<'_>, palette: &PaletteOverlay, width: u16
     let available_width = width.to_usize().saturating_sub(4).saturating_sub(esc_hint.len());
 
     let input_display = if palette.query.is_empty() {
-        let hint_padding = available_width.saturating_add(esc_hint.len()).saturating_sub(17);
+        let hint_padding = available_width.saturating_add(esc_hint.len()).saturating_sub(palette.placeholder.width());
         vec![
             Span::styled(" > ", Style::default().fg(theme::accent())),
-            Span::styled("Type to search...", Style::default().fg(theme::text_muted())),
+            Span::styled(palette.placeholder.clone(), Style::default().fg(theme::text_muted())),
             Span::styled(format!("{esc_hint:>hint_padding$}"), Style::default().fg(theme::text_muted())),
         ]
     } else {
-        let (before, after) = palette.query.split_at(palette.cursor);
-        let query_len = before.len().saturating_add(after.len());
+        let (before, after) = palette.query.split_at(text::floor_boundary(&palette.query, palette.cursor));
+        let query_len = palette.query.width();
         let padding = available_width.saturating_sub(query_len);
         vec![
             Span::styled(" > ", Style::default().fg(theme::accent())),
@@ -307,3 +357,27 @@ fn render_result_lines(frame: &mut Frame<'_>, palette: &PaletteOverlay, width: u
     let results = Paragraph::new(result_lines).style(Style::default().bg(theme::bg_surface()));
     frame.render_widget(results, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_query_is_edited_by_grapheme() {
+        let state = State::default();
+        let mut palette = CommandPalette::new();
+        palette.insert_str("caf\u{65}\u{301}\r\n\u{1f468}\u{200d}\u{1f469}", &state);
+        assert_eq!(palette.query, "caf\u{65}\u{301}  \u{1f468}\u{200d}\u{1f469}");
+        palette.backspace(&state);
+        assert_eq!(palette.query, "caf\u{65}\u{301}  ");
+        palette.cursor_left();
+        palette.cursor_left();
+        palette.cursor_left();
+        assert_eq!(palette.cursor, 3);
+        palette.delete(&state);
+        assert_eq!(palette.query, "caf  ");
+        palette.cursor_right();
+        palette.insert_char('\u{e9}', &state);
+        assert_eq!(palette.query, "caf \u{e9} ");
+    }
+}
@@ -0,0 +1,266 @@
+//! First-run guided tour.
+//!
+//! A few cards shown over the UI the first time Context Pilot starts: the
+//! sidebar, panels, the Ctrl shortcuts, the configuration overlay and a
+//! sample tool-using exchange. Finishing or skipping it records `tour_done`
+//! in the global settings, so it shows once per machine; the palette's
+//! "Tour" command replays it.
+
+use cp_base::config::global;
+use cp_base::state::actions::TourMove;
+use cp_render::conversation::{TourAnchor, TourOverlay};
+use cp_render::{Block as IrBlock, Semantic, Span as S};
+use ratatui::prelude::{Frame, Line, Rect, Span, Style};
+use ratatui::widgets::{Block as RBlock, BorderType, Borders, Clear, Paragraph};
+
+use crate::state::State;
+use crate::ui::theme;
+
+/// Global setting recording that the tour was finished or skipped.
+const DONE_SETTING: &str = "tour_done";
+
+/// Card width, borders included.
+const CARD_WIDTH: u16 = 64;
+
+/// Where a step's card goes.
+#[derive(Clone, Copy)]
+enum Place {
+    /// Centered.
+    Center,
+    /// Beside the sidebar.
+    Sidebar,
+    /// Above the status bar.
+    Bottom,
+}
+
+/// One card. In `body`, `` `text` `` is a key and a line starting with `> `
+/// is part of the sample exchange.
+struct Step {
+    /// Card title.
+    title: &'static str,
+    /// Card placement.
+    place: Place,
+    /// Card text.
+    body: &'static [&'static str],
+}
+
+/// The tour, in order.
+const STEPS: &[Step] = &[
+    Step {
+        title: "Welcome to Context Pilot",
+        place: Place::Center,
+        body: &[
+            "Everything the model sees lives in panels you can inspect,",
+            "open and close. This short tour shows where things are.",
+        ],
+    },
+    Step {
+        title: "The sidebar",
+        place: Place::Sidebar,
+        body: &[
+            "On the left: every panel in the context, with its size in",
+            "tokens, and the budget bar at the bottom.",
+            "",
+            "`Tab` / `Shift+Tab` move between panels.",
+            "`x` closes the selected panel, `Shift+\u{2191}` / `Shift+\u{2193}` move it.",
+            "`Alt+1`...`Alt+9` bind and jump to quick slots.",
+            "`Ctrl+V` switches to the threads view and back.",
+        ],
+    },
+    Step {
+        title: "Panels",
+        place: Place::Sidebar,
+        body: &[
+            "Files, the directory tree, todos, memories, consoles, git:",
+            "each is a panel, sent to the model with every request.",
+            "",
+            "The model opens and closes panels with its tools; so can you.",
+            "Type a panel ID such as `P3` and press `Enter` to jump to it.",
+            "Closing what is no longer needed keeps requests cheap.",
+        ],
+    },
+    Step {
+        title: "Ctrl shortcuts",
+        place: Place::Center,
+        body: &[
+            "`Ctrl+P` command palette: every command and panel",
+            "`Ctrl+K` clean the context     `Ctrl+B` message markers",
+            "`Ctrl+S` standing instructions `Ctrl+I` search index",
+            "`Ctrl+Z` undo the draft        `Ctrl+U` / `Ctrl+D` history",
+            "`Ctrl+L` clear the conversation `Ctrl+Q` quit",
+            "`Esc` stops the model while it is answering.",
+        ],
+    },
+    Step {
+        title: "Configuration",
+        place: Place::Center,
+        body: &[
+            "`Ctrl+H` opens the configuration overlay:",
+            "",
+            "`1`...`8` provider, `a`...`d` model, `t` theme,",
+            "`\u{2190}` / `\u{2192}` the context budget and cleaning threshold,",
+            "`r` the background context optimizer, `k` spell checking.",
+            "",
+            "API keys come from the environment or a `.env` file;",
+            "run `tui doctor` in a shell to check them.",
+        ],
+    },
+    Step {
+        title: "A sample exchange",
+        place: Place::Bottom,
+        body: &[
+            "Type below; `Enter` adds a line, `Enter` on an empty line",
+            "sends. The model answers with text and tool calls:",
+            "",
+            "> You    Why does the build fail on main?",
+            "> Tool   console: cargo build \u{2192} P9 (exit 101)",
+            "> Tool   Open src/lib.rs \u{2192} P10",
+            "> Model  `parse_args` lost its `pub` in the last commit...",
+            "",
+            "Each tool result shows up as a panel in the sidebar.",
+        ],
+    },
+    Step {
+        title: "You're set",
+        place: Place::Center,
+        body: &[
+            "That's the tour. Replay it any time from the command",
+            "palette: `Ctrl+P`, then \"Tour\". `?` lists every key.",
+            "",
+            "`Enter` to start working.",
+        ],
+    },
+];
+
+/// The tour's first step when it has never been finished or skipped.
+pub(crate) fn first_run_step() -> Option<usize> {
+    global::get_setting(DONE_SETTING).is_none().then_some(0)
+}
+
+/// Apply a tour move. Leaving the tour records it as done.
+pub(crate) fn apply(state: &mut State, step: TourMove) {
+    let current = state.flags.overlays.tour_step;
+    let next = match step {
+        TourMove::Start => Some(0),
+        TourMove::Next => current.map(|s| s.saturating_add(1)).filter(|&s| s < STEPS.len()),
+        TourMove::Back => current.map(|s| s.saturating_sub(1)),
+        TourMove::Skip => None,
+    };
+    if current.is_some() && next.is_none() {
+        let _r = global::set_setting(DONE_SETTING, "true");
+    }
+    state.flags.overlays.tour_step = next;
+    state.flags.ui.dirty = true;
+}
+
+/// The IR of the step on screen, if any.
+pub(crate) fn build_tour_overlay(state: &State) -> Option<TourOverlay> {
+    let index = state.flags.overlays.tour_step?;
+    let step = STEPS.get(index)?;
+    let anchor = match step.place {
+        Place::Center => TourAnchor::Center,
+        Place::Sidebar => TourAnchor::Sidebar { width: state.view_mode.width() },
+        Place::Bottom => TourAnchor::Bottom,
+    };
+    Some(TourOverlay {
+        title: step.title.to_owned(),
+        body: step.body.iter().map(|line| body_line(line)).collect(),
+        step: index.saturating_add(1),
+        total: STEPS.len(),
+        anchor,
+    })
+}
+
+/// One body line: sample-exchange lines are quoted, `` `keys` `` stand out.
+fn body_line(line: &str) -> IrBlock {
+    if let Some(sample) = line.strip_prefix("> ") {
+        return IrBlock::line(vec![
+            S::styled("\u{2502} ".to_owned(), Semantic::Border),
+            S::styled(sample.replace('`', ""), Semantic::Code),
+        ]);
+    }
+    let spans = line
+        .split('`')
+        .enumerate()
+        .filter(|&(_, part)| !part.is_empty())
+        .map(|(i, part)| {
+            let semantic = if i.is_multiple_of(2) { Semantic::Default } else { Semantic::KeyHint };
+            S::styled(part.to_owned(), semantic)
+        })
+        .collect();
+    IrBlock::line(spans)
+}
+
+/// Render the tour card over `area`.
+pub(crate) fn render_tour_overlay(frame: &mut Frame<'_>, tour: &TourOverlay, area: Rect) {
+    let mut lines: Vec<Line<'_>> = vec![Line::default()];
+    for line in crate::ui::ir::blocks_to_lines(&tour.body) {
+        let mut padded = vec![Span::raw("  ")];
+        padded.extend(line.spans);
+        lines.push(Line::from(padded));
+    }
+    lines.push(Line::default());
+    let last = tour.step == tour.total;
+    let hint = if last { "Enter finish  \u{2190} back" } else { "\u{2192} next  \u{2190} back  Esc skip" };
+    lines.push(Line::from(Span::styled(format!("  {hint}"), Style::default().fg(theme::text_muted()))));
+
+    let height = u16::try_from(lines.len()).unwrap_or(u16::MAX).saturating_add(2);
+    let card = place(tour.anchor, area, CARD_WIDTH.min(area.width), height.min(area.height));
+    let title = format!(" {} \u{b7} {}/{} ", tour.title, tour.step, tour.total);
+    let block = RBlock::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme::accent()))
+        .style(Style::default().bg(theme::bg_surface()))
+        .title(Span::styled(title, Style::default().fg(theme::accent()).bold()));
+    frame.render_widget(Clear, card);
+    frame.render_widget(Paragraph::new(lines).block(block), card);
+}
+
+/// The card's rectangle for `anchor`.
+fn place(anchor: TourAnchor, area: Rect, width: u16, height: u16) -> Rect {
+    let center_x = area.x.saturating_add(area.width.saturating_sub(width).saturating_div(2));
+    let center_y = area.y.saturating_add(area.height.saturating_sub(height).saturating_div(2));
+    match anchor {
+        TourAnchor::Center => Rect::new(center_x, center_y, width, height),
+        TourAnchor::Sidebar { width: sidebar } => {
+            let x = area.x.saturating_add(sidebar).saturating_add(2).min(area.right().saturating_sub(width));
+            Rect::new(x, area.y.saturating_add(2), width, height)
+        }
+        TourAnchor::Bottom => {
+            let y = area.bottom().saturating_sub(height).saturating_sub(crate::infra::constants::STATUS_BAR_HEIGHT);
+            Rect::new(center_x, y.max(area.y), width, height)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_stay_on_the_tour_and_number_its_cards() {
+        let mut state = State::default();
+        apply(&mut state, TourMove::Start);
+        apply(&mut state, TourMove::Back);
+        assert_eq!(state.flags.overlays.tour_step, Some(0));
+        apply(&mut state, TourMove::Next);
+        apply(&mut state, TourMove::Next);
+        apply(&mut state, TourMove::Back);
+        let overlay = build_tour_overlay(&state);
+        assert!(overlay.is_some_and(|o| o.step == 2 && o.total == STEPS.len()));
+        state.flags.overlays.tour_step = Some(STEPS.len());
+        assert!(build_tour_overlay(&state).is_none());
+    }
+
+    #[test]
+    fn cards_are_placed_inside_the_area() {
+        let area = Rect::new(0, 0, 100, 40);
+        assert_eq!(place(TourAnchor::Center, area, 60, 10), Rect::new(20, 15, 60, 10));
+        assert_eq!(place(TourAnchor::Sidebar { width: 30 }, area, 60, 10), Rect::new(32, 2, 60, 10));
+        assert_eq!(place(TourAnchor::Sidebar { width: 80 }, area, 60, 10).right(), 100);
+        let bottom = place(TourAnchor::Bottom, area, 60, 10);
+        assert_eq!(bottom.bottom(), 40u16.saturating_sub(crate::infra::constants::STATUS_BAR_HEIGHT));
+        assert_eq!(place(TourAnchor::Bottom, Rect::new(0, 5, 100, 8), 60, 10).y, 5);
+    }
+}
@@ -1,25 +1,11 @@
 use cp_base::cast::Safe as _;
 use cp_base::cast::float_math;
+use cp_base::ui::text;
 use unicode_width::UnicodeWidthStr as _;
 
 /// Truncate a string to fit within `max_width` display columns, appending '…' if truncated.
 pub(crate) fn truncate_string(s: &str, max_width: usize) -> String {
-    if s.width() <= max_width {
-        s.to_owned()
-    } else {
-        let mut result = String::new();
-        let mut width = 0usize;
-        for c in s.chars() {
-            let cw = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
-            if width.saturating_add(cw).saturating_add(1) > max_width {
-                result.push('\u{2026}');
-                break;
-            }
-            result.push(c);
-            width = width.saturating_add(cw);
-        }
-        result
-    }
+    text::ellipsize(s, max_width, "\u{2026}")
 }
 
 /// Format a number with K/M suffix for compact display.
@@ -50,7 +36,10 @@ pub(crate) fn format_time_ago(delta_ms: u64) -> String {
     }
 }
 
-/// Word-wrap text to fit within a given width.
+/// Word-wrap text to fit within `max_width` display columns.
+///
+/// Widths are unicode display widths, and a word longer than the line is
+/// broken across lines, so every returned line fits the viewport as drawn.
 pub(crate) fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
     if max_width == 0 {
         return vec![text.to_owned()];
@@ -60,22 +49,21 @@ pub(crate) fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
     let mut current_line = String::new();
     let mut current_width = 0usize;
 
-    for word in text.split_whitespace() {
-        let word_width = word.chars().count();
+    for word in text.split_whitespace().flat_map(|w| text::wrap_graphemes(w, max_width)) {
+        let word_width = word.width();
 
         if current_width == 0 {
             // First word on line
-            word.clone_into(&mut current_line);
+            current_line = word;
             current_width = word_width;
         } else if current_width.saturating_add(1).saturating_add(word_width) <= max_width {
             // Word fits on current line
             current_line.push(' ');
-            current_line.push_str(word);
+            current_line.push_str(&word);
             current_width = current_width.saturating_add(1).saturating_add(word_width);
         } else {
             // Word doesn't fit, start new line
-            lines.push(std::mem::take(&mut current_line));
-            word.clone_into(&mut current_line);
+            lines.push(std::mem::replace(&mut current_line, word));
             current_width = word_width;
         }
     }
@@ -379,3 +367,16 @@ impl TypewriterBuffer {
         Some(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_lines_fit_the_display_width() {
+        let wide = "\u{65e5}\u{672c}\u{8a9e}\u{306e}\u{30c6}\u{30ad}\u{30b9}\u{30c8} \u{77ed}\u{3044}";
+        assert!(wrap_text(wide, 10).iter().all(|l| l.width() <= 10));
+        let long_word = format!("see {}", "x".repeat(25));
+        assert_eq!(wrap_text(&long_word, 10), ["see", "xxxxxxxxxx", "xxxxxxxxxx", "xxxxx"]);
+    }
+}
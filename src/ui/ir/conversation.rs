@@ -6,11 +6,13 @@
 
 use cp_render::conversation::{
     Autocomplete, AutocompleteEntry, Conversation, HistorySection, InputArea, Message as IrMessage, Overlay,
-    PerfBudgetBar, PerfMeiliStats, PerfOp, PerfOverlay, StreamingTool, ToolResultPreview, ToolUsePreview,
+    PerfBudgetBar, PerfCachePanel, PerfCaches, PerfLatency, PerfMeiliStats, PerfOp, PerfOverlay, StreamingTool,
+    ToolResultPreview, ToolUsePreview,
 };
 use cp_render::{Block, Semantic};
 
 use crate::state::{Kind, MsgKind, MsgStatus, State, ToolResultRecord, ToolUseRecord};
+use cp_base::cast::Safe as _;
 use cp_base::cast::float_math;
 
 /// Build the conversation region from application state.
@@ -114,12 +116,7 @@ fn tool_result_to_ir(tr: &ToolResultRecord) -> ToolResultPreview {
     let source = tr.display.as_deref().unwrap_or(&tr.content);
 
     // Truncate content for summary
-    let summary = if source.len() > 80 {
-        let boundary = source.floor_char_boundary(77);
-        format!("{}...", source.get(..boundary).unwrap_or(""))
-    } else {
-        source.to_owned()
-    };
+    let summary = cp_base::ui::text::ellipsize(source, 80, "...");
 
     ToolResultPreview { tool_name: tr.tool_name.clone(), summary, success: !tr.is_error }
 }
@@ -149,7 +146,7 @@ fn build_input(state: &State) -> InputArea {
 
 // ── Overlays ─────────────────────────────────────────────────────────
 
-/// Build overlay stack from state (question form, autocomplete).
+/// Build overlay stack from state (question form, autocomplete, spelling).
 #[must_use]
 pub(crate) fn build_overlays(state: &State) -> Vec<Overlay> {
     let mut overlays = Vec::new();
@@ -161,6 +158,13 @@ pub(crate) fn build_overlays(state: &State) -> Vec<Overlay> {
         overlays.push(Overlay::Autocomplete(build_autocomplete(ac)));
     }
 
+    // Spelling suggestions overlay
+    if let Some(popup) = state.get_ext::<cp_base::ui::spell::Popup>()
+        && popup.active
+    {
+        overlays.push(Overlay::Autocomplete(build_spell_popup(state, popup)));
+    }
+
     // Config overlay
     if state.flags.config.config_view {
         overlays.push(Overlay::Config(crate::ui::help::config_overlay::build_config_overlay(state)));
@@ -176,6 +180,12 @@ pub(crate) fn build_overlays(state: &State) -> Vec<Overlay> {
         overlays.push(Overlay::SearchIndex(Box::new(crate::ui::search_overlay::build_search_index_overlay(state))));
     }
 
+    // Keybinding reference, then the guided tour on top of everything
+    overlays.extend(
+        state.flags.overlays.key_help.then(crate::ui::help::keymap::build_key_help_overlay).map(Overlay::KeyHelp),
+    );
+    overlays.extend(crate::ui::help::tour::build_tour_overlay(state).map(Overlay::Tour));
+
     overlays
 }
 
@@ -185,14 +195,17 @@ fn build_autocomplete(ac: &cp_base::state::autocomplete::Suggestions) -> Autocom
     let selected_relative = ac.selected.saturating_sub(ac.scroll_offset);
     let entries = visible
         .iter()
-        .map(|e| AutocompleteEntry {
-            label: e.name.clone(),
-            is_dir: e.is_dir,
-            icon: if e.is_dir { "\u{1f4c1}".into() } else { "\u{1f4c4}".into() },
+        .map(|e| {
+            let (label, icon) = e.panel.as_ref().map_or_else(
+                || (e.name.clone(), if e.is_dir { "\u{1f4c1}" } else { "\u{1f4c4}" }),
+                |title| (format!("{} \u{b7} {title}", e.name), "\u{1f4cb}"),
+            );
+            AutocompleteEntry { label, is_dir: e.is_dir, icon: icon.into() }
         })
         .collect();
 
     Autocomplete {
+        title: None,
         query: ac.query.clone(),
         entries,
         selected_index: selected_relative,
@@ -202,6 +215,26 @@ fn build_autocomplete(ac: &cp_base::state::autocomplete::Suggestions) -> Autocom
     }
 }
 
+/// Build the spelling suggestions popup, reusing the autocomplete layout.
+fn build_spell_popup(state: &State, popup: &cp_base::ui::spell::Popup) -> Autocomplete {
+    let entries = popup
+        .suggestions
+        .iter()
+        .map(|s| AutocompleteEntry { label: s.clone(), is_dir: false, icon: String::new() })
+        .collect();
+    Autocomplete {
+        title: Some(format!(" Spelling: {} \u{2014} Enter to replace, Esc to close ", popup.word)),
+        query: popup.word.clone(),
+        entries,
+        selected_index: popup.selected,
+        dir_prefix: String::new(),
+        total_matches: popup.suggestions.len(),
+        input_visual_lines: state
+            .get_ext::<cp_base::state::autocomplete::Suggestions>()
+            .map_or(2, |ac| ac.input_visual_lines),
+    }
+}
+
 // ── Perf overlay ─────────────────────────────────────────────────────
 
 /// Frame budget for 60fps (milliseconds).
@@ -323,6 +356,46 @@ fn build_perf_budget_bars(frame_avg_ms: f64) -> Vec<PerfBudgetBar> {
     vec![build_bar("60fps", FRAME_BUDGET_60FPS), build_bar("30fps", FRAME_BUDGET_30FPS)]
 }
 
+/// Build the per-provider latency rows (p50/p90 of recent requests).
+fn build_perf_latency(state: &State) -> Vec<PerfLatency> {
+    use crate::ui::perf::latency::{format_ms, summaries};
+    let pair = |[p50, p90]: [u64; 2]| format!("{}/{}", format_ms(p50), format_ms(p90));
+    summaries(state)
+        .into_iter()
+        .map(|row| PerfLatency {
+            provider: row.provider,
+            requests: row.requests,
+            ttft: pair(row.ttft),
+            model: pair(row.stream),
+            tools: pair(row.tools),
+        })
+        .collect()
+}
+
+/// Largest panel caches listed in the perf overlay.
+const PERF_CACHE_ROWS: usize = 5;
+
+/// Build the panel cache memory breakdown.
+fn build_perf_caches(state: &State) -> PerfCaches {
+    use crate::modules::overview::cache_budget;
+    use crate::ui::search_overlay::format_bytes;
+    let (rows, evicted) = cache_budget::usage(state);
+    let held: usize = rows.iter().map(|row| row.bytes).sum();
+    PerfCaches {
+        held: format_bytes(held.to_u64()),
+        budget: cache_budget::budget(state).label(),
+        evicted,
+        panels: rows
+            .into_iter()
+            .take(PERF_CACHE_ROWS)
+            .map(|row| PerfCachePanel {
+                label: format!("{} {}", row.id, row.name),
+                size: format_bytes(row.bytes.to_u64()),
+            })
+            .collect(),
+    }
+}
+
 /// Build the perf overlay IR data from the perf metrics snapshot.
 fn build_perf_overlay(state: &State) -> PerfOverlay {
     use crate::ui::perf::PERF;
@@ -348,5 +421,7 @@ fn build_perf_overlay(state: &State) -> PerfOverlay {
         budget_bars: build_perf_budget_bars(snapshot.frame_avg_ms),
         sparkline: snapshot.frame_times_ms,
         operations,
+        latency: build_perf_latency(state),
+        caches: build_perf_caches(state),
     }
 }
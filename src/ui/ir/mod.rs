@@ -49,16 +49,18 @@ pub(crate) mod render_panel {
         frame.render_widget(block, inner_area);
 
         // Resolve content from IR blocks
-        let text: Vec<Line<'static>> =
+        let mut text: Vec<Line<'static>> =
             if panel_content.blocks.is_empty() { Vec::new() } else { super::blocks_to_lines(&panel_content.blocks) };
+        let images = reserve_images(&mut text, content_area);
 
         // Calculate scroll bounds from wrapped content height
         let viewport_width = content_area.width.to_usize();
         let viewport_height = content_area.height.to_usize();
-        let content_height: usize = {
+        let text_heights: Vec<usize> = {
             let _guard = crate::profile!("panel::scroll_calc");
-            text.iter().map(|line| count_wrapped_lines(line, viewport_width)).sum()
+            text.iter().map(|line| count_wrapped_lines(line, viewport_width)).collect()
         };
+        let content_height: usize = text_heights.iter().sum();
         let max_scroll = content_height.saturating_sub(viewport_height).to_f32();
         state.max_scroll = max_scroll;
         state.scroll_offset = state.scroll_offset.clamp(0.0, max_scroll);
@@ -75,6 +77,43 @@ pub(crate) mod render_panel {
             let _guard = crate::profile!("panel::frame_render");
             frame.render_widget(paragraph, content_area);
         }
+        queue_images(images, &text_heights, content_area, state.scroll_offset.round().to_usize());
+    }
+
+    /// An image reserved in the panel text: first blank line, size in cells, image.
+    type Reserved = (usize, u16, u16, cp_graphics::source::Image);
+
+    /// Insert blank rows under every image reference whose image is ready
+    /// (terminal graphics only — otherwise the text is left alone).
+    fn reserve_images(text: &mut Vec<Line<'static>>, area: Rect) -> Vec<Reserved> {
+        if !cp_graphics::enabled() {
+            return Vec::new();
+        }
+        let plain: Vec<String> = text.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect();
+        let mut reserved = Vec::new();
+        let mut inserted = 0usize;
+        for anchor in cp_graphics::source::scan(&plain) {
+            let Some(image) = cp_graphics::source::resolve(&anchor.source) else { continue };
+            let (cols, rows) = cp_graphics::fit(&image, area.width, area.height.saturating_sub(1));
+            let at = anchor.line.saturating_add(1).saturating_add(inserted);
+            drop(text.splice(at..at, std::iter::repeat_n(Line::default(), rows.to_usize())));
+            inserted = inserted.saturating_add(rows.to_usize());
+            reserved.push((at, cols, rows, image));
+        }
+        reserved
+    }
+
+    /// Queue the reserved images lying fully inside the viewport.
+    fn queue_images(images: Vec<Reserved>, heights: &[usize], area: Rect, scroll: usize) {
+        for (at, cols, rows, image) in images {
+            let top: usize = heights.iter().take(at).sum();
+            let Some(row) = top.checked_sub(scroll) else { continue };
+            if row.saturating_add(rows.to_usize()) > area.height.to_usize() {
+                continue;
+            }
+            let y = area.y.saturating_add(row.to_u16());
+            cp_graphics::queue(cp_graphics::Placement { x: area.x, y, cols, rows, image });
+        }
     }
 }
 /// Sidebar adapter: renders [`cp_render::frame::Sidebar`] → ratatui.
@@ -84,7 +123,7 @@ mod render_sidebar_stats;
 /// Status bar adapter: renders [`cp_render::frame::StatusBar`] → ratatui.
 pub(crate) mod render_status_bar;
 /// Sidebar region builder.
-mod sidebar;
+pub(crate) mod sidebar;
 
 use cp_render::{Align, Semantic, Span as IrSpan, TreeNode};
 use ratatui::prelude::{Line, Span, Style};
@@ -108,6 +147,9 @@ pub(crate) fn semantic_to_style(semantic: Semantic) -> Style {
         Semantic::Error | Semantic::DiffRemove => Style::default().fg(theme::error()),
         Semantic::Code => Style::default().fg(theme::text_secondary()),
         Semantic::Border => Style::default().fg(theme::border()),
+        Semantic::Misspelled => {
+            Style::default().fg(theme::text()).underline_color(theme::error()).add_modifier(Modifier::UNDERLINED)
+        }
         // Default and Bold render as plain text foreground.
         Semantic::Default | Semantic::Bold => Style::default().fg(theme::text()),
     }
@@ -404,6 +446,7 @@ use cp_render::frame::{Frame as IrFrame, PanelContent};
 use crate::app::panels;
 use crate::state::State;
 use cp_base::panels::now_ms;
+use cp_base::state::data::review::PANEL_NOTE_META;
 
 /// Build a complete frame snapshot from application state.
 ///
@@ -423,8 +466,9 @@ pub(crate) fn build_frame(state: &State) -> IrFrame {
 
 /// Build the active panel content from application state.
 ///
-/// Calls `blocks()` on the panel for the currently selected context element.
-/// Returns a [`PanelContent`] with title, blocks, and optional refresh timestamp.
+/// Calls `blocks()` on the panel for the currently selected context element,
+/// under the user's private note on it, if any. Returns a [`PanelContent`]
+/// with title, blocks, and optional refresh timestamp.
 #[must_use]
 fn build_active_panel(state: &State) -> PanelContent {
     let context_type = state.context.get(state.selected_context).map_or_else(
@@ -434,7 +478,16 @@ fn build_active_panel(state: &State) -> PanelContent {
 
     let panel = panels::get_panel(&context_type);
     let title = panel.title(state);
-    let blocks = panel.blocks(state);
+    let note = state.context.get(state.selected_context).and_then(|c| c.get_meta_str(PANEL_NOTE_META));
+    let mut blocks: Vec<cp_render::Block> = note
+        .into_iter()
+        .flat_map(str::lines)
+        .map(|line| cp_render::Block::line(vec![IrSpan::styled(format!(" \u{270e} {line}"), Semantic::Muted).italic()]))
+        .collect();
+    if !blocks.is_empty() {
+        blocks.push(cp_render::Block::empty());
+    }
+    blocks.extend(panel.blocks(state));
 
     // Build "refreshed N ago" for dynamic panels
     let refreshed_ago =
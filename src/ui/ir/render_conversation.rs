@@ -12,6 +12,7 @@ use crate::state::State;
 use crate::ui::theme;
 use cp_base::cast::Safe as _;
 use cp_base::cast::float_math;
+use cp_base::state::data::review::MessageJump;
 
 /// Render the conversation panel with IR-controlled chrome and scrollbar.
 ///
@@ -59,6 +60,12 @@ pub(crate) fn render_conversation_from_ir(
     let max_scroll = content_height.saturating_sub(viewport_height).to_f32();
     state.max_scroll = max_scroll;
 
+    // Jump to a message picked in the markers palette (one-shot)
+    if let Some(line) = state.get_ext_mut::<MessageJump>().map(std::mem::take).and_then(|jump| jump.line) {
+        state.scroll_offset = line.to_f32();
+        state.flags.stream.user_scrolled = true;
+    }
+
     // Auto-scroll: snap to bottom unless user manually scrolled up
     if state.flags.stream.user_scrolled && state.scroll_offset.to_f64() >= float_math::sub(max_scroll.to_f64(), 0.5) {
         state.flags.stream.user_scrolled = false;
@@ -114,7 +121,9 @@ pub(crate) fn render_autocomplete_if_active(
             | cp_render::conversation::Overlay::Perf(_)
             | cp_render::conversation::Overlay::Config(_)
             | cp_render::conversation::Overlay::CommandPalette(_)
-            | cp_render::conversation::Overlay::SearchIndex(_) => None,
+            | cp_render::conversation::Overlay::SearchIndex(_)
+            | cp_render::conversation::Overlay::Tour(_)
+            | cp_render::conversation::Overlay::KeyHelp(_) => None,
         })
     }) else {
         return;
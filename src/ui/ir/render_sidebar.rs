@@ -3,7 +3,7 @@
 //! Consumes the pre-built IR snapshot instead of reading application
 //! state directly.
 
-use cp_render::frame::{Sidebar, SidebarEntry, SidebarMode, TokenBar, TokenStats};
+use cp_render::frame::{Sidebar, SidebarEntry, SidebarMode, TaskCard, TokenBar, TokenDetail, TokenStats};
 use ratatui::prelude::{Constraint, Direction, Frame, Layout, Line, Rect, Span, Style};
 use ratatui::widgets::Paragraph;
 
@@ -65,23 +65,22 @@ fn render_normal(frame: &mut Frame<'_>, sidebar: &Sidebar, area: Rect) {
     if let Some(tb) = sidebar.token_bar.as_ref() {
         render_token_bar_box(&mut lines, tb, cw);
     }
+    if let Some(heat) = sidebar.heat_map.as_ref() {
+        super::render_sidebar_stats::render_heat_map(&mut lines, heat, cw);
+    }
 
     // Separate fixed (id is empty for conversation, or is_fixed) from dynamic entries
     let (fixed_entries, dynamic_entries): (Vec<_>, Vec<_>) = sidebar.entries.iter().partition(|e| e.fixed);
 
     // Render fixed entries (conversation first, then P1-P9)
     for entry in &fixed_entries {
-        render_normal_entry(&mut lines, entry, cw);
+        render_entry(&mut lines, entry, sidebar.token_detail, cw);
     }
 
     // Dynamic entries with pagination
-    render_dynamic_entries(&mut lines, &dynamic_entries, cw);
+    render_dynamic_entries(&mut lines, &dynamic_entries, sidebar.token_detail, cw);
 
-    // PR card
-    if let Some(pr) = sidebar.pr_card.as_ref() {
-        lines.push(Line::from(""));
-        render_pr_card(&mut lines, pr, cw);
-    }
+    render_cards(&mut lines, sidebar, cw);
 
     // Token stats (rendered with rounded border)
     if let Some(stats) = sidebar.token_stats.as_ref() {
@@ -96,9 +95,26 @@ fn render_normal(frame: &mut Frame<'_>, sidebar: &Sidebar, area: Rect) {
     render_help_hints(frame, sidebar, base_style, sidebar_layout.get(1).copied());
 }
 
+/// Render the current-task and PR cards under the context list, when present.
+fn render_cards(lines: &mut Vec<Line<'static>>, sidebar: &Sidebar, cw: usize) {
+    if let Some(task) = sidebar.task_card.as_ref() {
+        lines.push(Line::from(""));
+        render_task_card(lines, task, cw);
+    }
+    if let Some(pr) = sidebar.pr_card.as_ref() {
+        lines.push(Line::from(""));
+        render_pr_card(lines, pr, cw);
+    }
+}
+
 /// Render the paginated dynamic-entry section: page-indicator separator plus the
 /// current page's entries. No-op when there are no dynamic entries.
-fn render_dynamic_entries(lines: &mut Vec<Line<'static>>, dynamic_entries: &[&SidebarEntry], cw: usize) {
+fn render_dynamic_entries(
+    lines: &mut Vec<Line<'static>>,
+    dynamic_entries: &[&SidebarEntry],
+    detail: Option<TokenDetail>,
+    cw: usize,
+) {
     let total_dynamic = dynamic_entries.len();
     if total_dynamic == 0 {
         return;
@@ -126,7 +142,15 @@ fn render_dynamic_entries(lines: &mut Vec<Line<'static>>, dynamic_entries: &[&Si
     let page_start = current_page.saturating_mul(MAX_DYNAMIC_PER_PAGE);
     let page_end = page_start.saturating_add(MAX_DYNAMIC_PER_PAGE).min(total_dynamic);
     for entry in dynamic_entries.get(page_start..page_end).unwrap_or(&[]) {
-        render_normal_entry(lines, entry, cw);
+        render_entry(lines, entry, detail, cw);
+    }
+}
+
+/// Render an entry, followed by the token breakdown when it is the selected one.
+fn render_entry(lines: &mut Vec<Line<'static>>, entry: &SidebarEntry, detail: Option<TokenDetail>, cw: usize) {
+    render_normal_entry(lines, entry, cw);
+    if let Some(shown) = detail.filter(|_| entry.active) {
+        super::render_sidebar_stats::render_token_detail(lines, shown, cw);
     }
 }
 
@@ -363,6 +387,48 @@ fn bar_cell_color(i: usize, geom: &BarGeom) -> ratatui::style::Color {
     }
 }
 
+// ── Task card ────────────────────────────────────────────────────────
+
+/// Compact elapsed time: `42s`, `4m05s`, `1h02m`.
+fn format_elapsed(secs: u64) -> String {
+    let hours = secs.checked_div(3600).unwrap_or(0);
+    let minutes = secs.checked_div(60).and_then(|m| m.checked_rem(60)).unwrap_or(0);
+    if hours > 0 {
+        format!("{hours}h{minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m{:02}s", secs.checked_rem(60).unwrap_or(0))
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Render the current-task card: active todo, then turns · elapsed · tokens,
+/// then acceptance criteria met.
+fn render_task_card(lines: &mut Vec<Line<'static>>, task: &TaskCard, cw: usize) {
+    let color = if task.streaming { theme::accent() } else { theme::text_muted() };
+    let todo = task.todo.as_deref().unwrap_or("no active todo");
+    let title = crate::ui::helpers::truncate_string(todo, cw.saturating_sub(7));
+    lines.push(padded(vec![
+        Span::styled("Task ", Style::default().fg(color).bold()),
+        Span::styled(title, Style::default().fg(theme::text_secondary())),
+    ]));
+    let detail = format!(
+        "{} turns \u{b7} {} \u{b7} {} tok",
+        task.tool_turns,
+        format_elapsed(task.elapsed_secs),
+        format_number(task.tokens.to_usize())
+    );
+    lines.push(padded(vec![Span::styled(detail, Style::default().fg(theme::text_muted()))]));
+    if let Some((met, total)) = task.goals {
+        let goal_color = if met >= total { theme::success() } else { theme::warning() };
+        lines.push(padded(vec![
+            Span::styled("Goals ", Style::default().fg(goal_color).bold()),
+            Span::styled(format!("{met}/{total} met"), Style::default().fg(theme::text_secondary())),
+        ]));
+    }
+    lines.push(padded(vec![Span::styled(chars::HORIZONTAL.repeat(cw), Style::default().fg(theme::border()))]));
+}
+
 // ── PR card ──────────────────────────────────────────────────────────
 
 /// Render the PR summary card.
@@ -2,9 +2,10 @@
 //!
 //! Extracted from `render_sidebar.rs` to stay within the 500-line limit.
 //! Renders the hit/miss/output table, cache breakpoint gauge, and total
-//! cost — wrapped in rounded borders (╭╮╰╯).
+//! cost — wrapped in rounded borders (╭╮╰╯) — the selected panel's token
+//! breakdown, and the dev-mode heat map of the next request.
 
-use cp_render::frame::TokenStats;
+use cp_render::frame::{HeatMap, TokenDetail, TokenStats};
 use ratatui::prelude::{Line, Span, Style};
 use unicode_width::UnicodeWidthStr as _;
 
@@ -13,6 +14,7 @@ use cp_base::cast::Safe as _;
 use cp_base::cast::float_math;
 
 use super::render_sidebar::padded;
+use super::semantic_to_style;
 
 /// Format an optional cost cell (`$K` tier ≥1000, 3dp <0.01, 2dp <1, else 1dp),
 /// empty string for `None`.
@@ -219,3 +221,81 @@ fn build_bp_gauge(alive_bp_positions: &[u16], gauge_width: usize) -> Vec<Span<'s
     }
     gauge_spans
 }
+
+/// Render the selected panel's token breakdown as a small tree under its
+/// entry: boilerplate, content, pages left out, and its share of the budget.
+pub(super) fn render_token_detail(lines: &mut Vec<Line<'static>>, detail: TokenDetail, cw: usize) {
+    let mut rows = vec![("header", detail.header), ("content", detail.content)];
+    if detail.truncated > 0 {
+        rows.push(("not sent", detail.truncated));
+    }
+    for (label, tokens) in rows {
+        let value = format_number(tokens.to_usize());
+        let fill = cw.saturating_sub(label.len()).saturating_sub(value.len()).saturating_sub(5);
+        lines.push(padded(vec![
+            Span::styled("   \u{251c} ", Style::default().fg(theme::border_muted())),
+            Span::styled(format!("{label}{}", " ".repeat(fill)), Style::default().fg(theme::text_muted())),
+            Span::styled(value, Style::default().fg(theme::text_secondary())),
+        ]));
+    }
+    let sent = detail.header.saturating_add(detail.content);
+    let share = if detail.budget > 0 { float_math::percent(f64::from(sent), f64::from(detail.budget)) } else { 0.0f64 };
+    lines.push(padded(vec![
+        Span::styled("   \u{2514} ", Style::default().fg(theme::border_muted())),
+        Span::styled(format!("{share:.1}%"), Style::default().fg(theme::accent())),
+        Span::styled(" of the budget", Style::default().fg(theme::text_muted())),
+    ]));
+}
+
+/// Render the heat map: one bar where each message or panel takes columns in
+/// proportion to its token share, coloured by heat (shades alternate so
+/// neighbours stay apart), then the three heaviest with their shares.
+pub(super) fn render_heat_map(lines: &mut Vec<Line<'static>>, heat: &HeatMap, cw: usize) {
+    let mut strip = Vec::new();
+    let mut start = 0usize;
+    let mut cumulative = 0usize;
+    for (i, cell) in heat.cells.iter().enumerate() {
+        cumulative = cumulative.saturating_add(usize::from(cell.permille));
+        let end = cumulative.saturating_mul(cw).checked_div(1000).unwrap_or(0).min(cw);
+        let shade = if i.is_multiple_of(2) { chars::BLOCK_FULL } else { "\u{2593}" };
+        strip.push(Span::styled(shade.repeat(end.saturating_sub(start)), semantic_to_style(cell.semantic)));
+        start = start.max(end);
+    }
+    lines.push(padded(strip));
+
+    let mut heaviest: Vec<_> = heat.cells.iter().collect();
+    heaviest.sort_by_key(|c| std::cmp::Reverse(c.tokens));
+    let mut top = Vec::new();
+    for cell in heaviest.into_iter().take(3) {
+        let pct = u32::from(cell.permille).checked_div(10).unwrap_or(0);
+        top.push(Span::styled(format!("{} ", cell.label), Style::default().fg(theme::text_secondary())));
+        top.push(Span::styled(format!("{pct}%  "), semantic_to_style(cell.semantic)));
+    }
+    lines.push(padded(top));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_render::Semantic;
+    use cp_render::frame::HeatCell;
+
+    /// A heat cell of `tokens` making up `permille` of the request.
+    fn cell(label: &str, tokens: u32, permille: u16) -> HeatCell {
+        HeatCell { label: label.to_owned(), tokens, permille, semantic: Semantic::Muted }
+    }
+
+    #[test]
+    fn heat_map_columns_follow_the_shares_and_fill_the_width() {
+        let heat =
+            HeatMap { cells: vec![cell("sys", 333, 333), cell("P1", 334, 334), cell("U1", 333, 333)], total: 1000 };
+        let mut lines = Vec::new();
+        render_heat_map(&mut lines, &heat, 10);
+        let widths: Vec<usize> =
+            lines.first().map_or_else(Vec::new, |l| l.spans.iter().skip(1).map(|s| s.content.width()).collect());
+        assert_eq!(widths, vec![3, 3, 4]);
+        let top: String =
+            lines.get(1).map_or_else(String::new, |l| l.spans.iter().map(|s| s.content.as_ref()).collect());
+        assert!(top.starts_with(" P1 33%"));
+    }
+}
@@ -11,9 +11,10 @@ use ratatui::prelude::{Frame, Line, Rect, Span, Style};
 use ratatui::widgets::Paragraph;
 
 use crate::infra::config::normalize_icon;
-use crate::state::State;
+use crate::state::{State, StreamPhase};
 use crate::ui::{helpers::spinner, theme};
 use cp_base::cast::Safe as _;
+use cp_base::state::flags::StreamState;
 
 /// Push a card span followed by a base-style separator space.
 fn push_card(spans: &mut Vec<Span<'static>>, label: String, style: Style, base: Style) {
@@ -21,8 +22,19 @@ fn push_card(spans: &mut Vec<Span<'static>>, label: String, style: Style, base:
     spans.push(Span::styled(" ", base));
 }
 
-/// Retry + loading badges (both spinner-driven counters).
+/// Offline + retry + stalled + loading badges (spinner/clock-driven counters).
 fn push_retry_loading(spans: &mut Vec<Span<'static>>, status: &StatusBar, spin: &str, base: Style) {
+    if status.offline {
+        push_card(spans, " OFFLINE ".into(), Style::default().fg(theme::bg_base()).bg(theme::error()).bold(), base);
+    }
+    if status.stalled_secs > 0 {
+        push_card(
+            spans,
+            format!(" STALLED {}s ", status.stalled_secs),
+            Style::default().fg(theme::bg_base()).bg(theme::warning()).bold(),
+            base,
+        );
+    }
     if status.retry_count > 0 {
         push_card(
             spans,
@@ -92,6 +104,18 @@ fn push_git(spans: &mut Vec<Span<'static>>, status: &StatusBar, base: Style) {
     }
 }
 
+/// Voice input card (recording / transcribing), right after the badge.
+fn push_voice(spans: &mut Vec<Span<'static>>, status: &StatusBar, base: Style) {
+    if let Some(voice) = status.voice.as_ref() {
+        push_card(
+            spans,
+            format!(" \u{1f399} {voice} "),
+            Style::default().fg(theme::bg_base()).bg(theme::error()).bold(),
+            base,
+        );
+    }
+}
+
 /// Auto-continue + reverie + queue + think cards.
 fn push_activity_cards(spans: &mut Vec<Span<'static>>, status: &StatusBar, spin: &str, base: Style) {
     if let Some(ac) = status.auto_continue.as_ref() {
@@ -134,7 +158,11 @@ fn push_activity_cards(spans: &mut Vec<Span<'static>>, status: &StatusBar, spin:
 
 /// Render the status bar from its IR snapshot.
 pub(crate) fn render_status_bar_from_ir(frame: &mut Frame<'_>, status: &StatusBar, area: Rect) {
-    let base_style = Style::default().bg(theme::bg_base()).fg(theme::text_muted());
+    let base_style = if status.flash {
+        Style::default().bg(theme::warning()).fg(theme::bg_base())
+    } else {
+        Style::default().bg(theme::bg_base()).fg(theme::text_muted())
+    };
     let spin = spinner();
 
     let mut spans = vec![Span::styled(" ", base_style)];
@@ -149,6 +177,7 @@ pub(crate) fn render_status_bar_from_ir(frame: &mut Frame<'_>, status: &StatusBa
     spans.push(Span::styled(badge_label, Style::default().fg(fg_badge).bg(bg_badge).bold()));
     spans.push(Span::styled(" ", base_style));
 
+    push_voice(&mut spans, status, base_style);
     push_retry_loading(&mut spans, status, spin, base_style);
     push_stop_agent_skills(&mut spans, status, base_style);
     push_git(&mut spans, status, base_style);
@@ -190,7 +219,8 @@ fn badge_colors(semantic: Semantic, _spin: &str) -> (ratatui::style::Color, rata
         | Semantic::DiffRemove
         | Semantic::Header
         | Semantic::Border
-        | Semantic::Bold => (theme::bg_base(), theme::text_muted()),
+        | Semantic::Bold
+        | Semantic::Misspelled => (theme::bg_base(), theme::text_muted()),
     }
 }
 
@@ -216,16 +246,30 @@ pub(crate) fn build_status_bar(state: &State) -> StatusBar {
         stop_reason: build_stop_reason(state),
         retry_count: state.api_retry_count.to_u8(),
         max_retries: crate::infra::constants::MAX_API_RETRIES.to_u8(),
+        stalled_secs: stalled_secs(&state.flags.stream, crate::app::panels::now_ms()),
+        offline: state.flags.lifecycle.offline_since_ms.is_some(),
         loading_count: state
             .context
             .iter()
-            .filter(|c| c.cached_content.is_none() && c.context_type.needs_cache())
+            .filter(|c| c.cached_content.is_none() && c.context_type.needs_cache() && !c.awaits_load())
             .count()
             .to_u16(),
         input_char_count: state.input.chars().count().to_u32(),
+        flash: state.flags.overlays.cue_flash_ms > 0,
+        voice: crate::infra::voice::status(state).map(str::to_owned),
     }
 }
 
+/// Seconds the current stream has gone without a delta at `now_ms`, once past
+/// its stall threshold (0 otherwise, and while tools run).
+fn stalled_secs(stream: &StreamState, now_ms: u64) -> u32 {
+    if stream.phase != StreamPhase::Receiving || stream.stall_secs == 0 {
+        return 0;
+    }
+    let silent = now_ms.saturating_sub(stream.last_delta_ms).checked_div(1000).unwrap_or(0);
+    if silent >= stream.stall_secs { silent.to_u32() } else { 0 }
+}
+
 // ── Primary badge ────────────────────────────────────────────────────
 
 /// The primary status badge (STREAMING / TOOLING / READY / BLOCKED / etc.).
@@ -362,3 +406,28 @@ fn build_think(state: &State) -> Option<ThinkCard> {
     }
     Some(ThinkCard { balance: ts.consecutive_count })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A receiving stream whose last delta arrived at t=0 with a 30s threshold.
+    fn receiving() -> StreamState {
+        StreamState { phase: StreamPhase::Receiving, last_delta_ms: 0, stall_secs: 30, ..StreamState::default() }
+    }
+
+    #[test]
+    fn stall_badge_appears_once_past_the_threshold() {
+        assert_eq!(stalled_secs(&receiving(), 29_999), 0);
+        assert_eq!(stalled_secs(&receiving(), 30_000), 30);
+        assert_eq!(stalled_secs(&receiving(), 95_500), 95);
+    }
+
+    #[test]
+    fn stall_badge_is_hidden_outside_receiving_or_when_disabled() {
+        let tooling = StreamState { phase: StreamPhase::ExecutingTools, ..receiving() };
+        let disabled = StreamState { stall_secs: 0, ..receiving() };
+        assert_eq!(stalled_secs(&tooling, 120_000), 0);
+        assert_eq!(stalled_secs(&disabled, 120_000), 0);
+    }
+}
@@ -3,13 +3,18 @@
 //! Extracts the sidebar data logic into pure functions returning IR types.
 //! No ratatui, no Frame.
 
-use cp_render::frame::{HelpHint, PrCard, Sidebar, SidebarEntry, SidebarMode, TokenBar, TokenRow, TokenStats};
+use cp_render::frame::{
+    HeatCell, HeatMap, HelpHint, PrCard, Sidebar, SidebarEntry, SidebarMode, TaskCard, TokenBar, TokenDetail, TokenRow,
+    TokenStats,
+};
 use cp_render::{ProgressSegment, Semantic};
 
-use crate::state::{Kind, State};
+use crate::modules::overview::panel_layout;
+use crate::state::{Kind, MsgStatus, State, estimate_tokens};
 use crate::ui::helpers::spinner;
 use cp_base::cast::Safe as _;
 use cp_base::cast::float_math;
+use cp_base::state::data::model_helpers::ModelPricing as _;
 
 /// Returns a count badge for fixed panels, replacing the panel ID (P1, P2, etc.)
 /// with a meaningful number that reflects the panel's content.
@@ -30,6 +35,7 @@ fn fixed_panel_badge(ctx_type: &str, state: &State) -> Option<String> {
         "callback" => cp_mod_callback::types::CallbackState::get(state).definitions.len(),
         "scratchpad" => cp_mod_scratchpad::types::ScratchpadState::get(state).scratchpad_cells.len(),
         "queue" => cp_mod_queue::types::QueueState::get(state).queued_calls.len(),
+        "ledger" => cp_mod_ledger::types::LedgerState::get(state).entries.len(),
         "overview" => state.context.len().saturating_add(2),
         "tools" => state.tools.iter().filter(|t| t.enabled).count(),
         _ => return None,
@@ -52,40 +58,34 @@ pub(crate) fn build_sidebar(state: &State) -> Sidebar {
             entries: Vec::new(),
             token_bar: None,
             token_stats: None,
+            task_card: None,
             pr_card: None,
             help_hints: Vec::new(),
+            token_detail: None,
+            heat_map: None,
         };
     }
 
     let entries = build_entries(state);
     let token_bar = Some(build_token_bar(state));
     let token_stats = build_token_stats(state);
+    let task_card = build_task_card(state);
     let pr_card = build_pr_card(state);
     let help_hints = build_help_hints(state);
+    let token_detail = build_token_detail(state);
+    let heat_map = build_heat_map(state);
 
-    Sidebar { mode, entries, token_bar, token_stats, pr_card, help_hints }
+    Sidebar { mode, entries, token_bar, token_stats, task_card, pr_card, help_hints, token_detail, heat_map }
 }
 
 // ── Entries ──────────────────────────────────────────────────────────
 
 /// Build the context element entries list for the sidebar.
 fn build_entries(state: &State) -> Vec<SidebarEntry> {
-    // Sort by panel ID numerically
+    // Fixed panels, then manually ordered panels, then the rest by panel ID
     let mut sorted_indices: Vec<usize> = (0..state.context.len()).collect();
-    sorted_indices.sort_by(|&a, &b| {
-        let id_a = state
-            .context
-            .get(a)
-            .and_then(|c| c.id.strip_prefix('P'))
-            .and_then(|n| n.parse::<usize>().ok())
-            .unwrap_or(usize::MAX);
-        let id_b = state
-            .context
-            .get(b)
-            .and_then(|c| c.id.strip_prefix('P'))
-            .and_then(|n| n.parse::<usize>().ok())
-            .unwrap_or(usize::MAX);
-        id_a.cmp(&id_b)
+    sorted_indices.sort_by_key(|&i| {
+        state.context.get(i).map_or((usize::MAX, usize::MAX), |c| panel_layout::display_key(state, c))
     });
 
     let mut entries = Vec::new();
@@ -123,7 +123,7 @@ fn build_entries(state: &State) -> Vec<SidebarEntry> {
 /// Resolves fixed-panel badges/shortcuts, running-console spinner, and the
 /// loading-spinner label suffix.
 fn context_to_entry(ctx: &crate::state::Entry, state: &State, active: bool) -> SidebarEntry {
-    let is_loading = ctx.cached_content.is_none() && ctx.context_type.needs_cache();
+    let is_loading = ctx.cached_content.is_none() && ctx.context_type.needs_cache() && !ctx.awaits_load();
     let is_fixed = ctx.context_type.is_fixed();
     let is_console = ctx.context_type.as_str() == "console";
     let is_running_console = is_console && ctx.get_meta_str("console_status").is_some_and(|s| s.starts_with("running"));
@@ -133,7 +133,7 @@ fn context_to_entry(ctx: &crate::state::Entry, state: &State, active: bool) -> S
     } else if ctx.total_pages > 1 {
         Some(format!("{}/{}", ctx.current_page.saturating_add(1), ctx.total_pages))
     } else {
-        None
+        crate::modules::overview::panel_group::slot_of(state, ctx).map(|slot| format!("\u{2325}{slot}"))
     };
 
     let shortcut = if is_fixed {
@@ -163,6 +163,89 @@ fn context_to_entry(ctx: &crate::state::Entry, state: &State, active: bool) -> S
     }
 }
 
+// ── Token detail ─────────────────────────────────────────────────────
+
+/// Show or hide the selected panel's token breakdown.
+pub(crate) const fn toggle_token_detail(state: &mut State) {
+    state.flags.overlays.token_detail = !state.flags.overlays.token_detail;
+    state.flags.ui.dirty = true;
+}
+
+/// Break the selected panel's prompt share into boilerplate, content and the
+/// pages left out. `None` when toggled off or on the conversation, which is
+/// sent as messages rather than as a panel.
+fn build_token_detail(state: &State) -> Option<TokenDetail> {
+    let ctx = state.context.get(state.selected_context).filter(|_| state.flags.overlays.token_detail)?;
+    if ctx.context_type == Kind::new(Kind::CONVERSATION) {
+        return None;
+    }
+    let item = crate::app::panels::get_panel(&ctx.context_type).context(state).into_iter().find(|i| i.id == ctx.id);
+    let paginated = ctx.total_pages > 1;
+    let header = item.as_ref().map_or(0, |i| {
+        let notice = if paginated { estimate_tokens(&i.content).saturating_sub(ctx.token_count) } else { 0 };
+        estimate_tokens(&i.banner())
+            .saturating_add(estimate_tokens(&crate::llms::panel_timestamp_text(i.last_refresh_ms)))
+            .saturating_add(notice)
+    });
+    let truncated = if paginated { ctx.full_token_count.saturating_sub(ctx.token_count) } else { 0 };
+    Some(TokenDetail {
+        header: header.to_u32(),
+        content: ctx.token_count.to_u32(),
+        truncated: truncated.to_u32(),
+        budget: state.effective_context_budget().to_u32(),
+    })
+}
+
+// ── Heat map ─────────────────────────────────────────────────────────
+
+/// Heat colour for a share of the request, in per-mille.
+const fn heat_semantic(permille: u16) -> Semantic {
+    if permille >= 200 {
+        Semantic::Error
+    } else if permille >= 100 {
+        Semantic::Warning
+    } else if permille >= 30 {
+        Semantic::Accent
+    } else {
+        Semantic::Muted
+    }
+}
+
+/// Split the next request into its system prompt and tools, each panel and
+/// each live message (dev mode only). The conversation panel is left out:
+/// its count is the sum of the messages it is sent as.
+fn build_heat_map(state: &State) -> Option<HeatMap> {
+    if !state.flags.ui.dev_mode {
+        return None;
+    }
+    let system_prompt = cp_mod_prompt::seed::get_active_agent_content(state);
+    let sys = estimate_tokens(&system_prompt)
+        .saturating_mul(2)
+        .saturating_add(crate::modules::overview::context::estimate_tool_definitions_tokens(state));
+    let panels = state
+        .context
+        .iter()
+        .filter(|c| c.context_type != Kind::new(Kind::CONVERSATION) && c.token_count > 0)
+        .map(|c| (c.id.clone(), c.token_count));
+    let messages = state
+        .messages
+        .iter()
+        .filter(|m| m.status != MsgStatus::Deleted && m.status != MsgStatus::Detached)
+        .map(|m| (m.id.clone(), crate::modules::conversation::refresh::estimate_message_tokens(m)))
+        .filter(|&(_, tokens)| tokens > 0);
+    let parts: Vec<(String, usize)> =
+        std::iter::once((String::from("sys"), sys)).chain(panels).chain(messages).collect();
+    let total: usize = parts.iter().map(|&(_, tokens)| tokens).sum();
+    let cells = parts
+        .into_iter()
+        .map(|(label, tokens)| {
+            let permille = tokens.saturating_mul(1000).checked_div(total).unwrap_or(0).to_u16();
+            HeatCell { label, tokens: tokens.to_u32(), permille, semantic: heat_semantic(permille) }
+        })
+        .collect();
+    Some(HeatMap { cells, total: total.to_u32() })
+}
+
 // ── Token bar ────────────────────────────────────────────────────────
 
 /// Build the token usage progress bar.
@@ -258,6 +341,47 @@ fn build_token_stats(state: &State) -> Option<TokenStats> {
     })
 }
 
+// ── Task card ────────────────────────────────────────────────────────
+
+/// Build the current-task card: progress since the last human message.
+/// `None` before the user has said anything.
+fn build_task_card(state: &State) -> Option<TaskCard> {
+    let started = state
+        .messages
+        .iter()
+        .rfind(|m| {
+            m.role == "user"
+                && m.msg_type == cp_base::state::data::message::MsgKind::TextMessage
+                && !m.content.trim_start().starts_with("/*")
+        })?
+        .timestamp_ms;
+    let streaming = state.flags.stream.phase.is_streaming();
+    let until =
+        if streaming { cp_base::panels::now_ms() } else { state.messages.last().map_or(started, |m| m.timestamp_ms) };
+    let todo = state.get_ext::<cp_mod_todo::types::TodoState>().and_then(|ts| {
+        let active = ts.todos.iter().find(|t| t.status == cp_mod_todo::types::TodoStatus::InProgress);
+        active.map(|t| t.name.clone())
+    });
+    let goals = state.get_ext::<cp_mod_todo::goals::GoalState>().filter(|g| !g.criteria.is_empty()).map(|g| {
+        let (met, total) = g.progress();
+        (met.to_u32(), total.to_u32())
+    });
+    let tokens = state
+        .stream_cache_hit_tokens
+        .saturating_add(state.stream_cache_miss_tokens)
+        .saturating_add(state.stream_output_tokens)
+        .saturating_add(state.streaming_estimated_tokens);
+
+    Some(TaskCard {
+        todo,
+        tool_turns: cp_mod_spine::types::SpineState::get(state).config.tool_turn_count.to_u32(),
+        elapsed_secs: cp_base::panels::time_arith::ms_to_secs(until.saturating_sub(started)),
+        tokens: tokens.to_u32(),
+        streaming,
+        goals,
+    })
+}
+
 // ── PR card ──────────────────────────────────────────────────────────
 
 /// Build the PR summary card from git state, if a branch PR exists.
@@ -276,25 +400,87 @@ fn build_pr_card(state: &State) -> Option<PrCard> {
 
 // ── Help hints ───────────────────────────────────────────────────────
 
-/// Build keyboard shortcut help hints for the sidebar.
+/// Build the sidebar's short list of key hints; `?` opens the full reference.
+/// The token-detail hint shows on any panel but the conversation, the close /
+/// move hints only while a dynamic panel is selected.
 fn build_help_hints(state: &State) -> Vec<HelpHint> {
     let copy_flash = {
         let ms = state.flags.overlays.copied_flash_ms;
         ms > 0 && cp_base::panels::now_ms().saturating_sub(ms) < 2_000
     };
+    let selected = state.context.get(state.selected_context);
+    let movable = selected.is_some_and(panel_layout::is_movable);
+    let detail_hint: &[(&str, &str)] = if selected.is_some_and(|c| c.context_type != Kind::new(Kind::CONVERSATION)) {
+        &[("i", "token detail")]
+    } else {
+        &[]
+    };
+    let panel_hints: &[(&str, &str)] =
+        if movable { &[("x", "close panel"), ("\u{21e7}\u{2191}\u{2193}", "move panel")] } else { &[] };
+    let gc_hint: &[(&str, &str)] =
+        if crate::modules::overview::panel_gc::undoable(state) > 0 { &[("Ctrl+Z", "reopen gc'd")] } else { &[] };
+
+    std::iter::once(("Tab", "next panel"))
+        .chain(detail_hint.iter().chain(panel_hints).chain(gc_hint).copied())
+        .chain([
+            ("Ctrl+P", "commands"),
+            ("Ctrl+C", if copy_flash { "copied \u{2713}" } else { "copy panel" }),
+            ("?", "all keys"),
+            ("Ctrl+Q", "quit"),
+        ])
+        .map(|(key, desc)| HelpHint { key: key.into(), description: desc.into() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::data::message::Message;
+    use cp_mod_todo::types::{TodoItem, TodoState, TodoStatus};
+
+    /// A message from `role` saying `text` at `at_ms`.
+    fn said(role: &str, text: &str, at_ms: u64) -> Message {
+        let mut message = Message::new_user("M".to_owned(), "UID_1_U".to_owned(), text.to_owned(), 1);
+        role.clone_into(&mut message.role);
+        message.timestamp_ms = at_ms;
+        message
+    }
 
-    [
-        ("Tab", "next panel"),
-        ("\u{2191}\u{2193}", "scroll"),
-        ("Ctrl+U/D", "history"),
-        ("Ctrl+C", if copy_flash { "copied \u{2713}" } else { "copy panel" }),
-        ("Ctrl+I", "search index"),
-        ("Ctrl+P", "commands"),
-        ("Ctrl+H", "config"),
-        ("Ctrl+V", "view"),
-        ("Ctrl+Q", "quit"),
-    ]
-    .into_iter()
-    .map(|(key, desc)| HelpHint { key: key.into(), description: desc.into() })
-    .collect()
+    #[test]
+    fn the_task_card_counts_from_the_last_human_message() {
+        let mut state = State::default();
+        state.set_ext(cp_mod_spine::types::SpineState::new());
+        assert!(build_task_card(&state).is_none());
+        cp_mod_spine::types::SpineState::get_mut(&mut state).config.tool_turn_count = 4;
+        let mut todos = TodoState::new();
+        let status = TodoStatus::InProgress;
+        todos.todos.push(TodoItem {
+            id: "X1".into(),
+            parent_id: None,
+            name: "Parse config".into(),
+            description: String::new(),
+            status,
+        });
+        state.set_ext(todos);
+        state.messages = vec![
+            said("user", "Fix the parser", 10_000),
+            said("user", "/* Auto-continuation */", 40_000),
+            said("assistant", "Done", 70_000),
+        ];
+        let card = build_task_card(&state).map(|c| (c.todo, c.tool_turns, c.elapsed_secs, c.streaming));
+        assert_eq!(card, Some((Some("Parse config".to_owned()), 4, 60, false)));
+    }
+
+    #[test]
+    fn the_breakdown_counts_the_pages_left_out() {
+        let mut state = State::default();
+        let mut entry = cp_base::state::context::make_default_entry("P9", Kind::new(Kind::PASTED), "log", false);
+        (entry.token_count, entry.full_token_count, entry.total_pages) = (400, 1000, 3);
+        state.context.push(entry);
+        state.selected_context = 0;
+        assert!(build_token_detail(&state).is_none());
+        state.flags.overlays.token_detail = true;
+        let detail = build_token_detail(&state).map(|d| (d.content, d.truncated));
+        assert_eq!(detail, Some((400, 600)));
+    }
 }
@@ -19,6 +19,7 @@ pub(crate) use crate::infra::constants::theme;
 /// Typewriter animation buffer re-exported from helpers.
 pub(crate) use helpers::TypewriterBuffer;
 
+use cp_base::config::accessors::color_depth;
 use ratatui::Frame;
 use ratatui::prelude::{Constraint, Direction, Layout, Rect, Style};
 use ratatui::widgets::Block;
@@ -74,11 +75,12 @@ pub(crate) fn render(frame: &mut Frame<'_>, state: &mut State) {
     }
 
     render_modal_overlays(frame, area, &ir_frame.overlays);
+    color_depth::downgrade_buffer(frame.buffer_mut());
 
     PERF.frame_end();
 }
 
-/// Render the full-area modal overlays (perf monitor, config, search-index) from
+/// Render the full-area modal overlays (perf monitor, config, search-index, key help, tour) from
 /// the IR overlay stack. The autocomplete popup is handled separately (it needs
 /// the content-area offset), so it is not touched here.
 fn render_modal_overlays(frame: &mut Frame<'_>, area: Rect, overlays: &[cp_render::conversation::Overlay]) {
@@ -90,7 +92,9 @@ fn render_modal_overlays(frame: &mut Frame<'_>, area: Rect, overlays: &[cp_rende
             | cp_render::conversation::Overlay::Autocomplete(_)
             | cp_render::conversation::Overlay::Config(_)
             | cp_render::conversation::Overlay::CommandPalette(_)
-            | cp_render::conversation::Overlay::SearchIndex(_) => None,
+            | cp_render::conversation::Overlay::SearchIndex(_)
+            | cp_render::conversation::Overlay::Tour(_)
+            | cp_render::conversation::Overlay::KeyHelp(_) => None,
         })
     }) {
         perf::render_perf_overlay_from_ir(frame, area, perf_overlay);
@@ -104,7 +108,9 @@ fn render_modal_overlays(frame: &mut Frame<'_>, area: Rect, overlays: &[cp_rende
             | cp_render::conversation::Overlay::Autocomplete(_)
             | cp_render::conversation::Overlay::Perf(_)
             | cp_render::conversation::Overlay::CommandPalette(_)
-            | cp_render::conversation::Overlay::SearchIndex(_) => None,
+            | cp_render::conversation::Overlay::SearchIndex(_)
+            | cp_render::conversation::Overlay::Tour(_)
+            | cp_render::conversation::Overlay::KeyHelp(_) => None,
         })
     }) {
         help::config_overlay::render_config_overlay(frame, config_overlay, area);
@@ -118,11 +124,49 @@ fn render_modal_overlays(frame: &mut Frame<'_>, area: Rect, overlays: &[cp_rende
             | cp_render::conversation::Overlay::Autocomplete(_)
             | cp_render::conversation::Overlay::Perf(_)
             | cp_render::conversation::Overlay::Config(_)
-            | cp_render::conversation::Overlay::CommandPalette(_) => None,
+            | cp_render::conversation::Overlay::CommandPalette(_)
+            | cp_render::conversation::Overlay::Tour(_)
+            | cp_render::conversation::Overlay::KeyHelp(_) => None,
         })
     }) {
         search_overlay::render_search_index_overlay(frame, search_overlay, area);
     }
+
+    render_help_overlays(frame, area, overlays);
+}
+
+/// Render the keybinding reference, then the guided tour card over
+/// everything else.
+fn render_help_overlays(frame: &mut Frame<'_>, area: Rect, overlays: &[cp_render::conversation::Overlay]) {
+    if let Some(help) = overlays.iter().find_map(|o| {
+        cp_base::deref_match!(o, {
+            cp_render::conversation::Overlay::KeyHelp(ref h) => Some(h),
+            cp_render::conversation::Overlay::QuestionForm(_)
+            | cp_render::conversation::Overlay::Autocomplete(_)
+            | cp_render::conversation::Overlay::Perf(_)
+            | cp_render::conversation::Overlay::Config(_)
+            | cp_render::conversation::Overlay::CommandPalette(_)
+            | cp_render::conversation::Overlay::SearchIndex(_)
+            | cp_render::conversation::Overlay::Tour(_) => None,
+        })
+    }) {
+        help::keymap::render_key_help_overlay(frame, help, area);
+    }
+
+    if let Some(tour) = overlays.iter().find_map(|o| {
+        cp_base::deref_match!(o, {
+            cp_render::conversation::Overlay::Tour(ref t) => Some(t),
+            cp_render::conversation::Overlay::QuestionForm(_)
+            | cp_render::conversation::Overlay::Autocomplete(_)
+            | cp_render::conversation::Overlay::Perf(_)
+            | cp_render::conversation::Overlay::Config(_)
+            | cp_render::conversation::Overlay::CommandPalette(_)
+            | cp_render::conversation::Overlay::SearchIndex(_)
+            | cp_render::conversation::Overlay::KeyHelp(_) => None,
+        })
+    }) {
+        help::tour::render_tour_overlay(frame, tour, area);
+    }
 }
 
 /// Render the body area: sidebar (if visible) and main content panel,
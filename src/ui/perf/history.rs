@@ -0,0 +1,219 @@
+//! Profile history: folded stacks of the `profile!` guards, per session.
+//!
+//! While recording — the perf overlay is on (F12), or `CP_PERF_HISTORY=1` —
+//! every guard also charges its self-time (total minus nested guards) to its
+//! call path, `app::tick;app::cache_updates`. The palette's "Export perf
+//! profile" writes those paths as folded stacks, which speedscope and
+//! inferno-flamegraph open as they are. With `CP_PERF_HISTORY=1` the session's
+//! per-operation totals are also appended to `perf-history.jsonl` on exit, one
+//! line per session tagged with the version, so runs of two versions can be
+//! compared offline.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::Ordering;
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+use cp_mod_spine::types::{NotificationType, SpineState};
+
+use super::PERF;
+use crate::state::State;
+
+/// Directory the exports and the history go to.
+const LOG_DIR: &str = ".context-pilot/logs";
+/// Session history, one JSON line per session.
+const HISTORY_FILE: &str = "perf-history.jsonl";
+
+/// Totals of one operation over the session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct OpTotals {
+    /// Times the guard ran.
+    count: u64,
+    /// Summed wall time (µs).
+    total_us: u64,
+    /// Slowest run (µs).
+    max_us: u64,
+}
+
+/// What the guards recorded since startup.
+#[derive(Debug, Default)]
+struct Profile {
+    /// Folded call path → self-time (µs).
+    stacks: HashMap<String, u64>,
+    /// Operation → totals.
+    ops: HashMap<&'static str, OpTotals>,
+}
+
+impl Profile {
+    /// Charge one closed guard: `spent` is its (self, total) time in µs.
+    fn charge(&mut self, folded: String, name: &'static str, spent: (u64, u64)) {
+        let (self_us, total_us) = spent;
+        let path = self.stacks.entry(folded).or_insert(0);
+        *path = path.saturating_add(self_us);
+        let op = self.ops.entry(name).or_default();
+        op.count = op.count.saturating_add(1);
+        op.total_us = op.total_us.saturating_add(total_us);
+        op.max_us = op.max_us.max(total_us);
+    }
+}
+
+/// The recorded profile, shared by every thread.
+static PROFILE: LazyLock<Mutex<Profile>> = LazyLock::new(|| Mutex::new(Profile::default()));
+
+/// When this session started (for the history line).
+static STARTED: OnceLock<String> = OnceLock::new();
+
+thread_local! {
+    /// Guards open on this thread: `(name, µs spent in nested guards)`.
+    static STACK: RefCell<Vec<(&'static str, u64)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Whether session history is on (`CP_PERF_HISTORY=1`). Cached.
+pub(crate) fn history_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| std::env::var("CP_PERF_HISTORY").is_ok_and(|v| v == "1" || v == "true"))
+}
+
+/// Whether guards are charged to the profile right now.
+pub(crate) fn is_recording() -> bool {
+    history_enabled() || PERF.enabled.load(Ordering::Relaxed)
+}
+
+/// Note the session start. Call once at startup.
+pub(crate) fn init() {
+    let _started = STARTED.get_or_init(cp_mod_utilities::time::now_utc_rfc3339_secs);
+}
+
+/// A guard named `name` opened on this thread.
+pub(crate) fn enter(name: &'static str) {
+    STACK.with(|s| s.borrow_mut().push((name, 0)));
+}
+
+/// The innermost guard of this thread closed after `total_us`.
+pub(crate) fn leave(total_us: u64) {
+    let Some((folded, name, self_us)) = STACK.with(|s| {
+        let mut stack = s.borrow_mut();
+        let children_us = stack.last().map_or(0, |entry| entry.1);
+        let folded = stack.iter().map(|entry| entry.0).collect::<Vec<_>>().join(";");
+        let (name, _) = stack.pop()?;
+        if let Some(parent) = stack.last_mut() {
+            parent.1 = parent.1.saturating_add(total_us);
+        }
+        Some((folded, name, total_us.saturating_sub(children_us)))
+    }) else {
+        return;
+    };
+    PROFILE.lock().unwrap_or_else(std::sync::PoisonError::into_inner).charge(folded, name, (self_us, total_us));
+}
+
+/// Whether anything was recorded yet.
+pub(crate) fn has_samples() -> bool {
+    !PROFILE.lock().unwrap_or_else(std::sync::PoisonError::into_inner).stacks.is_empty()
+}
+
+/// Folded stacks, `path self_µs` per line, heaviest first.
+fn folded(profile: &Profile) -> String {
+    let mut lines: Vec<(&String, u64)> = profile.stacks.iter().map(|(path, &us)| (path, us)).collect();
+    lines.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    lines.iter().fold(String::new(), |mut out, &(path, us)| {
+        let _r = writeln!(out, "{path} {us}");
+        out
+    })
+}
+
+/// Write the recorded profile as folded stacks; returns the file's path.
+pub(crate) fn export() -> std::io::Result<String> {
+    let text = folded(&PROFILE.lock().unwrap_or_else(std::sync::PoisonError::into_inner));
+    std::fs::create_dir_all(LOG_DIR)?;
+    let version = env!("CARGO_PKG_VERSION");
+    let path = format!("{LOG_DIR}/perf-{version}-{}.folded", cp_mod_utilities::time::now_utc_compact());
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Palette "Export perf profile": write the profile and say where (or why
+/// not) in a spine notification, marked processed so it never wakes the agent.
+pub(crate) fn export_and_report(state: &mut State) {
+    let content = match export() {
+        Ok(path) => format!("Perf profile written to {path} (open in speedscope, or pipe to inferno-flamegraph)"),
+        Err(e) => format!("Perf profile export failed: {e}"),
+    };
+    let id = SpineState::create_notification(state, NotificationType::Custom, "perf".to_owned(), content);
+    let _processed = SpineState::mark_notification_processed(state, &id);
+    state.flags.ui.dirty = true;
+}
+
+/// One session's history line: version, start and end, per-op totals.
+fn history_line(profile: &Profile, started: &str, ended: &str) -> serde_json::Value {
+    let mut by_time: Vec<(&&str, &OpTotals)> = profile.ops.iter().collect();
+    by_time.sort_by(|a, b| b.1.total_us.cmp(&a.1.total_us).then_with(|| a.0.cmp(b.0)));
+    let ops: Vec<serde_json::Value> = by_time
+        .into_iter()
+        .map(|(name, totals)| {
+            serde_json::json!({
+                "name": name,
+                "count": totals.count,
+                "total_us": totals.total_us,
+                "mean_us": totals.total_us.checked_div(totals.count).unwrap_or(0),
+                "max_us": totals.max_us,
+            })
+        })
+        .collect();
+    serde_json::json!({ "version": env!("CARGO_PKG_VERSION"), "started": started, "ended": ended, "ops": ops })
+}
+
+/// The history line of the profile so far; `None` when nothing was recorded.
+fn history_snapshot(started: &str, ended: &str) -> Option<serde_json::Value> {
+    let profile = PROFILE.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    (!profile.ops.is_empty()).then(|| history_line(&profile, started, ended))
+}
+
+/// Append this session's totals to the history (`CP_PERF_HISTORY=1` only).
+/// Call on shutdown; I/O errors are ignored.
+pub(crate) fn persist() {
+    if !history_enabled() {
+        return;
+    }
+    let started = STARTED.get().map_or("", String::as_str);
+    let ended = cp_mod_utilities::time::now_utc_rfc3339_secs();
+    let Some(line) = history_snapshot(started, &ended) else { return };
+    let _mkdir = std::fs::create_dir_all(LOG_DIR);
+    if let Ok(mut file) =
+        std::fs::OpenOptions::new().create(true).append(true).open(format!("{LOG_DIR}/{HISTORY_FILE}"))
+    {
+        let _r = std::io::Write::write_all(&mut file, format!("{line}\n").as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_guards_charge_self_time_to_their_path() {
+        let mut profile = Profile::default();
+        let _outer = profile.stacks.insert("tick".to_owned(), 30);
+        let _inner = profile.stacks.insert("tick;render".to_owned(), 70);
+        assert_eq!(folded(&profile), "tick;render 70\ntick 30\n");
+
+        enter("perf_test_outer");
+        enter("perf_test_inner");
+        leave(40);
+        leave(100);
+        let charged =
+            |path: &str| PROFILE.lock().unwrap_or_else(std::sync::PoisonError::into_inner).stacks.get(path).copied();
+        assert_eq!(charged("perf_test_outer;perf_test_inner"), Some(40));
+        assert_eq!(charged("perf_test_outer"), Some(60));
+    }
+
+    #[test]
+    fn history_lines_carry_version_and_mean() {
+        let mut profile = Profile::default();
+        let _op = profile.ops.insert("render", OpTotals { count: 4, total_us: 400, max_us: 250 });
+        let line = history_line(&profile, "2026-01-01T00:00:00Z", "2026-01-01T01:00:00Z");
+        assert_eq!(line.get("version").and_then(serde_json::Value::as_str), Some(env!("CARGO_PKG_VERSION")));
+        let mean = line.pointer("/ops/0/mean_us").and_then(serde_json::Value::as_u64);
+        assert_eq!(mean, Some(100));
+    }
+}
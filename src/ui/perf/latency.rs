@@ -0,0 +1,223 @@
+//! Per-request LLM latency: time to first token, model time, tool time.
+//!
+//! The main loop reports every request it sends ([`request_started`]) and the
+//! stream events it drains ([`on_stream_event`]). A finished request's timing
+//! lands on its assistant message (dev mode shows it under the message) and in
+//! a per-provider log of recent requests, whose percentiles the perf overlay
+//! (F12) lists so providers can be compared on the same workload. Tool time is
+//! the gap between a stream that called tools and the request resuming it.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use cp_base::cast::Safe as _;
+use cp_base::cast::float_math;
+use cp_base::config::llm_types::LlmProvider;
+use cp_base::state::data::message::Latency;
+
+use crate::state::State;
+
+/// Requests remembered per provider.
+const LOG_SIZE: usize = 200;
+
+/// The request on the wire.
+#[derive(Debug, Clone, Copy)]
+struct InFlight {
+    /// Provider it was sent to.
+    provider: LlmProvider,
+    /// When it was sent.
+    sent: Instant,
+    /// When its first event arrived.
+    first_event: Option<Instant>,
+}
+
+/// Tools running after a stream, until the request resuming it.
+#[derive(Debug, Clone)]
+struct ToolRun {
+    /// Assistant message whose stream called them.
+    msg_id: String,
+    /// Provider of that stream (its log entry gets the tool time).
+    provider: LlmProvider,
+    /// When the stream ended.
+    since: Instant,
+}
+
+/// Latency bookkeeping of this worker (runtime only).
+#[derive(Debug, Default)]
+pub(crate) struct LatencyLog {
+    /// The request being streamed.
+    in_flight: Option<InFlight>,
+    /// Tools run by the last stream, not yet resumed.
+    tools: Option<ToolRun>,
+    /// Recent timings per provider, oldest first, in order of first use.
+    samples: Vec<(LlmProvider, VecDeque<Latency>)>,
+}
+
+impl LatencyLog {
+    /// The log of `provider`, if it sent anything yet.
+    fn provider_mut(&mut self, provider: LlmProvider) -> Option<&mut VecDeque<Latency>> {
+        self.samples.iter_mut().find(|entry| entry.0 == provider).map(|entry| &mut entry.1)
+    }
+
+    /// Record a finished request of `provider`.
+    fn push(&mut self, provider: LlmProvider, timing: Latency) {
+        if self.provider_mut(provider).is_none() {
+            self.samples.push((provider, VecDeque::new()));
+        }
+        let Some(log) = self.provider_mut(provider) else { return };
+        if log.len() >= LOG_SIZE {
+            let _oldest = log.pop_front();
+        }
+        log.push_back(timing);
+    }
+}
+
+/// Percentiles of one provider's recent requests, in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProviderLatency {
+    /// Provider name.
+    pub provider: String,
+    /// Requests the percentiles cover.
+    pub requests: usize,
+    /// Time to first token, p50 and p90.
+    pub ttft: [u64; 2],
+    /// Model (stream) time, p50 and p90.
+    pub stream: [u64; 2],
+    /// Tool time of the requests that called tools, p50 and p90.
+    pub tools: [u64; 2],
+}
+
+/// Mutable bookkeeping, created on first use.
+fn log_mut(state: &mut State) -> &mut LatencyLog {
+    if state.get_ext::<LatencyLog>().is_none() {
+        state.set_ext(LatencyLog::default());
+    }
+    state.ext_mut::<LatencyLog>()
+}
+
+/// Milliseconds elapsed between two instants.
+fn ms_between(from: Instant, to: Instant) -> u64 {
+    to.saturating_duration_since(from).as_millis().to_u64()
+}
+
+/// A request was sent to the current provider. Closes the tool run it
+/// resumes, if any.
+pub(crate) fn request_started(state: &mut State) {
+    let now = Instant::now();
+    let provider = state.llm_provider;
+    let log = log_mut(state);
+    log.in_flight = Some(InFlight { provider, sent: now, first_event: None });
+    let Some(run) = log.tools.take() else { return };
+    let tools_ms = ms_between(run.since, now);
+    if let Some(last) = log.provider_mut(run.provider).and_then(VecDeque::back_mut) {
+        last.tools = tools_ms;
+    }
+    if let Some(timing) = state.messages.iter_mut().find(|m| m.id == run.msg_id).and_then(|m| m.latency.as_mut()) {
+        timing.tools = tools_ms;
+    }
+}
+
+/// What a drained stream event means for the request's timing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mark {
+    /// Any event before the last one.
+    Event,
+    /// The final event; `calls_tools` when the stream queued tool calls.
+    Done {
+        /// The stream queued tool calls.
+        calls_tools: bool,
+    },
+    /// The request failed.
+    Failed,
+}
+
+/// A stream event of the request in flight was drained.
+pub(crate) fn on_stream_event(state: &mut State, mark: Mark) {
+    let now = Instant::now();
+    let log = log_mut(state);
+    if mark == Mark::Failed {
+        log.in_flight = None;
+        return;
+    }
+    let Some(flight) = log.in_flight.as_mut() else { return };
+    let first = *flight.first_event.get_or_insert(now);
+    let Mark::Done { calls_tools } = mark else { return };
+    let timing = Latency { ttft: ms_between(flight.sent, first), stream: ms_between(flight.sent, now), tools: 0 };
+    let provider = flight.provider;
+    log.in_flight = None;
+    log.push(provider, timing);
+    let Some(msg) = state.messages.iter_mut().rev().find(|m| m.role == "assistant") else { return };
+    msg.latency = Some(timing);
+    let msg_id = msg.id.clone();
+    if calls_tools {
+        log_mut(state).tools = Some(ToolRun { msg_id, provider, since: now });
+    }
+}
+
+/// Nearest-rank p50 and p90 of `values` (zeros when empty).
+fn p50_p90(mut values: Vec<u64>) -> [u64; 2] {
+    values.sort_unstable();
+    [50usize, 90].map(|p| {
+        let rank = values.len().saturating_mul(p).div_ceil(100).saturating_sub(1);
+        values.get(rank).copied().unwrap_or(0)
+    })
+}
+
+/// Percentiles per provider, in order of first use.
+pub(crate) fn summaries(state: &State) -> Vec<ProviderLatency> {
+    let Some(log) = state.get_ext::<LatencyLog>() else { return Vec::new() };
+    log.samples
+        .iter()
+        .filter(|entry| !entry.1.is_empty())
+        .map(|entry| ProviderLatency {
+            provider: format!("{:?}", entry.0),
+            requests: entry.1.len(),
+            ttft: p50_p90(entry.1.iter().map(|t| t.ttft).collect()),
+            stream: p50_p90(entry.1.iter().map(|t| t.stream).collect()),
+            tools: p50_p90(entry.1.iter().map(|t| t.tools).filter(|&ms| ms > 0).collect()),
+        })
+        .collect()
+}
+
+/// Compact duration: `850ms`, `2.4s`, `37s`.
+pub(crate) fn format_ms(ms: u64) -> String {
+    if ms < 1_000 {
+        format!("{ms}ms")
+    } else if ms < 10_000 {
+        format!("{:.1}s", float_math::div_u64(ms, 1_000.0f64))
+    } else {
+        format!("{:.0}s", float_math::div_u64(ms, 1_000.0f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cp_base::state::data::message::Message;
+
+    #[test]
+    fn stream_and_tool_time_land_on_the_message_and_the_log() {
+        let mut state = State::default();
+        state.messages.push(Message::new_assistant("A1".to_owned(), "UID_1_A".to_owned()));
+        request_started(&mut state);
+        on_stream_event(&mut state, Mark::Event);
+        on_stream_event(&mut state, Mark::Done { calls_tools: true });
+        assert!(state.messages.first().is_some_and(|m| m.latency.is_some()));
+        assert!(log_mut(&mut state).tools.is_some());
+
+        request_started(&mut state);
+        assert!(log_mut(&mut state).tools.is_none());
+        let rows = summaries(&state);
+        assert_eq!(rows.len(), 1);
+        assert!(rows.first().is_some_and(|r| r.requests == 1 && r.provider == "Anthropic"));
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        assert_eq!(p50_p90((1..=10).collect()), [5, 9]);
+        assert_eq!(p50_p90(Vec::new()), [0, 0]);
+        assert_eq!(format_ms(850), "850ms");
+        assert_eq!(format_ms(2_400), "2.4s");
+        assert_eq!(format_ms(37_000), "37s");
+    }
+}
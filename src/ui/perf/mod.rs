@@ -3,6 +3,10 @@
 //! Provides low-overhead profiling with real-time stats collection.
 //! Toggle with F12.
 
+/// Profile history: folded-stack export and per-session totals.
+pub(crate) mod history;
+/// Per-request LLM latency (time to first token, model and tool time).
+pub(crate) mod latency;
 /// Performance overlay adapter (F12 panel) — renders from IR snapshot.
 mod overlay;
 pub(crate) use overlay::render_perf_overlay_from_ir;
@@ -370,6 +374,27 @@ impl PerfMetrics {
         self.frame_count.store(0, Ordering::Relaxed);
     }
 
+    /// Start monitoring from a clean slate.
+    pub(crate) fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+        self.reset();
+    }
+
+    /// Frame-time percentiles in microseconds over the recent frames, one per
+    /// entry of `percents` (nearest-rank). Zero when no frame was recorded.
+    pub(crate) fn frame_percentiles(&self, percents: &[usize]) -> Vec<u64> {
+        let mut samples =
+            self.frame_times.read().unwrap_or_else(std::sync::PoisonError::into_inner).recent(SAMPLE_RING_SIZE);
+        samples.sort_unstable();
+        percents
+            .iter()
+            .map(|&p| {
+                let rank = samples.len().saturating_mul(p).div_ceil(100).saturating_sub(1);
+                samples.get(rank).copied().unwrap_or(0)
+            })
+            .collect()
+    }
+
     /// Toggle monitoring on/off, returns new state
     pub(crate) fn toggle(&self) -> bool {
         let new_state = !self.enabled.load(Ordering::Relaxed);
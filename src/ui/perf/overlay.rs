@@ -19,7 +19,9 @@ use cp_base::cast::float_math;
 pub(crate) fn render_perf_overlay_from_ir(frame: &mut Frame<'_>, area: Rect, perf: &PerfOverlay) {
     // Overlay dimensions
     let overlay_width = 62u16;
-    let overlay_height = 30u16;
+    let latency_rows = if perf.latency.is_empty() { 0 } else { perf.latency.len().saturating_mul(2).saturating_add(2) };
+    let cache_rows = perf.caches.panels.len().saturating_add(2);
+    let overlay_height = 30u16.saturating_add(latency_rows.to_u16()).saturating_add(cache_rows.to_u16());
 
     // Position in top-right
     let x = area.width.saturating_sub(overlay_width.saturating_add(2));
@@ -71,6 +73,18 @@ pub(crate) fn render_perf_overlay_from_ir(frame: &mut Frame<'_>, area: Rect, per
     // Operation table
     render_op_table(&perf.operations, &mut lines);
 
+    // LLM request latency per provider
+    if !perf.latency.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(" Request latency (p50/p90)", semantic_to_style(Semantic::Accent).bold())));
+        for row in &perf.latency {
+            render_latency_row(row, &mut lines);
+        }
+    }
+
+    // Panel content caches
+    render_caches(&perf.caches, &mut lines);
+
     // Footer
     lines.push(Line::from(vec![
         Span::styled(" F12", semantic_to_style(Semantic::Accent)),
@@ -139,6 +153,42 @@ fn render_sparkline(values: &[f64]) -> Line<'static> {
     ])
 }
 
+/// Render one provider's latency: name and request count, then the times.
+fn render_latency_row(row: &cp_render::conversation::PerfLatency, lines: &mut Vec<Line<'static>>) {
+    lines.push(Line::from(vec![
+        Span::styled(format!(" {}", row.provider), semantic_to_style(Semantic::Default)),
+        Span::styled(format!("  {} requests", row.requests), semantic_to_style(Semantic::Muted)),
+    ]));
+    let muted = semantic_to_style(Semantic::Muted);
+    lines.push(Line::from(vec![
+        Span::styled("   ttft ", muted),
+        Span::styled(row.ttft.clone(), semantic_to_style(Semantic::Accent)),
+        Span::styled("  model ", muted),
+        Span::styled(row.model.clone(), semantic_to_style(Semantic::Accent)),
+        Span::styled("  tools ", muted),
+        Span::styled(row.tools.clone(), semantic_to_style(Semantic::Accent)),
+    ]));
+}
+
+/// Render the panel cache breakdown: total against the budget, then the
+/// largest caches.
+fn render_caches(caches: &cp_render::conversation::PerfCaches, lines: &mut Vec<Line<'static>>) {
+    let muted = semantic_to_style(Semantic::Muted);
+    let evicted = if caches.evicted > 0 { format!("  {} evicted", caches.evicted) } else { String::new() };
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(" Panel caches ", semantic_to_style(Semantic::Accent).bold()),
+        Span::styled(caches.held.clone(), semantic_to_style(Semantic::Default)),
+        Span::styled(format!(" / budget {}{evicted}", caches.budget), muted),
+    ]));
+    for panel in &caches.panels {
+        lines.push(Line::from(vec![
+            Span::styled(format!("   {:>8}  ", panel.size), semantic_to_style(Semantic::Accent)),
+            Span::styled(panel.label.clone(), muted),
+        ]));
+    }
+}
+
 /// Render the operation table using IR semantic styles.
 fn render_op_table(ops: &[cp_render::conversation::PerfOp], lines: &mut Vec<Line<'static>>) {
     // Column definitions: name, mean, std, cumul
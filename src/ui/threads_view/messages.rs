@@ -162,6 +162,7 @@ fn render_thread_input(frame: &mut Frame<'_>, state: &State, area: Rect) {
         paste_buffers: &state.paste_buffers,
         paste_buffer_labels: &state.paste_buffer_labels,
         viewport_width: input_area.width,
+        spell_check: cp_base::ui::spell::Settings::enabled(state),
     };
 
     let input_blocks = render_input_blocks(&state.input, state.input_cursor, state.input_selection_anchor, &ctx);